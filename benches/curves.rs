@@ -0,0 +1,56 @@
+//! Addition and multiplication by a full width scalar in G1 and G2 of the curves that
+//! have precomputed engines
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use criterion::measurement::WallTime;
+use criterion::BenchmarkGroup;
+use rand::{Rng, SeedableRng};
+use rand_xorshift::XorShiftRng;
+
+use eth_pairings::weierstrass::Group;
+use eth_pairings::engines::bls12_381::*;
+use eth_pairings::engines::bls12_377::*;
+
+const SEED: [u8; 16] = [0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc, 0xe5];
+
+fn bench_group<G: Group>(group: &mut BenchmarkGroup<WallTime>, curve: &str, generator: &G, rng: &mut XorShiftRng) {
+    let scalar: [u64; 4] = rng.gen();
+    let p = generator.mul(rng.gen::<[u64; 4]>());
+    let q = generator.mul(rng.gen::<[u64; 4]>());
+
+    group.bench_with_input(BenchmarkId::new("add", curve), &(p.clone(), q), |bencher, (p, q)| {
+        bencher.iter(|| {
+            let mut p = p.clone();
+            p.add_assign(q);
+
+            p
+        })
+    });
+
+    group.bench_with_input(BenchmarkId::new("mul", curve), &(p, scalar), |bencher, (p, scalar)| {
+        bencher.iter(|| p.mul(scalar))
+    });
+}
+
+fn bench_g1(c: &mut Criterion) {
+    let mut rng = XorShiftRng::from_seed(SEED);
+    let mut group = c.benchmark_group("g1");
+    bench_group(&mut group, "bls12_381", &BLS12_381_G1_GENERATOR, &mut rng);
+    bench_group(&mut group, "bls12_377", &BLS12_377_G1_GENERATOR, &mut rng);
+    #[cfg(feature = "eip_196")]
+    bench_group(&mut group, "bn254", &*eth_pairings::engines::bn254::BN254_G1_GENERATOR, &mut rng);
+    group.finish();
+}
+
+fn bench_g2(c: &mut Criterion) {
+    let mut rng = XorShiftRng::from_seed(SEED);
+    let mut group = c.benchmark_group("g2");
+    bench_group(&mut group, "bls12_381", &BLS12_381_G2_GENERATOR, &mut rng);
+    bench_group(&mut group, "bls12_377", &BLS12_377_G2_GENERATOR, &mut rng);
+    #[cfg(feature = "eip_196")]
+    bench_group(&mut group, "bn254", &*eth_pairings::engines::bn254::BN254_G2_GENERATOR, &mut rng);
+    group.finish();
+}
+
+criterion_group!(benches, bench_g1, bench_g2);
+criterion_main!(benches);
@@ -0,0 +1,75 @@
+//! Multiplication and inversion in the base field for every supported number of limbs.
+//! Moduli are random odd numbers of the largest bit length that still fits into the
+//! given number of limbs, same as the API would dispatch them
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use criterion::measurement::WallTime;
+use criterion::BenchmarkGroup;
+use rand::{Rng, SeedableRng};
+use rand_xorshift::XorShiftRng;
+
+use eth_pairings::field::*;
+use eth_pairings::fp::Fp;
+use eth_pairings::integers::MaxFieldUint;
+use eth_pairings::representation::ElementRepr;
+use eth_pairings::traits::FieldElement;
+
+const SEED: [u8; 16] = [0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc, 0xe5];
+
+fn random_bytes<R: Rng>(rng: &mut R, bits: usize) -> Vec<u8> {
+    let mut bytes: Vec<u8> = (0..bits.div_ceil(8)).map(|_| rng.gen()).collect();
+    bytes[0] &= 0xff >> (8 * bytes.len() - bits);
+
+    bytes
+}
+
+fn bench_limbs<E: ElementRepr>(group: &mut BenchmarkGroup<WallTime>, rng: &mut XorShiftRng) {
+    let limbs = E::NUM_LIMBS;
+    let bits = limbs * 64 - 1;
+    let mut modulus = random_bytes(rng, bits);
+    modulus[0] |= 0x80 >> (8 * modulus.len() - bits);
+    *modulus.last_mut().unwrap() |= 1;
+    let field: PrimeField<E> = field_from_modulus(&MaxFieldUint::from_big_endian(&modulus)).unwrap();
+
+    // modulus is not necessarily prime, so take the first element that has an inverse
+    let mut a = Fp::from_be_bytes(&field, &random_bytes(rng, bits - 1), true).unwrap();
+    let b = Fp::from_be_bytes(&field, &random_bytes(rng, bits - 1), true).unwrap();
+    while a.inverse().is_none() {
+        a.add_assign(&b);
+    }
+
+    group.bench_with_input(BenchmarkId::new("mul", limbs), &(a, b), |bencher, (a, b)| {
+        bencher.iter(|| {
+            let mut a = *a;
+            a.mul_assign(b);
+
+            a
+        })
+    });
+
+    group.bench_with_input(BenchmarkId::new("inverse", limbs), &a, |bencher, a| {
+        bencher.iter(|| a.inverse().unwrap())
+    });
+}
+
+fn bench_field(c: &mut Criterion) {
+    let mut rng = XorShiftRng::from_seed(SEED);
+    let mut group = c.benchmark_group("field");
+    bench_limbs::<U256Repr>(&mut group, &mut rng);
+    bench_limbs::<U320Repr>(&mut group, &mut rng);
+    bench_limbs::<U384Repr>(&mut group, &mut rng);
+    bench_limbs::<U448Repr>(&mut group, &mut rng);
+    bench_limbs::<U512Repr>(&mut group, &mut rng);
+    bench_limbs::<U576Repr>(&mut group, &mut rng);
+    bench_limbs::<U640Repr>(&mut group, &mut rng);
+    bench_limbs::<U704Repr>(&mut group, &mut rng);
+    bench_limbs::<U768Repr>(&mut group, &mut rng);
+    bench_limbs::<U832Repr>(&mut group, &mut rng);
+    bench_limbs::<U896Repr>(&mut group, &mut rng);
+    bench_limbs::<U960Repr>(&mut group, &mut rng);
+    bench_limbs::<U1024Repr>(&mut group, &mut rng);
+    group.finish();
+}
+
+criterion_group!(benches, bench_field);
+criterion_main!(benches);
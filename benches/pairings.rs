@@ -0,0 +1,135 @@
+//! Miller loops and final exponentiations for every pairing family. BLS12 and BN curves
+//! use the precomputed engines, MNT4 and MNT6 curves are only available through the byte
+//! interface, so for them a call that stops after the Miller loop is compared with a call
+//! for the full pairing, and the difference is the final exponentiation
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use criterion::measurement::WallTime;
+use criterion::BenchmarkGroup;
+use num_bigint::BigUint;
+use num_traits::Num;
+
+use eth_pairings::pairings::PairingEngine;
+use eth_pairings::engines::bls12_381::*;
+use eth_pairings::engines::bls12_377::*;
+use eth_pairings::public_interface::API;
+use eth_pairings::public_interface::encoding::*;
+
+fn bench_engine<E: PairingEngine>(group: &mut BenchmarkGroup<WallTime>, curve: &str, engine: &E, p: &E::G1, q: &E::G2) {
+    let points = [p.clone()];
+    let twists = [q.clone()];
+
+    group.bench_function(BenchmarkId::new("miller_loop", curve), |bencher| {
+        bencher.iter(|| engine.miller_loop_product(&points, &twists).unwrap())
+    });
+
+    let f = engine.miller_loop_product(&points, &twists).unwrap();
+    group.bench_function(BenchmarkId::new("final_exp", curve), |bencher| {
+        bencher.iter(|| engine.final_exp(&f).unwrap())
+    });
+}
+
+fn decimal(value: &str) -> Vec<u8> {
+    BigUint::from_str_radix(value, 10).unwrap().to_bytes_be()
+}
+
+fn bench_call(group: &mut BenchmarkGroup<WallTime>, curve: &str, call: &mut PairingCall) {
+    let input = call.encode().unwrap();
+    group.bench_function(BenchmarkId::new("pairing", curve), |bencher| {
+        bencher.iter(|| API::run(&input).unwrap())
+    });
+
+    let input = call.miller_loop_only().encode().unwrap();
+    group.bench_function(BenchmarkId::new("miller_loop", curve), |bencher| {
+        bencher.iter(|| API::run(&input).unwrap())
+    });
+}
+
+fn mnt4_298_call() -> PairingCall {
+    let g1 = G1CurveDescriptor::new(
+        &decimal("475922286169261325753349249653048451545124879242694725395555128576210262817955800483758081"),
+        &[2],
+        &decimal("423894536526684178289416011533888240029318103673896002803341544124054745019340795360841685"),
+        &decimal("475922286169261325753349249653048451545124878552823515553267735739164647307408490559963137")
+    ).unwrap();
+    let mut call = PairingCall::mnt4(
+        g1,
+        &[17],
+        &decimal("689871209842287392837045615510547309923794944"),
+        false,
+        &decimal("689871209842287392837045615510547309923794945"),
+        &[1],
+        false
+    ).unwrap();
+    let p = G1Point::new(
+        &decimal("60760244141852568949126569781626075788424196370144486719385562369396875346601926534016838"),
+        &decimal("363732850702582978263902770815145784459747722357071843971107674179038674942891694705904306")
+    );
+    let q = G2Point::new(
+        &[
+            &decimal("438374926219350099854919100077809681842783509163790991847867546339851681564223481322252708"),
+            &decimal("37620953615500480110935514360923278605464476459712393277679280819942849043649216370485641")
+        ],
+        &[
+            &decimal("37437409008528968268352521034936931842973546441370663118543015118291998305624025037512482"),
+            &decimal("424621479598893882672393190337420680597584695892317197646113820787463109735345923009077489")
+        ]
+    );
+    call.add_pair(p, q, false);
+
+    call
+}
+
+fn mnt6_298_call() -> PairingCall {
+    let g1 = G1CurveDescriptor::new(
+        &decimal("475922286169261325753349249653048451545124878552823515553267735739164647307408490559963137"),
+        &[11],
+        &decimal("106700080510851735677967319632585352256454251201367587890185989362936000262606668469523074"),
+        &decimal("475922286169261325753349249653048451545124879242694725395555128576210262817955800483758081")
+    ).unwrap();
+    let mut call = PairingCall::mnt6(
+        g1,
+        &[5],
+        &decimal("689871209842287392837045615510547309923794944"),
+        true,
+        &decimal("689871209842287392837045615510547309923794944"),
+        &[1],
+        true
+    ).unwrap();
+    let p = G1Point::new(
+        &decimal("336685752883082228109289846353937104185698209371404178342968838739115829740084426881123453"),
+        &decimal("402596290139780989709332707716568920777622032073762749862342374583908837063963736098549800")
+    );
+    let q = G2Point::new(
+        &[
+            &decimal("421456435772811846256826561593908322288509115489119907560382401870203318738334702321297427"),
+            &decimal("103072927438548502463527009961344915021167584706439945404959058962657261178393635706405114"),
+            &decimal("143029172143731852627002926324735183809768363301149009204849580478324784395590388826052558")
+        ],
+        &[
+            &decimal("464673596668689463130099227575639512541218133445388869383893594087634649237515554342751377"),
+            &decimal("100642907501977375184575075967118071807821117960152743335603284583254620685343989304941678"),
+            &decimal("123019855502969896026940545715841181300275180157288044663051565390506010149881373807142903")
+        ]
+    );
+    call.add_pair(p, q, false);
+
+    call
+}
+
+fn bench_pairings(c: &mut Criterion) {
+    let mut group = c.benchmark_group("pairings");
+    bench_engine(&mut group, "bls12_381", &BLS12_381_PAIRING_ENGINE, &BLS12_381_G1_GENERATOR, &BLS12_381_G2_GENERATOR);
+    bench_engine(&mut group, "bls12_377", &BLS12_377_PAIRING_ENGINE, &BLS12_377_G1_GENERATOR, &BLS12_377_G2_GENERATOR);
+    #[cfg(feature = "eip_196")]
+    {
+        use eth_pairings::engines::bn254::*;
+        bench_engine(&mut group, "bn254", &*BN254_PAIRING_ENGINE, &BN254_G1_GENERATOR, &BN254_G2_GENERATOR);
+    }
+    bench_call(&mut group, "mnt4_298", &mut mnt4_298_call());
+    bench_call(&mut group, "mnt6_298", &mut mnt6_298_call());
+    group.finish();
+}
+
+criterion_group!(benches, bench_pairings);
+criterion_main!(benches);
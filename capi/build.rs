@@ -0,0 +1,20 @@
+// Header is generated from the module with the C ABI only, everything else in the main crate
+// is either Rust API or other C APIs with headers of their own
+
+use std::env;
+use std::path::PathBuf;
+
+fn main() {
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let ffi = crate_dir.join("../src/public_interface/ffi.rs");
+    println!("cargo:rerun-if-changed={}", ffi.display());
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml")).expect("cbindgen.toml is valid");
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(ffi)
+        .generate()
+        .expect("header is generated")
+        .write_to_file(crate_dir.join("include/eip1962.h"));
+}
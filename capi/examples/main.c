@@ -0,0 +1,80 @@
+/*
+ * Adds the point (1, 2) to itself on y^2 = x^3 + 3 over F_p, p = 2^255 - 19, then shows how
+ * errors are reported by calling the precompile with a truncated input.
+ *
+ * Build and run with `make` in this folder, see the Makefile for the linker flags.
+ */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "eip1962.h"
+
+#define FIELD_LEN 32
+
+static size_t put_number(uint8_t *buf, uint8_t value) {
+  memset(buf, 0, FIELD_LEN);
+  buf[FIELD_LEN - 1] = value;
+
+  return FIELD_LEN;
+}
+
+static size_t g1_add_input(uint8_t *buf) {
+  size_t len = 0;
+
+  buf[len++] = FIELD_LEN;
+  /* modulus */
+  buf[len++] = 0x7f;
+  memset(buf + len, 0xff, FIELD_LEN - 2);
+  len += FIELD_LEN - 2;
+  buf[len++] = 0xed;
+  /* a and b */
+  len += put_number(buf + len, 0);
+  len += put_number(buf + len, 3);
+  /* group order, length then value */
+  buf[len++] = 1;
+  buf[len++] = 0xff;
+  /* two points */
+  for (int i = 0; i < 2; i++) {
+    len += put_number(buf + len, 1);
+    len += put_number(buf + len, 2);
+  }
+
+  return len;
+}
+
+static int run(const uint8_t *input, size_t len) {
+  uint8_t output[EIP1962_RESULT_BUFFER_LEN];
+  size_t out_len = sizeof(output);
+  uint8_t err[EIP1962_ERROR_BUFFER_LEN];
+
+  uint32_t code = eip1962_perform_operation(EIP1962_OPERATION_G1ADD, input, len, output, &out_len, err);
+  if (code != EIP1962_SUCCESS) {
+    printf("error %u: %s\n", code, (const char *) err);
+
+    return 1;
+  }
+
+  printf("0x");
+  for (size_t i = 0; i < out_len; i++) {
+    printf("%02x", output[i]);
+  }
+  printf("\n");
+
+  return 0;
+}
+
+int main(void) {
+  uint8_t input[512];
+  size_t len = g1_add_input(input);
+
+  if (run(input, len) != 0) {
+    return 1;
+  }
+  /* expected to fail with EIP1962_ERROR_INPUT */
+  if (run(input, len - 1) == 0) {
+    return 1;
+  }
+
+  return 0;
+}
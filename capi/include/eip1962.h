@@ -0,0 +1,78 @@
+/* Generated by cbindgen from src/public_interface/ffi.rs, do not edit */
+
+#ifndef eip1962_h
+#define eip1962_h
+
+#include <stddef.h>
+#include <stdint.h>
+
+#define EIP1962_OPERATION_G1ADD 1
+
+#define EIP1962_OPERATION_G1MUL 2
+
+#define EIP1962_OPERATION_G1MULTIEXP 3
+
+#define EIP1962_OPERATION_G2ADD 4
+
+#define EIP1962_OPERATION_G2MUL 5
+
+#define EIP1962_OPERATION_G2MULTIEXP 6
+
+#define EIP1962_OPERATION_BLS12PAIR 7
+
+#define EIP1962_OPERATION_BNPAIR 8
+
+#define EIP1962_OPERATION_MNT4PAIR 9
+
+#define EIP1962_OPERATION_MNT6PAIR 10
+
+#define EIP1962_ERROR_BUFFER_LEN 256
+
+#define EIP1962_RESULT_BUFFER_LEN 768
+
+#define EIP1962_SUCCESS 0
+
+#define EIP1962_ERROR_OVERFLOW 1
+
+#define EIP1962_ERROR_UNEXPECTED_ZERO 2
+
+#define EIP1962_ERROR_INPUT 3
+
+#define EIP1962_ERROR_DIVISION_BY_ZERO 4
+
+#define EIP1962_ERROR_UNKNOWN_PARAMETER 5
+
+#define EIP1962_ERROR_OUTPUT 6
+
+#define EIP1962_ERROR_MISSING_VALUE 7
+
+#define EIP1962_ERROR_UNKNOWN_OPERATION 8
+
+#define EIP1962_ERROR_NULL_POINTER 9
+
+#define EIP1962_ERROR_OUTPUT_BUFFER_TOO_SMALL 10
+
+#define EIP1962_ERROR_INTERNAL 11
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * # Safety
+ *
+ * `input` must point to `len` readable bytes, `output` to `*out_len` writable bytes
+ * and `err_buf` (if not null) to `EIP1962_ERROR_BUFFER_LEN` writable bytes
+ */
+uint32_t eip1962_perform_operation(uint8_t op,
+                                   const uint8_t *input,
+                                   size_t len,
+                                   uint8_t *output,
+                                   size_t *out_len,
+                                   uint8_t *err_buf);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* eip1962_h */
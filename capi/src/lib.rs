@@ -0,0 +1,5 @@
+//! `libeip1962.a` and `libeip1962.so` for C, C++ and Go (cgo) clients. The only entry point is
+//! `eip1962_perform_operation` from `eth_pairings::public_interface::ffi`, the header with it and
+//! the return codes is generated into `include/eip1962.h` by the build script.
+
+pub use eth_pairings::public_interface::ffi::eip1962_perform_operation;
@@ -1,5 +1,11 @@
 use crate::uint::construct_uint;
 
+#[cfg(not(feature = "wide"))]
 construct_uint! {
 	pub struct MaxFieldUint(16);
-}
\ No newline at end of file
+}
+
+#[cfg(feature = "wide")]
+construct_uint! {
+	pub struct MaxFieldUint(32);
+}
@@ -0,0 +1,8 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use eth_pairings::public_interface::API;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = API::run(data);
+});
@@ -0,0 +1,10 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use eth_pairings::public_interface::eip2537::perform_operation;
+use eth_pairings_fuzz::{Eip2537Operation, FixedSizeCall};
+
+fuzz_target!(|call: FixedSizeCall<Eip2537Operation>| {
+    let input = call.encode(call.operation.layout());
+    let _ = perform_operation(call.operation.operation_type(), &input);
+});
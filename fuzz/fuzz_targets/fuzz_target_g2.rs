@@ -0,0 +1,11 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use eth_pairings::public_interface::API;
+use eth_pairings_fuzz::G2Call;
+
+fuzz_target!(|call: G2Call| {
+    if let Some(input) = call.encode() {
+        let _ = API::run(&input);
+    }
+});
@@ -0,0 +1,354 @@
+//! Structured inputs for the fuzz targets. Calls are assembled with `public_interface::encoding`,
+//! so lengths and paddings are consistent and the fuzzer reaches the arithmetic, and then a few
+//! byte level mutations are applied to exercise the length splits in the decoders
+
+use libfuzzer_sys::arbitrary::{self, Arbitrary};
+
+use eth_pairings::public_interface::encoding::*;
+use eth_pairings::public_interface::eip2537::Eip2537OperationType;
+use eth_pairings::public_interface::eip196::Eip196OperationType;
+
+/// Change of the encoded call, offsets are taken modulo the current length
+#[derive(Arbitrary, Debug)]
+pub enum Mutation {
+    Truncate(u16),
+    Append(Vec<u8>),
+    FlipBit(u16, u8),
+    SetByte(u16, u8),
+}
+
+pub fn mutate(mut bytes: Vec<u8>, mutations: &[Mutation]) -> Vec<u8> {
+    for m in mutations.iter() {
+        let len = bytes.len();
+        match m {
+            Mutation::Truncate(offset) => {
+                if len != 0 {
+                    bytes.truncate(*offset as usize % len);
+                }
+            },
+            Mutation::Append(tail) => {
+                bytes.extend_from_slice(tail);
+            },
+            Mutation::FlipBit(offset, bit) => {
+                if len != 0 {
+                    bytes[*offset as usize % len] ^= 1u8 << (bit % 8);
+                }
+            },
+            Mutation::SetByte(offset, value) => {
+                if len != 0 {
+                    bytes[*offset as usize % len] = *value;
+                }
+            },
+        }
+    }
+
+    bytes
+}
+
+#[derive(Arbitrary, Debug)]
+pub struct G1Curve {
+    pub modulus: Vec<u8>,
+    pub a: Vec<u8>,
+    pub b: Vec<u8>,
+    pub group_order: Vec<u8>,
+}
+
+impl G1Curve {
+    pub fn descriptor(&self) -> Option<G1CurveDescriptor> {
+        G1CurveDescriptor::new(&self.modulus, &self.a, &self.b, &self.group_order).ok()
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+pub struct G1Coordinates {
+    pub x: Vec<u8>,
+    pub y: Vec<u8>,
+}
+
+impl G1Coordinates {
+    pub fn point(&self) -> G1Point {
+        G1Point::new(&self.x, &self.y)
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+pub enum GroupOperation<P> {
+    Add(P, P),
+    Mul(P, Vec<u8>),
+    Multiexp(Vec<(P, Vec<u8>)>),
+}
+
+#[derive(Arbitrary, Debug)]
+pub struct G1Call {
+    pub curve: G1Curve,
+    pub operation: GroupOperation<G1Coordinates>,
+    pub mutations: Vec<Mutation>,
+}
+
+impl G1Call {
+    pub fn encode(&self) -> Option<Vec<u8>> {
+        let descriptor = self.curve.descriptor()?;
+        let call = match &self.operation {
+            GroupOperation::Add(p0, p1) => descriptor.add(&p0.point(), &p1.point()),
+            GroupOperation::Mul(p, scalar) => descriptor.mul(&p.point(), scalar),
+            GroupOperation::Multiexp(pairs) => {
+                let pairs: Vec<_> = pairs.iter().map(|(p, s)| (p.point(), s.clone())).collect();
+                descriptor.multiexp(&pairs)
+            }
+        }.ok()?;
+
+        Some(mutate(call, &self.mutations))
+    }
+}
+
+/// Coefficients of an element in Fp2 or Fp3, only the first `extension_degree` are used
+#[derive(Arbitrary, Debug)]
+pub struct ExtensionElement(pub [Vec<u8>; 3]);
+
+impl ExtensionElement {
+    pub fn coefficients(&self, extension_degree: usize) -> Vec<&[u8]> {
+        self.0[..extension_degree].iter().map(|c| &c[..]).collect()
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+pub struct G2Coordinates {
+    pub x: ExtensionElement,
+    pub y: ExtensionElement,
+}
+
+impl G2Coordinates {
+    pub fn point(&self, extension_degree: usize) -> G2Point {
+        G2Point::new(&self.x.coefficients(extension_degree), &self.y.coefficients(extension_degree))
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+pub struct G2Curve {
+    pub modulus: Vec<u8>,
+    pub is_fp3: bool,
+    pub non_residue: Vec<u8>,
+    pub a: ExtensionElement,
+    pub b: ExtensionElement,
+    pub group_order: Vec<u8>,
+}
+
+impl G2Curve {
+    pub fn extension_degree(&self) -> usize {
+        if self.is_fp3 { 3 } else { 2 }
+    }
+
+    pub fn descriptor(&self) -> Option<G2CurveDescriptor> {
+        let degree = self.extension_degree();
+        G2CurveDescriptor::new(
+            &self.modulus,
+            degree,
+            &self.non_residue,
+            &self.a.coefficients(degree),
+            &self.b.coefficients(degree),
+            &self.group_order
+        ).ok()
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+pub struct G2Call {
+    pub curve: G2Curve,
+    pub operation: GroupOperation<G2Coordinates>,
+    pub mutations: Vec<Mutation>,
+}
+
+impl G2Call {
+    pub fn encode(&self) -> Option<Vec<u8>> {
+        let descriptor = self.curve.descriptor()?;
+        let degree = self.curve.extension_degree();
+        let call = match &self.operation {
+            GroupOperation::Add(p0, p1) => descriptor.add(&p0.point(degree), &p1.point(degree)),
+            GroupOperation::Mul(p, scalar) => descriptor.mul(&p.point(degree), scalar),
+            GroupOperation::Multiexp(pairs) => {
+                let pairs: Vec<_> = pairs.iter().map(|(p, s)| (p.point(degree), s.clone())).collect();
+                descriptor.multiexp(&pairs)
+            }
+        }.ok()?;
+
+        Some(mutate(call, &self.mutations))
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+pub struct Fp2TwistParameters {
+    pub fp2_non_residue: Vec<u8>,
+    pub fp6_non_residue: [Vec<u8>; 2],
+    pub twist_type_is_m: bool,
+    pub x: Vec<u8>,
+    pub x_is_negative: bool,
+}
+
+#[derive(Arbitrary, Debug)]
+pub struct MntParameters {
+    pub non_residue: Vec<u8>,
+    pub x: Vec<u8>,
+    pub x_is_negative: bool,
+    pub exp_w0: Vec<u8>,
+    pub exp_w1: Vec<u8>,
+    pub exp_w0_is_negative: bool,
+}
+
+#[derive(Arbitrary, Debug)]
+pub enum PairingFamily {
+    Bls12(Fp2TwistParameters),
+    Bn(Fp2TwistParameters),
+    Mnt4(MntParameters),
+    Mnt6(MntParameters),
+}
+
+#[derive(Arbitrary, Debug)]
+pub struct PairingInput {
+    pub curve: G1Curve,
+    pub family: PairingFamily,
+    pub pairs: Vec<(G1Coordinates, G2Coordinates, bool)>,
+    pub miller_loop_only: bool,
+    pub mutations: Vec<Mutation>,
+}
+
+impl PairingInput {
+    pub fn encode(&self) -> Option<Vec<u8>> {
+        let g1 = self.curve.descriptor()?;
+        let (mut call, extension_degree) = match &self.family {
+            PairingFamily::Bls12(p) | PairingFamily::Bn(p) => {
+                let constructor = match &self.family {
+                    PairingFamily::Bls12(_) => PairingCall::bls12,
+                    _ => PairingCall::bn,
+                };
+                let twist_type = if p.twist_type_is_m { TwistType::M } else { TwistType::D };
+                let call = constructor(
+                    g1,
+                    &p.fp2_non_residue,
+                    [&p.fp6_non_residue[0], &p.fp6_non_residue[1]],
+                    twist_type,
+                    &p.x,
+                    p.x_is_negative
+                ).ok()?;
+
+                (call, 2)
+            },
+            PairingFamily::Mnt4(p) | PairingFamily::Mnt6(p) => {
+                let (constructor, extension_degree): (fn(_, _, _, _, _, _, _) -> _, _) = match &self.family {
+                    PairingFamily::Mnt4(_) => (PairingCall::mnt4, 2),
+                    _ => (PairingCall::mnt6, 3),
+                };
+                let call = constructor(
+                    g1,
+                    &p.non_residue,
+                    &p.x,
+                    p.x_is_negative,
+                    &p.exp_w0,
+                    &p.exp_w1,
+                    p.exp_w0_is_negative
+                ).ok()?;
+
+                (call, extension_degree)
+            }
+        };
+        for (g1, g2, check_subgroup) in self.pairs.iter() {
+            call.add_pair(g1.point(), g2.point(extension_degree), *check_subgroup);
+        }
+        if self.miller_loop_only {
+            call.miller_loop_only();
+        }
+
+        Some(mutate(call.encode().ok()?, &self.mutations))
+    }
+}
+
+/// Fixed size calls: every item is cut or left padded with zeroes to the size of the
+/// element it takes in the input, so inputs are mostly of the valid length
+#[derive(Arbitrary, Debug)]
+pub struct FixedSizeCall<O> {
+    pub operation: O,
+    pub items: Vec<Vec<u8>>,
+    pub mutations: Vec<Mutation>,
+}
+
+impl<O> FixedSizeCall<O> {
+    pub fn encode(&self, layout: &[usize]) -> Vec<u8> {
+        let mut input = vec![];
+        for (item, size) in self.items.iter().zip(layout.iter().cycle()) {
+            if item.len() >= *size {
+                input.extend_from_slice(&item[(item.len() - size)..]);
+            } else {
+                input.extend(std::iter::repeat(0u8).take(size - item.len()));
+                input.extend_from_slice(item);
+            }
+        }
+
+        mutate(input, &self.mutations)
+    }
+}
+
+#[derive(Arbitrary, Debug, Clone, Copy)]
+pub enum Eip2537Operation {
+    G1Add,
+    G1Mul,
+    G1Multiexp,
+    G2Add,
+    G2Mul,
+    G2Multiexp,
+    Pairing,
+    MapFpToG1,
+    MapFp2ToG2,
+}
+
+impl Eip2537Operation {
+    pub fn operation_type(&self) -> Eip2537OperationType {
+        match self {
+            Eip2537Operation::G1Add => Eip2537OperationType::BLS12_G1ADD,
+            Eip2537Operation::G1Mul => Eip2537OperationType::BLS12_G1MUL,
+            Eip2537Operation::G1Multiexp => Eip2537OperationType::BLS12_G1MULTIEXP,
+            Eip2537Operation::G2Add => Eip2537OperationType::BLS12_G2ADD,
+            Eip2537Operation::G2Mul => Eip2537OperationType::BLS12_G2MUL,
+            Eip2537Operation::G2Multiexp => Eip2537OperationType::BLS12_G2MULTIEXP,
+            Eip2537Operation::Pairing => Eip2537OperationType::BLS12_PAIR,
+            Eip2537Operation::MapFpToG1 => Eip2537OperationType::BLS12_FP_TO_G1,
+            Eip2537Operation::MapFp2ToG2 => Eip2537OperationType::BLS12_FP2_TO_G2,
+        }
+    }
+
+    /// Sizes of the items of one operand in bytes
+    pub fn layout(&self) -> &'static [usize] {
+        use eth_pairings::public_interface::eip2537::{SERIALIZED_FP_BYTE_LENGTH as FP, SCALAR_BYTE_LENGTH as SCALAR};
+
+        match self {
+            Eip2537Operation::G1Add => &[FP, FP],
+            Eip2537Operation::G1Mul | Eip2537Operation::G1Multiexp => &[FP, FP, SCALAR],
+            Eip2537Operation::G2Add => &[FP, FP, FP, FP],
+            Eip2537Operation::G2Mul | Eip2537Operation::G2Multiexp => &[FP, FP, FP, FP, SCALAR],
+            Eip2537Operation::Pairing => &[FP, FP, FP, FP, FP, FP],
+            Eip2537Operation::MapFpToG1 | Eip2537Operation::MapFp2ToG2 => &[FP],
+        }
+    }
+}
+
+#[derive(Arbitrary, Debug, Clone, Copy)]
+pub enum Eip196Operation {
+    Add,
+    Mul,
+    Pairing,
+}
+
+impl Eip196Operation {
+    pub fn operation_type(&self) -> Eip196OperationType {
+        match self {
+            Eip196Operation::Add => Eip196OperationType::ADD,
+            Eip196Operation::Mul => Eip196OperationType::MUL,
+            Eip196Operation::Pairing => Eip196OperationType::PAIR,
+        }
+    }
+
+    /// Field elements and scalars have the same size
+    pub fn layout(&self) -> &'static [usize] {
+        use eth_pairings::public_interface::eip196::SERIALIZED_FP_BYTE_LENGTH as FP;
+
+        &[FP]
+    }
+}
@@ -0,0 +1,285 @@
+//! Node.js bindings, e.g. for test generation in JavaScript tooling that wants the exact results
+//! of the precompile. Build with `npm run build`, which also generates `index.js` and `index.d.ts`.
+//!
+//! Functions take the same input as `API::run`: operation byte followed by the operation input.
+//! Numbers are big endian `Buffer`s or `BigInt`s, the builders return the full input.
+//! Errors of `run` and `estimateGas` have the `ErrorCode` name as `code`, errors of the
+//! builders are thrown with the `InvalidArg` code.
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use eth_pairings::public_interface::{API, ApiError};
+use eth_pairings::public_interface::encoding;
+
+type Number = Either<Buffer, BigInt>;
+
+fn into_js_error(error: ApiError) -> Error<String> {
+    Error::new(error.code().name().to_owned(), error.to_string())
+}
+
+fn into_invalid_arg(error: ApiError) -> Error {
+    Error::new(Status::InvalidArg, error.to_string())
+}
+
+fn big_endian(value: &Number) -> Result<Vec<u8>> {
+    match value {
+        Either::A(bytes) => Ok(bytes.to_vec()),
+        Either::B(number) => {
+            if number.sign_bit {
+                return Err(Error::new(Status::InvalidArg, "Numbers must be non-negative".to_owned()));
+            }
+
+            Ok(number.words.iter().rev().flat_map(|w| w.to_be_bytes()).collect())
+        }
+    }
+}
+
+fn big_endian_list(values: &[Number]) -> Result<Vec<Vec<u8>>> {
+    values.iter().map(big_endian).collect()
+}
+
+fn as_slices(values: &[Vec<u8>]) -> Vec<&[u8]> {
+    values.iter().map(|v| &v[..]).collect()
+}
+
+/// Run the precompile on the full input, same as `API::run`
+#[napi]
+pub fn run(input: Buffer) -> std::result::Result<Buffer, Error<String>> {
+    API::run(&input).map(Buffer::from).map_err(into_js_error)
+}
+
+/// Gas for the same input as `run`
+#[napi]
+pub fn estimate_gas(input: Buffer) -> std::result::Result<BigInt, Error<String>> {
+    API::estimate_gas(&input).map(BigInt::from).map_err(into_js_error)
+}
+
+/// Affine point in G1, `null` is the point at infinity
+#[napi(object)]
+pub struct G1Point {
+    pub x: Number,
+    pub y: Number,
+}
+
+/// Affine point in G2, coordinates are lists of coefficients in the extension
+#[napi(object)]
+pub struct G2Point {
+    pub x: Vec<Number>,
+    pub y: Vec<Number>,
+}
+
+#[napi(object)]
+pub struct G1MultiexpPair {
+    pub point: Option<G1Point>,
+    pub scalar: Number,
+}
+
+#[napi(object)]
+pub struct G2MultiexpPair {
+    pub point: Option<G2Point>,
+    pub scalar: Number,
+}
+
+fn g1_point(point: &Option<G1Point>) -> Result<encoding::G1Point> {
+    match point {
+        Some(p) => Ok(encoding::G1Point::new(&big_endian(&p.x)?, &big_endian(&p.y)?)),
+        None => Ok(encoding::G1Point::zero()),
+    }
+}
+
+fn g2_point(point: &Option<G2Point>) -> Result<encoding::G2Point> {
+    match point {
+        Some(p) => {
+            let (x, y) = (big_endian_list(&p.x)?, big_endian_list(&p.y)?);
+
+            Ok(encoding::G2Point::new(&as_slices(&x), &as_slices(&y)))
+        },
+        None => Ok(encoding::G2Point::zero()),
+    }
+}
+
+#[napi]
+pub struct G1Curve {
+    descriptor: encoding::G1CurveDescriptor,
+}
+
+#[napi]
+impl G1Curve {
+    #[napi(constructor)]
+    pub fn new(modulus: Number, a: Number, b: Number, group_order: Number) -> Result<Self> {
+        let descriptor = encoding::G1CurveDescriptor::new(&big_endian(&modulus)?, &big_endian(&a)?, &big_endian(&b)?, &big_endian(&group_order)?).map_err(into_invalid_arg)?;
+
+        Ok(Self { descriptor })
+    }
+
+    #[napi]
+    pub fn add(&self, p0: Option<G1Point>, p1: Option<G1Point>) -> Result<Buffer> {
+        self.descriptor.add(&g1_point(&p0)?, &g1_point(&p1)?).map(Buffer::from).map_err(into_invalid_arg)
+    }
+
+    #[napi]
+    pub fn mul(&self, point: Option<G1Point>, scalar: Number) -> Result<Buffer> {
+        self.descriptor.mul(&g1_point(&point)?, &big_endian(&scalar)?).map(Buffer::from).map_err(into_invalid_arg)
+    }
+
+    #[napi]
+    pub fn multiexp(&self, pairs: Vec<G1MultiexpPair>) -> Result<Buffer> {
+        let pairs = pairs.iter().map(|p| Ok((g1_point(&p.point)?, big_endian(&p.scalar)?))).collect::<Result<Vec<_>>>()?;
+
+        self.descriptor.multiexp(&pairs).map(Buffer::from).map_err(into_invalid_arg)
+    }
+}
+
+#[napi]
+pub struct G2Curve {
+    descriptor: encoding::G2CurveDescriptor,
+}
+
+#[napi]
+impl G2Curve {
+    /// `a` and `b` are lists of `extensionDegree` coefficients
+    #[napi(constructor)]
+    pub fn new(modulus: Number, extension_degree: u32, non_residue: Number, a: Vec<Number>, b: Vec<Number>, group_order: Number) -> Result<Self> {
+        let (a, b) = (big_endian_list(&a)?, big_endian_list(&b)?);
+        let descriptor = encoding::G2CurveDescriptor::new(
+            &big_endian(&modulus)?,
+            extension_degree as usize,
+            &big_endian(&non_residue)?,
+            &as_slices(&a),
+            &as_slices(&b),
+            &big_endian(&group_order)?
+        ).map_err(into_invalid_arg)?;
+
+        Ok(Self { descriptor })
+    }
+
+    #[napi]
+    pub fn add(&self, p0: Option<G2Point>, p1: Option<G2Point>) -> Result<Buffer> {
+        self.descriptor.add(&g2_point(&p0)?, &g2_point(&p1)?).map(Buffer::from).map_err(into_invalid_arg)
+    }
+
+    #[napi]
+    pub fn mul(&self, point: Option<G2Point>, scalar: Number) -> Result<Buffer> {
+        self.descriptor.mul(&g2_point(&point)?, &big_endian(&scalar)?).map(Buffer::from).map_err(into_invalid_arg)
+    }
+
+    #[napi]
+    pub fn multiexp(&self, pairs: Vec<G2MultiexpPair>) -> Result<Buffer> {
+        let pairs = pairs.iter().map(|p| Ok((g2_point(&p.point)?, big_endian(&p.scalar)?))).collect::<Result<Vec<_>>>()?;
+
+        self.descriptor.multiexp(&pairs).map(Buffer::from).map_err(into_invalid_arg)
+    }
+}
+
+fn twist_type(value: &str) -> Result<encoding::TwistType> {
+    match value {
+        "M" => Ok(encoding::TwistType::M),
+        "D" => Ok(encoding::TwistType::D),
+        _ => Err(Error::new(Status::InvalidArg, "Twist type must be \"M\" or \"D\"".to_owned())),
+    }
+}
+
+fn fp6_non_residue(value: &[Number]) -> Result<Vec<Vec<u8>>> {
+    if value.len() != 2 {
+        return Err(Error::new(Status::InvalidArg, "Non-residue for Fp6 must have 2 coefficients".to_owned()));
+    }
+
+    big_endian_list(value)
+}
+
+#[napi]
+pub struct PairingCall {
+    call: encoding::PairingCall,
+}
+
+#[napi]
+impl PairingCall {
+    /// `fp6NonResidue` is a pair of coefficients in Fp2, `twistType` is `"M"` or `"D"`
+    #[napi(factory)]
+    pub fn bls12(g1: &G1Curve, fp2_non_residue: Number, fp6_non_residue: Vec<Number>, twist: String, x: Number, x_is_negative: bool) -> Result<Self> {
+        let fp6_non_residue = self::fp6_non_residue(&fp6_non_residue)?;
+        let call = encoding::PairingCall::bls12(
+            g1.descriptor.clone(),
+            &big_endian(&fp2_non_residue)?,
+            [&fp6_non_residue[0], &fp6_non_residue[1]],
+            twist_type(&twist)?,
+            &big_endian(&x)?,
+            x_is_negative
+        ).map_err(into_invalid_arg)?;
+
+        Ok(Self { call })
+    }
+
+    /// Same as `bls12`, `u` is the curve parameter
+    #[napi(factory)]
+    pub fn bn(g1: &G1Curve, fp2_non_residue: Number, fp6_non_residue: Vec<Number>, twist: String, u: Number, u_is_negative: bool) -> Result<Self> {
+        let fp6_non_residue = self::fp6_non_residue(&fp6_non_residue)?;
+        let call = encoding::PairingCall::bn(
+            g1.descriptor.clone(),
+            &big_endian(&fp2_non_residue)?,
+            [&fp6_non_residue[0], &fp6_non_residue[1]],
+            twist_type(&twist)?,
+            &big_endian(&u)?,
+            u_is_negative
+        ).map_err(into_invalid_arg)?;
+
+        Ok(Self { call })
+    }
+
+    #[napi(factory)]
+    pub fn mnt4(g1: &G1Curve, fp2_non_residue: Number, x: Number, x_is_negative: bool, exp_w0: Number, exp_w1: Number, exp_w0_is_negative: bool) -> Result<Self> {
+        let call = encoding::PairingCall::mnt4(
+            g1.descriptor.clone(),
+            &big_endian(&fp2_non_residue)?,
+            &big_endian(&x)?,
+            x_is_negative,
+            &big_endian(&exp_w0)?,
+            &big_endian(&exp_w1)?,
+            exp_w0_is_negative
+        ).map_err(into_invalid_arg)?;
+
+        Ok(Self { call })
+    }
+
+    #[napi(factory)]
+    pub fn mnt6(g1: &G1Curve, fp3_non_residue: Number, x: Number, x_is_negative: bool, exp_w0: Number, exp_w1: Number, exp_w0_is_negative: bool) -> Result<Self> {
+        let call = encoding::PairingCall::mnt6(
+            g1.descriptor.clone(),
+            &big_endian(&fp3_non_residue)?,
+            &big_endian(&x)?,
+            x_is_negative,
+            &big_endian(&exp_w0)?,
+            &big_endian(&exp_w1)?,
+            exp_w0_is_negative
+        ).map_err(into_invalid_arg)?;
+
+        Ok(Self { call })
+    }
+
+    /// Points are checked for being in the main subgroup unless `checkSubgroup` is `false`
+    #[napi]
+    pub fn add_pair(&mut self, g1: Option<G1Point>, g2: Option<G2Point>, check_subgroup: Option<bool>) -> Result<()> {
+        self.call.add_pair(g1_point(&g1)?, g2_point(&g2)?, check_subgroup.unwrap_or(true));
+
+        Ok(())
+    }
+
+    /// Turns the call into a pairing equation, the pairing of the pair is inverted if `isNegative`
+    #[napi]
+    pub fn add_pair_with_sign(&mut self, g1: Option<G1Point>, g2: Option<G2Point>, is_negative: bool, check_subgroup: Option<bool>) -> Result<()> {
+        self.call.add_pair_with_sign(g1_point(&g1)?, g2_point(&g2)?, check_subgroup.unwrap_or(true), is_negative);
+
+        Ok(())
+    }
+
+    #[napi]
+    pub fn miller_loop_only(&mut self) {
+        self.call.miller_loop_only();
+    }
+
+    #[napi]
+    pub fn encode(&self) -> Result<Buffer> {
+        self.call.encode().map(Buffer::from).map_err(into_invalid_arg)
+    }
+}
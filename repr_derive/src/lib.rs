@@ -244,7 +244,8 @@ fn prime_field_repr_impl(repr: &syn::Ident, limbs: usize) -> proc_macro2::TokenS
         gen
     }
 
-    fn mul_impl(
+    // Schoolbook product into r0, ..., r{2 * limbs - 1}
+    fn wide_mul_impl(
         a: proc_macro2::TokenStream,
         b: proc_macro2::TokenStream,
         limbs: usize,
@@ -277,6 +278,16 @@ fn prime_field_repr_impl(repr: &syn::Ident, limbs: usize) -> proc_macro2::TokenS
             });
         }
 
+        gen
+    }
+
+    fn mul_impl(
+        a: proc_macro2::TokenStream,
+        b: proc_macro2::TokenStream,
+        limbs: usize,
+    ) -> proc_macro2::TokenStream {
+        let mut gen = wide_mul_impl(a, b, limbs);
+
         let mut mont_calling = proc_macro2::TokenStream::new();
         mont_calling.append_separated(
             (0..(limbs * 2)).map(|i| get_temp(i)),
@@ -290,10 +301,55 @@ fn prime_field_repr_impl(repr: &syn::Ident, limbs: usize) -> proc_macro2::TokenS
         gen
     }
 
+    let wide_multiply_impl = wide_mul_impl(quote!{self}, quote!{other}, limbs);
+    let mut wide_lo = proc_macro2::TokenStream::new();
+    wide_lo.append_separated(
+        (0..limbs).map(|i| get_temp(i)),
+        proc_macro2::Punct::new(',', proc_macro2::Spacing::Alone),
+    );
+    let mut wide_hi = proc_macro2::TokenStream::new();
+    wide_hi.append_separated(
+        (limbs..(limbs * 2)).map(|i| get_temp(i)),
+        proc_macro2::Punct::new(',', proc_macro2::Spacing::Alone),
+    );
+    let mut reduce_wide_params = proc_macro2::TokenStream::new();
+    reduce_wide_params.append_separated(
+        (0..limbs)
+            .map(|i| quote!{ self.0[#i] })
+            .chain((0..limbs).map(|i| quote!{ hi.0[#i] })),
+        proc_macro2::Punct::new(',', proc_macro2::Spacing::Alone),
+    );
+
     let squaring_impl = sqr_impl(quote!{self}, limbs);
     let multiply_impl = mul_impl(quote!{self}, quote!{other}, limbs);
     let montgomery_impl = mont_impl(limbs);
 
+    // MULX/ADX backend of the main crate, only for the widths it's implemented for
+    let (asm_multiply, asm_square) = if limbs >= 4 && limbs <= 8 {
+        (
+            quote!{
+                #[cfg(all(feature = "asm", target_arch = "x86_64"))]
+                {
+                    if crate::asm::has_mulx_adx() {
+                        crate::asm::mont_mul(&mut self.0, &other.0, &modulus.0, mont_inv);
+                        return;
+                    }
+                }
+            },
+            quote!{
+                #[cfg(all(feature = "asm", target_arch = "x86_64"))]
+                {
+                    if crate::asm::has_mulx_adx() {
+                        crate::asm::mont_square(&mut self.0, &modulus.0, mont_inv);
+                        return;
+                    }
+                }
+            }
+        )
+    } else {
+        (proc_macro2::TokenStream::new(), proc_macro2::TokenStream::new())
+    };
+
     quote! {
 
         #[derive(Copy, Clone, PartialEq, Eq, Default)]
@@ -334,9 +390,9 @@ fn prime_field_repr_impl(repr: &syn::Ident, limbs: usize) -> proc_macro2::TokenS
             }
         }
 
-        impl ::std::fmt::Debug for #repr
+        impl ::core::fmt::Debug for #repr
         {
-            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
                 write!(f, "0x")?;
                 for i in self.0.iter().rev() {
                     write!(f, "{:016x}", *i)?;
@@ -346,8 +402,8 @@ fn prime_field_repr_impl(repr: &syn::Ident, limbs: usize) -> proc_macro2::TokenS
             }
         }
 
-        impl ::std::fmt::Display for #repr {
-            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        impl ::core::fmt::Display for #repr {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
                 write!(f, "0x")?;
                 for i in self.0.iter().rev() {
                     write!(f, "{:016x}", *i)?;
@@ -374,7 +430,7 @@ fn prime_field_repr_impl(repr: &syn::Ident, limbs: usize) -> proc_macro2::TokenS
         impl From<u64> for #repr {
             #[inline(always)]
             fn from(val: u64) -> #repr {
-                use std::default::Default;
+                use ::core::default::Default;
 
                 let mut repr = Self::default();
                 repr.0[0] = val;
@@ -384,22 +440,22 @@ fn prime_field_repr_impl(repr: &syn::Ident, limbs: usize) -> proc_macro2::TokenS
 
         impl Ord for #repr {
             #[inline(always)]
-            fn cmp(&self, other: &#repr) -> std::cmp::Ordering {
+            fn cmp(&self, other: &#repr) -> ::core::cmp::Ordering {
                 for (a, b) in self.0.iter().rev().zip(other.0.iter().rev()) {
                     if a < b {
-                        return std::cmp::Ordering::Less
+                        return ::core::cmp::Ordering::Less
                     } else if a > b {
-                        return std::cmp::Ordering::Greater
+                        return ::core::cmp::Ordering::Greater
                     }
                 }
 
-                std::cmp::Ordering::Equal
+                ::core::cmp::Ordering::Equal
             }
         }
 
         impl PartialOrd for #repr {
             #[inline(always)]
-            fn partial_cmp(&self, other: &#repr) -> Option<std::cmp::Ordering> {
+            fn partial_cmp(&self, other: &#repr) -> Option<::core::cmp::Ordering> {
                 Some(self.cmp(other))
             }
         }
@@ -464,7 +520,7 @@ fn prime_field_repr_impl(repr: &syn::Ident, limbs: usize) -> proc_macro2::TokenS
                 while n >= 64 {
                     let mut t = 0;
                     for i in self.0.iter_mut().rev() {
-                        std::mem::swap(&mut t, i);
+                        ::core::mem::swap(&mut t, i);
                     }
                     n -= 64;
                 }
@@ -512,7 +568,7 @@ fn prime_field_repr_impl(repr: &syn::Ident, limbs: usize) -> proc_macro2::TokenS
                 while n >= 64 {
                     let mut t = 0;
                     for i in &mut self.0 {
-                        std::mem::swap(&mut t, i);
+                        ::core::mem::swap(&mut t, i);
                     }
                     n -= 64;
                 }
@@ -563,29 +619,53 @@ fn prime_field_repr_impl(repr: &syn::Ident, limbs: usize) -> proc_macro2::TokenS
             #[inline]
             fn mont_mul_assign(&mut self, other: &#repr, modulus: &#repr, mont_inv: u64)
             {
-                #multiply_impl
+                self.mont_mul_assign_with_partial_reduction(other, modulus, mont_inv);
                 self.reduce(modulus);
             }
 
             #[inline]
             fn mont_square(&mut self, modulus: &#repr, mont_inv: u64)
             {
-                #squaring_impl
+                self.mont_square_with_partial_reduction(modulus, mont_inv);
                 self.reduce(modulus);
             }
 
             #[inline]
             fn mont_mul_assign_with_partial_reduction(&mut self, other: &#repr, modulus: &#repr, mont_inv: u64)
             {
+                #asm_multiply
                 #multiply_impl
             }
 
             #[inline]
             fn mont_square_with_partial_reduction(&mut self, modulus: &#repr, mont_inv: u64)
             {
+                #asm_square
                 #squaring_impl
             }
 
+            #[inline]
+            fn mul_wide(&self, other: &#repr) -> (#repr, #repr)
+            {
+                #wide_multiply_impl
+
+                (#repr([#wide_lo]), #repr([#wide_hi]))
+            }
+
+            #[inline]
+            fn mont_reduce_wide(&mut self, hi: &#repr, modulus: &#repr, mont_inv: u64)
+            {
+                let mut r = *self;
+                r.mont_partial_reduce(
+                    modulus,
+                    mont_inv,
+                    #reduce_wide_params
+                );
+                r.reduce(modulus);
+
+                *self = r;
+            }
+
             #[inline(always)]
             fn into_normal_repr(&self, modulus: &#repr, mont_inv: u64) -> #repr {
                 let mut r = *self;
@@ -0,0 +1,3 @@
+#!/bin/sh
+cargo clean -p eth_pairings
+RAYON_NUM_THREADS=4 NUM_SAMPLES=20 cargo test --release --features=gas_metering_mode -- --nocapture --ignored run_order_bit_length_sweep
@@ -0,0 +1,8 @@
+//! Heap allocated types that `std` has in its prelude, so the same code builds
+//! with and without the `std` feature.
+
+pub(crate) use alloc::vec::Vec;
+pub(crate) use alloc::string::String;
+#[cfg(feature = "ffi")]
+pub(crate) use alloc::string::ToString;
+pub(crate) use alloc::borrow::ToOwned;
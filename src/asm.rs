@@ -0,0 +1,182 @@
+//! Montgomery multiplication for 4 to 8 limbs with BMI2 (`MULX`) and ADX (`ADCX`/`ADOX`)
+//! instructions. Support is detected once at runtime, callers in `representation` fall back to
+//! the portable carry chains if the CPU doesn't have them. Results are only partially reduced
+//! (below twice the modulus), same as `mont_partial_reduce`
+
+use core::arch::x86_64::{_addcarryx_u64, _mulx_u64};
+use core::sync::atomic::{AtomicU8, Ordering};
+
+pub(crate) const MIN_LIMBS: usize = 4;
+pub(crate) const MAX_LIMBS: usize = 8;
+
+const UNKNOWN: u8 = 0;
+const UNSUPPORTED: u8 = 1;
+const SUPPORTED: u8 = 2;
+
+static SUPPORT: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+#[inline(always)]
+pub(crate) fn has_mulx_adx() -> bool {
+    match SUPPORT.load(Ordering::Relaxed) {
+        SUPPORTED => true,
+        UNSUPPORTED => false,
+        _ => {
+            let supported = std::is_x86_feature_detected!("bmi2") && std::is_x86_feature_detected!("adx");
+            SUPPORT.store(if supported { SUPPORTED } else { UNSUPPORTED }, Ordering::Relaxed);
+
+            supported
+        }
+    }
+}
+
+/// Returns `a + b * c + carry` and puts the high word into `carry`
+#[inline(always)]
+unsafe fn mac(a: u64, b: u64, c: u64, carry: &mut u64) -> u64 {
+    let mut hi = 0u64;
+    let lo = _mulx_u64(b, c, &mut hi);
+
+    let mut t = 0u64;
+    let c0 = _addcarryx_u64(0, lo, a, &mut t);
+    let mut r = 0u64;
+    let c1 = _addcarryx_u64(0, t, *carry, &mut r);
+
+    // can not overflow as a + b * c + carry < 2^128
+    *carry = hi + (c0 as u64) + (c1 as u64);
+
+    r
+}
+
+#[inline(always)]
+unsafe fn adc(a: u64, b: u64, carry: &mut u64) -> u64 {
+    let mut r = 0u64;
+    *carry = _addcarryx_u64(*carry as u8, a, b, &mut r) as u64;
+
+    r
+}
+
+/// Coarsely integrated operand scanning, `a` is overwritten by `a * b * R^-1`. Modulus has
+/// a spare top bit, so the intermediate sum fits into `N + 1` words
+#[target_feature(enable = "bmi2,adx")]
+unsafe fn mont_mul_cios<const N: usize>(a: &mut [u64; N], b: &[u64; N], modulus: &[u64; N], mont_inv: u64) {
+    debug_assert!(N >= MIN_LIMBS && N <= MAX_LIMBS);
+
+    let mut t = [0u64; N];
+    let mut t_top = 0u64;
+
+    for i in 0..N {
+        let mut carry = 0u64;
+        for j in 0..N {
+            t[j] = mac(t[j], a[j], b[i], &mut carry);
+        }
+        let mut t_hi = 0u64;
+        t_top = adc(t_top, carry, &mut t_hi);
+
+        let k = t[0].wrapping_mul(mont_inv);
+        let mut carry = 0u64;
+        let _ = mac(t[0], k, modulus[0], &mut carry);
+        for j in 1..N {
+            t[j - 1] = mac(t[j], k, modulus[j], &mut carry);
+        }
+        let mut top_carry = 0u64;
+        t[N - 1] = adc(t_top, carry, &mut top_carry);
+        t_top = t_hi + top_carry;
+    }
+
+    debug_assert_eq!(t_top, 0);
+
+    *a = t;
+}
+
+/// Safety is provided by the runtime check, callers must only use it if `has_mulx_adx()`
+/// returned `true`
+#[inline(always)]
+pub(crate) fn mont_mul<const N: usize>(a: &mut [u64; N], b: &[u64; N], modulus: &[u64; N], mont_inv: u64) {
+    debug_assert!(has_mulx_adx());
+    unsafe { mont_mul_cios(a, b, modulus, mont_inv) }
+}
+
+#[inline(always)]
+pub(crate) fn mont_square<const N: usize>(a: &mut [u64; N], modulus: &[u64; N], mont_inv: u64) {
+    let b = *a;
+    mont_mul(a, &b, modulus, mont_inv)
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use rand::{Rng, SeedableRng};
+    use rand_xorshift::XorShiftRng;
+
+    fn to_biguint(limbs: &[u64]) -> BigUint {
+        let mut bytes = vec![];
+        for limb in limbs.iter() {
+            bytes.extend_from_slice(&limb.to_le_bytes());
+        }
+
+        BigUint::from_bytes_le(&bytes)
+    }
+
+    fn random_below<R: Rng, const N: usize>(bound: &BigUint, rng: &mut R) -> [u64; N] {
+        let mut x = [0u64; N];
+        for limb in x.iter_mut() {
+            *limb = rng.gen();
+        }
+        let mut bytes = (to_biguint(&x) % bound).to_bytes_le();
+        bytes.resize(N * 8, 0u8);
+        let mut result = [0u64; N];
+        for (limb, chunk) in result.iter_mut().zip(bytes.chunks(8)) {
+            let mut word = [0u8; 8];
+            word.copy_from_slice(chunk);
+            *limb = u64::from_le_bytes(word);
+        }
+
+        result
+    }
+
+    fn check_for_limbs<const N: usize>(rng: &mut XorShiftRng) {
+        for _ in 0..100 {
+            let mut modulus = [0u64; N];
+            for limb in modulus.iter_mut() {
+                *limb = rng.gen();
+            }
+            modulus[0] |= 1;
+            modulus[N - 1] >>= 1;
+            let p = to_biguint(&modulus);
+
+            let mut inv = 1u64;
+            for _ in 0..63 {
+                inv = inv.wrapping_mul(inv);
+                inv = inv.wrapping_mul(modulus[0]);
+            }
+            inv = inv.wrapping_neg();
+
+            let a: [u64; N] = random_below(&p, rng);
+            let b: [u64; N] = random_below(&p, rng);
+
+            let mut c = a;
+            super::mont_mul(&mut c, &b, &modulus, inv);
+            let c = to_biguint(&c);
+            assert!(c < (&p << 1));
+            assert_eq!((c << (64 * N)) % &p, (to_biguint(&a) * to_biguint(&b)) % &p);
+
+            let mut s = a;
+            super::mont_square(&mut s, &modulus, inv);
+            let s = to_biguint(&s);
+            assert_eq!((s << (64 * N)) % &p, (to_biguint(&a) * to_biguint(&a)) % &p);
+        }
+    }
+
+    #[test]
+    fn test_mont_mul_against_biguint() {
+        if !super::has_mulx_adx() {
+            return;
+        }
+
+        let rng = &mut XorShiftRng::from_seed([0x5d, 0xbe, 0x62, 0x59, 0x8d, 0x31, 0x3d, 0x76, 0x32, 0x37, 0xdb, 0x17, 0xe5, 0xbc, 0x06, 0x54]);
+        check_for_limbs::<4>(rng);
+        check_for_limbs::<5>(rng);
+        check_for_limbs::<6>(rng);
+        check_for_limbs::<7>(rng);
+        check_for_limbs::<8>(rng);
+    }
+}
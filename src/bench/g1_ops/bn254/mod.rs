@@ -8,6 +8,7 @@ use crate::traits::ZeroAndOne;
 use rust_test::Bencher;
 use rust_test::black_box;
 use crate::multiexp::{peppinger};
+use crate::execution_context::ExecutionContext;
 use crate::weierstrass::Group;
 use num_bigint::BigUint;
 use num_traits::Num;
@@ -448,7 +449,7 @@ fn bench_peppinger_bn254(b: &mut Bencher) {
         scalar
     }).collect();
 
-    b.iter(move || peppinger(&bases, scalars.clone()));
+    b.iter(move || peppinger(&bases, scalars.clone(), &ExecutionContext::default()));
 }
 
 #[bench]
@@ -582,7 +583,7 @@ fn bench_peppinger_bn254_g2(b: &mut Bencher) {
         scalar
     }).collect();
 
-    b.iter(move || peppinger(&bases, scalars.clone()));
+    b.iter(move || peppinger(&bases, scalars.clone(), &ExecutionContext::default()));
 }
 
 #[bench]
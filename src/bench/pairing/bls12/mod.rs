@@ -1,5 +1,6 @@
 extern crate test as rust_test;
 use self::rust_test::Bencher;
+use crate::public_interface::ExecutionContext;
 
 use num_bigint::BigUint;
 use num_traits::FromPrimitive;
@@ -129,10 +130,10 @@ fn bench_bls12_pairings_from_vectors(b: &mut Bencher) {
     let curves = read_dir_and_grab_curves("src/test/test_vectors/bls12/");
     assert!(curves.len() != 0);
     for (curve, _) in curves.into_iter() {
-        let calldata = assemble_single_curve_params(curve, 4).unwrap();
+        let calldata = assemble_single_curve_params(&curve, 4, true).unwrap();
         let calldata = rust_test::black_box(calldata);
         b.iter(|| {
-            PublicPairingApi::pair(&(calldata.clone())).unwrap()
+            PublicPairingApi::pair(&(calldata.clone()), &ExecutionContext::default()).unwrap()
         });
     }
 }
\ No newline at end of file
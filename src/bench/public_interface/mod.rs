@@ -1,5 +1,6 @@
 extern crate test as rust_test;
 use self::rust_test::Bencher;
+use crate::public_interface::ExecutionContext;
 
 use crate::public_interface::PairingApi;
 
@@ -10,7 +11,7 @@ fn bench_bls12_381_pairing_2_through_the_api(b: &mut Bencher) {
     let calldata = assemble_bls12_381(2);
 
     b.iter(|| {
-        assert_eq!(crate::public_interface::PublicPairingApi::pair(&calldata).unwrap()[0], 1u8);
+        assert_eq!(crate::public_interface::PublicPairingApi::pair(&calldata, &ExecutionContext::default()).unwrap()[0], 1u8);
     });
 }
 
@@ -21,7 +22,7 @@ fn bench_bls12_381_pairing_4_through_the_api(b: &mut Bencher) {
     let calldata = assemble_bls12_381(4);
 
     b.iter(|| {
-        assert_eq!(crate::public_interface::PublicPairingApi::pair(&calldata).unwrap()[0], 1u8);
+        assert_eq!(crate::public_interface::PublicPairingApi::pair(&calldata, &ExecutionContext::default()).unwrap()[0], 1u8);
     });
 }
 #[bench]
@@ -31,7 +32,7 @@ fn bench_bls12_381_pairing_6_through_the_api(b: &mut Bencher) {
     let calldata = assemble_bls12_381(6);
 
     b.iter(|| {
-        assert_eq!(crate::public_interface::PublicPairingApi::pair(&calldata).unwrap()[0], 1u8);
+        assert_eq!(crate::public_interface::PublicPairingApi::pair(&calldata, &ExecutionContext::default()).unwrap()[0], 1u8);
     });
 }
 
@@ -42,7 +43,7 @@ fn bench_bls12_377_pairing_2_through_the_api(b: &mut Bencher) {
     let calldata = assemble_bls12_377(2);
 
     b.iter(|| {
-        assert_eq!(crate::public_interface::PublicPairingApi::pair(&calldata).unwrap()[0], 1u8);
+        assert_eq!(crate::public_interface::PublicPairingApi::pair(&calldata, &ExecutionContext::default()).unwrap()[0], 1u8);
     });
 }
 
@@ -53,7 +54,7 @@ fn bench_bls12_377_pairing_4_through_the_api(b: &mut Bencher) {
     let calldata = assemble_bls12_377(4);
 
     b.iter(|| {
-        assert_eq!(crate::public_interface::PublicPairingApi::pair(&calldata).unwrap()[0], 1u8);
+        assert_eq!(crate::public_interface::PublicPairingApi::pair(&calldata, &ExecutionContext::default()).unwrap()[0], 1u8);
     });
 }
 
@@ -64,7 +65,7 @@ fn bench_bls12_377_pairing_6_through_the_api(b: &mut Bencher) {
     let calldata = assemble_bls12_377(6);
 
     b.iter(|| {
-        assert_eq!(crate::public_interface::PublicPairingApi::pair(&calldata).unwrap()[0], 1u8);
+        assert_eq!(crate::public_interface::PublicPairingApi::pair(&calldata, &ExecutionContext::default()).unwrap()[0], 1u8);
     });
 }
 
@@ -75,7 +76,7 @@ fn bench_bn254_pairing_4_through_the_api(b: &mut Bencher) {
     let calldata = assemble_bn254(4);
 
     b.iter(|| {
-        assert_eq!(crate::public_interface::PublicPairingApi::pair(&calldata).unwrap()[0], 1u8);
+        assert_eq!(crate::public_interface::PublicPairingApi::pair(&calldata, &ExecutionContext::default()).unwrap()[0], 1u8);
     });
 }
 
@@ -86,8 +87,8 @@ fn bench_mnt4_753_pairing_4_through_the_api(b: &mut Bencher) {
     let calldata = assemble_mnt4_753(4);
 
     b.iter(|| {
-        // crate::public_interface::PublicPairingApi::pair(&calldata).unwrap();
-        assert_eq!(crate::public_interface::PublicPairingApi::pair(&calldata).unwrap()[0], 1u8);
+        // crate::public_interface::PublicPairingApi::pair(&calldata, &ExecutionContext::default()).unwrap();
+        assert_eq!(crate::public_interface::PublicPairingApi::pair(&calldata, &ExecutionContext::default()).unwrap()[0], 1u8);
     });
 }
 
@@ -98,8 +99,8 @@ fn bench_mnt4_753_pairing_1_through_the_api(b: &mut Bencher) {
     let calldata = assemble_mnt4_753(1);
 
     b.iter(|| {
-        // crate::public_interface::PublicPairingApi::pair(&calldata).unwrap();
-        assert_eq!(crate::public_interface::PublicPairingApi::pair(&calldata).unwrap()[0], 0u8);
+        // crate::public_interface::PublicPairingApi::pair(&calldata, &ExecutionContext::default()).unwrap();
+        assert_eq!(crate::public_interface::PublicPairingApi::pair(&calldata, &ExecutionContext::default()).unwrap()[0], 0u8);
     });
 }
 
@@ -110,7 +111,7 @@ fn bench_bls12_381_pairing_1_through_the_api(b: &mut Bencher) {
     let calldata = assemble_bls12_381(1);
 
     b.iter(|| {
-        assert_eq!(crate::public_interface::PublicPairingApi::pair(&calldata).unwrap()[0], 0u8);
+        assert_eq!(crate::public_interface::PublicPairingApi::pair(&calldata, &ExecutionContext::default()).unwrap()[0], 0u8);
     });
 }
 
@@ -121,6 +122,6 @@ fn bench_bls12_381_pairing_through_the_api_no_actual_pairing(b: &mut Bencher) {
     let calldata = assemble_bls12_381(0);
 
     b.iter(|| {
-        crate::public_interface::PublicPairingApi::pair(&calldata).expect_err("must fail for 0 pairs");
+        crate::public_interface::PublicPairingApi::pair(&calldata, &ExecutionContext::default()).expect_err("must fail for 0 pairs");
     });
 }
\ No newline at end of file
@@ -0,0 +1,90 @@
+//! Runs a single call through `API::run` and prints the output as hex, or the error code, the
+//! offset in the input where decoding failed and the description. Intended for debugging
+//! mismatches with other implementations without writing a harness.
+//!
+//! Usage: `eip1962-cli [--operation <byte>] [file]`. Calldata is read as hex from the file, or
+//! from the standard input if there is no file or it's `-`, with optional `0x` prefix and any
+//! whitespace in between. Without `--operation` the first byte of the calldata is the operation
+//! type, same as in `API::run`. Exit code is 0 on success, 1 if the call failed and 2 if the
+//! calldata could not be read.
+
+use std::fs;
+use std::io::Read;
+
+use eth_pairings::public_interface::{API, ErrorCode};
+
+const USAGE: &str = "Usage: eip1962-cli [--operation <byte>] [file]";
+
+fn from_hex(string: &str) -> Option<Vec<u8>> {
+    let string: String = string.split_whitespace().collect();
+    let string = string.strip_prefix("0x").unwrap_or(&string);
+
+    hex::decode(string).ok()
+}
+
+/// Decimal or hex with `0x` prefix
+fn parse_operation(string: &str) -> Option<u8> {
+    match string.strip_prefix("0x") {
+        Some(hex) => u8::from_str_radix(hex, 16).ok(),
+        None => string.parse().ok(),
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(2);
+}
+
+fn main() {
+    let mut operation = None;
+    let mut path = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--operation" | "-o" => {
+                let value = args.next().unwrap_or_else(|| fail(USAGE));
+                operation = Some(parse_operation(&value).unwrap_or_else(|| fail(&format!("Invalid operation {}", value))));
+            },
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                return;
+            },
+            _ if path.is_none() => path = Some(arg),
+            _ => fail(USAGE),
+        }
+    }
+
+    let contents = match path.as_deref() {
+        None | Some("-") => {
+            let mut contents = String::new();
+            if let Err(e) = std::io::stdin().read_to_string(&mut contents) {
+                fail(&format!("Failed to read the standard input: {}", e));
+            }
+
+            contents
+        },
+        Some(path) => fs::read_to_string(path).unwrap_or_else(|e| fail(&format!("Failed to read {}: {}", path, e))),
+    };
+    let calldata = from_hex(&contents).unwrap_or_else(|| fail("Calldata is not a hex string"));
+
+    let mut input = vec![];
+    input.extend(operation);
+    input.extend(calldata);
+
+    match API::run(&input) {
+        Ok(output) => println!("0x{}", hex::encode(&output)),
+        Err(e) => {
+            let code = e.code();
+            println!("error: {}", code.name());
+            if let ErrorCode::InputTooShort { expected, got } = code {
+                println!("expected: {}", expected);
+                println!("got: {}", got);
+            }
+            if let Some(offset) = e.offset(input.len()) {
+                println!("offset: {}", offset);
+            }
+            println!("description: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,12 @@
+//! Serves the API over JSON-RPC, see `eth_pairings::server` for the methods.
+//!
+//! Usage: `eip1962-server [address]`, by default listens on `127.0.0.1:1962`
+
+fn main() {
+    let address = std::env::args().nth(1).unwrap_or_else(|| "127.0.0.1:1962".to_owned());
+    eprintln!("Listening on {}", address);
+    if let Err(e) = eth_pairings::server::serve(&address) {
+        eprintln!("Failed to listen on {}: {}", address, e);
+        std::process::exit(1);
+    }
+}
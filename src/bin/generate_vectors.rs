@@ -0,0 +1,355 @@
+//! Walks the test vectors directory and writes fixtures for other implementations of the precompile.
+//! Every fixture has the full precompile input and either the expected output or the expected
+//! error code, both produced by this implementation. Where a vector already records a result
+//! the produced one is checked against it and differences are reported as warnings, since some
+//! vectors predate the current encoding and limits. Fixtures always follow the current behaviour.
+//!
+//! Usage: `generate_vectors [vectors directory] [output file]`, by default vectors are taken
+//! from `src/test/test_vectors` and fixtures are printed to the standard output
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use num_bigint::BigUint;
+use num_traits::Num;
+use serde::Serialize;
+use serde_json::Value;
+
+use eth_pairings::public_interface::{API, ApiError};
+use eth_pairings::public_interface::encoding::*;
+use eth_pairings::public_interface::eip2537::{perform_operation, Eip2537OperationType};
+
+#[derive(Clone, Copy)]
+enum Precompile {
+    Eip1962,
+    Eip2537(Eip2537OperationType),
+}
+
+impl Precompile {
+    fn name(&self) -> &'static str {
+        match self {
+            Precompile::Eip1962 => "eip1962",
+            Precompile::Eip2537(op) => match op {
+                Eip2537OperationType::BLS12_G1ADD => "eip2537_g1_add",
+                Eip2537OperationType::BLS12_G1MUL => "eip2537_g1_mul",
+                Eip2537OperationType::BLS12_G1MULTIEXP => "eip2537_g1_multiexp",
+                Eip2537OperationType::BLS12_G2ADD => "eip2537_g2_add",
+                Eip2537OperationType::BLS12_G2MUL => "eip2537_g2_mul",
+                Eip2537OperationType::BLS12_G2MULTIEXP => "eip2537_g2_multiexp",
+                Eip2537OperationType::BLS12_PAIR => "eip2537_pairing",
+                Eip2537OperationType::BLS12_FP_TO_G1 => "eip2537_map_fp_to_g1",
+                Eip2537OperationType::BLS12_FP2_TO_G2 => "eip2537_map_fp2_to_g2",
+            }
+        }
+    }
+
+    fn run(&self, input: &[u8]) -> Result<Vec<u8>, ApiError> {
+        match self {
+            Precompile::Eip1962 => API::run(input),
+            Precompile::Eip2537(op) => perform_operation(*op, input),
+        }
+    }
+
+    /// EIP-2537 vectors are one file per operation, negative ones are named after the failure
+    fn for_eip2537_file(stem: &str) -> Option<Self> {
+        let op = match stem {
+            "g1_add" => Eip2537OperationType::BLS12_G1ADD,
+            "g1_mul" | "g1_not_on_curve" => Eip2537OperationType::BLS12_G1MUL,
+            "g1_multiexp" => Eip2537OperationType::BLS12_G1MULTIEXP,
+            "g2_add" => Eip2537OperationType::BLS12_G2ADD,
+            "g2_mul" | "g2_not_on_curve" => Eip2537OperationType::BLS12_G2MUL,
+            "g2_multiexp" => Eip2537OperationType::BLS12_G2MULTIEXP,
+            "pairing" | "invalid_subgroup_for_pairing" => Eip2537OperationType::BLS12_PAIR,
+            "fp_to_g1" | "invalid_fp_encoding" => Eip2537OperationType::BLS12_FP_TO_G1,
+            "fp2_to_g2" | "invalid_fp2_encoding" => Eip2537OperationType::BLS12_FP2_TO_G2,
+            _ => return None
+        };
+
+        Some(Precompile::Eip2537(op))
+    }
+}
+
+#[derive(Serialize)]
+struct Fixture {
+    name: String,
+    precompile: &'static str,
+    input: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expected_output: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expected_error: Option<&'static str>,
+}
+
+/// Result recorded next to the input in the source vector
+enum Recorded {
+    Nothing,
+    Output(Vec<u8>),
+    /// Parameters may be outside of the limits of the precompile, then the error is expected
+    OutputIfAccepted(Vec<u8>),
+    Failure,
+}
+
+struct Generator {
+    fixtures: Vec<Fixture>,
+    mismatches: Vec<String>,
+}
+
+impl Generator {
+    fn add(&mut self, name: String, precompile: Precompile, input: &[u8], recorded: Recorded) {
+        let result = precompile.run(input);
+        match (&result, &recorded) {
+            (Ok(output), Recorded::Output(expected)) | (Ok(output), Recorded::OutputIfAccepted(expected)) if output != expected => {
+                self.mismatches.push(format!("{}: output 0x{} is different from recorded 0x{}", name, to_hex(output), to_hex(expected)));
+            },
+            (Ok(_), Recorded::Failure) => {
+                self.mismatches.push(format!("{}: succeeded while a failure is recorded", name));
+            },
+            (Err(err), Recorded::Output(_)) => {
+                self.mismatches.push(format!("{}: failed with {} while an output is recorded", name, err));
+            },
+            _ => {}
+        }
+
+        let (expected_output, expected_error) = match result {
+            Ok(output) => (Some(format!("0x{}", to_hex(&output))), None),
+            Err(err) => (None, Some(err.code().name())),
+        };
+
+        self.fixtures.push(Fixture {
+            name,
+            precompile: precompile.name(),
+            input: format!("0x{}", to_hex(input)),
+            expected_output,
+            expected_error,
+        });
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(string: &str) -> Option<Vec<u8>> {
+    let string = string.trim();
+    let string = string.strip_prefix("0x").unwrap_or(string);
+    if string.len() % 2 != 0 {
+        return None;
+    }
+
+    (0..string.len()).step_by(2).map(|i| u8::from_str_radix(string.get(i..i + 2)?, 16).ok()).collect()
+}
+
+/// Hex number from the curve description, `-` prefix is returned as a flag
+fn parse_signed_number(value: &Value) -> Result<(BigUint, bool), String> {
+    let string = value.as_str().ok_or_else(|| format!("{} is not a string", value))?.trim().to_ascii_lowercase();
+    let (string, is_negative) = match string.strip_prefix('-') {
+        Some(s) => (s.to_owned(), true),
+        None => (string.trim_start_matches('+').to_owned(), false),
+    };
+    let string = string.strip_prefix("0x").unwrap_or(&string);
+    // values exported from Python may have a trailing `L`
+    let string = string.strip_suffix('l').unwrap_or(string);
+    let number = BigUint::from_str_radix(string, 16).map_err(|_| format!("{} is not a hex number", value))?;
+
+    Ok((number, is_negative))
+}
+
+fn parse_number(value: &Value) -> Result<BigUint, String> {
+    match parse_signed_number(value)? {
+        (number, false) => Ok(number),
+        (_, true) => Err(format!("{} must not be negative", value)),
+    }
+}
+
+/// Reads a field element, negative values are taken modulo `modulus`
+fn field_element(curve: &Value, key: &str, modulus: &BigUint) -> Result<Vec<u8>, String> {
+    let (number, is_negative) = parse_signed_number(&curve[key]).map_err(|e| format!("{}: {}", key, e))?;
+    let number = if is_negative { modulus - number } else { number };
+
+    Ok(number.to_bytes_be())
+}
+
+fn number(curve: &Value, key: &str) -> Result<Vec<u8>, String> {
+    Ok(parse_number(&curve[key]).map_err(|e| format!("{}: {}", key, e))?.to_bytes_be())
+}
+
+/// Fixed length encoding of a point as returned by the precompile
+fn encode_coordinates(coordinates: &[Vec<u8>], modulus_len: usize) -> Vec<u8> {
+    let mut encoding = vec![];
+    for c in coordinates.iter() {
+        encoding.extend(std::iter::repeat(0u8).take(modulus_len - c.len()));
+        encoding.extend(c.iter());
+    }
+
+    encoding
+}
+
+/// Scalar multiplications from the curve description and a pairing of generators that
+/// must be equal to one
+fn add_curve(generator: &mut Generator, name: &str, curve_type: &str, curve: &Value) -> Result<(), String> {
+    let modulus = parse_number(&curve["q"])?;
+    let q = modulus.to_bytes_be();
+    let order = number(curve, "r")?;
+
+    let g1 = G1CurveDescriptor::new(&q, &field_element(curve, "A", &modulus)?, &field_element(curve, "B", &modulus)?, &order)
+        .map_err(|e| e.to_string())?;
+    let modulus_len = g1.modulus_len();
+
+    let non_residue = field_element(curve, "non_residue", &modulus)?;
+    let a_twist = [number(curve, "A_twist_0")?, number(curve, "A_twist_1")?];
+    let b_twist = [number(curve, "B_twist_0")?, number(curve, "B_twist_1")?];
+    let g2 = G2CurveDescriptor::new(
+        &q,
+        2,
+        &non_residue,
+        &[&a_twist[0], &a_twist[1]],
+        &[&b_twist[0], &b_twist[1]],
+        &order
+    ).map_err(|e| e.to_string())?;
+
+    let empty = vec![];
+    for (i, v) in curve["g1_scalar_mult_test_vectors"].as_array().unwrap_or(&empty).iter().enumerate() {
+        let base = G1Point::new(&number(v, "g_x")?, &number(v, "g_y")?);
+        let input = g1.mul(&base, &number(v, "a")?).map_err(|e| e.to_string())?;
+        let expected = encode_coordinates(&[number(v, "h_x")?, number(v, "h_y")?], modulus_len);
+        generator.add(format!("{}:g1_mul:{}", name, i), Precompile::Eip1962, &input, Recorded::Output(expected));
+    }
+
+    for (i, v) in curve["g2_scalar_mult_test_vectors"].as_array().unwrap_or(&empty).iter().enumerate() {
+        let base = G2Point::new(&[&number(v, "g_x_0")?, &number(v, "g_x_1")?], &[&number(v, "g_y_0")?, &number(v, "g_y_1")?]);
+        let input = g2.mul(&base, &number(v, "a")?).map_err(|e| e.to_string())?;
+        let expected = encode_coordinates(&[number(v, "h_x_0")?, number(v, "h_x_1")?, number(v, "h_y_0")?, number(v, "h_y_1")?], modulus_len);
+        generator.add(format!("{}:g2_mul:{}", name, i), Precompile::Eip1962, &input, Recorded::Output(expected));
+    }
+
+    let fp6_non_residue = [field_element(curve, "quadratic_non_residue_0", &modulus)?, field_element(curve, "quadratic_non_residue_1", &modulus)?];
+    let twist_type = if curve["is_D_type"].as_str() == Some("True") { TwistType::D } else { TwistType::M };
+    let (x, x_is_negative) = parse_signed_number(&curve["x"])?;
+    let pairing_for_type = match curve_type {
+        "bls12" => PairingCall::bls12,
+        "bn" => PairingCall::bn,
+        _ => return Err(format!("unknown curve type {}", curve_type)),
+    };
+    let mut pairing = pairing_for_type(g1, &non_residue, [&fp6_non_residue[0], &fp6_non_residue[1]], twist_type, &x.to_bytes_be(), x_is_negative)
+        .map_err(|e| e.to_string())?;
+
+    let g1_x = number(curve, "g1_x")?;
+    let g1_y = parse_number(&curve["g1_y"])?;
+    let g2_generator = G2Point::new(&[&number(curve, "g2_x_0")?, &number(curve, "g2_x_1")?], &[&number(curve, "g2_y_0")?, &number(curve, "g2_y_1")?]);
+    pairing
+        .add_pair(G1Point::new(&g1_x, &g1_y.to_bytes_be()), g2_generator.clone(), true)
+        .add_pair(G1Point::new(&g1_x, &(&modulus - &g1_y).to_bytes_be()), g2_generator, true);
+    let input = pairing.encode().map_err(|e| e.to_string())?;
+    generator.add(format!("{}:pairing", name), Precompile::Eip1962, &input, Recorded::OutputIfAccepted(vec![1u8]));
+
+    Ok(())
+}
+
+/// Vectors are `input,result` lines, result is either the output or a failure description
+fn add_csv(generator: &mut Generator, name: &str, precompile: Precompile, contents: &str) -> Result<(), String> {
+    for (i, line) in contents.lines().skip(1).enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (input, result) = match line.find(',') {
+            Some(pos) => (&line[..pos], line[pos + 1..].trim()),
+            None => (line, ""),
+        };
+        let input = from_hex(input).ok_or_else(|| format!("line {} has invalid input", i + 2))?;
+        let recorded = if result.is_empty() {
+            Recorded::Nothing
+        } else {
+            match from_hex(result) {
+                Some(output) => Recorded::Output(output),
+                None => Recorded::Failure,
+            }
+        };
+        generator.add(format!("{}:{}", name, i), precompile, &input, recorded);
+    }
+
+    Ok(())
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+fn add_file(generator: &mut Generator, root: &Path, path: &Path) -> Result<(), String> {
+    let name = path.strip_prefix(root).unwrap_or(path).to_string_lossy().replace('\\', "/");
+    let components: Vec<&str> = name.split('/').collect();
+    let stem = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+    let extension = path.extension().map(|s| s.to_string_lossy().into_owned());
+
+    match (components[0], extension.as_deref()) {
+        ("eip2537", Some("csv")) => {
+            let precompile = Precompile::for_eip2537_file(&stem).ok_or_else(|| format!("unknown operation for {}", name))?;
+            add_csv(generator, &name, precompile, &fs::read_to_string(path).map_err(|e| e.to_string())?)
+        },
+        (_, Some("csv")) => {
+            add_csv(generator, &name, Precompile::Eip1962, &fs::read_to_string(path).map_err(|e| e.to_string())?)
+        },
+        (curve_type, Some("curve")) => {
+            let curve: Value = serde_json::from_str(&fs::read_to_string(path).map_err(|e| e.to_string())?).map_err(|e| e.to_string())?;
+            add_curve(generator, &name, curve_type, &curve)
+        },
+        // fuzzing corpus entries are raw inputs
+        (_, None) if components.contains(&"fuzzing_corpus") => {
+            generator.add(name.clone(), Precompile::Eip1962, &fs::read(path).map_err(|e| e.to_string())?, Recorded::Nothing);
+
+            Ok(())
+        },
+        _ => {
+            eprintln!("Skipping {}", name);
+
+            Ok(())
+        }
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let root = PathBuf::from(args.get(1).map(|s| s.as_str()).unwrap_or("src/test/test_vectors"));
+
+    let mut files = vec![];
+    if let Err(e) = collect_files(&root, &mut files) {
+        eprintln!("Failed to read {}: {}", root.display(), e);
+        std::process::exit(1);
+    }
+    files.sort();
+
+    let mut generator = Generator {
+        fixtures: vec![],
+        mismatches: vec![],
+    };
+    for path in files.iter() {
+        if let Err(e) = add_file(&mut generator, &root, path) {
+            eprintln!("Failed to generate vectors from {}: {}", path.display(), e);
+            std::process::exit(1);
+        }
+    }
+
+    for m in generator.mismatches.iter() {
+        eprintln!("Warning: {}", m);
+    }
+
+    let json = serde_json::to_string_pretty(&generator.fixtures).expect("fixtures are serializable");
+    match args.get(2) {
+        Some(output) => {
+            if let Err(e) = fs::write(output, json) {
+                eprintln!("Failed to write {}: {}", output, e);
+                std::process::exit(1);
+            }
+        },
+        None => println!("{}", json),
+    }
+    eprintln!("Generated {} fixtures", generator.fixtures.len());
+}
@@ -0,0 +1,446 @@
+use crate::traits::{FieldElement, BitIterator, ZeroizeSecret};
+use crate::weierstrass::{CurveParameters, Group};
+use crate::traits::ZeroAndOne;
+use crate::integers::MaxGroupSizeUint;
+use crate::execution_context::ExecutionContext;
+use crate::alloc_prelude::*;
+
+/// Non-supersingular curve y^2 + x*y = x^3 + a*x^2 + b over a binary field. Such curve
+/// is non-singular iff b != 0. Points are kept in homogeneous projective coordinates
+/// (X : Y : Z) with x = X/Z and y = Y/Z, and point at infinity is (0 : 1 : 0)
+pub struct BinaryCurve<'a, C: CurveParameters> {
+    pub(crate) a: C::BaseFieldElement,
+    pub(crate) b: C::BaseFieldElement,
+    pub(crate) subgroup_order_repr: &'a [u64],
+    pub(crate) params: &'a C,
+}
+
+impl<'a, C: CurveParameters> Clone for BinaryCurve<'a, C> {
+    fn clone(&self) -> Self {
+        Self {
+            a: self.a.clone(),
+            b: self.b.clone(),
+            subgroup_order_repr: self.subgroup_order_repr,
+            params: self.params,
+        }
+    }
+}
+
+impl<'a, C: CurveParameters> BinaryCurve<'a, C> {
+    pub(crate) fn new(
+        subgroup_order: &'a [u64],
+        a: C::BaseFieldElement,
+        b: C::BaseFieldElement,
+        params: &'a C
+    ) -> Result<Self, ()> {
+        if b.is_zero() {
+            return Err(());
+        }
+
+        Ok(Self {
+            a: a,
+            b: b,
+            subgroup_order_repr: subgroup_order,
+            params: params,
+        })
+    }
+}
+
+pub struct BinaryCurvePoint<'a, C: CurveParameters> {
+    pub(crate) curve: &'a BinaryCurve<'a, C>,
+    pub(crate) x: C::BaseFieldElement,
+    pub(crate) y: C::BaseFieldElement,
+    pub(crate) z: C::BaseFieldElement,
+}
+
+#[cfg(feature = "zeroize")]
+impl<'a, C: CurveParameters> zeroize::Zeroize for BinaryCurvePoint<'a, C> {
+    fn zeroize(&mut self) {
+        self.x.zeroize_secret();
+        self.y.zeroize_secret();
+        self.z.zeroize_secret();
+    }
+}
+
+impl<'a, C: CurveParameters> Clone for BinaryCurvePoint<'a, C> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self {
+            curve: &self.curve,
+            x: self.x.clone(),
+            y: self.y.clone(),
+            z: self.z.clone()
+        }
+    }
+}
+
+impl<'a, C: CurveParameters> BinaryCurvePoint<'a, C> {
+    pub fn zero(curve: &'a BinaryCurve<C>) -> Self {
+        Self {
+            curve: curve,
+            x: C::BaseFieldElement::zero(curve.params.params()),
+            y: C::BaseFieldElement::one(curve.params.params()),
+            z: C::BaseFieldElement::zero(curve.params.params()),
+        }
+    }
+
+    /// Same as for Weierstrass curves over prime fields (0, 0) encodes the point at infinity,
+    /// it's never on the curve as b != 0
+    pub fn point_from_xy(
+        curve: &'a BinaryCurve<'a, C>,
+        x: C::BaseFieldElement,
+        y: C::BaseFieldElement,
+    ) -> BinaryCurvePoint<'a, C> {
+        if x.is_zero() && y.is_zero() {
+            return Self::zero(curve);
+        }
+
+        BinaryCurvePoint {
+            curve: curve,
+            x: x,
+            y: y,
+            z: C::BaseFieldElement::one(curve.params.params())
+        }
+    }
+
+    /// Y^2*Z + X*Y*Z == X^3 + a*X^2*Z + b*Z^3
+    pub fn is_on_curve(&self) -> bool {
+        if self.is_zero() {
+            return true;
+        }
+
+        let mut z_2 = self.z.clone();
+        z_2.square();
+
+        let mut lhs = self.y.clone();
+        lhs.add_assign(&self.x);
+        lhs.mul_assign(&self.y);
+        lhs.mul_assign(&self.z);
+
+        let mut x_2 = self.x.clone();
+        x_2.square();
+
+        // X^3 + a*X^2*Z
+        let mut t = self.curve.a.clone();
+        t.mul_assign(&self.z);
+        t.add_assign(&self.x);
+        let mut rhs = x_2;
+        rhs.mul_assign(&t);
+
+        // b*Z^3
+        let mut t = z_2;
+        t.mul_assign(&self.z);
+        t.mul_assign(&self.curve.b);
+        rhs.add_assign(&t);
+
+        lhs == rhs
+    }
+
+    pub fn is_normalized(&self) -> bool {
+        if self.is_zero() {
+            return true;
+        }
+
+        let one = C::BaseFieldElement::one(self.curve.params.params());
+
+        self.z == one
+    }
+
+    pub fn normalize(&mut self) {
+        if self.is_normalized() {
+            return;
+        }
+
+        let z_inv = self.z.inverse().unwrap();
+        self.x.mul_assign(&z_inv);
+        self.y.mul_assign(&z_inv);
+        self.z = C::BaseFieldElement::one(self.curve.params.params());
+    }
+
+    pub fn into_xy(&self) -> (C::BaseFieldElement, C::BaseFieldElement) {
+        if self.is_zero() {
+            return (
+                C::BaseFieldElement::zero(self.curve.params.params()),
+                C::BaseFieldElement::zero(self.curve.params.params())
+            );
+        }
+
+        let mut point = self.clone();
+        point.normalize();
+
+        (point.x, point.y)
+    }
+
+    fn add_assign_impl(&mut self, other: &Self) {
+        if other.is_zero() {
+            return;
+        }
+        if self.is_zero() {
+            *self = other.clone();
+            return;
+        }
+
+        // u = Y1*Z2 + Y2*Z1
+        let mut y1z2 = self.y.clone();
+        y1z2.mul_assign(&other.z);
+        let mut u = other.y.clone();
+        u.mul_assign(&self.z);
+        u.add_assign(&y1z2);
+
+        // v = X1*Z2 + X2*Z1
+        let mut x1z2 = self.x.clone();
+        x1z2.mul_assign(&other.z);
+        let mut v = other.x.clone();
+        v.mul_assign(&self.z);
+        v.add_assign(&x1z2);
+
+        if v.is_zero() {
+            if u.is_zero() {
+                // P + P
+                self.double_impl();
+            } else {
+                // P + (-P)
+                *self = Self::zero(self.curve);
+            }
+
+            return;
+        }
+
+        // w = Z1*Z2
+        let mut w = self.z.clone();
+        w.mul_assign(&other.z);
+
+        let mut vv = v.clone();
+        vv.square();
+        let mut vvv = vv.clone();
+        vvv.mul_assign(&v);
+
+        // A = (u^2 + u*v + a*v^2)*w + v^3
+        let mut big_a = u.clone();
+        big_a.add_assign(&v);
+        big_a.mul_assign(&u);
+        let mut t = vv.clone();
+        t.mul_assign(&self.curve.a);
+        big_a.add_assign(&t);
+        big_a.mul_assign(&w);
+        big_a.add_assign(&vvv);
+
+        // X3 = v*A
+        let mut x3 = v;
+        x3.mul_assign(&big_a);
+
+        // Y3 = u*(X1*Z2*v^2 + A) + X3 + Y1*Z2*v^3
+        let mut y3 = x1z2;
+        y3.mul_assign(&vv);
+        y3.add_assign(&big_a);
+        y3.mul_assign(&u);
+        y3.add_assign(&x3);
+        y1z2.mul_assign(&vvv);
+        y3.add_assign(&y1z2);
+
+        // Z3 = v^3*w
+        let mut z3 = vvv;
+        z3.mul_assign(&w);
+
+        self.x = x3;
+        self.y = y3;
+        self.z = z3;
+    }
+
+    fn double_impl(&mut self) {
+        if self.is_zero() {
+            return;
+        }
+
+        // v = X*Z
+        let mut v = self.x.clone();
+        v.mul_assign(&self.z);
+
+        if v.is_zero() {
+            // x = 0 is the point of order two
+            *self = Self::zero(self.curve);
+
+            return;
+        }
+
+        // u = X^2 + Y*Z
+        let mut x_2 = self.x.clone();
+        x_2.square();
+        let mut u = self.y.clone();
+        u.mul_assign(&self.z);
+        u.add_assign(&x_2);
+
+        // A = u^2 + u*v + a*v^2
+        let mut vv = v.clone();
+        vv.square();
+        let mut big_a = u.clone();
+        big_a.add_assign(&v);
+        big_a.mul_assign(&u);
+        let mut t = vv.clone();
+        t.mul_assign(&self.curve.a);
+        big_a.add_assign(&t);
+
+        // X3 = v*A
+        let mut x3 = v.clone();
+        x3.mul_assign(&big_a);
+
+        // Y3 = X^4*v + (u + v)*A
+        let mut y3 = x_2;
+        y3.square();
+        y3.mul_assign(&v);
+        u.add_assign(&v);
+        u.mul_assign(&big_a);
+        y3.add_assign(&u);
+
+        // Z3 = v^3
+        vv.mul_assign(&v);
+
+        self.x = x3;
+        self.y = y3;
+        self.z = vv;
+    }
+
+    /// -(x, y) = (x, x + y)
+    fn negate_impl(&mut self) {
+        self.y.add_assign(&self.x);
+    }
+
+    pub(crate) fn mul_impl<S: AsRef<[u64]>>(&self, exp: S) -> Self {
+        let mut res = Self::zero(&self.curve);
+
+        let mut found_one = false;
+
+        for i in BitIterator::new(exp)
+        {
+            if found_one {
+                res.double();
+            } else {
+                found_one = i;
+            }
+
+            if i {
+                res.add_assign(self);
+            }
+        }
+
+        res
+    }
+
+    pub(crate) fn wnaf_mul_with_window_size_impl<S: crate::representation::IntoWnaf>(&self, exp: S, window_size: u32) -> Self {
+        assert!(window_size >= 2u32);
+        let mut precomp_table = vec![Self::zero(&self.curve); (1 << (window_size-1)) as usize];
+
+        let index_for_positive = (1 << (window_size-2)) as usize;
+
+        let mut two_self = self.clone();
+        two_self.double();
+
+        let mut precomp = self.clone();
+        precomp_table[index_for_positive] = precomp.clone();
+        let mut neg_precomp = precomp.clone();
+        neg_precomp.negate();
+        precomp_table[index_for_positive-1] = neg_precomp;
+
+        for i in 1..index_for_positive {
+            precomp.add_assign(&two_self);
+            precomp_table[index_for_positive+i] = precomp.clone();
+            let mut neg_precomp = precomp.clone();
+            neg_precomp.negate();
+            precomp_table[index_for_positive-1-i] = neg_precomp;
+        }
+
+        let mut wnaf = exp.wnaf(window_size);
+
+        let mut res = Self::zero(&self.curve);
+        let mut found_nonzero = false;
+
+        for &w in wnaf.iter().rev() {
+            if found_nonzero {
+                res.double();
+            }
+            if w != 0 {
+                found_nonzero = true;
+                if w > 0 {
+                    let idx = (w >> 1) as usize;
+                    res.add_assign(&precomp_table[index_for_positive + idx]);
+                } else {
+                    let idx = ((-w) >> 1) as usize;
+                    res.add_assign(&precomp_table[index_for_positive - 1 - idx]);
+                }
+            }
+        }
+
+        wnaf.zeroize_secret();
+        precomp_table.zeroize_secret();
+
+        res
+    }
+}
+
+impl<'a, C: CurveParameters> Group for BinaryCurvePoint<'a, C> {
+    fn add_assign(&mut self, other: &Self) {
+        self.add_assign_impl(&other);
+    }
+
+    fn add_assign_mixed(&mut self, other: &Self) {
+        self.add_assign_impl(&other);
+    }
+
+    fn sub_assign(&mut self, other: &Self) {
+        let mut other_neg = other.clone();
+        other_neg.negate();
+        self.add_assign(&other_neg);
+    }
+
+    fn negate(&mut self) {
+        self.negate_impl();
+    }
+
+    fn double(&mut self) {
+        self.double_impl();
+    }
+
+    fn mul<S: AsRef<[u64]>>(&self, exp: S) -> Self {
+        // fuzzing allows points that are not on the curve, so keep the reference double-and-add
+        if crate::features::in_fuzzing() {
+            return self.mul_impl(exp);
+        }
+        let exp = exp.as_ref();
+        let window_size = crate::wnaf::window_size_for_scalar_bits(crate::representation::num_bits(exp));
+
+        self.wnaf_mul_with_window_size_impl(exp, window_size)
+    }
+
+    fn wnaf_mul<S: crate::representation::IntoWnaf>(&self, exp: S) -> Self {
+        const WINDOW_SIZE: u32 = 4;
+
+        self.wnaf_mul_with_window_size_impl(exp, WINDOW_SIZE)
+    }
+
+    fn wnaf_mul_with_window_size<S: crate::representation::IntoWnaf>(&self, exp: S, window_size: u32) -> Self {
+        self.wnaf_mul_with_window_size_impl(exp, window_size)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    fn check_correct_subgroup(&self) -> bool {
+        let p = self.wnaf_mul(self.curve.subgroup_order_repr);
+
+        p.is_zero()
+    }
+}
+
+/// Multiexponentiation with the bucket method
+pub(crate) fn binary_curve_multiexp<'a, C: CurveParameters>
+    (bases: &[BinaryCurvePoint<'a, C>], scalars: Vec<MaxGroupSizeUint>, ctx: &ExecutionContext) -> BinaryCurvePoint<'a, C>
+{
+    debug_assert!(bases.len() == scalars.len());
+    debug_assert!(bases.len() > 0);
+
+    let num_bits = crate::representation::num_bits(&bases[0].curve.subgroup_order_repr);
+    let zero_point = BinaryCurvePoint::zero(bases[0].curve);
+
+    crate::multiexp::bucket_multiexp(bases, scalars, zero_point, num_bits, ctx)
+}
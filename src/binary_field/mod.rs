@@ -0,0 +1,593 @@
+// Binary fields GF(2^m) = GF(2)[z]/f(z) for an irreducible polynomial f(z) of degree m,
+// as used by characteristic two curves (e.g. sect163r2, sect283k1, sect571r1). Elements
+// are polynomials of degree below m stored in the same limb representations as prime
+// field elements, bit i being the coefficient of z^i. The reduction polynomial itself
+// takes m + 1 bits, so the representation is chosen from its bit length in the same way
+// as for a prime field modulus. Addition is XOR, so negation is trivial and doubling gives zero
+
+pub mod curve;
+
+use crate::representation::ElementRepr;
+use crate::traits::{FieldElement, BitIterator, FieldExtension, ZeroAndOne, ConditionalSelect};
+use crate::representation::conditional_select_repr;
+use crate::weierstrass::CurveParameters;
+use crate::public_interface::constants::NUM_LIMBS_MAX;
+use crate::alloc_prelude::*;
+
+pub struct BinaryField<E: ElementRepr> {
+    // full reduction polynomial including the z^m term
+    polynomial: E,
+    degree: u32,
+    // exponents of the nonzero terms of f(z) - z^m, reduction is fast for sparse
+    // (trinomial or pentanomial) polynomials used in practice
+    reduction_terms: Vec<u32>,
+}
+
+impl<E: ElementRepr> Clone for BinaryField<E> {
+    fn clone(&self) -> Self {
+        Self {
+            polynomial: self.polynomial,
+            degree: self.degree,
+            reduction_terms: self.reduction_terms.clone(),
+        }
+    }
+}
+
+impl<E: ElementRepr> BinaryField<E> {
+    /// Creates a field from the reduction polynomial. Polynomial must be irreducible,
+    /// with a top bit fitting into the representation together with one spare bit
+    pub fn new(polynomial: E) -> Result<Self, ()> {
+        let bitlength = polynomial.num_bits();
+        if bitlength < 3 {
+            return Err(());
+        }
+        let num_limbs = crate::field::calculate_num_limbs(bitlength as usize)?;
+        if E::NUM_LIMBS != num_limbs {
+            return Err(());
+        }
+
+        let degree = bitlength - 1;
+        let reduction_terms = (0..degree).filter(|i| {
+            (polynomial.as_ref()[(i / 64) as usize] >> (i % 64)) & 1 == 1
+        }).collect();
+
+        let field = Self {
+            polynomial: polynomial,
+            degree: degree,
+            reduction_terms: reduction_terms,
+        };
+
+        if !field.is_irreducible() {
+            return Err(());
+        }
+
+        Ok(field)
+    }
+
+    pub fn degree(&self) -> u32 {
+        self.degree
+    }
+
+    pub fn polynomial(&self) -> &E {
+        &self.polynomial
+    }
+
+    pub(crate) fn is_valid_repr(&self, repr: &E) -> bool {
+        repr.num_bits() <= self.degree
+    }
+
+    /// Left-to-right comb method with a window of 4 bits (algorithm 2.36 from
+    /// "Guide to Elliptic Curve Cryptography")
+    fn mul_reduce(&self, a: &E, b: &E) -> E {
+        let num_limbs = E::NUM_LIMBS;
+        let a = a.as_ref();
+        let b = b.as_ref();
+
+        // table[u] = u(z) * b(z) for all polynomials u of degree below 4
+        let mut table = [[0u64; NUM_LIMBS_MAX + 1]; 16];
+        table[1][..num_limbs].copy_from_slice(b);
+        for u in 1..8 {
+            let mut carry = 0u64;
+            for k in 0..=num_limbs {
+                let limb = table[u][k];
+                table[2 * u][k] = (limb << 1) | carry;
+                carry = limb >> 63;
+            }
+            for k in 0..=num_limbs {
+                table[2 * u + 1][k] = table[2 * u][k] ^ table[1][k];
+            }
+        }
+
+        let mut product = [0u64; 2 * NUM_LIMBS_MAX];
+        for window in (0..16).rev() {
+            for (j, a_limb) in a.iter().enumerate() {
+                let u = ((a_limb >> (4 * window)) & 0xf) as usize;
+                if u == 0 {
+                    continue;
+                }
+                for (dst, src) in product[j..(j + num_limbs + 1)].iter_mut().zip(table[u].iter()) {
+                    *dst ^= *src;
+                }
+            }
+            if window != 0 {
+                let mut carry = 0u64;
+                for limb in product[..(2 * num_limbs)].iter_mut() {
+                    let t = *limb;
+                    *limb = (t << 4) | carry;
+                    carry = t >> 60;
+                }
+            }
+        }
+
+        self.reduce_wide(&mut product[..(2 * num_limbs)])
+    }
+
+    /// Squaring is linear over GF(2), so it's enough to interleave bits with zeroes
+    fn square_reduce(&self, a: &E) -> E {
+        let num_limbs = E::NUM_LIMBS;
+
+        let mut product = [0u64; 2 * NUM_LIMBS_MAX];
+        for (i, limb) in a.as_ref().iter().enumerate() {
+            product[2 * i] = spread_bits(*limb as u32);
+            product[2 * i + 1] = spread_bits((*limb >> 32) as u32);
+        }
+
+        self.reduce_wide(&mut product[..(2 * num_limbs)])
+    }
+
+    /// Reduces a product of two elements word by word using z^m = f(z) - z^m
+    fn reduce_wide(&self, product: &mut [u64]) -> E {
+        let degree = self.degree as usize;
+        let boundary_limb = degree / 64;
+        let boundary_mask = !((1u64 << (degree % 64)) - 1);
+
+        loop {
+            let top_bit = crate::representation::num_bits(product) as usize;
+            if top_bit <= degree {
+                break;
+            }
+            let limb = (top_bit - 1) / 64;
+            let mut word = product[limb];
+            if limb == boundary_limb {
+                word &= boundary_mask;
+            }
+            product[limb] ^= word;
+
+            // word * z^(64 * limb) = word * z^(64 * limb - m) * (f(z) - z^m), bits of the
+            // boundary limb below m are masked out, so a negative shift doesn't lose anything
+            for term in self.reduction_terms.iter() {
+                let shift = (64 * limb + *term as usize) as isize - degree as isize;
+                if shift < 0 {
+                    product[0] ^= word >> ((-shift) as u32);
+                    continue;
+                }
+                let shift = shift as usize;
+                let limb_shift = shift / 64;
+                let bit_shift = shift % 64;
+                product[limb_shift] ^= word << bit_shift;
+                if bit_shift != 0 {
+                    product[limb_shift + 1] ^= word >> (64 - bit_shift);
+                }
+            }
+        }
+
+        let mut result = E::default();
+        result.as_mut().copy_from_slice(&product[..E::NUM_LIMBS]);
+
+        result
+    }
+
+    /// Extended Euclidean algorithm for binary polynomials (algorithm 2.48 from
+    /// "Guide to Elliptic Curve Cryptography"). Degrees of g1 and g2 stay below m
+    fn inverse_repr(&self, element: &E) -> Option<E> {
+        if element.is_zero() {
+            return None;
+        }
+
+        let one = E::from(1);
+        let mut u = *element;
+        let mut v = self.polynomial;
+        let mut g1 = one;
+        let mut g2 = E::default();
+
+        while u != one {
+            if u.is_zero() {
+                return None;
+            }
+            if u.num_bits() < v.num_bits() {
+                core::mem::swap(&mut u, &mut v);
+                core::mem::swap(&mut g1, &mut g2);
+            }
+            let shift = u.num_bits() - v.num_bits();
+
+            let mut t = v;
+            t.shl(shift);
+            xor_assign(&mut u, &t);
+
+            let mut t = g2;
+            t.shl(shift);
+            xor_assign(&mut g1, &t);
+        }
+
+        Some(g1)
+    }
+
+    /// Rabin's test: f of degree m is irreducible iff z^(2^m) == z mod f and
+    /// gcd(z^(2^(m/q)) - z, f) == 1 for every prime q dividing m
+    fn is_irreducible(&self) -> bool {
+        let degree = self.degree;
+        let z = E::from(2);
+
+        let mut prime_divisors = vec![];
+        let mut rest = degree;
+        let mut q = 2;
+        while q * q <= rest {
+            if rest % q == 0 {
+                prime_divisors.push(q);
+                while rest % q == 0 {
+                    rest /= q;
+                }
+            }
+            q += 1;
+        }
+        if rest > 1 {
+            prime_divisors.push(rest);
+        }
+
+        for q in prime_divisors.into_iter() {
+            let mut h = z;
+            for _ in 0..(degree / q) {
+                h = self.square_reduce(&h);
+            }
+            xor_assign(&mut h, &z);
+            if !polynomial_gcd_is_one(h, self.polynomial) {
+                return false;
+            }
+        }
+
+        let mut h = z;
+        for _ in 0..degree {
+            h = self.square_reduce(&h);
+        }
+
+        h == z
+    }
+}
+
+/// Interleaves bits of the input with zeroes
+#[inline(always)]
+fn spread_bits(value: u32) -> u64 {
+    let mut x = value as u64;
+    x = (x | (x << 16)) & 0x0000ffff0000ffff;
+    x = (x | (x << 8)) & 0x00ff00ff00ff00ff;
+    x = (x | (x << 4)) & 0x0f0f0f0f0f0f0f0f;
+    x = (x | (x << 2)) & 0x3333333333333333;
+    x = (x | (x << 1)) & 0x5555555555555555;
+
+    x
+}
+
+#[inline(always)]
+fn xor_assign<E: ElementRepr>(a: &mut E, b: &E) {
+    for (a, b) in a.as_mut().iter_mut().zip(b.as_ref().iter()) {
+        *a ^= *b;
+    }
+}
+
+fn polynomial_gcd_is_one<E: ElementRepr>(mut a: E, mut b: E) -> bool {
+    let one = E::from(1);
+    loop {
+        if a.is_zero() {
+            return b == one;
+        }
+        if b.is_zero() {
+            return a == one;
+        }
+        let a_bits = a.num_bits();
+        let b_bits = b.num_bits();
+        if a_bits >= b_bits {
+            let mut t = b;
+            t.shl(a_bits - b_bits);
+            xor_assign(&mut a, &t);
+        } else {
+            let mut t = a;
+            t.shl(b_bits - a_bits);
+            xor_assign(&mut b, &t);
+        }
+    }
+}
+
+pub struct BinaryFieldElement<'a, E: ElementRepr> {
+    pub(crate) repr: E,
+    pub(crate) field: &'a BinaryField<E>,
+}
+
+impl<'a, E: ElementRepr> Clone for BinaryFieldElement<'a, E> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self {
+            repr: self.repr,
+            field: self.field,
+        }
+    }
+}
+
+impl<'a, E: ElementRepr> Copy for BinaryFieldElement<'a, E> {}
+
+impl<'a, E: ElementRepr> PartialEq for BinaryFieldElement<'a, E> {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        self.repr == other.repr
+    }
+}
+
+impl<'a, E: ElementRepr> Eq for BinaryFieldElement<'a, E> {}
+
+impl<'a, E: ElementRepr> core::fmt::Debug for BinaryFieldElement<'a, E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "0x")?;
+        for i in self.repr.as_ref().iter().rev() {
+            write!(f, "{:016x}", *i)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a, E: ElementRepr> core::fmt::Display for BinaryFieldElement<'a, E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "0x")?;
+        for i in self.repr.as_ref().iter().rev() {
+            write!(f, "{:016x}", *i)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a, E: ElementRepr> BinaryFieldElement<'a, E> {
+    pub fn from_repr(field: &'a BinaryField<E>, repr: E) -> Result<Self, ()> {
+        if !field.is_valid_repr(&repr) {
+            return Err(());
+        }
+
+        Ok(Self {
+            repr: repr,
+            field: field,
+        })
+    }
+
+    pub fn into_repr(&self) -> E {
+        self.repr
+    }
+
+    /// Big endian bytes are padded from the beginning up to the representation length
+    pub fn from_be_bytes(field: &'a BinaryField<E>, bytes: &[u8]) -> Result<Self, ()> {
+        let mut repr = E::default();
+        let necessary_length = E::NUM_LIMBS * 8;
+        if bytes.len() > necessary_length {
+            return Err(());
+        }
+        repr.read_be_from_slice_of_any_len(bytes).map_err(|_| ())?;
+
+        Self::from_repr(field, repr)
+    }
+}
+
+impl<'a, E: ElementRepr> ZeroAndOne for BinaryFieldElement<'a, E> {
+    type Params = &'a BinaryField<E>;
+
+    #[inline(always)]
+    fn zero(field: &'a BinaryField<E>) -> Self {
+        Self {
+            repr: E::default(),
+            field: field,
+        }
+    }
+
+    #[inline(always)]
+    fn one(field: &'a BinaryField<E>) -> Self {
+        Self {
+            repr: E::from(1),
+            field: field,
+        }
+    }
+}
+
+impl<'a, E: ElementRepr> ConditionalSelect for BinaryFieldElement<'a, E> {
+    #[inline(always)]
+    fn conditional_select(a: &Self, b: &Self, choice: bool) -> Self {
+        Self {
+            repr: conditional_select_repr(&a.repr, &b.repr, choice),
+            field: a.field
+        }
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<'a, E: ElementRepr> zeroize::Zeroize for BinaryFieldElement<'a, E> {
+    fn zeroize(&mut self) {
+        zeroize::Zeroize::zeroize(self.repr.as_mut());
+    }
+}
+
+impl<'a, E: ElementRepr> FieldElement for BinaryFieldElement<'a, E> {
+    #[inline(always)]
+    fn is_zero(&self) -> bool {
+        self.repr.is_zero()
+    }
+
+    #[inline(always)]
+    fn square(&mut self) {
+        self.repr = self.field.square_reduce(&self.repr);
+    }
+
+    #[inline(always)]
+    fn double(&mut self) {
+        self.repr = E::default();
+    }
+
+    #[inline(always)]
+    fn negate(&mut self) {}
+
+    #[inline(always)]
+    fn add_assign(&mut self, other: &Self) {
+        xor_assign(&mut self.repr, &other.repr);
+    }
+
+    #[inline(always)]
+    fn sub_assign(&mut self, other: &Self) {
+        xor_assign(&mut self.repr, &other.repr);
+    }
+
+    #[inline(always)]
+    fn mul_assign(&mut self, other: &Self) {
+        self.repr = self.field.mul_reduce(&self.repr, &other.repr);
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        self.field.inverse_repr(&self.repr).map(|repr| {
+            Self {
+                repr: repr,
+                field: self.field,
+            }
+        })
+    }
+
+    fn pow<S: AsRef<[u64]>>(&self, exp: S) -> Self {
+        let mut res = Self::one(self.field);
+
+        let mut found_one = false;
+
+        for i in BitIterator::new(exp) {
+            if found_one {
+                res.square();
+            } else {
+                found_one = i;
+            }
+
+            if i {
+                res.mul_assign(self);
+            }
+        }
+
+        res
+    }
+
+    fn mul_by_nonresidue<EXT: FieldExtension<Element = Self>>(&mut self, for_extesion: &EXT) {
+        for_extesion.multiply_by_non_residue(self);
+    }
+
+    fn conjugate(&mut self) {
+        unreachable!();
+    }
+
+    fn frobenius_map(&mut self, _power: usize) {
+        unreachable!();
+    }
+}
+
+pub struct CurveOverBinaryFieldParameters<'a, E: ElementRepr> {
+    pub field: &'a BinaryField<E>,
+}
+
+impl<'a, E: ElementRepr> Clone for CurveOverBinaryFieldParameters<'a, E> {
+    fn clone(&self) -> Self {
+        Self {
+            field: self.field
+        }
+    }
+}
+
+impl<'a, E: ElementRepr> CurveParameters for CurveOverBinaryFieldParameters<'a, E> {
+    type BaseFieldElement = BinaryFieldElement<'a, E>;
+    fn params(&self) -> <Self::BaseFieldElement as ZeroAndOne>::Params {
+        self.field
+    }
+}
+
+impl<'a, E: ElementRepr> CurveOverBinaryFieldParameters<'a, E> {
+    pub fn new(field: &'a BinaryField<E>) -> Self {
+        Self {
+            field
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::field::U256Repr;
+    use super::*;
+
+    fn sect163_field() -> BinaryField<U256Repr> {
+        // z^163 + z^7 + z^6 + z^3 + 1
+        let mut polynomial = U256Repr::from(0xc9);
+        polynomial.as_mut()[2] = 1u64 << 35;
+
+        BinaryField::new(polynomial).unwrap()
+    }
+
+    #[test]
+    fn test_binary_field_arithmetic() {
+        let field = sect163_field();
+        assert_eq!(field.degree(), 163);
+
+        let mut repr = U256Repr::default();
+        repr.as_mut()[0] = 0x3f0eba16286a2d57;
+        repr.as_mut()[1] = 0xea0991168d499463;
+        repr.as_mut()[2] = 0x7;
+        let a = BinaryFieldElement::from_repr(&field, repr).unwrap();
+
+        let a_inv = a.inverse().unwrap();
+        let mut product = a;
+        product.mul_assign(&a_inv);
+        assert_eq!(product, BinaryFieldElement::one(&field));
+
+        // a^(2^163) == a
+        let mut power = a;
+        for _ in 0..163 {
+            power.square();
+        }
+        assert_eq!(power, a);
+
+        let mut sum = a;
+        sum.add_assign(&a);
+        assert!(sum.is_zero());
+
+        // z^163 is out of field
+        let mut out_of_field = U256Repr::default();
+        out_of_field.as_mut()[2] = 1u64 << 35;
+        assert!(BinaryFieldElement::from_repr(&field, out_of_field).is_err());
+    }
+
+    #[test]
+    fn test_degree_multiple_of_limb_size() {
+        // z^64 + z^4 + z^3 + z + 1, reduction starts from a whole limb
+        let mut polynomial = U256Repr::from(0x1b);
+        polynomial.as_mut()[1] = 1;
+        let field = BinaryField::new(polynomial).unwrap();
+
+        let a = BinaryFieldElement::from_repr(&field, U256Repr::from(0xfedcba9876543210)).unwrap();
+        let mut product = a;
+        product.mul_assign(&a.inverse().unwrap());
+        assert_eq!(product, BinaryFieldElement::one(&field));
+
+        let mut power = a;
+        for _ in 0..64 {
+            power.square();
+        }
+        assert_eq!(power, a);
+    }
+
+    #[test]
+    fn test_reducible_polynomial_is_rejected() {
+        // (z^2 + z + 1)^2 = z^4 + z^2 + 1
+        assert!(BinaryField::new(U256Repr::from(0x15)).is_err());
+        // z^163 + z^8 + z + 1 has a factor z + 1
+        let mut polynomial = U256Repr::from(0x103);
+        polynomial.as_mut()[2] = 1u64 << 35;
+        assert!(BinaryField::new(polynomial).is_err());
+        // z^4 + z + 1 is irreducible
+        assert!(BinaryField::new(U256Repr::from(0x13)).is_ok());
+    }
+}
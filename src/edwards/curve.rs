@@ -0,0 +1,572 @@
+use crate::traits::{FieldElement, BitIterator, ConditionalSelect, ZeroizeSecret};
+use crate::weierstrass::{CurveParameters, Group};
+use crate::traits::ZeroAndOne;
+use crate::integers::MaxGroupSizeUint;
+use crate::execution_context::ExecutionContext;
+use crate::alloc_prelude::*;
+
+/// Addition formulas are complete if `a` is a square and `d` is not (e.g. Jubjub and Baby Jubjub).
+/// Otherwise (e.g. Bandersnatch) exceptional cases require `P + Q` or `P - Q` to be one of
+/// the points at infinity of the projective closure. Those have even order, so results are
+/// still exact for points of odd order
+pub struct TwistedEdwardsCurve<'a, C: CurveParameters> {
+    pub(crate) a: C::BaseFieldElement,
+    pub(crate) d: C::BaseFieldElement,
+    pub(crate) subgroup_order_repr: &'a [u64],
+    pub(crate) params: &'a C,
+}
+
+impl<'a, C: CurveParameters> Clone for TwistedEdwardsCurve<'a, C> {
+    fn clone(&self) -> Self {
+        Self {
+            a: self.a.clone(),
+            d: self.d.clone(),
+            subgroup_order_repr: self.subgroup_order_repr,
+            params: self.params,
+        }
+    }
+}
+
+impl<'a, C: CurveParameters> TwistedEdwardsCurve<'a, C> {
+    pub(crate) fn new(
+        subgroup_order: &'a [u64],
+        a: C::BaseFieldElement,
+        d: C::BaseFieldElement,
+        params: &'a C
+    ) -> Result<Self, ()> {
+        // curve is singular if a*d*(a - d) == 0
+        if a.is_zero() || d.is_zero() || a == d {
+            return Err(());
+        }
+
+        Ok(Self {
+            a: a,
+            d: d,
+            subgroup_order_repr: subgroup_order,
+            params: params,
+        })
+    }
+}
+
+pub struct EdwardsPoint<'a, C: CurveParameters> {
+    pub(crate) curve: &'a TwistedEdwardsCurve<'a, C>,
+    pub(crate) x: C::BaseFieldElement,
+    pub(crate) y: C::BaseFieldElement,
+    pub(crate) t: C::BaseFieldElement,
+    pub(crate) z: C::BaseFieldElement,
+}
+
+impl<'a, C: CurveParameters> Clone for EdwardsPoint<'a, C> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self {
+            curve: &self.curve,
+            x: self.x.clone(),
+            y: self.y.clone(),
+            t: self.t.clone(),
+            z: self.z.clone()
+        }
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<'a, C: CurveParameters> zeroize::Zeroize for EdwardsPoint<'a, C> {
+    fn zeroize(&mut self) {
+        self.x.zeroize_secret();
+        self.y.zeroize_secret();
+        self.t.zeroize_secret();
+        self.z.zeroize_secret();
+    }
+}
+
+impl<'a, C: CurveParameters> ConditionalSelect for EdwardsPoint<'a, C> {
+    #[inline(always)]
+    fn conditional_select(a: &Self, b: &Self, choice: bool) -> Self {
+        Self {
+            curve: a.curve,
+            x: C::BaseFieldElement::conditional_select(&a.x, &b.x, choice),
+            y: C::BaseFieldElement::conditional_select(&a.y, &b.y, choice),
+            t: C::BaseFieldElement::conditional_select(&a.t, &b.t, choice),
+            z: C::BaseFieldElement::conditional_select(&a.z, &b.z, choice),
+        }
+    }
+}
+
+impl<'a, C: CurveParameters> EdwardsPoint<'a, C> {
+    /// Neutral element (0, 1)
+    pub fn zero(curve: &'a TwistedEdwardsCurve<C>) -> Self {
+        Self {
+            curve: curve,
+            x: C::BaseFieldElement::zero(curve.params.params()),
+            y: C::BaseFieldElement::one(curve.params.params()),
+            t: C::BaseFieldElement::zero(curve.params.params()),
+            z: C::BaseFieldElement::one(curve.params.params()),
+        }
+    }
+
+    /// Unlike the Weierstrass form the neutral element is an affine point (0, 1),
+    /// so there is no special encoding for it
+    pub fn point_from_xy(
+        curve: &'a TwistedEdwardsCurve<'a, C>,
+        x: C::BaseFieldElement,
+        y: C::BaseFieldElement,
+    ) -> EdwardsPoint<'a, C> {
+        let mut t = x.clone();
+        t.mul_assign(&y);
+
+        EdwardsPoint {
+            curve: curve,
+            x: x,
+            y: y,
+            t: t,
+            z: C::BaseFieldElement::one(curve.params.params())
+        }
+    }
+
+    pub fn is_on_curve(&self) -> bool {
+        debug_assert!(self.is_normalized());
+
+        let mut x_2 = self.x.clone();
+        x_2.square();
+
+        let mut y_2 = self.y.clone();
+        y_2.square();
+
+        // a*x^2 + y^2
+        let mut lhs = x_2.clone();
+        lhs.mul_assign(&self.curve.a);
+        lhs.add_assign(&y_2);
+
+        // 1 + d*x^2*y^2
+        let mut rhs = x_2;
+        rhs.mul_assign(&y_2);
+        rhs.mul_assign(&self.curve.d);
+        rhs.add_assign(&C::BaseFieldElement::one(self.curve.params.params()));
+
+        lhs == rhs
+    }
+
+    pub fn is_normalized(&self) -> bool {
+        let one = C::BaseFieldElement::one(self.curve.params.params());
+
+        self.z == one
+    }
+
+    /// Returns false if Z is zero, that only happens for exceptional inputs of the addition formulas
+    pub fn normalize(&mut self) -> bool {
+        if self.is_normalized() {
+            return true;
+        }
+
+        match self.z.inverse() {
+            Some(z_inv) => {
+                self.x.mul_assign(&z_inv);
+                self.y.mul_assign(&z_inv);
+                self.t.mul_assign(&z_inv);
+                self.z = C::BaseFieldElement::one(self.curve.params.params());
+
+                true
+            },
+            None => {
+                false
+            }
+        }
+    }
+
+    pub fn into_xy(&self) -> Option<(C::BaseFieldElement, C::BaseFieldElement)> {
+        let mut point = self.clone();
+        if !point.normalize() {
+            return None;
+        }
+
+        Some((point.x, point.y))
+    }
+
+    fn add_assign_impl(&mut self, other: &Self, other_is_normalized: bool) {
+        // http://www.hyperelliptic.org/EFD/g1p/auto-twisted-extended.html#addition-add-2008-hwcd
+
+        // A = X1*X2
+        let mut a = self.x.clone();
+        a.mul_assign(&other.x);
+
+        // B = Y1*Y2
+        let mut b = self.y.clone();
+        b.mul_assign(&other.y);
+
+        // C = T1*d*T2
+        let mut c = self.t.clone();
+        c.mul_assign(&self.curve.d);
+        c.mul_assign(&other.t);
+
+        // D = Z1*Z2, or Z1 for the mixed addition
+        let mut d = self.z.clone();
+        if !other_is_normalized {
+            d.mul_assign(&other.z);
+        }
+
+        // E = (X1+Y1)*(X2+Y2)-A-B
+        let mut e = self.x.clone();
+        e.add_assign(&self.y);
+        let mut tmp = other.x.clone();
+        tmp.add_assign(&other.y);
+        e.mul_assign(&tmp);
+        e.sub_assign(&a);
+        e.sub_assign(&b);
+
+        // F = D-C
+        let mut f = d.clone();
+        f.sub_assign(&c);
+
+        // G = D+C
+        let mut g = d;
+        g.add_assign(&c);
+
+        // H = B-a*A
+        let mut h = b;
+        a.mul_assign(&self.curve.a);
+        h.sub_assign(&a);
+
+        // X3 = E*F, Y3 = G*H, T3 = E*H, Z3 = F*G
+        self.x = e.clone();
+        self.x.mul_assign(&f);
+
+        self.y = g.clone();
+        self.y.mul_assign(&h);
+
+        self.t = e;
+        self.t.mul_assign(&h);
+
+        self.z = f;
+        self.z.mul_assign(&g);
+    }
+
+    fn double_impl(&mut self) {
+        // http://www.hyperelliptic.org/EFD/g1p/auto-twisted-extended.html#doubling-dbl-2008-hwcd
+
+        // A = X1^2
+        let mut a = self.x.clone();
+        a.square();
+
+        // B = Y1^2
+        let mut b = self.y.clone();
+        b.square();
+
+        // C = 2*Z1^2
+        let mut c = self.z.clone();
+        c.square();
+        c.double();
+
+        // D = a*A
+        let mut d = a.clone();
+        d.mul_assign(&self.curve.a);
+
+        // E = (X1+Y1)^2-A-B
+        let mut e = self.x.clone();
+        e.add_assign(&self.y);
+        e.square();
+        e.sub_assign(&a);
+        e.sub_assign(&b);
+
+        // G = D+B
+        let mut g = d.clone();
+        g.add_assign(&b);
+
+        // F = G-C
+        let mut f = g.clone();
+        f.sub_assign(&c);
+
+        // H = D-B
+        let mut h = d;
+        h.sub_assign(&b);
+
+        // X3 = E*F, Y3 = G*H, T3 = E*H, Z3 = F*G
+        self.x = e.clone();
+        self.x.mul_assign(&f);
+
+        self.y = g.clone();
+        self.y.mul_assign(&h);
+
+        self.t = e;
+        self.t.mul_assign(&h);
+
+        self.z = f;
+        self.z.mul_assign(&g);
+    }
+
+    fn negate_impl(&mut self) {
+        self.x.negate();
+        self.t.negate();
+    }
+
+    pub(crate) fn mul_impl<S: AsRef<[u64]>>(&self, exp: S) -> Self {
+        let mut res = Self::zero(&self.curve);
+
+        let mut found_one = false;
+
+        for i in BitIterator::new(exp)
+        {
+            if found_one {
+                res.double();
+            } else {
+                found_one = i;
+            }
+
+            if i {
+                res.add_assign_mixed(self);
+            }
+        }
+
+        res
+    }
+
+    /// Fixed window multiplication for secret scalars. Every window of the scalar up to its
+    /// limb length is processed and every table entry is touched on lookup. Addition formulas
+    /// are complete, so the sequence of field operations doesn't depend on the scalar
+    pub(crate) fn mul_constant_time_impl<S: AsRef<[u64]>>(&self, exp: S) -> Self {
+        const WINDOW_SIZE: usize = 4;
+        const WINDOW_MASK: u64 = (1 << WINDOW_SIZE) - 1;
+
+        let exp = exp.as_ref();
+
+        // [0]P, [1]P, ..., [2^w - 1]P
+        let mut table = Vec::with_capacity(1 << WINDOW_SIZE);
+        table.push(Self::zero(&self.curve));
+        let mut tmp = self.clone();
+        for _ in 1..(1 << WINDOW_SIZE) {
+            table.push(tmp.clone());
+            tmp.add_assign(self);
+        }
+
+        let mut res = Self::zero(&self.curve);
+        let num_windows = exp.len() * 64 / WINDOW_SIZE;
+        for window in (0..num_windows).rev() {
+            for _ in 0..WINDOW_SIZE {
+                res.double();
+            }
+
+            let bit = window * WINDOW_SIZE;
+            let digit = (exp[bit / 64] >> (bit % 64)) & WINDOW_MASK;
+
+            let mut selected = Self::zero(&self.curve);
+            for (i, point) in table.iter().enumerate() {
+                selected.conditional_assign(point, crate::representation::constant_time_eq(i as u64, digit));
+            }
+
+            // selected entry may happen to be normalized, so don't take the mixed addition for it
+            res.add_assign_impl(&selected, false);
+            selected.zeroize_secret();
+        }
+
+        table.zeroize_secret();
+        tmp.zeroize_secret();
+
+        res
+    }
+
+    pub(crate) fn wnaf_mul_with_window_size_impl<S: crate::representation::IntoWnaf>(&self, exp: S, window_size: u32) -> Self {
+        assert!(window_size >= 2u32);
+        let mut precomp_table = vec![Self::zero(&self.curve); (1 << (window_size-1)) as usize];
+
+        let index_for_positive = (1 << (window_size-2)) as usize;
+
+        let mut two_self = self.clone();
+        two_self.double();
+
+        let mut precomp = self.clone();
+        precomp_table[index_for_positive] = precomp.clone();
+        let mut neg_precomp = precomp.clone();
+        neg_precomp.negate();
+        precomp_table[index_for_positive-1] = neg_precomp;
+
+        for i in 1..index_for_positive {
+            precomp.add_assign(&two_self);
+            precomp_table[index_for_positive+i] = precomp.clone();
+            let mut neg_precomp = precomp.clone();
+            neg_precomp.negate();
+            precomp_table[index_for_positive-1-i] = neg_precomp;
+        }
+
+        let mut wnaf = exp.wnaf(window_size);
+
+        let mut res = Self::zero(&self.curve);
+        let mut found_nonzero = false;
+
+        for &w in wnaf.iter().rev() {
+            if found_nonzero {
+                res.double();
+            }
+            if w != 0 {
+                found_nonzero = true;
+                if w > 0 {
+                    let idx = (w >> 1) as usize;
+                    res.add_assign(&precomp_table[index_for_positive + idx]);
+                } else {
+                    let idx = ((-w) >> 1) as usize;
+                    res.add_assign(&precomp_table[index_for_positive - 1 - idx]);
+                }
+            }
+        }
+
+        wnaf.zeroize_secret();
+        precomp_table.zeroize_secret();
+
+        res
+    }
+}
+
+impl<'a, C: CurveParameters> Group for EdwardsPoint<'a, C> {
+    fn add_assign(&mut self, other: &Self) {
+        let other_is_normalized = other.is_normalized();
+        self.add_assign_impl(&other, other_is_normalized);
+    }
+
+    fn add_assign_mixed(&mut self, other: &Self) {
+        self.add_assign(&other);
+    }
+
+    fn sub_assign(&mut self, other: &Self) {
+        let mut other_neg = other.clone();
+        other_neg.negate();
+        self.add_assign(&other_neg);
+    }
+
+    fn negate(&mut self) {
+        self.negate_impl();
+    }
+
+    fn double(&mut self) {
+        self.double_impl();
+    }
+
+    fn mul<S: AsRef<[u64]>>(&self, exp: S) -> Self {
+        // fuzzing allows points that are not on the curve, so keep the reference double-and-add
+        if crate::features::in_fuzzing() {
+            return self.mul_impl(exp);
+        }
+        if crate::features::in_constant_time() {
+            return self.mul_constant_time_impl(exp);
+        }
+        let exp = exp.as_ref();
+        let window_size = crate::wnaf::window_size_for_scalar_bits(crate::representation::num_bits(exp));
+
+        self.wnaf_mul_with_window_size_impl(exp, window_size)
+    }
+
+    fn wnaf_mul<S: crate::representation::IntoWnaf>(&self, exp: S) -> Self {
+        const WINDOW_SIZE: u32 = 4;
+
+        self.wnaf_mul_with_window_size_impl(exp, WINDOW_SIZE)
+    }
+
+    fn wnaf_mul_with_window_size<S: crate::representation::IntoWnaf>(&self, exp: S, window_size: u32) -> Self {
+        self.wnaf_mul_with_window_size_impl(exp, window_size)
+    }
+
+    /// X == 0 and Y == Z
+    fn is_zero(&self) -> bool {
+        if !self.x.is_zero() || self.z.is_zero() {
+            return false;
+        }
+
+        self.y == self.z
+    }
+
+    fn check_correct_subgroup(&self) -> bool {
+        let p = self.wnaf_mul(self.curve.subgroup_order_repr);
+
+        p.is_zero()
+    }
+}
+
+/// Multiexponentiation with the bucket method. Bases that are decoded from affine
+/// coordinates use the cheaper addition with Z2 = 1
+pub(crate) fn edwards_multiexp<'a, C: CurveParameters>
+    (bases: &[EdwardsPoint<'a, C>], scalars: Vec<MaxGroupSizeUint>, ctx: &ExecutionContext) -> EdwardsPoint<'a, C>
+{
+    debug_assert!(bases.len() == scalars.len());
+    debug_assert!(bases.len() > 0);
+
+    // buckets are indexed by the scalars, while the addition is complete
+    // and the sum of secret multiples doesn't branch
+    if crate::features::in_constant_time() {
+        let mut result = EdwardsPoint::zero(bases[0].curve);
+        for (base, scalar) in bases.iter().zip(scalars.iter()) {
+            let mut multiple = base.mul_constant_time_impl(scalar);
+            result.add_assign_impl(&multiple, false);
+            multiple.zeroize_secret();
+        }
+
+        return result;
+    }
+
+    let num_bits = crate::representation::num_bits(&bases[0].curve.subgroup_order_repr);
+    let zero_point = EdwardsPoint::zero(bases[0].curve);
+
+    crate::multiexp::bucket_multiexp(bases, scalars, zero_point, num_bits, ctx)
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use num_traits::Num;
+    use crate::field::{U256Repr, new_field};
+    use crate::fp::Fp;
+    use crate::weierstrass::{Group, CurveOverFpParameters};
+    use crate::integers::MaxGroupSizeUint;
+    use crate::test::biguint_to_u64_vec;
+    use super::*;
+
+    fn fp_from_dec<'a>(field: &'a crate::field::PrimeField<U256Repr>, value: &str) -> Fp<'a, U256Repr, crate::field::PrimeField<U256Repr>> {
+        let bytes = BigUint::from_str_radix(value, 10).unwrap().to_bytes_be();
+
+        Fp::from_be_bytes(field, &bytes, true).unwrap()
+    }
+
+    // Baby Jubjub over the scalar field of BN254 with the generator of the prime order subgroup
+    #[test]
+    fn test_baby_jubjub_group_law() {
+        let field = new_field::<U256Repr>("21888242871839275222246405745257275088548364400416034343698204186575808495617", 10).unwrap();
+        let a = fp_from_dec(&field, "168700");
+        let d = fp_from_dec(&field, "168696");
+        let order_big = BigUint::from_str_radix("2736030358979909402780800718157159386076813972158567259200215660948447373041", 10).unwrap();
+        let order = biguint_to_u64_vec(order_big.clone());
+        let params = CurveOverFpParameters::new(&field);
+        let curve = TwistedEdwardsCurve::new(&order, a, d, &params).unwrap();
+
+        let x = fp_from_dec(&field, "5299619240641551281634865583518297030282874472190772894086521144482721001553");
+        let y = fp_from_dec(&field, "16950150798460657717958625567821834550301663161624707787222815936182638968203");
+        let base = EdwardsPoint::point_from_xy(&curve, x, y);
+        assert!(base.is_on_curve());
+        assert!(base.check_correct_subgroup());
+        assert!(EdwardsPoint::zero(&curve).is_zero());
+
+        let mut doubled = base.clone();
+        doubled.double();
+        let mut added = base.clone();
+        added.add_assign(&base);
+        assert_eq!(doubled.into_xy(), added.into_xy());
+        assert_eq!(doubled.into_xy(), base.mul(&[2u64]).into_xy());
+
+        let mut zero = base.clone();
+        zero.sub_assign(&base);
+        assert!(zero.is_zero());
+
+        // (r + 1) * P == P
+        let order_plus_one = biguint_to_u64_vec(order_big + BigUint::from(1u64));
+        assert_eq!(base.mul(&order_plus_one).into_xy(), base.into_xy());
+        assert_eq!(base.mul_impl(&[1234567u64]).into_xy(), base.mul(&[1234567u64]).into_xy());
+        assert_eq!(base.mul_constant_time_impl(&order_plus_one).into_xy(), base.into_xy());
+        assert_eq!(base.mul_constant_time_impl(&[1234567u64, 0]).into_xy(), base.mul(&[1234567u64]).into_xy());
+
+        // 3 * P + 5 * (2 * P) == 13 * P
+        let mut two_base = doubled.clone();
+        assert!(two_base.normalize());
+        let bases = vec![base.clone(), two_base];
+        let scalars = vec![MaxGroupSizeUint::from(3u64), MaxGroupSizeUint::from(5u64)];
+        let result = edwards_multiexp(&bases, scalars, &ExecutionContext::default());
+        let mut normalized = result.clone();
+        assert!(normalized.normalize());
+        assert!(normalized.is_on_curve());
+        assert_eq!(result.into_xy(), base.mul(&[13u64]).into_xy());
+    }
+}
@@ -0,0 +1,7 @@
+// Twisted Edwards curves a*x^2 + y^2 = 1 + d*x^2*y^2 over the base field. Field
+// parameters are shared with the Weierstrass curves, so the same `CurveParameters`
+// and `Group` traits are used. Arithmetic is done in extended coordinates
+// (X : Y : T : Z) with x = X/Z, y = Y/Z and x*y = T/Z using unified formulas
+// from "Twisted Edwards Curves Revisited" by Hisil, Wong, Carter and Dawson
+
+pub mod curve;
@@ -8,6 +8,7 @@ use crate::weierstrass::curve::*;
 use crate::pairings::bls12::*;
 use crate::pairings::TwistType;
 use crate::integers::MaxFieldUint;
+use crate::alloc_prelude::*;
 
 const REPR_ZERO: U384Repr = U384Repr([0,0,0,0,0,0]);
 
@@ -417,7 +418,8 @@ pub const BLS12_377_G1_CURVE: WeierstrassCurve<'static, CurveOverFpParameters<'s
         b: BLS12_377_B_FOR_G1,
         curve_type: CurveType::AIsZero,
         subgroup_order_repr: &BLS12_377_SUBGROUP_ORDER,
-        params: &BLS12_377_G1_CURVE_PARAMETERS
+        params: &BLS12_377_G1_CURVE_PARAMETERS,
+        endomorphism: None
     };   
 
 pub const BLS12_377_G2_CURVE: WeierstrassCurve<'static, CurveOverFp2Parameters<'static, U384Repr, PrimeField<U384Repr>>> = 
@@ -426,7 +428,8 @@ pub const BLS12_377_G2_CURVE: WeierstrassCurve<'static, CurveOverFp2Parameters<'
         b: BLS12_377_B_FOR_G2,
         curve_type: CurveType::AIsZero,
         subgroup_order_repr: &BLS12_377_SUBGROUP_ORDER,
-        params: &BLS12_377_G2_CURVE_PARAMETERS
+        params: &BLS12_377_G2_CURVE_PARAMETERS,
+        endomorphism: None
     };   
 
 const BLS12_377_G1_GENERATOR_X: decl_fp!(U384Repr) = repr_into_fp!(
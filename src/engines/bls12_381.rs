@@ -9,6 +9,7 @@ use crate::weierstrass::curve::*;
 use crate::pairings::bls12::*;
 use crate::pairings::TwistType;
 use crate::integers::MaxFieldUint;
+use crate::alloc_prelude::*;
 
 struct Bls12_381Extension2;
 
@@ -461,7 +462,8 @@ pub const BLS12_381_G1_CURVE: WeierstrassCurve<'static, CurveOverFpParameters<'s
         b: BLS12_381_B_FOR_G1,
         curve_type: CurveType::AIsZero,
         subgroup_order_repr: &BLS12_381_SUBGROUP_ORDER,
-        params: &BLS12_381_G1_CURVE_PARAMETERS
+        params: &BLS12_381_G1_CURVE_PARAMETERS,
+        endomorphism: None
     };   
 
 pub const BLS12_381_G2_CURVE: WeierstrassCurve<'static, CurveOverFp2Parameters<'static, U384Repr, PrimeField<U384Repr>>> = 
@@ -470,7 +472,8 @@ pub const BLS12_381_G2_CURVE: WeierstrassCurve<'static, CurveOverFp2Parameters<'
         b: BLS12_381_B_FOR_G2,
         curve_type: CurveType::AIsZero,
         subgroup_order_repr: &BLS12_381_SUBGROUP_ORDER,
-        params: &BLS12_381_G2_CURVE_PARAMETERS
+        params: &BLS12_381_G2_CURVE_PARAMETERS,
+        endomorphism: None
     };   
 
 const BLS12_381_G1_GENERATOR_X: decl_fp!(U384Repr) = repr_into_fp!(
@@ -580,7 +583,8 @@ pub const BLS12_381_G1_CURVE_ISOGENY: WeierstrassCurve<'static, CurveOverFpParam
         b: BLS12_381_G1_ISOGENY_B,
         curve_type: CurveType::Generic,
         subgroup_order_repr: &BLS12_381_SUBGROUP_ORDER,
-        params: &BLS12_381_G1_CURVE_PARAMETERS
+        params: &BLS12_381_G1_CURVE_PARAMETERS,
+        endomorphism: None
     };  
 
 pub const BLS12_381_G1_SWU_Z: decl_fp!(U384Repr) = repr_into_fp!(
@@ -644,7 +648,8 @@ pub const BLS12_381_G2_CURVE_ISOGENY: WeierstrassCurve<'static, CurveOverFp2Para
         b: BLS12_381_G2_ISOGENY_B,
         curve_type: CurveType::AIsZero,
         subgroup_order_repr: &BLS12_381_SUBGROUP_ORDER,
-        params: &BLS12_381_G2_CURVE_PARAMETERS
+        params: &BLS12_381_G2_CURVE_PARAMETERS,
+        endomorphism: None
     };   
 
 pub const BLS12_381_G2_MAPPING_H_EFF: [u64; 10] = [
@@ -684,7 +689,7 @@ pub mod mapping {
             el, 
             &swu, 
             coerce_static(el, &BLS12_381_G1_CURVE_ISOGENY)
-        );
+        ).ok_or_else(|| ApiError::InputError("Failed to find a square root in the mapping".to_owned()))?;
 
         let (x, y) = apply_isogeny_map(
             &x_prime,
@@ -713,7 +718,7 @@ pub mod mapping {
             el, 
             &swu, 
             coerce_static(el, &BLS12_381_G2_CURVE_ISOGENY)
-        );
+        ).ok_or_else(|| ApiError::InputError("Failed to find a square root in the mapping".to_owned()))?;
 
         let (x, y) = apply_isogeny_map(
             &x_prime,
@@ -154,8 +154,20 @@ pub static BN254_G2_PARAMS: Lazy<CurveOverFp2Parameters<'static, U256Repr, Prime
 });
 
 pub static BN254_G1_CURVE: Lazy<WeierstrassCurve<'static, CurveOverFpParameters<'static, U256Repr, PrimeField<U256Repr>>>> = Lazy::new(|| {
-    let curve = WeierstrassCurve::new(&*BN254_SUBGROUP_ORDER, BN254_G1_A_COEFF.clone(), BN254_G1_B_COEFF.clone(), &*BN254_G1_PARAMS).unwrap();
-    
+    let mut curve = WeierstrassCurve::new(&*BN254_SUBGROUP_ORDER, BN254_G1_A_COEFF.clone(), BN254_G1_B_COEFF.clone(), &*BN254_G1_PARAMS).unwrap();
+
+    // cofactor is 1, so every point on the curve is in the subgroup and GLV multiplication is always valid
+    let endomorphism = {
+        let one = Fp::one(&*BN254_BASE_FIELD);
+        let mut two = one;
+        two.double();
+        let generator = CurvePoint::point_from_xy(&curve, one, two);
+
+        crate::weierstrass::glv::detect_glv_endomorphism(&curve, &generator)
+    };
+    debug_assert!(endomorphism.is_some());
+    curve.endomorphism = endomorphism;
+
     curve
 });
 
@@ -1,5 +1,5 @@
-use std::error::Error;
-use std::fmt;
+use core::fmt;
+use crate::alloc_prelude::*;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ApiError {
@@ -10,9 +10,135 @@ pub enum ApiError {
     UnknownParameter(String),
     OutputError(String),
     MissingValue,
+    /// Invalid input with a machine readable reason
+    Coded(CodedError),
+    /// Execution panicked, reported by the protected entry points instead of unwinding
+    /// into the caller. It's always a bug in the library and never a consensus result
+    InternalError(String),
 }
 
-impl Error for ApiError {
+/// Machine readable reason of a failure, so callers can map errors to deterministic
+/// revert reasons without parsing descriptions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    Overflow,
+    UnexpectedZero,
+    DivisionByZero,
+    UnknownParameter,
+    OutputError,
+    MissingValue,
+    /// Input is invalid in a way that has no more specific code
+    InvalidInput,
+    /// Input ends before the value being decoded, or before the total length declared
+    /// in it when that is checked upfront
+    InputTooShort {
+        expected: usize,
+        got: usize,
+    },
+    /// Input has bytes left after the last value
+    GarbageAtTheEnd,
+    InvalidModulus,
+    /// Encoded value is not below the field modulus
+    NotInField,
+    NotOnCurve,
+    NotInSubgroup,
+    /// Group order is not a prime number
+    CompositeGroupOrder,
+    /// Non-residue used to build an extension is zero or a residue
+    NonResidueInvalid,
+    /// Operation is priced above the gas limit or ran out of its operation budget
+    OutOfGas,
+    /// Execution panicked, see `ApiError::InternalError`
+    InternalError,
+}
+
+/// Invalid input together with its code. Decoding always consumes the input from the
+/// beginning, so the position is kept as a number of input bytes that were not consumed
+/// yet when decoding failed, see `ApiError::offset`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodedError {
+    pub code: ErrorCode,
+    pub bytes_left: Option<usize>,
+    pub description: String,
+}
+
+impl ErrorCode {
+    /// Stable name of the code without its fields, used in exported test vectors
+    pub fn name(&self) -> &'static str {
+        match self {
+            ErrorCode::Overflow => "Overflow",
+            ErrorCode::UnexpectedZero => "UnexpectedZero",
+            ErrorCode::DivisionByZero => "DivisionByZero",
+            ErrorCode::UnknownParameter => "UnknownParameter",
+            ErrorCode::OutputError => "OutputError",
+            ErrorCode::MissingValue => "MissingValue",
+            ErrorCode::InvalidInput => "InvalidInput",
+            ErrorCode::InputTooShort { .. } => "InputTooShort",
+            ErrorCode::GarbageAtTheEnd => "GarbageAtTheEnd",
+            ErrorCode::InvalidModulus => "InvalidModulus",
+            ErrorCode::NotInField => "NotInField",
+            ErrorCode::NotOnCurve => "NotOnCurve",
+            ErrorCode::NotInSubgroup => "NotInSubgroup",
+            ErrorCode::CompositeGroupOrder => "CompositeGroupOrder",
+            ErrorCode::NonResidueInvalid => "NonResidueInvalid",
+            ErrorCode::OutOfGas => "OutOfGas",
+            ErrorCode::InternalError => "InternalError",
+        }
+    }
+}
+
+impl ApiError {
+    pub(crate) fn with_code(code: ErrorCode, description: String) -> Self {
+        ApiError::Coded(CodedError {
+            code,
+            bytes_left: None,
+            description,
+        })
+    }
+
+    /// Records that decoding failed when `rest` was still not consumed
+    pub(crate) fn at(self, rest: &[u8]) -> Self {
+        match self {
+            ApiError::Coded(err) => {
+                ApiError::Coded(CodedError {
+                    bytes_left: Some(rest.len()),
+                    ..err
+                })
+            },
+            _ => self
+        }
+    }
+
+    pub(crate) fn garbage_at_the_end(rest: &[u8]) -> Self {
+        Self::with_code(ErrorCode::GarbageAtTheEnd, "Input contains garbage at the end".to_owned()).at(rest)
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ApiError::Overflow => ErrorCode::Overflow,
+            ApiError::UnexpectedZero(_) => ErrorCode::UnexpectedZero,
+            ApiError::InputError(_) => ErrorCode::InvalidInput,
+            ApiError::DivisionByZero => ErrorCode::DivisionByZero,
+            ApiError::UnknownParameter(_) => ErrorCode::UnknownParameter,
+            ApiError::OutputError(_) => ErrorCode::OutputError,
+            ApiError::MissingValue => ErrorCode::MissingValue,
+            ApiError::Coded(err) => err.code,
+            ApiError::InternalError(_) => ErrorCode::InternalError,
+        }
+    }
+
+    /// Byte offset where decoding failed for the input of `input_len` bytes that was passed to
+    /// the API, if the error is attributed to a position
+    pub fn offset(&self, input_len: usize) -> Option<usize> {
+        match self {
+            ApiError::Coded(CodedError { bytes_left: Some(bytes_left), .. }) => input_len.checked_sub(*bytes_left),
+            _ => None
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ApiError {
     fn description(&self) -> &str {
         match *self {
             ApiError::Overflow => "overflow",
@@ -22,6 +148,8 @@ impl Error for ApiError {
             ApiError::UnknownParameter(_) => "parameter has value out of bounds",
             ApiError::OutputError(_) => "error outputing results",
             ApiError::MissingValue => "missing value",
+            ApiError::Coded(_) => "invalid input parameters",
+            ApiError::InternalError(_) => "internal error",
         }
     }
 }
@@ -36,6 +164,34 @@ impl fmt::Display for ApiError {
             ApiError::UnknownParameter(descr) => write!(f, "parameter has value out of bounds, {}", descr),
             ApiError::OutputError(descr) => write!(f, "error outputing results, {}", descr),
             ApiError::MissingValue => write!(f, "missing value"),
+            ApiError::Coded(err) => write!(f, "invalid input parameters, {}", err.description),
+            ApiError::InternalError(descr) => write!(f, "internal error, {}", descr),
         }
     }
-}
\ No newline at end of file
+}
+
+/// Runs `f` and reports a panic inside of it as `ApiError::InternalError`, so that it does not
+/// unwind across the C boundary or into the host. Without `std` panics can not be caught and `f`
+/// is just called
+#[cfg(feature = "std")]
+pub(crate) fn catch_internal_error<T, F: FnOnce() -> Result<T, ApiError>>(f: F) -> Result<T, ApiError> {
+    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)) {
+        Ok(result) => result,
+        Err(payload) => {
+            let description = if let Some(message) = payload.downcast_ref::<&str>() {
+                (*message).to_owned()
+            } else if let Some(message) = payload.downcast_ref::<String>() {
+                message.clone()
+            } else {
+                "execution panicked".to_owned()
+            };
+
+            Err(ApiError::InternalError(description))
+        }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn catch_internal_error<T, F: FnOnce() -> Result<T, ApiError>>(f: F) -> Result<T, ApiError> {
+    f()
+}
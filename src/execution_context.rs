@@ -0,0 +1,85 @@
+//! State of a single `API` call that the arithmetic needs besides the input itself:
+//! the encoding of the points given by the ABI version of the input, the limits of
+//! `API::run_with_config`, the operation budget of `API::run_with_limit` and the execution
+//! report of `API::run_with_metadata`.
+//! The context is passed by reference from the entry point down to the decoders, Miller loops
+//! and multiexponentiations, so the worker threads of a parallel Miller loop see the same
+//! state as the calling thread
+
+use crate::operation_budget::OperationBudget;
+use crate::execution_report::Recorder;
+use crate::public_interface::{Limits, PointEncoding};
+
+/// Context of one call. `Default` is the context of `API::run`: default limits,
+/// no operation budget and nothing recorded
+#[derive(Default)]
+pub struct ExecutionContext {
+    point_encoding: PointEncoding,
+    limits: Limits,
+    budget: OperationBudget,
+    report: Recorder,
+}
+
+impl ExecutionContext {
+    /// Context of a call that checks the input against `limits`
+    pub(crate) fn with_limits(limits: Limits) -> Self {
+        Self {
+            limits,
+            ..Self::default()
+        }
+    }
+
+    /// Context of a call that may perform at most `operations` budget operations
+    #[cfg(feature = "gas_metering")]
+    pub(crate) fn with_budget(operations: u64) -> Self {
+        Self {
+            budget: OperationBudget::new(operations),
+            ..Self::default()
+        }
+    }
+
+    /// Context of a call that records an execution report
+    #[cfg(feature = "execution_report")]
+    pub(crate) fn with_report() -> Self {
+        Self {
+            report: Recorder::new(),
+            ..Self::default()
+        }
+    }
+
+    /// Set once the ABI version of the input is known, before the operation runs
+    pub(crate) fn set_point_encoding(&mut self, point_encoding: PointEncoding) {
+        self.point_encoding = point_encoding;
+    }
+
+    #[inline]
+    pub(crate) fn point_encoding(&self) -> PointEncoding {
+        self.point_encoding
+    }
+
+    #[inline]
+    pub(crate) fn limits(&self) -> &Limits {
+        &self.limits
+    }
+
+    #[inline]
+    pub(crate) fn report(&self) -> &Recorder {
+        &self.report
+    }
+
+    /// Charges the operation budget, returns `false` if it's exhausted and the caller should stop
+    #[inline]
+    pub(crate) fn charge(&self, operations: usize) -> bool {
+        self.budget.charge(operations)
+    }
+
+    #[cfg(feature = "gas_metering")]
+    pub(crate) fn budget_is_exhausted(&self) -> bool {
+        self.budget.is_exhausted()
+    }
+
+    #[cfg(all(test, feature = "gas_metering"))]
+    pub(crate) fn remaining_budget(&self) -> Option<u64> {
+        self.budget.remaining()
+    }
+}
@@ -0,0 +1,184 @@
+//! Metadata of what was actually executed by `API::run_with_metadata`. The recorder is a part of
+//! the `ExecutionContext` of the call, so the chunks of a parallel Miller loop count into the
+//! report of the call on whatever thread they run. Base field multiplications don't see the
+//! context, they are counted by the thread and the count over the call (and over every chunk)
+//! is moved into the recorder. Without the `execution_report` feature the recorder is empty and
+//! recording is a no-op, so the arithmetic does not pay for it
+
+#[cfg(feature = "execution_report")]
+pub use self::recorder::ExecutionReport;
+
+pub(crate) use self::recorder::{Recorder, count_field_multiplication};
+
+#[cfg(feature = "execution_report")]
+mod recorder {
+    use core::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+    use std::cell::Cell;
+    use std::time::Duration;
+
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct ExecutionReport {
+        /// Number of limbs used for the base field arithmetic
+        pub parsed_limbs: usize,
+        /// Number of (point, scalar) pairs, or of (G1, G2) pairs that the Miller loop runs over
+        /// (pairs with a point at infinity are skipped). Zero for operations without pairs
+        pub num_pairs: usize,
+        /// Multiplications and squarings in the base prime field, extension field
+        /// multiplications are counted through them
+        pub field_multiplications: u64,
+        /// Line function evaluations multiplied into the Miller loop accumulator, summed over
+        /// all the pairs. Zero for operations other than pairings
+        pub line_evaluations: u64,
+        /// Time spent in the Miller loops of a pairing, including the preparation of the G2 points
+        pub miller_loop_elapsed: Duration,
+        /// Time spent in the final exponentiation of a pairing
+        pub final_exp_elapsed: Duration,
+        pub elapsed: Duration,
+    }
+
+    thread_local! {
+        static FIELD_MULTIPLICATIONS: Cell<u64> = Cell::new(0);
+    }
+
+    #[inline(always)]
+    pub(crate) fn count_field_multiplication() {
+        FIELD_MULTIPLICATIONS.with(|c| c.set(c.get().wrapping_add(1)));
+    }
+
+    #[derive(Default)]
+    struct Counters {
+        parsed_limbs: AtomicUsize,
+        num_pairs: AtomicUsize,
+        field_multiplications: AtomicU64,
+        line_evaluations: AtomicU64,
+        miller_loop_nanos: AtomicU64,
+        final_exp_nanos: AtomicU64,
+    }
+
+    /// Counters of a call, nothing is recorded unless the call was started by `run_with_metadata`
+    #[derive(Default)]
+    pub(crate) struct Recorder {
+        counters: Option<Counters>,
+    }
+
+    impl Recorder {
+        pub(crate) fn new() -> Self {
+            Self {
+                counters: Some(Counters::default())
+            }
+        }
+
+        /// Only the first modulus parsed by the operation is recorded
+        pub(crate) fn record_limbs(&self, limbs: usize) {
+            if let Some(counters) = self.counters.as_ref() {
+                let _ = counters.parsed_limbs.compare_exchange(0, limbs, Ordering::Relaxed, Ordering::Relaxed);
+            }
+        }
+
+        pub(crate) fn record_num_pairs(&self, num_pairs: usize) {
+            if let Some(counters) = self.counters.as_ref() {
+                counters.num_pairs.store(num_pairs, Ordering::Relaxed);
+            }
+        }
+
+        #[inline(always)]
+        pub(crate) fn count_line_evaluation(&self) {
+            if let Some(counters) = self.counters.as_ref() {
+                counters.line_evaluations.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        /// Moves the field multiplications made by `f` on this thread into the recorder. The count
+        /// of the thread is restored after `f`, so a chunk that runs on the calling thread is not
+        /// counted twice by an enclosing call
+        pub(crate) fn count_field_multiplications<T, F: FnOnce() -> T>(&self, f: F) -> T {
+            let counters = match self.counters.as_ref() {
+                Some(counters) => counters,
+                None => return f()
+            };
+
+            let before = FIELD_MULTIPLICATIONS.with(|c| c.get());
+            let result = f();
+            let after = FIELD_MULTIPLICATIONS.with(|c| c.replace(before));
+            counters.field_multiplications.fetch_add(after.wrapping_sub(before), Ordering::Relaxed);
+
+            result
+        }
+
+        fn timed<T, F: FnOnce() -> T>(&self, total: impl Fn(&Counters) -> &AtomicU64, f: F) -> T {
+            let counters = match self.counters.as_ref() {
+                Some(counters) => counters,
+                None => return f()
+            };
+
+            let start = std::time::Instant::now();
+            let result = f();
+            let elapsed = start.elapsed().as_nanos() as u64;
+            total(counters).fetch_add(elapsed, Ordering::Relaxed);
+
+            result
+        }
+
+        /// Times are accumulated, so a pairing that is split into multiple Miller loops
+        /// (or final exponentiations) records the sum of them
+        pub(crate) fn time_miller_loop<T, F: FnOnce() -> T>(&self, f: F) -> T {
+            self.timed(|c| &c.miller_loop_nanos, f)
+        }
+
+        pub(crate) fn time_final_exp<T, F: FnOnce() -> T>(&self, f: F) -> T {
+            self.timed(|c| &c.final_exp_nanos, f)
+        }
+
+        pub(crate) fn finish(&self, elapsed: Duration) -> ExecutionReport {
+            let counters = match self.counters.as_ref() {
+                Some(counters) => counters,
+                None => return ExecutionReport { elapsed, ..ExecutionReport::default() }
+            };
+
+            ExecutionReport {
+                parsed_limbs: counters.parsed_limbs.load(Ordering::Relaxed),
+                num_pairs: counters.num_pairs.load(Ordering::Relaxed),
+                field_multiplications: counters.field_multiplications.load(Ordering::Relaxed),
+                line_evaluations: counters.line_evaluations.load(Ordering::Relaxed),
+                miller_loop_elapsed: Duration::from_nanos(counters.miller_loop_nanos.load(Ordering::Relaxed)),
+                final_exp_elapsed: Duration::from_nanos(counters.final_exp_nanos.load(Ordering::Relaxed)),
+                elapsed,
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "execution_report"))]
+mod recorder {
+    #[derive(Default)]
+    pub(crate) struct Recorder;
+
+    impl Recorder {
+        #[inline(always)]
+        pub(crate) fn record_limbs(&self, _limbs: usize) {}
+
+        #[inline(always)]
+        pub(crate) fn record_num_pairs(&self, _num_pairs: usize) {}
+
+        #[inline(always)]
+        pub(crate) fn count_line_evaluation(&self) {}
+
+        #[inline(always)]
+        pub(crate) fn count_field_multiplications<T, F: FnOnce() -> T>(&self, f: F) -> T {
+            f()
+        }
+
+        #[inline(always)]
+        pub(crate) fn time_miller_loop<T, F: FnOnce() -> T>(&self, f: F) -> T {
+            f()
+        }
+
+        #[inline(always)]
+        pub(crate) fn time_final_exp<T, F: FnOnce() -> T>(&self, f: F) -> T {
+            f()
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn count_field_multiplication() {}
+}
@@ -1,9 +1,11 @@
 use crate::field::{SizedPrimeField};
-use crate::representation::ElementRepr;
+use crate::representation::{ElementRepr, RepresentationDecodingError};
 use crate::traits::{FieldElement, BitIterator, FieldExtension, ZeroAndOne};
-use super::fp6_as_3_over_2::{Fp6, Extension3Over2};
-use super::fp2::Fp2;
+use super::fp6_as_3_over_2::{Fp6, WideFp6, Extension3Over2};
+use super::fp2::{Fp2, NonResidueMulPolicy};
 use super::Fp6Fp12FrobeniusBaseElements;
+use super::split_coefficients_encoding;
+use crate::alloc_prelude::*;
 
 // this implementation assumes extension using polynomial w^2 - v = 0
 pub struct Fp12<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >{
@@ -12,14 +14,14 @@ pub struct Fp12<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >{
     pub extension_field: &'a Extension2Over3Over2<'a, E, F>
 }
 
-impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >std::fmt::Display for Fp12<'a, E, F> {
-    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >core::fmt::Display for Fp12<'a, E, F> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
         write!(f, "Fq12({} + {} * w)", self.c0, self.c1)
     }
 }
 
-impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >std::fmt::Debug for Fp12<'a, E, F> {
-    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >core::fmt::Debug for Fp12<'a, E, F> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
         write!(f, "Fq12({} + {} * w)", self.c0, self.c1)
     }
 }
@@ -49,6 +51,20 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Eq for Fp12<'a, E, F> {
 }
 
 impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Fp12<'a, E, F> {
+    /// Decodes an element from the concatenation of equal length big endian encodings
+    /// of `c0` and `c1`
+    pub fn from_be_bytes(extension_field: &'a Extension2Over3Over2<'a, E, F>, bytes: &[u8]) -> Result<Self, RepresentationDecodingError> {
+        let encodings = split_coefficients_encoding(bytes, 2)?;
+        let c0 = Fp6::from_be_bytes(extension_field.field, encodings[0])?;
+        let c1 = Fp6::from_be_bytes(extension_field.field, encodings[1])?;
+
+        Ok(Self {
+            c0,
+            c1,
+            extension_field
+        })
+    }
+
     pub fn mul_by_034(
         &mut self,
         c0: & Fp2<'a, E, F>,
@@ -224,6 +240,189 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Fp12<'a, E, F> {
         self.c1.c2 = g5;
     }
 
+    // Karabina's compressed squaring, https://eprint.iacr.org/2010/542.pdf. Only
+    // g1 = c0.c1, g2 = c0.c2, g3 = c1.c0 and g5 = c1.c2 are updated, c0.c0 and c1.c1
+    // are left as is and must be restored by `karabina_decompress`
+    fn karabina_square_compressed(&mut self) {
+        let g1 = self.c0.c1;
+        let g2 = self.c0.c2;
+        let g3 = self.c1.c0;
+        let g5 = self.c1.c2;
+
+        let mut g1_squared = g1;
+        g1_squared.square();
+        let mut g5_squared = g5;
+        g5_squared.square();
+        let mut g2_squared = g2;
+        g2_squared.square();
+        let mut g3_squared = g3;
+        g3_squared.square();
+
+        // 2 * g1 * g5 = (g1 + g5)^2 - g1^2 - g5^2
+        let mut g1_g5 = g1;
+        g1_g5.add_assign(&g5);
+        g1_g5.square();
+        g1_g5.sub_assign(&g1_squared);
+        g1_g5.sub_assign(&g5_squared);
+
+        // 2 * g2 * g3 = (g2 + g3)^2 - g2^2 - g3^2
+        let mut g2_g3 = g2;
+        g2_g3.add_assign(&g3);
+        g2_g3.square();
+        g2_g3.sub_assign(&g2_squared);
+        g2_g3.sub_assign(&g3_squared);
+
+        // g1' = 3 * (g3^2 + xi * g2^2) - 2 * g1
+        let mut t = g2_squared;
+        t.mul_by_nonresidue(self.extension_field.field);
+        t.add_assign(&g3_squared);
+        let mut g1_new = t;
+        g1_new.sub_assign(&g1);
+        g1_new.double();
+        g1_new.add_assign(&t);
+
+        // g2' = 3 * (g1^2 + xi * g5^2) - 2 * g2
+        let mut t = g5_squared;
+        t.mul_by_nonresidue(self.extension_field.field);
+        t.add_assign(&g1_squared);
+        let mut g2_new = t;
+        g2_new.sub_assign(&g2);
+        g2_new.double();
+        g2_new.add_assign(&t);
+
+        // g3' = 3 * xi * (2 * g1 * g5) + 2 * g3
+        let mut t = g1_g5;
+        t.mul_by_nonresidue(self.extension_field.field);
+        let mut g3_new = t;
+        g3_new.add_assign(&g3);
+        g3_new.double();
+        g3_new.add_assign(&t);
+
+        // g5' = 3 * (2 * g2 * g3) + 2 * g5
+        let mut g5_new = g2_g3;
+        g5_new.add_assign(&g5);
+        g5_new.double();
+        g5_new.add_assign(&g2_g3);
+
+        self.c0.c1 = g1_new;
+        self.c0.c2 = g2_new;
+        self.c1.c0 = g3_new;
+        self.c1.c2 = g5_new;
+    }
+
+    // g4 = numerator / denominator, so that many elements are decompressed with one inversion
+    fn karabina_g4_fraction(&self) -> (Fp2<'a, E, F>, Fp2<'a, E, F>) {
+        let g1 = self.c0.c1;
+        let g2 = self.c0.c2;
+        let g3 = self.c1.c0;
+        let g5 = self.c1.c2;
+
+        if g3.is_zero() {
+            // g4 = 2 * g1 * g5 / g2
+            let mut numerator = g1;
+            numerator.mul_assign(&g5);
+            numerator.double();
+
+            (numerator, g2)
+        } else {
+            // g4 = (xi * g5^2 + 3 * g1^2 - 2 * g2) / (4 * g3)
+            let mut g1_squared = g1;
+            g1_squared.square();
+            let mut numerator = g1_squared;
+            numerator.sub_assign(&g2);
+            numerator.double();
+            numerator.add_assign(&g1_squared);
+            let mut t = g5;
+            t.square();
+            t.mul_by_nonresidue(self.extension_field.field);
+            numerator.add_assign(&t);
+
+            let mut denominator = g3;
+            denominator.double();
+            denominator.double();
+
+            (numerator, denominator)
+        }
+    }
+
+    // restores c1.c1 = g4 and then c0.c0 = xi * (2 * g4^2 + g3 * g5 - 3 * g1 * g2) + 1
+    fn karabina_decompress(&mut self, g4: Fp2<'a, E, F>) {
+        let mut g1_g2 = self.c0.c1;
+        g1_g2.mul_assign(&self.c0.c2);
+        let mut g3_g5 = self.c1.c0;
+        g3_g5.mul_assign(&self.c1.c2);
+
+        let mut g0 = g4;
+        g0.square();
+        g0.sub_assign(&g1_g2);
+        g0.double();
+        g0.sub_assign(&g1_g2);
+        g0.add_assign(&g3_g5);
+        g0.mul_by_nonresidue(self.extension_field.field);
+        g0.add_assign(&Fp2::one(self.extension_field.field.field));
+
+        self.c0.c0 = g0;
+        self.c1.c1 = g4;
+    }
+
+    /// Exponentiation in the cyclotomic subgroup with Karabina's compressed squarings. Powers
+    /// at the set bits of the exponent are kept compressed and are decompressed together with
+    /// a single inversion, so it's only beneficial for exponents of a low Hamming weight, such
+    /// as the BLS12 and BN curve parameters. Falls back to `cyclotomic_exp` if some power can
+    /// not be decompressed
+    pub fn cyclotomic_exp_compressed<S: AsRef<[u64]>>(&self, exp: S) -> Self {
+        let exp = exp.as_ref();
+        let num_bits = crate::pairings::calculate_bits(exp) as usize;
+        if num_bits == 0 {
+            return Self::one(self.extension_field);
+        }
+
+        let mut result = Self::one(self.extension_field);
+        let mut compressed = vec![];
+        let mut power = *self;
+        for i in 0..num_bits {
+            if i != 0 {
+                power.karabina_square_compressed();
+            }
+            if (exp[i / 64] >> (i % 64)) & 1 == 1 {
+                if i == 0 {
+                    result = power;
+                } else {
+                    compressed.push(power);
+                }
+            }
+        }
+
+        let fractions: Vec<_> = compressed.iter().map(|el| el.karabina_g4_fraction()).collect();
+
+        // batch inversion of the denominators
+        let mut prefix_products = Vec::with_capacity(fractions.len());
+        let mut acc = Fp2::one(self.extension_field.field.field);
+        for (_, denominator) in fractions.iter() {
+            prefix_products.push(acc);
+            acc.mul_assign(denominator);
+        }
+        let mut acc_inverse = match acc.inverse() {
+            Some(inverse) => inverse,
+            None => {
+                return self.cyclotomic_exp(exp);
+            }
+        };
+
+        for ((el, (numerator, denominator)), prefix) in compressed.iter_mut().zip(fractions.iter()).zip(prefix_products.iter()).rev() {
+            let mut denominator_inverse = acc_inverse;
+            denominator_inverse.mul_assign(prefix);
+            acc_inverse.mul_assign(denominator);
+
+            let mut g4 = *numerator;
+            g4.mul_assign(&denominator_inverse);
+            el.karabina_decompress(g4);
+            result.mul_assign(el);
+        }
+
+        result
+    }
+
     pub fn cyclotomic_exp<S: AsRef<[u64]>>(&self, exp: S) -> Self {
         let mut res = Self::one(&self.extension_field);
 
@@ -321,6 +520,29 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for Fp12<'a
 
     fn mul_assign(&mut self, other: &Self)
     {
+        if self.extension_field.field.field.non_residue_mul_policy == NonResidueMulPolicy::Negate {
+            // lazy reduction: 18 base field products of Fp6 multiplications are
+            // combined unreduced and every coefficient is reduced once
+            let aa = WideFp6::mul(&self.c0, &other.c0);
+            let bb = WideFp6::mul(&self.c1, &other.c1);
+            let mut a = self.c0;
+            a.add_assign(&self.c1);
+            let mut b = other.c0;
+            b.add_assign(&other.c1);
+
+            let mut c1 = WideFp6::mul(&a, &b);
+            c1.sub_assign(&aa);
+            c1.sub_assign(&bb);
+
+            let mut c0 = bb.mul_by_v();
+            c0.add_assign(&aa);
+
+            self.c0 = c0.reduce();
+            self.c1 = c1.reduce();
+
+            return;
+        }
+
         let mut aa = self.c0;
         aa.mul_assign(&other.c0);
         let mut bb = self.c1;
@@ -429,7 +651,9 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Clone for Extension2Over
 use crate::integers::*;
 
 impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Extension2Over3Over2<'a, E, F> {
-    pub (crate) fn new(non_residue: Fp6<'a, E, F>) -> Self {
+    /// Creates an extension with a given non-residue. Frobenius coefficients
+    /// must be calculated separately before using Frobenius maps
+    pub fn new(non_residue: Fp6<'a, E, F>) -> Self {
         let extension_2 = &non_residue.extension_field.field;
 
         Self {
@@ -443,8 +667,8 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Extension2Over3Over2<'a,
         }
     }
 
-    #[allow(dead_code)]
-    pub(crate) fn calculate_frobenius_coeffs_optimized(
+    /// Calculates coefficients for Frobenius maps from the modulus of the base prime field
+    pub fn calculate_frobenius_coeffs_optimized(
         &mut self,
         modulus: &MaxFieldUint,
     ) -> Result<(), ()> {    
@@ -0,0 +1,354 @@
+use crate::field::{SizedPrimeField};
+use crate::representation::ElementRepr;
+use crate::traits::{FieldElement, BitIterator, FieldExtension, ZeroAndOne};
+use super::fp8_as_2_over_4_generic::{Fp8, Extension2Over4};
+use super::fp4_as_2_over_2::Fp4;
+use super::Fp8Fp16FrobeniusBaseElements;
+
+// this implementation assumes extension using polynomial t^2 - s = 0
+// on top of Fp8 = Fp4[s]/(s^2 - xi), so Fp16 is Fp4[t]/(t^4 - xi)
+pub struct Fp16<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >{
+    pub c0: Fp8<'a, E, F>,
+    pub c1: Fp8<'a, E, F>,
+    pub extension_field: &'a Extension2Over8<'a, E, F>
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >core::fmt::Display for Fp16<'a, E, F> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "Fq16({} + {} * t)", self.c0, self.c1)
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >core::fmt::Debug for Fp16<'a, E, F> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "Fq16({} + {} * t)", self.c0, self.c1)
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Clone for Fp16<'a, E, F> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self{
+            c0: self.c0,
+            c1: self.c1,
+            extension_field: self.extension_field
+        }
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Copy for Fp16<'a, E, F> {}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > PartialEq for Fp16<'a, E, F> {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        self.c0 == other.c0 &&
+        self.c1 == other.c1
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Eq for Fp16<'a, E, F> {
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Fp16<'a, E, F> {
+    // multiplication by c0 + (c2 + c3 * s) * t, that is the form of line functions
+    // for quartic twists
+    pub fn mul_by_023(
+        &mut self,
+        c0: & Fp4<'a, E, F>,
+        c2: & Fp4<'a, E, F>,
+        c3: & Fp4<'a, E, F>,
+    ) {
+        let mut aa = self.c0;
+        aa.c0.mul_assign(c0);
+        aa.c1.mul_assign(c0);
+
+        let mut y1 = Fp8::zero(self.extension_field.field);
+        y1.c0 = *c2;
+        y1.c1 = *c3;
+
+        let mut bb = self.c1;
+        bb.mul_assign(&y1);
+
+        let mut o = y1;
+        o.c0.add_assign(c0);
+
+        self.c1.add_assign(&self.c0);
+        self.c1.mul_assign(&o);
+        self.c1.sub_assign(&aa);
+        self.c1.sub_assign(&bb);
+        self.c0 = bb;
+        self.c0.mul_by_nonresidue(self.extension_field);
+        self.c0.add_assign(&aa);
+    }
+
+    // squaring of an element of norm one over Fp8, c0^2 - s * c1^2 = 1. Then
+    // c0' = 1 + 2 * s * c1^2 and c1' = 2 * c0 * c1 = (c0 + c1)^2 - 1 - (1 + s) * c1^2,
+    // so there are only two squarings in Fp8
+    pub fn cyclotomic_square(&mut self) {
+        let one = Fp8::one(self.extension_field.field);
+
+        let mut t = self.c1;
+        t.square();
+        let mut s_t = t;
+        s_t.mul_by_nonresidue(self.extension_field);
+
+        let mut c1 = self.c0;
+        c1.add_assign(&self.c1);
+        c1.square();
+        c1.sub_assign(&one);
+        c1.sub_assign(&t);
+        c1.sub_assign(&s_t);
+
+        self.c0 = s_t;
+        self.c0.double();
+        self.c0.add_assign(&one);
+        self.c1 = c1;
+    }
+
+    pub fn cyclotomic_exp<S: AsRef<[u64]>>(&self, exp: S) -> Self {
+        let mut res = Self::one(self.extension_field);
+        let mut self_inverse = *self;
+        self_inverse.conjugate();
+
+        let mut found_nonzero = false;
+        use crate::pairings::into_ternary_wnaf;
+        let naf = into_ternary_wnaf(exp.as_ref());
+
+        for &value in naf.iter().rev() {
+            if found_nonzero {
+                res.cyclotomic_square();
+            }
+
+            if value != 0 {
+                found_nonzero = true;
+
+                if value > 0 {
+                    res.mul_assign(&self);
+                } else {
+                    res.mul_assign(&self_inverse);
+                }
+            }
+        }
+
+        res
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > ZeroAndOne for Fp16<'a, E, F> {
+    type Params = &'a Extension2Over8<'a, E, F>;
+
+    fn zero(extension_field: &'a Extension2Over8<'a, E, F>) -> Self {
+        let zero = Fp8::zero(extension_field.field);
+
+        Self {
+            c0: zero,
+            c1: zero,
+            extension_field: extension_field
+        }
+    }
+
+    fn one(extension_field: &'a Extension2Over8<'a, E, F>) -> Self {
+        let zero = Fp8::zero(extension_field.field);
+        let one = Fp8::one(extension_field.field);
+
+        Self {
+            c0: one,
+            c1: zero,
+            extension_field: extension_field
+        }
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for Fp16<'a, E, F> {
+    /// Returns true iff this element is zero.
+    fn is_zero(&self) -> bool {
+        self.c0.is_zero() &&
+        self.c1.is_zero()
+    }
+
+    fn add_assign(&mut self, other: &Self) {
+        self.c0.add_assign(&other.c0);
+        self.c1.add_assign(&other.c1);
+    }
+
+    fn double(&mut self) {
+        self.c0.double();
+        self.c1.double();
+    }
+
+    fn sub_assign(&mut self, other: &Self) {
+        self.c0.sub_assign(&other.c0);
+        self.c1.sub_assign(&other.c1);
+    }
+
+    fn negate(&mut self) {
+        self.c0.negate();
+        self.c1.negate();
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        let mut c0s = self.c0;
+        c0s.square();
+        let mut c1s = self.c1;
+        c1s.square();
+        c1s.mul_by_nonresidue(self.extension_field);
+        c0s.sub_assign(&c1s);
+
+        c0s.inverse().map(|t| {
+            let mut tmp = Fp16 {
+                c0: t,
+                c1: t,
+                extension_field: self.extension_field
+            };
+            tmp.c0.mul_assign(&self.c0);
+            tmp.c1.mul_assign(&self.c1);
+            tmp.c1.negate();
+
+            tmp
+        })
+    }
+
+    fn mul_assign(&mut self, other: &Self)
+    {
+        let mut aa = self.c0;
+        aa.mul_assign(&other.c0);
+        let mut bb = self.c1;
+        bb.mul_assign(&other.c1);
+        let mut o = other.c0;
+        o.add_assign(&other.c1);
+        self.c1.add_assign(&self.c0);
+        self.c1.mul_assign(&o);
+        self.c1.sub_assign(&aa);
+        self.c1.sub_assign(&bb);
+        self.c0 = bb;
+        self.c0.mul_by_nonresidue(self.extension_field);
+        self.c0.add_assign(&aa);
+    }
+
+    fn square(&mut self)
+    {
+        let mut ab = self.c0;
+        ab.mul_assign(&self.c1);
+        let mut c0c1 = self.c0;
+        c0c1.add_assign(&self.c1);
+        let mut c0 = self.c1;
+        c0.mul_by_nonresidue(self.extension_field);
+        c0.add_assign(&self.c0);
+        c0.mul_assign(&c0c1);
+        c0.sub_assign(&ab);
+        self.c1 = ab;
+        self.c1.add_assign(&ab);
+        ab.mul_by_nonresidue(self.extension_field);
+        c0.sub_assign(&ab);
+        self.c0 = c0;
+    }
+
+    fn conjugate(&mut self) {
+        self.c1.negate();
+    }
+
+    fn pow<S: AsRef<[u64]>>(&self, exp: S) -> Self {
+        let mut res = Self::one(&self.extension_field);
+
+        let mut found_one = false;
+
+        for i in BitIterator::new(exp) {
+            if found_one {
+                res.square();
+            } else {
+                found_one = i;
+            }
+
+            if i {
+                res.mul_assign(self);
+            }
+        }
+
+        res
+    }
+
+    fn mul_by_nonresidue<EXT: FieldExtension<Element = Self>>(&mut self, _for_extesion: &EXT) {
+        unreachable!();
+    }
+
+    fn frobenius_map(&mut self, power: usize) {
+        assert!(self.extension_field.frobenius_coeffs_are_calculated);
+        self.c0.frobenius_map(power);
+        self.c1.frobenius_map(power);
+
+        let coeff = &self.extension_field.frobenius_coeffs_c1[power % 16];
+        self.c1.c0.mul_assign(coeff);
+        self.c1.c1.mul_assign(coeff);
+    }
+}
+
+pub struct Extension2Over8<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > {
+    pub(crate) non_residue: Fp8<'a, E, F>,
+    pub(crate) field: &'a Extension2Over4<'a, E, F>,
+    pub(crate) frobenius_coeffs_c1: [Fp4<'a, E, F>; 16],
+    pub(crate) frobenius_coeffs_are_calculated: bool
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Clone for Extension2Over8<'a, E, F> {
+    fn clone(&self) -> Self {
+        Self {
+            non_residue: self.non_residue,
+            field: self.field,
+            frobenius_coeffs_c1: self.frobenius_coeffs_c1,
+            frobenius_coeffs_are_calculated: self.frobenius_coeffs_are_calculated
+        }
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Extension2Over8<'a, E, F> {
+    pub (crate) fn new(non_residue: Fp8<'a, E, F>) -> Self {
+        let extension_4 = non_residue.extension_field.field;
+
+        Self {
+            non_residue: non_residue,
+            field: non_residue.extension_field,
+            frobenius_coeffs_c1: [Fp4::zero(extension_4); 16],
+            frobenius_coeffs_are_calculated: false
+        }
+    }
+
+    pub(crate) fn calculate_frobenius_coeffs_with_precomp(
+        &mut self,
+        precomp: &Fp8Fp16FrobeniusBaseElements<'a, E, F>
+    ) -> Result<(), ()> {
+        // c1[k] = NON_RESIDUE**((q^k - 1) / 4) for k = 0..15
+        // c1[k] = c1[k-1].frobenius(1) * c1[1] as
+        // (q^k - 1) / 4 = ((q^(k-1) - 1) / 4) * q + (q - 1) / 4
+        let f_0 = Fp4::one(self.field.field);
+        let f_1 = precomp.non_residue_in_q_minus_one_by_four;
+
+        let mut coeffs_c1 = [f_0; 16];
+        for k in 1..16 {
+            let mut f_k = coeffs_c1[k-1];
+            f_k.frobenius_map(1);
+            f_k.mul_assign(&f_1);
+            coeffs_c1[k] = f_k;
+        }
+
+        self.frobenius_coeffs_c1 = coeffs_c1;
+        self.frobenius_coeffs_are_calculated = true;
+
+        Ok(())
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldExtension for Extension2Over8<'a, E, F> {
+    const EXTENSION_DEGREE: usize = 2;
+
+    type Element = Fp8<'a, E, F>;
+
+    fn multiply_by_non_residue(&self, el: &mut Self::Element) {
+        // IMPORTANT: This only works cause the structure of extension field for Fp16
+        // is t^2 - s = 0!
+        // (c0 + c1 * s)*s with s^2 - xi = 0 -> (c1*xi + c0 * s)
+        let mut new_c0 = el.c1;
+        new_c0.mul_by_nonresidue(&*el.extension_field);
+        el.c1 = el.c0;
+        el.c0 = new_c0;
+    }
+}
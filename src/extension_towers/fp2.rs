@@ -1,10 +1,12 @@
-use crate::fp::Fp;
+use crate::fp::{Fp, WideFp};
 use crate::field::{SizedPrimeField};
-use crate::representation::ElementRepr;
+use crate::representation::{ElementRepr, RepresentationDecodingError};
 use crate::traits::{FieldElement, BitIterator, FieldExtension};
-use crate::traits::ZeroAndOne;
+use crate::traits::{ZeroAndOne, ConditionalSelect};
 use crate::integers::*;
+use crate::alloc_prelude::*;
 use super::Fp2Fp4FrobeniusBaseElements;
+use super::split_coefficients_encoding;
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum NonResidueMulPolicy {
@@ -19,14 +21,14 @@ pub struct Fp2<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >{
     pub extension_field: &'a Extension2<'a, E, F>,
 }
 
-impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >std::fmt::Display for Fp2<'a, E, F> {
-    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >core::fmt::Display for Fp2<'a, E, F> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
         write!(f, "Fq2({} + {} * u)", self.c0, self.c1)
     }
 }
 
-impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >std::fmt::Debug for Fp2<'a, E, F> {
-    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >core::fmt::Debug for Fp2<'a, E, F> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
         write!(f, "Fq2({} + {} * u)", self.c0, self.c1)
     }
 }
@@ -56,6 +58,20 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Eq for Fp2<'a, E, F> {
 }
 
 impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Fp2<'a, E, F> {
+    /// Decodes an element from the concatenation of equal length big endian encodings
+    /// of `c0` and `c1`
+    pub fn from_be_bytes(extension_field: &'a Extension2<'a, E, F>, bytes: &[u8]) -> Result<Self, RepresentationDecodingError> {
+        let encodings = split_coefficients_encoding(bytes, 2)?;
+        let c0 = Fp::from_be_bytes(extension_field.field, encodings[0], true)?;
+        let c1 = Fp::from_be_bytes(extension_field.field, encodings[1], true)?;
+
+        Ok(Self {
+            c0,
+            c1,
+            extension_field
+        })
+    }
+
     pub fn mul_by_fp(&mut self, element: &Fp<'a, E, F>) {
         self.c0.mul_assign(&element);
         self.c1.mul_assign(&element);
@@ -72,6 +88,21 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Fp2<'a, E, F> {
 
         t1
     }
+
+    /// One of the square roots if the element is a square, see `square_root::sqrt_ext2`
+    pub fn sqrt(&self) -> Option<Self> {
+        crate::square_root::sqrt_ext2(self)
+    }
+}
+
+/// Checks whether an element is a square in Fp2. For a non-zero `a` we have
+/// a^((p^2 - 1)/2) = (a^(p + 1))^((p - 1)/2) = norm(a)^((p - 1)/2), so `a` is a square
+/// in Fp2 iff its norm is a square in the base field, see `square_root::legendre_symbol_fp2`.
+/// Zero is treated as a square.
+pub fn is_quadratic_residue<'a, E: ElementRepr, F: SizedPrimeField<Repr = E>>(element: &Fp2<'a, E, F>) -> bool {
+    use crate::square_root::{legendre_symbol_fp2, LegendreSymbol};
+
+    legendre_symbol_fp2(element) != LegendreSymbol::QuadraticNonResidue
 }
 
 impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > ZeroAndOne for Fp2<'a, E, F> {
@@ -100,6 +131,25 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > ZeroAndOne for Fp2<'a, E
     }
 }
 
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > ConditionalSelect for Fp2<'a, E, F> {
+    #[inline(always)]
+    fn conditional_select(a: &Self, b: &Self, choice: bool) -> Self {
+        Self {
+            c0: Fp::conditional_select(&a.c0, &b.c0, choice),
+            c1: Fp::conditional_select(&a.c1, &b.c1, choice),
+            extension_field: a.extension_field
+        }
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > zeroize::Zeroize for Fp2<'a, E, F> {
+    fn zeroize(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.c0);
+        zeroize::Zeroize::zeroize(&mut self.c1);
+    }
+}
+
 impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for Fp2<'a, E, F> {
     /// Returns true iff this element is zero.
     fn is_zero(&self) -> bool {
@@ -160,6 +210,12 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for Fp2<'a,
 
     fn mul_assign(&mut self, other: &Self)
     {
+        if self.extension_field.non_residue_mul_policy == NonResidueMulPolicy::Negate {
+            *self = WideFp2::mul(self, other).reduce();
+
+            return;
+        }
+
         let mut v0 = self.c0;
         v0.mul_assign(&other.c0);
         let mut v1 = self.c1;
@@ -176,6 +232,39 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for Fp2<'a,
         self.c0.add_assign(&v1);
     }
 
+    fn batch_mul_assign(a: &mut [Self], b: &[Self]) {
+        debug_assert_eq!(a.len(), b.len());
+        // base field products of `mul_assign` for all the elements go as one batch
+        let n = a.len();
+        let mut lhs = Vec::with_capacity(3 * n);
+        let mut rhs = Vec::with_capacity(3 * n);
+        lhs.extend(a.iter().map(|a| a.c0));
+        rhs.extend(b.iter().map(|b| b.c0));
+        lhs.extend(a.iter().map(|a| a.c1));
+        rhs.extend(b.iter().map(|b| b.c1));
+        for (a, b) in a.iter().zip(b.iter()) {
+            let mut t0 = a.c0;
+            t0.add_assign(&a.c1);
+            lhs.push(t0);
+            let mut t1 = b.c0;
+            t1.add_assign(&b.c1);
+            rhs.push(t1);
+        }
+
+        Fp::batch_mul_assign(&mut lhs, &rhs);
+
+        for (i, a) in a.iter_mut().enumerate() {
+            let v0 = lhs[i];
+            let mut v1 = lhs[n + i];
+            a.c1 = lhs[2 * n + i];
+            a.c1.sub_assign(&v0);
+            a.c1.sub_assign(&v1);
+            a.c0 = v0;
+            v1.mul_by_nonresidue(a.extension_field);
+            a.c0.add_assign(&v1);
+        }
+    }
+
     fn square(&mut self)
     {
         // v0 = c0 - c1
@@ -238,6 +327,88 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for Fp2<'a,
     }
 }
 
+/// Fp2 element with unreduced coefficients, see `WideFp`. Only for the extensions with
+/// non-residue -1, where the Karatsuba products combine without any base field multiplications
+pub(crate) struct WideFp2<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >{
+    pub(crate) c0: WideFp<'a, E, F>,
+    pub(crate) c1: WideFp<'a, E, F>,
+    pub(crate) extension_field: &'a Extension2<'a, E, F>,
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Clone for WideFp2<'a, E, F> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self {
+            c0: self.c0,
+            c1: self.c1,
+            extension_field: self.extension_field
+        }
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Copy for WideFp2<'a, E, F> {}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > WideFp2<'a, E, F> {
+    /// Karatsuba product of reduced elements, three base field products and no reductions
+    pub(crate) fn mul(a: &Fp2<'a, E, F>, b: &Fp2<'a, E, F>) -> Self {
+        debug_assert!(a.extension_field.non_residue_mul_policy == NonResidueMulPolicy::Negate);
+
+        let v0 = WideFp::mul(&a.c0, &b.c0);
+        let v1 = WideFp::mul(&a.c1, &b.c1);
+
+        let mut a_sum = a.c0;
+        a_sum.add_assign(&a.c1);
+        let mut b_sum = b.c0;
+        b_sum.add_assign(&b.c1);
+
+        // c1 = (a0 + a1) * (b0 + b1) - a0 * b0 - a1 * b1
+        let mut c1 = WideFp::mul(&a_sum, &b_sum);
+        c1.sub_assign(&v0);
+        c1.sub_assign(&v1);
+
+        // c0 = a0 * b0 - a1 * b1
+        let mut c0 = v0;
+        c0.sub_assign(&v1);
+
+        Self {
+            c0,
+            c1,
+            extension_field: a.extension_field
+        }
+    }
+
+    pub(crate) fn from_fp2(a: &Fp2<'a, E, F>) -> Self {
+        Self {
+            c0: WideFp::from_fp(&a.c0),
+            c1: WideFp::from_fp(&a.c1),
+            extension_field: a.extension_field
+        }
+    }
+
+    pub(crate) fn add_assign(&mut self, other: &Self) {
+        self.c0.add_assign(&other.c0);
+        self.c1.add_assign(&other.c1);
+    }
+
+    pub(crate) fn sub_assign(&mut self, other: &Self) {
+        self.c0.sub_assign(&other.c0);
+        self.c1.sub_assign(&other.c1);
+    }
+
+    pub(crate) fn negate(&mut self) {
+        self.c0.negate();
+        self.c1.negate();
+    }
+
+    pub(crate) fn reduce(&self) -> Fp2<'a, E, F> {
+        Fp2 {
+            c0: self.c0.reduce(),
+            c1: self.c1.reduce(),
+            extension_field: self.extension_field
+        }
+    }
+}
+
 // For example, BLS12-381 has non-residue = -1;
 pub struct Extension2<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > {
     pub(crate) field: &'a F,
@@ -260,7 +431,9 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Clone for Extension2<'a,
 }
 
 impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Extension2<'a, E, F> {
-    pub (crate) fn new(non_residue: Fp<'a, E, F>) -> Self {
+    /// Creates an extension with a given non-residue. Frobenius coefficients
+    /// must be calculated separately before using Frobenius maps
+    pub fn new(non_residue: Fp<'a, E, F>) -> Self {
         let field = non_residue.field;
 
         let mut minus_one = Fp::one(field);
@@ -283,7 +456,8 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Extension2<'a, E, F> {
         }
     }
 
-    pub(crate) fn calculate_frobenius_coeffs(
+    /// Calculates coefficients for Frobenius maps from the modulus of the base prime field
+    pub fn calculate_frobenius_coeffs(
         &mut self,
         modulus: &MaxFieldUint,
     ) -> Result<(), ()> {
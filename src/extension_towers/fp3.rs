@@ -1,8 +1,9 @@
 use crate::fp::Fp;
 use crate::field::{SizedPrimeField};
-use crate::representation::ElementRepr;
-use crate::traits::{FieldElement, BitIterator, FieldExtension, ZeroAndOne};
+use crate::representation::{ElementRepr, RepresentationDecodingError};
+use crate::traits::{FieldElement, BitIterator, FieldExtension, ZeroAndOne, ConditionalSelect};
 use super::Fp3Fp6FrobeniusBaseElements;
+use super::split_coefficients_encoding;
 
 // this implementation assumes extension using polynomial u^3 + m = 0
 pub struct Fp3<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >{
@@ -12,14 +13,14 @@ pub struct Fp3<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >{
     pub extension_field: &'a Extension3<'a, E, F>
 }
 
-impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >std::fmt::Display for Fp3<'a, E, F> {
-    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> std::fmt::Result {
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >core::fmt::Display for Fp3<'a, E, F> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> core::fmt::Result {
         write!(f, "Fq3({} + {} * u + {} * u^2)", self.c0, self.c1, self.c2)
     }
 }
 
-impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >std::fmt::Debug for Fp3<'a, E, F> {
-    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> std::fmt::Result {
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >core::fmt::Debug for Fp3<'a, E, F> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> core::fmt::Result {
         write!(f, "Fq3({} + {} * u) + {} * u^2", self.c0, self.c1, self.c2)
     }
 }
@@ -51,11 +52,98 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Eq for Fp3<'a, E, F> {
 }
 
 impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Fp3<'a, E, F> {
+    /// Decodes an element from the concatenation of equal length big endian encodings
+    /// of `c0`, `c1` and `c2`
+    pub fn from_be_bytes(extension_field: &'a Extension3<'a, E, F>, bytes: &[u8]) -> Result<Self, RepresentationDecodingError> {
+        let encodings = split_coefficients_encoding(bytes, 3)?;
+        let c0 = Fp::from_be_bytes(extension_field.field, encodings[0], true)?;
+        let c1 = Fp::from_be_bytes(extension_field.field, encodings[1], true)?;
+        let c2 = Fp::from_be_bytes(extension_field.field, encodings[2], true)?;
+
+        Ok(Self {
+            c0,
+            c1,
+            c2,
+            extension_field
+        })
+    }
+
     pub fn mul_by_fp(&mut self, element: &Fp<'a, E, F>) {
         self.c0.mul_assign(&element);
         self.c1.mul_assign(&element);
         self.c2.mul_assign(&element);
     }
+
+    pub fn mul_by_1(&mut self, c1: &Fp<'a, E, F>) {
+        let mut b_b = self.c1;
+        b_b.mul_assign(c1);
+
+        let mut t1 = *c1;
+        {
+            let mut tmp = self.c1;
+            tmp.add_assign(&self.c2);
+
+            t1.mul_assign(&tmp);
+            t1.sub_assign(&b_b);
+            t1.mul_by_nonresidue(self.extension_field);
+        }
+
+        let mut t2 = *c1;
+        {
+            let mut tmp = self.c0;
+            tmp.add_assign(&self.c1);
+
+            t2.mul_assign(&tmp);
+            t2.sub_assign(&b_b);
+        }
+
+        self.c0 = t1;
+        self.c1 = t2;
+        self.c2 = b_b;
+    }
+
+    pub fn mul_by_01(&mut self, c0: &Fp<'a, E, F>, c1: &Fp<'a, E, F>) {
+        let mut a_a = self.c0;
+        let mut b_b = self.c1;
+        a_a.mul_assign(c0);
+        b_b.mul_assign(c1);
+
+        let mut t1 = *c1;
+        {
+            let mut tmp = self.c1;
+            tmp.add_assign(&self.c2);
+
+            t1.mul_assign(&tmp);
+            t1.sub_assign(&b_b);
+            t1.mul_by_nonresidue(self.extension_field);
+            t1.add_assign(&a_a);
+        }
+
+        let mut t3 = *c0;
+        {
+            let mut tmp = self.c0;
+            tmp.add_assign(&self.c2);
+
+            t3.mul_assign(&tmp);
+            t3.sub_assign(&a_a);
+            t3.add_assign(&b_b);
+        }
+
+        let mut t2 = *c0;
+        t2.add_assign(c1);
+        {
+            let mut tmp = self.c0;
+            tmp.add_assign(&self.c1);
+
+            t2.mul_assign(&tmp);
+            t2.sub_assign(&a_a);
+            t2.sub_assign(&b_b);
+        }
+
+        self.c0 = t1;
+        self.c1 = t2;
+        self.c2 = t3;
+    }
 }
 
 impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > ZeroAndOne for Fp3<'a, E, F> {
@@ -85,6 +173,27 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > ZeroAndOne for Fp3<'a, E
     }
 }
 
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > ConditionalSelect for Fp3<'a, E, F> {
+    #[inline(always)]
+    fn conditional_select(a: &Self, b: &Self, choice: bool) -> Self {
+        Self {
+            c0: Fp::conditional_select(&a.c0, &b.c0, choice),
+            c1: Fp::conditional_select(&a.c1, &b.c1, choice),
+            c2: Fp::conditional_select(&a.c2, &b.c2, choice),
+            extension_field: a.extension_field
+        }
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > zeroize::Zeroize for Fp3<'a, E, F> {
+    fn zeroize(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.c0);
+        zeroize::Zeroize::zeroize(&mut self.c1);
+        zeroize::Zeroize::zeroize(&mut self.c2);
+    }
+}
+
 impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for Fp3<'a, E, F> {
     /// Returns true iff this element is zero.
     fn is_zero(&self) -> bool {
@@ -154,12 +263,7 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for Fp3<'a,
             let mut t6 = self.c0;
             t6.mul_assign(&s0);
             t6.add_assign(&a3);
-            let t6 = t6.inverse();
-            if t6.is_none() {
-                return None;
-            }
-
-            let t6 = t6.expect("is not None");
+            let t6 = t6.inverse()?;
 
             let mut c0 = t6;
             c0.mul_assign(&s0);
@@ -324,7 +428,9 @@ pub struct Extension3<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > {
 use crate::integers::*;
 
 impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Extension3<'a, E, F> {
-    pub (crate) fn new(non_residue: Fp<'a, E, F>) -> Self {
+    /// Creates an extension with a given non-residue. Frobenius coefficients
+    /// must be calculated separately before using Frobenius maps
+    pub fn new(non_residue: Fp<'a, E, F>) -> Self {
         let field = non_residue.field;
 
         let zeros = [Fp::zero(field), Fp::zero(field), Fp::zero(field)];
@@ -338,7 +444,8 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Extension3<'a, E, F> {
         }
     }
 
-    pub(crate) fn calculate_frobenius_coeffs_optimized(
+    /// Calculates coefficients for Frobenius maps from the modulus of the base prime field
+    pub fn calculate_frobenius_coeffs_optimized(
         &mut self,
         modulus: &MaxFieldUint,
     ) -> Result<(), ()> {
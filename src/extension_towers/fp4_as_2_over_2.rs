@@ -1,9 +1,10 @@
 use crate::fp::Fp;
 use crate::field::{SizedPrimeField};
-use crate::representation::ElementRepr;
-use crate::traits::{FieldElement, BitIterator, FieldExtension, ZeroAndOne};
+use crate::representation::{ElementRepr, RepresentationDecodingError};
+use crate::traits::{FieldElement, BitIterator, FieldExtension, ZeroAndOne, ConditionalSelect};
 use super::fp2::{Fp2, Extension2};
 use super::Fp2Fp4FrobeniusBaseElements;
+use super::split_coefficients_encoding;
 
 pub struct Fp4<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >{
     pub c0: Fp2<'a, E, F>,
@@ -11,14 +12,14 @@ pub struct Fp4<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >{
     pub extension_field: &'a Extension2Over2<'a, E, F>
 }
 
-impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >std::fmt::Display for Fp4<'a, E, F> {
-    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> std::fmt::Result {
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >core::fmt::Display for Fp4<'a, E, F> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> core::fmt::Result {
         write!(f, "Fq4({} + {} * v)", self.c0, self.c1)
     }
 }
 
-impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >std::fmt::Debug for Fp4<'a, E, F> {
-    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> std::fmt::Result {
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >core::fmt::Debug for Fp4<'a, E, F> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> core::fmt::Result {
         write!(f, "Fq4({} + {} * v)", self.c0, self.c1)
     }
 }
@@ -48,6 +49,50 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Eq for Fp4<'a, E, F> {
 }
 
 impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Fp4<'a, E, F> {
+    /// Decodes an element from the concatenation of equal length big endian encodings
+    /// of `c0` and `c1`
+    pub fn from_be_bytes(extension_field: &'a Extension2Over2<'a, E, F>, bytes: &[u8]) -> Result<Self, RepresentationDecodingError> {
+        let encodings = split_coefficients_encoding(bytes, 2)?;
+        let c0 = Fp2::from_be_bytes(extension_field.field, encodings[0])?;
+        let c1 = Fp2::from_be_bytes(extension_field.field, encodings[1])?;
+
+        Ok(Self {
+            c0,
+            c1,
+            extension_field
+        })
+    }
+
+    pub fn mul_by_fp(&mut self, element: &Fp<'a, E, F>) {
+        self.c0.mul_by_fp(element);
+        self.c1.mul_by_fp(element);
+    }
+
+    /// Squaring of an element of norm one over Fp2, that is `c0^2 - gamma * c1^2 = 1` for
+    /// the non-residue `gamma`. It holds after the easy part of the final exponentiation, and then
+    /// the square is `(1 + 2 * gamma * c1^2) + ((c0 + c1)^2 - 1 - (1 + gamma) * c1^2) * u`,
+    /// so it costs two squarings in Fp2 instead of two multiplications
+    pub fn cyclotomic_square(&mut self) {
+        let one = Fp2::one(self.extension_field.field);
+
+        let mut t = self.c1;
+        t.square();
+        let mut gamma_t = t;
+        gamma_t.mul_by_nonresidue(self.extension_field);
+
+        let mut s = self.c0;
+        s.add_assign(&self.c1);
+        s.square();
+        s.sub_assign(&one);
+        s.sub_assign(&t);
+        s.sub_assign(&gamma_t);
+
+        self.c0 = gamma_t;
+        self.c0.double();
+        self.c0.add_assign(&one);
+        self.c1 = s;
+    }
+
     pub fn cyclotomic_exp<S: AsRef<[u64]>>(&self, exp: S) -> Self {
         let mut res = Self::one(self.extension_field);
         let mut self_inverse = *self;
@@ -59,7 +104,7 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Fp4<'a, E, F> {
 
         for &value in naf.iter().rev() {
             if found_nonzero {
-                res.square();
+                res.cyclotomic_square();
             }
 
             if value != 0 {
@@ -102,6 +147,25 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > ZeroAndOne for Fp4<'a, E
     }
 }
 
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > ConditionalSelect for Fp4<'a, E, F> {
+    #[inline(always)]
+    fn conditional_select(a: &Self, b: &Self, choice: bool) -> Self {
+        Self {
+            c0: Fp2::conditional_select(&a.c0, &b.c0, choice),
+            c1: Fp2::conditional_select(&a.c1, &b.c1, choice),
+            extension_field: a.extension_field
+        }
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > zeroize::Zeroize for Fp4<'a, E, F> {
+    fn zeroize(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.c0);
+        zeroize::Zeroize::zeroize(&mut self.c1);
+    }
+}
+
 impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for Fp4<'a, E, F> {
     /// Returns true iff this element is zero.
     fn is_zero(&self) -> bool {
@@ -148,12 +212,7 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for Fp4<'a,
             v0.mul_by_nonresidue(self.extension_field);
             t0.sub_assign(&v0);
 
-            let t2 = t0.inverse();
-            if t2.is_none() {
-                return None;
-            }
-            
-            let t2 = t2.expect("is not None");
+            let t2 = t0.inverse()?;
 
             let mut c0 = a;
             c0.mul_assign(&t2);
@@ -257,14 +316,6 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for Fp4<'a,
 
     fn frobenius_map(&mut self, power: usize) {
         assert!(self.extension_field.frobenius_coeffs_are_calculated);
-        match power {
-            1 | 2 => {
-
-            },
-            _ => {
-                unreachable!("can not reach power {}", power);
-            }
-        }
         self.c0.frobenius_map(power);
         self.c1.frobenius_map(power);
         self.c1.mul_by_fp(&self.extension_field.frobenius_coeffs_c1[power % 4]);
@@ -292,7 +343,9 @@ pub struct Extension2Over2<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > {
 use crate::integers::*;
 
 impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Extension2Over2<'a, E, F> {
-    pub (crate) fn new(non_residue: Fp2<'a, E, F>) -> Self {
+    /// Creates an extension with a given non-residue. Frobenius coefficients
+    /// must be calculated separately before using Frobenius maps
+    pub fn new(non_residue: Fp2<'a, E, F>) -> Self {
         let field = non_residue.extension_field.field;
 
         let zeros = [Fp::zero(field), Fp::zero(field),
@@ -306,8 +359,8 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Extension2Over2<'a, E, F
         }
     }
 
-    #[allow(dead_code)]
-    pub(crate) fn calculate_frobenius_coeffs_optimized(
+    /// Calculates coefficients for Frobenius maps from the modulus of the base prime field
+    pub fn calculate_frobenius_coeffs_optimized(
         &mut self,
         modulus: &MaxFieldUint,
     ) -> Result<(), ()> {    
@@ -325,7 +378,7 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Extension2Over2<'a, E, F
         // c1 = Fp**( (q^1 - 1) / 4) has to be calculated
         // c2 = Fp**( (q^2 - 1) / 4) = Fp**( ((q - 1)/4) *(q+1)) = 
         // = c1 * c1.frobenius(1) = c1 ** 2
-        // c3 = c1**3 is not calculated
+        // c3 = c1**3
 
         // NON_REDISUE**(((q^0) - 1) / 4)
         let non_residue = &self.field.non_residue;
@@ -340,7 +393,9 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Extension2Over2<'a, E, F
         // f_2.frobenius_map(1); // we could leave it formally, but it's an identity
         f_2.square();
 
-        let f_3 = Fp::zero(self.field.field);
+        // c2 * c1.frobenius(2) == c1^3
+        let mut f_3 = f_2;
+        f_3.mul_assign(&f_1);
 
         self.frobenius_coeffs_c1 = [f_0, f_1, f_2, f_3];
         self.frobenius_coeffs_are_calculated = true;
@@ -361,7 +416,9 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Extension2Over2<'a, E, F
         // f_2.frobenius_map(1); // we could leave it formally, but it's an identity
         f_2.square();
 
-        let f_3 = Fp::zero(self.field.field);
+        // c2 * c1.frobenius(2) == c1^3
+        let mut f_3 = f_2;
+        f_3.mul_assign(&f_1);
 
         self.frobenius_coeffs_c1 = [f_0, f_1, f_2, f_3];
         self.frobenius_coeffs_are_calculated = true;
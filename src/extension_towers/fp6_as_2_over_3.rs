@@ -1,9 +1,10 @@
 use crate::fp::Fp;
 use crate::field::{SizedPrimeField};
-use crate::representation::ElementRepr;
+use crate::representation::{ElementRepr, RepresentationDecodingError};
 use crate::traits::{FieldElement, BitIterator, FieldExtension, ZeroAndOne};
 use super::fp3::{Fp3, Extension3};
 use super::Fp3Fp6FrobeniusBaseElements;
+use super::split_coefficients_encoding;
 
 pub struct Fp6<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >{
     pub c0: Fp3<'a, E, F>,
@@ -11,14 +12,14 @@ pub struct Fp6<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >{
     pub extension_field: &'a Extension2Over3<'a, E, F>
 }
 
-impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >std::fmt::Display for Fp6<'a, E, F> {
-    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> std::fmt::Result {
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >core::fmt::Display for Fp6<'a, E, F> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> core::fmt::Result {
         write!(f, "Fq6({} + {} * v)", self.c0, self.c1)
     }
 }
 
-impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >std::fmt::Debug for Fp6<'a, E, F> {
-    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> std::fmt::Result {
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >core::fmt::Debug for Fp6<'a, E, F> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> core::fmt::Result {
         write!(f, "Fq6({} + {} * v)", self.c0, self.c1)
     }
 }
@@ -48,6 +49,20 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Eq for Fp6<'a, E, F> {
 }
 
 impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Fp6<'a, E, F> {
+    /// Decodes an element from the concatenation of equal length big endian encodings
+    /// of `c0` and `c1`
+    pub fn from_be_bytes(extension_field: &'a Extension2Over3<'a, E, F>, bytes: &[u8]) -> Result<Self, RepresentationDecodingError> {
+        let encodings = split_coefficients_encoding(bytes, 2)?;
+        let c0 = Fp3::from_be_bytes(extension_field.field, encodings[0])?;
+        let c1 = Fp3::from_be_bytes(extension_field.field, encodings[1])?;
+
+        Ok(Self {
+            c0,
+            c1,
+            extension_field
+        })
+    }
+
     pub fn zero(extension_field: &'a Extension2Over3<'a, E, F>) -> Self {
         let zero = Fp3::zero(extension_field.field);
         
@@ -69,6 +84,81 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Fp6<'a, E, F> {
         }
     }
 
+    pub fn mul_by_014(
+        &mut self,
+        c0: & Fp<'a, E, F>,
+        c1: & Fp<'a, E, F>,
+        c4: & Fp<'a, E, F>,
+    ) {
+        let mut aa = self.c0;
+        aa.mul_by_01(c0, c1);
+        let mut bb = self.c1;
+        bb.mul_by_1(c4);
+        let mut o = *c1;
+        o.add_assign(c4);
+        self.c1.add_assign(&self.c0);
+        self.c1.mul_by_01(c0, &o);
+        self.c1.sub_assign(&aa);
+        self.c1.sub_assign(&bb);
+        self.c0 = bb;
+        self.c0.mul_by_nonresidue(self.extension_field);
+        self.c0.add_assign(&aa);
+    }
+
+    pub fn mul_by_034(
+        &mut self,
+        c0: & Fp<'a, E, F>,
+        c3: & Fp<'a, E, F>,
+        c4: & Fp<'a, E, F>,
+    ) {
+        let mut a = self.c0;
+        a.c0.mul_assign(c0);
+        a.c1.mul_assign(c0);
+        a.c2.mul_assign(c0);
+
+        let mut b = self.c1;
+        b.mul_by_01(&c3, &c4);
+
+        let mut t0 = *c0;
+        t0.add_assign(c3);
+
+        let mut e = self.c0;
+        e.add_assign(&self.c1);
+        e.mul_by_01(&t0, &c4);
+
+        self.c1 = e;
+        self.c1.sub_assign(&a);
+        self.c1.sub_assign(&b);
+
+        let mut t1 = b;
+        t1.mul_by_nonresidue(self.extension_field);
+        self.c0 = a;
+        self.c0.add_assign(&t1);
+    }
+
+    /// Granger-Scott squaring for the quadratic extension, same as for Fp4 over Fp2. Only valid
+    /// for elements of norm one over Fp3, e.g. after the easy part of the final exponentiation
+    pub fn cyclotomic_square(&mut self) {
+        let one = Fp3::one(self.extension_field.field);
+
+        let mut t = self.c1;
+        t.square();
+        let mut gamma_t = t;
+        gamma_t.mul_by_nonresidue(self.extension_field);
+
+        let mut s = self.c0;
+        s.add_assign(&self.c1);
+        s.square();
+        s.sub_assign(&one);
+        s.sub_assign(&t);
+        s.sub_assign(&gamma_t);
+
+        self.c0 = gamma_t;
+        self.c0.double();
+        self.c0.add_assign(&one);
+        self.c1 = s;
+    }
+
     pub fn cyclotomic_exp<S: AsRef<[u64]>>(&self, exp: S) -> Self {
         let mut res = Self::one(self.extension_field);
         let mut self_inverse = *self;
@@ -80,7 +170,7 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Fp6<'a, E, F> {
 
         for &value in naf.iter().rev() {
             if found_nonzero {
-                res.square();
+                res.cyclotomic_square();
             }
 
             if value != 0 {
@@ -170,12 +260,7 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for Fp6<'a,
             v0.mul_by_nonresidue(self.extension_field);
             t0.sub_assign(&v0);
 
-            let t2 = t0.inverse();
-            if t2.is_none() {
-                return None;
-            }
-            
-            let t2 = t2.expect("is not None");
+            let t2 = t0.inverse()?;
 
             let mut c0 = a;
             c0.mul_assign(&t2);
@@ -314,7 +399,9 @@ pub struct Extension2Over3<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > {
 use crate::integers::*;
 
 impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Extension2Over3<'a, E, F> {
-    pub (crate) fn new(non_residue: Fp3<'a, E, F>) -> Self {
+    /// Creates an extension with a given non-residue. Frobenius coefficients
+    /// must be calculated separately before using Frobenius maps
+    pub fn new(non_residue: Fp3<'a, E, F>) -> Self {
         let field = non_residue.extension_field.field;
 
         let zeros = [Fp::zero(field), Fp::zero(field), Fp::zero(field),
@@ -328,8 +415,8 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Extension2Over3<'a, E, F
         }
     }
 
-    #[allow(dead_code)]
-    pub(crate) fn calculate_frobenius_coeffs_optimized(
+    /// Calculates coefficients for Frobenius maps from the modulus of the base prime field
+    pub fn calculate_frobenius_coeffs_optimized(
         &mut self,
         modulus: &MaxFieldUint,
     ) -> Result<(), ()> {
@@ -1,9 +1,11 @@
 use crate::field::{SizedPrimeField};
-use crate::representation::ElementRepr;
+use crate::representation::{ElementRepr, RepresentationDecodingError};
 use crate::traits::{FieldElement, BitIterator, FieldExtension, ZeroAndOne};
-use super::fp2::{Fp2, Extension2};
+use super::fp2::{Fp2, WideFp2, Extension2};
+use super::fp2::NonResidueMulPolicy;
 use crate::fp::Fp;
 use super::Fp6Fp12FrobeniusBaseElements;
+use super::split_coefficients_encoding;
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum NonResidueMulPolicyFp6 {
@@ -25,14 +27,14 @@ pub struct Fp6<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >{
     pub extension_field: &'a Extension3Over2<'a, E, F>
 }
 
-impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >std::fmt::Display for Fp6<'a, E, F> {
-    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >core::fmt::Display for Fp6<'a, E, F> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
         write!(f, "Fq6({} + {} * v + {} * v^2)", self.c0, self.c1, self.c2)
     }
 }
 
-impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >std::fmt::Debug for Fp6<'a, E, F> {
-    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >core::fmt::Debug for Fp6<'a, E, F> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
         write!(f, "Fq6({} + {} * v + {} * v^2)", self.c0, self.c1, self.c2)
     }
 }
@@ -64,6 +66,22 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Eq for Fp6<'a, E, F> {
 }
 
 impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Fp6<'a, E, F> {
+    /// Decodes an element from the concatenation of equal length big endian encodings
+    /// of `c0`, `c1` and `c2`
+    pub fn from_be_bytes(extension_field: &'a Extension3Over2<'a, E, F>, bytes: &[u8]) -> Result<Self, RepresentationDecodingError> {
+        let encodings = split_coefficients_encoding(bytes, 3)?;
+        let c0 = Fp2::from_be_bytes(extension_field.field, encodings[0])?;
+        let c1 = Fp2::from_be_bytes(extension_field.field, encodings[1])?;
+        let c2 = Fp2::from_be_bytes(extension_field.field, encodings[2])?;
+
+        Ok(Self {
+            c0,
+            c1,
+            c2,
+            extension_field
+        })
+    }
+
     pub fn mul_by_1(&mut self, c1: &Fp2<'a, E, F>) {
         let mut b_b = self.c1;
         b_b.mul_assign(c1);
@@ -251,6 +269,12 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for Fp6<'a,
 
     fn mul_assign(&mut self, other: &Self)
     {
+        if self.extension_field.field.non_residue_mul_policy == NonResidueMulPolicy::Negate {
+            *self = WideFp6::mul(self, other).reduce();
+
+            return;
+        }
+
         let mut a_a = self.c0;
         let mut b_b = self.c1;
         let mut c_c = self.c2;
@@ -383,6 +407,106 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for Fp6<'a,
     }
 }
 
+/// Fp6 element with unreduced coefficients, see `WideFp2`. Only for the extensions where
+/// the base Fp2 has non-residue -1
+pub(crate) struct WideFp6<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >{
+    pub(crate) c0: WideFp2<'a, E, F>,
+    pub(crate) c1: WideFp2<'a, E, F>,
+    pub(crate) c2: WideFp2<'a, E, F>,
+    pub(crate) extension_field: &'a Extension3Over2<'a, E, F>,
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Clone for WideFp6<'a, E, F> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self {
+            c0: self.c0,
+            c1: self.c1,
+            c2: self.c2,
+            extension_field: self.extension_field
+        }
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Copy for WideFp6<'a, E, F> {}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > WideFp6<'a, E, F> {
+    /// Same Karatsuba formulas as `Fp6::mul_assign`, but every coefficient is reduced only once
+    pub(crate) fn mul(a: &Fp6<'a, E, F>, b: &Fp6<'a, E, F>) -> Self {
+        let extension_field = a.extension_field;
+
+        let v0 = WideFp2::mul(&a.c0, &b.c0);
+        let v1 = WideFp2::mul(&a.c1, &b.c1);
+        let v2 = WideFp2::mul(&a.c2, &b.c2);
+
+        let mut a_sum = a.c1;
+        a_sum.add_assign(&a.c2);
+        let mut b_sum = b.c1;
+        b_sum.add_assign(&b.c2);
+        let mut c0 = WideFp2::mul(&a_sum, &b_sum);
+        c0.sub_assign(&v1);
+        c0.sub_assign(&v2);
+        let mut c0 = extension_field.wide_mul_by_non_residue(&c0);
+        c0.add_assign(&v0);
+
+        let mut a_sum = a.c0;
+        a_sum.add_assign(&a.c1);
+        let mut b_sum = b.c0;
+        b_sum.add_assign(&b.c1);
+        let mut c1 = WideFp2::mul(&a_sum, &b_sum);
+        c1.sub_assign(&v0);
+        c1.sub_assign(&v1);
+        c1.add_assign(&extension_field.wide_mul_by_non_residue(&v2));
+
+        let mut a_sum = a.c0;
+        a_sum.add_assign(&a.c2);
+        let mut b_sum = b.c0;
+        b_sum.add_assign(&b.c2);
+        let mut c2 = WideFp2::mul(&a_sum, &b_sum);
+        c2.sub_assign(&v0);
+        c2.add_assign(&v1);
+        c2.sub_assign(&v2);
+
+        Self {
+            c0,
+            c1,
+            c2,
+            extension_field
+        }
+    }
+
+    pub(crate) fn add_assign(&mut self, other: &Self) {
+        self.c0.add_assign(&other.c0);
+        self.c1.add_assign(&other.c1);
+        self.c2.add_assign(&other.c2);
+    }
+
+    pub(crate) fn sub_assign(&mut self, other: &Self) {
+        self.c0.sub_assign(&other.c0);
+        self.c1.sub_assign(&other.c1);
+        self.c2.sub_assign(&other.c2);
+    }
+
+    /// Multiplication by `v`, same as the Fp12 non-residue multiplication
+    pub(crate) fn mul_by_v(&self) -> Self {
+        Self {
+            c0: self.extension_field.wide_mul_by_non_residue(&self.c2),
+            c1: self.c0,
+            c2: self.c1,
+            extension_field: self.extension_field
+        }
+    }
+
+    pub(crate) fn reduce(&self) -> Fp6<'a, E, F> {
+        Fp6 {
+            c0: self.c0.reduce(),
+            c1: self.c1.reduce(),
+            c2: self.c2.reduce(),
+            extension_field: self.extension_field
+        }
+    }
+}
+
 use crate::integers::*;
 
 // For example, BLS12-381 has non-residue = 1 + u;
@@ -409,7 +533,47 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Clone for Extension3Over
 }
 
 impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Extension3Over2<'a, E, F> {
-    pub (crate) fn new(non_residue: Fp2<'a, E, F>) -> Self {
+    /// Multiplication of an unreduced element by the non-residue. For `u` and `1 + u` with
+    /// `u^2 = -1` it's only additions, other non-residues fall back to the reduced form
+    pub(crate) fn wide_mul_by_non_residue(&self, el: &WideFp2<'a, E, F>) -> WideFp2<'a, E, F> {
+        debug_assert!(self.field.non_residue_mul_policy == NonResidueMulPolicy::Negate);
+        match self.non_residue_mul_policy {
+            NonResidueMulPolicyFp6::ZeroOne => {
+                // (c0 + c1 * u) * u = -c1 + c0 * u
+                let mut c0 = el.c1;
+                c0.negate();
+
+                WideFp2 {
+                    c0,
+                    c1: el.c0,
+                    extension_field: el.extension_field
+                }
+            },
+            NonResidueMulPolicyFp6::OneOne => {
+                // (c0 + c1 * u) * (1 + u) = (c0 - c1) + (c0 + c1) * u
+                let mut c0 = el.c0;
+                c0.sub_assign(&el.c1);
+                let mut c1 = el.c0;
+                c1.add_assign(&el.c1);
+
+                WideFp2 {
+                    c0,
+                    c1,
+                    extension_field: el.extension_field
+                }
+            },
+            _ => {
+                let mut reduced = el.reduce();
+                self.multiply_by_non_residue(&mut reduced);
+
+                WideFp2::from_fp2(&reduced)
+            }
+        }
+    }
+
+    /// Creates an extension with a given non-residue. Frobenius coefficients
+    /// must be calculated separately before using Frobenius maps
+    pub fn new(non_residue: Fp2<'a, E, F>) -> Self {
         let extension_2 = &non_residue.extension_field;
 
         let one_fp = Fp::one(extension_2.field);
@@ -442,8 +606,8 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Extension3Over2<'a, E, F
         }
     }
 
-    #[allow(dead_code)]
-    pub(crate) fn calculate_frobenius_coeffs_optimized(
+    /// Calculates coefficients for Frobenius maps from the modulus of the base prime field
+    pub fn calculate_frobenius_coeffs_optimized(
         &mut self,
         modulus: &MaxFieldUint
     ) -> Result<(), ()> {
@@ -609,10 +773,14 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldExtension for Exten
             NonResidueMulPolicyFp6::OneOne => {
                 debug_assert_eq!(Fp::one(self.field.field), self.non_residue.c0);
                 debug_assert_eq!(Fp::one(self.field.field), self.non_residue.c1);
-                let v0 = el.c0;
-                let v1 = el.c1;
-        
-                (v0, v1)
+
+                // (c0 + c1 * u) * (1 + u) = (c0 + beta * c1) + (c0 + c1) * u, no multiplications
+                let mut v1 = el.c1;
+                v1.mul_by_nonresidue(self.field);
+                el.c1.add_assign(&el.c0);
+                el.c0.add_assign(&v1);
+
+                return;
             },
             NonResidueMulPolicyFp6::OneFull => {
                 debug_assert_eq!(Fp::one(self.field.field), self.non_residue.c0);
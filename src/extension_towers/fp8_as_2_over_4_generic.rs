@@ -0,0 +1,294 @@
+use crate::fp::Fp;
+use crate::field::{SizedPrimeField};
+use crate::representation::ElementRepr;
+use crate::traits::{FieldElement, BitIterator, FieldExtension, ZeroAndOne};
+use super::fp4_as_2_over_2::{Fp4, Extension2Over2};
+use super::Fp8Fp16FrobeniusBaseElements;
+
+// this implementation assumes extension using polynomial s^2 - xi = 0 on top of Fp4 as 2 over 2
+// where xi is an arbitrary non-square in Fp4. Unlike Fp8 as 2 over 4 with xi = w it only
+// requires 4 | q - 1, at the price of Frobenius coefficients in Fp4. It's a middle step of
+// the Fp16 tower used by KSS16 curves
+pub struct Fp8<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >{
+    pub c0: Fp4<'a, E, F>,
+    pub c1: Fp4<'a, E, F>,
+    pub extension_field: &'a Extension2Over4<'a, E, F>
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >core::fmt::Display for Fp8<'a, E, F> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "Fq8({} + {} * s)", self.c0, self.c1)
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >core::fmt::Debug for Fp8<'a, E, F> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "Fq8({} + {} * s)", self.c0, self.c1)
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Clone for Fp8<'a, E, F> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self{
+            c0: self.c0,
+            c1: self.c1,
+            extension_field: self.extension_field
+        }
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Copy for Fp8<'a, E, F> {}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > PartialEq for Fp8<'a, E, F> {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        self.c0 == other.c0 &&
+        self.c1 == other.c1
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Eq for Fp8<'a, E, F> {
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Fp8<'a, E, F> {
+    pub fn mul_by_fp(&mut self, element: &Fp<'a, E, F>) {
+        self.c0.mul_by_fp(element);
+        self.c1.mul_by_fp(element);
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > ZeroAndOne for Fp8<'a, E, F> {
+    type Params = &'a Extension2Over4<'a, E, F>;
+
+    fn zero(extension_field: &'a Extension2Over4<'a, E, F>) -> Self {
+        let zero = Fp4::zero(extension_field.field);
+
+        Self {
+            c0: zero,
+            c1: zero,
+            extension_field: extension_field
+        }
+    }
+
+    fn one(extension_field: &'a Extension2Over4<'a, E, F>) -> Self {
+        let zero = Fp4::zero(extension_field.field);
+        let one = Fp4::one(extension_field.field);
+
+        Self {
+            c0: one,
+            c1: zero,
+            extension_field: extension_field
+        }
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for Fp8<'a, E, F> {
+    /// Returns true iff this element is zero.
+    fn is_zero(&self) -> bool {
+        self.c0.is_zero() &&
+        self.c1.is_zero()
+    }
+
+    fn add_assign(&mut self, other: &Self) {
+        self.c0.add_assign(&other.c0);
+        self.c1.add_assign(&other.c1);
+    }
+
+    fn double(&mut self) {
+        self.c0.double();
+        self.c1.double();
+    }
+
+    fn sub_assign(&mut self, other: &Self) {
+        self.c0.sub_assign(&other.c0);
+        self.c1.sub_assign(&other.c1);
+    }
+
+    fn negate(&mut self) {
+        self.c0.negate();
+        self.c1.negate();
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        if self.is_zero() {
+            None
+        } else {
+            // same as for Fp4: (a + b * s)^-1 = (a - b * s) / (a^2 - xi * b^2)
+            let a = self.c0;
+            let b = self.c1;
+
+            let mut t1 = b;
+            t1.square();
+            let mut t0 = a;
+            t0.square();
+
+            let mut v0 = t1;
+            v0.mul_by_nonresidue(self.extension_field);
+            t0.sub_assign(&v0);
+
+            let t2 = t0.inverse()?;
+
+            let mut c0 = a;
+            c0.mul_assign(&t2);
+            let mut c1 = b;
+            c1.mul_assign(&t2);
+            c1.negate();
+
+            Some(Self {
+                c0,
+                c1,
+                extension_field: self.extension_field
+            })
+        }
+    }
+
+    fn mul_assign(&mut self, other: &Self)
+    {
+        let a0 = self.c0;
+        let b0 = self.c1;
+        let a1 = other.c0;
+        let b1 = other.c1;
+
+        let mut a0a1 = a0;
+        a0a1.mul_assign(&a1);
+        let mut b0b1 = b0;
+        b0b1.mul_assign(&b1);
+        let mut t0 = b0b1;
+        t0.mul_by_nonresidue(self.extension_field);
+
+        let mut c0 = a0a1;
+        c0.add_assign(&t0);
+        let mut c1 = a0;
+        c1.add_assign(&b0);
+
+        let mut t1 = a1;
+        t1.add_assign(&b1);
+
+        c1.mul_assign(&t1);
+        c1.sub_assign(&a0a1);
+        c1.sub_assign(&b0b1);
+
+        self.c0 = c0;
+        self.c1 = c1;
+    }
+
+    fn square(&mut self)
+    {
+        let a = self.c0;
+        let b = self.c1;
+        let mut ab_add = a;
+        ab_add.add_assign(&b);
+        let mut ab_mul = a;
+        ab_mul.mul_assign(&b);
+
+        let mut t0 = b;
+        t0.mul_by_nonresidue(self.extension_field);
+        t0.add_assign(&a);
+
+        let mut t1 = ab_mul;
+        t1.mul_by_nonresidue(self.extension_field);
+
+        let mut c0 = ab_add;
+        c0.mul_assign(&t0);
+        c0.sub_assign(&ab_mul);
+        c0.sub_assign(&t1);
+
+        let mut c1 = ab_mul;
+        c1.double();
+
+        self.c0 = c0;
+        self.c1 = c1;
+    }
+
+    fn conjugate(&mut self) {
+        self.c1.negate();
+    }
+
+    fn pow<S: AsRef<[u64]>>(&self, exp: S) -> Self {
+        let mut res = Self::one(&self.extension_field);
+
+        let mut found_one = false;
+
+        for i in BitIterator::new(exp) {
+            if found_one {
+                res.square();
+            } else {
+                found_one = i;
+            }
+
+            if i {
+                res.mul_assign(self);
+            }
+        }
+
+        res
+    }
+
+    fn mul_by_nonresidue<EXT: FieldExtension<Element = Self>>(&mut self, for_extesion: &EXT) {
+        for_extesion.multiply_by_non_residue(self);
+    }
+
+    fn frobenius_map(&mut self, power: usize) {
+        assert!(self.extension_field.frobenius_coeffs_are_calculated);
+        self.c0.frobenius_map(power);
+        self.c1.frobenius_map(power);
+        self.c1.mul_assign(&self.extension_field.frobenius_coeffs_c1[power % 8]);
+    }
+}
+
+pub struct Extension2Over4<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > {
+    pub(crate) field: &'a Extension2Over2<'a, E, F>,
+    pub(crate) non_residue: Fp4<'a, E, F>,
+    pub(crate) frobenius_coeffs_c1: [Fp4<'a, E, F>; 8],
+    pub(crate) frobenius_coeffs_are_calculated: bool
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Extension2Over4<'a, E, F> {
+    pub (crate) fn new(non_residue: Fp4<'a, E, F>) -> Self {
+        let extension_4 = non_residue.extension_field;
+
+        Self {
+            non_residue: non_residue,
+            field: extension_4,
+            frobenius_coeffs_c1: [Fp4::zero(extension_4); 8],
+            frobenius_coeffs_are_calculated: false
+        }
+    }
+
+    pub(crate) fn calculate_frobenius_coeffs_with_precomp(
+        &mut self,
+        precomp: &Fp8Fp16FrobeniusBaseElements<'a, E, F>
+    ) -> Result<(), ()> {
+        // c1[k] = NON_RESIDUE**((q^k - 1) / 2) = (NON_RESIDUE**((q^k - 1) / 4))**2
+        // c1[k] = c1[k-1].frobenius(1) * c1[1] as
+        // (q^k - 1) / 2 = ((q^(k-1) - 1) / 2) * q + (q - 1) / 2
+        let f_0 = Fp4::one(self.field);
+        let mut f_1 = precomp.non_residue_in_q_minus_one_by_four;
+        f_1.square();
+
+        let mut coeffs_c1 = [f_0; 8];
+        for k in 1..8 {
+            let mut f_k = coeffs_c1[k-1];
+            f_k.frobenius_map(1);
+            f_k.mul_assign(&f_1);
+            coeffs_c1[k] = f_k;
+        }
+
+        self.frobenius_coeffs_c1 = coeffs_c1;
+        self.frobenius_coeffs_are_calculated = true;
+
+        Ok(())
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldExtension for Extension2Over4<'a, E, F> {
+    const EXTENSION_DEGREE: usize = 2;
+
+    type Element = Fp4<'a, E, F>;
+
+    fn multiply_by_non_residue(&self, el: &mut Self::Element) {
+        // non-residue is an arbitrary element of Fp4, so use a full multiplication
+        el.mul_assign(&self.non_residue);
+    }
+}
@@ -0,0 +1,412 @@
+use crate::fp::Fp;
+use crate::field::{SizedPrimeField};
+use crate::representation::ElementRepr;
+use crate::traits::{FieldElement, BitIterator, FieldExtension, ZeroAndOne, ConditionalSelect};
+use crate::integers::*;
+
+/// Largest degree of the binomial extension. Elements are stored in fixed size arrays,
+/// so this limit affects the size of every element
+pub const MAX_BINOMIAL_EXTENSION_DEGREE: usize = 12;
+
+// this implementation assumes extension using polynomial z^k - xi = 0 where xi is
+// an element of the base field and the degree k is only known at runtime. It's used
+// by the generic Cocks-Pinch pairing, where there is no fixed tower to build.
+// Frobenius map requires k | p - 1, so it's a multiplication of the coefficients by
+// powers of the k-th root of unity xi^((p-1)/k)
+pub struct FpK<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >{
+    pub coeffs: [Fp<'a, E, F>; MAX_BINOMIAL_EXTENSION_DEGREE],
+    pub extension_field: &'a ExtensionK<'a, E, F>
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >core::fmt::Display for FpK<'a, E, F> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "Fq{}({}", self.extension_field.degree, self.coeffs[0])?;
+        for (i, c) in self.coeffs[..self.extension_field.degree].iter().enumerate().skip(1) {
+            write!(f, " + {} * z^{}", c, i)?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >core::fmt::Debug for FpK<'a, E, F> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> core::fmt::Result {
+        core::fmt::Display::fmt(self, f)
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Clone for FpK<'a, E, F> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self{
+            coeffs: self.coeffs,
+            extension_field: self.extension_field
+        }
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Copy for FpK<'a, E, F> {}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > PartialEq for FpK<'a, E, F> {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        let degree = self.extension_field.degree;
+
+        self.coeffs[..degree] == other.coeffs[..degree]
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Eq for FpK<'a, E, F> {
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FpK<'a, E, F> {
+    /// Makes an element from the coefficients in powers of z. Number of coefficients
+    /// must be equal to the extension degree
+    pub fn from_coeffs(extension_field: &'a ExtensionK<'a, E, F>, coeffs: &[Fp<'a, E, F>]) -> Result<Self, ()> {
+        if coeffs.len() != extension_field.degree {
+            return Err(());
+        }
+
+        let mut result = Self::zero(extension_field);
+        result.coeffs[..coeffs.len()].copy_from_slice(coeffs);
+
+        Ok(result)
+    }
+
+    /// Embeds the base field element
+    pub fn from_fp(extension_field: &'a ExtensionK<'a, E, F>, element: &Fp<'a, E, F>) -> Self {
+        let mut result = Self::zero(extension_field);
+        result.coeffs[0] = *element;
+
+        result
+    }
+
+    pub fn mul_by_fp(&mut self, element: &Fp<'a, E, F>) {
+        let degree = self.extension_field.degree;
+        for c in self.coeffs[..degree].iter_mut() {
+            c.mul_assign(&element);
+        }
+    }
+
+    /// Product of all the conjugates of this element, that is an element of the base field
+    pub fn norm(&self) -> Fp<'a, E, F> {
+        let (norm, _) = self.norm_and_conjugates_product();
+
+        norm
+    }
+
+    // returns the norm and the product of all conjugates except the element itself
+    fn norm_and_conjugates_product(&self) -> (Fp<'a, E, F>, Self) {
+        let degree = self.extension_field.degree;
+
+        let mut conjugates_product = Self::one(self.extension_field);
+        let mut conjugate = *self;
+        for _ in 1..degree {
+            conjugate.frobenius_map(1);
+            conjugates_product.mul_assign(&conjugate);
+        }
+
+        let mut norm = conjugates_product;
+        norm.mul_assign(&self);
+        debug_assert!(norm.coeffs[1..degree].iter().all(|c| c.is_zero()));
+
+        (norm.coeffs[0], conjugates_product)
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > ZeroAndOne for FpK<'a, E, F> {
+    type Params = &'a ExtensionK<'a, E, F>;
+
+    fn zero(extension_field: &'a ExtensionK<'a, E, F>) -> Self {
+        let zero = Fp::zero(extension_field.field);
+
+        Self {
+            coeffs: [zero; MAX_BINOMIAL_EXTENSION_DEGREE],
+            extension_field: extension_field
+        }
+    }
+
+    fn one(extension_field: &'a ExtensionK<'a, E, F>) -> Self {
+        let mut result = Self::zero(extension_field);
+        result.coeffs[0] = Fp::one(extension_field.field);
+
+        result
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > ConditionalSelect for FpK<'a, E, F> {
+    #[inline(always)]
+    fn conditional_select(a: &Self, b: &Self, choice: bool) -> Self {
+        let mut coeffs = a.coeffs;
+        for (c, b) in coeffs.iter_mut().zip(b.coeffs.iter()) {
+            c.conditional_assign(b, choice);
+        }
+
+        Self {
+            coeffs: coeffs,
+            extension_field: a.extension_field
+        }
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > zeroize::Zeroize for FpK<'a, E, F> {
+    fn zeroize(&mut self) {
+        for c in self.coeffs.iter_mut() {
+            zeroize::Zeroize::zeroize(c);
+        }
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for FpK<'a, E, F> {
+    /// Returns true iff this element is zero.
+    fn is_zero(&self) -> bool {
+        self.coeffs[..self.extension_field.degree].iter().all(|c| c.is_zero())
+    }
+
+    fn add_assign(&mut self, other: &Self) {
+        let degree = self.extension_field.degree;
+        for (c, o) in self.coeffs[..degree].iter_mut().zip(other.coeffs[..degree].iter()) {
+            c.add_assign(o);
+        }
+    }
+
+    fn double(&mut self) {
+        let degree = self.extension_field.degree;
+        for c in self.coeffs[..degree].iter_mut() {
+            c.double();
+        }
+    }
+
+    fn sub_assign(&mut self, other: &Self) {
+        let degree = self.extension_field.degree;
+        for (c, o) in self.coeffs[..degree].iter_mut().zip(other.coeffs[..degree].iter()) {
+            c.sub_assign(o);
+        }
+    }
+
+    fn negate(&mut self) {
+        let degree = self.extension_field.degree;
+        for c in self.coeffs[..degree].iter_mut() {
+            c.negate();
+        }
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+
+        // x^-1 = (x^p * x^(p^2) * ... * x^(p^(k-1))) / N(x)
+        let (norm, conjugates_product) = self.norm_and_conjugates_product();
+        let norm_inv = norm.inverse()?;
+
+        let mut result = conjugates_product;
+        result.mul_by_fp(&norm_inv);
+
+        Some(result)
+    }
+
+    fn mul_assign(&mut self, other: &Self)
+    {
+        let degree = self.extension_field.degree;
+
+        // Karatsuba for every pair of coefficients, the cross term of a_i and a_j is
+        // (a_i + a_j) * (b_i + b_j) - a_i * b_i - a_j * b_j, so there are k * (k + 1) / 2
+        // base field multiplications instead of k^2
+        let mut diagonal = [Fp::zero(self.extension_field.field); MAX_BINOMIAL_EXTENSION_DEGREE];
+        for i in 0..degree {
+            diagonal[i] = self.coeffs[i];
+            diagonal[i].mul_assign(&other.coeffs[i]);
+        }
+
+        let mut product = self.full_product_with_diagonal(&diagonal);
+        for i in 0..degree {
+            for j in (i + 1)..degree {
+                let mut a = self.coeffs[i];
+                a.add_assign(&self.coeffs[j]);
+                let mut b = other.coeffs[i];
+                b.add_assign(&other.coeffs[j]);
+                a.mul_assign(&b);
+                a.sub_assign(&diagonal[i]);
+                a.sub_assign(&diagonal[j]);
+                product.add_at(i + j, &a);
+            }
+        }
+
+        self.coeffs = product.reduce();
+    }
+
+    fn square(&mut self)
+    {
+        let degree = self.extension_field.degree;
+
+        // cross terms are 2 * a_i * a_j = (a_i + a_j)^2 - a_i^2 - a_j^2, so all
+        // k * (k + 1) / 2 base field operations are squarings
+        let mut diagonal = [Fp::zero(self.extension_field.field); MAX_BINOMIAL_EXTENSION_DEGREE];
+        for i in 0..degree {
+            diagonal[i] = self.coeffs[i];
+            diagonal[i].square();
+        }
+
+        let mut product = self.full_product_with_diagonal(&diagonal);
+        for i in 0..degree {
+            for j in (i + 1)..degree {
+                let mut a = self.coeffs[i];
+                a.add_assign(&self.coeffs[j]);
+                a.square();
+                a.sub_assign(&diagonal[i]);
+                a.sub_assign(&diagonal[j]);
+                product.add_at(i + j, &a);
+            }
+        }
+
+        self.coeffs = product.reduce();
+    }
+
+    /// Frobenius map to the power k/2, so it's only meaningful for even degrees
+    fn conjugate(&mut self) {
+        debug_assert!(self.extension_field.degree % 2 == 0);
+        self.frobenius_map(self.extension_field.degree / 2);
+    }
+
+    fn pow<S: AsRef<[u64]>>(&self, exp: S) -> Self {
+        let mut res = Self::one(&self.extension_field);
+
+        let mut found_one = false;
+
+        for i in BitIterator::new(exp) {
+            if found_one {
+                res.square();
+            } else {
+                found_one = i;
+            }
+
+            if i {
+                res.mul_assign(self);
+            }
+        }
+
+        res
+    }
+
+    fn mul_by_nonresidue<EXT: FieldExtension<Element = Self>>(&mut self, for_extesion: &EXT) {
+        for_extesion.multiply_by_non_residue(self);
+    }
+
+    fn frobenius_map(&mut self, power: usize) {
+        assert!(self.extension_field.frobenius_coeffs_are_calculated);
+        let degree = self.extension_field.degree;
+        let power = power % degree;
+        for i in 1..degree {
+            self.coeffs[i].mul_assign(&self.extension_field.frobenius_roots[(i * power) % degree]);
+        }
+    }
+}
+
+// Product of two elements before the reduction by z^k - xi, the part at z^k and above
+// is kept separately and multiplied by the non-residue once per coefficient
+struct UnreducedProduct<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > {
+    low: [Fp<'a, E, F>; MAX_BINOMIAL_EXTENSION_DEGREE],
+    high: [Fp<'a, E, F>; MAX_BINOMIAL_EXTENSION_DEGREE],
+    extension_field: &'a ExtensionK<'a, E, F>
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > UnreducedProduct<'a, E, F> {
+    fn add_at(&mut self, power: usize, value: &Fp<'a, E, F>) {
+        let degree = self.extension_field.degree;
+        if power < degree {
+            self.low[power].add_assign(value);
+        } else {
+            self.high[power - degree].add_assign(value);
+        }
+    }
+
+    fn reduce(mut self) -> [Fp<'a, E, F>; MAX_BINOMIAL_EXTENSION_DEGREE] {
+        for i in 0..self.extension_field.degree {
+            self.high[i].mul_assign(&self.extension_field.non_residue);
+            self.low[i].add_assign(&self.high[i]);
+        }
+
+        self.low
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FpK<'a, E, F> {
+    // products of the coefficients with the same index land at z^(2i)
+    fn full_product_with_diagonal(&self, diagonal: &[Fp<'a, E, F>; MAX_BINOMIAL_EXTENSION_DEGREE]) -> UnreducedProduct<'a, E, F> {
+        let zero = Fp::zero(self.extension_field.field);
+        let mut product = UnreducedProduct {
+            low: [zero; MAX_BINOMIAL_EXTENSION_DEGREE],
+            high: [zero; MAX_BINOMIAL_EXTENSION_DEGREE],
+            extension_field: self.extension_field
+        };
+        for (i, d) in diagonal[..self.extension_field.degree].iter().enumerate() {
+            product.add_at(2 * i, d);
+        }
+
+        product
+    }
+}
+
+pub struct ExtensionK<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > {
+    pub(crate) field: &'a F,
+    pub(crate) degree: usize,
+    pub(crate) non_residue: Fp<'a, E, F>,
+    // powers of the root of unity xi^((p-1)/k)
+    pub(crate) frobenius_roots: [Fp<'a, E, F>; MAX_BINOMIAL_EXTENSION_DEGREE],
+    pub(crate) frobenius_coeffs_are_calculated: bool
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > ExtensionK<'a, E, F> {
+    pub(crate) fn new(non_residue: Fp<'a, E, F>, degree: usize) -> Result<Self, ()> {
+        if degree < 2 || degree > MAX_BINOMIAL_EXTENSION_DEGREE {
+            return Err(());
+        }
+
+        let field = non_residue.field;
+        let zeros = [Fp::zero(field); MAX_BINOMIAL_EXTENSION_DEGREE];
+
+        Ok(Self {
+            non_residue,
+            field: & field,
+            degree,
+            frobenius_roots: zeros,
+            frobenius_coeffs_are_calculated: false
+        })
+    }
+
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    pub(crate) fn calculate_frobenius_coeffs(
+        &mut self,
+        modulus: &MaxFieldUint,
+    ) -> Result<(), ()> {
+        // z^p = z * xi^((p-1)/k) if k | p - 1, and for higher powers of
+        // Frobenius the coefficient of z^i is xi^(i * (p^j - 1)/k) = (xi^((p-1)/k))^(i*j)
+        // as xi^((p-1)/k) is an element of the base field
+        let one = MaxFieldUint::from(1u64);
+        let divisor = MaxFieldUint::from(self.degree as u64);
+
+        let power = *modulus - one;
+        let (power, rem) = power.div_mod(divisor);
+        if !rem.is_zero() {
+            if !crate::features::in_gas_metering() {
+                return Err(());
+            }
+        }
+
+        let root = self.non_residue.pow(power.as_ref());
+
+        let mut current = Fp::one(self.field);
+        for i in 0..self.degree {
+            self.frobenius_roots[i] = current;
+            current.mul_assign(&root);
+        }
+
+        self.frobenius_coeffs_are_calculated = true;
+
+        Ok(())
+    }
+}
@@ -1,16 +1,40 @@
+//! Extension fields built as towers over a prime field `Fp`. Every level is described
+//! by an extension structure (e.g. `Extension2`) that holds a non-residue from the level
+//! below and precomputed Frobenius coefficients, and elements (e.g. `Fp2`) borrow it.
+//!
+//! To build `Fp12` as 2 over 3 over 2 one creates `Extension2` from a non-residue in `Fp`,
+//! `Extension3Over2` from a non-residue in `Fp2` and `Extension2Over3Over2` from a non-residue
+//! in `Fp6`, calling `calculate_frobenius_coeffs` (or `calculate_frobenius_coeffs_optimized`)
+//! with the base field modulus on every level before using Frobenius maps. Arithmetic is
+//! available through the `FieldElement` trait.
+//!
+//! Elements are decoded from the concatenation of big endian encodings of the coefficients
+//! c0, c1 (and c2), where every coefficient is itself encoded the same way for the level below.
+
 pub mod fp2;
 pub mod fp3;
 pub mod fp4_as_2_over_2;
 pub mod fp6_as_2_over_3;
 pub mod fp6_as_3_over_2;
 pub mod fp12_as_2_over3_over_2;
+pub mod fp12_as_3_over_4;
+pub mod fp24_as_2_over_3_over_4;
+pub mod fp8_as_2_over_4;
+pub mod fp24_as_3_over_8;
+pub mod fp48_as_2_over_3_over_8;
+pub mod fp9_as_3_over_3;
+pub mod fp18_as_2_over_3_over_3;
+pub mod fp8_as_2_over_4_generic;
+pub mod fp16_as_2_over_8;
+pub mod fpk;
 
 use crate::fp::Fp;
 use crate::field::{SizedPrimeField};
 use crate::traits::FieldElement;
-use crate::representation::{ElementRepr};
+use crate::representation::{ElementRepr, RepresentationDecodingError};
 use crate::traits::ZeroAndOne;
 use crate::integers::*;
+use crate::alloc_prelude::*;
 
 
 pub(crate) struct Fp2Fp4FrobeniusBaseElements<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >{
@@ -35,6 +59,28 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Fp2Fp4FrobeniusBaseEleme
     }
 }
 
+pub(crate) struct Fp4Fp8FrobeniusBaseElements<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >{
+    pub(crate) non_residue_in_q_minus_one_by_eight: Fp<'a, E, F>,
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Fp4Fp8FrobeniusBaseElements<'a, E, F> {
+    pub(crate) fn construct(modulus: &MaxFieldUint, non_residue: &Fp<'a, E, F>) -> Result<Self, ()> {
+        if !is_one_mod_eight(&modulus) {
+            if !crate::features::in_gas_metering() {
+                return Err(());
+            }
+        }
+
+        let power = *modulus >> 3;
+
+        let result = Fp4Fp8FrobeniusBaseElements::<'a, E, F> {
+            non_residue_in_q_minus_one_by_eight: non_residue.pow(power.as_ref())
+        };
+
+        Ok(result)
+    }
+}
+
 pub(crate) struct Fp3Fp6FrobeniusBaseElements<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >{
     pub(crate) non_residue_in_q_minus_one_by_six: Fp<'a, E, F>,
 }
@@ -120,6 +166,111 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Fp6Fp12FrobeniusBaseElem
     }
 }
 
+pub(crate) struct Fp12Fp24FrobeniusBaseElements<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >{
+    pub(crate) non_residue_in_q_minus_one_by_six: fp4_as_2_over_2::Fp4<'a, E, F>,
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Fp12Fp24FrobeniusBaseElements<'a, E, F> {
+    pub(crate) fn construct(modulus: &MaxFieldUint, non_residue: &fp4_as_2_over_2::Fp4<'a, E, F>) -> Result<Self, ()> {
+        // all other coefficients are obtained from this one by Frobenius maps
+        // in Fp4, so only 6 | q - 1 is required
+        let one = MaxFieldUint::from(1u64);
+        let six = MaxFieldUint::from(6u64);
+
+        let power = *modulus - one;
+        let (power, rem) = power.div_mod(six);
+        if !rem.is_zero() {
+            if !crate::features::in_gas_metering() {
+                return Err(());
+            }
+        }
+
+        let result = Fp12Fp24FrobeniusBaseElements::<'a, E, F> {
+            non_residue_in_q_minus_one_by_six: non_residue.pow(power.as_ref())
+        };
+
+        Ok(result)
+    }
+}
+
+pub(crate) struct Fp24Fp48FrobeniusBaseElements<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >{
+    pub(crate) non_residue_in_q_minus_one_by_six: fp8_as_2_over_4::Fp8<'a, E, F>,
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Fp24Fp48FrobeniusBaseElements<'a, E, F> {
+    pub(crate) fn construct(modulus: &MaxFieldUint, non_residue: &fp8_as_2_over_4::Fp8<'a, E, F>) -> Result<Self, ()> {
+        // same as for Fp12 and Fp24 over Fp4, all other coefficients are obtained
+        // by Frobenius maps in Fp8
+        let one = MaxFieldUint::from(1u64);
+        let six = MaxFieldUint::from(6u64);
+
+        let power = *modulus - one;
+        let (power, rem) = power.div_mod(six);
+        if !rem.is_zero() {
+            if !crate::features::in_gas_metering() {
+                return Err(());
+            }
+        }
+
+        let result = Fp24Fp48FrobeniusBaseElements::<'a, E, F> {
+            non_residue_in_q_minus_one_by_six: non_residue.pow(power.as_ref())
+        };
+
+        Ok(result)
+    }
+}
+
+pub(crate) struct Fp9Fp18FrobeniusBaseElements<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >{
+    pub(crate) non_residue_in_q_minus_one_by_six: fp3::Fp3<'a, E, F>,
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Fp9Fp18FrobeniusBaseElements<'a, E, F> {
+    pub(crate) fn construct(modulus: &MaxFieldUint, non_residue: &fp3::Fp3<'a, E, F>) -> Result<Self, ()> {
+        // same as for Fp12 and Fp24 over Fp4, all other coefficients are obtained
+        // by Frobenius maps in Fp3
+        let one = MaxFieldUint::from(1u64);
+        let six = MaxFieldUint::from(6u64);
+
+        let power = *modulus - one;
+        let (power, rem) = power.div_mod(six);
+        if !rem.is_zero() {
+            if !crate::features::in_gas_metering() {
+                return Err(());
+            }
+        }
+
+        let result = Fp9Fp18FrobeniusBaseElements::<'a, E, F> {
+            non_residue_in_q_minus_one_by_six: non_residue.pow(power.as_ref())
+        };
+
+        Ok(result)
+    }
+}
+
+pub(crate) struct Fp8Fp16FrobeniusBaseElements<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >{
+    pub(crate) non_residue_in_q_minus_one_by_four: fp4_as_2_over_2::Fp4<'a, E, F>,
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Fp8Fp16FrobeniusBaseElements<'a, E, F> {
+    pub(crate) fn construct(modulus: &MaxFieldUint, non_residue: &fp4_as_2_over_2::Fp4<'a, E, F>) -> Result<Self, ()> {
+        // coefficients for both Fp8 and Fp16 are obtained by Frobenius maps in Fp4,
+        // so only 4 | q - 1 is required
+        if !is_one_mod_four(&modulus) {
+            if !crate::features::in_gas_metering() {
+                return Err(());
+            }
+        }
+
+        let power = *modulus >> 2;
+
+        let result = Fp8Fp16FrobeniusBaseElements::<'a, E, F> {
+            non_residue_in_q_minus_one_by_four: non_residue.pow(power.as_ref())
+        };
+
+        Ok(result)
+    }
+}
+
 pub(crate) fn is_non_nth_root<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>
 (
     element: & Fp<'a, FE, F>,
@@ -191,6 +342,94 @@ pub(crate) fn is_non_nth_root_fp2<'a, FE: ElementRepr, F: SizedPrimeField<Repr =
     }
 }
 
+/// Same as `is_non_nth_root_fp4`, norm from Fp3 to the base field is
+/// c0^3 + xi * c1^3 + xi^2 * c2^3 - 3 * xi * c0 * c1 * c2
+pub(crate) fn is_non_nth_root_fp3<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>
+(
+    element: & self::fp3::Fp3<'a, FE, F>,
+    modulus: &MaxFieldUint,
+    n: u64
+) -> bool {
+    if element.is_zero() {
+        return false;
+    }
+
+    let xi = element.extension_field.non_residue;
+
+    let mut norm = element.c0;
+    norm.square();
+    norm.mul_assign(&element.c0);
+
+    let mut t = element.c1;
+    t.square();
+    t.mul_assign(&element.c1);
+    t.mul_assign(&xi);
+    norm.add_assign(&t);
+
+    let mut t = element.c2;
+    t.square();
+    t.mul_assign(&element.c2);
+    t.mul_assign(&xi);
+    t.mul_assign(&xi);
+    norm.add_assign(&t);
+
+    let mut t = element.c0;
+    t.mul_assign(&element.c1);
+    t.mul_assign(&element.c2);
+    t.mul_assign(&xi);
+    let mut t3 = t;
+    t3.double();
+    t3.add_assign(&t);
+    norm.sub_assign(&t3);
+
+    is_non_nth_root(&norm, modulus, n)
+}
+
+/// Checks that an element of Fp4 (as 2 over 2) is not an n-th power for n | q - 1.
+/// Norm from Fp4 to the base field maps n-th powers onto n-th powers and is surjective,
+/// so an element is an n-th power in Fp4 iff its norm is an n-th power in the base field
+pub(crate) fn is_non_nth_root_fp4<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>
+(
+    element: & self::fp4_as_2_over_2::Fp4<'a, FE, F>,
+    modulus: &MaxFieldUint,
+    n: u64
+) -> bool {
+    if element.is_zero() {
+        return false;
+    }
+
+    // norm from Fp4 to Fp2 is c0^2 - u * c1^2
+    let mut c1_squared = element.c1;
+    c1_squared.square();
+    c1_squared.mul_by_nonresidue(element.extension_field);
+    let mut norm = element.c0;
+    norm.square();
+    norm.sub_assign(&c1_squared);
+
+    is_non_nth_root(&norm.norm(), modulus, n)
+}
+
+/// Same as `is_non_nth_root_fp4`, norm from Fp8 to Fp4 is c0^2 - w * c1^2
+pub(crate) fn is_non_nth_root_fp8<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>
+(
+    element: & self::fp8_as_2_over_4::Fp8<'a, FE, F>,
+    modulus: &MaxFieldUint,
+    n: u64
+) -> bool {
+    if element.is_zero() {
+        return false;
+    }
+
+    let mut c1_squared = element.c1;
+    c1_squared.square();
+    c1_squared.mul_by_nonresidue(element.extension_field);
+    let mut norm = element.c0;
+    norm.square();
+    norm.sub_assign(&c1_squared);
+
+    is_non_nth_root_fp4(&norm, modulus, n)
+}
+
 pub(crate) fn is_one_mod_two
 (
     modulus: &MaxFieldUint,
@@ -230,4 +469,25 @@ pub(crate) fn is_one_mod_six
     let div_2 = *modulus >> 1;
     
     is_one_mod_three(&div_2)
-}
\ No newline at end of file
+}
+
+pub(crate) fn is_one_mod_eight
+(
+    modulus: &MaxFieldUint,
+) -> bool {
+    const LAST_THREE_BITS_MASK :u64 = 7;
+    modulus.low_u64() & LAST_THREE_BITS_MASK == 1u64
+}
+
+/// Splits an encoding of an extension field element into equal length encodings
+/// of its coefficients, lowest degree first
+pub(crate) fn split_coefficients_encoding(
+    bytes: &[u8],
+    num_coefficients: usize
+) -> Result<Vec<&[u8]>, RepresentationDecodingError> {
+    if bytes.len() == 0 || bytes.len() % num_coefficients != 0 {
+        return Err(RepresentationDecodingError::NotInField(format!("Encoding of length {} can not be split into {} coefficients", bytes.len(), num_coefficients)));
+    }
+
+    Ok(bytes.chunks(bytes.len() / num_coefficients).collect())
+}
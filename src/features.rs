@@ -3,7 +3,7 @@ pub(crate) fn in_gas_metering() -> bool {
     #[cfg(feature = "gas_metering_mode")]
     return true;
 
-    return std::option_env!("GAS_METERING").is_some();
+    return core::option_env!("GAS_METERING").is_some();
 }
 
 #[allow(unreachable_code)]
@@ -14,6 +14,18 @@ pub(crate) fn in_fuzzing() -> bool {
     return false;
 }
 
+/// Multiplications and multiexponentiations on Weierstrass and twisted Edwards curves use fixed
+/// windows, masked table lookups and complete addition formulas, so the sequence of field operations
+/// and memory accesses doesn't depend on the scalars, for use with secret scalars. Field arithmetic
+/// itself and curves over binary fields are not covered
+#[allow(unreachable_code)]
+pub(crate) fn in_constant_time() -> bool {
+    #[cfg(feature = "constant-time")]
+    return true;
+
+    return false;
+}
+
 pub(crate) fn in_fuzzing_or_gas_metering() -> bool {
     in_fuzzing() || in_gas_metering()
 }
\ No newline at end of file
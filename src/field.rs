@@ -1,4 +1,5 @@
 use eth_pairings_repr_derive::*;
+use crate::alloc_prelude::*;
 
 #[derive(ElementRepresentation)]
 #[NumberOfLimbs = "4"]
@@ -52,6 +53,86 @@ struct U960(U960Repr);
 #[NumberOfLimbs = "16"]
 struct U1024(U1024Repr);
 
+#[cfg(any(feature = "limbs-24", feature = "limbs-32"))]
+#[derive(ElementRepresentation)]
+#[NumberOfLimbs = "17"]
+struct U1088(U1088Repr);
+
+#[cfg(any(feature = "limbs-24", feature = "limbs-32"))]
+#[derive(ElementRepresentation)]
+#[NumberOfLimbs = "18"]
+struct U1152(U1152Repr);
+
+#[cfg(any(feature = "limbs-24", feature = "limbs-32"))]
+#[derive(ElementRepresentation)]
+#[NumberOfLimbs = "19"]
+struct U1216(U1216Repr);
+
+#[cfg(any(feature = "limbs-24", feature = "limbs-32"))]
+#[derive(ElementRepresentation)]
+#[NumberOfLimbs = "20"]
+struct U1280(U1280Repr);
+
+#[cfg(any(feature = "limbs-24", feature = "limbs-32"))]
+#[derive(ElementRepresentation)]
+#[NumberOfLimbs = "21"]
+struct U1344(U1344Repr);
+
+#[cfg(any(feature = "limbs-24", feature = "limbs-32"))]
+#[derive(ElementRepresentation)]
+#[NumberOfLimbs = "22"]
+struct U1408(U1408Repr);
+
+#[cfg(any(feature = "limbs-24", feature = "limbs-32"))]
+#[derive(ElementRepresentation)]
+#[NumberOfLimbs = "23"]
+struct U1472(U1472Repr);
+
+#[cfg(any(feature = "limbs-24", feature = "limbs-32"))]
+#[derive(ElementRepresentation)]
+#[NumberOfLimbs = "24"]
+struct U1536(U1536Repr);
+
+#[cfg(feature = "limbs-32")]
+#[derive(ElementRepresentation)]
+#[NumberOfLimbs = "25"]
+struct U1600(U1600Repr);
+
+#[cfg(feature = "limbs-32")]
+#[derive(ElementRepresentation)]
+#[NumberOfLimbs = "26"]
+struct U1664(U1664Repr);
+
+#[cfg(feature = "limbs-32")]
+#[derive(ElementRepresentation)]
+#[NumberOfLimbs = "27"]
+struct U1728(U1728Repr);
+
+#[cfg(feature = "limbs-32")]
+#[derive(ElementRepresentation)]
+#[NumberOfLimbs = "28"]
+struct U1792(U1792Repr);
+
+#[cfg(feature = "limbs-32")]
+#[derive(ElementRepresentation)]
+#[NumberOfLimbs = "29"]
+struct U1856(U1856Repr);
+
+#[cfg(feature = "limbs-32")]
+#[derive(ElementRepresentation)]
+#[NumberOfLimbs = "30"]
+struct U1920(U1920Repr);
+
+#[cfg(feature = "limbs-32")]
+#[derive(ElementRepresentation)]
+#[NumberOfLimbs = "31"]
+struct U1984(U1984Repr);
+
+#[cfg(feature = "limbs-32")]
+#[derive(ElementRepresentation)]
+#[NumberOfLimbs = "32"]
+struct U2048(U2048Repr);
+
 /// PrimeField is a structure that it instantiated at the runtime 
 /// and holds all the necessary information for further arithmetic
 /// operations (mainly precompiled Montgommery constants)
@@ -111,7 +192,7 @@ fn num_words(number: &MaxFieldSquaredUint) -> usize {
 }
 
 /// This trait represents an element of a field.
-pub trait SizedPrimeField: Sized + Send + Sync + std::fmt::Debug + 'static + Copy + Clone
+pub trait SizedPrimeField: Sized + Send + Sync + core::fmt::Debug + 'static + Copy + Clone
 {
     type Repr: ElementRepr;
 
@@ -176,16 +257,27 @@ impl<E: ElementRepr> SizedPrimeField for PrimeField<E> {
     }
 }
 
+/// Returns a number of 64-bit limbs used to represent elements of the field 
+/// with a modulus of `bitlength` bits.
+/// 
+/// Montgomery reduction drops the final carry and additions are done without carry
+/// propagation, so the top limb must always have at least one spare bit. A modulus that
+/// is an exact multiple of 64 bits long (256, 320, ...) therefore gets an extra limb.
+/// Short moduli, down to single limb ones, use the smallest representation of `NUM_LIMBS_MIN` limbs.
 pub(crate) fn calculate_num_limbs(bitlength: usize) -> Result<usize, ()> {
+    use crate::public_interface::constants::{NUM_LIMBS_MIN, NUM_LIMBS_MAX};
+
     let mut num_limbs = (bitlength / 64) + 1;
-    if num_limbs < 4 {
-        num_limbs = 4;
+    if num_limbs < NUM_LIMBS_MIN {
+        num_limbs = NUM_LIMBS_MIN;
     }
 
-    if num_limbs > 16 {
+    if num_limbs > NUM_LIMBS_MAX {
         return Err(());
     }
 
+    debug_assert!(bitlength < num_limbs * 64);
+
     Ok(num_limbs)
 }
 
@@ -325,4 +417,33 @@ mod test {
 
         assert!(i == REPEATS);
     }
+
+    #[cfg(feature = "limbs-32")]
+    #[test]
+    fn test_widest_modulus() {
+        use crate::integers::*;
+        use crate::fp::Fp;
+        use crate::traits::{FieldElement, ZeroAndOne};
+        use crate::public_interface::constants::MAX_MODULUS_BIT_LEN;
+
+        // 2^2040 - 3, odd and as wide as the length encoding allows
+        let modulus_biguint = (BigUint::from(1u64) << 2040) - BigUint::from(3u64);
+        assert_eq!(modulus_biguint.bits(), MAX_MODULUS_BIT_LEN);
+
+        let modulus = MaxFieldUint::from_big_endian(&modulus_biguint.to_bytes_be());
+        assert_eq!(super::calculate_num_limbs(modulus.bits()).unwrap(), 32);
+        assert!(super::field_from_modulus::<super::U1984Repr>(&modulus).is_err());
+        let field = super::field_from_modulus::<super::U2048Repr>(&modulus).unwrap();
+
+        let one = Fp::one(&field);
+        let mut two = one.clone();
+        two.double();
+        let mut minus_one = one.clone();
+        minus_one.negate();
+        // (p - 1) * 2 = p - 2 = -2
+        let mut t = minus_one.clone();
+        t.mul_assign(&two);
+        t.add_assign(&two);
+        assert!(t.is_zero());
+    }
 }
\ No newline at end of file
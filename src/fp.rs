@@ -3,7 +3,9 @@ use crate::traits::FieldElement;
 use crate::traits::BitIterator;
 use crate::traits::FieldExtension;
 use crate::field::SizedPrimeField;
-use crate::traits::ZeroAndOne;
+use crate::traits::{ZeroAndOne, ConditionalSelect};
+use crate::representation::conditional_select_repr;
+use crate::alloc_prelude::*;
 
 pub struct Fp<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > {
     pub(crate) repr: E,
@@ -24,7 +26,7 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Copy for Fp<'a, E, F> {}
 
 impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Ord for Fp<'a, E, F> {
     #[inline(always)]
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
         // use non-montgommery form
         let modulus = self.field.modulus();
         let mont_inv = self.field.mont_inv();
@@ -32,13 +34,13 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Ord for Fp<'a, E, F> {
         let that = other.repr.into_normal_repr(&modulus, mont_inv);
         for (a, b) in this.as_ref().iter().rev().zip(that.as_ref().iter().rev()) {
             if a < b {
-                return std::cmp::Ordering::Less
+                return core::cmp::Ordering::Less
             } else if a > b {
-                return std::cmp::Ordering::Greater
+                return core::cmp::Ordering::Greater
             }
         }
 
-        std::cmp::Ordering::Equal
+        core::cmp::Ordering::Equal
     }
 }
 
@@ -60,14 +62,14 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Eq for Fp<'a, E, F> {
 
 impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > PartialOrd for Fp<'a, E, F> {
     #[inline(always)]
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
         Some(self.cmp(other))
     }
 }
 
-impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > std::fmt::Debug for Fp<'a, E, F>
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > core::fmt::Debug for Fp<'a, E, F>
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         write!(f, "0x")?;
         // for i in self.repr.as_ref().iter().rev() {
         for i in self.into_repr().as_ref().iter().rev() {
@@ -78,8 +80,8 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > std::fmt::Debug for Fp<'
     }
 }
 
-impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > std::fmt::Display for Fp<'a, E, F> {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > core::fmt::Display for Fp<'a, E, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         write!(f, "0x")?;
         // for i in self.repr.as_ref().iter().rev() {
         for i in self.into_repr().as_ref().iter().rev() {
@@ -149,14 +151,12 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Fp<'a, E, F> {
     pub fn from_be_bytes(field: &'a F, bytes: &[u8], allow_padding: bool) -> Result<Self, RepresentationDecodingError> {
         let mut repr = E::default();
         if bytes.len() >= repr.as_ref().len() * 8 {
-            repr.read_be(bytes).map_err(|e| RepresentationDecodingError::NotInField(format!("Failed to read big endian bytes, {}", e)))?;
+            repr.read_be_from_slice(bytes).map_err(|e| RepresentationDecodingError::NotInField(format!("Failed to read big endian bytes, {}", e)))?;
         } else {
             if allow_padding {
-                let mut padded = vec![0u8; repr.as_ref().len() * 8 - bytes.len()];
-                padded.extend_from_slice(bytes);
-                repr.read_be(&padded[..]).map_err(|e| RepresentationDecodingError::NotInField(format!("Failed to read big endian bytes, {}", e)))?;
+                repr.read_be_from_slice_of_any_len(bytes).map_err(|e| RepresentationDecodingError::NotInField(format!("Failed to read big endian bytes, {}", e)))?;
             } else {
-                repr.read_be(&bytes[..]).map_err(|e| RepresentationDecodingError::NotInField(format!("Failed to read big endian bytes without padding, {}", e)))?;
+                repr.read_be_from_slice(&bytes[..]).map_err(|e| RepresentationDecodingError::NotInField(format!("Failed to read big endian bytes without padding, {}", e)))?;
             }
         }
         Self::from_repr(field, repr)
@@ -178,20 +178,18 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Fp<'a, E, F> {
                         return Err(RepresentationDecodingError::NotInField("top bytes of the padded BE encoding are NOT zeroes".to_owned()));
                     }
                 }
-                repr.read_be(&bytes[start..]).map_err(|e| RepresentationDecodingError::NotInField(format!("Failed to read big endian bytes, {}", e)))?;
+                repr.read_be_from_slice(&bytes[start..]).map_err(|e| RepresentationDecodingError::NotInField(format!("Failed to read big endian bytes, {}", e)))?;
             } else {
                 if bytes.len() != necessary_length {
                     return Err(RepresentationDecodingError::NotInField("supplied encoding is longer than expected".to_owned()));
                 }
-                repr.read_be(&bytes[..]).map_err(|e| RepresentationDecodingError::NotInField(format!("Failed to read big endian bytes, {}", e)))?;
+                repr.read_be_from_slice(&bytes[..]).map_err(|e| RepresentationDecodingError::NotInField(format!("Failed to read big endian bytes, {}", e)))?;
             }
         } else {
             if pad_beginning {
-                let mut padded = vec![0u8; necessary_length - bytes.len()];
-                padded.extend_from_slice(bytes);
-                repr.read_be(&padded[..]).map_err(|e| RepresentationDecodingError::NotInField(format!("Failed to read big endian bytes, {}", e)))?;
+                repr.read_be_from_slice_of_any_len(bytes).map_err(|e| RepresentationDecodingError::NotInField(format!("Failed to read big endian bytes, {}", e)))?;
             } else {
-                repr.read_be(&bytes[..]).map_err(|e| RepresentationDecodingError::NotInField(format!("Failed to read big endian bytes without padding, {}", e)))?;
+                repr.read_be_from_slice(&bytes[..]).map_err(|e| RepresentationDecodingError::NotInField(format!("Failed to read big endian bytes without padding, {}", e)))?;
             }
         }
         Self::from_repr(field, repr)
@@ -333,12 +331,14 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Fp<'a, E, F> {
     #[inline]
     fn mul_assign_with_partial_reduction(&mut self, other: &Self)
     {
+        crate::execution_report::count_field_multiplication();
         self.repr.mont_mul_assign_with_partial_reduction(&other.repr, &self.field.modulus(), self.field.mont_inv());
     }
 
     #[inline]
     fn square_with_partial_reduction(&mut self)
     {
+        crate::execution_report::count_field_multiplication();
         self.repr.mont_square_with_partial_reduction(&self.field.modulus(), self.field.mont_inv());
     }
 }
@@ -363,6 +363,24 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > ZeroAndOne for Fp<'a, E,
     }
 }
 
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > ConditionalSelect for Fp<'a, E, F> {
+    #[inline(always)]
+    fn conditional_select(a: &Self, b: &Self, choice: bool) -> Self {
+        Self {
+            repr: conditional_select_repr(&a.repr, &b.repr, choice),
+            field: a.field
+        }
+    }
+}
+
+// zero in Montgomery form is a zero representation, so the cleared element stays valid
+#[cfg(feature = "zeroize")]
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > zeroize::Zeroize for Fp<'a, E, F> {
+    fn zeroize(&mut self) {
+        zeroize::Zeroize::zeroize(self.repr.as_mut());
+    }
+}
+
 impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for Fp<'a, E, F> {
     /// Returns true iff this element is zero.
     #[inline]
@@ -416,12 +434,44 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for Fp<'a,
     #[inline]
     fn mul_assign(&mut self, other: &Self)
     {
+        crate::execution_report::count_field_multiplication();
         self.repr.mont_mul_assign(&other.repr, &self.field.modulus(), self.field.mont_inv());
     }
 
+    #[cfg(all(feature = "simd", any(target_arch = "x86_64", target_arch = "aarch64")))]
+    fn batch_mul_assign(a: &mut [Self], b: &[Self]) {
+        use crate::simd::LANES;
+        debug_assert_eq!(a.len(), b.len());
+
+        let mut processed = 0;
+        if a.len() >= LANES && crate::simd::supports::<E>() {
+            let modulus = a[0].field.modulus();
+            let mont_inv = a[0].field.mont_inv();
+            for (a, b) in a.chunks_exact_mut(LANES).zip(b.chunks_exact(LANES)) {
+                let mut lhs = [E::default(); LANES];
+                let mut rhs = [E::default(); LANES];
+                for lane in 0..LANES {
+                    crate::execution_report::count_field_multiplication();
+                    lhs[lane] = a[lane].repr;
+                    rhs[lane] = b[lane].repr;
+                }
+                crate::simd::mont_mul_lanes(&mut lhs, &rhs, modulus, mont_inv);
+                for (a, r) in a.iter_mut().zip(lhs.iter()) {
+                    a.repr = *r;
+                }
+                processed += LANES;
+            }
+        }
+
+        for (a, b) in a[processed..].iter_mut().zip(b[processed..].iter()) {
+            a.mul_assign(b);
+        }
+    }
+
     #[inline]
     fn square(&mut self)
     {
+        crate::execution_report::count_field_multiplication();
         self.repr.mont_square(&self.field.modulus(), self.field.mont_inv());
     }
 
@@ -480,4 +530,107 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for Fp<'a,
     fn frobenius_map(&mut self, _power: usize) {
         // unreachable!();
     }
-}
\ No newline at end of file
+}
+
+/// Product of field elements that is not reduced yet, as a double width value below
+/// `W = modulus * 2^(64 * NUM_LIMBS)`. Such values are added and subtracted modulo `W`,
+/// which is a multiple of the modulus, and the sum is reduced once (lazy reduction).
+/// Sums stay below `2 * W`, so the modulus must have a spare top bit, which is always
+/// the case for the number of limbs chosen by `calculate_num_limbs`
+pub(crate) struct WideFp<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > {
+    lo: E,
+    hi: E,
+    field: &'a F,
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Clone for WideFp<'a, E, F> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self {
+            lo: self.lo,
+            hi: self.hi,
+            field: self.field
+        }
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Copy for WideFp<'a, E, F> {}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > WideFp<'a, E, F> {
+    /// `a * b` without the Montgomery reduction
+    #[inline]
+    pub(crate) fn mul(a: &Fp<'a, E, F>, b: &Fp<'a, E, F>) -> Self {
+        crate::execution_report::count_field_multiplication();
+        let (lo, hi) = a.repr.mul_wide(&b.repr);
+
+        Self {
+            lo,
+            hi,
+            field: a.field
+        }
+    }
+
+    /// `a * 2^(64 * NUM_LIMBS)`, so that `reduce` gives back `a`
+    #[inline]
+    pub(crate) fn from_fp(a: &Fp<'a, E, F>) -> Self {
+        Self {
+            lo: E::default(),
+            hi: a.repr,
+            field: a.field
+        }
+    }
+
+    #[inline]
+    pub(crate) fn add_assign(&mut self, other: &Self) {
+        let mut carry = 0u64;
+        for (a, b) in self.lo.as_mut().iter_mut().zip(other.lo.as_ref().iter()) {
+            *a = crate::arithmetics::adc(*a, *b, &mut carry);
+        }
+        for (a, b) in self.hi.as_mut().iter_mut().zip(other.hi.as_ref().iter()) {
+            *a = crate::arithmetics::adc(*a, *b, &mut carry);
+        }
+        debug_assert!(carry == 0);
+
+        // the low half of `W` is zero
+        let modulus = self.field.modulus();
+        if &self.hi >= modulus {
+            self.hi.sub_noborrow(modulus);
+        }
+    }
+
+    #[inline]
+    pub(crate) fn sub_assign(&mut self, other: &Self) {
+        let mut borrow = 0u64;
+        for (a, b) in self.lo.as_mut().iter_mut().zip(other.lo.as_ref().iter()) {
+            *a = crate::arithmetics::sbb(*a, *b, &mut borrow);
+        }
+        for (a, b) in self.hi.as_mut().iter_mut().zip(other.hi.as_ref().iter()) {
+            *a = crate::arithmetics::sbb(*a, *b, &mut borrow);
+        }
+
+        // wrapped around, so add `W`
+        if borrow != 0 {
+            self.hi.add_nocarry(self.field.modulus());
+        }
+    }
+
+    #[inline]
+    pub(crate) fn negate(&mut self) {
+        let mut zero = *self;
+        zero.lo = E::default();
+        zero.hi = E::default();
+        zero.sub_assign(self);
+        *self = zero;
+    }
+
+    #[inline]
+    pub(crate) fn reduce(&self) -> Fp<'a, E, F> {
+        let mut repr = self.lo;
+        repr.mont_reduce_wide(&self.hi, self.field.modulus(), self.field.mont_inv());
+
+        Fp {
+            repr,
+            field: self.field
+        }
+    }
+}
@@ -111,6 +111,18 @@ pub(crate) static MULTIEXP_PARAMS_INSTANCE: Lazy<G1G2MultiexpParams> = Lazy::new
     serde_json::from_str(MULTIEXP_PARAMS_JSON).expect("must deserialize parameters")
 });
 
+// Miller-Rabin test of the group order is an exponentiation modulo the order for each of 12 bases,
+// so its time is cubic in the number of limbs. Coefficients of 1, 2 and 3 powers of limbs are fitted
+// to the running time at 30 MGas/second with a 25% margin
+const PRIMALITY_TEST_PRICE_COEFFICIENTS: [u64; 3] = [1125, 225, 140];
+
+pub(crate) fn meter_primality_test(order_limbs: usize) -> Result<u64, ApiError> {
+    let limbs = order_limbs as u64;
+    let [linear, quadratic, cubic] = PRIMALITY_TEST_PRICE_COEFFICIENTS;
+
+    Ok(limbs * (linear + limbs * (quadratic + limbs * cubic)))
+}
+
 pub(crate) fn meter_addition<P: ArithmeticAdditionParams>(modulus_limbs: usize, parameters: &P) -> Result<u64, ApiError> {
     let found = *parameters.params().get(&modulus_limbs).ok_or(ApiError::MissingValue)?;
 
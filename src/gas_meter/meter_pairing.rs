@@ -10,12 +10,37 @@ use super::parsers::*;
 use crate::pairings::calculate_hamming_weight;
 use crate::public_interface::decode_utils::*;
 use crate::public_interface::sane_limits::*;
+use crate::public_interface::PointEncoding;
 
 pub(crate) const MNT4_MAX_MODULUS_POWER: usize = 4;
 pub(crate) const MNT6_MAX_MODULUS_POWER: usize = 6;
 pub(crate) const BN_MAX_MODULUS_POWER: usize = 6;
 pub(crate) const BLS12_MAX_MODULUS_POWER: usize = 6;
 
+// There is no separately fitted model for BLS24 yet, so it's priced by the BLS12 model
+// for the same parameters times a conservative factor. Every Fp24 and Fp4 operation is
+// about 3 times more expensive than the Fp12 and Fp2 ones, and the hard part
+// of the final exponentiation needs about twice as many exponentiations by X
+pub(crate) const BLS24_TO_BLS12_COST_FACTOR: u64 = 6;
+// Fp4 multiplication is done with 3 multiplications in Fp2
+pub(crate) const BLS24_G2_TO_EXT_2_COST_FACTOR: u64 = 3;
+
+// Same for BLS48: Fp48 and Fp8 operations are about 9 times more expensive than
+// the Fp12 and Fp2 ones, and the hard part needs about 3.5 times more exponentiations by X
+pub(crate) const BLS48_TO_BLS12_COST_FACTOR: u64 = 32;
+pub(crate) const BLS48_G2_TO_EXT_2_COST_FACTOR: u64 = 9;
+
+// KSS16 and KSS18 are priced the same way. Fp16 and Fp18 operations are about 2 times
+// more expensive than the Fp12 ones, the hard part of the final exponentiation needs
+// about twice as many exponentiations by X and KSS16 only has the quadratic cyclotomic
+// squaring over Fp8, that is cheaper than the generic one by a third
+pub(crate) const KSS16_TO_BLS12_COST_FACTOR: u64 = 8;
+pub(crate) const KSS18_TO_BLS12_COST_FACTOR: u64 = 8;
+// Fp4 multiplication is done with 3 multiplications in Fp2, Fp3 one is about
+// twice as expensive as the Fp2 one
+pub(crate) const KSS16_G2_TO_EXT_2_COST_FACTOR: u64 = 3;
+pub(crate) const KSS18_G2_TO_EXT_2_COST_FACTOR: u64 = 2;
+
 #[derive(Clone, Deserialize, Debug)]
 pub(crate) struct MntPairingParams {
     #[serde(deserialize_with = "parse_hashmap_usize_u64_from_ints")]
@@ -85,7 +110,7 @@ pub(crate) static BN_PARAMS_INSTANCE: Lazy<BnPairingParams> = Lazy::new(|| {
     serde_json::from_str(BN_PARAMS_JSON).expect("must deserialize parameters")
 });
 
-pub(crate) fn meter_mnt_pairing(input: &[u8], params: &MntPairingParams, max_power: usize, ext_degree: usize) -> Result<u64, ApiError> {
+pub(crate) fn meter_mnt_pairing(input: &[u8], params: &MntPairingParams, max_power: usize, ext_degree: usize, tail: PairingInputTail, limits: &Limits, encoding: PointEncoding) -> Result<u64, ApiError> {
     let (
         modulus, 
         order_len, 
@@ -94,8 +119,8 @@ pub(crate) fn meter_mnt_pairing(input: &[u8], params: &MntPairingParams, max_pow
         (exp_w0_bits, exp_w0_hamming),
         (exp_w1_bits, exp_w1_hamming),
         (num_g1_subgroup_checks, num_g2_subgroup_checks),
-        _
-    ) = parse_mnt_pairing_parameters(&input, ext_degree)?;
+        with_final_exp
+    ) = parse_mnt_pairing_parameters(input, ext_degree, tail, limits, encoding)?;
 
     let modulus_limbs = num_limbs_for_modulus(&modulus)?;
     // let order_limbs = num_units_for_group_order(&order)?;
@@ -105,6 +130,7 @@ pub(crate) fn meter_mnt_pairing(input: &[u8], params: &MntPairingParams, max_pow
         modulus_limbs,
         order_limbs,
         num_pairs,
+        with_final_exp,
         (ate_loop_bits, ate_loop_hamming), 
         (exp_w0_bits, exp_w0_hamming),
         (exp_w1_bits, exp_w1_hamming),
@@ -136,10 +162,123 @@ pub(crate) fn meter_mnt_pairing(input: &[u8], params: &MntPairingParams, max_pow
     Ok(estimate)
 }
 
+// BW6 uses the same Fp6 tower as MNT6, so it's priced by the MNT6 model. Both loops
+// of the Miller loop are accounted as one loop of the total length, and the hard part of
+// the final exponentiation is one exponentiation by (p^2 - p + 1)/r that is priced
+// as the exponentiation by w0 of MNT6 with trivial w1. Line functions are evaluated
+// with Fp coefficients, so the model is conservative for the Miller loop
+pub(crate) fn meter_bw6_pairing(input: &[u8], params: &MntPairingParams, max_power: usize, tail: PairingInputTail, limits: &Limits, encoding: PointEncoding) -> Result<u64, ApiError> {
+    use crate::integers::{MaxFieldSquaredUint};
+
+    let (
+        modulus, 
+        order,
+        order_len, 
+        num_pairs, 
+        (ate_loop_bits, ate_loop_hamming), 
+        (num_g1_subgroup_checks, num_g2_subgroup_checks),
+        with_final_exp
+    ) = parse_bw6_pairing_parameters(input, tail, limits, encoding)?;
+
+    let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+    let order_limbs = num_units_for_group_order_length(order_len)?;
+
+    let (hard_part_bits, hard_part_hamming) = {
+        let modulus = MaxFieldSquaredUint::from(modulus.as_ref());
+        let order = MaxFieldSquaredUint::from(order.as_ref());
+        let mut power = modulus.adaptive_multiplication(modulus);
+        power -= modulus;
+        power += MaxFieldSquaredUint::from(1u64);
+        let (hard_part, _) = power.div_mod(order);
+
+        (hard_part.bits() as u64, calculate_hamming_weight(hard_part.as_ref()) as u64)
+    };
+
+    let mut estimate = calculate_mnt_pairing_cost(
+        modulus_limbs,
+        order_limbs,
+        num_pairs,
+        with_final_exp,
+        (ate_loop_bits, ate_loop_hamming), 
+        (hard_part_bits, hard_part_hamming),
+        (1, 1),
+        params,
+        max_power
+    )?;
+
+    let subgroup_check_cost_per_point = super::meter_arith::meter_multiplication(modulus_limbs, order_limbs, &*super::meter_arith::G1_MULTIPLICATION_PARAMS_INSTANCE, false)?;
+    let num_subgroup_checks = (num_g1_subgroup_checks + num_g2_subgroup_checks) as u64;
+    let subgroup_checks_cost = subgroup_check_cost_per_point.checked_mul(num_subgroup_checks).ok_or(ApiError::Overflow)?;
+
+    estimate = estimate.checked_add(subgroup_checks_cost).ok_or(ApiError::Overflow)?;
+
+    Ok(estimate)
+}
+
+// There is no fitted model for the generic Cocks-Pinch pairing, so it's priced as BW6 by the
+// MNT6 model with the ate loop count and the supplied hard part, times a factor that depends on
+// the embedding degree. Miller loop is done in affine coordinates with an inversion in Fp^k per
+// step and every multiplication in Fp^k takes k^2 multiplications in the base field, so the factor
+// grows as k^3 and is equal to 6 for k = 6. G2 subgroup checks are priced as multiplications
+// in G2 over Fp3 times (k/3)^2
+fn cocks_pinch_cost_factors(embedding_degree: usize) -> (u64, u64) {
+    let k = embedding_degree as u64;
+    let cost_factor = core::cmp::max(1, (k * k * k).div_ceil(36));
+    let g2_cost_factor = core::cmp::max(1, (k * k).div_ceil(9));
+
+    (cost_factor, g2_cost_factor)
+}
+
+pub(crate) fn meter_cocks_pinch_pairing(input: &[u8], params: &MntPairingParams, max_power: usize, tail: PairingInputTail, limits: &Limits, encoding: PointEncoding) -> Result<u64, ApiError> {
+    let (
+        modulus, 
+        order_len, 
+        embedding_degree,
+        num_pairs, 
+        (ate_loop_bits, ate_loop_hamming), 
+        (hard_part_bits, hard_part_hamming),
+        (num_g1_subgroup_checks, num_g2_subgroup_checks),
+        with_final_exp
+    ) = parse_cocks_pinch_pairing_parameters(input, tail, limits, encoding)?;
+
+    let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+    let order_limbs = num_units_for_group_order_length(order_len)?;
+
+    let (cost_factor, g2_cost_factor) = cocks_pinch_cost_factors(embedding_degree);
+
+    let mut estimate = calculate_mnt_pairing_cost(
+        modulus_limbs,
+        order_limbs,
+        num_pairs,
+        with_final_exp,
+        (ate_loop_bits, ate_loop_hamming), 
+        (hard_part_bits, hard_part_hamming),
+        (1, 1),
+        params,
+        max_power
+    )?;
+
+    estimate = estimate.checked_mul(cost_factor).ok_or(ApiError::Overflow)?;
+
+    let g1_subgroup_check_cost_per_point = super::meter_arith::meter_multiplication(modulus_limbs, order_limbs, &*super::meter_arith::G1_MULTIPLICATION_PARAMS_INSTANCE, false)?;
+    let g1_subgroup_checks_cost = g1_subgroup_check_cost_per_point.checked_mul(num_g1_subgroup_checks as u64).ok_or(ApiError::Overflow)?;
+
+    estimate = estimate.checked_add(g1_subgroup_checks_cost).ok_or(ApiError::Overflow)?;
+
+    let g2_subgroup_check_cost_per_point = super::meter_arith::meter_multiplication(modulus_limbs, order_limbs, &*super::meter_arith::G2_EXT_3_MULTIPLICATION_PARAMS_INSTANCE, false)?;
+    let g2_subgroup_check_cost_per_point = g2_subgroup_check_cost_per_point.checked_mul(g2_cost_factor).ok_or(ApiError::Overflow)?;
+    let g2_subgroup_checks_cost = g2_subgroup_check_cost_per_point.checked_mul(num_g2_subgroup_checks as u64).ok_or(ApiError::Overflow)?;
+
+    estimate = estimate.checked_add(g2_subgroup_checks_cost).ok_or(ApiError::Overflow)?;
+
+    Ok(estimate)
+}
+
 fn calculate_mnt_pairing_cost(
     modulus_limbs: usize,
     _order_limbs: usize,
     num_pairs: usize,
+    with_final_exp: bool,
     (ate_loop_bits, ate_loop_hamming): (u64, u64), 
     (exp_w0_bits, exp_w0_hamming): (u64, u64),
     (exp_w1_bits, exp_w1_hamming): (u64, u64),
@@ -192,13 +331,15 @@ fn calculate_mnt_pairing_cost(
 
     let mut result = one_off;
     result = result.checked_add(miller_cost).ok_or(ApiError::Overflow)?;
-    result = result.checked_add(final_exp_cost).ok_or(ApiError::Overflow)?;
+    if with_final_exp {
+        result = result.checked_add(final_exp_cost).ok_or(ApiError::Overflow)?;
+    }
     result = result.checked_div(params.multiplier).ok_or(ApiError::Overflow)?;
 
     Ok(result)
 }
 
-pub(crate) fn meter_bls12_pairing(input: &[u8], params: &Bls12PairingParams, max_power: usize) -> Result<u64, ApiError> {
+pub(crate) fn meter_bls12_pairing(input: &[u8], params: &Bls12PairingParams, max_power: usize, tail: PairingInputTail, limits: &Limits, encoding: PointEncoding) -> Result<u64, ApiError> {
     let (
         modulus, 
         order_len, 
@@ -206,8 +347,8 @@ pub(crate) fn meter_bls12_pairing(input: &[u8], params: &Bls12PairingParams, max
         x,
         _,
         (num_g1_subgroup_checks, num_g2_subgroup_checks),
-        _
-    ) = parse_bls12_bn_pairing_parameters(&input, MAX_BLS12_X_BIT_LENGTH)?;
+        with_final_exp
+    ) = parse_bls12_bn_pairing_parameters(input, limits.max_bls12_x_bit_length, 2, 12, tail, encoding)?;
 
     let modulus_limbs = num_limbs_for_modulus(&modulus)?;
     // let order_limbs = num_units_for_group_order(&order)?;
@@ -216,7 +357,7 @@ pub(crate) fn meter_bls12_pairing(input: &[u8], params: &Bls12PairingParams, max
     let x_bits = x.bits();
     let x_hamming = calculate_hamming_weight(&x.as_ref());
 
-    if x_hamming > MAX_BLS12_X_HAMMING {
+    if x_hamming > limits.max_bls12_x_hamming {
         return Err(ApiError::InputError(format!("Hamming weight for scalar is too large, file {}, line {}", file!(), line!())));
     }
 
@@ -224,6 +365,7 @@ pub(crate) fn meter_bls12_pairing(input: &[u8], params: &Bls12PairingParams, max
         modulus_limbs,
         order_limbs,
         num_pairs,
+        with_final_exp,
         (x_bits as u64, x_hamming as u64),
         params,
         max_power
@@ -243,7 +385,117 @@ pub(crate) fn meter_bls12_pairing(input: &[u8], params: &Bls12PairingParams, max
 }
 
 
-pub(crate) fn meter_bn_pairing(input: &[u8], params: &BnPairingParams, max_power: usize) -> Result<u64, ApiError> {
+pub(crate) fn meter_bls24_pairing(input: &[u8], params: &Bls12PairingParams, max_power: usize, tail: PairingInputTail, limits: &Limits, encoding: PointEncoding) -> Result<u64, ApiError> {
+    meter_bls12_like_pairing(
+        input,
+        params,
+        max_power,
+        tail,
+        encoding,
+        (4, 24),
+        (limits.max_bls24_x_bit_length, limits.max_bls24_x_hamming),
+        (BLS24_TO_BLS12_COST_FACTOR, BLS24_G2_TO_EXT_2_COST_FACTOR)
+    )
+}
+
+pub(crate) fn meter_bls48_pairing(input: &[u8], params: &Bls12PairingParams, max_power: usize, tail: PairingInputTail, limits: &Limits, encoding: PointEncoding) -> Result<u64, ApiError> {
+    meter_bls12_like_pairing(
+        input,
+        params,
+        max_power,
+        tail,
+        encoding,
+        (8, 48),
+        (limits.max_bls48_x_bit_length, limits.max_bls48_x_hamming),
+        (BLS48_TO_BLS12_COST_FACTOR, BLS48_G2_TO_EXT_2_COST_FACTOR)
+    )
+}
+
+pub(crate) fn meter_kss16_pairing(input: &[u8], params: &Bls12PairingParams, max_power: usize, tail: PairingInputTail, limits: &Limits, encoding: PointEncoding) -> Result<u64, ApiError> {
+    meter_bls12_like_pairing(
+        input,
+        params,
+        max_power,
+        tail,
+        encoding,
+        (4, 16),
+        (limits.max_kss16_x_bit_length, limits.max_kss16_x_hamming),
+        (KSS16_TO_BLS12_COST_FACTOR, KSS16_G2_TO_EXT_2_COST_FACTOR)
+    )
+}
+
+pub(crate) fn meter_kss18_pairing(input: &[u8], params: &Bls12PairingParams, max_power: usize, tail: PairingInputTail, limits: &Limits, encoding: PointEncoding) -> Result<u64, ApiError> {
+    meter_bls12_like_pairing(
+        input,
+        params,
+        max_power,
+        tail,
+        encoding,
+        (3, 18),
+        (limits.max_kss18_x_bit_length, limits.max_kss18_x_hamming),
+        (KSS18_TO_BLS12_COST_FACTOR, KSS18_G2_TO_EXT_2_COST_FACTOR)
+    )
+}
+
+// Prices a pairing for a family with the same structure as BLS12, but twist defined
+// over the extension of degree `twist_degree` and the given embedding degree,
+// as the BLS12 one times a cost factor
+fn meter_bls12_like_pairing(
+    input: &[u8], 
+    params: &Bls12PairingParams, 
+    max_power: usize, 
+    tail: PairingInputTail,
+    encoding: PointEncoding,
+    (twist_degree, embedding_degree): (usize, usize),
+    (max_x_bit_length, max_x_hamming): (usize, u32),
+    (cost_factor, g2_cost_factor): (u64, u64)
+) -> Result<u64, ApiError> {
+    let (
+        modulus, 
+        order_len, 
+        num_pairs, 
+        x,
+        _,
+        (num_g1_subgroup_checks, num_g2_subgroup_checks),
+        with_final_exp
+    ) = parse_bls12_bn_pairing_parameters(input, max_x_bit_length, twist_degree, embedding_degree, tail, encoding)?;
+
+    let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+    let order_limbs = num_units_for_group_order_length(order_len)?;
+
+    let x_bits = x.bits();
+    let x_hamming = calculate_hamming_weight(x.as_ref());
+
+    if x_hamming > max_x_hamming {
+        return Err(ApiError::InputError(format!("Hamming weight for scalar is too large, file {}, line {}", file!(), line!())));
+    }
+
+    let mut estimate = calculate_bls12_pairing_cost(
+        modulus_limbs,
+        order_limbs,
+        num_pairs,
+        with_final_exp,
+        (x_bits as u64, x_hamming as u64),
+        params,
+        max_power
+    )?;
+    estimate = estimate.checked_mul(cost_factor).ok_or(ApiError::Overflow)?;
+
+    let g1_subgroup_check_cost_per_point = super::meter_arith::meter_multiplication(modulus_limbs, order_limbs, &*super::meter_arith::G1_MULTIPLICATION_PARAMS_INSTANCE, false)?;
+    let g1_subgroup_check_cost = g1_subgroup_check_cost_per_point.checked_mul(num_g1_subgroup_checks as u64).ok_or(ApiError::Overflow)?;
+
+    estimate = estimate.checked_add(g1_subgroup_check_cost).ok_or(ApiError::Overflow)?;
+
+    let g2_subgroup_check_cost_per_point = super::meter_arith::meter_multiplication(modulus_limbs, order_limbs, &*super::meter_arith::G2_EXT_2_MULTIPLICATION_PARAMS_INSTANCE, false)?;
+    let g2_subgroup_check_cost_per_point = g2_subgroup_check_cost_per_point.checked_mul(g2_cost_factor).ok_or(ApiError::Overflow)?;
+    let g2_subgroup_check_cost = g2_subgroup_check_cost_per_point.checked_mul(num_g2_subgroup_checks as u64).ok_or(ApiError::Overflow)?;
+
+    estimate = estimate.checked_add(g2_subgroup_check_cost).ok_or(ApiError::Overflow)?;
+
+    Ok(estimate)
+}
+
+pub(crate) fn meter_bn_pairing(input: &[u8], params: &BnPairingParams, max_power: usize, tail: PairingInputTail, limits: &Limits, encoding: PointEncoding) -> Result<u64, ApiError> {
     let (
         modulus, 
         order_len, 
@@ -251,8 +503,8 @@ pub(crate) fn meter_bn_pairing(input: &[u8], params: &BnPairingParams, max_power
         u,
         u_is_negative,
         (num_g1_subgroup_checks, num_g2_subgroup_checks),
-        _
-    ) = parse_bls12_bn_pairing_parameters(&input, MAX_BN_U_BIT_LENGTH)?;
+        with_final_exp
+    ) = parse_bls12_bn_pairing_parameters(input, limits.max_bn_u_bit_length, 2, 12, tail, encoding)?;
     use crate::integers::MaxLoopParametersUint;
 
     let modulus_limbs = num_limbs_for_modulus(&modulus)?;
@@ -280,7 +532,7 @@ pub(crate) fn meter_bn_pairing(input: &[u8], params: &BnPairingParams, max_power
 
     let six_u_plus_two_hamming = calculate_hamming_weight(six_u_plus_two.as_ref());
 
-    if six_u_plus_two_hamming > MAX_BN_SIX_U_PLUS_TWO_HAMMING {
+    if six_u_plus_two_hamming > limits.max_bn_six_u_plus_two_hamming {
         return Err(ApiError::InputError(format!("Hamming weight for scalar is too large, file {}, line {}", file!(), line!())));
     }
 
@@ -288,6 +540,7 @@ pub(crate) fn meter_bn_pairing(input: &[u8], params: &BnPairingParams, max_power
         modulus_limbs,
         order_limbs,
         num_pairs,
+        with_final_exp,
         (six_u_plus_two_bits as u64, six_u_plus_two_hamming as u64),
         (u_bits as u64, u_hamming as u64),
         params,
@@ -311,6 +564,7 @@ fn calculate_bls12_pairing_cost(
     modulus_limbs: usize,
     _order_limbs: usize,
     num_pairs: usize,
+    with_final_exp: bool,
     (x_bits, x_hamming): (u64, u64),
     params: &Bls12PairingParams, 
     max_power: usize
@@ -355,7 +609,9 @@ fn calculate_bls12_pairing_cost(
 
     let mut result = one_off;
     result = result.checked_add(miller_cost).ok_or(ApiError::Overflow)?;
-    result = result.checked_add(final_exp_cost).ok_or(ApiError::Overflow)?;
+    if with_final_exp {
+        result = result.checked_add(final_exp_cost).ok_or(ApiError::Overflow)?;
+    }
     result = result.checked_div(params.multiplier).ok_or(ApiError::Overflow)?;
 
     Ok(result)
@@ -365,6 +621,7 @@ fn calculate_bn_pairing_cost(
     modulus_limbs: usize,
     _order_limbs: usize,
     num_pairs: usize,
+    with_final_exp: bool,
     (six_u_plus_two_bits, six_u_plus_two_hamming): (u64, u64),
     (u_bits, u_hamming): (u64, u64),
     params: &BnPairingParams, 
@@ -407,7 +664,9 @@ fn calculate_bn_pairing_cost(
 
     let mut result = one_off;
     result = result.checked_add(miller_cost).ok_or(ApiError::Overflow)?;
-    result = result.checked_add(final_exp_cost).ok_or(ApiError::Overflow)?;
+    if with_final_exp {
+        result = result.checked_add(final_exp_cost).ok_or(ApiError::Overflow)?;
+    }
     result = result.checked_div(params.multiplier).ok_or(ApiError::Overflow)?;
 
     Ok(result)
@@ -485,6 +744,7 @@ mod test {
             10, 
             5, 
             1, 
+            true, 
             (613, 292), 
             (613, 312), 
             (315, 157), 
@@ -522,6 +782,7 @@ mod test {
             12, 
             6, 
             1, 
+            true, 
             (ate_bits as u64, ate_hamming as u64), 
             (w0_bits as u64, w0_hamming as u64), 
             (w1_bits as u64, w1_hamming as u64), 
@@ -540,6 +801,7 @@ mod test {
             4, 
             4, 
             4, 
+            true, 
             (65, six_u_plus_two_hamming as u64), 
             (63, u_hamming as u64), 
             &*super::BN_PARAMS_INSTANCE, 
@@ -557,6 +819,7 @@ mod test {
             6, 
             4, 
             4, 
+            true, 
             (x_bits as u64, x_hamming as u64), 
             &*super::BLS12_PARAMS_INSTANCE, 
             6).unwrap();
@@ -573,6 +836,7 @@ mod test {
             6, 
             4, 
             1, 
+            true, 
             (x_bits as u64, x_hamming as u64), 
             &*super::BLS12_PARAMS_INSTANCE, 
             6).unwrap();
@@ -590,6 +854,7 @@ mod test {
             6, 
             4, 
             1, 
+            true, 
             (x_bits as u64, x_hamming as u64), 
             &*super::BLS12_PARAMS_INSTANCE, 
             6).unwrap();
@@ -2,6 +2,8 @@ mod parsers;
 mod meter_arith;
 mod meter_pairing;
 
+pub mod model;
+
 extern crate serde;
 extern crate serde_json;
 extern crate once_cell;
@@ -11,49 +13,70 @@ use crate::public_interface::decode_utils::*;
 use crate::public_interface::constants::*;
 use self::parsers::*;
 use crate::public_interface::OperationType;
+use crate::public_interface::{validate_input_length, validate_pairing_input_length, is_well_known_curve};
+use crate::public_interface::{point_len, num_points, check_operation, PointEncoding};
+use crate::public_interface::Limits;
 
 pub struct GasMeter;
 
 // This is pure rust API
 pub fn meter_operation(operation: OperationType, input: &[u8]) -> Result<u64, ApiError> {
-    match operation {
+    let limits = Limits::DEFAULT;
+    // the legacy API only takes uncompressed points
+    let encoding = PointEncoding::Uncompressed;
+    let price = match operation {
         OperationType::G1ADD => {
-            meter_addition_g1(&input)
+            meter_addition_g1(input, encoding)
         },
         OperationType::G1MUL => {
-            meter_multiplication_g1(&input)
+            meter_multiplication_g1(input, encoding)
         },
         OperationType::G1MULTIEXP => {
-            meter_multiexp_g1(&input)
+            meter_multiexp_g1(input, encoding)
         },
         OperationType::G2ADD => {
-            meter_addition_g2(&input)
+            meter_addition_g2(input, encoding)
         },
         OperationType::G2MUL => {
-            meter_multiplication_g2(&input)
+            meter_multiplication_g2(input, encoding)
         },
         OperationType::G2MULTIEXP => {
-            meter_multiexp_g2(&input)
+            meter_multiexp_g2(input, encoding)
         },
         OperationType::MNT4PAIR => {
-            meter_mnt4(&input)
+            validate_pairing_input_length(OPERATION_PAIRING, MNT4, input, encoding)?;
+            meter_mnt4(input, PairingInputTail::Pairs, &limits, encoding)
         },
         OperationType::MNT6PAIR => {
-            meter_mnt6(&input)
+            validate_pairing_input_length(OPERATION_PAIRING, MNT6, input, encoding)?;
+            meter_mnt6(input, PairingInputTail::Pairs, &limits, encoding)
         },
         OperationType::BLS12PAIR => {
-            meter_bls12(&input)
+            validate_pairing_input_length(OPERATION_PAIRING, BLS12, input, encoding)?;
+            meter_bls12(input, PairingInputTail::Pairs, &limits, encoding)
         },
         OperationType::BNPAIR => {
-            meter_bn(&input)
+            validate_pairing_input_length(OPERATION_PAIRING, BN, input, encoding)?;
+            meter_bn(input, PairingInputTail::Pairs, &limits, encoding)
         } 
-    }
+    }?;
+
+    let primality_test = match operation {
+        OperationType::G2ADD | OperationType::G2MUL | OperationType::G2MULTIEXP => {
+            meter_primality_test_g2(input, &limits)
+        },
+        _ => {
+            meter_primality_test_g1(input, &limits)
+        }
+    }?;
+
+    price.checked_add(primality_test).ok_or(ApiError::Overflow)
 }
 
-fn meter_addition_g1(input: &[u8]) -> Result<u64, ApiError> {
+fn meter_addition_g1(input: &[u8], encoding: PointEncoding) -> Result<u64, ApiError> {
 
     let (modulus, modulus_len, _, rest) = parse_g1_curve_parameters(&input)?;
-    if rest.len() != modulus_len * 4 {
+    if rest.len() != checked_len_mul(point_len(modulus_len, encoding)?, 2)? {
         return Err(ApiError::InputError("Input is either too short or contains garbage for g1 addition metering".to_owned()));
     }
     let modulus_limbs = num_limbs_for_modulus(&modulus)?;
@@ -63,10 +86,10 @@ fn meter_addition_g1(input: &[u8]) -> Result<u64, ApiError> {
     meter_arith::meter_addition(modulus_limbs, params)
 }
 
-fn meter_addition_g2(input: &[u8]) -> Result<u64, ApiError> {
+fn meter_addition_g2(input: &[u8], encoding: PointEncoding) -> Result<u64, ApiError> {
 
     let (modulus, modulus_len, _, ext_degree, rest) = parse_g2_curve_parameters(&input)?;
-    if rest.len() != modulus_len * 4 * (ext_degree as usize) {
+    if rest.len() != checked_len_mul(point_len(checked_len_mul(modulus_len, ext_degree as usize)?, encoding)?, 2)? {
         return Err(ApiError::InputError("Input is either too short or contains garbage for g2 addition metering".to_owned()));
     }
     let modulus_limbs = num_limbs_for_modulus(&modulus)?;
@@ -83,9 +106,9 @@ fn meter_addition_g2(input: &[u8]) -> Result<u64, ApiError> {
 }
 
 
-fn meter_multiplication_g1(input: &[u8]) -> Result<u64, ApiError> {
+fn meter_multiplication_g1(input: &[u8], encoding: PointEncoding) -> Result<u64, ApiError> {
     let (modulus, modulus_len, order_len, rest) = parse_g1_curve_parameters(&input)?;
-    if rest.len() != modulus_len * 2 + order_len {
+    if rest.len() != checked_len_add(point_len(modulus_len, encoding)?, order_len)? {
         return Err(ApiError::InputError("Input is either too short or contains garbage for g1 multiplication metering".to_owned()));
     }
     let modulus_limbs = num_limbs_for_modulus(&modulus)?;
@@ -97,9 +120,9 @@ fn meter_multiplication_g1(input: &[u8]) -> Result<u64, ApiError> {
     meter_arith::meter_multiplication(modulus_limbs, order_limbs, params, true)
 }
 
-fn meter_multiplication_g2(input: &[u8]) -> Result<u64, ApiError> {
+fn meter_multiplication_g2(input: &[u8], encoding: PointEncoding) -> Result<u64, ApiError> {
     let (modulus, modulus_len, order_len, ext_degree, rest) = parse_g2_curve_parameters(&input)?;
-    if rest.len() != modulus_len * 2 * (ext_degree as usize) + order_len {
+    if rest.len() != checked_len_add(point_len(checked_len_mul(modulus_len, ext_degree as usize)?, encoding)?, order_len)? {
         return Err(ApiError::InputError("Input is either too short or contains garbage for g2 multiplication metering".to_owned()));
     }
 
@@ -118,7 +141,264 @@ fn meter_multiplication_g2(input: &[u8]) -> Result<u64, ApiError> {
     meter_arith::meter_multiplication(modulus_limbs, order_limbs, params, true)
 }
 
-fn meter_multiexp_g1(input: &[u8]) -> Result<u64, ApiError> {
+/// Subgroup check is a multiplication by the group order, so it's priced
+/// as a multiplication by a scalar of the order length
+fn meter_subgroup_check_g1(input: &[u8], encoding: PointEncoding) -> Result<u64, ApiError> {
+    let (modulus, modulus_len, order_len, rest) = parse_g1_curve_parameters(input)?;
+    if rest.len() != point_len(modulus_len, encoding)? {
+        return Err(ApiError::InputError("Input is either too short or contains garbage for g1 subgroup check metering".to_owned()));
+    }
+    let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+    let order_limbs = num_units_for_group_order_length(order_len)?;
+
+    let params = &*meter_arith::G1_MULTIPLICATION_PARAMS_INSTANCE;
+
+    meter_arith::meter_multiplication(modulus_limbs, order_limbs, params, true)
+}
+
+fn meter_subgroup_check_g2(input: &[u8], encoding: PointEncoding) -> Result<u64, ApiError> {
+    let (modulus, modulus_len, order_len, ext_degree, rest) = parse_g2_curve_parameters(input)?;
+    if rest.len() != point_len(checked_len_mul(modulus_len, ext_degree as usize)?, encoding)? {
+        return Err(ApiError::InputError("Input is either too short or contains garbage for g2 subgroup check metering".to_owned()));
+    }
+
+    let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+    let order_limbs = num_units_for_group_order_length(order_len)?;
+
+    let params = if ext_degree == EXTENSION_DEGREE_2 {
+        &*meter_arith::G2_EXT_2_MULTIPLICATION_PARAMS_INSTANCE
+    } else if ext_degree == EXTENSION_DEGREE_3 {
+        &*meter_arith::G2_EXT_3_MULTIPLICATION_PARAMS_INSTANCE
+    } else {
+        unreachable!();
+    };
+
+    meter_arith::meter_multiplication(modulus_limbs, order_limbs, params, true)
+}
+
+/// Cofactor clearing is priced as a multiplication by a scalar of the cofactor length
+fn meter_clear_cofactor_g1(input: &[u8], encoding: PointEncoding) -> Result<u64, ApiError> {
+    let (modulus, modulus_len, _, rest) = parse_g1_curve_parameters(input)?;
+    let (_, rest) = split(rest, point_len(modulus_len, encoding)?, "Input is not long enough to get point")?;
+    let ((cofactor_len, _), rest) = decode_group_order_with_length(rest)?;
+    if !rest.is_empty() {
+        return Err(ApiError::InputError("Input contains garbage for g1 cofactor clearing metering".to_owned()));
+    }
+    let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+    let cofactor_limbs = num_units_for_group_order_length(cofactor_len)?;
+
+    let params = &*meter_arith::G1_MULTIPLICATION_PARAMS_INSTANCE;
+
+    meter_arith::meter_multiplication(modulus_limbs, cofactor_limbs, params, true)
+}
+
+fn meter_clear_cofactor_g2(input: &[u8], encoding: PointEncoding) -> Result<u64, ApiError> {
+    let (modulus, modulus_len, _, ext_degree, rest) = parse_g2_curve_parameters(input)?;
+    let (_, rest) = split(rest, point_len(checked_len_mul(modulus_len, ext_degree as usize)?, encoding)?, "Input is not long enough to get point")?;
+    let ((cofactor_len, _), rest) = decode_group_order_with_length(rest)?;
+    if !rest.is_empty() {
+        return Err(ApiError::InputError("Input contains garbage for g2 cofactor clearing metering".to_owned()));
+    }
+    let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+    let cofactor_limbs = num_units_for_group_order_length(cofactor_len)?;
+
+    let params = if ext_degree == EXTENSION_DEGREE_2 {
+        &*meter_arith::G2_EXT_2_MULTIPLICATION_PARAMS_INSTANCE
+    } else if ext_degree == EXTENSION_DEGREE_3 {
+        &*meter_arith::G2_EXT_3_MULTIPLICATION_PARAMS_INSTANCE
+    } else {
+        unreachable!();
+    };
+
+    meter_arith::meter_multiplication(modulus_limbs, cofactor_limbs, params, true)
+}
+
+/// Point validation costs a few field multiplications and is priced as an addition
+fn meter_validate_point_g1(input: &[u8], encoding: PointEncoding) -> Result<u64, ApiError> {
+    let (modulus, modulus_len, _, rest) = parse_g1_curve_parameters(input)?;
+    if rest.len() != point_len(modulus_len, encoding)? {
+        return Err(ApiError::InputError("Input is either too short or contains garbage for g1 point validation metering".to_owned()));
+    }
+    let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+
+    let params = &*meter_arith::G1_ADDITION_PARAMS_INSTANCE;
+
+    meter_arith::meter_addition(modulus_limbs, params)
+}
+
+fn meter_validate_point_g2(input: &[u8], encoding: PointEncoding) -> Result<u64, ApiError> {
+    let (modulus, modulus_len, _, ext_degree, rest) = parse_g2_curve_parameters(input)?;
+    if rest.len() != point_len(checked_len_mul(modulus_len, ext_degree as usize)?, encoding)? {
+        return Err(ApiError::InputError("Input is either too short or contains garbage for g2 point validation metering".to_owned()));
+    }
+    let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+
+    let params = if ext_degree == EXTENSION_DEGREE_2 {
+        &*meter_arith::G2_EXT_2_ADDITION_PARAMS_INSTANCE
+    } else if ext_degree == EXTENSION_DEGREE_3 {
+        &*meter_arith::G2_EXT_3_ADDITION_PARAMS_INSTANCE
+    } else {
+        unreachable!();
+    };
+
+    meter_arith::meter_addition(modulus_limbs, params)
+}
+
+fn meter_sum_g1(input: &[u8], encoding: PointEncoding) -> Result<u64, ApiError> {
+    let (modulus, modulus_len, _, rest) = parse_g1_curve_parameters(input)?;
+    let (num_points_encoding, rest) = split(rest, BYTES_FOR_LENGTH_ENCODING, "Input is not long enough to get number of points")?;
+    let num_points = num_points_encoding[0] as usize;
+
+    if num_points == 0 {
+        return Err(ApiError::InputError("Invalid number of points".to_owned()));
+    }
+
+    if rest.len() != checked_len_mul(num_points, point_len(modulus_len, encoding)?)? {
+        return Err(ApiError::InputError("Input is either too short or contains garbage for g1 sum metering".to_owned()));
+    }
+    let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+
+    let params = &*meter_arith::G1_ADDITION_PARAMS_INSTANCE;
+    let addition = meter_arith::meter_addition(modulus_limbs, params)?;
+
+    meter_sum(modulus_limbs, modulus_len, num_points, addition)
+}
+
+fn meter_sum_g2(input: &[u8], encoding: PointEncoding) -> Result<u64, ApiError> {
+    let (modulus, modulus_len, _, ext_degree, rest) = parse_g2_curve_parameters(input)?;
+    let (num_points_encoding, rest) = split(rest, BYTES_FOR_LENGTH_ENCODING, "Input is not long enough to get number of points")?;
+    let num_points = num_points_encoding[0] as usize;
+
+    if num_points == 0 {
+        return Err(ApiError::InputError("Invalid number of points".to_owned()));
+    }
+
+    let point_len = point_len(checked_len_mul(modulus_len, ext_degree as usize)?, encoding)?;
+    if rest.len() != checked_len_mul(num_points, point_len)? {
+        return Err(ApiError::InputError("Input is either too short or contains garbage for g2 sum metering".to_owned()));
+    }
+    let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+
+    let params = if ext_degree == EXTENSION_DEGREE_2 {
+        &*meter_arith::G2_EXT_2_ADDITION_PARAMS_INSTANCE
+    } else if ext_degree == EXTENSION_DEGREE_3 {
+        &*meter_arith::G2_EXT_3_ADDITION_PARAMS_INSTANCE
+    } else {
+        unreachable!();
+    };
+    let addition = meter_arith::meter_addition(modulus_limbs, params)?;
+
+    meter_sum(modulus_limbs, modulus_len, num_points, addition)
+}
+
+/// Sum of points is priced as an addition per point and an inversion per level of
+/// the reduction tree after the first one, that adds the affine points from the input.
+/// Inversion in the extension is a single inversion in the base field, priced as in
+/// `meter_field_inverse`
+fn meter_sum(modulus_limbs: usize, modulus_len: usize, num_points: usize, addition: u64) -> Result<u64, ApiError> {
+    let exponent_limbs = num_units_for_group_order_length(modulus_len)?;
+    let params = &*meter_arith::G1_MULTIPLICATION_PARAMS_INSTANCE;
+    let inversion = meter_arith::meter_multiplication(modulus_limbs, exponent_limbs, params, true)?;
+
+    let levels = (num_points as u64).next_power_of_two().trailing_zeros() as u64;
+    let additions = addition.checked_mul(num_points as u64).ok_or(ApiError::Overflow)?;
+    let inversions = inversion.checked_mul(levels.saturating_sub(1)).ok_or(ApiError::Overflow)?;
+
+    additions.checked_add(inversions).ok_or(ApiError::Overflow)
+}
+
+/// Every ladder step costs about the same as a doubling and a windowed addition,
+/// so the ladder is priced as a G1 multiplication by a scalar of the same length
+fn meter_montgomery_ladder(input: &[u8]) -> Result<u64, ApiError> {
+    let (modulus, modulus_len, order_len, rest) = parse_g1_curve_parameters(input)?;
+    if rest.len() != checked_len_add(modulus_len, order_len)? {
+        return Err(ApiError::InputError("Input is either too short or contains garbage for Montgomery ladder metering".to_owned()));
+    }
+    let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+    let order_limbs = num_units_for_group_order_length(order_len)?;
+
+    let params = &*meter_arith::G1_MULTIPLICATION_PARAMS_INSTANCE;
+
+    meter_arith::meter_multiplication(modulus_limbs, order_limbs, params, true)
+}
+
+// binary field multiplication is done in software with the comb method and costs
+// about as much as several Montgomery multiplications with the same number of limbs
+const BINARY_FIELD_PRICE_MULTIPLIER: u64 = 8;
+
+/// Curves over binary fields use the same input layout as G1 and projective formulas
+/// with about the same number of multiplications, so G1 prices are scaled
+fn meter_binary_curve_operation(input: &[u8], g1_meter: fn(&[u8], PointEncoding) -> Result<u64, ApiError>, encoding: PointEncoding) -> Result<u64, ApiError> {
+    let price = g1_meter(input, encoding)?;
+
+    price.checked_mul(BINARY_FIELD_PRICE_MULTIPLIER).ok_or(ApiError::Overflow)
+}
+
+/// Field addition and multiplication are priced as a G1 addition that takes about a dozen
+/// of multiplications, so it's an upper bound
+fn meter_field_operation(input: &[u8], num_elements: usize) -> Result<u64, ApiError> {
+    let ((modulus, modulus_len), rest) = get_base_field_params(input)?;
+    if rest.len() != checked_len_mul(modulus_len, num_elements)? {
+        return Err(ApiError::InputError("Input is either too short or contains garbage for field operation metering".to_owned()));
+    }
+    let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+
+    let params = &*meter_arith::G1_ADDITION_PARAMS_INSTANCE;
+
+    meter_arith::meter_addition(modulus_limbs, params)
+}
+
+/// Binary inversion makes at most twice the modulus bit length of cheap steps,
+/// so it's priced as a G1 multiplication by a scalar of the modulus length
+fn meter_field_inverse(input: &[u8]) -> Result<u64, ApiError> {
+    let ((modulus, modulus_len), rest) = get_base_field_params(input)?;
+    if rest.len() != modulus_len {
+        return Err(ApiError::InputError("Input is either too short or contains garbage for field inversion metering".to_owned()));
+    }
+    let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+    let exponent_limbs = num_units_for_group_order_length(modulus_len)?;
+
+    let params = &*meter_arith::G1_MULTIPLICATION_PARAMS_INSTANCE;
+
+    meter_arith::meter_multiplication(modulus_limbs, exponent_limbs, params, true)
+}
+
+/// Square-and-multiply is priced as a G1 multiplication by a scalar of the exponent length
+fn meter_field_exp(input: &[u8]) -> Result<u64, ApiError> {
+    let ((modulus, modulus_len), rest) = get_base_field_params(input)?;
+    let (_, rest) = split(rest, modulus_len, "Input is not long enough to get field element")?;
+    let ((exponent_len, _), rest) = decode_group_order_with_length(rest)?;
+    if !rest.is_empty() {
+        return Err(ApiError::InputError("Input contains garbage for field exponentiation metering".to_owned()));
+    }
+    let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+    let exponent_limbs = num_units_for_group_order_length(exponent_len)?;
+
+    let params = &*meter_arith::G1_MULTIPLICATION_PARAMS_INSTANCE;
+
+    meter_arith::meter_multiplication(modulus_limbs, exponent_limbs, params, true)
+}
+
+/// Square root in Fp2 takes a few exponentiations by a power of the modulus length in Fp
+/// or Fp2, so it's priced as a G2 multiplication by a scalar of the modulus length
+fn meter_field_sqrt_ext2(input: &[u8]) -> Result<u64, ApiError> {
+    let ((modulus, modulus_len), rest) = get_base_field_params(input)?;
+    let (extension_degree, rest) = split(rest, EXTENSION_DEGREE_ENCODING_LENGTH, "Input is not long enough to get extension degree")?;
+    if extension_degree[0] != EXTENSION_DEGREE_2 {
+        return Err(ApiError::UnknownParameter("Extension degree expected to be 2".to_owned()));
+    }
+    if rest.len() != checked_len_mul(modulus_len, 3)? {
+        return Err(ApiError::InputError("Input is either too short or contains garbage for Fp2 square root metering".to_owned()));
+    }
+    let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+    let exponent_limbs = num_units_for_group_order_length(modulus_len)?;
+
+    let params = &*meter_arith::G2_EXT_2_MULTIPLICATION_PARAMS_INSTANCE;
+
+    meter_arith::meter_multiplication(modulus_limbs, exponent_limbs, params, true)
+}
+
+fn meter_multiexp_g1(input: &[u8], encoding: PointEncoding) -> Result<u64, ApiError> {
     let (modulus, modulus_len, order_len, rest) = parse_g1_curve_parameters(&input)?;
     let modulus_limbs = num_limbs_for_modulus(&modulus)?;
     // let order_limbs = num_units_for_group_order(&order)?;
@@ -131,7 +411,8 @@ fn meter_multiexp_g1(input: &[u8]) -> Result<u64, ApiError> {
         return Err(ApiError::InputError("Invalid number of pairs".to_owned()));
     }
 
-    if rest.len() != num_pairs * (modulus_len * 2 +  order_len) {
+    let pair_len = checked_len_add(point_len(modulus_len, encoding)?, order_len)?;
+    if rest.len() != checked_len_mul(num_pairs, pair_len)? {
         return Err(ApiError::InputError("Input is either too short or contains garbage for g1 multiexp metering".to_owned()));
     }
 
@@ -141,7 +422,7 @@ fn meter_multiexp_g1(input: &[u8]) -> Result<u64, ApiError> {
     meter_arith::meter_multiexp(modulus_limbs, order_limbs, num_pairs, params, discounts)
 }
 
-fn meter_multiexp_g2(input: &[u8]) -> Result<u64, ApiError> {
+fn meter_multiexp_g2(input: &[u8], encoding: PointEncoding) -> Result<u64, ApiError> {
     let (modulus, modulus_len, order_len, ext_degree, rest) = parse_g2_curve_parameters(&input)?;
 
     let modulus_limbs = num_limbs_for_modulus(&modulus)?;
@@ -163,7 +444,8 @@ fn meter_multiexp_g2(input: &[u8]) -> Result<u64, ApiError> {
         return Err(ApiError::InputError("Invalid number of pairs".to_owned()));
     }
 
-    if rest.len() != num_pairs * (modulus_len * 2 * (ext_degree as usize) +  order_len) {
+    let pair_len = checked_len_add(point_len(checked_len_mul(modulus_len, ext_degree as usize)?, encoding)?, order_len)?;
+    if rest.len() != checked_len_mul(num_pairs, pair_len)? {
         return Err(ApiError::InputError("Input is either too short or contains garbage for g2 multiexp metering".to_owned()));
     }
 
@@ -172,82 +454,478 @@ fn meter_multiexp_g2(input: &[u8]) -> Result<u64, ApiError> {
     meter_arith::meter_multiexp(modulus_limbs, order_limbs, num_pairs, params, discounts)
 }
 
-fn meter_bls12(input: &[u8]) -> Result<u64, ApiError> {
-    self::meter_pairing::meter_bls12_pairing(input, &*self::meter_pairing::BLS12_PARAMS_INSTANCE, self::meter_pairing::BLS12_MAX_MODULUS_POWER)
+/// Mapping is priced as a multiplication by the cofactor plus one more multiplication
+/// with a scalar as long as the modulus, that covers the square root, Legendre symbol
+/// and inversion exponentiations. Isogeny evaluation is cheap compared to both
+#[cfg(feature = "mappings")]
+fn meter_map_to_g1(input: &[u8], limits: &Limits) -> Result<u64, ApiError> {
+    let (modulus, modulus_len, _, rest) = parse_g1_curve_parameters(input)?;
+    let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+
+    let curve_encoding = &input[..(input.len() - rest.len())];
+    let (_, rest) = split(rest, modulus_len, "Input is not long enough to get Z")?;
+    let (isogeny_mode, rest) = split(rest, ISOGENY_MODE_ENCODING_LENGTH, "Input is not long enough to get isogeny mode")?;
+    let rest = match isogeny_mode[0] {
+        ISOGENY_NONE => rest,
+        ISOGENY_EXPLICIT => {
+            let (_, rest) = split(rest, checked_len_mul(modulus_len, 2)?, "Input is not long enough to get isogenous curve")?;
+            let (degree_encoding, rest) = split(rest, BYTES_FOR_LENGTH_ENCODING, "Input is not long enough to get isogeny degree")?;
+            let map_degree = degree_encoding[0] as usize;
+            if map_degree == 0 || map_degree > limits.max_isogeny_map_degree {
+                return Err(ApiError::InputError("Invalid isogeny map degree".to_owned()));
+            }
+            let coeffs_len = checked_len_mul(checked_len_mul(map_degree + 1, 4)?, modulus_len)?;
+            let (_, rest) = split(rest, coeffs_len, "Input is not long enough to get isogeny map")?;
+
+            rest
+        },
+        ISOGENY_BUILT_IN => {
+            crate::public_interface::built_in_isogeny(curve_encoding)?;
+
+            rest
+        },
+        _ => {
+            return Err(ApiError::InputError("Unknown isogeny mode".to_owned()));
+        }
+    };
+
+    meter_mapping_tail(rest, modulus_limbs, modulus_len, 1)
+}
+
+/// SvdW makes up to three Legendre symbols and a square root per call, and as many to validate
+/// and prepare the constants, that is about twice as many exponentiations as simplified SWU
+#[cfg(feature = "mappings")]
+fn meter_map_to_g1_svdw(input: &[u8]) -> Result<u64, ApiError> {
+    let (modulus, modulus_len, _, rest) = parse_g1_curve_parameters(input)?;
+    let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+
+    let (_, rest) = split(rest, modulus_len, "Input is not long enough to get Z")?;
+
+    meter_mapping_tail(rest, modulus_limbs, modulus_len, 2)
+}
+
+/// Cofactor and the field element to map that follow the map specific parameters,
+/// exponentiations are priced in units of a G1 multiplication as described above
+#[cfg(feature = "mappings")]
+fn meter_mapping_tail(input: &[u8], modulus_limbs: usize, modulus_len: usize, exponentiation_units: u64) -> Result<u64, ApiError> {
+    let ((cofactor_len, _), rest) = decode_group_order_with_length(input)?;
+    if rest.len() != modulus_len {
+        return Err(ApiError::InputError("Input is either too short or contains garbage for mapping metering".to_owned()));
+    }
+
+    let cofactor_limbs = num_units_for_group_order_length(cofactor_len)?;
+    let exponent_limbs = num_units_for_group_order_length(modulus_len)?;
+
+    let params = &*meter_arith::G1_MULTIPLICATION_PARAMS_INSTANCE;
+
+    let cofactor_clearing = meter_arith::meter_multiplication(modulus_limbs, cofactor_limbs, params, true)?;
+    let exponentiations = meter_arith::meter_multiplication(modulus_limbs, exponent_limbs, params, false)?
+        .checked_mul(exponentiation_units).ok_or(ApiError::Overflow)?;
+
+    cofactor_clearing.checked_add(exponentiations).ok_or(ApiError::Overflow)
+}
+
+/// Price of one compression of SHA-256, 12 gas per word as for the SHA-256 precompile of EVM.
+/// SHA-512 has twice longer blocks and is priced the same per byte
+#[cfg(feature = "mappings")]
+const SHA256_BLOCK_PRICE: u64 = 24;
+#[cfg(feature = "mappings")]
+const SHA512_BLOCK_PRICE: u64 = 48;
+
+/// Hashing is priced by the number of compressions in `expand_message_xmd`, and the reduction of
+/// every element as a G1 addition, that is more expensive than a few multiplications it takes
+#[cfg(feature = "mappings")]
+fn meter_hash_to_field(input: &[u8]) -> Result<u64, ApiError> {
+    use crate::mapping::hash_to_field::{HashFunction, MAX_EXPANDED_LEN, MAX_NUM_HASH_BLOCKS};
+
+    let ((modulus, _), rest) = get_base_field_params(input)?;
+    let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+    let (hash, security_bits, num_elements, dst, msg) = crate::public_interface::decode_hash_to_field_parameters(rest)?;
+
+    let element_len = (modulus.bits() + security_bits).div_ceil(8);
+    let len_in_bytes = checked_len_mul(element_len, num_elements)?;
+    let num_hashes = len_in_bytes.div_ceil(hash.output_len());
+    if len_in_bytes > MAX_EXPANDED_LEN || num_hashes > MAX_NUM_HASH_BLOCKS {
+        return Err(ApiError::InputError("Too many uniform bytes requested".to_owned()));
+    }
+
+    let (block_price, padding_len) = match hash {
+        HashFunction::Sha256 => (SHA256_BLOCK_PRICE, 9),
+        HashFunction::Sha512 => (SHA512_BLOCK_PRICE, 17),
+    };
+    let blocks = |len: usize| -> u64 {
+        (len + padding_len).div_ceil(hash.block_len()) as u64
+    };
+
+    // b_0 hashes the zero block, message, length, separator and DST with its length,
+    // every next block hashes the previous one, counter and DST with its length
+    let first_hash_len = checked_len_add(checked_len_add(hash.block_len(), msg.len())?, dst.len() + 4)?;
+    let next_hash_len = hash.output_len() + dst.len() + 2;
+    let num_blocks = blocks(first_hash_len).checked_add(blocks(next_hash_len).checked_mul(num_hashes as u64).ok_or(ApiError::Overflow)?).ok_or(ApiError::Overflow)?;
+    let hashing = num_blocks.checked_mul(block_price).ok_or(ApiError::Overflow)?;
+
+    let params = &*meter_arith::G1_ADDITION_PARAMS_INSTANCE;
+    let reduction = meter_arith::meter_addition(modulus_limbs, params)?.checked_mul(num_elements as u64).ok_or(ApiError::Overflow)?;
+
+    hashing.checked_add(reduction).ok_or(ApiError::Overflow)
+}
+
+/// Recovering Y of a compressed point is a square root in the field of its coordinates. It takes
+/// an exponentiation by a power of the modulus length, that is priced as for the field inversion
+/// in the base field, as for `meter_field_sqrt_ext2` in Fp2, and with an exponent three times
+/// longer in Fp3. Curve parameters of pairings start in the same way as the G1 ones. The length
+/// of the input is already checked by the caller
+fn meter_decompression(bytes: &[u8]) -> Result<u64, ApiError> {
+    let (op_type, rest) = split(bytes, OPERATION_ENCODING_LENGTH, "Input should be longer than operation type encoding")?;
+    let (num_g1_points, num_g2_points, g2_extension_degree) = match num_points(op_type[0], rest) {
+        Some(num_points) => num_points,
+        None => {
+            return Ok(0);
+        }
+    };
+
+    let parameters = match op_type[0] {
+        OPERATION_PAIRING | OPERATION_PAIRING_GT | OPERATION_MILLER_LOOP |
+        OPERATION_PAIRING_EQUATION | OPERATION_BLS_VERIFY | OPERATION_KZG_OPENING => &rest[CURVE_TYPE_LENGTH..],
+        _ => rest
+    };
+    let ((modulus, modulus_len), _) = get_base_field_params(parameters)?;
+    let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+    let exponent_limbs = num_units_for_group_order_length(modulus_len)?;
+
+    let sqrt_in_fp = meter_arith::meter_multiplication(modulus_limbs, exponent_limbs, &*meter_arith::G1_MULTIPLICATION_PARAMS_INSTANCE, true)?;
+    let sqrt_in_g2_field = match g2_extension_degree {
+        0 | 1 => sqrt_in_fp,
+        2 => meter_arith::meter_multiplication(modulus_limbs, exponent_limbs, &*meter_arith::G2_EXT_2_MULTIPLICATION_PARAMS_INSTANCE, true)?,
+        3 => meter_arith::meter_multiplication(modulus_limbs, 3 * exponent_limbs, &*meter_arith::G2_EXT_3_MULTIPLICATION_PARAMS_INSTANCE, true)?,
+        _ => {
+            return Err(ApiError::UnknownParameter("Compressed points are not supported for G2 in extensions of degree above 3".to_owned()));
+        }
+    };
+
+    let g1_points = sqrt_in_fp.checked_mul(num_g1_points as u64).ok_or(ApiError::Overflow)?;
+    let g2_points = sqrt_in_g2_field.checked_mul(num_g2_points as u64).ok_or(ApiError::Overflow)?;
+
+    g1_points.checked_add(g2_points).ok_or(ApiError::Overflow)
 }
 
-fn meter_bn(input: &[u8]) -> Result<u64, ApiError> {
-    self::meter_pairing::meter_bn_pairing(input, &*self::meter_pairing::BN_PARAMS_INSTANCE, self::meter_pairing::BN_MAX_MODULUS_POWER)
+/// Decoding of the input tests the group order for primality. Curve parameters of
+/// pairings start in the same way as the G1 ones
+fn meter_primality_test_g1(input: &[u8], limits: &Limits) -> Result<u64, ApiError> {
+    if !limits.require_prime_group_order {
+        return Ok(0);
+    }
+    let (_, _, order_len, _) = parse_g1_curve_parameters(input)?;
+    let order_limbs = num_units_for_group_order_length(order_len)?;
+
+    meter_arith::meter_primality_test(order_limbs)
+}
+
+fn meter_primality_test_g2(input: &[u8], limits: &Limits) -> Result<u64, ApiError> {
+    if !limits.require_prime_group_order {
+        return Ok(0);
+    }
+    let (_, _, order_len, _, _) = parse_g2_curve_parameters(input)?;
+    let order_limbs = num_units_for_group_order_length(order_len)?;
+
+    meter_arith::meter_primality_test(order_limbs)
+}
+
+fn meter_bls12(input: &[u8], tail: PairingInputTail, limits: &Limits, encoding: PointEncoding) -> Result<u64, ApiError> {
+    self::meter_pairing::meter_bls12_pairing(input, &self::meter_pairing::BLS12_PARAMS_INSTANCE, self::meter_pairing::BLS12_MAX_MODULUS_POWER, tail, limits, encoding)
+}
+
+fn meter_bls24(input: &[u8], tail: PairingInputTail, limits: &Limits, encoding: PointEncoding) -> Result<u64, ApiError> {
+    self::meter_pairing::meter_bls24_pairing(input, &self::meter_pairing::BLS12_PARAMS_INSTANCE, self::meter_pairing::BLS12_MAX_MODULUS_POWER, tail, limits, encoding)
 }
 
-fn meter_mnt4(input: &[u8]) -> Result<u64, ApiError> {
+fn meter_bls48(input: &[u8], tail: PairingInputTail, limits: &Limits, encoding: PointEncoding) -> Result<u64, ApiError> {
+    self::meter_pairing::meter_bls48_pairing(input, &self::meter_pairing::BLS12_PARAMS_INSTANCE, self::meter_pairing::BLS12_MAX_MODULUS_POWER, tail, limits, encoding)
+}
+
+fn meter_kss16(input: &[u8], tail: PairingInputTail, limits: &Limits, encoding: PointEncoding) -> Result<u64, ApiError> {
+    self::meter_pairing::meter_kss16_pairing(input, &self::meter_pairing::BLS12_PARAMS_INSTANCE, self::meter_pairing::BLS12_MAX_MODULUS_POWER, tail, limits, encoding)
+}
+
+fn meter_kss18(input: &[u8], tail: PairingInputTail, limits: &Limits, encoding: PointEncoding) -> Result<u64, ApiError> {
+    self::meter_pairing::meter_kss18_pairing(input, &self::meter_pairing::BLS12_PARAMS_INSTANCE, self::meter_pairing::BLS12_MAX_MODULUS_POWER, tail, limits, encoding)
+}
+
+fn meter_bn(input: &[u8], tail: PairingInputTail, limits: &Limits, encoding: PointEncoding) -> Result<u64, ApiError> {
+    self::meter_pairing::meter_bn_pairing(input, &self::meter_pairing::BN_PARAMS_INSTANCE, self::meter_pairing::BN_MAX_MODULUS_POWER, tail, limits, encoding)
+}
+
+fn meter_mnt4(input: &[u8], tail: PairingInputTail, limits: &Limits, encoding: PointEncoding) -> Result<u64, ApiError> {
     self::meter_pairing::meter_mnt_pairing(
         input, 
         &*self::meter_pairing::MNT4_PARAMS_INSTANCE, 
         self::meter_pairing::MNT4_MAX_MODULUS_POWER,
-        2
+        2,
+        tail,
+        limits,
+        encoding
     )
 }
 
-fn meter_mnt6(input: &[u8]) -> Result<u64, ApiError> {
+fn meter_mnt6(input: &[u8], tail: PairingInputTail, limits: &Limits, encoding: PointEncoding) -> Result<u64, ApiError> {
     self::meter_pairing::meter_mnt_pairing(
         input, 
         &*self::meter_pairing::MNT6_PARAMS_INSTANCE, 
         self::meter_pairing::MNT6_MAX_MODULUS_POWER,
-        3
+        3,
+        tail,
+        limits,
+        encoding
     )
 }
 
+fn meter_bw6(input: &[u8], tail: PairingInputTail, limits: &Limits, encoding: PointEncoding) -> Result<u64, ApiError> {
+    self::meter_pairing::meter_bw6_pairing(
+        input, 
+        &self::meter_pairing::MNT6_PARAMS_INSTANCE, 
+        self::meter_pairing::MNT6_MAX_MODULUS_POWER,
+        tail,
+        limits,
+        encoding
+    )
+}
+
+fn meter_cocks_pinch(input: &[u8], tail: PairingInputTail, limits: &Limits, encoding: PointEncoding) -> Result<u64, ApiError> {
+    self::meter_pairing::meter_cocks_pinch_pairing(
+        input, 
+        &self::meter_pairing::MNT6_PARAMS_INSTANCE, 
+        self::meter_pairing::MNT6_MAX_MODULUS_POWER,
+        tail,
+        limits,
+        encoding
+    )
+}
+
+fn meter_pairing_operation(input: &[u8], tail: PairingInputTail, limits: &Limits, encoding: PointEncoding) -> Result<u64, ApiError> {
+    let (curve_type, rest) = split(input, CURVE_TYPE_LENGTH, "Input should be longer than curve type encoding")?;
+
+    let pairing = match curve_type[0] {
+        BLS12 => {
+            meter_bls12(rest, tail, limits, encoding)
+        },
+        BN => {
+            meter_bn(rest, tail, limits, encoding)
+        },
+        MNT4 => {
+            meter_mnt4(rest, tail, limits, encoding)
+        },
+        MNT6 => {
+            meter_mnt6(rest, tail, limits, encoding)
+        },
+        BLS24 => {
+            meter_bls24(rest, tail, limits, encoding)
+        },
+        BLS48 => {
+            meter_bls48(rest, tail, limits, encoding)
+        },
+        BW6 => {
+            meter_bw6(rest, tail, limits, encoding)
+        },
+        KSS16 => {
+            meter_kss16(rest, tail, limits, encoding)
+        },
+        KSS18 => {
+            meter_kss18(rest, tail, limits, encoding)
+        },
+        COCKS_PINCH => {
+            meter_cocks_pinch(rest, tail, limits, encoding)
+        },
+        _ => {
+            return Err(ApiError::InputError("Unknown curve type".to_owned()));
+        }
+    }?;
+
+    if tail != PairingInputTail::KzgOpening {
+        return Ok(pairing);
+    }
+
+    // the commitment is combined with the generator and the proof times the value and the evaluation point
+    let (modulus, _, order_len, _) = parse_g1_curve_parameters(rest)?;
+    let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+    let order_limbs = num_units_for_group_order_length(order_len)?;
+    let multiexp = meter_arith::meter_multiexp(modulus_limbs, order_limbs, 2, &*meter_arith::G1_MULTIPLICATION_PARAMS_INSTANCE, &*meter_arith::MULTIEXP_PARAMS_INSTANCE)?;
+
+    pairing.checked_add(multiexp).ok_or(ApiError::Overflow)
+}
+
 impl GasMeter {
     pub fn meter(bytes: &[u8]) -> Result<u64, ApiError> {
+        Self::meter_with_limits(bytes, &Limits::DEFAULT)
+    }
+
+    fn meter_with_limits(bytes: &[u8], limits: &Limits) -> Result<u64, ApiError> {
+        let (version, bytes) = split_abi_version(bytes)?;
+        match version {
+            AbiVersion::V1 => Self::meter_v1(bytes, limits, PointEncoding::Uncompressed),
+            // checking the twist costs a single inversion
+            AbiVersion::V2 => Self::meter_v1(&crate::public_interface::strip_twist_description(bytes)?, limits, PointEncoding::Uncompressed),
+            AbiVersion::V3 => Self::meter_v3(bytes, limits)
+        }
+    }
+
+    /// Compressed points cost the same operation as in `V1` and a square root per point
+    fn meter_v3(bytes: &[u8], limits: &Limits) -> Result<u64, ApiError> {
+        check_operation(bytes)?;
+        let price = Self::meter_v1(bytes, limits, PointEncoding::Compressed)?;
+
+        price.checked_add(meter_decompression(bytes)?).ok_or(ApiError::Overflow)
+    }
+
+    /// Prices the input for `API::run_with_config` with the same `limits`
+    pub fn meter_with_config(bytes: &[u8], limits: &Limits) -> Result<u64, ApiError> {
+        limits.validate()?;
+
+        Self::meter_with_limits(bytes, limits)
+    }
+
+    fn meter_v1(bytes: &[u8], limits: &Limits, encoding: PointEncoding) -> Result<u64, ApiError> {
         let (op_type, rest) = split(bytes, OPERATION_ENCODING_LENGTH , "Input should be longer than operation type encoding")?;
         let operation = op_type[0];
+        // pairings and mappings rely on the same upfront length check as `API::run`
+        validate_input_length(operation, rest, encoding)?;
         let result = match operation {
             OPERATION_G1_ADD => {
-                meter_addition_g1(&rest)
+                meter_addition_g1(rest, encoding)
             },
             OPERATION_G2_ADD => {
-                meter_addition_g2(&rest)
+                meter_addition_g2(rest, encoding)
             },
             OPERATION_G1_MUL => {
-                meter_multiplication_g1(&rest)
+                meter_multiplication_g1(rest, encoding)
             },
             OPERATION_G2_MUL => {
-                meter_multiplication_g2(&rest)
+                meter_multiplication_g2(rest, encoding)
             }
             OPERATION_G1_MULTIEXP => {
-                meter_multiexp_g1(&rest)
+                meter_multiexp_g1(rest, encoding)
             },
             OPERATION_G2_MULTIEXP => {
-                meter_multiexp_g2(&rest)
-            },
-            OPERATION_PAIRING => {
-                let (curve_type, rest) = split(rest, CURVE_TYPE_LENGTH, "Input should be longer than curve type encoding")?;
-
-                match curve_type[0] {
-                    BLS12 => {
-                        meter_bls12(&rest)
-                    },
-                    BN => {
-                        meter_bn(&rest)
-                    },
-                    MNT4 => {
-                        meter_mnt4(&rest)
-                    },
-                    MNT6 => {
-                        meter_mnt6(&rest)
-                    },
-                    _ => {
-                        return Err(ApiError::InputError("Unknown curve type".to_owned()));
-                    }
-                }
+                meter_multiexp_g2(rest, encoding)
+            },
+            OPERATION_G1_SUM => {
+                meter_sum_g1(rest, encoding)
+            },
+            OPERATION_G2_SUM => {
+                meter_sum_g2(rest, encoding)
+            },
+            OPERATION_G1_SUBGROUP_CHECK => {
+                meter_subgroup_check_g1(rest, encoding)
+            },
+            OPERATION_G2_SUBGROUP_CHECK => {
+                meter_subgroup_check_g2(rest, encoding)
+            },
+            OPERATION_G1_CLEAR_COFACTOR => {
+                meter_clear_cofactor_g1(rest, encoding)
+            },
+            OPERATION_G2_CLEAR_COFACTOR => {
+                meter_clear_cofactor_g2(rest, encoding)
+            },
+            OPERATION_G1_VALIDATE_POINT => {
+                meter_validate_point_g1(rest, encoding)
+            },
+            OPERATION_G2_VALIDATE_POINT => {
+                meter_validate_point_g2(rest, encoding)
+            },
+            // twisted Edwards curves use the same input layout as G1 and unified addition
+            // in extended coordinates is cheaper than the Jacobian one, so G1 prices are an upper bound
+            OPERATION_EDWARDS_ADD => {
+                meter_addition_g1(rest, encoding)
+            },
+            OPERATION_EDWARDS_MUL => {
+                meter_multiplication_g1(rest, encoding)
+            },
+            OPERATION_EDWARDS_MULTIEXP => {
+                meter_multiexp_g1(rest, encoding)
+            },
+            OPERATION_MONTGOMERY_LADDER => {
+                meter_montgomery_ladder(rest)
+            },
+            OPERATION_BINARY_ADD => {
+                meter_binary_curve_operation(rest, meter_addition_g1, encoding)
+            },
+            OPERATION_BINARY_MUL => {
+                meter_binary_curve_operation(rest, meter_multiplication_g1, encoding)
+            },
+            OPERATION_BINARY_MULTIEXP => {
+                meter_binary_curve_operation(rest, meter_multiexp_g1, encoding)
+            },
+            OPERATION_FIELD_ADD | OPERATION_FIELD_MUL => {
+                meter_field_operation(rest, 2)
+            },
+            OPERATION_FIELD_INVERSE => {
+                meter_field_inverse(rest)
+            },
+            OPERATION_FIELD_EXP => {
+                meter_field_exp(rest)
+            },
+            OPERATION_FIELD_EXT2_SQRT => {
+                meter_field_sqrt_ext2(rest)
+            },
+            OPERATION_PAIRING | OPERATION_PAIRING_GT => {
+                meter_pairing_operation(rest, PairingInputTail::Pairs, limits, encoding)
+            },
+            // the Miller loop alone is priced as a pairing without the final exponentiation
+            OPERATION_MILLER_LOOP => {
+                meter_pairing_operation(rest, PairingInputTail::MillerLoopPairs, limits, encoding)
+            },
+            OPERATION_FINAL_EXP => {
+                meter_pairing_operation(rest, PairingInputTail::MillerLoopValues, limits, encoding)
+            },
+            OPERATION_PAIRING_EQUATION => {
+                meter_pairing_operation(rest, PairingInputTail::SignedPairs, limits, encoding)
+            },
+            OPERATION_BLS_VERIFY => {
+                meter_pairing_operation(rest, PairingInputTail::BlsSignature, limits, encoding)
+            },
+            OPERATION_KZG_OPENING => {
+                meter_pairing_operation(rest, PairingInputTail::KzgOpening, limits, encoding)
+            },
+            #[cfg(feature = "mappings")]
+            OPERATION_MAP_TO_G1 => {
+                meter_map_to_g1(rest, limits)
+            },
+            #[cfg(feature = "mappings")]
+            OPERATION_MAP_TO_G1_SVDW => {
+                meter_map_to_g1_svdw(rest)
+            },
+            #[cfg(feature = "mappings")]
+            OPERATION_HASH_TO_FIELD => {
+                meter_hash_to_field(rest)
             },
             _ => {
                 Err(ApiError::InputError("Unknown operation type".to_owned()))
             }
         };
 
-        result
+        let primality_test = match operation {
+            OPERATION_FIELD_ADD | OPERATION_FIELD_MUL | OPERATION_FIELD_INVERSE | OPERATION_FIELD_EXP |
+            OPERATION_FIELD_EXT2_SQRT | OPERATION_HASH_TO_FIELD => {
+                return result;
+            },
+            OPERATION_G2_ADD | OPERATION_G2_MUL | OPERATION_G2_MULTIEXP | OPERATION_G2_SUM |
+            OPERATION_G2_SUBGROUP_CHECK | OPERATION_G2_CLEAR_COFACTOR | OPERATION_G2_VALIDATE_POINT => {
+                meter_primality_test_g2(rest, limits)
+            },
+            // the fast path of the well known curves doesn't run the test
+            OPERATION_PAIRING | OPERATION_PAIRING_GT | OPERATION_MILLER_LOOP | OPERATION_FINAL_EXP |
+            OPERATION_PAIRING_EQUATION | OPERATION_BLS_VERIFY | OPERATION_KZG_OPENING => {
+                if is_well_known_curve(rest) {
+                    return result;
+                }
+                let (_, rest) = split(rest, CURVE_TYPE_LENGTH, "Input should be longer than curve type encoding")?;
+                meter_primality_test_g1(rest, limits)
+            },
+            _ => {
+                meter_primality_test_g1(rest, limits)
+            }
+        };
+
+        result?.checked_add(primality_test?).ok_or(ApiError::Overflow)
     }
 }
 
@@ -265,4 +943,191 @@ mod test {
         println!("MNT4-753 for 4 pairs = {}", price);
         
     }
+
+    #[test]
+    fn test_estimate_gas_matches_meter() {
+        use crate::test::pairings::mnt4::assemble_mnt4_753;
+        use crate::public_interface::{API, OperationType};
+        use crate::public_interface::constants::OPERATION_PAIRING;
+
+        let calldata = assemble_mnt4_753(2);
+        let mut input = vec![OPERATION_PAIRING];
+        input.extend(calldata.iter());
+
+        let estimate = API::estimate_gas(&input).unwrap();
+        assert_eq!(estimate, super::meter_operation(OperationType::MNT4PAIR, &calldata[1..]).unwrap());
+        assert_eq!(estimate, super::GasMeter::meter(&input).unwrap());
+
+        input.extend_from_slice(&[0u8, 0u8]);
+        assert!(API::estimate_gas(&input).is_err());
+        assert!(API::estimate_gas(&[0xff]).is_err());
+    }
+
+    #[test]
+    fn test_final_exp_is_cheaper_than_pairing() {
+        use crate::test::pairings::mnt4::assemble_mnt4_753;
+        use crate::public_interface::constants::{OPERATION_PAIRING, OPERATION_MILLER_LOOP, OPERATION_FINAL_EXP, PAIRING_MODE_MILLER_LOOP_ONLY};
+
+        let mut pairing_input = vec![OPERATION_PAIRING];
+        pairing_input.extend(assemble_mnt4_753(2));
+        let pairing_price = super::GasMeter::meter(&pairing_input).unwrap();
+
+        pairing_input[0] = OPERATION_MILLER_LOOP;
+        let miller_loop_price = super::GasMeter::meter(&pairing_input).unwrap();
+        assert!(miller_loop_price < pairing_price);
+
+        let mut final_exp_input = vec![OPERATION_FINAL_EXP];
+        final_exp_input.extend(assemble_mnt4_753(0));
+        final_exp_input.pop();
+        final_exp_input.push(2u8);
+        final_exp_input.extend(vec![0u8; 2 * 4 * 95]);
+        let final_exp_price = super::GasMeter::meter(&final_exp_input).unwrap();
+        assert!(final_exp_price < pairing_price);
+
+        final_exp_input.push(0u8);
+        assert!(super::GasMeter::meter(&final_exp_input).is_err());
+
+        pairing_input[0] = OPERATION_PAIRING;
+        pairing_input.push(PAIRING_MODE_MILLER_LOOP_ONLY);
+        assert_eq!(super::GasMeter::meter(&pairing_input).unwrap(), miller_loop_price);
+    }
+    #[test]
+    fn test_sum_is_priced_per_point_and_level() {
+        use crate::public_interface::encoding::{G1CurveDescriptor, G1Point};
+        use crate::public_interface::constants::OPERATION_FIELD_INVERSE;
+
+        let modulus = hex::decode("30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47").unwrap();
+        let order = hex::decode("30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001").unwrap();
+        let curve = G1CurveDescriptor::new(&modulus, &[0u8], &[3u8], &order).unwrap();
+        let generator = G1Point::new(&[1u8], &[2u8]);
+
+        // the group order is tested once per call
+        let primality_test = super::meter_arith::meter_primality_test(4).unwrap();
+        let addition = super::GasMeter::meter(&curve.add(&generator, &generator).unwrap()).unwrap() - primality_test;
+        let mut inverse_input = vec![OPERATION_FIELD_INVERSE, 32u8];
+        inverse_input.extend(modulus.iter());
+        inverse_input.extend(vec![0u8; 31]);
+        inverse_input.push(2u8);
+        let inversion = super::GasMeter::meter(&inverse_input).unwrap();
+
+        let price = |num_points: usize| super::GasMeter::meter(&curve.sum(&vec![generator.clone(); num_points]).unwrap()).unwrap() - primality_test;
+        assert_eq!(price(1), addition);
+        assert_eq!(price(2), 2 * addition);
+        assert_eq!(price(3), 3 * addition + inversion);
+        assert_eq!(price(255), 255 * addition + 7 * inversion);
+    }
+
+    #[test]
+    fn test_primality_test_is_priced_by_order_limbs() {
+        use crate::public_interface::encoding::{G1CurveDescriptor, G1Point};
+        use crate::public_interface::Limits;
+
+        let modulus = hex::decode("30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47").unwrap();
+        let order = hex::decode("30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001").unwrap();
+        // 1024 bit MODP group prime from RFC 2409
+        let large_order = hex::decode("ffffffffffffffffc90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74020bbea63b139b22514a08798e3404ddef9519b3cd3a431b302b0a6df25f14374fe1356d6d51c245e485b576625e7ec6f44c42e9a637ed6b0bff5cb6f406b7edee386bfb5a899fa5ae9f24117c4b1fe649286651ece65381ffffffffffffffff").unwrap();
+        let generator = G1Point::new(&[1u8], &[2u8]);
+        let addition = |order: &[u8]| G1CurveDescriptor::new(&modulus, &[0u8], &[3u8], order).unwrap().add(&generator, &generator).unwrap();
+
+        let price = super::GasMeter::meter(&addition(&order)).unwrap();
+        let large_order_price = super::GasMeter::meter(&addition(&large_order)).unwrap();
+        let primality_test = |limbs: usize| super::meter_arith::meter_primality_test(limbs).unwrap();
+        assert_eq!(large_order_price - price, primality_test(16) - primality_test(4));
+
+        let limits = Limits { require_prime_group_order: false, ..Limits::DEFAULT };
+        assert_eq!(super::GasMeter::meter_with_config(&addition(&large_order), &limits).unwrap(), price - primality_test(4));
+    }
+
+    #[test]
+    fn test_fast_path_pairings_are_not_charged_for_primality_test() {
+        use crate::test::pairings::bn::assemble_bn254;
+        use crate::test::pairings::bls12::{assemble_bls12_381, assemble_bls12_377};
+        use crate::public_interface::Limits;
+        use crate::public_interface::constants::OPERATION_PAIRING;
+
+        let without_primality_test = Limits { require_prime_group_order: false, ..Limits::DEFAULT };
+        let pairing = |calldata: Vec<u8>| {
+            let mut input = vec![OPERATION_PAIRING];
+            input.extend(calldata);
+            input
+        };
+
+        // fast paths don't decode the group order, so the price is the same as without the test
+        for input in vec![pairing(assemble_bn254(2)), pairing(assemble_bls12_381(2))].into_iter() {
+            assert!(super::is_well_known_curve(&input[1..]));
+            let price = super::GasMeter::meter(&input).unwrap();
+            assert_eq!(price, super::GasMeter::meter_with_config(&input, &without_primality_test).unwrap());
+        }
+
+        let input = pairing(assemble_bls12_377(2));
+        assert!(!super::is_well_known_curve(&input[1..]));
+        let price = super::GasMeter::meter(&input).unwrap();
+        let primality_test = super::meter_arith::meter_primality_test(4).unwrap();
+        assert_eq!(price, super::GasMeter::meter_with_config(&input, &without_primality_test).unwrap() + primality_test);
+    }
+
+    #[test]
+    fn test_run_with_limit() {
+        use crate::test::pairings::bn::assemble_bn254;
+        use crate::public_interface::{API, ErrorCode};
+        use crate::public_interface::constants::OPERATION_PAIRING;
+
+        let mut input = vec![OPERATION_PAIRING];
+        input.extend(assemble_bn254(2));
+        let price = API::estimate_gas(&input).unwrap();
+
+        assert_eq!(API::run_with_limit(&input, price).unwrap(), API::run(&input).unwrap());
+        assert_eq!(API::run_with_limit(&input, price - 1).unwrap_err().code(), ErrorCode::OutOfGas);
+    }
+
+    #[test]
+    fn test_operation_budget_stops_miller_loop() {
+        use crate::test::pairings::bn::assemble_bn254;
+        use crate::public_interface::{API, ExecutionContext};
+        use crate::public_interface::constants::OPERATION_PAIRING;
+
+        let mut input = vec![OPERATION_PAIRING];
+        input.extend(assemble_bn254(2));
+        let expected = API::run(&input).unwrap();
+
+        let mut ctx = ExecutionContext::with_budget(10);
+        let _ = API::run_in_context(&input, &mut ctx);
+        assert!(ctx.budget_is_exhausted());
+
+        // the budget belongs to the context of a single call
+        assert_eq!(API::run(&input).unwrap(), expected);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_operation_budget_is_shared_with_parallel_miller_loop() {
+        use crate::test::pairings::bn::assemble_bn254;
+        use crate::public_interface::{API, ExecutionContext};
+        use crate::public_interface::constants::OPERATION_PAIRING;
+
+        let mut input = vec![OPERATION_PAIRING];
+        input.extend(assemble_bn254(8));
+
+        let single_thread = rayon::ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+        let four_threads = rayon::ThreadPoolBuilder::new().num_threads(4).build().unwrap();
+        let run_unlimited = |pool: &rayon::ThreadPool| pool.install(|| {
+            let mut ctx = ExecutionContext::with_budget(u64::MAX);
+            let result = API::run_in_context(&input, &mut ctx).unwrap();
+
+            (result, u64::MAX - ctx.remaining_budget().unwrap())
+        });
+
+        let (expected, operations) = run_unlimited(&single_thread);
+        let (result, parallel_operations) = run_unlimited(&four_threads);
+        assert_eq!(result, expected);
+        // every chunk repeats the iterations of the loop, so splitting can only add operations
+        assert!(parallel_operations >= operations);
+
+        four_threads.install(|| {
+            let mut ctx = ExecutionContext::with_budget(parallel_operations * 3 / 4);
+            let _ = API::run_in_context(&input, &mut ctx);
+            assert!(ctx.budget_is_exhausted());
+            assert_eq!(ctx.remaining_budget(), Some(0));
+        });
+    }
 }
\ No newline at end of file
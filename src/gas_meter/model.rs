@@ -0,0 +1,457 @@
+//! Fitting of the gas models from the timing reports written by the gas meter test harness
+//! (`ReportWriter` for BLS12, BN, MNT and arithmetic reports).
+//!
+//! Models are sums of monomials over named features (bit lengths, hamming weights, limbs,
+//! number of pairs), in the same form as `miller` and `final_exp` entries of the JSON models:
+//! every term is a coefficient and a list of (feature index, power). Pairing reports are
+//! usually fitted in two steps: first the running time for every fixed set of parameters is
+//! regressed over the number of pairs with `fit_per_pair_cost`, then the per pair cost
+//! (Miller loop) and fixed cost (final exponentiation) are fitted with `fit_model`.
+
+use crate::errors::ApiError;
+use std::collections::BTreeMap;
+
+/// Variable index and power, both as in the serialized models
+pub type Term = Vec<(usize, usize)>;
+
+/// Numeric table parsed from a report
+#[derive(Clone, Debug)]
+pub struct Samples {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<f64>>,
+}
+
+impl Samples {
+    pub fn column_index(&self, name: &str) -> Result<usize, ApiError> {
+        self.columns.iter().position(|c| c == name).ok_or_else(|| {
+            ApiError::UnknownParameter(format!("Report has no column {}", name))
+        })
+    }
+
+    /// Parses a report with a header line and numeric values separated by commas
+    pub fn from_csv<R: std::io::Read>(mut reader: R) -> Result<Self, ApiError> {
+        let mut contents = String::new();
+        reader.read_to_string(&mut contents).map_err(|e| {
+            ApiError::InputError(format!("Failed to read the report: {}", e))
+        })?;
+
+        let mut lines = contents.lines().map(|l| l.trim()).filter(|l| !l.is_empty());
+        let header = lines.next().ok_or(ApiError::MissingValue)?;
+        let columns: Vec<String> = header.split(',').map(|c| c.trim().trim_matches('"').to_owned()).collect();
+
+        let mut rows = vec![];
+        for (line_number, line) in lines.enumerate() {
+            let row = line.split(',').map(|v| {
+                v.trim().trim_matches('"').parse::<f64>().map_err(|_| {
+                    ApiError::InputError(format!("Value `{}` in row {} is not a number", v, line_number + 1))
+                })
+            }).collect::<Result<Vec<f64>, ApiError>>()?;
+
+            if row.len() != columns.len() {
+                return Err(ApiError::InputError(format!("Row {} has {} values for {} columns", line_number + 1, row.len(), columns.len())));
+            }
+
+            rows.push(row);
+        }
+
+        Ok(Self {
+            columns,
+            rows
+        })
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_csv_path<P: AsRef<std::path::Path>>(path: P) -> Result<Self, ApiError> {
+        let file = std::fs::File::open(path).map_err(|e| {
+            ApiError::InputError(format!("Failed to open the report: {}", e))
+        })?;
+
+        Self::from_csv(file)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResidualStatistics {
+    pub num_samples: usize,
+    pub max_abs: f64,
+    pub mean_abs: f64,
+    pub rms: f64,
+    /// Largest residual relative to the observed value
+    pub max_relative: f64,
+    pub r_squared: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct FittedModel {
+    pub features: Vec<String>,
+    pub terms: Vec<Term>,
+    pub coefficients: Vec<f64>,
+    pub residuals: ResidualStatistics,
+}
+
+impl FittedModel {
+    pub fn evaluate(&self, features: &[f64]) -> f64 {
+        self.coefficients.iter().zip(self.terms.iter()).map(|(c, term)| {
+            c * evaluate_term(term, features)
+        }).sum()
+    }
+
+    /// Coefficients scaled by `multiplier` and rounded, in the form used by the JSON models.
+    /// Negative coefficients can not be represented there, so they are an error
+    pub fn integer_terms(&self, multiplier: u64) -> Result<Vec<(u64, Term)>, ApiError> {
+        let mut result = Vec::with_capacity(self.terms.len());
+        for (c, term) in self.coefficients.iter().zip(self.terms.iter()) {
+            let scaled = (c * multiplier as f64).round();
+            if scaled < 0.0 {
+                return Err(ApiError::UnknownParameter(format!("Coefficient {} for term {:?} is negative", c, term)));
+            }
+            if scaled > u64::MAX as f64 {
+                return Err(ApiError::Overflow);
+            }
+            result.push((scaled as u64, term.clone()));
+        }
+
+        Ok(result)
+    }
+
+    /// Features with their maximal powers, in the form of `miller_features` of the JSON models
+    pub fn feature_powers(&self) -> Vec<(String, u64)> {
+        let mut max_powers = vec![0u64; self.features.len()];
+        for term in self.terms.iter() {
+            for &(variable, power) in term.iter() {
+                if max_powers[variable] < power as u64 {
+                    max_powers[variable] = power as u64;
+                }
+            }
+        }
+
+        self.features.iter().cloned().zip(max_powers).collect()
+    }
+
+    /// Model in the layout of the JSON pairing models together with the fit quality
+    pub fn to_json(&self, multiplier: u64) -> Result<serde_json::Value, ApiError> {
+        let terms = self.integer_terms(multiplier)?;
+
+        Ok(serde_json::json!({
+            "multiplier": multiplier,
+            "features": self.feature_powers(),
+            "terms": terms,
+            "coefficients": self.coefficients,
+            "residuals": {
+                "num_samples": self.residuals.num_samples,
+                "max_abs": self.residuals.max_abs,
+                "mean_abs": self.residuals.mean_abs,
+                "rms": self.residuals.rms,
+                "max_relative": self.residuals.max_relative,
+                "r_squared": self.residuals.r_squared,
+            }
+        }))
+    }
+}
+
+fn evaluate_term(term: &Term, features: &[f64]) -> f64 {
+    term.iter().map(|&(variable, power)| features[variable].powi(power as i32)).product()
+}
+
+fn select_columns(samples: &Samples, names: &[&str]) -> Result<Vec<usize>, ApiError> {
+    names.iter().map(|name| samples.column_index(name)).collect()
+}
+
+/// Least squares fit of `target` with a sum of `terms` over the `features` columns.
+/// Empty term is a constant
+pub fn fit_model(samples: &Samples, features: &[&str], target: &str, terms: &[Term]) -> Result<FittedModel, ApiError> {
+    if terms.is_empty() {
+        return Err(ApiError::MissingValue);
+    }
+    for term in terms.iter() {
+        for &(variable, power) in term.iter() {
+            if variable >= features.len() || power == 0 {
+                return Err(ApiError::InputError(format!("Invalid term {:?} for {} features", term, features.len())));
+            }
+        }
+    }
+    if samples.rows.len() < terms.len() {
+        return Err(ApiError::InputError(format!("{} samples are not enough to fit {} terms", samples.rows.len(), terms.len())));
+    }
+
+    let feature_indexes = select_columns(samples, features)?;
+    let target_index = samples.column_index(target)?;
+
+    let feature_values: Vec<Vec<f64>> = samples.rows.iter().map(|row| {
+        feature_indexes.iter().map(|&i| row[i]).collect()
+    }).collect();
+    let observed: Vec<f64> = samples.rows.iter().map(|row| row[target_index]).collect();
+
+    let design: Vec<Vec<f64>> = feature_values.iter().map(|values| {
+        terms.iter().map(|term| evaluate_term(term, values)).collect()
+    }).collect();
+
+    let coefficients = solve_least_squares(&design, &observed)?;
+
+    let mut model = FittedModel {
+        features: features.iter().map(|f| (*f).to_owned()).collect(),
+        terms: terms.to_vec(),
+        coefficients,
+        residuals: ResidualStatistics {
+            num_samples: 0,
+            max_abs: 0.0,
+            mean_abs: 0.0,
+            rms: 0.0,
+            max_relative: 0.0,
+            r_squared: 0.0,
+        }
+    };
+
+    let predicted: Vec<f64> = feature_values.iter().map(|values| model.evaluate(values)).collect();
+    model.residuals = residual_statistics(&observed, &predicted);
+
+    Ok(model)
+}
+
+/// For every distinct combination of `group_by` columns fits `target = fixed + per_pair * num_pairs`.
+/// Result has the `group_by` columns followed by `per_pair` and `fixed`
+pub fn fit_per_pair_cost(samples: &Samples, group_by: &[&str], pairs_column: &str, target: &str) -> Result<Samples, ApiError> {
+    let group_indexes = select_columns(samples, group_by)?;
+    let pairs_index = samples.column_index(pairs_column)?;
+    let target_index = samples.column_index(target)?;
+
+    // f64 can not be a key, but all the grouping features are integers
+    let mut groups: BTreeMap<Vec<i64>, Vec<(f64, f64)>> = BTreeMap::new();
+    for row in samples.rows.iter() {
+        let key = group_indexes.iter().map(|&i| row[i].round() as i64).collect();
+        groups.entry(key).or_default().push((row[pairs_index], row[target_index]));
+    }
+
+    let mut columns: Vec<String> = group_by.iter().map(|c| (*c).to_owned()).collect();
+    columns.push("per_pair".to_owned());
+    columns.push("fixed".to_owned());
+
+    let mut rows = Vec::with_capacity(groups.len());
+    for (key, points) in groups.into_iter() {
+        let design: Vec<Vec<f64>> = points.iter().map(|(pairs, _)| vec![1.0, *pairs]).collect();
+        let observed: Vec<f64> = points.iter().map(|(_, t)| *t).collect();
+        let solution = solve_least_squares(&design, &observed).map_err(|_| {
+            ApiError::InputError(format!("Group {:?} needs measurements for at least two different numbers of pairs", key))
+        })?;
+
+        let mut row: Vec<f64> = key.into_iter().map(|k| k as f64).collect();
+        row.push(solution[1]);
+        row.push(solution[0]);
+        rows.push(row);
+    }
+
+    Ok(Samples {
+        columns,
+        rows
+    })
+}
+
+fn residual_statistics(observed: &[f64], predicted: &[f64]) -> ResidualStatistics {
+    let n = observed.len() as f64;
+    let mean = observed.iter().sum::<f64>() / n;
+
+    let mut max_abs = 0.0f64;
+    let mut sum_abs = 0.0f64;
+    let mut sum_squares = 0.0f64;
+    let mut total_squares = 0.0f64;
+    let mut max_relative = 0.0f64;
+    for (o, p) in observed.iter().zip(predicted.iter()) {
+        let r = (o - p).abs();
+        max_abs = max_abs.max(r);
+        sum_abs += r;
+        sum_squares += r * r;
+        total_squares += (o - mean) * (o - mean);
+        if *o != 0.0 {
+            max_relative = max_relative.max(r / o.abs());
+        }
+    }
+
+    let r_squared = if total_squares == 0.0 {
+        1.0
+    } else {
+        1.0 - sum_squares / total_squares
+    };
+
+    ResidualStatistics {
+        num_samples: observed.len(),
+        max_abs,
+        mean_abs: sum_abs / n,
+        rms: (sum_squares / n).sqrt(),
+        max_relative,
+        r_squared
+    }
+}
+
+/// Solves normal equations with Gaussian elimination. Columns are scaled to unit
+/// magnitude first, features like x_bit_length * modulus_limbs^6 are otherwise
+/// many orders of magnitude apart
+fn solve_least_squares(design: &[Vec<f64>], observed: &[f64]) -> Result<Vec<f64>, ApiError> {
+    let num_terms = design.first().map(|r| r.len()).ok_or(ApiError::MissingValue)?;
+
+    let mut scales = vec![0.0f64; num_terms];
+    for row in design.iter() {
+        for (s, v) in scales.iter_mut().zip(row.iter()) {
+            *s = s.max(v.abs());
+        }
+    }
+    if scales.contains(&0.0) {
+        return Err(ApiError::UnexpectedZero("Term is zero for all the samples".to_owned()));
+    }
+
+    // augmented matrix [A^T A | A^T b]
+    let mut matrix = vec![vec![0.0f64; num_terms + 1]; num_terms];
+    for (row, b) in design.iter().zip(observed.iter()) {
+        for i in 0..num_terms {
+            let a_i = row[i] / scales[i];
+            for j in 0..num_terms {
+                matrix[i][j] += a_i * row[j] / scales[j];
+            }
+            matrix[i][num_terms] += a_i * b;
+        }
+    }
+
+    for column in 0..num_terms {
+        let pivot = (column..num_terms).max_by(|&a, &b| {
+            matrix[a][column].abs().partial_cmp(&matrix[b][column].abs()).expect("values are finite")
+        }).expect("range is not empty");
+        if matrix[pivot][column].abs() < 1e-12 {
+            return Err(ApiError::InputError("Terms are linearly dependent on the given samples".to_owned()));
+        }
+        matrix.swap(column, pivot);
+
+        let pivot_row = matrix[column].clone();
+        for (row_index, row) in matrix.iter_mut().enumerate() {
+            if row_index == column {
+                continue;
+            }
+            let factor = row[column] / pivot_row[column];
+            for (value, pivot_value) in row[column..].iter_mut().zip(pivot_row[column..].iter()) {
+                *value -= factor * pivot_value;
+            }
+        }
+    }
+
+    Ok((0..num_terms).map(|i| matrix[i][num_terms] / matrix[i][i] / scales[i]).collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::gas_meter::ReportFormat;
+    use crate::test::gas_meter::bls12::{Bls12Report, Bls12ReportWriter};
+
+    const BLS12_FEATURES: [&str; 3] = ["x_bit_length", "x_hamming_weight", "modulus_limbs"];
+
+    // same shape as the Miller loop model in bls12_model.json
+    fn bls12_terms() -> Vec<Term> {
+        vec![
+            vec![(0, 1), (2, 1)],
+            vec![(0, 1), (2, 2)],
+            vec![(1, 1), (2, 1)],
+            vec![(1, 1), (2, 2)],
+        ]
+    }
+
+    fn synthetic_bls12_reports() -> Vec<Bls12Report> {
+        let miller = |bits: u64, hamming: u64, limbs: u64| 30 * bits * limbs + 4 * bits * limbs * limbs + 25 * hamming * limbs + 5 * hamming * limbs * limbs;
+        let final_exp = |bits: u64, hamming: u64, limbs: u64| 118 * bits * limbs + 9 * bits * limbs * limbs + 147 * hamming * limbs + 17 * hamming * limbs * limbs;
+
+        let mut reports = vec![];
+        for limbs in 4..=8u64 {
+            for &(bits, hamming) in [(32u64, 4u64), (64, 6), (64, 20), (100, 40)].iter() {
+                for num_pairs in 2..=4u64 {
+                    let run_microseconds = 1000 + final_exp(bits, hamming, limbs) + num_pairs * miller(bits, hamming, limbs);
+                    reports.push(Bls12Report {
+                        x_bit_length: bits as usize,
+                        x_hamming_weight: hamming as usize,
+                        modulus_limbs: limbs as usize,
+                        group_limbs: 4,
+                        num_pairs: num_pairs as usize,
+                        x_is_negative: false,
+                        line_evaluations: 0,
+                        run_microseconds,
+                        run_microseconds_miller_loop: 0,
+                        run_microseconds_final_exp: 0
+                    });
+                }
+            }
+        }
+
+        reports
+    }
+
+    #[test]
+    fn test_fit_recovers_exact_polynomial() {
+        let mut rows = vec![];
+        for a in 1..6 {
+            for b in 1..6 {
+                let (a, b) = (a as f64, b as f64);
+                rows.push(vec![a, b, 7.0 + 3.0 * a * b + 0.5 * b * b * b]);
+            }
+        }
+        let samples = Samples {
+            columns: vec!["a".to_owned(), "b".to_owned(), "t".to_owned()],
+            rows
+        };
+
+        let model = fit_model(&samples, &["a", "b"], "t", &[vec![], vec![(0, 1), (1, 1)], vec![(1, 3)]]).unwrap();
+        assert!((model.coefficients[0] - 7.0).abs() < 1e-6);
+        assert!((model.coefficients[1] - 3.0).abs() < 1e-6);
+        assert!((model.coefficients[2] - 0.5).abs() < 1e-6);
+        assert!(model.residuals.max_abs < 1e-6);
+        assert!((model.residuals.r_squared - 1.0).abs() < 1e-9);
+
+        assert_eq!(model.integer_terms(1000).unwrap()[2], (500, vec![(1, 3)]));
+        assert_eq!(model.feature_powers(), vec![("a".to_owned(), 1), ("b".to_owned(), 3)]);
+
+        let json = model.to_json(1000).unwrap();
+        assert_eq!(json["terms"][1], serde_json::json!([3000, [[0, 1], [1, 1]]]));
+        assert_eq!(json["residuals"]["num_samples"], 25);
+
+        // linearly dependent terms
+        assert!(fit_model(&samples, &["a", "b"], "t", &[vec![(0, 1)], vec![(0, 1)]]).is_err());
+        assert!(fit_model(&samples, &["a", "b"], "t", &[vec![(2, 1)]]).is_err());
+        assert!(fit_model(&samples, &["a", "c"], "t", &[vec![(0, 1)]]).is_err());
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn test_fit_bls12_report() {
+        let path = std::env::temp_dir().join(format!("bls12_model_fit_{}.csv", std::process::id()));
+        {
+            let mut writer = Bls12ReportWriter::new_for_path_with_format(&path, ReportFormat::Csv);
+            for report in synthetic_bls12_reports().into_iter() {
+                writer.write_report(report);
+            }
+        }
+
+        let samples = Samples::from_csv_path(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(samples.rows.len(), 5 * 4 * 3);
+
+        let per_pair = fit_per_pair_cost(&samples, &BLS12_FEATURES, "num_pairs", "run_microseconds").unwrap();
+        assert_eq!(per_pair.rows.len(), 5 * 4);
+
+        let miller = fit_model(&per_pair, &BLS12_FEATURES, "per_pair", &bls12_terms()).unwrap();
+        let miller_terms = miller.integer_terms(1).unwrap();
+        let coeffs: Vec<u64> = miller_terms.iter().map(|(c, _)| *c).collect();
+        assert_eq!(coeffs, vec![30, 4, 25, 5]);
+        assert!(miller.residuals.max_relative < 1e-9);
+
+        let mut final_exp_terms = bls12_terms();
+        final_exp_terms.push(vec![]);
+        let final_exp = fit_model(&per_pair, &BLS12_FEATURES, "fixed", &final_exp_terms).unwrap();
+        let coeffs: Vec<u64> = final_exp.integer_terms(1).unwrap().iter().map(|(c, _)| *c).collect();
+        assert_eq!(coeffs, vec![118, 9, 147, 17, 1000]);
+    }
+
+    #[test]
+    fn test_invalid_reports() {
+        assert!(Samples::from_csv("".as_bytes()).is_err());
+        assert!(Samples::from_csv("a,b\n1,2,3\n".as_bytes()).is_err());
+        assert!(Samples::from_csv("a,b\n1,x\n".as_bytes()).is_err());
+
+        let samples = Samples::from_csv("n,t\n1,10\n1,11\n".as_bytes()).unwrap();
+        assert!(fit_per_pair_cost(&samples, &[], "n", "t").is_err());
+    }
+}
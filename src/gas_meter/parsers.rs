@@ -1,6 +1,8 @@
 use crate::public_interface::decode_utils::*;
 use crate::public_interface::decode_g1::*;
 use crate::public_interface::constants::*;
+use crate::public_interface::{point_len, PointEncoding};
+use crate::public_interface::Limits;
 use crate::errors::ApiError;
 use crate::integers::*;
 use crate::pairings::calculate_hamming_weight;
@@ -21,7 +23,7 @@ pub(crate) fn parse_g1_curve_parameters<'a>(bytes: &'a [u8]) -> Result<(
     let (_, rest) = split(rest, modulus_len, "Input is not long enough to get A parameter")?;
     let (_, rest) = split(rest, modulus_len, "Input is not long enough to get B parameter")?;
 
-    let (order_len, _, rest) = parse_group_order_from_encoding(rest)?;
+    let (order_len, _, rest) = parse_nonzero_group_order_from_encoding(rest)?;
 
     if rest.len() == 0 {
         return Err(ApiError::InputError("Input is not long enough".to_owned()));
@@ -57,11 +59,11 @@ pub(crate) fn parse_g2_curve_parameters<'a>(bytes: &'a [u8]) -> Result<(
         return Err(ApiError::InputError("Invalid extension degree".to_owned()));
     }
     let (_, rest) = split(rest, modulus_len, "Input is not long enough to get non-residue")?;
-    let extension_field_element_len = modulus_len * (extension_degree as usize);
+    let extension_field_element_len = checked_len_mul(modulus_len, extension_degree as usize)?;
     let (_, rest) = split(rest, extension_field_element_len, "Input is not long enough to get A parameter")?;
     let (_, rest) = split(rest, extension_field_element_len, "Input is not long enough to get B parameter")?;
 
-    let (order_len, _, rest) = parse_group_order_from_encoding(rest)?;
+    let (order_len, _, rest) = parse_nonzero_group_order_from_encoding(rest)?;
     if rest.len() == 0 {
         return Err(ApiError::InputError("Input is not long enough".to_owned()));
     }
@@ -77,7 +79,154 @@ pub(crate) fn parse_g2_curve_parameters<'a>(bytes: &'a [u8]) -> Result<(
     )
 }
 
-pub(crate) fn parse_mnt_pairing_parameters<'a>(bytes: &'a [u8], ext_degree: usize) -> Result<(
+/// What follows the curve parameters in a pairing call
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PairingInputTail {
+    /// Number of pairs and the list of (G1, G2) pairs
+    Pairs,
+    /// Same as `Pairs` for the Miller loop operation, the final exponentiation is skipped
+    /// whatever the pairing mode is
+    MillerLoopPairs,
+    /// Same as `Pairs` with a sign before every pair, as for the pairing equation
+    SignedPairs,
+    /// Public key group, generator, public key, message and signature, checked as two pairs
+    BlsSignature,
+    /// Generators, tau times the G2 generator, commitment, evaluation point, value and proof, checked as two pairs
+    KzgOpening,
+    /// Number of Miller loop values and the list of values to finalize
+    MillerLoopValues
+}
+
+/// Skips the sign that precedes every pair of `PairingInputTail::SignedPairs`,
+/// negation of a G1 point is free in comparison to the pairing
+fn skip_pair_sign(bytes: &[u8], tail: PairingInputTail) -> Result<&[u8], ApiError> {
+    if tail != PairingInputTail::SignedPairs {
+        return Ok(bytes);
+    }
+    let (_, rest) = decode_sign_is_negative(bytes)?;
+
+    Ok(rest)
+}
+
+/// Checks the bytes after the list of pairs: the optional pairing mode or nothing
+/// for the signed pairs of the pairing equation. Returns whether the final exponentiation is done
+fn check_after_pairs(bytes: &[u8], tail: PairingInputTail) -> Result<bool, ApiError> {
+    if tail == PairingInputTail::SignedPairs {
+        if !bytes.is_empty() {
+            return Err(ApiError::garbage_at_the_end(bytes));
+        }
+
+        return Ok(true);
+    }
+    let miller_loop_only = decode_pairing_mode_is_miller_loop_only(bytes)?;
+
+    Ok(!miller_loop_only && tail != PairingInputTail::MillerLoopPairs)
+}
+
+/// Parses the list of pairs after the curve parameters up to the end of the input. Returns the number of pairs,
+/// the numbers of G1 and G2 subgroup checks requested for them and whether the final exponentiation is done
+fn parse_pairs(bytes: &[u8], g1_encoding_len: usize, g2_encoding_len: usize, order_len: usize, tail: PairingInputTail) -> Result<(usize, (usize, usize), bool), ApiError> {
+    if tail == PairingInputTail::BlsSignature {
+        return parse_bls_signature(bytes, g1_encoding_len, g2_encoding_len);
+    }
+    if tail == PairingInputTail::KzgOpening {
+        return parse_kzg_opening(bytes, g1_encoding_len, g2_encoding_len, order_len);
+    }
+
+    let (num_pairs_encoding, rest) = split(bytes, BYTES_FOR_LENGTH_ENCODING, "Input is not long enough to get number of pairs")?;
+    let num_pairs = num_pairs_encoding[0] as usize;
+    if num_pairs == 0 {
+        return Err(ApiError::InputError("Zero pairs encoded".to_owned()));
+    }
+
+    let mut num_g1_subgroup_checks = 0;
+    let mut num_g2_subgroup_checks = 0;
+
+    let mut global_rest = rest;
+
+    for _ in 0..num_pairs {
+        let rest = skip_pair_sign(global_rest, tail)?;
+        let (check_g1, rest) = decode_boolean(rest)?;
+        let (_, rest) = split(rest, g1_encoding_len, "input is not long enough to get G1 point encoding")?;
+        let (check_g2, rest) = decode_boolean(rest)?;
+        let (_, rest) = split(rest, g2_encoding_len, "input is not long enough to get G2 point encoding")?;
+        global_rest = rest;
+
+        if check_g1 {
+            num_g1_subgroup_checks += 1;
+        }
+
+        if check_g2 {
+            num_g2_subgroup_checks += 1;
+        }
+    }
+
+    let with_final_exp = check_after_pairs(global_rest, tail)?;
+
+    Ok((num_pairs, (num_g1_subgroup_checks, num_g2_subgroup_checks), with_final_exp))
+}
+
+/// BLS signature check is a product of two pairings, all four points are always checked to be in the subgroup
+fn parse_bls_signature(bytes: &[u8], g1_encoding_len: usize, g2_encoding_len: usize) -> Result<(usize, (usize, usize), bool), ApiError> {
+    let (public_key_in_g1, rest) = decode_bls_public_key_in_g1(bytes)?;
+    let (public_key_encoding_len, signature_encoding_len) = if public_key_in_g1 {
+        (g1_encoding_len, g2_encoding_len)
+    } else {
+        (g2_encoding_len, g1_encoding_len)
+    };
+
+    let (_, rest) = split(rest, checked_len_mul(public_key_encoding_len, 2)?, "Input is not long enough to get generator and public key")?;
+    let (_, rest) = split(rest, checked_len_mul(signature_encoding_len, 2)?, "Input is not long enough to get message and signature")?;
+    if !rest.is_empty() {
+        return Err(ApiError::garbage_at_the_end(rest));
+    }
+
+    Ok((2, (2, 2), true))
+}
+
+/// KZG opening check is a product of two pairings over the generators G1 and G2, tau times the generator of G2,
+/// the commitment and the proof, that are always checked to be in the subgroup. Multiplications
+/// by the evaluation point and the value are priced by the caller
+fn parse_kzg_opening(bytes: &[u8], g1_encoding_len: usize, g2_encoding_len: usize, order_len: usize) -> Result<(usize, (usize, usize), bool), ApiError> {
+    let (_, rest) = split(bytes, g1_encoding_len, "Input is not long enough to get G1 generator")?;
+    let (_, rest) = split(rest, checked_len_mul(g2_encoding_len, 2)?, "Input is not long enough to get G2 generator and its tau multiple")?;
+    let (_, rest) = split(rest, g1_encoding_len, "Input is not long enough to get commitment")?;
+    let (_, rest) = split(rest, checked_len_mul(order_len, 2)?, "Input is not long enough to get evaluation point and value")?;
+    let (_, rest) = split(rest, g1_encoding_len, "Input is not long enough to get proof")?;
+    if !rest.is_empty() {
+        return Err(ApiError::garbage_at_the_end(rest));
+    }
+
+    Ok((2, (3, 2), true))
+}
+
+/// Checks the list of Miller loop values passed to the final exponentiation,
+/// each value is an element of Fp12, Fp6 or Fp4 of `value_encoding_len` bytes
+fn parse_miller_loop_values(bytes: &[u8], value_encoding_len: usize) -> Result<&[u8], ApiError> {
+    let (num_values_encoding, rest) = split(bytes, BYTES_FOR_LENGTH_ENCODING, "Input is not long enough to get number of Miller loop values")?;
+    let num_values = num_values_encoding[0] as usize;
+    if num_values == 0 {
+        return Err(ApiError::InputError("Zero Miller loop values encoded".to_owned()));
+    }
+
+    let values_len = checked_len_mul(value_encoding_len, num_values)?;
+    let (_, rest) = split(rest, values_len, "Input is not long enough to get Miller loop values")?;
+    if !rest.is_empty() {
+        return Err(ApiError::InputError("Input has garbage at the end for final exponentiation".to_owned()));
+    }
+
+    Ok(rest)
+}
+
+/// - modulus,
+/// - group order length,
+/// - number of pairs,
+/// - bit length and hamming weight of the ate loop count,
+/// - bit length and hamming weight of w0 of the final exponentiation,
+/// - bit length and hamming weight of w1 of the final exponentiation,
+/// - numbers of G1 and G2 subgroup checks,
+/// - if the final exponentiation is done
+type MntPairingParameters = (
     MaxFieldUint, 
     usize,
     usize,
@@ -85,19 +234,19 @@ pub(crate) fn parse_mnt_pairing_parameters<'a>(bytes: &'a [u8], ext_degree: usiz
     (u64, u64),
     (u64, u64),
     (usize, usize),
-    &'a [u8]), ApiError> 
-{
-    use crate::public_interface::sane_limits::*;
+    bool);
 
+pub(crate) fn parse_mnt_pairing_parameters(bytes: &[u8], ext_degree: usize, tail: PairingInputTail, limits: &Limits, encoding: PointEncoding) -> Result<MntPairingParameters, ApiError>
+{
     let ((modulus, modulus_len), rest) = get_base_field_params(&bytes)?;
     let (_, rest) = split(rest, modulus_len, "Input is not long enough to get A parameter")?;
     let (_, rest) = split(rest, modulus_len, "Input is not long enough to get B parameter")?;
 
-    let (order_len, _, rest) = parse_group_order_from_encoding(rest)?;
+    let (order_len, _, rest) = parse_nonzero_group_order_from_encoding(rest)?;
 
     let (_, rest) = split(rest, modulus_len, "Input is not long enough to get non-residue")?;
 
-    let (x, rest) = decode_loop_parameter_scalar_with_bit_limit(&rest, MAX_ATE_PAIRING_ATE_LOOP_COUNT)?;
+    let (x, rest) = decode_loop_parameter_scalar_with_bit_limit(rest, limits.max_ate_pairing_ate_loop_count)?;
     if x.is_zero() {
         return Err(ApiError::InputError("Ate pairing loop count parameters can not be zero".to_owned()));
     }
@@ -105,7 +254,7 @@ pub(crate) fn parse_mnt_pairing_parameters<'a>(bytes: &'a [u8], ext_degree: usiz
     let ate_loop_bits = x.bits();
     let ate_loop_hamming = calculate_hamming_weight(&x.as_ref());
 
-    if ate_loop_hamming > MAX_ATE_PAIRING_ATE_LOOP_COUNT_HAMMING {
+    if ate_loop_hamming > limits.max_ate_pairing_ate_loop_count_hamming {
         return Err(ApiError::InputError("Ate pairing loop has too large hamming weight".to_owned()));
     }
 
@@ -118,14 +267,14 @@ pub(crate) fn parse_mnt_pairing_parameters<'a>(bytes: &'a [u8], ext_degree: usiz
         },
     };
 
-    let (exp_w0, rest) = decode_loop_parameter_scalar_with_bit_limit(&rest, MAX_ATE_PAIRING_FINAL_EXP_W0_BIT_LENGTH)?;
+    let (exp_w0, rest) = decode_loop_parameter_scalar_with_bit_limit(rest, limits.max_ate_pairing_final_exp_w0_bit_length)?;
     if exp_w0.is_zero() {
         return Err(ApiError::InputError("Final exp w0 loop count parameters can not be zero".to_owned()));
     }
     let exp_w0_bits = exp_w0.bits();
     let exp_w0_hamming = calculate_hamming_weight(&exp_w0.as_ref());
 
-    let (exp_w1, rest) = decode_loop_parameter_scalar_with_bit_limit(&rest, MAX_ATE_PAIRING_FINAL_EXP_W1_BIT_LENGTH)?;
+    let (exp_w1, rest) = decode_loop_parameter_scalar_with_bit_limit(rest, limits.max_ate_pairing_final_exp_w1_bit_length)?;
     if exp_w1.is_zero() {
         return Err(ApiError::InputError("Final exp w1 loop count parameters can not be zero".to_owned()));
     }
@@ -141,41 +290,27 @@ pub(crate) fn parse_mnt_pairing_parameters<'a>(bytes: &'a [u8], ext_degree: usiz
         },
     };
 
-    let (num_pairs_encoding, rest) = split(rest, BYTES_FOR_LENGTH_ENCODING, "Input is not long enough to get number of pairs")?;
-    let num_pairs = num_pairs_encoding[0] as usize;
-
-    if num_pairs == 0 {
-        return Err(ApiError::InputError("Zero pairs encoded".to_owned()));
+    if tail == PairingInputTail::MillerLoopValues {
+        let value_encoding_len = checked_len_mul(checked_len_mul(modulus_len, 2)?, ext_degree)?;
+        parse_miller_loop_values(rest, value_encoding_len)?;
+
+        return Ok(
+            (
+                modulus,
+                order_len,
+                0,
+                (ate_loop_bits as u64, ate_loop_hamming as u64),
+                (exp_w0_bits as u64, exp_w0_hamming as u64),
+                (exp_w1_bits as u64, exp_w1_hamming as u64),
+                (0, 0),
+                true
+            )
+        );
     }
-    
-    let mut num_g1_subgroup_checks = 0;
-    let mut num_g2_subgroup_checks = 0;
 
-    let mut grobal_rest = rest;
-
-    if num_pairs == 0 {
-        return Err(ApiError::InputError("Zero pairs encoded".to_owned()));
-    }
-
-    for _ in 0..num_pairs {
-        let (check_g1, rest) = decode_boolean(&grobal_rest)?;
-        let (_, rest) = split(rest, modulus_len*2, "input is not long enough to get G1 point encoding")?;
-        let (check_g2, rest) = decode_boolean(&rest)?;
-        let (_, rest) = split(rest, modulus_len*2*ext_degree, "input is not long enough to get G2 point encoding")?;
-        grobal_rest = rest;
-
-        if check_g1 {
-            num_g1_subgroup_checks += 1;
-        }
-
-        if check_g2 {
-            num_g2_subgroup_checks += 1;
-        }
-    }
-
-    if grobal_rest.len() != 0 {
-        return Err(ApiError::InputError("Input has garbage at the end for MNT4/6 pairing".to_owned()));
-    }
+    let g1_encoding_len = point_len(modulus_len, encoding)?;
+    let g2_encoding_len = point_len(checked_len_mul(modulus_len, ext_degree)?, encoding)?;
+    let (num_pairs, (num_g1_subgroup_checks, num_g2_subgroup_checks), with_final_exp) = parse_pairs(rest, g1_encoding_len, g2_encoding_len, order_len, tail)?;
 
     Ok(
         (
@@ -186,19 +321,31 @@ pub(crate) fn parse_mnt_pairing_parameters<'a>(bytes: &'a [u8], ext_degree: usiz
             (exp_w0_bits as u64, exp_w0_hamming as u64),
             (exp_w1_bits as u64, exp_w1_hamming as u64),
             (num_g1_subgroup_checks, num_g2_subgroup_checks),
-            rest
+            with_final_exp
         )
     )
 }
 
-pub(crate) fn parse_bls12_bn_pairing_parameters<'a>(bytes: &'a [u8], max_x_bit_limit: usize) -> Result<(
+/// - modulus,
+/// - group order length,
+/// - number of pairs,
+/// - loop parameter,
+/// - if the loop parameter is negative,
+/// - numbers of G1 and G2 subgroup checks,
+/// - if the final exponentiation is done
+type Bls12BnPairingParameters = (
     MaxFieldUint, 
     usize,
     usize,
     MaxLoopParametersUint,
     bool,
     (usize, usize),
-    &'a [u8]), ApiError> 
+    bool);
+
+/// Parses BLS12, BN, BLS24, BLS48, KSS16 and KSS18 pairing calls. They only differ by the degree of the field
+/// the twist is defined over (Fp2, Fp3, Fp4 or Fp8), that also defines the length of the twist non-residue
+/// and of G2 points, and by the embedding degree that defines the length of the Miller loop values
+pub(crate) fn parse_bls12_bn_pairing_parameters(bytes: &[u8], max_x_bit_limit: usize, twist_degree: usize, embedding_degree: usize, tail: PairingInputTail, encoding: PointEncoding) -> Result<Bls12BnPairingParameters, ApiError>
 {
     use crate::pairings::TwistType;
 
@@ -206,16 +353,19 @@ pub(crate) fn parse_bls12_bn_pairing_parameters<'a>(bytes: &'a [u8], max_x_bit_l
     let (_, rest) = split(rest, modulus_len, "Input is not long enough to get A parameter")?;
     let (_, rest) = split(rest, modulus_len, "Input is not long enough to get B parameter")?;
 
-    let (order_len, _, rest) = parse_group_order_from_encoding(rest)?;
+    let (order_len, _, rest) = parse_nonzero_group_order_from_encoding(rest)?;
     
     let (_, rest) = split(rest, modulus_len, "Input is not long enough to get Fp2 non-residue")?;
-    let (_, rest) = split(rest, modulus_len*2, "Input is not long enough to get Fp6/Fp12 non-residue")?;
+    let (_, rest) = split(rest, checked_len_mul(modulus_len, twist_degree)?, "Input is not long enough to get Fp6/Fp12 non-residue")?;
 
     let (twist_type_encoding, rest) = split(rest, TWIST_TYPE_LENGTH, "Input is not long enough to get twist type")?;
 
+    // BLS12 and BN twists are over Fp2 and may be detected from G2 points
+    let twist_type_may_be_detected = twist_degree == 2 && embedding_degree == 12;
     let _ = match twist_type_encoding[0] {
-        TWIST_TYPE_D => TwistType::D,
-        TWIST_TYPE_M => TwistType::M, 
+        TWIST_TYPE_D => Some(TwistType::D),
+        TWIST_TYPE_M => Some(TwistType::M), 
+        TWIST_TYPE_DETECT if twist_type_may_be_detected => None,
         _ => {
             return Err(ApiError::UnknownParameter("Unknown twist type supplied".to_owned()));
         },
@@ -235,47 +385,232 @@ pub(crate) fn parse_bls12_bn_pairing_parameters<'a>(bytes: &'a [u8], max_x_bit_l
         },
     };
 
-    let (num_pairs_encoding, rest) = split(rest, BYTES_FOR_LENGTH_ENCODING, "Input is not long enough to get number of pairs")?;
-    let num_pairs = num_pairs_encoding[0] as usize;
+    if tail == PairingInputTail::MillerLoopValues {
+        parse_miller_loop_values(rest, checked_len_mul(modulus_len, embedding_degree)?)?;
+
+        return Ok(
+            (
+                modulus,
+                order_len,
+                0,
+                x,
+                x_is_negative,
+                (0, 0),
+                true
+            )
+        );
+    }
 
-    let mut num_g1_subgroup_checks = 0;
-    let mut num_g2_subgroup_checks = 0;
+    let g1_encoding_len = point_len(modulus_len, encoding)?;
+    let g2_encoding_len = point_len(checked_len_mul(modulus_len, twist_degree)?, encoding)?;
+    let (num_pairs, (num_g1_subgroup_checks, num_g2_subgroup_checks), with_final_exp) = parse_pairs(rest, g1_encoding_len, g2_encoding_len, order_len, tail)?;
 
-    let mut grobal_rest = rest;
+    Ok(
+        (
+            modulus,
+            order_len,
+            num_pairs,
+            x,
+            x_is_negative,
+            (num_g1_subgroup_checks, num_g2_subgroup_checks),
+            with_final_exp
+        )
+    )
+}
 
-    if num_pairs == 0 {
-        return Err(ApiError::InputError("Zero pairs encoded".to_owned()));
-    }
+/// - modulus,
+/// - group order,
+/// - group order length,
+/// - number of pairs,
+/// - bit length and hamming weight of the ate loop count,
+/// - numbers of G1 and G2 subgroup checks,
+/// - if the final exponentiation is done
+type Bw6PairingParameters = (
+    MaxFieldUint, 
+    MaxGroupSizeUint,
+    usize,
+    usize,
+    (u64, u64),
+    (usize, usize),
+    bool);
 
-    for _ in 0..num_pairs {
-        let (check_g1, rest) = decode_boolean(&grobal_rest)?;
-        let (_, rest) = split(rest, modulus_len*2, "input is not long enough to get G1 point encoding")?;
-        let (check_g2, rest) = decode_boolean(&rest)?;
-        let (_, rest) = split(rest, modulus_len*4, "input is not long enough to get G2 point encoding")?;
-        grobal_rest = rest;
+/// Parses BW6 pairing calls. Both G1 and G2 points are over the base field and there are
+/// two ate loop counts, so the returned bit length and hamming weight are the sums over both loops
+pub(crate) fn parse_bw6_pairing_parameters(bytes: &[u8], tail: PairingInputTail, limits: &Limits, encoding: PointEncoding) -> Result<Bw6PairingParameters, ApiError>
+{
+    use crate::pairings::TwistType;
 
-        if check_g1 {
-            num_g1_subgroup_checks += 1;
+    let ((modulus, modulus_len), rest) = get_base_field_params(bytes)?;
+    let (_, rest) = split(rest, modulus_len, "Input is not long enough to get A parameter")?;
+    let (_, rest) = split(rest, modulus_len, "Input is not long enough to get B parameter")?;
+
+    let (order_len, order, rest) = parse_nonzero_group_order_from_encoding(rest)?;
+
+    let (_, rest) = split(rest, modulus_len, "Input is not long enough to get Fp3 non-residue")?;
+
+    let (twist_type_encoding, rest) = split(rest, TWIST_TYPE_LENGTH, "Input is not long enough to get twist type")?;
+
+    let _ = match twist_type_encoding[0] {
+        TWIST_TYPE_D => TwistType::D,
+        TWIST_TYPE_M => TwistType::M, 
+        _ => {
+            return Err(ApiError::UnknownParameter("Unknown twist type supplied".to_owned()));
+        },
+    };
+
+    let mut ate_loop_bits = 0u64;
+    let mut ate_loop_hamming = 0u64;
+    let mut rest = rest;
+
+    for _ in 0..2 {
+        let (loop_count, r) = decode_loop_parameter_scalar_with_bit_limit(rest, limits.max_ate_pairing_ate_loop_count)?;
+        if loop_count.is_zero() {
+            return Err(ApiError::InputError("Ate pairing loop count parameters can not be zero".to_owned()));
         }
 
-        if check_g2 {
-            num_g2_subgroup_checks += 1;
+        let hamming = calculate_hamming_weight(loop_count.as_ref());
+        if hamming > limits.max_ate_pairing_ate_loop_count_hamming {
+            return Err(ApiError::InputError("Ate pairing loop has too large hamming weight".to_owned()));
         }
+
+        ate_loop_bits += loop_count.bits() as u64;
+        ate_loop_hamming += hamming as u64;
+
+        let (loop_count_sign, r) = split(r, SIGN_ENCODING_LENGTH, "Input is not long enough to get ate loop count sign encoding")?;
+        let _ = match loop_count_sign[0] {
+            SIGN_PLUS => false,
+            SIGN_MINUS => true,
+            _ => {
+                return Err(ApiError::InputError("Ate loop count sign is not encoded properly".to_owned()));
+            },
+        };
+
+        rest = r;
+    }
+
+    if tail == PairingInputTail::MillerLoopValues {
+        parse_miller_loop_values(rest, checked_len_mul(modulus_len, 6)?)?;
+
+        return Ok(
+            (
+                modulus,
+                order,
+                order_len,
+                0,
+                (ate_loop_bits, ate_loop_hamming),
+                (0, 0),
+                true
+            )
+        );
     }
 
-    if grobal_rest.len() != 0 {
-        return Err(ApiError::InputError("Input has garbage at the end for BLS12/BN pairing".to_owned()));
+    let point_encoding_len = point_len(modulus_len, encoding)?;
+    let (num_pairs, (num_g1_subgroup_checks, num_g2_subgroup_checks), with_final_exp) = parse_pairs(rest, point_encoding_len, point_encoding_len, order_len, tail)?;
+
+    Ok(
+        (
+            modulus,
+            order,
+            order_len,
+            num_pairs,
+            (ate_loop_bits, ate_loop_hamming),
+            (num_g1_subgroup_checks, num_g2_subgroup_checks),
+            with_final_exp
+        )
+    )
+}
+
+/// - modulus,
+/// - group order length,
+/// - embedding degree,
+/// - number of pairs,
+/// - bit length and hamming weight of the ate loop count,
+/// - bit length and hamming weight of the hard part of the final exponentiation,
+/// - numbers of G1 and G2 subgroup checks,
+/// - if the final exponentiation is done
+type CocksPinchPairingParameters = (
+    MaxFieldUint, 
+    usize,
+    usize,
+    usize,
+    (u64, u64),
+    (u64, u64),
+    (usize, usize),
+    bool);
+
+/// Parses Cocks-Pinch pairing calls. Returns the embedding degree, bit lengths and hamming weights
+/// of the ate loop count and of the hard part of the final exponentiation
+pub(crate) fn parse_cocks_pinch_pairing_parameters(bytes: &[u8], tail: PairingInputTail, limits: &Limits, encoding: PointEncoding) -> Result<CocksPinchPairingParameters, ApiError>
+{
+    let ((modulus, modulus_len), rest) = get_base_field_params(bytes)?;
+    let (_, rest) = split(rest, modulus_len, "Input is not long enough to get A parameter")?;
+    let (_, rest) = split(rest, modulus_len, "Input is not long enough to get B parameter")?;
+
+    let (order_len, _, rest) = parse_nonzero_group_order_from_encoding(rest)?;
+
+    let (embedding_degree, rest) = decode_embedding_degree(rest, limits.max_cocks_pinch_embedding_degree)?;
+
+    let (_, rest) = split(rest, modulus_len, "Input is not long enough to get Fp^k non-residue")?;
+
+    let (ate_loop_count, rest) = decode_loop_parameter_scalar_with_bit_limit(rest, limits.max_ate_pairing_ate_loop_count)?;
+    if ate_loop_count.is_zero() {
+        return Err(ApiError::InputError("Ate pairing loop count parameters can not be zero".to_owned()));
+    }
+
+    let ate_loop_bits = ate_loop_count.bits();
+    let ate_loop_hamming = calculate_hamming_weight(ate_loop_count.as_ref());
+
+    if ate_loop_hamming > limits.max_ate_pairing_ate_loop_count_hamming {
+        return Err(ApiError::InputError("Ate pairing loop has too large hamming weight".to_owned()));
+    }
+
+    let (_, rest) = decode_sign_is_negative(rest)?;
+
+    let (hard_part, rest) = decode_loop_parameter_scalar_with_bit_limit(rest, limits.max_cocks_pinch_final_exp_bit_length)?;
+    if hard_part.is_zero() {
+        return Err(ApiError::InputError("Final exponentiation exponent can not be zero".to_owned()));
+    }
+
+    let hard_part_bits = hard_part.bits();
+    let hard_part_hamming = calculate_hamming_weight(hard_part.as_ref());
+
+    if hard_part_hamming > limits.max_cocks_pinch_final_exp_hamming {
+        return Err(ApiError::InputError("Final exponentiation exponent has too large hamming weight".to_owned()));
+    }
+
+    let value_encoding_len = checked_len_mul(modulus_len, embedding_degree)?;
+
+    if tail == PairingInputTail::MillerLoopValues {
+        parse_miller_loop_values(rest, value_encoding_len)?;
+
+        return Ok(
+            (
+                modulus,
+                order_len,
+                embedding_degree,
+                0,
+                (ate_loop_bits as u64, ate_loop_hamming as u64),
+                (hard_part_bits as u64, hard_part_hamming as u64),
+                (0, 0),
+                true
+            )
+        );
     }
 
+    let g1_encoding_len = point_len(modulus_len, encoding)?;
+    let g2_encoding_len = point_len(value_encoding_len, encoding)?;
+    let (num_pairs, (num_g1_subgroup_checks, num_g2_subgroup_checks), with_final_exp) = parse_pairs(rest, g1_encoding_len, g2_encoding_len, order_len, tail)?;
+
     Ok(
         (
             modulus,
             order_len,
+            embedding_degree,
             num_pairs,
-            x,
-            x_is_negative,
+            (ate_loop_bits as u64, ate_loop_hamming as u64),
+            (hard_part_bits as u64, hard_part_hamming as u64),
             (num_g1_subgroup_checks, num_g2_subgroup_checks),
-            rest
+            with_final_exp
         )
     )
 }
@@ -1,28 +1,44 @@
 #![allow(dead_code)]
 
 #![cfg_attr(feature = "benchmarks", feature(test))]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg_attr(not(feature = "std"), macro_use)]
+extern crate alloc;
 
 extern crate byteorder;
 extern crate eth_pairings_repr_derive;
 extern crate fixed_width_field;
 extern crate fixed_width_group_and_loop;
 
+mod alloc_prelude;
 mod arithmetics;
+#[cfg(all(feature = "asm", target_arch = "x86_64"))]
+mod asm;
+#[cfg(all(feature = "simd", any(target_arch = "x86_64", target_arch = "aarch64")))]
+mod simd;
 pub mod traits;
 pub mod representation;
 pub mod field;
 pub mod fp;
 pub mod weierstrass;
+pub mod edwards;
+pub mod montgomery;
+pub mod binary_field;
 mod mont_inverse;
 mod multiexp;
 pub mod extension_towers;
 pub mod pairings;
 mod sliding_window_exp;
 mod errors;
+pub mod execution_report;
+mod operation_budget;
+mod execution_context;
 pub mod integers;
 mod features;
 mod wnaf;
 pub mod square_root;
+mod primality;
 pub mod engines;
 
 #[cfg(feature = "mappings")]
@@ -33,9 +49,21 @@ pub mod public_interface;
 #[cfg(feature = "gas_metering")]
 pub mod gas_meter;
 
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(feature = "server")]
+pub mod server;
+
+#[cfg(feature = "python")]
+mod python;
+
 #[cfg(feature = "external_tests")]
 pub mod external_tests;
 
+#[cfg(any(test, feature = "test_utils"))]
+pub mod test_utils;
+
 #[cfg(test)]
 mod test;
 
@@ -58,6 +86,7 @@ mod tests {
     use crate::weierstrass::curve::*;
     use crate::traits::FieldElement;
     use crate::multiexp::{peppinger};
+    use crate::execution_context::ExecutionContext;
     use crate::weierstrass::Group;
     use crate::traits::ZeroAndOne;
     use crate::weierstrass::{CurveOverFpParameters};
@@ -175,12 +204,84 @@ mod tests {
             acc.into_xy()
         };
 
-        let ben_coster_res = peppinger(&bases, scalars).into_xy();
+        let ben_coster_res = peppinger(&bases, scalars, &ExecutionContext::default()).into_xy();
 
         assert!(ben_coster_res.0 == naive_res.0);
         assert!(ben_coster_res.1 == naive_res.1);
     }
 
+    #[test]
+    fn test_batch_normalize() {
+        use crate::engines::bls12_381::*;
+        use crate::public_interface::decode_g1::{serialize_g1_point, serialize_g1_points};
+        use rand::{Rng, SeedableRng};
+        use rand_xorshift::XorShiftRng;
+
+        let rng = &mut XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        let mut points: Vec<_> = (0..16).map(|_| BLS12_381_G1_GENERATOR.mul(&[rng.gen::<u64>()])).collect();
+        points[3] = CurvePoint::zero(&BLS12_381_G1_CURVE);
+        points[7] = BLS12_381_G1_GENERATOR.clone();
+        let expected: Vec<_> = points.iter().map(|p| p.into_xy()).collect();
+        let expected_encoding: Vec<u8> = points.iter().flat_map(|p| serialize_g1_point(48, p).unwrap()).collect();
+        assert!(points.iter().any(|p| !p.is_normalized()));
+
+        let scalars: Vec<_> = (0..16).map(|_| MaxGroupSizeUint::from(rng.gen::<u64>())).collect();
+        let naive_res = {
+            let mut acc = CurvePoint::zero(&BLS12_381_G1_CURVE);
+            for (s, g) in scalars.iter().zip(points.iter()) {
+                acc.add_assign(&g.mul(s.as_ref()));
+            }
+
+            acc.into_xy()
+        };
+        // projective bases
+        assert!(peppinger(&points, scalars.clone(), &ExecutionContext::default()).into_xy() == naive_res);
+
+        let mut normalized = points.clone();
+        batch_normalize(&mut normalized);
+        assert!(normalized.iter().all(|p| p.is_normalized()));
+        for (p, (x, y)) in normalized.iter().zip(expected.iter()) {
+            assert!(p.is_zero() || (p.x == *x && p.y == *y));
+        }
+        assert!(normalized[3].is_zero());
+
+        assert_eq!(serialize_g1_points(48, &mut points).unwrap(), expected_encoding);
+
+        batch_normalize(&mut normalized[..0]);
+    }
+
+    #[test]
+    fn test_sum_points() {
+        use crate::engines::bls12_381::*;
+        use rand::{Rng, SeedableRng};
+        use rand_xorshift::XorShiftRng;
+
+        let rng = &mut XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        let mut points: Vec<_> = (0..13).map(|_| {
+            let mut p = BLS12_381_G1_GENERATOR.mul(&[rng.gen::<u64>()]);
+            p.normalize();
+
+            p
+        }).collect();
+        // zero, a pair that needs doubling and a pair that cancels out
+        points[2] = CurvePoint::zero(&BLS12_381_G1_CURVE);
+        points[9] = points[4].clone();
+        let mut negated = points[5].clone();
+        negated.negate();
+        points[11] = negated;
+
+        for num_points in 0..points.len() {
+            let mut naive = CurvePoint::zero(&BLS12_381_G1_CURVE);
+            for p in points[..num_points].iter() {
+                naive.add_assign(p);
+            }
+
+            let sum = sum_points(&BLS12_381_G1_CURVE, points[..num_points].to_vec(), &ExecutionContext::default());
+            assert_eq!(sum.is_zero(), naive.is_zero());
+            assert!(sum.is_zero() || sum.into_xy() == naive.into_xy(), "{} points", num_points);
+        }
+    }
+
     #[test]
     fn test_wnaf_decomposition() {
         use crate::representation::ElementRepr;
@@ -255,6 +356,113 @@ mod tests {
         assert!(should_be_zero.is_zero());
     }
 
+    #[test]
+    fn test_mul_matches_double_and_add_for_any_scalar_length() {
+        use crate::engines::bls12_381::*;
+        use rand::{Rng, SeedableRng};
+        use rand_xorshift::XorShiftRng;
+
+        let rng = &mut XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        for num_limbs in 1..17 {
+            let mut scalar: Vec<u64> = (0..num_limbs).map(|_| rng.gen()).collect();
+            let g1 = BLS12_381_G1_GENERATOR.mul(&[rng.gen::<u64>()]);
+            let g2 = BLS12_381_G2_GENERATOR.mul(&[rng.gen::<u64>()]);
+            for _ in 0..2 {
+                assert!(g1.mul(&scalar).into_xy() == g1.mul_impl(&scalar).into_xy());
+                assert!(g2.mul(&scalar).into_xy() == g2.mul_impl(&scalar).into_xy());
+
+                // top limbs full of ones make the highest wNAF digit carry out
+                for limb in scalar.iter_mut() {
+                    *limb = u64::max_value();
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_constant_time_mul_matches_double_and_add() {
+        use crate::engines::bls12_381::*;
+        use rand::{Rng, SeedableRng};
+        use rand_xorshift::XorShiftRng;
+
+        let rng = &mut XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        let g1 = BLS12_381_G1_GENERATOR.mul(&[rng.gen::<u64>()]);
+        let g2 = BLS12_381_G2_GENERATOR.mul(&[rng.gen::<u64>()]);
+
+        assert!(g1.mul_constant_time_impl(&[0u64; 4]).is_zero());
+        assert!(g2.mul_constant_time_impl(&[0u64; 4]).is_zero());
+
+        for num_limbs in 1..5 {
+            let scalar: Vec<u64> = (0..num_limbs).map(|_| rng.gen()).collect();
+            assert!(g1.mul_constant_time_impl(&scalar).into_xy() == g1.mul_impl(&scalar).into_xy());
+            assert!(g2.mul_constant_time_impl(&scalar).into_xy() == g2.mul_impl(&scalar).into_xy());
+        }
+
+        // complete formulas take the point at infinity and equal points in the same way
+        let zero = crate::weierstrass::curve::CurvePoint::zero(g1.curve);
+        assert!(zero.mul_constant_time_impl(&[rng.gen::<u64>()]).is_zero());
+        assert!(g1.mul_constant_time_impl(BLS12_381_G1_CURVE.subgroup_order_repr).is_zero());
+
+        let scalars: Vec<Vec<u64>> = (0..3).map(|_| vec![rng.gen::<u64>(), rng.gen::<u64>()]).collect();
+        let bases = vec![g1.clone(), g1.clone(), BLS12_381_G1_GENERATOR.clone()];
+        let mut expected = g1.mul_impl(&scalars[0]);
+        expected.add_assign(&g1.mul_impl(&scalars[1]));
+        expected.add_assign(&BLS12_381_G1_GENERATOR.mul_impl(&scalars[2]));
+        assert!(crate::weierstrass::curve::CurvePoint::multiexp_constant_time(&bases, &scalars).into_xy() == expected.into_xy());
+    }
+
+    #[test]
+    fn test_coordinate_system_for_operation() {
+        use crate::weierstrass::{CoordinateSystem, PointOperation};
+
+        let expected = if crate::features::in_constant_time() {
+            CoordinateSystem::Projective
+        } else {
+            CoordinateSystem::Jacobian
+        };
+        for operation in [PointOperation::Mul, PointOperation::FixedBaseMul, PointOperation::Multiexp].iter() {
+            assert_eq!(CoordinateSystem::for_operation(*operation), expected);
+        }
+    }
+
+    #[test]
+    fn test_doubling_for_a_is_minus_3() {
+        // NIST P-256
+        let field = new_field::<U320Repr>("ffffffff00000001000000000000000000000000ffffffffffffffffffffffff", 16).unwrap();
+        let group_order = BigUint::from_str_radix("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551", 16).unwrap();
+        let group_order = biguint_to_u64_vec(group_order);
+        let one = Fp::one(&field);
+        let mut a_coeff = one.clone();
+        a_coeff.double();
+        a_coeff.add_assign(&one);
+        a_coeff.negate();
+        let b_coeff = Fp::from_be_bytes(&field, &hex::decode("5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b").unwrap(), true).unwrap();
+        let x = Fp::from_be_bytes(&field, &hex::decode("6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296").unwrap(), true).unwrap();
+        let y = Fp::from_be_bytes(&field, &hex::decode("4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5").unwrap(), true).unwrap();
+
+        let params = CurveOverFpParameters::new(&field);
+        let curve = WeierstrassCurve::new(&group_order, a_coeff, b_coeff, &params).unwrap();
+        assert!(curve.curve_type == crate::weierstrass::CurveType::AIsMinus3);
+
+        let point = CurvePoint::point_from_xy(&curve, x, y);
+        assert!(point.is_on_curve());
+
+        let mut p = point.mul(&[0x1234567890abcdefu64]);
+        for _ in 0..10 {
+            let mut expected = p.clone();
+            expected.double_generic_impl();
+            p.double();
+            assert!(p.into_xy() == expected.into_xy());
+        }
+
+        assert!(point.mul(&group_order[..]).is_zero());
+
+        // complete formulas for secret scalars take A into account
+        let scalar = [0x1234567890abcdefu64, 0xfedcba0987654321u64];
+        assert!(point.mul_constant_time_impl(&scalar).into_xy() == point.mul_impl(&scalar).into_xy());
+        assert!(point.mul_constant_time_impl(&group_order[..]).is_zero());
+    }
+
     #[test]
     fn test_behavior_of_inversion() {
         // make a ring using modulus that is two primes product
@@ -0,0 +1,325 @@
+//! Hashing of arbitrary messages to field elements from RFC 9380: `expand_message_xmd`
+//! (section 5.3.1) with SHA-256 or SHA-512 and `hash_to_field` (section 5.2) that reduces
+//! `L = ceil((ceil(log2(p)) + k) / 8)` uniform bytes per base field element, so the result
+//! is statistically close to uniform with the security parameter `k`.
+//! Reduction is done by Horner's rule in the field over chunks that are one byte shorter than
+//! the modulus, so every chunk is already reduced and no wide integer arithmetic is needed
+
+use crate::fp::Fp;
+use crate::representation::ElementRepr;
+use crate::field::SizedPrimeField;
+use crate::traits::{FieldElement, ZeroAndOne};
+use crate::extension_towers::fp2::{Fp2, Extension2};
+
+extern crate sha2;
+use self::sha2::{Digest, Sha256, Sha512};
+
+/// Longest domain separation tag, longer ones should be hashed with `reduce_oversize_dst`
+pub const MAX_DST_LEN: usize = 255;
+/// `len_in_bytes` is encoded in two bytes
+pub const MAX_EXPANDED_LEN: usize = 65535;
+/// Counter of hash invocations is encoded in one byte
+pub const MAX_NUM_HASH_BLOCKS: usize = 255;
+
+const OVERSIZE_DST_PREFIX: &[u8] = b"H2C-OVERSIZE-DST-";
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum HashFunction {
+    Sha256,
+    Sha512
+}
+
+impl HashFunction {
+    /// `b_in_bytes` of the RFC
+    pub fn output_len(&self) -> usize {
+        match self {
+            HashFunction::Sha256 => 32,
+            HashFunction::Sha512 => 64,
+        }
+    }
+
+    /// `s_in_bytes` of the RFC
+    pub fn block_len(&self) -> usize {
+        match self {
+            HashFunction::Sha256 => 64,
+            HashFunction::Sha512 => 128,
+        }
+    }
+
+    fn hash(&self, parts: &[&[u8]]) -> Vec<u8> {
+        match self {
+            HashFunction::Sha256 => {
+                let mut hasher = Sha256::new();
+                for p in parts.iter() {
+                    hasher.update(p);
+                }
+
+                hasher.finalize().to_vec()
+            },
+            HashFunction::Sha512 => {
+                let mut hasher = Sha512::new();
+                for p in parts.iter() {
+                    hasher.update(p);
+                }
+
+                hasher.finalize().to_vec()
+            }
+        }
+    }
+}
+
+/// DST that should be used instead of the one longer than `MAX_DST_LEN` (section 5.3.3)
+pub fn reduce_oversize_dst(dst: &[u8], hash: HashFunction) -> Vec<u8> {
+    hash.hash(&[OVERSIZE_DST_PREFIX, dst])
+}
+
+/// `len_in_bytes` uniformly random bytes from the message and the domain separation tag.
+/// `None` if the requested length is zero or too large for the hash function, or if the
+/// tag is longer than `MAX_DST_LEN`
+pub fn expand_message_xmd(msg: &[u8], dst: &[u8], len_in_bytes: usize, hash: HashFunction) -> Option<Vec<u8>> {
+    let b_in_bytes = hash.output_len();
+    let ell = (len_in_bytes + b_in_bytes - 1) / b_in_bytes;
+    if len_in_bytes == 0 || len_in_bytes > MAX_EXPANDED_LEN || ell > MAX_NUM_HASH_BLOCKS {
+        return None;
+    }
+    if dst.len() > MAX_DST_LEN {
+        return None;
+    }
+
+    let dst_len = [dst.len() as u8];
+    let z_pad = vec![0u8; hash.block_len()];
+    let l_i_b_str = (len_in_bytes as u16).to_be_bytes();
+
+    let b_0 = hash.hash(&[&z_pad, msg, &l_i_b_str, &[0u8], dst, &dst_len]);
+
+    let mut b_i = hash.hash(&[&b_0, &[1u8], dst, &dst_len]);
+    let mut uniform_bytes = Vec::with_capacity(ell * b_in_bytes);
+    uniform_bytes.extend_from_slice(&b_i);
+    for i in 2..=ell {
+        let xored: Vec<u8> = b_0.iter().zip(b_i.iter()).map(|(a, b)| a ^ b).collect();
+        b_i = hash.hash(&[&xored, &[i as u8], dst, &dst_len]);
+        uniform_bytes.extend_from_slice(&b_i);
+    }
+    uniform_bytes.truncate(len_in_bytes);
+
+    Some(uniform_bytes)
+}
+
+/// Number of uniform bytes `L` per base field element for the security parameter `k` in bits
+pub fn bytes_per_element<E: ElementRepr, F: SizedPrimeField<Repr = E>>(field: &F, security_bits: usize) -> usize {
+    let modulus_bits = field.modulus().num_bits() as usize;
+
+    (modulus_bits + security_bits + 7) / 8
+}
+
+/// Big endian integer of any length reduced modulo the field characteristic
+pub fn reduce_be_bytes<'a, E: ElementRepr, F: SizedPrimeField<Repr = E>>(bytes: &[u8], field: &'a F) -> Option<Fp<'a, E, F>> {
+    let modulus_bytes = (field.modulus().num_bits() as usize + 7) / 8;
+    if modulus_bytes <= 1 {
+        // neither 2^8 nor every byte value is below a one byte modulus, so bits are accumulated one by one
+        let one = Fp::one(field);
+        let mut result = Fp::zero(field);
+        for byte in bytes.iter() {
+            for i in (0..8).rev() {
+                result.double();
+                if (byte >> i) & 1 == 1 {
+                    result.add_assign(&one);
+                }
+            }
+        }
+
+        return Some(result);
+    }
+
+    // 2^(8 * chunk_len) < p since the top byte of an odd prime is not zero
+    let chunk_len = modulus_bytes - 1;
+
+    let mut shift_encoding = vec![0u8; chunk_len + 1];
+    shift_encoding[0] = 1;
+    let shift = Fp::from_be_bytes(field, &shift_encoding, true).ok()?;
+
+    let first_chunk_len = match bytes.len() % chunk_len {
+        0 => chunk_len,
+        l => l
+    };
+
+    let mut result = Fp::zero(field);
+    let mut start = 0;
+    let mut end = first_chunk_len;
+    while end <= bytes.len() {
+        let chunk = Fp::from_be_bytes(field, &bytes[start..end], true).ok()?;
+        result.mul_assign(&shift);
+        result.add_assign(&chunk);
+        start = end;
+        end += chunk_len;
+    }
+
+    Some(result)
+}
+
+/// `count` elements of the base field, `None` under the same conditions as for `expand_message_xmd`
+pub fn hash_to_field_fp<'a, E: ElementRepr, F: SizedPrimeField<Repr = E>>(
+    msg: &[u8],
+    dst: &[u8],
+    count: usize,
+    security_bits: usize,
+    hash: HashFunction,
+    field: &'a F
+) -> Option<Vec<Fp<'a, E, F>>> {
+    let l = bytes_per_element(field, security_bits);
+    let uniform_bytes = expand_message_xmd(msg, dst, count.checked_mul(l)?, hash)?;
+
+    let elements = uniform_bytes.chunks(l).map(|chunk| reduce_be_bytes(chunk, field)).collect::<Option<Vec<_>>>()?;
+
+    Some(elements)
+}
+
+/// `count` elements of the quadratic extension, coefficients are taken from consecutive
+/// chunks of uniform bytes starting from `c0`
+pub fn hash_to_field_fp2<'a, E: ElementRepr, F: SizedPrimeField<Repr = E>>(
+    msg: &[u8],
+    dst: &[u8],
+    count: usize,
+    security_bits: usize,
+    hash: HashFunction,
+    extension: &'a Extension2<'a, E, F>
+) -> Option<Vec<Fp2<'a, E, F>>> {
+    let coefficients = hash_to_field_fp(msg, dst, count.checked_mul(2)?, security_bits, hash, extension.field)?;
+
+    let elements = coefficients.chunks(2).map(|c| {
+        let mut el = Fp2::zero(extension);
+        el.c0 = c[0].clone();
+        el.c1 = c[1].clone();
+
+        el
+    }).collect();
+
+    Some(elements)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::engines::bls12_381::*;
+    use crate::mapping::constants::*;
+
+    const DST_SHA256: &[u8] = b"QUUX-V01-CS02-with-expander-SHA256-128";
+    const DST_SHA512: &[u8] = b"QUUX-V01-CS02-with-expander-SHA512-256";
+
+    #[test]
+    fn test_expand_message_xmd_sha256_vectors() {
+        // RFC 9380, appendix K.1
+        let vectors: [(&[u8], usize, &str); 4] = [
+            (b"", 0x20, "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235"),
+            (b"abc", 0x20, "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615"),
+            (b"abcdef0123456789", 0x20, "eff31487c770a893cfb36f912fbfcbff40d5661771ca4b2cb4eafe524333f5c1"),
+            (b"", 0x80, "af84c27ccfd45d41914fdff5df25293e221afc53d8ad2ac06d5e3e29485dadbee0d121587713a3e0dd4d5e69e93eb7cd4f5df4cd103e188cf60cb02edc3edf18eda8576c412b18ffb658e3dd6ec849469b979d444cf7b26911a08e63cf31f9dcc541708d3491184472c2c29bb749d4286b004ceb5ee6b9a7fa5b646c993f0ced"),
+        ];
+
+        for (msg, len, expected) in vectors.iter() {
+            let uniform_bytes = expand_message_xmd(msg, DST_SHA256, *len, HashFunction::Sha256).unwrap();
+            assert_eq!(hex::encode(&uniform_bytes), *expected);
+        }
+    }
+
+    #[test]
+    fn test_expand_message_xmd_sha512_vectors() {
+        // RFC 9380, appendix K.3
+        let vectors: [(&[u8], usize, &str); 2] = [
+            (b"", 0x20, "6b9a7312411d92f921c6f68ca0b6380730a1a4d982c507211a90964c394179ba"),
+            (b"abc", 0x20, "0da749f12fbe5483eb066a5f595055679b976e93abe9be6f0f6318bce7aca8dc"),
+        ];
+
+        for (msg, len, expected) in vectors.iter() {
+            let uniform_bytes = expand_message_xmd(msg, DST_SHA512, *len, HashFunction::Sha512).unwrap();
+            assert_eq!(hex::encode(&uniform_bytes), *expected);
+        }
+    }
+
+    #[test]
+    fn test_expand_message_xmd_limits() {
+        assert!(expand_message_xmd(b"abc", DST_SHA256, 0, HashFunction::Sha256).is_none());
+        assert!(expand_message_xmd(b"abc", DST_SHA256, 255 * 32, HashFunction::Sha256).is_some());
+        assert!(expand_message_xmd(b"abc", DST_SHA256, 255 * 32 + 1, HashFunction::Sha256).is_none());
+        assert!(expand_message_xmd(b"abc", DST_SHA512, MAX_EXPANDED_LEN + 1, HashFunction::Sha512).is_none());
+
+        let long_dst = vec![0x61u8; MAX_DST_LEN + 1];
+        assert!(expand_message_xmd(b"abc", &long_dst, 32, HashFunction::Sha256).is_none());
+        let reduced = reduce_oversize_dst(&long_dst, HashFunction::Sha256);
+        assert_eq!(reduced.len(), 32);
+        assert!(expand_message_xmd(b"abc", &reduced, 32, HashFunction::Sha256).is_some());
+    }
+
+    #[test]
+    fn test_reduce_be_bytes() {
+        let modulus = BLS12_381_FIELD.modulus();
+        let mut modulus_bytes = vec![];
+        for limb in modulus.as_ref().iter().rev() {
+            modulus_bytes.extend_from_slice(&limb.to_be_bytes());
+        }
+
+        // p reduces to zero, p + 1 to one and a zero prefix is ignored
+        assert!(reduce_be_bytes(&modulus_bytes, &BLS12_381_FIELD).unwrap().is_zero());
+        let mut p_plus_one = modulus_bytes.clone();
+        *p_plus_one.last_mut().unwrap() += 1;
+        assert_eq!(reduce_be_bytes(&p_plus_one, &BLS12_381_FIELD).unwrap(), BLS12_381_FP_ONE);
+        let mut padded = vec![0u8; 16];
+        padded.extend_from_slice(&p_plus_one);
+        assert_eq!(reduce_be_bytes(&padded, &BLS12_381_FIELD).unwrap(), BLS12_381_FP_ONE);
+
+        // 2^512 - 1 = (2^384) * (2^128 - 1) + (2^384 - 1)
+        let all_ones = vec![0xffu8; 64];
+        let mut expected = Fp::from_be_bytes(&BLS12_381_FIELD, &[0xffu8; 16], true).unwrap();
+        let two_to_384 = reduce_be_bytes(&{ let mut v = vec![1u8]; v.extend_from_slice(&[0u8; 48]); v }, &BLS12_381_FIELD).unwrap();
+        expected.mul_assign(&two_to_384);
+        let mut low = two_to_384.clone();
+        low.sub_assign(&BLS12_381_FP_ONE);
+        expected.add_assign(&low);
+        assert_eq!(reduce_be_bytes(&all_ones, &BLS12_381_FIELD).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_reduce_be_bytes_one_byte_modulus() {
+        use crate::field::{field_from_modulus, U256Repr};
+        use crate::integers::MaxFieldUint;
+
+        let field = field_from_modulus::<U256Repr>(&MaxFieldUint::from(13u64)).unwrap();
+        for bytes in [vec![], vec![12u8], vec![13u8], vec![0xffu8], vec![0x28, 0x61, 0x64], vec![0xffu8; 9]].iter() {
+            let expected = bytes.iter().fold(0u64, |acc, b| (acc * 256 + *b as u64) % 13);
+            let expected = Fp::from_be_bytes(&field, &[expected as u8], true).unwrap();
+            assert_eq!(reduce_be_bytes(bytes, &field).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_hash_to_field_bls12_381_g2_vector() {
+        // RFC 9380, appendix J.10.1, BLS12381G2_XMD:SHA-256_SSWU_RO_ with msg = ""
+        let dst = b"QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_RO_";
+        let u = hash_to_field_fp2(b"", dst, 2, 128, HashFunction::Sha256, &BLS12_381_EXTENSION_2_FIELD).unwrap();
+
+        let expected = [
+            ("03dbc2cce174e91ba93cbb08f26b917f98194a2ea08d1cce75b2b9cc9f21689d80bd79b594a613d0a68eb807dfdc1cf8", "05a2acec64114845711a54199ea339abd125ba38253b70a92c876df10598bd1986b739cad67961eb94f7076511b3b39a"),
+            ("02f99798e8a5acdeed60d7e18e9120521ba1f47ec090984662846bc825de191b5b7641148c0dbc237726a334473eee94", "145a81e418d4010cc027a68f14391b30074e89e60ee7a22f87217b2f6eb0c4b94c9115b436e6fa4607e95a98de30a435"),
+        ];
+        for (el, (c0, c1)) in u.iter().zip(expected.iter()) {
+            assert_eq!(el.c0, str_radix_into_field(c0, 16, &BLS12_381_FIELD));
+            assert_eq!(el.c1, str_radix_into_field(c1, 16, &BLS12_381_FIELD));
+        }
+    }
+
+    #[test]
+    fn test_hash_to_field_fp2_uses_consecutive_coefficients() {
+        let dst = b"QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_RO_";
+        let coefficients = hash_to_field_fp(b"abc", dst, 4, 128, HashFunction::Sha256, &BLS12_381_FIELD).unwrap();
+        let elements = hash_to_field_fp2(b"abc", dst, 2, 128, HashFunction::Sha256, &BLS12_381_EXTENSION_2_FIELD).unwrap();
+
+        for (el, c) in elements.iter().zip(coefficients.chunks(2)) {
+            assert_eq!(el.c0, c[0]);
+            assert_eq!(el.c1, c[1]);
+        }
+
+        // the requested length is hashed into every block, so all four elements are expanded at once
+        let uniform_bytes = expand_message_xmd(b"abc", dst, 4 * 64, HashFunction::Sha256).unwrap();
+        assert_eq!(coefficients[3], reduce_be_bytes(&uniform_bytes[(3 * 64)..], &BLS12_381_FIELD).unwrap());
+    }
+}
@@ -1,3 +1,8 @@
+//! Evaluation of isogeny maps given as rational functions, so SWU can be applied to an isogenous
+//! curve with non-zero A and B and the result moved to a target curve where A * B == 0.
+//! Coefficients are either supplied in the mapping call or built in for known curves
+//! (see `crate::mapping::constants`)
+
 use crate::traits::*;
 
 use crate::weierstrass::*;
@@ -43,8 +48,27 @@ pub(crate) fn apply_isogeny_map<
         y_den.add_assign(&params.k4[i]);
     }
 
-    let x_den = x_den.inverse().unwrap_or(C::BaseFieldElement::zero(curve_params.params()));
-    let y_den = y_den.inverse().unwrap_or(C::BaseFieldElement::zero(curve_params.params()));
+    // one inversion for both denominators, a zero denominator maps to zero like in the separate inversions
+    let (x_den, y_den) = {
+        let mut product = x_den.clone();
+        product.mul_assign(&y_den);
+        match product.inverse() {
+            Some(product_inv) => {
+                let mut x_den_inv = product_inv.clone();
+                x_den_inv.mul_assign(&y_den);
+                let mut y_den_inv = product_inv;
+                y_den_inv.mul_assign(&x_den);
+
+                (x_den_inv, y_den_inv)
+            },
+            None => {
+                (
+                    x_den.inverse().unwrap_or(C::BaseFieldElement::zero(curve_params.params())),
+                    y_den.inverse().unwrap_or(C::BaseFieldElement::zero(curve_params.params()))
+                )
+            }
+        }
+    };
 
     x_num.mul_assign(&x_den);
     y_num.mul_assign(&y_den);
@@ -5,8 +5,10 @@ use crate::traits::*;
 use crate::extension_towers::fp2::Fp2;
 
 pub mod simple_swu;
+pub mod svdw;
 pub mod isogeny;
 pub mod parameters;
+pub mod hash_to_field;
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub enum Sign {
@@ -49,7 +51,7 @@ fn sign_of_fp2<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >(
     }
 }
 
-mod constants {
+pub(crate) mod constants {
     use super::isogeny::*;
     use super::simple_swu::*;
     use crate::engines::bls12_381::*;
@@ -339,7 +341,7 @@ mod test {
             &random_el, 
             &swu, 
             &BLS12_381_G1_CURVE_ISOGENY
-        );
+        ).unwrap();
 
         let (x, y) = apply_isogeny_map(
             &x_prime,
@@ -361,7 +363,7 @@ mod test {
             &random_el, 
             &swu, 
             &BLS12_381_G2_CURVE_ISOGENY
-        );
+        ).unwrap();
 
         let (x, y) = apply_isogeny_map(
             &x_prime,
@@ -16,6 +16,7 @@ pub struct SwuParameters<C: CurveParameters> {
     pub minus_z_inv: C::BaseFieldElement
 }
 
+/// Returns `None` only if the modulus is not prime and the square root can not be found
 pub(crate) fn simplified_swu_fp<
     'a,
     E: ElementRepr, 
@@ -25,7 +26,7 @@ pub(crate) fn simplified_swu_fp<
     u: &C::BaseFieldElement,
     params: &SwuParameters<C>,
     curve: &WeierstrassCurve<'a, C>
-) -> (C::BaseFieldElement, C::BaseFieldElement) {
+) -> Option<(C::BaseFieldElement, C::BaseFieldElement)> {
     let one = Fp::one(u.field);
 
     // we do NOT use constant time operations here
@@ -102,7 +103,7 @@ pub(crate) fn simplified_swu_fp<
         gx2
     };
     // 19.   y = sqrt(y2)
-    let mut y = sqrt(&y2).expect("y2 is a square");
+    let mut y = sqrt(&y2)?;
 
     // 20.  e3 = sgn0(u) == sgn0(y)  # Fix sign of y
     let u_sign = sign_of_fp(&u);
@@ -130,9 +131,10 @@ pub(crate) fn simplified_swu_fp<
     }
 
     // 22. return (x, y)
-    (x, y)
+    Some((x, y))
 }
 
+/// Returns `None` only if the modulus is not prime and the square root can not be found
 pub(crate) fn simplified_swu_fp2<
     'a, 
     E: ElementRepr, 
@@ -142,7 +144,7 @@ pub(crate) fn simplified_swu_fp2<
     u: &Fp2<'a, E, F>,
     params: &SwuParameters<C>,
     curve: &WeierstrassCurve<'a, C>
-) -> (Fp2<'a, E, F>, Fp2<'a, E, F>)  {
+) -> Option<(Fp2<'a, E, F>, Fp2<'a, E, F>)> {
     let one = Fp2::one(u.extension_field);
 
     // we do NOT use constant time operations here
@@ -219,7 +221,7 @@ pub(crate) fn simplified_swu_fp2<
         gx2
     };
     // 19.   y = sqrt(y2)
-    let mut y = sqrt_ext2(&y2).expect("y2 is a square");
+    let mut y = sqrt_ext2(&y2)?;
 
     // 20.  e3 = sgn0(u) == sgn0(y)  # Fix sign of y
     let u_sign = sign_of_fp2(&u);
@@ -249,5 +251,5 @@ pub(crate) fn simplified_swu_fp2<
     }
 
     // 22. return (x, y)
-    (x, y)
+    Some((x, y))
 }
@@ -0,0 +1,132 @@
+use crate::fp::*;
+use crate::representation::*;
+use crate::field::*;
+use crate::traits::*;
+use super::*;
+
+use crate::weierstrass::*;
+use crate::weierstrass::curve::*;
+use crate::square_root::*;
+
+/// Constants of the Shallue-van de Woestijne map from RFC 9380 (section 6.6.1):
+/// `c1 = g(Z)`, `c2 = -Z / 2`, `c3 = sqrt(-g(Z) * (3 * Z^2 + 4 * A))` with `sgn0(c3) == 0`
+/// and `c4 = -4 * g(Z) / (3 * Z^2 + 4 * A)`
+#[derive(Clone)]
+pub struct SvdwParameters<C: CurveParameters> {
+    pub z: C::BaseFieldElement,
+    pub c1: C::BaseFieldElement,
+    pub c2: C::BaseFieldElement,
+    pub c3: C::BaseFieldElement,
+    pub c4: C::BaseFieldElement
+}
+
+fn is_square<'a, E: ElementRepr, F: SizedPrimeField<Repr = E>>(el: &Fp<'a, E, F>) -> bool {
+    legendre_symbol_fp(el) != LegendreSymbol::QuadraticNonResidue
+}
+
+/// Unlike the simplified SWU this map works for any curve, including `A * B == 0`,
+/// so no isogeny is needed. Returns `None` only if the modulus is not prime
+/// and the square root can not be found
+pub(crate) fn svdw_fp<
+    'a,
+    E: ElementRepr,
+    F: SizedPrimeField<Repr = E>,
+    C: CurveParameters<BaseFieldElement = Fp<'a, E, F>>
+> (
+    u: &C::BaseFieldElement,
+    params: &SvdwParameters<C>,
+    curve: &WeierstrassCurve<'a, C>
+) -> Option<(C::BaseFieldElement, C::BaseFieldElement)> {
+    let one = Fp::one(u.field);
+
+    // we do NOT use constant time operations here
+
+    // 1.  tv1 = u^2
+    // 2.  tv1 = tv1 * c1
+    let mut tv1 = u.clone();
+    tv1.square();
+    tv1.mul_assign(&params.c1);
+
+    // 3.  tv2 = 1 + tv1
+    let mut tv2 = one.clone();
+    tv2.add_assign(&tv1);
+
+    // 4.  tv1 = 1 - tv1
+    let mut t = one.clone();
+    t.sub_assign(&tv1);
+    let tv1 = t;
+
+    // 5.  tv3 = tv1 * tv2
+    let mut tv3 = tv1.clone();
+    tv3.mul_assign(&tv2);
+
+    // 6.  tv3 = inv0(tv3)
+    let tv3 = tv3.inverse().unwrap_or(Fp::zero(u.field));
+
+    // 7.  tv4 = u * tv1
+    // 8.  tv4 = tv4 * tv3
+    // 9.  tv4 = tv4 * c3
+    let mut tv4 = u.clone();
+    tv4.mul_assign(&tv1);
+    tv4.mul_assign(&tv3);
+    tv4.mul_assign(&params.c3);
+
+    // 10.  x1 = c2 - tv4
+    let mut x1 = params.c2.clone();
+    x1.sub_assign(&tv4);
+
+    // 11. gx1 = x1^3 + A * x1 + B
+    // 12.  e1 = is_square(gx1)
+    let gx1 = curve.evaluate_rhs(&x1);
+    let e1 = is_square(&gx1);
+
+    // 13.  x2 = c2 + tv4
+    let mut x2 = params.c2.clone();
+    x2.add_assign(&tv4);
+
+    // 14. gx2 = x2^3 + A * x2 + B
+    // 15.  e2 = is_square(gx2) AND NOT e1   # avoid short-circuit logic ops
+    // only computed if needed since we are not constant time
+    let (x, gx) = if e1 {
+        (x1, gx1)
+    } else {
+        let gx2 = curve.evaluate_rhs(&x2);
+        if is_square(&gx2) {
+            (x2, gx2)
+        } else {
+            // 16.  x3 = tv2^2
+            // 17.  x3 = x3 * tv3
+            // 18.  x3 = x3^2
+            // 19.  x3 = x3 * c4
+            // 20.  x3 = x3 + Z
+            let mut x3 = tv2.clone();
+            x3.square();
+            x3.mul_assign(&tv3);
+            x3.square();
+            x3.mul_assign(&params.c4);
+            x3.add_assign(&params.z);
+
+            let gx3 = curve.evaluate_rhs(&x3);
+
+            (x3, gx3)
+        }
+    };
+
+    // 21.   x = CMOV(x3, x1, e1)    # x = x1 if gx1 is square, else x = x3
+    // 22.   x = CMOV(x, x2, e2)     # x = x2 if gx2 is square and gx1 is not
+    // 23.  gx = x^3 + A * x + B
+    // 24.   y = sqrt(gx)
+    let mut y = sqrt(&gx)?;
+
+    // 25.  e3 = sgn0(u) == sgn0(y)
+    // 26.   y = CMOV(-y, y, e3)     # select correct sign of y
+    // our SignZero is equal to SignPlus
+    let u_is_odd = sign_of_fp(u) == Sign::SignMinus;
+    let y_is_odd = sign_of_fp(&y) == Sign::SignMinus;
+    if u_is_odd != y_is_odd {
+        y.negate();
+    }
+
+    // 27. return (x, y)
+    Some((x, y))
+}
@@ -224,7 +224,6 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >Fp<'a, E, F> {
             // now back into montgomery form
             let el = Fp::from_repr(self.field, r);
             if el.is_err() {
-                println!("Representation is invalid");
                 return None;
             }
             let el = el.expect("guaranteed to exist");
@@ -0,0 +1,207 @@
+// Montgomery curves B*y^2 = x^3 + A*x^2 + x over the base field. Only the x-coordinate
+// (Montgomery ladder) scalar multiplication is implemented, as used by X25519 and X448.
+// Ladder works on the curve and its quadratic twist at the same time, so B is only
+// checked to be non-zero and input x-coordinates are not checked to be on the curve
+
+use crate::traits::{FieldElement, ZeroAndOne, ConditionalSelect, ZeroizeSecret};
+use crate::weierstrass::CurveParameters;
+
+pub struct MontgomeryCurve<'a, C: CurveParameters> {
+    pub(crate) a: C::BaseFieldElement,
+    pub(crate) b: C::BaseFieldElement,
+    // (A - 2)/4
+    pub(crate) a24: C::BaseFieldElement,
+    pub(crate) params: &'a C,
+}
+
+impl<'a, C: CurveParameters> Clone for MontgomeryCurve<'a, C> {
+    fn clone(&self) -> Self {
+        Self {
+            a: self.a.clone(),
+            b: self.b.clone(),
+            a24: self.a24.clone(),
+            params: self.params,
+        }
+    }
+}
+
+impl<'a, C: CurveParameters> MontgomeryCurve<'a, C> {
+    pub(crate) fn new(
+        a: C::BaseFieldElement,
+        b: C::BaseFieldElement,
+        params: &'a C
+    ) -> Result<Self, ()> {
+        if b.is_zero() {
+            return Err(());
+        }
+
+        let one = C::BaseFieldElement::one(params.params());
+        let mut two = one.clone();
+        two.double();
+        let mut four = two.clone();
+        four.double();
+
+        // curve is singular if A^2 == 4
+        let mut a_squared = a.clone();
+        a_squared.square();
+        if a_squared == four {
+            return Err(());
+        }
+
+        let four_inv = four.inverse().ok_or(())?;
+        let mut a24 = a.clone();
+        a24.sub_assign(&two);
+        a24.mul_assign(&four_inv);
+
+        Ok(Self {
+            a: a,
+            b: b,
+            a24: a24,
+            params: params,
+        })
+    }
+
+    /// Returns the x-coordinate of [scalar]P for a point P with x-coordinate `u`. Exactly `num_bits`
+    /// ladder steps are performed (higher bits of the scalar are ignored), so the sequence of field
+    /// operations doesn't depend on the scalar value. Point at infinity is mapped to zero as in RFC 7748
+    pub fn ladder<S: AsRef<[u64]>>(&self, u: &C::BaseFieldElement, scalar: S, num_bits: usize) -> C::BaseFieldElement {
+        let scalar = scalar.as_ref();
+        let zero = C::BaseFieldElement::zero(self.params.params());
+        let one = C::BaseFieldElement::one(self.params.params());
+
+        let x_1 = u.clone();
+        let mut x_2 = one.clone();
+        let mut z_2 = zero.clone();
+        let mut x_3 = u.clone();
+        let mut z_3 = one;
+        let mut swap = false;
+
+        for i in (0..num_bits).rev() {
+            let limb = i / 64;
+            let bit = if limb < scalar.len() {
+                (scalar[limb] >> (i % 64)) & 1 == 1
+            } else {
+                false
+            };
+
+            swap ^= bit;
+            C::BaseFieldElement::conditional_swap(&mut x_2, &mut x_3, swap);
+            C::BaseFieldElement::conditional_swap(&mut z_2, &mut z_3, swap);
+            swap = bit;
+
+            // A = x_2 + z_2, AA = A^2
+            let mut a = x_2.clone();
+            a.add_assign(&z_2);
+            let mut aa = a.clone();
+            aa.square();
+
+            // B = x_2 - z_2, BB = B^2
+            let mut b = x_2;
+            b.sub_assign(&z_2);
+            let mut bb = b.clone();
+            bb.square();
+
+            // E = AA - BB
+            let mut e = aa.clone();
+            e.sub_assign(&bb);
+
+            // C = x_3 + z_3, D = x_3 - z_3
+            let mut c = x_3.clone();
+            c.add_assign(&z_3);
+            let mut d = x_3;
+            d.sub_assign(&z_3);
+
+            // DA = D * A, CB = C * B
+            d.mul_assign(&a);
+            c.mul_assign(&b);
+
+            // x_3 = (DA + CB)^2
+            x_3 = d.clone();
+            x_3.add_assign(&c);
+            x_3.square();
+
+            // z_3 = x_1 * (DA - CB)^2
+            z_3 = d;
+            z_3.sub_assign(&c);
+            z_3.square();
+            z_3.mul_assign(&x_1);
+
+            // x_2 = AA * BB
+            x_2 = aa.clone();
+            x_2.mul_assign(&bb);
+
+            // z_2 = E * (AA + a24 * E)
+            z_2 = e.clone();
+            z_2.mul_assign(&self.a24);
+            z_2.add_assign(&aa);
+            z_2.mul_assign(&e);
+
+            a.zeroize_secret();
+            aa.zeroize_secret();
+            b.zeroize_secret();
+            bb.zeroize_secret();
+            c.zeroize_secret();
+            e.zeroize_secret();
+        }
+
+        C::BaseFieldElement::conditional_swap(&mut x_2, &mut x_3, swap);
+        C::BaseFieldElement::conditional_swap(&mut z_2, &mut z_3, swap);
+
+        let result = match z_2.inverse() {
+            Some(z_inv) => {
+                let mut x = x_2.clone();
+                x.mul_assign(&z_inv);
+
+                x
+            },
+            None => {
+                zero
+            }
+        };
+
+        x_2.zeroize_secret();
+        z_2.zeroize_secret();
+        x_3.zeroize_secret();
+        z_3.zeroize_secret();
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use num_traits::Num;
+    use crate::field::{U256Repr, new_field};
+    use crate::fp::Fp;
+    use crate::weierstrass::CurveOverFpParameters;
+    use crate::test::biguint_to_u64_vec;
+    use super::*;
+
+    fn fp_from_hex<'a>(field: &'a crate::field::PrimeField<U256Repr>, value: &str) -> Fp<'a, U256Repr, crate::field::PrimeField<U256Repr>> {
+        let bytes = BigUint::from_str_radix(value, 16).unwrap().to_bytes_be();
+
+        Fp::from_be_bytes(field, &bytes, true).unwrap()
+    }
+
+    // Diffie-Hellman test vector from RFC 7748, section 6.1. Encodings there are little endian,
+    // these are reversed, and the secret scalar is already clamped
+    #[test]
+    fn test_x25519_ladder() {
+        let field = new_field::<U256Repr>("7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed", 16).unwrap();
+        let params = CurveOverFpParameters::new(&field);
+        let a = fp_from_hex(&field, "76d06");
+        let b = fp_from_hex(&field, "1");
+        let curve = MontgomeryCurve::new(a, b, &params).unwrap();
+
+        let base = fp_from_hex(&field, "9");
+        let scalar = biguint_to_u64_vec(BigUint::from_str_radix("6a2cb91da5fb77b12a99c0eb872f4cdf4566b25172c1163c7da518730a6d0770", 16).unwrap());
+        let public_key = curve.ladder(&base, &scalar, 256);
+        assert_eq!(public_key, fp_from_hex(&field, "6a4e9baa8ea9a4ebf41a38260d3abf0d5af73eb4dc7d8b7454a7308909f02085"));
+
+        // zero scalar gives the point at infinity
+        assert!(curve.ladder(&base, &[0u64], 256).is_zero());
+        // x = 0 is the point of order two
+        assert!(curve.ladder(&fp_from_hex(&field, "0"), &[2u64], 256).is_zero());
+    }
+}
@@ -1,14 +1,118 @@
 use crate::weierstrass::Group;
-use crate::weierstrass::curve::CurvePoint;
-use crate::weierstrass::CurveParameters;
+use crate::weierstrass::curve::{CurvePoint, FixedBaseTable, batch_normalize};
+use crate::weierstrass::{CurveParameters, CoordinateSystem, PointOperation};
 use crate::integers::MaxGroupSizeUint;
+use crate::execution_context::ExecutionContext;
+use crate::alloc_prelude::*;
 
 pub(crate) fn peppinger<'a, C: CurveParameters>
-    (bases: &[CurvePoint<'a, C>], mut scalars: Vec<MaxGroupSizeUint>) -> CurvePoint<'a, C>
+    (bases: &[CurvePoint<'a, C>], scalars: Vec<MaxGroupSizeUint>, ctx: &ExecutionContext) -> CurvePoint<'a, C>
 {
     use crate::representation::*;
     debug_assert!(bases.len() == scalars.len());
 
+    // buckets and comb tables are indexed by the scalars
+    if CoordinateSystem::for_operation(PointOperation::Multiexp) == CoordinateSystem::Projective {
+        return CurvePoint::multiexp_constant_time(bases, &scalars);
+    }
+
+    // bucket accumulation uses mixed addition, so bring any projective bases to affine at once
+    let normalized_bases;
+    let bases = if bases.iter().all(|b| b.is_normalized()) {
+        bases
+    } else {
+        let mut tmp = bases.to_vec();
+        batch_normalize(&mut tmp);
+        normalized_bases = tmp;
+
+        &normalized_bases[..]
+    };
+
+    let num_bits = num_bits(&bases[0].curve.subgroup_order_repr);
+    let zero_point = CurvePoint::zero(bases[0].curve);
+
+    // fuzzing allows points that are not on the curve, where different ways to add up the same
+    // multiples give different results, so only the buckets are used there
+    if bases.len() < FIXED_BASE_MIN_REPEATS || bases.len() > FIXED_BASE_MAX_SEARCH || crate::features::in_fuzzing() {
+        return bucket_multiexp(bases, scalars, zero_point, num_bits, ctx);
+    }
+
+    let (mut result, other_bases, other_scalars) = sum_repeated_bases(bases, scalars, num_bits, ctx);
+    if !other_bases.is_empty() {
+        result.add_assign(&bucket_multiexp(&other_bases, other_scalars, zero_point, num_bits, ctx));
+    }
+
+    result
+}
+
+/// Minimal number of scalars for the same base to multiply it with a comb table. Building the table
+/// costs about as much as taking so many scalars through the buckets
+const FIXED_BASE_MIN_REPEATS: usize = 8;
+/// Repeated bases are found by comparing every base with the distinct ones before it, so only
+/// for inputs of this size. It covers any multiexp encoded in bytes, that has at most 255 pairs
+const FIXED_BASE_MAX_SEARCH: usize = 256;
+
+/// Sums the multiples of the bases that are repeated at least `FIXED_BASE_MIN_REPEATS` times with their
+/// comb tables, returns the sum and the other bases and scalars. Bases are expected to be normalized.
+/// Scalars that are longer than the group order stay with the other ones, as the buckets don't take all
+/// of their bits, so the result is the same as of the buckets alone
+fn sum_repeated_bases<'a, C: CurveParameters>
+    (bases: &[CurvePoint<'a, C>], scalars: Vec<MaxGroupSizeUint>, num_bits: u32, ctx: &ExecutionContext) -> (CurvePoint<'a, C>, Vec<CurvePoint<'a, C>>, Vec<MaxGroupSizeUint>)
+{
+    use crate::representation::num_bits as scalar_bits;
+
+    let fits_table = |i: usize| !bases[i].is_zero() && scalar_bits(scalars[i].as_ref()) <= num_bits;
+
+    // index of the first base equal to every base
+    let mut first_equal: Vec<usize> = Vec::with_capacity(bases.len());
+    for (i, base) in bases.iter().enumerate() {
+        let first = (0..i).find(|&j| first_equal[j] == j && is_same_normalized_point(&bases[j], base)).unwrap_or(i);
+        first_equal.push(first);
+    }
+
+    let mut num_repeats = vec![0usize; bases.len()];
+    for i in (0..bases.len()).filter(|&i| fits_table(i)) {
+        num_repeats[first_equal[i]] += 1;
+    }
+
+    let mut result = CurvePoint::zero(bases[0].curve);
+    let mut in_table = vec![false; bases.len()];
+    for (first, _) in num_repeats.iter().enumerate().filter(|(_, &n)| n >= FIXED_BASE_MIN_REPEATS) {
+        let indexes: Vec<usize> = (0..bases.len()).filter(|&i| first_equal[i] == first && fits_table(i)).collect();
+        let table = FixedBaseTable::new(&bases[first], num_bits as usize);
+        let multiples = table.sum_of_multiples(&indexes.iter().map(|&i| &scalars[i]).collect::<Vec<_>>(), ctx);
+        result.add_assign(&multiples);
+        for i in indexes.into_iter() {
+            in_table[i] = true;
+        }
+    }
+
+    let mut other_bases = Vec::with_capacity(bases.len());
+    let mut other_scalars = Vec::with_capacity(bases.len());
+    for ((base, scalar), in_table) in bases.iter().zip(scalars.into_iter()).zip(in_table.into_iter()) {
+        if !in_table {
+            other_bases.push(base.clone());
+            other_scalars.push(scalar);
+        }
+    }
+
+    (result, other_bases, other_scalars)
+}
+
+fn is_same_normalized_point<'a, C: CurveParameters>(a: &CurvePoint<'a, C>, b: &CurvePoint<'a, C>) -> bool {
+    a.x == b.x && a.y == b.y && a.z == b.z
+}
+
+/// Number of additions into distinct buckets that are done as one batch
+const MIXED_ADDITION_BATCH: usize = 8;
+
+/// Bucket accumulation for any group. Bases are added with mixed addition, so these should
+/// be normalized if the group representation benefits from it
+pub(crate) fn bucket_multiexp<G: Group>
+    (bases: &[G], mut scalars: Vec<MaxGroupSizeUint>, zero_point: G, num_bits: u32, ctx: &ExecutionContext) -> G
+{
+    debug_assert!(bases.len() == scalars.len());
+
     let c = if bases.len() < 32 {
         3u32
     } else {
@@ -17,13 +121,17 @@ pub(crate) fn peppinger<'a, C: CurveParameters>
 
     let mask = (1u64 << c) - 1u64;
     let mut cur = 0;
-    let num_bits = num_bits(&bases[0].curve.subgroup_order_repr);
-    let zero_point = CurvePoint::zero(bases[0].curve);
 
     let mut windows = Vec::with_capacity((num_bits / c + 1) as usize);
     let mut buckets = Vec::with_capacity((1 << c) - 1);
+    let mut pending_indexes = Vec::with_capacity(MIXED_ADDITION_BATCH);
+    let mut pending_bases = Vec::with_capacity(MIXED_ADDITION_BATCH);
 
     while cur <= num_bits {
+        if !ctx.charge(bases.len() + 2 * ((1 << c) - 1)) {
+            return zero_point;
+        }
+
         let mut acc = zero_point.clone();
 
         buckets.truncate(0);
@@ -33,13 +141,18 @@ pub(crate) fn peppinger<'a, C: CurveParameters>
             let index = (s.as_ref()[0] & mask) as usize;
 
             if index != 0 {
-                buckets[index - 1].add_assign_mixed(&g);
+                if pending_indexes.len() == MIXED_ADDITION_BATCH || pending_indexes.contains(&(index - 1)) {
+                    add_into_buckets(&mut buckets, &mut pending_indexes, &mut pending_bases);
+                }
+                pending_indexes.push(index - 1);
+                pending_bases.push(g.clone());
             }
 
             *s >>= c;
 
             // right_shift_representation(s, c as u64);
         }
+        add_into_buckets(&mut buckets, &mut pending_indexes, &mut pending_bases);
 
         let mut running_sum = zero_point.clone();
         for exp in buckets.iter().rev() {
@@ -63,4 +176,77 @@ pub(crate) fn peppinger<'a, C: CurveParameters>
     }
 
     acc
-}
\ No newline at end of file
+}
+
+fn add_into_buckets<G: Group>(buckets: &mut [G], indexes: &mut Vec<usize>, bases: &mut Vec<G>) {
+    let mut sums: Vec<G> = indexes.iter().map(|&i| buckets[i].clone()).collect();
+    G::batch_add_assign_mixed(&mut sums, &bases[..]);
+    for (&i, sum) in indexes.iter().zip(sums.into_iter()) {
+        buckets[i] = sum;
+    }
+
+    indexes.truncate(0);
+    bases.truncate(0);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::engines::bls12_381::*;
+    use crate::test_utils::{random_point, random_scalar};
+    use rand::{Rng, SeedableRng};
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_fixed_base_table_mul() {
+        let rng = &mut XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        let p = random_point(&BLS12_381_G1_GENERATOR, rng);
+        let table = FixedBaseTable::new(&p, crate::representation::num_bits(&BLS12_381_SUBGROUP_ORDER) as usize);
+
+        for _ in 0..20 {
+            let k = random_scalar(&BLS12_381_SUBGROUP_ORDER, rng);
+            assert!(table.mul(&k).into_xy() == p.mul_impl(&k).into_xy());
+        }
+
+        // scalars longer than the table
+        let k: Vec<u64> = (0..4).map(|_| rng.gen()).collect();
+        assert!(table.mul(&k).into_xy() == p.mul_impl(&k).into_xy());
+        assert!(table.mul(&[0u64]).is_zero());
+        assert!(table.mul(&BLS12_381_SUBGROUP_ORDER).is_zero());
+        assert!(table.mul(&[1u64]).into_xy() == p.into_xy());
+    }
+
+    #[test]
+    fn test_multiexp_with_repeated_bases() {
+        let rng = &mut XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        let repeated = random_point(&BLS12_381_G1_GENERATOR, rng);
+
+        let mut bases = vec![];
+        let mut scalars = vec![];
+        for i in 0..40 {
+            // every third base is the same, one of its scalars is longer than the group order
+            let base = if i % 3 == 0 { repeated.clone() } else { random_point(&BLS12_381_G1_GENERATOR, rng) };
+            let scalar = if i == 9 {
+                MaxGroupSizeUint::from(&[u64::max_value(); 4][..])
+            } else {
+                MaxGroupSizeUint::from(&random_scalar(&BLS12_381_SUBGROUP_ORDER, rng)[..])
+            };
+            bases.push(base);
+            scalars.push(scalar);
+        }
+
+        let mut expected = CurvePoint::zero(&BLS12_381_G1_CURVE);
+        for (base, scalar) in bases.iter().zip(scalars.iter()) {
+            expected.add_assign(&base.mul_impl(scalar));
+        }
+
+        let (_, other_bases, _) = sum_repeated_bases(&bases, scalars.clone(), crate::representation::num_bits(&BLS12_381_SUBGROUP_ORDER), &ExecutionContext::default());
+        assert_eq!(other_bases.len(), 40 - 13);
+        assert!(peppinger(&bases, scalars.clone(), &ExecutionContext::default()).into_xy() == expected.into_xy());
+
+        // same as the buckets alone
+        let zero_point = CurvePoint::zero(&BLS12_381_G1_CURVE);
+        let buckets = bucket_multiexp(&bases, scalars, zero_point, crate::representation::num_bits(&BLS12_381_SUBGROUP_ORDER), &ExecutionContext::default());
+        assert!(buckets.into_xy() == expected.into_xy());
+    }
+}
@@ -0,0 +1,86 @@
+//! Deterministic budget for `API::run_with_limit`. Miller loops and multiexponentiations
+//! charge a number of abstract operations per iteration (line evaluations, point additions
+//! and doublings) and stop early once the budget is exhausted. The result computed after
+//! that is garbage and is never returned, `run_with_limit` reports an error instead.
+//! Without a limit (or without the `gas_metering` feature) charging always succeeds.
+//! The budget is a part of the `ExecutionContext` of the call, so the worker threads that run
+//! chunks of a parallel Miller loop charge the same budget
+
+pub(crate) use self::budget::OperationBudget;
+
+/// Number of budget operations allowed per unit of gas. A single operation is much cheaper
+/// than one gas for any parameters, so the budget only triggers when the gas model
+/// underestimates the work by orders of magnitude
+pub const OPERATIONS_PER_GAS: u64 = 1;
+
+#[cfg(feature = "gas_metering")]
+mod budget {
+    use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+    struct State {
+        remaining: AtomicU64,
+        exhausted: AtomicBool,
+    }
+
+    /// Number of operations left, no budget means an unlimited call
+    #[derive(Default)]
+    pub(crate) struct OperationBudget {
+        state: Option<State>,
+    }
+
+    impl OperationBudget {
+        pub(crate) fn new(operations: u64) -> Self {
+            let state = State {
+                remaining: AtomicU64::new(operations),
+                exhausted: AtomicBool::new(false),
+            };
+
+            Self {
+                state: Some(state)
+            }
+        }
+
+        /// Returns `false` if the budget is exhausted and the caller should stop
+        #[inline]
+        pub(crate) fn charge(&self, operations: usize) -> bool {
+            match self.state.as_ref() {
+                None => true,
+                Some(state) => {
+                    let charged = state.remaining.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |remaining| {
+                        remaining.checked_sub(operations as u64)
+                    });
+                    if charged.is_err() {
+                        state.remaining.store(0, Ordering::Relaxed);
+                        state.exhausted.store(true, Ordering::Relaxed);
+
+                        return false;
+                    }
+
+                    true
+                }
+            }
+        }
+
+        pub(crate) fn is_exhausted(&self) -> bool {
+            self.state.as_ref().map(|state| state.exhausted.load(Ordering::Relaxed)).unwrap_or(false)
+        }
+
+        #[cfg(test)]
+        pub(crate) fn remaining(&self) -> Option<u64> {
+            self.state.as_ref().map(|state| state.remaining.load(Ordering::Relaxed))
+        }
+    }
+}
+
+#[cfg(not(feature = "gas_metering"))]
+mod budget {
+    #[derive(Default)]
+    pub(crate) struct OperationBudget;
+
+    impl OperationBudget {
+        #[inline(always)]
+        pub(crate) fn charge(&self, _operations: usize) -> bool {
+            true
+        }
+    }
+}
@@ -0,0 +1,721 @@
+use crate::field::SizedPrimeField;
+use crate::fp::Fp;
+use crate::representation::ElementRepr;
+use crate::traits::{FieldElement, MsbBitIterator, ZeroAndOne};
+use crate::weierstrass::Group;
+use crate::weierstrass::{CurveParameters};
+use crate::weierstrass::curve::{WeierstrassCurve, CurvePoint};
+use crate::extension_towers::fp2::{Extension2};
+use crate::extension_towers::fp4_as_2_over_2::{Fp4, Extension2Over2};
+use crate::extension_towers::fp12_as_3_over_4::{Extension3Over4};
+use crate::extension_towers::fp24_as_2_over_3_over_4::{Fp24, Extension2Over3Over4};
+use crate::pairings::PairingEngine;
+use crate::pairings::TwistType;
+use crate::pairings::{normalize_nonzero_pairs, prepare_distinct_twists, DistinctTwists, multi_miller_loop, two_inverse, naf_after_leading_one, calculate_bits, calculate_hamming_weight, calculate_naf_hamming_weight, into_ternary_wnaf};
+use crate::execution_context::ExecutionContext;
+use crate::alloc_prelude::*;
+
+/// Coefficients of a line evaluation in the Miller loop
+type LineCoefficients<'a, FE, F> = (Fp4<'a, FE, F>, Fp4<'a, FE, F>, Fp4<'a, FE, F>);
+
+pub(crate) struct PreparedTwistPoint<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> {
+    pub(crate) ell_coeffs: Vec<LineCoefficients<'a, FE, F>>
+}
+
+#[derive(Clone)]
+pub struct Bls24InstanceParams<
+'a, 
+    FE: ElementRepr, 
+    F: SizedPrimeField<Repr = FE>, 
+    CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+    CTW: CurveParameters<BaseFieldElement = Fp4<'a, FE, F>>
+> {
+    pub x: &'a [u64],
+    pub x_is_negative: bool,
+    pub twist_type: TwistType,
+    pub base_field: &'a F,
+    pub curve: &'a WeierstrassCurve<'a, CB>,
+    pub curve_twist: &'a WeierstrassCurve<'a, CTW>,
+    pub fp2_extension: &'a Extension2<'a, FE, F>,
+    pub fp4_extension: &'a Extension2Over2<'a, FE, F>,
+    pub fp12_extension: &'a Extension3Over4<'a, FE, F>,
+    pub fp24_extension: &'a Extension2Over3Over4<'a, FE, F>,
+    pub force_no_naf: bool
+}
+
+#[derive(Clone)]
+pub struct Bls24Instance<
+    'a, 
+        FE: ElementRepr, 
+        F: SizedPrimeField<Repr = FE>, 
+        CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+        CTW: CurveParameters<BaseFieldElement = Fp4<'a, FE, F>>
+    > {
+    pub x: &'a [u64],
+    pub x_is_negative: bool,
+    pub twist_type: TwistType,
+    pub base_field: &'a F,
+    pub curve: &'a WeierstrassCurve<'a, CB>,
+    pub curve_twist: &'a WeierstrassCurve<'a, CTW>,
+    pub fp2_extension: &'a Extension2<'a, FE, F>,
+    pub fp4_extension: &'a Extension2Over2<'a, FE, F>,
+    pub fp12_extension: &'a Extension3Over4<'a, FE, F>,
+    pub fp24_extension: &'a Extension2Over3Over4<'a, FE, F>,
+    pub prefer_naf: bool,
+    pub x_naf: Vec<i8>
+}
+
+impl<
+    'a, 
+        FE: ElementRepr, 
+        F: SizedPrimeField<Repr = FE>, 
+        CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+        CTW: CurveParameters<BaseFieldElement = Fp4<'a, FE, F>>
+    > Bls24Instance<'a, FE, F, CB, CTW> 
+{
+    pub fn from_params(params: Bls24InstanceParams::<'a, FE, F, CB, CTW>) -> Self {
+        let (prefer_naf, naf) = if params.force_no_naf {
+            (false, vec![])
+        } else {
+            let naf_vec = into_ternary_wnaf(params.x);
+            let original_bits = calculate_bits(params.x);
+            let original_hamming = calculate_hamming_weight(params.x);
+            let naf_hamming = calculate_naf_hamming_weight(&naf_vec);
+            let naf_length = naf_vec.len() as u32;
+
+            let naf_is_beneficial = naf_length + naf_hamming < original_bits + original_hamming;
+
+            if naf_is_beneficial {
+                (true, naf_vec)
+            } else {
+                (false, vec![])
+            }
+        };
+
+        Self {
+            x: params.x,
+            x_is_negative: params.x_is_negative,
+            twist_type: params.twist_type,
+            base_field: params.base_field,
+            curve: params.curve,
+            curve_twist: params.curve_twist,
+            fp2_extension: params.fp2_extension,
+            fp4_extension: params.fp4_extension,
+            fp12_extension: params.fp12_extension,
+            fp24_extension: params.fp24_extension,
+            prefer_naf,
+            x_naf: naf
+        }
+    }
+}
+
+impl<
+    'a, 
+        FE: ElementRepr, 
+        F: SizedPrimeField<Repr = FE>, 
+        CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+        CTW: CurveParameters<BaseFieldElement = Fp4<'a, FE, F>>
+    > Bls24Instance<'a, FE, F, CB, CTW> {
+    fn ell(
+        &self,
+        f: &mut Fp24<'a, FE, F>,
+        coeffs: &LineCoefficients<'a, FE, F>,
+        p: & CurvePoint<'a, CB>,
+    ) {
+        debug_assert!(p.is_normalized());
+        let mut c0 = coeffs.0;
+        let mut c1 = coeffs.1;
+        let mut c2 = coeffs.2;
+
+        match self.twist_type {
+            TwistType::M => {
+                c2.mul_by_fp(&p.y);
+                c1.mul_by_fp(&p.x);
+                f.mul_by_014(&c0, &c1, &c2);
+            },
+            TwistType::D => {
+                c0.mul_by_fp(&p.y);
+                c1.mul_by_fp(&p.x);
+                f.mul_by_034(&c0, &c1, &c2);
+            },
+        }
+    }
+
+    fn exp_by_x(&self, f: &mut Fp24<'a, FE, F>) {
+        *f = f.cyclotomic_exp(self.x);
+        if self.x_is_negative {
+            f.conjugate();
+        }
+    }
+
+    fn exp_by_x_minus_one(&self, f: &mut Fp24<'a, FE, F>) {
+        let mut f_inv = *f;
+        f_inv.conjugate();
+        self.exp_by_x(f);
+        f.mul_assign(&f_inv);
+    }
+
+    fn doubling_step(
+        &self,
+        r: &mut CurvePoint<'a, CTW>,
+        two_inv: &Fp<'a, FE, F>,
+    ) -> LineCoefficients<'a, FE, F> {
+        // Use adapted formulas from ZEXE instead
+        let mut a = r.x;
+        a.mul_assign(&r.y);
+        a.mul_by_fp(two_inv);
+        let mut b = r.y;
+        b.square();
+        let mut c = r.z;
+        c.square();
+
+        let mut e = self.curve_twist.b;
+        let mut t0 = c;
+        t0.double();
+        t0.add_assign(&c);
+
+        e.mul_assign(&t0);
+
+        let mut f = e;
+        f.double();
+        f.add_assign(&e);
+
+        let mut g = b;
+        g.add_assign(&f);
+        g.mul_by_fp(two_inv);
+
+        let mut h = r.y;
+        h.add_assign(&r.z);
+        h.square();
+
+        let mut t1 = b;
+        t1.add_assign(&c);
+
+        h.sub_assign(&t1);
+
+        let mut i = e;
+        i.sub_assign(&b);
+
+        let mut j = r.x;
+        j.square();
+
+        let mut e_square = e;
+        e_square.square();
+
+        r.x = b;
+        r.x.sub_assign(&f);
+        r.x.mul_assign(&a);
+
+        let mut e_square_by_3 = e_square;
+        e_square_by_3.double();
+        e_square_by_3.add_assign(&e_square);
+
+        r.y = g;
+        r.y.square();
+        r.y.sub_assign(&e_square_by_3);
+
+        r.z = b;
+        r.z.mul_assign(&h);
+
+        let mut j_by_three = j;
+        j_by_three.double();
+        j_by_three.add_assign(&j);
+        h.negate();
+
+        match self.twist_type {
+            TwistType::M => {
+                (i, j_by_three, h)
+            },
+            TwistType::D => {
+                (h, j_by_three, i)
+            },
+        }
+    }
+
+    fn addition_step(
+        &self,
+        r: &mut CurvePoint<'a, CTW>,
+        q: & CurvePoint<'a, CTW>,
+    ) -> LineCoefficients<'a, FE, F> {
+        debug_assert!(q.is_normalized());
+        // use adapted zexe formulas too instead of ones from pairing crate
+        let mut theta = q.y;
+        theta.mul_assign(&r.z);
+        theta.negate();
+        theta.add_assign(&r.y);
+
+        let mut lambda = q.x;
+        lambda.mul_assign(&r.z);
+        lambda.negate();
+        lambda.add_assign(&r.x);
+
+        let mut c = theta;
+        c.square();
+        let mut d = lambda;
+        d.square();
+        let mut e = lambda;
+        e.mul_assign(&d);
+        let mut f = r.z;
+        f.mul_assign(&c);
+        let mut g = r.x;
+        g.mul_assign(&d);
+
+        let mut h = g;
+        h.double();
+        h.negate();
+        h.add_assign(&e);
+        h.add_assign(&f);
+        
+
+        r.x = lambda;
+        r.x.mul_assign(&h);
+
+        let mut t0 = g;
+        t0.sub_assign(&h);
+        t0.mul_assign(&theta);
+
+        r.y.mul_assign(&e);
+        r.y.negate();
+        r.y.add_assign(&t0);
+
+        r.z.mul_assign(&e);
+
+        let mut t1 = lambda;
+        t1.mul_assign(&q.y);
+        
+        let mut j = theta;
+        j.mul_assign(&q.x);
+        j.sub_assign(&t1);
+
+        theta.negate();
+        match self.twist_type {
+            TwistType::M => (j, theta, lambda),
+            TwistType::D => (lambda, theta, j),
+        }
+    }
+
+    fn prepare(&self, twist_point: & CurvePoint<'a, CTW>, two_inv: &Fp<'a, FE, F>) -> Result<PreparedTwistPoint<'a, FE, F>, ()> {
+        debug_assert!(twist_point.is_normalized());
+
+        if twist_point.is_zero() {
+            return Ok(PreparedTwistPoint {
+                ell_coeffs: vec![],
+            });
+        }
+
+        let mut ell_coeffs = Vec::with_capacity(self.x.len() * 64 * 2);
+        let mut r = CurvePoint::<CTW>::point_from_xy(self.curve_twist, twist_point.x, twist_point.y);
+
+        for i in MsbBitIterator::new(&self.x).skip(1) {
+            ell_coeffs.push(self.doubling_step(&mut r, two_inv));
+
+            if i {
+                ell_coeffs.push(self.addition_step(&mut r, twist_point));
+            }
+        }
+
+        Ok(PreparedTwistPoint {
+            ell_coeffs,
+        })
+    }
+
+    fn prepare_naf(&self, twist_point: & CurvePoint<'a, CTW>, two_inv: &Fp<'a, FE, F>) -> Result<PreparedTwistPoint<'a, FE, F>, ()> {
+        debug_assert!(twist_point.is_normalized());
+
+        if twist_point.is_zero() {
+            return Ok(PreparedTwistPoint {
+                ell_coeffs: vec![],
+            });
+        }
+
+        let mut ell_coeffs = Vec::with_capacity(self.x.len() * 64 * 2);
+
+        let mut twist_point_negated = twist_point.clone();
+        twist_point_negated.negate();
+
+        let mut r = CurvePoint::<CTW>::point_from_xy(self.curve_twist, twist_point.x, twist_point.y);
+
+        let it = naf_after_leading_one(&self.x_naf)?;
+
+        for &i in it {
+            ell_coeffs.push(self.doubling_step(&mut r, two_inv));
+            
+            if i != 0 {
+                if i > 0 {
+                    ell_coeffs.push(self.addition_step(&mut r, twist_point));
+                } else {
+                    ell_coeffs.push(self.addition_step(&mut r, &twist_point_negated));
+                }
+            }
+        }
+
+        Ok(PreparedTwistPoint {
+            ell_coeffs,
+        })
+    }
+
+    fn prepare_distinct_twists<'b, I>(&self, i: I, use_naf: bool) -> Result<DistinctTwists<'a, 'b, CB, Vec<LineCoefficients<'a, FE, F>>>, ()>
+    where 'a: 'b,
+        I: IntoIterator<
+            Item = &'b (&'b CurvePoint<'a, CB>,
+                &'b CurvePoint<'a, CTW>)
+        >
+    {
+        let two_inv = two_inverse(self.base_field)?;
+
+        prepare_distinct_twists(i, |q| {
+            let coeffs = if use_naf {
+                self.prepare_naf(q, &two_inv)?
+            } else {
+                self.prepare(q, &two_inv)?
+            };
+
+            Ok(coeffs.ell_coeffs)
+        })
+    }
+
+    fn miller_loop_naf<'b, I>(&self, i: I, ctx: &ExecutionContext) -> Result<Fp24<'a, FE, F>, ()>
+    where 'a: 'b,
+        I: IntoIterator<
+            Item = &'b (&'b CurvePoint<'a, CB>, 
+                &'b CurvePoint<'a, CTW>)
+        >
+    {
+        let (g1_references, twist_indexes, prepared_coeffs) = self.prepare_distinct_twists(i, true)?;
+        let mut step = 0;
+
+        let mut f = Fp24::one(self.fp24_extension);
+
+        let it = naf_after_leading_one(&self.x_naf)?;
+
+        for &i in it {
+            if !ctx.charge(g1_references.len() + 1) {
+                return Ok(f);
+            }
+
+            f.square();
+
+            for (p, &index) in g1_references.iter().zip(twist_indexes.iter()) {
+                self.ell(&mut f, &prepared_coeffs[index][step], p);
+            }
+            step += 1;
+
+            if i != 0 {
+                for (p, &index) in g1_references.iter().zip(twist_indexes.iter()) {
+                    self.ell(&mut f, &prepared_coeffs[index][step], p);
+                }
+                step += 1;
+            }
+        }
+
+        if self.x_is_negative {
+            f.conjugate();
+        }
+
+        Ok(f)
+    }
+
+    fn miller_loop<'b, I>(&self, i: I, ctx: &ExecutionContext) -> Result<Fp24<'a, FE, F>, ()>
+    where 'a: 'b,
+        I: IntoIterator<
+            Item = &'b (&'b CurvePoint<'a, CB>, 
+                &'b CurvePoint<'a, CTW>)
+        >
+    {
+        let (g1_references, twist_indexes, prepared_coeffs) = self.prepare_distinct_twists(i, false)?;
+        let mut step = 0;
+
+        let mut f = Fp24::one(self.fp24_extension);
+
+        for i in MsbBitIterator::new(&self.x).skip(1) {
+            if !ctx.charge(g1_references.len() + 1) {
+                return Ok(f);
+            }
+
+            f.square();
+
+            for (p, &index) in g1_references.iter().zip(twist_indexes.iter()) {
+                self.ell(&mut f, &prepared_coeffs[index][step], p);
+            }
+            step += 1;
+
+            if i {
+                for (p, &index) in g1_references.iter().zip(twist_indexes.iter()) {
+                    self.ell(&mut f, &prepared_coeffs[index][step], p);
+                }
+                step += 1;
+            }
+        }
+
+        if self.x_is_negative {
+            f.conjugate();
+        }
+
+        Ok(f)
+    }
+
+    fn final_exponentiation(&self, f: &Fp24<'a, FE, F>) -> Option<Fp24<'a, FE, F>> {
+        // Final exponent is (p^24 - 1)/r = (p^12 - 1)(p^4 + 1) * (p^8 - p^4 + 1)/r.
+        // For the hard part we use that for BLS24 curves
+        // 3 * (p^8 - p^4 + 1)/r = (x - 1)^2 * (x + p) * (x^2 + p^2) * (x^4 + p^4 - 1) + 3,
+        // so the result is the cube of the reduced pairing, which is still a
+        // non-degenerate bilinear map as r != 3
+
+        match f.inverse() {
+            Some(f_inv) => {
+                // f1 = f^(p^12) is a conjugation as w^(p^12) = -w
+                let mut f1 = *f;
+                f1.conjugate();
+
+                // r = f^(p^12 - 1)
+                let mut r = f1;
+                r.mul_assign(&f_inv);
+
+                // r = f^((p^12 - 1)(p^4 + 1))
+                let f2 = r;
+                r.frobenius_map(4);
+                r.mul_assign(&f2);
+
+                // now r is in the cyclotomic subgroup, so inversion is a conjugation
+
+                // a = r^((x - 1)^2)
+                let mut a = r;
+                self.exp_by_x_minus_one(&mut a);
+                self.exp_by_x_minus_one(&mut a);
+
+                // b = a^(x + p)
+                let mut b = a;
+                self.exp_by_x(&mut b);
+                let mut t0 = a;
+                t0.frobenius_map(1);
+                b.mul_assign(&t0);
+
+                // c = b^(x^2 + p^2)
+                let mut c = b;
+                self.exp_by_x(&mut c);
+                self.exp_by_x(&mut c);
+                let mut t0 = b;
+                t0.frobenius_map(2);
+                c.mul_assign(&t0);
+
+                // d = c^(x^4 + p^4 - 1)
+                let mut d = c;
+                for _ in 0..4 {
+                    self.exp_by_x(&mut d);
+                }
+                let mut t0 = c;
+                t0.frobenius_map(4);
+                d.mul_assign(&t0);
+                let mut t0 = c;
+                t0.conjugate();
+                d.mul_assign(&t0);
+
+                // result = d * r^3
+                let mut r_cubed = r;
+                r_cubed.cyclotomic_square();
+                r_cubed.mul_assign(&r);
+                d.mul_assign(&r_cubed);
+
+                Some(d)
+            },
+            None => None,
+        }
+    }
+}
+
+
+impl<
+    'a, 
+        FE: ElementRepr, 
+        F: SizedPrimeField<Repr = FE>, 
+        CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+        CTW: CurveParameters<BaseFieldElement = Fp4<'a, FE, F>>
+    > PairingEngine for Bls24Instance<'a, FE, F, CB, CTW> {
+    type PairingResult = Fp24<'a, FE, F>;
+    type G1 = CurvePoint<'a, CB>;
+    type G2 = CurvePoint<'a, CTW>;
+
+    fn miller_loop_product_with_context<'b>
+        (&self, points: &'b [CurvePoint<'a, CB>], twists: &'b [CurvePoint<'a, CTW>], ctx: &ExecutionContext) -> Option<Self::PairingResult> {
+            if points.len() != twists.len() {
+                return None;
+            }
+
+            if !crate::features::in_gas_metering() && (points.is_empty() || twists.is_empty()) {
+                return None;
+            }
+            
+            let (g1, g2) = normalize_nonzero_pairs(points, twists);
+            let pairs: Vec<_> = g1.iter().zip(g2.iter()).collect();
+
+            if pairs.is_empty() {
+                return Some(Fp24::one(self.fp24_extension));
+            }
+
+            let loop_result = if self.prefer_naf {
+                debug_assert!(!self.x_naf.is_empty());
+
+                multi_miller_loop(&pairs[..], ctx, |chunk| self.miller_loop_naf(chunk, ctx))
+            } else {
+                multi_miller_loop(&pairs[..], ctx, |chunk| self.miller_loop(chunk, ctx))
+            };
+
+            loop_result.ok()
+        }
+
+    fn final_exp(&self, f: &Fp24<'a, FE, F>) -> Option<Self::PairingResult> {
+            self.final_exponentiation(f)
+        }   
+}
+
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use crate::field::{U256Repr, new_field};
+    use crate::fp::Fp;
+    use crate::traits::{FieldElement, ZeroAndOne};
+    use crate::extension_towers::Fp12Fp24FrobeniusBaseElements;
+    use crate::extension_towers::fp2::{Fp2, Extension2};
+    use crate::extension_towers::fp4_as_2_over_2::{Fp4, Extension2Over2};
+    use crate::extension_towers::fp12_as_3_over_4::{Fp12, Extension3Over4};
+    use crate::extension_towers::fp24_as_2_over_3_over_4::{Fp24, Extension2Over3Over4};
+    use crate::extension_towers::Fp2Fp4FrobeniusBaseElements;
+    use num_traits::Num;
+    use crate::weierstrass::curve::{CurvePoint, WeierstrassCurve};
+    use crate::weierstrass::{Group, CurveOverFpParameters, CurveOverFp4Parameters};
+    use crate::pairings::{PairingEngine, TwistType};
+    use crate::test::{biguint_to_u64_vec};
+    use crate::integers::MaxFieldUint;
+
+    fn fp_from_dec<'a>(field: &'a crate::field::PrimeField<U256Repr>, value: &str) -> Fp<'a, U256Repr, crate::field::PrimeField<U256Repr>> {
+        let bytes = BigUint::from_str_radix(value, 10).unwrap().to_bytes_be();
+
+        Fp::from_be_bytes(field, &bytes, true).unwrap()
+    }
+
+    // Small BLS24 curve with x = -65831, y^2 = x^3 + 1 over a 159 bit field,
+    // Fp2 = Fp[u]/(u^2 - 5), Fp4 = Fp2[v]/(v^2 - u) and D-type twist with non-residue v
+    #[test]
+    fn test_bls24_pairing_bilinearity() {
+        let modulus_dec = "509561329363378480588473444847905545412792304537";
+        let base_field = new_field::<U256Repr>(modulus_dec, 10).unwrap();
+        let modulus = BigUint::from_str_radix(modulus_dec, 10).unwrap();
+        let modulus = MaxFieldUint::from_big_endian(&modulus.to_bytes_be());
+        let group_order = BigUint::from_str_radix("352730990601061952998750963997732433121", 10).unwrap();
+        let group_order = biguint_to_u64_vec(group_order);
+
+        let fp_non_residue = fp_from_dec(&base_field, "5");
+
+        let base_precomp = Fp2Fp4FrobeniusBaseElements::construct(&modulus, &fp_non_residue).unwrap();
+        let mut extension_2 = Extension2::new(fp_non_residue);
+        extension_2.calculate_frobenius_coeffs_with_precomp(&base_precomp).unwrap();
+
+        let mut extension_4 = Extension2Over2::new(Fp2::zero(&extension_2));
+        extension_4.calculate_frobenius_coeffs_with_precomp(&base_precomp).unwrap();
+
+        let mut fp4_non_residue = Fp4::zero(&extension_4);
+        fp4_non_residue.c1 = Fp2::one(&extension_2);
+
+        let precomp = Fp12Fp24FrobeniusBaseElements::construct(&modulus, &fp4_non_residue).unwrap();
+
+        let mut extension_12 = Extension3Over4::new(fp4_non_residue);
+        extension_12.calculate_frobenius_coeffs_with_precomp(&precomp).unwrap();
+
+        let mut extension_24 = Extension2Over3Over4::new(Fp12::zero(&extension_12));
+        extension_24.calculate_frobenius_coeffs_with_precomp(&precomp).unwrap();
+
+        let b_fp = Fp::one(&base_field);
+        let mut b_fp4 = fp4_non_residue.inverse().unwrap();
+        b_fp4.mul_by_fp(&b_fp);
+
+        let fp_params = CurveOverFpParameters::new(&base_field);
+        let fp4_params = CurveOverFp4Parameters::new(&extension_4);
+
+        let curve = WeierstrassCurve::new(&group_order.as_ref(), Fp::zero(&base_field), b_fp, &fp_params).unwrap();
+        let twist = WeierstrassCurve::new(&group_order.as_ref(), Fp4::zero(&extension_4), b_fp4, &fp4_params).unwrap();
+
+        let p = CurvePoint::point_from_xy(
+            &curve,
+            fp_from_dec(&base_field, "457790000106890064450002378016534442147523860194"),
+            fp_from_dec(&base_field, "126137624424856046836872075631991664015010953721")
+        );
+
+        let fp4_from_dec = |coeffs: [&str; 4]| {
+            let mut el = Fp4::zero(&extension_4);
+            el.c0.c0 = fp_from_dec(&base_field, coeffs[0]);
+            el.c0.c1 = fp_from_dec(&base_field, coeffs[1]);
+            el.c1.c0 = fp_from_dec(&base_field, coeffs[2]);
+            el.c1.c1 = fp_from_dec(&base_field, coeffs[3]);
+
+            el
+        };
+
+        let q = CurvePoint::point_from_xy(
+            &twist,
+            fp4_from_dec([
+                "341414800597707762976808732983152662637523783877",
+                "141691995327673744953574629504250241877962255762",
+                "48799140057592223441658588006612166393686753186",
+                "241749287001419140937726197234860175718091732121"
+            ]),
+            fp4_from_dec([
+                "419085460258284150223517265584578688306909997541",
+                "123955142087292394082021673448497760936131371517",
+                "341315689391155539071066006374380341141222365689",
+                "240884755628479114790426109864863130144025085584"
+            ])
+        );
+
+        assert!(p.is_on_curve());
+        assert!(q.is_on_curve());
+        assert!(p.check_correct_subgroup());
+        assert!(q.check_correct_subgroup());
+
+        let engine = super::Bls24InstanceParams {
+            x: &[65831],
+            x_is_negative: true,
+            twist_type: TwistType::D,
+            base_field: &base_field,
+            curve: &curve,
+            curve_twist: &twist,
+            fp2_extension: &extension_2,
+            fp4_extension: &extension_4,
+            fp12_extension: &extension_12,
+            fp24_extension: &extension_24,
+            force_no_naf: false
+        };
+
+        let engine = super::Bls24Instance::from_params(engine);
+
+        let one = Fp24::one(&extension_24);
+
+        let e = engine.pair(&[p.clone()], &[q.clone()]).unwrap();
+        assert!(e != one);
+        assert_eq!(e.pow(&group_order), one);
+
+        let a = [0x1234567890abcdefu64, 0x42];
+        let b = [0xfedcba0987654321u64];
+
+        let expected = e.pow(&a).pow(&b);
+
+        let ap = p.mul(&a);
+        let bq = q.mul(&b);
+        let ap_bq = engine.pair(&[ap], &[bq]).unwrap();
+        assert_eq!(ap_bq, expected);
+
+        let abp = p.mul(&a).mul(&b);
+        let e_abp_q = engine.pair(&[abp], &[q.clone()]).unwrap();
+        assert_eq!(e_abp_q, expected);
+
+        let mut minus_p = p.clone();
+        minus_p.negate();
+        let product = engine.pair(&[p.clone(), minus_p], &[q.clone(), q.clone()]).unwrap();
+        assert_eq!(product, one);
+
+        // Miller loop and final exponentiation done separately give the same result
+        let miller_loop = engine.miller_loop_product(&[p], &[q]).unwrap();
+        let finalized = engine.final_exp(&miller_loop).unwrap();
+        assert_eq!(finalized, e);
+    }
+}
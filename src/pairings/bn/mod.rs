@@ -10,11 +10,14 @@ use crate::extension_towers::fp12_as_2_over3_over_2::{Fp12, Extension2Over3Over2
 use crate::extension_towers::fp6_as_3_over_2::{Extension3Over2};
 use crate::pairings::{PairingEngine};
 use crate::pairings::TwistType;
-use crate::pairings::{calculate_bits, calculate_hamming_weight, calculate_naf_hamming_weight, into_ternary_wnaf};
+use crate::pairings::subgroup;
+use crate::pairings::prepared_g2::{self, PreparedTwistPoint, LoopBinding};
+use crate::pairings::{normalize_nonzero_pairs, prepare_distinct_twists, DistinctTwists, multi_miller_loop, two_inverse, naf_after_leading_one, calculate_bits, calculate_hamming_weight, calculate_naf_hamming_weight, into_ternary_wnaf};
+use crate::integers::MaxFieldSquaredUint;
+use crate::execution_context::ExecutionContext;
+use crate::alloc_prelude::*;
 
-pub(crate) struct PreparedTwistPoint<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> {
-    pub(crate) ell_coeffs: Vec<(Fp2<'a, FE, F>, Fp2<'a, FE, F>, Fp2<'a, FE, F>)>
-}
+pub use crate::pairings::prepared_g2::{PreparedG2, PreparedG2Error};
 
 #[derive(Clone)]
 pub struct BnInstanceParams<
@@ -117,33 +120,53 @@ impl<
         CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
         CTW: CurveParameters<BaseFieldElement = Fp2<'a, FE, F>>
     > BnInstance<'a, FE, F, CB, CTW> {
-    fn ell(
+    // Line coefficients of all the pairs are scaled by the G1 coordinates with one batched
+    // multiplication, as products for different pairs are independent
+    fn ell_for_pairs(
         &self,
         f: &mut Fp12<'a, FE, F>,
-        coeffs: &(Fp2<'a, FE, F>, Fp2<'a, FE, F>, Fp2<'a, FE, F>),
-        p: & CurvePoint<'a, CB>,
+        pairs: &[(&CurvePoint<'a, CB>, &PreparedTwistPoint<'a, FE, F>)],
+        step: usize,
+        ctx: &ExecutionContext,
     ) {
-        debug_assert!(p.is_normalized());
-        let mut c0 = coeffs.0.clone();
-        let mut c1 = coeffs.1.clone();
-        let mut c2 = coeffs.2.clone();
+        let mut scaled = Vec::with_capacity(4 * pairs.len());
+        let mut scalars = Vec::with_capacity(4 * pairs.len());
+        for (p, prepared) in pairs.iter() {
+            debug_assert!(p.is_normalized());
+            let coeffs = &prepared.ell_coeffs[step];
+            let scaled_by_y = match self.twist_type {
+                TwistType::M => &coeffs.2,
+                TwistType::D => &coeffs.0,
+            };
+            scaled.extend_from_slice(&[scaled_by_y.c0, scaled_by_y.c1, coeffs.1.c0, coeffs.1.c1]);
+            scalars.extend_from_slice(&[p.y, p.y, p.x, p.x]);
+        }
 
-        match self.twist_type {
-            TwistType::M => {
-                c2.mul_by_fp(&p.y);
-                c1.mul_by_fp(&p.x);
-                f.mul_by_014(&c0, &c1, &c2);
-            },
-            TwistType::D => {
-                c0.mul_by_fp(&p.y);
-                c1.mul_by_fp(&p.x);
-                f.mul_by_034(&c0, &c1, &c2);
-            },
+        Fp::batch_mul_assign(&mut scaled, &scalars);
+
+        for (scaled, (_, prepared)) in scaled.chunks(4).zip(pairs.iter()) {
+            ctx.report().count_line_evaluation();
+            let (mut c0, mut c1, mut c2) = prepared.ell_coeffs[step].clone();
+            c1.c0 = scaled[2];
+            c1.c1 = scaled[3];
+
+            match self.twist_type {
+                TwistType::M => {
+                    c2.c0 = scaled[0];
+                    c2.c1 = scaled[1];
+                    f.mul_by_014(&c0, &c1, &c2);
+                },
+                TwistType::D => {
+                    c0.c0 = scaled[0];
+                    c0.c1 = scaled[1];
+                    f.mul_by_034(&c0, &c1, &c2);
+                },
+            }
         }
     }
 
     fn exp_by_x(&self, f: &mut Fp12<'a, FE, F>) {
-        *f = f.cyclotomic_exp(&self.u);
+        *f = f.cyclotomic_exp_compressed(&self.u);
         if self.u_is_negative {
             f.conjugate();
         }
@@ -383,7 +406,13 @@ impl<
         }
     }
 
-    fn prepare_naf(&self, twist_point: & CurvePoint<'a, CTW>, two_inv: &Fp<'a, FE, F>) -> PreparedTwistPoint<'a, FE, F> {
+    // `naf` are the digits of the loop parameter after the leading one, from the most significant
+    fn prepare_naf<'n, N: Iterator<Item = &'n i8>>(
+        &self,
+        twist_point: & CurvePoint<'a, CTW>,
+        two_inv: &Fp<'a, FE, F>,
+        naf: N
+    ) -> PreparedTwistPoint<'a, FE, F> {
         debug_assert!(twist_point.is_normalized());
 
         if twist_point.is_zero() {
@@ -399,14 +428,7 @@ impl<
 
         let mut r = CurvePoint::<CTW>::point_from_xy(&self.curve_twist, twist_point.x.clone(), twist_point.y.clone());
 
-        let mut it = self.six_u_plus_2_naf.iter().rev();
-        
-        {
-            let first = it.next().expect("naf has enough coefficients");
-            assert_eq!(*first, 1);
-        }
-
-        for &i in it {
+        for &i in naf {
             ell_coeffs.push(self.doubling_step(&mut r, &two_inv));
             
             if i != 0 {
@@ -443,113 +465,87 @@ impl<
         }
     }
 
-    fn miller_loop<'b, I>(&self, i: I) -> Fp12<'a, FE, F>
+    fn prepare_distinct_twists<'b, I>(&self, i: I, use_naf: bool) -> Result<DistinctTwists<'a, 'b, CB, PreparedTwistPoint<'a, FE, F>>, ()>
     where 'a: 'b,
         I: IntoIterator<
-            Item = &'b (&'b CurvePoint<'a, CB>, 
+            Item = &'b (&'b CurvePoint<'a, CB>,
                 &'b CurvePoint<'a, CTW>)
         >
     {
-        let mut g1_references = vec![];
-        let mut prepared_coeffs = vec![];
-
-        let mut two_inv = Fp::one(self.base_field);
-        two_inv.double();
-        let two_inv = two_inv.inverse().expect("inverse of 2 is guaranteed to exist");
-
-        for (p, q) in i.into_iter() {
-            if !p.is_zero() && !q.is_zero() {
-                let coeffs = self.prepare(&q.clone(), &two_inv);
-                let ell_coeffs = coeffs.ell_coeffs;
-                prepared_coeffs.push(ell_coeffs);
-                g1_references.push(p);
-            }
-        }
-
-        let mut prepared_coeffs: Vec<_> = prepared_coeffs.into_iter().map(|el| el.into_iter()).collect();
-
-        let mut f = Fp12::one(&self.fp12_extension);
-
-        for i in MsbBitIterator::new(&self.six_u_plus_2).skip(1) {    
-            f.square();
-
-            for (p, coeffs) in g1_references.iter().zip(prepared_coeffs.iter_mut()) {
-                self.ell(&mut f, &coeffs.next().unwrap(), p);
-            }
-
-            if i {
-                for (p, coeffs) in g1_references.iter().zip(prepared_coeffs.iter_mut()) {
-                    self.ell(&mut f, &coeffs.next().unwrap(), p);
-                }
-            }
-        }
-
-        // Another part of this (G2 -> -G2) is handled in the preparation of coeffs
-        // https://eprint.iacr.org/2013/722.pdf Algorithm 1
-        if self.u_is_negative {
-            f.conjugate();
-        }
+        let two_inv = two_inverse(self.base_field)?;
+        let naf = if use_naf {
+            Some(naf_after_leading_one(&self.six_u_plus_2_naf)?)
+        } else {
+            None
+        };
 
-        for (p, coeffs) in g1_references.iter().zip(prepared_coeffs.iter_mut()) {
-            self.ell(&mut f, &coeffs.next().unwrap(), p);
-        }
+        prepare_distinct_twists(i, |q| {
+            let prepared = match naf {
+                Some(ref naf) => self.prepare_naf(q, &two_inv, naf.clone()),
+                None => self.prepare(q, &two_inv),
+            };
 
-        for (p, coeffs) in g1_references.iter().zip(prepared_coeffs.iter_mut()) {
-            self.ell(&mut f, &coeffs.next().unwrap(), p);
-        }
+            Ok(prepared)
+        })
+    }
 
-        for (_p, coeffs) in g1_references.iter().zip(prepared_coeffs.iter_mut()) {
-            debug_assert!(coeffs.next().is_none());
-        }
+    fn miller_loop_naf<'b, I>(&self, i: I, ctx: &ExecutionContext) -> Result<Fp12<'a, FE, F>, ()>
+    where 'a: 'b,
+        I: IntoIterator<
+            Item = &'b (&'b CurvePoint<'a, CB>, 
+                &'b CurvePoint<'a, CTW>)
+        >
+    {
+        let (g1_references, twist_indexes, prepared_twists) = self.prepare_distinct_twists(i, true)?;
+        let pairs: Vec<_> = g1_references.into_iter().zip(twist_indexes.into_iter().map(|index| &prepared_twists[index])).collect();
 
-        f
+        self.miller_loop_with_prepared(&pairs, true, ctx)
     }
 
-    fn miller_loop_naf<'b, I>(&self, i: I) -> Fp12<'a, FE, F>
+    fn miller_loop<'b, I>(&self, i: I, ctx: &ExecutionContext) -> Result<Fp12<'a, FE, F>, ()>
     where 'a: 'b,
         I: IntoIterator<
             Item = &'b (&'b CurvePoint<'a, CB>, 
                 &'b CurvePoint<'a, CTW>)
         >
     {
-        let mut g1_references = vec![];
-        let mut prepared_coeffs = vec![];
-
-        let mut two_inv = Fp::one(self.base_field);
-        two_inv.double();
-        let two_inv = two_inv.inverse().expect("inverse of 2 is guaranteed to exist");
-
-        for (p, q) in i.into_iter() {
-            if !p.is_zero() && !q.is_zero() {
-                let coeffs = self.prepare_naf(&q.clone(), &two_inv);
-                let ell_coeffs = coeffs.ell_coeffs;
-                prepared_coeffs.push(ell_coeffs);
-                g1_references.push(p);
-            }
-        }
+        let (g1_references, twist_indexes, prepared_twists) = self.prepare_distinct_twists(i, false)?;
+        let pairs: Vec<_> = g1_references.into_iter().zip(twist_indexes.into_iter().map(|index| &prepared_twists[index])).collect();
+
+        self.miller_loop_with_prepared(&pairs, false, ctx)
+    }
 
-        let mut prepared_coeffs: Vec<_> = prepared_coeffs.into_iter().map(|el| el.into_iter()).collect();
+    // All the arithmetic on the twist is done in `prepare` or `prepare_naf`, here the prepared
+    // lines are only evaluated at the G1 points, and the accumulator is squared once per step
+    // for all the pairs. `use_naf` must match the way the twist points were prepared
+    fn miller_loop_with_prepared(
+        &self,
+        pairs: &[(&CurvePoint<'a, CB>, &PreparedTwistPoint<'a, FE, F>)],
+        use_naf: bool,
+        ctx: &ExecutionContext
+    ) -> Result<Fp12<'a, FE, F>, ()> {
+        let has_addition_step: Vec<bool> = if use_naf {
+            naf_after_leading_one(&self.six_u_plus_2_naf)?.map(|&i| i != 0).collect()
+        } else {
+            MsbBitIterator::new(&self.six_u_plus_2).skip(1).collect()
+        };
 
+        let mut step = 0;
         let mut f = Fp12::one(self.fp12_extension);
 
-        let mut it = self.six_u_plus_2_naf.iter().rev();
-        
-        {
-            let first = it.next().expect("naf has enough coefficients");
-            assert_eq!(*first, 1);
-        }
+        for addition in has_addition_step.into_iter() {
+            if !ctx.charge(pairs.len() + 1) {
+                return Ok(f);
+            }
 
-        for &i in it {
             f.square();
 
-            for (p, coeffs) in g1_references.iter().zip(prepared_coeffs.iter_mut()) {
-                self.ell(&mut f, &coeffs.next().expect("next miller loop element for doubling step"), p);
-            }
+            self.ell_for_pairs(&mut f, pairs, step, ctx);
+            step += 1;
 
-            if i != 0 {
-                for (p, coeffs) in g1_references.iter().zip(prepared_coeffs.iter_mut()) {
-                    self.ell(&mut f, &coeffs.next().expect("next miller loop element for addition step"), p);
-                }
+            if addition {
+                self.ell_for_pairs(&mut f, pairs, step, ctx);
+                step += 1;
             }
         }
 
@@ -559,15 +555,15 @@ impl<
             f.conjugate();
         }
 
-        for (p, coeffs) in g1_references.iter().zip(prepared_coeffs.iter_mut()) {
-            self.ell(&mut f, &coeffs.next().expect("next miller loop element for end of miller loop step 1"), p);
-        }
+        self.ell_for_pairs(&mut f, pairs, step, ctx);
+        step += 1;
 
-        for (p, coeffs) in g1_references.iter().zip(prepared_coeffs.iter_mut()) {
-            self.ell(&mut f, &coeffs.next().expect("next miller loop element for end of miller loop step 2"), p);
-        }
+        self.ell_for_pairs(&mut f, pairs, step, ctx);
+        step += 1;
 
-        f
+        debug_assert!(pairs.iter().all(|(_, prepared)| prepared.ell_coeffs.len() == step));
+
+        Ok(f)
     }
 
     fn final_exponentiation(&self, f: &Fp12<'a, FE, F>) -> Option<Fp12<'a, FE, F>> {
@@ -638,7 +634,7 @@ impl<
                 y6.mul_assign(&fu3p);
                 y6.conjugate();
 
-                y6.square();
+                y6.cyclotomic_square();
                 y6.mul_assign(&y4);
                 y6.mul_assign(&y5);
 
@@ -648,16 +644,16 @@ impl<
 
                 y6.mul_assign(&y2);
 
-                t1.square();
+                t1.cyclotomic_square();
                 t1.mul_assign(&y6);
-                t1.square();
+                t1.cyclotomic_square();
 
                 let mut t0 = t1.clone();
                 t0.mul_assign(&y1);
 
                 t1.mul_assign(&y0);
 
-                t0.square();
+                t0.cyclotomic_square();
                 t0.mul_assign(&t1);
 
                 Some(t0)
@@ -668,6 +664,74 @@ impl<
 }
 
 
+impl<
+    'a, 
+        FE: ElementRepr, 
+        F: SizedPrimeField<Repr = FE>, 
+        CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+        CTW: CurveParameters<BaseFieldElement = Fp2<'a, FE, F>>
+    > BnInstance<'a, FE, F, CB, CTW> {
+    fn loop_binding(&self) -> LoopBinding<'a, FE, F> {
+        LoopBinding {
+            loop_parameter: self.six_u_plus_2,
+            loop_parameter_is_negative: self.u_is_negative,
+            use_naf: self.prefer_naf,
+            twist_type: self.twist_type,
+            fp2_extension: self.fp2_extension,
+        }
+    }
+
+    /// Precomputes the lines of the Miller loop for `twist_point`. The result is only valid
+    /// for this engine instance
+    pub fn prepare_g2(&self, twist_point: &CurvePoint<'a, CTW>) -> Result<PreparedG2<'a, FE, F>, PreparedG2Error> {
+        let naf = if self.prefer_naf {
+            Some(naf_after_leading_one(&self.six_u_plus_2_naf).map_err(|_| PreparedG2Error::InvalidEngine)?)
+        } else {
+            None
+        };
+
+        prepared_g2::prepare_g2(twist_point, self.base_field, self.loop_binding(), |q, two_inv| {
+            match naf {
+                Some(naf) => self.prepare_naf(q, two_inv, naf),
+                None => self.prepare(q, two_inv),
+            }
+        })
+    }
+
+    /// Same as `miller_loop_product`, but with G2 points prepared by `prepare_g2`. Fails if
+    /// the lengths don't match, the input is empty or a point was prepared by another engine
+    pub fn miller_loop_with_prepared_g2(&self, points: &[CurvePoint<'a, CB>], prepared: &[&PreparedG2<'a, FE, F>]) -> Result<Fp12<'a, FE, F>, PreparedG2Error> {
+        let ctx = ExecutionContext::default();
+
+        prepared_g2::miller_loop_with_prepared_g2(points, prepared, &self.loop_binding(), self.fp12_extension, &ctx, |chunk| {
+            self.miller_loop_with_prepared(chunk, self.prefer_naf, &ctx)
+        })
+    }
+
+    /// Full pairing product with G2 points prepared by `prepare_g2`
+    pub fn pair_with_prepared_g2(&self, points: &[CurvePoint<'a, CB>], prepared: &[&PreparedG2<'a, FE, F>]) -> Result<Fp12<'a, FE, F>, PreparedG2Error> {
+        let loop_result = self.miller_loop_with_prepared_g2(points, prepared)?;
+
+        self.final_exp(&loop_result).ok_or(PreparedG2Error::InvalidEngine)
+    }
+
+    /// Same result as `check_correct_subgroup` for a G1 point, but usually without
+    /// a multiplication by the group order
+    pub fn g1_subgroup_check(&self, point: &CurvePoint<'a, CB>) -> bool {
+        subgroup::bn_g1_subgroup_check(point, self.u, self.u_is_negative, self.fp6_extension)
+    }
+
+    /// psi(Q) = [6u^2]Q. Exact subgroup check for BN254, but not for an arbitrary BN curve,
+    /// so callers must know the curve
+    pub(crate) fn g2_subgroup_check_by_psi(&self, point: &CurvePoint<'a, CTW>) -> bool {
+        let u = MaxFieldSquaredUint::from(self.u);
+        let six_u_squared = MaxFieldSquaredUint::from(6u64) * u * u;
+
+        subgroup::psi_eigenvalue_check(point, self.fp12_extension, self.twist_type, six_u_squared.as_ref(), false)
+    }
+}
+
+
 impl<
     'a, 
         FE: ElementRepr, 
@@ -679,8 +743,8 @@ impl<
     type G1 = CurvePoint<'a, CB>;
     type G2 = CurvePoint<'a, CTW>;
 
-    fn pair<'b>
-        (&self, points: &'b [CurvePoint<'a, CB>], twists: &'b [CurvePoint<'a, CTW>]) -> Option<Self::PairingResult> {
+    fn miller_loop_product_with_context<'b>
+        (&self, points: &'b [CurvePoint<'a, CB>], twists: &'b [CurvePoint<'a, CTW>], ctx: &ExecutionContext) -> Option<Self::PairingResult> {
             if points.len() != twists.len() {
                 return None;
             }
@@ -691,29 +755,26 @@ impl<
                 }
             }
             
-            let mut pairs = Vec::with_capacity(points.len());
-            for (p, q) in points.iter().zip(twists.iter()) {
-                if !p.is_zero() && !q.is_zero() {
-                    pairs.push((p, q));
-                }
-            }
+            let (g1, g2) = normalize_nonzero_pairs(points, twists);
+            let pairs: Vec<_> = g1.iter().zip(g2.iter()).collect();
 
             if pairs.len() == 0 {
                 return Some(Fp12::one(self.fp12_extension));
             }
 
             let loop_result = if self.prefer_naf {
-                let loop_result = self.miller_loop_naf(&pairs[..]);
-                debug_assert!(self.six_u_plus_2_naf.len() > 0);
+                debug_assert!(!self.six_u_plus_2_naf.is_empty());
 
-                loop_result
+                multi_miller_loop(&pairs[..], ctx, |chunk| self.miller_loop_naf(chunk, ctx))
             } else {
-                let loop_result = self.miller_loop(&pairs[..]);
-
-                loop_result
+                multi_miller_loop(&pairs[..], ctx, |chunk| self.miller_loop(chunk, ctx))
             };
 
-            self.final_exponentiation(&loop_result)
+            loop_result.ok()
+        }
+
+    fn final_exp(&self, f: &Fp12<'a, FE, F>) -> Option<Self::PairingResult> {
+            self.final_exponentiation(f)
         }   
 }
 
@@ -728,11 +789,10 @@ mod tests {
     use crate::extension_towers::fp12_as_2_over3_over_2::{Fp12, Extension2Over3Over2};
     use num_traits::Num;
     use crate::weierstrass::curve::{CurvePoint, WeierstrassCurve};
-    use crate::weierstrass::{CurveParameters, CurveOverFpParameters, CurveOverFp2Parameters};
+    use crate::weierstrass::{CurveOverFpParameters, CurveOverFp2Parameters};
     use crate::pairings::{PairingEngine};
     use crate::representation::ElementRepr;
     use crate::test::{biguint_to_u64_vec};
-    use crate::sliding_window_exp::WindowExpBase;
     use crate::integers::MaxFieldUint;
 
     #[test]
@@ -998,4 +1058,100 @@ mod tests {
         assert!(ans1 == ans2);
         assert!(ans1 == ans3);
     }
+
+    #[cfg(feature = "eip_196")]
+    #[test]
+    fn test_pairing_with_shared_twist_points() {
+        use crate::engines::bn254::*;
+        use crate::weierstrass::Group;
+
+        let engine = BN254_PAIRING_ENGINE.clone();
+        let mut naf_engine = BN254_PAIRING_ENGINE.clone();
+        naf_engine.six_u_plus_2_naf = crate::pairings::into_ternary_wnaf(naf_engine.six_u_plus_2);
+        naf_engine.prefer_naf = true;
+
+        // 8 pairs sharing 2 distinct twist points
+        let mut q1 = BN254_G2_GENERATOR.mul(vec![987654321]);
+        q1.normalize();
+        let mut points = vec![];
+        let mut twists = vec![];
+        for i in 0..8u64 {
+            let mut p = BN254_G1_GENERATOR.mul(vec![1000 + i]);
+            p.normalize();
+            points.push(p);
+            if i % 2 == 0 {
+                twists.push(BN254_G2_GENERATOR.clone());
+            } else {
+                twists.push(q1.clone());
+            }
+        }
+
+        let mut results = vec![];
+        for engine in vec![engine, naf_engine].iter() {
+            let mut naive = Fp12::one(&*BN254_EXT12_FIELD);
+            for (p, q) in points.iter().zip(twists.iter()) {
+                naive.mul_assign(&engine.pair(&[p.clone()], &[q.clone()]).unwrap());
+            }
+
+            let result = engine.pair(&points, &twists).unwrap();
+            assert!(result == naive);
+            results.push(result);
+        }
+
+        assert!(results[0] == results[1]);
+    }
+
+    #[test]
+    fn test_pairing_with_prepared_g2() {
+        use crate::engines::bn254::*;
+        use crate::weierstrass::Group;
+        use crate::pairings::prepared_g2::PreparedG2Error;
+
+        let engine = BN254_PAIRING_ENGINE.clone();
+        let mut naf_engine = BN254_PAIRING_ENGINE.clone();
+        naf_engine.six_u_plus_2_naf = crate::pairings::into_ternary_wnaf(naf_engine.six_u_plus_2);
+        naf_engine.prefer_naf = true;
+
+        let q0 = BN254_G2_GENERATOR.clone();
+        let q1 = BN254_G2_GENERATOR.mul(vec![987654321]);
+        let points: Vec<_> = (0..4u64).map(|i| BN254_G1_GENERATOR.mul(vec![1000 + i])).collect();
+        let twists = vec![q0.clone(), q1.clone(), q0.clone(), q1.clone()];
+
+        for engine in vec![&engine, &naf_engine].into_iter() {
+            let p0 = engine.prepare_g2(&q0).unwrap();
+            let p1 = engine.prepare_g2(&q1).unwrap();
+            let prepared = vec![&p0, &p1, &p0, &p1];
+
+            let expected = engine.pair(&points, &twists).unwrap();
+            assert!(engine.pair_with_prepared_g2(&points, &prepared).unwrap() == expected);
+
+            let expected = engine.miller_loop_product(&points, &twists).unwrap();
+            assert!(engine.miller_loop_with_prepared_g2(&points, &prepared).unwrap() == expected);
+
+            let zero = engine.prepare_g2(&CurvePoint::zero(&q0.curve)).unwrap();
+            assert!(zero.is_zero());
+            let result = engine.pair_with_prepared_g2(&points[..1], &[&zero]).unwrap();
+            assert!(result == Fp12::one(&*BN254_EXT12_FIELD));
+
+            assert!(engine.pair_with_prepared_g2(&points, &prepared[..1]).err() == Some(PreparedG2Error::LengthMismatch));
+        }
+
+        // lines depend on the way the loop parameter is represented
+        let prepared = engine.prepare_g2(&q0).unwrap();
+        assert!(naf_engine.pair_with_prepared_g2(&points[..1], &[&prepared]).err() == Some(PreparedG2Error::EngineMismatch));
+
+        // and on the loop parameter itself and the extension, that is compared by identity
+        let mut other_parameter = BN254_PAIRING_ENGINE.clone();
+        other_parameter.six_u_plus_2 = &[3u64];
+        assert!(other_parameter.miller_loop_with_prepared_g2(&points[..1], &[&prepared]).err() == Some(PreparedG2Error::EngineMismatch));
+
+        let mut other_sign = BN254_PAIRING_ENGINE.clone();
+        other_sign.u_is_negative = !other_sign.u_is_negative;
+        assert!(other_sign.miller_loop_with_prepared_g2(&points[..1], &[&prepared]).err() == Some(PreparedG2Error::EngineMismatch));
+
+        let mut other_extension = BN254_PAIRING_ENGINE.clone();
+        other_extension.fp2_extension = Box::leak(Box::new(engine.fp2_extension.clone()));
+        assert!(other_extension.miller_loop_with_prepared_g2(&points[..1], &[&prepared]).err() == Some(PreparedG2Error::EngineMismatch));
+        assert!(other_extension.prepare_g2(&q0).is_ok());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,576 @@
+use crate::field::SizedPrimeField;
+use crate::fp::Fp;
+use crate::representation::ElementRepr;
+use crate::traits::FieldElement;
+use crate::weierstrass::Group;
+use crate::weierstrass::{CurveParameters};
+use crate::weierstrass::curve::{WeierstrassCurve, CurvePoint};
+use crate::extension_towers::fp3::{Extension3};
+use crate::extension_towers::fp6_as_2_over_3::{Fp6, Extension2Over3};
+use crate::pairings::PairingEngine;
+use crate::pairings::TwistType;
+use crate::pairings::{normalize_nonzero_pairs, prepare_distinct_twists, DistinctTwists, multi_miller_loop, two_inverse, naf_after_leading_one, into_loop_digits};
+use crate::execution_context::ExecutionContext;
+use crate::alloc_prelude::*;
+
+/// Coefficients of a line evaluation in the Miller loop
+type LineCoefficients<'a, FE, F> = (Fp<'a, FE, F>, Fp<'a, FE, F>, Fp<'a, FE, F>);
+
+/// Lines of a twist point for the first and the second loop of the optimal ate pairing
+type PreparedLoops<'a, FE, F> = (PreparedTwistPoint<'a, FE, F>, PreparedTwistPoint<'a, FE, F>);
+
+// BW6 curves have embedding degree 6 and a sextic twist, so G2 is a group of points
+// over the base field itself and line functions have coefficients in Fp.
+// Fp6 is built as Fp3[v]/(v^2 - u) on top of Fp3 = Fp[u]/(u^3 - xi), so an element
+// has the same layout in powers of v as Fp12 has in powers of w for BLS12 curves
+// and the same sparse multiplications are used for line evaluation
+
+pub(crate) struct PreparedTwistPoint<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> {
+    pub(crate) ell_coeffs: Vec<LineCoefficients<'a, FE, F>>
+}
+
+#[derive(Clone)]
+pub struct Bw6InstanceParams<
+'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+    CTW: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>
+> {
+    pub ate_loop_count_1: &'a [u64],
+    pub ate_loop_count_1_is_negative: bool,
+    pub ate_loop_count_2: &'a [u64],
+    pub ate_loop_count_2_is_negative: bool,
+    pub final_exp_hard_part: &'a [u64],
+    pub twist_type: TwistType,
+    pub base_field: &'a F,
+    pub curve: &'a WeierstrassCurve<'a, CB>,
+    pub curve_twist: &'a WeierstrassCurve<'a, CTW>,
+    pub fp3_extension: &'a Extension3<'a, FE, F>,
+    pub fp6_extension: &'a Extension2Over3<'a, FE, F>,
+    pub force_no_naf: bool
+}
+
+#[derive(Clone)]
+pub struct Bw6Instance<
+    'a,
+        FE: ElementRepr,
+        F: SizedPrimeField<Repr = FE>,
+        CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+        CTW: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>
+    > {
+    pub ate_loop_count_1: &'a [u64],
+    pub ate_loop_count_1_is_negative: bool,
+    pub ate_loop_count_2: &'a [u64],
+    pub ate_loop_count_2_is_negative: bool,
+    pub final_exp_hard_part: &'a [u64],
+    pub twist_type: TwistType,
+    pub base_field: &'a F,
+    pub curve: &'a WeierstrassCurve<'a, CB>,
+    pub curve_twist: &'a WeierstrassCurve<'a, CTW>,
+    pub fp3_extension: &'a Extension3<'a, FE, F>,
+    pub fp6_extension: &'a Extension2Over3<'a, FE, F>,
+    pub ate_loop_1_digits: Vec<i8>,
+    pub ate_loop_2_digits: Vec<i8>
+}
+
+impl<
+    'a,
+        FE: ElementRepr,
+        F: SizedPrimeField<Repr = FE>,
+        CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+        CTW: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>
+    > Bw6Instance<'a, FE, F, CB, CTW>
+{
+    pub fn from_params(params: Bw6InstanceParams::<'a, FE, F, CB, CTW>) -> Self {
+        let ate_loop_1_digits = into_loop_digits(params.ate_loop_count_1, params.force_no_naf);
+        let ate_loop_2_digits = into_loop_digits(params.ate_loop_count_2, params.force_no_naf);
+
+        Self {
+            ate_loop_count_1: params.ate_loop_count_1,
+            ate_loop_count_1_is_negative: params.ate_loop_count_1_is_negative,
+            ate_loop_count_2: params.ate_loop_count_2,
+            ate_loop_count_2_is_negative: params.ate_loop_count_2_is_negative,
+            final_exp_hard_part: params.final_exp_hard_part,
+            twist_type: params.twist_type,
+            base_field: params.base_field,
+            curve: params.curve,
+            curve_twist: params.curve_twist,
+            fp3_extension: params.fp3_extension,
+            fp6_extension: params.fp6_extension,
+            ate_loop_1_digits,
+            ate_loop_2_digits
+        }
+    }
+}
+
+impl<
+    'a,
+        FE: ElementRepr,
+        F: SizedPrimeField<Repr = FE>,
+        CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+        CTW: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>
+    > Bw6Instance<'a, FE, F, CB, CTW> {
+    fn ell(
+        &self,
+        f: &mut Fp6<'a, FE, F>,
+        coeffs: &LineCoefficients<'a, FE, F>,
+        p: & CurvePoint<'a, CB>,
+    ) {
+        debug_assert!(p.is_normalized());
+        let mut c0 = coeffs.0;
+        let mut c1 = coeffs.1;
+        let mut c2 = coeffs.2;
+
+        match self.twist_type {
+            TwistType::M => {
+                c2.mul_assign(&p.y);
+                c1.mul_assign(&p.x);
+                f.mul_by_014(&c0, &c1, &c2);
+            },
+            TwistType::D => {
+                c0.mul_assign(&p.y);
+                c1.mul_assign(&p.x);
+                f.mul_by_034(&c0, &c1, &c2);
+            },
+        }
+    }
+
+    fn doubling_step(
+        &self,
+        r: &mut CurvePoint<'a, CTW>,
+        two_inv: &Fp<'a, FE, F>,
+    ) -> LineCoefficients<'a, FE, F> {
+        // same adapted ZEXE formulas as for BLS12, but over the base field
+        let mut a = r.x;
+        a.mul_assign(&r.y);
+        a.mul_assign(two_inv);
+        let mut b = r.y;
+        b.square();
+        let mut c = r.z;
+        c.square();
+
+        let mut e = self.curve_twist.b;
+        let mut t0 = c;
+        t0.double();
+        t0.add_assign(&c);
+
+        e.mul_assign(&t0);
+
+        let mut f = e;
+        f.double();
+        f.add_assign(&e);
+
+        let mut g = b;
+        g.add_assign(&f);
+        g.mul_assign(two_inv);
+
+        let mut h = r.y;
+        h.add_assign(&r.z);
+        h.square();
+
+        let mut t1 = b;
+        t1.add_assign(&c);
+
+        h.sub_assign(&t1);
+
+        let mut i = e;
+        i.sub_assign(&b);
+
+        let mut j = r.x;
+        j.square();
+
+        let mut e_square = e;
+        e_square.square();
+
+        r.x = b;
+        r.x.sub_assign(&f);
+        r.x.mul_assign(&a);
+
+        let mut e_square_by_3 = e_square;
+        e_square_by_3.double();
+        e_square_by_3.add_assign(&e_square);
+
+        r.y = g;
+        r.y.square();
+        r.y.sub_assign(&e_square_by_3);
+
+        r.z = b;
+        r.z.mul_assign(&h);
+
+        let mut j_by_three = j;
+        j_by_three.double();
+        j_by_three.add_assign(&j);
+        h.negate();
+
+        match self.twist_type {
+            TwistType::M => {
+                (i, j_by_three, h)
+            },
+            TwistType::D => {
+                (h, j_by_three, i)
+            },
+        }
+    }
+
+    fn addition_step(
+        &self,
+        r: &mut CurvePoint<'a, CTW>,
+        q: & CurvePoint<'a, CTW>,
+    ) -> LineCoefficients<'a, FE, F> {
+        debug_assert!(q.is_normalized());
+        let mut theta = q.y;
+        theta.mul_assign(&r.z);
+        theta.negate();
+        theta.add_assign(&r.y);
+
+        let mut lambda = q.x;
+        lambda.mul_assign(&r.z);
+        lambda.negate();
+        lambda.add_assign(&r.x);
+
+        let mut c = theta;
+        c.square();
+        let mut d = lambda;
+        d.square();
+        let mut e = lambda;
+        e.mul_assign(&d);
+        let mut f = r.z;
+        f.mul_assign(&c);
+        let mut g = r.x;
+        g.mul_assign(&d);
+
+        let mut h = g;
+        h.double();
+        h.negate();
+        h.add_assign(&e);
+        h.add_assign(&f);
+
+        r.x = lambda;
+        r.x.mul_assign(&h);
+
+        let mut t0 = g;
+        t0.sub_assign(&h);
+        t0.mul_assign(&theta);
+
+        r.y.mul_assign(&e);
+        r.y.negate();
+        r.y.add_assign(&t0);
+
+        r.z.mul_assign(&e);
+
+        let mut t1 = lambda;
+        t1.mul_assign(&q.y);
+
+        let mut j = theta;
+        j.mul_assign(&q.x);
+        j.sub_assign(&t1);
+
+        theta.negate();
+        match self.twist_type {
+            TwistType::M => (j, theta, lambda),
+            TwistType::D => (lambda, theta, j),
+        }
+    }
+
+    fn prepare(&self, twist_point: & CurvePoint<'a, CTW>, digits: &[i8], two_inv: &Fp<'a, FE, F>) -> Result<PreparedTwistPoint<'a, FE, F>, ()> {
+        debug_assert!(twist_point.is_normalized());
+
+        if twist_point.is_zero() {
+            return Ok(PreparedTwistPoint {
+                ell_coeffs: vec![],
+            });
+        }
+
+        let mut ell_coeffs = Vec::with_capacity(digits.len() * 2);
+
+        let mut twist_point_negated = twist_point.clone();
+        twist_point_negated.negate();
+
+        let mut r = CurvePoint::<CTW>::point_from_xy(self.curve_twist, twist_point.x, twist_point.y);
+
+        let it = naf_after_leading_one(digits)?;
+
+        for &i in it {
+            ell_coeffs.push(self.doubling_step(&mut r, two_inv));
+
+            if i != 0 {
+                if i > 0 {
+                    ell_coeffs.push(self.addition_step(&mut r, twist_point));
+                } else {
+                    ell_coeffs.push(self.addition_step(&mut r, &twist_point_negated));
+                }
+            }
+        }
+
+        Ok(PreparedTwistPoint {
+            ell_coeffs,
+        })
+    }
+
+    fn prepare_distinct_twists<'b, I>(&self, i: I) -> Result<DistinctTwists<'a, 'b, CB, PreparedLoops<'a, FE, F>>, ()>
+    where 'a: 'b,
+        I: IntoIterator<
+            Item = &'b (&'b CurvePoint<'a, CB>,
+                &'b CurvePoint<'a, CTW>)
+        >
+    {
+        let two_inv = two_inverse(self.base_field)?;
+
+        // Both loops of the optimal ate pairing use the same twist point
+        prepare_distinct_twists(i, |q| {
+            let coeffs_1 = self.prepare(q, &self.ate_loop_1_digits, &two_inv)?;
+            let coeffs_2 = self.prepare(q, &self.ate_loop_2_digits, &two_inv)?;
+
+            Ok((coeffs_1, coeffs_2))
+        })
+    }
+
+    fn single_loop(
+        &self,
+        g1_references: &[&CurvePoint<'a, CB>],
+        prepared_coeffs: &[&PreparedTwistPoint<'a, FE, F>],
+        digits: &[i8],
+        is_negative: bool,
+        ctx: &ExecutionContext
+    ) -> Fp6<'a, FE, F> {
+        let mut step = 0;
+
+        let mut f = Fp6::one(self.fp6_extension);
+
+        for &i in digits.iter().rev().skip(1) {
+            if !ctx.charge(g1_references.len() + 1) {
+                return f;
+            }
+
+            f.square();
+
+            for (p, coeffs) in g1_references.iter().zip(prepared_coeffs.iter()) {
+                self.ell(&mut f, &coeffs.ell_coeffs[step], p);
+            }
+            step += 1;
+
+            if i != 0 {
+                for (p, coeffs) in g1_references.iter().zip(prepared_coeffs.iter()) {
+                    self.ell(&mut f, &coeffs.ell_coeffs[step], p);
+                }
+                step += 1;
+            }
+        }
+
+        if is_negative {
+            f.conjugate();
+        }
+
+        f
+    }
+
+    fn miller_loop<'b, I>(&self, i: I, ctx: &ExecutionContext) -> Result<Fp6<'a, FE, F>, ()>
+    where 'a: 'b,
+        I: IntoIterator<
+            Item = &'b (&'b CurvePoint<'a, CB>,
+                &'b CurvePoint<'a, CTW>)
+        >
+    {
+        // Optimal ate pairing for BW6 curves uses two loop counts such that
+        // r | ate_loop_count_1 + ate_loop_count_2 * p, and the Miller loop value is
+        // f_{ate_loop_count_1, Q}(P) * f_{ate_loop_count_2, Q}(P)^p
+        let (g1_references, twist_indexes, prepared_coeffs) = self.prepare_distinct_twists(i)?;
+
+        let coeffs_1: Vec<_> = twist_indexes.iter().map(|&index| &prepared_coeffs[index].0).collect();
+        let coeffs_2: Vec<_> = twist_indexes.iter().map(|&index| &prepared_coeffs[index].1).collect();
+
+        let mut f = self.single_loop(&g1_references, &coeffs_1, &self.ate_loop_1_digits, self.ate_loop_count_1_is_negative, ctx);
+        let mut f_2 = self.single_loop(&g1_references, &coeffs_2, &self.ate_loop_2_digits, self.ate_loop_count_2_is_negative, ctx);
+        f_2.frobenius_map(1);
+        f.mul_assign(&f_2);
+
+        Ok(f)
+    }
+
+    fn final_exponentiation(&self, f: &Fp6<'a, FE, F>) -> Option<Fp6<'a, FE, F>> {
+        // Final exponent is (p^6 - 1)/r = (p^3 - 1)(p + 1) * (p^2 - p + 1)/r.
+        // Hard part is supplied as a number, as there is no common polynomial
+        // representation for BW6 curves built over different inner curves
+
+        match f.inverse() {
+            Some(f_inv) => {
+                // f1 = f^(p^3) is a conjugation as v^(p^3) = -v
+                let mut f1 = *f;
+                f1.conjugate();
+
+                // r = f^(p^3 - 1)
+                let mut r = f1;
+                r.mul_assign(&f_inv);
+
+                // r = f^((p^3 - 1)(p + 1))
+                let f2 = r;
+                r.frobenius_map(1);
+                r.mul_assign(&f2);
+
+                // now r is in the cyclotomic subgroup, so inversion is a conjugation
+                Some(r.cyclotomic_exp(self.final_exp_hard_part))
+            },
+            None => None,
+        }
+    }
+}
+
+
+impl<
+    'a,
+        FE: ElementRepr,
+        F: SizedPrimeField<Repr = FE>,
+        CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+        CTW: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>
+    > PairingEngine for Bw6Instance<'a, FE, F, CB, CTW> {
+    type PairingResult = Fp6<'a, FE, F>;
+    type G1 = CurvePoint<'a, CB>;
+    type G2 = CurvePoint<'a, CTW>;
+
+    fn miller_loop_product_with_context<'b>
+        (&self, points: &'b [CurvePoint<'a, CB>], twists: &'b [CurvePoint<'a, CTW>], ctx: &ExecutionContext) -> Option<Self::PairingResult> {
+            if points.len() != twists.len() {
+                return None;
+            }
+
+            if !crate::features::in_gas_metering() && (points.is_empty() || twists.is_empty()) {
+                return None;
+            }
+
+            let (g1, g2) = normalize_nonzero_pairs(points, twists);
+            let pairs: Vec<_> = g1.iter().zip(g2.iter()).collect();
+
+            if pairs.is_empty() {
+                return Some(Fp6::one(self.fp6_extension));
+            }
+
+            let loop_result = multi_miller_loop(&pairs[..], ctx, |chunk| self.miller_loop(chunk, ctx));
+
+            loop_result.ok()
+        }
+
+    fn final_exp(&self, f: &Fp6<'a, FE, F>) -> Option<Self::PairingResult> {
+            self.final_exponentiation(f)
+        }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use crate::field::{U256Repr, new_field};
+    use crate::fp::Fp;
+    use crate::traits::{FieldElement, ZeroAndOne};
+    use crate::extension_towers::Fp3Fp6FrobeniusBaseElements;
+    use crate::extension_towers::fp3::{Fp3, Extension3};
+    use crate::extension_towers::fp6_as_2_over_3::{Fp6, Extension2Over3};
+    use num_traits::Num;
+    use crate::weierstrass::curve::{CurvePoint, WeierstrassCurve};
+    use crate::weierstrass::{Group, CurveOverFpParameters};
+    use crate::pairings::{PairingEngine, TwistType};
+    use crate::test::{biguint_to_u64_vec};
+    use crate::integers::MaxFieldUint;
+
+    fn fp_from_dec<'a>(field: &'a crate::field::PrimeField<U256Repr>, value: &str) -> Fp<'a, U256Repr, crate::field::PrimeField<U256Repr>> {
+        let bytes = BigUint::from_str_radix(value, 10).unwrap().to_bytes_be();
+
+        Fp::from_be_bytes(field, &bytes, true).unwrap()
+    }
+
+    // Small curve with embedding degree 6, y^2 = x^3 + 22 over a 139 bit field,
+    // Fp3 = Fp[u]/(u^3 - 11), Fp6 = Fp3[v]/(v^2 - u), D-type twist y^2 = x^3 + 2 over Fp
+    // and loop counts -1085797004 and -2344861129
+    #[test]
+    fn test_bw6_pairing_bilinearity() {
+        let modulus_dec = "461213518087780290495466348546711772191201";
+        let base_field = new_field::<U256Repr>(modulus_dec, 10).unwrap();
+        let modulus = BigUint::from_str_radix(modulus_dec, 10).unwrap();
+        let group_order_big = BigUint::from_str_radix("9223372036854788173", 10).unwrap();
+        let hard_part = (&modulus * &modulus - &modulus + 1u64) / &group_order_big;
+        let hard_part = biguint_to_u64_vec(hard_part);
+        let group_order = biguint_to_u64_vec(group_order_big);
+        let modulus = MaxFieldUint::from_big_endian(&modulus.to_bytes_be());
+
+        let fp_non_residue = fp_from_dec(&base_field, "11");
+
+        let precomp = Fp3Fp6FrobeniusBaseElements::construct(&modulus, &fp_non_residue).unwrap();
+        let mut extension_3 = Extension3::new(fp_non_residue);
+        extension_3.calculate_frobenius_coeffs_with_precomp(&precomp).unwrap();
+
+        let mut extension_6 = Extension2Over3::new(Fp3::zero(&extension_3));
+        extension_6.calculate_frobenius_coeffs_with_precomp(&precomp).unwrap();
+
+        let b_fp = fp_from_dec(&base_field, "22");
+        let mut b_twist = fp_non_residue.inverse().unwrap();
+        b_twist.mul_assign(&b_fp);
+        assert!(b_twist == fp_from_dec(&base_field, "2"));
+
+        let fp_params = CurveOverFpParameters::new(&base_field);
+
+        let curve = WeierstrassCurve::new(&group_order.as_ref(), Fp::zero(&base_field), b_fp, &fp_params).unwrap();
+        let twist = WeierstrassCurve::new(&group_order.as_ref(), Fp::zero(&base_field), b_twist, &fp_params).unwrap();
+
+        let p = CurvePoint::point_from_xy(
+            &curve,
+            fp_from_dec(&base_field, "344648827532110128529698186265253138548165"),
+            fp_from_dec(&base_field, "443923173248018889622668723838080333274019")
+        );
+
+        let q = CurvePoint::point_from_xy(
+            &twist,
+            fp_from_dec(&base_field, "226162852731288560233755255709866552999025"),
+            fp_from_dec(&base_field, "218500077896441239969305494774620509128679")
+        );
+
+        assert!(p.is_on_curve());
+        assert!(q.is_on_curve());
+        assert!(p.check_correct_subgroup());
+        assert!(q.check_correct_subgroup());
+
+        for &force_no_naf in [false, true].iter() {
+            let engine = super::Bw6InstanceParams {
+                ate_loop_count_1: &[1085797004],
+                ate_loop_count_1_is_negative: true,
+                ate_loop_count_2: &[2344861129],
+                ate_loop_count_2_is_negative: true,
+                final_exp_hard_part: &hard_part,
+                twist_type: TwistType::D,
+                base_field: &base_field,
+                curve: &curve,
+                curve_twist: &twist,
+                fp3_extension: &extension_3,
+                fp6_extension: &extension_6,
+                force_no_naf
+            };
+
+            let engine = super::Bw6Instance::from_params(engine);
+
+            let one = Fp6::one(&extension_6);
+
+            let e = engine.pair(&[p.clone()], &[q.clone()]).unwrap();
+            assert!(e != one);
+            assert_eq!(e.pow(&group_order), one);
+
+            let a = [0x1234567890abcdefu64];
+            let b = [0xfedcba0987654321u64];
+
+            let expected = e.pow(&a).pow(&b);
+
+            let ap = p.mul(&a);
+            let bq = q.mul(&b);
+            let ap_bq = engine.pair(&[ap], &[bq]).unwrap();
+            assert_eq!(ap_bq, expected);
+
+            let mut minus_p = p.clone();
+            minus_p.negate();
+            let product = engine.pair(&[p.clone(), minus_p], &[q.clone(), q.clone()]).unwrap();
+            assert_eq!(product, one);
+
+            // Miller loop and final exponentiation done separately give the same result
+            let miller_loop = engine.miller_loop_product(&[p.clone()], &[q.clone()]).unwrap();
+            let finalized = engine.final_exp(&miller_loop).unwrap();
+            assert_eq!(finalized, e);
+        }
+    }
+}
@@ -0,0 +1,583 @@
+use crate::field::SizedPrimeField;
+use crate::fp::Fp;
+use crate::representation::ElementRepr;
+use crate::traits::{FieldElement, ZeroAndOne};
+use crate::weierstrass::Group;
+use crate::weierstrass::{CurveParameters};
+use crate::weierstrass::curve::{WeierstrassCurve, CurvePoint};
+use crate::extension_towers::fpk::{FpK, ExtensionK};
+use crate::pairings::PairingEngine;
+use crate::pairings::{normalize_nonzero_pairs, multi_miller_loop, naf_after_leading_one, into_loop_digits};
+use crate::execution_context::ExecutionContext;
+use crate::alloc_prelude::*;
+
+// Generic ate pairing for curves with an arbitrary embedding degree k, such as the ones
+// produced by the Cocks-Pinch method. There is no twist that is known in advance, so G2 is
+// a group of points of the same curve over Fp^k = Fp[z]/(z^k - xi) and Miller loop is done
+// in affine coordinates over Fp^k. For odd k vertical lines are not eliminated by the final
+// exponentiation, so numerator and denominator of the Miller function are accumulated
+// separately and divided once at the end of the loop.
+// Final exponentiation is split as (p^k - 1)/r = (p^k - 1)/Phi_k(p) * Phi_k(p)/r where the
+// first (easy) part is evaluated using the Frobenius map and the second (hard) part is supplied
+// by the caller
+
+#[derive(Clone)]
+pub(crate) enum LineEvaluation<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> {
+    /// l(P) = y_P - lambda * x_P + c, v(P) = x_P - x of the resulting point
+    Sloped(FpK<'a, FE, F>, FpK<'a, FE, F>, FpK<'a, FE, F>),
+    /// l(P) = x_P - x, v(P) = 1, the resulting point is the point at infinity
+    Vertical(FpK<'a, FE, F>),
+    /// one of the points is at infinity, so l(P) = v(P) = 1
+    Trivial
+}
+
+pub(crate) struct PreparedPoint<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> {
+    pub(crate) lines: Vec<LineEvaluation<'a, FE, F>>,
+    pub(crate) x: FpK<'a, FE, F>,
+    // x coordinate of the [ate_loop_count]Q, if it's not the point at infinity
+    pub(crate) final_x: Option<FpK<'a, FE, F>>
+}
+
+#[derive(Clone)]
+pub struct CocksPinchInstanceParams<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+    CTW: CurveParameters<BaseFieldElement = FpK<'a, FE, F>>
+> {
+    pub ate_loop_count: &'a [u64],
+    pub ate_loop_count_is_negative: bool,
+    pub final_exp_hard_part: &'a [u64],
+    pub base_field: &'a F,
+    pub curve: &'a WeierstrassCurve<'a, CB>,
+    pub curve_over_extension: &'a WeierstrassCurve<'a, CTW>,
+    pub fpk_extension: &'a ExtensionK<'a, FE, F>,
+    pub force_no_naf: bool
+}
+
+#[derive(Clone)]
+pub struct CocksPinchInstance<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+    CTW: CurveParameters<BaseFieldElement = FpK<'a, FE, F>>
+> {
+    pub ate_loop_count: &'a [u64],
+    pub ate_loop_count_is_negative: bool,
+    pub final_exp_hard_part: &'a [u64],
+    pub base_field: &'a F,
+    pub curve: &'a WeierstrassCurve<'a, CB>,
+    pub curve_over_extension: &'a WeierstrassCurve<'a, CTW>,
+    pub fpk_extension: &'a ExtensionK<'a, FE, F>,
+    pub ate_loop_digits: Vec<i8>,
+    pub easy_part_coeffs: Vec<i64>
+}
+
+/// Coefficients of the cyclotomic polynomial Phi_n, lowest degree first
+pub(crate) fn cyclotomic_polynomial(n: usize) -> Vec<i64> {
+    debug_assert!(n >= 1);
+    // z^n - 1 is a product of Phi_d over all d | n
+    let mut result = vec![0i64; n + 1];
+    result[0] = -1;
+    result[n] = 1;
+    for d in 1..n {
+        if n % d == 0 {
+            result = divide_by_monic_polynomial(&result, &cyclotomic_polynomial(d));
+        }
+    }
+
+    result
+}
+
+/// Coefficients of (z^k - 1)/Phi_k(z), so the easy part of the final exponentiation
+/// is an exponentiation by this polynomial evaluated at p
+pub(crate) fn easy_part_polynomial(k: usize) -> Vec<i64> {
+    let mut z_k_minus_one = vec![0i64; k + 1];
+    z_k_minus_one[0] = -1;
+    z_k_minus_one[k] = 1;
+
+    divide_by_monic_polynomial(&z_k_minus_one, &cyclotomic_polynomial(k))
+}
+
+// exact division of integer polynomials
+fn divide_by_monic_polynomial(dividend: &[i64], divisor: &[i64]) -> Vec<i64> {
+    let divisor_degree = divisor.len() - 1;
+    debug_assert!(divisor[divisor_degree] == 1);
+    let mut remainder = dividend.to_vec();
+    let quotient_len = dividend.len() - divisor_degree;
+    let mut quotient = vec![0i64; quotient_len];
+    for i in (0..quotient_len).rev() {
+        let c = remainder[i + divisor_degree];
+        quotient[i] = c;
+        for (j, d) in divisor.iter().enumerate() {
+            remainder[i + j] -= c * d;
+        }
+    }
+    debug_assert!(remainder.iter().all(|&c| c == 0));
+
+    quotient
+}
+
+impl<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+    CTW: CurveParameters<BaseFieldElement = FpK<'a, FE, F>>
+> CocksPinchInstance<'a, FE, F, CB, CTW>
+{
+    pub fn from_params(params: CocksPinchInstanceParams::<'a, FE, F, CB, CTW>) -> Self {
+        let ate_loop_digits = into_loop_digits(&params.ate_loop_count, params.force_no_naf);
+        let easy_part_coeffs = easy_part_polynomial(params.fpk_extension.degree());
+
+        Self {
+            ate_loop_count: params.ate_loop_count,
+            ate_loop_count_is_negative: params.ate_loop_count_is_negative,
+            final_exp_hard_part: params.final_exp_hard_part,
+            base_field: params.base_field,
+            curve: params.curve,
+            curve_over_extension: params.curve_over_extension,
+            fpk_extension: params.fpk_extension,
+            ate_loop_digits,
+            easy_part_coeffs
+        }
+    }
+}
+
+impl<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+    CTW: CurveParameters<BaseFieldElement = FpK<'a, FE, F>>
+> CocksPinchInstance<'a, FE, F, CB, CTW> {
+    // evaluates the line and the vertical line at P and accumulates them
+    // into the numerator and denominator
+    fn ell(
+        &self,
+        num: &mut FpK<'a, FE, F>,
+        den: &mut FpK<'a, FE, F>,
+        line: &LineEvaluation<'a, FE, F>,
+        p: &CurvePoint<'a, CB>,
+    ) {
+        debug_assert!(p.is_normalized());
+        match line {
+            LineEvaluation::Sloped(lambda, c, x_next) => {
+                let mut l = *lambda;
+                l.mul_by_fp(&p.x);
+                l.negate();
+                l.add_assign(&c);
+                l.coeffs[0].add_assign(&p.y);
+                num.mul_assign(&l);
+
+                let mut v = *x_next;
+                v.negate();
+                v.coeffs[0].add_assign(&p.x);
+                den.mul_assign(&v);
+            },
+            LineEvaluation::Vertical(x) => {
+                let mut l = *x;
+                l.negate();
+                l.coeffs[0].add_assign(&p.x);
+                num.mul_assign(&l);
+            },
+            LineEvaluation::Trivial => {}
+        }
+    }
+
+    // line through R and S in affine coordinates, R is replaced by R + S
+    fn addition_step(
+        &self,
+        r: &mut Option<(FpK<'a, FE, F>, FpK<'a, FE, F>)>,
+        s: &(FpK<'a, FE, F>, FpK<'a, FE, F>),
+    ) -> Result<LineEvaluation<'a, FE, F>, ()> {
+        let (x_r, y_r) = match r {
+            Some(point) => *point,
+            None => {
+                *r = Some(*s);
+
+                return Ok(LineEvaluation::Trivial);
+            }
+        };
+
+        let (x_s, y_s) = *s;
+
+        let lambda = if x_r == x_s {
+            if y_r == y_s {
+                return self.doubling_step(r);
+            }
+
+            *r = None;
+
+            return Ok(LineEvaluation::Vertical(x_r));
+        } else {
+            let mut num = y_s;
+            num.sub_assign(&y_r);
+            let mut den = x_s;
+            den.sub_assign(&x_r);
+            let den_inv = den.inverse().ok_or(())?;
+            num.mul_assign(&den_inv);
+
+            num
+        };
+
+        let mut x_next = lambda;
+        x_next.square();
+        x_next.sub_assign(&x_r);
+        x_next.sub_assign(&x_s);
+
+        Ok(self.sloped_line(r, lambda, x_r, y_r, x_next))
+    }
+
+    // tangent line at R in affine coordinates, R is replaced by 2R
+    fn doubling_step(
+        &self,
+        r: &mut Option<(FpK<'a, FE, F>, FpK<'a, FE, F>)>,
+    ) -> Result<LineEvaluation<'a, FE, F>, ()> {
+        let (x_r, y_r) = match r {
+            Some(point) => *point,
+            None => {
+                return Ok(LineEvaluation::Trivial);
+            }
+        };
+
+        if y_r.is_zero() {
+            *r = None;
+
+            return Ok(LineEvaluation::Vertical(x_r));
+        }
+
+        // lambda = (3 * x^2 + a) / (2 * y)
+        let mut lambda = x_r;
+        lambda.square();
+        let t = lambda;
+        lambda.double();
+        lambda.add_assign(&t);
+        lambda.add_assign(&self.curve_over_extension.a);
+        let mut den = y_r;
+        den.double();
+        // nonzero elements of the extension are not invertible if the modulus
+        // of the extension is not irreducible
+        let den_inv = den.inverse().ok_or(())?;
+        lambda.mul_assign(&den_inv);
+
+        let mut x_next = lambda;
+        x_next.square();
+        x_next.sub_assign(&x_r);
+        x_next.sub_assign(&x_r);
+
+        Ok(self.sloped_line(r, lambda, x_r, y_r, x_next))
+    }
+
+    fn sloped_line(
+        &self,
+        r: &mut Option<(FpK<'a, FE, F>, FpK<'a, FE, F>)>,
+        lambda: FpK<'a, FE, F>,
+        x_r: FpK<'a, FE, F>,
+        y_r: FpK<'a, FE, F>,
+        x_next: FpK<'a, FE, F>
+    ) -> LineEvaluation<'a, FE, F> {
+        // y_next = lambda * (x_r - x_next) - y_r
+        let mut y_next = x_r;
+        y_next.sub_assign(&x_next);
+        y_next.mul_assign(&lambda);
+        y_next.sub_assign(&y_r);
+
+        *r = Some((x_next, y_next));
+
+        // c = lambda * x_r - y_r
+        let mut c = lambda;
+        c.mul_assign(&x_r);
+        c.sub_assign(&y_r);
+
+        LineEvaluation::Sloped(lambda, c, x_next)
+    }
+
+    fn prepare(&self, q: &CurvePoint<'a, CTW>) -> Result<PreparedPoint<'a, FE, F>, ()> {
+        debug_assert!(q.is_normalized());
+
+        let mut lines = Vec::with_capacity(self.ate_loop_digits.len() * 2);
+
+        let q_affine = (q.x, q.y);
+        let mut q_negated = q_affine;
+        q_negated.1.negate();
+
+        let mut r = Some(q_affine);
+
+        let it = naf_after_leading_one(&self.ate_loop_digits)?;
+
+        for &i in it {
+            lines.push(self.doubling_step(&mut r)?);
+
+            if i != 0 {
+                if i > 0 {
+                    lines.push(self.addition_step(&mut r, &q_affine)?);
+                } else {
+                    lines.push(self.addition_step(&mut r, &q_negated)?);
+                }
+            }
+        }
+
+        Ok(PreparedPoint {
+            lines,
+            x: q.x,
+            final_x: r.map(|(x, _)| x)
+        })
+    }
+
+    fn miller_loop<'b, I>(&self, i: I, ctx: &ExecutionContext) -> Option<FpK<'a, FE, F>>
+    where 'a: 'b,
+        I: IntoIterator<
+            Item = &'b (&'b CurvePoint<'a, CB>,
+                &'b CurvePoint<'a, CTW>)
+        >
+    {
+        let mut g1_references = vec![];
+        let mut prepared = vec![];
+        for (p, q) in i.into_iter() {
+            if !p.is_zero() && !q.is_zero() {
+                g1_references.push(*p);
+                prepared.push(self.prepare(q).ok()?);
+            }
+        }
+
+        let mut num = FpK::one(self.fpk_extension);
+        let mut den = FpK::one(self.fpk_extension);
+
+        let mut step = 0;
+
+        for &i in self.ate_loop_digits.iter().rev().skip(1) {
+            if !ctx.charge(g1_references.len() + 1) {
+                return None;
+            }
+
+            num.square();
+            den.square();
+
+            for (p, coeffs) in g1_references.iter().zip(prepared.iter()) {
+                self.ell(&mut num, &mut den, &coeffs.lines[step], p);
+            }
+            step += 1;
+
+            if i != 0 {
+                for (p, coeffs) in g1_references.iter().zip(prepared.iter()) {
+                    self.ell(&mut num, &mut den, &coeffs.lines[step], p);
+                    if i < 0 {
+                        // f_{-1, Q} is the inverse of the vertical line at Q
+                        let mut v = coeffs.x;
+                        v.negate();
+                        v.coeffs[0].add_assign(&p.x);
+                        den.mul_assign(&v);
+                    }
+                }
+                step += 1;
+            }
+        }
+
+        if self.ate_loop_count_is_negative {
+            // f_{-T, Q} = 1 / (f_{T, Q} * v_{[T]Q})
+            core::mem::swap(&mut num, &mut den);
+            for (p, coeffs) in g1_references.iter().zip(prepared.iter()) {
+                if let Some(x) = coeffs.final_x {
+                    let mut v = x;
+                    v.negate();
+                    v.coeffs[0].add_assign(&p.x);
+                    den.mul_assign(&v);
+                }
+            }
+        }
+
+        let den_inv = den.inverse()?;
+        num.mul_assign(&den_inv);
+
+        Some(num)
+    }
+
+    fn final_exponentiation(&self, f: &FpK<'a, FE, F>) -> Option<FpK<'a, FE, F>> {
+        let f_inv = f.inverse()?;
+
+        // f^((p^k - 1)/Phi_k(p)) = prod (f^(p^i))^(e_i) where e_i are the
+        // coefficients of (z^k - 1)/Phi_k(z)
+        let mut r = FpK::one(self.fpk_extension);
+        for (i, &e) in self.easy_part_coeffs.iter().enumerate() {
+            if e == 0 {
+                continue;
+            }
+            let base = if e > 0 { f } else { &f_inv };
+            let mut t = base.pow(&[e.unsigned_abs()]);
+            t.frobenius_map(i);
+            r.mul_assign(&t);
+        }
+
+        Some(r.pow(&self.final_exp_hard_part))
+    }
+}
+
+impl<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+    CTW: CurveParameters<BaseFieldElement = FpK<'a, FE, F>>
+> PairingEngine for CocksPinchInstance<'a, FE, F, CB, CTW> {
+    type PairingResult = FpK<'a, FE, F>;
+    type G1 = CurvePoint<'a, CB>;
+    type G2 = CurvePoint<'a, CTW>;
+
+    fn miller_loop_product_with_context<'b>
+        (&self, points: &'b [CurvePoint<'a, CB>], twists: &'b [CurvePoint<'a, CTW>], ctx: &ExecutionContext) -> Option<Self::PairingResult> {
+            if points.len() != twists.len() {
+                return None;
+            }
+
+            if !crate::features::in_gas_metering() {
+                if points.len() == 0 || twists.len() == 0 {
+                    return None;
+                }
+            }
+
+            let (g1, g2) = normalize_nonzero_pairs(points, twists);
+            let pairs: Vec<_> = g1.iter().zip(g2.iter()).collect();
+
+            if pairs.len() == 0 {
+                return Some(FpK::one(self.fpk_extension));
+            }
+
+            let loop_result = multi_miller_loop(&pairs[..], ctx, |chunk| self.miller_loop(chunk, ctx).ok_or(()));
+
+            loop_result.ok()
+        }
+
+    fn final_exp(&self, f: &FpK<'a, FE, F>) -> Option<Self::PairingResult> {
+            self.final_exponentiation(f)
+        }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use crate::field::{U256Repr, new_field};
+    use crate::fp::Fp;
+    use crate::traits::{FieldElement, ZeroAndOne};
+    use crate::extension_towers::fpk::{FpK, ExtensionK};
+    use num_traits::Num;
+    use crate::weierstrass::curve::{CurvePoint, WeierstrassCurve};
+    use crate::weierstrass::{Group, CurveOverFpParameters, CurveOverFpKParameters};
+    use crate::pairings::PairingEngine;
+    use crate::test::{biguint_to_u64_vec};
+    use crate::integers::MaxFieldUint;
+
+    fn fp_from_dec<'a>(field: &'a crate::field::PrimeField<U256Repr>, value: &str) -> Fp<'a, U256Repr, crate::field::PrimeField<U256Repr>> {
+        let bytes = BigUint::from_str_radix(value, 10).unwrap().to_bytes_be();
+
+        Fp::from_be_bytes(field, &bytes, true).unwrap()
+    }
+
+    #[test]
+    fn test_cyclotomic_polynomials() {
+        assert_eq!(super::cyclotomic_polynomial(1), vec![-1, 1]);
+        assert_eq!(super::cyclotomic_polynomial(6), vec![1, -1, 1]);
+        assert_eq!(super::cyclotomic_polynomial(12), vec![1, 0, -1, 0, 1]);
+        assert_eq!(super::easy_part_polynomial(5), vec![-1, 1]);
+        assert_eq!(super::easy_part_polynomial(6), vec![-1, -1, 0, 1, 1]);
+    }
+
+    // Small Cocks-Pinch curve with embedding degree 5, y^2 = x^3 + 7 over an 83 bit field
+    // with a 41 bit subgroup order, Fp5 = Fp[z]/(z^5 - 2). Ate loop count is t - 1 or
+    // (t - 1) mod r - r for the negative case
+    #[test]
+    fn test_cocks_pinch_pairing_bilinearity() {
+        let modulus_dec = "6669147293370943146157621";
+        let base_field = new_field::<U256Repr>(modulus_dec, 10).unwrap();
+        let modulus = BigUint::from_str_radix(modulus_dec, 10).unwrap();
+        let group_order_big = BigUint::from_str_radix("1099511628571", 10).unwrap();
+        let hard_part = biguint_to_u64_vec(BigUint::from_str_radix("1799208151994759109712912690976886054790052464049937194354814033004831421883430758152855", 10).unwrap());
+        let group_order = biguint_to_u64_vec(group_order_big.clone());
+        let modulus = MaxFieldUint::from_big_endian(&modulus.to_bytes_be());
+
+        let fp_non_residue = fp_from_dec(&base_field, "2");
+        let mut extension = ExtensionK::new(fp_non_residue, 5).unwrap();
+        extension.calculate_frobenius_coeffs(&modulus).unwrap();
+
+        let b_fp = fp_from_dec(&base_field, "7");
+        let fp_params = CurveOverFpParameters::new(&base_field);
+        let fpk_params = CurveOverFpKParameters::new(&extension);
+
+        let curve = WeierstrassCurve::new(&group_order.as_ref(), Fp::zero(&base_field), b_fp, &fp_params).unwrap();
+        let curve_over_extension = WeierstrassCurve::new(
+            &group_order.as_ref(),
+            FpK::zero(&extension),
+            FpK::from_fp(&extension, &b_fp),
+            &fpk_params
+        ).unwrap();
+
+        let p = CurvePoint::point_from_xy(
+            &curve,
+            fp_from_dec(&base_field, "3852697215329928555837742"),
+            fp_from_dec(&base_field, "485752745167586870853691")
+        );
+
+        let q_x: Vec<_> = ["84451820603761329244309", "6087127693465387264541652", "2464468506536579002497788", "6263390187983063537673046", "3197107704930740033495559"]
+            .iter().map(|c| fp_from_dec(&base_field, c)).collect();
+        let q_y: Vec<_> = ["2343156159961721298578647", "329734223208893644627253", "4902287778180822052674421", "562624720711297297974601", "2862101813947284359295557"]
+            .iter().map(|c| fp_from_dec(&base_field, c)).collect();
+
+        let q = CurvePoint::point_from_xy(
+            &curve_over_extension,
+            FpK::from_coeffs(&extension, &q_x).unwrap(),
+            FpK::from_coeffs(&extension, &q_y).unwrap()
+        );
+
+        assert!(p.is_on_curve());
+        assert!(q.is_on_curve());
+        assert!(p.check_correct_subgroup());
+        assert!(q.check_correct_subgroup());
+
+        // 3792171496596 = t - 1 and 605875017688 = r - ((t - 1) mod r)
+        for &is_negative in [false, true].iter() {
+            for &force_no_naf in [false, true].iter() {
+                let engine = super::CocksPinchInstanceParams {
+                    ate_loop_count: if is_negative { &[605875017688u64] } else { &[3792171496596u64] },
+                    ate_loop_count_is_negative: is_negative,
+                    final_exp_hard_part: &hard_part,
+                    base_field: &base_field,
+                    curve: &curve,
+                    curve_over_extension: &curve_over_extension,
+                    fpk_extension: &extension,
+                    force_no_naf
+                };
+
+                let engine = super::CocksPinchInstance::from_params(engine);
+
+                let one = FpK::one(&extension);
+
+                let e = engine.pair(&[p.clone()], &[q.clone()]).unwrap();
+                assert!(e != one);
+                assert_eq!(e.pow(&group_order), one);
+
+                let a = [0x1234567890abcdefu64];
+                let b = [0xfedcba0987654321u64];
+
+                let expected = e.pow(&a).pow(&b);
+
+                let ap = p.mul(&a);
+                let bq = q.mul(&b);
+                let ap_bq = engine.pair(&[ap], &[bq]).unwrap();
+                assert_eq!(ap_bq, expected);
+
+                let mut minus_p = p.clone();
+                minus_p.negate();
+                let product = engine.pair(&[p.clone(), minus_p], &[q.clone(), q.clone()]).unwrap();
+                assert_eq!(product, one);
+
+                // Miller loop and final exponentiation done separately give the same result
+                let miller_loop = engine.miller_loop_product(&[p.clone()], &[q.clone()]).unwrap();
+                let finalized = engine.final_exp(&miller_loop).unwrap();
+                assert_eq!(finalized, e);
+            }
+        }
+    }
+}
@@ -0,0 +1,711 @@
+use crate::field::SizedPrimeField;
+use crate::fp::Fp;
+use crate::representation::ElementRepr;
+use crate::traits::{FieldElement, MsbBitIterator, ZeroAndOne};
+use crate::weierstrass::Group;
+use crate::weierstrass::{CurveParameters};
+use crate::weierstrass::curve::{WeierstrassCurve, CurvePoint};
+use crate::extension_towers::fp2::{Extension2};
+use crate::extension_towers::fp4_as_2_over_2::{Fp4, Extension2Over2};
+use crate::extension_towers::fp8_as_2_over_4_generic::{Extension2Over4};
+use crate::extension_towers::fp16_as_2_over_8::{Fp16, Extension2Over8};
+use crate::pairings::PairingEngine;
+use crate::pairings::TwistType;
+use crate::pairings::{normalize_nonzero_pairs, prepare_distinct_twists, DistinctTwists, multi_miller_loop, naf_after_leading_one, calculate_bits, calculate_hamming_weight, calculate_naf_hamming_weight, into_ternary_wnaf};
+use crate::execution_context::ExecutionContext;
+use crate::alloc_prelude::*;
+
+/// Coefficients of a line evaluation in the Miller loop
+type LineCoefficients<'a, FE, F> = (Fp4<'a, FE, F>, Fp4<'a, FE, F>);
+
+// KSS16 curves y^2 = x^3 + a*x with seed x have
+// p = (x^10 + 2x^9 + 5x^8 + 48x^6 + 152x^5 + 240x^4 + 625x^2 + 2398x + 3125)/980
+// and r = (x^8 + 48x^4 + 625)/61250. Optimal ate pairing uses x*p^3 + p^4 + 2 = 0 mod r, so
+// f = (f_{x,Q}(P) * l_{[x]Q,[p]Q}(P))^(p^3) * l_{Q,Q}(P)
+// https://eprint.iacr.org/2007/390.pdf, https://eprint.iacr.org/2008/096.pdf
+
+// 14 * (p^8 + 1)/r = sum(lambda_i(x) * p^i), coefficients of lambda_i(x) are
+// listed from the lowest power of x
+const HARD_PART_COEFFICIENTS: [[i64; 10]; 8] = [
+    [1372, 0, 0, -1390, -1172, -278, 0, -55, -22, -11],
+    [0, 0, 1100, 1280, 220, 0, 75, 30, 15, 0],
+    [0, 4750, 3300, 950, 0, 125, 50, 25, 0, 0],
+    [-15000, -13000, -3000, 0, -625, -250, -125, 0, 0, 0],
+    [4704, 0, 0, 145, -54, 29, 0, -10, -4, -2],
+    [0, 0, -2925, -2290, -585, 0, -100, -40, -20, 0],
+    [0, 5125, 4850, 1025, 0, 250, 100, 50, 0, 0],
+    [4375, 1750, 875, 0, 0, 0, 0, 0, 0, 0],
+];
+
+// Lines are stored as (lambda, lambda * x_T - y_T) for a slope lambda through
+// the affine point T on the twist
+pub(crate) struct PreparedTwistPoint<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> {
+    pub(crate) ell_coeffs: Vec<LineCoefficients<'a, FE, F>>
+}
+
+#[derive(Clone)]
+pub struct Kss16InstanceParams<
+'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+    CTW: CurveParameters<BaseFieldElement = Fp4<'a, FE, F>>
+> {
+    pub x: &'a [u64],
+    pub x_is_negative: bool,
+    pub twist_type: TwistType,
+    pub base_field: &'a F,
+    pub curve: &'a WeierstrassCurve<'a, CB>,
+    pub curve_twist: &'a WeierstrassCurve<'a, CTW>,
+    pub fp2_extension: &'a Extension2<'a, FE, F>,
+    pub fp4_extension: &'a Extension2Over2<'a, FE, F>,
+    pub fp8_extension: &'a Extension2Over4<'a, FE, F>,
+    pub fp16_extension: &'a Extension2Over8<'a, FE, F>,
+    pub force_no_naf: bool
+}
+
+#[derive(Clone)]
+pub struct Kss16Instance<
+    'a,
+        FE: ElementRepr,
+        F: SizedPrimeField<Repr = FE>,
+        CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+        CTW: CurveParameters<BaseFieldElement = Fp4<'a, FE, F>>
+    > {
+    pub x: &'a [u64],
+    pub x_is_negative: bool,
+    pub twist_type: TwistType,
+    pub base_field: &'a F,
+    pub curve: &'a WeierstrassCurve<'a, CB>,
+    pub curve_twist: &'a WeierstrassCurve<'a, CTW>,
+    pub fp2_extension: &'a Extension2<'a, FE, F>,
+    pub fp4_extension: &'a Extension2Over2<'a, FE, F>,
+    pub fp8_extension: &'a Extension2Over4<'a, FE, F>,
+    pub fp16_extension: &'a Extension2Over8<'a, FE, F>,
+    pub prefer_naf: bool,
+    pub x_naf: Vec<i8>,
+    pub twist_frobenius_x_coeff: Fp4<'a, FE, F>,
+    pub twist_frobenius_y_coeff: Fp4<'a, FE, F>
+}
+
+impl<
+    'a,
+        FE: ElementRepr,
+        F: SizedPrimeField<Repr = FE>,
+        CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+        CTW: CurveParameters<BaseFieldElement = Fp4<'a, FE, F>>
+    > Kss16Instance<'a, FE, F, CB, CTW>
+{
+    pub fn from_params(params: Kss16InstanceParams::<'a, FE, F, CB, CTW>) -> Self {
+        let (prefer_naf, naf) = if params.force_no_naf {
+            (false, vec![])
+        } else {
+            let naf_vec = into_ternary_wnaf(params.x);
+            let original_bits = calculate_bits(params.x);
+            let original_hamming = calculate_hamming_weight(params.x);
+            let naf_hamming = calculate_naf_hamming_weight(&naf_vec);
+            let naf_length = naf_vec.len() as u32;
+
+            let naf_is_beneficial = naf_length + naf_hamming < original_bits + original_hamming;
+
+            if naf_is_beneficial {
+                (true, naf_vec)
+            } else {
+                (false, vec![])
+            }
+        };
+
+        // Frobenius on the twist is untwist-Frobenius-twist, that for D type twist
+        // (x, y) -> (x * t^2, y * t^3) with t^4 = xi is
+        // (x, y) -> (x^p * xi^((p - 1)/2), y^p * xi^(3(p - 1)/4)),
+        // and uses inverse coefficients for M type twist
+        let mut x_coeff = params.fp8_extension.frobenius_coeffs_c1[1];
+        let mut y_coeff = x_coeff;
+        y_coeff.mul_assign(&params.fp16_extension.frobenius_coeffs_c1[1]);
+        if params.twist_type == TwistType::M {
+            let zero = Fp4::zero(params.fp4_extension);
+            x_coeff = x_coeff.inverse().unwrap_or(zero);
+            y_coeff = y_coeff.inverse().unwrap_or(zero);
+        }
+
+        Self {
+            x: params.x,
+            x_is_negative: params.x_is_negative,
+            twist_type: params.twist_type,
+            base_field: params.base_field,
+            curve: params.curve,
+            curve_twist: params.curve_twist,
+            fp2_extension: params.fp2_extension,
+            fp4_extension: params.fp4_extension,
+            fp8_extension: params.fp8_extension,
+            fp16_extension: params.fp16_extension,
+            prefer_naf,
+            x_naf: naf,
+            twist_frobenius_x_coeff: x_coeff,
+            twist_frobenius_y_coeff: y_coeff
+        }
+    }
+}
+
+impl<
+    'a,
+        FE: ElementRepr,
+        F: SizedPrimeField<Repr = FE>,
+        CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+        CTW: CurveParameters<BaseFieldElement = Fp4<'a, FE, F>>
+    > Kss16Instance<'a, FE, F, CB, CTW> {
+    fn ell(
+        &self,
+        f: &mut Fp16<'a, FE, F>,
+        coeffs: &LineCoefficients<'a, FE, F>,
+        p: & CurvePoint<'a, CB>,
+    ) {
+        debug_assert!(p.is_normalized());
+        // line y - lambda * x - (y_T - lambda * x_T) is evaluated at the untwisted P.
+        // For D type twist it's y_P - lambda * x_P * t + (lambda * x_T - y_T) * t^3,
+        // for M type twist it's multiplied by t^4 = xi that is in a proper subfield
+        let mut lambda_by_x = coeffs.0;
+        lambda_by_x.mul_by_fp(&p.x);
+        lambda_by_x.negate();
+
+        match self.twist_type {
+            TwistType::M => {
+                let mut c0 = self.fp16_extension.field.non_residue;
+                c0.mul_by_fp(&p.y);
+                f.mul_by_023(&c0, &coeffs.1, &lambda_by_x);
+            },
+            TwistType::D => {
+                let mut c0 = Fp4::zero(self.fp4_extension);
+                c0.c0.c0 = p.y;
+                f.mul_by_023(&c0, &lambda_by_x, &coeffs.1);
+            },
+        }
+    }
+
+    fn exp_by_x(&self, f: &mut Fp16<'a, FE, F>) {
+        *f = f.cyclotomic_exp(self.x);
+        if self.x_is_negative {
+            f.conjugate();
+        }
+    }
+
+    // The twist has a non-zero "a" coefficient, so both steps work in affine coordinates
+    fn doubling_step(
+        &self,
+        r: &mut (Fp4<'a, FE, F>, Fp4<'a, FE, F>),
+    ) -> LineCoefficients<'a, FE, F> {
+        let zero = Fp4::zero(self.fp4_extension);
+
+        // lambda = (3 * x^2 + a) / (2 * y)
+        let mut lambda = r.0;
+        lambda.square();
+        let mut t0 = lambda;
+        lambda.double();
+        lambda.add_assign(&t0);
+        lambda.add_assign(&self.curve_twist.a);
+
+        t0 = r.1;
+        t0.double();
+        lambda.mul_assign(&t0.inverse().unwrap_or(zero));
+
+        self.apply_step(r, &lambda, &r.0.clone())
+    }
+
+    fn addition_step(
+        &self,
+        r: &mut (Fp4<'a, FE, F>, Fp4<'a, FE, F>),
+        q: &(Fp4<'a, FE, F>, Fp4<'a, FE, F>),
+    ) -> LineCoefficients<'a, FE, F> {
+        let zero = Fp4::zero(self.fp4_extension);
+
+        // lambda = (y_Q - y_R) / (x_Q - x_R)
+        let mut lambda = q.1;
+        lambda.sub_assign(&r.1);
+
+        let mut t0 = q.0;
+        t0.sub_assign(&r.0);
+        lambda.mul_assign(&t0.inverse().unwrap_or(zero));
+
+        self.apply_step(r, &lambda, &q.0)
+    }
+
+    // sets r = r + q for a line with slope lambda through r and q and
+    // returns its coefficients
+    fn apply_step(
+        &self,
+        r: &mut (Fp4<'a, FE, F>, Fp4<'a, FE, F>),
+        lambda: &Fp4<'a, FE, F>,
+        q_x: &Fp4<'a, FE, F>
+    ) -> LineCoefficients<'a, FE, F> {
+        let mut c = *lambda;
+        c.mul_assign(&r.0);
+        c.sub_assign(&r.1);
+
+        let mut x = *lambda;
+        x.square();
+        x.sub_assign(&r.0);
+        x.sub_assign(q_x);
+
+        let mut y = r.0;
+        y.sub_assign(&x);
+        y.mul_assign(lambda);
+        y.sub_assign(&r.1);
+
+        r.0 = x;
+        r.1 = y;
+
+        (*lambda, c)
+    }
+
+    fn prepare(&self, twist_point: & CurvePoint<'a, CTW>) -> Result<PreparedTwistPoint<'a, FE, F>, ()> {
+        debug_assert!(twist_point.is_normalized());
+
+        if twist_point.is_zero() {
+            return Ok(PreparedTwistPoint {
+                ell_coeffs: vec![],
+            });
+        }
+
+        let mut ell_coeffs = Vec::with_capacity(self.x.len() * 64 * 2);
+        let q = (twist_point.x, twist_point.y);
+        let mut r = q;
+
+        for i in MsbBitIterator::new(&self.x).skip(1) {
+            ell_coeffs.push(self.doubling_step(&mut r));
+
+            if i {
+                ell_coeffs.push(self.addition_step(&mut r, &q));
+            }
+        }
+
+        self.prepare_final_lines(&mut r, &q, &mut ell_coeffs);
+
+        Ok(PreparedTwistPoint {
+            ell_coeffs,
+        })
+    }
+
+    fn prepare_naf(&self, twist_point: & CurvePoint<'a, CTW>) -> Result<PreparedTwistPoint<'a, FE, F>, ()> {
+        debug_assert!(twist_point.is_normalized());
+
+        if twist_point.is_zero() {
+            return Ok(PreparedTwistPoint {
+                ell_coeffs: vec![],
+            });
+        }
+
+        let mut ell_coeffs = Vec::with_capacity(self.x.len() * 64 * 2);
+
+        let q = (twist_point.x, twist_point.y);
+        let mut q_negated = q;
+        q_negated.1.negate();
+
+        let mut r = q;
+
+        let it = naf_after_leading_one(&self.x_naf)?;
+
+        for &i in it {
+            ell_coeffs.push(self.doubling_step(&mut r));
+
+            if i != 0 {
+                if i > 0 {
+                    ell_coeffs.push(self.addition_step(&mut r, &q));
+                } else {
+                    ell_coeffs.push(self.addition_step(&mut r, &q_negated));
+                }
+            }
+        }
+
+        self.prepare_final_lines(&mut r, &q, &mut ell_coeffs);
+
+        Ok(PreparedTwistPoint {
+            ell_coeffs,
+        })
+    }
+
+    // Appends coefficients of the lines that follow the main loop: l_{[x]Q,[p]Q} and
+    // the tangent l_{Q,Q}. Here r = [|x|]Q as computed by the main loop
+    fn prepare_final_lines(
+        &self,
+        r: &mut (Fp4<'a, FE, F>, Fp4<'a, FE, F>),
+        q: &(Fp4<'a, FE, F>, Fp4<'a, FE, F>),
+        ell_coeffs: &mut Vec<LineCoefficients<'a, FE, F>>
+    ) {
+        let mut qp = *q;
+        qp.0.frobenius_map(1);
+        qp.0.mul_assign(&self.twist_frobenius_x_coeff);
+        qp.1.frobenius_map(1);
+        qp.1.mul_assign(&self.twist_frobenius_y_coeff);
+
+        if self.x_is_negative {
+            r.1.negate();
+        }
+
+        ell_coeffs.push(self.addition_step(r, &qp));
+
+        let mut q2 = *q;
+        ell_coeffs.push(self.doubling_step(&mut q2));
+    }
+
+    fn prepare_distinct_twists<'b, I>(&self, i: I, use_naf: bool) -> Result<DistinctTwists<'a, 'b, CB, Vec<LineCoefficients<'a, FE, F>>>, ()>
+    where 'a: 'b,
+        I: IntoIterator<
+            Item = &'b (&'b CurvePoint<'a, CB>,
+                &'b CurvePoint<'a, CTW>)
+        >
+    {
+        prepare_distinct_twists(i, |q| {
+            let coeffs = if use_naf {
+                self.prepare_naf(q)?
+            } else {
+                self.prepare(q)?
+            };
+
+            Ok(coeffs.ell_coeffs)
+        })
+    }
+
+    fn miller_loop_naf<'b, I>(&self, i: I, ctx: &ExecutionContext) -> Result<Fp16<'a, FE, F>, ()>
+    where 'a: 'b,
+        I: IntoIterator<
+            Item = &'b (&'b CurvePoint<'a, CB>,
+                &'b CurvePoint<'a, CTW>)
+        >
+    {
+        let (g1_references, twist_indexes, prepared_coeffs) = self.prepare_distinct_twists(i, true)?;
+        let mut step = 0;
+
+        let mut f = Fp16::one(self.fp16_extension);
+
+        let it = naf_after_leading_one(&self.x_naf)?;
+
+        for &i in it {
+            if !ctx.charge(g1_references.len() + 1) {
+                return Ok(f);
+            }
+
+            f.square();
+
+            for (p, &index) in g1_references.iter().zip(twist_indexes.iter()) {
+                self.ell(&mut f, &prepared_coeffs[index][step], p);
+            }
+            step += 1;
+
+            if i != 0 {
+                for (p, &index) in g1_references.iter().zip(twist_indexes.iter()) {
+                    self.ell(&mut f, &prepared_coeffs[index][step], p);
+                }
+                step += 1;
+            }
+        }
+
+        self.finalize_miller_loop(&mut f, &g1_references, &twist_indexes, &prepared_coeffs, step);
+
+        Ok(f)
+    }
+
+    fn miller_loop<'b, I>(&self, i: I, ctx: &ExecutionContext) -> Result<Fp16<'a, FE, F>, ()>
+    where 'a: 'b,
+        I: IntoIterator<
+            Item = &'b (&'b CurvePoint<'a, CB>,
+                &'b CurvePoint<'a, CTW>)
+        >
+    {
+        let (g1_references, twist_indexes, prepared_coeffs) = self.prepare_distinct_twists(i, false)?;
+        let mut step = 0;
+
+        let mut f = Fp16::one(self.fp16_extension);
+
+        for i in MsbBitIterator::new(&self.x).skip(1) {
+            if !ctx.charge(g1_references.len() + 1) {
+                return Ok(f);
+            }
+
+            f.square();
+
+            for (p, &index) in g1_references.iter().zip(twist_indexes.iter()) {
+                self.ell(&mut f, &prepared_coeffs[index][step], p);
+            }
+            step += 1;
+
+            if i {
+                for (p, &index) in g1_references.iter().zip(twist_indexes.iter()) {
+                    self.ell(&mut f, &prepared_coeffs[index][step], p);
+                }
+                step += 1;
+            }
+        }
+
+        self.finalize_miller_loop(&mut f, &g1_references, &twist_indexes, &prepared_coeffs, step);
+
+        Ok(f)
+    }
+
+    // f = (f_{x,Q}(P) * l_{[x]Q,[p]Q}(P))^(p^3) * l_{Q,Q}(P), where step points to the
+    // coefficients appended by `prepare_final_lines`
+    fn finalize_miller_loop(
+        &self,
+        f: &mut Fp16<'a, FE, F>,
+        g1_references: &[&CurvePoint<'a, CB>],
+        twist_indexes: &[usize],
+        prepared_coeffs: &[Vec<LineCoefficients<'a, FE, F>>],
+        step: usize
+    ) {
+        if self.x_is_negative {
+            f.conjugate();
+        }
+
+        for (p, &index) in g1_references.iter().zip(twist_indexes.iter()) {
+            self.ell(f, &prepared_coeffs[index][step], p);
+        }
+
+        f.frobenius_map(3);
+
+        for (p, &index) in g1_references.iter().zip(twist_indexes.iter()) {
+            self.ell(f, &prepared_coeffs[index][step + 1], p);
+        }
+    }
+
+    fn final_exponentiation(&self, f: &Fp16<'a, FE, F>) -> Option<Fp16<'a, FE, F>> {
+        // Final exponent is (p^16 - 1)/r = (p^8 - 1) * (p^8 + 1)/r.
+        // For the hard part we use the decomposition of 14 * (p^8 + 1)/r in base p
+        // with coefficients being polynomials in x, so the result is a power of the reduced
+        // pairing coprime to r, that is still a non-degenerate bilinear map
+
+        match f.inverse() {
+            Some(f_inv) => {
+                // f1 = f^(p^8) is a conjugation as t^(p^8) = -t
+                let mut r = *f;
+                r.conjugate();
+
+                // r = f^(p^8 - 1)
+                r.mul_assign(&f_inv);
+
+                // now r has order dividing p^8 + 1, so inversion is a conjugation
+
+                // powers[j] = r^(x^j)
+                let mut powers = [r; 10];
+                for j in 1..10 {
+                    let mut t = powers[j-1];
+                    self.exp_by_x(&mut t);
+                    powers[j] = t;
+                }
+
+                let mut result = Fp16::one(self.fp16_extension);
+                for (i, coeffs) in HARD_PART_COEFFICIENTS.iter().enumerate() {
+                    // t = r^(lambda_i(x))
+                    let mut t = Fp16::one(self.fp16_extension);
+                    for (power, &c) in powers.iter().zip(coeffs.iter()) {
+                        if c == 0 {
+                            continue;
+                        }
+                        let mut tmp = power.cyclotomic_exp([c.unsigned_abs()]);
+                        if c < 0 {
+                            tmp.conjugate();
+                        }
+                        t.mul_assign(&tmp);
+                    }
+                    t.frobenius_map(i);
+                    result.mul_assign(&t);
+                }
+
+                Some(result)
+            },
+            None => None,
+        }
+    }
+}
+
+
+impl<
+    'a,
+        FE: ElementRepr,
+        F: SizedPrimeField<Repr = FE>,
+        CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+        CTW: CurveParameters<BaseFieldElement = Fp4<'a, FE, F>>
+    > PairingEngine for Kss16Instance<'a, FE, F, CB, CTW> {
+    type PairingResult = Fp16<'a, FE, F>;
+    type G1 = CurvePoint<'a, CB>;
+    type G2 = CurvePoint<'a, CTW>;
+
+    fn miller_loop_product_with_context<'b>
+        (&self, points: &'b [CurvePoint<'a, CB>], twists: &'b [CurvePoint<'a, CTW>], ctx: &ExecutionContext) -> Option<Self::PairingResult> {
+            if points.len() != twists.len() {
+                return None;
+            }
+
+            if !crate::features::in_gas_metering() && (points.is_empty() || twists.is_empty()) {
+                return None;
+            }
+
+            let (g1, g2) = normalize_nonzero_pairs(points, twists);
+            let pairs: Vec<_> = g1.iter().zip(g2.iter()).collect();
+
+            if pairs.is_empty() {
+                return Some(Fp16::one(self.fp16_extension));
+            }
+
+            let loop_result = if self.prefer_naf {
+                debug_assert!(!self.x_naf.is_empty());
+
+                multi_miller_loop(&pairs[..], ctx, |chunk| self.miller_loop_naf(chunk, ctx))
+            } else {
+                multi_miller_loop(&pairs[..], ctx, |chunk| self.miller_loop(chunk, ctx))
+            };
+
+            loop_result.ok()
+        }
+
+    fn final_exp(&self, f: &Fp16<'a, FE, F>) -> Option<Self::PairingResult> {
+            self.final_exponentiation(f)
+        }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use crate::field::{U256Repr, new_field};
+    use crate::fp::Fp;
+    use crate::traits::{FieldElement, ZeroAndOne};
+    use crate::extension_towers::{Fp2Fp4FrobeniusBaseElements, Fp8Fp16FrobeniusBaseElements};
+    use crate::extension_towers::fp2::{Fp2, Extension2};
+    use crate::extension_towers::fp4_as_2_over_2::{Fp4, Extension2Over2};
+    use crate::extension_towers::fp8_as_2_over_4_generic::{Fp8, Extension2Over4};
+    use crate::extension_towers::fp16_as_2_over_8::{Fp16, Extension2Over8};
+    use num_traits::Num;
+    use crate::weierstrass::curve::{CurvePoint, WeierstrassCurve};
+    use crate::weierstrass::{Group, CurveOverFpParameters, CurveOverFp4Parameters};
+    use crate::pairings::{PairingEngine, TwistType};
+    use crate::test::{biguint_to_u64_vec};
+    use crate::integers::MaxFieldUint;
+
+    fn fp_from_dec<'a>(field: &'a crate::field::PrimeField<U256Repr>, value: &str) -> Fp<'a, U256Repr, crate::field::PrimeField<U256Repr>> {
+        let bytes = BigUint::from_str_radix(value, 10).unwrap().to_bytes_be();
+
+        Fp::from_be_bytes(field, &bytes, true).unwrap()
+    }
+
+    // Small KSS16 curve with x = -84045, y^2 = x^3 + 5x over a 154 bit field,
+    // Fp2 = Fp[u]/(u^2 - 2), Fp4 = Fp2[v]/(v^2 - u) and M-type twist with non-residue 2 + v
+    #[test]
+    fn test_kss16_pairing_bilinearity() {
+        let modulus_dec = "17942477277728791684549136700205802947266958333";
+        let base_field = new_field::<U256Repr>(modulus_dec, 10).unwrap();
+        let modulus = BigUint::from_str_radix(modulus_dec, 10).unwrap();
+        let modulus = MaxFieldUint::from_big_endian(&modulus.to_bytes_be());
+        let group_order = BigUint::from_str_radix("40643299649185454740867818722884673", 10).unwrap();
+        let group_order = biguint_to_u64_vec(group_order);
+
+        let fp_non_residue = fp_from_dec(&base_field, "2");
+
+        let base_precomp = Fp2Fp4FrobeniusBaseElements::construct(&modulus, &fp_non_residue).unwrap();
+        let mut extension_2 = Extension2::new(fp_non_residue);
+        extension_2.calculate_frobenius_coeffs_with_precomp(&base_precomp).unwrap();
+
+        let mut extension_4 = Extension2Over2::new(Fp2::zero(&extension_2));
+        extension_4.calculate_frobenius_coeffs_with_precomp(&base_precomp).unwrap();
+
+        let mut fp4_non_residue = Fp4::zero(&extension_4);
+        fp4_non_residue.c0.c0 = fp_from_dec(&base_field, "2");
+        fp4_non_residue.c1 = Fp2::one(&extension_2);
+
+        let precomp = Fp8Fp16FrobeniusBaseElements::construct(&modulus, &fp4_non_residue).unwrap();
+
+        let mut extension_8 = Extension2Over4::new(fp4_non_residue);
+        extension_8.calculate_frobenius_coeffs_with_precomp(&precomp).unwrap();
+
+        let mut extension_16 = Extension2Over8::new(Fp8::zero(&extension_8));
+        extension_16.calculate_frobenius_coeffs_with_precomp(&precomp).unwrap();
+
+        let a_fp = fp_from_dec(&base_field, "5");
+        let mut a_fp4 = fp4_non_residue;
+        a_fp4.mul_by_fp(&a_fp);
+
+        let fp_params = CurveOverFpParameters::new(&base_field);
+        let fp4_params = CurveOverFp4Parameters::new(&extension_4);
+
+        let curve = WeierstrassCurve::new_with_zero_b(&group_order.as_ref(), a_fp, &fp_params).unwrap();
+        let twist = WeierstrassCurve::new_with_zero_b(&group_order.as_ref(), a_fp4, &fp4_params).unwrap();
+
+        let p = CurvePoint::point_from_xy(
+            &curve,
+            fp_from_dec(&base_field, "5399182452450254504523203832035213105968782836"),
+            fp_from_dec(&base_field, "11034869319790254163250885232701303103978798695")
+        );
+
+        let fp4_from_dec = |coeffs: [&str; 4]| {
+            let mut el = Fp4::zero(&extension_4);
+            el.c0.c0 = fp_from_dec(&base_field, coeffs[0]);
+            el.c0.c1 = fp_from_dec(&base_field, coeffs[1]);
+            el.c1.c0 = fp_from_dec(&base_field, coeffs[2]);
+            el.c1.c1 = fp_from_dec(&base_field, coeffs[3]);
+
+            el
+        };
+
+        let q = CurvePoint::point_from_xy(
+            &twist,
+            fp4_from_dec([
+                "17053033852143890331558587767401947948970909371",
+                "13878401213269477078486144407317034842854086905",
+                "17589869197630374436435019256005671350157759415",
+                "15067195570525305636873859161502710529656507463"
+            ]),
+            fp4_from_dec([
+                "3187907940622497455066339938792659585484632742",
+                "4110035121887103554498184448125497378613842213",
+                "16038278261138142161186031517219614027916683837",
+                "13783057708050799853750345858066910700920726264"
+            ])
+        );
+
+        assert!(p.is_on_curve());
+        assert!(q.is_on_curve());
+        assert!(p.check_correct_subgroup());
+        assert!(q.check_correct_subgroup());
+
+        for &force_no_naf in [false, true].iter() {
+            let engine = super::Kss16InstanceParams {
+                x: &[84045],
+                x_is_negative: true,
+                twist_type: TwistType::M,
+                base_field: &base_field,
+                curve: &curve,
+                curve_twist: &twist,
+                fp2_extension: &extension_2,
+                fp4_extension: &extension_4,
+                fp8_extension: &extension_8,
+                fp16_extension: &extension_16,
+                force_no_naf
+            };
+
+            let engine = super::Kss16Instance::from_params(engine);
+
+            let one = Fp16::one(&extension_16);
+
+            let e = engine.pair(&[p.clone()], &[q.clone()]).unwrap();
+            assert!(e != one);
+            assert_eq!(e.pow(&group_order), one);
+
+            let a = [0x1234567890abcdefu64];
+            let b = [0xfedcba0987654321u64];
+
+            let expected = e.pow(&a).pow(&b);
+
+            let ap = p.mul(&a);
+            let bq = q.mul(&b);
+            let ap_bq = engine.pair(&[ap], &[bq]).unwrap();
+            assert_eq!(ap_bq, expected);
+
+            let mut minus_p = p.clone();
+            minus_p.negate();
+            let product = engine.pair(&[p.clone(), minus_p], &[q.clone(), q.clone()]).unwrap();
+            assert_eq!(product, one);
+
+            // Miller loop and final exponentiation done separately give the same result
+            let miller_loop = engine.miller_loop_product(&[p.clone()], &[q.clone()]).unwrap();
+            let finalized = engine.final_exp(&miller_loop).unwrap();
+            assert_eq!(finalized, e);
+        }
+    }
+}
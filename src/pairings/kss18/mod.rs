@@ -0,0 +1,775 @@
+use crate::field::SizedPrimeField;
+use crate::fp::Fp;
+use crate::representation::ElementRepr;
+use crate::traits::{FieldElement, MsbBitIterator, ZeroAndOne};
+use crate::weierstrass::Group;
+use crate::weierstrass::{CurveParameters};
+use crate::weierstrass::curve::{WeierstrassCurve, CurvePoint};
+use crate::extension_towers::fp3::{Fp3, Extension3};
+use crate::extension_towers::fp9_as_3_over_3::{Extension3Over3};
+use crate::extension_towers::fp18_as_2_over_3_over_3::{Fp18, Extension2Over3Over3};
+use crate::pairings::PairingEngine;
+use crate::pairings::TwistType;
+use crate::pairings::{normalize_nonzero_pairs, prepare_distinct_twists, DistinctTwists, multi_miller_loop, two_inverse, naf_after_leading_one, calculate_bits, calculate_hamming_weight, calculate_naf_hamming_weight, into_ternary_wnaf};
+use crate::execution_context::ExecutionContext;
+use crate::alloc_prelude::*;
+
+// KSS18 curves with seed x have p = (x^8 + 5x^7 + 7x^6 + 37x^5 + 188x^4 + 259x^3 + 343x^2 + 1763x + 2401)/21
+// and r = (x^6 + 37x^3 + 343)/343. Optimal ate pairing uses x + 3p - p^4 = 0 mod r, so
+// f = f_{x,Q}(P) * f_{3,Q}(P)^p * l_{[x]Q,[3p]Q}(P)
+// https://eprint.iacr.org/2008/096.pdf, https://eprint.iacr.org/2007/390.pdf
+
+// 3 * (p^6 - p^3 + 1)/r = sum(lambda_i(x) * p^i), coefficients of lambda_i(x) are
+// listed from the lowest power of x
+const HARD_PART_COEFFICIENTS: [[i64; 8]; 6] = [
+    [3, 0, -434, -319, -62, -21, -15, -3],
+    [0, 1911, 1407, 273, 98, 70, 14, 0],
+    [-6517, -4802, -931, -343, -245, -49, 0, 0],
+    [54, 0, -609, -450, -87, -35, -25, -5],
+    [0, 784, 581, 112, 49, 35, 7, 0],
+    [343, 245, 49, 0, 0, 0, 0, 0],
+];
+
+/// Coefficients of a line evaluation in the Miller loop
+type LineCoefficients<'a, FE, F> = (Fp3<'a, FE, F>, Fp3<'a, FE, F>, Fp3<'a, FE, F>);
+
+pub(crate) struct PreparedTwistPoint<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> {
+    pub(crate) ell_coeffs: Vec<LineCoefficients<'a, FE, F>>
+}
+
+#[derive(Clone)]
+pub struct Kss18InstanceParams<
+'a, 
+    FE: ElementRepr, 
+    F: SizedPrimeField<Repr = FE>, 
+    CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+    CTW: CurveParameters<BaseFieldElement = Fp3<'a, FE, F>>
+> {
+    pub x: &'a [u64],
+    pub x_is_negative: bool,
+    pub twist_type: TwistType,
+    pub base_field: &'a F,
+    pub curve: &'a WeierstrassCurve<'a, CB>,
+    pub curve_twist: &'a WeierstrassCurve<'a, CTW>,
+    pub fp3_extension: &'a Extension3<'a, FE, F>,
+    pub fp9_extension: &'a Extension3Over3<'a, FE, F>,
+    pub fp18_extension: &'a Extension2Over3Over3<'a, FE, F>,
+    pub force_no_naf: bool
+}
+
+#[derive(Clone)]
+pub struct Kss18Instance<
+    'a, 
+        FE: ElementRepr, 
+        F: SizedPrimeField<Repr = FE>, 
+        CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+        CTW: CurveParameters<BaseFieldElement = Fp3<'a, FE, F>>
+    > {
+    pub x: &'a [u64],
+    pub x_is_negative: bool,
+    pub twist_type: TwistType,
+    pub base_field: &'a F,
+    pub curve: &'a WeierstrassCurve<'a, CB>,
+    pub curve_twist: &'a WeierstrassCurve<'a, CTW>,
+    pub fp3_extension: &'a Extension3<'a, FE, F>,
+    pub fp9_extension: &'a Extension3Over3<'a, FE, F>,
+    pub fp18_extension: &'a Extension2Over3Over3<'a, FE, F>,
+    pub prefer_naf: bool,
+    pub x_naf: Vec<i8>,
+    pub twist_frobenius_x_coeff: Fp3<'a, FE, F>,
+    pub twist_frobenius_y_coeff: Fp3<'a, FE, F>
+}
+
+impl<
+    'a, 
+        FE: ElementRepr, 
+        F: SizedPrimeField<Repr = FE>, 
+        CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+        CTW: CurveParameters<BaseFieldElement = Fp3<'a, FE, F>>
+    > Kss18Instance<'a, FE, F, CB, CTW> 
+{
+    pub fn from_params(params: Kss18InstanceParams::<'a, FE, F, CB, CTW>) -> Self {
+        let (prefer_naf, naf) = if params.force_no_naf {
+            (false, vec![])
+        } else {
+            let naf_vec = into_ternary_wnaf(params.x);
+            let original_bits = calculate_bits(params.x);
+            let original_hamming = calculate_hamming_weight(params.x);
+            let naf_hamming = calculate_naf_hamming_weight(&naf_vec);
+            let naf_length = naf_vec.len() as u32;
+
+            let naf_is_beneficial = naf_length + naf_hamming < original_bits + original_hamming;
+
+            if naf_is_beneficial {
+                (true, naf_vec)
+            } else {
+                (false, vec![])
+            }
+        };
+
+        // Frobenius on the twist is untwist-Frobenius-twist, that for D type twist
+        // (x, y) -> (x * w^2, y * w^3) with w^6 = xi is
+        // (x, y) -> (x^p * xi^((p - 1)/3), y^p * xi^((p - 1)/2)),
+        // and uses inverse coefficients for M type twist
+        let mut x_coeff = params.fp9_extension.frobenius_coeffs_c1[1];
+        let mut y_coeff = x_coeff;
+        y_coeff.mul_assign(&params.fp18_extension.frobenius_coeffs_c1[1]);
+        if params.twist_type == TwistType::M {
+            let zero = Fp3::zero(params.fp3_extension);
+            x_coeff = x_coeff.inverse().unwrap_or(zero);
+            y_coeff = y_coeff.inverse().unwrap_or(zero);
+        }
+
+        Self {
+            x: params.x,
+            x_is_negative: params.x_is_negative,
+            twist_type: params.twist_type,
+            base_field: params.base_field,
+            curve: params.curve,
+            curve_twist: params.curve_twist,
+            fp3_extension: params.fp3_extension,
+            fp9_extension: params.fp9_extension,
+            fp18_extension: params.fp18_extension,
+            prefer_naf,
+            x_naf: naf,
+            twist_frobenius_x_coeff: x_coeff,
+            twist_frobenius_y_coeff: y_coeff
+        }
+    }
+}
+
+impl<
+    'a, 
+        FE: ElementRepr, 
+        F: SizedPrimeField<Repr = FE>, 
+        CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+        CTW: CurveParameters<BaseFieldElement = Fp3<'a, FE, F>>
+    > Kss18Instance<'a, FE, F, CB, CTW> {
+    fn ell(
+        &self,
+        f: &mut Fp18<'a, FE, F>,
+        coeffs: &LineCoefficients<'a, FE, F>,
+        p: & CurvePoint<'a, CB>,
+    ) {
+        debug_assert!(p.is_normalized());
+        let mut c0 = coeffs.0;
+        let mut c1 = coeffs.1;
+        let mut c2 = coeffs.2;
+
+        match self.twist_type {
+            TwistType::M => {
+                c2.mul_by_fp(&p.y);
+                c1.mul_by_fp(&p.x);
+                f.mul_by_014(&c0, &c1, &c2);
+            },
+            TwistType::D => {
+                c0.mul_by_fp(&p.y);
+                c1.mul_by_fp(&p.x);
+                f.mul_by_034(&c0, &c1, &c2);
+            },
+        }
+    }
+
+    fn exp_by_x(&self, f: &mut Fp18<'a, FE, F>) {
+        *f = f.cyclotomic_exp(self.x);
+        if self.x_is_negative {
+            f.conjugate();
+        }
+    }
+
+    fn doubling_step(
+        &self,
+        r: &mut CurvePoint<'a, CTW>,
+        two_inv: &Fp<'a, FE, F>,
+    ) -> LineCoefficients<'a, FE, F> {
+        // Use adapted formulas from ZEXE instead
+        let mut a = r.x;
+        a.mul_assign(&r.y);
+        a.mul_by_fp(two_inv);
+        let mut b = r.y;
+        b.square();
+        let mut c = r.z;
+        c.square();
+
+        let mut e = self.curve_twist.b;
+        let mut t0 = c;
+        t0.double();
+        t0.add_assign(&c);
+
+        e.mul_assign(&t0);
+
+        let mut f = e;
+        f.double();
+        f.add_assign(&e);
+
+        let mut g = b;
+        g.add_assign(&f);
+        g.mul_by_fp(two_inv);
+
+        let mut h = r.y;
+        h.add_assign(&r.z);
+        h.square();
+
+        let mut t1 = b;
+        t1.add_assign(&c);
+
+        h.sub_assign(&t1);
+
+        let mut i = e;
+        i.sub_assign(&b);
+
+        let mut j = r.x;
+        j.square();
+
+        let mut e_square = e;
+        e_square.square();
+
+        r.x = b;
+        r.x.sub_assign(&f);
+        r.x.mul_assign(&a);
+
+        let mut e_square_by_3 = e_square;
+        e_square_by_3.double();
+        e_square_by_3.add_assign(&e_square);
+
+        r.y = g;
+        r.y.square();
+        r.y.sub_assign(&e_square_by_3);
+
+        r.z = b;
+        r.z.mul_assign(&h);
+
+        let mut j_by_three = j;
+        j_by_three.double();
+        j_by_three.add_assign(&j);
+        h.negate();
+
+        match self.twist_type {
+            TwistType::M => {
+                (i, j_by_three, h)
+            },
+            TwistType::D => {
+                (h, j_by_three, i)
+            },
+        }
+    }
+
+    fn addition_step(
+        &self,
+        r: &mut CurvePoint<'a, CTW>,
+        q: & CurvePoint<'a, CTW>,
+    ) -> LineCoefficients<'a, FE, F> {
+        debug_assert!(q.is_normalized());
+        // use adapted zexe formulas too instead of ones from pairing crate
+        let mut theta = q.y;
+        theta.mul_assign(&r.z);
+        theta.negate();
+        theta.add_assign(&r.y);
+
+        let mut lambda = q.x;
+        lambda.mul_assign(&r.z);
+        lambda.negate();
+        lambda.add_assign(&r.x);
+
+        let mut c = theta;
+        c.square();
+        let mut d = lambda;
+        d.square();
+        let mut e = lambda;
+        e.mul_assign(&d);
+        let mut f = r.z;
+        f.mul_assign(&c);
+        let mut g = r.x;
+        g.mul_assign(&d);
+
+        let mut h = g;
+        h.double();
+        h.negate();
+        h.add_assign(&e);
+        h.add_assign(&f);
+        
+
+        r.x = lambda;
+        r.x.mul_assign(&h);
+
+        let mut t0 = g;
+        t0.sub_assign(&h);
+        t0.mul_assign(&theta);
+
+        r.y.mul_assign(&e);
+        r.y.negate();
+        r.y.add_assign(&t0);
+
+        r.z.mul_assign(&e);
+
+        let mut t1 = lambda;
+        t1.mul_assign(&q.y);
+        
+        let mut j = theta;
+        j.mul_assign(&q.x);
+        j.sub_assign(&t1);
+
+        theta.negate();
+        match self.twist_type {
+            TwistType::M => (j, theta, lambda),
+            TwistType::D => (lambda, theta, j),
+        }
+    }
+
+    fn prepare(&self, twist_point: & CurvePoint<'a, CTW>, two_inv: &Fp<'a, FE, F>) -> Result<PreparedTwistPoint<'a, FE, F>, ()> {
+        debug_assert!(twist_point.is_normalized());
+
+        if twist_point.is_zero() {
+            return Ok(PreparedTwistPoint {
+                ell_coeffs: vec![],
+            });
+        }
+
+        let mut ell_coeffs = Vec::with_capacity(self.x.len() * 64 * 2);
+        let mut r = CurvePoint::<CTW>::point_from_xy(self.curve_twist, twist_point.x, twist_point.y);
+
+        for i in MsbBitIterator::new(&self.x).skip(1) {
+            ell_coeffs.push(self.doubling_step(&mut r, two_inv));
+
+            if i {
+                ell_coeffs.push(self.addition_step(&mut r, twist_point));
+            }
+        }
+
+        self.prepare_final_lines(&mut r, twist_point, two_inv, &mut ell_coeffs);
+
+        Ok(PreparedTwistPoint {
+            ell_coeffs,
+        })
+    }
+
+    fn prepare_naf(&self, twist_point: & CurvePoint<'a, CTW>, two_inv: &Fp<'a, FE, F>) -> Result<PreparedTwistPoint<'a, FE, F>, ()> {
+        debug_assert!(twist_point.is_normalized());
+
+        if twist_point.is_zero() {
+            return Ok(PreparedTwistPoint {
+                ell_coeffs: vec![],
+            });
+        }
+
+        let mut ell_coeffs = Vec::with_capacity(self.x.len() * 64 * 2);
+
+        let mut twist_point_negated = twist_point.clone();
+        twist_point_negated.negate();
+
+        let mut r = CurvePoint::<CTW>::point_from_xy(self.curve_twist, twist_point.x, twist_point.y);
+
+        let it = naf_after_leading_one(&self.x_naf)?;
+
+        for &i in it {
+            ell_coeffs.push(self.doubling_step(&mut r, two_inv));
+            
+            if i != 0 {
+                if i > 0 {
+                    ell_coeffs.push(self.addition_step(&mut r, twist_point));
+                } else {
+                    ell_coeffs.push(self.addition_step(&mut r, &twist_point_negated));
+                }
+            }
+        }
+
+        self.prepare_final_lines(&mut r, twist_point, two_inv, &mut ell_coeffs);
+
+        Ok(PreparedTwistPoint {
+            ell_coeffs,
+        })
+    }
+
+    // Appends coefficients of the lines that follow the main loop: l_{[x]Q,[3p]Q} and
+    // two lines of f_{3,Q}. Here r = [|x|]Q as computed by the main loop
+    fn prepare_final_lines(
+        &self, 
+        r: &mut CurvePoint<'a, CTW>, 
+        twist_point: & CurvePoint<'a, CTW>, 
+        two_inv: &Fp<'a, FE, F>,
+        ell_coeffs: &mut Vec<LineCoefficients<'a, FE, F>>
+    ) {
+        let mut q3 = CurvePoint::<CTW>::point_from_xy(self.curve_twist, twist_point.x, twist_point.y);
+        let doubling_coeffs = self.doubling_step(&mut q3, two_inv);
+        let addition_coeffs = self.addition_step(&mut q3, twist_point);
+
+        // doubling and addition steps work in homogeneous coordinates
+        let (mut x, mut y) = q3.into_xy_from_homogenious();
+        x.frobenius_map(1);
+        x.mul_assign(&self.twist_frobenius_x_coeff);
+        y.frobenius_map(1);
+        y.mul_assign(&self.twist_frobenius_y_coeff);
+        let q3p = CurvePoint::<CTW>::point_from_xy(self.curve_twist, x, y);
+
+        if self.x_is_negative {
+            r.negate();
+        }
+
+        ell_coeffs.push(self.addition_step(r, &q3p));
+        ell_coeffs.push(doubling_coeffs);
+        ell_coeffs.push(addition_coeffs);
+    }
+
+    fn prepare_distinct_twists<'b, I>(&self, i: I, use_naf: bool) -> Result<DistinctTwists<'a, 'b, CB, Vec<LineCoefficients<'a, FE, F>>>, ()>
+    where 'a: 'b,
+        I: IntoIterator<
+            Item = &'b (&'b CurvePoint<'a, CB>,
+                &'b CurvePoint<'a, CTW>)
+        >
+    {
+        let two_inv = two_inverse(self.base_field)?;
+
+        prepare_distinct_twists(i, |q| {
+            let coeffs = if use_naf {
+                self.prepare_naf(q, &two_inv)?
+            } else {
+                self.prepare(q, &two_inv)?
+            };
+
+            Ok(coeffs.ell_coeffs)
+        })
+    }
+
+    fn miller_loop_naf<'b, I>(&self, i: I, ctx: &ExecutionContext) -> Result<Fp18<'a, FE, F>, ()>
+    where 'a: 'b,
+        I: IntoIterator<
+            Item = &'b (&'b CurvePoint<'a, CB>, 
+                &'b CurvePoint<'a, CTW>)
+        >
+    {
+        let (g1_references, twist_indexes, prepared_coeffs) = self.prepare_distinct_twists(i, true)?;
+        let mut step = 0;
+
+        let mut f = Fp18::one(self.fp18_extension);
+
+        let it = naf_after_leading_one(&self.x_naf)?;
+
+        for &i in it {
+            if !ctx.charge(g1_references.len() + 1) {
+                return Ok(f);
+            }
+
+            f.square();
+
+            for (p, &index) in g1_references.iter().zip(twist_indexes.iter()) {
+                self.ell(&mut f, &prepared_coeffs[index][step], p);
+            }
+            step += 1;
+
+            if i != 0 {
+                for (p, &index) in g1_references.iter().zip(twist_indexes.iter()) {
+                    self.ell(&mut f, &prepared_coeffs[index][step], p);
+                }
+                step += 1;
+            }
+        }
+
+        self.finalize_miller_loop(&mut f, &g1_references, &twist_indexes, &prepared_coeffs, step);
+
+        Ok(f)
+    }
+
+    fn miller_loop<'b, I>(&self, i: I, ctx: &ExecutionContext) -> Result<Fp18<'a, FE, F>, ()>
+    where 'a: 'b,
+        I: IntoIterator<
+            Item = &'b (&'b CurvePoint<'a, CB>, 
+                &'b CurvePoint<'a, CTW>)
+        >
+    {
+        let (g1_references, twist_indexes, prepared_coeffs) = self.prepare_distinct_twists(i, false)?;
+        let mut step = 0;
+
+        let mut f = Fp18::one(self.fp18_extension);
+
+        for i in MsbBitIterator::new(&self.x).skip(1) {
+            if !ctx.charge(g1_references.len() + 1) {
+                return Ok(f);
+            }
+
+            f.square();
+
+            for (p, &index) in g1_references.iter().zip(twist_indexes.iter()) {
+                self.ell(&mut f, &prepared_coeffs[index][step], p);
+            }
+            step += 1;
+
+            if i {
+                for (p, &index) in g1_references.iter().zip(twist_indexes.iter()) {
+                    self.ell(&mut f, &prepared_coeffs[index][step], p);
+                }
+                step += 1;
+            }
+        }
+
+        self.finalize_miller_loop(&mut f, &g1_references, &twist_indexes, &prepared_coeffs, step);
+
+        Ok(f)
+    }
+
+    // f = f_{x,Q}(P) * l_{[x]Q,[3p]Q}(P) * f_{3,Q}(P)^p, where step points to the
+    // coefficients appended by `prepare_final_lines`
+    fn finalize_miller_loop(
+        &self,
+        f: &mut Fp18<'a, FE, F>,
+        g1_references: &[&CurvePoint<'a, CB>],
+        twist_indexes: &[usize],
+        prepared_coeffs: &[Vec<LineCoefficients<'a, FE, F>>],
+        step: usize
+    ) {
+        if self.x_is_negative {
+            f.conjugate();
+        }
+
+        let mut f3 = Fp18::one(self.fp18_extension);
+        for (p, &index) in g1_references.iter().zip(twist_indexes.iter()) {
+            self.ell(f, &prepared_coeffs[index][step], p);
+            self.ell(&mut f3, &prepared_coeffs[index][step + 1], p);
+            self.ell(&mut f3, &prepared_coeffs[index][step + 2], p);
+        }
+
+        f3.frobenius_map(1);
+        f.mul_assign(&f3);
+    }
+
+    fn final_exponentiation(&self, f: &Fp18<'a, FE, F>) -> Option<Fp18<'a, FE, F>> {
+        // Final exponent is (p^18 - 1)/r = (p^9 - 1)(p^3 + 1) * (p^6 - p^3 + 1)/r.
+        // For the hard part we use the decomposition of 3 * (p^6 - p^3 + 1)/r in base p
+        // with coefficients being polynomials in x, so the result is the cube of the reduced
+        // pairing, which is still a non-degenerate bilinear map as r != 3
+
+        match f.inverse() {
+            Some(f_inv) => {
+                // f1 = f^(p^9) is a conjugation as w^(p^9) = -w
+                let mut f1 = *f;
+                f1.conjugate();
+
+                // r = f^(p^9 - 1)
+                let mut r = f1;
+                r.mul_assign(&f_inv);
+
+                // r = f^((p^9 - 1)(p^3 + 1))
+                let f2 = r;
+                r.frobenius_map(3);
+                r.mul_assign(&f2);
+
+                // now r is in the cyclotomic subgroup, so inversion is a conjugation
+
+                // powers[j] = r^(x^j)
+                let mut powers = [r; 8];
+                for j in 1..8 {
+                    let mut t = powers[j-1];
+                    self.exp_by_x(&mut t);
+                    powers[j] = t;
+                }
+
+                let mut result = Fp18::one(self.fp18_extension);
+                for (i, coeffs) in HARD_PART_COEFFICIENTS.iter().enumerate() {
+                    // t = r^(lambda_i(x))
+                    let mut t = Fp18::one(self.fp18_extension);
+                    for (power, &c) in powers.iter().zip(coeffs.iter()) {
+                        if c == 0 {
+                            continue;
+                        }
+                        let mut tmp = power.cyclotomic_exp([c.unsigned_abs()]);
+                        if c < 0 {
+                            tmp.conjugate();
+                        }
+                        t.mul_assign(&tmp);
+                    }
+                    t.frobenius_map(i);
+                    result.mul_assign(&t);
+                }
+
+                Some(result)
+            },
+            None => None,
+        }
+    }
+}
+
+
+impl<
+    'a, 
+        FE: ElementRepr, 
+        F: SizedPrimeField<Repr = FE>, 
+        CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+        CTW: CurveParameters<BaseFieldElement = Fp3<'a, FE, F>>
+    > PairingEngine for Kss18Instance<'a, FE, F, CB, CTW> {
+    type PairingResult = Fp18<'a, FE, F>;
+    type G1 = CurvePoint<'a, CB>;
+    type G2 = CurvePoint<'a, CTW>;
+
+    fn miller_loop_product_with_context<'b>
+        (&self, points: &'b [CurvePoint<'a, CB>], twists: &'b [CurvePoint<'a, CTW>], ctx: &ExecutionContext) -> Option<Self::PairingResult> {
+            if points.len() != twists.len() {
+                return None;
+            }
+
+            if !crate::features::in_gas_metering() && (points.is_empty() || twists.is_empty()) {
+                return None;
+            }
+            
+            let (g1, g2) = normalize_nonzero_pairs(points, twists);
+            let pairs: Vec<_> = g1.iter().zip(g2.iter()).collect();
+
+            if pairs.is_empty() {
+                return Some(Fp18::one(self.fp18_extension));
+            }
+
+            let loop_result = if self.prefer_naf {
+                debug_assert!(!self.x_naf.is_empty());
+
+                multi_miller_loop(&pairs[..], ctx, |chunk| self.miller_loop_naf(chunk, ctx))
+            } else {
+                multi_miller_loop(&pairs[..], ctx, |chunk| self.miller_loop(chunk, ctx))
+            };
+
+            loop_result.ok()
+        }
+
+    fn final_exp(&self, f: &Fp18<'a, FE, F>) -> Option<Self::PairingResult> {
+            self.final_exponentiation(f)
+        }   
+}
+
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use crate::field::{U256Repr, new_field};
+    use crate::fp::Fp;
+    use crate::traits::{FieldElement, ZeroAndOne};
+    use crate::extension_towers::{Fp3Fp6FrobeniusBaseElements, Fp9Fp18FrobeniusBaseElements};
+    use crate::extension_towers::fp3::{Fp3, Extension3};
+    use crate::extension_towers::fp9_as_3_over_3::{Fp9, Extension3Over3};
+    use crate::extension_towers::fp18_as_2_over_3_over_3::{Fp18, Extension2Over3Over3};
+    use num_traits::Num;
+    use crate::weierstrass::curve::{CurvePoint, WeierstrassCurve};
+    use crate::weierstrass::{Group, CurveOverFpParameters, CurveOverFp3Parameters};
+    use crate::pairings::{PairingEngine, TwistType};
+    use crate::test::{biguint_to_u64_vec};
+    use crate::integers::MaxFieldUint;
+
+    fn fp_from_dec<'a>(field: &'a crate::field::PrimeField<U256Repr>, value: &str) -> Fp<'a, U256Repr, crate::field::PrimeField<U256Repr>> {
+        let bytes = BigUint::from_str_radix(value, 10).unwrap().to_bytes_be();
+
+        Fp::from_be_bytes(field, &bytes, true).unwrap()
+    }
+
+    // Small KSS18 curve with x = 29834, y^2 = x^3 + 3 over a 115 bit field,
+    // Fp3 = Fp[u]/(u^3 - 3) and D-type twist with non-residue 2 + u
+    #[test]
+    fn test_kss18_pairing_bilinearity() {
+        let modulus_dec = "29891339064057947269104759486668551";
+        let base_field = new_field::<U256Repr>(modulus_dec, 10).unwrap();
+        let modulus = BigUint::from_str_radix(modulus_dec, 10).unwrap();
+        let modulus = MaxFieldUint::from_big_endian(&modulus.to_bytes_be());
+        let group_order = BigUint::from_str_radix("2055771269706915143651449", 10).unwrap();
+        let group_order = biguint_to_u64_vec(group_order);
+
+        let fp_non_residue = fp_from_dec(&base_field, "3");
+
+        let base_precomp = Fp3Fp6FrobeniusBaseElements::construct(&modulus, &fp_non_residue).unwrap();
+        let mut extension_3 = Extension3::new(fp_non_residue);
+        extension_3.calculate_frobenius_coeffs_with_precomp(&base_precomp).unwrap();
+
+        let mut fp3_non_residue = Fp3::zero(&extension_3);
+        fp3_non_residue.c0 = fp_from_dec(&base_field, "2");
+        fp3_non_residue.c1 = Fp::one(&base_field);
+
+        let precomp = Fp9Fp18FrobeniusBaseElements::construct(&modulus, &fp3_non_residue).unwrap();
+
+        let mut extension_9 = Extension3Over3::new(fp3_non_residue);
+        extension_9.calculate_frobenius_coeffs_with_precomp(&precomp).unwrap();
+
+        let mut extension_18 = Extension2Over3Over3::new(Fp9::zero(&extension_9));
+        extension_18.calculate_frobenius_coeffs_with_precomp(&precomp).unwrap();
+
+        let b_fp = fp_from_dec(&base_field, "3");
+        let mut b_fp3 = fp3_non_residue.inverse().unwrap();
+        b_fp3.mul_by_fp(&b_fp);
+
+        let fp_params = CurveOverFpParameters::new(&base_field);
+        let fp3_params = CurveOverFp3Parameters::new(&extension_3);
+
+        let curve = WeierstrassCurve::new(&group_order.as_ref(), Fp::zero(&base_field), b_fp, &fp_params).unwrap();
+        let twist = WeierstrassCurve::new(&group_order.as_ref(), Fp3::zero(&extension_3), b_fp3, &fp3_params).unwrap();
+
+        let p = CurvePoint::point_from_xy(
+            &curve,
+            fp_from_dec(&base_field, "20348325738303987274204642675031933"),
+            fp_from_dec(&base_field, "9125504658269780392299759789195774")
+        );
+
+        let fp3_from_dec = |coeffs: [&str; 3]| {
+            let mut el = Fp3::zero(&extension_3);
+            el.c0 = fp_from_dec(&base_field, coeffs[0]);
+            el.c1 = fp_from_dec(&base_field, coeffs[1]);
+            el.c2 = fp_from_dec(&base_field, coeffs[2]);
+
+            el
+        };
+
+        let q = CurvePoint::point_from_xy(
+            &twist,
+            fp3_from_dec([
+                "23647863247977117649259236473995796",
+                "26242209167743785876686397305904356",
+                "11270792907538847780638122983850104"
+            ]),
+            fp3_from_dec([
+                "28625498509801273066805536237290511",
+                "24683992006752141764924395052106815",
+                "16434150333801080175706901282939000"
+            ])
+        );
+
+        assert!(p.is_on_curve());
+        assert!(q.is_on_curve());
+        assert!(p.check_correct_subgroup());
+        assert!(q.check_correct_subgroup());
+
+        for &force_no_naf in [false, true].iter() {
+            let engine = super::Kss18InstanceParams {
+                x: &[29834],
+                x_is_negative: false,
+                twist_type: TwistType::D,
+                base_field: &base_field,
+                curve: &curve,
+                curve_twist: &twist,
+                fp3_extension: &extension_3,
+                fp9_extension: &extension_9,
+                fp18_extension: &extension_18,
+                force_no_naf
+            };
+
+            let engine = super::Kss18Instance::from_params(engine);
+
+            let one = Fp18::one(&extension_18);
+
+            let e = engine.pair(&[p.clone()], &[q.clone()]).unwrap();
+            assert!(e != one);
+            assert_eq!(e.pow(&group_order), one);
+
+            let a = [0x1234567890abcdefu64];
+            let b = [0xfedcba0987654321u64];
+
+            let expected = e.pow(&a).pow(&b);
+
+            let ap = p.mul(&a);
+            let bq = q.mul(&b);
+            let ap_bq = engine.pair(&[ap], &[bq]).unwrap();
+            assert_eq!(ap_bq, expected);
+
+            let mut minus_p = p.clone();
+            minus_p.negate();
+            let product = engine.pair(&[p.clone(), minus_p], &[q.clone(), q.clone()]).unwrap();
+            assert_eq!(product, one);
+
+            // Miller loop and final exponentiation done separately give the same result
+            let miller_loop = engine.miller_loop_product(&[p.clone()], &[q.clone()]).unwrap();
+            let finalized = engine.final_exp(&miller_loop).unwrap();
+            assert_eq!(finalized, e);
+        }
+    }
+}
@@ -7,8 +7,10 @@ use crate::weierstrass::curve::{WeierstrassCurve, CurvePoint};
 use crate::extension_towers::fp2::{Fp2, Extension2};
 use crate::extension_towers::fp4_as_2_over_2::{Fp4, Extension2Over2};
 use crate::pairings::PairingEngine;
-use crate::pairings::{calculate_bits, calculate_hamming_weight, calculate_naf_hamming_weight, into_ternary_wnaf};
+use crate::pairings::{normalize_nonzero_pairs, multi_miller_loop, naf_after_leading_one, calculate_bits, calculate_hamming_weight, calculate_naf_hamming_weight, into_ternary_wnaf};
 use crate::weierstrass::Group;
+use crate::execution_context::ExecutionContext;
+use crate::alloc_prelude::*;
 
 #[derive(Clone)]
 pub struct MNT4InstanceParams<
@@ -120,6 +122,13 @@ struct PrecomputedG2<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> {
     pub(crate) addition_coefficients: Vec<AteAdditionCoefficients<'a, FE, F>>,
 }
 
+// G1 dependent part of the addition lines is the same for all the steps
+struct PrecomputedPair<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> {
+    pub(crate) p: PrecomputedG1<'a, FE, F>,
+    pub(crate) q: PrecomputedG2<'a, FE, F>,
+    pub(crate) l1_coeff: Fp2<'a, FE, F>,
+}
+
 struct AteDoubleCoefficients<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> {
     pub(crate) c_h:  Fp2<'a, FE, F>,
     pub(crate) c_4c: Fp2<'a, FE, F>,
@@ -146,34 +155,59 @@ impl<
         CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
         CTW: CurveParameters<BaseFieldElement = Fp2<'a, FE, F>>
     > MNT4Instance<'a, FE, F, CB, CTW> {
-    fn miller_loop<'b, I>(&self, i: I) -> Result< Fp4<'a, FE, F>, () >
+    fn miller_loop<'b, I>(&self, i: I, ctx: &ExecutionContext) -> Result<Fp4<'a, FE, F>, ()>
     where 'a: 'b,
         I: IntoIterator<
             Item = &'b (&'b CurvePoint<'a, CB>, 
                 &'b CurvePoint<'a, CTW>)
         >
     {
-        let mut f = Fp4::one(self.fp4_extension);
-        for (p, q) in i.into_iter() {
-            f.mul_assign(&self.ate_pairing_loop(p, q)?);
-        }
+        let precomputed = self.precompute_pairs(i, false)?;
 
-        Ok(f)
+        self.miller_loop_with_precomputed(&precomputed, false, ctx)
     }
 
-    fn miller_loop_naf<'b, I>(&self, i: I) -> Result< Fp4<'a, FE, F>, () >
+    fn miller_loop_naf<'b, I>(&self, i: I, ctx: &ExecutionContext) -> Result<Fp4<'a, FE, F>, ()>
     where 'a: 'b,
         I: IntoIterator<
             Item = &'b (&'b CurvePoint<'a, CB>, 
                 &'b CurvePoint<'a, CTW>)
         >
     {
-        let mut f = Fp4::one(self.fp4_extension);
+        let precomputed = self.precompute_pairs(i, true)?;
+
+        self.miller_loop_with_precomputed(&precomputed, true, ctx)
+    }
+
+    fn precompute_pairs<'b, I>(&self, i: I, use_naf: bool) -> Result<Vec<PrecomputedPair<'a, FE, F>>, ()>
+    where 'a: 'b,
+        I: IntoIterator<
+            Item = &'b (&'b CurvePoint<'a, CB>, 
+                &'b CurvePoint<'a, CTW>)
+        >
+    {
+        let twist_inv = self.twist.inverse().ok_or(())?;
+
+        let mut precomputed = vec![];
         for (p, q) in i.into_iter() {
-            f.mul_assign(&self.ate_pairing_loop_naf(p, q)?);
+            debug_assert!(p.is_normalized());
+            debug_assert!(q.is_normalized());
+
+            let p = self.precompute_g1(p);
+            let q = if use_naf {
+                self.precompute_g2_naf(q, &twist_inv)?
+            } else {
+                self.precompute_g2(q, &twist_inv)?
+            };
+
+            let mut l1_coeff = Fp2::zero(&self.fp2_extension);
+            l1_coeff.c0 = p.x.clone();
+            l1_coeff.sub_assign(&q.x_over_twist);
+
+            precomputed.push(PrecomputedPair { p, q, l1_coeff });
         }
 
-        Ok(f)
+        Ok(precomputed)
     }
 
     fn precompute_g1(&self, g1_point: &CurvePoint<'a, CB>) -> PrecomputedG1<'a, FE, F> {
@@ -438,12 +472,7 @@ impl<
         let mut g2_point_negated = g2_point.clone();
         g2_point_negated.negate();
 
-        let mut it = self.x_naf.iter().rev();
-        
-        {
-            let first = it.next().expect("naf has enough coefficients");
-            assert_eq!(*first, 1);
-        }
+        let it = naf_after_leading_one(&self.x_naf)?;
 
         for &i in it {
             let coeff = self.doubling_step(&mut r);
@@ -485,196 +514,91 @@ impl<
         Ok(g2_p)
     }
 
-    fn ate_pairing_loop(
-        &self, 
-        point: &CurvePoint<'a, CB>, 
-        twist_point: &CurvePoint<'a, CTW> 
-    ) -> Result<Fp4<'a, FE, F>, ()> {
-        debug_assert!(point.is_normalized());
-        debug_assert!(twist_point.is_normalized());
-
-        let twist_inv = self.twist.inverse().ok_or(())?;
-
-        let p = self.precompute_g1(&point);
-        let q = self.precompute_g2(&twist_point, &twist_inv)?;
-        let mut l1_coeff = Fp2::zero(&self.fp2_extension);
-        l1_coeff.c0 = p.x.clone();
-        l1_coeff.sub_assign(&q.x_over_twist);
-
-        let mut f = Fp4::one(self.fp4_extension);
-
-        let mut dbl_idx: usize = 0;
-        let mut add_idx: usize = 0;
-
-        // The for loop is executed for all bits (EXCEPT the MSB itself) of
-        for bit in MsbBitIterator::new(&self.x).skip(1) {
-
-            let dc = &q.double_coefficients[dbl_idx];
-            dbl_idx += 1;
-
-            let mut g_rr_at_p = Fp4::zero(&self.fp4_extension);
-
-            let mut t0 = dc.c_j.clone();
-            t0.mul_assign(&p.x_by_twist);
-            t0.negate();
-            t0.add_assign(&dc.c_l);
-            t0.sub_assign(&dc.c_4c);
-
-            let mut t1 = dc.c_h.clone();
-            t1.mul_assign(&p.y_by_twist);
-
-            g_rr_at_p.c0 = t0;
-            g_rr_at_p.c1 = t1;
-
-            f.square();
-            f.mul_assign(&g_rr_at_p);
-
-            if bit {
-                let ac = &q.addition_coefficients[add_idx];
-                add_idx += 1;
-
-                let mut g_rq_at_p = Fp4::zero(&self.fp4_extension);
-
-                let mut t0 = ac.c_rz.clone();
-                t0.mul_assign(&p.y_by_twist);
-
-                let mut t = l1_coeff.clone();
-                t.mul_assign(&ac.c_l1);
-
-                let mut t1 = q.y_over_twist.clone();
-                t1.mul_assign(&ac.c_rz);
-                t1.add_assign(&t);
-                t1.negate();
-
-                g_rq_at_p.c0 = t0;
-                g_rq_at_p.c1 = t1;
-
-                f.mul_assign(&g_rq_at_p);
-            }
-        }
-
-        if self.x_is_negative {
-            let ac = &q.addition_coefficients[add_idx];
-
-            let mut g_rnegr_at_p = Fp4::zero(&self.fp4_extension);
+    fn doubling_line_at(&self, dc: &AteDoubleCoefficients<'a, FE, F>, p: &PrecomputedG1<'a, FE, F>) -> Fp4<'a, FE, F> {
+        let mut g_rr_at_p = Fp4::zero(&self.fp4_extension);
 
-            let mut t0 = ac.c_rz.clone();
-            t0.mul_assign(&p.y_by_twist);
+        let mut t0 = dc.c_j.clone();
+        t0.mul_assign(&p.x_by_twist);
+        t0.negate();
+        t0.add_assign(&dc.c_l);
+        t0.sub_assign(&dc.c_4c);
 
-            let mut t = l1_coeff.clone();
-            t.mul_assign(&ac.c_l1);
-
-            let mut t1 = q.y_over_twist.clone();
-            t1.mul_assign(&ac.c_rz);
-            t1.add_assign(&t);
-            t1.negate();
+        let mut t1 = dc.c_h.clone();
+        t1.mul_assign(&p.y_by_twist);
 
-            g_rnegr_at_p.c0 = t0;
-            g_rnegr_at_p.c1 = t1;
-
-            f.mul_assign(&g_rnegr_at_p);
-            f = f.inverse().ok_or(())?;
-        }
+        g_rr_at_p.c0 = t0;
+        g_rr_at_p.c1 = t1;
 
-        Ok(f)
+        g_rr_at_p
     }
 
-    fn ate_pairing_loop_naf(
+    fn addition_line_at(
         &self, 
-        point: &CurvePoint<'a, CB>, 
-        twist_point: &CurvePoint<'a, CTW> 
-    ) -> Result<Fp4<'a, FE, F>, ()> {
-        debug_assert!(point.is_normalized());
-        debug_assert!(twist_point.is_normalized());
+        ac: &AteAdditionCoefficients<'a, FE, F>, 
+        pair: &PrecomputedPair<'a, FE, F>, 
+        negate: bool
+    ) -> Fp4<'a, FE, F> {
+        let mut g_rq_at_p = Fp4::zero(&self.fp4_extension);
+
+        let mut t0 = ac.c_rz.clone();
+        t0.mul_assign(&pair.p.y_by_twist);
+
+        let mut t = pair.l1_coeff.clone();
+        t.mul_assign(&ac.c_l1);
+
+        let mut t1 = pair.q.y_over_twist.clone();
+        t1.mul_assign(&ac.c_rz);
+        t1.add_assign(&t);
+        if negate {
+            t1.negate();
+        }
 
-        let twist_inv = self.twist.inverse().ok_or(())?;
+        g_rq_at_p.c0 = t0;
+        g_rq_at_p.c1 = t1;
 
-        let p = self.precompute_g1(&point);
-        let q = self.precompute_g2_naf(&twist_point, &twist_inv)?;
+        g_rq_at_p
+    }
 
-        let mut l1_coeff = Fp2::zero(&self.fp2_extension);
-        l1_coeff.c0 = p.x.clone();
-        l1_coeff.sub_assign(&q.x_over_twist);
+    // The accumulator is squared once per step for all the pairs, and the lines of every pair are
+    // multiplied into it. For the negative loop parameter the product is inverted once at the end.
+    // Each step is described by (has addition, addition of the negated point)
+    fn miller_loop_with_precomputed(&self, pairs: &[PrecomputedPair<'a, FE, F>], use_naf: bool, ctx: &ExecutionContext) -> Result<Fp4<'a, FE, F>, ()> {
+        let steps: Vec<(bool, bool)> = if use_naf {
+            naf_after_leading_one(&self.x_naf)?.map(|&i| (i != 0, i < 0)).collect()
+        } else {
+            MsbBitIterator::new(&self.x).skip(1).map(|bit| (bit, false)).collect()
+        };
 
         let mut f = Fp4::one(self.fp4_extension);
 
-        let mut dbl_idx: usize = 0;
         let mut add_idx: usize = 0;
 
-        let mut it = self.x_naf.iter().rev();
-        
-        {
-            let first = it.next().expect("naf has enough coefficients");
-            assert_eq!(*first, 1);
-        }
-
-        for &i in it {
-            let dc = &q.double_coefficients[dbl_idx];
-            dbl_idx += 1;
-
-            let mut g_rr_at_p = Fp4::zero(&self.fp4_extension);
-
-            let mut t0 = dc.c_j.clone();
-            t0.mul_assign(&p.x_by_twist);
-            t0.negate();
-            t0.add_assign(&dc.c_l);
-            t0.sub_assign(&dc.c_4c);
-
-            let mut t1 = dc.c_h.clone();
-            t1.mul_assign(&p.y_by_twist);
-
-            g_rr_at_p.c0 = t0;
-            g_rr_at_p.c1 = t1;
+        for (dbl_idx, (addition, negative)) in steps.into_iter().enumerate() {
+            if !ctx.charge(pairs.len()) {
+                return Err(());
+            }
 
             f.square();
-            f.mul_assign(&g_rr_at_p);
-
-            if i != 0 {
-                let ac = &q.addition_coefficients[add_idx];
-                add_idx += 1;
-
-                let mut g_rq_at_p = Fp4::zero(&self.fp4_extension);
-
-                let mut t0 = ac.c_rz.clone();
-                t0.mul_assign(&p.y_by_twist);
 
-                let mut t = l1_coeff.clone();
-                t.mul_assign(&ac.c_l1);
+            for pair in pairs.iter() {
+                f.mul_assign(&self.doubling_line_at(&pair.q.double_coefficients[dbl_idx], &pair.p));
+                ctx.report().count_line_evaluation();
+            }
 
-                let mut t1 = q.y_over_twist.clone();
-                t1.mul_assign(&ac.c_rz);
-                t1.add_assign(&t);
-                if i > 0 {
-                    t1.negate();
+            if addition {
+                for pair in pairs.iter() {
+                    f.mul_assign(&self.addition_line_at(&pair.q.addition_coefficients[add_idx], pair, !negative));
+                    ctx.report().count_line_evaluation();
                 }
-
-                g_rq_at_p.c0 = t0;
-                g_rq_at_p.c1 = t1;
-
-                f.mul_assign(&g_rq_at_p);
+                add_idx += 1;
             }
         }
 
         if self.x_is_negative {
-            let ac = &q.addition_coefficients[add_idx];
-
-            let mut g_rnegr_at_p = Fp4::zero(&self.fp4_extension);
-
-            let mut t0 = ac.c_rz.clone();
-            t0.mul_assign(&p.y_by_twist);
-
-            let mut t = l1_coeff.clone();
-            t.mul_assign(&ac.c_l1);
-
-            let mut t1 = q.y_over_twist.clone();
-            t1.mul_assign(&ac.c_rz);
-            t1.add_assign(&t);
-            t1.negate();
-
-            g_rnegr_at_p.c0 = t0;
-            g_rnegr_at_p.c1 = t1;
-
-            f.mul_assign(&g_rnegr_at_p);
+            for pair in pairs.iter() {
+                f.mul_assign(&self.addition_line_at(&pair.q.addition_coefficients[add_idx], pair, true));
+                ctx.report().count_line_evaluation();
+            }
             f = f.inverse().ok_or(())?;
         }
 
@@ -682,11 +606,7 @@ impl<
     }
 
     fn final_exponentiation(&self, f: &Fp4<'a, FE, F>) -> Option<Fp4<'a, FE, F>> {
-        let value_inv = f.inverse();
-        if value_inv.is_none() {
-            return None;
-        }
-        let value_inv = value_inv.expect("is some");
+        let value_inv = f.inverse()?;
         let value_to_first_chunk = self.final_exponentiation_part_one(f, &value_inv);
         let value_inv_to_first_chunk = self.final_exponentiation_part_one(&value_inv, f);
         
@@ -734,8 +654,8 @@ impl<
     type G1 = CurvePoint<'a, CB>;
     type G2 = CurvePoint<'a, CTW>;
 
-    fn pair<'b>
-        (&self, points: &'b [CurvePoint<'a, CB>], twists: &'b [CurvePoint<'a, CTW>]) -> Option<Self::PairingResult> {
+    fn miller_loop_product_with_context<'b>
+        (&self, points: &'b [CurvePoint<'a, CB>], twists: &'b [CurvePoint<'a, CTW>], ctx: &ExecutionContext) -> Option<Self::PairingResult> {
             if points.len() != twists.len() {
                 return None;
             }
@@ -746,30 +666,24 @@ impl<
                 }
             }
 
-            let mut pairs = Vec::with_capacity(points.len());
-            for (p, q) in points.iter().zip(twists.iter()) {
-                if !p.is_zero() && !q.is_zero() {
-                    pairs.push((p, q));
-                }
-            }
+            let (g1, g2) = normalize_nonzero_pairs(points, twists);
+            let pairs: Vec<_> = g1.iter().zip(g2.iter()).collect();
 
             if pairs.len() == 0 {
                 return Some(Fp4::one(self.fp4_extension));
             }
 
             let loop_result = if self.prefer_naf {
-                self.miller_loop_naf(&pairs[..])
+                multi_miller_loop(&pairs[..], ctx, |chunk| self.miller_loop_naf(chunk, ctx))
             } else {
-                self.miller_loop(&pairs[..])
-            };  
-
-            if loop_result.is_err() {
-                return None;
-            }
+                multi_miller_loop(&pairs[..], ctx, |chunk| self.miller_loop(chunk, ctx))
+            };
 
-            let loop_result = loop_result.expect("is some");
+            loop_result.ok()
+        }
 
-            self.final_exponentiation(&loop_result)
+    fn final_exp(&self, f: &Fp4<'a, FE, F>) -> Option<Self::PairingResult> {
+            self.final_exponentiation(f)
         }   
 }
 
@@ -782,7 +696,7 @@ mod tests {
     use crate::fp::Fp;
     use crate::traits::{FieldElement, ZeroAndOne};
     use crate::extension_towers::fp2::{Fp2, Extension2};
-    use crate::extension_towers::fp4_as_2_over_2::{Extension2Over2};
+    use crate::extension_towers::fp4_as_2_over_2::{Fp4, Extension2Over2};
     use num_traits::Num;
     use crate::weierstrass::{Group, CurveOverFpParameters, CurveOverFp2Parameters};
     use crate::weierstrass::curve::{CurvePoint, WeierstrassCurve};
@@ -893,16 +807,25 @@ mod tests {
         let mut q2 = q.mul(vec![12345678]);
         q2.normalize();
 
-        let _pairing_result = engine.pair(&[p.clone()], &[q.clone()]).unwrap();
+        let pairing_result = engine.pair(&[p.clone()], &[q.clone()]).unwrap();
 
-        let ans1 = engine.pair(&[p.clone()], &[q2]).unwrap();
-        let ans2 = engine.pair(&[p2], &[q.clone()]).unwrap();
-        let ans3 = engine.pair(&[p], &[q]).unwrap();
-        let ans3 = ans3.pow(&vec![12345678]);
+        let ans1 = engine.pair(&[p.clone()], &[q2.clone()]).unwrap();
+        let ans2 = engine.pair(&[p2.clone()], &[q.clone()]).unwrap();
+        let ans3 = pairing_result.pow(&vec![12345678]);
 
         assert!(ans1 == ans2);
         assert!(ans1 == ans3);
 
+        let mut minus_p2 = p2.clone();
+        minus_p2.negate();
+        let product = engine.pair(&[p.clone(), minus_p2], &[q2.clone(), q.clone()]).unwrap();
+        assert!(product == Fp4::one(&extension_4));
+
+        let product = engine.pair(&[p.clone(), p2.clone()], &[q.clone(), q2.clone()]).unwrap();
+        let mut expected = engine.pair(&[p2], &[q2]).unwrap();
+        expected.mul_assign(&pairing_result);
+        assert!(product == expected);
+
         // // let expected_c0_c0_c0 = BigUint::from_str_radix("2819105605953691245277803056322684086884703000473961065716485506033588504203831029066448642358042597501014294104502", 10).unwrap();
         
         // let expected_c0_c0_c0 = BigUint::from_str_radix("1250ebd871fc0a92a7b2d83168d0d727272d441befa15c503dd8e90ce98db3e7b6d194f60839c508a84305aaca1789b6", 16).unwrap();
@@ -7,8 +7,9 @@ use crate::weierstrass::curve::{WeierstrassCurve, CurvePoint};
 use crate::extension_towers::fp3::{Fp3, Extension3};
 use crate::extension_towers::fp6_as_2_over_3::{Fp6, Extension2Over3};
 use crate::pairings::PairingEngine;
-use crate::pairings::{calculate_bits, calculate_hamming_weight, calculate_naf_hamming_weight, into_ternary_wnaf};
-use crate::weierstrass::Group;
+use crate::pairings::{normalize_nonzero_pairs, multi_miller_loop, calculate_bits, calculate_hamming_weight, calculate_naf_hamming_weight, into_ternary_wnaf};
+use crate::execution_context::ExecutionContext;
+use crate::alloc_prelude::*;
 
 #[derive(Clone)]
 pub struct MNT6InstanceParams<
@@ -121,6 +122,13 @@ struct PrecomputedG2<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> {
     pub addition_coefficients: Vec<AteAdditionCoefficients<'a, FE, F>>,
 }
 
+// G1 dependent part of the addition lines is the same for all the steps
+struct PrecomputedPair<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> {
+    pub p: PrecomputedG1<'a, FE, F>,
+    pub q: PrecomputedG2<'a, FE, F>,
+    pub l1_coeff: Fp3<'a, FE, F>,
+}
+
 struct AteDoubleCoefficients<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> {
     pub c_h:  Fp3<'a, FE, F>,
     pub c_4c: Fp3<'a, FE, F>,
@@ -147,19 +155,43 @@ impl<
         CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
         CTW: CurveParameters<BaseFieldElement = Fp3<'a, FE, F>>
     > MNT6Instance<'a, FE, F, CB, CTW> {
-    fn miller_loop<'b, I>(&self, i: I) -> Result<Fp6<'a, FE, F>, ()>
+    fn miller_loop<'b, I>(&self, i: I, ctx: &ExecutionContext) -> Result<Fp6<'a, FE, F>, ()>
     where 'a: 'b,
         I: IntoIterator<
             Item = &'b (&'b CurvePoint<'a, CB>, 
                 &'b CurvePoint<'a, CTW>)
         >
     {
-        let mut f = Fp6::one(self.fp6_extension);
+        let precomputed = self.precompute_pairs(i)?;
+
+        self.miller_loop_with_precomputed(&precomputed, ctx)
+    }
+
+    fn precompute_pairs<'b, I>(&self, i: I) -> Result<Vec<PrecomputedPair<'a, FE, F>>, ()>
+    where 'a: 'b,
+        I: IntoIterator<
+            Item = &'b (&'b CurvePoint<'a, CB>, 
+                &'b CurvePoint<'a, CTW>)
+        >
+    {
+        let twist_inv = self.twist.inverse().ok_or(())?;
+
+        let mut precomputed = vec![];
         for (p, q) in i.into_iter() {
-            f.mul_assign(&self.ate_pairing_loop(p, q)?);
+            debug_assert!(p.is_normalized());
+            debug_assert!(q.is_normalized());
+
+            let p = self.precompute_g1(p);
+            let q = self.precompute_g2(q, &twist_inv)?;
+
+            let mut l1_coeff = Fp3::zero(&self.fp3_extension);
+            l1_coeff.c0 = p.x.clone();
+            l1_coeff.sub_assign(&q.x_over_twist);
+
+            precomputed.push(PrecomputedPair { p, q, l1_coeff });
         }
 
-        Ok(f)
+        Ok(precomputed)
     }
 
     fn precompute_g1(&self, g1_point: &CurvePoint<'a, CB>) -> PrecomputedG1<'a, FE, F> {
@@ -397,94 +429,80 @@ impl<
         Ok(g2_p)
     }
 
-    fn ate_pairing_loop(
-        &self, 
-        point: &CurvePoint<'a, CB>, 
-        twist_point: &CurvePoint<'a, CTW> 
-    ) -> Result<Fp6<'a, FE, F>, ()> {
-        debug_assert!(point.is_normalized());
-        debug_assert!(twist_point.is_normalized());
-
-        let twist_inv = self.twist.inverse().ok_or(())?;
+    fn doubling_line_at(&self, dc: &AteDoubleCoefficients<'a, FE, F>, p: &PrecomputedG1<'a, FE, F>) -> Fp6<'a, FE, F> {
+        let mut g_rr_at_p = Fp6::zero(&self.fp6_extension);
 
-        let p = self.precompute_g1(&point);
-        let q = self.precompute_g2(&twist_point, &twist_inv)?;
-        let mut l1_coeff = Fp3::zero(&self.fp3_extension);
-        l1_coeff.c0 = p.x.clone();
-        l1_coeff.sub_assign(&q.x_over_twist);
+        let mut t0 = dc.c_j.clone();
+        t0.mul_assign(&p.x_by_twist);
+        t0.negate();
+        t0.add_assign(&dc.c_l);
+        t0.sub_assign(&dc.c_4c);
 
-        let mut f = Fp6::one(self.fp6_extension);
+        let mut t1 = dc.c_h.clone();
+        t1.mul_assign(&p.y_by_twist);
 
-        let mut dbl_idx: usize = 0;
-        let mut add_idx: usize = 0;
+        g_rr_at_p.c0 = t0;
+        g_rr_at_p.c1 = t1;
 
-        // The for loop is executed for all bits (EXCEPT the MSB itself) of
-        for bit in MsbBitIterator::new(&self.x).skip(1) {
+        g_rr_at_p
+    }
 
-            let dc = &q.double_coefficients[dbl_idx];
-            dbl_idx += 1;
+    fn addition_line_at(
+        &self, 
+        ac: &AteAdditionCoefficients<'a, FE, F>, 
+        pair: &PrecomputedPair<'a, FE, F>
+    ) -> Fp6<'a, FE, F> {
+        let mut g_rq_at_p = Fp6::zero(&self.fp6_extension);
 
-            let mut g_rr_at_p = Fp6::zero(&self.fp6_extension);
+        let mut t0 = ac.c_rz.clone();
+        t0.mul_assign(&pair.p.y_by_twist);
 
-            let mut t0 = dc.c_j.clone();
-            t0.mul_assign(&p.x_by_twist);
-            t0.negate();
-            t0.add_assign(&dc.c_l);
-            t0.sub_assign(&dc.c_4c);
+        let mut t = pair.l1_coeff.clone();
+        t.mul_assign(&ac.c_l1);
 
-            let mut t1 = dc.c_h.clone();
-            t1.mul_assign(&p.y_by_twist);
+        let mut t1 = pair.q.y_over_twist.clone();
+        t1.mul_assign(&ac.c_rz);
+        t1.add_assign(&t);
+        t1.negate();
 
-            g_rr_at_p.c0 = t0;
-            g_rr_at_p.c1 = t1;
+        g_rq_at_p.c0 = t0;
+        g_rq_at_p.c1 = t1;
 
-            f.square();
-            f.mul_assign(&g_rr_at_p);
-
-            if bit {
-                let ac = &q.addition_coefficients[add_idx];
-                add_idx += 1;
+        g_rq_at_p
+    }
 
-                let mut g_rq_at_p = Fp6::zero(&self.fp6_extension);
+    // One squaring per step is shared by all the pairs, same as for MNT4
+    fn miller_loop_with_precomputed(&self, pairs: &[PrecomputedPair<'a, FE, F>], ctx: &ExecutionContext) -> Result<Fp6<'a, FE, F>, ()> {
+        let mut f = Fp6::one(self.fp6_extension);
 
-                let mut t0 = ac.c_rz.clone();
-                t0.mul_assign(&p.y_by_twist);
+        let mut add_idx: usize = 0;
 
-                let mut t = l1_coeff.clone();
-                t.mul_assign(&ac.c_l1);
+        for (dbl_idx, bit) in MsbBitIterator::new(&self.x).skip(1).enumerate() {
+            if !ctx.charge(pairs.len()) {
+                return Err(());
+            }
 
-                let mut t1 = q.y_over_twist.clone();
-                t1.mul_assign(&ac.c_rz);
-                t1.add_assign(&t);
-                t1.negate();
+            f.square();
 
-                g_rq_at_p.c0 = t0;
-                g_rq_at_p.c1 = t1;
+            for pair in pairs.iter() {
+                f.mul_assign(&self.doubling_line_at(&pair.q.double_coefficients[dbl_idx], &pair.p));
+                ctx.report().count_line_evaluation();
+            }
 
-                f.mul_assign(&g_rq_at_p);
+            if bit {
+                for pair in pairs.iter() {
+                    f.mul_assign(&self.addition_line_at(&pair.q.addition_coefficients[add_idx], pair));
+                    ctx.report().count_line_evaluation();
+                }
+                add_idx += 1;
             }
         }
 
         if self.x_is_negative {
-            let ac = &q.addition_coefficients[add_idx];
-
-            let mut g_rnegr_at_p = Fp6::zero(&self.fp6_extension);
-
-            let mut t0 = ac.c_rz.clone();
-            t0.mul_assign(&p.y_by_twist);
-
-            let mut t = l1_coeff.clone();
-            t.mul_assign(&ac.c_l1);
-
-            let mut t1 = q.y_over_twist.clone();
-            t1.mul_assign(&ac.c_rz);
-            t1.add_assign(&t);
-            t1.negate();
-
-            g_rnegr_at_p.c0 = t0;
-            g_rnegr_at_p.c1 = t1;
-
-            f.mul_assign(&g_rnegr_at_p);
+            for pair in pairs.iter() {
+                f.mul_assign(&self.addition_line_at(&pair.q.addition_coefficients[add_idx], pair));
+                ctx.report().count_line_evaluation();
+            }
             f = f.inverse().ok_or(())?;
         }
 
@@ -492,11 +510,7 @@ impl<
     }
 
     fn final_exponentiation(&self, f: &Fp6<'a, FE, F>) -> Option<Fp6<'a, FE, F>> {
-        let value_inv = f.inverse();
-        if value_inv.is_none() {
-            return None;
-        }
-        let value_inv = value_inv.expect("is some");
+        let value_inv = f.inverse()?;
         let value_to_first_chunk = self.final_exponentiation_part_one(f, &value_inv);
         let value_inv_to_first_chunk = self.final_exponentiation_part_one(&value_inv, f);
         
@@ -549,8 +563,8 @@ impl<
     type G1 = CurvePoint<'a, CB>;
     type G2 = CurvePoint<'a, CTW>;
 
-    fn pair<'b>
-        (&self, points: &'b [CurvePoint<'a, CB>], twists: &'b [CurvePoint<'a, CTW>]) -> Option<Self::PairingResult> {
+    fn miller_loop_product_with_context<'b>
+        (&self, points: &'b [CurvePoint<'a, CB>], twists: &'b [CurvePoint<'a, CTW>], ctx: &ExecutionContext) -> Option<Self::PairingResult> {
             if points.len() != twists.len() {
                 return None;
             }
@@ -561,12 +575,8 @@ impl<
                 }
             }
             
-            let mut pairs = Vec::with_capacity(points.len());
-            for (p, q) in points.iter().zip(twists.iter()) {
-                if !p.is_zero() && !q.is_zero() {
-                    pairs.push((p, q));
-                }
-            }
+            let (g1, g2) = normalize_nonzero_pairs(points, twists);
+            let pairs: Vec<_> = g1.iter().zip(g2.iter()).collect();
 
             if pairs.len() == 0 {
                 return Some(Fp6::one(self.fp6_extension));
@@ -578,15 +588,13 @@ impl<
             //     self.miller_loop(&pairs[..])
             // };  
 
-            let loop_result = self.miller_loop(&pairs[..]);
-
-            if loop_result.is_err() {
-                return None;
-            }
+            let loop_result = multi_miller_loop(&pairs[..], ctx, |chunk| self.miller_loop(chunk, ctx));
 
-            let loop_result = loop_result.expect("is some");
+            loop_result.ok()
+        }
 
-            self.final_exponentiation(&loop_result)
+    fn final_exp(&self, f: &Fp6<'a, FE, F>) -> Option<Self::PairingResult> {
+            self.final_exponentiation(f)
         }   
 }
 
@@ -597,9 +605,9 @@ mod tests {
     use crate::fp::Fp;
     use crate::traits::{FieldElement, ZeroAndOne};
     use crate::extension_towers::fp3::{Fp3, Extension3};
-    use crate::extension_towers::fp6_as_2_over_3::{Extension2Over3};
+    use crate::extension_towers::fp6_as_2_over_3::{Fp6, Extension2Over3};
     use num_traits::Num;
-    use crate::weierstrass::{CurveOverFpParameters, CurveOverFp3Parameters};
+    use crate::weierstrass::{Group, CurveOverFpParameters, CurveOverFp3Parameters};
     use crate::weierstrass::curve::{CurvePoint, WeierstrassCurve};
     use crate::pairings::{PairingEngine};
     use crate::test::{biguint_to_u64_vec};
@@ -714,7 +722,16 @@ mod tests {
 
         let engine = super::MNT6Instance::from_params(engine);
 
-        let pairing_result = engine.pair(&[p], &[q]).unwrap();
+        let pairing_result = engine.pair(&[p.clone()], &[q.clone()]).unwrap();
+
+        // negative loop parameter, lines of all the pairs are accumulated before the inversion
+        let mut p2 = p.mul(vec![12345678]);
+        p2.normalize();
+        p2.negate();
+        let mut q2 = q.mul(vec![12345678]);
+        q2.normalize();
+        let product = engine.pair(&[p.clone(), p2], &[q2, q]).unwrap();
+        assert!(product == Fp6::one(&extension_6));
 
         // // let expected_c0_c0_c0 = BigUint::from_str_radix("2819105605953691245277803056322684086884703000473961065716485506033588504203831029066448642358042597501014294104502", 10).unwrap();
         
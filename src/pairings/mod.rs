@@ -1,11 +1,27 @@
 
 use crate::traits::{FieldElement};
 use crate::weierstrass::Group;
+use crate::weierstrass::CurveParameters;
+use crate::weierstrass::curve::{CurvePoint, batch_normalize};
+use crate::fp::Fp;
+use crate::field::SizedPrimeField;
+use crate::representation::ElementRepr;
+use crate::traits::ZeroAndOne;
+use crate::execution_context::ExecutionContext;
+use crate::alloc_prelude::*;
 
 pub mod bls12;
+pub mod bls24;
+pub mod bls48;
+pub mod bw6;
+pub mod kss16;
+pub mod kss18;
+pub mod cocks_pinch;
 pub mod bn;
 pub mod mnt6;
 pub mod mnt4;
+pub(crate) mod subgroup;
+pub mod prepared_g2;
 
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
 pub enum TwistType {
@@ -18,7 +34,154 @@ pub trait PairingEngine: Sized + Clone + Send + Sync {
     type G1: Group;
     type G2: Group;
 
-    fn pair<'b> (&self, points: &'b [Self::G1], twists: &'b [Self::G2]) -> Option<Self::PairingResult>;
+    /// Product of the Miller loops over all pairs, without the final exponentiation.
+    /// The loops charge the operation budget of `ctx`
+    fn miller_loop_product_with_context<'b> (&self, points: &'b [Self::G1], twists: &'b [Self::G2], ctx: &ExecutionContext) -> Option<Self::PairingResult>;
+
+    /// Final exponentiation of a (possibly accumulated) Miller loop product
+    fn final_exp(&self, f: &Self::PairingResult) -> Option<Self::PairingResult>;
+
+    fn pair_with_context<'b> (&self, points: &'b [Self::G1], twists: &'b [Self::G2], ctx: &ExecutionContext) -> Option<Self::PairingResult> {
+        let loop_result = self.miller_loop_product_with_context(points, twists, ctx)?;
+
+        self.final_exp(&loop_result)
+    }
+
+    /// Same as `miller_loop_product_with_context` without an operation budget
+    fn miller_loop_product<'b> (&self, points: &'b [Self::G1], twists: &'b [Self::G2]) -> Option<Self::PairingResult> {
+        self.miller_loop_product_with_context(points, twists, &ExecutionContext::default())
+    }
+
+    fn pair<'b> (&self, points: &'b [Self::G1], twists: &'b [Self::G2]) -> Option<Self::PairingResult> {
+        self.pair_with_context(points, twists, &ExecutionContext::default())
+    }
+}
+
+/// Drops pairs where either point is the identity and brings the remaining points
+/// to affine form, using one batch inversion per group for all pairs
+pub(crate) fn normalize_nonzero_pairs<'a, CB: CurveParameters, CTW: CurveParameters>(
+    points: &[CurvePoint<'a, CB>], 
+    twists: &[CurvePoint<'a, CTW>]
+) -> (Vec<CurvePoint<'a, CB>>, Vec<CurvePoint<'a, CTW>>) {
+    let mut g1 = Vec::with_capacity(points.len());
+    let mut g2 = Vec::with_capacity(twists.len());
+    for (p, q) in points.iter().zip(twists.iter()) {
+        if !p.is_zero() && !q.is_zero() {
+            g1.push(p.clone());
+            g2.push(q.clone());
+        }
+    }
+
+    if g1.len() != 0 {
+        batch_normalize(&mut g1);
+        batch_normalize(&mut g2);
+    }
+
+    (g1, g2)
+}
+
+/// G1 points of the pairs, index of the prepared twist point of every pair and the prepared twist points
+pub(crate) type DistinctTwists<'a, 'b, CB, T> = (Vec<&'b CurvePoint<'a, CB>>, Vec<usize>, Vec<T>);
+
+/// Drops pairs with a point at infinity and prepares the twist points of the rest with `prepare`.
+/// Line coefficients depend only on the twist point, so a point that appears in several
+/// pairs (e.g. a generator or a verification key) is prepared once. Points are expected
+/// to be normalized, so affine coordinates are compared
+pub(crate) fn prepare_distinct_twists<
+    'a,
+    'b,
+    CB: CurveParameters + 'a,
+    CTW: CurveParameters + 'a,
+    T,
+    I: IntoIterator<Item = &'b (&'b CurvePoint<'a, CB>, &'b CurvePoint<'a, CTW>)>,
+    P: FnMut(&CurvePoint<'a, CTW>) -> Result<T, ()>
+    >
+    (
+        pairs: I,
+        mut prepare: P
+    ) -> Result<DistinctTwists<'a, 'b, CB, T>, ()>
+    where 'a: 'b
+{
+    let mut g1_references = vec![];
+    let mut twist_indexes = vec![];
+    let mut distinct_twists: Vec<&CurvePoint<'a, CTW>> = vec![];
+    let mut prepared_twists = vec![];
+
+    for (p, q) in pairs.into_iter() {
+        if !p.is_zero() && !q.is_zero() {
+            debug_assert!(q.is_normalized());
+            let existing = distinct_twists.iter().position(|t| t.x == q.x && t.y == q.y);
+            let index = match existing {
+                Some(index) => index,
+                None => {
+                    distinct_twists.push(q);
+                    prepared_twists.push(prepare(q)?);
+
+                    prepared_twists.len() - 1
+                }
+            };
+            twist_indexes.push(index);
+            g1_references.push(*p);
+        }
+    }
+
+    Ok((g1_references, twist_indexes, prepared_twists))
+}
+
+/// Inverse of two in the base field for the doubling steps. It always exists for an odd modulus,
+/// but is still checked so that a malformed field results in an error and not a panic
+pub(crate) fn two_inverse<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>(base_field: &'a F) -> Result<Fp<'a, FE, F>, ()> {
+    let mut two = Fp::one(base_field);
+    two.double();
+
+    two.inverse().ok_or(())
+}
+
+/// Digits of the NAF of a loop parameter after the leading one, most significant first. The leading
+/// digit is consumed by the initialization of the accumulator, so it must be one
+pub(crate) fn naf_after_leading_one(naf: &[i8]) -> Result<core::iter::Rev<core::slice::Iter<'_, i8>>, ()> {
+    let mut it = naf.iter().rev();
+    match it.next() {
+        Some(1) => Ok(it),
+        _ => Err(()),
+    }
+}
+
+/// Pairs per chunk below which splitting the Miller loop does not pay off: every chunk
+/// repeats the squarings and the final conjugations of the loop
+#[cfg(feature = "parallel")]
+const MIN_PAIRS_PER_PARALLEL_CHUNK: usize = 2;
+
+/// Runs `miller_loop` over all the pairs. With the `parallel` feature the pairs are split into chunks
+/// that are processed on the rayon thread pool and the results are multiplied. Miller loop over
+/// all the pairs is the product of Miller loops over the chunks, so the caller still performs
+/// the final exponentiation only once. `miller_loop` charges the operation budget of the call
+/// on every chunk, field multiplications of the chunks are counted into the report of `ctx`
+pub(crate) fn multi_miller_loop<T, R, L>(pairs: &[T], ctx: &ExecutionContext, miller_loop: L) -> Result<R, ()>
+    where T: Sync, R: FieldElement + Send, L: Fn(&[T]) -> Result<R, ()> + Sync
+{
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+
+        let num_threads = rayon::current_num_threads();
+        if num_threads > 1 && pairs.len() >= 2 * MIN_PAIRS_PER_PARALLEL_CHUNK {
+            let chunk_size = core::cmp::max(pairs.len().div_ceil(num_threads), MIN_PAIRS_PER_PARALLEL_CHUNK);
+            let results: Vec<Result<R, ()>> = pairs.par_chunks(chunk_size).map(|chunk| {
+                ctx.report().count_field_multiplications(|| miller_loop(chunk))
+            }).collect();
+
+            let mut results = results.into_iter();
+            let mut f = results.next().ok_or(())??;
+            for r in results {
+                f.mul_assign(&r?);
+            }
+
+            return Ok(f);
+        }
+    }
+
+    ctx.report().count_field_multiplications(|| miller_loop(pairs))
 }
 
 pub(crate) fn calculate_hamming_weight(representation: &[u64]) -> u32 {
@@ -137,6 +300,31 @@ pub(crate) fn into_ternary_wnaf(repr: &[u64]) -> Vec<i8> {
     res
 }
 
+/// Signed digits of the loop count, least significant first: either plain binary
+/// or NAF if the latter requires less steps in the Miller loop
+pub(crate) fn into_loop_digits(scalar: &[u64], force_no_naf: bool) -> Vec<i8> {
+    let original_bits = calculate_bits(scalar);
+
+    if !force_no_naf {
+        let naf_vec = into_ternary_wnaf(scalar);
+        let original_hamming = calculate_hamming_weight(scalar);
+        let naf_hamming = calculate_naf_hamming_weight(&naf_vec);
+        let naf_length = naf_vec.len() as u32;
+
+        if naf_length + naf_hamming < original_bits + original_hamming {
+            return naf_vec;
+        }
+    }
+
+    let mut digits = Vec::with_capacity(original_bits as usize);
+    for i in 0..(original_bits as usize) {
+        let bit = (scalar[i / 64] >> (i % 64)) & 1u64;
+        digits.push(bit as i8);
+    }
+
+    digits
+}
+
 #[cfg(test)]
 mod test {
     use num_bigint::BigUint;
@@ -152,7 +340,6 @@ mod test {
     use crate::extension_towers::fp12_as_2_over3_over_2::{Extension2Over3Over2};
     use num_traits::Num;
     use crate::test::{biguint_to_u64_vec};
-    use crate::sliding_window_exp::WindowExpBase;
     use crate::integers::MaxFieldUint;
 
     #[test]
@@ -618,4 +805,27 @@ mod test {
 
 
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_multi_pairing_is_product_of_single_pairings() {
+        use crate::engines::bls12_381::*;
+        use crate::weierstrass::Group;
+        use crate::pairings::PairingEngine;
+
+        let engine = BLS12_381_PAIRING_ENGINE;
+        let mut points = vec![];
+        let mut twists = vec![];
+        for i in 1..=9u64 {
+            points.push(BLS12_381_G1_GENERATOR.mul(&[i]));
+            twists.push(BLS12_381_G2_GENERATOR.mul(&[i + 7]));
+        }
+
+        let mut expected = engine.pair(&points[..1], &twists[..1]).unwrap();
+        for (p, q) in points.iter().zip(twists.iter()).skip(1) {
+            expected.mul_assign(&engine.pair(&[p.clone()], &[q.clone()]).unwrap());
+        }
+
+        let result = engine.pair(&points, &twists).unwrap();
+        assert!(result == expected);
+    }
+}
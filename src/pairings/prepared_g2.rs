@@ -0,0 +1,139 @@
+use crate::field::SizedPrimeField;
+use crate::fp::Fp;
+use crate::representation::ElementRepr;
+use crate::traits::ZeroAndOne;
+use crate::weierstrass::{Group, CurveParameters};
+use crate::weierstrass::curve::{CurvePoint, batch_normalize};
+use crate::extension_towers::fp2::{Fp2, Extension2};
+use crate::extension_towers::fp12_as_2_over3_over_2::{Fp12, Extension2Over3Over2};
+use crate::pairings::TwistType;
+use crate::pairings::{multi_miller_loop, two_inverse};
+use crate::execution_context::ExecutionContext;
+use crate::alloc_prelude::*;
+
+/// Line coefficients of a point on a twist over Fp2, one triple per step of the Miller loop
+#[derive(Clone)]
+pub(crate) struct PreparedTwistPoint<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> {
+    pub(crate) ell_coeffs: Vec<(Fp2<'a, FE, F>, Fp2<'a, FE, F>, Fp2<'a, FE, F>)>
+}
+
+/// Parameters of the engine that line coefficients depend on besides the point: the loop
+/// parameter and the way it's represented define the steps, the twist type and the Fp2
+/// extension define the meaning of the coefficients. The extension is compared by identity
+#[derive(Clone)]
+pub(crate) struct LoopBinding<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> {
+    pub(crate) loop_parameter: &'a [u64],
+    pub(crate) loop_parameter_is_negative: bool,
+    pub(crate) use_naf: bool,
+    pub(crate) twist_type: TwistType,
+    pub(crate) fp2_extension: &'a Extension2<'a, FE, F>,
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> LoopBinding<'a, FE, F> {
+    fn matches(&self, other: &Self) -> bool {
+        self.loop_parameter == other.loop_parameter
+            && self.loop_parameter_is_negative == other.loop_parameter_is_negative
+            && self.use_naf == other.use_naf
+            && self.twist_type == other.twist_type
+            && core::ptr::eq(self.fp2_extension, other.fp2_extension)
+    }
+}
+
+/// Line coefficients of a G2 point for the Miller loop of a specific BLS12 or BN engine instance.
+/// Preparation is the dominant per pair cost, so a G2 point that takes part in many pairings
+/// (e.g. a public key or a verification key element) can be prepared once with `prepare_g2`
+/// and reused. The point remembers the engine parameters it was prepared for and is rejected
+/// by engines with other ones
+#[derive(Clone)]
+pub struct PreparedG2<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> {
+    pub(crate) prepared: PreparedTwistPoint<'a, FE, F>,
+    pub(crate) binding: LoopBinding<'a, FE, F>,
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> PreparedG2<'a, FE, F> {
+    /// Point at infinity doesn't contribute to the Miller loop
+    pub fn is_zero(&self) -> bool {
+        self.prepared.ell_coeffs.is_empty()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PreparedG2Error {
+    /// Numbers of G1 points and prepared G2 points differ or are zero
+    LengthMismatch,
+    /// A G2 point was prepared by an engine with another loop parameter, twist or extension
+    EngineMismatch,
+    /// Engine parameters are malformed
+    InvalidEngine,
+}
+
+/// Normalizes the twist point and computes its lines with `prepare`, that gets the inverse of two
+pub(crate) fn prepare_g2<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    CTW: CurveParameters<BaseFieldElement = Fp2<'a, FE, F>>,
+    P: FnOnce(&CurvePoint<'a, CTW>, &Fp<'a, FE, F>) -> PreparedTwistPoint<'a, FE, F>
+    >
+    (
+        twist_point: &CurvePoint<'a, CTW>,
+        base_field: &'a F,
+        binding: LoopBinding<'a, FE, F>,
+        prepare: P
+    ) -> Result<PreparedG2<'a, FE, F>, PreparedG2Error>
+{
+    let mut q = twist_point.clone();
+    q.normalize();
+
+    let two_inv = two_inverse(base_field).map_err(|_| PreparedG2Error::InvalidEngine)?;
+    let prepared = prepare(&q, &two_inv);
+
+    Ok(PreparedG2 {
+        prepared,
+        binding,
+    })
+}
+
+/// Checks that every prepared point was made for `binding`, drops pairs with a point at infinity
+/// and runs `miller_loop` over the rest
+pub(crate) fn miller_loop_with_prepared_g2<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+    L: Fn(&[(&CurvePoint<'a, CB>, &PreparedTwistPoint<'a, FE, F>)]) -> Result<Fp12<'a, FE, F>, ()> + Sync
+    >
+    (
+        points: &[CurvePoint<'a, CB>],
+        prepared: &[&PreparedG2<'a, FE, F>],
+        binding: &LoopBinding<'a, FE, F>,
+        fp12_extension: &'a Extension2Over3Over2<'a, FE, F>,
+        ctx: &ExecutionContext,
+        miller_loop: L
+    ) -> Result<Fp12<'a, FE, F>, PreparedG2Error>
+{
+    if points.len() != prepared.len() || points.len() == 0 {
+        return Err(PreparedG2Error::LengthMismatch);
+    }
+
+    let mut g1 = vec![];
+    let mut g2 = vec![];
+    for (p, q) in points.iter().zip(prepared.iter()) {
+        if !q.binding.matches(binding) {
+            return Err(PreparedG2Error::EngineMismatch);
+        }
+        if !p.is_zero() && !q.is_zero() {
+            g1.push(p.clone());
+            g2.push(&q.prepared);
+        }
+    }
+
+    if g1.len() == 0 {
+        return Ok(Fp12::one(fp12_extension));
+    }
+
+    batch_normalize(&mut g1);
+    let pairs: Vec<_> = g1.iter().zip(g2.into_iter()).collect();
+
+    multi_miller_loop(&pairs[..], ctx, |chunk| miller_loop(chunk)).map_err(|_| PreparedG2Error::InvalidEngine)
+}
@@ -0,0 +1,294 @@
+//! Subgroup membership tests for BLS12 and BN curves that use endomorphisms instead of
+//! a multiplication by the full group order.
+//!
+//! In G1 both families have the endomorphism phi: (x, y) -> (beta * x, y), where beta is
+//! a non-trivial cube root of unity, and phi^2 + phi + 1 = 0. The degree of [a] + [b]phi is
+//! a^2 - ab + b^2, and for (a, b) = (x^2, 1) on BLS12 and (2u + 1, 6u^2 + 4u + 1) on BN it is
+//! exactly the group order r. Composing an endomorphism with its dual gives the multiplication
+//! by its degree, so every point in the kernel has order dividing r, whatever the curve is.
+//! Points of order r are in the kernel of either [a] + [b]phi or its conjugate [a - b] - [b]phi
+//! unless the curve has more than one subgroup of order r, so a point that fails the test is
+//! checked again by multiplying by r and the result is always the same as `check_correct_subgroup`.
+//!
+//! In G2 the test is psi(Q) = [lambda]Q, where psi is the untwist-Frobenius-twist endomorphism
+//! and lambda = p mod r. Here the kernel is larger than r, so the test is only valid for curves
+//! where it's known to be exact (BLS12-381 with lambda = x, BN254 with lambda = 6u^2)
+
+use crate::field::SizedPrimeField;
+use crate::fp::Fp;
+use crate::representation::ElementRepr;
+use crate::traits::{FieldElement, ZeroAndOne};
+use crate::weierstrass::Group;
+use crate::weierstrass::CurveParameters;
+use crate::weierstrass::curve::CurvePoint;
+use crate::extension_towers::fp2::Fp2;
+use crate::extension_towers::fp6_as_3_over_2::Extension3Over2;
+use crate::extension_towers::fp12_as_2_over3_over_2::Extension2Over3Over2;
+use crate::integers::MaxFieldSquaredUint;
+use crate::pairings::TwistType;
+
+/// Non-trivial cube root of unity in Fp. It's the second Frobenius coefficient of Fp6,
+/// that is non_residue^((p^2 - 1)/3) = norm(non_residue)^((p - 1)/3)
+fn cube_root_of_unity<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>(
+    fp6_extension: &Extension3Over2<'a, FE, F>
+) -> Option<Fp<'a, FE, F>> {
+    let candidate = &fp6_extension.frobenius_coeffs_c1[2];
+    if !candidate.c1.is_zero() {
+        return None;
+    }
+    let beta = candidate.c0.clone();
+    let one = Fp::one(beta.field);
+    if beta == one {
+        return None;
+    }
+
+    let mut sum = beta.clone();
+    sum.square();
+    sum.add_assign(&beta);
+    sum.add_assign(&one);
+    if !sum.is_zero() {
+        return None;
+    }
+
+    Some(beta)
+}
+
+/// Whether `point` is mapped to zero by [a] + [b]phi or by [a - b] - [b]phi
+fn is_in_kernel_of_phi_combination<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, C: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>>(
+    point: &CurvePoint<'a, C>,
+    beta: &Fp<'a, FE, F>,
+    a: &MaxFieldSquaredUint,
+    a_is_negative: bool,
+    b: &MaxFieldSquaredUint
+) -> bool {
+    let mut a_point = point.wnaf_mul_impl(a.as_ref());
+    if a_is_negative {
+        a_point.negate();
+    }
+    let b_point = point.wnaf_mul_impl(b.as_ref());
+    let mut phi_b_point = b_point.clone();
+    phi_b_point.x.mul_assign(beta);
+
+    let mut image = a_point.clone();
+    image.add_assign(&phi_b_point);
+    if image.is_zero() {
+        return true;
+    }
+
+    let mut conjugate_image = a_point;
+    conjugate_image.sub_assign(&b_point);
+    conjugate_image.sub_assign(&phi_b_point);
+
+    conjugate_image.is_zero()
+}
+
+/// (a, sign of a, b) such that [a] + [b]phi has degree equal to `order` for a BLS12 curve
+fn bls12_g1_kernel_parameters(x: &[u64], order: &MaxFieldSquaredUint) -> Option<(MaxFieldSquaredUint, bool, MaxFieldSquaredUint)> {
+    let x = MaxFieldSquaredUint::from(x);
+    // x^4 - x^2 + 1 has about four times the bits of x, larger x can not give the order
+    if 4 * x.bits() > order.bits() + 4 {
+        return None;
+    }
+    let x_squared = x * x;
+    let one = MaxFieldSquaredUint::one();
+    if x_squared * x_squared - x_squared + one != *order {
+        return None;
+    }
+
+    Some((x_squared, false, one))
+}
+
+/// Same as `bls12_g1_kernel_parameters` for a BN curve
+fn bn_g1_kernel_parameters(u: &[u64], u_is_negative: bool, order: &MaxFieldSquaredUint) -> Option<(MaxFieldSquaredUint, bool, MaxFieldSquaredUint)> {
+    let u = MaxFieldSquaredUint::from(u);
+    // same for 36u^4 + 36u^3 + 18u^2 + 6u + 1 with any sign of u
+    if 4 * u.bits() > order.bits() + 4 {
+        return None;
+    }
+    let one = MaxFieldSquaredUint::one();
+    let two = MaxFieldSquaredUint::from(2u64);
+    let four = MaxFieldSquaredUint::from(4u64);
+    let six = MaxFieldSquaredUint::from(6u64);
+    let eighteen = MaxFieldSquaredUint::from(18u64);
+    let thirty_six = MaxFieldSquaredUint::from(36u64);
+    let u_squared = u * u;
+    let u_cubed = u_squared * u;
+
+    // manually handle the sign of u: a = 2u + 1, b = 6u^2 + 4u + 1 (always positive)
+    let (r, a, b) = if u_is_negative {
+        let r = thirty_six * u_squared * u_squared - thirty_six * u_cubed + eighteen * u_squared - six * u + one;
+        let a = two * u - one;
+        let b = six * u_squared - four * u + one;
+
+        (r, a, b)
+    } else {
+        let r = thirty_six * u_squared * u_squared + thirty_six * u_cubed + eighteen * u_squared + six * u + one;
+        let a = two * u + one;
+        let b = six * u_squared + four * u + one;
+
+        (r, a, b)
+    };
+    if r != *order {
+        return None;
+    }
+
+    Some((a, u_is_negative, b))
+}
+
+/// Points that are not on the curve can only reach the check in fuzzing or gas metering,
+/// these and curves with `a != 0` always use the full check
+fn g1_subgroup_check<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, C: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>, P>(
+    point: &CurvePoint<'a, C>,
+    fp6_extension: &Extension3Over2<'a, FE, F>,
+    kernel_parameters: P
+) -> bool
+    where P: FnOnce(&MaxFieldSquaredUint) -> Option<(MaxFieldSquaredUint, bool, MaxFieldSquaredUint)>
+{
+    if point.curve.a.is_zero() && point.is_normalized() && point.is_on_curve() {
+        let order = MaxFieldSquaredUint::from(point.curve.subgroup_order_repr);
+        if let (Some(beta), Some((a, a_is_negative, b))) = (cube_root_of_unity(fp6_extension), kernel_parameters(&order)) {
+            if is_in_kernel_of_phi_combination(point, &beta, &a, a_is_negative, &b) {
+                return true;
+            }
+        }
+    }
+
+    point.check_correct_subgroup()
+}
+
+/// Fast path of `check_correct_subgroup` for G1 of a BLS12 curve with parameter `x`
+pub(crate) fn bls12_g1_subgroup_check<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, C: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>>(
+    point: &CurvePoint<'a, C>,
+    x: &[u64],
+    fp6_extension: &Extension3Over2<'a, FE, F>
+) -> bool {
+    g1_subgroup_check(point, fp6_extension, |order| bls12_g1_kernel_parameters(x, order))
+}
+
+/// Fast path of `check_correct_subgroup` for G1 of a BN curve with parameter `u`
+pub(crate) fn bn_g1_subgroup_check<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, C: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>>(
+    point: &CurvePoint<'a, C>,
+    u: &[u64],
+    u_is_negative: bool,
+    fp6_extension: &Extension3Over2<'a, FE, F>
+) -> bool {
+    g1_subgroup_check(point, fp6_extension, |order| bn_g1_kernel_parameters(u, u_is_negative, order))
+}
+
+/// psi(Q) = twist(frobenius(untwist(Q))). For Jacobian coordinates the Frobenius is applied
+/// to all of them and X and Y are multiplied by the twisting coefficients
+fn psi<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, C: CurveParameters<BaseFieldElement = Fp2<'a, FE, F>>>(
+    point: &CurvePoint<'a, C>,
+    fp12_extension: &Extension2Over3Over2<'a, FE, F>,
+    twist_type: TwistType
+) -> Option<CurvePoint<'a, C>> {
+    // non_residue^((p - 1)/6)
+    let gamma = &fp12_extension.frobenius_coeffs_c1[1];
+    let mut x_coeff = gamma.clone();
+    x_coeff.square();
+    let mut y_coeff = x_coeff.clone();
+    y_coeff.mul_assign(gamma);
+    if twist_type == TwistType::M {
+        x_coeff = x_coeff.inverse()?;
+        y_coeff = y_coeff.inverse()?;
+    }
+
+    let mut result = point.clone();
+    result.x.c1.negate();
+    result.x.mul_assign(&x_coeff);
+    result.y.c1.negate();
+    result.y.mul_assign(&y_coeff);
+    result.z.c1.negate();
+
+    Some(result)
+}
+
+/// Whether psi(Q) = [lambda]Q. Only a subgroup check for the curves mentioned in the module
+/// documentation, points that are not on the curve always use the full check
+pub(crate) fn psi_eigenvalue_check<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, C: CurveParameters<BaseFieldElement = Fp2<'a, FE, F>>>(
+    point: &CurvePoint<'a, C>,
+    fp12_extension: &Extension2Over3Over2<'a, FE, F>,
+    twist_type: TwistType,
+    lambda: &[u64],
+    lambda_is_negative: bool
+) -> bool {
+    if !point.is_normalized() || !point.is_on_curve() {
+        return point.check_correct_subgroup();
+    }
+
+    let mut difference = match psi(point, fp12_extension, twist_type) {
+        Some(psi_point) => psi_point,
+        None => {
+            return point.check_correct_subgroup();
+        }
+    };
+    let mut multiplied = point.wnaf_mul_impl(lambda);
+    if lambda_is_negative {
+        multiplied.negate();
+    }
+    difference.sub_assign(&multiplied);
+
+    difference.is_zero()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{random_point, random_point_outside_subgroup};
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_bls12_381_subgroup_checks() {
+        use crate::engines::bls12_381::*;
+
+        let rng = &mut XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        let engine = BLS12_381_PAIRING_ENGINE;
+        let order = MaxFieldSquaredUint::from(&BLS12_381_SUBGROUP_ORDER[..]);
+        let (a, a_is_negative, b) = bls12_g1_kernel_parameters(engine.x, &order).expect("order is x^4 - x^2 + 1");
+        let beta = cube_root_of_unity(engine.fp6_extension).expect("p = 1 mod 3");
+
+        assert!(engine.g1_subgroup_check(&CurvePoint::zero(&BLS12_381_G1_CURVE)));
+        assert!(engine.g2_subgroup_check_by_psi(&CurvePoint::zero(&BLS12_381_G2_CURVE)));
+
+        for _ in 0..8 {
+            // must not need the fallback for points of the subgroup
+            let p = random_point(&BLS12_381_G1_GENERATOR, rng);
+            assert!(is_in_kernel_of_phi_combination(&p, &beta, &a, a_is_negative, &b));
+            assert!(engine.g1_subgroup_check(&p));
+
+            let q = random_point(&BLS12_381_G2_GENERATOR, rng);
+            assert!(engine.g2_subgroup_check_by_psi(&q));
+
+            let p = random_point_outside_subgroup(&BLS12_381_G1_CURVE, rng).unwrap();
+            assert!(!is_in_kernel_of_phi_combination(&p, &beta, &a, a_is_negative, &b));
+            assert!(!engine.g1_subgroup_check(&p));
+
+            let q = random_point_outside_subgroup(&BLS12_381_G2_CURVE, rng).unwrap();
+            assert!(!engine.g2_subgroup_check_by_psi(&q));
+        }
+    }
+
+    #[test]
+    fn test_bn254_subgroup_checks() {
+        use crate::engines::bn254::*;
+
+        let rng = &mut XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        let engine = &*BN254_PAIRING_ENGINE;
+        let order = MaxFieldSquaredUint::from(&BN254_SUBGROUP_ORDER[..]);
+        let (a, a_is_negative, b) = bn_g1_kernel_parameters(engine.u, engine.u_is_negative, &order).expect("order is 36u^4 + 36u^3 + 18u^2 + 6u + 1");
+        let beta = cube_root_of_unity(engine.fp6_extension).expect("p = 1 mod 3");
+
+        for _ in 0..8 {
+            let p = random_point(&BN254_G1_GENERATOR, rng);
+            assert!(is_in_kernel_of_phi_combination(&p, &beta, &a, a_is_negative, &b));
+            assert!(engine.g1_subgroup_check(&p));
+
+            let q = random_point(&BN254_G2_GENERATOR, rng);
+            assert!(engine.g2_subgroup_check_by_psi(&q));
+
+            let q = random_point_outside_subgroup(&BN254_G2_CURVE, rng).unwrap();
+            assert!(!engine.g2_subgroup_check_by_psi(&q));
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! Miller-Rabin test of the group orders supplied in the input. Orders are not fixed in advance,
+//! so arithmetic is done on limbs of the order width with Montgomery multiplication here instead
+//! of building a field for every supported width
+
+use crate::integers::{MaxGroupSizeUint, MaxFieldSquaredUint};
+use crate::public_interface::constants::NUM_GROUP_LIMBS_MAX;
+use crate::execution_context::ExecutionContext;
+
+/// First 12 primes. The test with these bases is exact for numbers below 3.18 * 10^23.
+/// Larger composite numbers that pass it with all of these bases can be constructed,
+/// so for them passing the test is not a proof of primality
+const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+type Limbs = [u64; NUM_GROUP_LIMBS_MAX];
+
+struct Montgomery<'a> {
+    modulus: &'a [u64],
+    // -modulus^-1 mod 2^64
+    inv: u64,
+}
+
+impl<'a> Montgomery<'a> {
+    fn new(modulus: &'a [u64]) -> Self {
+        let mut inv = 1u64;
+        for _ in 0..63 {
+            inv = inv.wrapping_mul(inv);
+            inv = inv.wrapping_mul(modulus[0]);
+        }
+
+        Self {
+            modulus,
+            inv: inv.wrapping_neg(),
+        }
+    }
+
+    /// Montgomery representation of `value`, that is `value * 2^(64 * limbs) mod modulus`
+    fn to_montgomery(&self, value: u64) -> Limbs {
+        let num_limbs = self.modulus.len();
+        let modulus = MaxFieldSquaredUint::from(self.modulus);
+        let value = (MaxFieldSquaredUint::from(value) << ((num_limbs * 64) as u32)) % modulus;
+
+        let mut result = [0u64; NUM_GROUP_LIMBS_MAX];
+        result[..num_limbs].copy_from_slice(&value.as_ref()[..num_limbs]);
+
+        result
+    }
+
+    /// Coarsely integrated operand scanning, the modulus may use the top bit of its top limb
+    fn mul(&self, a: &Limbs, b: &Limbs) -> Limbs {
+        let n = self.modulus.len();
+        let mut t = [0u64; NUM_GROUP_LIMBS_MAX + 2];
+        for i in 0..n {
+            let mut carry = 0u64;
+            for j in 0..n {
+                let v = (t[j] as u128) + (a[j] as u128) * (b[i] as u128) + (carry as u128);
+                t[j] = v as u64;
+                carry = (v >> 64) as u64;
+            }
+            let v = (t[n] as u128) + (carry as u128);
+            t[n] = v as u64;
+            t[n + 1] = (v >> 64) as u64;
+
+            let k = t[0].wrapping_mul(self.inv);
+            let v = (t[0] as u128) + (k as u128) * (self.modulus[0] as u128);
+            let mut carry = (v >> 64) as u64;
+            for j in 1..n {
+                let v = (t[j] as u128) + (k as u128) * (self.modulus[j] as u128) + (carry as u128);
+                t[j - 1] = v as u64;
+                carry = (v >> 64) as u64;
+            }
+            let v = (t[n] as u128) + (carry as u128);
+            t[n - 1] = v as u64;
+            t[n] = t[n + 1] + ((v >> 64) as u64);
+        }
+
+        let mut result = [0u64; NUM_GROUP_LIMBS_MAX];
+        result[..n].copy_from_slice(&t[..n]);
+        if t[n] != 0 || !self.is_below_modulus(&result) {
+            let mut borrow = 0u64;
+            for j in 0..n {
+                let (v, b0) = result[j].overflowing_sub(self.modulus[j]);
+                let (v, b1) = v.overflowing_sub(borrow);
+                result[j] = v;
+                borrow = (b0 | b1) as u64;
+            }
+        }
+
+        result
+    }
+
+    /// `modulus - value` for a non-zero `value` below the modulus
+    fn negate(&self, value: &Limbs) -> Limbs {
+        let mut result = [0u64; NUM_GROUP_LIMBS_MAX];
+        let mut borrow = 0u64;
+        for j in 0..self.modulus.len() {
+            let (v, b0) = self.modulus[j].overflowing_sub(value[j]);
+            let (v, b1) = v.overflowing_sub(borrow);
+            result[j] = v;
+            borrow = (b0 | b1) as u64;
+        }
+
+        result
+    }
+
+    fn is_below_modulus(&self, value: &Limbs) -> bool {
+        for j in (0..self.modulus.len()).rev() {
+            if value[j] != self.modulus[j] {
+                return value[j] < self.modulus[j];
+            }
+        }
+
+        false
+    }
+
+    fn pow(&self, base: &Limbs, exp: &MaxGroupSizeUint, one: &Limbs) -> Limbs {
+        let mut result = *one;
+        for i in (0..exp.bits()).rev() {
+            result = self.mul(&result, &result);
+            if exp.bit(i) {
+                result = self.mul(&result, base);
+            }
+        }
+
+        result
+    }
+}
+
+fn rem_u64(value: &[u64], divisor: u64) -> u64 {
+    let mut rem = 0u128;
+    for limb in value.iter().rev() {
+        rem = ((rem << 64) | (*limb as u128)) % (divisor as u128);
+    }
+
+    rem as u64
+}
+
+/// Miller-Rabin test with the fixed `BASES`, so the result is the same for every call
+pub(crate) fn is_probable_prime(number: &MaxGroupSizeUint, ctx: &ExecutionContext) -> bool {
+    let num_limbs = number.as_ref().iter().rposition(|limb| *limb != 0).map(|i| i + 1).unwrap_or(0);
+    let limbs = &number.as_ref()[..num_limbs];
+    if num_limbs == 0 || (num_limbs == 1 && limbs[0] < 2) {
+        return false;
+    }
+
+    // bases are also used for trial division, that filters most of the composite numbers
+    for base in BASES.iter() {
+        if num_limbs == 1 && limbs[0] == *base {
+            return true;
+        }
+        if rem_u64(limbs, *base) == 0 {
+            return false;
+        }
+    }
+
+    // number - 1 = d * 2^s with odd d
+    let minus_one = *number - MaxGroupSizeUint::one();
+    let s = minus_one.trailing_zeros();
+    let d = minus_one >> s;
+
+    let montgomery = Montgomery::new(limbs);
+    let one = montgomery.to_montgomery(1);
+    let minus_one = montgomery.negate(&one);
+
+    'bases: for base in BASES.iter() {
+        // one operation per Montgomery squaring, the result is garbage once the budget is exhausted
+        if !ctx.charge(num_limbs * 64) {
+            return false;
+        }
+        let mut x = montgomery.pow(&montgomery.to_montgomery(*base), &d, &one);
+        if x == one || x == minus_one {
+            continue;
+        }
+        for _ in 1..s {
+            x = montgomery.mul(&x, &x);
+            if x == minus_one {
+                continue 'bases;
+            }
+        }
+
+        return false;
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn from_hex(value: &str) -> MaxGroupSizeUint {
+        MaxGroupSizeUint::from_big_endian(&hex::decode(value).unwrap())
+    }
+
+    #[test]
+    fn test_small_numbers_against_trial_division() {
+        for n in 0u64..5000 {
+            let is_prime = n >= 2 && (2..n).take_while(|d| d * d <= n).all(|d| n % d != 0);
+            assert_eq!(is_probable_prime(&MaxGroupSizeUint::from(n), &ExecutionContext::default()), is_prime, "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn test_pseudoprimes() {
+        // Carmichael numbers
+        for n in [561u64, 41041, 825265, 321197185].iter() {
+            assert!(!is_probable_prime(&MaxGroupSizeUint::from(*n), &ExecutionContext::default()));
+        }
+        // strong pseudoprime to all the prime bases up to 23
+        assert!(!is_probable_prime(&MaxGroupSizeUint::from(3825123056546413051u64), &ExecutionContext::default()));
+    }
+
+    #[test]
+    fn test_group_orders() {
+        // BN254 and BLS12-381 main subgroup orders
+        let bn254 = from_hex("30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001");
+        let bls12_381 = from_hex("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001");
+        assert!(is_probable_prime(&bn254, &ExecutionContext::default()));
+        assert!(is_probable_prime(&bls12_381, &ExecutionContext::default()));
+        assert!(!is_probable_prime(&(bn254 * bls12_381), &ExecutionContext::default()));
+        assert!(!is_probable_prime(&(bls12_381 * MaxGroupSizeUint::from(3u64)), &ExecutionContext::default()));
+
+        // 1024 bit MODP group prime from RFC 2409, the top bit of the top limb is set
+        let modp = from_hex("ffffffffffffffffc90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74020bbea63b139b22514a08798e3404ddef9519b3cd3a431b302b0a6df25f14374fe1356d6d51c245e485b576625e7ec6f44c42e9a637ed6b0bff5cb6f406b7edee386bfb5a899fa5ae9f24117c4b1fe649286651ece65381ffffffffffffffff");
+        assert!(is_probable_prime(&modp, &ExecutionContext::default()));
+        // composite without factors below 37
+        assert!(!is_probable_prime(&(modp - MaxGroupSizeUint::from(10u64)), &ExecutionContext::default()));
+    }
+}
@@ -1,45 +1,120 @@
+// Arms up to 16 limbs are only left out when `limbs-8` or `limbs-12` is the widest enabled feature,
+// without any `limbs-*` feature the width is 16 limbs, see `constants::NUM_LIMBS_MAX`
+// The optional last argument, the `ExecutionContext` of the call, is passed after `$argument`
 #[macro_export]
 macro_rules! expand_for_modulus_limbs {
-    ($modulus_limbs: expr, $implementation: tt, $argument: expr, $func: tt) => {
+    ($modulus_limbs: expr, $implementation: tt, $argument: expr, $func: tt $(, $context: expr)?) => {
         match $modulus_limbs {
             4 => {
-                $implementation::<U256Repr>::$func(&$argument)
+                $implementation::<U256Repr>::$func(&$argument $(, $context)?)
             },
             5 => {
-                $implementation::<U320Repr>::$func(&$argument)
+                $implementation::<U320Repr>::$func(&$argument $(, $context)?)
             },
             6 => {
-                $implementation::<U384Repr>::$func(&$argument)
+                $implementation::<U384Repr>::$func(&$argument $(, $context)?)
             },
             7 => {
-                $implementation::<U448Repr>::$func(&$argument)
+                $implementation::<U448Repr>::$func(&$argument $(, $context)?)
             },
             8 => {
-                $implementation::<U512Repr>::$func(&$argument)
+                $implementation::<U512Repr>::$func(&$argument $(, $context)?)
             },
+            #[cfg(not(all(feature = "limbs-8", not(any(feature = "limbs-12", feature = "limbs-16", feature = "limbs-24", feature = "limbs-32")))))]
             9 => {
-                $implementation::<U576Repr>::$func(&$argument)
+                $implementation::<U576Repr>::$func(&$argument $(, $context)?)
             },
+            #[cfg(not(all(feature = "limbs-8", not(any(feature = "limbs-12", feature = "limbs-16", feature = "limbs-24", feature = "limbs-32")))))]
             10 => {
-                $implementation::<U640Repr>::$func(&$argument)
+                $implementation::<U640Repr>::$func(&$argument $(, $context)?)
             },
+            #[cfg(not(all(feature = "limbs-8", not(any(feature = "limbs-12", feature = "limbs-16", feature = "limbs-24", feature = "limbs-32")))))]
             11 => {
-                $implementation::<U704Repr>::$func(&$argument)
+                $implementation::<U704Repr>::$func(&$argument $(, $context)?)
             },
+            #[cfg(not(all(feature = "limbs-8", not(any(feature = "limbs-12", feature = "limbs-16", feature = "limbs-24", feature = "limbs-32")))))]
             12 => {
-                $implementation::<U768Repr>::$func($argument)
+                $implementation::<U768Repr>::$func($argument $(, $context)?)
             },
+            #[cfg(not(all(any(feature = "limbs-8", feature = "limbs-12"), not(any(feature = "limbs-16", feature = "limbs-24", feature = "limbs-32")))))]
             13 => {
-                $implementation::<U832Repr>::$func(&$argument)
+                $implementation::<U832Repr>::$func(&$argument $(, $context)?)
             },
+            #[cfg(not(all(any(feature = "limbs-8", feature = "limbs-12"), not(any(feature = "limbs-16", feature = "limbs-24", feature = "limbs-32")))))]
             14 => {
-                $implementation::<U896Repr>::$func(&$argument)
+                $implementation::<U896Repr>::$func(&$argument $(, $context)?)
             },
+            #[cfg(not(all(any(feature = "limbs-8", feature = "limbs-12"), not(any(feature = "limbs-16", feature = "limbs-24", feature = "limbs-32")))))]
             15 => {
-                $implementation::<U960Repr>::$func(&$argument)
+                $implementation::<U960Repr>::$func(&$argument $(, $context)?)
             },
+            #[cfg(not(all(any(feature = "limbs-8", feature = "limbs-12"), not(any(feature = "limbs-16", feature = "limbs-24", feature = "limbs-32")))))]
             16 => {
-                $implementation::<U1024Repr>::$func($argument)
+                $implementation::<U1024Repr>::$func($argument $(, $context)?)
+            },
+            #[cfg(any(feature = "limbs-24", feature = "limbs-32"))]
+            17 => {
+                $implementation::<U1088Repr>::$func(&$argument $(, $context)?)
+            },
+            #[cfg(any(feature = "limbs-24", feature = "limbs-32"))]
+            18 => {
+                $implementation::<U1152Repr>::$func(&$argument $(, $context)?)
+            },
+            #[cfg(any(feature = "limbs-24", feature = "limbs-32"))]
+            19 => {
+                $implementation::<U1216Repr>::$func(&$argument $(, $context)?)
+            },
+            #[cfg(any(feature = "limbs-24", feature = "limbs-32"))]
+            20 => {
+                $implementation::<U1280Repr>::$func(&$argument $(, $context)?)
+            },
+            #[cfg(any(feature = "limbs-24", feature = "limbs-32"))]
+            21 => {
+                $implementation::<U1344Repr>::$func(&$argument $(, $context)?)
+            },
+            #[cfg(any(feature = "limbs-24", feature = "limbs-32"))]
+            22 => {
+                $implementation::<U1408Repr>::$func(&$argument $(, $context)?)
+            },
+            #[cfg(any(feature = "limbs-24", feature = "limbs-32"))]
+            23 => {
+                $implementation::<U1472Repr>::$func(&$argument $(, $context)?)
+            },
+            #[cfg(any(feature = "limbs-24", feature = "limbs-32"))]
+            24 => {
+                $implementation::<U1536Repr>::$func(&$argument $(, $context)?)
+            },
+            #[cfg(feature = "limbs-32")]
+            25 => {
+                $implementation::<U1600Repr>::$func(&$argument $(, $context)?)
+            },
+            #[cfg(feature = "limbs-32")]
+            26 => {
+                $implementation::<U1664Repr>::$func(&$argument $(, $context)?)
+            },
+            #[cfg(feature = "limbs-32")]
+            27 => {
+                $implementation::<U1728Repr>::$func(&$argument $(, $context)?)
+            },
+            #[cfg(feature = "limbs-32")]
+            28 => {
+                $implementation::<U1792Repr>::$func(&$argument $(, $context)?)
+            },
+            #[cfg(feature = "limbs-32")]
+            29 => {
+                $implementation::<U1856Repr>::$func(&$argument $(, $context)?)
+            },
+            #[cfg(feature = "limbs-32")]
+            30 => {
+                $implementation::<U1920Repr>::$func(&$argument $(, $context)?)
+            },
+            #[cfg(feature = "limbs-32")]
+            31 => {
+                $implementation::<U1984Repr>::$func(&$argument $(, $context)?)
+            },
+            #[cfg(feature = "limbs-32")]
+            32 => {
+                $implementation::<U2048Repr>::$func(&$argument $(, $context)?)
             },
 
             field_limbs => {
@@ -0,0 +1,184 @@
+//! This api consists of
+//! - Addition
+//! - Multiplication
+//! - Multiexponentiations
+//!
+//! for curves y^2 + x*y = x^3 + a*x^2 + b over binary fields GF(2^m).
+//! Every call has common parameters in the same layout as for G1 operations:
+//! - Lengths of the reduction polynomial (in bytes)
+//! - Reduction polynomial
+//! - Curve A
+//! - Curve B
+//! - Length of a scalar field (curve order) (in bytes)
+//! - Curve order
+//!
+//! Assumptions:
+//! - one byte for length encoding
+
+use crate::weierstrass::Group;
+use crate::binary_field::CurveOverBinaryFieldParameters;
+use crate::binary_field::curve::{BinaryCurve, binary_curve_multiexp};
+use crate::representation::ElementRepr;
+use crate::traits::ZeroizeSecret;
+use crate::field::*;
+use super::constants::*;
+
+use super::decode_g1::*;
+use super::decode_binary::*;
+use super::decode_utils::*;
+
+use crate::errors::{ApiError, ErrorCode};
+use crate::execution_context::ExecutionContext;
+use crate::alloc_prelude::*;
+
+pub trait BinaryCurveApi {
+    fn add_points(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError>;
+    fn mul_point(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError>;
+    fn multiexp(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError>;
+}
+
+pub struct BinaryCurveApiImplementation<FE: ElementRepr> {
+    _marker_fe: core::marker::PhantomData<FE>,
+}
+
+impl<FE: ElementRepr> BinaryCurveApi for BinaryCurveApiImplementation<FE> {
+    fn add_points(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (field, field_len, rest) = parse_binary_field_from_encoding::<FE>(bytes)?;
+        let (a, rest) = decode_binary_field_element(rest, field_len, &field)?;
+        let (b, rest) = decode_binary_field_element(rest, field_len, &field)?;
+        let (_order_len, order, rest) = parse_group_order_from_encoding(rest, ctx)?;
+
+        let params = CurveOverBinaryFieldParameters::new(&field);
+
+        let curve = BinaryCurve::new(order.as_ref(), a, b, &params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        let (mut p_0, rest) = decode_binary_point_from_xy(rest, field_len, &curve)?;
+        let (p_1, rest) = decode_binary_point_from_xy(rest, field_len, &curve)?;
+
+        if !rest.is_empty() {
+            return Err(ApiError::garbage_at_the_end(rest));
+        }
+
+        if !p_0.is_on_curve() && !crate::features::in_fuzzing_or_gas_metering() {
+            return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point 0 is not on curve, file {}, line {}", file!(), line!())));
+        }
+        if !p_1.is_on_curve() && !crate::features::in_fuzzing_or_gas_metering() {
+            return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point 1 is not on curve, file {}, line {}", file!(), line!())));
+        }
+
+        p_0.add_assign(&p_1);
+
+        serialize_binary_point(field_len, &p_0)
+    }
+
+    fn mul_point(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (field, field_len, rest) = parse_binary_field_from_encoding::<FE>(bytes)?;
+        let (a, rest) = decode_binary_field_element(rest, field_len, &field)?;
+        let (b, rest) = decode_binary_field_element(rest, field_len, &field)?;
+        let (order_len, order, rest) = parse_group_order_from_encoding(rest, ctx)?;
+
+        let params = CurveOverBinaryFieldParameters::new(&field);
+
+        let curve = BinaryCurve::new(order.as_ref(), a, b, &params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        let (p_0, rest) = decode_binary_point_from_xy(rest, field_len, &curve)?;
+        let (mut scalar, rest) = decode_scalar_representation(rest, order_len)?;
+
+        if !rest.is_empty() {
+            return Err(ApiError::garbage_at_the_end(rest));
+        }
+
+        if !p_0.is_on_curve() && !crate::features::in_fuzzing_or_gas_metering() {
+            return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point is not on curve, file {}, line {}", file!(), line!())));
+        }
+
+        let p = p_0.mul(scalar);
+        scalar.as_mut().zeroize_secret();
+
+        serialize_binary_point(field_len, &p)
+    }
+
+    fn multiexp(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (field, field_len, rest) = parse_binary_field_from_encoding::<FE>(bytes)?;
+        let (a, rest) = decode_binary_field_element(rest, field_len, &field)?;
+        let (b, rest) = decode_binary_field_element(rest, field_len, &field)?;
+        let (order_len, order, rest) = parse_group_order_from_encoding(rest, ctx)?;
+
+        let params = CurveOverBinaryFieldParameters::new(&field);
+
+        let curve = BinaryCurve::new(order.as_ref(), a, b, &params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        let (num_pairs_encoding, rest) = split(rest, BYTES_FOR_LENGTH_ENCODING, "Input is not long enough to get number of pairs")?;
+        let num_pairs = num_pairs_encoding[0] as usize;
+        ctx.report().record_num_pairs(num_pairs);
+
+        if num_pairs == 0 {
+            return Err(ApiError::InputError("Invalid number of pairs".to_owned()));
+        }
+
+        let expected_pair_len = checked_len_add(checked_len_mul(2, field_len)?, order_len)?;
+        if rest.len() != checked_len_mul(expected_pair_len, num_pairs)? {
+            return Err(ApiError::InputError("Input length is invalid for number of pairs".to_owned()));
+        }
+
+        let mut global_rest = rest;
+        let mut bases = Vec::with_capacity(num_pairs);
+        let mut scalars = Vec::with_capacity(num_pairs);
+
+        for _ in 0..num_pairs {
+            let (p, local_rest) = decode_binary_point_from_xy(global_rest, field_len, &curve)?;
+            let (scalar, local_rest) = decode_scalar_representation(local_rest, order_len)?;
+            if !p.is_on_curve() && !crate::features::in_fuzzing_or_gas_metering() {
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point is not on curve, file {}, line {}", file!(), line!())));
+            }
+            bases.push(p);
+            scalars.push(scalar);
+            global_rest = local_rest;
+        }
+
+        if !global_rest.is_empty() {
+            return Err(ApiError::garbage_at_the_end(global_rest));
+        }
+
+        let result = binary_curve_multiexp(&bases, scalars, ctx);
+
+        serialize_binary_point(field_len, &result)
+    }
+}
+
+pub struct PublicBinaryCurveApi;
+
+impl BinaryCurveApi for PublicBinaryCurveApi {
+    fn add_points(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (_, polynomial, _) = parse_modulus_and_length(bytes)?;
+        let field_limbs = num_limbs_for_modulus(&polynomial)?;
+
+        let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(field_limbs, BinaryCurveApiImplementation, bytes, add_points, ctx);
+
+        result
+    }
+
+    fn mul_point(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (_, polynomial, _) = parse_modulus_and_length(bytes)?;
+        let field_limbs = num_limbs_for_modulus(&polynomial)?;
+
+        let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(field_limbs, BinaryCurveApiImplementation, bytes, mul_point, ctx);
+
+        result
+    }
+
+    fn multiexp(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (_, polynomial, _) = parse_modulus_and_length(bytes)?;
+        let field_limbs = num_limbs_for_modulus(&polynomial)?;
+
+        let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(field_limbs, BinaryCurveApiImplementation, bytes, multiexp, ctx);
+
+        result
+    }
+}
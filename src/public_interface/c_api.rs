@@ -1,4 +1,5 @@
 use super::unified_api::{OperationType, PREALLOCATE_FOR_ERROR_BYTES, PREALLOCATE_FOR_RESULT_BYTES, perform_operation};
+use crate::errors::catch_internal_error;
 
 // this is C interface
 #[no_mangle]
@@ -19,18 +20,19 @@ pub extern "C" fn c_perform_operation(
 
     let operation = OperationType::from_u8(op_u8);
 
-    if operation.is_none() {
-        let written = err_out.write(b"Unknown operation type\0");
-        if let Ok(bytes_written) = written {
-            unsafe { *char_len = bytes_written as u32 };
-        } else {
-            unsafe { *char_len = 0u32 };
-        }
-
-        return 1u32;
-    }
+    let operation = match operation {
+        Some(operation) => operation,
+        None => {
+            let written = err_out.write(b"Unknown operation type\0");
+            if let Ok(bytes_written) = written {
+                unsafe { *char_len = bytes_written as u32 };
+            } else {
+                unsafe { *char_len = 0u32 };
+            }
 
-    let operation = operation.expect("is some");
+            return 1u32;
+        }
+    };
     
     let input_i8: & [i8] = unsafe { std::slice::from_raw_parts(i, i_len as usize) };
     let input: &[u8] = unsafe { std::mem::transmute(input_i8) };
@@ -38,7 +40,7 @@ pub extern "C" fn c_perform_operation(
     let raw_out_i8: &mut [i8] = unsafe { std::slice::from_raw_parts_mut(o, PREALLOCATE_FOR_RESULT_BYTES) };
     let mut raw_out: &mut [u8] = unsafe { std::mem::transmute(raw_out_i8) };
 
-    let result = perform_operation(operation, input);
+    let result = catch_internal_error(|| perform_operation(operation, input));
 
     match result {
         Ok(result) => {
@@ -91,23 +93,24 @@ pub extern "C" fn c_meter_operation(
 
     let operation = OperationType::from_u8(op_u8);
 
-    if operation.is_none() {
-        let written = err_out.write(b"Unknown operation type\0");
-        if let Ok(bytes_written) = written {
-            unsafe { *char_len = bytes_written as u32 };
-        } else {
-            unsafe { *char_len = 0u32 };
-        }
-
-        return 1u32;
-    }
+    let operation = match operation {
+        Some(operation) => operation,
+        None => {
+            let written = err_out.write(b"Unknown operation type\0");
+            if let Ok(bytes_written) = written {
+                unsafe { *char_len = bytes_written as u32 };
+            } else {
+                unsafe { *char_len = 0u32 };
+            }
 
-    let operation = operation.expect("is some");
+            return 1u32;
+        }
+    };
     
     let input_i8: & [i8] = unsafe { std::slice::from_raw_parts(i, i_len as usize) };
     let input: &[u8] = unsafe { std::mem::transmute(input_i8) };
 
-    let result = meter_operation(operation, input);
+    let result = catch_internal_error(|| meter_operation(operation, input));
 
     match result {
         Ok(result) => {
@@ -5,23 +5,59 @@ pub const BLS12: u8 = 0x01;
 pub const BN: u8 = 0x02;
 pub const MNT4: u8 = 0x03;
 pub const MNT6: u8 = 0x04;
+pub const BLS24: u8 = 0x05;
+pub const BLS48: u8 = 0x06;
+pub const BW6: u8 = 0x07;
+pub const KSS16: u8 = 0x08;
+pub const KSS18: u8 = 0x09;
+// generic ate pairing with explicit embedding degree, loop count and final exponent
+pub const COCKS_PINCH: u8 = 0x0a;
 
 pub const TWIST_TYPE_LENGTH: usize = 1;
 pub const TWIST_TYPE_M: u8 = 0x01;
 pub const TWIST_TYPE_D: u8 = 0x02;
+// BLS12 and BN pairings only, the twist type is the one the G2 points are on
+pub const TWIST_TYPE_DETECT: u8 = 0x00;
 
 pub const SIGN_ENCODING_LENGTH: usize = 1;
 pub const SIGN_PLUS: u8 = 0x00;
 pub const SIGN_MINUS: u8 = 0x01;
 
+// compressed points are encoded as X followed by a flag
+pub const COMPRESSION_FLAG_LENGTH: usize = 1;
+pub const COMPRESSED_Y_IS_EVEN: u8 = 0x00;
+pub const COMPRESSED_Y_IS_ODD: u8 = 0x01;
+pub const COMPRESSED_POINT_AT_INFINITY: u8 = 0x02;
+
 pub const BOOLEAN_ENCODING_LENGTH: usize = 1;
 pub const BOOLEAN_FALSE: u8 = 0x00;
 pub const BOOLEAN_TRUE: u8 = 0x01;
 
+pub const ISOGENY_MODE_ENCODING_LENGTH: usize = 1;
+pub const ISOGENY_NONE: u8 = 0x00;
+pub const ISOGENY_EXPLICIT: u8 = 0x01;
+pub const ISOGENY_BUILT_IN: u8 = 0x02;
+
+// hash function of expand_message_xmd for OPERATION_HASH_TO_FIELD
+pub const HASH_FUNCTION_ENCODING_LENGTH: usize = 1;
+pub const HASH_FUNCTION_SHA256: u8 = 0x01;
+pub const HASH_FUNCTION_SHA512: u8 = 0x02;
+
 pub const EXTENSION_DEGREE_ENCODING_LENGTH: usize = 1;
+pub const EXTENSION_DEGREE_1: u8 = 0x01;
 pub const EXTENSION_DEGREE_2: u8 = 0x02;
 pub const EXTENSION_DEGREE_3: u8 = 0x03;
 
+// optional prefix of the input followed by the ABI version and then the operation type. Inputs
+// without it use the first version, prefix is not a valid operation type so both can't be confused
+pub const ABI_VERSION_PREFIX: u8 = 0xff;
+pub const ABI_VERSION_ENCODING_LENGTH: usize = 1;
+pub const ABI_VERSION_1: u8 = 0x01;
+// G2 operations also carry the twist description after the group order
+pub const ABI_VERSION_2: u8 = 0x02;
+// points are compressed, see `COMPRESSION_FLAG_LENGTH`
+pub const ABI_VERSION_3: u8 = 0x03;
+
 pub const OPERATION_ENCODING_LENGTH: usize = 1;
 
 pub const OPERATION_G1_ADD: u8 = 0x01;
@@ -34,22 +70,132 @@ pub const OPERATION_G2_MULTIEXP: u8 = 0x06;
 
 pub const OPERATION_PAIRING: u8 = 0x07;
 
+pub const OPERATION_MAP_TO_G1: u8 = 0x08;
+
+pub const OPERATION_G1_SUBGROUP_CHECK: u8 = 0x09;
+pub const OPERATION_G2_SUBGROUP_CHECK: u8 = 0x0a;
+
+pub const OPERATION_G1_CLEAR_COFACTOR: u8 = 0x0b;
+pub const OPERATION_G2_CLEAR_COFACTOR: u8 = 0x0c;
+
+pub const OPERATION_G1_VALIDATE_POINT: u8 = 0x0d;
+pub const OPERATION_G2_VALIDATE_POINT: u8 = 0x0e;
+
+// same input as OPERATION_PAIRING, returns the pairing value in GT
+pub const OPERATION_PAIRING_GT: u8 = 0x0f;
+
+// same input as OPERATION_PAIRING, returns the product of Miller loops without the final exponentiation
+pub const OPERATION_MILLER_LOOP: u8 = 0x10;
+// same curve parameters as OPERATION_PAIRING followed by a number of Miller loop values
+// and the values themselves, returns the final exponentiation of their product in GT
+pub const OPERATION_FINAL_EXP: u8 = 0x11;
+
+// operations on twisted Edwards curves a*x^2 + y^2 = 1 + d*x^2*y^2, with the same
+// common prefix as G1 operations where `b` is replaced by `d`
+pub const OPERATION_EDWARDS_ADD: u8 = 0x12;
+pub const OPERATION_EDWARDS_MUL: u8 = 0x13;
+pub const OPERATION_EDWARDS_MULTIEXP: u8 = 0x14;
+
+// x-coordinate only scalar multiplication on Montgomery curves B*y^2 = x^3 + A*x^2 + x,
+// with the same common prefix as G1 operations
+pub const OPERATION_MONTGOMERY_LADDER: u8 = 0x15;
+
+// operations on curves y^2 + x*y = x^3 + a*x^2 + b over binary fields GF(2^m), with the same
+// common prefix as G1 operations where the modulus is replaced by the reduction polynomial
+pub const OPERATION_BINARY_ADD: u8 = 0x16;
+pub const OPERATION_BINARY_MUL: u8 = 0x17;
+pub const OPERATION_BINARY_MULTIEXP: u8 = 0x18;
+
+// raw arithmetic in a prime field given by the modulus, operands follow the modulus
+pub const OPERATION_FIELD_ADD: u8 = 0x19;
+pub const OPERATION_FIELD_MUL: u8 = 0x1a;
+pub const OPERATION_FIELD_INVERSE: u8 = 0x1b;
+pub const OPERATION_FIELD_EXP: u8 = 0x1c;
+// modulus is followed by the Fp2 non-residue (as for G2 operations) and an element of Fp2
+pub const OPERATION_FIELD_EXT2_SQRT: u8 = 0x1d;
+
+// expand_message_xmd of a message into uniform bytes reduced into elements of Fp or Fp2
+pub const OPERATION_HASH_TO_FIELD: u8 = 0x1e;
+// same as OPERATION_MAP_TO_G1 with the Shallue-van de Woestijne map, that needs no isogeny
+pub const OPERATION_MAP_TO_G1_SVDW: u8 = 0x1f;
+
+// curve parameters as for the addition followed by the number of points and the points,
+// returns the sum of all of them
+pub const OPERATION_G1_SUM: u8 = 0x20;
+pub const OPERATION_G2_SUM: u8 = 0x21;
+
+// same input as OPERATION_PAIRING where every pair is preceded by a sign (SIGN_PLUS or SIGN_MINUS),
+// returns whether the product of the pairings, inverted for the pairs with SIGN_MINUS, is one
+pub const OPERATION_PAIRING_EQUATION: u8 = 0x22;
+// same curve parameters as OPERATION_PAIRING followed by the group of the public key, generator
+// of this group, public key, message point and signature, returns whether the BLS signature is valid
+pub const OPERATION_BLS_VERIFY: u8 = 0x23;
+// same curve parameters as OPERATION_PAIRING followed by the generators of G1 and G2, tau times the generator
+// of G2, commitment, evaluation point and value as scalars, and proof, returns whether the KZG opening is valid
+pub const OPERATION_KZG_OPENING: u8 = 0x24;
+
+// optional last byte of the pairing calldata, after the list of pairs.
+// If it's PAIRING_MODE_MILLER_LOOP_ONLY the final exponentiation is skipped
+// and the unreduced value is returned as for OPERATION_MILLER_LOOP
+pub const PAIRING_MODE_ENCODING_LENGTH: usize = 1;
+pub const PAIRING_MODE_FULL: u8 = 0x00;
+pub const PAIRING_MODE_MILLER_LOOP_ONLY: u8 = 0x01;
+
+// group of the public key of OPERATION_BLS_VERIFY, message and signature are in the other group
+pub const BLS_PUBLIC_KEY_GROUP_ENCODING_LENGTH: usize = 1;
+pub const BLS_PUBLIC_KEY_IN_G1: u8 = 0x01;
+pub const BLS_PUBLIC_KEY_IN_G2: u8 = 0x02;
+
+// result of the point validation operations
+pub const POINT_VALIDATION_RESULT_LENGTH: usize = 1;
+pub const POINT_IS_VALID: u8 = 0x00;
+pub const POINT_IS_AT_INFINITY: u8 = 0x01;
+pub const POINT_IS_NOT_ON_CURVE: u8 = 0x02;
+pub const POINT_COORDINATE_NOT_IN_FIELD: u8 = 0x03;
+
 pub const NUM_LIMBS_MIN: usize = 4;
+// Maximum number of modulus limbs is the largest of the enabled `limbs-*` features, arithmetic
+// is not instantiated for the wider representations. Without any of them it's 16 limbs
+#[cfg(feature = "limbs-32")]
+pub const NUM_LIMBS_MAX: usize = 32;
+#[cfg(all(feature = "limbs-24", not(feature = "limbs-32")))]
+pub const NUM_LIMBS_MAX: usize = 24;
+#[cfg(all(
+    any(feature = "limbs-16", not(any(feature = "limbs-8", feature = "limbs-12"))),
+    not(any(feature = "limbs-24", feature = "limbs-32"))
+))]
 pub const NUM_LIMBS_MAX: usize = 16;
+#[cfg(all(feature = "limbs-12", not(any(feature = "limbs-16", feature = "limbs-24", feature = "limbs-32"))))]
+pub const NUM_LIMBS_MAX: usize = 12;
+#[cfg(all(feature = "limbs-8", not(any(feature = "limbs-12", feature = "limbs-16", feature = "limbs-24", feature = "limbs-32"))))]
+pub const NUM_LIMBS_MAX: usize = 8;
 pub const NUM_GROUP_LIMBS_MIN: usize = 1;
 pub const NUM_GROUP_LIMBS_MAX: usize = 16;
 
-pub const MAX_MODULUS_BYTE_LEN: usize = 128;
+pub const MAX_MODULUS_BYTE_LEN: usize = if NUM_LIMBS_MAX * 8 > MAX_WIDE_MODULUS_BYTE_LEN {
+    MAX_WIDE_MODULUS_BYTE_LEN
+} else {
+    NUM_LIMBS_MAX * 8
+};
+// top limb always keeps one spare bit, see `field::calculate_num_limbs`
+pub const MAX_MODULUS_BIT_LEN: usize = if NUM_LIMBS_MAX * 64 - 1 > MAX_MODULUS_BYTE_LEN * 8 {
+    MAX_MODULUS_BYTE_LEN * 8
+} else {
+    NUM_LIMBS_MAX * 64 - 1
+};
 pub const MAX_GROUP_BYTE_LEN: usize = 128;
 
 use static_assertions::const_assert;
 use crate::integers::*;
+use super::sane_limits::MAX_WIDE_MODULUS_BYTE_LEN;
 
-const_assert!(MAX_MODULUS_BYTE_LEN == NUM_LIMBS_MAX * 8);
+const_assert!(MAX_MODULUS_BYTE_LEN <= NUM_LIMBS_MAX * 8);
+const_assert!(MAX_MODULUS_BIT_LEN <= MAX_MODULUS_BYTE_LEN * 8);
+const_assert!(MAX_MODULUS_BIT_LEN < NUM_LIMBS_MAX * 64);
 
 const_assert!(MAX_GROUP_BYTE_LEN == NUM_GROUP_LIMBS_MAX * 8);
 
-const_assert!(std::mem::size_of::<MaxFieldUint>() >= NUM_LIMBS_MAX * 8);
-const_assert!(std::mem::size_of::<MaxFieldSquaredUint>() >= NUM_LIMBS_MAX * 8 * 2);
+const_assert!(core::mem::size_of::<MaxFieldUint>() >= NUM_LIMBS_MAX * 8);
+const_assert!(core::mem::size_of::<MaxFieldSquaredUint>() >= NUM_LIMBS_MAX * 8 * 2);
 
-const_assert!(std::mem::size_of::<MaxGroupSizeUint>() >= NUM_GROUP_LIMBS_MAX * 8);
+const_assert!(core::mem::size_of::<MaxGroupSizeUint>() >= NUM_GROUP_LIMBS_MAX * 8);
@@ -0,0 +1,484 @@
+//! Fast paths for the curves that make up almost all of the real pairing traffic.
+//!
+//! If the curve parameters of a pairing call are exactly the canonical encoding of BLS12-381
+//! or BN254 (minimal lengths, same non-residues, twist type and loop parameter as in `crate::engines`),
+//! the call uses the precomputed fields, Frobenius coefficients and engines from `crate::engines`
+//! instead of building them from the input. Points are decoded and checked by the same code
+//! as in the generic path, so results and errors do not depend on which path is taken. Only the
+//! optional subgroup checks differ: they use endomorphisms that are known to give exactly the
+//! subgroup for these two curves, see `crate::pairings::subgroup`.
+//! Any other encoding, even of the same curve, goes through the generic path.
+
+use crate::field::SizedPrimeField;
+use crate::fp::Fp;
+use crate::extension_towers::fp2::{Fp2, Extension2};
+use crate::extension_towers::fp6_as_3_over_2::Extension3Over2;
+use crate::extension_towers::fp12_as_2_over3_over_2::Fp12;
+use crate::pairings::{PairingEngine, TwistType};
+use crate::representation::ElementRepr;
+use crate::traits::ZeroAndOne;
+use crate::weierstrass::CurveParameters;
+use crate::weierstrass::curve::{CurvePoint, WeierstrassCurve};
+
+use super::constants::*;
+use super::decode_fp::{serialize_fp_fixed_len, serialize_fp2_fixed_len, serialize_fp12_fixed_len, decode_fp12};
+use super::pairing_ops::{decode_pairs_with_fp2_twist, PairingOutput, PairingPipeline, PairingStages, ParsedPairing, SubgroupChecks};
+
+use crate::errors::ApiError;
+use crate::execution_context::ExecutionContext;
+use crate::alloc_prelude::*;
+
+fn minimal_be_bytes(limbs: &[u64]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(limbs.len() * 8);
+    for limb in limbs.iter().rev() {
+        result.extend_from_slice(&limb.to_be_bytes());
+    }
+    let leading_zeros = result.iter().take_while(|b| **b == 0).count();
+
+    result.split_off(leading_zeros)
+}
+
+/// Encoding of everything between the curve type and the number of pairs
+/// in a BLS12 or BN pairing call, see `PairingApiImplementation::pair_bls12`
+fn encode_pairing_curve_parameters<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>
+    >
+    (
+        base_field: &'a F,
+        curve: &'a WeierstrassCurve<'a, CB>,
+        fp2_extension: &'a Extension2<'a, FE, F>,
+        fp6_extension: &'a Extension3Over2<'a, FE, F>,
+        twist_type: TwistType,
+        loop_parameter: &[u64],
+        loop_parameter_is_negative: bool,
+    ) -> Result<Vec<u8>, ApiError>
+{
+    let modulus = minimal_be_bytes(base_field.modulus().as_ref());
+    let modulus_len = modulus.len();
+    let order = minimal_be_bytes(curve.subgroup_order_repr);
+    let loop_parameter = minimal_be_bytes(loop_parameter);
+
+    let mut result = vec![];
+    result.push(modulus_len as u8);
+    result.extend(modulus);
+    result.extend(serialize_fp_fixed_len(modulus_len, &curve.a)?);
+    result.extend(serialize_fp_fixed_len(modulus_len, &curve.b)?);
+    result.push(order.len() as u8);
+    result.extend(order);
+    result.extend(serialize_fp_fixed_len(modulus_len, &fp2_extension.non_residue)?);
+    result.extend(serialize_fp2_fixed_len(modulus_len, &fp6_extension.non_residue)?);
+    result.push(match twist_type {
+        TwistType::M => TWIST_TYPE_M,
+        TwistType::D => TWIST_TYPE_D,
+    });
+    result.push(loop_parameter.len() as u8);
+    result.extend(loop_parameter);
+    result.push(if loop_parameter_is_negative { SIGN_MINUS } else { SIGN_PLUS });
+
+    Ok(result)
+}
+
+fn pair_with_engine<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+    CTW: CurveParameters<BaseFieldElement = Fp2<'a, FE, F>>,
+    E: PairingEngine<G1 = CurvePoint<'a, CB>, G2 = CurvePoint<'a, CTW>, PairingResult = Fp12<'a, FE, F>>,
+    G1: Fn(&CurvePoint<'a, CB>) -> bool,
+    G2: Fn(&CurvePoint<'a, CTW>) -> bool
+    >
+    (
+        engine: &E,
+        one: Fp12<'a, FE, F>,
+        modulus_len: usize,
+        g1_curve: &'a WeierstrassCurve<'a, CB>,
+        g2_curve: &'a WeierstrassCurve<'a, CTW>,
+        pairs: &[u8],
+        output: PairingOutput,
+        ctx: &ExecutionContext,
+        in_g1: G1,
+        in_g2: G2
+    ) -> Result<Vec<u8>, ApiError>
+{
+    let serialize = |el: &Fp12<'a, FE, F>| serialize_fp12_fixed_len(modulus_len, el);
+    let extension_12 = one.extension_field;
+
+    // parameters of the fast path are encoded with minimal lengths
+    let order_len = minimal_be_bytes(g1_curve.subgroup_order_repr).len();
+
+    // the parameters are known, so the parse stage is skipped
+    PairingPipeline::new(output, ctx).run(ParsedPairing {
+        engine,
+        one,
+        rest: pairs,
+        prepare: |rest, output| decode_pairs_with_fp2_twist(rest, modulus_len, order_len, g1_curve, g2_curve, SubgroupChecks::new(in_g1, in_g2), output, ctx.point_encoding()),
+        decode_gt: |rest| decode_fp12(rest, modulus_len, extension_12),
+        serialize
+    })
+}
+
+#[cfg(feature = "once_cell")]
+fn bls12_381_parameters() -> Option<&'static [u8]> {
+    use crate::engines::bls12_381::*;
+    use once_cell::sync::Lazy;
+
+    static PARAMETERS: Lazy<Option<Vec<u8>>> = Lazy::new(|| {
+        let engine = BLS12_381_PAIRING_ENGINE;
+        encode_pairing_curve_parameters(
+            engine.base_field,
+            engine.curve,
+            engine.fp2_extension,
+            engine.fp6_extension,
+            engine.twist_type,
+            engine.x,
+            engine.x_is_negative
+        ).ok()
+    });
+
+    PARAMETERS.as_deref()
+}
+
+// the encoding is only built once and there is nowhere to keep it without once_cell,
+// so such builds always take the generic path
+#[cfg(not(feature = "once_cell"))]
+fn bls12_381_parameters() -> Option<&'static [u8]> {
+    None
+}
+
+fn try_pair_bls12_381(bytes: &[u8], output: PairingOutput, ctx: &ExecutionContext) -> Option<Result<Vec<u8>, ApiError>> {
+    use crate::engines::bls12_381::*;
+
+    let engine = BLS12_381_PAIRING_ENGINE;
+    let parameters = bls12_381_parameters()?;
+    if !bytes.starts_with(parameters) {
+        return None;
+    }
+    let modulus_len = parameters[0] as usize;
+
+    Some(pair_with_engine(
+        &engine,
+        Fp12::one(engine.fp12_extension),
+        modulus_len,
+        engine.curve,
+        engine.curve_twist,
+        &bytes[parameters.len()..],
+        output,
+        ctx,
+        |p| engine.g1_subgroup_check(p),
+        |q| engine.g2_subgroup_check_by_psi(q)
+    ))
+}
+
+#[cfg(feature = "eip_196")]
+fn bn254_parameters() -> Option<&'static [u8]> {
+    use crate::engines::bn254::*;
+    use once_cell::sync::Lazy;
+
+    static PARAMETERS: Lazy<Option<Vec<u8>>> = Lazy::new(|| {
+        let engine = &*BN254_PAIRING_ENGINE;
+        encode_pairing_curve_parameters(
+            engine.base_field,
+            engine.curve,
+            engine.fp2_extension,
+            engine.fp6_extension,
+            engine.twist_type,
+            engine.u,
+            engine.u_is_negative
+        ).ok()
+    });
+
+    PARAMETERS.as_deref()
+}
+
+#[cfg(not(feature = "eip_196"))]
+fn bn254_parameters() -> Option<&'static [u8]> {
+    None
+}
+
+#[cfg(feature = "eip_196")]
+fn try_pair_bn254(bytes: &[u8], output: PairingOutput, ctx: &ExecutionContext) -> Option<Result<Vec<u8>, ApiError>> {
+    use crate::engines::bn254::*;
+
+    let engine = &*BN254_PAIRING_ENGINE;
+    let parameters = bn254_parameters()?;
+    if !bytes.starts_with(parameters) {
+        return None;
+    }
+    let modulus_len = parameters[0] as usize;
+
+    Some(pair_with_engine(
+        engine,
+        Fp12::one(engine.fp12_extension),
+        modulus_len,
+        engine.curve,
+        engine.curve_twist,
+        &bytes[parameters.len()..],
+        output,
+        ctx,
+        // the cofactor of G1 is one, so every point on the curve is in the subgroup
+        // and points are checked to be on the curve while decoding
+        |_| true,
+        |q| engine.g2_subgroup_check_by_psi(q)
+    ))
+}
+
+#[cfg(not(feature = "eip_196"))]
+fn try_pair_bn254(_bytes: &[u8], _output: PairingOutput, _ctx: &ExecutionContext) -> Option<Result<Vec<u8>, ApiError>> {
+    None
+}
+
+/// Returns `None` if the input is not for one of the well known curves and should be
+/// handled by the generic implementation. `bytes` start with the curve type
+pub(crate) fn try_pair_well_known_curve(bytes: &[u8], output: PairingOutput, ctx: &ExecutionContext) -> Option<Result<Vec<u8>, ApiError>> {
+    let (curve_type, rest) = bytes.split_first()?;
+    match *curve_type {
+        BLS12 => try_pair_bls12_381(rest, output, ctx),
+        BN => try_pair_bn254(rest, output, ctx),
+        _ => None
+    }
+}
+
+/// Inputs for the well known curves don't decode the curve parameters and so don't test
+/// the group order for primality. `bytes` start with the curve type
+#[cfg(feature = "gas_metering")]
+pub(crate) fn is_well_known_curve(bytes: &[u8]) -> bool {
+    let (curve_type, rest) = match bytes.split_first() {
+        Some(split) => split,
+        None => return false
+    };
+    let parameters = match *curve_type {
+        BLS12 => bls12_381_parameters(),
+        BN => bn254_parameters(),
+        _ => None
+    };
+
+    parameters.map(|parameters| rest.starts_with(parameters)).unwrap_or(false)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::field::{U256Repr, U384Repr};
+    use crate::traits::FieldElement;
+    use crate::weierstrass::Group;
+    use crate::public_interface::decode_g1::serialize_g1_point;
+    use crate::public_interface::decode_g2::serialize_g2_point_in_fp2;
+    use crate::public_interface::pairing_ops::{PairingApi, PairingApiImplementation};
+
+    fn encode_pairs<
+        'a,
+        FE: ElementRepr,
+        F: SizedPrimeField<Repr = FE>,
+        CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+        CTW: CurveParameters<BaseFieldElement = Fp2<'a, FE, F>>
+    >(modulus_len: usize, pairs: &[(CurvePoint<'a, CB>, CurvePoint<'a, CTW>)]) -> Vec<u8> {
+        let mut encoding = vec![pairs.len() as u8];
+        for (g1, g2) in pairs.iter() {
+            encoding.push(BOOLEAN_TRUE);
+            encoding.extend(serialize_g1_point(modulus_len, g1).unwrap());
+            encoding.push(BOOLEAN_TRUE);
+            encoding.extend(serialize_g2_point_in_fp2(modulus_len, g2).unwrap());
+        }
+
+        encoding
+    }
+
+    fn assert_same_as_generic<FE: ElementRepr>(input: &[u8]) -> Result<Vec<u8>, ApiError> {
+        let fast = try_pair_well_known_curve(input, PairingOutput::IsOne, &ExecutionContext::default()).expect("must take the fast path");
+        let generic = PairingApiImplementation::<FE>::pair(input, &ExecutionContext::default());
+        assert_eq!(format!("{:?}", fast), format!("{:?}", generic));
+
+        let fast_value = try_pair_well_known_curve(input, PairingOutput::Value, &ExecutionContext::default()).expect("must take the fast path");
+        let generic_value = PairingApiImplementation::<FE>::pair_to_gt(input, &ExecutionContext::default());
+        assert_eq!(format!("{:?}", fast_value), format!("{:?}", generic_value));
+
+        let fast_miller_loop = try_pair_well_known_curve(input, PairingOutput::MillerLoop, &ExecutionContext::default()).expect("must take the fast path");
+        let generic_miller_loop = PairingApiImplementation::<FE>::miller_loop(input, &ExecutionContext::default());
+        assert_eq!(format!("{:?}", fast_miller_loop), format!("{:?}", generic_miller_loop));
+
+        fast
+    }
+
+    #[test]
+    fn test_bls12_381_fast_path() {
+        use crate::engines::bls12_381::*;
+
+        let engine = BLS12_381_PAIRING_ENGINE;
+        let mut input = vec![BLS12];
+        input.extend(encode_pairing_curve_parameters(engine.base_field, engine.curve, engine.fp2_extension, engine.fp6_extension, engine.twist_type, engine.x, engine.x_is_negative).unwrap());
+
+        let g1 = BLS12_381_G1_GENERATOR;
+        let g2 = BLS12_381_G2_GENERATOR;
+        let mut minus_g1 = g1.clone();
+        minus_g1.negate();
+
+        let mut valid = input.clone();
+        valid.extend(encode_pairs(48, &[(g1.clone(), g2.clone()), (minus_g1, g2.clone())]));
+        assert_eq!(assert_same_as_generic::<U384Repr>(&valid).unwrap(), vec![1u8]);
+
+        let mut not_one = input.clone();
+        not_one.extend(encode_pairs(48, &[(g1.clone(), g2.clone())]));
+        assert_eq!(assert_same_as_generic::<U384Repr>(&not_one).unwrap(), vec![0u8]);
+
+        // a single trailing byte is the pairing mode
+        let mut garbage = not_one.clone();
+        garbage.extend_from_slice(&[0u8, 0u8]);
+        assert!(assert_same_as_generic::<U384Repr>(&garbage).is_err());
+
+        let mut off_curve = g1.clone();
+        off_curve.y.double();
+        let mut not_on_curve = input.clone();
+        not_on_curve.extend(encode_pairs(48, &[(off_curve, g2.clone())]));
+        assert!(assert_same_as_generic::<U384Repr>(&not_on_curve).is_err());
+
+        // same parameters with positive x are not BLS12-381 and go through the generic path
+        let mut other_curve = not_one.clone();
+        let sign_position = input.len() - 1;
+        other_curve[sign_position] = SIGN_PLUS;
+        assert!(try_pair_well_known_curve(&other_curve, PairingOutput::IsOne, &ExecutionContext::default()).is_none());
+    }
+
+    /// Stops after the prepare stage and returns the number of the pairs
+    struct PrepareOnly;
+
+    impl PairingStages for PrepareOnly {
+        fn output(&self) -> PairingOutput {
+            PairingOutput::IsOne
+        }
+
+        fn run<'e, 'b, E, P, D, S>(self, parsed: ParsedPairing<'e, 'b, E, P, D, S>) -> Result<Vec<u8>, ApiError>
+            where
+                E: PairingEngine,
+                P: FnOnce(&'b [u8], PairingOutput) -> Result<(Vec<E::G1>, Vec<E::G2>, PairingOutput), ApiError>,
+                D: Fn(&'b [u8]) -> Result<(E::PairingResult, &'b [u8]), ApiError>,
+                S: Fn(&E::PairingResult) -> Result<Vec<u8>, ApiError>
+        {
+            let (g1_points, _, _) = (parsed.prepare)(parsed.rest, self.output())?;
+
+            Ok(vec![g1_points.len() as u8])
+        }
+    }
+
+    #[test]
+    fn test_parse_stage_of_family() {
+        use crate::engines::bls12_381::*;
+        use crate::public_interface::pairing_ops::{Bls12Family, PairingFamily};
+
+        let engine = BLS12_381_PAIRING_ENGINE;
+        let mut input = encode_pairing_curve_parameters(engine.base_field, engine.curve, engine.fp2_extension, engine.fp6_extension, engine.twist_type, engine.x, engine.x_is_negative).unwrap();
+        let pair = (BLS12_381_G1_GENERATOR, BLS12_381_G2_GENERATOR);
+        input.extend(encode_pairs(48, &[pair.clone(), pair.clone(), pair]));
+
+        let ctx = ExecutionContext::default();
+        assert_eq!(Bls12Family::parse::<U384Repr, _>(&input, &ctx, PrepareOnly).unwrap(), vec![3u8]);
+    }
+
+    #[test]
+    fn test_bls12_381_pairing_value() {
+        use crate::engines::bls12_381::*;
+
+        let engine = BLS12_381_PAIRING_ENGINE;
+        let mut input = vec![BLS12];
+        input.extend(encode_pairing_curve_parameters(engine.base_field, engine.curve, engine.fp2_extension, engine.fp6_extension, engine.twist_type, engine.x, engine.x_is_negative).unwrap());
+
+        let g1 = BLS12_381_G1_GENERATOR;
+        let g2 = BLS12_381_G2_GENERATOR;
+        let mut two_g1 = g1.clone();
+        two_g1.double();
+
+        let mut single = input.clone();
+        single.extend(encode_pairs(48, &[(g1.clone(), g2.clone())]));
+        let value = try_pair_well_known_curve(&single, PairingOutput::Value, &ExecutionContext::default()).unwrap().unwrap();
+        let expected = engine.pair(&[g1.clone()], &[g2.clone()]).unwrap();
+        assert_eq!(value, serialize_fp12_fixed_len(48, &expected).unwrap());
+        assert_eq!(value.len(), 12 * 48);
+
+        // e(2P, Q) = e(P, Q)^2
+        let mut double = input.clone();
+        double.extend(encode_pairs(48, &[(two_g1, g2.clone())]));
+        let mut expected_square = expected;
+        expected_square.square();
+        assert_eq!(assert_same_as_generic::<U384Repr>(&double).unwrap(), vec![0u8]);
+        assert_eq!(
+            try_pair_well_known_curve(&double, PairingOutput::Value, &ExecutionContext::default()).unwrap().unwrap(), 
+            serialize_fp12_fixed_len(48, &expected_square).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_bls12_381_final_exp_of_accumulated_miller_loops() {
+        use crate::engines::bls12_381::*;
+
+        let engine = BLS12_381_PAIRING_ENGINE;
+        let mut input = vec![BLS12];
+        input.extend(encode_pairing_curve_parameters(engine.base_field, engine.curve, engine.fp2_extension, engine.fp6_extension, engine.twist_type, engine.x, engine.x_is_negative).unwrap());
+
+        let g1 = BLS12_381_G1_GENERATOR;
+        let g2 = BLS12_381_G2_GENERATOR;
+        let mut two_g1 = g1.clone();
+        two_g1.double();
+
+        let mut first = input.clone();
+        first.extend(encode_pairs(48, &[(g1.clone(), g2.clone())]));
+        let mut second = input.clone();
+        second.extend(encode_pairs(48, &[(two_g1.clone(), g2.clone())]));
+        let mut both = input.clone();
+        both.extend(encode_pairs(48, &[(g1.clone(), g2.clone()), (two_g1, g2.clone())]));
+
+        let mut final_exp_input = input.clone();
+        final_exp_input.push(2u8);
+        final_exp_input.extend(try_pair_well_known_curve(&first, PairingOutput::MillerLoop, &ExecutionContext::default()).unwrap().unwrap());
+        final_exp_input.extend(try_pair_well_known_curve(&second, PairingOutput::MillerLoop, &ExecutionContext::default()).unwrap().unwrap());
+
+        let fast = try_pair_well_known_curve(&final_exp_input, PairingOutput::FinalExponentiation, &ExecutionContext::default()).unwrap().unwrap();
+        let generic = PairingApiImplementation::<U384Repr>::final_exp(&final_exp_input, &ExecutionContext::default()).unwrap();
+        assert_eq!(fast, generic);
+        assert_eq!(fast, PairingApiImplementation::<U384Repr>::pair_to_gt(&both, &ExecutionContext::default()).unwrap());
+
+        final_exp_input.push(0u8);
+        assert!(try_pair_well_known_curve(&final_exp_input, PairingOutput::FinalExponentiation, &ExecutionContext::default()).unwrap().is_err());
+
+        let mut miller_loop_only = first.clone();
+        miller_loop_only.push(PAIRING_MODE_MILLER_LOOP_ONLY);
+        assert_eq!(
+            assert_same_as_generic::<U384Repr>(&miller_loop_only).unwrap(),
+            try_pair_well_known_curve(&first, PairingOutput::MillerLoop, &ExecutionContext::default()).unwrap().unwrap()
+        );
+    }
+
+    #[cfg(feature = "eip_196")]
+    #[test]
+    fn test_bn254_fast_path() {
+        use crate::engines::bn254::*;
+
+        let engine = &*BN254_PAIRING_ENGINE;
+        let mut input = vec![BN];
+        input.extend(encode_pairing_curve_parameters(engine.base_field, engine.curve, engine.fp2_extension, engine.fp6_extension, engine.twist_type, engine.u, engine.u_is_negative).unwrap());
+
+        let g1 = BN254_G1_GENERATOR.clone();
+        let g2 = BN254_G2_GENERATOR.clone();
+        let mut minus_g2 = g2.clone();
+        minus_g2.negate();
+
+        let mut valid = input.clone();
+        valid.extend(encode_pairs(32, &[(g1.clone(), g2.clone()), (g1.clone(), minus_g2)]));
+        assert_eq!(assert_same_as_generic::<U256Repr>(&valid).unwrap(), vec![1u8]);
+
+        let mut not_one = input.clone();
+        not_one.extend(encode_pairs(32, &[(g1.clone(), g2.clone())]));
+        assert_eq!(assert_same_as_generic::<U256Repr>(&not_one).unwrap(), vec![0u8]);
+
+        let mut no_pairs = input.clone();
+        no_pairs.push(0u8);
+        assert!(assert_same_as_generic::<U256Repr>(&no_pairs).is_err());
+
+        // API entry point takes the fast path as well
+        let mut api_input = vec![OPERATION_PAIRING];
+        api_input.extend(valid);
+        assert_eq!(crate::public_interface::API::run(&api_input).unwrap(), vec![1u8]);
+    }
+}
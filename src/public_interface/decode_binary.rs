@@ -0,0 +1,118 @@
+use crate::binary_field::{BinaryField, BinaryFieldElement};
+use crate::binary_field::curve::{BinaryCurve, BinaryCurvePoint};
+use crate::representation::ElementRepr;
+use crate::weierstrass::CurveParameters;
+
+use super::decode_utils::*;
+
+use crate::errors::ApiError;
+use crate::alloc_prelude::*;
+
+/// Reduction polynomial f(z) is encoded in place of the modulus as a big endian integer,
+/// bit i being the coefficient of z^i. Field elements use the same byte length
+pub fn parse_binary_field_from_encoding<
+    'a,
+    FE: ElementRepr,
+    >(encoding: &'a [u8]) -> Result<(BinaryField<FE>, usize, &'a [u8]), ApiError>
+{
+    let ((polynomial, polynomial_len), rest) = get_base_field_params(&encoding)?;
+    let mut repr = FE::default();
+    for (dst, src) in repr.as_mut().iter_mut().zip(polynomial.as_ref().iter()) {
+        *dst = *src;
+    }
+    let field = BinaryField::new(repr).map_err(|_| {
+        ApiError::InputError(format!("Reduction polynomial is not irreducible, file {}, line {}", file!(), line!()))
+    })?;
+    if rest.len() < polynomial_len {
+        return Err(ApiError::InputError("Input is not long enough".to_owned()));
+    }
+
+    Ok((field, polynomial_len, rest))
+}
+
+pub fn decode_binary_field_element<
+    'a,
+    'b,
+    FE: ElementRepr,
+    >
+(
+    bytes: &'b [u8],
+    field_byte_len: usize,
+    field: &'a BinaryField<FE>
+) -> Result<(BinaryFieldElement<'a, FE>, &'b [u8]), ApiError>
+{
+    let (encoding, rest) = split(bytes, field_byte_len, "Input is not long enough to get binary field element")?;
+    let el = BinaryFieldElement::from_be_bytes(field, encoding).map_err(|_| {
+        ApiError::InputError(format!("Binary field element has degree above the field degree, file {}, line {}", file!(), line!()))
+    })?;
+
+    Ok((el, rest))
+}
+
+pub fn serialize_binary_field_element_fixed_len<
+    'a,
+    FE: ElementRepr,
+    >
+(
+    encoding_byte_len: usize,
+    element: &BinaryFieldElement<'a, FE>
+) -> Result<Vec<u8>, ApiError>
+{
+    let repr = element.into_repr();
+    let expected_byte_len = repr.as_ref().len() * 8;
+    let mut bytes: Vec<u8> = vec![0u8; expected_byte_len];
+    repr.write_be_to_slice(&mut bytes).map_err(|_| {
+        ApiError::OutputError("Failed to serialize binary field element".to_owned())
+    })?;
+    if encoding_byte_len >= expected_byte_len {
+        let mut padded = vec![0u8; encoding_byte_len - expected_byte_len];
+        padded.extend(bytes);
+
+        return Ok(padded);
+    }
+    let trim = expected_byte_len - encoding_byte_len;
+    bytes.drain(0..trim);
+
+    debug_assert_eq!(bytes.len(), encoding_byte_len);
+
+    Ok(bytes)
+}
+
+pub fn serialize_binary_point<
+    'a,
+    FE: ElementRepr,
+    C: CurveParameters<BaseFieldElement = BinaryFieldElement<'a, FE>>
+    >
+    (
+        encoding_length: usize,
+        point: &BinaryCurvePoint<'a, C>
+    ) -> Result<Vec<u8>, ApiError>
+{
+    let (x, y) = point.into_xy();
+    let mut result = Vec::with_capacity(2*encoding_length);
+    result.extend(serialize_binary_field_element_fixed_len(encoding_length, &x)?);
+    result.extend(serialize_binary_field_element_fixed_len(encoding_length, &y)?);
+
+    Ok(result)
+}
+
+/// Point at infinity is encoded as (0, 0)
+pub fn decode_binary_point_from_xy<
+    'a,
+    'b,
+    FE: ElementRepr,
+    C: CurveParameters<BaseFieldElement = BinaryFieldElement<'b, FE>>
+    >
+    (
+        bytes: &'a [u8],
+        field_byte_len: usize,
+        curve: &'b BinaryCurve<'b, C>
+    ) -> Result<(BinaryCurvePoint<'b, C>, &'a [u8]), ApiError>
+{
+    let (x, rest) = decode_binary_field_element(&bytes, field_byte_len, curve.params.params())?;
+    let (y, rest) = decode_binary_field_element(&rest, field_byte_len, curve.params.params())?;
+
+    let p: BinaryCurvePoint<'b, C> = BinaryCurvePoint::point_from_xy(&curve, x, y);
+
+    Ok((p, rest))
+}
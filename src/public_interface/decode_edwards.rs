@@ -0,0 +1,53 @@
+use crate::edwards::curve::{TwistedEdwardsCurve, EdwardsPoint};
+use crate::field::{SizedPrimeField};
+use crate::fp::Fp;
+use crate::representation::ElementRepr;
+use crate::weierstrass::CurveParameters;
+
+use super::decode_fp::*;
+
+use crate::errors::ApiError;
+use crate::alloc_prelude::*;
+
+pub fn serialize_edwards_point<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    C: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>
+    >
+    (
+        encoding_length: usize,
+        point: &EdwardsPoint<'a, C>
+    ) -> Result<Vec<u8>, ApiError>
+{
+    let (x, y) = point.into_xy().ok_or_else(|| {
+        ApiError::InputError(format!("Exceptional case of the addition formulas, file {}, line {}", file!(), line!()))
+    })?;
+    let mut result = Vec::with_capacity(2*encoding_length);
+    append_fp_fixed_len(encoding_length, &x, &mut result)?;
+    append_fp_fixed_len(encoding_length, &y, &mut result)?;
+
+    Ok(result)
+}
+
+/// Neutral element is encoded as (0, 1)
+pub fn decode_edwards_point_from_xy<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    C: CurveParameters<BaseFieldElement = Fp<'b, FE, F>>
+    >
+    (
+        bytes: &'a [u8], 
+        field_byte_len: usize,
+        curve: &'b TwistedEdwardsCurve<'b, C>
+    ) -> Result<(EdwardsPoint<'b, C>, &'a [u8]), ApiError>
+{
+    let (x, rest) = decode_fp(&bytes, field_byte_len, curve.params.params())?;
+    let (y, rest) = decode_fp(&rest, field_byte_len, curve.params.params())?;
+    
+    let p: EdwardsPoint<'b, C> = EdwardsPoint::point_from_xy(&curve, x, y);
+    
+    Ok((p, rest))
+}
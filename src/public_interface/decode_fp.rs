@@ -2,14 +2,29 @@ use crate::field::{SizedPrimeField};
 use crate::fp::Fp;
 use crate::extension_towers::fp2;
 use crate::extension_towers::fp3;
+use crate::extension_towers::fp4_as_2_over_2;
+use crate::extension_towers::fp6_as_2_over_3;
+use crate::extension_towers::fp6_as_3_over_2;
+use crate::extension_towers::fp12_as_2_over3_over_2;
+use crate::extension_towers::fp12_as_3_over_4;
+use crate::extension_towers::fp24_as_2_over_3_over_4;
+use crate::extension_towers::fp8_as_2_over_4;
+use crate::extension_towers::fp24_as_3_over_8;
+use crate::extension_towers::fp48_as_2_over_3_over_8;
+use crate::extension_towers::fp9_as_3_over_3;
+use crate::extension_towers::fp18_as_2_over_3_over_3;
+use crate::extension_towers::fp8_as_2_over_4_generic;
+use crate::extension_towers::fp16_as_2_over_8;
+use crate::extension_towers::fpk;
 use crate::representation::ElementRepr;
-use crate::traits::ZeroAndOne;
+use crate::traits::{FieldElement, ZeroAndOne};
 use crate::integers::MaxFieldUint;
 use crate::field::PrimeField;
 
-use crate::errors::ApiError;
+use crate::errors::{ApiError, ErrorCode};
 use super::decode_utils::*;
 use crate::field::field_from_modulus;
+use crate::alloc_prelude::*;
 
 pub fn parse_base_field_from_encoding<
     'a,
@@ -18,7 +33,7 @@ pub fn parse_base_field_from_encoding<
 {
     let ((modulus, modulus_len), rest) = get_base_field_params(&encoding)?;
     let field = field_from_modulus::<FE>(&modulus).map_err(|_| {
-        ApiError::InputError("Failed to create prime field from modulus".to_owned())
+        ApiError::with_code(ErrorCode::InvalidModulus, "Failed to create prime field from modulus".to_owned())
     })?;
     if rest.len() < modulus_len {
         return Err(ApiError::InputError("Input is not long enough".to_owned()));
@@ -27,6 +42,15 @@ pub fn parse_base_field_from_encoding<
     Ok((field, modulus_len, modulus, rest))
 }
 
+/// Checks that every `field_byte_len` long element of the encoding is below the modulus
+pub(crate) fn encoded_elements_are_in_field(
+    encoding: &[u8], 
+    field_byte_len: usize,
+    modulus: &MaxFieldUint
+) -> bool {
+    encoding.chunks(field_byte_len).all(|el| MaxFieldUint::from_big_endian(el) < *modulus)
+}
+
 pub fn decode_fp<
     'a,
     'b,
@@ -41,7 +65,7 @@ pub fn decode_fp<
 {
     let (x_encoding, rest) = split(bytes, field_byte_len, "Input is not long enough to get Fp element")?;
     let x = Fp::from_be_bytes(base_field, x_encoding, true).map_err(|e| {
-        ApiError::InputError(format!("Failed to parse Fp element, {}", e))
+        ApiError::with_code(ErrorCode::NotInField, format!("Failed to parse Fp element, {}", e)).at(bytes)
     })?;
 
     Ok((x, rest))
@@ -170,28 +194,34 @@ pub fn serialize_fp_fixed_len<
     element: &'a Fp<'a, FE, F>
 ) -> Result<Vec<u8>, ApiError>
 {
-    let mut bytes: Vec<u8> = vec![0u8; encoding_byte_len];
-    let element = element.into_repr();
-    let expected_byte_len = element.as_ref().len() * 8;
-    if encoding_byte_len >= expected_byte_len {
-        let start = encoding_byte_len - expected_byte_len;
-        element.write_be(&mut bytes[start..]).map_err(|_| {
-            ApiError::OutputError("Failed to serialize Fp element".to_owned())
-        })?;
-    } else {
-        bytes.truncate(0);
-        element.write_be(&mut bytes).map_err(|_| {
-            ApiError::OutputError("Failed to serialize Fp element".to_owned())
-        })?;
-        let trim = expected_byte_len - encoding_byte_len;
-        bytes.drain(0..trim);
-    }
-
-    debug_assert_eq!(bytes.len(), encoding_byte_len);
+    let mut bytes = Vec::with_capacity(encoding_byte_len);
+    append_fp_fixed_len(encoding_byte_len, element, &mut bytes)?;
 
     Ok(bytes)
 }
 
+/// Writes the element directly at the end of `bytes`, so elements of extensions and points
+/// don't need a temporary buffer each
+pub(crate) fn append_fp_fixed_len<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+(
+    encoding_byte_len: usize,
+    element: &'a Fp<'a, FE, F>,
+    bytes: &mut Vec<u8>
+) -> Result<(), ApiError>
+{
+    let start = bytes.len();
+    bytes.resize(start + encoding_byte_len, 0u8);
+    element.into_repr().write_be_to_slice_of_any_len(&mut bytes[start..]).map_err(|_| {
+        ApiError::OutputError("Failed to serialize Fp element".to_owned())
+    })?;
+
+    Ok(())
+}
+
 pub fn serialize_fp2_fixed_len<
     'a,
     FE: ElementRepr,
@@ -203,12 +233,28 @@ pub fn serialize_fp2_fixed_len<
     ) -> Result<Vec<u8>, ApiError>
 {
     let mut bytes = Vec::with_capacity(2*encoding_byte_len);
-    bytes.extend(serialize_fp_fixed_len(encoding_byte_len, &element.c0)?);
-    bytes.extend(serialize_fp_fixed_len(encoding_byte_len, &element.c1)?);
+    append_fp2_fixed_len(encoding_byte_len, element, &mut bytes)?;
 
     Ok(bytes)
 }
 
+pub(crate) fn append_fp2_fixed_len<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+    (
+        encoding_byte_len: usize,
+        element: &'a fp2::Fp2<'a, FE, F>,
+        bytes: &mut Vec<u8>
+    ) -> Result<(), ApiError>
+{
+    append_fp_fixed_len(encoding_byte_len, &element.c0, bytes)?;
+    append_fp_fixed_len(encoding_byte_len, &element.c1, bytes)?;
+
+    Ok(())
+}
+
 pub fn serialize_fp3_fixed_len<
     'a,
     FE: ElementRepr,
@@ -220,9 +266,947 @@ pub fn serialize_fp3_fixed_len<
     ) -> Result<Vec<u8>, ApiError>
 {
     let mut bytes = Vec::with_capacity(3*encoding_byte_len);
-    bytes.extend(serialize_fp_fixed_len(encoding_byte_len, &element.c0)?);
-    bytes.extend(serialize_fp_fixed_len(encoding_byte_len, &element.c1)?);
-    bytes.extend(serialize_fp_fixed_len(encoding_byte_len, &element.c2)?);
+    append_fp3_fixed_len(encoding_byte_len, element, &mut bytes)?;
 
     Ok(bytes)
-}
\ No newline at end of file
+}
+
+pub(crate) fn append_fp3_fixed_len<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+    (
+        encoding_byte_len: usize,
+        element: &'a fp3::Fp3<'a, FE, F>,
+        bytes: &mut Vec<u8>
+    ) -> Result<(), ApiError>
+{
+    append_fp_fixed_len(encoding_byte_len, &element.c0, bytes)?;
+    append_fp_fixed_len(encoding_byte_len, &element.c1, bytes)?;
+    append_fp_fixed_len(encoding_byte_len, &element.c2, bytes)?;
+
+    Ok(())
+}
+
+// Parity of the canonical representation, used as a sign of Y in compressed point encodings.
+// Extension elements take the parity of the first non-zero coefficient starting from c0
+pub(crate) fn fp_is_odd<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >(element: &Fp<'a, FE, F>) -> bool
+{
+    element.into_repr().is_odd()
+}
+
+pub(crate) fn fp2_is_odd<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >(element: &fp2::Fp2<'a, FE, F>) -> bool
+{
+    if !element.c0.is_zero() {
+        fp_is_odd(&element.c0)
+    } else {
+        fp_is_odd(&element.c1)
+    }
+}
+
+pub(crate) fn fp3_is_odd<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >(element: &fp3::Fp3<'a, FE, F>) -> bool
+{
+    if !element.c0.is_zero() {
+        fp_is_odd(&element.c0)
+    } else if !element.c1.is_zero() {
+        fp_is_odd(&element.c1)
+    } else {
+        fp_is_odd(&element.c2)
+    }
+}
+
+// Higher extension elements are encoded by recursively writing their coefficients
+// in order c0, c1(, c2), so every tower level uses the same layout as Fp2 and Fp3 above.
+// E.g. an Fp12 element as 2 over 3 over 2 is encoded as
+// c0.c0.c0 || c0.c0.c1 || c0.c1.c0 || ... || c1.c2.c1,
+// each base field element being big endian and exactly `field_byte_len` bytes long.
+// Every base field coefficient must be canonical (less than modulus), and decoding
+// errors report the full coefficient path (like "Fp12.c1.c2.c0") of the offending element.
+
+fn decode_fp_at_path<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+(
+    bytes: &'b [u8], 
+    field_byte_len: usize,
+    base_field: &'a F,
+    path: &str
+) -> Result<(Fp<'a, FE, F>, &'b [u8]), ApiError>
+{
+    if bytes.len() < field_byte_len {
+        return Err(ApiError::InputError(format!("Input is not long enough to get {} element", path)));
+    }
+    let (encoding, rest) = bytes.split_at(field_byte_len);
+    let x = Fp::from_be_bytes(base_field, encoding, true).map_err(|e| {
+        ApiError::InputError(format!("Failed to parse {} element, {}", path, e))
+    })?;
+
+    Ok((x, rest))
+}
+
+fn decode_fp2_at_path<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+(
+    bytes: &'b [u8], 
+    field_byte_len: usize,
+    extension_field: &'a fp2::Extension2<'a, FE, F>,
+    path: &str
+) -> Result<(fp2::Fp2<'a, FE, F>, &'b [u8]), ApiError>
+{
+    let (c0, rest) = decode_fp_at_path(bytes, field_byte_len, extension_field.field, &format!("{}.c0", path))?;
+    let (c1, rest) = decode_fp_at_path(rest, field_byte_len, extension_field.field, &format!("{}.c1", path))?;
+
+    let mut x = fp2::Fp2::zero(extension_field);
+    x.c0 = c0;
+    x.c1 = c1;
+
+    Ok((x, rest))
+}
+
+fn decode_fp3_at_path<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+(
+    bytes: &'b [u8], 
+    field_byte_len: usize,
+    extension_field: &'a fp3::Extension3<'a, FE, F>,
+    path: &str
+) -> Result<(fp3::Fp3<'a, FE, F>, &'b [u8]), ApiError>
+{
+    let (c0, rest) = decode_fp_at_path(bytes, field_byte_len, extension_field.field, &format!("{}.c0", path))?;
+    let (c1, rest) = decode_fp_at_path(rest, field_byte_len, extension_field.field, &format!("{}.c1", path))?;
+    let (c2, rest) = decode_fp_at_path(rest, field_byte_len, extension_field.field, &format!("{}.c2", path))?;
+
+    let mut x = fp3::Fp3::zero(extension_field);
+    x.c0 = c0;
+    x.c1 = c1;
+    x.c2 = c2;
+
+    Ok((x, rest))
+}
+
+fn decode_fp6_as_3_over_2_at_path<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+(
+    bytes: &'b [u8], 
+    field_byte_len: usize,
+    extension_field: &'a fp6_as_3_over_2::Extension3Over2<'a, FE, F>,
+    path: &str
+) -> Result<(fp6_as_3_over_2::Fp6<'a, FE, F>, &'b [u8]), ApiError>
+{
+    let (c0, rest) = decode_fp2_at_path(bytes, field_byte_len, extension_field.field, &format!("{}.c0", path))?;
+    let (c1, rest) = decode_fp2_at_path(rest, field_byte_len, extension_field.field, &format!("{}.c1", path))?;
+    let (c2, rest) = decode_fp2_at_path(rest, field_byte_len, extension_field.field, &format!("{}.c2", path))?;
+
+    let mut x = fp6_as_3_over_2::Fp6::zero(extension_field);
+    x.c0 = c0;
+    x.c1 = c1;
+    x.c2 = c2;
+
+    Ok((x, rest))
+}
+
+fn decode_fp4_at_path<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+(
+    bytes: &'b [u8], 
+    field_byte_len: usize,
+    extension_field: &'a fp4_as_2_over_2::Extension2Over2<'a, FE, F>,
+    path: &str
+) -> Result<(fp4_as_2_over_2::Fp4<'a, FE, F>, &'b [u8]), ApiError>
+{
+    let (c0, rest) = decode_fp2_at_path(bytes, field_byte_len, extension_field.field, &format!("{}.c0", path))?;
+    let (c1, rest) = decode_fp2_at_path(rest, field_byte_len, extension_field.field, &format!("{}.c1", path))?;
+
+    let mut x = fp4_as_2_over_2::Fp4::zero(extension_field);
+    x.c0 = c0;
+    x.c1 = c1;
+
+    Ok((x, rest))
+}
+
+fn decode_fp12_as_3_over_4_at_path<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+(
+    bytes: &'b [u8], 
+    field_byte_len: usize,
+    extension_field: &'a fp12_as_3_over_4::Extension3Over4<'a, FE, F>,
+    path: &str
+) -> Result<(fp12_as_3_over_4::Fp12<'a, FE, F>, &'b [u8]), ApiError>
+{
+    let (c0, rest) = decode_fp4_at_path(bytes, field_byte_len, extension_field.field, &format!("{}.c0", path))?;
+    let (c1, rest) = decode_fp4_at_path(rest, field_byte_len, extension_field.field, &format!("{}.c1", path))?;
+    let (c2, rest) = decode_fp4_at_path(rest, field_byte_len, extension_field.field, &format!("{}.c2", path))?;
+
+    let mut x = fp12_as_3_over_4::Fp12::zero(extension_field);
+    x.c0 = c0;
+    x.c1 = c1;
+    x.c2 = c2;
+
+    Ok((x, rest))
+}
+
+fn decode_fp8_at_path<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+(
+    bytes: &'b [u8], 
+    field_byte_len: usize,
+    extension_field: &'a fp8_as_2_over_4::Extension2Over4<'a, FE, F>,
+    path: &str
+) -> Result<(fp8_as_2_over_4::Fp8<'a, FE, F>, &'b [u8]), ApiError>
+{
+    let (c0, rest) = decode_fp4_at_path(bytes, field_byte_len, extension_field.field, &format!("{}.c0", path))?;
+    let (c1, rest) = decode_fp4_at_path(rest, field_byte_len, extension_field.field, &format!("{}.c1", path))?;
+
+    let mut x = fp8_as_2_over_4::Fp8::zero(extension_field);
+    x.c0 = c0;
+    x.c1 = c1;
+
+    Ok((x, rest))
+}
+
+fn decode_fp24_as_3_over_8_at_path<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+(
+    bytes: &'b [u8], 
+    field_byte_len: usize,
+    extension_field: &'a fp24_as_3_over_8::Extension3Over8<'a, FE, F>,
+    path: &str
+) -> Result<(fp24_as_3_over_8::Fp24<'a, FE, F>, &'b [u8]), ApiError>
+{
+    let (c0, rest) = decode_fp8_at_path(bytes, field_byte_len, extension_field.field, &format!("{}.c0", path))?;
+    let (c1, rest) = decode_fp8_at_path(rest, field_byte_len, extension_field.field, &format!("{}.c1", path))?;
+    let (c2, rest) = decode_fp8_at_path(rest, field_byte_len, extension_field.field, &format!("{}.c2", path))?;
+
+    let mut x = fp24_as_3_over_8::Fp24::zero(extension_field);
+    x.c0 = c0;
+    x.c1 = c1;
+    x.c2 = c2;
+
+    Ok((x, rest))
+}
+
+fn decode_fp9_at_path<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+(
+    bytes: &'b [u8], 
+    field_byte_len: usize,
+    extension_field: &'a fp9_as_3_over_3::Extension3Over3<'a, FE, F>,
+    path: &str
+) -> Result<(fp9_as_3_over_3::Fp9<'a, FE, F>, &'b [u8]), ApiError>
+{
+    let (c0, rest) = decode_fp3_at_path(bytes, field_byte_len, extension_field.field, &format!("{}.c0", path))?;
+    let (c1, rest) = decode_fp3_at_path(rest, field_byte_len, extension_field.field, &format!("{}.c1", path))?;
+    let (c2, rest) = decode_fp3_at_path(rest, field_byte_len, extension_field.field, &format!("{}.c2", path))?;
+
+    let mut x = fp9_as_3_over_3::Fp9::zero(extension_field);
+    x.c0 = c0;
+    x.c1 = c1;
+    x.c2 = c2;
+
+    Ok((x, rest))
+}
+
+fn decode_fp8_over_fp4_at_path<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+(
+    bytes: &'b [u8], 
+    field_byte_len: usize,
+    extension_field: &'a fp8_as_2_over_4_generic::Extension2Over4<'a, FE, F>,
+    path: &str
+) -> Result<(fp8_as_2_over_4_generic::Fp8<'a, FE, F>, &'b [u8]), ApiError>
+{
+    let (c0, rest) = decode_fp4_at_path(bytes, field_byte_len, extension_field.field, &format!("{}.c0", path))?;
+    let (c1, rest) = decode_fp4_at_path(rest, field_byte_len, extension_field.field, &format!("{}.c1", path))?;
+
+    let mut x = fp8_as_2_over_4_generic::Fp8::zero(extension_field);
+    x.c0 = c0;
+    x.c1 = c1;
+
+    Ok((x, rest))
+}
+
+pub fn decode_fp4<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+(
+    bytes: &'b [u8], 
+    field_byte_len: usize,
+    extension_field: &'a fp4_as_2_over_2::Extension2Over2<'a, FE, F>
+) -> Result<(fp4_as_2_over_2::Fp4<'a, FE, F>, &'b [u8]), ApiError>
+{
+    decode_fp4_at_path(bytes, field_byte_len, extension_field, "Fp4")
+}
+
+pub fn decode_fp8<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+(
+    bytes: &'b [u8], 
+    field_byte_len: usize,
+    extension_field: &'a fp8_as_2_over_4::Extension2Over4<'a, FE, F>
+) -> Result<(fp8_as_2_over_4::Fp8<'a, FE, F>, &'b [u8]), ApiError>
+{
+    decode_fp8_at_path(bytes, field_byte_len, extension_field, "Fp8")
+}
+
+pub fn decode_fp6_as_2_over_3<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+(
+    bytes: &'b [u8], 
+    field_byte_len: usize,
+    extension_field: &'a fp6_as_2_over_3::Extension2Over3<'a, FE, F>
+) -> Result<(fp6_as_2_over_3::Fp6<'a, FE, F>, &'b [u8]), ApiError>
+{
+    let (c0, rest) = decode_fp3_at_path(bytes, field_byte_len, extension_field.field, "Fp6.c0")?;
+    let (c1, rest) = decode_fp3_at_path(rest, field_byte_len, extension_field.field, "Fp6.c1")?;
+
+    let mut x = fp6_as_2_over_3::Fp6::zero(extension_field);
+    x.c0 = c0;
+    x.c1 = c1;
+
+    Ok((x, rest))
+}
+
+pub fn decode_fp6_as_3_over_2<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+(
+    bytes: &'b [u8], 
+    field_byte_len: usize,
+    extension_field: &'a fp6_as_3_over_2::Extension3Over2<'a, FE, F>
+) -> Result<(fp6_as_3_over_2::Fp6<'a, FE, F>, &'b [u8]), ApiError>
+{
+    decode_fp6_as_3_over_2_at_path(bytes, field_byte_len, extension_field, "Fp6")
+}
+
+pub fn decode_fp12<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+(
+    bytes: &'b [u8], 
+    field_byte_len: usize,
+    extension_field: &'a fp12_as_2_over3_over_2::Extension2Over3Over2<'a, FE, F>
+) -> Result<(fp12_as_2_over3_over_2::Fp12<'a, FE, F>, &'b [u8]), ApiError>
+{
+    let (c0, rest) = decode_fp6_as_3_over_2_at_path(bytes, field_byte_len, extension_field.field, "Fp12.c0")?;
+    let (c1, rest) = decode_fp6_as_3_over_2_at_path(rest, field_byte_len, extension_field.field, "Fp12.c1")?;
+
+    let mut x = fp12_as_2_over3_over_2::Fp12::zero(extension_field);
+    x.c0 = c0;
+    x.c1 = c1;
+
+    Ok((x, rest))
+}
+
+pub fn decode_fp24<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+(
+    bytes: &'b [u8], 
+    field_byte_len: usize,
+    extension_field: &'a fp24_as_2_over_3_over_4::Extension2Over3Over4<'a, FE, F>
+) -> Result<(fp24_as_2_over_3_over_4::Fp24<'a, FE, F>, &'b [u8]), ApiError>
+{
+    let (c0, rest) = decode_fp12_as_3_over_4_at_path(bytes, field_byte_len, extension_field.field, "Fp24.c0")?;
+    let (c1, rest) = decode_fp12_as_3_over_4_at_path(rest, field_byte_len, extension_field.field, "Fp24.c1")?;
+
+    let mut x = fp24_as_2_over_3_over_4::Fp24::zero(extension_field);
+    x.c0 = c0;
+    x.c1 = c1;
+
+    Ok((x, rest))
+}
+
+pub fn decode_fp48<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+(
+    bytes: &'b [u8], 
+    field_byte_len: usize,
+    extension_field: &'a fp48_as_2_over_3_over_8::Extension2Over3Over8<'a, FE, F>
+) -> Result<(fp48_as_2_over_3_over_8::Fp48<'a, FE, F>, &'b [u8]), ApiError>
+{
+    let (c0, rest) = decode_fp24_as_3_over_8_at_path(bytes, field_byte_len, extension_field.field, "Fp48.c0")?;
+    let (c1, rest) = decode_fp24_as_3_over_8_at_path(rest, field_byte_len, extension_field.field, "Fp48.c1")?;
+
+    let mut x = fp48_as_2_over_3_over_8::Fp48::zero(extension_field);
+    x.c0 = c0;
+    x.c1 = c1;
+
+    Ok((x, rest))
+}
+
+pub fn decode_fp18<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+(
+    bytes: &'b [u8], 
+    field_byte_len: usize,
+    extension_field: &'a fp18_as_2_over_3_over_3::Extension2Over3Over3<'a, FE, F>
+) -> Result<(fp18_as_2_over_3_over_3::Fp18<'a, FE, F>, &'b [u8]), ApiError>
+{
+    let (c0, rest) = decode_fp9_at_path(bytes, field_byte_len, extension_field.field, "Fp18.c0")?;
+    let (c1, rest) = decode_fp9_at_path(rest, field_byte_len, extension_field.field, "Fp18.c1")?;
+
+    let mut x = fp18_as_2_over_3_over_3::Fp18::zero(extension_field);
+    x.c0 = c0;
+    x.c1 = c1;
+
+    Ok((x, rest))
+}
+
+pub fn decode_fp16<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+(
+    bytes: &'b [u8], 
+    field_byte_len: usize,
+    extension_field: &'a fp16_as_2_over_8::Extension2Over8<'a, FE, F>
+) -> Result<(fp16_as_2_over_8::Fp16<'a, FE, F>, &'b [u8]), ApiError>
+{
+    let (c0, rest) = decode_fp8_over_fp4_at_path(bytes, field_byte_len, extension_field.field, "Fp16.c0")?;
+    let (c1, rest) = decode_fp8_over_fp4_at_path(rest, field_byte_len, extension_field.field, "Fp16.c1")?;
+
+    let mut x = fp16_as_2_over_8::Fp16::zero(extension_field);
+    x.c0 = c0;
+    x.c1 = c1;
+
+    Ok((x, rest))
+}
+
+/// Element of the binomial extension of runtime degree k is encoded as k base field
+/// elements, coefficients of z^0, ..., z^(k-1)
+pub fn decode_fpk<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+(
+    bytes: &'b [u8], 
+    field_byte_len: usize,
+    extension_field: &'a fpk::ExtensionK<'a, FE, F>
+) -> Result<(fpk::FpK<'a, FE, F>, &'b [u8]), ApiError>
+{
+    let mut x = fpk::FpK::zero(extension_field);
+    let mut rest = bytes;
+    for i in 0..extension_field.degree {
+        let (c, r) = decode_fp_at_path(rest, field_byte_len, extension_field.field, &format!("FpK.c{}", i))?;
+        x.coeffs[i] = c;
+        rest = r;
+    }
+
+    Ok((x, rest))
+}
+
+pub fn serialize_fp4_fixed_len<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+    (
+        encoding_byte_len: usize,
+        element: &'a fp4_as_2_over_2::Fp4<'a, FE, F>
+    ) -> Result<Vec<u8>, ApiError>
+{
+    let mut bytes = Vec::with_capacity(4*encoding_byte_len);
+    append_fp2_fixed_len(encoding_byte_len, &element.c0, &mut bytes)?;
+    append_fp2_fixed_len(encoding_byte_len, &element.c1, &mut bytes)?;
+
+    Ok(bytes)
+}
+
+pub fn serialize_fp6_as_2_over_3_fixed_len<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+    (
+        encoding_byte_len: usize,
+        element: &'a fp6_as_2_over_3::Fp6<'a, FE, F>
+    ) -> Result<Vec<u8>, ApiError>
+{
+    let mut bytes = Vec::with_capacity(6*encoding_byte_len);
+    append_fp3_fixed_len(encoding_byte_len, &element.c0, &mut bytes)?;
+    append_fp3_fixed_len(encoding_byte_len, &element.c1, &mut bytes)?;
+
+    Ok(bytes)
+}
+
+pub fn serialize_fp6_as_3_over_2_fixed_len<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+    (
+        encoding_byte_len: usize,
+        element: &'a fp6_as_3_over_2::Fp6<'a, FE, F>
+    ) -> Result<Vec<u8>, ApiError>
+{
+    let mut bytes = Vec::with_capacity(6*encoding_byte_len);
+    append_fp2_fixed_len(encoding_byte_len, &element.c0, &mut bytes)?;
+    append_fp2_fixed_len(encoding_byte_len, &element.c1, &mut bytes)?;
+    append_fp2_fixed_len(encoding_byte_len, &element.c2, &mut bytes)?;
+
+    Ok(bytes)
+}
+
+pub fn serialize_fp12_fixed_len<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+    (
+        encoding_byte_len: usize,
+        element: &'a fp12_as_2_over3_over_2::Fp12<'a, FE, F>
+    ) -> Result<Vec<u8>, ApiError>
+{
+    let mut bytes = Vec::with_capacity(12*encoding_byte_len);
+    bytes.extend(serialize_fp6_as_3_over_2_fixed_len(encoding_byte_len, &element.c0)?);
+    bytes.extend(serialize_fp6_as_3_over_2_fixed_len(encoding_byte_len, &element.c1)?);
+
+    Ok(bytes)
+}
+
+pub fn serialize_fp12_as_3_over_4_fixed_len<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+    (
+        encoding_byte_len: usize,
+        element: &'a fp12_as_3_over_4::Fp12<'a, FE, F>
+    ) -> Result<Vec<u8>, ApiError>
+{
+    let mut bytes = Vec::with_capacity(12*encoding_byte_len);
+    bytes.extend(serialize_fp4_fixed_len(encoding_byte_len, &element.c0)?);
+    bytes.extend(serialize_fp4_fixed_len(encoding_byte_len, &element.c1)?);
+    bytes.extend(serialize_fp4_fixed_len(encoding_byte_len, &element.c2)?);
+
+    Ok(bytes)
+}
+
+pub fn serialize_fp24_fixed_len<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+    (
+        encoding_byte_len: usize,
+        element: &'a fp24_as_2_over_3_over_4::Fp24<'a, FE, F>
+    ) -> Result<Vec<u8>, ApiError>
+{
+    let mut bytes = Vec::with_capacity(24*encoding_byte_len);
+    bytes.extend(serialize_fp12_as_3_over_4_fixed_len(encoding_byte_len, &element.c0)?);
+    bytes.extend(serialize_fp12_as_3_over_4_fixed_len(encoding_byte_len, &element.c1)?);
+
+    Ok(bytes)
+}
+
+pub fn serialize_fp8_fixed_len<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+    (
+        encoding_byte_len: usize,
+        element: &'a fp8_as_2_over_4::Fp8<'a, FE, F>
+    ) -> Result<Vec<u8>, ApiError>
+{
+    let mut bytes = Vec::with_capacity(8*encoding_byte_len);
+    bytes.extend(serialize_fp4_fixed_len(encoding_byte_len, &element.c0)?);
+    bytes.extend(serialize_fp4_fixed_len(encoding_byte_len, &element.c1)?);
+
+    Ok(bytes)
+}
+
+pub fn serialize_fp24_as_3_over_8_fixed_len<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+    (
+        encoding_byte_len: usize,
+        element: &'a fp24_as_3_over_8::Fp24<'a, FE, F>
+    ) -> Result<Vec<u8>, ApiError>
+{
+    let mut bytes = Vec::with_capacity(24*encoding_byte_len);
+    bytes.extend(serialize_fp8_fixed_len(encoding_byte_len, &element.c0)?);
+    bytes.extend(serialize_fp8_fixed_len(encoding_byte_len, &element.c1)?);
+    bytes.extend(serialize_fp8_fixed_len(encoding_byte_len, &element.c2)?);
+
+    Ok(bytes)
+}
+
+pub fn serialize_fp48_fixed_len<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+    (
+        encoding_byte_len: usize,
+        element: &'a fp48_as_2_over_3_over_8::Fp48<'a, FE, F>
+    ) -> Result<Vec<u8>, ApiError>
+{
+    let mut bytes = Vec::with_capacity(48*encoding_byte_len);
+    bytes.extend(serialize_fp24_as_3_over_8_fixed_len(encoding_byte_len, &element.c0)?);
+    bytes.extend(serialize_fp24_as_3_over_8_fixed_len(encoding_byte_len, &element.c1)?);
+
+    Ok(bytes)
+}
+
+pub fn serialize_fp9_fixed_len<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+    (
+        encoding_byte_len: usize,
+        element: &'a fp9_as_3_over_3::Fp9<'a, FE, F>
+    ) -> Result<Vec<u8>, ApiError>
+{
+    let mut bytes = Vec::with_capacity(9*encoding_byte_len);
+    append_fp3_fixed_len(encoding_byte_len, &element.c0, &mut bytes)?;
+    append_fp3_fixed_len(encoding_byte_len, &element.c1, &mut bytes)?;
+    append_fp3_fixed_len(encoding_byte_len, &element.c2, &mut bytes)?;
+
+    Ok(bytes)
+}
+
+pub fn serialize_fp18_fixed_len<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+    (
+        encoding_byte_len: usize,
+        element: &'a fp18_as_2_over_3_over_3::Fp18<'a, FE, F>
+    ) -> Result<Vec<u8>, ApiError>
+{
+    let mut bytes = Vec::with_capacity(18*encoding_byte_len);
+    bytes.extend(serialize_fp9_fixed_len(encoding_byte_len, &element.c0)?);
+    bytes.extend(serialize_fp9_fixed_len(encoding_byte_len, &element.c1)?);
+
+    Ok(bytes)
+}
+
+pub fn serialize_fp8_over_fp4_fixed_len<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+    (
+        encoding_byte_len: usize,
+        element: &'a fp8_as_2_over_4_generic::Fp8<'a, FE, F>
+    ) -> Result<Vec<u8>, ApiError>
+{
+    let mut bytes = Vec::with_capacity(8*encoding_byte_len);
+    bytes.extend(serialize_fp4_fixed_len(encoding_byte_len, &element.c0)?);
+    bytes.extend(serialize_fp4_fixed_len(encoding_byte_len, &element.c1)?);
+
+    Ok(bytes)
+}
+
+pub fn serialize_fp16_fixed_len<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+    (
+        encoding_byte_len: usize,
+        element: &'a fp16_as_2_over_8::Fp16<'a, FE, F>
+    ) -> Result<Vec<u8>, ApiError>
+{
+    let mut bytes = Vec::with_capacity(16*encoding_byte_len);
+    bytes.extend(serialize_fp8_over_fp4_fixed_len(encoding_byte_len, &element.c0)?);
+    bytes.extend(serialize_fp8_over_fp4_fixed_len(encoding_byte_len, &element.c1)?);
+
+    Ok(bytes)
+}
+
+pub fn serialize_fpk_fixed_len<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+    (
+        encoding_byte_len: usize,
+        element: &'a fpk::FpK<'a, FE, F>
+    ) -> Result<Vec<u8>, ApiError>
+{
+    let degree = element.extension_field.degree;
+    let mut bytes = Vec::with_capacity(degree*encoding_byte_len);
+    for c in element.coeffs[..degree].iter() {
+        append_fp_fixed_len(encoding_byte_len, c, &mut bytes)?;
+    }
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::engines::bls12_381::*;
+    use crate::field::U384Repr;
+    use crate::extension_towers::fp3::Extension3;
+    use crate::extension_towers::fp4_as_2_over_2::Extension2Over2;
+    use crate::extension_towers::fp6_as_2_over_3::Extension2Over3;
+    use num_bigint::BigUint;
+    use rand::{Rng, SeedableRng};
+    use rand_xorshift::XorShiftRng;
+
+    const BLS12_381_BYTE_LEN: usize = 48;
+
+    fn bls12_381_modulus() -> BigUint {
+        BigUint::from_bytes_be(&hex::decode("1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab").unwrap())
+    }
+
+    fn make_random_encoding<R: Rng>(rng: &mut R, num_coeffs: usize) -> Vec<u8> {
+        let modulus = bls12_381_modulus();
+        let mut encoding = Vec::with_capacity(num_coeffs * BLS12_381_BYTE_LEN);
+        for _ in 0..num_coeffs {
+            let mut buff = vec![0u8; BLS12_381_BYTE_LEN * 2];
+            rng.fill_bytes(&mut buff);
+            let num = BigUint::from_bytes_be(&buff) % &modulus;
+            let as_be = num.to_bytes_be();
+            encoding.extend(vec![0u8; BLS12_381_BYTE_LEN - as_be.len()]);
+            encoding.extend(as_be);
+        }
+
+        encoding
+    }
+
+    // replaces a coefficient at the given position with the modulus itself
+    fn make_non_canonical(encoding: &[u8], coeff_index: usize) -> Vec<u8> {
+        let mut encoding = encoding.to_vec();
+        let modulus = bls12_381_modulus().to_bytes_be();
+        let start = coeff_index * BLS12_381_BYTE_LEN;
+        encoding[start..(start + BLS12_381_BYTE_LEN)].copy_from_slice(&modulus);
+
+        encoding
+    }
+
+    fn expect_error_with_path<T>(result: Result<T, ApiError>, path: &str) {
+        match result {
+            Err(ApiError::InputError(msg)) => {
+                assert!(msg.contains(&format!("{} element", path)), "error `{}` must mention {}", msg, path);
+            },
+            Err(e) => panic!("unexpected error {}", e),
+            Ok(..) => panic!("non-canonical encoding must be rejected")
+        }
+    }
+
+    #[test]
+    fn test_fp12_encoding_round_trip() {
+        let mut rng = XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        for _ in 0..100 {
+            let encoding = make_random_encoding(&mut rng, 12);
+            let (x, rest) = decode_fp12(&encoding, BLS12_381_BYTE_LEN, &BLS12_381_EXTENSION_12_FIELD).unwrap();
+            assert!(rest.is_empty());
+
+            let serialized = serialize_fp12_fixed_len(BLS12_381_BYTE_LEN, &x).unwrap();
+            assert_eq!(serialized.len(), 12 * BLS12_381_BYTE_LEN);
+            assert_eq!(serialized, encoding);
+
+            let (y, _) = decode_fp12(&serialized, BLS12_381_BYTE_LEN, &BLS12_381_EXTENSION_12_FIELD).unwrap();
+            assert!(x == y);
+        }
+    }
+
+    #[test]
+    fn test_fp12_coefficient_ordering() {
+        let mut rng = XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        let encoding = make_random_encoding(&mut rng, 12);
+        let (x, _) = decode_fp12(&encoding, BLS12_381_BYTE_LEN, &BLS12_381_EXTENSION_12_FIELD).unwrap();
+
+        let chunk = |i: usize| &encoding[(i * BLS12_381_BYTE_LEN)..((i + 1) * BLS12_381_BYTE_LEN)];
+        assert_eq!(serialize_fp_fixed_len(BLS12_381_BYTE_LEN, &x.c0.c0.c0).unwrap(), chunk(0));
+        assert_eq!(serialize_fp_fixed_len(BLS12_381_BYTE_LEN, &x.c0.c0.c1).unwrap(), chunk(1));
+        assert_eq!(serialize_fp_fixed_len(BLS12_381_BYTE_LEN, &x.c0.c2.c1).unwrap(), chunk(5));
+        assert_eq!(serialize_fp_fixed_len(BLS12_381_BYTE_LEN, &x.c1.c0.c0).unwrap(), chunk(6));
+        assert_eq!(serialize_fp_fixed_len(BLS12_381_BYTE_LEN, &x.c1.c2.c0).unwrap(), chunk(10));
+    }
+
+    #[test]
+    fn test_fp12_decoding_errors() {
+        let mut rng = XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        let encoding = make_random_encoding(&mut rng, 12);
+
+        expect_error_with_path(decode_fp12(&make_non_canonical(&encoding, 0), BLS12_381_BYTE_LEN, &BLS12_381_EXTENSION_12_FIELD), "Fp12.c0.c0.c0");
+        expect_error_with_path(decode_fp12(&make_non_canonical(&encoding, 3), BLS12_381_BYTE_LEN, &BLS12_381_EXTENSION_12_FIELD), "Fp12.c0.c1.c1");
+        expect_error_with_path(decode_fp12(&make_non_canonical(&encoding, 10), BLS12_381_BYTE_LEN, &BLS12_381_EXTENSION_12_FIELD), "Fp12.c1.c2.c0");
+
+        expect_error_with_path(decode_fp12(&encoding[..(12 * BLS12_381_BYTE_LEN - 1)], BLS12_381_BYTE_LEN, &BLS12_381_EXTENSION_12_FIELD), "Fp12.c1.c2.c1");
+        expect_error_with_path(decode_fp12(&encoding[..(6 * BLS12_381_BYTE_LEN)], BLS12_381_BYTE_LEN, &BLS12_381_EXTENSION_12_FIELD), "Fp12.c1.c0.c0");
+    }
+
+    #[test]
+    fn test_fp6_as_3_over_2_encoding_round_trip() {
+        let mut rng = XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        for _ in 0..100 {
+            let encoding = make_random_encoding(&mut rng, 6);
+            let (x, rest) = decode_fp6_as_3_over_2(&encoding, BLS12_381_BYTE_LEN, &BLS12_381_EXTENSION_6_FIELD).unwrap();
+            assert!(rest.is_empty());
+
+            let serialized = serialize_fp6_as_3_over_2_fixed_len(BLS12_381_BYTE_LEN, &x).unwrap();
+            assert_eq!(serialized.len(), 6 * BLS12_381_BYTE_LEN);
+            assert_eq!(serialized, encoding);
+        }
+
+        let encoding = make_random_encoding(&mut rng, 6);
+        expect_error_with_path(decode_fp6_as_3_over_2(&make_non_canonical(&encoding, 4), BLS12_381_BYTE_LEN, &BLS12_381_EXTENSION_6_FIELD), "Fp6.c2.c0");
+    }
+
+    #[test]
+    fn test_fp4_encoding_round_trip() {
+        // non-residue is irrelevant for encoding, so any Fp2 element works here
+        let extension_4 = Extension2Over2::new(BLS12_381_FP2_ONE.clone());
+
+        let mut rng = XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        for _ in 0..100 {
+            let encoding = make_random_encoding(&mut rng, 4);
+            let (x, rest) = decode_fp4(&encoding, BLS12_381_BYTE_LEN, &extension_4).unwrap();
+            assert!(rest.is_empty());
+
+            let serialized = serialize_fp4_fixed_len(BLS12_381_BYTE_LEN, &x).unwrap();
+            assert_eq!(serialized.len(), 4 * BLS12_381_BYTE_LEN);
+            assert_eq!(serialized, encoding);
+        }
+
+        let encoding = make_random_encoding(&mut rng, 4);
+        expect_error_with_path(decode_fp4(&make_non_canonical(&encoding, 1), BLS12_381_BYTE_LEN, &extension_4), "Fp4.c0.c1");
+    }
+
+    #[test]
+    fn test_fp6_as_2_over_3_encoding_round_trip() {
+        // non-residues are irrelevant for encoding, so any elements work here
+        let extension_3 = Extension3::<U384Repr, _>::new(BLS12_381_FP_ONE.clone());
+        let extension_6 = Extension2Over3::new(fp3::Fp3::one(&extension_3));
+
+        let mut rng = XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        for _ in 0..100 {
+            let encoding = make_random_encoding(&mut rng, 6);
+            let (x, rest) = decode_fp6_as_2_over_3(&encoding, BLS12_381_BYTE_LEN, &extension_6).unwrap();
+            assert!(rest.is_empty());
+
+            let serialized = serialize_fp6_as_2_over_3_fixed_len(BLS12_381_BYTE_LEN, &x).unwrap();
+            assert_eq!(serialized.len(), 6 * BLS12_381_BYTE_LEN);
+            assert_eq!(serialized, encoding);
+        }
+
+        let encoding = make_random_encoding(&mut rng, 6);
+        expect_error_with_path(decode_fp6_as_2_over_3(&make_non_canonical(&encoding, 5), BLS12_381_BYTE_LEN, &extension_6), "Fp6.c1.c2");
+    }
+
+    #[test]
+    fn test_fp_encodings_of_any_length() {
+        let mut rng = XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        for _ in 0..100 {
+            let mut encoding = vec![0u8; 16];
+            rng.fill(&mut encoding[..]);
+            let mut padded = vec![0u8; BLS12_381_BYTE_LEN - encoding.len()];
+            padded.extend_from_slice(&encoding);
+
+            // shorter than the representation and oversized with zero padding decode the same
+            let (x, rest) = decode_fp(&encoding, encoding.len(), &BLS12_381_FIELD).unwrap();
+            assert!(rest.is_empty());
+            let (y, _) = decode_fp(&padded, BLS12_381_BYTE_LEN, &BLS12_381_FIELD).unwrap();
+            assert_eq!(x, y);
+            let mut oversized = vec![0u8; 16];
+            oversized.extend_from_slice(&padded);
+            let (z, _) = decode_fp_oversized(&oversized, oversized.len(), &BLS12_381_FIELD).unwrap();
+            assert_eq!(x, z);
+
+            assert_eq!(serialize_fp_fixed_len(encoding.len(), &x).unwrap(), encoding);
+            assert_eq!(serialize_fp_fixed_len(BLS12_381_BYTE_LEN, &x).unwrap(), padded);
+        }
+
+        // value doesn't fit into the requested length
+        let minus_one = {
+            let mut el = Fp::one(&BLS12_381_FIELD);
+            el.negate();
+            el
+        };
+        assert!(serialize_fp_fixed_len(16, &minus_one).is_err());
+    }
+}
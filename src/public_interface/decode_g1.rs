@@ -1,21 +1,38 @@
-use crate::weierstrass::curve::{WeierstrassCurve, CurvePoint};
+use crate::weierstrass::curve::{WeierstrassCurve, CurvePoint, batch_normalize};
 use crate::field::{SizedPrimeField};
 use crate::fp::Fp;
 use crate::representation::ElementRepr;
-use crate::weierstrass::CurveParameters;
+use crate::weierstrass::{Group, CurveParameters};
 use crate::integers::{MaxGroupSizeUint};
 
 use super::decode_fp::*;
 
-use super::decode_utils::{split, decode_group_order_with_length};
+use super::point_encoding::PointEncoding;
+use super::decode_utils::{split, decode_group_order_with_length, decode_compression_flag, encode_compression_flag};
+use crate::square_root::sqrt;
+use crate::traits::FieldElement;
 
-use crate::errors::ApiError;
+use crate::errors::{ApiError, ErrorCode};
+use crate::execution_context::ExecutionContext;
+use crate::alloc_prelude::*;
 
 pub fn parse_group_order_from_encoding<
     'a
-    >(encoding: &'a [u8]) -> Result<(usize, MaxGroupSizeUint, &'a [u8]), ApiError>
+    >(encoding: &'a [u8], ctx: &ExecutionContext) -> Result<(usize, MaxGroupSizeUint, &'a [u8]), ApiError>
 {
-    let ((order_len, order), rest) = decode_group_order_with_length(&encoding)?;
+    let (order_len, order, rest) = parse_nonzero_group_order_from_encoding(encoding)?;
+    // subgroup checks and scalar reduction are meaningless for a composite order
+    if ctx.limits().require_prime_group_order && !crate::primality::is_probable_prime(&order, ctx) {
+        return Err(ApiError::with_code(ErrorCode::CompositeGroupOrder, "Group order is not prime".to_owned()).at(encoding));
+    }
+
+    Ok((order_len, order, rest))
+}
+
+/// Same as `parse_group_order_from_encoding` without the primality test. The gas meter
+/// prices the test from the order length instead of running it
+pub(crate) fn parse_nonzero_group_order_from_encoding(encoding: &[u8]) -> Result<(usize, MaxGroupSizeUint, &[u8]), ApiError> {
+    let ((order_len, order), rest) = decode_group_order_with_length(encoding)?;
     if order.is_zero() {
         return Err(ApiError::InputError(format!("Group order is zero, file {}, line {}", file!(), line!())))
     }
@@ -23,6 +40,19 @@ pub fn parse_group_order_from_encoding<
     Ok((order_len, order, rest))
 }
 
+/// Cofactor is encoded in the same way as the group order
+pub(crate) fn parse_cofactor_from_encoding<
+    'a
+    >(encoding: &'a [u8]) -> Result<(usize, MaxGroupSizeUint, &'a [u8]), ApiError>
+{
+    let ((cofactor_len, cofactor), rest) = decode_group_order_with_length(&encoding)?;
+    if cofactor.is_zero() {
+        return Err(ApiError::InputError(format!("Cofactor is zero, file {}, line {}", file!(), line!())))
+    }
+
+    Ok((cofactor_len, cofactor, rest))
+}
+
 pub fn parse_ab_in_base_field_from_encoding<
     'a,
     FE: ElementRepr,
@@ -52,8 +82,31 @@ pub fn serialize_g1_point<
 {
     let (x, y) = point.into_xy();
     let mut result = Vec::with_capacity(2*encoding_length);
-    result.extend(serialize_fp_fixed_len(encoding_length, &x)?);
-    result.extend(serialize_fp_fixed_len(encoding_length, &y)?);
+    append_fp_fixed_len(encoding_length, &x, &mut result)?;
+    append_fp_fixed_len(encoding_length, &y, &mut result)?;
+
+    Ok(result)
+}
+
+/// Serializes points one after another, normalizing all of them with a single inversion
+pub fn serialize_g1_points<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    C: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>
+    >
+    (
+        encoding_length: usize,
+        points: &mut [CurvePoint<'a, C>]
+    ) -> Result<Vec<u8>, ApiError>
+{
+    batch_normalize(points);
+    let mut result = Vec::with_capacity(2*encoding_length*points.len());
+    for point in points.iter() {
+        let (x, y) = point.into_xy();
+        append_fp_fixed_len(encoding_length, &x, &mut result)?;
+        append_fp_fixed_len(encoding_length, &y, &mut result)?;
+    }
 
     Ok(result)
 }
@@ -68,9 +121,14 @@ pub fn decode_g1_point_from_xy<
     (
         bytes: &'a [u8], 
         field_byte_len: usize,
-        curve: &'b WeierstrassCurve<'b, C>
+        curve: &'b WeierstrassCurve<'b, C>,
+        encoding: PointEncoding
     ) -> Result<(CurvePoint<'b, C>, &'a [u8]), ApiError>
 {
+    if encoding == PointEncoding::Compressed {
+        return decode_g1_point_compressed(bytes, field_byte_len, curve);
+    }
+
     let (x, rest) = decode_fp(&bytes, field_byte_len, curve.params.params())?;
     let (y, rest) = decode_fp(&rest, field_byte_len, curve.params.params())?;
     
@@ -100,6 +158,75 @@ pub fn decode_g1_point_from_xy_oversized<
     Ok((p, rest))
 }
 
+// Compressed encoding is X followed by a flag byte that holds the parity of Y
+// or marks the point at infinity (in which case X must be zero)
+pub fn decode_g1_point_compressed<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    C: CurveParameters<BaseFieldElement = Fp<'b, FE, F>>
+    >
+    (
+        bytes: &'a [u8], 
+        field_byte_len: usize,
+        curve: &'b WeierstrassCurve<'b, C>
+    ) -> Result<(CurvePoint<'b, C>, &'a [u8]), ApiError>
+{
+    let (x, rest) = decode_fp(bytes, field_byte_len, curve.params.params())?;
+    let (y_is_odd, rest) = decode_compression_flag(rest)?;
+    let y_is_odd = match y_is_odd {
+        Some(y_is_odd) => y_is_odd,
+        None => {
+            if !x.is_zero() {
+                return Err(ApiError::InputError("Point at infinity must have zero X".to_owned()));
+            }
+
+            return Ok((CurvePoint::zero(curve), rest));
+        }
+    };
+
+    let rhs = curve.evaluate_rhs(&x);
+    let mut y = sqrt(&rhs).ok_or_else(|| {
+        ApiError::with_code(ErrorCode::NotOnCurve, "Compressed point is not on curve".to_owned())
+    })?;
+    if fp_is_odd(&y) != y_is_odd {
+        y.negate();
+        if y.is_zero() {
+            return Err(ApiError::InputError("Compression flag is odd for zero Y".to_owned()));
+        }
+    }
+
+    let p: CurvePoint<'b, C> = CurvePoint::point_from_xy(curve, x, y);
+    
+    Ok((p, rest))
+}
+
+pub fn serialize_g1_point_compressed<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    C: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>
+    >
+    (
+        encoding_length: usize,
+        point: &CurvePoint<'a, C>
+    ) -> Result<Vec<u8>, ApiError>
+{
+    let (x, y) = point.into_xy();
+    let y_is_odd = if point.is_zero() {
+        None
+    } else {
+        Some(fp_is_odd(&y))
+    };
+
+    let mut result = Vec::with_capacity(encoding_length + 1);
+    append_fp_fixed_len(encoding_length, &x, &mut result)?;
+    result.push(encode_compression_flag(y_is_odd));
+
+    Ok(result)
+}
+
 pub fn decode_scalar_representation<
     'a
     >
@@ -114,3 +241,141 @@ pub fn decode_scalar_representation<
     Ok((scalar, rest))
 }
 
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::engines::bls12_381::*;
+    use crate::public_interface::constants::*;
+    use crate::test_utils::random_point;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    const BLS12_381_BYTE_LEN: usize = 48;
+
+    fn expect_input_error<T>(result: Result<T, ApiError>) {
+        match result {
+            Err(ApiError::InputError(..)) | Err(ApiError::Coded(..)) => {},
+            Err(e) => panic!("unexpected error {}", e),
+            Ok(..) => panic!("invalid compressed encoding must be rejected")
+        }
+    }
+
+    #[test]
+    fn test_g1_compressed_round_trip() {
+        let mut rng = XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        for _ in 0..16 {
+            let point = random_point(&BLS12_381_G1_GENERATOR, &mut rng);
+            let encoding = serialize_g1_point_compressed(BLS12_381_BYTE_LEN, &point).unwrap();
+            assert_eq!(encoding.len(), BLS12_381_BYTE_LEN + COMPRESSION_FLAG_LENGTH);
+
+            let (decoded, rest) = decode_g1_point_compressed(&encoding, BLS12_381_BYTE_LEN, &BLS12_381_G1_CURVE).unwrap();
+            assert!(rest.is_empty());
+            assert!(decoded.into_xy() == point.into_xy());
+
+            // flipping the flag must give the negated point
+            let mut flipped = encoding.clone();
+            flipped[BLS12_381_BYTE_LEN] ^= COMPRESSED_Y_IS_ODD;
+            let (decoded, _) = decode_g1_point_compressed(&flipped, BLS12_381_BYTE_LEN, &BLS12_381_G1_CURVE).unwrap();
+            let mut negated = point.clone();
+            negated.negate();
+            assert!(decoded.into_xy() == negated.into_xy());
+        }
+    }
+
+    #[test]
+    fn test_g1_compressed_round_trip_for_one_mod_four_modulus() {
+        use crate::field::{U256Repr, new_field};
+        use crate::weierstrass::CurveOverFpParameters;
+        use crate::traits::ZeroAndOne;
+
+        const BYTE_LEN: usize = 32;
+
+        // BN254 scalar field, p - 1 is divisible by 2^28
+        let field = new_field::<U256Repr>("21888242871839275222246405745257275088548364400416034343698204186575808495617", 10).unwrap();
+        let params = CurveOverFpParameters::new(&field);
+        // decoding doesn't use the group order
+        let order = [1u64];
+        let b = Fp::from_repr(&field, U256Repr::from(5)).unwrap();
+        let curve = WeierstrassCurve::new(&order, Fp::zero(&field), b, &params).unwrap();
+
+        let one = Fp::one(&field);
+        let mut x = one.clone();
+        let mut num_points = 0;
+        while num_points < 16 {
+            x.add_assign(&one);
+            let y = match sqrt(&curve.evaluate_rhs(&x)) {
+                Some(y) => y,
+                None => continue
+            };
+            let point = CurvePoint::point_from_xy(&curve, x.clone(), y);
+            assert!(point.is_on_curve());
+
+            let encoding = serialize_g1_point_compressed(BYTE_LEN, &point).unwrap();
+            let (decoded, _) = decode_g1_point_compressed(&encoding, BYTE_LEN, &curve).unwrap();
+            assert!(decoded.into_xy() == point.into_xy());
+
+            num_points += 1;
+        }
+    }
+
+    #[test]
+    fn test_g1_compressed_point_at_infinity() {
+        let zero = CurvePoint::zero(&BLS12_381_G1_CURVE);
+        let encoding = serialize_g1_point_compressed(BLS12_381_BYTE_LEN, &zero).unwrap();
+        let mut expected = vec![0u8; BLS12_381_BYTE_LEN];
+        expected.push(COMPRESSED_POINT_AT_INFINITY);
+        assert_eq!(encoding, expected);
+
+        let (decoded, _) = decode_g1_point_compressed(&encoding, BLS12_381_BYTE_LEN, &BLS12_381_G1_CURVE).unwrap();
+        assert!(decoded.is_zero());
+    }
+
+    #[test]
+    fn test_g1_compressed_decoding_errors() {
+        let mut rng = XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        let point = random_point(&BLS12_381_G1_GENERATOR, &mut rng);
+        let encoding = serialize_g1_point_compressed(BLS12_381_BYTE_LEN, &point).unwrap();
+
+        let mut bad_flag = encoding.clone();
+        bad_flag[BLS12_381_BYTE_LEN] = 0x03;
+        expect_input_error(decode_g1_point_compressed(&bad_flag, BLS12_381_BYTE_LEN, &BLS12_381_G1_CURVE));
+
+        let mut infinity_with_x = encoding.clone();
+        infinity_with_x[BLS12_381_BYTE_LEN] = COMPRESSED_POINT_AT_INFINITY;
+        expect_input_error(decode_g1_point_compressed(&infinity_with_x, BLS12_381_BYTE_LEN, &BLS12_381_G1_CURVE));
+
+        expect_input_error(decode_g1_point_compressed(&encoding[..BLS12_381_BYTE_LEN], BLS12_381_BYTE_LEN, &BLS12_381_G1_CURVE));
+
+        // find an X that has no Y on the curve
+        let mut x = BLS12_381_FP_ONE.clone();
+        while sqrt(&BLS12_381_G1_CURVE.evaluate_rhs(&x)).is_some() {
+            x.add_assign(&BLS12_381_FP_ONE);
+        }
+        let mut not_on_curve = serialize_fp_fixed_len(BLS12_381_BYTE_LEN, &x).unwrap();
+        not_on_curve.push(COMPRESSED_Y_IS_EVEN);
+        expect_input_error(decode_g1_point_compressed(&not_on_curve, BLS12_381_BYTE_LEN, &BLS12_381_G1_CURVE));
+    }
+
+    #[test]
+    fn test_composite_group_order_is_rejected() {
+        use crate::public_interface::API;
+        use crate::public_interface::encoding::{G1CurveDescriptor, G1Point};
+
+        // BN254 with the order multiplied by 3
+        let modulus = hex::decode("30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47").unwrap();
+        let order = hex::decode("30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001").unwrap();
+        let composite = (num_bigint::BigUint::from_bytes_be(&order) * 3u64).to_bytes_be();
+        let generator = G1Point::new(&[1u8], &[2u8]);
+
+        let curve = G1CurveDescriptor::new(&modulus, &[0u8], &[3u8], &order).unwrap();
+        assert!(API::run(&curve.mul(&generator, &[7u8]).unwrap()).is_ok());
+
+        let curve = G1CurveDescriptor::new(&modulus, &[0u8], &[3u8], &composite).unwrap();
+        let input = curve.mul(&generator, &[7u8]).unwrap();
+        let err = API::run(&input).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::CompositeGroupOrder);
+        // offset of the group order length
+        assert_eq!(err.offset(input.len()), Some(OPERATION_ENCODING_LENGTH + 1 + 3 * 32));
+    }
+}
@@ -3,17 +3,23 @@ use crate::fp::Fp;
 use crate::extension_towers::*;
 use crate::extension_towers::fp2;
 use crate::extension_towers::fp3;
+use crate::extension_towers::fp4_as_2_over_2;
+use crate::extension_towers::fp8_as_2_over_4;
+use crate::extension_towers::fpk;
 use crate::representation::{ElementRepr};
-use crate::weierstrass::curve::{WeierstrassCurve, CurvePoint};
+use crate::weierstrass::curve::{WeierstrassCurve, CurvePoint, batch_normalize};
 use crate::traits::FieldElement;
-use crate::weierstrass::CurveParameters;
+use crate::weierstrass::{Group, CurveParameters};
 use crate::integers::MaxFieldUint;
 
 use super::decode_fp::*;
 use super::constants::*;
-use super::decode_utils::split;
+use super::decode_utils::{split, decode_compression_flag, encode_compression_flag};
+use super::point_encoding::PointEncoding;
+use crate::square_root::{sqrt_ext2, sqrt_ext3};
 
-use crate::errors::ApiError;
+use crate::errors::{ApiError, ErrorCode};
+use crate::alloc_prelude::*;
 
 pub fn create_fp2_extension<
     'a,
@@ -43,7 +49,7 @@ pub fn create_fp2_extension<
         let not_a_square = is_non_nth_root(&fp_non_residue, modulus, 2);
         if !not_a_square {
             if !crate::features::in_fuzzing_or_gas_metering() {
-                return Err(ApiError::InputError(format!("Non-residue for Fp2 is actually a residue, file {}, line {}", file!(), line!())));
+                return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp2 is actually a residue, file {}, line {}", file!(), line!())));
             }
         }
     }
@@ -86,7 +92,7 @@ pub fn create_fp3_extension<
         let not_a_cube = is_non_nth_root(&fp_non_residue, modulus, 3);
         if !not_a_cube {
             if !crate::features::in_fuzzing_or_gas_metering() {
-                return Err(ApiError::InputError(format!("Non-residue for Fp3 is actually a residue, file {}, line {}", file!(), line!())));
+                return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp3 is actually a residue, file {}, line {}", file!(), line!())));
             }
         }
     }
@@ -101,6 +107,34 @@ pub fn create_fp3_extension<
     Ok((extension_3, rest))
 }
 
+/// Y can only be recovered for coordinates in the base field and its extensions of degree 2 and 3
+fn reject_compressed_point(encoding: PointEncoding) -> Result<(), ApiError> {
+    if encoding == PointEncoding::Compressed {
+        return Err(ApiError::UnknownParameter("Compressed points are not supported for G2 in extensions of degree above 3".to_owned()));
+    }
+
+    Ok(())
+}
+
+/// G2 of BW6 curves is a group of points on a twist over the base field itself,
+/// so points are encoded in the same way as G1 points
+pub fn decode_g2_point_from_xy_in_fp<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    C: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>
+    >
+    (
+        bytes: &'b [u8], 
+        field_byte_len: usize,
+        curve: &'a WeierstrassCurve<'a, C>,
+        encoding: PointEncoding
+    ) -> Result<(CurvePoint<'a, C>, &'b [u8]), ApiError>
+{
+    super::decode_g1::decode_g1_point_from_xy(bytes, field_byte_len, curve, encoding)
+}
+
 pub fn decode_g2_point_from_xy_in_fp2<
     'a,
     'b,
@@ -111,9 +145,14 @@ pub fn decode_g2_point_from_xy_in_fp2<
     (
         bytes: &'b [u8], 
         field_byte_len: usize,
-        curve: &'a WeierstrassCurve<'a, C>
+        curve: &'a WeierstrassCurve<'a, C>,
+        encoding: PointEncoding
     ) -> Result<(CurvePoint<'a, C>, &'b [u8]), ApiError>
 {
+    if encoding == PointEncoding::Compressed {
+        return decode_g2_point_compressed_in_fp2(bytes, field_byte_len, curve);
+    }
+
     let (x, rest) = decode_fp2(&bytes, field_byte_len, curve.params.params())?;
     let (y, rest) = decode_fp2(&rest, field_byte_len, curve.params.params())?;
     
@@ -153,9 +192,14 @@ pub fn decode_g2_point_from_xy_in_fp3<
     (
         bytes: &'b [u8], 
         field_byte_len: usize,
-        curve: &'a WeierstrassCurve<'a, C>
+        curve: &'a WeierstrassCurve<'a, C>,
+        encoding: PointEncoding
     ) -> Result<(CurvePoint<'a, C>, &'b [u8]), ApiError>
 {
+    if encoding == PointEncoding::Compressed {
+        return decode_g2_point_compressed_in_fp3(bytes, field_byte_len, curve);
+    }
+
     let (x, rest) = decode_fp3(&bytes, field_byte_len, curve.params.params())?;
     let (y, rest) = decode_fp3(&rest, field_byte_len, curve.params.params())?;
     
@@ -164,6 +208,78 @@ pub fn decode_g2_point_from_xy_in_fp3<
     Ok((p, rest))
 }
 
+pub fn decode_g2_point_from_xy_in_fpk<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    C: CurveParameters<BaseFieldElement = fpk::FpK<'a, FE, F>>
+    >
+    (
+        bytes: &'b [u8], 
+        field_byte_len: usize,
+        curve: &'a WeierstrassCurve<'a, C>,
+        encoding: PointEncoding
+    ) -> Result<(CurvePoint<'a, C>, &'b [u8]), ApiError>
+{
+    reject_compressed_point(encoding)?;
+
+    let (x, rest) = decode_fpk(&bytes, field_byte_len, curve.params.params())?;
+    let (y, rest) = decode_fpk(&rest, field_byte_len, curve.params.params())?;
+    
+    let p: CurvePoint<'a, C> = CurvePoint::point_from_xy(&curve, x, y);
+    
+    Ok((p, rest))
+}
+
+pub fn decode_g2_point_from_xy_in_fp4<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    C: CurveParameters<BaseFieldElement = fp4_as_2_over_2::Fp4<'a, FE, F>>
+    >
+    (
+        bytes: &'b [u8], 
+        field_byte_len: usize,
+        curve: &'a WeierstrassCurve<'a, C>,
+        encoding: PointEncoding
+    ) -> Result<(CurvePoint<'a, C>, &'b [u8]), ApiError>
+{
+    reject_compressed_point(encoding)?;
+
+    let (x, rest) = decode_fp4(&bytes, field_byte_len, curve.params.params())?;
+    let (y, rest) = decode_fp4(&rest, field_byte_len, curve.params.params())?;
+    
+    let p: CurvePoint<'a, C> = CurvePoint::point_from_xy(&curve, x, y);
+    
+    Ok((p, rest))
+}
+
+pub fn decode_g2_point_from_xy_in_fp8<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    C: CurveParameters<BaseFieldElement = fp8_as_2_over_4::Fp8<'a, FE, F>>
+    >
+    (
+        bytes: &'b [u8], 
+        field_byte_len: usize,
+        curve: &'a WeierstrassCurve<'a, C>,
+        encoding: PointEncoding
+    ) -> Result<(CurvePoint<'a, C>, &'b [u8]), ApiError>
+{
+    reject_compressed_point(encoding)?;
+
+    let (x, rest) = decode_fp8(&bytes, field_byte_len, curve.params.params())?;
+    let (y, rest) = decode_fp8(&rest, field_byte_len, curve.params.params())?;
+    
+    let p: CurvePoint<'a, C> = CurvePoint::point_from_xy(&curve, x, y);
+    
+    Ok((p, rest))
+}
+
 pub fn serialize_g2_point_in_fp2<
     'a,
     FE: ElementRepr,
@@ -177,12 +293,35 @@ pub fn serialize_g2_point_in_fp2<
 {
     let (x, y) = point.into_xy();
     let mut result = Vec::with_capacity(4*encoding_length);
-    result.extend(serialize_fp2_fixed_len(encoding_length, &x)?);
-    result.extend(serialize_fp2_fixed_len(encoding_length, &y)?);
+    append_fp2_fixed_len(encoding_length, &x, &mut result)?;
+    append_fp2_fixed_len(encoding_length, &y, &mut result)?;
     
     Ok(result)
 }
 
+/// Serializes points one after another, normalizing all of them with a single inversion
+pub fn serialize_g2_points_in_fp2<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    C: CurveParameters<BaseFieldElement = fp2::Fp2<'a, FE, F>>
+    >
+    (
+        encoding_length: usize,
+        points: &mut [CurvePoint<'a, C>]
+    ) -> Result<Vec<u8>, ApiError>
+{
+    batch_normalize(points);
+    let mut result = Vec::with_capacity(4*encoding_length*points.len());
+    for point in points.iter() {
+        let (x, y) = point.into_xy();
+        append_fp2_fixed_len(encoding_length, &x, &mut result)?;
+        append_fp2_fixed_len(encoding_length, &y, &mut result)?;
+    }
+
+    Ok(result)
+}
+
 pub fn serialize_g2_point_in_fp3<
     'a,
     FE: ElementRepr,
@@ -196,12 +335,169 @@ pub fn serialize_g2_point_in_fp3<
 {
     let (x, y) = point.into_xy();
     let mut result = Vec::with_capacity(6*encoding_length);
-    result.extend(serialize_fp3_fixed_len(encoding_length, &x)?);
-    result.extend(serialize_fp3_fixed_len(encoding_length, &y)?);
+    append_fp3_fixed_len(encoding_length, &x, &mut result)?;
+    append_fp3_fixed_len(encoding_length, &y, &mut result)?;
     
     Ok(result)
 }
 
+/// Serializes points one after another, normalizing all of them with a single inversion
+pub fn serialize_g2_points_in_fp3<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    C: CurveParameters<BaseFieldElement = fp3::Fp3<'a, FE, F>>
+    >
+    (
+        encoding_length: usize,
+        points: &mut [CurvePoint<'a, C>]
+    ) -> Result<Vec<u8>, ApiError>
+{
+    batch_normalize(points);
+    let mut result = Vec::with_capacity(6*encoding_length*points.len());
+    for point in points.iter() {
+        let (x, y) = point.into_xy();
+        append_fp3_fixed_len(encoding_length, &x, &mut result)?;
+        append_fp3_fixed_len(encoding_length, &y, &mut result)?;
+    }
+
+    Ok(result)
+}
+
+pub fn decode_g2_point_compressed_in_fp2<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    C: CurveParameters<BaseFieldElement = fp2::Fp2<'a, FE, F>>
+    >
+    (
+        bytes: &'b [u8], 
+        field_byte_len: usize,
+        curve: &'a WeierstrassCurve<'a, C>
+    ) -> Result<(CurvePoint<'a, C>, &'b [u8]), ApiError>
+{
+    let (x, rest) = decode_fp2(bytes, field_byte_len, curve.params.params())?;
+    let (y_is_odd, rest) = decode_compression_flag(rest)?;
+    let y_is_odd = match y_is_odd {
+        Some(y_is_odd) => y_is_odd,
+        None => {
+            if !x.is_zero() {
+                return Err(ApiError::InputError("Point at infinity must have zero X".to_owned()));
+            }
+
+            return Ok((CurvePoint::zero(curve), rest));
+        }
+    };
+
+    let rhs = curve.evaluate_rhs(&x);
+    let mut y = sqrt_ext2(&rhs).ok_or_else(|| {
+        ApiError::with_code(ErrorCode::NotOnCurve, "Compressed point is not on curve".to_owned())
+    })?;
+    if fp2_is_odd(&y) != y_is_odd {
+        y.negate();
+        if y.is_zero() {
+            return Err(ApiError::InputError("Compression flag is odd for zero Y".to_owned()));
+        }
+    }
+
+    let p: CurvePoint<'a, C> = CurvePoint::point_from_xy(curve, x, y);
+    
+    Ok((p, rest))
+}
+
+pub fn serialize_g2_point_compressed_in_fp2<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    C: CurveParameters<BaseFieldElement = fp2::Fp2<'a, FE, F>>
+    >
+    (
+        encoding_length: usize,
+        point: &CurvePoint<'a, C>
+    ) -> Result<Vec<u8>, ApiError>
+{
+    let (x, y) = point.into_xy();
+    let y_is_odd = if point.is_zero() {
+        None
+    } else {
+        Some(fp2_is_odd(&y))
+    };
+
+    let mut result = Vec::with_capacity(2*encoding_length + 1);
+    append_fp2_fixed_len(encoding_length, &x, &mut result)?;
+    result.push(encode_compression_flag(y_is_odd));
+
+    Ok(result)
+}
+
+pub fn decode_g2_point_compressed_in_fp3<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    C: CurveParameters<BaseFieldElement = fp3::Fp3<'a, FE, F>>
+    >
+    (
+        bytes: &'b [u8], 
+        field_byte_len: usize,
+        curve: &'a WeierstrassCurve<'a, C>
+    ) -> Result<(CurvePoint<'a, C>, &'b [u8]), ApiError>
+{
+    let (x, rest) = decode_fp3(bytes, field_byte_len, curve.params.params())?;
+    let (y_is_odd, rest) = decode_compression_flag(rest)?;
+    let y_is_odd = match y_is_odd {
+        Some(y_is_odd) => y_is_odd,
+        None => {
+            if !x.is_zero() {
+                return Err(ApiError::InputError("Point at infinity must have zero X".to_owned()));
+            }
+
+            return Ok((CurvePoint::zero(curve), rest));
+        }
+    };
+
+    let rhs = curve.evaluate_rhs(&x);
+    let mut y = sqrt_ext3(&rhs).ok_or_else(|| {
+        ApiError::with_code(ErrorCode::NotOnCurve, "Compressed point is not on curve".to_owned())
+    })?;
+    if fp3_is_odd(&y) != y_is_odd {
+        y.negate();
+        if y.is_zero() {
+            return Err(ApiError::InputError("Compression flag is odd for zero Y".to_owned()));
+        }
+    }
+
+    let p: CurvePoint<'a, C> = CurvePoint::point_from_xy(curve, x, y);
+    
+    Ok((p, rest))
+}
+
+pub fn serialize_g2_point_compressed_in_fp3<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    C: CurveParameters<BaseFieldElement = fp3::Fp3<'a, FE, F>>
+    >
+    (
+        encoding_length: usize,
+        point: &CurvePoint<'a, C>
+    ) -> Result<Vec<u8>, ApiError>
+{
+    let (x, y) = point.into_xy();
+    let y_is_odd = if point.is_zero() {
+        None
+    } else {
+        Some(fp3_is_odd(&y))
+    };
+
+    let mut result = Vec::with_capacity(3*encoding_length + 1);
+    append_fp3_fixed_len(encoding_length, &x, &mut result)?;
+    result.push(encode_compression_flag(y_is_odd));
+
+    Ok(result)
+}
+
 pub fn parse_ab_in_fp2_from_encoding<
     'a,
     'b,
@@ -234,4 +530,132 @@ pub fn parse_ab_in_fp3_from_encoding<
     let (b, rest) = decode_fp3(&rest, modulus_len, field)?;
 
     Ok((a, b, rest))
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::engines::bls12_381::*;
+    use crate::field::{U256Repr, new_field};
+    use crate::traits::ZeroAndOne;
+    use crate::weierstrass::CurveOverFp3Parameters;
+    use crate::test_utils::{random_point, random_fp3};
+    use crate::test::biguint_to_u64_vec;
+    use num_bigint::BigUint;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    const BLS12_381_BYTE_LEN: usize = 48;
+
+    fn expect_input_error<T>(result: Result<T, ApiError>) {
+        match result {
+            Err(ApiError::InputError(..)) | Err(ApiError::Coded(..)) => {},
+            Err(e) => panic!("unexpected error {}", e),
+            Ok(..) => panic!("invalid compressed encoding must be rejected")
+        }
+    }
+
+    #[test]
+    fn test_g2_compressed_in_fp2_round_trip() {
+        let mut rng = XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        for _ in 0..8 {
+            let point = random_point(&BLS12_381_G2_GENERATOR, &mut rng);
+            let encoding = serialize_g2_point_compressed_in_fp2(BLS12_381_BYTE_LEN, &point).unwrap();
+            assert_eq!(encoding.len(), 2 * BLS12_381_BYTE_LEN + COMPRESSION_FLAG_LENGTH);
+
+            let (decoded, rest) = decode_g2_point_compressed_in_fp2(&encoding, BLS12_381_BYTE_LEN, &BLS12_381_G2_CURVE).unwrap();
+            assert!(rest.is_empty());
+            assert!(decoded.into_xy() == point.into_xy());
+
+            let mut flipped = encoding.clone();
+            flipped[2 * BLS12_381_BYTE_LEN] ^= COMPRESSED_Y_IS_ODD;
+            let (decoded, _) = decode_g2_point_compressed_in_fp2(&flipped, BLS12_381_BYTE_LEN, &BLS12_381_G2_CURVE).unwrap();
+            let mut negated = point.clone();
+            negated.negate();
+            assert!(decoded.into_xy() == negated.into_xy());
+        }
+
+        let zero = CurvePoint::zero(&BLS12_381_G2_CURVE);
+        let encoding = serialize_g2_point_compressed_in_fp2(BLS12_381_BYTE_LEN, &zero).unwrap();
+        assert_eq!(encoding[2 * BLS12_381_BYTE_LEN], COMPRESSED_POINT_AT_INFINITY);
+        let (decoded, _) = decode_g2_point_compressed_in_fp2(&encoding, BLS12_381_BYTE_LEN, &BLS12_381_G2_CURVE).unwrap();
+        assert!(decoded.is_zero());
+    }
+
+    #[test]
+    fn test_g2_compressed_in_fp2_decoding_errors() {
+        let mut rng = XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        let point = random_point(&BLS12_381_G2_GENERATOR, &mut rng);
+        let encoding = serialize_g2_point_compressed_in_fp2(BLS12_381_BYTE_LEN, &point).unwrap();
+
+        let mut bad_flag = encoding.clone();
+        bad_flag[2 * BLS12_381_BYTE_LEN] = 0xff;
+        expect_input_error(decode_g2_point_compressed_in_fp2(&bad_flag, BLS12_381_BYTE_LEN, &BLS12_381_G2_CURVE));
+
+        let mut infinity_with_x = encoding.clone();
+        infinity_with_x[2 * BLS12_381_BYTE_LEN] = COMPRESSED_POINT_AT_INFINITY;
+        expect_input_error(decode_g2_point_compressed_in_fp2(&infinity_with_x, BLS12_381_BYTE_LEN, &BLS12_381_G2_CURVE));
+
+        let mut x = BLS12_381_FP2_ONE.clone();
+        while sqrt_ext2(&BLS12_381_G2_CURVE.evaluate_rhs(&x)).is_some() {
+            x.add_assign(&BLS12_381_FP2_ONE);
+        }
+        let mut not_on_curve = serialize_fp2_fixed_len(BLS12_381_BYTE_LEN, &x).unwrap();
+        not_on_curve.push(COMPRESSED_Y_IS_ODD);
+        expect_input_error(decode_g2_point_compressed_in_fp2(&not_on_curve, BLS12_381_BYTE_LEN, &BLS12_381_G2_CURVE));
+    }
+
+    /// Curves y^2 = x^3 + b over the cubic extension of `field`, with b such that a random (x, y) is on the curve
+    fn check_g2_compressed_in_fp3_round_trip<E: ElementRepr, F: SizedPrimeField<Repr = E>>(field: &F, modulus: BigUint, byte_len: usize) {
+        // p = 1 mod 3 so a cubic non-residue exists
+        let one = Fp::one(field);
+        let exp = biguint_to_u64_vec((modulus - BigUint::from(1u64)) / BigUint::from(3u64));
+        let mut non_residue = one.clone();
+        loop {
+            non_residue.add_assign(&one);
+            if non_residue.pow(&exp) != one {
+                break;
+            }
+        }
+        let extension_3 = fp3::Extension3::<E, _>::new(non_residue);
+        let params = CurveOverFp3Parameters::new(&extension_3);
+
+        let mut rng = XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        for _ in 0..4 {
+            let x = random_fp3(&extension_3, &mut rng);
+            let y = random_fp3(&extension_3, &mut rng);
+            let mut b = y.clone();
+            b.square();
+            let mut x_cubed = x.clone();
+            x_cubed.square();
+            x_cubed.mul_assign(&x);
+            b.sub_assign(&x_cubed);
+
+            let curve = WeierstrassCurve::new(&BLS12_381_SUBGROUP_ORDER, fp3::Fp3::zero(&extension_3), b, &params).unwrap();
+            let point = CurvePoint::point_from_xy(&curve, x, y);
+            assert!(point.is_on_curve());
+
+            let encoding = serialize_g2_point_compressed_in_fp3(byte_len, &point).unwrap();
+            assert_eq!(encoding.len(), 3 * byte_len + COMPRESSION_FLAG_LENGTH);
+
+            let (decoded, rest) = decode_g2_point_compressed_in_fp3(&encoding, byte_len, &curve).unwrap();
+            assert!(rest.is_empty());
+            assert!(decoded.into_xy() == point.into_xy());
+        }
+    }
+
+    #[test]
+    fn test_g2_compressed_in_fp3_round_trip() {
+        // BLS12-381 base field, p = 3 mod 4
+        let modulus = BigUint::from_bytes_be(&hex::decode("1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab").unwrap());
+        check_g2_compressed_in_fp3_round_trip(&BLS12_381_FIELD, modulus, BLS12_381_BYTE_LEN);
+    }
+
+    #[test]
+    fn test_g2_compressed_in_fp3_round_trip_for_one_mod_four() {
+        // BN254 scalar field, p = 1 mod 4 with the 2-adicity of 28
+        let modulus = "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+        let field = new_field::<U256Repr>(modulus, 10).unwrap();
+        check_g2_compressed_in_fp3_round_trip(&field, modulus.parse().unwrap(), 32);
+    }
+}
@@ -1,19 +1,70 @@
 use crate::integers::{MaxFieldUint, MaxGroupSizeUint, MaxLoopParametersUint};
 
 use crate::public_interface::constants::*;
+use crate::public_interface::sane_limits::*;
+use crate::public_interface::PointEncoding;
 
-use crate::errors::ApiError;
+use crate::errors::{ApiError, ErrorCode};
+use crate::alloc_prelude::*;
 
 pub(crate) fn split<'a>(bytes: &'a [u8], at: usize, err: &'static str) 
     -> Result<(&'a [u8], &'a [u8]), ApiError> 
 {
     if bytes.len() < at {
-        Err(ApiError::InputError(err.to_owned()))
+        Err(ApiError::with_code(ErrorCode::InputTooShort { expected: at, got: bytes.len() }, err.to_owned()).at(bytes))
     } else {
         Ok(bytes.split_at(at))
     }
 }
 
+/// Multiplies lengths or counts that come from the input. Returns an error instead of wrapping,
+/// so on 32-bit targets a huge declared size can not turn into a small one and pass length checks
+pub(crate) fn checked_len_mul(a: usize, b: usize) -> Result<usize, ApiError> {
+    a.checked_mul(b).ok_or(ApiError::Overflow)
+}
+
+/// Adds lengths or counts that come from the input, see `checked_len_mul`
+pub(crate) fn checked_len_add(a: usize, b: usize) -> Result<usize, ApiError> {
+    a.checked_add(b).ok_or(ApiError::Overflow)
+}
+
+/// Layout of the input after the optional version prefix
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum AbiVersion {
+    /// Layout without the prefix, as documented in `documentation/ABI.md`
+    V1,
+    /// Same as `V1`, but G2 operations describe the curve as a twist: the group order is followed
+    /// by the twist type, B of the curve over the base field and the twist non-residue
+    V2,
+    /// Same as `V1`, but points are compressed: X is followed by a flag with the parity of Y.
+    /// Points with coordinates in extensions of degree above 3 can not be decompressed
+    V3
+}
+
+impl AbiVersion {
+    pub(crate) fn point_encoding(&self) -> PointEncoding {
+        match self {
+            AbiVersion::V1 | AbiVersion::V2 => PointEncoding::Uncompressed,
+            AbiVersion::V3 => PointEncoding::Compressed
+        }
+    }
+}
+
+/// Strips the version prefix if there is one, inputs without it are of the first version
+pub(crate) fn split_abi_version(bytes: &[u8]) -> Result<(AbiVersion, &[u8]), ApiError> {
+    if bytes.first() != Some(&ABI_VERSION_PREFIX) {
+        return Ok((AbiVersion::V1, bytes));
+    }
+
+    let (version, rest) = split(&bytes[1..], ABI_VERSION_ENCODING_LENGTH, "Input should be longer than ABI version encoding")?;
+    match version[0] {
+        ABI_VERSION_1 => Ok((AbiVersion::V1, rest)),
+        ABI_VERSION_2 => Ok((AbiVersion::V2, rest)),
+        ABI_VERSION_3 => Ok((AbiVersion::V3, rest)),
+        _ => Err(ApiError::UnknownParameter(format!("Unsupported ABI version {}", version[0])))
+    }
+}
+
 fn is_even(x: &MaxFieldUint) -> bool {
     x.low_u64() & 1 == 0
 }
@@ -29,7 +80,7 @@ pub(crate) fn decode_group_order_with_length<
 
     let (length_encoding, rest) = split(bytes, BYTES_FOR_LENGTH_ENCODING, "Input is not long enough to get modulus length")?;
     let length = length_encoding[0] as usize;
-    if length == 0 {
+    if length < MIN_GROUP_BYTE_LEN {
         return Err(ApiError::InputError(format!("Encoded group length is zero, file {}, line {}", file!(), line!())));
     }
     if length > MAX_GROUP_BYTE_LEN {
@@ -86,6 +137,40 @@ pub(crate) fn decode_twist_type<
     Ok((twist_type, rest))
 }
 
+/// Same as `decode_twist_type`, also accepts `TWIST_TYPE_DETECT` that gives `None`
+pub(crate) fn decode_twist_type_or_detect<
+    'a
+    >
+    (
+        bytes: &'a [u8], 
+    ) -> Result<(Option<TwistType>, &'a [u8]), ApiError>
+{
+    if bytes.first() == Some(&TWIST_TYPE_DETECT) {
+        return Ok((None, &bytes[TWIST_TYPE_LENGTH..]));
+    }
+    let (twist_type, rest) = decode_twist_type(bytes)?;
+
+    Ok((Some(twist_type), rest))
+}
+
+/// Embedding degree of the Cocks-Pinch pairing, one byte
+pub(crate) fn decode_embedding_degree<
+    'a
+    >
+    (
+        bytes: &'a [u8], 
+        max_degree: usize
+    ) -> Result<(usize, &'a [u8]), ApiError>
+{
+    let (degree_encoding, rest) = split(bytes, EXTENSION_DEGREE_ENCODING_LENGTH, "Input is not long enough to get embedding degree")?;
+    let degree = degree_encoding[0] as usize;
+    if degree < MIN_COCKS_PINCH_EMBEDDING_DEGREE || degree > max_degree {
+        return Err(ApiError::InputError(format!("Embedding degree must be between {} and {}", MIN_COCKS_PINCH_EMBEDDING_DEGREE, max_degree)));
+    }
+
+    Ok((degree, rest))
+}
+
 pub(crate) fn decode_boolean<
     'a
     >
@@ -105,6 +190,70 @@ pub(crate) fn decode_boolean<
     Ok((boolean, rest))
 }
 
+/// Decodes the optional pairing mode byte that ends the list of pairs.
+/// Returns `true` if only the Miller loop should be computed
+pub(crate) fn decode_pairing_mode_is_miller_loop_only(bytes: &[u8]) -> Result<bool, ApiError> {
+    if bytes.is_empty() {
+        return Ok(false);
+    }
+    if bytes.len() != PAIRING_MODE_ENCODING_LENGTH {
+        return Err(ApiError::garbage_at_the_end(bytes));
+    }
+
+    match bytes[0] {
+        PAIRING_MODE_FULL => Ok(false),
+        PAIRING_MODE_MILLER_LOOP_ONLY => Ok(true),
+        _ => Err(ApiError::UnknownParameter("Unknown pairing mode".to_owned()))
+    }
+}
+
+/// Decodes the group of the public key of the BLS signature check.
+/// Returns `true` if public keys are in G1 and signatures are in G2
+pub(crate) fn decode_bls_public_key_in_g1(bytes: &[u8]) -> Result<(bool, &[u8]), ApiError> {
+    let (public_key_group, rest) = split(bytes, BLS_PUBLIC_KEY_GROUP_ENCODING_LENGTH, "Input is not long enough to get public key group")?;
+    let public_key_in_g1 = match public_key_group[0] {
+        BLS_PUBLIC_KEY_IN_G1 => true,
+        BLS_PUBLIC_KEY_IN_G2 => false,
+        _ => {
+            return Err(ApiError::UnknownParameter("Unknown public key group".to_owned()));
+        }
+    };
+
+    Ok((public_key_in_g1, rest))
+}
+
+pub(crate) fn encode_boolean(value: bool) -> Vec<u8> {
+    if value {
+        vec![BOOLEAN_TRUE]
+    } else {
+        vec![BOOLEAN_FALSE]
+    }
+}
+
+// Returns `None` for the point at infinity and whether Y is odd otherwise
+pub(crate) fn decode_compression_flag(bytes: &[u8]) -> Result<(Option<bool>, &[u8]), ApiError>
+{
+    let (flag, rest) = split(bytes, COMPRESSION_FLAG_LENGTH, "Input is not long enough to get compression flag")?;
+    let y_is_odd = match flag[0] {
+        COMPRESSED_Y_IS_EVEN => Some(false),
+        COMPRESSED_Y_IS_ODD => Some(true),
+        COMPRESSED_POINT_AT_INFINITY => None,
+        _ => {
+            return Err(ApiError::InputError("compression flag is not encoded properly".to_owned()));
+        },
+    };
+
+    Ok((y_is_odd, rest))
+}
+
+pub(crate) fn encode_compression_flag(y_is_odd: Option<bool>) -> u8 {
+    match y_is_odd {
+        Some(false) => COMPRESSED_Y_IS_EVEN,
+        Some(true) => COMPRESSED_Y_IS_ODD,
+        None => COMPRESSED_POINT_AT_INFINITY,
+    }
+}
+
 pub(crate) fn parse_modulus_and_length<
     'a
     >
@@ -117,7 +266,7 @@ pub(crate) fn parse_modulus_and_length<
     let (length_encoding, rest) = split(bytes, BYTES_FOR_LENGTH_ENCODING, "Input is not long enough to get modulus length")?;
     let length = length_encoding[0] as usize;
     if length > MAX_MODULUS_BYTE_LEN {
-        return Err(ApiError::InputError(format!("Encoded modulus length is too large, file {}, line {}", file!(), line!())));
+        return Err(ApiError::with_code(ErrorCode::InvalidModulus, format!("Encoded modulus length is too large, file {}, line {}", file!(), line!())));
     }
     let (be_encoding, rest) = split(rest, length, "Input is not long enough to get modulus")?;
     let x = MaxFieldUint::from_big_endian(&be_encoding);
@@ -165,25 +314,28 @@ pub(crate) fn get_base_field_params(bytes: &[u8]) -> Result<((MaxFieldUint, usiz
 
     let (modulus_len, rest) = split(bytes, BYTES_FOR_LENGTH_ENCODING, "Input is not long enough to get modulus length")?;
     let modulus_len = modulus_len[0] as usize;
-    if modulus_len == 0 {
-        return Err(ApiError::InputError(format!("Modulus is length is zero, file {}, line {}", file!(), line!())));
+    if modulus_len < MIN_MODULUS_BYTE_LEN {
+        return Err(ApiError::with_code(ErrorCode::InvalidModulus, format!("Modulus is length is zero, file {}, line {}", file!(), line!())));
     }
     if modulus_len > MAX_MODULUS_BYTE_LEN {
-        return Err(ApiError::InputError(format!("Encoded modulus length is too large, file {}, line {}", file!(), line!())));
+        return Err(ApiError::with_code(ErrorCode::InvalidModulus, format!("Encoded modulus length is too large, file {}, line {}", file!(), line!())));
     }
     let (modulus_encoding, rest) = split(rest, modulus_len, "Input is not long enough to get modulus")?;
     if modulus_encoding[0] == 0u8 {
-        return Err(ApiError::InputError(format!("In modulus encoding highest byte is zero, file {}, line {}", file!(), line!())));
+        return Err(ApiError::with_code(ErrorCode::InvalidModulus, format!("In modulus encoding highest byte is zero, file {}, line {}", file!(), line!())));
     }
     let modulus = MaxFieldUint::from_big_endian(&modulus_encoding);
     if modulus.is_zero() {
         return Err(ApiError::UnexpectedZero("Modulus can not be zero".to_owned()));
     }
     if is_even(&modulus) {
-        return Err(ApiError::InputError(format!("Modulus is even, file {}, line {}", file!(), line!())));
+        return Err(ApiError::with_code(ErrorCode::InvalidModulus, format!("Modulus is even, file {}, line {}", file!(), line!())));
+    }
+    if modulus < MaxFieldUint::from(MIN_MODULUS) {
+        return Err(ApiError::with_code(ErrorCode::InvalidModulus, format!("Modulus is less than {}, file {}, line {}", MIN_MODULUS, file!(), line!())));
     }
-    if modulus < MaxFieldUint::from(3u64) {
-        return Err(ApiError::InputError(format!("Modulus is less than 3, file {}, line {}", file!(), line!())));
+    if modulus.bits() > MAX_MODULUS_BIT_LEN {
+        return Err(ApiError::with_code(ErrorCode::InvalidModulus, format!("Modulus is longer than {} bits, file {}, line {}", MAX_MODULUS_BIT_LEN, file!(), line!())));
     }
 
     Ok(((modulus, modulus_len), rest))
@@ -193,7 +345,7 @@ pub(crate) fn num_limbs_for_modulus(modulus: &MaxFieldUint) -> Result<usize, Api
     use crate::field::calculate_num_limbs;
 
     let modulus_limbs = calculate_num_limbs(modulus.bits())
-        .map_err(|_| ApiError::InputError(format!("Modulus is too large, file {}, line {}", file!(), line!())) )?;
+        .map_err(|_| ApiError::with_code(ErrorCode::InvalidModulus, format!("Modulus is too large, file {}, line {}", file!(), line!())) )?;
 
     Ok(modulus_limbs)
 }
@@ -241,7 +393,7 @@ pub(crate) fn decode_loop_parameter_scalar_with_bit_limit<
     let (length_encoding, rest) = split(bytes, BYTES_FOR_LENGTH_ENCODING, "Input is not long enough to get modulus length")?;
     let max_length_for_bits = (bit_limit + 7) / 8;
     let length = length_encoding[0] as usize;
-    if length == 0 {
+    if length < MIN_LOOP_PARAMETERS_BYTE_LEN {
         return Err(ApiError::InputError(format!("Loop parameter scalar has zero length, file {}, line {}", file!(), line!())));
     }
     if length > max_length_for_bits {
@@ -261,3 +413,77 @@ pub(crate) fn decode_loop_parameter_scalar_with_bit_limit<
     Ok((x, rest))
 }
 
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_checked_length_arithmetic() {
+        assert_eq!(checked_len_mul(6, 128).unwrap(), 768);
+        assert_eq!(checked_len_add(768, 128).unwrap(), 896);
+
+        assert_eq!(checked_len_mul(usize::MAX / 2 + 1, 2), Err(ApiError::Overflow));
+        assert_eq!(checked_len_add(usize::MAX, 1), Err(ApiError::Overflow));
+        assert_eq!(checked_len_add(usize::MAX - 1, 1).unwrap(), usize::MAX);
+
+        // declared sizes of u32::MAX scale only overflow on 32-bit targets
+        let huge = u32::MAX as usize;
+        let product = checked_len_mul(huge, huge);
+        if cfg!(target_pointer_width = "64") {
+            assert_eq!(product.unwrap(), (u32::MAX as u64 * u32::MAX as u64) as usize);
+        } else {
+            assert_eq!(product, Err(ApiError::Overflow));
+        }
+    }
+
+    #[test]
+    fn test_abi_version_prefix() {
+        use crate::public_interface::API;
+
+        let input = vec![OPERATION_FIELD_ADD, 1, 97, 90, 10];
+        let (version, rest) = split_abi_version(&input).unwrap();
+        assert_eq!(version, AbiVersion::V1);
+        assert_eq!(rest, &input[..]);
+
+        let mut versioned = vec![ABI_VERSION_PREFIX, ABI_VERSION_1];
+        versioned.extend_from_slice(&input);
+        let (version, rest) = split_abi_version(&versioned).unwrap();
+        assert_eq!(version, AbiVersion::V1);
+        assert_eq!(rest, &input[..]);
+
+        assert_eq!(API::run(&versioned).unwrap(), vec![3]);
+        assert_eq!(API::run(&versioned).unwrap(), API::run(&input).unwrap());
+        #[cfg(feature = "gas_metering")]
+        assert_eq!(API::estimate_gas(&versioned).unwrap(), API::estimate_gas(&input).unwrap());
+
+        assert!(split_abi_version(&[ABI_VERSION_PREFIX]).is_err());
+        versioned[1] = ABI_VERSION_2;
+        assert_eq!(split_abi_version(&versioned).unwrap().0, AbiVersion::V2);
+        assert_eq!(API::run(&versioned).unwrap(), vec![3]);
+        // field operations have no points, so compression doesn't change them
+        versioned[1] = ABI_VERSION_3;
+        assert_eq!(split_abi_version(&versioned).unwrap().0, AbiVersion::V3);
+        assert_eq!(API::run(&versioned).unwrap(), vec![3]);
+        #[cfg(feature = "gas_metering")]
+        assert_eq!(API::estimate_gas(&versioned).unwrap(), API::estimate_gas(&input).unwrap());
+        versioned[1] = 0x04;
+        assert!(split_abi_version(&versioned).is_err());
+        assert!(API::run(&versioned).is_err());
+        assert!(split_abi_version(&[]).is_ok());
+    }
+
+    #[test]
+    fn test_pair_length_overflow_is_an_error() {
+        // pair length and count as computed for multiexp length validation
+        let pair_len = |modulus_len: usize, order_len: usize, num_pairs: usize| -> Result<usize, ApiError> {
+            let expected_pair_len = checked_len_add(checked_len_mul(6, modulus_len)?, order_len)?;
+            checked_len_mul(expected_pair_len, num_pairs)
+        };
+
+        assert_eq!(pair_len(MAX_MODULUS_BYTE_LEN, MAX_GROUP_BYTE_LEN, 255).unwrap(), (6 * MAX_MODULUS_BYTE_LEN + MAX_GROUP_BYTE_LEN) * 255);
+        assert_eq!(pair_len(usize::MAX / 6 + 1, 1, 1), Err(ApiError::Overflow));
+        assert_eq!(pair_len(usize::MAX / 6, 6, 1), Err(ApiError::Overflow));
+        assert_eq!(pair_len(usize::MAX / 12, 1, 3), Err(ApiError::Overflow));
+    }
+}
@@ -3,43 +3,15 @@ pub const EIP196_PREALLOCATE_FOR_ERROR_BYTES: usize = 256;
 pub const EIP196_PREALLOCATE_FOR_RESULT_BYTES: usize = 32 * 2; // maximum for G2 point
 
 use static_assertions::const_assert;
+use crate::errors::catch_internal_error;
 const_assert!(EIP196_PREALLOCATE_FOR_RESULT_BYTES == super::SERIALIZED_G1_POINT_BYTE_LENGTH);
 
-#[allow(non_camel_case_types)]
-#[repr(u8)]
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub enum Eip196OperationType {
-    ADD = 1,
-    MUL = 2,
-    PAIR = 3,
-}
-
-impl Eip196OperationType {
-    pub fn from_u8(value: u8) -> Option<Self> {
-        match value {
-            EIP196_ADD_OPERATION_RAW_VALUE => {
-                Some(Eip196OperationType::ADD)
-            },
-            EIP196_MUL_OPERATION_RAW_VALUE => {
-                Some(Eip196OperationType::MUL)
-            },
-            EIP196_PAIR_OPERATION_RAW_VALUE => {
-                Some(Eip196OperationType::PAIR)
-            },
-            _ => {
-                None
-            }
-        }
-    }
-
-    pub fn as_u8(&self) -> u8 {
-        *self as u8
-    }
-}
-
-pub const EIP196_ADD_OPERATION_RAW_VALUE: u8 = Eip196OperationType::ADD as u8;
-pub const EIP196_MUL_OPERATION_RAW_VALUE: u8 = Eip196OperationType::MUL as u8;
-pub const EIP196_PAIR_OPERATION_RAW_VALUE: u8 = Eip196OperationType::PAIR as u8;
+pub use super::{
+    Eip196OperationType,
+    EIP196_ADD_OPERATION_RAW_VALUE,
+    EIP196_MUL_OPERATION_RAW_VALUE,
+    EIP196_PAIR_OPERATION_RAW_VALUE,
+};
 
 // this is C interface
 #[no_mangle]
@@ -60,18 +32,19 @@ pub extern "C" fn eip196_perform_operation(
 
     let operation = Eip196OperationType::from_u8(op_u8);
 
-    if operation.is_none() {
-        let written = err_out.write(b"Unknown operation type\0");
-        if let Ok(bytes_written) = written {
-            unsafe { *char_len = bytes_written as u32 };
-        } else {
-            unsafe { *char_len = 0u32 };
-        }
-
-        return 1u32;
-    }
+    let operation = match operation {
+        Some(operation) => operation,
+        None => {
+            let written = err_out.write(b"Unknown operation type\0");
+            if let Ok(bytes_written) = written {
+                unsafe { *char_len = bytes_written as u32 };
+            } else {
+                unsafe { *char_len = 0u32 };
+            }
 
-    let operation = operation.expect("is some");
+            return 1u32;
+        }
+    };
     
     let input_i8: & [i8] = unsafe { std::slice::from_raw_parts(i, i_len as usize) };
     let input: &[u8] = unsafe { std::mem::transmute(input_i8) };
@@ -79,11 +52,7 @@ pub extern "C" fn eip196_perform_operation(
     let raw_out_i8: &mut [i8] = unsafe { std::slice::from_raw_parts_mut(o, EIP196_PREALLOCATE_FOR_RESULT_BYTES) };
     let mut raw_out: &mut [u8] = unsafe { std::mem::transmute(raw_out_i8) };
 
-    let result = match operation {
-        Eip196OperationType::ADD => super::EIP196Executor::add(&input).map(|r| r[..].to_vec()),
-        Eip196OperationType::MUL => super::EIP196Executor::mul(&input).map(|r| r[..].to_vec()),
-        Eip196OperationType::PAIR => super::EIP196Executor::pair(&input).map(|r| r[..].to_vec()),
-    };
+    let result = catch_internal_error(|| super::perform_operation(operation, &input));
 
     match result {
         Ok(result) => {
@@ -2,6 +2,7 @@ pub struct EIP196Executor;
 
 use crate::engines::bn254::*;
 use crate::public_interface::ApiError;
+use crate::errors::ErrorCode;
 
 pub const SCALAR_BYTE_LENGTH: usize = 32;
 
@@ -22,6 +23,51 @@ use crate::pairings::PairingEngine;
 #[cfg(feature = "eip_196_c_api")]
 pub mod c_api;
 
+#[allow(non_camel_case_types)]
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Eip196OperationType {
+    ADD = 1,
+    MUL = 2,
+    PAIR = 3,
+}
+
+impl Eip196OperationType {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            EIP196_ADD_OPERATION_RAW_VALUE => {
+                Some(Eip196OperationType::ADD)
+            },
+            EIP196_MUL_OPERATION_RAW_VALUE => {
+                Some(Eip196OperationType::MUL)
+            },
+            EIP196_PAIR_OPERATION_RAW_VALUE => {
+                Some(Eip196OperationType::PAIR)
+            },
+            _ => {
+                None
+            }
+        }
+    }
+
+    pub fn as_u8(&self) -> u8 {
+        *self as u8
+    }
+}
+
+pub const EIP196_ADD_OPERATION_RAW_VALUE: u8 = Eip196OperationType::ADD as u8;
+pub const EIP196_MUL_OPERATION_RAW_VALUE: u8 = Eip196OperationType::MUL as u8;
+pub const EIP196_PAIR_OPERATION_RAW_VALUE: u8 = Eip196OperationType::PAIR as u8;
+
+// This is pure rust API
+pub fn perform_operation(operation: Eip196OperationType, input: &[u8]) -> Result<Vec<u8>, ApiError> {
+    match operation {
+        Eip196OperationType::ADD => EIP196Executor::add(&input).map(|r| r[..].to_vec()),
+        Eip196OperationType::MUL => EIP196Executor::mul(&input).map(|r| r[..].to_vec()),
+        Eip196OperationType::PAIR => EIP196Executor::pair(&input).map(|r| r[..].to_vec()),
+    }
+}
+
 fn pairing_result_false() -> [u8; SERIALIZED_PAIRING_RESULT_BYTE_LENGTH] {
     [0u8; SERIALIZED_PAIRING_RESULT_BYTE_LENGTH]
 }
@@ -73,12 +119,12 @@ impl EIP196Executor {
 
         if !p_0.is_on_curve() {
             if !crate::features::in_fuzzing_or_gas_metering() {
-                return Err(ApiError::InputError(format!("Point 0 is not on curve, file {}, line {}", file!(), line!())));
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point 0 is not on curve, file {}, line {}", file!(), line!())));
             }
         }
         if !p_1.is_on_curve() {
             if !crate::features::in_fuzzing_or_gas_metering() {
-                return Err(ApiError::InputError(format!("Point 1 is not on curve, file {}, line {}", file!(), line!())));
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point 1 is not on curve, file {}, line {}", file!(), line!())));
             }
         }
 
@@ -101,7 +147,7 @@ impl EIP196Executor {
 
         if !p_0.is_on_curve() {
             if !crate::features::in_fuzzing_or_gas_metering() {
-                return Err(ApiError::InputError(format!("Point is not on curve, file {}, line {}", file!(), line!())));
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point is not on curve, file {}, line {}", file!(), line!())));
             }
         }
 
@@ -162,13 +208,13 @@ impl EIP196Executor {
 
             if !g1.is_on_curve() {
                 if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError("G1 point is not on curve".to_owned()));
+                    return Err(ApiError::with_code(ErrorCode::NotOnCurve, "G1 point is not on curve".to_owned()));
                 }
             }
 
             if !g2.is_on_curve() {
                 if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError("G2 point is not on curve".to_owned()));
+                    return Err(ApiError::with_code(ErrorCode::NotOnCurve, "G2 point is not on curve".to_owned()));
                 }
             }
 
@@ -178,7 +224,7 @@ impl EIP196Executor {
             // "fast" subgroup checks using empirical data
             if g2.wnaf_mul_with_window_size(&BN254_SUBGROUP_ORDER[..], 5).is_zero() == false {
                 if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError("G2 point is not in the expected subgroup".to_owned()));
+                    return Err(ApiError::with_code(ErrorCode::NotInSubgroup, "G2 point is not in the expected subgroup".to_owned()));
                 }
             }
 
@@ -196,17 +242,14 @@ impl EIP196Executor {
 
         let engine = &*BN254_PAIRING_ENGINE;
 
-        let pairing_result = engine.pair(&g1_points, &g2_points);
-
-        if pairing_result.is_none() {
-            return Err(ApiError::UnknownParameter("Pairing engine returned no value".to_owned()));
-        }
+        let pairing_result = engine.pair(&g1_points, &g2_points).ok_or_else(|| {
+            ApiError::UnknownParameter("Pairing engine returned no value".to_owned())
+        })?;
 
         use crate::extension_towers::fp12_as_2_over3_over_2::Fp12;
         use crate::traits::ZeroAndOne;
 
         let one_fp12 = Fp12::one(&*BN254_EXT12_FIELD);
-        let pairing_result = pairing_result.unwrap();
         let result = if pairing_result == one_fp12 {
             pairing_result_true()
         } else {
@@ -215,4 +258,87 @@ impl EIP196Executor {
 
         Ok(result)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // 2 * (1, 2)
+    const DOUBLED_GENERATOR: &str = "030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd315ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4";
+
+    fn g1_generator_encoding() -> Vec<u8> {
+        decode_g1::serialize_g1_point(SERIALIZED_FP_BYTE_LENGTH, &*BN254_G1_GENERATOR).unwrap()
+    }
+
+    // EIP-197 encodes Fp2 elements as (c1, c0)
+    fn g2_generator_encoding() -> Vec<u8> {
+        let encoding = decode_g2::serialize_g2_point_in_fp2(SERIALIZED_FP_BYTE_LENGTH, &*BN254_G2_GENERATOR).unwrap();
+        let mut swapped = vec![];
+        for chunk in encoding.chunks(SERIALIZED_FP2_BYTE_LENGTH) {
+            swapped.extend_from_slice(&chunk[SERIALIZED_FP_BYTE_LENGTH..]);
+            swapped.extend_from_slice(&chunk[..SERIALIZED_FP_BYTE_LENGTH]);
+        }
+
+        swapped
+    }
+
+    #[test]
+    fn test_add_and_mul_vectors() {
+        let g1 = g1_generator_encoding();
+        let mut expected_g1 = vec![0u8; SERIALIZED_FP_BYTE_LENGTH * 2];
+        expected_g1[SERIALIZED_FP_BYTE_LENGTH - 1] = 1;
+        expected_g1[SERIALIZED_G1_POINT_BYTE_LENGTH - 1] = 2;
+        assert_eq!(g1, expected_g1);
+
+        let add_input = [g1.clone(), g1.clone()].concat();
+        let result = perform_operation(Eip196OperationType::ADD, &add_input).unwrap();
+        assert_eq!(hex::encode(&result), DOUBLED_GENERATOR);
+
+        let mut mul_input = g1.clone();
+        mul_input.extend(vec![0u8; SCALAR_BYTE_LENGTH - 1]);
+        mul_input.push(2u8);
+        let result = perform_operation(Eip196OperationType::MUL, &mul_input).unwrap();
+        assert_eq!(hex::encode(&result), DOUBLED_GENERATOR);
+    }
+
+    #[test]
+    fn test_short_and_long_inputs() {
+        // inputs are padded with zeroes, so empty input is a sum of two points at infinity
+        let result = perform_operation(Eip196OperationType::ADD, &[]).unwrap();
+        assert_eq!(result, vec![0u8; SERIALIZED_G1_POINT_BYTE_LENGTH]);
+
+        // multiplication by missing (zero) scalar
+        let result = perform_operation(Eip196OperationType::MUL, &g1_generator_encoding()).unwrap();
+        assert_eq!(result, vec![0u8; SERIALIZED_G1_POINT_BYTE_LENGTH]);
+
+        // extra bytes are ignored
+        let mut add_input = [g1_generator_encoding(), g1_generator_encoding()].concat();
+        add_input.extend(vec![0xffu8; 10]);
+        let result = perform_operation(Eip196OperationType::ADD, &add_input).unwrap();
+        assert_eq!(hex::encode(&result), DOUBLED_GENERATOR);
+
+        let mut not_on_curve = g1_generator_encoding();
+        not_on_curve[SERIALIZED_G1_POINT_BYTE_LENGTH - 1] = 3;
+        assert!(perform_operation(Eip196OperationType::MUL, &not_on_curve).is_err());
+    }
+
+    #[test]
+    fn test_pairing_vectors() {
+        assert_eq!(perform_operation(Eip196OperationType::PAIR, &[]).unwrap(), pairing_result_true().to_vec());
+
+        let g2 = g2_generator_encoding();
+        let mut minus_g1 = BN254_G1_GENERATOR.clone();
+        minus_g1.negate();
+        let minus_g1 = decode_g1::serialize_g1_point(SERIALIZED_FP_BYTE_LENGTH, &minus_g1).unwrap();
+
+        // e(P, Q) * e(-P, Q) == 1
+        let input = [g1_generator_encoding(), g2.clone(), minus_g1, g2.clone()].concat();
+        assert_eq!(perform_operation(Eip196OperationType::PAIR, &input).unwrap(), pairing_result_true().to_vec());
+
+        let input = [g1_generator_encoding(), g2.clone()].concat();
+        assert_eq!(perform_operation(Eip196OperationType::PAIR, &input).unwrap(), pairing_result_false().to_vec());
+
+        assert!(perform_operation(Eip196OperationType::PAIR, &input[1..]).is_err());
+    }
+}
@@ -3,75 +3,21 @@ pub const EIP2537_PREALLOCATE_FOR_ERROR_BYTES: usize = 256;
 pub const EIP2537_PREALLOCATE_FOR_RESULT_BYTES: usize = 64 * 2 * 2; // maximum for G2 point
 
 use static_assertions::const_assert;
+use crate::errors::catch_internal_error;
 const_assert!(EIP2537_PREALLOCATE_FOR_RESULT_BYTES == super::SERIALIZED_G2_POINT_BYTE_LENGTH);
 
-#[allow(non_camel_case_types)]
-#[repr(u8)]
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub enum Eip2537OperationType {
-    BLS12_G1ADD = 1,
-    BLS12_G1MUL = 2,
-    BLS12_G1MULTIEXP = 3,
-    BLS12_G2ADD = 4,
-    BLS12_G2MUL = 5,
-    BLS12_G2MULTIEXP = 6,
-    BLS12_PAIR = 7,
-    BLS12_FP_TO_G1 = 8,
-    BLS12_FP2_TO_G2 = 9,
-}
-
-impl Eip2537OperationType {
-    pub fn from_u8(value: u8) -> Option<Self> {
-        match value {
-            BLS12_G1ADD_OPERATION_RAW_VALUE => {
-                Some(Eip2537OperationType::BLS12_G1ADD)
-            },
-            BLS12_G1MUL_OPERATION_RAW_VALUE => {
-                Some(Eip2537OperationType::BLS12_G1MUL)
-            },
-            BLS12_G1MULTIEXP_OPERATION_RAW_VALUE => {
-                Some(Eip2537OperationType::BLS12_G1MULTIEXP)
-            },
-            BLS12_G2ADD_OPERATION_RAW_VALUE => {
-                Some(Eip2537OperationType::BLS12_G2ADD)
-            },
-            BLS12_G2MUL_OPERATION_RAW_VALUE => {
-                Some(Eip2537OperationType::BLS12_G2MUL)
-            },
-            BLS12_G2MULTIEXP_OPERATION_RAW_VALUE => {
-                Some(Eip2537OperationType::BLS12_G2MULTIEXP)
-            },
-            BLS12_PAIR_OPERATION_RAW_VALUE => {
-                Some(Eip2537OperationType::BLS12_PAIR)
-            },
-            BLS12_MAP_FP_TO_G1_OPERATION_RAW_VALUE => {
-                Some(Eip2537OperationType::BLS12_FP_TO_G1)
-            },
-            BLS12_MAP_FP2_TO_G2_OPERATION_RAW_VALUE => {
-                Some(Eip2537OperationType::BLS12_FP2_TO_G2)
-            },
-            _ => {
-                None
-            }
-        }
-    }
-
-    pub fn as_u8(&self) -> u8 {
-        *self as u8
-    }
-}
-
-pub const BLS12_G1ADD_OPERATION_RAW_VALUE: u8 = Eip2537OperationType::BLS12_G1ADD as u8;
-pub const BLS12_G1MUL_OPERATION_RAW_VALUE: u8 = Eip2537OperationType::BLS12_G1MUL as u8;
-pub const BLS12_G1MULTIEXP_OPERATION_RAW_VALUE: u8 = Eip2537OperationType::BLS12_G1MULTIEXP as u8;
-
-pub const BLS12_G2ADD_OPERATION_RAW_VALUE: u8 = Eip2537OperationType::BLS12_G2ADD as u8;
-pub const BLS12_G2MUL_OPERATION_RAW_VALUE: u8 = Eip2537OperationType::BLS12_G2MUL as u8;
-pub const BLS12_G2MULTIEXP_OPERATION_RAW_VALUE: u8 = Eip2537OperationType::BLS12_G2MULTIEXP as u8;
-
-pub const BLS12_PAIR_OPERATION_RAW_VALUE: u8 = Eip2537OperationType::BLS12_PAIR as u8;
-pub const BLS12_MAP_FP_TO_G1_OPERATION_RAW_VALUE: u8 = Eip2537OperationType::BLS12_FP_TO_G1 as u8;
-pub const BLS12_MAP_FP2_TO_G2_OPERATION_RAW_VALUE: u8 = Eip2537OperationType::BLS12_FP2_TO_G2 as u8;
+pub use super::{
+    Eip2537OperationType,
+    BLS12_G1ADD_OPERATION_RAW_VALUE,
+    BLS12_G1MUL_OPERATION_RAW_VALUE,
+    BLS12_G1MULTIEXP_OPERATION_RAW_VALUE,
+    BLS12_G2ADD_OPERATION_RAW_VALUE,
+    BLS12_G2MUL_OPERATION_RAW_VALUE,
+    BLS12_G2MULTIEXP_OPERATION_RAW_VALUE,
+    BLS12_PAIR_OPERATION_RAW_VALUE,
+    BLS12_MAP_FP_TO_G1_OPERATION_RAW_VALUE,
+    BLS12_MAP_FP2_TO_G2_OPERATION_RAW_VALUE,
+};
 
 // this is C interface
 #[no_mangle]
@@ -92,18 +38,19 @@ pub extern "C" fn eip2537_perform_operation(
 
     let operation = Eip2537OperationType::from_u8(op_u8);
 
-    if operation.is_none() {
-        let written = err_out.write(b"Unknown operation type\0");
-        if let Ok(bytes_written) = written {
-            unsafe { *char_len = bytes_written as u32 };
-        } else {
-            unsafe { *char_len = 0u32 };
-        }
-
-        return 1u32;
-    }
+    let operation = match operation {
+        Some(operation) => operation,
+        None => {
+            let written = err_out.write(b"Unknown operation type\0");
+            if let Ok(bytes_written) = written {
+                unsafe { *char_len = bytes_written as u32 };
+            } else {
+                unsafe { *char_len = 0u32 };
+            }
 
-    let operation = operation.expect("is some");
+            return 1u32;
+        }
+    };
     
     let input_i8: & [i8] = unsafe { std::slice::from_raw_parts(i, i_len as usize) };
     let input: &[u8] = unsafe { std::mem::transmute(input_i8) };
@@ -111,17 +58,7 @@ pub extern "C" fn eip2537_perform_operation(
     let raw_out_i8: &mut [i8] = unsafe { std::slice::from_raw_parts_mut(o, EIP2537_PREALLOCATE_FOR_RESULT_BYTES) };
     let mut raw_out: &mut [u8] = unsafe { std::mem::transmute(raw_out_i8) };
 
-    let result = match operation {
-        Eip2537OperationType::BLS12_G1ADD => super::EIP2537Executor::g1_add(&input).map(|r| r[..].to_vec()),
-        Eip2537OperationType::BLS12_G1MUL => super::EIP2537Executor::g1_mul(&input).map(|r| r[..].to_vec()),
-        Eip2537OperationType::BLS12_G1MULTIEXP => super::EIP2537Executor::g1_multiexp(&input).map(|r| r[..].to_vec()),
-        Eip2537OperationType::BLS12_G2ADD => super::EIP2537Executor::g2_add(&input).map(|r| r[..].to_vec()),
-        Eip2537OperationType::BLS12_G2MUL => super::EIP2537Executor::g2_mul(&input).map(|r| r[..].to_vec()),
-        Eip2537OperationType::BLS12_G2MULTIEXP => super::EIP2537Executor::g2_multiexp(&input).map(|r| r[..].to_vec()),
-        Eip2537OperationType::BLS12_PAIR => super::EIP2537Executor::pair(&input).map(|r| r[..].to_vec()),
-        Eip2537OperationType::BLS12_FP_TO_G1 => super::EIP2537Executor::map_fp_to_g1(&input).map(|r| r[..].to_vec()),
-        Eip2537OperationType::BLS12_FP2_TO_G2 => super::EIP2537Executor::map_fp2_to_g2(&input).map(|r| r[..].to_vec()),
-    };
+    let result = catch_internal_error(|| super::perform_operation(operation, &input));
 
     match result {
         Ok(result) => {
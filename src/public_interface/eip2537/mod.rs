@@ -2,6 +2,7 @@ pub struct EIP2537Executor;
 
 use crate::engines::bls12_381::{self, mapping};
 use crate::public_interface::ApiError;
+use crate::errors::ErrorCode;
 
 pub const SCALAR_BYTE_LENGTH: usize = 32;
 
@@ -19,11 +20,95 @@ use crate::public_interface::decode_g2;
 
 use crate::weierstrass::Group;
 use crate::multiexp::peppinger;
+use crate::execution_context::ExecutionContext;
 use crate::pairings::PairingEngine;
 
 #[cfg(feature = "eip_2357_c_api")]
 pub mod c_api;
 
+#[allow(non_camel_case_types)]
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Eip2537OperationType {
+    BLS12_G1ADD = 1,
+    BLS12_G1MUL = 2,
+    BLS12_G1MULTIEXP = 3,
+    BLS12_G2ADD = 4,
+    BLS12_G2MUL = 5,
+    BLS12_G2MULTIEXP = 6,
+    BLS12_PAIR = 7,
+    BLS12_FP_TO_G1 = 8,
+    BLS12_FP2_TO_G2 = 9,
+}
+
+impl Eip2537OperationType {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            BLS12_G1ADD_OPERATION_RAW_VALUE => {
+                Some(Eip2537OperationType::BLS12_G1ADD)
+            },
+            BLS12_G1MUL_OPERATION_RAW_VALUE => {
+                Some(Eip2537OperationType::BLS12_G1MUL)
+            },
+            BLS12_G1MULTIEXP_OPERATION_RAW_VALUE => {
+                Some(Eip2537OperationType::BLS12_G1MULTIEXP)
+            },
+            BLS12_G2ADD_OPERATION_RAW_VALUE => {
+                Some(Eip2537OperationType::BLS12_G2ADD)
+            },
+            BLS12_G2MUL_OPERATION_RAW_VALUE => {
+                Some(Eip2537OperationType::BLS12_G2MUL)
+            },
+            BLS12_G2MULTIEXP_OPERATION_RAW_VALUE => {
+                Some(Eip2537OperationType::BLS12_G2MULTIEXP)
+            },
+            BLS12_PAIR_OPERATION_RAW_VALUE => {
+                Some(Eip2537OperationType::BLS12_PAIR)
+            },
+            BLS12_MAP_FP_TO_G1_OPERATION_RAW_VALUE => {
+                Some(Eip2537OperationType::BLS12_FP_TO_G1)
+            },
+            BLS12_MAP_FP2_TO_G2_OPERATION_RAW_VALUE => {
+                Some(Eip2537OperationType::BLS12_FP2_TO_G2)
+            },
+            _ => {
+                None
+            }
+        }
+    }
+
+    pub fn as_u8(&self) -> u8 {
+        *self as u8
+    }
+}
+
+pub const BLS12_G1ADD_OPERATION_RAW_VALUE: u8 = Eip2537OperationType::BLS12_G1ADD as u8;
+pub const BLS12_G1MUL_OPERATION_RAW_VALUE: u8 = Eip2537OperationType::BLS12_G1MUL as u8;
+pub const BLS12_G1MULTIEXP_OPERATION_RAW_VALUE: u8 = Eip2537OperationType::BLS12_G1MULTIEXP as u8;
+
+pub const BLS12_G2ADD_OPERATION_RAW_VALUE: u8 = Eip2537OperationType::BLS12_G2ADD as u8;
+pub const BLS12_G2MUL_OPERATION_RAW_VALUE: u8 = Eip2537OperationType::BLS12_G2MUL as u8;
+pub const BLS12_G2MULTIEXP_OPERATION_RAW_VALUE: u8 = Eip2537OperationType::BLS12_G2MULTIEXP as u8;
+
+pub const BLS12_PAIR_OPERATION_RAW_VALUE: u8 = Eip2537OperationType::BLS12_PAIR as u8;
+pub const BLS12_MAP_FP_TO_G1_OPERATION_RAW_VALUE: u8 = Eip2537OperationType::BLS12_FP_TO_G1 as u8;
+pub const BLS12_MAP_FP2_TO_G2_OPERATION_RAW_VALUE: u8 = Eip2537OperationType::BLS12_FP2_TO_G2 as u8;
+
+// This is pure rust API
+pub fn perform_operation(operation: Eip2537OperationType, input: &[u8]) -> Result<Vec<u8>, ApiError> {
+    match operation {
+        Eip2537OperationType::BLS12_G1ADD => EIP2537Executor::g1_add(&input).map(|r| r[..].to_vec()),
+        Eip2537OperationType::BLS12_G1MUL => EIP2537Executor::g1_mul(&input).map(|r| r[..].to_vec()),
+        Eip2537OperationType::BLS12_G1MULTIEXP => EIP2537Executor::g1_multiexp(&input).map(|r| r[..].to_vec()),
+        Eip2537OperationType::BLS12_G2ADD => EIP2537Executor::g2_add(&input).map(|r| r[..].to_vec()),
+        Eip2537OperationType::BLS12_G2MUL => EIP2537Executor::g2_mul(&input).map(|r| r[..].to_vec()),
+        Eip2537OperationType::BLS12_G2MULTIEXP => EIP2537Executor::g2_multiexp(&input).map(|r| r[..].to_vec()),
+        Eip2537OperationType::BLS12_PAIR => EIP2537Executor::pair(&input).map(|r| r[..].to_vec()),
+        Eip2537OperationType::BLS12_FP_TO_G1 => EIP2537Executor::map_fp_to_g1(&input).map(|r| r[..].to_vec()),
+        Eip2537OperationType::BLS12_FP2_TO_G2 => EIP2537Executor::map_fp2_to_g2(&input).map(|r| r[..].to_vec()),
+    }
+}
+
 fn pairing_result_false() -> [u8; SERIALIZED_PAIRING_RESULT_BYTE_LENGTH] {
     [0u8; SERIALIZED_PAIRING_RESULT_BYTE_LENGTH]
 }
@@ -46,12 +131,12 @@ impl EIP2537Executor {
 
         if !p_0.is_on_curve() {
             if !crate::features::in_fuzzing_or_gas_metering() {
-                return Err(ApiError::InputError(format!("Point 0 is not on curve, file {}, line {}", file!(), line!())));
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point 0 is not on curve, file {}, line {}", file!(), line!())));
             }
         }
         if !p_1.is_on_curve() {
             if !crate::features::in_fuzzing_or_gas_metering() {
-                return Err(ApiError::InputError(format!("Point 1 is not on curve, file {}, line {}", file!(), line!())));
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point 1 is not on curve, file {}, line {}", file!(), line!())));
             }
         }
 
@@ -76,7 +161,7 @@ impl EIP2537Executor {
 
         if !p_0.is_on_curve() {
             if !crate::features::in_fuzzing_or_gas_metering() {
-                return Err(ApiError::InputError(format!("Point is not on curve, file {}, line {}", file!(), line!())));
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point is not on curve, file {}, line {}", file!(), line!())));
             }
         }
 
@@ -110,7 +195,7 @@ impl EIP2537Executor {
             let (scalar, local_rest) = decode_g1::decode_scalar_representation(local_rest, SCALAR_BYTE_LENGTH)?;
             if !p.is_on_curve() {
                 if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError(format!("Point is not on curve, file {}, line {}", file!(), line!())));
+                    return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point is not on curve, file {}, line {}", file!(), line!())));
                 }
             }
             bases.push(p);
@@ -122,7 +207,7 @@ impl EIP2537Executor {
             return Err(ApiError::InputError(format!("Multiexp with empty input pairs, file {}, line {}", file!(), line!())));
         } 
 
-        let result = peppinger(&bases, scalars);
+        let result = peppinger(&bases, scalars, &ExecutionContext::default());
 
         let mut output = [0u8; SERIALIZED_G1_POINT_BYTE_LENGTH];
 
@@ -143,12 +228,12 @@ impl EIP2537Executor {
 
         if !p_0.is_on_curve() {
             if !crate::features::in_fuzzing_or_gas_metering() {
-                return Err(ApiError::InputError(format!("Point 0 is not on curve, file {}, line {}", file!(), line!())));
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point 0 is not on curve, file {}, line {}", file!(), line!())));
             }
         }
         if !p_1.is_on_curve() {
             if !crate::features::in_fuzzing_or_gas_metering() {
-                return Err(ApiError::InputError(format!("Point 1 is not on curve, file {}, line {}", file!(), line!())));
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point 1 is not on curve, file {}, line {}", file!(), line!())));
             }
         }
 
@@ -173,7 +258,7 @@ impl EIP2537Executor {
 
         if !p_0.is_on_curve() {
             if !crate::features::in_fuzzing_or_gas_metering() {
-                return Err(ApiError::InputError(format!("Point is not on curve, file {}, line {}", file!(), line!())));
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point is not on curve, file {}, line {}", file!(), line!())));
             }
         }
 
@@ -207,7 +292,7 @@ impl EIP2537Executor {
             let (scalar, local_rest) = decode_g1::decode_scalar_representation(local_rest, SCALAR_BYTE_LENGTH)?;
             if !p.is_on_curve() {
                 if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError(format!("Point is not on curve, file {}, line {}", file!(), line!())));
+                    return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point is not on curve, file {}, line {}", file!(), line!())));
                 }
             }
             bases.push(p);
@@ -219,7 +304,7 @@ impl EIP2537Executor {
             return Err(ApiError::InputError(format!("Multiexp with empty input pairs, file {}, line {}", file!(), line!())));
         } 
 
-        let result = peppinger(&bases, scalars);
+        let result = peppinger(&bases, scalars, &ExecutionContext::default());
 
         let mut output = [0u8; SERIALIZED_G2_POINT_BYTE_LENGTH];
 
@@ -253,25 +338,25 @@ impl EIP2537Executor {
 
             if !g1.is_on_curve() {
                 if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError("G1 point is not on curve".to_owned()));
+                    return Err(ApiError::with_code(ErrorCode::NotOnCurve, "G1 point is not on curve".to_owned()));
                 }
             }
 
             if !g2.is_on_curve() {
                 if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError("G2 point is not on curve".to_owned()));
+                    return Err(ApiError::with_code(ErrorCode::NotOnCurve, "G2 point is not on curve".to_owned()));
                 }
             }
             // "fast" subgroup checks using empirical data
             if g1.wnaf_mul_with_window_size(&bls12_381::BLS12_381_SUBGROUP_ORDER[..], 5).is_zero() == false {
                 if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError("G1 point is not in the expected subgroup".to_owned()));
+                    return Err(ApiError::with_code(ErrorCode::NotInSubgroup, "G1 point is not in the expected subgroup".to_owned()));
                 }
             }
 
             if g2.wnaf_mul_with_window_size(&bls12_381::BLS12_381_SUBGROUP_ORDER[..], 5).is_zero() == false {
                 if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError("G2 point is not in the expected subgroup".to_owned()));
+                    return Err(ApiError::with_code(ErrorCode::NotInSubgroup, "G2 point is not in the expected subgroup".to_owned()));
                 }
             }
 
@@ -289,17 +374,14 @@ impl EIP2537Executor {
 
         let engine = &bls12_381::BLS12_381_PAIRING_ENGINE;
 
-        let pairing_result = engine.pair(&g1_points, &g2_points);
-
-        if pairing_result.is_none() {
-            return Err(ApiError::UnknownParameter("Pairing engine returned no value".to_owned()));
-        }
+        let pairing_result = engine.pair(&g1_points, &g2_points).ok_or_else(|| {
+            ApiError::UnknownParameter("Pairing engine returned no value".to_owned())
+        })?;
 
         use crate::extension_towers::fp12_as_2_over3_over_2::Fp12;
         use crate::traits::ZeroAndOne;
 
         let one_fp12 = Fp12::one(&bls12_381::BLS12_381_EXTENSION_12_FIELD);
-        let pairing_result = pairing_result.unwrap();
         let result = if pairing_result == one_fp12 {
             pairing_result_true()
         } else {
@@ -612,6 +694,72 @@ mod test {
     const MULTIEXP_INPUT: usize = 16;
     const WRITE_VECTORS: bool = true;
 
+    #[test]
+    fn test_perform_operation_routes_to_executor() {
+        for raw in 1u8..=9 {
+            let operation = Eip2537OperationType::from_u8(raw).unwrap();
+            assert_eq!(operation.as_u8(), raw);
+        }
+        assert!(Eip2537OperationType::from_u8(0).is_none());
+        assert!(Eip2537OperationType::from_u8(10).is_none());
+
+        let g1 = decode_g1::serialize_g1_point(SERIALIZED_FP_BYTE_LENGTH, &bls12_381::BLS12_381_G1_GENERATOR).unwrap();
+        let g2 = decode_g2::serialize_g2_point_in_fp2(SERIALIZED_FP_BYTE_LENGTH, &bls12_381::BLS12_381_G2_GENERATOR).unwrap();
+        let mut scalar = vec![0u8; SCALAR_BYTE_LENGTH];
+        scalar[SCALAR_BYTE_LENGTH - 1] = 3;
+
+        let g1_add_input = [g1.clone(), g1.clone()].concat();
+        assert_eq!(
+            perform_operation(Eip2537OperationType::BLS12_G1ADD, &g1_add_input).unwrap(),
+            EIP2537Executor::g1_add(&g1_add_input).unwrap().to_vec()
+        );
+
+        let g1_mul_input = [g1.clone(), scalar.clone()].concat();
+        assert_eq!(
+            perform_operation(Eip2537OperationType::BLS12_G1MUL, &g1_mul_input).unwrap(),
+            EIP2537Executor::g1_mul(&g1_mul_input).unwrap().to_vec()
+        );
+        assert_eq!(
+            perform_operation(Eip2537OperationType::BLS12_G1MULTIEXP, &g1_mul_input).unwrap(),
+            EIP2537Executor::g1_mul(&g1_mul_input).unwrap().to_vec()
+        );
+
+        let g2_add_input = [g2.clone(), g2.clone()].concat();
+        assert_eq!(
+            perform_operation(Eip2537OperationType::BLS12_G2ADD, &g2_add_input).unwrap(),
+            EIP2537Executor::g2_add(&g2_add_input).unwrap().to_vec()
+        );
+
+        let g2_mul_input = [g2.clone(), scalar.clone()].concat();
+        assert_eq!(
+            perform_operation(Eip2537OperationType::BLS12_G2MUL, &g2_mul_input).unwrap(),
+            EIP2537Executor::g2_mul(&g2_mul_input).unwrap().to_vec()
+        );
+        assert_eq!(
+            perform_operation(Eip2537OperationType::BLS12_G2MULTIEXP, &g2_mul_input).unwrap(),
+            EIP2537Executor::g2_mul(&g2_mul_input).unwrap().to_vec()
+        );
+
+        let pairing_input = [g1.clone(), g2.clone()].concat();
+        assert_eq!(
+            perform_operation(Eip2537OperationType::BLS12_PAIR, &pairing_input).unwrap(),
+            pairing_result_false().to_vec()
+        );
+
+        let fp = vec![0u8; SERIALIZED_FP_BYTE_LENGTH];
+        assert_eq!(
+            perform_operation(Eip2537OperationType::BLS12_FP_TO_G1, &fp).unwrap(),
+            EIP2537Executor::map_fp_to_g1(&fp).unwrap().to_vec()
+        );
+        let fp2 = vec![0u8; SERIALIZED_FP2_BYTE_LENGTH];
+        assert_eq!(
+            perform_operation(Eip2537OperationType::BLS12_FP2_TO_G2, &fp2).unwrap(),
+            EIP2537Executor::map_fp2_to_g2(&fp2).unwrap().to_vec()
+        );
+
+        assert!(perform_operation(Eip2537OperationType::BLS12_G1ADD, &g1).is_err());
+    }
+
     #[test]
     fn test_g1_add() {
         let mut rng = XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
@@ -735,7 +883,7 @@ mod test {
                 scalars.push(scalar);
             }
 
-            let p = peppinger(&points, scalars);
+            let p = peppinger(&points, scalars, &ExecutionContext::default());
 
             let expected = decode_g1::serialize_g1_point(SERIALIZED_FP_BYTE_LENGTH, &p).unwrap();
             assert!(expected.len() == SERIALIZED_G1_POINT_BYTE_LENGTH);
@@ -882,7 +1030,7 @@ mod test {
                 scalars.push(scalar);
             }
 
-            let p = peppinger(&points, scalars);
+            let p = peppinger(&points, scalars, &ExecutionContext::default());
 
             let expected = decode_g2::serialize_g2_point_in_fp2(SERIALIZED_FP_BYTE_LENGTH, &p).unwrap();
             assert!(expected.len() == SERIALIZED_G2_POINT_BYTE_LENGTH);
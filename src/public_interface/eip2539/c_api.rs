@@ -3,6 +3,7 @@ pub const EIP2539_PREALLOCATE_FOR_ERROR_BYTES: usize = 256;
 pub const EIP2539_PREALLOCATE_FOR_RESULT_BYTES: usize = 64 * 2 * 2; // maximum for G2 point
 
 use static_assertions::const_assert;
+use crate::errors::catch_internal_error;
 const_assert!(EIP2539_PREALLOCATE_FOR_RESULT_BYTES == super::SERIALIZED_G2_POINT_BYTE_LENGTH);
 
 #[allow(non_camel_case_types)]
@@ -92,18 +93,19 @@ pub extern "C" fn eip2539_perform_operation(
 
     let operation = Eip2537OperationType::from_u8(op_u8);
 
-    if operation.is_none() {
-        let written = err_out.write(b"Unknown operation type\0");
-        if let Ok(bytes_written) = written {
-            unsafe { *char_len = bytes_written as u32 };
-        } else {
-            unsafe { *char_len = 0u32 };
-        }
-
-        return 1u32;
-    }
+    let operation = match operation {
+        Some(operation) => operation,
+        None => {
+            let written = err_out.write(b"Unknown operation type\0");
+            if let Ok(bytes_written) = written {
+                unsafe { *char_len = bytes_written as u32 };
+            } else {
+                unsafe { *char_len = 0u32 };
+            }
 
-    let operation = operation.expect("is some");
+            return 1u32;
+        }
+    };
     
     let input_i8: & [i8] = unsafe { std::slice::from_raw_parts(i, i_len as usize) };
     let input: &[u8] = unsafe { std::mem::transmute(input_i8) };
@@ -111,7 +113,7 @@ pub extern "C" fn eip2539_perform_operation(
     let raw_out_i8: &mut [i8] = unsafe { std::slice::from_raw_parts_mut(o, EIP2539_PREALLOCATE_FOR_RESULT_BYTES) };
     let mut raw_out: &mut [u8] = unsafe { std::mem::transmute(raw_out_i8) };
 
-    let result = match operation {
+    let result = catch_internal_error(|| match operation {
         Eip2537OperationType::BLS12_G1ADD => super::EIP2539Executor::g1_add(&input).map(|r| r[..].to_vec()),
         Eip2537OperationType::BLS12_G1MUL => super::EIP2539Executor::g1_mul(&input).map(|r| r[..].to_vec()),
         Eip2537OperationType::BLS12_G1MULTIEXP => super::EIP2539Executor::g1_multiexp(&input).map(|r| r[..].to_vec()),
@@ -121,7 +123,7 @@ pub extern "C" fn eip2539_perform_operation(
         Eip2537OperationType::BLS12_PAIR => super::EIP2539Executor::pair(&input).map(|r| r[..].to_vec()),
         // Eip2537OperationType::BLS12_FP_TO_G1 => super::EIP2539Executor::map_fp_to_g1(&input).map(|r| r[..].to_vec()),
         // Eip2537OperationType::BLS12_FP2_TO_G2 => super::EIP2539Executor::map_fp2_to_g2(&input).map(|r| r[..].to_vec()),
-    };
+    });
 
     match result {
         Ok(result) => {
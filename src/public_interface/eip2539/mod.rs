@@ -2,6 +2,8 @@ pub struct EIP2539Executor;
 
 use crate::engines::bls12_377;
 use crate::public_interface::ApiError;
+use crate::errors::ErrorCode;
+use crate::alloc_prelude::*;
 
 pub const SCALAR_BYTE_LENGTH: usize = 32;
 
@@ -19,6 +21,7 @@ use crate::public_interface::decode_g2;
 
 use crate::weierstrass::Group;
 use crate::multiexp::peppinger;
+use crate::execution_context::ExecutionContext;
 use crate::pairings::PairingEngine;
 
 #[cfg(feature = "eip_2359_c_api")]
@@ -46,12 +49,12 @@ impl EIP2539Executor {
 
         if !p_0.is_on_curve() {
             if !crate::features::in_fuzzing_or_gas_metering() {
-                return Err(ApiError::InputError(format!("Point 0 is not on curve, file {}, line {}", file!(), line!())));
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point 0 is not on curve, file {}, line {}", file!(), line!())));
             }
         }
         if !p_1.is_on_curve() {
             if !crate::features::in_fuzzing_or_gas_metering() {
-                return Err(ApiError::InputError(format!("Point 1 is not on curve, file {}, line {}", file!(), line!())));
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point 1 is not on curve, file {}, line {}", file!(), line!())));
             }
         }
 
@@ -76,7 +79,7 @@ impl EIP2539Executor {
 
         if !p_0.is_on_curve() {
             if !crate::features::in_fuzzing_or_gas_metering() {
-                return Err(ApiError::InputError(format!("Point is not on curve, file {}, line {}", file!(), line!())));
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point is not on curve, file {}, line {}", file!(), line!())));
             }
         }
 
@@ -110,7 +113,7 @@ impl EIP2539Executor {
             let (scalar, local_rest) = decode_g1::decode_scalar_representation(local_rest, SCALAR_BYTE_LENGTH)?;
             if !p.is_on_curve() {
                 if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError(format!("Point is not on curve, file {}, line {}", file!(), line!())));
+                    return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point is not on curve, file {}, line {}", file!(), line!())));
                 }
             }
             bases.push(p);
@@ -122,7 +125,7 @@ impl EIP2539Executor {
             return Err(ApiError::InputError(format!("Multiexp with empty input pairs, file {}, line {}", file!(), line!())));
         } 
 
-        let result = peppinger(&bases, scalars);
+        let result = peppinger(&bases, scalars, &ExecutionContext::default());
 
         let mut output = [0u8; SERIALIZED_G1_POINT_BYTE_LENGTH];
 
@@ -143,12 +146,12 @@ impl EIP2539Executor {
 
         if !p_0.is_on_curve() {
             if !crate::features::in_fuzzing_or_gas_metering() {
-                return Err(ApiError::InputError(format!("Point 0 is not on curve, file {}, line {}", file!(), line!())));
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point 0 is not on curve, file {}, line {}", file!(), line!())));
             }
         }
         if !p_1.is_on_curve() {
             if !crate::features::in_fuzzing_or_gas_metering() {
-                return Err(ApiError::InputError(format!("Point 1 is not on curve, file {}, line {}", file!(), line!())));
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point 1 is not on curve, file {}, line {}", file!(), line!())));
             }
         }
 
@@ -173,7 +176,7 @@ impl EIP2539Executor {
 
         if !p_0.is_on_curve() {
             if !crate::features::in_fuzzing_or_gas_metering() {
-                return Err(ApiError::InputError(format!("Point is not on curve, file {}, line {}", file!(), line!())));
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point is not on curve, file {}, line {}", file!(), line!())));
             }
         }
 
@@ -207,7 +210,7 @@ impl EIP2539Executor {
             let (scalar, local_rest) = decode_g1::decode_scalar_representation(local_rest, SCALAR_BYTE_LENGTH)?;
             if !p.is_on_curve() {
                 if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError(format!("Point is not on curve, file {}, line {}", file!(), line!())));
+                    return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point is not on curve, file {}, line {}", file!(), line!())));
                 }
             }
             bases.push(p);
@@ -219,7 +222,7 @@ impl EIP2539Executor {
             return Err(ApiError::InputError(format!("Multiexp with empty input pairs, file {}, line {}", file!(), line!())));
         } 
 
-        let result = peppinger(&bases, scalars);
+        let result = peppinger(&bases, scalars, &ExecutionContext::default());
 
         let mut output = [0u8; SERIALIZED_G2_POINT_BYTE_LENGTH];
 
@@ -253,25 +256,25 @@ impl EIP2539Executor {
 
             if !g1.is_on_curve() {
                 if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError("G1 point is not on curve".to_owned()));
+                    return Err(ApiError::with_code(ErrorCode::NotOnCurve, "G1 point is not on curve".to_owned()));
                 }
             }
 
             if !g2.is_on_curve() {
                 if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError("G2 point is not on curve".to_owned()));
+                    return Err(ApiError::with_code(ErrorCode::NotOnCurve, "G2 point is not on curve".to_owned()));
                 }
             }
             // "fast" subgroup checks using empirical data
             if g1.wnaf_mul_with_window_size(&bls12_377::BLS12_377_SUBGROUP_ORDER[..], 5).is_zero() == false {
                 if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError("G1 point is not in the expected subgroup".to_owned()));
+                    return Err(ApiError::with_code(ErrorCode::NotInSubgroup, "G1 point is not in the expected subgroup".to_owned()));
                 }
             }
 
             if g2.wnaf_mul_with_window_size(&bls12_377::BLS12_377_SUBGROUP_ORDER[..], 5).is_zero() == false {
                 if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError("G2 point is not in the expected subgroup".to_owned()));
+                    return Err(ApiError::with_code(ErrorCode::NotInSubgroup, "G2 point is not in the expected subgroup".to_owned()));
                 }
             }
 
@@ -289,17 +292,14 @@ impl EIP2539Executor {
 
         let engine = &bls12_377::BLS12_377_PAIRING_ENGINE;
 
-        let pairing_result = engine.pair(&g1_points, &g2_points);
-
-        if pairing_result.is_none() {
-            return Err(ApiError::UnknownParameter("Pairing engine returned no value".to_owned()));
-        }
+        let pairing_result = engine.pair(&g1_points, &g2_points).ok_or_else(|| {
+            ApiError::UnknownParameter("Pairing engine returned no value".to_owned())
+        })?;
 
         use crate::extension_towers::fp12_as_2_over3_over_2::Fp12;
         use crate::traits::ZeroAndOne;
 
         let one_fp12 = Fp12::one(&bls12_377::BLS12_377_EXTENSION_12_FIELD);
-        let pairing_result = pairing_result.unwrap();
         let result = if pairing_result == one_fp12 {
             pairing_result_true()
         } else {
@@ -736,7 +736,7 @@ mod test {
                 scalars.push(scalar);
             }
 
-            let p = peppinger(&points, scalars);
+            let p = peppinger(&points, scalars, &ExecutionContext::default());
 
             let expected = decode_g1::serialize_g1_point(SERIALIZED_FP_BYTE_LENGTH, &p).unwrap();
             assert!(expected.len() == SERIALIZED_G1_POINT_BYTE_LENGTH);
@@ -883,7 +883,7 @@ mod test {
                 scalars.push(scalar);
             }
 
-            let p = peppinger(&points, scalars);
+            let p = peppinger(&points, scalars, &ExecutionContext::default());
 
             let expected = decode_g2::serialize_g2_point_in_fp2(SERIALIZED_FP_BYTE_LENGTH, &p).unwrap();
             assert!(expected.len() == SERIALIZED_G2_POINT_BYTE_LENGTH);
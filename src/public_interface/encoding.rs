@@ -0,0 +1,611 @@
+/// Builders that produce the exact byte layout expected by `API::run`, so integrators
+/// don't have to assemble length prefixes and paddings by hand.
+///
+/// All the numbers are passed as big endian bytes of any length, leading zeroes are
+/// ignored. Field elements are padded to the modulus length, scalars to the group order length,
+/// and loop parameters are encoded with a length prefix and a separate sign byte.
+/// Every call returned by the builders starts with the operation type.
+
+use super::constants::*;
+use crate::errors::ApiError;
+use crate::alloc_prelude::*;
+
+fn strip_leading_zeroes(value: &[u8]) -> &[u8] {
+    let start = value.iter().position(|&b| b != 0u8).unwrap_or(value.len());
+
+    &value[start..]
+}
+
+fn encode_fixed_len(value: &[u8], len: usize, name: &str, bytes: &mut Vec<u8>) -> Result<(), ApiError> {
+    let value = strip_leading_zeroes(value);
+    if value.len() > len {
+        return Err(ApiError::InputError(format!("{} is longer than {} bytes", name, len)));
+    }
+    bytes.resize(bytes.len() + len - value.len(), 0u8);
+    bytes.extend_from_slice(value);
+
+    Ok(())
+}
+
+fn encode_with_length(value: &[u8], name: &str, bytes: &mut Vec<u8>) -> Result<usize, ApiError> {
+    let value = strip_leading_zeroes(value);
+    if value.is_empty() {
+        return Err(ApiError::UnexpectedZero(format!("{} is zero", name)));
+    }
+    if value.len() > 255 {
+        return Err(ApiError::InputError(format!("{} is longer than 255 bytes", name)));
+    }
+    bytes.push(value.len() as u8);
+    bytes.extend_from_slice(value);
+
+    Ok(value.len())
+}
+
+fn encode_sign(is_negative: bool, bytes: &mut Vec<u8>) {
+    bytes.push(if is_negative { SIGN_MINUS } else { SIGN_PLUS });
+}
+
+fn encode_num_pairs(num_pairs: usize, bytes: &mut Vec<u8>) -> Result<(), ApiError> {
+    if num_pairs == 0 || num_pairs > 255 {
+        return Err(ApiError::InputError(format!("Number of pairs must be from 1 to 255, got {}", num_pairs)));
+    }
+    bytes.push(num_pairs as u8);
+
+    Ok(())
+}
+
+/// Affine point in G1, the point at infinity is encoded as (0, 0)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct G1Point {
+    pub x: Vec<u8>,
+    pub y: Vec<u8>,
+}
+
+impl G1Point {
+    pub fn new(x: &[u8], y: &[u8]) -> Self {
+        Self {
+            x: x.to_vec(),
+            y: y.to_vec(),
+        }
+    }
+
+    pub fn zero() -> Self {
+        Self::default()
+    }
+}
+
+/// Affine point in G2, coordinates are listed as coefficients `c0, c1, ...` in the extension.
+/// The point at infinity is encoded with all coefficients being zero
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct G2Point {
+    pub x: Vec<Vec<u8>>,
+    pub y: Vec<Vec<u8>>,
+}
+
+impl G2Point {
+    pub fn new(x: &[&[u8]], y: &[&[u8]]) -> Self {
+        Self {
+            x: x.iter().map(|c| c.to_vec()).collect(),
+            y: y.iter().map(|c| c.to_vec()).collect(),
+        }
+    }
+
+    pub fn zero() -> Self {
+        Self::default()
+    }
+}
+
+/// Twist type of the curve for BLS12 and BN pairings
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TwistType {
+    M,
+    D,
+}
+
+/// Curve in G1: `y^2 = x^3 + a*x + b` over the prime field with the main subgroup order
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct G1CurveDescriptor {
+    modulus: Vec<u8>,
+    a: Vec<u8>,
+    b: Vec<u8>,
+    group_order: Vec<u8>,
+}
+
+impl G1CurveDescriptor {
+    pub fn new(modulus: &[u8], a: &[u8], b: &[u8], group_order: &[u8]) -> Result<Self, ApiError> {
+        let modulus = strip_leading_zeroes(modulus);
+        if modulus.is_empty() || modulus.len() > 255 {
+            return Err(ApiError::InputError("Modulus must be from 1 to 255 bytes long".to_owned()));
+        }
+        let descriptor = Self {
+            modulus: modulus.to_vec(),
+            a: a.to_vec(),
+            b: b.to_vec(),
+            group_order: strip_leading_zeroes(group_order).to_vec(),
+        };
+        // check lengths once, so encoding of calls fails only on the operands
+        descriptor.encode()?;
+
+        Ok(descriptor)
+    }
+
+    /// Length of every encoded field element
+    pub fn modulus_len(&self) -> usize {
+        self.modulus.len()
+    }
+
+    /// Length of every encoded scalar
+    pub fn group_order_len(&self) -> usize {
+        self.group_order.len()
+    }
+
+    /// Curve description common for all G1 operations:
+    /// - Length of modulus (in bytes)
+    /// - Field modulus
+    /// - Curve A
+    /// - Curve B
+    /// - Length of the group order (in bytes)
+    /// - Group order
+    pub fn encode(&self) -> Result<Vec<u8>, ApiError> {
+        let mut bytes = Vec::with_capacity(3 * self.modulus_len() + self.group_order_len() + 2);
+        bytes.push(self.modulus.len() as u8);
+        bytes.extend_from_slice(&self.modulus);
+        encode_fixed_len(&self.a, self.modulus_len(), "Curve A", &mut bytes)?;
+        encode_fixed_len(&self.b, self.modulus_len(), "Curve B", &mut bytes)?;
+        encode_with_length(&self.group_order, "Group order", &mut bytes)?;
+
+        Ok(bytes)
+    }
+
+    pub fn encode_point(&self, point: &G1Point, bytes: &mut Vec<u8>) -> Result<(), ApiError> {
+        encode_fixed_len(&point.x, self.modulus_len(), "G1 point X", bytes)?;
+        encode_fixed_len(&point.y, self.modulus_len(), "G1 point Y", bytes)?;
+
+        Ok(())
+    }
+
+    pub fn encode_scalar(&self, scalar: &[u8], bytes: &mut Vec<u8>) -> Result<(), ApiError> {
+        encode_fixed_len(scalar, self.group_order_len(), "Scalar", bytes)
+    }
+
+    fn call(&self, operation: u8) -> Result<Vec<u8>, ApiError> {
+        let mut bytes = vec![operation];
+        bytes.extend(self.encode()?);
+
+        Ok(bytes)
+    }
+
+    pub fn add(&self, p0: &G1Point, p1: &G1Point) -> Result<Vec<u8>, ApiError> {
+        let mut bytes = self.call(OPERATION_G1_ADD)?;
+        self.encode_point(p0, &mut bytes)?;
+        self.encode_point(p1, &mut bytes)?;
+
+        Ok(bytes)
+    }
+
+    pub fn mul(&self, point: &G1Point, scalar: &[u8]) -> Result<Vec<u8>, ApiError> {
+        let mut bytes = self.call(OPERATION_G1_MUL)?;
+        self.encode_point(point, &mut bytes)?;
+        self.encode_scalar(scalar, &mut bytes)?;
+
+        Ok(bytes)
+    }
+
+    pub fn multiexp(&self, pairs: &[(G1Point, Vec<u8>)]) -> Result<Vec<u8>, ApiError> {
+        let mut bytes = self.call(OPERATION_G1_MULTIEXP)?;
+        encode_num_pairs(pairs.len(), &mut bytes)?;
+        for (point, scalar) in pairs.iter() {
+            self.encode_point(point, &mut bytes)?;
+            self.encode_scalar(scalar, &mut bytes)?;
+        }
+
+        Ok(bytes)
+    }
+
+    pub fn sum(&self, points: &[G1Point]) -> Result<Vec<u8>, ApiError> {
+        let mut bytes = self.call(OPERATION_G1_SUM)?;
+        encode_num_pairs(points.len(), &mut bytes)?;
+        for point in points.iter() {
+            self.encode_point(point, &mut bytes)?;
+        }
+
+        Ok(bytes)
+    }
+}
+
+/// Curve in G2: `y^2 = x^3 + a*x + b` over the extension of degree 2 or 3 of the prime field,
+/// built with the given non-residue, with the main subgroup order
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct G2CurveDescriptor {
+    modulus: Vec<u8>,
+    extension_degree: usize,
+    non_residue: Vec<u8>,
+    a: Vec<Vec<u8>>,
+    b: Vec<Vec<u8>>,
+    group_order: Vec<u8>,
+}
+
+impl G2CurveDescriptor {
+    pub fn new(
+        modulus: &[u8],
+        extension_degree: usize,
+        non_residue: &[u8],
+        a: &[&[u8]],
+        b: &[&[u8]],
+        group_order: &[u8]
+    ) -> Result<Self, ApiError> {
+        if extension_degree != EXTENSION_DEGREE_2 as usize && extension_degree != EXTENSION_DEGREE_3 as usize {
+            return Err(ApiError::UnknownParameter(format!("Extension degree must be 2 or 3, got {}", extension_degree)));
+        }
+        if a.len() != extension_degree || b.len() != extension_degree {
+            return Err(ApiError::InputError(format!("Curve coefficients must have {} coefficients", extension_degree)));
+        }
+        let modulus = strip_leading_zeroes(modulus);
+        if modulus.is_empty() || modulus.len() > 255 {
+            return Err(ApiError::InputError("Modulus must be from 1 to 255 bytes long".to_owned()));
+        }
+        let descriptor = Self {
+            modulus: modulus.to_vec(),
+            extension_degree,
+            non_residue: non_residue.to_vec(),
+            a: a.iter().map(|c| c.to_vec()).collect(),
+            b: b.iter().map(|c| c.to_vec()).collect(),
+            group_order: strip_leading_zeroes(group_order).to_vec(),
+        };
+        descriptor.encode()?;
+
+        Ok(descriptor)
+    }
+
+    pub fn modulus_len(&self) -> usize {
+        self.modulus.len()
+    }
+
+    pub fn group_order_len(&self) -> usize {
+        self.group_order.len()
+    }
+
+    pub fn extension_degree(&self) -> usize {
+        self.extension_degree
+    }
+
+    /// Curve description common for all G2 operations:
+    /// - Length of modulus (in bytes)
+    /// - Field modulus
+    /// - Extension degree
+    /// - Non-residue
+    /// - Curve A in the extension
+    /// - Curve B in the extension
+    /// - Length of the group order (in bytes)
+    /// - Group order
+    pub fn encode(&self) -> Result<Vec<u8>, ApiError> {
+        let mut bytes = vec![self.modulus.len() as u8];
+        bytes.extend_from_slice(&self.modulus);
+        bytes.push(self.extension_degree as u8);
+        encode_fixed_len(&self.non_residue, self.modulus_len(), "Non-residue", &mut bytes)?;
+        self.encode_extension_element(&self.a, "Curve A", &mut bytes)?;
+        self.encode_extension_element(&self.b, "Curve B", &mut bytes)?;
+        encode_with_length(&self.group_order, "Group order", &mut bytes)?;
+
+        Ok(bytes)
+    }
+
+    fn encode_extension_element(&self, coeffs: &[Vec<u8>], name: &str, bytes: &mut Vec<u8>) -> Result<(), ApiError> {
+        // zero point has no coefficients at all
+        if coeffs.len() > self.extension_degree {
+            return Err(ApiError::InputError(format!("{} has more than {} coefficients", name, self.extension_degree)));
+        }
+        for i in 0..self.extension_degree {
+            let c = coeffs.get(i).map(|c| &c[..]).unwrap_or(&[]);
+            encode_fixed_len(c, self.modulus_len(), name, bytes)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn encode_point(&self, point: &G2Point, bytes: &mut Vec<u8>) -> Result<(), ApiError> {
+        self.encode_extension_element(&point.x, "G2 point X", bytes)?;
+        self.encode_extension_element(&point.y, "G2 point Y", bytes)?;
+
+        Ok(())
+    }
+
+    pub fn encode_scalar(&self, scalar: &[u8], bytes: &mut Vec<u8>) -> Result<(), ApiError> {
+        encode_fixed_len(scalar, self.group_order_len(), "Scalar", bytes)
+    }
+
+    fn call(&self, operation: u8) -> Result<Vec<u8>, ApiError> {
+        let mut bytes = vec![operation];
+        bytes.extend(self.encode()?);
+
+        Ok(bytes)
+    }
+
+    pub fn add(&self, p0: &G2Point, p1: &G2Point) -> Result<Vec<u8>, ApiError> {
+        let mut bytes = self.call(OPERATION_G2_ADD)?;
+        self.encode_point(p0, &mut bytes)?;
+        self.encode_point(p1, &mut bytes)?;
+
+        Ok(bytes)
+    }
+
+    pub fn mul(&self, point: &G2Point, scalar: &[u8]) -> Result<Vec<u8>, ApiError> {
+        let mut bytes = self.call(OPERATION_G2_MUL)?;
+        self.encode_point(point, &mut bytes)?;
+        self.encode_scalar(scalar, &mut bytes)?;
+
+        Ok(bytes)
+    }
+
+    pub fn multiexp(&self, pairs: &[(G2Point, Vec<u8>)]) -> Result<Vec<u8>, ApiError> {
+        let mut bytes = self.call(OPERATION_G2_MULTIEXP)?;
+        encode_num_pairs(pairs.len(), &mut bytes)?;
+        for (point, scalar) in pairs.iter() {
+            self.encode_point(point, &mut bytes)?;
+            self.encode_scalar(scalar, &mut bytes)?;
+        }
+
+        Ok(bytes)
+    }
+
+    pub fn sum(&self, points: &[G2Point]) -> Result<Vec<u8>, ApiError> {
+        let mut bytes = self.call(OPERATION_G2_SUM)?;
+        encode_num_pairs(points.len(), &mut bytes)?;
+        for point in points.iter() {
+            self.encode_point(point, &mut bytes)?;
+        }
+
+        Ok(bytes)
+    }
+}
+
+/// Pairing call for BLS12, BN, MNT4 and MNT6 curves. Curve specific parameters are given
+/// to the constructors, pairs are added one by one. Once a pair is added with a sign
+/// the call is encoded as a pairing equation
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PairingCall {
+    curve_type: u8,
+    g1: G1CurveDescriptor,
+    g2_extension_degree: usize,
+    parameters: Vec<u8>,
+    pairs: Vec<(G1Point, G2Point, bool, bool)>,
+    miller_loop_only: bool,
+    equation: bool,
+}
+
+impl PairingCall {
+    fn with_fp2_twist(
+        curve_type: u8,
+        g1: G1CurveDescriptor,
+        fp2_non_residue: &[u8],
+        fp6_non_residue: [&[u8]; 2],
+        twist_type: TwistType,
+        x: &[u8],
+        x_is_negative: bool
+    ) -> Result<Self, ApiError> {
+        // - non-residue for Fp2
+        // - non-residue for Fp6
+        // - twist type M/D
+        // - parameter X
+        // - sign of X
+        let modulus_len = g1.modulus_len();
+        let mut parameters = vec![];
+        encode_fixed_len(fp2_non_residue, modulus_len, "Non-residue for Fp2", &mut parameters)?;
+        encode_fixed_len(fp6_non_residue[0], modulus_len, "Non-residue for Fp6", &mut parameters)?;
+        encode_fixed_len(fp6_non_residue[1], modulus_len, "Non-residue for Fp6", &mut parameters)?;
+        parameters.push(match twist_type {
+            TwistType::M => TWIST_TYPE_M,
+            TwistType::D => TWIST_TYPE_D,
+        });
+        encode_with_length(x, "Parameter X", &mut parameters)?;
+        encode_sign(x_is_negative, &mut parameters);
+
+        Ok(Self {
+            curve_type,
+            g1,
+            g2_extension_degree: 2,
+            parameters,
+            pairs: vec![],
+            miller_loop_only: false,
+            equation: false,
+        })
+    }
+
+    pub fn bls12(
+        g1: G1CurveDescriptor,
+        fp2_non_residue: &[u8],
+        fp6_non_residue: [&[u8]; 2],
+        twist_type: TwistType,
+        x: &[u8],
+        x_is_negative: bool
+    ) -> Result<Self, ApiError> {
+        Self::with_fp2_twist(BLS12, g1, fp2_non_residue, fp6_non_residue, twist_type, x, x_is_negative)
+    }
+
+    /// `u` is the curve parameter, `6u + 2` loop is computed by the implementation
+    pub fn bn(
+        g1: G1CurveDescriptor,
+        fp2_non_residue: &[u8],
+        fp6_non_residue: [&[u8]; 2],
+        twist_type: TwistType,
+        u: &[u8],
+        u_is_negative: bool
+    ) -> Result<Self, ApiError> {
+        Self::with_fp2_twist(BN, g1, fp2_non_residue, fp6_non_residue, twist_type, u, u_is_negative)
+    }
+
+    fn mnt(
+        curve_type: u8,
+        g2_extension_degree: usize,
+        g1: G1CurveDescriptor,
+        non_residue: &[u8],
+        x: &[u8],
+        x_is_negative: bool,
+        exp_w0: &[u8],
+        exp_w1: &[u8],
+        exp_w0_is_negative: bool
+    ) -> Result<Self, ApiError> {
+        // - non-residue for the twist
+        // - parameter X
+        // - sign of X
+        // - final exponentiation W0
+        // - final exponentiation W1
+        // - sign of W0
+        let mut parameters = vec![];
+        encode_fixed_len(non_residue, g1.modulus_len(), "Non-residue", &mut parameters)?;
+        encode_with_length(x, "Parameter X", &mut parameters)?;
+        encode_sign(x_is_negative, &mut parameters);
+        encode_with_length(exp_w0, "Exponent W0", &mut parameters)?;
+        encode_with_length(exp_w1, "Exponent W1", &mut parameters)?;
+        encode_sign(exp_w0_is_negative, &mut parameters);
+
+        Ok(Self {
+            curve_type,
+            g1,
+            g2_extension_degree,
+            parameters,
+            pairs: vec![],
+            miller_loop_only: false,
+            equation: false,
+        })
+    }
+
+    pub fn mnt4(
+        g1: G1CurveDescriptor,
+        fp2_non_residue: &[u8],
+        x: &[u8],
+        x_is_negative: bool,
+        exp_w0: &[u8],
+        exp_w1: &[u8],
+        exp_w0_is_negative: bool
+    ) -> Result<Self, ApiError> {
+        Self::mnt(MNT4, 2, g1, fp2_non_residue, x, x_is_negative, exp_w0, exp_w1, exp_w0_is_negative)
+    }
+
+    pub fn mnt6(
+        g1: G1CurveDescriptor,
+        fp3_non_residue: &[u8],
+        x: &[u8],
+        x_is_negative: bool,
+        exp_w0: &[u8],
+        exp_w1: &[u8],
+        exp_w0_is_negative: bool
+    ) -> Result<Self, ApiError> {
+        Self::mnt(MNT6, 3, g1, fp3_non_residue, x, x_is_negative, exp_w0, exp_w1, exp_w0_is_negative)
+    }
+
+    /// Adds a pair, `check_subgroup` asks to check both points for being in the main subgroup
+    pub fn add_pair(&mut self, g1: G1Point, g2: G2Point, check_subgroup: bool) -> &mut Self {
+        self.pairs.push((g1, g2, check_subgroup, false));
+
+        self
+    }
+
+    /// Adds a pair of the pairing equation, the pairing of the pair is inverted if `is_negative`.
+    /// So e(A, B) == e(C, D) is checked by adding (A, B) as positive and (C, D) as negative
+    pub fn add_pair_with_sign(&mut self, g1: G1Point, g2: G2Point, check_subgroup: bool, is_negative: bool) -> &mut Self {
+        self.pairs.push((g1, g2, check_subgroup, is_negative));
+        self.equation = true;
+
+        self
+    }
+
+    /// Asks to return the result of the Miller loop without the final exponentiation
+    pub fn miller_loop_only(&mut self) -> &mut Self {
+        self.miller_loop_only = true;
+
+        self
+    }
+
+    /// Encodes G2 points only, coefficients of the twist are not needed for that
+    fn g2_points_encoder(&self) -> G2CurveDescriptor {
+        G2CurveDescriptor {
+            modulus: self.g1.modulus.clone(),
+            extension_degree: self.g2_extension_degree,
+            non_residue: vec![],
+            a: vec![],
+            b: vec![],
+            group_order: vec![],
+        }
+    }
+
+    fn encode_curve(&self, operation: u8) -> Result<Vec<u8>, ApiError> {
+        let mut bytes = vec![operation, self.curve_type];
+        bytes.extend(self.g1.encode()?);
+        bytes.extend_from_slice(&self.parameters);
+
+        Ok(bytes)
+    }
+
+    /// BLS signature check on the curve of the call with the public key in G1 and the signature in G2.
+    /// `message` is the message hashed to G2. Pairs added to the call are not used
+    pub fn bls_verify_with_g1_public_key(&self, generator: &G1Point, public_key: &G1Point, message: &G2Point, signature: &G2Point) -> Result<Vec<u8>, ApiError> {
+        let g2_points_encoder = self.g2_points_encoder();
+        let mut bytes = self.encode_curve(OPERATION_BLS_VERIFY)?;
+        bytes.push(BLS_PUBLIC_KEY_IN_G1);
+        self.g1.encode_point(generator, &mut bytes)?;
+        self.g1.encode_point(public_key, &mut bytes)?;
+        g2_points_encoder.encode_point(message, &mut bytes)?;
+        g2_points_encoder.encode_point(signature, &mut bytes)?;
+
+        Ok(bytes)
+    }
+
+    /// Same as `bls_verify_with_g1_public_key` with the public key in G2 and the signature in G1
+    pub fn bls_verify_with_g2_public_key(&self, generator: &G2Point, public_key: &G2Point, message: &G1Point, signature: &G1Point) -> Result<Vec<u8>, ApiError> {
+        let g2_points_encoder = self.g2_points_encoder();
+        let mut bytes = self.encode_curve(OPERATION_BLS_VERIFY)?;
+        bytes.push(BLS_PUBLIC_KEY_IN_G2);
+        g2_points_encoder.encode_point(generator, &mut bytes)?;
+        g2_points_encoder.encode_point(public_key, &mut bytes)?;
+        self.g1.encode_point(message, &mut bytes)?;
+        self.g1.encode_point(signature, &mut bytes)?;
+
+        Ok(bytes)
+    }
+
+    /// KZG opening check on the curve of the call: whether `proof` opens `commitment` to `value` at `point`
+    /// for the setup given by the generators and `tau_g2`. Pairs added to the call are not used
+    pub fn kzg_verify(&self, g1_generator: &G1Point, g2_generator: &G2Point, tau_g2: &G2Point, commitment: &G1Point, point: &[u8], value: &[u8], proof: &G1Point) -> Result<Vec<u8>, ApiError> {
+        let g2_points_encoder = self.g2_points_encoder();
+        let mut bytes = self.encode_curve(OPERATION_KZG_OPENING)?;
+        self.g1.encode_point(g1_generator, &mut bytes)?;
+        g2_points_encoder.encode_point(g2_generator, &mut bytes)?;
+        g2_points_encoder.encode_point(tau_g2, &mut bytes)?;
+        self.g1.encode_point(commitment, &mut bytes)?;
+        self.g1.encode_scalar(point, &mut bytes)?;
+        self.g1.encode_scalar(value, &mut bytes)?;
+        self.g1.encode_point(proof, &mut bytes)?;
+
+        Ok(bytes)
+    }
+
+    pub fn encode(&self) -> Result<Vec<u8>, ApiError> {
+        let modulus_len = self.g1.modulus_len();
+        let g2_coeffs_encoder = self.g2_points_encoder();
+
+        if self.equation && self.miller_loop_only {
+            return Err(ApiError::InputError("Pairing equation can not be Miller loop only".to_owned()));
+        }
+
+        let operation = if self.equation { OPERATION_PAIRING_EQUATION } else { OPERATION_PAIRING };
+        let mut bytes = self.encode_curve(operation)?;
+        encode_num_pairs(self.pairs.len(), &mut bytes)?;
+        bytes.reserve(self.pairs.len() * (3 + 2 * modulus_len * (1 + self.g2_extension_degree)));
+        for (g1, g2, check_subgroup, is_negative) in self.pairs.iter() {
+            if self.equation {
+                encode_sign(*is_negative, &mut bytes);
+            }
+            bytes.push(if *check_subgroup { BOOLEAN_TRUE } else { BOOLEAN_FALSE });
+            self.g1.encode_point(g1, &mut bytes)?;
+            bytes.push(if *check_subgroup { BOOLEAN_TRUE } else { BOOLEAN_FALSE });
+            g2_coeffs_encoder.encode_point(g2, &mut bytes)?;
+        }
+        if self.miller_loop_only {
+            bytes.push(PAIRING_MODE_MILLER_LOOP_ONLY);
+        }
+
+        Ok(bytes)
+    }
+}
@@ -0,0 +1,253 @@
+//! Stable C ABI for clients that embed the library (Go, C++) and do not want to wrap the Rust API.
+//!
+//! `op` is one of the `EIP1962_OPERATION_*` values. Caller provides an output buffer and
+//! puts its capacity into `*out_len`, on success `*out_len` is overwritten with the
+//! number of bytes written. `EIP1962_RESULT_BUFFER_LEN` is enough for any result. Error buffer
+//! must be at least `EIP1962_ERROR_BUFFER_LEN` long and receives a null terminated description
+//! for any non-zero return code.
+//!
+//! The C header `capi/include/eip1962.h` is generated from this file, so constants here are
+//! literals that cbindgen can read and are checked against the library at compile time.
+//!
+//! Return codes are part of the ABI and are never reused:
+//!
+//! | code | meaning                                   |
+//! |------|-------------------------------------------|
+//! | 0    | success                                   |
+//! | 1    | `ApiError::Overflow`                      |
+//! | 2    | `ApiError::UnexpectedZero`                |
+//! | 3    | `ApiError::InputError`, `ApiError::Coded` |
+//! | 4    | `ApiError::DivisionByZero`                |
+//! | 5    | `ApiError::UnknownParameter`              |
+//! | 6    | `ApiError::OutputError`                   |
+//! | 7    | `ApiError::MissingValue`                  |
+//! | 8    | unknown operation type                    |
+//! | 9    | null pointer passed for a required buffer |
+//! | 10   | output buffer is too small for the result |
+//! | 11   | `ApiError::InternalError`, a caught panic |
+//!
+//! A panic during execution never unwinds across this boundary, it's caught and reported
+//! with code 11. Without the `std` feature panics can not be caught and abort instead
+
+use super::unified_api::*;
+use crate::errors::{ApiError, catch_internal_error};
+use crate::alloc_prelude::*;
+
+use static_assertions::const_assert_eq;
+
+pub const EIP1962_OPERATION_G1ADD: u8 = 1;
+pub const EIP1962_OPERATION_G1MUL: u8 = 2;
+pub const EIP1962_OPERATION_G1MULTIEXP: u8 = 3;
+pub const EIP1962_OPERATION_G2ADD: u8 = 4;
+pub const EIP1962_OPERATION_G2MUL: u8 = 5;
+pub const EIP1962_OPERATION_G2MULTIEXP: u8 = 6;
+pub const EIP1962_OPERATION_BLS12PAIR: u8 = 7;
+pub const EIP1962_OPERATION_BNPAIR: u8 = 8;
+pub const EIP1962_OPERATION_MNT4PAIR: u8 = 9;
+pub const EIP1962_OPERATION_MNT6PAIR: u8 = 10;
+
+const_assert_eq!(EIP1962_OPERATION_G1ADD, G1ADD_OPERATION_RAW_VALUE);
+const_assert_eq!(EIP1962_OPERATION_G1MUL, G1MUL_OPERATION_RAW_VALUE);
+const_assert_eq!(EIP1962_OPERATION_G1MULTIEXP, G1MULTIEXP_OPERATION_RAW_VALUE);
+const_assert_eq!(EIP1962_OPERATION_G2ADD, G2ADD_OPERATION_RAW_VALUE);
+const_assert_eq!(EIP1962_OPERATION_G2MUL, G2MUL_OPERATION_RAW_VALUE);
+const_assert_eq!(EIP1962_OPERATION_G2MULTIEXP, G2MULTIEXP_OPERATION_RAW_VALUE);
+const_assert_eq!(EIP1962_OPERATION_BLS12PAIR, BLS12PAIR_OPERATION_RAW_VALUE);
+const_assert_eq!(EIP1962_OPERATION_BNPAIR, BNPAIR_OPERATION_RAW_VALUE);
+const_assert_eq!(EIP1962_OPERATION_MNT4PAIR, MNT4PAIR_OPERATION_RAW_VALUE);
+const_assert_eq!(EIP1962_OPERATION_MNT6PAIR, MNT6PAIR_OPERATION_RAW_VALUE);
+
+pub const EIP1962_ERROR_BUFFER_LEN: usize = 256;
+#[cfg(not(any(feature = "limbs-24", feature = "limbs-32")))]
+pub const EIP1962_RESULT_BUFFER_LEN: usize = 768;
+#[cfg(all(feature = "limbs-24", not(feature = "limbs-32")))]
+pub const EIP1962_RESULT_BUFFER_LEN: usize = 1152;
+#[cfg(feature = "limbs-32")]
+pub const EIP1962_RESULT_BUFFER_LEN: usize = 1530;
+
+const_assert_eq!(EIP1962_ERROR_BUFFER_LEN, PREALLOCATE_FOR_ERROR_BYTES);
+const_assert_eq!(EIP1962_RESULT_BUFFER_LEN, PREALLOCATE_FOR_RESULT_BYTES);
+
+pub const EIP1962_SUCCESS: u32 = 0;
+pub const EIP1962_ERROR_OVERFLOW: u32 = 1;
+pub const EIP1962_ERROR_UNEXPECTED_ZERO: u32 = 2;
+pub const EIP1962_ERROR_INPUT: u32 = 3;
+pub const EIP1962_ERROR_DIVISION_BY_ZERO: u32 = 4;
+pub const EIP1962_ERROR_UNKNOWN_PARAMETER: u32 = 5;
+pub const EIP1962_ERROR_OUTPUT: u32 = 6;
+pub const EIP1962_ERROR_MISSING_VALUE: u32 = 7;
+pub const EIP1962_ERROR_UNKNOWN_OPERATION: u32 = 8;
+pub const EIP1962_ERROR_NULL_POINTER: u32 = 9;
+pub const EIP1962_ERROR_OUTPUT_BUFFER_TOO_SMALL: u32 = 10;
+pub const EIP1962_ERROR_INTERNAL: u32 = 11;
+
+pub fn error_code(error: &ApiError) -> u32 {
+    match error {
+        ApiError::Overflow => EIP1962_ERROR_OVERFLOW,
+        ApiError::UnexpectedZero(_) => EIP1962_ERROR_UNEXPECTED_ZERO,
+        ApiError::InputError(_) => EIP1962_ERROR_INPUT,
+        ApiError::DivisionByZero => EIP1962_ERROR_DIVISION_BY_ZERO,
+        ApiError::UnknownParameter(_) => EIP1962_ERROR_UNKNOWN_PARAMETER,
+        ApiError::OutputError(_) => EIP1962_ERROR_OUTPUT,
+        ApiError::MissingValue => EIP1962_ERROR_MISSING_VALUE,
+        ApiError::Coded(_) => EIP1962_ERROR_INPUT,
+        ApiError::InternalError(_) => EIP1962_ERROR_INTERNAL,
+    }
+}
+
+/// Copies as much of the description as fits and always terminates it with zero
+fn write_error(err_buf: *mut u8, description: &str) {
+    if err_buf.is_null() {
+        return;
+    }
+    let err_out = unsafe { core::slice::from_raw_parts_mut(err_buf, PREALLOCATE_FOR_ERROR_BYTES) };
+    let description = description.as_bytes();
+    let len = core::cmp::min(description.len(), PREALLOCATE_FOR_ERROR_BYTES - 1);
+    err_out[..len].copy_from_slice(&description[..len]);
+    err_out[len] = 0u8;
+}
+
+// this is C interface
+/// # Safety
+///
+/// `input` must point to `len` readable bytes, `output` to `*out_len` writable bytes
+/// and `err_buf` (if not null) to `EIP1962_ERROR_BUFFER_LEN` writable bytes
+#[no_mangle]
+pub unsafe extern "C" fn eip1962_perform_operation(
+    op: u8,
+    input: *const u8,
+    len: usize,
+    output: *mut u8,
+    out_len: *mut usize,
+    err_buf: *mut u8) -> u32
+{
+    if output.is_null() || out_len.is_null() || (input.is_null() && len != 0) {
+        write_error(err_buf, "Null pointer is passed for a buffer");
+
+        return EIP1962_ERROR_NULL_POINTER;
+    }
+
+    let operation = match OperationType::from_u8(op) {
+        Some(operation) => operation,
+        None => {
+            write_error(err_buf, "Unknown operation type");
+
+            return EIP1962_ERROR_UNKNOWN_OPERATION;
+        }
+    };
+
+    let input: &[u8] = if len == 0 {
+        &[]
+    } else {
+        unsafe { core::slice::from_raw_parts(input, len) }
+    };
+
+    match catch_internal_error(|| perform_operation(operation, input)) {
+        Ok(result) => {
+            let capacity = unsafe { *out_len };
+            if result.len() > capacity {
+                write_error(err_buf, &format!("Result takes {} bytes, but output buffer has {}", result.len(), capacity));
+
+                return EIP1962_ERROR_OUTPUT_BUFFER_TOO_SMALL;
+            }
+
+            let raw_out = unsafe { core::slice::from_raw_parts_mut(output, result.len()) };
+            raw_out.copy_from_slice(&result);
+            unsafe { *out_len = result.len() };
+
+            EIP1962_SUCCESS
+        },
+        Err(error) => {
+            write_error(err_buf, &error.to_string());
+
+            error_code(&error)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn g1_add_input() -> Vec<u8> {
+        // curve y^2 = x^3 + 3 over F_p, p = 2^255 - 19, and two copies of the point (1, 2)
+        let modulus = hex::decode("7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed").unwrap();
+        let mut one = vec![0u8; 32];
+        one[31] = 1;
+        let mut two = vec![0u8; 32];
+        two[31] = 2;
+        let mut three = vec![0u8; 32];
+        three[31] = 3;
+
+        let mut input = vec![32u8];
+        input.extend(modulus);
+        input.extend(vec![0u8; 32]);
+        input.extend(three);
+        input.extend(vec![1u8, 0xfb]);
+        for _ in 0..2 {
+            input.extend(one.clone());
+            input.extend(two.clone());
+        }
+
+        input
+    }
+
+    fn call(op: u8, input: &[u8], capacity: usize) -> (u32, Vec<u8>, String) {
+        let mut output = vec![0u8; capacity];
+        let mut out_len = capacity;
+        let mut err = vec![0u8; PREALLOCATE_FOR_ERROR_BYTES];
+        let code = unsafe { eip1962_perform_operation(op, input.as_ptr(), input.len(), output.as_mut_ptr(), &mut out_len, err.as_mut_ptr()) };
+        let err_len = err.iter().position(|b| *b == 0).unwrap();
+        let description = String::from_utf8(err[..err_len].to_vec()).unwrap();
+        if code == EIP1962_SUCCESS {
+            output.truncate(out_len);
+        }
+
+        (code, output, description)
+    }
+
+    #[test]
+    fn test_ffi_matches_rust_api() {
+        let input = g1_add_input();
+        let expected = perform_operation(OperationType::G1ADD, &input).unwrap();
+
+        let (code, output, _) = call(G1ADD_OPERATION_RAW_VALUE, &input, PREALLOCATE_FOR_RESULT_BYTES);
+        assert_eq!(code, EIP1962_SUCCESS);
+        assert_eq!(output, expected);
+
+        let (code, _, description) = call(G1ADD_OPERATION_RAW_VALUE, &input, expected.len() - 1);
+        assert_eq!(code, EIP1962_ERROR_OUTPUT_BUFFER_TOO_SMALL);
+        assert!(!description.is_empty());
+    }
+
+    #[test]
+    fn test_ffi_error_codes() {
+        let input = g1_add_input();
+
+        let (code, _, description) = call(0xff, &input, PREALLOCATE_FOR_RESULT_BYTES);
+        assert_eq!(code, EIP1962_ERROR_UNKNOWN_OPERATION);
+        assert_eq!(description, "Unknown operation type");
+
+        let truncated = &input[..input.len() - 1];
+        let error = perform_operation(OperationType::G1ADD, truncated).unwrap_err();
+        let (code, _, description) = call(G1ADD_OPERATION_RAW_VALUE, truncated, PREALLOCATE_FOR_RESULT_BYTES);
+        assert_eq!(code, error_code(&error));
+        assert_eq!(description, error.to_string());
+
+        let (code, _, _) = call(G1ADD_OPERATION_RAW_VALUE, &[], PREALLOCATE_FOR_RESULT_BYTES);
+        assert_eq!(code, EIP1962_ERROR_INPUT);
+
+        let mut out_len = 0usize;
+        let mut err = vec![0u8; PREALLOCATE_FOR_ERROR_BYTES];
+        let code = unsafe { eip1962_perform_operation(G1ADD_OPERATION_RAW_VALUE, input.as_ptr(), input.len(), core::ptr::null_mut(), &mut out_len, err.as_mut_ptr()) };
+        assert_eq!(code, EIP1962_ERROR_NULL_POINTER);
+    }
+
+    #[test]
+    fn test_long_error_is_truncated() {
+        let mut err = vec![0xffu8; PREALLOCATE_FOR_ERROR_BYTES];
+        write_error(err.as_mut_ptr(), &"a".repeat(1000));
+        assert_eq!(err[PREALLOCATE_FOR_ERROR_BYTES - 1], 0);
+        assert!(err[..PREALLOCATE_FOR_ERROR_BYTES - 1].iter().all(|b| *b == b'a'));
+    }
+}
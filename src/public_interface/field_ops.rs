@@ -0,0 +1,174 @@
+//! This api consists of raw arithmetic in a prime field
+//! - Addition
+//! - Multiplication
+//! - Inversion
+//! - Exponentiation
+//! - Square root in Fp2
+//!
+//! Every call starts with
+//! - Lengths of modulus (in bytes)
+//! - Field modulus
+//!
+//! followed by operands (and the Fp2 non-residue for square roots), every field element being encoded as the modulus (and being below it).
+//! Exponent is encoded as a length (in bytes) followed by the big endian value, as a group order.
+//! Result is a single field element.
+//!
+//! Assumptions:
+//! - one byte for length encoding
+
+use crate::representation::ElementRepr;
+use crate::field::*;
+use crate::fp::Fp;
+use crate::traits::{FieldElement, ZeroAndOne};
+
+use super::decode_fp::*;
+use super::decode_g2::create_fp2_extension;
+use super::decode_utils::*;
+
+use crate::errors::ApiError;
+use crate::alloc_prelude::*;
+
+pub trait FieldApi {
+    fn add(bytes: &[u8]) -> Result<Vec<u8>, ApiError>;
+    fn mul(bytes: &[u8]) -> Result<Vec<u8>, ApiError>;
+    fn inverse(bytes: &[u8]) -> Result<Vec<u8>, ApiError>;
+    fn exp(bytes: &[u8]) -> Result<Vec<u8>, ApiError>;
+    fn sqrt_ext2(bytes: &[u8]) -> Result<Vec<u8>, ApiError>;
+}
+
+pub struct FieldApiImplementation<FE: ElementRepr> {
+    _marker_fe: core::marker::PhantomData<FE>,
+}
+
+impl<FE: ElementRepr> FieldApi for FieldApiImplementation<FE> {
+    fn add(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+        let (field, modulus_len, _, rest) = parse_base_field_from_encoding::<FE>(bytes)?;
+        let (mut a, rest) = decode_fp(rest, modulus_len, &field)?;
+        let (b, rest) = decode_fp(rest, modulus_len, &field)?;
+
+        if !rest.is_empty() {
+            return Err(ApiError::garbage_at_the_end(rest));
+        }
+
+        a.add_assign(&b);
+
+        serialize_fp_fixed_len(modulus_len, &a)
+    }
+
+    fn mul(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+        let (field, modulus_len, _, rest) = parse_base_field_from_encoding::<FE>(bytes)?;
+        let (mut a, rest) = decode_fp(rest, modulus_len, &field)?;
+        let (b, rest) = decode_fp(rest, modulus_len, &field)?;
+
+        if !rest.is_empty() {
+            return Err(ApiError::garbage_at_the_end(rest));
+        }
+
+        a.mul_assign(&b);
+
+        serialize_fp_fixed_len(modulus_len, &a)
+    }
+
+    fn inverse(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+        let (field, modulus_len, _, rest) = parse_base_field_from_encoding::<FE>(bytes)?;
+        let (a, rest) = decode_fp(rest, modulus_len, &field)?;
+
+        if !rest.is_empty() {
+            return Err(ApiError::garbage_at_the_end(rest));
+        }
+
+        let inverse = a.inverse().ok_or(ApiError::InputError(format!("Element is not invertible, file {}, line {}", file!(), line!())))?;
+
+        // modulus is not checked for primality, so an element may share a factor with it
+        // and the binary inversion result has to be checked
+        let mut maybe_one = inverse;
+        maybe_one.mul_assign(&a);
+        if maybe_one != Fp::one(&field) {
+            return Err(ApiError::InputError(format!("Element is not invertible, file {}, line {}", file!(), line!())));
+        }
+
+        serialize_fp_fixed_len(modulus_len, &inverse)
+    }
+
+    fn exp(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+        let (field, modulus_len, _, rest) = parse_base_field_from_encoding::<FE>(bytes)?;
+        let (a, rest) = decode_fp(rest, modulus_len, &field)?;
+        let ((_, exponent), rest) = decode_group_order_with_length(rest)?;
+
+        if !rest.is_empty() {
+            return Err(ApiError::garbage_at_the_end(rest));
+        }
+
+        let result = a.pow(exponent.as_ref());
+
+        serialize_fp_fixed_len(modulus_len, &result)
+    }
+
+    fn sqrt_ext2(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+        let (field, modulus_len, modulus, rest) = parse_base_field_from_encoding::<FE>(bytes)?;
+        let (extension_2, rest) = create_fp2_extension(rest, &modulus, modulus_len, &field, true)?;
+        let (element, rest) = decode_fp2(rest, modulus_len, &extension_2)?;
+
+        if !rest.is_empty() {
+            return Err(ApiError::garbage_at_the_end(rest));
+        }
+
+        let mut root = element.sqrt().ok_or(ApiError::InputError(format!("Element is not a square, file {}, line {}", file!(), line!())))?;
+
+        // of two roots return the even one in the same sense as for compressed points
+        if fp2_is_odd(&root) {
+            root.negate();
+        }
+
+        serialize_fp2_fixed_len(modulus_len, &root)
+    }
+}
+
+pub struct PublicFieldApi;
+
+impl FieldApi for PublicFieldApi {
+    fn add(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+        let (_, modulus, _) = parse_modulus_and_length(bytes)?;
+        let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+
+        let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, FieldApiImplementation, bytes, add);
+
+        result
+    }
+
+    fn mul(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+        let (_, modulus, _) = parse_modulus_and_length(bytes)?;
+        let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+
+        let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, FieldApiImplementation, bytes, mul);
+
+        result
+    }
+
+    fn inverse(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+        let (_, modulus, _) = parse_modulus_and_length(bytes)?;
+        let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+
+        let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, FieldApiImplementation, bytes, inverse);
+
+        result
+    }
+
+    fn exp(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+        let (_, modulus, _) = parse_modulus_and_length(bytes)?;
+        let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+
+        let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, FieldApiImplementation, bytes, exp);
+
+        result
+    }
+
+    fn sqrt_ext2(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+        let (_, modulus, _) = parse_modulus_and_length(bytes)?;
+        let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+
+        let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, FieldApiImplementation, bytes, sqrt_ext2);
+
+        result
+    }
+}
@@ -0,0 +1,224 @@
+//! Operations for the common 4, 6 and 8 limb moduli. `API::run` decodes the number of limbs
+//! once and runs the table of `API::run_operation` with one of the implementations below, where
+//! every operation is bound to a single representation at compile time, so there is no second
+//! decoding of the modulus and no `expand_for_modulus_limbs!` dispatch per call. Other widths
+//! go through `Public*Api`
+
+use crate::representation::ElementRepr;
+use crate::errors::ApiError;
+use crate::execution_context::ExecutionContext;
+use crate::alloc_prelude::*;
+
+use super::constants::*;
+use super::decode_utils::{split, parse_modulus_and_length, parse_modulus_and_extension_degree, num_limbs_for_modulus};
+use super::g2_ops::{G2Api, G2ApiImplementationFp2, G2ApiImplementationFp3};
+use super::pairing_ops::{PairingApi, PairingApiImplementation, PairingOutput};
+
+/// Number of limbs of the field modulus of an operation. `None` if the input is too short or
+/// the modulus is invalid, the generic path then reports the error
+pub(crate) fn modulus_limbs(op_type: u8, bytes: &[u8]) -> Option<usize> {
+    let bytes = match op_type {
+        OPERATION_PAIRING | OPERATION_PAIRING_GT | OPERATION_MILLER_LOOP | OPERATION_FINAL_EXP | OPERATION_PAIRING_EQUATION | OPERATION_BLS_VERIFY | OPERATION_KZG_OPENING => {
+            let (_curve_type, rest) = split(bytes, CURVE_TYPE_LENGTH, "Input should be longer than curve type encoding").ok()?;
+
+            rest
+        },
+        _ => bytes
+    };
+
+    let (_, modulus, _) = parse_modulus_and_length(bytes).ok()?;
+
+    num_limbs_for_modulus(&modulus).ok()
+}
+
+/// G2 operations of a fixed representation, the extension degree is still read from the input
+pub(crate) struct G2ByExtensionDegree<FE: ElementRepr> {
+    _marker_fe: core::marker::PhantomData<FE>,
+}
+
+impl<FE: ElementRepr> G2ByExtensionDegree<FE> {
+    fn run<F2, F3>(bytes: &[u8], ctx: &ExecutionContext, fp2: F2, fp3: F3) -> Result<Vec<u8>, ApiError>
+        where F2: FnOnce(&[u8], &ExecutionContext) -> Result<Vec<u8>, ApiError>,
+            F3: FnOnce(&[u8], &ExecutionContext) -> Result<Vec<u8>, ApiError>
+    {
+        let (_, _, extension_degree, _, _) = parse_modulus_and_extension_degree(bytes)?;
+        match extension_degree {
+            EXTENSION_DEGREE_2 => fp2(bytes, ctx),
+            EXTENSION_DEGREE_3 => fp3(bytes, ctx),
+            _ => Err(ApiError::InputError("Invalid extension degree".to_owned()))
+        }
+    }
+}
+
+impl<FE: ElementRepr> G2Api for G2ByExtensionDegree<FE> {
+    fn add_points(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        Self::run(bytes, ctx, G2ApiImplementationFp2::<FE>::add_points, G2ApiImplementationFp3::<FE>::add_points)
+    }
+
+    fn mul_point(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        Self::run(bytes, ctx, G2ApiImplementationFp2::<FE>::mul_point, G2ApiImplementationFp3::<FE>::mul_point)
+    }
+
+    fn multiexp(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        Self::run(bytes, ctx, G2ApiImplementationFp2::<FE>::multiexp, G2ApiImplementationFp3::<FE>::multiexp)
+    }
+
+    fn sum_points(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        Self::run(bytes, ctx, G2ApiImplementationFp2::<FE>::sum_points, G2ApiImplementationFp3::<FE>::sum_points)
+    }
+
+    fn check_subgroup(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        Self::run(bytes, ctx, G2ApiImplementationFp2::<FE>::check_subgroup, G2ApiImplementationFp3::<FE>::check_subgroup)
+    }
+
+    fn clear_cofactor(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        Self::run(bytes, ctx, G2ApiImplementationFp2::<FE>::clear_cofactor, G2ApiImplementationFp3::<FE>::clear_cofactor)
+    }
+
+    fn validate_point(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        Self::run(bytes, ctx, G2ApiImplementationFp2::<FE>::validate_point, G2ApiImplementationFp3::<FE>::validate_point)
+    }
+}
+
+/// Pairings of a fixed representation, BLS12-381 and BN254 still take their fast path
+pub(crate) struct PairingOrWellKnownCurve<FE: ElementRepr> {
+    _marker_fe: core::marker::PhantomData<FE>,
+}
+
+impl<FE: ElementRepr> PairingOrWellKnownCurve<FE> {
+    fn run<F>(bytes: &[u8], ctx: &ExecutionContext, output: PairingOutput, pairing: F) -> Result<Vec<u8>, ApiError>
+        where F: FnOnce(&[u8], &ExecutionContext) -> Result<Vec<u8>, ApiError>
+    {
+        if let Some(result) = super::curves::try_pair_well_known_curve(bytes, output, ctx) {
+            return result;
+        }
+
+        pairing(bytes, ctx)
+    }
+}
+
+impl<FE: ElementRepr> PairingApi for PairingOrWellKnownCurve<FE> {
+    fn pair(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        Self::run(bytes, ctx, PairingOutput::IsOne, PairingApiImplementation::<FE>::pair)
+    }
+
+    fn pair_to_gt(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        Self::run(bytes, ctx, PairingOutput::Value, PairingApiImplementation::<FE>::pair_to_gt)
+    }
+
+    fn miller_loop(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        Self::run(bytes, ctx, PairingOutput::MillerLoop, PairingApiImplementation::<FE>::miller_loop)
+    }
+
+    fn final_exp(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        Self::run(bytes, ctx, PairingOutput::FinalExponentiation, PairingApiImplementation::<FE>::final_exp)
+    }
+
+    fn pairing_equation(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        Self::run(bytes, ctx, PairingOutput::Equation, PairingApiImplementation::<FE>::pairing_equation)
+    }
+
+    fn bls_verify(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        Self::run(bytes, ctx, PairingOutput::BlsSignature, PairingApiImplementation::<FE>::bls_verify)
+    }
+
+    fn kzg_verify(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        Self::run(bytes, ctx, PairingOutput::KzgOpening, PairingApiImplementation::<FE>::kzg_verify)
+    }
+}
+
+macro_rules! specialize_for_limbs {
+    ($($name: ident => $repr: ty),*) => {
+        $(
+            pub(crate) struct $name;
+
+            impl super::Operations for $name {
+                type G1 = super::g1_ops::G1ApiImplementation<$repr>;
+                type G2 = G2ByExtensionDegree<$repr>;
+                type Pairing = PairingOrWellKnownCurve<$repr>;
+                type Edwards = super::edwards_ops::EdwardsApiImplementation<$repr>;
+                type Montgomery = super::montgomery_ops::MontgomeryApiImplementation<$repr>;
+                type BinaryCurve = super::binary_ops::BinaryCurveApiImplementation<$repr>;
+                type Field = super::field_ops::FieldApiImplementation<$repr>;
+                #[cfg(feature = "mappings")]
+                type Mapping = super::mapping_ops::MappingApiImplementation<$repr>;
+            }
+        )*
+    };
+}
+
+specialize_for_limbs!(
+    Limbs4 => crate::field::U256Repr,
+    Limbs6 => crate::field::U384Repr,
+    Limbs8 => crate::field::U512Repr
+);
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigUint;
+    use num_traits::Num;
+
+    use super::*;
+    use crate::public_interface::{API, Operations, PublicOperations};
+    use crate::test::parsers::pad_for_len_be;
+
+    fn field_exp(modulus: &BigUint, base: &BigUint, exponent: &[u8]) -> Vec<u8> {
+        let modulus_len = (modulus.bits() as usize + 7) / 8;
+        let mut input = vec![modulus_len as u8];
+        input.extend(pad_for_len_be(modulus.to_bytes_be(), modulus_len));
+        input.extend(pad_for_len_be(base.to_bytes_be(), modulus_len));
+        input.push(exponent.len() as u8);
+        input.extend_from_slice(exponent);
+
+        input
+    }
+
+    fn check_width<O: Operations>(op_type: u8, input: &[u8], limbs: usize) -> Vec<u8> {
+        assert_eq!(modulus_limbs(op_type, input), Some(limbs));
+
+        let ctx = ExecutionContext::default();
+        let result = API::run_operation::<O>(op_type, input, &ctx).unwrap();
+        assert_eq!(API::run_operation::<PublicOperations>(op_type, input, &ctx).unwrap(), result);
+
+        let mut encoding = vec![op_type];
+        encoding.extend_from_slice(input);
+        assert_eq!(API::run(&encoding).unwrap(), result);
+
+        result
+    }
+
+    #[test]
+    fn test_field_exponentiation_for_each_width() {
+        // BN254, BLS12-381 and 2^511 - 187 base fields
+        let moduli = vec![
+            ("21888242871839275222246405745257275088696311157297823662689037894645226208583", 4),
+            ("4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559787", 6),
+            ("6703903964971298549787012499102923063739682910296196688861780721860882015036773488400937149083451713845015929093243025426876941405973284973216824503041861", 8)
+        ];
+        let base = BigUint::from(0x1234567u64);
+        let exponent = [0x0a, 0xbc, 0xde, 0xf1];
+        for (modulus, limbs) in moduli.into_iter() {
+            let modulus = BigUint::from_str_radix(modulus, 10).unwrap();
+            let input = field_exp(&modulus, &base, &exponent);
+            let result = match limbs {
+                4 => check_width::<Limbs4>(OPERATION_FIELD_EXP, &input, limbs),
+                6 => check_width::<Limbs6>(OPERATION_FIELD_EXP, &input, limbs),
+                _ => check_width::<Limbs8>(OPERATION_FIELD_EXP, &input, limbs)
+            };
+
+            let expected = base.modpow(&BigUint::from_bytes_be(&exponent), &modulus);
+            assert_eq!(BigUint::from_bytes_be(&result), expected);
+        }
+    }
+
+    #[test]
+    fn test_pairings_for_each_width() {
+        use crate::test::pairings::bn::assemble_bn254;
+        use crate::test::pairings::bls12::{assemble_bls12_381, assemble_bls12_377};
+
+        // fast paths of BN254 and BLS12-381 and the generic BLS12 implementation
+        assert_eq!(check_width::<Limbs4>(OPERATION_PAIRING, &assemble_bn254(2), 4), vec![BOOLEAN_TRUE]);
+        assert_eq!(check_width::<Limbs6>(OPERATION_PAIRING, &assemble_bls12_381(2), 6), vec![BOOLEAN_TRUE]);
+        assert_eq!(check_width::<Limbs6>(OPERATION_PAIRING, &assemble_bls12_377(2), 6), vec![BOOLEAN_TRUE]);
+    }
+}
@@ -16,33 +16,47 @@
 /// - one byte for length encoding
 
 use crate::weierstrass::{Group, CurveOverFpParameters};
-use crate::weierstrass::curve::{CurvePoint, WeierstrassCurve};
+use crate::weierstrass::curve::{CurvePoint, WeierstrassCurve, sum_points};
+use crate::weierstrass::glv::detect_glv_endomorphism_for_decoded_curve;
 use crate::representation::ElementRepr;
+use crate::traits::ZeroizeSecret;
 use crate::multiexp::peppinger;
 use crate::field::*;
 use super::constants::*;
 
 use super::decode_g1::*;
 use super::decode_utils::*;
+use super::point_encoding::point_len;
 use super::decode_fp::*;
 
-use crate::errors::ApiError;
+use crate::errors::{ApiError, ErrorCode};
+use crate::execution_context::ExecutionContext;
+use crate::alloc_prelude::*;
 
 pub trait G1Api {
-    fn add_points(bytes: &[u8]) -> Result<Vec<u8>, ApiError>;
-    fn mul_point(bytes: &[u8]) -> Result<Vec<u8>, ApiError>;
-    fn multiexp(bytes: &[u8]) -> Result<Vec<u8>, ApiError>;
+    fn add_points(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError>;
+    fn mul_point(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError>;
+    fn multiexp(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError>;
+    /// Sum of the number of points that follow the curve parameters
+    fn sum_points(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError>;
+    /// Returns a single boolean byte: whether the point is in the subgroup of the declared order
+    fn check_subgroup(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError>;
+    /// Multiplies the point by the cofactor that follows it in the input
+    fn clear_cofactor(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError>;
+    /// Returns a single byte that tells if the point is valid, is the point at infinity,
+    /// is not on the curve or has a coordinate that is not a field element
+    fn validate_point(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError>;
 }
 
 pub struct G1ApiImplementation<FE: ElementRepr> {
-    _marker_fe: std::marker::PhantomData<FE>,
+    _marker_fe: core::marker::PhantomData<FE>,
 }
 
 impl<FE: ElementRepr> G1Api for G1ApiImplementation<FE> {
-    fn add_points(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+    fn add_points(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
         let (field, modulus_len, _, rest) = parse_base_field_from_encoding::<FE>(&bytes)?;
         let (a, b, rest) = parse_ab_in_base_field_from_encoding(&rest, modulus_len, &field)?;
-        let (_order_len, order, rest) = parse_group_order_from_encoding(rest)?;
+        let (_order_len, order, rest) = parse_group_order_from_encoding(rest, ctx)?;
 
         let fp_params = CurveOverFpParameters::new(&field);
 
@@ -50,21 +64,21 @@ impl<FE: ElementRepr> G1Api for G1ApiImplementation<FE> {
             ApiError::InputError("Curve shape is not supported".to_owned())
         })?;
 
-        let (mut p_0, rest) = decode_g1_point_from_xy(rest, modulus_len, &curve)?;
-        let (p_1, rest) = decode_g1_point_from_xy(rest, modulus_len, &curve)?;
+        let (mut p_0, rest) = decode_g1_point_from_xy(rest, modulus_len, &curve, ctx.point_encoding())?;
+        let (p_1, rest) = decode_g1_point_from_xy(rest, modulus_len, &curve, ctx.point_encoding())?;
 
         if rest.len() != 0 {
-            return Err(ApiError::InputError("Input contains garbage at the end".to_owned()));
+            return Err(ApiError::garbage_at_the_end(rest));
         }
 
         if !p_0.is_on_curve() {
             if !crate::features::in_fuzzing_or_gas_metering() {
-                return Err(ApiError::InputError(format!("Point 0 is not on curve, file {}, line {}", file!(), line!())));
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point 0 is not on curve, file {}, line {}", file!(), line!())));
             }
         }
         if !p_1.is_on_curve() {
             if !crate::features::in_fuzzing_or_gas_metering() {
-                return Err(ApiError::InputError(format!("Point 1 is not on curve, file {}, line {}", file!(), line!())));
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point 1 is not on curve, file {}, line {}", file!(), line!())));
             }
         }
 
@@ -73,39 +87,44 @@ impl<FE: ElementRepr> G1Api for G1ApiImplementation<FE> {
         serialize_g1_point(modulus_len, &p_0)   
     }
 
-    fn mul_point(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+    fn mul_point(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
         let (field, modulus_len, _, rest) = parse_base_field_from_encoding::<FE>(&bytes)?;
         let (a, b, rest) = parse_ab_in_base_field_from_encoding(&rest, modulus_len, &field)?;
-        let (order_len, order, rest) = parse_group_order_from_encoding(rest)?;
+        let (order_len, order, rest) = parse_group_order_from_encoding(rest, ctx)?;
 
         let fp_params = CurveOverFpParameters::new(&field);
 
-        let curve = WeierstrassCurve::new(&order.as_ref(), a, b, &fp_params).map_err(|_| {
+        let mut curve = WeierstrassCurve::new(order.as_ref(), a, b, &fp_params).map_err(|_| {
             ApiError::InputError("Curve shape is not supported".to_owned())
         })?;
+        // fuzzing and gas metering allow points that are not on the curve, and GLV is only valid in the subgroup
+        if !crate::features::in_fuzzing_or_gas_metering() {
+            curve.endomorphism = detect_glv_endomorphism_for_decoded_curve(&curve, ctx);
+        }
 
-        let (p_0, rest) = decode_g1_point_from_xy(rest, modulus_len, &curve)?;
-        let (scalar, rest) = decode_scalar_representation(rest, order_len)?;
+        let (p_0, rest) = decode_g1_point_from_xy(rest, modulus_len, &curve, ctx.point_encoding())?;
+        let (mut scalar, rest) = decode_scalar_representation(rest, order_len)?;
 
         if rest.len() != 0 {
-            return Err(ApiError::InputError("Input contains garbage at the end".to_owned()));
+            return Err(ApiError::garbage_at_the_end(rest));
         }
 
         if !p_0.is_on_curve() {
             if !crate::features::in_fuzzing_or_gas_metering() {
-                return Err(ApiError::InputError(format!("Point is not on curve, file {}, line {}", file!(), line!())));
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point is not on curve, file {}, line {}", file!(), line!())));
             }
         }
 
         let p = p_0.mul(&scalar);
+        scalar.as_mut().zeroize_secret();
 
         serialize_g1_point(modulus_len, &p)   
     }
 
-    fn multiexp(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+    fn multiexp(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
         let (field, modulus_len, _, rest) = parse_base_field_from_encoding::<FE>(&bytes)?;
         let (a, b, rest) = parse_ab_in_base_field_from_encoding(&rest, modulus_len, &field)?;
-        let (order_len, order, rest) = parse_group_order_from_encoding(rest)?;
+        let (order_len, order, rest) = parse_group_order_from_encoding(rest, ctx)?;
 
         let fp_params = CurveOverFpParameters::new(&field);
 
@@ -115,13 +134,14 @@ impl<FE: ElementRepr> G1Api for G1ApiImplementation<FE> {
 
         let (num_pairs_encoding, rest) = split(rest, BYTES_FOR_LENGTH_ENCODING, "Input is not long enough to get number of pairs")?;
         let num_pairs = num_pairs_encoding[0] as usize;
+        ctx.report().record_num_pairs(num_pairs);
 
         if num_pairs == 0 {
             return Err(ApiError::InputError("Invalid number of pairs".to_owned()));
         }
 
-        let expected_pair_len = 2*modulus_len + order_len;
-        if rest.len() != expected_pair_len * num_pairs {
+        let expected_pair_len = checked_len_add(point_len(modulus_len, ctx.point_encoding())?, order_len)?;
+        if rest.len() != checked_len_mul(expected_pair_len, num_pairs)? {
             return Err(ApiError::InputError("Input length is invalid for number of pairs".to_owned()));
         }
 
@@ -130,11 +150,11 @@ impl<FE: ElementRepr> G1Api for G1ApiImplementation<FE> {
         let mut scalars = Vec::with_capacity(num_pairs);
 
         for _ in 0..num_pairs {
-            let (p, local_rest) = decode_g1_point_from_xy(global_rest, modulus_len, &curve)?;
+            let (p, local_rest) = decode_g1_point_from_xy(global_rest, modulus_len, &curve, ctx.point_encoding())?;
             let (scalar, local_rest) = decode_scalar_representation(local_rest, order_len)?;
             if !p.is_on_curve() {
                 if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError(format!("Point is not on curve, file {}, line {}", file!(), line!())));
+                    return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point is not on curve, file {}, line {}", file!(), line!())));
                 }
             }
             bases.push(p);
@@ -143,7 +163,7 @@ impl<FE: ElementRepr> G1Api for G1ApiImplementation<FE> {
         }
 
         if global_rest.len() != 0 {
-            return Err(ApiError::InputError("Input contains garbage at the end".to_owned()));
+            return Err(ApiError::garbage_at_the_end(global_rest));
         }
 
         if bases.len() != scalars.len() || bases.len() == 0 {
@@ -155,38 +175,204 @@ impl<FE: ElementRepr> G1Api for G1ApiImplementation<FE> {
             }
         } 
 
-        let result = peppinger(&bases, scalars);
+        let result = peppinger(&bases, scalars, ctx);
 
         serialize_g1_point(modulus_len, &result)   
     }
+
+    fn sum_points(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (field, modulus_len, _, rest) = parse_base_field_from_encoding::<FE>(&bytes)?;
+        let (a, b, rest) = parse_ab_in_base_field_from_encoding(&rest, modulus_len, &field)?;
+        let (_order_len, order, rest) = parse_group_order_from_encoding(rest, ctx)?;
+
+        let fp_params = CurveOverFpParameters::new(&field);
+
+        let curve = WeierstrassCurve::new(&order.as_ref(), a, b, &fp_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        let (num_points_encoding, rest) = split(rest, BYTES_FOR_LENGTH_ENCODING, "Input is not long enough to get number of points")?;
+        let num_points = num_points_encoding[0] as usize;
+
+        if num_points == 0 {
+            return Err(ApiError::InputError("Invalid number of points".to_owned()));
+        }
+
+        if rest.len() != checked_len_mul(point_len(modulus_len, ctx.point_encoding())?, num_points)? {
+            return Err(ApiError::InputError("Input length is invalid for number of points".to_owned()));
+        }
+
+        let mut global_rest = rest;
+        let mut points = Vec::with_capacity(num_points);
+
+        for i in 0..num_points {
+            let (p, local_rest) = decode_g1_point_from_xy(global_rest, modulus_len, &curve, ctx.point_encoding())?;
+            if !p.is_on_curve() {
+                if !crate::features::in_fuzzing_or_gas_metering() {
+                    return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point {} is not on curve, file {}, line {}", i, file!(), line!())).at(global_rest));
+                }
+            }
+            points.push(p);
+            global_rest = local_rest;
+        }
+
+        let result = sum_points(&curve, points, ctx);
+
+        serialize_g1_point(modulus_len, &result)
+    }
+
+    fn check_subgroup(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (field, modulus_len, _, rest) = parse_base_field_from_encoding::<FE>(&bytes)?;
+        let (a, b, rest) = parse_ab_in_base_field_from_encoding(&rest, modulus_len, &field)?;
+        let (_order_len, order, rest) = parse_group_order_from_encoding(rest, ctx)?;
+
+        let fp_params = CurveOverFpParameters::new(&field);
+
+        let curve = WeierstrassCurve::new(&order.as_ref(), a, b, &fp_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        let (p_0, rest) = decode_g1_point_from_xy(rest, modulus_len, &curve, ctx.point_encoding())?;
+
+        if rest.len() != 0 {
+            return Err(ApiError::garbage_at_the_end(rest));
+        }
+
+        if !p_0.is_on_curve() {
+            if !crate::features::in_fuzzing_or_gas_metering() {
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point is not on curve, file {}, line {}", file!(), line!())));
+            }
+        }
+
+        Ok(encode_boolean(p_0.check_correct_subgroup()))
+    }
+
+    fn clear_cofactor(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (field, modulus_len, _, rest) = parse_base_field_from_encoding::<FE>(&bytes)?;
+        let (a, b, rest) = parse_ab_in_base_field_from_encoding(&rest, modulus_len, &field)?;
+        let (_order_len, order, rest) = parse_group_order_from_encoding(rest, ctx)?;
+
+        let fp_params = CurveOverFpParameters::new(&field);
+
+        let curve = WeierstrassCurve::new(&order.as_ref(), a, b, &fp_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        let (p_0, rest) = decode_g1_point_from_xy(rest, modulus_len, &curve, ctx.point_encoding())?;
+        let (_cofactor_len, cofactor, rest) = parse_cofactor_from_encoding(rest)?;
+
+        if rest.len() != 0 {
+            return Err(ApiError::garbage_at_the_end(rest));
+        }
+
+        if !p_0.is_on_curve() {
+            if !crate::features::in_fuzzing_or_gas_metering() {
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point is not on curve, file {}, line {}", file!(), line!())));
+            }
+        }
+
+        let p = p_0.mul(&cofactor.as_ref());
+
+        serialize_g1_point(modulus_len, &p)
+    }
+
+    fn validate_point(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (field, modulus_len, modulus, rest) = parse_base_field_from_encoding::<FE>(&bytes)?;
+        let (a, b, rest) = parse_ab_in_base_field_from_encoding(&rest, modulus_len, &field)?;
+        let (_order_len, order, rest) = parse_group_order_from_encoding(rest, ctx)?;
+
+        let fp_params = CurveOverFpParameters::new(&field);
+
+        let curve = WeierstrassCurve::new(&order.as_ref(), a, b, &fp_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        let (point_encoding, rest) = split(rest, point_len(modulus_len, ctx.point_encoding())?, "Input is not long enough to get point")?;
+
+        if rest.len() != 0 {
+            return Err(ApiError::garbage_at_the_end(rest));
+        }
+
+        if !encoded_elements_are_in_field(point_encoding, modulus_len, &modulus) {
+            return Ok(vec![POINT_COORDINATE_NOT_IN_FIELD]);
+        }
+
+        let (p_0, _) = decode_g1_point_from_xy(point_encoding, modulus_len, &curve, ctx.point_encoding())?;
+
+        let result = if p_0.is_zero() {
+            POINT_IS_AT_INFINITY
+        } else if !p_0.is_on_curve() {
+            POINT_IS_NOT_ON_CURVE
+        } else {
+            POINT_IS_VALID
+        };
+
+        Ok(vec![result])
+    }
 }
 
 pub struct PublicG1Api;
 
 impl G1Api for PublicG1Api {
-    fn add_points(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+    fn add_points(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
         let (_, modulus, _) = parse_modulus_and_length(&bytes)?;
         let modulus_limbs = num_limbs_for_modulus(&modulus)?;
 
-        let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, G1ApiImplementation, bytes, add_points); 
+        let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, G1ApiImplementation, bytes, add_points, ctx); 
 
         result
     }
 
-    fn mul_point(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+    fn mul_point(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
         let (_, modulus, _) = parse_modulus_and_length(&bytes)?;
         let modulus_limbs = num_limbs_for_modulus(&modulus)?;
         
-        let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, G1ApiImplementation, bytes, mul_point); 
+        let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, G1ApiImplementation, bytes, mul_point, ctx); 
+
+        result
+    }
+
+    fn multiexp(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (_, modulus, _) = parse_modulus_and_length(&bytes)?;
+        let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+
+        let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, G1ApiImplementation, bytes, multiexp, ctx); 
+
+        result
+    }
+
+    fn sum_points(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (_, modulus, _) = parse_modulus_and_length(&bytes)?;
+        let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+
+        let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, G1ApiImplementation, bytes, sum_points, ctx); 
+
+        result
+    }
+
+    fn check_subgroup(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (_, modulus, _) = parse_modulus_and_length(&bytes)?;
+        let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+
+        let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, G1ApiImplementation, bytes, check_subgroup, ctx); 
+
+        result
+    }
+
+    fn clear_cofactor(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (_, modulus, _) = parse_modulus_and_length(&bytes)?;
+        let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+
+        let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, G1ApiImplementation, bytes, clear_cofactor, ctx); 
 
         result
     }
 
-    fn multiexp(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+    fn validate_point(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
         let (_, modulus, _) = parse_modulus_and_length(&bytes)?;
         let modulus_limbs = num_limbs_for_modulus(&modulus)?;
 
-        let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, G1ApiImplementation, bytes, multiexp); 
+        let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, G1ApiImplementation, bytes, validate_point, ctx); 
 
         result
     }
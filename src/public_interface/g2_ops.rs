@@ -1,17 +1,22 @@
 use crate::weierstrass::{Group, CurveOverFp2Parameters, CurveOverFp3Parameters};
-use crate::weierstrass::curve::{CurvePoint, WeierstrassCurve};
+use crate::weierstrass::curve::{CurvePoint, WeierstrassCurve, sum_points};
 use crate::representation::ElementRepr;
+use crate::traits::ZeroizeSecret;
 use crate::multiexp::peppinger;
 
 use crate::field::*;
 
 use super::decode_utils::*;
+use super::point_encoding::point_len;
 use super::decode_g2::*;
 use super::decode_g1::*;
 use super::constants::*;
 use super::decode_fp::*;
+use super::pairing_ops::twist_b;
 
-use crate::errors::ApiError;
+use crate::errors::{ApiError, ErrorCode};
+use crate::execution_context::ExecutionContext;
+use crate::alloc_prelude::*;
 
 /// Every call has common parameters (may be redundant):
 /// - Lengths of modulus (in bytes)
@@ -24,21 +29,30 @@ use crate::errors::ApiError;
 /// - Curve order
 
 pub trait G2Api {
-    fn add_points(bytes: &[u8]) -> Result<Vec<u8>, ApiError>;
-    fn mul_point(bytes: &[u8]) -> Result<Vec<u8>, ApiError>;
-    fn multiexp(bytes: &[u8]) -> Result<Vec<u8>, ApiError>;
+    fn add_points(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError>;
+    fn mul_point(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError>;
+    fn multiexp(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError>;
+    /// Sum of the number of points that follow the curve parameters
+    fn sum_points(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError>;
+    /// Returns a single boolean byte: whether the point is in the subgroup of the declared order
+    fn check_subgroup(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError>;
+    /// Multiplies the point by the cofactor that follows it in the input
+    fn clear_cofactor(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError>;
+    /// Returns a single byte that tells if the point is valid, is the point at infinity,
+    /// is not on the curve or has a coordinate that is not a field element
+    fn validate_point(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError>;
 }
 
 pub struct G2ApiImplementationFp2<FE: ElementRepr> {
-    _marker_fe: std::marker::PhantomData<FE>,
+    _marker_fe: core::marker::PhantomData<FE>,
 }
 
 impl<FE: ElementRepr> G2Api for G2ApiImplementationFp2<FE> {
-    fn add_points(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+    fn add_points(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
         let (field, modulus_len, modulus, rest) = parse_base_field_from_encoding::<FE>(&bytes)?;
         let (extension_2, rest) = create_fp2_extension(rest, &modulus, modulus_len, &field, false)?;
         let (a, b, rest) = parse_ab_in_fp2_from_encoding(&rest, modulus_len, &extension_2)?;
-        let (_order_len, order, rest) = parse_group_order_from_encoding(rest)?;
+        let (_order_len, order, rest) = parse_group_order_from_encoding(rest, ctx)?;
 
         let fp2_params = CurveOverFp2Parameters::new(&extension_2);
 
@@ -46,21 +60,21 @@ impl<FE: ElementRepr> G2Api for G2ApiImplementationFp2<FE> {
             ApiError::InputError("Curve shape is not supported".to_owned())
         })?;
 
-        let (mut p_0, rest) = decode_g2_point_from_xy_in_fp2(rest, modulus_len, &curve)?;
-        let (p_1, rest) = decode_g2_point_from_xy_in_fp2(rest, modulus_len, &curve)?;
+        let (mut p_0, rest) = decode_g2_point_from_xy_in_fp2(rest, modulus_len, &curve, ctx.point_encoding())?;
+        let (p_1, rest) = decode_g2_point_from_xy_in_fp2(rest, modulus_len, &curve, ctx.point_encoding())?;
 
         if rest.len() != 0 {
-            return Err(ApiError::InputError("Input contains garbage at the end".to_owned()));
+            return Err(ApiError::garbage_at_the_end(rest));
         }
 
         if !p_0.is_on_curve() {
             if !crate::features::in_fuzzing_or_gas_metering() {
-                return Err(ApiError::InputError(format!("Point 0 is not on curve, file {}, line {}", file!(), line!())));
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point 0 is not on curve, file {}, line {}", file!(), line!())));
             }
         }
         if !p_1.is_on_curve() {
             if !crate::features::in_fuzzing_or_gas_metering() {
-                return Err(ApiError::InputError(format!("Point 1 is not on curve, file {}, line {}", file!(), line!())));
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point 1 is not on curve, file {}, line {}", file!(), line!())));
             }
         }
 
@@ -69,11 +83,11 @@ impl<FE: ElementRepr> G2Api for G2ApiImplementationFp2<FE> {
         serialize_g2_point_in_fp2(modulus_len, &p_0)   
     }
 
-    fn mul_point(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+    fn mul_point(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
         let (field, modulus_len, modulus, rest) = parse_base_field_from_encoding::<FE>(&bytes)?;
         let (extension_2, rest) = create_fp2_extension(rest, &modulus, modulus_len, &field, false)?;
         let (a, b, rest) = parse_ab_in_fp2_from_encoding(&rest, modulus_len, &extension_2)?;
-        let (order_len, order, rest) = parse_group_order_from_encoding(rest)?;
+        let (order_len, order, rest) = parse_group_order_from_encoding(rest, ctx)?;
 
         let fp2_params = CurveOverFp2Parameters::new(&extension_2);
 
@@ -81,29 +95,30 @@ impl<FE: ElementRepr> G2Api for G2ApiImplementationFp2<FE> {
             ApiError::InputError("Curve shape is not supported".to_owned())
         })?;
 
-        let (p_0, rest) = decode_g2_point_from_xy_in_fp2(rest, modulus_len, &curve)?;
-        let (scalar, rest) = decode_scalar_representation(rest, order_len)?;
+        let (p_0, rest) = decode_g2_point_from_xy_in_fp2(rest, modulus_len, &curve, ctx.point_encoding())?;
+        let (mut scalar, rest) = decode_scalar_representation(rest, order_len)?;
 
         if rest.len() != 0 {
-            return Err(ApiError::InputError("Input contains garbage at the end".to_owned()));
+            return Err(ApiError::garbage_at_the_end(rest));
         }
 
         if !p_0.is_on_curve() {
             if !crate::features::in_fuzzing_or_gas_metering() {
-                return Err(ApiError::InputError(format!("Point is not on curve, file {}, line {}", file!(), line!())));
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point is not on curve, file {}, line {}", file!(), line!())));
             }
         }
 
         let p = p_0.mul(&scalar);
+        scalar.as_mut().zeroize_secret();
 
         serialize_g2_point_in_fp2(modulus_len, &p)   
     }
 
-    fn multiexp(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+    fn multiexp(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
         let (field, modulus_len, modulus, rest) = parse_base_field_from_encoding::<FE>(&bytes)?;
         let (extension_2, rest) = create_fp2_extension(&rest, &modulus, modulus_len, &field, false)?;
         let (a, b, rest) = parse_ab_in_fp2_from_encoding(&rest, modulus_len, &extension_2)?;
-        let (order_len, order, rest) = parse_group_order_from_encoding(rest)?;
+        let (order_len, order, rest) = parse_group_order_from_encoding(rest, ctx)?;
 
         let fp2_params = CurveOverFp2Parameters::new(&extension_2);
 
@@ -113,13 +128,14 @@ impl<FE: ElementRepr> G2Api for G2ApiImplementationFp2<FE> {
 
         let (num_pairs_encoding, rest) = split(rest, BYTES_FOR_LENGTH_ENCODING, "Input is not long enough to get number of pairs")?;
         let num_pairs = num_pairs_encoding[0] as usize;
+        ctx.report().record_num_pairs(num_pairs);
 
         if num_pairs == 0 {
             return Err(ApiError::InputError("Invalid number of pairs".to_owned()));
         }
 
-        let expected_pair_len = 4*modulus_len + order_len;
-        if rest.len() != expected_pair_len * num_pairs {
+        let expected_pair_len = checked_len_add(point_len(checked_len_mul(2, modulus_len)?, ctx.point_encoding())?, order_len)?;
+        if rest.len() != checked_len_mul(expected_pair_len, num_pairs)? {
             return Err(ApiError::InputError("Input length is invalid for number of pairs".to_owned()));
         }
 
@@ -128,10 +144,10 @@ impl<FE: ElementRepr> G2Api for G2ApiImplementationFp2<FE> {
         let mut scalars = Vec::with_capacity(num_pairs);
 
         for _ in 0..num_pairs {
-            let (p, local_rest) = decode_g2_point_from_xy_in_fp2(global_rest, modulus_len, &curve)?;
+            let (p, local_rest) = decode_g2_point_from_xy_in_fp2(global_rest, modulus_len, &curve, ctx.point_encoding())?;
             if !p.is_on_curve() {
                 if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError(format!("Point is not on curve, file {}, line {}", file!(), line!())));
+                    return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point is not on curve, file {}, line {}", file!(), line!())));
                 }
             }
             let (scalar, local_rest) = decode_scalar_representation(local_rest, order_len)?;
@@ -141,7 +157,7 @@ impl<FE: ElementRepr> G2Api for G2ApiImplementationFp2<FE> {
         }
 
         if global_rest.len() != 0 {
-            return Err(ApiError::InputError("Input contains garbage at the end".to_owned()));
+            return Err(ApiError::garbage_at_the_end(global_rest));
         }
 
         if bases.len() != scalars.len() || bases.len() == 0 {
@@ -153,22 +169,177 @@ impl<FE: ElementRepr> G2Api for G2ApiImplementationFp2<FE> {
             }
         } 
 
-        let result = peppinger(&bases, scalars);
+        let result = peppinger(&bases, scalars, ctx);
 
         serialize_g2_point_in_fp2(modulus_len, &result)   
     }
+
+    fn sum_points(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (field, modulus_len, modulus, rest) = parse_base_field_from_encoding::<FE>(&bytes)?;
+        let (extension_2, rest) = create_fp2_extension(&rest, &modulus, modulus_len, &field, false)?;
+        let (a, b, rest) = parse_ab_in_fp2_from_encoding(&rest, modulus_len, &extension_2)?;
+        let (_order_len, order, rest) = parse_group_order_from_encoding(rest, ctx)?;
+
+        let fp2_params = CurveOverFp2Parameters::new(&extension_2);
+
+        let curve = WeierstrassCurve::new(&order.as_ref(), a, b, &fp2_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        let (num_points_encoding, rest) = split(rest, BYTES_FOR_LENGTH_ENCODING, "Input is not long enough to get number of points")?;
+        let num_points = num_points_encoding[0] as usize;
+
+        if num_points == 0 {
+            return Err(ApiError::InputError("Invalid number of points".to_owned()));
+        }
+
+        if rest.len() != checked_len_mul(point_len(checked_len_mul(2, modulus_len)?, ctx.point_encoding())?, num_points)? {
+            return Err(ApiError::InputError("Input length is invalid for number of points".to_owned()));
+        }
+
+        let mut global_rest = rest;
+        let mut points = Vec::with_capacity(num_points);
+
+        for i in 0..num_points {
+            let (p, local_rest) = decode_g2_point_from_xy_in_fp2(global_rest, modulus_len, &curve, ctx.point_encoding())?;
+            if !p.is_on_curve() {
+                if !crate::features::in_fuzzing_or_gas_metering() {
+                    return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point {} is not on curve, file {}, line {}", i, file!(), line!())).at(global_rest));
+                }
+            }
+            points.push(p);
+            global_rest = local_rest;
+        }
+
+        let result = sum_points(&curve, points, ctx);
+
+        serialize_g2_point_in_fp2(modulus_len, &result)
+    }
+
+    fn check_subgroup(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (field, modulus_len, modulus, rest) = parse_base_field_from_encoding::<FE>(&bytes)?;
+        let (extension_2, rest) = create_fp2_extension(rest, &modulus, modulus_len, &field, false)?;
+        let (a, b, rest) = parse_ab_in_fp2_from_encoding(&rest, modulus_len, &extension_2)?;
+        let (_order_len, order, rest) = parse_group_order_from_encoding(rest, ctx)?;
+
+        let fp2_params = CurveOverFp2Parameters::new(&extension_2);
+
+        let curve = WeierstrassCurve::new(&order.as_ref(), a, b, &fp2_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        let (p_0, rest) = decode_g2_point_from_xy_in_fp2(rest, modulus_len, &curve, ctx.point_encoding())?;
+
+        if rest.len() != 0 {
+            return Err(ApiError::garbage_at_the_end(rest));
+        }
+
+        if !p_0.is_on_curve() {
+            if !crate::features::in_fuzzing_or_gas_metering() {
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point is not on curve, file {}, line {}", file!(), line!())));
+            }
+        }
+
+        Ok(encode_boolean(p_0.check_correct_subgroup()))
+    }
+
+    fn clear_cofactor(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (field, modulus_len, modulus, rest) = parse_base_field_from_encoding::<FE>(&bytes)?;
+        let (extension_2, rest) = create_fp2_extension(rest, &modulus, modulus_len, &field, false)?;
+        let (a, b, rest) = parse_ab_in_fp2_from_encoding(&rest, modulus_len, &extension_2)?;
+        let (_order_len, order, rest) = parse_group_order_from_encoding(rest, ctx)?;
+
+        let fp2_params = CurveOverFp2Parameters::new(&extension_2);
+
+        let curve = WeierstrassCurve::new(&order.as_ref(), a, b, &fp2_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        let (p_0, rest) = decode_g2_point_from_xy_in_fp2(rest, modulus_len, &curve, ctx.point_encoding())?;
+        let (_cofactor_len, cofactor, rest) = parse_cofactor_from_encoding(rest)?;
+
+        if rest.len() != 0 {
+            return Err(ApiError::garbage_at_the_end(rest));
+        }
+
+        if !p_0.is_on_curve() {
+            if !crate::features::in_fuzzing_or_gas_metering() {
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point is not on curve, file {}, line {}", file!(), line!())));
+            }
+        }
+
+        let p = p_0.mul(&cofactor.as_ref());
+
+        serialize_g2_point_in_fp2(modulus_len, &p)
+    }
+
+    fn validate_point(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (field, modulus_len, modulus, rest) = parse_base_field_from_encoding::<FE>(&bytes)?;
+        let (extension_2, rest) = create_fp2_extension(rest, &modulus, modulus_len, &field, false)?;
+        let (a, b, rest) = parse_ab_in_fp2_from_encoding(&rest, modulus_len, &extension_2)?;
+        let (_order_len, order, rest) = parse_group_order_from_encoding(rest, ctx)?;
+
+        let fp2_params = CurveOverFp2Parameters::new(&extension_2);
+
+        let curve = WeierstrassCurve::new(&order.as_ref(), a, b, &fp2_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        let (point_encoding, rest) = split(rest, point_len(checked_len_mul(2, modulus_len)?, ctx.point_encoding())?, "Input is not long enough to get point")?;
+
+        if rest.len() != 0 {
+            return Err(ApiError::garbage_at_the_end(rest));
+        }
+
+        if !encoded_elements_are_in_field(point_encoding, modulus_len, &modulus) {
+            return Ok(vec![POINT_COORDINATE_NOT_IN_FIELD]);
+        }
+
+        let (p_0, _) = decode_g2_point_from_xy_in_fp2(point_encoding, modulus_len, &curve, ctx.point_encoding())?;
+
+        let result = if p_0.is_zero() {
+            POINT_IS_AT_INFINITY
+        } else if !p_0.is_on_curve() {
+            POINT_IS_NOT_ON_CURVE
+        } else {
+            POINT_IS_VALID
+        };
+
+        Ok(vec![result])
+    }
+}
+
+impl<FE: ElementRepr> G2ApiImplementationFp2<FE> {
+    /// Checks that B of the curve is B of the curve over the base field twisted as described
+    /// after the group order, see `AbiVersion::V2`
+    pub(crate) fn check_twist(bytes: &[u8], ctx: &ExecutionContext) -> Result<(), ApiError> {
+        let (field, modulus_len, modulus, rest) = parse_base_field_from_encoding::<FE>(&bytes)?;
+        let (extension_2, rest) = create_fp2_extension(rest, &modulus, modulus_len, &field, false)?;
+        let (_a, b_twist, rest) = parse_ab_in_fp2_from_encoding(&rest, modulus_len, &extension_2)?;
+        let (_order_len, _order, rest) = parse_group_order_from_encoding(rest, ctx)?;
+
+        let (twist_type, rest) = decode_twist_type(rest)?;
+        let (b, rest) = decode_fp(rest, modulus_len, &field)?;
+        let (twist_non_residue, _) = decode_fp2(rest, modulus_len, &extension_2)?;
+
+        if b_twist != twist_b(&b, &twist_non_residue, twist_type)? {
+            return Err(ApiError::InputError("Curve B coefficient does not match the twist description".to_owned()));
+        }
+
+        Ok(())
+    }
 }
 
 pub struct G2ApiImplementationFp3<FE: ElementRepr> {
-    _marker_fe: std::marker::PhantomData<FE>,
+    _marker_fe: core::marker::PhantomData<FE>,
 }
 
 impl<FE: ElementRepr> G2Api for G2ApiImplementationFp3<FE> {
-    fn add_points(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+    fn add_points(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
         let (field, modulus_len, modulus, rest) = parse_base_field_from_encoding::<FE>(&bytes)?;
         let (extension_3, rest) = create_fp3_extension(rest, &modulus, modulus_len, &field, false)?;
         let (a, b, rest) = parse_ab_in_fp3_from_encoding(&rest, modulus_len, &extension_3)?;
-        let (_order_len, order, rest) = parse_group_order_from_encoding(rest)?;
+        let (_order_len, order, rest) = parse_group_order_from_encoding(rest, ctx)?;
 
         let fp3_params = CurveOverFp3Parameters::new(&extension_3);
 
@@ -176,21 +347,21 @@ impl<FE: ElementRepr> G2Api for G2ApiImplementationFp3<FE> {
             ApiError::InputError("Curve shape is not supported".to_owned())
         })?;
 
-        let (mut p_0, rest) = decode_g2_point_from_xy_in_fp3(rest, modulus_len, &curve)?;
-        let (p_1, rest) = decode_g2_point_from_xy_in_fp3(rest, modulus_len, &curve)?;
+        let (mut p_0, rest) = decode_g2_point_from_xy_in_fp3(rest, modulus_len, &curve, ctx.point_encoding())?;
+        let (p_1, rest) = decode_g2_point_from_xy_in_fp3(rest, modulus_len, &curve, ctx.point_encoding())?;
 
         if rest.len() != 0 {
-            return Err(ApiError::InputError("Input contains garbage at the end".to_owned()));
+            return Err(ApiError::garbage_at_the_end(rest));
         }
 
         if !p_0.is_on_curve() {
             if !crate::features::in_fuzzing_or_gas_metering() {
-                return Err(ApiError::InputError(format!("Point 0 is not on curve, file {}, line {}", file!(), line!())));
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point 0 is not on curve, file {}, line {}", file!(), line!())));
             }
         }
         if !p_1.is_on_curve() {
             if !crate::features::in_fuzzing_or_gas_metering() {
-                return Err(ApiError::InputError(format!("Point 1 is not on curve, file {}, line {}", file!(), line!())));
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point 1 is not on curve, file {}, line {}", file!(), line!())));
             }
         }
 
@@ -199,11 +370,11 @@ impl<FE: ElementRepr> G2Api for G2ApiImplementationFp3<FE> {
         serialize_g2_point_in_fp3(modulus_len, &p_0)
     }
 
-    fn mul_point(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+    fn mul_point(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
         let (field, modulus_len, modulus, rest) = parse_base_field_from_encoding::<FE>(&bytes)?;
         let (extension_3, rest) = create_fp3_extension(rest, &modulus, modulus_len, &field, false)?;
         let (a, b, rest) = parse_ab_in_fp3_from_encoding(&rest, modulus_len, &extension_3)?;
-        let (order_len, order, rest) = parse_group_order_from_encoding(rest)?;
+        let (order_len, order, rest) = parse_group_order_from_encoding(rest, ctx)?;
 
         let fp3_params = CurveOverFp3Parameters::new(&extension_3);
 
@@ -211,29 +382,30 @@ impl<FE: ElementRepr> G2Api for G2ApiImplementationFp3<FE> {
             ApiError::InputError("Curve shape is not supported".to_owned())
         })?;
 
-        let (p_0, rest) = decode_g2_point_from_xy_in_fp3(rest, modulus_len, &curve)?;
-        let (scalar, rest) = decode_scalar_representation(rest, order_len)?;
+        let (p_0, rest) = decode_g2_point_from_xy_in_fp3(rest, modulus_len, &curve, ctx.point_encoding())?;
+        let (mut scalar, rest) = decode_scalar_representation(rest, order_len)?;
 
         if rest.len() != 0 {
-            return Err(ApiError::InputError("Input contains garbage at the end".to_owned()));
+            return Err(ApiError::garbage_at_the_end(rest));
         }
 
         if !p_0.is_on_curve() {
             if !crate::features::in_fuzzing_or_gas_metering() {
-                return Err(ApiError::InputError(format!("Point is not on curve, file {}, line {}", file!(), line!())));
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point is not on curve, file {}, line {}", file!(), line!())));
             }
         }
 
         let p = p_0.mul(&scalar);
+        scalar.as_mut().zeroize_secret();
 
         serialize_g2_point_in_fp3(modulus_len, &p)   
     }
 
-    fn multiexp(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+    fn multiexp(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
         let (field, modulus_len, modulus, rest) = parse_base_field_from_encoding::<FE>(&bytes)?;
         let (extension_3, rest) = create_fp3_extension(&rest, &modulus, modulus_len, &field, false)?;
         let (a, b, rest) = parse_ab_in_fp3_from_encoding(&rest, modulus_len, &extension_3)?;
-        let (order_len, order, rest) = parse_group_order_from_encoding(rest)?;
+        let (order_len, order, rest) = parse_group_order_from_encoding(rest, ctx)?;
 
         let fp3_params = CurveOverFp3Parameters::new(&extension_3);
 
@@ -243,13 +415,14 @@ impl<FE: ElementRepr> G2Api for G2ApiImplementationFp3<FE> {
 
         let (num_pairs_encoding, rest) = split(rest, BYTES_FOR_LENGTH_ENCODING, "Input is not long enough to get number of pairs")?;
         let num_pairs = num_pairs_encoding[0] as usize;
+        ctx.report().record_num_pairs(num_pairs);
 
         if num_pairs == 0 {
             return Err(ApiError::InputError("Invalid number of pairs".to_owned()));
         }
 
-        let expected_pair_len = 6*modulus_len + order_len;
-        if rest.len() != expected_pair_len * num_pairs {
+        let expected_pair_len = checked_len_add(point_len(checked_len_mul(3, modulus_len)?, ctx.point_encoding())?, order_len)?;
+        if rest.len() != checked_len_mul(expected_pair_len, num_pairs)? {
             return Err(ApiError::InputError("Input length is invalid for number of pairs".to_owned()));
         }
 
@@ -258,10 +431,10 @@ impl<FE: ElementRepr> G2Api for G2ApiImplementationFp3<FE> {
         let mut scalars = Vec::with_capacity(num_pairs);
 
         for _ in 0..num_pairs {
-            let (p, local_rest) = decode_g2_point_from_xy_in_fp3(global_rest, modulus_len, &curve)?;
+            let (p, local_rest) = decode_g2_point_from_xy_in_fp3(global_rest, modulus_len, &curve, ctx.point_encoding())?;
             if !p.is_on_curve() {
                 if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError(format!("Point is not on curve, file {}, line {}", file!(), line!())));
+                    return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point is not on curve, file {}, line {}", file!(), line!())));
                 }
             }
             let (scalar, local_rest) = decode_scalar_representation(local_rest, order_len)?;
@@ -271,7 +444,7 @@ impl<FE: ElementRepr> G2Api for G2ApiImplementationFp3<FE> {
         }
 
         if global_rest.len() != 0 {
-            return Err(ApiError::InputError("Input contains garbage at the end".to_owned()));
+            return Err(ApiError::garbage_at_the_end(global_rest));
         }
 
         if bases.len() != scalars.len() || bases.len() == 0 {
@@ -283,27 +456,302 @@ impl<FE: ElementRepr> G2Api for G2ApiImplementationFp3<FE> {
             }
         } 
 
-        let result = peppinger(&bases, scalars);
+        let result = peppinger(&bases, scalars, ctx);
 
         serialize_g2_point_in_fp3(modulus_len, &result)   
     }
+
+    fn sum_points(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (field, modulus_len, modulus, rest) = parse_base_field_from_encoding::<FE>(&bytes)?;
+        let (extension_3, rest) = create_fp3_extension(&rest, &modulus, modulus_len, &field, false)?;
+        let (a, b, rest) = parse_ab_in_fp3_from_encoding(&rest, modulus_len, &extension_3)?;
+        let (_order_len, order, rest) = parse_group_order_from_encoding(rest, ctx)?;
+
+        let fp3_params = CurveOverFp3Parameters::new(&extension_3);
+
+        let curve = WeierstrassCurve::new(&order.as_ref(), a, b, &fp3_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        let (num_points_encoding, rest) = split(rest, BYTES_FOR_LENGTH_ENCODING, "Input is not long enough to get number of points")?;
+        let num_points = num_points_encoding[0] as usize;
+
+        if num_points == 0 {
+            return Err(ApiError::InputError("Invalid number of points".to_owned()));
+        }
+
+        if rest.len() != checked_len_mul(point_len(checked_len_mul(3, modulus_len)?, ctx.point_encoding())?, num_points)? {
+            return Err(ApiError::InputError("Input length is invalid for number of points".to_owned()));
+        }
+
+        let mut global_rest = rest;
+        let mut points = Vec::with_capacity(num_points);
+
+        for i in 0..num_points {
+            let (p, local_rest) = decode_g2_point_from_xy_in_fp3(global_rest, modulus_len, &curve, ctx.point_encoding())?;
+            if !p.is_on_curve() {
+                if !crate::features::in_fuzzing_or_gas_metering() {
+                    return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point {} is not on curve, file {}, line {}", i, file!(), line!())).at(global_rest));
+                }
+            }
+            points.push(p);
+            global_rest = local_rest;
+        }
+
+        let result = sum_points(&curve, points, ctx);
+
+        serialize_g2_point_in_fp3(modulus_len, &result)
+    }
+
+    fn check_subgroup(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (field, modulus_len, modulus, rest) = parse_base_field_from_encoding::<FE>(&bytes)?;
+        let (extension_3, rest) = create_fp3_extension(rest, &modulus, modulus_len, &field, false)?;
+        let (a, b, rest) = parse_ab_in_fp3_from_encoding(&rest, modulus_len, &extension_3)?;
+        let (_order_len, order, rest) = parse_group_order_from_encoding(rest, ctx)?;
+
+        let fp3_params = CurveOverFp3Parameters::new(&extension_3);
+
+        let curve = WeierstrassCurve::new(&order.as_ref(), a, b, &fp3_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        let (p_0, rest) = decode_g2_point_from_xy_in_fp3(rest, modulus_len, &curve, ctx.point_encoding())?;
+
+        if rest.len() != 0 {
+            return Err(ApiError::garbage_at_the_end(rest));
+        }
+
+        if !p_0.is_on_curve() {
+            if !crate::features::in_fuzzing_or_gas_metering() {
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point is not on curve, file {}, line {}", file!(), line!())));
+            }
+        }
+
+        Ok(encode_boolean(p_0.check_correct_subgroup()))
+    }
+
+    fn clear_cofactor(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (field, modulus_len, modulus, rest) = parse_base_field_from_encoding::<FE>(&bytes)?;
+        let (extension_3, rest) = create_fp3_extension(rest, &modulus, modulus_len, &field, false)?;
+        let (a, b, rest) = parse_ab_in_fp3_from_encoding(&rest, modulus_len, &extension_3)?;
+        let (_order_len, order, rest) = parse_group_order_from_encoding(rest, ctx)?;
+
+        let fp3_params = CurveOverFp3Parameters::new(&extension_3);
+
+        let curve = WeierstrassCurve::new(&order.as_ref(), a, b, &fp3_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        let (p_0, rest) = decode_g2_point_from_xy_in_fp3(rest, modulus_len, &curve, ctx.point_encoding())?;
+        let (_cofactor_len, cofactor, rest) = parse_cofactor_from_encoding(rest)?;
+
+        if rest.len() != 0 {
+            return Err(ApiError::garbage_at_the_end(rest));
+        }
+
+        if !p_0.is_on_curve() {
+            if !crate::features::in_fuzzing_or_gas_metering() {
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point is not on curve, file {}, line {}", file!(), line!())));
+            }
+        }
+
+        let p = p_0.mul(&cofactor.as_ref());
+
+        serialize_g2_point_in_fp3(modulus_len, &p)
+    }
+
+    fn validate_point(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (field, modulus_len, modulus, rest) = parse_base_field_from_encoding::<FE>(&bytes)?;
+        let (extension_3, rest) = create_fp3_extension(rest, &modulus, modulus_len, &field, false)?;
+        let (a, b, rest) = parse_ab_in_fp3_from_encoding(&rest, modulus_len, &extension_3)?;
+        let (_order_len, order, rest) = parse_group_order_from_encoding(rest, ctx)?;
+
+        let fp3_params = CurveOverFp3Parameters::new(&extension_3);
+
+        let curve = WeierstrassCurve::new(&order.as_ref(), a, b, &fp3_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        let (point_encoding, rest) = split(rest, point_len(checked_len_mul(3, modulus_len)?, ctx.point_encoding())?, "Input is not long enough to get point")?;
+
+        if rest.len() != 0 {
+            return Err(ApiError::garbage_at_the_end(rest));
+        }
+
+        if !encoded_elements_are_in_field(point_encoding, modulus_len, &modulus) {
+            return Ok(vec![POINT_COORDINATE_NOT_IN_FIELD]);
+        }
+
+        let (p_0, _) = decode_g2_point_from_xy_in_fp3(point_encoding, modulus_len, &curve, ctx.point_encoding())?;
+
+        let result = if p_0.is_zero() {
+            POINT_IS_AT_INFINITY
+        } else if !p_0.is_on_curve() {
+            POINT_IS_NOT_ON_CURVE
+        } else {
+            POINT_IS_VALID
+        };
+
+        Ok(vec![result])
+    }
+}
+
+impl<FE: ElementRepr> G2ApiImplementationFp3<FE> {
+    /// Same as `G2ApiImplementationFp2::check_twist`
+    pub(crate) fn check_twist(bytes: &[u8], ctx: &ExecutionContext) -> Result<(), ApiError> {
+        use crate::pairings::TwistType;
+        use crate::traits::FieldElement;
+
+        let (field, modulus_len, modulus, rest) = parse_base_field_from_encoding::<FE>(&bytes)?;
+        let (extension_3, rest) = create_fp3_extension(rest, &modulus, modulus_len, &field, false)?;
+        let (_a, b_twist, rest) = parse_ab_in_fp3_from_encoding(&rest, modulus_len, &extension_3)?;
+        let (_order_len, _order, rest) = parse_group_order_from_encoding(rest, ctx)?;
+
+        let (twist_type, rest) = decode_twist_type(rest)?;
+        let (b, rest) = decode_fp(rest, modulus_len, &field)?;
+        let (twist_non_residue, _) = decode_fp3(rest, modulus_len, &extension_3)?;
+
+        let mut b_expected = match twist_type {
+            TwistType::D => twist_non_residue.inverse().ok_or(ApiError::UnexpectedZero("Twist non-residue must be invertible".to_owned()))?,
+            TwistType::M => twist_non_residue
+        };
+        b_expected.mul_by_fp(&b);
+
+        if b_twist != b_expected {
+            return Err(ApiError::InputError("Curve B coefficient does not match the twist description".to_owned()));
+        }
+
+        Ok(())
+    }
 }
 
 pub struct PublicG2Api;
 
+impl PublicG2Api {
+    /// Checks the twist description of the second version of the ABI, see `AbiVersion::V2`
+    pub(crate) fn check_twist(bytes: &[u8], ctx: &ExecutionContext) -> Result<(), ApiError> {
+        let (modulus, _, extension_degree, _, _) = parse_modulus_and_extension_degree(&bytes)?;
+        let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+
+        match extension_degree {
+            EXTENSION_DEGREE_2 => {
+                expand_for_modulus_limbs!(modulus_limbs, G2ApiImplementationFp2, bytes, check_twist, ctx)
+            },
+            EXTENSION_DEGREE_3 => {
+                expand_for_modulus_limbs!(modulus_limbs, G2ApiImplementationFp3, bytes, check_twist, ctx)
+            },
+            _ => {
+                Err(ApiError::InputError("Invalid extension degree".to_owned()))
+            }
+        }
+    }
+}
+
 impl G2Api for PublicG2Api {
-    fn add_points(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+    fn add_points(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (modulus, _, extension_degree, _, _) = parse_modulus_and_extension_degree(&bytes)?;
+        let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+
+        let result: Result<Vec<u8>, ApiError> = match extension_degree {
+            EXTENSION_DEGREE_2 => {
+                let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, G2ApiImplementationFp2, bytes, add_points, ctx); 
+
+                result
+            },
+            EXTENSION_DEGREE_3 => {
+                let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, G2ApiImplementationFp3, bytes, add_points, ctx); 
+
+                result
+            },
+            _ => {
+                return Err(ApiError::InputError("Invalid extension degree".to_owned()));
+            }
+        };
+
+        result
+    }
+
+    fn mul_point(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (modulus, _, extension_degree, _, _) = parse_modulus_and_extension_degree(&bytes)?;
+        let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+
+        let result: Result<Vec<u8>, ApiError> = match extension_degree {
+            EXTENSION_DEGREE_2 => {
+                let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, G2ApiImplementationFp2, bytes, mul_point, ctx); 
+
+                result
+            },
+            EXTENSION_DEGREE_3 => {
+                let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, G2ApiImplementationFp3, bytes, mul_point, ctx); 
+
+                result
+            },
+            _ => {
+                return Err(ApiError::InputError("Invalid extension degree".to_owned()));
+            }
+        };
+
+        result
+    }
+
+    fn multiexp(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (modulus, _, extension_degree, _, _) = parse_modulus_and_extension_degree(&bytes)?;
+        let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+
+        let result: Result<Vec<u8>, ApiError> = match extension_degree {
+            EXTENSION_DEGREE_2 => {
+                let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, G2ApiImplementationFp2, bytes, multiexp, ctx); 
+
+                result
+            },
+            EXTENSION_DEGREE_3 => {
+                let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, G2ApiImplementationFp3, bytes, multiexp, ctx); 
+
+                result
+            },
+            _ => {
+                return Err(ApiError::InputError("Invalid extension degree".to_owned()));
+            }
+        };
+
+        result
+    }
+
+    fn sum_points(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (modulus, _, extension_degree, _, _) = parse_modulus_and_extension_degree(&bytes)?;
+        let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+
+        let result: Result<Vec<u8>, ApiError> = match extension_degree {
+            EXTENSION_DEGREE_2 => {
+                let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, G2ApiImplementationFp2, bytes, sum_points, ctx); 
+
+                result
+            },
+            EXTENSION_DEGREE_3 => {
+                let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, G2ApiImplementationFp3, bytes, sum_points, ctx); 
+
+                result
+            },
+            _ => {
+                return Err(ApiError::InputError("Invalid extension degree".to_owned()));
+            }
+        };
+
+        result
+    }
+
+    fn check_subgroup(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
         let (modulus, _, extension_degree, _, _) = parse_modulus_and_extension_degree(&bytes)?;
         let modulus_limbs = num_limbs_for_modulus(&modulus)?;
 
         let result: Result<Vec<u8>, ApiError> = match extension_degree {
             EXTENSION_DEGREE_2 => {
-                let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, G2ApiImplementationFp2, bytes, add_points); 
+                let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, G2ApiImplementationFp2, bytes, check_subgroup, ctx); 
 
                 result
             },
             EXTENSION_DEGREE_3 => {
-                let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, G2ApiImplementationFp3, bytes, add_points); 
+                let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, G2ApiImplementationFp3, bytes, check_subgroup, ctx); 
 
                 result
             },
@@ -315,18 +763,18 @@ impl G2Api for PublicG2Api {
         result
     }
 
-    fn mul_point(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+    fn clear_cofactor(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
         let (modulus, _, extension_degree, _, _) = parse_modulus_and_extension_degree(&bytes)?;
         let modulus_limbs = num_limbs_for_modulus(&modulus)?;
 
         let result: Result<Vec<u8>, ApiError> = match extension_degree {
             EXTENSION_DEGREE_2 => {
-                let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, G2ApiImplementationFp2, bytes, mul_point); 
+                let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, G2ApiImplementationFp2, bytes, clear_cofactor, ctx); 
 
                 result
             },
             EXTENSION_DEGREE_3 => {
-                let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, G2ApiImplementationFp3, bytes, mul_point); 
+                let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, G2ApiImplementationFp3, bytes, clear_cofactor, ctx); 
 
                 result
             },
@@ -338,18 +786,18 @@ impl G2Api for PublicG2Api {
         result
     }
 
-    fn multiexp(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+    fn validate_point(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
         let (modulus, _, extension_degree, _, _) = parse_modulus_and_extension_degree(&bytes)?;
         let modulus_limbs = num_limbs_for_modulus(&modulus)?;
 
         let result: Result<Vec<u8>, ApiError> = match extension_degree {
             EXTENSION_DEGREE_2 => {
-                let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, G2ApiImplementationFp2, bytes, multiexp); 
+                let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, G2ApiImplementationFp2, bytes, validate_point, ctx); 
 
                 result
             },
             EXTENSION_DEGREE_3 => {
-                let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, G2ApiImplementationFp3, bytes, multiexp); 
+                let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, G2ApiImplementationFp3, bytes, validate_point, ctx); 
 
                 result
             },
@@ -0,0 +1,567 @@
+//! Exact length of the input computed from the lengths and counts declared in it, checked by
+//! `API::run` before any modulus, field or curve is constructed, so garbage input of the wrong
+//! length is rejected after reading a few bytes. Headers that can not be read or declare
+//! invalid lengths (zero or above the limits) are left to the decoders, that report the same
+//! errors as before. Decoders keep their own length checks since `Public*Api` are entry points
+//! as well, `PublicPairingApi`, `PublicMappingApi` and the gas meter call the check themselves
+//! before the curve is constructed. Hashing to
+//! the field ends with a message of any length and curves in other forms are not checked here.
+//! The same lengths locate the twist description of G2 operations in the second version of the ABI.
+//! Points are of the length in the encoding of the ABI version of the input, see `point_encoding`
+
+use super::constants::*;
+use super::decode_utils::{split, checked_len_add, checked_len_mul};
+use super::point_encoding::{point_len, PointEncoding};
+use crate::errors::{ApiError, ErrorCode};
+use crate::alloc_prelude::*;
+
+/// Declared length at `offset`, `None` if the input ends before it or it's not in `1..=max`
+fn read_len(bytes: &[u8], offset: usize, max: usize) -> Option<usize> {
+    let len = *bytes.get(offset)? as usize;
+    if len == 0 || len > max {
+        return None;
+    }
+
+    Some(len)
+}
+
+/// Sum of lengths, `None` on overflow like for other lengths that can not be checked upfront
+fn add(a: usize, b: usize) -> Option<usize> {
+    checked_len_add(a, b).ok()
+}
+
+/// Product of a length and a count, `None` on overflow
+fn mul(a: usize, b: usize) -> Option<usize> {
+    checked_len_mul(a, b).ok()
+}
+
+/// Modulus length, group order length and the offset after the common G1 parameters
+fn g1_parameters(bytes: &[u8]) -> Option<(usize, usize, usize)> {
+    let modulus_len = read_len(bytes, 0, MAX_MODULUS_BYTE_LEN)?;
+    // modulus, A and B
+    let offset = add(BYTES_FOR_LENGTH_ENCODING, mul(3, modulus_len)?)?;
+    let order_len = read_len(bytes, offset, MAX_GROUP_BYTE_LEN)?;
+
+    Some((modulus_len, order_len, add(add(offset, BYTES_FOR_LENGTH_ENCODING)?, order_len)?))
+}
+
+/// Length of the extension element, group order length and the offset after
+/// the common G2 parameters
+fn g2_parameters(bytes: &[u8]) -> Option<(usize, usize, usize)> {
+    let modulus_len = read_len(bytes, 0, MAX_MODULUS_BYTE_LEN)?;
+    let offset = add(BYTES_FOR_LENGTH_ENCODING, modulus_len)?;
+    let extension_degree = match *bytes.get(offset)? {
+        EXTENSION_DEGREE_2 => 2,
+        EXTENSION_DEGREE_3 => 3,
+        _ => return None
+    };
+    let element_len = mul(extension_degree, modulus_len)?;
+    // non-residue, A and B
+    let offset = add(add(add(offset, EXTENSION_DEGREE_ENCODING_LENGTH)?, modulus_len)?, mul(2, element_len)?)?;
+    let order_len = read_len(bytes, offset, MAX_GROUP_BYTE_LEN)?;
+
+    Some((element_len, order_len, add(add(offset, BYTES_FOR_LENGTH_ENCODING)?, order_len)?))
+}
+
+/// Points with scalars after the number of pairs at `offset`, points alone for zero `order_len`
+fn multiexp_len(bytes: &[u8], offset: usize, point_len: usize, order_len: usize) -> Option<usize> {
+    let num_pairs = read_len(bytes, offset, u8::MAX as usize)?;
+
+    add(add(offset, BYTES_FOR_LENGTH_ENCODING)?, mul(num_pairs, add(point_len, order_len)?)?)
+}
+
+/// Point followed by the cofactor with its length
+fn point_and_cofactor_len(bytes: &[u8], offset: usize, point_len: usize) -> Option<usize> {
+    let offset = add(offset, point_len)?;
+    let cofactor_len = read_len(bytes, offset, MAX_GROUP_BYTE_LEN)?;
+
+    add(add(offset, BYTES_FOR_LENGTH_ENCODING)?, cofactor_len)
+}
+
+/// Length of a loop parameter or exponent with its length at `offset`, limits on the bit length
+/// depend on the curve family and are left to the decoders
+fn scalar_len(bytes: &[u8], offset: usize) -> Option<usize> {
+    let len = read_len(bytes, offset, u8::MAX as usize)?;
+
+    add(BYTES_FOR_LENGTH_ENCODING, len)
+}
+
+/// Lengths of G1 points, G2 points and Miller loop values, group order length and the offset after
+/// the curve parameters of a pairing call of the curve family, `bytes` start after the curve type
+fn pairing_parameters(curve_type: u8, bytes: &[u8], encoding: PointEncoding) -> Option<(usize, usize, usize, usize, usize)> {
+    let (modulus_len, order_len, offset) = g1_parameters(bytes)?;
+    let g1_len = point_len(modulus_len, encoding).ok()?;
+    match curve_type {
+        BLS12 | BN | BLS24 | BLS48 | KSS16 | KSS18 => {
+            let (twist_degree, embedding_degree) = match curve_type {
+                BLS12 | BN => (2, 12),
+                BLS24 => (4, 24),
+                BLS48 => (8, 48),
+                KSS16 => (4, 16),
+                _ => (3, 18)
+            };
+            // non-residues for the first extension and for the twist, and the twist type
+            let twist_element_len = mul(twist_degree, modulus_len)?;
+            let offset = add(add(add(offset, modulus_len)?, twist_element_len)?, TWIST_TYPE_LENGTH)?;
+            // X and its sign
+            let offset = add(add(offset, scalar_len(bytes, offset)?)?, SIGN_ENCODING_LENGTH)?;
+
+            Some((g1_len, point_len(twist_element_len, encoding).ok()?, mul(embedding_degree, modulus_len)?, order_len, offset))
+        },
+        MNT4 | MNT6 => {
+            let extension_degree = if curve_type == MNT4 { 2 } else { 3 };
+            // non-residue, ate loop count and its sign
+            let offset = add(offset, modulus_len)?;
+            let offset = add(add(offset, scalar_len(bytes, offset)?)?, SIGN_ENCODING_LENGTH)?;
+            // w0, w1 and the sign of w0
+            let offset = add(offset, scalar_len(bytes, offset)?)?;
+            let offset = add(add(offset, scalar_len(bytes, offset)?)?, SIGN_ENCODING_LENGTH)?;
+            let element_len = mul(extension_degree, modulus_len)?;
+
+            Some((g1_len, point_len(element_len, encoding).ok()?, mul(2, element_len)?, order_len, offset))
+        },
+        BW6 => {
+            // non-residue, twist type and two ate loop counts with their signs
+            let mut offset = add(add(offset, modulus_len)?, TWIST_TYPE_LENGTH)?;
+            for _ in 0..2 {
+                offset = add(add(offset, scalar_len(bytes, offset)?)?, SIGN_ENCODING_LENGTH)?;
+            }
+
+            Some((g1_len, g1_len, mul(6, modulus_len)?, order_len, offset))
+        },
+        COCKS_PINCH => {
+            let embedding_degree = read_len(bytes, offset, u8::MAX as usize)?;
+            // non-residue, ate loop count with its sign and the hard part of the final exponentiation
+            let offset = add(add(offset, EXTENSION_DEGREE_ENCODING_LENGTH)?, modulus_len)?;
+            let offset = add(add(offset, scalar_len(bytes, offset)?)?, SIGN_ENCODING_LENGTH)?;
+            let offset = add(offset, scalar_len(bytes, offset)?)?;
+            let element_len = mul(embedding_degree, modulus_len)?;
+
+            Some((g1_len, point_len(element_len, encoding).ok()?, element_len, order_len, offset))
+        },
+        _ => None
+    }
+}
+
+/// Length of the pairing call after the curve type, with the layout after the curve parameters
+/// given by the operation and the optional pairing mode byte if the input has it
+fn pairing_len(op_type: u8, curve_type: u8, bytes: &[u8], encoding: PointEncoding) -> Option<usize> {
+    let (g1_len, g2_len, value_len, order_len, offset) = pairing_parameters(curve_type, bytes, encoding)?;
+    match op_type {
+        OPERATION_FINAL_EXP => {
+            let num_values = read_len(bytes, offset, u8::MAX as usize)?;
+
+            add(add(offset, BYTES_FOR_LENGTH_ENCODING)?, mul(num_values, value_len)?)
+        },
+        // the public key and the signature are in different groups whichever group the public key is in
+        OPERATION_BLS_VERIFY => add(add(offset, BLS_PUBLIC_KEY_GROUP_ENCODING_LENGTH)?, mul(2, add(g1_len, g2_len)?)?),
+        // generators, tau times the G2 generator, commitment, evaluation point, value and proof
+        OPERATION_KZG_OPENING => add(add(offset, mul(3, g1_len)?)?, mul(2, add(g2_len, order_len)?)?),
+        OPERATION_PAIRING_EQUATION | OPERATION_PAIRING | OPERATION_PAIRING_GT | OPERATION_MILLER_LOOP => {
+            // zero pairs are rejected by the decoders outside of gas metering
+            let num_pairs = *bytes.get(offset)? as usize;
+            let sign_len = if op_type == OPERATION_PAIRING_EQUATION { SIGN_ENCODING_LENGTH } else { 0 };
+            let pair_len = add(add(sign_len + 2 * BOOLEAN_ENCODING_LENGTH, g1_len)?, g2_len)?;
+            let end = add(add(offset, BYTES_FOR_LENGTH_ENCODING)?, mul(num_pairs, pair_len)?)?;
+            // the equation is always checked in full, so it doesn't take the pairing mode
+            if op_type != OPERATION_PAIRING_EQUATION && bytes.len() > end {
+                return add(end, PAIRING_MODE_ENCODING_LENGTH);
+            }
+
+            Some(end)
+        },
+        _ => None
+    }
+}
+
+/// Length of the mapping call, Z is followed by the isogeny for the simplified SWU map,
+/// and then by the cofactor and the element to map for both maps
+fn mapping_len(op_type: u8, bytes: &[u8]) -> Option<usize> {
+    let (modulus_len, _, offset) = g1_parameters(bytes)?;
+    let offset = add(offset, modulus_len)?;
+    let offset = if op_type == OPERATION_MAP_TO_G1 {
+        let isogeny_mode = *bytes.get(offset)?;
+        let offset = add(offset, ISOGENY_MODE_ENCODING_LENGTH)?;
+        match isogeny_mode {
+            ISOGENY_NONE | ISOGENY_BUILT_IN => offset,
+            ISOGENY_EXPLICIT => {
+                // isogenous curve, degree of the map and its four polynomials
+                let offset = add(offset, mul(2, modulus_len)?)?;
+                let map_degree = read_len(bytes, offset, u8::MAX as usize)?;
+
+                add(add(offset, BYTES_FOR_LENGTH_ENCODING)?, mul(4 * (map_degree + 1), modulus_len)?)?
+            },
+            _ => return None
+        }
+    } else {
+        offset
+    };
+    let cofactor_len = read_len(bytes, offset, MAX_GROUP_BYTE_LEN)?;
+
+    add(add(add(offset, BYTES_FOR_LENGTH_ENCODING)?, cofactor_len)?, modulus_len)
+}
+
+/// Exact length of the input of the operation after the operation type, `None` if the operation
+/// is not covered, the declared lengths are invalid or their sum doesn't fit into `usize`
+pub(crate) fn expected_input_len(op_type: u8, bytes: &[u8], encoding: PointEncoding) -> Option<usize> {
+    match op_type {
+        OPERATION_G1_ADD | OPERATION_G1_MUL | OPERATION_G1_MULTIEXP | OPERATION_G1_SUM |
+        OPERATION_G1_SUBGROUP_CHECK | OPERATION_G1_VALIDATE_POINT | OPERATION_G1_CLEAR_COFACTOR => {
+            let (modulus_len, order_len, offset) = g1_parameters(bytes)?;
+            let point_len = point_len(modulus_len, encoding).ok()?;
+            match op_type {
+                OPERATION_G1_ADD => add(offset, mul(2, point_len)?),
+                OPERATION_G1_MUL => add(offset, add(point_len, order_len)?),
+                OPERATION_G1_MULTIEXP => multiexp_len(bytes, offset, point_len, order_len),
+                OPERATION_G1_SUM => multiexp_len(bytes, offset, point_len, 0),
+                OPERATION_G1_CLEAR_COFACTOR => point_and_cofactor_len(bytes, offset, point_len),
+                _ => add(offset, point_len)
+            }
+        },
+        OPERATION_G2_ADD | OPERATION_G2_MUL | OPERATION_G2_MULTIEXP | OPERATION_G2_SUM |
+        OPERATION_G2_SUBGROUP_CHECK | OPERATION_G2_VALIDATE_POINT | OPERATION_G2_CLEAR_COFACTOR => {
+            let (element_len, order_len, offset) = g2_parameters(bytes)?;
+            let point_len = point_len(element_len, encoding).ok()?;
+            match op_type {
+                OPERATION_G2_ADD => add(offset, mul(2, point_len)?),
+                OPERATION_G2_MUL => add(offset, add(point_len, order_len)?),
+                OPERATION_G2_MULTIEXP => multiexp_len(bytes, offset, point_len, order_len),
+                OPERATION_G2_SUM => multiexp_len(bytes, offset, point_len, 0),
+                OPERATION_G2_CLEAR_COFACTOR => point_and_cofactor_len(bytes, offset, point_len),
+                _ => add(offset, point_len)
+            }
+        },
+        OPERATION_FIELD_ADD | OPERATION_FIELD_MUL | OPERATION_FIELD_INVERSE | OPERATION_FIELD_EXP => {
+            let modulus_len = read_len(bytes, 0, MAX_MODULUS_BYTE_LEN)?;
+            let offset = add(BYTES_FOR_LENGTH_ENCODING, modulus_len)?;
+            match op_type {
+                OPERATION_FIELD_ADD | OPERATION_FIELD_MUL => add(offset, mul(2, modulus_len)?),
+                OPERATION_FIELD_INVERSE => add(offset, modulus_len),
+                _ => {
+                    // exponent may be zero, but its length may not
+                    let offset = add(offset, modulus_len)?;
+                    let exponent_len = read_len(bytes, offset, MAX_GROUP_BYTE_LEN)?;
+
+                    add(add(offset, BYTES_FOR_LENGTH_ENCODING)?, exponent_len)
+                }
+            }
+        },
+        OPERATION_FIELD_EXT2_SQRT => {
+            let modulus_len = read_len(bytes, 0, MAX_MODULUS_BYTE_LEN)?;
+            let offset = add(BYTES_FOR_LENGTH_ENCODING, modulus_len)?;
+            if *bytes.get(offset)? != EXTENSION_DEGREE_2 {
+                return None;
+            }
+
+            // non-residue and the element
+            add(add(offset, EXTENSION_DEGREE_ENCODING_LENGTH)?, mul(3, modulus_len)?)
+        },
+        OPERATION_PAIRING | OPERATION_PAIRING_GT | OPERATION_MILLER_LOOP | OPERATION_FINAL_EXP |
+        OPERATION_PAIRING_EQUATION | OPERATION_BLS_VERIFY | OPERATION_KZG_OPENING => {
+            let (curve_type, rest) = bytes.split_first()?;
+
+            add(CURVE_TYPE_LENGTH, pairing_len(op_type, *curve_type, rest, encoding)?)
+        },
+        OPERATION_MAP_TO_G1 | OPERATION_MAP_TO_G1_SVDW => mapping_len(op_type, bytes),
+        _ => None
+    }
+}
+
+/// Extension degree of the coordinates of G2 points of the curve family, `bytes` start after the curve type
+#[cfg(feature = "gas_metering")]
+fn g2_extension_degree(curve_type: u8, bytes: &[u8]) -> Option<usize> {
+    match curve_type {
+        BW6 => Some(1),
+        BLS12 | BN | MNT4 => Some(2),
+        MNT6 | KSS18 => Some(3),
+        BLS24 | KSS16 => Some(4),
+        BLS48 => Some(8),
+        COCKS_PINCH => {
+            let (_, _, offset) = g1_parameters(bytes)?;
+
+            read_len(bytes, offset, u8::MAX as usize)
+        },
+        _ => None
+    }
+}
+
+/// Numbers of G1 and G2 points in the input of the operation and the extension degree of the
+/// coordinates of G2 points, that the gas meter prices the decompression of. `None` if the
+/// operation takes no points or the declared lengths are invalid
+#[cfg(feature = "gas_metering")]
+pub(crate) fn num_points(op_type: u8, bytes: &[u8]) -> Option<(usize, usize, usize)> {
+    match op_type {
+        OPERATION_G1_ADD | OPERATION_G1_MUL | OPERATION_G1_MULTIEXP | OPERATION_G1_SUM |
+        OPERATION_G1_SUBGROUP_CHECK | OPERATION_G1_VALIDATE_POINT | OPERATION_G1_CLEAR_COFACTOR => {
+            let (_, _, offset) = g1_parameters(bytes)?;
+            let num_points = match op_type {
+                OPERATION_G1_ADD => 2,
+                OPERATION_G1_MULTIEXP | OPERATION_G1_SUM => read_len(bytes, offset, u8::MAX as usize)?,
+                _ => 1
+            };
+
+            Some((num_points, 0, 0))
+        },
+        OPERATION_G2_ADD | OPERATION_G2_MUL | OPERATION_G2_MULTIEXP | OPERATION_G2_SUM |
+        OPERATION_G2_SUBGROUP_CHECK | OPERATION_G2_VALIDATE_POINT | OPERATION_G2_CLEAR_COFACTOR => {
+            let (element_len, _, offset) = g2_parameters(bytes)?;
+            let num_points = match op_type {
+                OPERATION_G2_ADD => 2,
+                OPERATION_G2_MULTIEXP | OPERATION_G2_SUM => read_len(bytes, offset, u8::MAX as usize)?,
+                _ => 1
+            };
+
+            Some((0, num_points, element_len / read_len(bytes, 0, MAX_MODULUS_BYTE_LEN)?))
+        },
+        OPERATION_PAIRING | OPERATION_PAIRING_GT | OPERATION_MILLER_LOOP |
+        OPERATION_PAIRING_EQUATION | OPERATION_BLS_VERIFY | OPERATION_KZG_OPENING => {
+            let (curve_type, rest) = bytes.split_first()?;
+            let (_, _, _, _, offset) = pairing_parameters(*curve_type, rest, PointEncoding::Compressed)?;
+            let extension_degree = g2_extension_degree(*curve_type, rest)?;
+            match op_type {
+                OPERATION_BLS_VERIFY => Some((2, 2, extension_degree)),
+                OPERATION_KZG_OPENING => Some((3, 2, extension_degree)),
+                _ => {
+                    let num_pairs = *rest.get(offset)? as usize;
+
+                    Some((num_pairs, num_pairs, extension_degree))
+                }
+            }
+        },
+        _ => None
+    }
+}
+
+/// Removes the twist description of a G2 operation in the second version of the ABI, so the
+/// rest is the input of the first version. Other operations are the same in both versions
+pub(crate) fn strip_twist_description(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+    let (op_type, rest) = split(bytes, OPERATION_ENCODING_LENGTH, "Input should be longer than operation type encoding")?;
+    match op_type[0] {
+        OPERATION_G2_ADD | OPERATION_G2_MUL | OPERATION_G2_MULTIEXP | OPERATION_G2_SUM |
+        OPERATION_G2_SUBGROUP_CHECK | OPERATION_G2_VALIDATE_POINT | OPERATION_G2_CLEAR_COFACTOR => {},
+        _ => {
+            return Ok(bytes.to_vec());
+        }
+    }
+
+    let (element_len, _, offset) = g2_parameters(rest).ok_or_else(|| {
+        ApiError::InputError("Invalid G2 curve parameters before the twist description".to_owned())
+    })?;
+    let modulus_len = rest[0] as usize;
+    // twist type, B over the base field and the twist non-residue
+    let description_len = checked_len_add(checked_len_add(TWIST_TYPE_LENGTH, modulus_len)?, element_len)?;
+    let (parameters, rest) = split(rest, offset, "Input is not long enough to get G2 curve parameters")?;
+    let (_, rest) = split(rest, description_len, "Input is not long enough to get twist description")?;
+
+    let mut stripped = Vec::with_capacity(bytes.len() - description_len);
+    stripped.extend_from_slice(op_type);
+    stripped.extend_from_slice(parameters);
+    stripped.extend_from_slice(rest);
+
+    Ok(stripped)
+}
+
+/// Rejects the input if its length differs from the one declared in it
+pub(crate) fn validate_input_length(op_type: u8, bytes: &[u8], encoding: PointEncoding) -> Result<(), ApiError> {
+    check_length(bytes, expected_input_len(op_type, bytes, encoding))
+}
+
+/// Same as `validate_input_length` for a pairing call without the curve type, as taken
+/// by the gas meter of the curve family
+#[cfg(feature = "gas_metering")]
+pub(crate) fn validate_pairing_input_length(op_type: u8, curve_type: u8, bytes: &[u8], encoding: PointEncoding) -> Result<(), ApiError> {
+    check_length(bytes, pairing_len(op_type, curve_type, bytes, encoding))
+}
+
+fn check_length(bytes: &[u8], expected: Option<usize>) -> Result<(), ApiError> {
+    let expected = match expected {
+        Some(expected) => expected,
+        None => {
+            return Ok(());
+        }
+    };
+
+    if bytes.len() < expected {
+        return Err(ApiError::with_code(
+            ErrorCode::InputTooShort { expected, got: bytes.len() },
+            "Input is shorter than declared by the lengths in it".to_owned()
+        ).at(&bytes[bytes.len()..]));
+    }
+    if bytes.len() > expected {
+        return Err(ApiError::garbage_at_the_end(&bytes[expected..]));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::public_interface::API;
+    use crate::public_interface::encoding::{G1CurveDescriptor, G1Point};
+
+    fn bn254_curve() -> G1CurveDescriptor {
+        let modulus = hex::decode("30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47").unwrap();
+        let order = hex::decode("30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001").unwrap();
+
+        G1CurveDescriptor::new(&modulus, &[0u8], &[3u8], &order).unwrap()
+    }
+
+    #[test]
+    fn test_expected_length_of_encoded_calls() {
+        let curve = bn254_curve();
+        let generator = G1Point::new(&[1u8], &[2u8]);
+
+        let calls = vec![
+            (OPERATION_G1_ADD, curve.add(&generator, &generator).unwrap()),
+            (OPERATION_G1_MUL, curve.mul(&generator, &[7u8]).unwrap()),
+            (OPERATION_G1_MULTIEXP, curve.multiexp(&[(generator.clone(), vec![7u8]), (generator.clone(), vec![9u8])]).unwrap()),
+            (OPERATION_G1_SUM, curve.sum(&[generator.clone(), generator.clone(), generator.clone()]).unwrap()),
+        ];
+
+        for (op_type, call) in calls.into_iter() {
+            let input = &call[OPERATION_ENCODING_LENGTH..];
+            assert_eq!(expected_input_len(op_type, input, PointEncoding::Uncompressed), Some(input.len()));
+            assert!(validate_input_length(op_type, input, PointEncoding::Uncompressed).is_ok());
+            assert!(API::run(&call).is_ok());
+
+            let mut truncated = call.clone();
+            truncated.pop();
+            let err = API::run(&truncated).unwrap_err();
+            assert_eq!(err.code(), ErrorCode::InputTooShort { expected: input.len(), got: input.len() - 1 });
+            assert_eq!(err.offset(truncated.len()), Some(truncated.len()));
+
+            let mut garbage = call.clone();
+            garbage.push(0u8);
+            let err = API::run(&garbage).unwrap_err();
+            assert_eq!(err.code(), ErrorCode::GarbageAtTheEnd);
+            assert_eq!(err.offset(garbage.len()), Some(call.len()));
+        }
+    }
+
+    #[test]
+    fn test_invalid_headers_are_left_to_decoders() {
+        // zero modulus length, zero number of pairs and unknown extension degree
+        assert_eq!(expected_input_len(OPERATION_G1_ADD, &[0u8, 1, 2, 3], PointEncoding::Uncompressed), None);
+        let curve = bn254_curve();
+        let mut multiexp = curve.multiexp(&[(G1Point::new(&[1u8], &[2u8]), vec![7u8])]).unwrap();
+        let num_pairs_offset = curve.encode().unwrap().len() + OPERATION_ENCODING_LENGTH;
+        multiexp[num_pairs_offset] = 0;
+        assert_eq!(expected_input_len(OPERATION_G1_MULTIEXP, &multiexp[OPERATION_ENCODING_LENGTH..], PointEncoding::Uncompressed), None);
+        assert!(API::run(&multiexp).is_err());
+
+        assert_eq!(expected_input_len(OPERATION_FIELD_EXT2_SQRT, &[1u8, 7, 3, 1, 1, 1], PointEncoding::Uncompressed), None);
+        assert_eq!(expected_input_len(OPERATION_PAIRING, &[BLS12, 1, 7], PointEncoding::Uncompressed), None);
+        assert_eq!(expected_input_len(OPERATION_PAIRING, &[0xff, 1, 7, 1, 1, 1, 7], PointEncoding::Uncompressed), None);
+
+        // declared lengths beyond the end of the input
+        assert_eq!(expected_input_len(OPERATION_G1_MUL, &[32u8, 1, 2, 3], PointEncoding::Uncompressed), None);
+        assert!(validate_input_length(OPERATION_G1_MUL, &[32u8, 1, 2, 3], PointEncoding::Uncompressed).is_ok());
+    }
+
+    #[test]
+    fn test_pairing_length_of_every_family() {
+        use crate::test::pairings::{bls12, bn, mnt4, bls24, bls48, bw6, kss16, kss18, cocks_pinch};
+
+        let calls = vec![
+            bls12::assemble_bls12_381(2),
+            bn::assemble_bn254(2),
+            mnt4::assemble_mnt4_753(2),
+            bls24::assemble_bls24_test_curve(2),
+            bls48::assemble_bls48_test_curve(2),
+            bw6::assemble_bw6_test_curve(2, SIGN_PLUS),
+            kss16::assemble_kss16_test_curve(2),
+            kss18::assemble_kss18_test_curve(2),
+            cocks_pinch::assemble_cocks_pinch_test_curve(2),
+        ];
+
+        for call in calls.into_iter() {
+            assert_eq!(expected_input_len(OPERATION_PAIRING, &call, PointEncoding::Uncompressed), Some(call.len()));
+            assert_eq!(expected_input_len(OPERATION_MILLER_LOOP, &call, PointEncoding::Uncompressed), Some(call.len()));
+
+            let mut with_mode = call.clone();
+            with_mode.push(PAIRING_MODE_MILLER_LOOP_ONLY);
+            assert_eq!(expected_input_len(OPERATION_PAIRING_GT, &with_mode, PointEncoding::Uncompressed), Some(with_mode.len()));
+
+            let mut garbage = with_mode.clone();
+            garbage.push(0u8);
+            let err = validate_input_length(OPERATION_PAIRING, &garbage, PointEncoding::Uncompressed).unwrap_err();
+            assert_eq!(err.code(), ErrorCode::GarbageAtTheEnd);
+            assert_eq!(err.offset(garbage.len()), Some(with_mode.len()));
+
+            let mut truncated = call.clone();
+            truncated.pop();
+            let err = validate_input_length(OPERATION_PAIRING, &truncated, PointEncoding::Uncompressed).unwrap_err();
+            assert_eq!(err.code(), ErrorCode::InputTooShort { expected: call.len(), got: truncated.len() });
+        }
+    }
+
+    #[test]
+    fn test_pairing_length_after_the_curve_parameters() {
+        use crate::test::pairings::bn::assemble_bn254;
+
+        // BN254 with no pairs is the curve parameters followed by zero number of pairs
+        let parameters = assemble_bn254(0);
+        let offset = parameters.len() - BYTES_FOR_LENGTH_ENCODING;
+        let modulus_len = 32;
+        let (g1_len, g2_len, value_len) = (2 * modulus_len, 4 * modulus_len, 12 * modulus_len);
+
+        let mut final_exp = parameters[..offset].to_vec();
+        final_exp.push(3u8);
+        assert_eq!(expected_input_len(OPERATION_FINAL_EXP, &final_exp, PointEncoding::Uncompressed), Some(offset + 1 + 3 * value_len));
+        assert_eq!(expected_input_len(OPERATION_BLS_VERIFY, &final_exp, PointEncoding::Uncompressed), Some(offset + 1 + 2 * g1_len + 2 * g2_len));
+        assert_eq!(expected_input_len(OPERATION_KZG_OPENING, &final_exp, PointEncoding::Uncompressed), Some(offset + 3 * g1_len + 2 * g2_len + 2 * 32));
+
+        let mut equation = final_exp.clone();
+        equation[offset] = 2u8;
+        let pair_len = SIGN_ENCODING_LENGTH + 2 * BOOLEAN_ENCODING_LENGTH + g1_len + g2_len;
+        assert_eq!(expected_input_len(OPERATION_PAIRING_EQUATION, &equation, PointEncoding::Uncompressed), Some(offset + 1 + 2 * pair_len));
+
+        // the equation doesn't take the pairing mode
+        let mut garbage = equation.clone();
+        garbage.resize(offset + 1 + 2 * pair_len + PAIRING_MODE_ENCODING_LENGTH, 0u8);
+        let err = validate_input_length(OPERATION_PAIRING_EQUATION, &garbage, PointEncoding::Uncompressed).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::GarbageAtTheEnd);
+
+        // the gas meter of the curve family takes the input after the curve type
+        let mut pairing = parameters[CURVE_TYPE_LENGTH..].to_vec();
+        assert!(validate_pairing_input_length(OPERATION_PAIRING, BN, &pairing, PointEncoding::Uncompressed).is_ok());
+        pairing.extend_from_slice(&[PAIRING_MODE_FULL, 0u8]);
+        let err = validate_pairing_input_length(OPERATION_PAIRING, BN, &pairing, PointEncoding::Uncompressed).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::GarbageAtTheEnd);
+    }
+
+    #[test]
+    fn test_mapping_length() {
+        // p = 97, A = 0, B = 3, order 5, Z = 2
+        let parameters = [1u8, 97, 0, 3, 1, 5, 2];
+        let svdw = [&parameters[..], &[1u8, 1, 4][..]].concat();
+        assert_eq!(expected_input_len(OPERATION_MAP_TO_G1_SVDW, &svdw, PointEncoding::Uncompressed), Some(svdw.len()));
+
+        let no_isogeny = [&parameters[..], &[ISOGENY_NONE, 1, 1, 4][..]].concat();
+        assert_eq!(expected_input_len(OPERATION_MAP_TO_G1, &no_isogeny, PointEncoding::Uncompressed), Some(no_isogeny.len()));
+
+        // isogenous curve A' and B', degree 1 map with four polynomials of two coefficients
+        let isogeny = [&[ISOGENY_EXPLICIT, 1, 2, 1][..], &[1u8; 8][..]].concat();
+        let explicit = [&parameters[..], &isogeny[..], &[1u8, 1, 4][..]].concat();
+        assert_eq!(expected_input_len(OPERATION_MAP_TO_G1, &explicit, PointEncoding::Uncompressed), Some(explicit.len()));
+
+        let mut garbage = explicit.clone();
+        garbage.push(0u8);
+        let err = validate_input_length(OPERATION_MAP_TO_G1, &garbage, PointEncoding::Uncompressed).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::GarbageAtTheEnd);
+
+        assert_eq!(expected_input_len(OPERATION_MAP_TO_G1, &[&parameters[..], &[0x7fu8, 1, 1, 4][..]].concat(), PointEncoding::Uncompressed), None);
+    }
+
+    #[test]
+    fn test_field_operations_length() {
+        assert_eq!(expected_input_len(OPERATION_FIELD_ADD, &[1u8, 97], PointEncoding::Uncompressed), Some(4));
+        assert_eq!(expected_input_len(OPERATION_FIELD_INVERSE, &[1u8, 97], PointEncoding::Uncompressed), Some(3));
+        assert_eq!(expected_input_len(OPERATION_FIELD_EXP, &[1u8, 97, 5, 2], PointEncoding::Uncompressed), Some(6));
+        assert_eq!(expected_input_len(OPERATION_FIELD_EXT2_SQRT, &[1u8, 103, EXTENSION_DEGREE_2], PointEncoding::Uncompressed), Some(6));
+
+        let err = API::run(&[OPERATION_FIELD_ADD, 1, 97, 90]).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::InputTooShort { expected: 4, got: 3 });
+    }
+}
@@ -0,0 +1,876 @@
+//! Deterministic map of a base field element to G1 using the simplified SWU method
+//! from RFC 9380 (section 6.6.2), with an optional isogeny for curves where A * B == 0
+//! (like BLS12-381) and final cofactor clearing.
+//!
+//! Encoding (after the operation type byte):
+//! - Common G1 parameters: modulus length, modulus, A, B, group order length, group order
+//! - Z, non-square constant of the SWU map
+//! - Isogeny mode (one byte): none (0x00), explicit (0x01) or built in (0x02)
+//! - If the isogeny is explicit:
+//!     - A' and B' of the isogenous curve that SWU maps to
+//!     - Degree of the isogeny map (one byte)
+//!     - x numerator, x denominator, y numerator and y denominator coefficients,
+//!       `degree + 1` field elements each, starting from the constant term
+//! - Built in isogenies take the same data from a table for known curves (BLS12-381 G1), the
+//!   curve parameters must be encoded exactly as in the table (minimal lengths)
+//! - Cofactor to clear, encoded the same way as the group order
+//! - Field element to map
+//!
+//! Result is the mapped point encoded as (X, Y)
+//!
+//! Map to G1 with the Shallue-van de Woestijne method (RFC 9380, section 6.6.1) works for any
+//! curve, including A * B == 0, without an isogeny:
+//! - Common G1 parameters: modulus length, modulus, A, B, group order length, group order
+//! - Z, constant of the SvdW map
+//! - Cofactor to clear, encoded the same way as the group order
+//! - Field element to map
+//!
+//! Result is the mapped point encoded as (X, Y)
+//!
+//! Hash to field (RFC 9380, section 5.2) with `expand_message_xmd`:
+//! - Modulus length and modulus
+//! - Extension degree (one byte), 1 for Fp or 2 for Fp2. Non-residue is not needed since
+//!   elements of Fp2 are returned as coefficients
+//! - Hash function (one byte): SHA-256 (0x01) or SHA-512 (0x02)
+//! - Security parameter k in bytes (one byte)
+//! - Number of elements (one byte)
+//! - Domain separation tag length (one byte) and the tag
+//! - Message, till the end of the input
+//!
+//! Result is the list of elements, coefficients of each Fp2 element start from c0
+
+use crate::weierstrass::{Group, CurveOverFpParameters};
+use crate::weierstrass::curve::{CurvePoint, WeierstrassCurve};
+use crate::representation::ElementRepr;
+use crate::traits::{FieldElement, ZeroAndOne};
+use crate::field::*;
+use crate::fp::Fp;
+use crate::mapping::simple_swu::{SwuParameters, simplified_swu_fp};
+use crate::mapping::isogeny::{IsogenyParameters, apply_isogeny_map};
+use crate::mapping::svdw::{SvdwParameters, svdw_fp};
+use crate::mapping::hash_to_field::{HashFunction, hash_to_field_fp};
+use crate::square_root::{legendre_symbol_fp, LegendreSymbol, sqrt};
+use super::constants::*;
+
+use super::decode_g1::*;
+use super::decode_utils::*;
+use super::decode_fp::*;
+
+use crate::errors::{ApiError, ErrorCode};
+use crate::execution_context::ExecutionContext;
+
+extern crate once_cell;
+use self::once_cell::sync::Lazy;
+
+/// Decoded elements and the rest of the input
+type DecodedElements<'a, 'b, FE, F> = (Vec<Fp<'a, FE, F>>, &'b [u8]);
+
+/// Curve that SWU maps to, the isogeny from it and the rest of the input
+type DecodedIsogeny<'a, 'b, FE, F> = (WeierstrassCurve<'a, CurveOverFpParameters<'a, FE, F>>, IsogenyParameters<CurveOverFpParameters<'a, FE, F>>, &'b [u8]);
+
+/// Hash function, security parameter, number of elements, DST and message
+type HashToFieldParameters<'b> = (HashFunction, usize, usize, &'b [u8], &'b [u8]);
+
+pub trait MappingApi {
+    fn map_to_g1(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError>;
+    fn map_to_g1_svdw(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError>;
+    fn hash_to_field(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError>;
+}
+
+pub struct MappingApiImplementation<FE: ElementRepr> {
+    _marker_fe: core::marker::PhantomData<FE>,
+}
+
+fn is_square<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>(el: &Fp<'a, FE, F>) -> bool {
+    legendre_symbol_fp(el) != LegendreSymbol::QuadraticNonResidue
+}
+
+fn decode_fp_vector<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+(
+    bytes: &'b [u8],
+    field_byte_len: usize,
+    num_elements: usize,
+    base_field: &'a F
+) -> Result<DecodedElements<'a, 'b, FE, F>, ApiError> {
+    let mut result = Vec::with_capacity(num_elements);
+    let mut rest = bytes;
+    for _ in 0..num_elements {
+        let (el, local_rest) = decode_fp(rest, field_byte_len, base_field)?;
+        result.push(el);
+        rest = local_rest;
+    }
+
+    Ok((result, rest))
+}
+
+fn limbs_into_be_bytes(limbs: &[u64]) -> Vec<u8> {
+    let mut result: Vec<u8> = limbs.iter().rev().flat_map(|limb| limb.to_be_bytes().to_vec()).collect();
+    while result.len() > 1 && result[0] == 0 {
+        result.remove(0);
+    }
+
+    result
+}
+
+/// Curve encoding (everything before Z) and explicit isogeny encoding (A', B', degree and coefficients)
+fn bls12_381_g1_isogeny() -> Result<(Vec<u8>, Vec<u8>), ApiError> {
+    use crate::engines::bls12_381::*;
+    use crate::mapping::constants::calculate_bls12_381_g1_mapping_params;
+
+    const BYTE_LEN: usize = 48;
+    let serialize = |el: &Fp<U384Repr, PrimeField<U384Repr>>| serialize_fp_fixed_len(BYTE_LEN, el);
+
+    let mut curve_encoding = vec![BYTE_LEN as u8];
+    curve_encoding.extend(limbs_into_be_bytes(BLS12_381_MODULUS.as_ref()));
+    curve_encoding.extend(serialize(&BLS12_381_G1_CURVE.a)?);
+    curve_encoding.extend(serialize(&BLS12_381_G1_CURVE.b)?);
+    let order = limbs_into_be_bytes(&BLS12_381_SUBGROUP_ORDER);
+    curve_encoding.push(order.len() as u8);
+    curve_encoding.extend(order);
+
+    let (_, iso) = calculate_bls12_381_g1_mapping_params(&BLS12_381_FIELD);
+    let mut isogeny_encoding = serialize(&BLS12_381_G1_CURVE_ISOGENY.a)?;
+    isogeny_encoding.extend(serialize(&BLS12_381_G1_CURVE_ISOGENY.b)?);
+    isogeny_encoding.push(iso.map_degree as u8);
+    for coeffs in vec![&iso.k1, &iso.k2, &iso.k3, &iso.k4].into_iter() {
+        for c in coeffs.iter() {
+            isogeny_encoding.extend(serialize(c)?);
+        }
+    }
+
+    Ok((curve_encoding, isogeny_encoding))
+}
+
+static BUILT_IN_ISOGENIES: Lazy<Vec<(Vec<u8>, Vec<u8>)>> = Lazy::new(|| {
+    // an encoding that fails to build is reported as a missing built in isogeny
+    vec![bls12_381_g1_isogeny()].into_iter().filter_map(|e| e.ok()).collect()
+});
+
+/// Explicit encoding of the built in isogeny for the curve encoded as `curve_encoding`
+pub(crate) fn built_in_isogeny(curve_encoding: &[u8]) -> Result<&'static [u8], ApiError> {
+    BUILT_IN_ISOGENIES.iter()
+        .find(|(curve, _)| curve.as_slice() == curve_encoding)
+        .map(|(_, isogeny)| isogeny.as_slice())
+        .ok_or(ApiError::InputError(format!("There is no built in isogeny for the curve, file {}, line {}", file!(), line!())))
+}
+
+/// Parameters of the hash to field operation that follow the modulus: hash function, security
+/// parameter in bits, number of base field elements, DST and the message
+pub(crate) fn decode_hash_to_field_parameters(bytes: &[u8]) -> Result<HashToFieldParameters<'_>, ApiError> {
+    let (extension_degree, rest) = split(bytes, EXTENSION_DEGREE_ENCODING_LENGTH, "Input is not long enough to get extension degree")?;
+    let extension_degree = match extension_degree[0] {
+        EXTENSION_DEGREE_1 => 1,
+        EXTENSION_DEGREE_2 => 2,
+        _ => {
+            return Err(ApiError::UnknownParameter("Extension degree expected to be 1 or 2".to_owned()));
+        }
+    };
+
+    let (hash_encoding, rest) = split(rest, HASH_FUNCTION_ENCODING_LENGTH, "Input is not long enough to get hash function")?;
+    let hash = match hash_encoding[0] {
+        HASH_FUNCTION_SHA256 => HashFunction::Sha256,
+        HASH_FUNCTION_SHA512 => HashFunction::Sha512,
+        _ => {
+            return Err(ApiError::UnknownParameter("Unknown hash function".to_owned()));
+        }
+    };
+
+    let (security_encoding, rest) = split(rest, BYTES_FOR_LENGTH_ENCODING, "Input is not long enough to get security parameter")?;
+    let security_bits = (security_encoding[0] as usize) * 8;
+    if security_bits == 0 {
+        return Err(ApiError::InputError(format!("Security parameter is zero, file {}, line {}", file!(), line!())));
+    }
+
+    let (count_encoding, rest) = split(rest, BYTES_FOR_LENGTH_ENCODING, "Input is not long enough to get number of elements")?;
+    let count = count_encoding[0] as usize;
+    if count == 0 {
+        return Err(ApiError::InputError(format!("Number of elements is zero, file {}, line {}", file!(), line!())));
+    }
+
+    let (dst_len, rest) = split(rest, BYTES_FOR_LENGTH_ENCODING, "Input is not long enough to get DST length")?;
+    let (dst, msg) = split(rest, dst_len[0] as usize, "Input is not long enough to get DST")?;
+
+    Ok((hash, security_bits, count * extension_degree, dst, msg))
+}
+
+/// Isogenous curve and the isogeny map in the explicit encoding
+fn decode_isogeny<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+(
+    bytes: &'b [u8],
+    modulus_len: usize,
+    order: &'a [u64],
+    fp_params: &'a CurveOverFpParameters<'a, FE, F>,
+    max_degree: usize
+) -> Result<DecodedIsogeny<'a, 'b, FE, F>, ApiError> {
+    let field = fp_params.field;
+    let (a_prime, rest) = decode_fp(bytes, modulus_len, field)?;
+    let (b_prime, rest) = decode_fp(rest, modulus_len, field)?;
+    let isogenous_curve = WeierstrassCurve::new(order, a_prime, b_prime, fp_params).map_err(|_| {
+        ApiError::InputError("Isogenous curve shape is not supported".to_owned())
+    })?;
+
+    let (degree_encoding, rest) = split(rest, BYTES_FOR_LENGTH_ENCODING, "Input is not long enough to get isogeny degree")?;
+    let map_degree = degree_encoding[0] as usize;
+    if map_degree == 0 {
+        return Err(ApiError::InputError(format!("Isogeny map degree is zero, file {}, line {}", file!(), line!())));
+    }
+    if map_degree > max_degree {
+        return Err(ApiError::InputError(format!("Isogeny map degree is too large, file {}, line {}", file!(), line!())));
+    }
+
+    let (k1, rest) = decode_fp_vector(rest, modulus_len, map_degree + 1, field)?;
+    let (k2, rest) = decode_fp_vector(rest, modulus_len, map_degree + 1, field)?;
+    let (k3, rest) = decode_fp_vector(rest, modulus_len, map_degree + 1, field)?;
+    let (k4, rest) = decode_fp_vector(rest, modulus_len, map_degree + 1, field)?;
+
+    let isogeny = IsogenyParameters {
+        map_degree,
+        k1,
+        k2,
+        k3,
+        k4
+    };
+
+    Ok((isogenous_curve, isogeny, rest))
+}
+
+/// Parameters of the SWU map: -B/A and -1/Z. Checks that Z satisfies the criteria
+/// from RFC 9380, so the map is well defined for every input
+fn make_swu_parameters<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+(
+    z: Fp<'a, FE, F>,
+    curve: &WeierstrassCurve<'a, CurveOverFpParameters<'a, FE, F>>
+) -> Result<SwuParameters<CurveOverFpParameters<'a, FE, F>>, ApiError> {
+    if curve.a.is_zero() || curve.b.is_zero() {
+        return Err(ApiError::InputError(format!("Simplified SWU requires non-zero A and B, file {}, line {}", file!(), line!())));
+    }
+
+    if is_square(&z) {
+        return Err(ApiError::InputError(format!("Z must be a non-square, file {}, line {}", file!(), line!())));
+    }
+
+    let mut minus_one = Fp::one(z.field);
+    minus_one.negate();
+    if z == minus_one {
+        return Err(ApiError::InputError(format!("Z must not be equal to -1, file {}, line {}", file!(), line!())));
+    }
+
+    let a_inv = curve.a.inverse().ok_or(ApiError::UnexpectedZero("A is not invertible".to_owned()))?;
+    let z_inv = z.inverse().ok_or(ApiError::UnexpectedZero("Z is not invertible".to_owned()))?;
+
+    // g(B / (Z * A)) must be a square, otherwise an input with Z * u^2 + Z^2 * u^4 == 0
+    // does not map to the curve
+    let mut x = curve.b;
+    x.mul_assign(&a_inv);
+    x.mul_assign(&z_inv);
+    if !is_square(&curve.evaluate_rhs(&x)) {
+        return Err(ApiError::InputError(format!("g(B / (Z * A)) must be a square, file {}, line {}", file!(), line!())));
+    }
+
+    let mut minus_b_by_a = curve.b;
+    minus_b_by_a.negate();
+    minus_b_by_a.mul_assign(&a_inv);
+
+    let mut minus_z_inv = z_inv;
+    minus_z_inv.negate();
+
+    Ok(SwuParameters {
+        z,
+        minus_b_by_a,
+        minus_z_inv
+    })
+}
+
+/// Constants of the SvdW map. Checks that Z satisfies the criteria from RFC 9380,
+/// so the map is well defined for every input
+fn make_svdw_parameters<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+(
+    z: Fp<'a, FE, F>,
+    curve: &WeierstrassCurve<'a, CurveOverFpParameters<'a, FE, F>>
+) -> Result<SvdwParameters<CurveOverFpParameters<'a, FE, F>>, ApiError> {
+    let mut two = Fp::one(z.field);
+    two.double();
+    let mut four = two;
+    four.double();
+    let mut three = two;
+    three.add_assign(&Fp::one(z.field));
+
+    let g_z = curve.evaluate_rhs(&z);
+    if g_z.is_zero() {
+        return Err(ApiError::InputError(format!("g(Z) must not be zero, file {}, line {}", file!(), line!())));
+    }
+
+    // h = 3 * Z^2 + 4 * A
+    let mut h = z;
+    h.square();
+    h.mul_assign(&three);
+    let mut four_a = curve.a;
+    four_a.mul_assign(&four);
+    h.add_assign(&four_a);
+    if h.is_zero() {
+        return Err(ApiError::InputError(format!("3 * Z^2 + 4 * A must not be zero, file {}, line {}", file!(), line!())));
+    }
+
+    let h_inv = h.inverse().ok_or(ApiError::UnexpectedZero("3 * Z^2 + 4 * A is not invertible".to_owned()))?;
+    let two_inv = two.inverse().ok_or(ApiError::UnexpectedZero("2 is not invertible".to_owned()))?;
+
+    let mut c2 = z;
+    c2.mul_assign(&two_inv);
+    c2.negate();
+
+    let mut c4 = g_z;
+    c4.mul_assign(&four);
+    c4.mul_assign(&h_inv);
+    c4.negate();
+
+    // -(3 * Z^2 + 4 * A) / (4 * g(Z)) is the inverse of c4
+    if !is_square(&c4) {
+        return Err(ApiError::InputError(format!("-(3 * Z^2 + 4 * A) / (4 * g(Z)) must be a square, file {}, line {}", file!(), line!())));
+    }
+
+    if !is_square(&g_z) && !is_square(&curve.evaluate_rhs(&c2)) {
+        return Err(ApiError::InputError(format!("One of g(Z) and g(-Z / 2) must be a square, file {}, line {}", file!(), line!())));
+    }
+
+    let mut c3 = g_z;
+    c3.mul_assign(&h);
+    c3.negate();
+    let mut c3 = sqrt(&c3).ok_or(
+        ApiError::with_code(ErrorCode::InvalidModulus, format!("Failed to find a square root, modulus is not prime, file {}, line {}", file!(), line!()))
+    )?;
+    if fp_is_odd(&c3) {
+        c3.negate();
+    }
+
+    Ok(SvdwParameters {
+        z,
+        c1: g_z,
+        c2,
+        c3,
+        c4
+    })
+}
+
+/// Checks the mapped point and clears the cofactor
+fn finish_mapping<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+(
+    curve: &'a WeierstrassCurve<'a, CurveOverFpParameters<'a, FE, F>>,
+    x: Fp<'a, FE, F>,
+    y: Fp<'a, FE, F>,
+    cofactor: &[u64],
+    modulus_len: usize
+) -> Result<Vec<u8>, ApiError> {
+    let point = CurvePoint::point_from_xy(curve, x, y);
+    if !point.is_on_curve() {
+        return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Mapped point is not on curve, file {}, line {}", file!(), line!())));
+    }
+
+    let mut result = point.mul(cofactor);
+    result.normalize();
+
+    serialize_g1_point(modulus_len, &result)
+}
+
+impl<FE: ElementRepr> MappingApi for MappingApiImplementation<FE> {
+    fn map_to_g1(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (field, modulus_len, _, rest) = parse_base_field_from_encoding::<FE>(bytes)?;
+        let (a, b, rest) = parse_ab_in_base_field_from_encoding(rest, modulus_len, &field)?;
+        let (_order_len, order, rest) = parse_group_order_from_encoding(rest, ctx)?;
+
+        let fp_params = CurveOverFpParameters::new(&field);
+
+        let curve = WeierstrassCurve::new(order.as_ref(), a, b, &fp_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        let curve_encoding = &bytes[..(bytes.len() - rest.len())];
+
+        let (z, rest) = decode_fp(rest, modulus_len, &field)?;
+        let (isogeny_mode, rest) = split(rest, ISOGENY_MODE_ENCODING_LENGTH, "Input is not long enough to get isogeny mode")?;
+
+        let (isogenous_curve, isogeny, rest) = match isogeny_mode[0] {
+            ISOGENY_NONE => (None, None, rest),
+            ISOGENY_EXPLICIT => {
+                let (isogenous_curve, isogeny, rest) = decode_isogeny(rest, modulus_len, order.as_ref(), &fp_params, ctx.limits().max_isogeny_map_degree)?;
+
+                (Some(isogenous_curve), Some(isogeny), rest)
+            },
+            ISOGENY_BUILT_IN => {
+                let encoding = built_in_isogeny(curve_encoding)?;
+                let (isogenous_curve, isogeny, encoding_rest) = decode_isogeny(encoding, modulus_len, order.as_ref(), &fp_params, ctx.limits().max_isogeny_map_degree)?;
+                debug_assert!(encoding_rest.is_empty());
+
+                (Some(isogenous_curve), Some(isogeny), rest)
+            },
+            _ => {
+                return Err(ApiError::InputError(format!("Unknown isogeny mode, file {}, line {}", file!(), line!())));
+            }
+        };
+
+        let (_, cofactor, rest) = parse_cofactor_from_encoding(rest)?;
+
+        let (u, rest) = decode_fp(rest, modulus_len, &field)?;
+
+        if !rest.is_empty() {
+            return Err(ApiError::garbage_at_the_end(rest));
+        }
+
+        let swu_curve = isogenous_curve.as_ref().unwrap_or(&curve);
+        let swu = make_swu_parameters(z, swu_curve)?;
+
+        let (x, y) = simplified_swu_fp(&u, &swu, swu_curve).ok_or(
+            ApiError::with_code(ErrorCode::InvalidModulus, format!("Failed to find a square root, modulus is not prime, file {}, line {}", file!(), line!()))
+        )?;
+        let (x, y) = match isogeny {
+            Some(isogeny) => apply_isogeny_map(&x, &y, &isogeny, &fp_params),
+            None => (x, y)
+        };
+
+        finish_mapping(&curve, x, y, cofactor.as_ref(), modulus_len)
+    }
+
+    fn map_to_g1_svdw(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (field, modulus_len, _, rest) = parse_base_field_from_encoding::<FE>(bytes)?;
+        let (a, b, rest) = parse_ab_in_base_field_from_encoding(rest, modulus_len, &field)?;
+        let (_order_len, order, rest) = parse_group_order_from_encoding(rest, ctx)?;
+
+        let fp_params = CurveOverFpParameters::new(&field);
+
+        let curve = WeierstrassCurve::new(order.as_ref(), a, b, &fp_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        let (z, rest) = decode_fp(rest, modulus_len, &field)?;
+        let (_, cofactor, rest) = parse_cofactor_from_encoding(rest)?;
+        let (u, rest) = decode_fp(rest, modulus_len, &field)?;
+
+        if !rest.is_empty() {
+            return Err(ApiError::garbage_at_the_end(rest));
+        }
+
+        let svdw = make_svdw_parameters(z, &curve)?;
+
+        let (x, y) = svdw_fp(&u, &svdw, &curve).ok_or(
+            ApiError::with_code(ErrorCode::InvalidModulus, format!("Failed to find a square root, modulus is not prime, file {}, line {}", file!(), line!()))
+        )?;
+
+        finish_mapping(&curve, x, y, cofactor.as_ref(), modulus_len)
+    }
+
+    fn hash_to_field(bytes: &[u8], _ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (field, modulus_len, _, rest) = parse_base_field_from_encoding::<FE>(bytes)?;
+        let (hash, security_bits, num_elements, dst, msg) = decode_hash_to_field_parameters(rest)?;
+
+        let elements = hash_to_field_fp(msg, dst, num_elements, security_bits, hash, &field).ok_or(
+            ApiError::InputError(format!("Too many uniform bytes requested, file {}, line {}", file!(), line!()))
+        )?;
+
+        let mut result = Vec::with_capacity(modulus_len * elements.len());
+        for el in elements.iter() {
+            result.extend(serialize_fp_fixed_len(modulus_len, el)?);
+        }
+
+        Ok(result)
+    }
+}
+
+pub struct PublicMappingApi;
+
+impl MappingApi for PublicMappingApi {
+    fn map_to_g1(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        super::validate_input_length(OPERATION_MAP_TO_G1, bytes, ctx.point_encoding())?;
+        let (_, modulus, _) = parse_modulus_and_length(bytes)?;
+        let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+
+        let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, MappingApiImplementation, bytes, map_to_g1, ctx);
+
+        result
+    }
+
+    fn map_to_g1_svdw(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        super::validate_input_length(OPERATION_MAP_TO_G1_SVDW, bytes, ctx.point_encoding())?;
+        let (_, modulus, _) = parse_modulus_and_length(bytes)?;
+        let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+
+        let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, MappingApiImplementation, bytes, map_to_g1_svdw, ctx);
+
+        result
+    }
+
+    fn hash_to_field(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (_, modulus, _) = parse_modulus_and_length(bytes)?;
+        let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+
+        let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, MappingApiImplementation, bytes, hash_to_field, ctx);
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::engines::bls12_381::*;
+    use crate::mapping::constants::*;
+    use crate::public_interface::{API, PointEncoding};
+
+    const BLS12_381_BYTE_LEN: usize = 48;
+
+    fn encode_fp(el: &Fp<U384Repr, PrimeField<U384Repr>>) -> Vec<u8> {
+        serialize_fp_fixed_len(BLS12_381_BYTE_LEN, el).unwrap()
+    }
+
+    fn encode_curve(a: &Fp<U384Repr, PrimeField<U384Repr>>, b: &Fp<U384Repr, PrimeField<U384Repr>>) -> Vec<u8> {
+        let mut encoding = vec![OPERATION_MAP_TO_G1, BLS12_381_BYTE_LEN as u8];
+        encoding.extend(limbs_into_be_bytes(BLS12_381_MODULUS.as_ref()));
+        encoding.extend(encode_fp(a));
+        encoding.extend(encode_fp(b));
+        let order = limbs_into_be_bytes(&BLS12_381_SUBGROUP_ORDER);
+        encoding.push(order.len() as u8);
+        encoding.extend(order);
+
+        encoding
+    }
+
+    fn encode_cofactor(cofactor: &[u64]) -> Vec<u8> {
+        let cofactor = limbs_into_be_bytes(cofactor);
+        let mut encoding = vec![cofactor.len() as u8];
+        encoding.extend(cofactor);
+
+        encoding
+    }
+
+    fn encode_bls12_381_mapping(u: &Fp<U384Repr, PrimeField<U384Repr>>) -> Vec<u8> {
+        let (_, iso) = calculate_bls12_381_g1_mapping_params(&BLS12_381_FIELD);
+
+        let mut encoding = encode_curve(&BLS12_381_G1_CURVE.a, &BLS12_381_G1_CURVE.b);
+        encoding.extend(encode_fp(&BLS12_381_G1_SWU_Z));
+        encoding.push(BOOLEAN_TRUE);
+        encoding.extend(encode_fp(&BLS12_381_G1_CURVE_ISOGENY.a));
+        encoding.extend(encode_fp(&BLS12_381_G1_CURVE_ISOGENY.b));
+        encoding.push(iso.map_degree as u8);
+        for coeffs in vec![&iso.k1, &iso.k2, &iso.k3, &iso.k4].into_iter() {
+            for c in coeffs.iter() {
+                encoding.extend(encode_fp(c));
+            }
+        }
+        encoding.extend(encode_cofactor(&BLS12_381_G1_MAPPING_H_EFF));
+        encoding.extend(encode_fp(u));
+
+        encoding
+    }
+
+    #[test]
+    fn test_map_to_g1_matches_bls12_381_mapping() {
+        let mut u = BLS12_381_FP_ONE.clone();
+        for _ in 0..8 {
+            u.double();
+            u.add_assign(&BLS12_381_G1_SWU_Z);
+
+            let result = API::run(&encode_bls12_381_mapping(&u)).unwrap();
+            let expected = crate::engines::bls12_381::mapping::fp_to_g1(&u).unwrap();
+            assert_eq!(result, serialize_g1_point(BLS12_381_BYTE_LEN, &expected).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_map_to_g1_ietf_vector() {
+        // from https://tools.ietf.org/html/draft-irtf-cfrg-hash-to-curve-07#page-143
+        let u = str_radix_into_field("07fdf49ea58e96015d61f6b5c9d1c8f277146a533ae7fbca2a8ef4c41055cd961fbc6e26979b5554e4b4f22330c0e16d", 16, &BLS12_381_FIELD);
+        let result = API::run(&encode_bls12_381_mapping(&u)).unwrap();
+
+        assert_eq!(hex::encode(&result[..BLS12_381_BYTE_LEN]), "1223effdbb2d38152495a864d78eee14cb0992d89a241707abb03819a91a6d2fd65854ab9a69e9aacb0cbebfd490732c");
+        assert_eq!(hex::encode(&result[BLS12_381_BYTE_LEN..]), "0f925d61e0b235ecd945cbf0309291878df0d06e5d80d6b84aa4ff3e00633b26f9a7cb3523ef737d90e6d71e8b98b2d5");
+    }
+
+    #[test]
+    fn test_map_to_g1_without_isogeny() {
+        // SWU can be applied directly to the isogenous curve since A * B != 0 there
+        let mut encoding = encode_curve(&BLS12_381_G1_CURVE_ISOGENY.a, &BLS12_381_G1_CURVE_ISOGENY.b);
+        encoding.extend(encode_fp(&BLS12_381_G1_SWU_Z));
+        encoding.push(BOOLEAN_FALSE);
+        encoding.extend(encode_cofactor(&[1u64]));
+        encoding.extend(encode_fp(&BLS12_381_FP_ONE));
+
+        let result = API::run(&encoding).unwrap();
+        let (p, rest) = decode_g1_point_from_xy(&result, BLS12_381_BYTE_LEN, &BLS12_381_G1_CURVE_ISOGENY, PointEncoding::Uncompressed).unwrap();
+        assert!(rest.is_empty());
+        assert!(p.is_on_curve());
+    }
+
+    fn encode_bls12_381_built_in_mapping(u: &Fp<U384Repr, PrimeField<U384Repr>>) -> Vec<u8> {
+        let mut encoding = encode_curve(&BLS12_381_G1_CURVE.a, &BLS12_381_G1_CURVE.b);
+        encoding.extend(encode_fp(&BLS12_381_G1_SWU_Z));
+        encoding.push(ISOGENY_BUILT_IN);
+        encoding.extend(encode_cofactor(&BLS12_381_G1_MAPPING_H_EFF));
+        encoding.extend(encode_fp(u));
+
+        encoding
+    }
+
+    #[test]
+    fn test_map_to_g1_with_built_in_isogeny() {
+        let mut u = BLS12_381_FP_ONE.clone();
+        for _ in 0..4 {
+            u.double();
+            u.add_assign(&BLS12_381_G1_SWU_Z);
+
+            let result = API::run(&encode_bls12_381_built_in_mapping(&u)).unwrap();
+            assert_eq!(result, API::run(&encode_bls12_381_mapping(&u)).unwrap());
+        }
+
+        // only for the known curve
+        let mut encoding = encode_curve(&BLS12_381_G1_CURVE_ISOGENY.a, &BLS12_381_G1_CURVE_ISOGENY.b);
+        encoding.extend(encode_fp(&BLS12_381_G1_SWU_Z));
+        encoding.push(ISOGENY_BUILT_IN);
+        encoding.extend(encode_cofactor(&[1u64]));
+        encoding.extend(encode_fp(&BLS12_381_FP_ONE));
+        assert!(API::run(&encoding).is_err());
+
+        let mut unknown_mode = encode_bls12_381_built_in_mapping(&BLS12_381_FP_ONE);
+        let mode_offset = encode_curve(&BLS12_381_G1_CURVE.a, &BLS12_381_G1_CURVE.b).len() + BLS12_381_BYTE_LEN;
+        unknown_mode[mode_offset] = 0x03;
+        assert!(API::run(&unknown_mode).is_err());
+    }
+
+    #[test]
+    fn test_map_to_g1_rejects_invalid_parameters() {
+        let encoding = encode_bls12_381_mapping(&BLS12_381_FP_ONE);
+        let z_offset = encode_curve(&BLS12_381_G1_CURVE.a, &BLS12_381_G1_CURVE.b).len();
+
+        // Z = 4 is a square
+        let mut square_z = encoding.clone();
+        let mut four = BLS12_381_FP_ONE.clone();
+        four.double();
+        four.double();
+        square_z[z_offset..(z_offset + BLS12_381_BYTE_LEN)].copy_from_slice(&encode_fp(&four));
+        assert!(API::run(&square_z).is_err());
+
+        // A and B of the main curve can not be used without isogeny
+        let mut encoding = encode_curve(&BLS12_381_G1_CURVE.a, &BLS12_381_G1_CURVE.b);
+        encoding.extend(encode_fp(&BLS12_381_G1_SWU_Z));
+        encoding.push(BOOLEAN_FALSE);
+        encoding.extend(encode_cofactor(&[1u64]));
+        encoding.extend(encode_fp(&BLS12_381_FP_ONE));
+        assert!(API::run(&encoding).is_err());
+
+        let mut garbage = encode_bls12_381_mapping(&BLS12_381_FP_ONE);
+        garbage.push(0u8);
+        assert!(API::run(&garbage).is_err());
+
+        let mut truncated = encode_bls12_381_mapping(&BLS12_381_FP_ONE);
+        truncated.pop();
+        assert!(API::run(&truncated).is_err());
+    }
+
+    #[cfg(feature = "gas_metering")]
+    #[test]
+    fn test_map_to_g1_gas_estimate() {
+        let encoding = encode_bls12_381_mapping(&BLS12_381_FP_ONE);
+        let price = API::estimate_gas(&encoding).unwrap();
+        assert!(price > 0);
+
+        let mut without_isogeny = encode_curve(&BLS12_381_G1_CURVE_ISOGENY.a, &BLS12_381_G1_CURVE_ISOGENY.b);
+        without_isogeny.extend(encode_fp(&BLS12_381_G1_SWU_Z));
+        without_isogeny.push(BOOLEAN_FALSE);
+        without_isogeny.extend(encode_cofactor(&BLS12_381_G1_MAPPING_H_EFF));
+        without_isogeny.extend(encode_fp(&BLS12_381_FP_ONE));
+        assert_eq!(API::estimate_gas(&without_isogeny).unwrap(), price);
+        assert_eq!(API::estimate_gas(&encode_bls12_381_built_in_mapping(&BLS12_381_FP_ONE)).unwrap(), price);
+
+        let mut garbage = encoding.clone();
+        garbage.push(0u8);
+        assert!(API::estimate_gas(&garbage).is_err());
+    }
+
+    fn small_fp(k: u64) -> Fp<'static, U384Repr, PrimeField<U384Repr>> {
+        let mut el = Fp::zero(&BLS12_381_FIELD);
+        for _ in 0..k {
+            el.add_assign(&BLS12_381_FP_ONE);
+        }
+
+        el
+    }
+
+    /// First valid Z of 1, -1, 2, -2, ... same as `find_z_svdw` from RFC 9380
+    fn find_svdw_z() -> Fp<'static, U384Repr, PrimeField<U384Repr>> {
+        for k in 1.. {
+            for &negative in [false, true].iter() {
+                let mut z = small_fp(k);
+                if negative {
+                    z.negate();
+                }
+                if make_svdw_parameters(z.clone(), &BLS12_381_G1_CURVE).is_ok() {
+                    return z;
+                }
+            }
+        }
+
+        unreachable!()
+    }
+
+    fn encode_svdw_mapping(z: &Fp<U384Repr, PrimeField<U384Repr>>, cofactor: &[u64], u: &Fp<U384Repr, PrimeField<U384Repr>>) -> Vec<u8> {
+        let mut encoding = encode_curve(&BLS12_381_G1_CURVE.a, &BLS12_381_G1_CURVE.b);
+        encoding[0] = OPERATION_MAP_TO_G1_SVDW;
+        encoding.extend(encode_fp(z));
+        encoding.extend(encode_cofactor(cofactor));
+        encoding.extend(encode_fp(u));
+
+        encoding
+    }
+
+    #[test]
+    fn test_map_to_g1_svdw() {
+        // A = 0 for BLS12-381, so simplified SWU can not be used without isogeny
+        let z = find_svdw_z();
+        let svdw = make_svdw_parameters(z.clone(), &BLS12_381_G1_CURVE).unwrap();
+
+        let mut inputs = vec![Fp::zero(&BLS12_381_FIELD), BLS12_381_FP_ONE.clone()];
+        let mut u = BLS12_381_FP_ONE.clone();
+        for _ in 0..8 {
+            u.double();
+            u.add_assign(&z);
+            inputs.push(u.clone());
+        }
+        // u^2 * g(Z) == 1 or -1 makes 1 - u^2 * g(Z) or 1 + u^2 * g(Z) zero
+        let c1_inv = svdw.c1.inverse().unwrap();
+        let mut minus_c1_inv = c1_inv.clone();
+        minus_c1_inv.negate();
+        inputs.extend(sqrt(&c1_inv));
+        inputs.extend(sqrt(&minus_c1_inv));
+        assert!(inputs.len() > 10);
+
+        for u in inputs.iter() {
+            let result = API::run(&encode_svdw_mapping(&z, &[1u64], u)).unwrap();
+            let (p, rest) = decode_g1_point_from_xy(&result, BLS12_381_BYTE_LEN, &BLS12_381_G1_CURVE, PointEncoding::Uncompressed).unwrap();
+            assert!(rest.is_empty());
+            assert!(p.is_on_curve());
+            let (y, _) = decode_fp(&result[BLS12_381_BYTE_LEN..], BLS12_381_BYTE_LEN, &BLS12_381_FIELD).unwrap();
+            assert_eq!(fp_is_odd(&y), fp_is_odd(u));
+
+            let result = API::run(&encode_svdw_mapping(&z, &BLS12_381_G1_MAPPING_H_EFF, u)).unwrap();
+            let (p, _) = decode_g1_point_from_xy(&result, BLS12_381_BYTE_LEN, &BLS12_381_G1_CURVE, PointEncoding::Uncompressed).unwrap();
+            assert!(p.is_on_curve());
+            assert!(p.check_correct_subgroup());
+        }
+    }
+
+    #[test]
+    fn test_map_to_g1_svdw_rejects_invalid_parameters() {
+        let z = find_svdw_z();
+        let invalid_z = (1..64).map(|k| small_fp(k)).find(|z| make_svdw_parameters(z.clone(), &BLS12_381_G1_CURVE).is_err()).unwrap();
+        assert!(API::run(&encode_svdw_mapping(&invalid_z, &[1u64], &BLS12_381_FP_ONE)).is_err());
+
+        let mut garbage = encode_svdw_mapping(&z, &[1u64], &BLS12_381_FP_ONE);
+        garbage.push(0u8);
+        assert!(API::run(&garbage).is_err());
+
+        let mut truncated = encode_svdw_mapping(&z, &[1u64], &BLS12_381_FP_ONE);
+        truncated.pop();
+        assert!(API::run(&truncated).is_err());
+    }
+
+    #[cfg(feature = "gas_metering")]
+    #[test]
+    fn test_map_to_g1_svdw_gas_estimate() {
+        let z = find_svdw_z();
+        let encoding = encode_svdw_mapping(&z, &BLS12_381_G1_MAPPING_H_EFF, &BLS12_381_FP_ONE);
+        let price = API::estimate_gas(&encoding).unwrap();
+        assert!(price > API::estimate_gas(&encode_bls12_381_built_in_mapping(&BLS12_381_FP_ONE)).unwrap());
+
+        let mut garbage = encoding.clone();
+        garbage.push(0u8);
+        assert!(API::estimate_gas(&garbage).is_err());
+    }
+
+    fn encode_hash_to_field(extension_degree: u8, hash: u8, count: u8, dst: &[u8], msg: &[u8]) -> Vec<u8> {
+        let mut encoding = vec![OPERATION_HASH_TO_FIELD, BLS12_381_BYTE_LEN as u8];
+        encoding.extend(limbs_into_be_bytes(BLS12_381_MODULUS.as_ref()));
+        encoding.extend(vec![extension_degree, hash, 16, count, dst.len() as u8]);
+        encoding.extend_from_slice(dst);
+        encoding.extend_from_slice(msg);
+
+        encoding
+    }
+
+    #[test]
+    fn test_hash_to_field_ietf_vector() {
+        // RFC 9380, appendix J.10.1, BLS12381G2_XMD:SHA-256_SSWU_RO_ with msg = ""
+        let dst = b"QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_RO_";
+        let result = API::run(&encode_hash_to_field(EXTENSION_DEGREE_2, HASH_FUNCTION_SHA256, 2, dst, b"")).unwrap();
+        assert_eq!(result.len(), 4 * BLS12_381_BYTE_LEN);
+
+        let expected = [
+            "03dbc2cce174e91ba93cbb08f26b917f98194a2ea08d1cce75b2b9cc9f21689d80bd79b594a613d0a68eb807dfdc1cf8",
+            "05a2acec64114845711a54199ea339abd125ba38253b70a92c876df10598bd1986b739cad67961eb94f7076511b3b39a",
+            "02f99798e8a5acdeed60d7e18e9120521ba1f47ec090984662846bc825de191b5b7641148c0dbc237726a334473eee94",
+            "145a81e418d4010cc027a68f14391b30074e89e60ee7a22f87217b2f6eb0c4b94c9115b436e6fa4607e95a98de30a435",
+        ];
+        for (el, expected) in result.chunks(BLS12_381_BYTE_LEN).zip(expected.iter()) {
+            assert_eq!(hex::encode(el), *expected);
+        }
+
+        // Fp2 elements are the consecutive pairs of Fp elements
+        let in_fp = API::run(&encode_hash_to_field(EXTENSION_DEGREE_1, HASH_FUNCTION_SHA256, 4, dst, b"")).unwrap();
+        assert_eq!(in_fp, result);
+    }
+
+    #[test]
+    fn test_hash_to_field_rejects_invalid_parameters() {
+        let dst = b"QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_RO_";
+        assert!(API::run(&encode_hash_to_field(EXTENSION_DEGREE_1, HASH_FUNCTION_SHA512, 2, dst, b"abc")).is_ok());
+
+        assert!(API::run(&encode_hash_to_field(EXTENSION_DEGREE_3, HASH_FUNCTION_SHA256, 2, dst, b"abc")).is_err());
+        assert!(API::run(&encode_hash_to_field(EXTENSION_DEGREE_1, 0x03, 2, dst, b"abc")).is_err());
+        assert!(API::run(&encode_hash_to_field(EXTENSION_DEGREE_1, HASH_FUNCTION_SHA256, 0, dst, b"abc")).is_err());
+        // 255 * 64 bytes is more than 255 blocks of SHA-256
+        assert!(API::run(&encode_hash_to_field(EXTENSION_DEGREE_1, HASH_FUNCTION_SHA256, 255, dst, b"abc")).is_err());
+
+        let mut truncated = encode_hash_to_field(EXTENSION_DEGREE_1, HASH_FUNCTION_SHA256, 2, dst, b"");
+        truncated.pop();
+        assert!(API::run(&truncated).is_err());
+    }
+
+    #[test]
+    fn test_hash_to_field_one_byte_modulus() {
+        // p = 13, 17 uniform bytes 0x8da95c17f96e3d0e9d30ee770964d6d450 of "x" under DST "a" are 1 modulo p
+        let result = API::run(&[OPERATION_HASH_TO_FIELD, 1, 13, EXTENSION_DEGREE_1, HASH_FUNCTION_SHA256, 16, 1, 1, b'a', b'x']).unwrap();
+        assert_eq!(result, vec![1u8]);
+    }
+
+    #[cfg(feature = "gas_metering")]
+    #[test]
+    fn test_hash_to_field_gas_estimate() {
+        let dst = b"QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_RO_";
+        let short = API::estimate_gas(&encode_hash_to_field(EXTENSION_DEGREE_1, HASH_FUNCTION_SHA256, 2, dst, b"abc")).unwrap();
+        let long = API::estimate_gas(&encode_hash_to_field(EXTENSION_DEGREE_1, HASH_FUNCTION_SHA256, 2, dst, &[0x61u8; 1024])).unwrap();
+        let in_fp2 = API::estimate_gas(&encode_hash_to_field(EXTENSION_DEGREE_2, HASH_FUNCTION_SHA256, 2, dst, b"abc")).unwrap();
+        assert!(short > 0);
+        assert!(long > short);
+        assert!(in_fp2 > short);
+
+        assert!(API::estimate_gas(&encode_hash_to_field(EXTENSION_DEGREE_1, HASH_FUNCTION_SHA256, 255, dst, b"abc")).is_err());
+    }
+}
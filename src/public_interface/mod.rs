@@ -1,5 +1,7 @@
 pub mod decode_g1;
 pub mod decode_g2;
+pub mod decode_edwards;
+pub mod decode_binary;
 pub mod decode_fp;
 pub(crate) mod decode_utils;
 
@@ -8,24 +10,62 @@ pub(crate) mod api_specialization_macro;
 
 mod g1_ops;
 mod g2_ops;
+mod edwards_ops;
+mod montgomery_ops;
+mod binary_ops;
+mod field_ops;
 mod pairing_ops;
+mod curves;
+mod fixed_limbs;
+mod input_length;
+mod point_encoding;
+#[cfg(feature = "mappings")]
+mod mapping_ops;
 
 pub mod sane_limits;
 pub mod constants;
+pub mod encoding;
+pub mod typed;
 
 pub use pairing_ops::{PairingApi, PublicPairingApi};
 pub use g1_ops::{G1Api, PublicG1Api};
 pub use g2_ops::{G2Api, PublicG2Api};
+pub use edwards_ops::{EdwardsApi, PublicEdwardsApi};
+pub use montgomery_ops::{MontgomeryApi, PublicMontgomeryApi};
+pub use binary_ops::{BinaryCurveApi, PublicBinaryCurveApi};
+pub use field_ops::{FieldApi, PublicFieldApi};
+#[cfg(feature = "mappings")]
+pub use mapping_ops::{MappingApi, PublicMappingApi};
+#[cfg(feature = "mappings")]
+pub(crate) use mapping_ops::{built_in_isogeny, decode_hash_to_field_parameters};
+pub(crate) use input_length::validate_input_length;
+#[cfg(feature = "gas_metering")]
+pub(crate) use input_length::{strip_twist_description, validate_pairing_input_length, num_points};
+#[cfg(feature = "gas_metering")]
+pub(crate) use point_encoding::{point_len, check_operation};
+#[cfg(feature = "gas_metering")]
+pub(crate) use curves::is_well_known_curve;
 
 mod unified_api;
 pub use self::unified_api::{OperationType, perform_operation, PREALLOCATE_FOR_ERROR_BYTES, PREALLOCATE_FOR_RESULT_BYTES};
-pub use crate::errors::ApiError;
+pub use crate::errors::{ApiError, ErrorCode, CodedError};
+pub use self::decode_utils::AbiVersion;
+pub use self::point_encoding::PointEncoding;
+pub use self::sane_limits::Limits;
+pub use crate::execution_context::ExecutionContext;
+#[cfg(feature = "execution_report")]
+pub use crate::execution_report::ExecutionReport;
+
+use crate::alloc_prelude::*;
 
 #[cfg(feature = "c_api")]
 mod c_api;
 #[cfg(feature = "c_api")]
 pub use self::c_api::{c_perform_operation};
 
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
 #[cfg(feature = "eip_2537")]
 pub mod eip2537;
 
@@ -35,40 +75,279 @@ pub mod eip2539;
 #[cfg(feature = "eip_196")]
 pub mod eip196;
 
+/// Implementations of every group of operations, `API::run_operation` runs one of them
+pub(crate) trait Operations {
+    type G1: G1Api;
+    type G2: G2Api;
+    type Pairing: PairingApi;
+    type Edwards: EdwardsApi;
+    type Montgomery: MontgomeryApi;
+    type BinaryCurve: BinaryCurveApi;
+    type Field: FieldApi;
+    #[cfg(feature = "mappings")]
+    type Mapping: MappingApi;
+}
+
+/// Operations that decode the number of limbs of the modulus in every call
+pub(crate) struct PublicOperations;
+
+impl Operations for PublicOperations {
+    type G1 = PublicG1Api;
+    type G2 = PublicG2Api;
+    type Pairing = PublicPairingApi;
+    type Edwards = PublicEdwardsApi;
+    type Montgomery = PublicMontgomeryApi;
+    type BinaryCurve = PublicBinaryCurveApi;
+    type Field = PublicFieldApi;
+    #[cfg(feature = "mappings")]
+    type Mapping = PublicMappingApi;
+}
+
 pub struct API;
 
 impl API {
+    /// Runs the operation encoded in `bytes`, optionally prefixed with the ABI version
     pub fn run(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+        Self::run_in_context(bytes, &mut ExecutionContext::default())
+    }
+
+    /// Same as `run` in the context `ctx`, the point encoding of the context is set from
+    /// the ABI version of the input
+    pub(crate) fn run_in_context(bytes: &[u8], ctx: &mut ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (version, bytes) = decode_utils::split_abi_version(bytes)?;
+        ctx.set_point_encoding(version.point_encoding());
+        let ctx = &*ctx;
+
+        ctx.report().count_field_multiplications(|| {
+            match version {
+                AbiVersion::V1 => Self::run_v1(bytes, ctx),
+                AbiVersion::V2 => Self::run_v2(bytes, ctx),
+                AbiVersion::V3 => Self::run_v3(bytes, ctx)
+            }
+        })
+    }
+
+    fn run_v3(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        point_encoding::check_operation(bytes)?;
+
+        Self::run_v1(bytes, ctx)
+    }
+
+    fn run_v2(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
         use decode_utils::split;
         use constants::*;
 
         let (op_type, rest) = split(bytes, OPERATION_ENCODING_LENGTH , "Input should be longer than operation type encoding")?;
-
         match op_type[0] {
+            OPERATION_G2_ADD | OPERATION_G2_MUL | OPERATION_G2_MULTIEXP | OPERATION_G2_SUM |
+            OPERATION_G2_SUBGROUP_CHECK | OPERATION_G2_VALIDATE_POINT | OPERATION_G2_CLEAR_COFACTOR => {
+                PublicG2Api::check_twist(rest, ctx)?;
+            },
+            _ => {}
+        }
+
+        Self::run_v1(&input_length::strip_twist_description(bytes)?, ctx)
+    }
+
+    fn run_v1(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        use decode_utils::split;
+        use constants::*;
+
+        let (op_type, rest) = split(bytes, OPERATION_ENCODING_LENGTH , "Input should be longer than operation type encoding")?;
+        input_length::validate_input_length(op_type[0], rest, ctx.point_encoding())?;
+
+        // the most common widths are dispatched once here to code that is monomorphized
+        // for the width, the rest decode the modulus again in the `Public*Api` calls
+        let limbs = fixed_limbs::modulus_limbs(op_type[0], rest);
+        if let Some(limbs) = limbs {
+            ctx.report().record_limbs(limbs);
+        }
+
+        match limbs {
+            Some(4) => Self::run_operation::<fixed_limbs::Limbs4>(op_type[0], rest, ctx),
+            Some(6) => Self::run_operation::<fixed_limbs::Limbs6>(op_type[0], rest, ctx),
+            Some(8) => Self::run_operation::<fixed_limbs::Limbs8>(op_type[0], rest, ctx),
+            _ => Self::run_operation::<PublicOperations>(op_type[0], rest, ctx)
+        }
+    }
+
+    /// The single table of operations, shared by all the implementations of `Operations`
+    pub(crate) fn run_operation<O: Operations>(op_type: u8, rest: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        use constants::*;
+
+        match op_type {
             OPERATION_G1_ADD => {
-                PublicG1Api::add_points(&rest)
+                O::G1::add_points(rest, ctx)
             },
             OPERATION_G1_MUL => {
-                PublicG1Api::mul_point(&rest)
+                O::G1::mul_point(rest, ctx)
             },
             OPERATION_G1_MULTIEXP => {
-                PublicG1Api::multiexp(&rest)
+                O::G1::multiexp(rest, ctx)
             },
             OPERATION_G2_ADD => {
-                PublicG2Api::add_points(&rest)
+                O::G2::add_points(rest, ctx)
             },
             OPERATION_G2_MUL => {
-                PublicG2Api::mul_point(&rest)
+                O::G2::mul_point(rest, ctx)
             },
             OPERATION_G2_MULTIEXP => {
-                PublicG2Api::multiexp(&rest)
+                O::G2::multiexp(rest, ctx)
+            },
+            OPERATION_G1_SUM => {
+                O::G1::sum_points(rest, ctx)
+            },
+            OPERATION_G2_SUM => {
+                O::G2::sum_points(rest, ctx)
             },
             OPERATION_PAIRING => {
-                PublicPairingApi::pair(&rest)
+                O::Pairing::pair(rest, ctx)
+            },
+            OPERATION_PAIRING_GT => {
+                O::Pairing::pair_to_gt(rest, ctx)
+            },
+            OPERATION_MILLER_LOOP => {
+                O::Pairing::miller_loop(rest, ctx)
+            },
+            OPERATION_FINAL_EXP => {
+                O::Pairing::final_exp(rest, ctx)
+            },
+            OPERATION_PAIRING_EQUATION => {
+                O::Pairing::pairing_equation(rest, ctx)
+            },
+            OPERATION_BLS_VERIFY => {
+                O::Pairing::bls_verify(rest, ctx)
+            },
+            OPERATION_KZG_OPENING => {
+                O::Pairing::kzg_verify(rest, ctx)
+            },
+            OPERATION_G1_SUBGROUP_CHECK => {
+                O::G1::check_subgroup(rest, ctx)
+            },
+            OPERATION_G2_SUBGROUP_CHECK => {
+                O::G2::check_subgroup(rest, ctx)
+            },
+            OPERATION_G1_CLEAR_COFACTOR => {
+                O::G1::clear_cofactor(rest, ctx)
+            },
+            OPERATION_G2_CLEAR_COFACTOR => {
+                O::G2::clear_cofactor(rest, ctx)
+            },
+            OPERATION_G1_VALIDATE_POINT => {
+                O::G1::validate_point(rest, ctx)
+            },
+            OPERATION_G2_VALIDATE_POINT => {
+                O::G2::validate_point(rest, ctx)
+            },
+            OPERATION_EDWARDS_ADD => {
+                O::Edwards::add_points(rest, ctx)
+            },
+            OPERATION_EDWARDS_MUL => {
+                O::Edwards::mul_point(rest, ctx)
+            },
+            OPERATION_EDWARDS_MULTIEXP => {
+                O::Edwards::multiexp(rest, ctx)
+            },
+            OPERATION_MONTGOMERY_LADDER => {
+                O::Montgomery::ladder(rest, ctx)
+            },
+            OPERATION_BINARY_ADD => {
+                O::BinaryCurve::add_points(rest, ctx)
+            },
+            OPERATION_BINARY_MUL => {
+                O::BinaryCurve::mul_point(rest, ctx)
+            },
+            OPERATION_BINARY_MULTIEXP => {
+                O::BinaryCurve::multiexp(rest, ctx)
+            },
+            OPERATION_FIELD_ADD => {
+                O::Field::add(rest)
+            },
+            OPERATION_FIELD_MUL => {
+                O::Field::mul(rest)
+            },
+            OPERATION_FIELD_INVERSE => {
+                O::Field::inverse(rest)
+            },
+            OPERATION_FIELD_EXP => {
+                O::Field::exp(rest)
+            },
+            OPERATION_FIELD_EXT2_SQRT => {
+                O::Field::sqrt_ext2(rest)
+            },
+            #[cfg(feature = "mappings")]
+            OPERATION_MAP_TO_G1 => {
+                O::Mapping::map_to_g1(rest, ctx)
+            },
+            #[cfg(feature = "mappings")]
+            OPERATION_MAP_TO_G1_SVDW => {
+                O::Mapping::map_to_g1_svdw(rest, ctx)
+            },
+            #[cfg(feature = "mappings")]
+            OPERATION_HASH_TO_FIELD => {
+                O::Mapping::hash_to_field(rest, ctx)
             },
             _ => {
-                return Err(ApiError::InputError("Unknown operation type".to_owned()));
+                Err(ApiError::InputError("Unknown operation type".to_owned()))
             }
         }
     }
+
+    /// Same as `run`, but a panic during execution is caught and returned as
+    /// `ApiError::InternalError` instead of unwinding into the caller. Hosts that can not afford
+    /// to abort on a bug in the library should call this one
+    #[cfg(feature = "std")]
+    pub fn run_protected(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+        crate::errors::catch_internal_error(|| Self::run(bytes))
+    }
+
+    /// Same as `run`, but with `limits` on the loop parameters, embedding degree and isogeny
+    /// degree instead of the defaults. The limits are in effect for the duration of the call
+    /// only. Pairings on the well known curves (BLS12-381, BN254) take their fast path and
+    /// are not checked against the limits
+    pub fn run_with_config(bytes: &[u8], limits: &Limits) -> Result<Vec<u8>, ApiError> {
+        limits.validate()?;
+
+        Self::run_in_context(bytes, &mut ExecutionContext::with_limits(*limits))
+    }
+
+    /// Same as `run`, but also reports what was executed: number of limbs the arithmetic
+    /// was dispatched to, number of pairs, base field multiplications and wall time
+    #[cfg(feature = "execution_report")]
+    pub fn run_with_metadata(bytes: &[u8]) -> Result<(Vec<u8>, ExecutionReport), ApiError> {
+        let mut ctx = ExecutionContext::with_report();
+        let start = std::time::Instant::now();
+        let output = Self::run_in_context(bytes, &mut ctx)?;
+        let report = ctx.report().finish(start.elapsed());
+
+        Ok((output, report))
+    }
+
+    /// Prices the same input as `run` from the lengths and counts in it, 
+    /// without performing the arithmetic
+    #[cfg(feature = "gas_metering")]
+    pub fn estimate_gas(bytes: &[u8]) -> Result<u64, ApiError> {
+        crate::gas_meter::GasMeter::meter(bytes)
+    }
+
+    /// Same as `run`, but refuses to execute an input priced above `gas_limit`. Miller loops and
+    /// multiexponentiations additionally stop once the operation budget derived from the limit
+    /// is exhausted, so the work is bounded even if the gas model underprices some input
+    #[cfg(feature = "gas_metering")]
+    pub fn run_with_limit(bytes: &[u8], gas_limit: u64) -> Result<Vec<u8>, ApiError> {
+        use crate::operation_budget::OPERATIONS_PER_GAS;
+
+        let gas = Self::estimate_gas(bytes)?;
+        if gas > gas_limit {
+            return Err(ApiError::with_code(ErrorCode::OutOfGas, format!("Operation costs {} gas, limit is {}", gas, gas_limit)));
+        }
+
+        let mut ctx = ExecutionContext::with_budget(gas_limit.saturating_mul(OPERATIONS_PER_GAS));
+        let result = Self::run_in_context(bytes, &mut ctx);
+        if ctx.budget_is_exhausted() {
+            return Err(ApiError::with_code(ErrorCode::OutOfGas, format!("Operation budget of {} gas is exhausted", gas_limit)));
+        }
+
+        result
+    }
 }
\ No newline at end of file
@@ -0,0 +1,76 @@
+/// This api consists of the x-coordinate only scalar multiplication (Montgomery ladder)
+/// for curves B*y^2 = x^3 + A*x^2 + x over the base field.
+/// Every call has common parameters in the same layout as for G1 operations:
+/// - Lengths of modulus (in bytes)
+/// - Field modulus
+/// - Curve A
+/// - Curve B
+/// - Length of a scalar field (curve order) (in bytes)
+/// - Curve order
+///
+/// Assumptions:
+/// - one byte for length encoding
+
+use crate::weierstrass::CurveOverFpParameters;
+use crate::montgomery::MontgomeryCurve;
+use crate::representation::ElementRepr;
+use crate::traits::ZeroizeSecret;
+use crate::field::*;
+
+use super::decode_g1::*;
+use super::decode_fp::*;
+use super::decode_utils::*;
+
+use crate::errors::ApiError;
+use crate::execution_context::ExecutionContext;
+use crate::alloc_prelude::*;
+
+pub trait MontgomeryApi {
+    /// Takes an x-coordinate and a scalar, returns the x-coordinate of the product
+    fn ladder(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError>;
+}
+
+pub struct MontgomeryApiImplementation<FE: ElementRepr> {
+    _marker_fe: core::marker::PhantomData<FE>,
+}
+
+impl<FE: ElementRepr> MontgomeryApi for MontgomeryApiImplementation<FE> {
+    fn ladder(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (field, modulus_len, _, rest) = parse_base_field_from_encoding::<FE>(&bytes)?;
+        let (a, b, rest) = parse_ab_in_base_field_from_encoding(&rest, modulus_len, &field)?;
+        let (order_len, _order, rest) = parse_group_order_from_encoding(rest, ctx)?;
+
+        let fp_params = CurveOverFpParameters::new(&field);
+
+        let curve = MontgomeryCurve::new(a, b, &fp_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        let (u, rest) = decode_fp(&rest, modulus_len, &field)?;
+        let (mut scalar, rest) = decode_scalar_representation(rest, order_len)?;
+
+        if rest.len() != 0 {
+            return Err(ApiError::garbage_at_the_end(rest));
+        }
+
+        // number of ladder steps only depends on the scalar encoding length
+        let num_bits = checked_len_mul(order_len, 8)?;
+        let result = curve.ladder(&u, &scalar, num_bits);
+        scalar.as_mut().zeroize_secret();
+
+        serialize_fp_fixed_len(modulus_len, &result)
+    }
+}
+
+pub struct PublicMontgomeryApi;
+
+impl MontgomeryApi for PublicMontgomeryApi {
+    fn ladder(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (_, modulus, _) = parse_modulus_and_length(&bytes)?;
+        let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+
+        let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, MontgomeryApiImplementation, bytes, ladder, ctx);
+
+        result
+    }
+}
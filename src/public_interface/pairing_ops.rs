@@ -15,10 +15,16 @@
 /// 
 /// 
 
-use crate::weierstrass::curve::WeierstrassCurve;
-use crate::weierstrass::{Group, CurveOverFpParameters, CurveOverFp2Parameters, CurveOverFp3Parameters};
+use crate::weierstrass::curve::{WeierstrassCurve, CurvePoint};
+use crate::weierstrass::{Group, CurveParameters, CurveOverFpParameters, CurveOverFp2Parameters, CurveOverFp3Parameters, CurveOverFp4Parameters, CurveOverFp8Parameters, CurveOverFpKParameters};
 use crate::pairings::*;
 use crate::pairings::bls12::{Bls12Instance, Bls12InstanceParams};
+use crate::pairings::bls24::{Bls24Instance, Bls24InstanceParams};
+use crate::pairings::bls48::{Bls48Instance, Bls48InstanceParams};
+use crate::pairings::bw6::{Bw6Instance, Bw6InstanceParams};
+use crate::pairings::kss16::{Kss16Instance, Kss16InstanceParams};
+use crate::pairings::kss18::{Kss18Instance, Kss18InstanceParams};
+use crate::pairings::cocks_pinch::{CocksPinchInstance, CocksPinchInstanceParams, cyclotomic_polynomial};
 use crate::pairings::bn::{BnInstance, BnInstanceParams};
 use crate::pairings::mnt4::{MNT4Instance, MNT4InstanceParams};
 use crate::pairings::mnt6::{MNT6Instance, MNT6InstanceParams};
@@ -26,6 +32,8 @@ use crate::representation::{ElementRepr};
 use crate::traits::{FieldElement, ZeroAndOne};
 use crate::extension_towers::*;
 use crate::fp::Fp;
+use crate::extension_towers::fp2::{Fp2, Extension2};
+use crate::field::{SizedPrimeField, PrimeField};
 use crate::integers::*;
 
 use super::decode_g1::*;
@@ -33,290 +41,2011 @@ use super::decode_utils::*;
 use super::decode_fp::*;
 use super::decode_g2::*;
 use super::constants::*;
-use super::sane_limits::*;
+use super::point_encoding::{point_len, PointEncoding};
 
-use crate::errors::ApiError;
+use crate::errors::{ApiError, ErrorCode};
+use crate::execution_context::ExecutionContext;
+use crate::alloc_prelude::*;
 
 fn pairing_result_false() -> Vec<u8> {
     vec![0u8]
 }
 
-fn pairing_result_true() -> Vec<u8> {
+pub(crate) fn pairing_result_true() -> Vec<u8> {
     vec![1u8]
 }
 
+/// Subgroup checks of G1 and G2 points done while decoding pairs
+pub(crate) struct SubgroupChecks<G1, G2> {
+    pub(crate) in_g1: G1,
+    pub(crate) in_g2: G2
+}
+
+impl<G1, G2> SubgroupChecks<G1, G2> {
+    pub(crate) fn new<'a, CB: CurveParameters, CTW: CurveParameters>(in_g1: G1, in_g2: G2) -> Self
+        where G1: Fn(&CurvePoint<'a, CB>) -> bool,
+            G2: Fn(&CurvePoint<'a, CTW>) -> bool
+    {
+        Self { in_g1, in_g2 }
+    }
+}
+
+/// Pairs of G1 and G2 points decoded from the input
+type DecodedPairs<'a, CB, CTW> = (Vec<CurvePoint<'a, CB>>, Vec<CurvePoint<'a, CTW>>);
+
+/// Decoded pairs and the output to produce
+type DecodedPairsWithOutput<'a, CB, CTW> = (Vec<CurvePoint<'a, CB>>, Vec<CurvePoint<'a, CTW>>, PairingOutput);
+
+/// Decodes the list of pairs that ends every BLS12 and BN pairing call, checks the points
+/// and drops pairs where either point is zero. Also returns the output to produce, that is `MillerLoop`
+/// if the optional pairing mode byte after the pairs asks to skip the final exponentiation. Shared by the generic path and the fast paths
+/// for well known curves, so both give the same result and the same errors. The optional subgroup checks
+/// are done by `subgroup_checks`, that must give the same result as `check_correct_subgroup`
+pub(crate) fn decode_pairs_with_fp2_twist<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+    CTW: CurveParameters<BaseFieldElement = Fp2<'a, FE, F>>,
+    G1: Fn(&CurvePoint<'a, CB>) -> bool,
+    G2: Fn(&CurvePoint<'a, CTW>) -> bool
+    >
+    (
+        rest: &[u8],
+        modulus_len: usize,
+        order_len: usize,
+        g1_curve: &'a WeierstrassCurve<'a, CB>,
+        g2_curve: &'a WeierstrassCurve<'a, CTW>,
+        subgroup_checks: SubgroupChecks<G1, G2>,
+        output: PairingOutput,
+        encoding: PointEncoding
+    ) -> Result<DecodedPairsWithOutput<'a, CB, CTW>, ApiError>
+{
+    decode_pairs_with_subgroup_checks(rest, modulus_len, order_len, g1_curve, |rest| decode_g2_point_from_xy_in_fp2(rest, modulus_len, g2_curve, encoding), subgroup_checks, output, encoding)
+}
+
+/// Coefficient B' of the twist y^2 = x^3 + B' of a BLS12 or BN curve y^2 = x^3 + B,
+/// that is B / xi for the D-type twist and B * xi for the M-type one
+pub(crate) fn twist_b<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+    (
+        b: &Fp<'a, FE, F>,
+        fp2_non_residue: &Fp2<'a, FE, F>,
+        twist_type: TwistType
+    ) -> Result<Fp2<'a, FE, F>, ApiError>
+{
+    let mut b_fp2 = match twist_type {
+        TwistType::D => fp2_non_residue.inverse().ok_or(ApiError::UnexpectedZero("Fp2 non-residue must be invertible".to_owned()))?,
+        TwistType::M => *fp2_non_residue
+    };
+    b_fp2.mul_by_fp(b);
+
+    Ok(b_fp2)
+}
+
+/// Encodings of the G2 points in the part of the input that follows the curve parameters, in the order
+/// they appear in the layout of `output`. Only lengths are checked, the points are not decoded.
+/// The final exponentiation takes Miller loop values and has no G2 points
+fn g2_point_encodings(rest: &[u8], modulus_len: usize, output: PairingOutput, encoding: PointEncoding) -> Result<Vec<&[u8]>, ApiError> {
+    let g1_len = point_len(modulus_len, encoding)?;
+    let g2_len = point_len(checked_len_mul(2, modulus_len)?, encoding)?;
+
+    let mut encodings = vec![];
+    match output {
+        PairingOutput::FinalExponentiation => {},
+        PairingOutput::BlsSignature => {
+            let (public_key_in_g1, rest) = decode_bls_public_key_in_g1(rest)?;
+            // the message and the signature follow the generator and the public key in G1,
+            // or the generator and the public key are the first points
+            let rest = if public_key_in_g1 {
+                split(rest, checked_len_mul(2, g1_len)?, "Input is not long enough to get G1 points")?.1
+            } else {
+                rest
+            };
+            let (first, rest) = split(rest, g2_len, "Input is not long enough to get G2 point")?;
+            let (second, _) = split(rest, g2_len, "Input is not long enough to get G2 point")?;
+            encodings.push(first);
+            encodings.push(second);
+        },
+        PairingOutput::KzgOpening => {
+            let (_, rest) = split(rest, g1_len, "Input is not long enough to get G1 point")?;
+            let (generator, rest) = split(rest, g2_len, "Input is not long enough to get G2 point")?;
+            let (tau_g2, _) = split(rest, g2_len, "Input is not long enough to get G2 point")?;
+            encodings.push(generator);
+            encodings.push(tau_g2);
+        },
+        PairingOutput::IsOne | PairingOutput::Value | PairingOutput::MillerLoop | PairingOutput::Equation => {
+            let sign_len = if output == PairingOutput::Equation { SIGN_ENCODING_LENGTH } else { 0 };
+            let before_g2_len = checked_len_add(sign_len + 2 * BOOLEAN_ENCODING_LENGTH, g1_len)?;
+
+            let (num_pairs_encoding, mut global_rest) = split(rest, BYTES_FOR_LENGTH_ENCODING, "Input is not long enough to get number of pairs")?;
+            for _ in 0..num_pairs_encoding[0] {
+                let (_, rest) = split(global_rest, before_g2_len, "Input is not long enough to get G1 point")?;
+                let (g2, rest) = split(rest, g2_len, "Input is not long enough to get G2 point")?;
+                encodings.push(g2);
+                global_rest = rest;
+            }
+        }
+    }
+
+    Ok(encodings)
+}
+
+/// Twist type encoded as `TWIST_TYPE_DETECT` is the one that the first non-zero G2 point
+/// of the input in the layout of `output` lies on. If there is no such point all the pairings
+/// are trivial (or, for the final exponentiation, the twist is not used) and either twist
+/// gives the same result. If the point is on neither twist (or the input can not be decoded)
+/// the D-type is returned and decoding of the points reports the error
+pub(crate) fn detect_twist_type<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >
+    (
+        rest: &[u8],
+        modulus_len: usize,
+        extension_2: &'a Extension2<'a, FE, F>,
+        b: &Fp<'a, FE, F>,
+        fp2_non_residue: &Fp2<'a, FE, F>,
+        output: PairingOutput,
+        encoding: PointEncoding
+    ) -> Result<TwistType, ApiError>
+{
+    let b_d = twist_b(b, fp2_non_residue, TwistType::D)?;
+    let b_m = twist_b(b, fp2_non_residue, TwistType::M)?;
+
+    let twist_type_of_first_point = || -> Result<Option<TwistType>, ApiError> {
+        for point in g2_point_encodings(rest, modulus_len, output, encoding)? {
+            let (x, rest) = decode_fp2(point, modulus_len, extension_2)?;
+            let (y, _) = decode_fp2(rest, modulus_len, extension_2)?;
+            if x.is_zero() && y.is_zero() {
+                continue;
+            }
+
+            // y^2 - x^3 is B' of the twist the point is on
+            let mut b_twist = y;
+            b_twist.square();
+            let mut x_cubed = x;
+            x_cubed.square();
+            x_cubed.mul_assign(&x);
+            b_twist.sub_assign(&x_cubed);
+
+            if b_twist == b_m {
+                return Ok(Some(TwistType::M));
+            }
+            if b_twist == b_d {
+                return Ok(Some(TwistType::D));
+            }
+
+            return Ok(None);
+        }
+
+        Ok(None)
+    };
+
+    Ok(twist_type_of_first_point().ok().flatten().unwrap_or(TwistType::D))
+}
+
+/// Same as `decode_pairs_with_fp2_twist`, G2 points are decoded by `decode_g2` and subgroup checks
+/// multiply by the group order
+pub(crate) fn decode_pairs<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+    CTW: CurveParameters,
+    D: Fn(&'b [u8]) -> Result<(CurvePoint<'a, CTW>, &'b [u8]), ApiError>
+    >
+    (
+        rest: &'b [u8],
+        modulus_len: usize,
+        order_len: usize,
+        g1_curve: &'a WeierstrassCurve<'a, CB>,
+        decode_g2: D,
+        output: PairingOutput,
+        encoding: PointEncoding
+    ) -> Result<DecodedPairsWithOutput<'a, CB, CTW>, ApiError>
+{
+    let subgroup_checks = SubgroupChecks::new(|p: &CurvePoint<'a, CB>| p.check_correct_subgroup(), |q: &CurvePoint<'a, CTW>| q.check_correct_subgroup());
+
+    decode_pairs_with_subgroup_checks(rest, modulus_len, order_len, g1_curve, decode_g2, subgroup_checks, output, encoding)
+}
+
+fn decode_pairs_with_subgroup_checks<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+    CTW: CurveParameters,
+    D: Fn(&'b [u8]) -> Result<(CurvePoint<'a, CTW>, &'b [u8]), ApiError>,
+    G1: Fn(&CurvePoint<'a, CB>) -> bool,
+    G2: Fn(&CurvePoint<'a, CTW>) -> bool
+    >
+    (
+        rest: &'b [u8],
+        modulus_len: usize,
+        order_len: usize,
+        g1_curve: &'a WeierstrassCurve<'a, CB>,
+        decode_g2: D,
+        subgroup_checks: SubgroupChecks<G1, G2>,
+        output: PairingOutput,
+        encoding: PointEncoding
+    ) -> Result<DecodedPairsWithOutput<'a, CB, CTW>, ApiError>
+{
+    let SubgroupChecks { in_g1, in_g2 } = subgroup_checks;
+
+    if output == PairingOutput::BlsSignature {
+        let (g1_points, g2_points) = decode_bls_signature_pairs(rest, modulus_len, g1_curve, decode_g2, in_g1, in_g2, encoding)?;
+
+        return Ok((g1_points, g2_points, output));
+    }
+
+    if output == PairingOutput::KzgOpening {
+        let (g1_points, g2_points) = decode_kzg_opening_pairs(rest, modulus_len, order_len, g1_curve, decode_g2, in_g1, in_g2, encoding)?;
+
+        return Ok((g1_points, g2_points, output));
+    }
+
+    let (num_pairs_encoding, rest) = split(rest, BYTES_FOR_LENGTH_ENCODING, "Input is not long enough to get number of pairs")?;
+    let num_pairs = num_pairs_encoding[0] as usize;
+
+    if num_pairs == 0 && !crate::features::in_gas_metering() {
+        return Err(ApiError::InputError("Zero pairs encoded".to_owned()));
+    }
+
+    let mut global_rest = rest;
+
+    let mut g1_points = vec![];
+    let mut g2_points = vec![];
+
+    for _ in 0..num_pairs {
+        let (is_negative, rest) = if output == PairingOutput::Equation {
+            decode_sign_is_negative(global_rest)?
+        } else {
+            (false, global_rest)
+        };
+        let (check_g1_subgroup, rest) = decode_boolean(rest)?;
+        let (mut g1, rest) = decode_g1_point_from_xy(rest, modulus_len, g1_curve, encoding)?;
+        let (check_g2_subgroup, rest) = decode_boolean(rest)?;
+        let (g2, rest) = decode_g2(rest)?;
+        global_rest = rest;
+
+        if !g1.is_on_curve() && !crate::features::in_fuzzing_or_gas_metering() {
+            return Err(ApiError::with_code(ErrorCode::NotOnCurve, "G1 point is not on curve".to_owned()));
+        }
+
+        if !g2.is_on_curve() && !crate::features::in_fuzzing_or_gas_metering() {
+            return Err(ApiError::with_code(ErrorCode::NotOnCurve, "G2 point is not on curve".to_owned()));
+        }
+
+        if check_g1_subgroup && !in_g1(&g1) && !crate::features::in_fuzzing_or_gas_metering() {
+            return Err(ApiError::with_code(ErrorCode::NotInSubgroup, "G1 or G2 point is not in the expected subgroup".to_owned()));
+        }
+
+        if check_g2_subgroup && !in_g2(&g2) && !crate::features::in_fuzzing_or_gas_metering() {
+            return Err(ApiError::with_code(ErrorCode::NotInSubgroup, "G1 or G2 point is not in the expected subgroup".to_owned()));
+        }
+
+        if !g1.is_zero() && !g2.is_zero() {
+            // e(-P, Q) is the inverse of e(P, Q)
+            if is_negative {
+                g1.negate();
+            }
+            g1_points.push(g1);
+            g2_points.push(g2);
+        }
+    }
+
+    // the equation is always checked in full, so it doesn't take the pairing mode
+    if output == PairingOutput::Equation {
+        if !global_rest.is_empty() {
+            return Err(ApiError::garbage_at_the_end(global_rest));
+        }
+
+        return Ok((g1_points, g2_points, output));
+    }
+
+    let output = if decode_pairing_mode_is_miller_loop_only(global_rest)? { PairingOutput::MillerLoop } else { output };
+
+    Ok((g1_points, g2_points, output))
+}
+
+/// Checks that a point of a pairing input is on the curve and in the subgroup, `group` names it in the errors
+fn check_point_in_subgroup<'a, C: CurveParameters, S: Fn(&CurvePoint<'a, C>) -> bool>(
+    p: &CurvePoint<'a, C>,
+    in_subgroup: &S,
+    group: &str
+) -> Result<(), ApiError> {
+    if !p.is_on_curve() && !crate::features::in_fuzzing_or_gas_metering() {
+        return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("{} point is not on curve", group)));
+    }
+    if !in_subgroup(p) && !crate::features::in_fuzzing_or_gas_metering() {
+        return Err(ApiError::with_code(ErrorCode::NotInSubgroup, "G1 or G2 point is not in the expected subgroup".to_owned()));
+    }
+
+    Ok(())
+}
+
+/// Decodes the group of the public key, the generator of this group, the public key, the message point and
+/// the signature, and turns the check e(pk, H(m)) == e(g, sig) into the pairs (pk, H(m)) and (-g, sig),
+/// or (H(m), pk) and (-sig, g) for public keys in G2. All points are checked to be on the curve and in
+/// the subgroup, the generator and the public key can not be zero
+fn decode_bls_signature_pairs<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+    CTW: CurveParameters,
+    D: Fn(&'b [u8]) -> Result<(CurvePoint<'a, CTW>, &'b [u8]), ApiError>,
+    G1: Fn(&CurvePoint<'a, CB>) -> bool,
+    G2: Fn(&CurvePoint<'a, CTW>) -> bool
+    >
+    (
+        rest: &'b [u8],
+        modulus_len: usize,
+        g1_curve: &'a WeierstrassCurve<'a, CB>,
+        decode_g2: D,
+        in_g1: G1,
+        in_g2: G2,
+        encoding: PointEncoding
+    ) -> Result<DecodedPairs<'a, CB, CTW>, ApiError>
+{
+
+    let check_g1 = |p: &CurvePoint<'a, CB>| check_point_in_subgroup(p, &in_g1, "G1");
+    let check_g2 = |q: &CurvePoint<'a, CTW>| check_point_in_subgroup(q, &in_g2, "G2");
+
+    let (public_key_in_g1, rest) = decode_bls_public_key_in_g1(rest)?;
+    let (g1_points, g2_points, rest) = if public_key_in_g1 {
+        let (mut generator, rest) = decode_g1_point_from_xy(rest, modulus_len, g1_curve, encoding)?;
+        let (public_key, rest) = decode_g1_point_from_xy(rest, modulus_len, g1_curve, encoding)?;
+        let (message, rest) = decode_g2(rest)?;
+        let (signature, rest) = decode_g2(rest)?;
+        if generator.is_zero() || public_key.is_zero() {
+            return Err(ApiError::UnexpectedZero("Generator and public key can not be zero".to_owned()));
+        }
+        check_g1(&generator)?;
+        check_g1(&public_key)?;
+        check_g2(&message)?;
+        check_g2(&signature)?;
+        generator.negate();
+
+        (vec![public_key, generator], vec![message, signature], rest)
+    } else {
+        let (generator, rest) = decode_g2(rest)?;
+        let (public_key, rest) = decode_g2(rest)?;
+        let (message, rest) = decode_g1_point_from_xy(rest, modulus_len, g1_curve, encoding)?;
+        let (mut signature, rest) = decode_g1_point_from_xy(rest, modulus_len, g1_curve, encoding)?;
+        if generator.is_zero() || public_key.is_zero() {
+            return Err(ApiError::UnexpectedZero("Generator and public key can not be zero".to_owned()));
+        }
+        check_g2(&generator)?;
+        check_g2(&public_key)?;
+        check_g1(&message)?;
+        check_g1(&signature)?;
+        signature.negate();
+
+        (vec![message, signature], vec![public_key, generator], rest)
+    };
+
+    if !rest.is_empty() {
+        return Err(ApiError::garbage_at_the_end(rest));
+    }
+
+    // pairs with the zero message or signature don't contribute to the product
+    let (g1_points, g2_points) = g1_points.into_iter().zip(g2_points).filter(|(p, q)| !p.is_zero() && !q.is_zero()).unzip();
+
+    Ok((g1_points, g2_points))
+}
+
+/// Decodes the generators of G1 and G2, tau times the generator of G2, the commitment, the evaluation point z,
+/// the value y and the proof, and turns the opening check e(C - y*G, H) == e(proof, tau*H - z*H) into the pairs
+/// (C - y*G + z*proof, H) and (-proof, tau*H), so only G1 is multiplied. All points are checked to be on the curve
+/// and in the subgroup, the generators can not be zero
+fn decode_kzg_opening_pairs<
+    'a,
+    'b,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    CB: CurveParameters<BaseFieldElement = Fp<'a, FE, F>>,
+    CTW: CurveParameters,
+    D: Fn(&'b [u8]) -> Result<(CurvePoint<'a, CTW>, &'b [u8]), ApiError>,
+    G1: Fn(&CurvePoint<'a, CB>) -> bool,
+    G2: Fn(&CurvePoint<'a, CTW>) -> bool
+    >
+    (
+        rest: &'b [u8],
+        modulus_len: usize,
+        order_len: usize,
+        g1_curve: &'a WeierstrassCurve<'a, CB>,
+        decode_g2: D,
+        in_g1: G1,
+        in_g2: G2,
+        encoding: PointEncoding
+    ) -> Result<DecodedPairs<'a, CB, CTW>, ApiError>
+{
+
+    let (g1_generator, rest) = decode_g1_point_from_xy(rest, modulus_len, g1_curve, encoding)?;
+    let (g2_generator, rest) = decode_g2(rest)?;
+    let (tau_g2, rest) = decode_g2(rest)?;
+    let (commitment, rest) = decode_g1_point_from_xy(rest, modulus_len, g1_curve, encoding)?;
+    let (z, rest) = decode_scalar_representation(rest, order_len)?;
+    let (y, rest) = decode_scalar_representation(rest, order_len)?;
+    let (mut proof, rest) = decode_g1_point_from_xy(rest, modulus_len, g1_curve, encoding)?;
+
+    if !rest.is_empty() {
+        return Err(ApiError::garbage_at_the_end(rest));
+    }
+
+    if g1_generator.is_zero() || g2_generator.is_zero() {
+        return Err(ApiError::UnexpectedZero("Generators can not be zero".to_owned()));
+    }
+
+    for p in [&g1_generator, &commitment, &proof].iter() {
+        check_point_in_subgroup(p, &in_g1, "G1")?;
+    }
+    for q in [&g2_generator, &tau_g2].iter() {
+        check_point_in_subgroup(q, &in_g2, "G2")?;
+    }
+
+    let mut lhs = commitment;
+    lhs.sub_assign(&g1_generator.mul(y));
+    lhs.add_assign(&proof.mul(z));
+    proof.negate();
+
+    // pairs with a zero point don't contribute to the product
+    let (g1_points, g2_points) = vec![lhs, proof].into_iter().zip(vec![g2_generator, tau_g2]).filter(|(p, q)| !p.is_zero() && !q.is_zero()).unzip();
+
+    Ok((g1_points, g2_points))
+}
+
+/// Encoding of the result of a pairing call
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PairingOutput {
+    /// Single boolean byte, whether the result is one
+    IsOne,
+    /// Final exponentiated value as a fixed length element of GT (Fp12, Fp6 or Fp4)
+    Value,
+    /// Product of the Miller loops without the final exponentiation, encoded as `Value`
+    MillerLoop,
+    /// The list of pairs is replaced by a list of Miller loop values, the final exponentiation
+    /// of their product is encoded as `Value`
+    FinalExponentiation,
+    /// Every pair is preceded by a sign and the pairings of the pairs with a minus sign are inverted,
+    /// the product is encoded as `IsOne`
+    Equation,
+    /// The pairs are replaced by a BLS signature with its public key and message,
+    /// whether it's valid is encoded as `IsOne`
+    BlsSignature,
+    /// The pairs are replaced by a KZG commitment with its opening at a point,
+    /// whether the opening is valid is encoded as `IsOne`
+    KzgOpening
+}
+
+/// Encodes the pairing result as requested, `serialize` is a fixed length serialization of GT
+pub(crate) fn encode_pairing_result<F: FieldElement, S: Fn(&F) -> Result<Vec<u8>, ApiError>>(
+    pairing_result: Option<F>, 
+    one: &F,
+    output: PairingOutput,
+    serialize: S
+) -> Result<Vec<u8>, ApiError> {
+    let pairing_result = pairing_result.ok_or(ApiError::UnknownParameter("Pairing engine returned no value".to_owned()))?;
+    match output {
+        PairingOutput::IsOne | PairingOutput::Equation | PairingOutput::BlsSignature | PairingOutput::KzgOpening => {
+            let result = if &pairing_result == one {
+                pairing_result_true()
+            } else {
+                pairing_result_false()
+            };
+
+            Ok(result)
+        },
+        PairingOutput::Value | PairingOutput::MillerLoop | PairingOutput::FinalExponentiation => {
+            serialize(&pairing_result)
+        }
+    }
+}
+
+/// What the parse stage of a family hands to the later stages: the engine built from the curve
+/// parameters, one of GT, the input that follows the parameters, and the decoders that depend on
+/// the parameters. `prepare` decodes and checks the pairs, `decode_gt` decodes the Miller loop
+/// values of final exponentiation only calls and `serialize` is a fixed length serialization of GT
+pub(crate) struct ParsedPairing<'e, 'b, E: PairingEngine, P, D, S> {
+    pub(crate) engine: &'e E,
+    pub(crate) one: E::PairingResult,
+    pub(crate) rest: &'b [u8],
+    pub(crate) prepare: P,
+    pub(crate) decode_gt: D,
+    pub(crate) serialize: S
+}
+
+/// Stages of a pairing call that follow the parse stage of a family. The engine borrows the fields
+/// and the towers decoded by the parse stage, so it is handed over to the stages instead of
+/// being returned
+pub(crate) trait PairingStages {
+    /// Output the call asks for, the parse stage may need it to detect the twist type from the pairs
+    fn output(&self) -> PairingOutput;
+
+    fn run<'e, 'b, E, P, D, S>(self, parsed: ParsedPairing<'e, 'b, E, P, D, S>) -> Result<Vec<u8>, ApiError>
+        where
+            E: PairingEngine,
+            P: FnOnce(&'b [u8], PairingOutput) -> Result<(Vec<E::G1>, Vec<E::G2>, PairingOutput), ApiError>,
+            D: Fn(&'b [u8]) -> Result<(E::PairingResult, &'b [u8]), ApiError>,
+            S: Fn(&E::PairingResult) -> Result<Vec<u8>, ApiError>;
+}
+
+/// Stages shared by all the families and the fast path of the well known curves: the pairs are
+/// prepared, then the engine runs the Miller loop and the final exponentiation as far as `output`
+/// asks. Final exponentiation only calls skip the pairs
+pub(crate) struct PairingPipeline<'c> {
+    output: PairingOutput,
+    ctx: &'c ExecutionContext
+}
+
+impl<'c> PairingPipeline<'c> {
+    pub(crate) fn new(output: PairingOutput, ctx: &'c ExecutionContext) -> Self {
+        Self {
+            output,
+            ctx
+        }
+    }
+
+    /// Decodes and checks the pairs. Pairing equations and signatures change the output to `IsOne`
+    fn prepare<'b, G1, G2, P>(&self, rest: &'b [u8], prepare: P) -> Result<(Vec<G1>, Vec<G2>, PairingOutput), ApiError>
+        where P: FnOnce(&'b [u8], PairingOutput) -> Result<(Vec<G1>, Vec<G2>, PairingOutput), ApiError>
+    {
+        let (g1_points, g2_points, output) = prepare(rest, self.output)?;
+        debug_assert!(g1_points.len() == g2_points.len());
+        self.ctx.report().record_num_pairs(g1_points.len());
+
+        Ok((g1_points, g2_points, output))
+    }
+
+    fn miller_loop<E: PairingEngine>(&self, engine: &E, g1_points: &[E::G1], g2_points: &[E::G2]) -> Option<E::PairingResult> {
+        self.ctx.report().time_miller_loop(|| engine.miller_loop_product_with_context(g1_points, g2_points, self.ctx))
+    }
+
+    fn final_exp<E: PairingEngine>(&self, engine: &E, f: &E::PairingResult) -> Option<E::PairingResult> {
+        self.ctx.report().time_final_exp(|| engine.final_exp(f))
+    }
+}
+
+impl<'c> PairingStages for PairingPipeline<'c> {
+    fn output(&self) -> PairingOutput {
+        self.output
+    }
+
+    fn run<'e, 'b, E, P, D, S>(self, parsed: ParsedPairing<'e, 'b, E, P, D, S>) -> Result<Vec<u8>, ApiError>
+        where
+            E: PairingEngine,
+            P: FnOnce(&'b [u8], PairingOutput) -> Result<(Vec<E::G1>, Vec<E::G2>, PairingOutput), ApiError>,
+            D: Fn(&'b [u8]) -> Result<(E::PairingResult, &'b [u8]), ApiError>,
+            S: Fn(&E::PairingResult) -> Result<Vec<u8>, ApiError>
+    {
+        let ParsedPairing { engine, one, rest, prepare, decode_gt, serialize } = parsed;
+        if self.output == PairingOutput::FinalExponentiation {
+            let miller_loop_value = decode_miller_loop_values_product(rest, decode_gt)?;
+            let pairing_result = self.final_exp(engine, &miller_loop_value);
+
+            return encode_pairing_result(pairing_result, &one, self.output, serialize);
+        }
+
+        let (g1_points, g2_points, output) = self.prepare(rest, prepare)?;
+        if g1_points.is_empty() {
+            return encode_pairing_result(Some(one.clone()), &one, output, serialize);
+        }
+
+        let miller_loop_value = self.miller_loop(engine, &g1_points, &g2_points);
+        let pairing_result = match output {
+            PairingOutput::MillerLoop => miller_loop_value,
+            _ => miller_loop_value.and_then(|f| self.final_exp(engine, &f))
+        };
+
+        encode_pairing_result(pairing_result, &one, output, serialize)
+    }
+}
+
+/// Decodes the number of Miller loop values followed by the values themselves
+/// and returns their product. Callers accumulate Miller loops over several calls this way
+pub(crate) fn decode_miller_loop_values_product<
+    'b,
+    F: FieldElement,
+    D: Fn(&'b [u8]) -> Result<(F, &'b [u8]), ApiError>
+    >
+    (
+        rest: &'b [u8],
+        decode_value: D
+    ) -> Result<F, ApiError>
+{
+    let (num_values_encoding, rest) = split(rest, BYTES_FOR_LENGTH_ENCODING, "Input is not long enough to get number of Miller loop values")?;
+    let num_values = num_values_encoding[0] as usize;
+    if num_values == 0 {
+        return Err(ApiError::InputError("Zero Miller loop values encoded".to_owned()));
+    }
+
+    let (mut product, mut global_rest) = decode_value(rest)?;
+    for _ in 1..num_values {
+        let (value, rest) = decode_value(global_rest)?;
+        product.mul_assign(&value);
+        global_rest = rest;
+    }
+
+    if !global_rest.is_empty() {
+        return Err(ApiError::garbage_at_the_end(global_rest));
+    }
+
+    Ok(product)
+}
+
 pub struct PublicPairingApi;
 
-impl PairingApi for PublicPairingApi {
-    fn pair(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
-        use crate::field::*;
-        let (_curve_type, rest) = split(bytes, CURVE_TYPE_LENGTH, "Input should be longer than curve type encoding")?;
-        let (_, modulus, _) = parse_modulus_and_length(&rest)?;
-        let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+/// The operations of `PublicPairingApi` only differ by the operation type and the output: the input
+/// length is checked upfront, the well known curves take the fast path and other inputs go to
+/// the families of `PairingFamilies::ALL` by the number of limbs of the modulus
+macro_rules! public_pairing_operations {
+    ($($func: ident => ($op_type: expr, $output: expr)),* $(,)?) => {
+        impl PairingApi for PublicPairingApi {
+            $(
+                fn $func(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+                    use crate::field::*;
+                    super::validate_input_length($op_type, bytes, ctx.point_encoding())?;
+                    if let Some(result) = super::curves::try_pair_well_known_curve(bytes, $output, ctx) {
+                        return result;
+                    }
 
-        let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, PairingApiImplementation, bytes, pair); 
+                    let (_curve_type, rest) = split(bytes, CURVE_TYPE_LENGTH, "Input should be longer than curve type encoding")?;
+                    let (_, modulus, _) = parse_modulus_and_length(rest)?;
+                    let modulus_limbs = num_limbs_for_modulus(&modulus)?;
 
-        result
+                    expand_for_modulus_limbs!(modulus_limbs, PairingApiImplementation, bytes, $func, ctx)
+                }
+            )*
+        }
     }
 }
 
+public_pairing_operations!(
+    pair => (OPERATION_PAIRING, PairingOutput::IsOne),
+    pair_to_gt => (OPERATION_PAIRING_GT, PairingOutput::Value),
+    miller_loop => (OPERATION_MILLER_LOOP, PairingOutput::MillerLoop),
+    final_exp => (OPERATION_FINAL_EXP, PairingOutput::FinalExponentiation),
+    pairing_equation => (OPERATION_PAIRING_EQUATION, PairingOutput::Equation),
+    bls_verify => (OPERATION_BLS_VERIFY, PairingOutput::BlsSignature),
+    kzg_verify => (OPERATION_KZG_OPENING, PairingOutput::KzgOpening),
+);
+
 pub trait PairingApi {
-    fn pair(bytes: &[u8]) -> Result<Vec<u8>, ApiError>;
+    fn pair(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError>;
+    /// Same input as `pair`, returns the pairing value as a fixed length element of GT:
+    /// Fp12 for BLS12 and BN, Fp24 for BLS24, Fp48 for BLS48, Fp16 for KSS16, Fp18 for KSS18,
+    /// Fp6 for BW6 and MNT6, Fp4 for MNT4 and Fp^k for Cocks-Pinch curves of embedding degree k
+    fn pair_to_gt(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError>;
+    /// Same input as `pair`, returns the product of the Miller loops over all pairs without
+    /// the final exponentiation, encoded as in `pair_to_gt`
+    fn miller_loop(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError>;
+    /// Curve parameters as in `pair` followed by the number of Miller loop values and the values,
+    /// returns the final exponentiation of their product encoded as in `pair_to_gt`.
+    /// Values may come from several `miller_loop` calls
+    fn final_exp(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError>;
+    /// Same input as `pair` with a sign before every pair, returns whether the product of the pairings
+    /// is one after inverting the ones with a minus sign. So e(A, B) == e(C, D) is checked by
+    /// the pairs (A, B) and (C, D) with the signs plus and minus
+    fn pairing_equation(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError>;
+    /// Curve parameters as in `pair` followed by the group of the public key, the generator of this group,
+    /// the public key, the message point and the signature in the other group. Returns whether
+    /// e(pk, H(m)) == e(g, sig), with the arguments of the pairings swapped for public keys in G2
+    fn bls_verify(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError>;
+    /// Curve parameters as in `pair` followed by the generators G of G1 and H of G2, tau*H, the commitment C,
+    /// the evaluation point z and the value y as scalars of the group order length, and the proof.
+    /// Returns whether e(C - y*G, H) == e(proof, tau*H - z*H)
+    fn kzg_verify(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError>;
 }
 
 pub(crate) struct PairingApiImplementation<FE: ElementRepr> {
-    _marker_fe: std::marker::PhantomData<FE>,
+    _marker_fe: core::marker::PhantomData<FE>,
 }
 
 impl<FE: ElementRepr> PairingApi for PairingApiImplementation<FE> {
-    fn pair(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
-        let (curve_type, rest) = split(bytes, CURVE_TYPE_LENGTH, "Input should be longer than curve type encoding")?;
+    fn pair(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        PairingApiImplementation::<FE>::pair_with_output(bytes, PairingOutput::IsOne, ctx)
+    }
 
-        match curve_type[0] {
-            BLS12 => {
-                PairingApiImplementation::<FE>::pair_bls12(&rest)
-            },
-            BN => {
-                PairingApiImplementation::<FE>::pair_bn(&rest)
-            },
-            MNT4 => {
-                PairingApiImplementation::<FE>::pair_mnt4(&rest)
-            },
-            MNT6 => {
-                PairingApiImplementation::<FE>::pair_mnt6(&rest)
-            },
-            _ => {
-                return Err(ApiError::InputError("Unknown curve type".to_owned()));
-            }
-        }
+    fn pair_to_gt(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        PairingApiImplementation::<FE>::pair_with_output(bytes, PairingOutput::Value, ctx)
+    }
+
+    fn miller_loop(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        PairingApiImplementation::<FE>::pair_with_output(bytes, PairingOutput::MillerLoop, ctx)
+    }
+
+    fn final_exp(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        PairingApiImplementation::<FE>::pair_with_output(bytes, PairingOutput::FinalExponentiation, ctx)
+    }
+
+    fn pairing_equation(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        PairingApiImplementation::<FE>::pair_with_output(bytes, PairingOutput::Equation, ctx)
+    }
+
+    fn bls_verify(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        PairingApiImplementation::<FE>::pair_with_output(bytes, PairingOutput::BlsSignature, ctx)
+    }
+
+    fn kzg_verify(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        PairingApiImplementation::<FE>::pair_with_output(bytes, PairingOutput::KzgOpening, ctx)
+    }
+}
+
+/// Family of pairing friendly curves behind one curve type byte. A family implements the parse stage:
+/// it decodes the curve parameters that follow the curve type, builds its `PairingEngine` and hands it
+/// to `stages` together with the decoders of its pairs and GT elements. The engine borrows the fields
+/// and the towers decoded from the same input, so all the stages run within one call
+pub(crate) trait PairingFamily {
+    const CURVE_TYPE: u8;
+
+    fn parse<FE: ElementRepr, S: PairingStages>(bytes: &[u8], ctx: &ExecutionContext, stages: S) -> Result<Vec<u8>, ApiError>;
+
+    /// Parse stage followed by the stages of `PairingPipeline`
+    fn pair_with_output<FE: ElementRepr>(bytes: &[u8], output: PairingOutput, ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        Self::parse::<FE, _>(bytes, ctx, PairingPipeline::new(output, ctx))
+    }
+}
+
+type PairWithOutput = fn(&[u8], PairingOutput, &ExecutionContext) -> Result<Vec<u8>, ApiError>;
+
+struct PairingFamilies<FE: ElementRepr> {
+    _marker_fe: core::marker::PhantomData<FE>,
+}
+
+impl<FE: ElementRepr> PairingFamilies<FE> {
+    /// All the families by curve type, a new family only needs an entry here
+    const ALL: &'static [(u8, PairWithOutput)] = &[
+        (Bls12Family::CURVE_TYPE, Bls12Family::pair_with_output::<FE>),
+        (BnFamily::CURVE_TYPE, BnFamily::pair_with_output::<FE>),
+        (Mnt4Family::CURVE_TYPE, Mnt4Family::pair_with_output::<FE>),
+        (Mnt6Family::CURVE_TYPE, Mnt6Family::pair_with_output::<FE>),
+        (Bls24Family::CURVE_TYPE, Bls24Family::pair_with_output::<FE>),
+        (Bls48Family::CURVE_TYPE, Bls48Family::pair_with_output::<FE>),
+        (Bw6Family::CURVE_TYPE, Bw6Family::pair_with_output::<FE>),
+        (Kss16Family::CURVE_TYPE, Kss16Family::pair_with_output::<FE>),
+        (Kss18Family::CURVE_TYPE, Kss18Family::pair_with_output::<FE>),
+        (CocksPinchFamily::CURVE_TYPE, CocksPinchFamily::pair_with_output::<FE>),
+    ];
+}
+
+/// Restriction on the coefficients A and B of G1 that a family puts on the encoding,
+/// with the name of the family for the error
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum G1Coefficients {
+    Any,
+    ZeroA(&'static str),
+    ZeroB(&'static str)
+}
+
+/// A and B of G1, the length of the group order encoding, the group order and the rest of the input
+type G1Parameters<'a, FE, F> = (Fp<'a, FE, F>, Fp<'a, FE, F>, usize, MaxGroupSizeUint, &'a [u8]);
+
+/// Parsing shared by all the families: the base field is followed by A and B of G1 and the group order
+fn parse_g1_parameters<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>
+    >(
+        bytes: &'a [u8],
+        modulus_len: usize,
+        base_field: &'a F,
+        coefficients: G1Coefficients,
+        ctx: &ExecutionContext
+    ) -> Result<G1Parameters<'a, FE, F>, ApiError>
+{
+    let (a_fp, b_fp, rest) = parse_ab_in_base_field_from_encoding(bytes, modulus_len, base_field)?;
+    match coefficients {
+        G1Coefficients::ZeroA(family) if !a_fp.is_zero() => {
+            return Err(ApiError::UnknownParameter(format!("A parameter must be zero for {} curve", family)));
+        },
+        G1Coefficients::ZeroB(family) if !b_fp.is_zero() => {
+            return Err(ApiError::UnknownParameter(format!("B parameter must be zero for {} curve", family)));
+        },
+        _ => {}
     }
+    let (order_len, order, rest) = parse_group_order_from_encoding(rest, ctx)?;
+
+    Ok((a_fp, b_fp, order_len, order, rest))
 }
 
 impl<FE: ElementRepr>PairingApiImplementation<FE> {
-    pub(crate) fn pair_bls12(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+    fn pair_with_output(bytes: &[u8], output: PairingOutput, ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        let (curve_type, rest) = split(bytes, CURVE_TYPE_LENGTH, "Input should be longer than curve type encoding")?;
+
+        let (_, pair_with_output) = PairingFamilies::<FE>::ALL.iter()
+            .find(|(family_curve_type, _)| *family_curve_type == curve_type[0])
+            .ok_or(ApiError::InputError("Unknown curve type".to_owned()))?;
+
+        pair_with_output(rest, output, ctx)
+    }
+
+    pub(crate) fn pair_bls12(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        Bls12Family::pair_with_output::<FE>(bytes, PairingOutput::IsOne, ctx)
+    }
+
+    pub(crate) fn pair_bn(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        BnFamily::pair_with_output::<FE>(bytes, PairingOutput::IsOne, ctx)
+    }
+
+    pub(crate) fn pair_mnt6(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        Mnt6Family::pair_with_output::<FE>(bytes, PairingOutput::IsOne, ctx)
+    }
+
+    pub(crate) fn pair_mnt4(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        Mnt4Family::pair_with_output::<FE>(bytes, PairingOutput::IsOne, ctx)
+    }
+
+    pub(crate) fn pair_bls24(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        Bls24Family::pair_with_output::<FE>(bytes, PairingOutput::IsOne, ctx)
+    }
+
+    pub(crate) fn pair_bls48(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        Bls48Family::pair_with_output::<FE>(bytes, PairingOutput::IsOne, ctx)
+    }
+
+    pub(crate) fn pair_bw6(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        Bw6Family::pair_with_output::<FE>(bytes, PairingOutput::IsOne, ctx)
+    }
+
+    pub(crate) fn pair_kss16(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        Kss16Family::pair_with_output::<FE>(bytes, PairingOutput::IsOne, ctx)
+    }
+
+    pub(crate) fn pair_kss18(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        Kss18Family::pair_with_output::<FE>(bytes, PairingOutput::IsOne, ctx)
+    }
+
+    pub(crate) fn pair_cocks_pinch(bytes: &[u8], ctx: &ExecutionContext) -> Result<Vec<u8>, ApiError> {
+        CocksPinchFamily::pair_with_output::<FE>(bytes, PairingOutput::IsOne, ctx)
+    }
+}
+
+pub(crate) struct Bls12Family;
+
+impl PairingFamily for Bls12Family {
+    const CURVE_TYPE: u8 = BLS12;
+
+    fn parse<FE: ElementRepr, S: PairingStages>(bytes: &[u8], ctx: &ExecutionContext, stages: S) -> Result<Vec<u8>, ApiError> {
         use crate::extension_towers::fp2::{Fp2, Extension2};
         use crate::extension_towers::fp6_as_3_over_2::{Fp6, Extension3Over2};
         use crate::extension_towers::fp12_as_2_over3_over_2::{Fp12, Extension2Over3Over2};
 
-        let (base_field, modulus_len, modulus, rest) = parse_base_field_from_encoding::<FE>(&bytes)?;
-        let (a_fp, b_fp, rest) = parse_ab_in_base_field_from_encoding(&rest, modulus_len, &base_field)?;
-        if !a_fp.is_zero() {
-            return Err(ApiError::UnknownParameter("A parameter must be zero for BLS12 curve".to_owned()));
-        }
-        let (_order_len, order, rest) = parse_group_order_from_encoding(rest)?;
+        let (base_field, modulus_len, modulus, rest) = parse_base_field_from_encoding::<FE>(&bytes)?;
+        let (a_fp, b_fp, order_len, order, rest) = parse_g1_parameters(rest, modulus_len, &base_field, G1Coefficients::ZeroA("BLS12"), ctx)?;
+        let fp_params = CurveOverFpParameters::new(&base_field);
+        let g1_curve = WeierstrassCurve::new(&order.as_ref(), a_fp, b_fp.clone(), &fp_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+
+        // Now we need to expect:
+        // - non-residue for Fp2
+        // - non-residue for Fp6
+        // - twist type M/D
+        // - parameter X
+        // - sign of X
+        // - number of pairs
+        // - list of encoded pairs
+        // - optional pairing mode
+
+        let (fp_non_residue, rest) = decode_fp(&rest, modulus_len, &base_field)?;
+
+        {
+            if fp_non_residue.is_zero() {
+                return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp2 is zero file {}, line {}", file!(), line!())));
+            }
+            let is_not_a_square = is_non_nth_root(&fp_non_residue, &modulus, 2u64);
+            if !is_not_a_square {
+                if !crate::features::in_fuzzing_or_gas_metering() {
+                    return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp2 is actually a residue file {}, line {}", file!(), line!())));
+                }
+            }
+        }
+
+        // build an extension field
+        let mut extension_2 = Extension2::new(fp_non_residue);
+        extension_2.calculate_frobenius_coeffs(&modulus).map_err(|_| {
+            ApiError::InputError("Failed to calculate Frobenius coeffs for Fp2".to_owned())
+        })?;
+
+        let (fp2_non_residue, rest) = decode_fp2(&rest, modulus_len, &extension_2)?;
+
+        {
+            if fp2_non_residue.is_zero() {
+                return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp6(12) is zero, file {}, line {}", file!(), line!())));
+            }
+            let is_not_a_6th_root = is_non_nth_root_fp2(&fp2_non_residue, &modulus, 6u64);
+            if !is_not_a_6th_root {
+                if !crate::features::in_fuzzing_or_gas_metering() {
+                    return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp6(12) is actually a residue, file {}, line {}", file!(), line!())));
+                }
+            }
+            // Fp12 is built as a quadratic extension of Fp6, so the non-residue must also be a non-square in Fp2
+            if fp2::is_quadratic_residue(&fp2_non_residue) && !crate::features::in_fuzzing_or_gas_metering() {
+                return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp6(12) is a square in Fp2, file {}, line {}", file!(), line!())));
+            }
+        }
+
+        let (twist_type, rest) = decode_twist_type_or_detect(rest)?;
+
+        let base_precomp = Fp6Fp12FrobeniusBaseElements::construct(
+            &modulus, 
+            &fp2_non_residue
+        ).map_err(|_| {
+            ApiError::UnknownParameter("Can not make base precomputations for Fp6/Fp12 frobenius".to_owned())
+        })?;
+
+        let mut extension_6 = Extension3Over2::new(fp2_non_residue.clone());
+        {
+            extension_6.calculate_frobenius_coeffs_with_precomp(&base_precomp).map_err(|_| {
+                ApiError::UnknownParameter("Can not calculate Frobenius coefficients for Fp6".to_owned())
+            })?;
+        }
+
+        let mut extension_12 = Extension2Over3Over2::new(Fp6::zero(&extension_6));
+        {
+            extension_12.calculate_frobenius_coeffs_with_precomp(&base_precomp).map_err(|_| {
+                ApiError::InputError("Can not calculate Frobenius coefficients for Fp12".to_owned())
+            })?;
+        }
+
+        let (x, rest) = decode_loop_parameter_scalar_with_bit_limit(rest, ctx.limits().max_bls12_x_bit_length)?;
+        if x.is_zero() {
+            return Err(ApiError::InputError("Loop count parameters can not be zero".to_owned()));
+        }
+
+        if calculate_hamming_weight(x.as_ref()) > ctx.limits().max_bls12_x_hamming {
+            return Err(ApiError::InputError("X has too large hamming weight".to_owned()));
+        }
+
+        let (x_is_negative, rest) = decode_sign_is_negative(rest)?;
+
+        // the pairs follow, the twist type may be detected from them
+        let twist_type = match twist_type {
+            Some(twist_type) => twist_type,
+            None => detect_twist_type(rest, modulus_len, &extension_2, &b_fp, &fp2_non_residue, stages.output(), ctx.point_encoding())?
+        };
+        let b_fp2 = twist_b(&b_fp, &fp2_non_residue, twist_type)?;
+        let a_fp2 = Fp2::zero(&extension_2);
+
+        let fp2_params = CurveOverFp2Parameters::new(&extension_2);
+        let g2_curve = WeierstrassCurve::new(order.as_ref(), a_fp2, b_fp2, &fp2_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        let one = Fp12::one(&extension_12);
+        let serialize = |el: &Fp12<FE, PrimeField<FE>>| serialize_fp12_fixed_len(modulus_len, el);
+
+        let engine_params = Bls12InstanceParams {
+            x: &x.as_ref(),
+            x_is_negative: x_is_negative,
+            twist_type: twist_type,
+            base_field: &base_field,
+            curve: &g1_curve,
+            curve_twist: &g2_curve,
+            fp2_extension: &extension_2,
+            fp6_extension: &extension_6,
+            fp12_extension: &extension_12,
+            force_no_naf: true
+        };
+
+        let engine = Bls12Instance::from_params(engine_params);
+
+        stages.run(ParsedPairing {
+            engine: &engine,
+            one,
+            rest,
+            prepare: |rest, output| decode_pairs_with_fp2_twist(rest, modulus_len, order_len, &g1_curve, &g2_curve, SubgroupChecks::new(|p| engine.g1_subgroup_check(p), |q| q.check_correct_subgroup()), output, ctx.point_encoding()),
+            decode_gt: |rest| decode_fp12(rest, modulus_len, &extension_12),
+            serialize
+        })
+    }
+
+}
+
+pub(crate) struct Bls24Family;
+
+impl PairingFamily for Bls24Family {
+    const CURVE_TYPE: u8 = BLS24;
+
+    fn parse<FE: ElementRepr, S: PairingStages>(bytes: &[u8], ctx: &ExecutionContext, stages: S) -> Result<Vec<u8>, ApiError> {
+        use crate::extension_towers::fp2::{Fp2, Extension2};
+        use crate::extension_towers::fp4_as_2_over_2::{Fp4, Extension2Over2};
+        use crate::extension_towers::fp12_as_3_over_4::{Fp12, Extension3Over4};
+        use crate::extension_towers::fp24_as_2_over_3_over_4::{Fp24, Extension2Over3Over4};
+
+        let (base_field, modulus_len, modulus, rest) = parse_base_field_from_encoding::<FE>(bytes)?;
+        let (a_fp, b_fp, order_len, order, rest) = parse_g1_parameters(rest, modulus_len, &base_field, G1Coefficients::ZeroA("BLS24"), ctx)?;
+        let fp_params = CurveOverFpParameters::new(&base_field);
+        let g1_curve = WeierstrassCurve::new(order.as_ref(), a_fp, b_fp, &fp_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        // Now we need to expect:
+        // - non-residue for Fp2, Fp4 is built as Fp2[v]/(v^2 - u)
+        // - non-residue for Fp12(24) in Fp4
+        // - twist type M/D
+        // - parameter X
+        // - sign of X
+        // - number of pairs
+        // - list of encoded pairs
+        // - optional pairing mode
+
+        let (fp_non_residue, rest) = decode_fp(rest, modulus_len, &base_field)?;
+
+        {
+            if fp_non_residue.is_zero() {
+                return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp2 is zero file {}, line {}", file!(), line!())));
+            }
+            // Fp4 is Fp[v]/(v^4 - non-residue), so it must be a non-square
+            let is_not_a_square = is_non_nth_root(&fp_non_residue, &modulus, 2u64);
+            if !is_not_a_square && !crate::features::in_fuzzing_or_gas_metering() {
+                return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp2 is actually a residue file {}, line {}", file!(), line!())));
+            }
+        }
+
+        // Fp4 as 2 over 2 requires modulus = 1 mod 4
+        let base_precomp = Fp2Fp4FrobeniusBaseElements::construct(
+            &modulus, &fp_non_residue
+        ).map_err(|_| {
+            ApiError::UnknownParameter("Can not make base precomputations for Fp2/Fp4 frobenius".to_owned())
+        })?;
+
+        let mut extension_2 = Extension2::new(fp_non_residue);
+        extension_2.calculate_frobenius_coeffs_with_precomp(&base_precomp).map_err(|_| {
+            ApiError::InputError("Failed to calculate Frobenius coeffs for Fp2".to_owned())
+        })?;
+
+        let mut extension_4 = Extension2Over2::new(Fp2::zero(&extension_2));
+        extension_4.calculate_frobenius_coeffs_with_precomp(&base_precomp).map_err(|_| {
+            ApiError::UnknownParameter("Can not calculate Frobenius coefficients for Fp4".to_owned())
+        })?;
+
+        let (fp4_non_residue, rest) = decode_fp4(rest, modulus_len, &extension_4)?;
+
+        {
+            if fp4_non_residue.is_zero() {
+                return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp12(24) is zero, file {}, line {}", file!(), line!())));
+            }
+            // Fp24 is Fp4[w]/(w^6 - non-residue), so it must be neither a square nor a cube in Fp4
+            let is_not_a_square = is_non_nth_root_fp4(&fp4_non_residue, &modulus, 2u64);
+            let is_not_a_cube = is_non_nth_root_fp4(&fp4_non_residue, &modulus, 3u64);
+            if (!is_not_a_square || !is_not_a_cube) && !crate::features::in_fuzzing_or_gas_metering() {
+                return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp12(24) is actually a residue, file {}, line {}", file!(), line!())));
+            }
+        }
+
+        let (twist_type, rest) = decode_twist_type(rest)?;
+
+        let base_precomp = Fp12Fp24FrobeniusBaseElements::construct(
+            &modulus, 
+            &fp4_non_residue
+        ).map_err(|_| {
+            ApiError::UnknownParameter("Can not make base precomputations for Fp12/Fp24 frobenius".to_owned())
+        })?;
+
+        let mut extension_12 = Extension3Over4::new(fp4_non_residue);
+        {
+            extension_12.calculate_frobenius_coeffs_with_precomp(&base_precomp).map_err(|_| {
+                ApiError::UnknownParameter("Can not calculate Frobenius coefficients for Fp12".to_owned())
+            })?;
+        }
+
+        let mut extension_24 = Extension2Over3Over4::new(Fp12::zero(&extension_12));
+        {
+            extension_24.calculate_frobenius_coeffs_with_precomp(&base_precomp).map_err(|_| {
+                ApiError::InputError("Can not calculate Frobenius coefficients for Fp24".to_owned())
+            })?;
+        }
+
+        let fp4_non_residue_inv = fp4_non_residue.inverse().ok_or(ApiError::UnexpectedZero("Fp4 non-residue must be invertible".to_owned()))?;
+        let b_fp4 = match twist_type {
+            TwistType::D => {
+                let mut b_fp4 = fp4_non_residue_inv;
+                b_fp4.mul_by_fp(&b_fp);
+
+                b_fp4
+            },
+            TwistType::M => {
+                let mut b_fp4 = fp4_non_residue;
+                b_fp4.mul_by_fp(&b_fp);
+
+                b_fp4
+            },
+        };
+
+        let a_fp4 = Fp4::zero(&extension_4);
+
+        let fp4_params = CurveOverFp4Parameters::new(&extension_4);
+        let g2_curve = WeierstrassCurve::new(order.as_ref(), a_fp4, b_fp4, &fp4_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        let (x, rest) = decode_loop_parameter_scalar_with_bit_limit(rest, ctx.limits().max_bls24_x_bit_length)?;
+        if x.is_zero() {
+            return Err(ApiError::InputError("Loop count parameters can not be zero".to_owned()));
+        }
+
+        if calculate_hamming_weight(x.as_ref()) > ctx.limits().max_bls24_x_hamming {
+            return Err(ApiError::InputError("X has too large hamming weight".to_owned()));
+        }
+
+        let (x_is_negative, rest) = decode_sign_is_negative(rest)?;
+
+        let one = Fp24::one(&extension_24);
+        let serialize = |el: &Fp24<FE, PrimeField<FE>>| serialize_fp24_fixed_len(modulus_len, el);
+
+        let engine_params = Bls24InstanceParams {
+            x: x.as_ref(),
+            x_is_negative,
+            twist_type,
+            base_field: &base_field,
+            curve: &g1_curve,
+            curve_twist: &g2_curve,
+            fp2_extension: &extension_2,
+            fp4_extension: &extension_4,
+            fp12_extension: &extension_12,
+            fp24_extension: &extension_24,
+            force_no_naf: true
+        };
+
+        let engine = Bls24Instance::from_params(engine_params);
+
+        stages.run(ParsedPairing {
+            engine: &engine,
+            one,
+            rest,
+            prepare: |rest, output| decode_pairs(rest, modulus_len, order_len, &g1_curve, |rest| decode_g2_point_from_xy_in_fp4(rest, modulus_len, &g2_curve, ctx.point_encoding()), output, ctx.point_encoding()),
+            decode_gt: |rest| decode_fp24(rest, modulus_len, &extension_24),
+            serialize
+        })
+    }
+
+}
+
+pub(crate) struct Bls48Family;
+
+impl PairingFamily for Bls48Family {
+    const CURVE_TYPE: u8 = BLS48;
+
+    fn parse<FE: ElementRepr, S: PairingStages>(bytes: &[u8], ctx: &ExecutionContext, stages: S) -> Result<Vec<u8>, ApiError> {
+        use crate::extension_towers::fp2::{Fp2, Extension2};
+        use crate::extension_towers::fp4_as_2_over_2::{Fp4, Extension2Over2};
+        use crate::extension_towers::fp8_as_2_over_4::{Fp8, Extension2Over4};
+        use crate::extension_towers::fp24_as_3_over_8::{Fp24, Extension3Over8};
+        use crate::extension_towers::fp48_as_2_over_3_over_8::{Fp48, Extension2Over3Over8};
+
+        let (base_field, modulus_len, modulus, rest) = parse_base_field_from_encoding::<FE>(bytes)?;
+        let (a_fp, b_fp, order_len, order, rest) = parse_g1_parameters(rest, modulus_len, &base_field, G1Coefficients::ZeroA("BLS48"), ctx)?;
+        let fp_params = CurveOverFpParameters::new(&base_field);
+        let g1_curve = WeierstrassCurve::new(order.as_ref(), a_fp, b_fp, &fp_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        // Now we need to expect:
+        // - non-residue for Fp2, Fp4 is built as Fp2[v]/(v^2 - u) and Fp8 as Fp4[z]/(z^2 - v)
+        // - non-residue for Fp24(48) in Fp8
+        // - twist type M/D
+        // - parameter X
+        // - sign of X
+        // - number of pairs
+        // - list of encoded pairs
+        // - optional pairing mode
+
+        let (fp_non_residue, rest) = decode_fp(rest, modulus_len, &base_field)?;
+
+        {
+            if fp_non_residue.is_zero() {
+                return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp2 is zero file {}, line {}", file!(), line!())));
+            }
+            // Fp8 is Fp[z]/(z^8 - non-residue), so it must be a non-square
+            let is_not_a_square = is_non_nth_root(&fp_non_residue, &modulus, 2u64);
+            if !is_not_a_square && !crate::features::in_fuzzing_or_gas_metering() {
+                return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp2 is actually a residue file {}, line {}", file!(), line!())));
+            }
+        }
+
+        // Fp4 as 2 over 2 requires modulus = 1 mod 4
+        let base_precomp = Fp2Fp4FrobeniusBaseElements::construct(
+            &modulus, &fp_non_residue
+        ).map_err(|_| {
+            ApiError::UnknownParameter("Can not make base precomputations for Fp2/Fp4 frobenius".to_owned())
+        })?;
+
+        let mut extension_2 = Extension2::new(fp_non_residue);
+        extension_2.calculate_frobenius_coeffs_with_precomp(&base_precomp).map_err(|_| {
+            ApiError::InputError("Failed to calculate Frobenius coeffs for Fp2".to_owned())
+        })?;
+
+        let mut extension_4 = Extension2Over2::new(Fp2::zero(&extension_2));
+        extension_4.calculate_frobenius_coeffs_with_precomp(&base_precomp).map_err(|_| {
+            ApiError::UnknownParameter("Can not calculate Frobenius coefficients for Fp4".to_owned())
+        })?;
+
+        // Fp8 as 2 over 4 requires modulus = 1 mod 8
+        let fp8_precomp = Fp4Fp8FrobeniusBaseElements::construct(
+            &modulus, &fp_non_residue
+        ).map_err(|_| {
+            ApiError::UnknownParameter("Can not make base precomputations for Fp8 frobenius".to_owned())
+        })?;
+
+        let mut extension_8 = Extension2Over4::new(Fp4::zero(&extension_4));
+        extension_8.calculate_frobenius_coeffs_with_precomp(&fp8_precomp).map_err(|_| {
+            ApiError::UnknownParameter("Can not calculate Frobenius coefficients for Fp8".to_owned())
+        })?;
+
+        let (fp8_non_residue, rest) = decode_fp8(rest, modulus_len, &extension_8)?;
+
+        {
+            if fp8_non_residue.is_zero() {
+                return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp24(48) is zero, file {}, line {}", file!(), line!())));
+            }
+            // Fp48 is Fp8[w]/(w^6 - non-residue), so it must be neither a square nor a cube in Fp8
+            let is_not_a_square = is_non_nth_root_fp8(&fp8_non_residue, &modulus, 2u64);
+            let is_not_a_cube = is_non_nth_root_fp8(&fp8_non_residue, &modulus, 3u64);
+            if (!is_not_a_square || !is_not_a_cube) && !crate::features::in_fuzzing_or_gas_metering() {
+                return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp24(48) is actually a residue, file {}, line {}", file!(), line!())));
+            }
+        }
+
+        let (twist_type, rest) = decode_twist_type(rest)?;
+
+        let base_precomp = Fp24Fp48FrobeniusBaseElements::construct(
+            &modulus, 
+            &fp8_non_residue
+        ).map_err(|_| {
+            ApiError::UnknownParameter("Can not make base precomputations for Fp24/Fp48 frobenius".to_owned())
+        })?;
+
+        let mut extension_24 = Extension3Over8::new(fp8_non_residue);
+        {
+            extension_24.calculate_frobenius_coeffs_with_precomp(&base_precomp).map_err(|_| {
+                ApiError::UnknownParameter("Can not calculate Frobenius coefficients for Fp24".to_owned())
+            })?;
+        }
+
+        let mut extension_48 = Extension2Over3Over8::new(Fp24::zero(&extension_24));
+        {
+            extension_48.calculate_frobenius_coeffs_with_precomp(&base_precomp).map_err(|_| {
+                ApiError::InputError("Can not calculate Frobenius coefficients for Fp48".to_owned())
+            })?;
+        }
+
+        let fp8_non_residue_inv = fp8_non_residue.inverse().ok_or(ApiError::UnexpectedZero("Fp8 non-residue must be invertible".to_owned()))?;
+        let b_fp8 = match twist_type {
+            TwistType::D => {
+                let mut b_fp8 = fp8_non_residue_inv;
+                b_fp8.mul_by_fp(&b_fp);
+
+                b_fp8
+            },
+            TwistType::M => {
+                let mut b_fp8 = fp8_non_residue;
+                b_fp8.mul_by_fp(&b_fp);
+
+                b_fp8
+            },
+        };
+
+        let a_fp8 = Fp8::zero(&extension_8);
+
+        let fp8_params = CurveOverFp8Parameters::new(&extension_8);
+        let g2_curve = WeierstrassCurve::new(order.as_ref(), a_fp8, b_fp8, &fp8_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        let (x, rest) = decode_loop_parameter_scalar_with_bit_limit(rest, ctx.limits().max_bls48_x_bit_length)?;
+        if x.is_zero() {
+            return Err(ApiError::InputError("Loop count parameters can not be zero".to_owned()));
+        }
+
+        if calculate_hamming_weight(x.as_ref()) > ctx.limits().max_bls48_x_hamming {
+            return Err(ApiError::InputError("X has too large hamming weight".to_owned()));
+        }
+
+        let (x_is_negative, rest) = decode_sign_is_negative(rest)?;
+
+        let one = Fp48::one(&extension_48);
+        let serialize = |el: &Fp48<FE, PrimeField<FE>>| serialize_fp48_fixed_len(modulus_len, el);
+
+        let engine_params = Bls48InstanceParams {
+            x: x.as_ref(),
+            x_is_negative,
+            twist_type,
+            base_field: &base_field,
+            curve: &g1_curve,
+            curve_twist: &g2_curve,
+            fp2_extension: &extension_2,
+            fp4_extension: &extension_4,
+            fp8_extension: &extension_8,
+            fp24_extension: &extension_24,
+            fp48_extension: &extension_48,
+            force_no_naf: true
+        };
+
+        let engine = Bls48Instance::from_params(engine_params);
+
+        stages.run(ParsedPairing {
+            engine: &engine,
+            one,
+            rest,
+            prepare: |rest, output| decode_pairs(rest, modulus_len, order_len, &g1_curve, |rest| decode_g2_point_from_xy_in_fp8(rest, modulus_len, &g2_curve, ctx.point_encoding()), output, ctx.point_encoding()),
+            decode_gt: |rest| decode_fp48(rest, modulus_len, &extension_48),
+            serialize
+        })
+    }
+
+}
+
+pub(crate) struct Bw6Family;
+
+impl PairingFamily for Bw6Family {
+    const CURVE_TYPE: u8 = BW6;
+
+    fn parse<FE: ElementRepr, S: PairingStages>(bytes: &[u8], ctx: &ExecutionContext, stages: S) -> Result<Vec<u8>, ApiError> {
+        use crate::extension_towers::fp3::{Fp3, Extension3};
+        use crate::extension_towers::fp6_as_2_over_3::{Fp6, Extension2Over3};
+
+        let (base_field, modulus_len, modulus, rest) = parse_base_field_from_encoding::<FE>(bytes)?;
+        let (a_fp, b_fp, order_len, order, rest) = parse_g1_parameters(rest, modulus_len, &base_field, G1Coefficients::ZeroA("BW6"), ctx)?;
+        let fp_params = CurveOverFpParameters::new(&base_field);
+        let g1_curve = WeierstrassCurve::new(order.as_ref(), a_fp, b_fp, &fp_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        // Now we need to expect:
+        // - non-residue for Fp3, Fp6 is built as Fp3[v]/(v^2 - u)
+        // - twist type M/D
+        // - first ate loop count
+        // - sign of the first ate loop count
+        // - second ate loop count
+        // - sign of the second ate loop count
+        // - number of pairs
+        // - list of encoded pairs, G2 points are over the base field
+        // - optional pairing mode
+
+        let (fp_non_residue, rest) = decode_fp(rest, modulus_len, &base_field)?;
+
+        {
+            if fp_non_residue.is_zero() {
+                return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp3 is zero file {}, line {}", file!(), line!())));
+            }
+            // Fp6 is Fp[v]/(v^6 - non-residue)
+            let is_not_a_root = is_non_nth_root(&fp_non_residue, &modulus, 6u64);
+            if !is_not_a_root && !crate::features::in_fuzzing_or_gas_metering() {
+                return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp3 is actually a residue, file {}, line {}", file!(), line!())));
+            }
+        }
+
+        let (twist_type, rest) = decode_twist_type(rest)?;
+
+        let base_precomp = Fp3Fp6FrobeniusBaseElements::construct(
+            &modulus, &fp_non_residue
+        ).map_err(|_| {
+            ApiError::UnknownParameter("Can not make base precomputations for Fp3/Fp6 frobenius".to_owned())
+        })?;
+
+        let mut extension_3 = Extension3::new(fp_non_residue);
+        extension_3.calculate_frobenius_coeffs_with_precomp(&base_precomp).map_err(|_| {
+            ApiError::InputError("Failed to calculate Frobenius coeffs for Fp3".to_owned())
+        })?;
+
+        let mut extension_6 = Extension2Over3::new(Fp3::zero(&extension_3));
+        {
+            extension_6.calculate_frobenius_coeffs_with_precomp(&base_precomp).map_err(|_| {
+                ApiError::UnknownParameter("Can not calculate Frobenius coefficients for Fp6".to_owned())
+            })?;
+        }
+
+        // the twist is a curve over the base field itself
+        let fp_non_residue_inv = fp_non_residue.inverse().ok_or(ApiError::UnexpectedZero("Fp non-residue must be invertible".to_owned()))?;
+        let b_twist = match twist_type {
+            TwistType::D => {
+                let mut b_twist = fp_non_residue_inv;
+                b_twist.mul_assign(&b_fp);
+
+                b_twist
+            },
+            TwistType::M => {
+                let mut b_twist = fp_non_residue;
+                b_twist.mul_assign(&b_fp);
+
+                b_twist
+            },
+        };
+
+        let g2_curve = WeierstrassCurve::new(order.as_ref(), a_fp, b_twist, &fp_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        let (ate_loop_count_1, rest) = decode_loop_parameter_scalar_with_bit_limit(rest, ctx.limits().max_ate_pairing_ate_loop_count)?;
+        if ate_loop_count_1.is_zero() {
+            return Err(ApiError::InputError("Ate loop count parameters can not be zero".to_owned()));
+        }
+
+        if calculate_hamming_weight(ate_loop_count_1.as_ref()) > ctx.limits().max_ate_pairing_ate_loop_count_hamming {
+            return Err(ApiError::InputError("Ate loop count has too large hamming weight".to_owned()));
+        }
+
+        let (ate_loop_count_1_is_negative, rest) = decode_sign_is_negative(rest)?;
+
+        let (ate_loop_count_2, rest) = decode_loop_parameter_scalar_with_bit_limit(rest, ctx.limits().max_ate_pairing_ate_loop_count)?;
+        if ate_loop_count_2.is_zero() {
+            return Err(ApiError::InputError("Ate loop count parameters can not be zero".to_owned()));
+        }
+
+        if calculate_hamming_weight(ate_loop_count_2.as_ref()) > ctx.limits().max_ate_pairing_ate_loop_count_hamming {
+            return Err(ApiError::InputError("Ate loop count has too large hamming weight".to_owned()));
+        }
+
+        let (ate_loop_count_2_is_negative, rest) = decode_sign_is_negative(rest)?;
+
+        // The group order must divide p^2 - p + 1 (embedding degree 6) and
+        // ate_loop_count_1 + ate_loop_count_2 * p, otherwise the result is not a pairing
+        let modulus = MaxFieldSquaredUint::from(modulus.as_ref());
+        let order = MaxFieldSquaredUint::from(order.as_ref());
+        let final_exp_hard_part = {
+            let mut power = modulus.adaptive_multiplication(modulus);
+            power -= modulus;
+            power += MaxFieldSquaredUint::from(1u64);
+            let (hard_part, rem) = power.div_mod(order);
+            if !rem.is_zero() && !crate::features::in_fuzzing_or_gas_metering() {
+                return Err(ApiError::InputError("Group order does not divide p^2 - p + 1".to_owned()));
+            }
+
+            hard_part
+        };
+
+        {
+            let signed_mod_order = |scalar: &[u64], is_negative: bool| {
+                let value = MaxFieldSquaredUint::from(scalar) % order;
+                if is_negative && !value.is_zero() {
+                    order - value
+                } else {
+                    value
+                }
+            };
+
+            let c_1 = signed_mod_order(ate_loop_count_1.as_ref(), ate_loop_count_1_is_negative);
+            let c_2 = signed_mod_order(ate_loop_count_2.as_ref(), ate_loop_count_2_is_negative);
+            let c_2_by_p = c_2.adaptive_multiplication(modulus % order) % order;
+            if !((c_1 + c_2_by_p) % order).is_zero() && !crate::features::in_fuzzing_or_gas_metering() {
+                return Err(ApiError::InputError("Group order does not divide ate loop count combination".to_owned()));
+            }
+        }
+
+        let one = Fp6::one(&extension_6);
+        let serialize = |el: &Fp6<FE, PrimeField<FE>>| serialize_fp6_as_2_over_3_fixed_len(modulus_len, el);
+
+        let engine_params = Bw6InstanceParams {
+            ate_loop_count_1: ate_loop_count_1.as_ref(),
+            ate_loop_count_1_is_negative,
+            ate_loop_count_2: ate_loop_count_2.as_ref(),
+            ate_loop_count_2_is_negative,
+            final_exp_hard_part: final_exp_hard_part.as_ref(),
+            twist_type,
+            base_field: &base_field,
+            curve: &g1_curve,
+            curve_twist: &g2_curve,
+            fp3_extension: &extension_3,
+            fp6_extension: &extension_6,
+            force_no_naf: false
+        };
+
+        let engine = Bw6Instance::from_params(engine_params);
+
+        stages.run(ParsedPairing {
+            engine: &engine,
+            one,
+            rest,
+            prepare: |rest, output| decode_pairs(rest, modulus_len, order_len, &g1_curve, |rest| decode_g2_point_from_xy_in_fp(rest, modulus_len, &g2_curve, ctx.point_encoding()), output, ctx.point_encoding()),
+            decode_gt: |rest| decode_fp6_as_2_over_3(rest, modulus_len, &extension_6),
+            serialize
+        })
+    }
+
+}
+
+pub(crate) struct Kss16Family;
+
+impl PairingFamily for Kss16Family {
+    const CURVE_TYPE: u8 = KSS16;
+
+    fn parse<FE: ElementRepr, S: PairingStages>(bytes: &[u8], ctx: &ExecutionContext, stages: S) -> Result<Vec<u8>, ApiError> {
+        use crate::extension_towers::fp2::{Fp2, Extension2};
+        use crate::extension_towers::fp4_as_2_over_2::{Extension2Over2};
+        use crate::extension_towers::fp8_as_2_over_4_generic::{Fp8, Extension2Over4};
+        use crate::extension_towers::fp16_as_2_over_8::{Fp16, Extension2Over8};
+
+        let (base_field, modulus_len, modulus, rest) = parse_base_field_from_encoding::<FE>(bytes)?;
+        let (a_fp, _, order_len, order, rest) = parse_g1_parameters(rest, modulus_len, &base_field, G1Coefficients::ZeroB("KSS16"), ctx)?;
+        let fp_params = CurveOverFpParameters::new(&base_field);
+        let g1_curve = WeierstrassCurve::new_with_zero_b(order.as_ref(), a_fp, &fp_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        // Now we need to expect:
+        // - non-residue for Fp2, Fp4 is built as Fp2[v]/(v^2 - u)
+        // - non-residue for Fp8(16) in Fp4
+        // - twist type M/D
+        // - parameter X
+        // - sign of X
+        // - number of pairs
+        // - list of encoded pairs
+        // - optional pairing mode
+
+        let (fp_non_residue, rest) = decode_fp(rest, modulus_len, &base_field)?;
+
+        {
+            if fp_non_residue.is_zero() {
+                return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp2 is zero file {}, line {}", file!(), line!())));
+            }
+            // Fp4 is Fp[v]/(v^4 - non-residue), so it must be a non-square
+            let is_not_a_square = is_non_nth_root(&fp_non_residue, &modulus, 2u64);
+            if !is_not_a_square && !crate::features::in_fuzzing_or_gas_metering() {
+                return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp2 is actually a residue file {}, line {}", file!(), line!())));
+            }
+        }
+
+        // Fp4 as 2 over 2 requires modulus = 1 mod 4
+        let base_precomp = Fp2Fp4FrobeniusBaseElements::construct(
+            &modulus, &fp_non_residue
+        ).map_err(|_| {
+            ApiError::UnknownParameter("Can not make base precomputations for Fp2/Fp4 frobenius".to_owned())
+        })?;
+
+        let mut extension_2 = Extension2::new(fp_non_residue);
+        extension_2.calculate_frobenius_coeffs_with_precomp(&base_precomp).map_err(|_| {
+            ApiError::InputError("Failed to calculate Frobenius coeffs for Fp2".to_owned())
+        })?;
+
+        let mut extension_4 = Extension2Over2::new(Fp2::zero(&extension_2));
+        extension_4.calculate_frobenius_coeffs_with_precomp(&base_precomp).map_err(|_| {
+            ApiError::UnknownParameter("Can not calculate Frobenius coefficients for Fp4".to_owned())
+        })?;
+
+        let (fp4_non_residue, rest) = decode_fp4(rest, modulus_len, &extension_4)?;
+
+        {
+            if fp4_non_residue.is_zero() {
+                return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp8(16) is zero, file {}, line {}", file!(), line!())));
+            }
+            // Fp16 is Fp4[t]/(t^4 - non-residue), so it must be a non-square in Fp4
+            let is_not_a_square = is_non_nth_root_fp4(&fp4_non_residue, &modulus, 2u64);
+            if !is_not_a_square && !crate::features::in_fuzzing_or_gas_metering() {
+                return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp8(16) is actually a residue, file {}, line {}", file!(), line!())));
+            }
+        }
+
+        let (twist_type, rest) = decode_twist_type(rest)?;
+
+        let base_precomp = Fp8Fp16FrobeniusBaseElements::construct(
+            &modulus, 
+            &fp4_non_residue
+        ).map_err(|_| {
+            ApiError::UnknownParameter("Can not make base precomputations for Fp8/Fp16 frobenius".to_owned())
+        })?;
+
+        let mut extension_8 = Extension2Over4::new(fp4_non_residue);
+        {
+            extension_8.calculate_frobenius_coeffs_with_precomp(&base_precomp).map_err(|_| {
+                ApiError::UnknownParameter("Can not calculate Frobenius coefficients for Fp8".to_owned())
+            })?;
+        }
+
+        let mut extension_16 = Extension2Over8::new(Fp8::zero(&extension_8));
+        {
+            extension_16.calculate_frobenius_coeffs_with_precomp(&base_precomp).map_err(|_| {
+                ApiError::InputError("Can not calculate Frobenius coefficients for Fp16".to_owned())
+            })?;
+        }
+
+        // the twist is y^2 = x^3 + a' * x with a' = a / non-residue for D type and a * non-residue for M type
+        let fp4_non_residue_inv = fp4_non_residue.inverse().ok_or(ApiError::UnexpectedZero("Fp4 non-residue must be invertible".to_owned()))?;
+        let a_fp4 = match twist_type {
+            TwistType::D => {
+                let mut a_fp4 = fp4_non_residue_inv;
+                a_fp4.mul_by_fp(&a_fp);
+
+                a_fp4
+            },
+            TwistType::M => {
+                let mut a_fp4 = fp4_non_residue;
+                a_fp4.mul_by_fp(&a_fp);
+
+                a_fp4
+            },
+        };
+
+        let fp4_params = CurveOverFp4Parameters::new(&extension_4);
+        let g2_curve = WeierstrassCurve::new_with_zero_b(order.as_ref(), a_fp4, &fp4_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        let (x, rest) = decode_loop_parameter_scalar_with_bit_limit(rest, ctx.limits().max_kss16_x_bit_length)?;
+        if x.is_zero() {
+            return Err(ApiError::InputError("Loop count parameters can not be zero".to_owned()));
+        }
+
+        if calculate_hamming_weight(x.as_ref()) > ctx.limits().max_kss16_x_hamming {
+            return Err(ApiError::InputError("X has too large hamming weight".to_owned()));
+        }
+
+        let (x_is_negative, rest) = decode_sign_is_negative(rest)?;
+
+        let one = Fp16::one(&extension_16);
+        let serialize = |el: &Fp16<FE, PrimeField<FE>>| serialize_fp16_fixed_len(modulus_len, el);
+
+        let engine_params = Kss16InstanceParams {
+            x: x.as_ref(),
+            x_is_negative,
+            twist_type,
+            base_field: &base_field,
+            curve: &g1_curve,
+            curve_twist: &g2_curve,
+            fp2_extension: &extension_2,
+            fp4_extension: &extension_4,
+            fp8_extension: &extension_8,
+            fp16_extension: &extension_16,
+            force_no_naf: true
+        };
+
+        let engine = Kss16Instance::from_params(engine_params);
+
+        stages.run(ParsedPairing {
+            engine: &engine,
+            one,
+            rest,
+            prepare: |rest, output| decode_pairs(rest, modulus_len, order_len, &g1_curve, |rest| decode_g2_point_from_xy_in_fp4(rest, modulus_len, &g2_curve, ctx.point_encoding()), output, ctx.point_encoding()),
+            decode_gt: |rest| decode_fp16(rest, modulus_len, &extension_16),
+            serialize
+        })
+    }
+
+}
+
+pub(crate) struct Kss18Family;
+
+impl PairingFamily for Kss18Family {
+    const CURVE_TYPE: u8 = KSS18;
+
+    fn parse<FE: ElementRepr, S: PairingStages>(bytes: &[u8], ctx: &ExecutionContext, stages: S) -> Result<Vec<u8>, ApiError> {
+        use crate::extension_towers::fp3::{Fp3, Extension3};
+        use crate::extension_towers::fp9_as_3_over_3::{Fp9, Extension3Over3};
+        use crate::extension_towers::fp18_as_2_over_3_over_3::{Fp18, Extension2Over3Over3};
+
+        let (base_field, modulus_len, modulus, rest) = parse_base_field_from_encoding::<FE>(bytes)?;
+        let (a_fp, b_fp, order_len, order, rest) = parse_g1_parameters(rest, modulus_len, &base_field, G1Coefficients::ZeroA("KSS18"), ctx)?;
         let fp_params = CurveOverFpParameters::new(&base_field);
-        let g1_curve = WeierstrassCurve::new(&order.as_ref(), a_fp, b_fp.clone(), &fp_params).map_err(|_| {
+        let g1_curve = WeierstrassCurve::new(order.as_ref(), a_fp, b_fp, &fp_params).map_err(|_| {
             ApiError::InputError("Curve shape is not supported".to_owned())
         })?;
 
-
         // Now we need to expect:
-        // - non-residue for Fp2
-        // - non-residue for Fp6
+        // - non-residue for Fp3
+        // - non-residue for Fp9(18) in Fp3
         // - twist type M/D
         // - parameter X
         // - sign of X
         // - number of pairs
         // - list of encoded pairs
+        // - optional pairing mode
 
-        let (fp_non_residue, rest) = decode_fp(&rest, modulus_len, &base_field)?;
+        let (fp_non_residue, rest) = decode_fp(rest, modulus_len, &base_field)?;
 
         {
             if fp_non_residue.is_zero() {
-                return Err(ApiError::InputError(format!("Non-residue for Fp2 is zero file {}, line {}", file!(), line!())));
+                return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp3 is zero file {}, line {}", file!(), line!())));
             }
-            let is_not_a_square = is_non_nth_root(&fp_non_residue, &modulus, 2u64);
-            if !is_not_a_square {
-                if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError(format!("Non-residue for Fp2 is actually a residue file {}, line {}", file!(), line!())));
-                }
+            let is_not_a_cube = is_non_nth_root(&fp_non_residue, &modulus, 3u64);
+            if !is_not_a_cube && !crate::features::in_fuzzing_or_gas_metering() {
+                return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp3 is actually a residue file {}, line {}", file!(), line!())));
             }
         }
 
-        // build an extension field
-        let mut extension_2 = Extension2::new(fp_non_residue);
-        extension_2.calculate_frobenius_coeffs(&modulus).map_err(|_| {
-            ApiError::InputError("Failed to calculate Frobenius coeffs for Fp2".to_owned())
+        let base_precomp = Fp3Fp6FrobeniusBaseElements::construct(
+            &modulus, &fp_non_residue
+        ).map_err(|_| {
+            ApiError::UnknownParameter("Can not make base precomputations for Fp3 frobenius".to_owned())
         })?;
 
-        let (fp2_non_residue, rest) = decode_fp2(&rest, modulus_len, &extension_2)?;
+        let mut extension_3 = Extension3::new(fp_non_residue);
+        extension_3.calculate_frobenius_coeffs_with_precomp(&base_precomp).map_err(|_| {
+            ApiError::InputError("Failed to calculate Frobenius coeffs for Fp3".to_owned())
+        })?;
+
+        let (fp3_non_residue, rest) = decode_fp3(rest, modulus_len, &extension_3)?;
 
         {
-            if fp2_non_residue.is_zero() {
-                return Err(ApiError::InputError(format!("Non-residue for Fp6(12) is zero, file {}, line {}", file!(), line!())));
+            if fp3_non_residue.is_zero() {
+                return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp9(18) is zero, file {}, line {}", file!(), line!())));
             }
-            let is_not_a_6th_root = is_non_nth_root_fp2(&fp2_non_residue, &modulus, 6u64);
-            if !is_not_a_6th_root {
-                if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError(format!("Non-residue for Fp6(12) is actually a residue, file {}, line {}", file!(), line!())));
-                }
+            // Fp18 is Fp3[w]/(w^6 - non-residue), so it must be neither a square nor a cube in Fp3
+            let is_not_a_square = is_non_nth_root_fp3(&fp3_non_residue, &modulus, 2u64);
+            let is_not_a_cube = is_non_nth_root_fp3(&fp3_non_residue, &modulus, 3u64);
+            if (!is_not_a_square || !is_not_a_cube) && !crate::features::in_fuzzing_or_gas_metering() {
+                return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp9(18) is actually a residue, file {}, line {}", file!(), line!())));
             }
         }
 
         let (twist_type, rest) = decode_twist_type(rest)?;
 
-        let base_precomp = Fp6Fp12FrobeniusBaseElements::construct(
+        let base_precomp = Fp9Fp18FrobeniusBaseElements::construct(
             &modulus, 
-            &fp2_non_residue
+            &fp3_non_residue
         ).map_err(|_| {
-            ApiError::UnknownParameter("Can not make base precomputations for Fp6/Fp12 frobenius".to_owned())
+            ApiError::UnknownParameter("Can not make base precomputations for Fp9/Fp18 frobenius".to_owned())
         })?;
 
-        let mut extension_6 = Extension3Over2::new(fp2_non_residue.clone());
+        let mut extension_9 = Extension3Over3::new(fp3_non_residue);
         {
-            extension_6.calculate_frobenius_coeffs_with_precomp(&base_precomp).map_err(|_| {
-                ApiError::UnknownParameter("Can not calculate Frobenius coefficients for Fp6".to_owned())
+            extension_9.calculate_frobenius_coeffs_with_precomp(&base_precomp).map_err(|_| {
+                ApiError::UnknownParameter("Can not calculate Frobenius coefficients for Fp9".to_owned())
             })?;
         }
 
-        let mut extension_12 = Extension2Over3Over2::new(Fp6::zero(&extension_6));
+        let mut extension_18 = Extension2Over3Over3::new(Fp9::zero(&extension_9));
         {
-            extension_12.calculate_frobenius_coeffs_with_precomp(&base_precomp).map_err(|_| {
-                ApiError::InputError("Can not calculate Frobenius coefficients for Fp12".to_owned())
+            extension_18.calculate_frobenius_coeffs_with_precomp(&base_precomp).map_err(|_| {
+                ApiError::InputError("Can not calculate Frobenius coefficients for Fp18".to_owned())
             })?;
         }
 
-        let fp2_non_residue_inv = fp2_non_residue.inverse().ok_or(ApiError::UnexpectedZero("Fp2 non-residue must be invertible".to_owned()))?;
-        let b_fp2 = match twist_type {
+        let fp3_non_residue_inv = fp3_non_residue.inverse().ok_or(ApiError::UnexpectedZero("Fp3 non-residue must be invertible".to_owned()))?;
+        let b_fp3 = match twist_type {
             TwistType::D => {
-                let mut b_fp2 = fp2_non_residue_inv.clone();
-                b_fp2.mul_by_fp(&b_fp);
+                let mut b_fp3 = fp3_non_residue_inv;
+                b_fp3.mul_by_fp(&b_fp);
 
-                b_fp2
+                b_fp3
             },
             TwistType::M => {
-                let mut b_fp2 = fp2_non_residue.clone();
-                b_fp2.mul_by_fp(&b_fp);
+                let mut b_fp3 = fp3_non_residue;
+                b_fp3.mul_by_fp(&b_fp);
 
-                b_fp2
+                b_fp3
             },
         };
 
-        let a_fp2 = Fp2::zero(&extension_2);
+        let a_fp3 = Fp3::zero(&extension_3);
 
-        let fp2_params = CurveOverFp2Parameters::new(&extension_2);
-        let g2_curve = WeierstrassCurve::new(&order.as_ref(), a_fp2, b_fp2, &fp2_params).map_err(|_| {
+        let fp3_params = CurveOverFp3Parameters::new(&extension_3);
+        let g2_curve = WeierstrassCurve::new(order.as_ref(), a_fp3, b_fp3, &fp3_params).map_err(|_| {
             ApiError::InputError("Curve shape is not supported".to_owned())
         })?;
 
-        let (x, rest) = decode_loop_parameter_scalar_with_bit_limit(&rest, MAX_BLS12_X_BIT_LENGTH)?;
+        let (x, rest) = decode_loop_parameter_scalar_with_bit_limit(rest, ctx.limits().max_kss18_x_bit_length)?;
         if x.is_zero() {
             return Err(ApiError::InputError("Loop count parameters can not be zero".to_owned()));
         }
 
-        if calculate_hamming_weight(&x.as_ref()) > MAX_BLS12_X_HAMMING {
+        if calculate_hamming_weight(x.as_ref()) > ctx.limits().max_kss18_x_hamming {
             return Err(ApiError::InputError("X has too large hamming weight".to_owned()));
         }
 
         let (x_is_negative, rest) = decode_sign_is_negative(rest)?;
 
-        let (num_pairs_encoding, rest) = split(rest, BYTES_FOR_LENGTH_ENCODING, "Input is not long enough to get number of pairs")?;
-        let num_pairs = num_pairs_encoding[0] as usize;
+        let one = Fp18::one(&extension_18);
+        let serialize = |el: &Fp18<FE, PrimeField<FE>>| serialize_fp18_fixed_len(modulus_len, el);
 
-        if num_pairs == 0 {
-            if !crate::features::in_gas_metering() {
-                return Err(ApiError::InputError("Zero pairs encoded".to_owned()));
-            }
-        }
+        let engine_params = Kss18InstanceParams {
+            x: x.as_ref(),
+            x_is_negative,
+            twist_type,
+            base_field: &base_field,
+            curve: &g1_curve,
+            curve_twist: &g2_curve,
+            fp3_extension: &extension_3,
+            fp9_extension: &extension_9,
+            fp18_extension: &extension_18,
+            force_no_naf: true
+        };
+
+        let engine = Kss18Instance::from_params(engine_params);
 
-        let mut global_rest = rest;
+        stages.run(ParsedPairing {
+            engine: &engine,
+            one,
+            rest,
+            prepare: |rest, output| decode_pairs(rest, modulus_len, order_len, &g1_curve, |rest| decode_g2_point_from_xy_in_fp3(rest, modulus_len, &g2_curve, ctx.point_encoding()), output, ctx.point_encoding()),
+            decode_gt: |rest| decode_fp18(rest, modulus_len, &extension_18),
+            serialize
+        })
+    }
 
-        let mut g1_points = vec![];
-        let mut g2_points = vec![];
+}
 
-        for _ in 0..num_pairs {
-            let (check_g1_subgroup, rest) = decode_boolean(&global_rest)?;
-            let (g1, rest) = decode_g1_point_from_xy(&rest, modulus_len, &g1_curve)?;
-            let (check_g2_subgroup, rest) = decode_boolean(&rest)?;
-            let (g2, rest) = decode_g2_point_from_xy_in_fp2(&rest, modulus_len, &g2_curve)?;
-            global_rest = rest;
+pub(crate) struct CocksPinchFamily;
 
-            if !g1.is_on_curve() {
-                if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError("G1 point is not on curve".to_owned()));
-                }
-            }
+impl PairingFamily for CocksPinchFamily {
+    const CURVE_TYPE: u8 = COCKS_PINCH;
 
-            if !g2.is_on_curve() {
-                if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError("G2 point is not on curve".to_owned()));
+    fn parse<FE: ElementRepr, S: PairingStages>(bytes: &[u8], ctx: &ExecutionContext, stages: S) -> Result<Vec<u8>, ApiError> {
+        use crate::extension_towers::fpk::{FpK, ExtensionK};
+
+        let (base_field, modulus_len, modulus, rest) = parse_base_field_from_encoding::<FE>(bytes)?;
+        let (a_fp, b_fp, order_len, order, rest) = parse_g1_parameters(rest, modulus_len, &base_field, G1Coefficients::Any, ctx)?;
+        let fp_params = CurveOverFpParameters::new(&base_field);
+        let g1_curve = if b_fp.is_zero() {
+            WeierstrassCurve::new_with_zero_b(order.as_ref(), a_fp, &fp_params)
+        } else {
+            WeierstrassCurve::new(order.as_ref(), a_fp, b_fp, &fp_params)
+        }.map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        // Now we need to expect:
+        // - embedding degree k
+        // - non-residue for Fp^k = Fp[z]/(z^k - non-residue)
+        // - ate loop count
+        // - sign of the ate loop count
+        // - hard part of the final exponentiation
+        // - number of pairs
+        // - list of encoded pairs, G2 points are points of the same curve over Fp^k
+        // - optional pairing mode
+
+        let (embedding_degree, rest) = decode_embedding_degree(rest, ctx.limits().max_cocks_pinch_embedding_degree)?;
+
+        let (fp_non_residue, rest) = decode_fp(rest, modulus_len, &base_field)?;
+
+        {
+            if fp_non_residue.is_zero() {
+                return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp^k is zero file {}, line {}", file!(), line!())));
+            }
+            // z^k - non-residue is irreducible if non-residue is not an l-th power for every prime l | k
+            for l in 2..=embedding_degree {
+                let is_prime = (2..l).all(|d| l % d != 0);
+                if !is_prime || embedding_degree % l != 0 {
+                    continue;
+                }
+                let is_not_a_root = is_non_nth_root(&fp_non_residue, &modulus, l as u64);
+                if !is_not_a_root && !crate::features::in_fuzzing_or_gas_metering() {
+                    return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp^k is actually a residue file {}, line {}", file!(), line!())));
                 }
             }
+        }
 
-            if check_g1_subgroup {
-                if !g1.check_correct_subgroup() {
-                    if !crate::features::in_fuzzing_or_gas_metering() {
-                        return Err(ApiError::InputError("G1 or G2 point is not in the expected subgroup".to_owned()));
-                    }
-                }
+        let mut extension_k = ExtensionK::new(fp_non_residue, embedding_degree).map_err(|_| {
+            ApiError::InputError("Embedding degree is not supported".to_owned())
+        })?;
+        extension_k.calculate_frobenius_coeffs(&modulus).map_err(|_| {
+            ApiError::InputError("Embedding degree must divide p - 1".to_owned())
+        })?;
+
+        // G2 is a subgroup of the same curve over Fp^k
+        let fpk_params = CurveOverFpKParameters::new(&extension_k);
+        let a_fpk = FpK::from_fp(&extension_k, &a_fp);
+        let g2_curve = if b_fp.is_zero() {
+            WeierstrassCurve::new_with_zero_b(order.as_ref(), a_fpk, &fpk_params)
+        } else {
+            WeierstrassCurve::new(order.as_ref(), a_fpk, FpK::from_fp(&extension_k, &b_fp), &fpk_params)
+        }.map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        let (ate_loop_count, rest) = decode_loop_parameter_scalar_with_bit_limit(rest, ctx.limits().max_ate_pairing_ate_loop_count)?;
+        if ate_loop_count.is_zero() {
+            return Err(ApiError::InputError("Ate loop count parameters can not be zero".to_owned()));
+        }
+
+        if calculate_hamming_weight(ate_loop_count.as_ref()) > ctx.limits().max_ate_pairing_ate_loop_count_hamming {
+            return Err(ApiError::InputError("Ate loop count has too large hamming weight".to_owned()));
+        }
+
+        let (ate_loop_count_is_negative, rest) = decode_sign_is_negative(rest)?;
+
+        let (final_exp_hard_part, rest) = decode_loop_parameter_scalar_with_bit_limit(rest, ctx.limits().max_cocks_pinch_final_exp_bit_length)?;
+        if final_exp_hard_part.is_zero() {
+            return Err(ApiError::InputError("Final exponentiation exponent can not be zero".to_owned()));
+        }
+
+        if calculate_hamming_weight(final_exp_hard_part.as_ref()) > ctx.limits().max_cocks_pinch_final_exp_hamming {
+            return Err(ApiError::InputError("Final exponentiation exponent has too large hamming weight".to_owned()));
+        }
+
+        if !crate::features::in_fuzzing_or_gas_metering() {
+            // Parameters can not be checked against a family, so check that the group order
+            // divides Phi_k(p), the ate loop count is a power of p modulo the group order, and that
+            // the hard part is not divisible by the group order and is equal to Phi_k(p)/r modulo p,
+            // as Phi_k(p) = 1 mod p
+            let modulus = MaxFieldSquaredUint::from(modulus.as_ref());
+            let order = MaxFieldSquaredUint::from(order.as_ref());
+            let p_mod_r = modulus % order;
+
+            let mut cyclotomic_value = MaxFieldSquaredUint::zero();
+            for &c in cyclotomic_polynomial(embedding_degree).iter().rev() {
+                cyclotomic_value = cyclotomic_value.adaptive_multiplication(p_mod_r) % order;
+                let c_mod_r = MaxFieldSquaredUint::from(c.unsigned_abs()) % order;
+                cyclotomic_value = if c >= 0 {
+                    (cyclotomic_value + c_mod_r) % order
+                } else {
+                    (cyclotomic_value + order - c_mod_r) % order
+                };
+            }
+            if !cyclotomic_value.is_zero() {
+                return Err(ApiError::InputError("Group order does not divide Phi_k(p)".to_owned()));
             }
 
-            if check_g2_subgroup {
-                if !g2.check_correct_subgroup() {
-                    if !crate::features::in_fuzzing_or_gas_metering() {
-                        return Err(ApiError::InputError("G1 or G2 point is not in the expected subgroup".to_owned()));
-                    }
+            let loop_count_mod_r = {
+                let value = MaxFieldSquaredUint::from(ate_loop_count.as_ref()) % order;
+                if ate_loop_count_is_negative && !value.is_zero() {
+                    order - value
+                } else {
+                    value
+                }
+            };
+            let mut p_power = p_mod_r;
+            let mut is_power_of_p = false;
+            for _ in 1..embedding_degree {
+                if p_power == loop_count_mod_r {
+                    is_power_of_p = true;
+                    break;
                 }
+                p_power = p_power.adaptive_multiplication(p_mod_r) % order;
             }
-
-            if !g1.is_zero() && !g2.is_zero() {
-                g1_points.push(g1);
-                g2_points.push(g2);
+            if !is_power_of_p {
+                return Err(ApiError::InputError("Ate loop count is not a power of p modulo the group order".to_owned()));
             }
-        }
 
-        if global_rest.len() != 0 {
-            return Err(ApiError::InputError("Input contains garbage at the end".to_owned()));
+            let hard_part = MaxFieldSquaredUint::from(final_exp_hard_part.as_ref());
+            if (hard_part % order).is_zero() {
+                return Err(ApiError::InputError("Final exponentiation exponent is divisible by the group order".to_owned()));
+            }
+            let hard_part_by_r = (hard_part % modulus).adaptive_multiplication(order % modulus) % modulus;
+            if hard_part_by_r != MaxFieldSquaredUint::from(1u64) {
+                return Err(ApiError::InputError("Final exponentiation exponent is not Phi_k(p)/r".to_owned()));
+            }
         }
 
-        debug_assert!(g1_points.len() == g2_points.len());
-        if g1_points.len() == 0 {
-            return Ok(pairing_result_true());
-        }
+        let one = FpK::one(&extension_k);
+        let serialize = |el: &FpK<FE, PrimeField<FE>>| serialize_fpk_fixed_len(modulus_len, el);
 
-        let engine_params = Bls12InstanceParams {
-            x: &x.as_ref(),
-            x_is_negative: x_is_negative,
-            twist_type: twist_type,
+        let engine_params = CocksPinchInstanceParams {
+            ate_loop_count: ate_loop_count.as_ref(),
+            ate_loop_count_is_negative,
+            final_exp_hard_part: final_exp_hard_part.as_ref(),
             base_field: &base_field,
             curve: &g1_curve,
-            curve_twist: &g2_curve,
-            fp2_extension: &extension_2,
-            fp6_extension: &extension_6,
-            fp12_extension: &extension_12,
+            curve_over_extension: &g2_curve,
+            fpk_extension: &extension_k,
             force_no_naf: true
         };
 
-        let engine = Bls12Instance::from_params(engine_params);
+        let engine = CocksPinchInstance::from_params(engine_params);
 
-        let pairing_result = engine.pair(&g1_points, &g2_points);
+        stages.run(ParsedPairing {
+            engine: &engine,
+            one,
+            rest,
+            prepare: |rest, output| decode_pairs(rest, modulus_len, order_len, &g1_curve, |rest| decode_g2_point_from_xy_in_fpk(rest, modulus_len, &g2_curve, ctx.point_encoding()), output, ctx.point_encoding()),
+            decode_gt: |rest| decode_fpk(rest, modulus_len, &extension_k),
+            serialize
+        })
+    }
 
-        if pairing_result.is_none() {
-            return Err(ApiError::UnknownParameter("Pairing engine returned no value".to_owned()));
-        }
+}
 
-        let one_fp12 = Fp12::one(&extension_12);
-        let pairing_result = pairing_result.unwrap();
-        let result = if pairing_result == one_fp12 {
-            pairing_result_true()
-        } else {
-            pairing_result_false()
-        };
+pub(crate) struct BnFamily;
 
-        Ok(result)
-    }
+impl PairingFamily for BnFamily {
+    const CURVE_TYPE: u8 = BN;
 
-    pub(crate) fn pair_bn(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+    fn parse<FE: ElementRepr, S: PairingStages>(bytes: &[u8], ctx: &ExecutionContext, stages: S) -> Result<Vec<u8>, ApiError> {
         use crate::extension_towers::fp2::{Fp2, Extension2};
         use crate::extension_towers::fp6_as_3_over_2::{Fp6, Extension3Over2};
         use crate::extension_towers::fp12_as_2_over3_over_2::{Fp12, Extension2Over3Over2};
 
         let (base_field, modulus_len, modulus, rest) = parse_base_field_from_encoding::<FE>(&bytes)?;
-        let (a_fp, b_fp, rest) = parse_ab_in_base_field_from_encoding(&rest, modulus_len, &base_field)?;
-        if !a_fp.is_zero() {
-            return Err(ApiError::UnknownParameter("A parameter must be zero for BN curve".to_owned()));
-        }
-        let (_order_len, order, rest) = parse_group_order_from_encoding(rest)?;
+        let (a_fp, b_fp, order_len, order, rest) = parse_g1_parameters(rest, modulus_len, &base_field, G1Coefficients::ZeroA("BN"), ctx)?;
         let fp_params = CurveOverFpParameters::new(&base_field);
         let g1_curve = WeierstrassCurve::new(&order.as_ref(), a_fp, b_fp.clone(), &fp_params).map_err(|_| {
             ApiError::InputError("Curve shape is not supported".to_owned())
@@ -331,18 +2060,19 @@ impl<FE: ElementRepr>PairingApiImplementation<FE> {
         // - sign of U
         // - number of pairs
         // - list of encoded pairs
+        // - optional pairing mode
         // U is used instead of x for convention of go-ethereum people :)
 
         let (fp_non_residue, rest) = decode_fp(&rest, modulus_len, &base_field)?;
 
         {
             if fp_non_residue.is_zero() {
-                return Err(ApiError::InputError(format!("Non-residue for Fp2 is zero file {}, line {}", file!(), line!())));
+                return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp2 is zero file {}, line {}", file!(), line!())));
             }
             let is_not_a_square = is_non_nth_root(&fp_non_residue, &modulus, 2u64);
             if !is_not_a_square {
                 if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError(format!("Non-residue for Fp2 is actually a residue file {}, line {}", file!(), line!())));
+                    return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp2 is actually a residue file {}, line {}", file!(), line!())));
                 }
             }
         }
@@ -357,17 +2087,21 @@ impl<FE: ElementRepr>PairingApiImplementation<FE> {
 
         {
             if fp2_non_residue.is_zero() {
-                return Err(ApiError::InputError(format!("Non-residue for Fp6(12) is zero, file {}, line {}", file!(), line!())));
+                return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp6(12) is zero, file {}, line {}", file!(), line!())));
             }
             let is_not_a_6th_root = is_non_nth_root_fp2(&fp2_non_residue, &modulus, 6u64);
             if !is_not_a_6th_root {
                 if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError(format!("Non-residue for Fp6(12) is actually a residue, file {}, line {}", file!(), line!())));
+                    return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp6(12) is actually a residue, file {}, line {}", file!(), line!())));
                 }
             }
+            // Fp12 is built as a quadratic extension of Fp6, so the non-residue must also be a non-square in Fp2
+            if fp2::is_quadratic_residue(&fp2_non_residue) && !crate::features::in_fuzzing_or_gas_metering() {
+                return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp6(12) is a square in Fp2, file {}, line {}", file!(), line!())));
+            }
         }
 
-        let (twist_type, rest) = decode_twist_type(&rest)?;
+        let (twist_type, rest) = decode_twist_type_or_detect(rest)?;
 
         let base_precomp = Fp6Fp12FrobeniusBaseElements::construct(
             &modulus, 
@@ -390,23 +2124,19 @@ impl<FE: ElementRepr>PairingApiImplementation<FE> {
             })?;
         }
 
-        let fp2_non_residue_inv = fp2_non_residue.inverse().ok_or(ApiError::UnexpectedZero("Fp2 non-residue must be invertible".to_owned()))?;
-
-        let b_fp2 = match twist_type {
-            TwistType::D => {
-                let mut b_fp2 = fp2_non_residue_inv.clone();
-                b_fp2.mul_by_fp(&b_fp);
+        let (u, rest) = decode_loop_parameter_scalar_with_bit_limit(rest, ctx.limits().max_bn_u_bit_length)?;
+        if u.is_zero() {
+            return Err(ApiError::InputError("Loop count parameters can not be zero".to_owned()));
+        }
 
-                b_fp2
-            },
-            TwistType::M => {
-                let mut b_fp2 = fp2_non_residue.clone();
-                b_fp2.mul_by_fp(&b_fp);
+        let (u_is_negative, rest) = decode_sign_is_negative(rest)?;
 
-                b_fp2
-            },
+        // the pairs follow, the twist type may be detected from them
+        let twist_type = match twist_type {
+            Some(twist_type) => twist_type,
+            None => detect_twist_type(rest, modulus_len, &extension_2, &b_fp, &fp2_non_residue, stages.output(), ctx.point_encoding())?
         };
-
+        let b_fp2 = twist_b(&b_fp, &fp2_non_residue, twist_type)?;
         let a_fp2 = Fp2::zero(&extension_2);
 
         let fp2_params = CurveOverFp2Parameters::new(&extension_2);
@@ -414,13 +2144,6 @@ impl<FE: ElementRepr>PairingApiImplementation<FE> {
             ApiError::InputError("Curve shape is not supported".to_owned())
         })?;
 
-        let (u, rest) = decode_loop_parameter_scalar_with_bit_limit(&rest, MAX_BN_U_BIT_LENGTH)?;
-        if u.is_zero() {
-            return Err(ApiError::InputError("Loop count parameters can not be zero".to_owned()));
-        }
-
-        let (u_is_negative, rest) = decode_sign_is_negative(rest)?;
-
         let two = MaxLoopParametersUint::from(2u64);
         let six = MaxLoopParametersUint::from(6u64);
 
@@ -435,7 +2158,7 @@ impl<FE: ElementRepr>PairingApiImplementation<FE> {
             six_u_plus_two
         };
 
-        if calculate_hamming_weight(&six_u_plus_two.as_ref()) > MAX_BN_SIX_U_PLUS_TWO_HAMMING {
+        if calculate_hamming_weight(six_u_plus_two.as_ref()) > ctx.limits().max_bn_six_u_plus_two_hamming {
             return Err(ApiError::InputError("|6*U + 2| has too large hamming weight".to_owned()));
         }
 
@@ -443,69 +2166,8 @@ impl<FE: ElementRepr>PairingApiImplementation<FE> {
 
         let fp2_non_residue_in_p_minus_one_over_2 = fp2_non_residue.pow(p_minus_one_over_2.as_ref());
 
-        let (num_pairs_encoding, rest) = split(rest, BYTES_FOR_LENGTH_ENCODING, "Input is not long enough to get number of pairs")?;
-        let num_pairs = num_pairs_encoding[0] as usize;
-
-        if num_pairs == 0 {
-            if !crate::features::in_gas_metering() {
-                return Err(ApiError::InputError("Zero pairs encoded".to_owned()));
-            }
-        }
-
-        let mut global_rest = rest;
-
-        let mut g1_points = vec![];
-        let mut g2_points = vec![];
-
-        for _ in 0..num_pairs {
-            let (check_g1_subgroup, rest) = decode_boolean(&global_rest)?;
-            let (g1, rest) = decode_g1_point_from_xy(&rest, modulus_len, &g1_curve)?;
-            let (check_g2_subgroup, rest) = decode_boolean(&rest)?;
-            let (g2, rest) = decode_g2_point_from_xy_in_fp2(&rest, modulus_len, &g2_curve)?;
-            global_rest = rest;
-
-            if !g1.is_on_curve() {
-                if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError("G1 point is not on curve".to_owned()));
-                }
-            }
-
-            if !g2.is_on_curve() {
-                if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError("G2 point is not on curve".to_owned()));
-                }
-            }
-
-            if check_g1_subgroup {
-                if !g1.check_correct_subgroup() {
-                    if !crate::features::in_fuzzing_or_gas_metering() {
-                        return Err(ApiError::InputError("G1 or G2 point is not in the expected subgroup".to_owned()));
-                    }
-                }
-            }
-
-            if check_g2_subgroup {
-                if !g2.check_correct_subgroup() {
-                    if !crate::features::in_fuzzing_or_gas_metering() {
-                        return Err(ApiError::InputError("G1 or G2 point is not in the expected subgroup".to_owned()));
-                    }
-                }
-            }
-
-            if !g1.is_zero() && !g2.is_zero() {
-                g1_points.push(g1);
-                g2_points.push(g2);
-            }
-        }
-
-        if global_rest.len() != 0 {
-            return Err(ApiError::InputError("Input contains garbage at the end".to_owned()));
-        }
-
-        debug_assert!(g1_points.len() == g2_points.len());
-        if g1_points.len() == 0 {
-            return Ok(pairing_result_true());
-        }
+        let one = Fp12::one(&extension_12);
+        let serialize = |el: &Fp12<FE, PrimeField<FE>>| serialize_fp12_fixed_len(modulus_len, el);
 
         let engine_params = BnInstanceParams {
             u: &u.as_ref(),
@@ -524,30 +2186,29 @@ impl<FE: ElementRepr>PairingApiImplementation<FE> {
 
         let engine = BnInstance::from_params(engine_params);
 
-        let pairing_result = engine.pair(&g1_points, &g2_points);
+        stages.run(ParsedPairing {
+            engine: &engine,
+            one,
+            rest,
+            prepare: |rest, output| decode_pairs_with_fp2_twist(rest, modulus_len, order_len, &g1_curve, &g2_curve, SubgroupChecks::new(|p| engine.g1_subgroup_check(p), |q| q.check_correct_subgroup()), output, ctx.point_encoding()),
+            decode_gt: |rest| decode_fp12(rest, modulus_len, &extension_12),
+            serialize
+        })
+    }
 
-        if pairing_result.is_none() {
-            return Err(ApiError::UnknownParameter("Pairing engine returned no value".to_owned()));
-        }
+}
 
-        let one_fp12 = Fp12::one(&extension_12);
-        let pairing_result = pairing_result.unwrap();
-        let result = if pairing_result == one_fp12 {
-            pairing_result_true()
-        } else {
-            pairing_result_false()
-        };
+pub(crate) struct Mnt6Family;
 
-        Ok(result)
-    }
+impl PairingFamily for Mnt6Family {
+    const CURVE_TYPE: u8 = MNT6;
 
-    pub(crate) fn pair_mnt6(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+    fn parse<FE: ElementRepr, S: PairingStages>(bytes: &[u8], ctx: &ExecutionContext, stages: S) -> Result<Vec<u8>, ApiError> {
         use crate::extension_towers::fp3::{Fp3, Extension3};
         use crate::extension_towers::fp6_as_2_over_3::{Fp6, Extension2Over3};
 
         let (base_field, modulus_len, modulus, rest) = parse_base_field_from_encoding::<FE>(&bytes)?;
-        let (a_fp, b_fp, rest) = parse_ab_in_base_field_from_encoding(&rest, modulus_len, &base_field)?;
-        let (_order_len, order, rest) = parse_group_order_from_encoding(rest)?;
+        let (a_fp, b_fp, order_len, order, rest) = parse_g1_parameters(rest, modulus_len, &base_field, G1Coefficients::Any, ctx)?;
         let fp_params = CurveOverFpParameters::new(&base_field);
         let g1_curve = WeierstrassCurve::new(&order.as_ref(), a_fp.clone(), b_fp.clone(), &fp_params).map_err(|_| {
             ApiError::InputError("Curve shape is not supported".to_owned())
@@ -564,17 +2225,18 @@ impl<FE: ElementRepr>PairingApiImplementation<FE> {
         // - exp_w0_is_negative
         // - number of pairs
         // - list of encoded pairs
+        // - optional pairing mode
 
         let (fp_non_residue, rest) = decode_fp(&rest, modulus_len, &base_field)?;
 
         {
             if fp_non_residue.is_zero() {
-                return Err(ApiError::InputError(format!("Non-residue for Fp3 is zero file {}, line {}", file!(), line!())));
+                return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp3 is zero file {}, line {}", file!(), line!())));
             }
             let is_not_a_root = is_non_nth_root(&fp_non_residue, &modulus, 6u64);
             if !is_not_a_root {
                 if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError(format!("Non-residue for Fp3 is actually a residue, file {}, line {}", file!(), line!())));
+                    return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp3 is actually a residue, file {}, line {}", file!(), line!())));
                 }
             }
         }
@@ -621,103 +2283,42 @@ impl<FE: ElementRepr>PairingApiImplementation<FE> {
             ApiError::InputError("Curve shape is not supported".to_owned())
         })?;
 
-        let (x, rest) = decode_loop_parameter_scalar_with_bit_limit(&rest, MAX_ATE_PAIRING_ATE_LOOP_COUNT)?;
+        let (x, rest) = decode_loop_parameter_scalar_with_bit_limit(rest, ctx.limits().max_ate_pairing_ate_loop_count)?;
         if x.is_zero() {
             return Err(ApiError::InputError("Ate loop count parameters can not be zero".to_owned()));
         }
 
-        if calculate_hamming_weight(&x.as_ref()) > MAX_ATE_PAIRING_ATE_LOOP_COUNT_HAMMING {
+        if calculate_hamming_weight(x.as_ref()) > ctx.limits().max_ate_pairing_ate_loop_count_hamming {
             return Err(ApiError::InputError("X has too large hamming weight".to_owned()));
         }
 
         let (x_is_negative, rest) = decode_sign_is_negative(rest)?;
 
-        let (exp_w0, rest) = decode_loop_parameter_scalar_with_bit_limit(&rest, MAX_ATE_PAIRING_FINAL_EXP_W0_BIT_LENGTH)?;
+        let (exp_w0, rest) = decode_loop_parameter_scalar_with_bit_limit(rest, ctx.limits().max_ate_pairing_final_exp_w0_bit_length)?;
         if exp_w0.is_zero() {
             return Err(ApiError::InputError("Final exp w0 loop count parameters can not be zero".to_owned()));
         }
 
-        let (exp_w1, rest) = decode_loop_parameter_scalar_with_bit_limit(&rest, MAX_ATE_PAIRING_FINAL_EXP_W1_BIT_LENGTH)?;
+        let (exp_w1, rest) = decode_loop_parameter_scalar_with_bit_limit(rest, ctx.limits().max_ate_pairing_final_exp_w1_bit_length)?;
         if exp_w1.is_zero() {
             return Err(ApiError::InputError("Final exp w1 loop count parameters can not be zero".to_owned()));
         }
 
         let (exp_w0_is_negative, rest) = decode_sign_is_negative(rest)?;
 
-        let (num_pairs_encoding, rest) = split(rest, BYTES_FOR_LENGTH_ENCODING, "Input is not long enough to get number of pairs")?;
-        let num_pairs = num_pairs_encoding[0] as usize;
-
-        if num_pairs == 0 {
-            if !crate::features::in_gas_metering() {
-                return Err(ApiError::InputError("Zero pairs encoded".to_owned()));
-            }
-        }
-
-        let mut global_rest = rest;
-
-        let mut g1_points = vec![];
-        let mut g2_points = vec![];
-
-        for _ in 0..num_pairs {
-            let (check_g1_subgroup, rest) = decode_boolean(&global_rest)?;
-            let (g1, rest) = decode_g1_point_from_xy(&rest, modulus_len, &g1_curve)?;
-            let (check_g2_subgroup, rest) = decode_boolean(&rest)?;
-            let (g2, rest) = decode_g2_point_from_xy_in_fp3(&rest, modulus_len, &g2_curve)?;
-            global_rest = rest;
-
-            if !g1.is_on_curve() {
-                if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError("G1 point is not on curve".to_owned()));
-                }
-            }
-
-            if !g2.is_on_curve() {
-                if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError("G2 point is not on curve".to_owned()));
-                }
-            }
-
-            if check_g1_subgroup {
-                if !g1.check_correct_subgroup() {
-                    if !crate::features::in_fuzzing_or_gas_metering() {
-                        return Err(ApiError::InputError("G1 or G2 point is not in the expected subgroup".to_owned()));
-                    }
-                }
-            }
-
-            if check_g2_subgroup {
-                if !g2.check_correct_subgroup() {
-                    if !crate::features::in_fuzzing_or_gas_metering() {
-                        return Err(ApiError::InputError("G1 or G2 point is not in the expected subgroup".to_owned()));
-                    }
-                }
-            }
-
-            if !g1.is_zero() && !g2.is_zero() {
-                g1_points.push(g1);
-                g2_points.push(g2);
-            }
-        }
-
-        if global_rest.len() != 0 {
-            return Err(ApiError::InputError("Input contains garbage at the end".to_owned()));
-        }
-
-        debug_assert!(g1_points.len() == g2_points.len());
-        if g1_points.len() == 0 {
-            return Ok(pairing_result_true());
-        }
+        let one = Fp6::one(&extension_6);
+        let serialize = |el: &Fp6<FE, PrimeField<FE>>| serialize_fp6_as_2_over_3_fixed_len(modulus_len, el);
 
         let engine_params = MNT6InstanceParams {
-            x: &x.as_ref(),
-            x_is_negative: x_is_negative,
+            x: x.as_ref(),
+            x_is_negative,
             exp_w0: exp_w0.as_ref(),
             exp_w1: exp_w1.as_ref(),
-            exp_w0_is_negative: exp_w0_is_negative,
+            exp_w0_is_negative,
             base_field: &base_field,
             curve: &g1_curve,
             curve_twist: &g2_curve,
-            twist: twist,
+            twist,
             fp3_extension: &extension_3,
             fp6_extension: &extension_6,
             force_no_naf: true
@@ -725,30 +2326,29 @@ impl<FE: ElementRepr>PairingApiImplementation<FE> {
 
         let engine = MNT6Instance::from_params(engine_params);
 
-        let pairing_result = engine.pair(&g1_points, &g2_points);
+        stages.run(ParsedPairing {
+            engine: &engine,
+            one,
+            rest,
+            prepare: |rest, output| decode_pairs(rest, modulus_len, order_len, &g1_curve, |rest| decode_g2_point_from_xy_in_fp3(rest, modulus_len, &g2_curve, ctx.point_encoding()), output, ctx.point_encoding()),
+            decode_gt: |rest| decode_fp6_as_2_over_3(rest, modulus_len, &extension_6),
+            serialize
+        })
+    }
 
-        if pairing_result.is_none() {
-            return Err(ApiError::UnknownParameter("Pairing engine returned no value".to_owned()));
-        }
+}
 
-        let one_fp6 = Fp6::one(&extension_6);
-        let pairing_result = pairing_result.unwrap();
-        let result = if pairing_result == one_fp6 {
-            pairing_result_true()
-        } else {
-            pairing_result_false()
-        };
+pub(crate) struct Mnt4Family;
 
-        Ok(result)
-    }
+impl PairingFamily for Mnt4Family {
+    const CURVE_TYPE: u8 = MNT4;
 
-    pub(crate) fn pair_mnt4(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+    fn parse<FE: ElementRepr, S: PairingStages>(bytes: &[u8], ctx: &ExecutionContext, stages: S) -> Result<Vec<u8>, ApiError> {
         use crate::extension_towers::fp2::{Fp2, Extension2};
         use crate::extension_towers::fp4_as_2_over_2::{Fp4, Extension2Over2};
 
         let (base_field, modulus_len, modulus, rest) = parse_base_field_from_encoding::<FE>(&bytes)?;
-        let (a_fp, b_fp, rest) = parse_ab_in_base_field_from_encoding(&rest, modulus_len, &base_field)?;
-        let (_order_len, order, rest) = parse_group_order_from_encoding(rest)?;
+        let (a_fp, b_fp, order_len, order, rest) = parse_g1_parameters(rest, modulus_len, &base_field, G1Coefficients::Any, ctx)?;
         let fp_params = CurveOverFpParameters::new(&base_field);
         let g1_curve = WeierstrassCurve::new(&order.as_ref(), a_fp.clone(), b_fp.clone(), &fp_params).map_err(|_| {
             ApiError::InputError("Curve shape is not supported".to_owned())
@@ -765,17 +2365,18 @@ impl<FE: ElementRepr>PairingApiImplementation<FE> {
         // - exp_w0_is_negative
         // - number of pairs
         // - list of encoded pairs
+        // - optional pairing mode
 
         let (fp_non_residue, rest) = decode_fp(&rest, modulus_len, &base_field)?;
 
         {
             if fp_non_residue.is_zero() {
-                return Err(ApiError::InputError(format!("Non-residue for Fp2 is zero file {}, line {}", file!(), line!())));
+                return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp2 is zero file {}, line {}", file!(), line!())));
             }
             let is_not_a_root = is_non_nth_root(&fp_non_residue, &modulus, 4u64);
             if !is_not_a_root {
                 if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError(format!("Non-residue for Fp2 is actually a residue, file {}, line {}", file!(), line!())));
+                    return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp2 is actually a residue, file {}, line {}", file!(), line!())));
                 }
             }
         }
@@ -824,102 +2425,41 @@ impl<FE: ElementRepr>PairingApiImplementation<FE> {
             ApiError::InputError("Curve shape is not supported".to_owned())
         })?;
 
-        let (x, rest) = decode_loop_parameter_scalar_with_bit_limit(&rest, MAX_ATE_PAIRING_ATE_LOOP_COUNT)?;
+        let (x, rest) = decode_loop_parameter_scalar_with_bit_limit(rest, ctx.limits().max_ate_pairing_ate_loop_count)?;
         if x.is_zero() {
             return Err(ApiError::InputError("Ate pairing loop count parameters can not be zero".to_owned()));
         }
 
-        if calculate_hamming_weight(&x.as_ref()) > MAX_ATE_PAIRING_ATE_LOOP_COUNT_HAMMING {
+        if calculate_hamming_weight(x.as_ref()) > ctx.limits().max_ate_pairing_ate_loop_count_hamming {
             return Err(ApiError::InputError("X has too large hamming weight".to_owned()));
         }
 
         let (x_is_negative, rest) = decode_sign_is_negative(rest)?;
 
-        let (exp_w0, rest) = decode_loop_parameter_scalar_with_bit_limit(&rest, MAX_ATE_PAIRING_FINAL_EXP_W0_BIT_LENGTH)?;
+        let (exp_w0, rest) = decode_loop_parameter_scalar_with_bit_limit(rest, ctx.limits().max_ate_pairing_final_exp_w0_bit_length)?;
         if exp_w0.is_zero() {
             return Err(ApiError::InputError("Final exp w0 loop count parameters can not be zero".to_owned()));
         }
-        let (exp_w1, rest) = decode_loop_parameter_scalar_with_bit_limit(&rest, MAX_ATE_PAIRING_FINAL_EXP_W1_BIT_LENGTH)?;
+        let (exp_w1, rest) = decode_loop_parameter_scalar_with_bit_limit(rest, ctx.limits().max_ate_pairing_final_exp_w1_bit_length)?;
         if exp_w1.is_zero() {
             return Err(ApiError::InputError("Final exp w1 loop count parameters can not be zero".to_owned()));
         }
 
         let (exp_w0_is_negative, rest) = decode_sign_is_negative(rest)?;
 
-        let (num_pairs_encoding, rest) = split(rest, BYTES_FOR_LENGTH_ENCODING, "Input is not long enough to get number of pairs")?;
-        let num_pairs = num_pairs_encoding[0] as usize;
-
-        if num_pairs == 0 {
-            if !crate::features::in_gas_metering() {
-                return Err(ApiError::InputError("Zero pairs encoded".to_owned()));
-            }
-        }
-
-        let mut global_rest = rest;
-
-        let mut g1_points = vec![];
-        let mut g2_points = vec![];
-
-        for _ in 0..num_pairs {
-            let (check_g1_subgroup, rest) = decode_boolean(&global_rest)?;
-            let (g1, rest) = decode_g1_point_from_xy(&rest, modulus_len, &g1_curve)?;
-            let (check_g2_subgroup, rest) = decode_boolean(&rest)?;
-            let (g2, rest) = decode_g2_point_from_xy_in_fp2(&rest, modulus_len, &g2_curve)?;
-            global_rest = rest;
-
-            if !g1.is_on_curve() {
-                if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError("G1 point is not on curve".to_owned()));
-                }
-            }
-
-            if !g2.is_on_curve() {
-                if !crate::features::in_fuzzing_or_gas_metering() {
-                    return Err(ApiError::InputError("G2 point is not on curve".to_owned()));
-                }
-            }
-
-            if check_g1_subgroup {
-                if !g1.check_correct_subgroup() {
-                    if !crate::features::in_fuzzing_or_gas_metering() {
-                        return Err(ApiError::InputError("G1 or G2 point is not in the expected subgroup".to_owned()));
-                    }
-                }
-            }
-
-            if check_g2_subgroup {
-                if !g2.check_correct_subgroup() {
-                    if !crate::features::in_fuzzing_or_gas_metering() {
-                        return Err(ApiError::InputError("G1 or G2 point is not in the expected subgroup".to_owned()));
-                    }
-                }
-            }
-
-            if !g1.is_zero() && !g2.is_zero() {
-                g1_points.push(g1);
-                g2_points.push(g2);
-            }
-        }
-
-        if global_rest.len() != 0 {
-            return Err(ApiError::InputError("Input contains garbage at the end".to_owned()));
-        }
-
-        debug_assert!(g1_points.len() == g2_points.len());
-        if g1_points.len() == 0 {
-            return Ok(pairing_result_true());
-        }
+        let one = Fp4::one(&extension_4);
+        let serialize = |el: &Fp4<FE, PrimeField<FE>>| serialize_fp4_fixed_len(modulus_len, el);
 
         let engine = MNT4InstanceParams {
-            x: &x.as_ref(),
-            x_is_negative: x_is_negative,
-            exp_w0: &exp_w0.as_ref(),
-            exp_w1: &exp_w1.as_ref(),
-            exp_w0_is_negative: exp_w0_is_negative,
+            x: x.as_ref(),
+            x_is_negative,
+            exp_w0: exp_w0.as_ref(),
+            exp_w1: exp_w1.as_ref(),
+            exp_w0_is_negative,
             base_field: &base_field,
             curve: &g1_curve,
             curve_twist: &g2_curve,
-            twist: twist,
+            twist,
             fp2_extension: &extension_2,
             fp4_extension: &extension_4,
             force_no_naf: true
@@ -927,20 +2467,28 @@ impl<FE: ElementRepr>PairingApiImplementation<FE> {
 
         let engine = MNT4Instance::from_params(engine);
 
-        let pairing_result = engine.pair(&g1_points, &g2_points);
-
-        if pairing_result.is_none() {
-            return Err(ApiError::UnknownParameter("Pairing engine returned no value".to_owned()));
-        }
-
-        let one_fp4 = Fp4::one(&extension_4);
-        let pairing_result = pairing_result.unwrap();
-        let result = if pairing_result == one_fp4 {
-            pairing_result_true()
-        } else {
-            pairing_result_false()
-        };
-
-        Ok(result)
+        stages.run(ParsedPairing {
+            engine: &engine,
+            one,
+            rest,
+            prepare: |rest, output| decode_pairs(rest, modulus_len, order_len, &g1_curve, |rest| decode_g2_point_from_xy_in_fp2(rest, modulus_len, &g2_curve, ctx.point_encoding()), output, ctx.point_encoding()),
+            decode_gt: |rest| decode_fp4(rest, modulus_len, &extension_4),
+            serialize
+        })
+    }
+}
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::field::U256Repr;
+
+    #[test]
+    fn test_pairing_families_cover_curve_types() {
+        let mut curve_types: Vec<u8> = PairingFamilies::<U256Repr>::ALL.iter().map(|(curve_type, _)| *curve_type).collect();
+        curve_types.sort();
+        assert_eq!(curve_types, vec![BLS12, BN, MNT4, MNT6, BLS24, BLS48, BW6, KSS16, KSS18, COCKS_PINCH]);
+
+        let err = PairingApiImplementation::<U256Repr>::pair(&[0xaa, 1, 7], &ExecutionContext::default()).unwrap_err();
+        assert_eq!(err, ApiError::InputError("Unknown curve type".to_owned()));
     }
-}
\ No newline at end of file
+}
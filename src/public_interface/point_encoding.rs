@@ -0,0 +1,39 @@
+//! Encoding of the curve points in the input. In the third version of the ABI points are compressed:
+//! X is followed by a flag with the parity of Y, or with the mark of the point at infinity for zero X.
+//! Decoders of points with coordinates in the base field and its extensions of degree 2 and 3
+//! recover Y with a square root, others reject compressed points. Lengths of points in the upfront
+//! length check and in the gas meter follow the same encoding. Outputs are always uncompressed
+
+use super::constants::*;
+use super::decode_utils::{split, checked_len_add, checked_len_mul};
+use crate::errors::ApiError;
+use crate::alloc_prelude::*;
+
+/// Encoding of the points in the input. Points are compressed in the third version of the ABI
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PointEncoding {
+    #[default]
+    Uncompressed,
+    Compressed
+}
+
+/// Curves in other forms have their own encodings of points, so their operations
+/// don't take compressed points. `bytes` start with the operation type
+pub(crate) fn check_operation(bytes: &[u8]) -> Result<(), ApiError> {
+    let (op_type, _) = split(bytes, OPERATION_ENCODING_LENGTH, "Input should be longer than operation type encoding")?;
+    match op_type[0] {
+        OPERATION_EDWARDS_ADD | OPERATION_EDWARDS_MUL | OPERATION_EDWARDS_MULTIEXP | OPERATION_MONTGOMERY_LADDER |
+        OPERATION_BINARY_ADD | OPERATION_BINARY_MUL | OPERATION_BINARY_MULTIEXP => {
+            Err(ApiError::UnknownParameter("Compressed points are only supported for curves in Weierstrass form".to_owned()))
+        },
+        _ => Ok(())
+    }
+}
+
+/// Length of a point with coordinates of `coordinate_len` bytes
+pub(crate) fn point_len(coordinate_len: usize, encoding: PointEncoding) -> Result<usize, ApiError> {
+    match encoding {
+        PointEncoding::Compressed => checked_len_add(coordinate_len, COMPRESSION_FLAG_LENGTH),
+        PointEncoding::Uncompressed => checked_len_mul(coordinate_len, 2)
+    }
+}
@@ -1,8 +1,17 @@
+// Defaults of `Limits`, the values used by `API::run`
 pub const MAX_BLS12_X_BIT_LENGTH: usize = 128;
 pub const MAX_BN_U_BIT_LENGTH: usize = 128;
+pub const MAX_BLS24_X_BIT_LENGTH: usize = 128;
+pub const MAX_BLS48_X_BIT_LENGTH: usize = 128;
+pub const MAX_KSS16_X_BIT_LENGTH: usize = 128;
+pub const MAX_KSS18_X_BIT_LENGTH: usize = 128;
 
 pub const MAX_BLS12_X_HAMMING: u32 = 128u32;
 pub const MAX_BN_SIX_U_PLUS_TWO_HAMMING: u32 = 128u32;
+pub const MAX_BLS24_X_HAMMING: u32 = 128u32;
+pub const MAX_BLS48_X_HAMMING: u32 = 128u32;
+pub const MAX_KSS16_X_HAMMING: u32 = 128u32;
+pub const MAX_KSS18_X_HAMMING: u32 = 128u32;
 
 pub const MAX_ATE_PAIRING_ATE_LOOP_COUNT: usize = 2032;
 pub const MAX_ATE_PAIRING_ATE_LOOP_COUNT_HAMMING: u32 = 2032u32;
@@ -10,9 +19,145 @@ pub const MAX_ATE_PAIRING_ATE_LOOP_COUNT_HAMMING: u32 = 2032u32;
 pub const MAX_ATE_PAIRING_FINAL_EXP_W0_BIT_LENGTH: usize = 2032;
 pub const MAX_ATE_PAIRING_FINAL_EXP_W1_BIT_LENGTH: usize = 2032;
 
+// Cocks-Pinch pairing uses the ate loop count limits above, hard part of the final 
+// exponentiation is limited by the loop parameter encoding
+pub const MAX_COCKS_PINCH_EMBEDDING_DEGREE: usize = 12;
+pub const MAX_COCKS_PINCH_FINAL_EXP_BIT_LENGTH: usize = 2032;
+pub const MAX_COCKS_PINCH_FINAL_EXP_HAMMING: u32 = 2032u32;
+
+// Moduli above 1023 bits need the `limbs-24` or `limbs-32` feature. Lengths are encoded with
+// a single byte, so even with `limbs-32` the modulus is at most 255 bytes (2040 bits)
+pub const MAX_WIDE_MODULUS_BYTE_LEN: usize = 255;
+
+// BLS12-381 G1 uses an 11-isogeny with polynomials of degree 15
+pub const MAX_ISOGENY_MAP_DEGREE: usize = 32;
+
 pub const MAX_LOOP_PARAMETERS_BYTE_LEN: usize = MAX_ATE_PAIRING_FINAL_EXP_W0_BIT_LENGTH / 8;
 
+// Lower bounds. There is no lower bound on the modulus bit length: moduli shorter 
+// than NUM_LIMBS_MIN limbs use the smallest representation, so tiny test curves 
+// go through exactly the same code as the real ones
+pub const MIN_MODULUS_BYTE_LEN: usize = 1;
+pub const MIN_MODULUS: u64 = 3;
+pub const MIN_GROUP_BYTE_LEN: usize = 1;
+pub const MIN_LOOP_PARAMETERS_BYTE_LEN: usize = 1;
+pub const MIN_COCKS_PINCH_EMBEDDING_DEGREE: usize = 2;
+
 use static_assertions::const_assert;
 use crate::integers::*;
+use crate::errors::ApiError;
+use crate::alloc_prelude::*;
+
+const_assert!(core::mem::size_of::<MaxLoopParametersUint>() >= MAX_LOOP_PARAMETERS_BYTE_LEN);
+const_assert!(MAX_WIDE_MODULUS_BYTE_LEN < 1 << (8 * crate::public_interface::constants::BYTES_FOR_LENGTH_ENCODING));
+const_assert!(MAX_COCKS_PINCH_EMBEDDING_DEGREE <= crate::extension_towers::fpk::MAX_BINOMIAL_EXTENSION_DEGREE);
+
+/// Upper bounds on the parameters of pairings and mappings, and other checks of the input
+/// that are configurable at runtime.
+/// `Limits::default()` are the constants above, other values can be passed to
+/// `API::run_with_config`, e.g. to allow longer loops on a private chain
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Limits {
+    pub max_bls12_x_bit_length: usize,
+    pub max_bn_u_bit_length: usize,
+    pub max_bls24_x_bit_length: usize,
+    pub max_bls48_x_bit_length: usize,
+    pub max_kss16_x_bit_length: usize,
+    pub max_kss18_x_bit_length: usize,
+
+    pub max_bls12_x_hamming: u32,
+    pub max_bn_six_u_plus_two_hamming: u32,
+    pub max_bls24_x_hamming: u32,
+    pub max_bls48_x_hamming: u32,
+    pub max_kss16_x_hamming: u32,
+    pub max_kss18_x_hamming: u32,
+
+    pub max_ate_pairing_ate_loop_count: usize,
+    pub max_ate_pairing_ate_loop_count_hamming: u32,
+
+    pub max_ate_pairing_final_exp_w0_bit_length: usize,
+    pub max_ate_pairing_final_exp_w1_bit_length: usize,
+
+    pub max_cocks_pinch_embedding_degree: usize,
+    pub max_cocks_pinch_final_exp_bit_length: usize,
+    pub max_cocks_pinch_final_exp_hamming: u32,
+
+    pub max_isogeny_map_degree: usize,
+
+    /// Claimed group orders must pass the Miller-Rabin test. May be turned off
+    /// for toy curves that are built with composite orders
+    pub require_prime_group_order: bool,
+}
+
+impl Limits {
+    pub const DEFAULT: Self = Self {
+        max_bls12_x_bit_length: MAX_BLS12_X_BIT_LENGTH,
+        max_bn_u_bit_length: MAX_BN_U_BIT_LENGTH,
+        max_bls24_x_bit_length: MAX_BLS24_X_BIT_LENGTH,
+        max_bls48_x_bit_length: MAX_BLS48_X_BIT_LENGTH,
+        max_kss16_x_bit_length: MAX_KSS16_X_BIT_LENGTH,
+        max_kss18_x_bit_length: MAX_KSS18_X_BIT_LENGTH,
+
+        max_bls12_x_hamming: MAX_BLS12_X_HAMMING,
+        max_bn_six_u_plus_two_hamming: MAX_BN_SIX_U_PLUS_TWO_HAMMING,
+        max_bls24_x_hamming: MAX_BLS24_X_HAMMING,
+        max_bls48_x_hamming: MAX_BLS48_X_HAMMING,
+        max_kss16_x_hamming: MAX_KSS16_X_HAMMING,
+        max_kss18_x_hamming: MAX_KSS18_X_HAMMING,
+
+        max_ate_pairing_ate_loop_count: MAX_ATE_PAIRING_ATE_LOOP_COUNT,
+        max_ate_pairing_ate_loop_count_hamming: MAX_ATE_PAIRING_ATE_LOOP_COUNT_HAMMING,
+
+        max_ate_pairing_final_exp_w0_bit_length: MAX_ATE_PAIRING_FINAL_EXP_W0_BIT_LENGTH,
+        max_ate_pairing_final_exp_w1_bit_length: MAX_ATE_PAIRING_FINAL_EXP_W1_BIT_LENGTH,
+
+        max_cocks_pinch_embedding_degree: MAX_COCKS_PINCH_EMBEDDING_DEGREE,
+        max_cocks_pinch_final_exp_bit_length: MAX_COCKS_PINCH_FINAL_EXP_BIT_LENGTH,
+        max_cocks_pinch_final_exp_hamming: MAX_COCKS_PINCH_FINAL_EXP_HAMMING,
+
+        max_isogeny_map_degree: MAX_ISOGENY_MAP_DEGREE,
+
+        require_prime_group_order: true,
+    };
+
+    /// Limits can be raised only as far as the values still fit the fixed width integers
+    /// and the one byte encodings
+    pub fn validate(&self) -> Result<(), ApiError> {
+        let max_loop_bits = MAX_LOOP_PARAMETERS_BYTE_LEN * 8;
+        let bit_lengths = [
+            self.max_bls12_x_bit_length,
+            self.max_bls24_x_bit_length,
+            self.max_bls48_x_bit_length,
+            self.max_kss16_x_bit_length,
+            self.max_kss18_x_bit_length,
+            self.max_ate_pairing_ate_loop_count,
+            self.max_ate_pairing_final_exp_w0_bit_length,
+            self.max_ate_pairing_final_exp_w1_bit_length,
+            self.max_cocks_pinch_final_exp_bit_length,
+        ];
+        for &bit_length in bit_lengths.iter() {
+            if bit_length == 0 || bit_length > max_loop_bits {
+                return Err(ApiError::UnknownParameter(format!("Loop parameter bit length limit must be between 1 and {}, got {}", max_loop_bits, bit_length)));
+            }
+        }
+        // |6u + 2| is computed in the same width as u
+        if self.max_bn_u_bit_length == 0 || self.max_bn_u_bit_length > max_loop_bits - 3 {
+            return Err(ApiError::UnknownParameter(format!("BN u bit length limit must be between 1 and {}", max_loop_bits - 3)));
+        }
+        if self.max_cocks_pinch_embedding_degree < MIN_COCKS_PINCH_EMBEDDING_DEGREE ||
+            self.max_cocks_pinch_embedding_degree > crate::extension_towers::fpk::MAX_BINOMIAL_EXTENSION_DEGREE {
+            return Err(ApiError::UnknownParameter(format!("Embedding degree limit must be between {} and {}", MIN_COCKS_PINCH_EMBEDDING_DEGREE, crate::extension_towers::fpk::MAX_BINOMIAL_EXTENSION_DEGREE)));
+        }
+        if self.max_isogeny_map_degree == 0 || self.max_isogeny_map_degree > u8::MAX as usize {
+            return Err(ApiError::UnknownParameter("Isogeny map degree limit must be between 1 and 255".to_owned()));
+        }
+
+        Ok(())
+    }
+}
 
-const_assert!(std::mem::size_of::<MaxLoopParametersUint>() >= MAX_LOOP_PARAMETERS_BYTE_LEN);
\ No newline at end of file
+impl Default for Limits {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
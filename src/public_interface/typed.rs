@@ -0,0 +1,742 @@
+//! Typed counterpart of the byte oriented API for in-process callers. Curve parameters,
+//! scalars and point coordinates are held as integers, and every call builds the field
+//! and the curve for the required number of limbs directly from them, so nothing is
+//! encoded into bytes and parsed back. Validation is the same as in `API::run`
+//!
+//! Point at infinity is represented as `(0, 0)`, same as in the byte encoding
+
+use crate::weierstrass::{Group, CurveParameters, CurveOverFpParameters, CurveOverFp2Parameters};
+use crate::weierstrass::curve::{WeierstrassCurve, CurvePoint, FixedBaseTable, batch_normalize};
+use crate::pairings::PairingEngine;
+use crate::pairings::calculate_hamming_weight;
+use crate::pairings::bls12::{Bls12Instance, Bls12InstanceParams};
+use crate::pairings::bn::{BnInstance, BnInstanceParams};
+use crate::representation::ElementRepr;
+use crate::traits::{FieldElement, ZeroAndOne};
+use crate::extension_towers::*;
+use crate::extension_towers::fp2::{Fp2, Extension2};
+use crate::extension_towers::fp6_as_3_over_2::{Fp6, Extension3Over2};
+use crate::extension_towers::fp12_as_2_over3_over_2::{Fp12, Extension2Over3Over2};
+use crate::multiexp::peppinger;
+use crate::execution_context::ExecutionContext;
+use crate::fp::Fp;
+use crate::field::*;
+use crate::integers::*;
+
+use super::decode_utils::num_limbs_for_modulus;
+use super::encoding::TwistType;
+use super::sane_limits::*;
+
+use crate::errors::{ApiError, ErrorCode};
+use crate::alloc_prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "serialization::G1AffineDescription", from = "serialization::G1AffineDescription"))]
+pub struct G1Affine {
+    pub x: MaxFieldUint,
+    pub y: MaxFieldUint,
+}
+
+impl G1Affine {
+    pub fn new(x: MaxFieldUint, y: MaxFieldUint) -> Self {
+        Self { x, y }
+    }
+
+    pub fn zero() -> Self {
+        Self::new(MaxFieldUint::zero(), MaxFieldUint::zero())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.x.is_zero() && self.y.is_zero()
+    }
+}
+
+/// Point on the quadratic twist, coordinates are `c0 + c1 * u`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "serialization::G2AffineDescription", from = "serialization::G2AffineDescription"))]
+pub struct G2Affine {
+    pub x: [MaxFieldUint; 2],
+    pub y: [MaxFieldUint; 2],
+}
+
+impl G2Affine {
+    pub fn new(x: [MaxFieldUint; 2], y: [MaxFieldUint; 2]) -> Self {
+        Self { x, y }
+    }
+
+    pub fn zero() -> Self {
+        Self::new([MaxFieldUint::zero(); 2], [MaxFieldUint::zero(); 2])
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.x.iter().chain(self.y.iter()).all(|c| c.is_zero())
+    }
+}
+
+/// Short Weierstrass curve `y^2 = x^3 + a*x + b` over the prime field
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "serialization::CurveDescription", try_from = "serialization::CurveDescription"))]
+pub struct Curve {
+    modulus: MaxFieldUint,
+    a: MaxFieldUint,
+    b: MaxFieldUint,
+    order: MaxGroupSizeUint,
+    modulus_limbs: usize,
+}
+
+impl Curve {
+    pub fn new(modulus: MaxFieldUint, a: MaxFieldUint, b: MaxFieldUint, order: MaxGroupSizeUint) -> Result<Self, ApiError> {
+        let modulus_limbs = num_limbs_for_modulus(&modulus)?;
+        if order.is_zero() {
+            return Err(ApiError::InputError(format!("Group order is zero, file {}, line {}", file!(), line!())));
+        }
+
+        let curve = Self {
+            modulus,
+            a,
+            b,
+            order,
+            modulus_limbs
+        };
+
+        let result: Result<(), ApiError> = expand_for_modulus_limbs!(modulus_limbs, TypedImplementation, &curve, validate_curve);
+        result?;
+
+        Ok(curve)
+    }
+
+    pub fn modulus(&self) -> &MaxFieldUint {
+        &self.modulus
+    }
+
+    pub fn order(&self) -> &MaxGroupSizeUint {
+        &self.order
+    }
+
+    /// Point must have coordinates in the field, but is not required to be on the curve
+    pub fn is_on_curve(&self, p: &G1Affine) -> Result<bool, ApiError> {
+        expand_for_modulus_limbs!(self.modulus_limbs, TypedImplementation, &(self, p), is_on_curve)
+    }
+
+    pub fn add(&self, p: &G1Affine, q: &G1Affine) -> Result<G1Affine, ApiError> {
+        expand_for_modulus_limbs!(self.modulus_limbs, TypedImplementation, &(self, p, q), add)
+    }
+
+    pub fn mul(&self, p: &G1Affine, scalar: &MaxGroupSizeUint) -> Result<G1Affine, ApiError> {
+        expand_for_modulus_limbs!(self.modulus_limbs, TypedImplementation, &(self, p, scalar), mul)
+    }
+
+    pub fn multiexp(&self, pairs: &[(G1Affine, MaxGroupSizeUint)]) -> Result<G1Affine, ApiError> {
+        if pairs.is_empty() {
+            return Err(ApiError::InputError(format!("Multiexp with empty input pairs, file {}, line {}", file!(), line!())));
+        }
+
+        expand_for_modulus_limbs!(self.modulus_limbs, TypedImplementation, &(self, pairs), multiexp)
+    }
+
+    /// Multiples of the same point, e.g. the generator, by every scalar. The comb table of the point
+    /// is computed once, so it's several times faster than `mul` for every scalar
+    pub fn mul_fixed_base(&self, p: &G1Affine, scalars: &[MaxGroupSizeUint]) -> Result<Vec<G1Affine>, ApiError> {
+        expand_for_modulus_limbs!(self.modulus_limbs, TypedImplementation, &(self, p, scalars), mul_fixed_base)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+enum PairingFamily {
+    Bls12,
+    Bn,
+}
+
+/// Pairing friendly curve with embedding degree 12 and a quadratic twist,
+/// built over `Fp2 = Fp[u]/(u^2 - fp_non_residue)` and `Fp6 = Fp2[v]/(v^3 - fp2_non_residue)`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "serialization::EngineDescription", try_from = "serialization::EngineDescription"))]
+pub struct Engine {
+    family: PairingFamily,
+    curve: Curve,
+    fp_non_residue: MaxFieldUint,
+    fp2_non_residue: [MaxFieldUint; 2],
+    twist_type: TwistType,
+    loop_parameter: MaxLoopParametersUint,
+    loop_parameter_is_negative: bool,
+}
+
+impl Engine {
+    pub fn bls12(
+        curve: Curve,
+        fp_non_residue: MaxFieldUint,
+        fp2_non_residue: [MaxFieldUint; 2],
+        twist_type: TwistType,
+        x: MaxLoopParametersUint,
+        x_is_negative: bool
+    ) -> Result<Self, ApiError> {
+        if !curve.a.is_zero() {
+            return Err(ApiError::UnknownParameter("A parameter must be zero for BLS12 curve".to_owned()));
+        }
+        if x.is_zero() {
+            return Err(ApiError::InputError("Loop count parameters can not be zero".to_owned()));
+        }
+        let limits = Limits::DEFAULT;
+        if x.bits() > limits.max_bls12_x_bit_length {
+            return Err(ApiError::InputError(format!("Loop parameter is too large for bit length, max {} bits, file {}, line {}", limits.max_bls12_x_bit_length, file!(), line!())));
+        }
+        if calculate_hamming_weight(&x.as_ref()) > limits.max_bls12_x_hamming {
+            return Err(ApiError::InputError("X has too large hamming weight".to_owned()));
+        }
+
+        Self::with_family(PairingFamily::Bls12, curve, fp_non_residue, fp2_non_residue, twist_type, x, x_is_negative)
+    }
+
+    /// `u` is the parameter of the curve family, the loop runs over `6u + 2`
+    pub fn bn(
+        curve: Curve,
+        fp_non_residue: MaxFieldUint,
+        fp2_non_residue: [MaxFieldUint; 2],
+        twist_type: TwistType,
+        u: MaxLoopParametersUint,
+        u_is_negative: bool
+    ) -> Result<Self, ApiError> {
+        if !curve.a.is_zero() {
+            return Err(ApiError::UnknownParameter("A parameter must be zero for BN curve".to_owned()));
+        }
+        if u.is_zero() {
+            return Err(ApiError::InputError("Loop count parameters can not be zero".to_owned()));
+        }
+        let limits = Limits::DEFAULT;
+        if u.bits() > limits.max_bn_u_bit_length {
+            return Err(ApiError::InputError(format!("Loop parameter is too large for bit length, max {} bits, file {}, line {}", limits.max_bn_u_bit_length, file!(), line!())));
+        }
+        if calculate_hamming_weight(&six_u_plus_two(&u, u_is_negative).as_ref()) > limits.max_bn_six_u_plus_two_hamming {
+            return Err(ApiError::InputError("|6*U + 2| has too large hamming weight".to_owned()));
+        }
+
+        Self::with_family(PairingFamily::Bn, curve, fp_non_residue, fp2_non_residue, twist_type, u, u_is_negative)
+    }
+
+    fn with_family(
+        family: PairingFamily,
+        curve: Curve,
+        fp_non_residue: MaxFieldUint,
+        fp2_non_residue: [MaxFieldUint; 2],
+        twist_type: TwistType,
+        loop_parameter: MaxLoopParametersUint,
+        loop_parameter_is_negative: bool
+    ) -> Result<Self, ApiError> {
+        let engine = Self {
+            family,
+            curve,
+            fp_non_residue,
+            fp2_non_residue,
+            twist_type,
+            loop_parameter,
+            loop_parameter_is_negative
+        };
+
+        // pairing of an empty set builds the towers and the twist, so it checks the parameters
+        engine.pairing(&[])?;
+
+        Ok(engine)
+    }
+
+    pub fn curve(&self) -> &Curve {
+        &self.curve
+    }
+
+    /// Returns whether the product of pairings of all pairs is equal to one. Points must be
+    /// on their curves and in the subgroup, pairs where either point is zero are skipped
+    pub fn pairing(&self, pairs: &[(G1Affine, G2Affine)]) -> Result<bool, ApiError> {
+        expand_for_modulus_limbs!(self.curve.modulus_limbs, TypedImplementation, &(self, pairs), pairing)
+    }
+}
+
+fn six_u_plus_two(u: &MaxLoopParametersUint, u_is_negative: bool) -> MaxLoopParametersUint {
+    let two = MaxLoopParametersUint::from(2u64);
+    let six = MaxLoopParametersUint::from(6u64);
+
+    // we need only absolute value of 6u+2, so manually handle negative and positive U
+    if u_is_negative {
+        (six * *u) - two
+    } else {
+        (six * *u) + two
+    }
+}
+
+fn fp_from_uint<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>(field: &'a F, value: &MaxFieldUint) -> Result<Fp<'a, FE, F>, ApiError> {
+    let limbs = value.as_ref();
+    if limbs[FE::NUM_LIMBS..].iter().any(|&l| l != 0) {
+        return Err(ApiError::with_code(ErrorCode::NotInField, "Failed to parse Fp element, value is larger than the modulus".to_owned()));
+    }
+    let mut repr = FE::default();
+    repr.as_mut().copy_from_slice(&limbs[..FE::NUM_LIMBS]);
+
+    Fp::from_repr(field, repr).map_err(|e| {
+        ApiError::with_code(ErrorCode::NotInField, format!("Failed to parse Fp element, {}", e))
+    })
+}
+
+fn fp_into_uint<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>(element: &Fp<'a, FE, F>) -> MaxFieldUint {
+    let mut limbs = [0u64; 16];
+    limbs[..FE::NUM_LIMBS].copy_from_slice(element.into_repr().as_ref());
+
+    MaxFieldUint::from_limbs(limbs)
+}
+
+fn fp2_from_uints<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>(extension: &'a Extension2<'a, FE, F>, value: &[MaxFieldUint; 2]) -> Result<Fp2<'a, FE, F>, ApiError> {
+    let mut element = Fp2::zero(extension);
+    element.c0 = fp_from_uint(extension.field, &value[0])?;
+    element.c1 = fp_from_uint(extension.field, &value[1])?;
+
+    Ok(element)
+}
+
+fn g1_from_affine<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>(
+    curve: &'a WeierstrassCurve<'a, CurveOverFpParameters<'a, FE, F>>,
+    p: &G1Affine
+) -> Result<CurvePoint<'a, CurveOverFpParameters<'a, FE, F>>, ApiError> {
+    let x = fp_from_uint(curve.params.params(), &p.x)?;
+    let y = fp_from_uint(curve.params.params(), &p.y)?;
+
+    Ok(CurvePoint::point_from_xy(curve, x, y))
+}
+
+fn g1_into_affine<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>(p: &CurvePoint<'a, CurveOverFpParameters<'a, FE, F>>) -> G1Affine {
+    let (x, y) = p.into_xy();
+
+    G1Affine::new(fp_into_uint(&x), fp_into_uint(&y))
+}
+
+fn checked_g1_from_affine<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>(
+    curve: &'a WeierstrassCurve<'a, CurveOverFpParameters<'a, FE, F>>,
+    p: &G1Affine
+) -> Result<CurvePoint<'a, CurveOverFpParameters<'a, FE, F>>, ApiError> {
+    let p = g1_from_affine(curve, p)?;
+    if !p.is_on_curve() {
+        return Err(ApiError::with_code(ErrorCode::NotOnCurve, format!("Point is not on curve, file {}, line {}", file!(), line!())));
+    }
+
+    Ok(p)
+}
+
+struct TypedImplementation<FE: ElementRepr> {
+    _marker_fe: core::marker::PhantomData<FE>,
+}
+
+impl<FE: ElementRepr> TypedImplementation<FE> {
+    fn base_field(curve: &Curve) -> Result<PrimeField<FE>, ApiError> {
+        field_from_modulus::<FE>(&curve.modulus).map_err(|_| {
+            ApiError::with_code(ErrorCode::InvalidModulus, "Failed to create prime field from modulus".to_owned())
+        })
+    }
+
+    fn validate_curve(curve: &Curve) -> Result<(), ApiError> {
+        let field = Self::base_field(curve)?;
+        let a = fp_from_uint(&field, &curve.a)?;
+        let b = fp_from_uint(&field, &curve.b)?;
+        let fp_params = CurveOverFpParameters::new(&field);
+        WeierstrassCurve::new(&curve.order.as_ref(), a, b, &fp_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        Ok(())
+    }
+
+    fn is_on_curve(input: &(&Curve, &G1Affine)) -> Result<bool, ApiError> {
+        let (curve, p) = *input;
+        let field = Self::base_field(curve)?;
+        let fp_params = CurveOverFpParameters::new(&field);
+        let curve = WeierstrassCurve::new(&curve.order.as_ref(), fp_from_uint(&field, &curve.a)?, fp_from_uint(&field, &curve.b)?, &fp_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        Ok(g1_from_affine(&curve, p)?.is_on_curve())
+    }
+
+    fn add(input: &(&Curve, &G1Affine, &G1Affine)) -> Result<G1Affine, ApiError> {
+        let (curve, p, q) = *input;
+        let field = Self::base_field(curve)?;
+        let fp_params = CurveOverFpParameters::new(&field);
+        let curve = WeierstrassCurve::new(&curve.order.as_ref(), fp_from_uint(&field, &curve.a)?, fp_from_uint(&field, &curve.b)?, &fp_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        let mut p = checked_g1_from_affine(&curve, p)?;
+        let q = checked_g1_from_affine(&curve, q)?;
+        p.add_assign(&q);
+
+        Ok(g1_into_affine(&p))
+    }
+
+    fn mul(input: &(&Curve, &G1Affine, &MaxGroupSizeUint)) -> Result<G1Affine, ApiError> {
+        let (curve, p, scalar) = *input;
+        let field = Self::base_field(curve)?;
+        let fp_params = CurveOverFpParameters::new(&field);
+        let curve = WeierstrassCurve::new(&curve.order.as_ref(), fp_from_uint(&field, &curve.a)?, fp_from_uint(&field, &curve.b)?, &fp_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        let p = checked_g1_from_affine(&curve, p)?;
+
+        Ok(g1_into_affine(&p.mul(scalar)))
+    }
+
+    fn multiexp(input: &(&Curve, &[(G1Affine, MaxGroupSizeUint)])) -> Result<G1Affine, ApiError> {
+        let (curve, pairs) = *input;
+        let field = Self::base_field(curve)?;
+        let fp_params = CurveOverFpParameters::new(&field);
+        let curve = WeierstrassCurve::new(&curve.order.as_ref(), fp_from_uint(&field, &curve.a)?, fp_from_uint(&field, &curve.b)?, &fp_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        let mut bases = Vec::with_capacity(pairs.len());
+        for (p, _) in pairs.iter() {
+            bases.push(checked_g1_from_affine(&curve, p)?);
+        }
+        let scalars = pairs.iter().map(|(_, s)| *s).collect();
+
+        Ok(g1_into_affine(&peppinger(&bases, scalars, &ExecutionContext::default())))
+    }
+
+    fn mul_fixed_base(input: &(&Curve, &G1Affine, &[MaxGroupSizeUint])) -> Result<Vec<G1Affine>, ApiError> {
+        let (curve, p, scalars) = *input;
+        let field = Self::base_field(curve)?;
+        let fp_params = CurveOverFpParameters::new(&field);
+        let curve = WeierstrassCurve::new(&curve.order.as_ref(), fp_from_uint(&field, &curve.a)?, fp_from_uint(&field, &curve.b)?, &fp_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        let p = checked_g1_from_affine(&curve, p)?;
+        let table = FixedBaseTable::new(&p, crate::representation::num_bits(curve.subgroup_order_repr) as usize);
+        let mut multiples: Vec<_> = scalars.iter().map(|s| table.mul(s)).collect();
+        batch_normalize(&mut multiples);
+
+        Ok(multiples.iter().map(g1_into_affine).collect())
+    }
+
+    fn pairing(input: &(&Engine, &[(G1Affine, G2Affine)])) -> Result<bool, ApiError> {
+        let (engine, pairs) = *input;
+        let modulus = engine.curve.modulus;
+        let base_field = Self::base_field(&engine.curve)?;
+        let b_fp = fp_from_uint(&base_field, &engine.curve.b)?;
+        let fp_params = CurveOverFpParameters::new(&base_field);
+        let g1_curve = WeierstrassCurve::new(&engine.curve.order.as_ref(), Fp::zero(&base_field), b_fp.clone(), &fp_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        let fp_non_residue = fp_from_uint(&base_field, &engine.fp_non_residue)?;
+        if fp_non_residue.is_zero() {
+            return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp2 is zero file {}, line {}", file!(), line!())));
+        }
+        if !is_non_nth_root(&fp_non_residue, &modulus, 2u64) {
+            return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp2 is actually a residue file {}, line {}", file!(), line!())));
+        }
+
+        let mut extension_2 = Extension2::new(fp_non_residue);
+        extension_2.calculate_frobenius_coeffs(&modulus).map_err(|_| {
+            ApiError::InputError("Failed to calculate Frobenius coeffs for Fp2".to_owned())
+        })?;
+
+        let fp2_non_residue = fp2_from_uints(&extension_2, &engine.fp2_non_residue)?;
+        if fp2_non_residue.is_zero() {
+            return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp6(12) is zero, file {}, line {}", file!(), line!())));
+        }
+        if !is_non_nth_root_fp2(&fp2_non_residue, &modulus, 6u64) {
+            return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp6(12) is actually a residue, file {}, line {}", file!(), line!())));
+        }
+        // Fp12 is built as a quadratic extension of Fp6, so the non-residue must also be a non-square in Fp2
+        if fp2::is_quadratic_residue(&fp2_non_residue) {
+            return Err(ApiError::with_code(ErrorCode::NonResidueInvalid, format!("Non-residue for Fp6(12) is a square in Fp2, file {}, line {}", file!(), line!())));
+        }
+
+        let base_precomp = Fp6Fp12FrobeniusBaseElements::construct(
+            &modulus,
+            &fp2_non_residue
+        ).map_err(|_| {
+            ApiError::UnknownParameter("Can not make base precomputations for Fp6/Fp12 frobenius".to_owned())
+        })?;
+
+        let mut extension_6 = Extension3Over2::new(fp2_non_residue.clone());
+        extension_6.calculate_frobenius_coeffs_with_precomp(&base_precomp).map_err(|_| {
+            ApiError::UnknownParameter("Can not calculate Frobenius coefficients for Fp6".to_owned())
+        })?;
+
+        let mut extension_12 = Extension2Over3Over2::new(Fp6::zero(&extension_6));
+        extension_12.calculate_frobenius_coeffs_with_precomp(&base_precomp).map_err(|_| {
+            ApiError::InputError("Can not calculate Frobenius coefficients for Fp12".to_owned())
+        })?;
+
+        let twist_type = match engine.twist_type {
+            TwistType::M => crate::pairings::TwistType::M,
+            TwistType::D => crate::pairings::TwistType::D,
+        };
+
+        let mut b_fp2 = match twist_type {
+            crate::pairings::TwistType::D => {
+                fp2_non_residue.inverse().ok_or(ApiError::UnexpectedZero("Fp2 non-residue must be invertible".to_owned()))?
+            },
+            crate::pairings::TwistType::M => fp2_non_residue.clone(),
+        };
+        b_fp2.mul_by_fp(&b_fp);
+
+        let fp2_params = CurveOverFp2Parameters::new(&extension_2);
+        let g2_curve = WeierstrassCurve::new(&engine.curve.order.as_ref(), Fp2::zero(&extension_2), b_fp2, &fp2_params).map_err(|_| {
+            ApiError::InputError("Curve shape is not supported".to_owned())
+        })?;
+
+        let mut g1_points = Vec::with_capacity(pairs.len());
+        let mut g2_points = Vec::with_capacity(pairs.len());
+        for (g1, g2) in pairs.iter() {
+            let g1 = checked_g1_from_affine(&g1_curve, g1)?;
+            let g2 = CurvePoint::point_from_xy(&g2_curve, fp2_from_uints(&extension_2, &g2.x)?, fp2_from_uints(&extension_2, &g2.y)?);
+            if !g2.is_on_curve() {
+                return Err(ApiError::with_code(ErrorCode::NotOnCurve, "G2 point is not on curve".to_owned()));
+            }
+            if !g1.check_correct_subgroup() || !g2.check_correct_subgroup() {
+                return Err(ApiError::with_code(ErrorCode::NotInSubgroup, "G1 or G2 point is not in the expected subgroup".to_owned()));
+            }
+            if !g1.is_zero() && !g2.is_zero() {
+                g1_points.push(g1);
+                g2_points.push(g2);
+            }
+        }
+
+        if g1_points.is_empty() {
+            return Ok(true);
+        }
+
+        let loop_parameter = engine.loop_parameter;
+        let six_u_plus_two = six_u_plus_two(&loop_parameter, engine.loop_parameter_is_negative);
+        let pairing_result = match engine.family {
+            PairingFamily::Bls12 => {
+                let engine_params = Bls12InstanceParams {
+                    x: &loop_parameter.as_ref(),
+                    x_is_negative: engine.loop_parameter_is_negative,
+                    twist_type,
+                    base_field: &base_field,
+                    curve: &g1_curve,
+                    curve_twist: &g2_curve,
+                    fp2_extension: &extension_2,
+                    fp6_extension: &extension_6,
+                    fp12_extension: &extension_12,
+                    force_no_naf: true
+                };
+
+                Bls12Instance::from_params(engine_params).pair(&g1_points, &g2_points)
+            },
+            PairingFamily::Bn => {
+                let p_minus_one_over_2 = (modulus - MaxFieldUint::from(1u64)) >> 1;
+
+                let engine_params = BnInstanceParams {
+                    u: &loop_parameter.as_ref(),
+                    six_u_plus_2: &six_u_plus_two.as_ref(),
+                    u_is_negative: engine.loop_parameter_is_negative,
+                    twist_type,
+                    base_field: &base_field,
+                    curve: &g1_curve,
+                    curve_twist: &g2_curve,
+                    fp2_extension: &extension_2,
+                    fp6_extension: &extension_6,
+                    fp12_extension: &extension_12,
+                    non_residue_in_p_minus_one_over_2: fp2_non_residue.pow(p_minus_one_over_2.as_ref()),
+                    force_no_naf: true
+                };
+
+                BnInstance::from_params(engine_params).pair(&g1_points, &g2_points)
+            }
+        };
+
+        let pairing_result = pairing_result.ok_or(ApiError::UnknownParameter("Pairing engine returned no value".to_owned()))?;
+
+        Ok(pairing_result == Fp12::one(&extension_12))
+    }
+}
+
+/// Serialized form of the typed API objects. Integers are big endian hex strings with `0x` prefix,
+/// same as in the JSON test vectors. Curves and engines are validated when deserialized
+#[cfg(feature = "serde")]
+mod serialization {
+    use super::*;
+    use core::convert::TryFrom;
+    use serde::{Serialize, Deserialize, Serializer, Deserializer};
+
+    trait BigEndianUint: Sized {
+        const BYTE_LEN: usize;
+
+        fn to_be_bytes(&self) -> Vec<u8>;
+        fn from_be_bytes(bytes: &[u8]) -> Self;
+    }
+
+    macro_rules! impl_big_endian_uint {
+        ($name: ident, $n_words: expr) => {
+            impl BigEndianUint for $name {
+                const BYTE_LEN: usize = $n_words * 8;
+
+                fn to_be_bytes(&self) -> Vec<u8> {
+                    let mut bytes = vec![0u8; Self::BYTE_LEN];
+                    self.to_big_endian(&mut bytes);
+
+                    bytes
+                }
+
+                fn from_be_bytes(bytes: &[u8]) -> Self {
+                    $name::from_big_endian(bytes)
+                }
+            }
+        }
+    }
+
+    impl_big_endian_uint!(MaxFieldUint, 16);
+    impl_big_endian_uint!(MaxGroupSizeUint, 16);
+    impl_big_endian_uint!(MaxLoopParametersUint, 32);
+
+    #[derive(Clone, Copy)]
+    pub(super) struct Hex<T>(T);
+
+    impl<T: BigEndianUint> Serialize for Hex<T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let digits: String = self.0.to_be_bytes().iter().map(|b| format!("{:02x}", b)).collect();
+            let digits = digits.trim_start_matches('0');
+            let digits = if digits.is_empty() { "0" } else { digits };
+
+            serializer.serialize_str(&format!("0x{}", digits))
+        }
+    }
+
+    impl<'de, T: BigEndianUint> Deserialize<'de> for Hex<T> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            use serde::de::Error;
+
+            let string = String::deserialize(deserializer)?;
+            let unprefixed = string.strip_prefix("0x").unwrap_or(&string);
+            if unprefixed.is_empty() {
+                return Err(D::Error::custom("empty hex integer"));
+            }
+            let digits = unprefixed.trim_start_matches('0');
+            if digits.len() > T::BYTE_LEN * 2 {
+                return Err(D::Error::custom(format!("integer {} is too large", string)));
+            }
+            // odd number of digits is padded from the left
+            let padded_digits = if digits.len() % 2 == 1 { format!("0{}", digits) } else { digits.to_owned() };
+            let mut bytes = vec![0u8; padded_digits.len() / 2];
+            for (b, pair) in bytes.iter_mut().zip(padded_digits.as_bytes().chunks(2)) {
+                let pair = core::str::from_utf8(pair).map_err(D::Error::custom)?;
+                *b = u8::from_str_radix(pair, 16).map_err(|_| D::Error::custom(format!("{} is not a hex integer", string)))?;
+            }
+
+            Ok(Hex(T::from_be_bytes(&bytes)))
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct G1AffineDescription {
+        x: Hex<MaxFieldUint>,
+        y: Hex<MaxFieldUint>,
+    }
+
+    impl From<G1Affine> for G1AffineDescription {
+        fn from(p: G1Affine) -> Self {
+            Self { x: Hex(p.x), y: Hex(p.y) }
+        }
+    }
+
+    impl From<G1AffineDescription> for G1Affine {
+        fn from(p: G1AffineDescription) -> Self {
+            Self::new(p.x.0, p.y.0)
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct G2AffineDescription {
+        x: [Hex<MaxFieldUint>; 2],
+        y: [Hex<MaxFieldUint>; 2],
+    }
+
+    impl From<G2Affine> for G2AffineDescription {
+        fn from(p: G2Affine) -> Self {
+            Self { x: [Hex(p.x[0]), Hex(p.x[1])], y: [Hex(p.y[0]), Hex(p.y[1])] }
+        }
+    }
+
+    impl From<G2AffineDescription> for G2Affine {
+        fn from(p: G2AffineDescription) -> Self {
+            Self::new([p.x[0].0, p.x[1].0], [p.y[0].0, p.y[1].0])
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct CurveDescription {
+        modulus: Hex<MaxFieldUint>,
+        a: Hex<MaxFieldUint>,
+        b: Hex<MaxFieldUint>,
+        order: Hex<MaxGroupSizeUint>,
+    }
+
+    impl From<Curve> for CurveDescription {
+        fn from(curve: Curve) -> Self {
+            Self { 
+                modulus: Hex(curve.modulus), 
+                a: Hex(curve.a), 
+                b: Hex(curve.b), 
+                order: Hex(curve.order) 
+            }
+        }
+    }
+
+    impl TryFrom<CurveDescription> for Curve {
+        type Error = ApiError;
+
+        fn try_from(curve: CurveDescription) -> Result<Self, ApiError> {
+            Self::new(curve.modulus.0, curve.a.0, curve.b.0, curve.order.0)
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct EngineDescription {
+        family: PairingFamily,
+        curve: Curve,
+        fp_non_residue: Hex<MaxFieldUint>,
+        fp2_non_residue: [Hex<MaxFieldUint>; 2],
+        twist_type: TwistType,
+        loop_parameter: Hex<MaxLoopParametersUint>,
+        loop_parameter_is_negative: bool,
+    }
+
+    impl From<Engine> for EngineDescription {
+        fn from(engine: Engine) -> Self {
+            Self {
+                family: engine.family,
+                curve: engine.curve,
+                fp_non_residue: Hex(engine.fp_non_residue),
+                fp2_non_residue: [Hex(engine.fp2_non_residue[0]), Hex(engine.fp2_non_residue[1])],
+                twist_type: engine.twist_type,
+                loop_parameter: Hex(engine.loop_parameter),
+                loop_parameter_is_negative: engine.loop_parameter_is_negative,
+            }
+        }
+    }
+
+    impl TryFrom<EngineDescription> for Engine {
+        type Error = ApiError;
+
+        fn try_from(engine: EngineDescription) -> Result<Self, ApiError> {
+            let constructor = match engine.family {
+                PairingFamily::Bls12 => Engine::bls12,
+                PairingFamily::Bn => Engine::bn,
+            };
+
+            constructor(
+                engine.curve,
+                engine.fp_non_residue.0,
+                [engine.fp2_non_residue[0].0, engine.fp2_non_residue[1].0],
+                engine.twist_type,
+                engine.loop_parameter.0,
+                engine.loop_parameter_is_negative
+            )
+        }
+    }
+}
@@ -3,13 +3,20 @@ use crate::public_interface::g1_ops::{G1Api, PublicG1Api};
 use crate::public_interface::g2_ops::{G2Api, PublicG2Api};
 
 use crate::errors::ApiError;
+use crate::execution_context::ExecutionContext;
+use crate::alloc_prelude::*;
 
 // For C style API caller has to preallocate some buffers for results 
 pub const PREALLOCATE_FOR_ERROR_BYTES: usize = 256;
+#[cfg(not(any(feature = "limbs-24", feature = "limbs-32")))]
 pub const PREALLOCATE_FOR_RESULT_BYTES: usize = 768;
+#[cfg(all(feature = "limbs-24", not(feature = "limbs-32")))]
+pub const PREALLOCATE_FOR_RESULT_BYTES: usize = 1152;
+#[cfg(feature = "limbs-32")]
+pub const PREALLOCATE_FOR_RESULT_BYTES: usize = 1530;
 
 use static_assertions::const_assert;
-const_assert!(PREALLOCATE_FOR_RESULT_BYTES == crate::public_interface::constants::MAX_MODULUS_BYTE_LEN * 3 * 2);
+const_assert!(PREALLOCATE_FOR_RESULT_BYTES >= crate::public_interface::constants::MAX_MODULUS_BYTE_LEN * 3 * 2);
 
 #[repr(u8)]
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -85,26 +92,29 @@ pub const MNT6PAIR_OPERATION_RAW_VALUE: u8 = OperationType::MNT6PAIR as u8;
 
 // This is pure rust API
 pub fn perform_operation(operation: OperationType, input: &[u8]) -> Result<Vec<u8>, ApiError> {
-    assert!(!crate::features::in_gas_metering(), "must be run only in production mode");
+    if crate::features::in_gas_metering() {
+        return Err(ApiError::UnknownParameter("Operations must be run only in production mode".to_owned()));
+    }
 
+    let ctx = ExecutionContext::default();
     match operation {
         OperationType::G1ADD => {
-            PublicG1Api::add_points(&input)
+            PublicG1Api::add_points(&input, &ctx)
         },
         OperationType::G1MUL => {
-            PublicG1Api::mul_point(&input)
+            PublicG1Api::mul_point(&input, &ctx)
         },
         OperationType::G1MULTIEXP => {
-            PublicG1Api::multiexp(&input)
+            PublicG1Api::multiexp(&input, &ctx)
         },
         OperationType::G2ADD => {
-            PublicG2Api::add_points(&input)
+            PublicG2Api::add_points(&input, &ctx)
         },
         OperationType::G2MUL => {
-            PublicG2Api::mul_point(&input)
+            PublicG2Api::mul_point(&input, &ctx)
         },
         OperationType::G2MULTIEXP => {
-            PublicG2Api::multiexp(&input)
+            PublicG2Api::multiexp(&input, &ctx)
         },
         OperationType::BLS12PAIR | OperationType::BNPAIR | OperationType::MNT4PAIR | OperationType::MNT6PAIR => {
             use crate::field::*;
@@ -119,28 +129,28 @@ pub fn perform_operation(operation: OperationType, input: &[u8]) -> Result<Vec<u
 
             match operation {
                 OperationType::BLS12PAIR => {
-                    let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, PairingApiImplementation, input, pair_bls12); 
+                    let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, PairingApiImplementation, input, pair_bls12, &ctx); 
 
                     result
                 },
                 OperationType::BNPAIR => {
-                    let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, PairingApiImplementation, input, pair_bn); 
+                    let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, PairingApiImplementation, input, pair_bn, &ctx); 
 
                     result
                 },
                 OperationType::MNT4PAIR => {
-                    let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, PairingApiImplementation, input, pair_mnt4); 
+                    let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, PairingApiImplementation, input, pair_mnt4, &ctx); 
 
                     result
                 },
                 OperationType::MNT6PAIR => {
-                    let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, PairingApiImplementation, input, pair_mnt6); 
+                    let result: Result<Vec<u8>, ApiError> = expand_for_modulus_limbs!(modulus_limbs, PairingApiImplementation, input, pair_mnt6, &ctx); 
 
                     result
                 },
 
                 _ => {
-                    unreachable!()
+                    Err(ApiError::UnknownParameter("Unknown pairing operation".to_owned()))
                 }
             }
         }
@@ -0,0 +1,333 @@
+//! Python bindings, so curves constructed in Sage or Python can be executed with this exact
+//! implementation. Build and install into the current environment with
+//!
+//! `maturin develop --release`, `pyproject.toml` enables the `python` feature
+//!
+//! Numbers can be given as Python integers (or anything with `__index__`, e.g. Sage integers)
+//! or as big endian `bytes`. Builders are the same as in `public_interface::encoding` and
+//! return the full input for `run_input`, starting with the operation type. Failures raise
+//! `ApiError` with the `ErrorCode` name, the description and the offset in the input or `None`.
+
+use pyo3::prelude::*;
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::types::PyBytes;
+
+use crate::errors;
+use crate::public_interface::API;
+use crate::public_interface::encoding;
+
+create_exception!(eth_pairings, ApiError, PyException);
+
+fn into_py_error(error: errors::ApiError, input_len: usize) -> PyErr {
+    ApiError::new_err((error.code().name(), error.to_string(), error.offset(input_len)))
+}
+
+fn into_value_error(error: errors::ApiError) -> PyErr {
+    PyValueError::new_err(error.to_string())
+}
+
+fn big_endian(value: &Bound<'_, PyAny>) -> PyResult<Vec<u8>> {
+    if let Ok(bytes) = value.downcast::<PyBytes>() {
+        return Ok(bytes.as_bytes().to_vec());
+    }
+    let value = value.call_method0("__index__")?;
+    let bit_length: usize = value.call_method0("bit_length")?.extract()?;
+    let bytes = value.call_method1("to_bytes", (bit_length.div_ceil(8), "big")).map_err(|_| PyValueError::new_err("Numbers must be non-negative"))?;
+
+    bytes.extract()
+}
+
+fn big_endian_list(values: &Bound<'_, PyAny>) -> PyResult<Vec<Vec<u8>>> {
+    values.try_iter()?.map(|v| big_endian(&v?)).collect()
+}
+
+fn as_slices(values: &[Vec<u8>]) -> Vec<&[u8]> {
+    values.iter().map(|v| &v[..]).collect()
+}
+
+/// `None` is the point at infinity, otherwise a pair `(x, y)`
+fn g1_point(point: &Bound<'_, PyAny>) -> PyResult<encoding::G1Point> {
+    if point.is_none() {
+        return Ok(encoding::G1Point::zero());
+    }
+    let (x, y): (Bound<'_, PyAny>, Bound<'_, PyAny>) = point.extract()?;
+
+    Ok(encoding::G1Point::new(&big_endian(&x)?, &big_endian(&y)?))
+}
+
+/// `None` is the point at infinity, otherwise a pair `(x, y)` of coefficient lists
+fn g2_point(point: &Bound<'_, PyAny>) -> PyResult<encoding::G2Point> {
+    if point.is_none() {
+        return Ok(encoding::G2Point::zero());
+    }
+    let (x, y): (Bound<'_, PyAny>, Bound<'_, PyAny>) = point.extract()?;
+    let (x, y) = (big_endian_list(&x)?, big_endian_list(&y)?);
+
+    Ok(encoding::G2Point::new(&as_slices(&x), &as_slices(&y)))
+}
+
+fn scalar_pairs<P>(pairs: &Bound<'_, PyAny>, point: impl Fn(&Bound<'_, PyAny>) -> PyResult<P>) -> PyResult<Vec<(P, Vec<u8>)>> {
+    pairs.try_iter()?.map(|pair| {
+        let (p, scalar): (Bound<'_, PyAny>, Bound<'_, PyAny>) = pair?.extract()?;
+
+        Ok((point(&p)?, big_endian(&scalar)?))
+    }).collect()
+}
+
+/// Runs `calldata` as the operation with the given type, same as `API::run` for the
+/// operation byte followed by `calldata`
+#[pyfunction]
+fn run<'py>(py: Python<'py>, operation: u8, calldata: &[u8]) -> PyResult<Bound<'py, PyBytes>> {
+    let mut input = vec![operation];
+    input.extend_from_slice(calldata);
+
+    run_input(py, &input)
+}
+
+/// Runs the full input that starts with the operation type, e.g. produced by the builders
+#[pyfunction]
+fn run_input<'py>(py: Python<'py>, input: &[u8]) -> PyResult<Bound<'py, PyBytes>> {
+    let output = py.allow_threads(|| API::run(input)).map_err(|e| into_py_error(e, input.len()))?;
+
+    Ok(PyBytes::new(py, &output))
+}
+
+/// Gas for the same arguments as `run`
+#[pyfunction]
+fn estimate_gas(operation: u8, calldata: &[u8]) -> PyResult<u64> {
+    let mut input = vec![operation];
+    input.extend_from_slice(calldata);
+
+    API::estimate_gas(&input).map_err(|e| into_py_error(e, input.len()))
+}
+
+#[pyclass(name = "G1Curve", module = "eth_pairings")]
+#[derive(Clone)]
+struct PyG1Curve(encoding::G1CurveDescriptor);
+
+#[pymethods]
+impl PyG1Curve {
+    #[new]
+    fn new(modulus: &Bound<'_, PyAny>, a: &Bound<'_, PyAny>, b: &Bound<'_, PyAny>, group_order: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let descriptor = encoding::G1CurveDescriptor::new(&big_endian(modulus)?, &big_endian(a)?, &big_endian(b)?, &big_endian(group_order)?).map_err(into_value_error)?;
+
+        Ok(Self(descriptor))
+    }
+
+    fn add<'py>(&self, py: Python<'py>, p0: &Bound<'py, PyAny>, p1: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyBytes>> {
+        let call = self.0.add(&g1_point(p0)?, &g1_point(p1)?).map_err(into_value_error)?;
+
+        Ok(PyBytes::new(py, &call))
+    }
+
+    fn mul<'py>(&self, py: Python<'py>, point: &Bound<'py, PyAny>, scalar: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyBytes>> {
+        let call = self.0.mul(&g1_point(point)?, &big_endian(scalar)?).map_err(into_value_error)?;
+
+        Ok(PyBytes::new(py, &call))
+    }
+
+    /// `pairs` is an iterable of `(point, scalar)`
+    fn multiexp<'py>(&self, py: Python<'py>, pairs: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyBytes>> {
+        let call = self.0.multiexp(&scalar_pairs(pairs, g1_point)?).map_err(into_value_error)?;
+
+        Ok(PyBytes::new(py, &call))
+    }
+}
+
+#[pyclass(name = "G2Curve", module = "eth_pairings")]
+struct PyG2Curve(encoding::G2CurveDescriptor);
+
+#[pymethods]
+impl PyG2Curve {
+    /// `a` and `b` are lists of `extension_degree` coefficients
+    #[new]
+    fn new(
+        modulus: &Bound<'_, PyAny>,
+        extension_degree: usize,
+        non_residue: &Bound<'_, PyAny>,
+        a: &Bound<'_, PyAny>,
+        b: &Bound<'_, PyAny>,
+        group_order: &Bound<'_, PyAny>
+    ) -> PyResult<Self> {
+        let (a, b) = (big_endian_list(a)?, big_endian_list(b)?);
+        let descriptor = encoding::G2CurveDescriptor::new(
+            &big_endian(modulus)?,
+            extension_degree,
+            &big_endian(non_residue)?,
+            &as_slices(&a),
+            &as_slices(&b),
+            &big_endian(group_order)?
+        ).map_err(into_value_error)?;
+
+        Ok(Self(descriptor))
+    }
+
+    fn add<'py>(&self, py: Python<'py>, p0: &Bound<'py, PyAny>, p1: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyBytes>> {
+        let call = self.0.add(&g2_point(p0)?, &g2_point(p1)?).map_err(into_value_error)?;
+
+        Ok(PyBytes::new(py, &call))
+    }
+
+    fn mul<'py>(&self, py: Python<'py>, point: &Bound<'py, PyAny>, scalar: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyBytes>> {
+        let call = self.0.mul(&g2_point(point)?, &big_endian(scalar)?).map_err(into_value_error)?;
+
+        Ok(PyBytes::new(py, &call))
+    }
+
+    /// `pairs` is an iterable of `(point, scalar)`
+    fn multiexp<'py>(&self, py: Python<'py>, pairs: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyBytes>> {
+        let call = self.0.multiexp(&scalar_pairs(pairs, g2_point)?).map_err(into_value_error)?;
+
+        Ok(PyBytes::new(py, &call))
+    }
+}
+
+fn twist_type(value: &str) -> PyResult<encoding::TwistType> {
+    match value {
+        "M" => Ok(encoding::TwistType::M),
+        "D" => Ok(encoding::TwistType::D),
+        _ => Err(PyValueError::new_err("Twist type must be \"M\" or \"D\"")),
+    }
+}
+
+#[pyclass(name = "PairingCall", module = "eth_pairings")]
+struct PyPairingCall(encoding::PairingCall);
+
+#[pymethods]
+impl PyPairingCall {
+    /// `fp6_non_residue` is a pair of coefficients in Fp2, `twist_type` is `"M"` or `"D"`
+    #[staticmethod]
+    fn bls12(
+        g1: &PyG1Curve,
+        fp2_non_residue: &Bound<'_, PyAny>,
+        fp6_non_residue: &Bound<'_, PyAny>,
+        twist: &str,
+        x: &Bound<'_, PyAny>,
+        x_is_negative: bool
+    ) -> PyResult<Self> {
+        let fp6_non_residue = big_endian_list(fp6_non_residue)?;
+        if fp6_non_residue.len() != 2 {
+            return Err(PyValueError::new_err("Non-residue for Fp6 must have 2 coefficients"));
+        }
+        let call = encoding::PairingCall::bls12(
+            g1.0.clone(),
+            &big_endian(fp2_non_residue)?,
+            [&fp6_non_residue[0], &fp6_non_residue[1]],
+            twist_type(twist)?,
+            &big_endian(x)?,
+            x_is_negative
+        ).map_err(into_value_error)?;
+
+        Ok(Self(call))
+    }
+
+    /// Same as `bls12`, `u` is the curve parameter
+    #[staticmethod]
+    fn bn(
+        g1: &PyG1Curve,
+        fp2_non_residue: &Bound<'_, PyAny>,
+        fp6_non_residue: &Bound<'_, PyAny>,
+        twist: &str,
+        u: &Bound<'_, PyAny>,
+        u_is_negative: bool
+    ) -> PyResult<Self> {
+        let fp6_non_residue = big_endian_list(fp6_non_residue)?;
+        if fp6_non_residue.len() != 2 {
+            return Err(PyValueError::new_err("Non-residue for Fp6 must have 2 coefficients"));
+        }
+        let call = encoding::PairingCall::bn(
+            g1.0.clone(),
+            &big_endian(fp2_non_residue)?,
+            [&fp6_non_residue[0], &fp6_non_residue[1]],
+            twist_type(twist)?,
+            &big_endian(u)?,
+            u_is_negative
+        ).map_err(into_value_error)?;
+
+        Ok(Self(call))
+    }
+
+    #[staticmethod]
+    fn mnt4(
+        g1: &PyG1Curve,
+        fp2_non_residue: &Bound<'_, PyAny>,
+        x: &Bound<'_, PyAny>,
+        x_is_negative: bool,
+        exp_w0: &Bound<'_, PyAny>,
+        exp_w1: &Bound<'_, PyAny>,
+        exp_w0_is_negative: bool
+    ) -> PyResult<Self> {
+        let call = encoding::PairingCall::mnt4(
+            g1.0.clone(),
+            &big_endian(fp2_non_residue)?,
+            &big_endian(x)?,
+            x_is_negative,
+            &big_endian(exp_w0)?,
+            &big_endian(exp_w1)?,
+            exp_w0_is_negative
+        ).map_err(into_value_error)?;
+
+        Ok(Self(call))
+    }
+
+    #[staticmethod]
+    fn mnt6(
+        g1: &PyG1Curve,
+        fp3_non_residue: &Bound<'_, PyAny>,
+        x: &Bound<'_, PyAny>,
+        x_is_negative: bool,
+        exp_w0: &Bound<'_, PyAny>,
+        exp_w1: &Bound<'_, PyAny>,
+        exp_w0_is_negative: bool
+    ) -> PyResult<Self> {
+        let call = encoding::PairingCall::mnt6(
+            g1.0.clone(),
+            &big_endian(fp3_non_residue)?,
+            &big_endian(x)?,
+            x_is_negative,
+            &big_endian(exp_w0)?,
+            &big_endian(exp_w1)?,
+            exp_w0_is_negative
+        ).map_err(into_value_error)?;
+
+        Ok(Self(call))
+    }
+
+    #[pyo3(signature = (g1, g2, check_subgroup = true))]
+    fn add_pair(&mut self, g1: &Bound<'_, PyAny>, g2: &Bound<'_, PyAny>, check_subgroup: bool) -> PyResult<()> {
+        self.0.add_pair(g1_point(g1)?, g2_point(g2)?, check_subgroup);
+
+        Ok(())
+    }
+
+    #[pyo3(signature = (g1, g2, is_negative, check_subgroup = true))]
+    fn add_pair_with_sign(&mut self, g1: &Bound<'_, PyAny>, g2: &Bound<'_, PyAny>, is_negative: bool, check_subgroup: bool) -> PyResult<()> {
+        self.0.add_pair_with_sign(g1_point(g1)?, g2_point(g2)?, check_subgroup, is_negative);
+
+        Ok(())
+    }
+
+    fn miller_loop_only(&mut self) {
+        self.0.miller_loop_only();
+    }
+
+    fn encode<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        let call = self.0.encode().map_err(into_value_error)?;
+
+        Ok(PyBytes::new(py, &call))
+    }
+}
+
+#[pymodule]
+fn eth_pairings(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(run, m)?)?;
+    m.add_function(wrap_pyfunction!(run_input, m)?)?;
+    m.add_function(wrap_pyfunction!(estimate_gas, m)?)?;
+    m.add_class::<PyG1Curve>()?;
+    m.add_class::<PyG2Curve>()?;
+    m.add_class::<PyPairingCall>()?;
+    m.add("ApiError", m.py().get_type::<ApiError>())?;
+
+    Ok(())
+}
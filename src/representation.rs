@@ -1,7 +1,9 @@
 use byteorder;
-use std::fmt;
-use std::error::Error;
+use byteorder::ByteOrder;
+use core::fmt;
+#[cfg(feature = "std")]
 use std::io::{self, Read, Write};
+use crate::alloc_prelude::*;
 
 /// This trait represents a wrapper around a biginteger which can encode any element of a particular
 /// prime field. It is a smart wrapper around a sequence of `u64` limbs, least-significant digit
@@ -58,6 +60,7 @@ pub trait ElementRepr:
     fn shl(&mut self, amt: u32);
 
     /// Writes this `PrimeFieldRepr` as a big endian integer.
+    #[cfg(feature = "std")]
     fn write_be<W: Write>(&self, mut writer: W) -> io::Result<()> {
         use byteorder::{BigEndian, WriteBytesExt};
 
@@ -69,6 +72,7 @@ pub trait ElementRepr:
     }
 
     /// Reads a big endian integer into this representation.
+    #[cfg(feature = "std")]
     fn read_be<R: Read>(&mut self, mut reader: R) -> io::Result<()> {
         use byteorder::{BigEndian, ReadBytesExt};
 
@@ -80,6 +84,7 @@ pub trait ElementRepr:
     }
 
     /// Writes this `PrimeFieldRepr` as a little endian integer.
+    #[cfg(feature = "std")]
     fn write_le<W: Write>(&self, mut writer: W) -> io::Result<()> {
         use byteorder::{LittleEndian, WriteBytesExt};
 
@@ -91,6 +96,7 @@ pub trait ElementRepr:
     }
 
     /// Reads a little endian integer into this representation.
+    #[cfg(feature = "std")]
     fn read_le<R: Read>(&mut self, mut reader: R) -> io::Result<()> {
         use byteorder::{LittleEndian, ReadBytesExt};
 
@@ -101,12 +107,86 @@ pub trait ElementRepr:
         Ok(())
     }
 
+    /// Writes this `PrimeFieldRepr` as a big endian integer into the beginning
+    /// of the slice, same as `write_be` but does not need `std::io`.
+    fn write_be_to_slice(&self, bytes: &mut [u8]) -> Result<(), SliceTooShortError> {
+        let limbs = self.as_ref();
+        if bytes.len() < limbs.len() * 8 {
+            return Err(SliceTooShortError);
+        }
+        for (digit, chunk) in limbs.iter().rev().zip(bytes.chunks_exact_mut(8)) {
+            byteorder::BigEndian::write_u64(chunk, *digit);
+        }
+
+        Ok(())
+    }
+
+    /// Reads a big endian integer from the beginning of the slice, same as `read_be`
+    /// but does not need `std::io`.
+    fn read_be_from_slice(&mut self, bytes: &[u8]) -> Result<(), SliceTooShortError> {
+        let limbs = self.as_mut();
+        if bytes.len() < limbs.len() * 8 {
+            return Err(SliceTooShortError);
+        }
+        for (digit, chunk) in limbs.iter_mut().rev().zip(bytes.chunks_exact(8)) {
+            *digit = byteorder::BigEndian::read_u64(chunk);
+        }
+
+        Ok(())
+    }
+
+    /// Reads a big endian integer occupying the whole slice, that can be shorter than the
+    /// representation, without padding it into a temporary buffer. Leading bytes beyond the
+    /// representation length must be zero
+    fn read_be_from_slice_of_any_len(&mut self, bytes: &[u8]) -> Result<(), SliceTooShortError> {
+        let limbs = self.as_mut();
+        let byte_len = limbs.len() * 8;
+        let start = bytes.len().saturating_sub(byte_len);
+        if bytes[..start].iter().any(|&b| b != 0u8) {
+            return Err(SliceTooShortError);
+        }
+        for digit in limbs.iter_mut() {
+            *digit = 0u64;
+        }
+        for (i, &b) in bytes[start..].iter().rev().enumerate() {
+            limbs[i / 8] |= (b as u64) << ((i % 8) * 8);
+        }
+
+        Ok(())
+    }
+
+    /// Writes this representation as a big endian integer occupying the whole slice, that can
+    /// be shorter than the representation if the value fits into it
+    fn write_be_to_slice_of_any_len(&self, bytes: &mut [u8]) -> Result<(), SliceTooShortError> {
+        let limbs = self.as_ref();
+        for (i, b) in bytes.iter_mut().rev().enumerate() {
+            *b = if i / 8 < limbs.len() {
+                (limbs[i / 8] >> ((i % 8) * 8)) as u8
+            } else {
+                0u8
+            };
+        }
+        let byte_len = limbs.len() * 8;
+        for i in bytes.len()..byte_len {
+            if (limbs[i / 8] >> ((i % 8) * 8)) as u8 != 0u8 {
+                return Err(SliceTooShortError);
+            }
+        }
+
+        Ok(())
+    }
+
     // these two functions are mixing a representation and (Montgommery) form,
     // but it's a necessary evil
     fn mont_mul_assign(&mut self, other: &Self, modulus: &Self, mont_inv: u64);
     fn mont_square(&mut self, modulus: &Self, mont_inv: u64);
     fn mont_mul_assign_with_partial_reduction(&mut self, other: &Self, modulus: &Self, mont_inv: u64);
     fn mont_square_with_partial_reduction(&mut self, modulus: &Self, mont_inv: u64);
+    /// Double width product without reduction as the low and the high halves
+    fn mul_wide(&self, other: &Self) -> (Self, Self);
+    /// Montgomery reduction of a double width value below `modulus * 2^(64 * NUM_LIMBS)`,
+    /// `self` is the low half and `hi` is the high half. Result is fully reduced
+    fn mont_reduce_wide(&mut self, hi: &Self, modulus: &Self, mont_inv: u64);
     fn into_normal_repr(&self, modulus: &Self, mont_inv: u64) -> Self;
     fn reduce(&mut self, modulus: &Self);
 }
@@ -123,7 +203,8 @@ pub enum RepresentationDecodingError {
     NotInField(String),
 }
 
-impl Error for RepresentationDecodingError {
+#[cfg(feature = "std")]
+impl std::error::Error for RepresentationDecodingError {
     fn description(&self) -> &str {
         match *self {
             RepresentationDecodingError::NotInField(..) => "not an element of the field",
@@ -141,6 +222,16 @@ impl fmt::Display for RepresentationDecodingError {
     }
 }
 
+/// Slice is shorter than the encoding of the representation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SliceTooShortError;
+
+impl fmt::Display for SliceTooShortError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "failed to fill whole buffer")
+    }
+}
+
 pub(crate) fn num_bits(repr: &[u64]) -> u32 {
     let mut bits = (64 * repr.len()) as u32;
     for limb in repr.iter().rev() {
@@ -157,6 +248,26 @@ pub(crate) fn num_bits(repr: &[u64]) -> u32 {
     bits
 }
 
+/// Compares two words without branching on them, for choices of `conditional_select_repr`
+#[inline(always)]
+pub(crate) fn constant_time_eq(a: u64, b: u64) -> bool {
+    let difference = a ^ b;
+
+    ((difference | difference.wrapping_neg()) >> 63) == 0
+}
+
+/// Returns `b` if `choice` is set and `a` otherwise, by masking limbs instead of branching
+#[inline(always)]
+pub(crate) fn conditional_select_repr<E: ElementRepr>(a: &E, b: &E, choice: bool) -> E {
+    let mask = 0u64.wrapping_sub(choice as u64);
+    let mut result = *a;
+    for ((r, a), b) in result.as_mut().iter_mut().zip(a.as_ref().iter()).zip(b.as_ref().iter()) {
+        *r = (a & !mask) | (b & mask);
+    }
+
+    result
+}
+
 pub(crate) fn right_shift_representation(repr: &mut [u64], shift: u64) {
     let num_libs = repr.len();
     for i in 0..(num_libs - 1) {
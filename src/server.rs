@@ -0,0 +1,200 @@
+//! JSON-RPC 2.0 over HTTP, so that implementations in other languages can use this one as an
+//! oracle during cross-client testing. Requests are `POST`ed to any path, batches are supported.
+//!
+//! - `eip1962_run` with params `[input]` returns the output of `API::run` as hex
+//! - `eip1962_estimateGas` with params `[input]` returns the price from `API::estimate_gas` as
+//!   a hex quantity, same as `eth_estimateGas`
+//!
+//! Inputs are hex with `0x` prefix and start with the operation type. Failed calls are
+//! reported as errors with code `-32000`, and the `data` has the `ErrorCode` name as `error`,
+//! the `offset` in the input if the error is attached to a position and the `description`.
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::errors::ApiError;
+use crate::public_interface::API;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const EXECUTION_ERROR: i64 = -32000;
+
+// largest inputs are a few kilobytes, so this leaves room for big batches
+const MAX_BODY_LEN: usize = 1 << 20;
+
+#[derive(Deserialize)]
+struct Request {
+    jsonrpc: String,
+    method: String,
+    #[serde(default)]
+    params: Value,
+    #[serde(default)]
+    id: Value,
+}
+
+struct RpcError {
+    code: i64,
+    message: String,
+    data: Option<Value>,
+}
+
+impl RpcError {
+    fn new(code: i64, message: &str) -> Self {
+        Self {
+            code,
+            message: message.to_owned(),
+            data: None,
+        }
+    }
+
+    fn from_api_error(error: ApiError, input_len: usize) -> Self {
+        let mut data = json!({
+            "error": error.code().name(),
+            "description": error.to_string(),
+        });
+        if let Some(offset) = error.offset(input_len) {
+            data["offset"] = json!(offset);
+        }
+
+        Self {
+            code: EXECUTION_ERROR,
+            message: "Execution failed".to_owned(),
+            data: Some(data),
+        }
+    }
+
+    fn into_response(self, id: Value) -> Value {
+        let mut error = json!({
+            "code": self.code,
+            "message": self.message,
+        });
+        if let Some(data) = self.data {
+            error["data"] = data;
+        }
+
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": error,
+        })
+    }
+}
+
+fn from_hex(string: &str) -> Option<Vec<u8>> {
+    hex::decode(string.strip_prefix("0x")?).ok()
+}
+
+fn input_from_params(params: &Value) -> Result<Vec<u8>, RpcError> {
+    let input = match params.as_array().map(|p| p.as_slice()) {
+        Some([Value::String(input)]) => input,
+        _ => return Err(RpcError::new(INVALID_PARAMS, "Expected a single hex string as params")),
+    };
+
+    from_hex(input).ok_or_else(|| RpcError::new(INVALID_PARAMS, "Input is not a hex string with 0x prefix"))
+}
+
+fn call(method: &str, params: &Value) -> Result<Value, RpcError> {
+    match method {
+        "eip1962_run" => {
+            let input = input_from_params(params)?;
+            let output = API::run(&input).map_err(|e| RpcError::from_api_error(e, input.len()))?;
+
+            Ok(json!(format!("0x{}", hex::encode(&output))))
+        },
+        "eip1962_estimateGas" => {
+            let input = input_from_params(params)?;
+            let gas = API::estimate_gas(&input).map_err(|e| RpcError::from_api_error(e, input.len()))?;
+
+            Ok(json!(format!("0x{:x}", gas)))
+        },
+        _ => Err(RpcError::new(METHOD_NOT_FOUND, "Method not found")),
+    }
+}
+
+fn handle_single(request: Value) -> Value {
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let request: Request = match serde_json::from_value(request) {
+        Ok(request) => request,
+        Err(_) => return RpcError::new(INVALID_REQUEST, "Invalid request").into_response(id),
+    };
+    if request.jsonrpc != "2.0" {
+        return RpcError::new(INVALID_REQUEST, "Only JSON-RPC 2.0 is supported").into_response(request.id);
+    }
+
+    match call(&request.method, &request.params) {
+        Ok(result) => json!({
+            "jsonrpc": "2.0",
+            "id": request.id,
+            "result": result,
+        }),
+        Err(e) => e.into_response(request.id),
+    }
+}
+
+/// Handles the body of a single request or a batch and returns the body of the response.
+/// Notifications are answered as well, since the callers are test harnesses
+pub fn handle(body: &str) -> String {
+    let response = match serde_json::from_str::<Value>(body) {
+        Ok(Value::Array(requests)) if !requests.is_empty() => {
+            Value::Array(requests.into_iter().map(handle_single).collect())
+        },
+        Ok(Value::Array(_)) => RpcError::new(INVALID_REQUEST, "Empty batch").into_response(Value::Null),
+        Ok(request) => handle_single(request),
+        Err(_) => RpcError::new(PARSE_ERROR, "Parse error").into_response(Value::Null),
+    };
+
+    response.to_string()
+}
+
+fn respond(mut request: tiny_http::Request) {
+    use std::io::Read;
+    use tiny_http::{Header, Method, Response};
+
+    let content_type = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).expect("header is valid");
+    if *request.method() != Method::Post {
+        let _ = request.respond(Response::from_string("Only POST is supported").with_status_code(405));
+        return;
+    }
+    if request.body_length().is_some_and(|len| len > MAX_BODY_LEN) {
+        let _ = request.respond(Response::from_string("Body is too large").with_status_code(413));
+        return;
+    }
+    // the declared length is optional, so the read is capped as well
+    let mut body = String::new();
+    let read = request.as_reader().take(MAX_BODY_LEN as u64 + 1).read_to_string(&mut body);
+    if read.is_err() {
+        let _ = request.respond(Response::from_string("Body is not valid UTF-8").with_status_code(400));
+        return;
+    }
+    if body.len() > MAX_BODY_LEN {
+        let _ = request.respond(Response::from_string("Body is too large").with_status_code(413));
+        return;
+    }
+    let _ = request.respond(Response::from_string(handle(&body)).with_header(content_type));
+}
+
+/// Listens on `address`, e.g. `127.0.0.1:1962`, and serves requests from a fixed number of
+/// worker threads, one per available core. Only returns if the address can't be bound
+pub fn serve(address: &str) -> std::io::Result<()> {
+    use std::io::Error;
+    use std::sync::Arc;
+    use tiny_http::Server;
+
+    let server = Arc::new(Server::http(address).map_err(Error::other)?);
+    let num_workers = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    let workers: Vec<_> = (0..num_workers).map(|_| {
+        let server = Arc::clone(&server);
+        std::thread::spawn(move || {
+            for request in server.incoming_requests() {
+                respond(request);
+            }
+        })
+    }).collect();
+    for worker in workers {
+        let _ = worker.join();
+    }
+
+    Ok(())
+}
@@ -0,0 +1,284 @@
+//! Lane-parallel Montgomery multiplication of independent field elements with AVX2 (x86_64) or
+//! NEON (aarch64). Limbs are split into 32 bit digits that are kept in 64 bit vector lanes, so
+//! `LANES` products are computed at once with 32x32 -> 64 bit vector multiplications. AVX2
+//! support is detected once at runtime, NEON is always present on aarch64. Callers fall back
+//! to `mont_mul_assign` for the widths outside of 4 to 8 limbs, the tail of a batch and CPUs
+//! without the extension. Results are fully reduced, same as `mont_mul_assign`
+
+use crate::representation::ElementRepr;
+
+pub(crate) const LANES: usize = 4;
+pub(crate) const MIN_LIMBS: usize = 4;
+pub(crate) const MAX_LIMBS: usize = 8;
+
+const DIGIT_MASK: u64 = 0xffffffff;
+
+#[cfg(target_arch = "x86_64")]
+mod backend {
+    use core::arch::x86_64::*;
+    use core::sync::atomic::{AtomicU8, Ordering};
+    use super::{LANES, DIGIT_MASK};
+
+    const UNKNOWN: u8 = 0;
+    const UNSUPPORTED: u8 = 1;
+    const SUPPORTED: u8 = 2;
+
+    static SUPPORT: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+    #[inline(always)]
+    pub(super) fn is_available() -> bool {
+        match SUPPORT.load(Ordering::Relaxed) {
+            SUPPORTED => true,
+            UNSUPPORTED => false,
+            _ => {
+                let supported = std::is_x86_feature_detected!("avx2");
+                SUPPORT.store(if supported { SUPPORTED } else { UNSUPPORTED }, Ordering::Relaxed);
+
+                supported
+            }
+        }
+    }
+
+    /// Coarsely integrated operand scanning over `D` digits, `x` is overwritten by
+    /// `x * y * 2^(-32 * D)`. Every digit is below 2^32, so `t + a * b + carry` always fits
+    /// into a lane
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn mont_mul<const D: usize>(x: &mut [[u64; LANES]; D], y: &[[u64; LANES]; D], modulus: &[u64; D], mont_inv: u64) {
+        let mask = _mm256_set1_epi64x(DIGIT_MASK as i64);
+        let inv = _mm256_set1_epi64x(mont_inv as i64);
+
+        let mut a = [_mm256_setzero_si256(); D];
+        let mut p = [_mm256_setzero_si256(); D];
+        for j in 0..D {
+            a[j] = _mm256_loadu_si256(x[j].as_ptr() as *const __m256i);
+            p[j] = _mm256_set1_epi64x(modulus[j] as i64);
+        }
+
+        let mut t = [_mm256_setzero_si256(); D];
+        let mut t_top = _mm256_setzero_si256();
+
+        for i in 0..D {
+            let b = _mm256_loadu_si256(y[i].as_ptr() as *const __m256i);
+
+            let mut carry = _mm256_setzero_si256();
+            for j in 0..D {
+                let s = _mm256_add_epi64(_mm256_add_epi64(t[j], _mm256_mul_epu32(a[j], b)), carry);
+                t[j] = _mm256_and_si256(s, mask);
+                carry = _mm256_srli_epi64(s, 32);
+            }
+            let s = _mm256_add_epi64(t_top, carry);
+            t_top = _mm256_and_si256(s, mask);
+            let t_hi = _mm256_srli_epi64(s, 32);
+
+            // only the low 32 bits of the product are used by the multiplication below
+            let k = _mm256_mul_epu32(t[0], inv);
+            let s = _mm256_add_epi64(t[0], _mm256_mul_epu32(k, p[0]));
+            let mut carry = _mm256_srli_epi64(s, 32);
+            for j in 1..D {
+                let s = _mm256_add_epi64(_mm256_add_epi64(t[j], _mm256_mul_epu32(k, p[j])), carry);
+                t[j - 1] = _mm256_and_si256(s, mask);
+                carry = _mm256_srli_epi64(s, 32);
+            }
+            let s = _mm256_add_epi64(t_top, carry);
+            t[D - 1] = _mm256_and_si256(s, mask);
+            t_top = _mm256_add_epi64(t_hi, _mm256_srli_epi64(s, 32));
+        }
+
+        if cfg!(debug_assertions) {
+            let mut top = [0u64; LANES];
+            _mm256_storeu_si256(top.as_mut_ptr() as *mut __m256i, t_top);
+            debug_assert!(top.iter().all(|&w| w == 0));
+        }
+
+        for j in 0..D {
+            _mm256_storeu_si256(x[j].as_mut_ptr() as *mut __m256i, t[j]);
+        }
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod backend {
+    use core::arch::aarch64::*;
+    use super::{LANES, DIGIT_MASK};
+
+    #[inline(always)]
+    pub(super) fn is_available() -> bool {
+        true
+    }
+
+    /// Same as the AVX2 version, a 128 bit register holds two lanes, so lanes are processed
+    /// in halves
+    pub(super) unsafe fn mont_mul<const D: usize>(x: &mut [[u64; LANES]; D], y: &[[u64; LANES]; D], modulus: &[u64; D], mont_inv: u64) {
+        let mask = vdupq_n_u64(DIGIT_MASK);
+        let inv = vdup_n_u32(mont_inv as u32);
+
+        for half in 0..(LANES / 2) {
+            let offset = half * 2;
+
+            let mut a = [vdup_n_u32(0); D];
+            let mut p = [vdup_n_u32(0); D];
+            for j in 0..D {
+                a[j] = vmovn_u64(vld1q_u64(x[j][offset..].as_ptr()));
+                p[j] = vdup_n_u32(modulus[j] as u32);
+            }
+
+            let mut t = [vdupq_n_u64(0); D];
+            let mut t_top = vdupq_n_u64(0);
+
+            for i in 0..D {
+                let b = vmovn_u64(vld1q_u64(y[i][offset..].as_ptr()));
+
+                let mut carry = vdupq_n_u64(0);
+                for j in 0..D {
+                    let s = vaddq_u64(vaddq_u64(t[j], vmull_u32(a[j], b)), carry);
+                    t[j] = vandq_u64(s, mask);
+                    carry = vshrq_n_u64::<32>(s);
+                }
+                let s = vaddq_u64(t_top, carry);
+                t_top = vandq_u64(s, mask);
+                let t_hi = vshrq_n_u64::<32>(s);
+
+                let k = vmovn_u64(vmull_u32(vmovn_u64(t[0]), inv));
+                let s = vaddq_u64(t[0], vmull_u32(k, p[0]));
+                let mut carry = vshrq_n_u64::<32>(s);
+                for j in 1..D {
+                    let s = vaddq_u64(vaddq_u64(t[j], vmull_u32(k, p[j])), carry);
+                    t[j - 1] = vandq_u64(s, mask);
+                    carry = vshrq_n_u64::<32>(s);
+                }
+                let s = vaddq_u64(t_top, carry);
+                t[D - 1] = vandq_u64(s, mask);
+                t_top = vaddq_u64(t_hi, vshrq_n_u64::<32>(s));
+            }
+
+            debug_assert!(vgetq_lane_u64::<0>(t_top) == 0 && vgetq_lane_u64::<1>(t_top) == 0);
+
+            for j in 0..D {
+                vst1q_u64(x[j][offset..].as_mut_ptr(), t[j]);
+            }
+        }
+    }
+}
+
+/// Whether `mont_mul_lanes` can be used for elements of type `E` on this CPU
+#[inline(always)]
+pub(crate) fn supports<E: ElementRepr>() -> bool {
+    E::NUM_LIMBS >= MIN_LIMBS && E::NUM_LIMBS <= MAX_LIMBS && backend::is_available()
+}
+
+/// Multiplies `a[i]` by `b[i]` in Montgomery form for every lane. Callers must check
+/// `supports::<E>()` first
+pub(crate) fn mont_mul_lanes<E: ElementRepr>(a: &mut [E; LANES], b: &[E; LANES], modulus: &E, mont_inv: u64) {
+    debug_assert!(supports::<E>());
+    match E::NUM_LIMBS {
+        4 => mont_mul_with_digits::<E, 8>(a, b, modulus, mont_inv),
+        5 => mont_mul_with_digits::<E, 10>(a, b, modulus, mont_inv),
+        6 => mont_mul_with_digits::<E, 12>(a, b, modulus, mont_inv),
+        7 => mont_mul_with_digits::<E, 14>(a, b, modulus, mont_inv),
+        8 => mont_mul_with_digits::<E, 16>(a, b, modulus, mont_inv),
+        _ => unreachable!(),
+    }
+}
+
+#[inline(always)]
+fn mont_mul_with_digits<E: ElementRepr, const D: usize>(a: &mut [E; LANES], b: &[E; LANES], modulus: &E, mont_inv: u64) {
+    let mut x = [[0u64; LANES]; D];
+    let mut y = [[0u64; LANES]; D];
+    for lane in 0..LANES {
+        split_into_digits(a[lane].as_ref(), lane, &mut x);
+        split_into_digits(b[lane].as_ref(), lane, &mut y);
+    }
+
+    let mut p = [0u64; D];
+    for (i, limb) in modulus.as_ref().iter().enumerate() {
+        p[2 * i] = limb & DIGIT_MASK;
+        p[2 * i + 1] = limb >> 32;
+    }
+
+    // -p^-1 mod 2^64 truncated to the digit size is -p^-1 mod 2^32
+    unsafe { backend::mont_mul(&mut x, &y, &p, mont_inv & DIGIT_MASK) };
+
+    for lane in 0..LANES {
+        for (i, limb) in a[lane].as_mut().iter_mut().enumerate() {
+            *limb = x[2 * i][lane] | (x[2 * i + 1][lane] << 32);
+        }
+        if a[lane] >= *modulus {
+            a[lane].sub_noborrow(modulus);
+        }
+    }
+}
+
+#[inline(always)]
+fn split_into_digits<const D: usize>(limbs: &[u64], lane: usize, digits: &mut [[u64; LANES]; D]) {
+    for (i, limb) in limbs.iter().enumerate() {
+        digits[2 * i][lane] = limb & DIGIT_MASK;
+        digits[2 * i + 1][lane] = limb >> 32;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::representation::ElementRepr;
+    use crate::field::{U256Repr, U320Repr, U384Repr, U448Repr, U512Repr};
+    use rand::{Rng, SeedableRng};
+    use rand_xorshift::XorShiftRng;
+
+    fn random_repr<E: ElementRepr, R: Rng>(rng: &mut R) -> E {
+        let mut repr = E::default();
+        for limb in repr.as_mut().iter_mut() {
+            *limb = rng.gen();
+        }
+
+        repr
+    }
+
+    fn check_for_repr<E: ElementRepr>(rng: &mut XorShiftRng) {
+        for _ in 0..100 {
+            let mut modulus: E = random_repr(rng);
+            modulus.as_mut()[0] |= 1;
+            modulus.as_mut()[E::NUM_LIMBS - 1] >>= 1;
+
+            let mut inv = 1u64;
+            for _ in 0..63 {
+                inv = inv.wrapping_mul(inv);
+                inv = inv.wrapping_mul(modulus.as_ref()[0]);
+            }
+            inv = inv.wrapping_neg();
+
+            let mut a = [E::default(); super::LANES];
+            let mut b = [E::default(); super::LANES];
+            for (a, b) in a.iter_mut().zip(b.iter_mut()) {
+                *a = random_repr(rng);
+                while *a >= modulus {
+                    a.div2();
+                }
+                *b = random_repr(rng);
+                while *b >= modulus {
+                    b.div2();
+                }
+            }
+
+            let mut expected = a;
+            for (e, b) in expected.iter_mut().zip(b.iter()) {
+                e.mont_mul_assign(b, &modulus, inv);
+            }
+
+            super::mont_mul_lanes(&mut a, &b, &modulus, inv);
+            assert_eq!(a, expected);
+        }
+    }
+
+    #[test]
+    fn test_mont_mul_lanes_against_scalar() {
+        if !super::backend::is_available() {
+            return;
+        }
+
+        let rng = &mut XorShiftRng::from_seed([0x3c, 0x1f, 0x72, 0x09, 0xa4, 0x5e, 0x66, 0xd1, 0x0b, 0x8f, 0x24, 0x97, 0x3a, 0xc5, 0x71, 0xe8]);
+        check_for_repr::<U256Repr>(rng);
+        check_for_repr::<U320Repr>(rng);
+        check_for_repr::<U384Repr>(rng);
+        check_for_repr::<U448Repr>(rng);
+        check_for_repr::<U512Repr>(rng);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::traits::FieldElement;
 use crate::traits::LsbBitIterator;
+use crate::alloc_prelude::*;
 
 #[allow(dead_code)]
 pub(crate) fn calculate_window_table<F: FieldElement>(base: &F, window: usize) -> Vec<F> {
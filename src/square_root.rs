@@ -1,9 +1,11 @@
 use crate::fp::Fp;
 use crate::representation::ElementRepr;
 use crate::field::*;
-use crate::extension_towers::fp2::{Extension2, Fp2};
+use crate::extension_towers::fp2::{Extension2, Fp2, NonResidueMulPolicy};
+use crate::extension_towers::fp3::Fp3;
 use crate::traits::FieldElement;
 use crate::traits::ZeroAndOne;
+use crate::alloc_prelude::*;
 
 pub(crate) fn modulus_is_one_mod_four<E: ElementRepr, F: SizedPrimeField<Repr = E>>(field: &F) -> bool {
     const MASK: u64 = 3; // last two bits
@@ -69,12 +71,98 @@ pub fn legendre_symbol_fp2<'a, E: ElementRepr, F: SizedPrimeField<Repr = E>>(ele
     legendre_symbol_fp(&a)
 }
 
-fn sqrt_for_one_mod_four<'a, E: ElementRepr, F: SizedPrimeField<Repr = E>>(_element: &Fp<'a, E, F>) -> Option<Fp<'a, E, F>> {
-    // TODO, or consider to slow
-    
+// for a prime modulus the smallest quadratic non-residue is tiny, so the limit
+// only stops the search for composite moduli
+const MAX_NON_RESIDUE_CANDIDATE: u64 = 1000;
+
+fn find_quadratic_non_residue<'a, E: ElementRepr, F: SizedPrimeField<Repr = E>>(field: &'a F) -> Option<Fp<'a, E, F>> {
+    for candidate in 2..MAX_NON_RESIDUE_CANDIDATE {
+        let candidate = Fp::from_repr(field, E::from(candidate)).ok()?;
+        if legendre_symbol_fp(&candidate) == LegendreSymbol::QuadraticNonResidue {
+            return Some(candidate);
+        }
+    }
+
+    None
+}
+
+/// Searches for a quadratic non-residue of the form k + u in Fp2, e.g. to choose
+/// the non-residue of an Fp4 or Fp12 tower over it
+pub(crate) fn find_quadratic_non_residue_ext2<'a, E: ElementRepr, F: SizedPrimeField<Repr = E>>(extension: &'a Extension2<'a, E, F>) -> Option<Fp2<'a, E, F>> {
+    for candidate in 0..MAX_NON_RESIDUE_CANDIDATE {
+        let mut candidate_fp2 = Fp2::zero(extension);
+        candidate_fp2.c0 = Fp::from_repr(extension.field, E::from(candidate)).ok()?;
+        candidate_fp2.c1 = Fp::one(extension.field);
+        if !crate::extension_towers::fp2::is_quadratic_residue(&candidate_fp2) {
+            return Some(candidate_fp2);
+        }
+    }
+
     None
 }
 
+/// Tonelli-Shanks: with p - 1 = q*2^s the candidate a^((q + 1)/2) is corrected
+/// by powers of z^q for a non-residue z in at most s steps
+fn sqrt_for_one_mod_four<'a, E: ElementRepr, F: SizedPrimeField<Repr = E>>(element: &Fp<'a, E, F>) -> Option<Fp<'a, E, F>> {
+    if element.is_zero() {
+        return Some(element.clone());
+    }
+
+    let one = Fp::one(element.field);
+
+    let mut q = *element.field.modulus();
+    q.sub_noborrow(&E::from(1));
+    let mut s = 0u32;
+    while q.is_even() {
+        q.div2();
+        s += 1;
+    }
+
+    let z = find_quadratic_non_residue(element.field)?;
+
+    let mut q_plus_one_by_two = q;
+    q_plus_one_by_two.add_nocarry(&E::from(1));
+    q_plus_one_by_two.div2();
+
+    let mut c = z.pow(q.as_ref());
+    let mut t = element.pow(q.as_ref());
+    let mut r = element.pow(q_plus_one_by_two.as_ref());
+    let mut m = s;
+
+    while t != one {
+        // least i such that t^(2^i) == 1, it's always below m for squares
+        let mut i = 0u32;
+        let mut t_power = t.clone();
+        while t_power != one {
+            t_power.square();
+            i += 1;
+            if i == m {
+                return None;
+            }
+        }
+
+        let mut b = c.clone();
+        for _ in 0..(m - i - 1) {
+            b.square();
+        }
+
+        m = i;
+        c = b.clone();
+        c.square();
+        t.mul_assign(&c);
+        r.mul_assign(&b);
+    }
+
+    // modulus is not checked for primality
+    let mut check = r.clone();
+    check.square();
+    if &check == element {
+        Some(r)
+    } else {
+        None
+    }
+}
+
 pub fn sqrt_for_three_mod_four<'a, E: ElementRepr, F: SizedPrimeField<Repr = E>>(element: &Fp<'a, E, F>) -> Option<Fp<'a, E, F>> {
     // this is a simple case: we compute the power 
     // we know that it's 3 mod 4, so just bit shift
@@ -100,30 +188,13 @@ pub fn sqrt_for_three_mod_four<'a, E: ElementRepr, F: SizedPrimeField<Repr = E>>
     }
 }
 
-fn sqrt_for_one_mod_sixteen<'a, E: ElementRepr, F: SizedPrimeField<Repr = E>>(element: &Fp<'a, E, F>) -> Option<Fp<'a, E, F>> {
-    // Requires to know a generator and roots of unity
-    // postpone for now
-    // we know that it's 1 mod 16, so we can use simple bit shifts
-
-    match legendre_symbol_fp(&element) {
-        LegendreSymbol::Zero => {
-            // it's zero, so clone
-            Some(element.clone())
-        },
-        LegendreSymbol::QuadraticNonResidue => {
-            None
-        },
-        LegendreSymbol::QuadraticResidue => {
-            None
-        }
-    }
-}
-
+/// Square root in the base field. An exponentiation is enough for p = 3 mod 4,
+/// Tonelli-Shanks is used otherwise
 pub fn sqrt<'a, E: ElementRepr, F: SizedPrimeField<Repr = E>>(element: &Fp<'a, E, F>) -> Option<Fp<'a, E, F>> {
     if modulus_is_three_mod_four(element.field) {
         sqrt_for_three_mod_four(&element)
     } else {
-        None
+        sqrt_for_one_mod_four(&element)
     }
 }
 
@@ -175,10 +246,160 @@ pub(crate) fn sqrt_for_three_mod_four_ext2<'a, E: ElementRepr, F: SizedPrimeFiel
     }
 }
 
+/// Complex method: for a = a0 + a1*u with u^2 = β a root x0 + x1*u satisfies
+/// x0^2 + β*x1^2 = a0 and 2*x0*x1 = a1, so x0^2 = (a0 ± sqrt(norm(a)))/2. Works for any
+/// modulus where the base field square root is available
+pub(crate) fn sqrt_ext2_complex_method<'a, E: ElementRepr, F: SizedPrimeField<Repr = E>>(element: &Fp2<'a, E, F>) -> Option<Fp2<'a, E, F>> {
+    if element.is_zero() {
+        return Some(element.clone());
+    }
+
+    let field = element.extension_field.field;
+    let mut result = Fp2::zero(element.extension_field);
+
+    if element.c1.is_zero() {
+        // either a0 is a square in the base field, or a0/β is
+        if let Some(root) = sqrt(&element.c0) {
+            result.c0 = root;
+        } else {
+            let mut t = element.extension_field.non_residue.inverse()?;
+            t.mul_assign(&element.c0);
+            result.c1 = sqrt(&t)?;
+        }
+    } else {
+        let alpha = sqrt(&element.norm())?;
+
+        let mut two = Fp::one(field);
+        two.double();
+        let two_inv = two.inverse()?;
+
+        let mut delta = element.c0.clone();
+        delta.add_assign(&alpha);
+        delta.mul_assign(&two_inv);
+        let x0 = match sqrt(&delta) {
+            Some(x0) => x0,
+            None => {
+                let mut delta = element.c0.clone();
+                delta.sub_assign(&alpha);
+                delta.mul_assign(&two_inv);
+
+                sqrt(&delta)?
+            }
+        };
+
+        // x0 != 0 as a1 != 0
+        let mut x1 = x0.clone();
+        x1.double();
+        let mut x1 = x1.inverse()?;
+        x1.mul_assign(&element.c1);
+
+        result.c0 = x0;
+        result.c1 = x1;
+    }
+
+    let mut check = result.clone();
+    check.square();
+    if &check == element {
+        Some(result)
+    } else {
+        None
+    }
+}
+
+/// Square root in Fp2. The specialized algorithm from Adj and Rodríguez-Henríquez
+/// is used for p = 3 mod 4 and u^2 = -1, the complex method otherwise
 pub fn sqrt_ext2<'a, E: ElementRepr, F: SizedPrimeField<Repr = E>>(element: &Fp2<'a, E, F>) -> Option<Fp2<'a, E, F>> {
-    if modulus_is_three_mod_four_ext2(element.extension_field) {
+    if modulus_is_three_mod_four_ext2(element.extension_field) && element.extension_field.non_residue_mul_policy == NonResidueMulPolicy::Negate {
         sqrt_for_three_mod_four_ext2(&element)
+    } else {
+        sqrt_ext2_complex_method(&element)
+    }
+}
+
+fn mul_limbs(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let mut result = vec![0u64; a.len() + b.len()];
+    for (i, &a_limb) in a.iter().enumerate() {
+        let mut carry = 0u128;
+        for (j, &b_limb) in b.iter().enumerate() {
+            let tmp = (a_limb as u128) * (b_limb as u128) + (result[i + j] as u128) + carry;
+            result[i + j] = tmp as u64;
+            carry = tmp >> 64;
+        }
+        result[i + b.len()] = carry as u64;
+    }
+
+    result
+}
+
+/// Tonelli-Shanks in Fp3 with p^3 - 1 = q*2^s. Since p^2 + p + 1 is odd the 2-adicity is the same as
+/// in the base field, and a non-residue of the base field stays a non-residue in the extension of odd degree
+pub fn sqrt_ext3<'a, E: ElementRepr, F: SizedPrimeField<Repr = E>>(element: &Fp3<'a, E, F>) -> Option<Fp3<'a, E, F>> {
+    use crate::representation::right_shift_representation;
+
+    if element.is_zero() {
+        return Some(*element);
+    }
+
+    let extension_field = element.extension_field;
+    let one = Fp3::one(extension_field);
+
+    // p^3 is odd, so subtracting one doesn't borrow
+    let modulus = extension_field.field.modulus().as_ref();
+    let mut q = mul_limbs(&mul_limbs(modulus, modulus), modulus);
+    q[0] -= 1;
+    let mut s = 0u32;
+    while q[0] & 1 == 0 {
+        right_shift_representation(&mut q, 1);
+        s += 1;
+    }
+
+    let mut z = Fp3::zero(extension_field);
+    z.c0 = find_quadratic_non_residue(extension_field.field)?;
+
+    // q is odd and the product has a spare top limb, so adding one doesn't overflow
+    let mut q_plus_one_by_two = q.clone();
+    for limb in q_plus_one_by_two.iter_mut() {
+        let (sum, overflow) = limb.overflowing_add(1);
+        *limb = sum;
+        if !overflow {
+            break;
+        }
+    }
+    right_shift_representation(&mut q_plus_one_by_two, 1);
+
+    let mut c = z.pow(&q);
+    let mut t = element.pow(&q);
+    let mut r = element.pow(&q_plus_one_by_two);
+    let mut m = s;
+
+    while t != one {
+        let mut i = 0u32;
+        let mut t_power = t;
+        while t_power != one {
+            t_power.square();
+            i += 1;
+            if i == m {
+                return None;
+            }
+        }
+
+        let mut b = c;
+        for _ in 0..(m - i - 1) {
+            b.square();
+        }
+
+        m = i;
+        c = b;
+        c.square();
+        t.mul_assign(&c);
+        r.mul_assign(&b);
+    }
+
+    let mut check = r;
+    check.square();
+    if &check == element {
+        Some(r)
     } else {
         None
     }
-}
\ No newline at end of file
+}
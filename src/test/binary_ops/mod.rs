@@ -0,0 +1,273 @@
+use crate::public_interface::constants::*;
+use crate::public_interface::ExecutionContext;
+use crate::public_interface::{BinaryCurveApi, PublicBinaryCurveApi, API};
+
+use num_bigint::BigUint;
+use num_traits::Num;
+
+use crate::test::parsers::*;
+
+struct BinaryTestVector {
+    polynomial: &'static str,
+    a: &'static str,
+    b: &'static str,
+    order: &'static str,
+    points: [(&'static str, &'static str); 3],
+    sum_of_first_two: (&'static str, &'static str),
+    scalars: [&'static str; 3],
+    first_point_scalar: &'static str,
+    first_point_mul: (&'static str, &'static str),
+    multiexp: (&'static str, &'static str),
+}
+
+// Curves are from SEC 2, all values are in hex. Points are the generator, a public key
+// generated by OpenSSL and 3 times the generator. Results are calculated with affine formulas
+// NIST B-163
+const SECT163R2: BinaryTestVector = BinaryTestVector {
+    polynomial: "800000000000000000000000000000000000000c9",
+    a: "1",
+    b: "20a601907b8c953ca1481eb10512f78744a3205fd",
+    order: "40000000000000000000292fe77e70c12a4234c33",
+    points: [
+        ("3f0eba16286a2d57ea0991168d4994637e8343e36", "d51fbc6c71a0094fa2cdd545b11c5c0c797324f1"),
+        ("72a136030548a7dfd22c325107d29c7cd81a6086e", "6b0d6f3d63fa9e0997313f2ce5ab2736789031498"),
+        ("634000577f86aa315009d6f9b906691f6edd691fe", "401a3de0d6c2ec014e6fba5653587bd45dc2230be"),
+    ],
+    sum_of_first_two: ("124b2bab70381f3bb7753468811322920bbc11e36", "77a177e22164414074ef4a5ca0020a601e1fbdbd5"),
+    scalars: [
+        "1d2c3b4a59687f1e2d3c4b5a69788796a5b4c3d2",
+        "40000000000000000000292fe77e70c12a4234c30",
+        "3039"
+    ],
+    // secret key for the second point
+    first_point_scalar: "15d20ff0810d30ea656b32f4476a6eddcf8552e28",
+    first_point_mul: ("72a136030548a7dfd22c325107d29c7cd81a6086e", "6b0d6f3d63fa9e0997313f2ce5ab2736789031498"),
+    multiexp: ("4ee7b8f3a72671be52c47f937a43dbecb062d66c0", "7472a020058ea31bc59139ef01d9cdf4899353132"),
+};
+
+// NIST K-283, a Koblitz curve with a = 0 and b = 1
+const SECT283K1: BinaryTestVector = BinaryTestVector {
+    polynomial: "800000000000000000000000000000000000000000000000000000000000000000010a1",
+    a: "0",
+    b: "1",
+    order: "1ffffffffffffffffffffffffffffffffffe9ae2ed07577265dff7f94451e061e163c61",
+    points: [
+        ("503213f78ca44883f1a3b8162f188e553cd265f23c1567a16876913b0c2ac2458492836", "1ccda380f1c9e318d90f95d07e5426fe87e45c0e8184698e45962364e34116177dd2259"),
+        ("45bb3d15b209902ff565352382428c51f72d212fd4e5445c90790bb1ecc50a37c102b81", "252c7ddfe1cb8b3e3df58211b89643188d2309b67dddabe37fee2655e7d927b0cf6bb50"),
+        ("15dccc30a8b1f5146412d51fec337741090321408aac521391ad36c5912e280124fe3b5", "53fc9bed137312952ad97f6a98c4c7ac1b421635fbafe28898e9213d979d5b4d279f192"),
+    ],
+    sum_of_first_two: ("257dedc60a503f0e08c3bb78f2cd28c81fd81466b0f0d3342f4095d5c033d804e6bfdeb", "3f87f5bd85c1ebb8eb737c05371cc10728151e2147f00d06d7985f878c929e2efdb3264"),
+    scalars: [
+        "1d2c3b4a59687f1e2d3c4b5a69788796a5b4c3d2",
+        "1ffffffffffffffffffffffffffffffffffe9ae2ed07577265dff7f94451e061e163c5e",
+        "3039"
+    ],
+    // secret key for the second point
+    first_point_scalar: "1281becebb559220e534a1b02c6c0d9ef6850c6fae9a108ab3b06a2dd00d2933e70d887",
+    first_point_mul: ("45bb3d15b209902ff565352382428c51f72d212fd4e5445c90790bb1ecc50a37c102b81", "252c7ddfe1cb8b3e3df58211b89643188d2309b67dddabe37fee2655e7d927b0cf6bb50"),
+    multiexp: ("109db56c709406ab0e89bdc90181c6a3feffcb523e3c9c447d7fa337a8da1875b12e1c7", "186ade5c2aa9672343465eb4fea3b47ba16a3639e4008237a76ff58dda7d292fb678323"),
+};
+
+// NIST B-571, polynomial takes 572 bits and is encoded with 72 bytes
+const SECT571R1: BinaryTestVector = BinaryTestVector {
+    polynomial: "80000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000425",
+    a: "1",
+    b: "2f40e7e2221f295de297117b7f3d62f5c6a97ffcb8ceff1cd6ba8ce4a9a18ad84ffabbd8efa59332be7ad6756a66e294afd185a78ff12aa520e4de739baca0c7ffeff7f2955727a",
+    order: "3ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffe661ce18ff55987308059b186823851ec7dd9ca1161de93d5174d66e8382e9bb2fe84e47",
+    points: [
+        ("303001d34b856296c16c0d40d3cd7750a93d1d2955fa80aa5f40fc8db7b2abdbde53950f4c0d293cdd711a35b67fb1499ae60038614f1394abfa3b4c850d927e1e7769c8eec2d19", "37bf27342da639b6dccfffeb73d69d78c6c27a6009cbbca1980f8533921e8a684423e43bab08a576291af8f461bb2a8b3531d2f0485c19b16e2f1516e23dd3c1a4827af1b8ac15b"),
+        ("30ae5c9e92cc41b2ea8d52916c7c9b07257dc6e265582f62e5ea4424786c91a4f9d94a092fb55fc2a6249d19ba4cd50e07ca6aead9b76ca23e013ad9ad416166deb1d5595d5d2ef", "1042e92d6ef5fe5d793ecb51a873a276e62efaed99cd7accbae976e190f2f95f24fc021a7d0510e96663dcb44af601bd55e416a0cf1c34ee2e0e995117f3894d2bc81e9a0daf030"),
+        ("72d033e612ca6dd14c28f1f6689af9a97bba7fd88a25bae969dd1c91e75a9f680442dea747eab06e73b746911780505dab0e03149de396b1020fbaf55580cf4d6eb9738ce0d26f8", "494ba50070ca0fc97e89f3ea55de5aed4d5bd139b3725618dfeb90152230aa53fa37b6e39baad54a77771f0df01bffee82fbb0bad45f2d5c89f704c6eba6722b1e4433638d87dda"),
+    ],
+    sum_of_first_two: ("da819b11878530088416bb4cd72f4cf3cb7a9a96e3b87cf53fea611c0995cb3f7a295589da56552ce32ee341a17bf6f4277cd08e57879c1925ca6a7485262ca58e5fa3a6f890a0", "7de4ed33b9cb94ac113414ae4a52a7ebc0f112df464a3bc59ed514c448154bfb142b7c3b49bd4cf730c3d5d992d77fd7efb8d203a9ceb69b1150fdf6b726be66b9b299b2b65a2e0"),
+    scalars: [
+        "1d2c3b4a59687f1e2d3c4b5a69788796a5b4c3d2",
+        "3ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffe661ce18ff55987308059b186823851ec7dd9ca1161de93d5174d66e8382e9bb2fe84e44",
+        "3039"
+    ],
+    // secret key for the second point
+    first_point_scalar: "3c8914ba1e863496fcfe3c3f7872347dd35c2ccf0369002c408bbafc4f9f44ef23d64d5df1025c2a70321191fed79fe2655849a34c96e463410d35c50753f2389a58e156f28e409",
+    first_point_mul: ("30ae5c9e92cc41b2ea8d52916c7c9b07257dc6e265582f62e5ea4424786c91a4f9d94a092fb55fc2a6249d19ba4cd50e07ca6aead9b76ca23e013ad9ad416166deb1d5595d5d2ef", "1042e92d6ef5fe5d793ecb51a873a276e62efaed99cd7accbae976e190f2f95f24fc021a7d0510e96663dcb44af601bd55e416a0cf1c34ee2e0e995117f3894d2bc81e9a0daf030"),
+    multiexp: ("39c55925eff9b93ee01ea8da101c7836573b446772b4585b7aecfc73d6435d15db993a437dd79c4a4d49efb3000bce13702b0fc7729a6c1e96a8bfbf13772faa477ccb62c476cd8", "6447c49971130e053f667a99949f0f707a49c839b66fe984dd76b80800403667ba4a71cd3a7a883b918edbd0782e865768cee4a6175f9125594fd6981bf77d1c6a7bd0de0aecd4b"),
+};
+fn encode_hex(value: &str, len: usize) -> Vec<u8> {
+    let value = BigUint::from_str_radix(value, 16).unwrap();
+
+    pad_for_len_be(value.to_bytes_be(), len)
+}
+
+fn field_length(vector: &BinaryTestVector) -> usize {
+    BigUint::from_str_radix(vector.polynomial, 16).unwrap().to_bytes_be().len()
+}
+
+fn order_length(vector: &BinaryTestVector) -> usize {
+    BigUint::from_str_radix(vector.order, 16).unwrap().to_bytes_be().len()
+}
+
+fn encode_point(vector: &BinaryTestVector, point: (&str, &str)) -> Vec<u8> {
+    let mut encoding = encode_hex(point.0, field_length(vector));
+    encoding.extend(encode_hex(point.1, field_length(vector)));
+
+    encoding
+}
+
+// - Lengths of the reduction polynomial (in bytes)
+// - Reduction polynomial
+// - Curve A
+// - Curve B
+// - Length of the group order
+// - Group order
+fn assemble_prefix(vector: &BinaryTestVector) -> Vec<u8> {
+    let field_len = field_length(vector);
+    let order_len = order_length(vector);
+    let mut calldata = vec![field_len as u8];
+    calldata.extend(encode_hex(vector.polynomial, field_len));
+    calldata.extend(encode_hex(vector.a, field_len));
+    calldata.extend(encode_hex(vector.b, field_len));
+    calldata.push(order_len as u8);
+    calldata.extend(encode_hex(vector.order, order_len));
+
+    calldata
+}
+
+fn assemble_addition(vector: &BinaryTestVector) -> Vec<u8> {
+    let mut calldata = assemble_prefix(vector);
+    calldata.extend(encode_point(vector, vector.points[0]));
+    calldata.extend(encode_point(vector, vector.points[1]));
+
+    calldata
+}
+
+fn assemble_multiplication(vector: &BinaryTestVector) -> Vec<u8> {
+    let mut calldata = assemble_prefix(vector);
+    calldata.extend(encode_point(vector, vector.points[0]));
+    calldata.extend(encode_hex(vector.first_point_scalar, order_length(vector)));
+
+    calldata
+}
+
+fn assemble_multiexp(vector: &BinaryTestVector) -> Vec<u8> {
+    let mut calldata = assemble_prefix(vector);
+    calldata.push(3u8);
+    for (point, scalar) in vector.points.iter().zip(vector.scalars.iter()) {
+        calldata.extend(encode_point(vector, *point));
+        calldata.extend(encode_hex(scalar, order_length(vector)));
+    }
+
+    calldata
+}
+
+fn run_test_vector(vector: &BinaryTestVector) {
+    let result = PublicBinaryCurveApi::add_points(&assemble_addition(vector), &ExecutionContext::default()).unwrap();
+    assert_eq!(result, encode_point(vector, vector.sum_of_first_two));
+
+    let result = PublicBinaryCurveApi::mul_point(&assemble_multiplication(vector), &ExecutionContext::default()).unwrap();
+    assert_eq!(result, encode_point(vector, vector.first_point_mul));
+
+    let result = PublicBinaryCurveApi::multiexp(&assemble_multiexp(vector), &ExecutionContext::default()).unwrap();
+    assert_eq!(result, encode_point(vector, vector.multiexp));
+
+    // same through the operation byte
+    let mut input = vec![OPERATION_BINARY_MULTIEXP];
+    input.extend(assemble_multiexp(vector));
+    assert_eq!(API::run(&input).unwrap(), encode_point(vector, vector.multiexp));
+}
+
+#[test]
+fn test_sect163r2_operations() {
+    run_test_vector(&SECT163R2);
+}
+
+#[test]
+fn test_sect283k1_operations() {
+    run_test_vector(&SECT283K1);
+}
+
+#[test]
+fn test_sect571r1_operations() {
+    run_test_vector(&SECT571R1);
+}
+
+#[test]
+fn test_binary_curve_point_at_infinity() {
+    let infinity = encode_point(&SECT163R2, ("0", "0"));
+    let (x, y) = SECT163R2.points[0];
+
+    // P + (-P) = O, where -(x, y) = (x, x + y)
+    let minus_y = BigUint::from_str_radix(x, 16).unwrap() ^ BigUint::from_str_radix(y, 16).unwrap();
+    let mut calldata = assemble_prefix(&SECT163R2);
+    calldata.extend(encode_point(&SECT163R2, (x, y)));
+    calldata.extend(encode_point(&SECT163R2, (x, &minus_y.to_str_radix(16))));
+    assert_eq!(PublicBinaryCurveApi::add_points(&calldata, &ExecutionContext::default()).unwrap(), infinity);
+
+    // n * G = O
+    let mut calldata = assemble_prefix(&SECT163R2);
+    calldata.extend(encode_point(&SECT163R2, (x, y)));
+    calldata.extend(encode_hex(SECT163R2.order, order_length(&SECT163R2)));
+    assert_eq!(PublicBinaryCurveApi::mul_point(&calldata, &ExecutionContext::default()).unwrap(), infinity);
+
+    // O + P = P
+    let mut calldata = assemble_prefix(&SECT163R2);
+    calldata.extend(infinity.clone());
+    calldata.extend(encode_point(&SECT163R2, (x, y)));
+    assert_eq!(PublicBinaryCurveApi::add_points(&calldata, &ExecutionContext::default()).unwrap(), encode_point(&SECT163R2, (x, y)));
+
+    // P + P is the same as 2 * P
+    let mut calldata = assemble_prefix(&SECT163R2);
+    calldata.extend(encode_point(&SECT163R2, (x, y)));
+    calldata.extend(encode_point(&SECT163R2, (x, y)));
+    let doubled = PublicBinaryCurveApi::add_points(&calldata, &ExecutionContext::default()).unwrap();
+    let mut calldata = assemble_prefix(&SECT163R2);
+    calldata.extend(encode_point(&SECT163R2, (x, y)));
+    calldata.extend(encode_hex("2", order_length(&SECT163R2)));
+    assert_eq!(PublicBinaryCurveApi::mul_point(&calldata, &ExecutionContext::default()).unwrap(), doubled);
+}
+
+#[test]
+fn test_binary_curve_invalid_inputs() {
+    // point is not on the curve
+    let mut calldata = assemble_prefix(&SECT283K1);
+    calldata.extend(encode_point(&SECT283K1, ("2", "1")));
+    calldata.extend(encode_point(&SECT283K1, SECT283K1.points[0]));
+    assert!(PublicBinaryCurveApi::add_points(&calldata, &ExecutionContext::default()).is_err());
+
+    // b == 0 is a singular curve
+    let mut vector = SECT283K1;
+    vector.b = "0";
+    assert!(PublicBinaryCurveApi::add_points(&assemble_addition(&vector), &ExecutionContext::default()).is_err());
+
+    // z^283 + z^12 + z^7 + z^5 + z + 1 is divisible by z + 1
+    let mut vector = SECT283K1;
+    vector.polynomial = "800000000000000000000000000000000000000000000000000000000000000000010a3";
+    assert!(PublicBinaryCurveApi::add_points(&assemble_addition(&vector), &ExecutionContext::default()).is_err());
+
+    // coordinate has degree above the field degree
+    let mut calldata = assemble_prefix(&SECT283K1);
+    calldata.extend(encode_point(&SECT283K1, (SECT283K1.polynomial, "1")));
+    calldata.extend(encode_point(&SECT283K1, SECT283K1.points[0]));
+    assert!(PublicBinaryCurveApi::add_points(&calldata, &ExecutionContext::default()).is_err());
+
+    // garbage at the end
+    let mut calldata = assemble_multiplication(&SECT283K1);
+    calldata.push(0u8);
+    assert!(PublicBinaryCurveApi::mul_point(&calldata, &ExecutionContext::default()).is_err());
+}
+
+#[test]
+fn test_meter_binary_curve_operations() {
+    let mut input = vec![OPERATION_BINARY_ADD];
+    input.extend(assemble_addition(&SECT283K1));
+    let addition = crate::gas_meter::GasMeter::meter(&input).unwrap();
+
+    let mut input = vec![OPERATION_BINARY_MUL];
+    input.extend(assemble_multiplication(&SECT283K1));
+    let multiplication = crate::gas_meter::GasMeter::meter(&input).unwrap();
+
+    let mut input = vec![OPERATION_BINARY_MULTIEXP];
+    input.extend(assemble_multiexp(&SECT283K1));
+    let multiexp = crate::gas_meter::GasMeter::meter(&input).unwrap();
+
+    assert!(addition > 0);
+    assert!(multiplication > addition);
+    assert!(multiexp > multiplication);
+}
@@ -0,0 +1,205 @@
+// Operations on compressed points in the third version of the ABI give the same results as on
+// the same points uncompressed. Inputs are compressed from the encodings of the first version
+
+use num_bigint::BigUint;
+use num_traits::Num;
+
+use crate::public_interface::API;
+use crate::public_interface::constants::*;
+use crate::test::parsers::pad_for_len_be;
+use crate::test::pairings::bn::assemble_bn254;
+use crate::test::pairings::bls12::{assemble_bls12_381, assemble_bls12_377};
+use crate::test::pairings::bls24::assemble_bls24_test_curve;
+
+/// X followed by the parity of the first non-zero coefficient of Y, coefficients are `modulus_len` bytes
+fn compress(point: &[u8], modulus_len: usize) -> Vec<u8> {
+    let (x, y) = point.split_at(point.len() / 2);
+    let flag = if point.iter().all(|&b| b == 0) {
+        COMPRESSED_POINT_AT_INFINITY
+    } else {
+        match y.chunks(modulus_len).find(|c| c.iter().any(|&b| b != 0)) {
+            Some(c) if c[modulus_len - 1] & 1 == 1 => COMPRESSED_Y_IS_ODD,
+            _ => COMPRESSED_Y_IS_EVEN
+        }
+    };
+
+    let mut compressed = x.to_vec();
+    compressed.push(flag);
+
+    compressed
+}
+
+/// Compresses the points of the pairs at the end of a pairing call
+fn compress_pairs(input: &[u8], num_pairs: usize, modulus_len: usize, twist_degree: usize) -> Vec<u8> {
+    let (g1_len, g2_len) = (2 * modulus_len, 2 * twist_degree * modulus_len);
+    let pair_len = 2 * BOOLEAN_ENCODING_LENGTH + g1_len + g2_len;
+    let (head, pairs) = input.split_at(input.len() - num_pairs * pair_len);
+
+    let mut compressed = head.to_vec();
+    for pair in pairs.chunks(pair_len) {
+        let (g1_check, rest) = pair.split_at(BOOLEAN_ENCODING_LENGTH);
+        let (g1, rest) = rest.split_at(g1_len);
+        let (g2_check, g2) = rest.split_at(BOOLEAN_ENCODING_LENGTH);
+        compressed.extend_from_slice(g1_check);
+        compressed.extend(compress(g1, modulus_len));
+        compressed.extend_from_slice(g2_check);
+        compressed.extend(compress(g2, modulus_len));
+    }
+
+    compressed
+}
+
+fn v1(operation: u8, input: &[u8]) -> Vec<u8> {
+    let mut encoding = vec![operation];
+    encoding.extend_from_slice(input);
+
+    encoding
+}
+
+fn v3(operation: u8, input: &[u8]) -> Vec<u8> {
+    let mut encoding = vec![ABI_VERSION_PREFIX, ABI_VERSION_3, operation];
+    encoding.extend_from_slice(input);
+
+    encoding
+}
+
+#[test]
+fn test_pairings_on_compressed_points() {
+    // fast paths of BN254 and BLS12-381 and the generic BLS12 implementation
+    for (input, modulus_len) in vec![(assemble_bn254(2), 32), (assemble_bls12_381(2), 48), (assemble_bls12_377(2), 48)] {
+        let compressed = compress_pairs(&input, 2, modulus_len, 2);
+        assert!(compressed.len() < input.len());
+
+        let expected = API::run(&v1(OPERATION_PAIRING, &input)).unwrap();
+        assert_eq!(API::run(&v3(OPERATION_PAIRING, &compressed)).unwrap(), expected);
+
+        // uncompressed points are not of the length of compressed ones
+        assert!(API::run(&v3(OPERATION_PAIRING, &input)).is_err());
+
+        #[cfg(feature = "gas_metering")]
+        assert!(API::estimate_gas(&v3(OPERATION_PAIRING, &compressed)).unwrap() > API::estimate_gas(&v1(OPERATION_PAIRING, &input)).unwrap());
+    }
+}
+
+#[test]
+fn test_compressed_points_in_higher_extensions_are_rejected() {
+    let input = assemble_bls24_test_curve(2);
+    let compressed = compress_pairs(&input, 2, input[1] as usize, 4);
+    assert!(API::run(&v3(OPERATION_PAIRING, &compressed)).is_err());
+
+    // curves in other forms have their own encodings
+    assert!(API::run(&[ABI_VERSION_PREFIX, ABI_VERSION_3, OPERATION_EDWARDS_ADD]).is_err());
+}
+
+#[test]
+fn test_g1_operations_on_compressed_points() {
+    // BN254 with the generator (1, 2)
+    let modulus_len = 32;
+    let modulus = BigUint::from_str_radix("21888242871839275222246405745257275088696311157297823662689037894645226208583", 10).unwrap();
+    let order = BigUint::from_str_radix("21888242871839275222246405745257275088548364400416034343698204186575808495617", 10).unwrap();
+    let mut parameters = vec![modulus_len as u8];
+    parameters.extend(pad_for_len_be(modulus.to_bytes_be(), modulus_len));
+    parameters.extend(pad_for_len_be(vec![0], modulus_len));
+    parameters.extend(pad_for_len_be(vec![3], modulus_len));
+    parameters.push(modulus_len as u8);
+    parameters.extend(pad_for_len_be(order.to_bytes_be(), modulus_len));
+
+    let mut generator = pad_for_len_be(vec![1], modulus_len);
+    generator.extend(pad_for_len_be(vec![2], modulus_len));
+    let scalar = pad_for_len_be(vec![0x12, 0x34, 0x56], modulus_len);
+
+    let mut mul = parameters.clone();
+    mul.extend_from_slice(&generator);
+    mul.extend_from_slice(&scalar);
+    let point = API::run(&v1(OPERATION_G1_MUL, &mul)).unwrap();
+
+    let mut compressed_mul = parameters.clone();
+    compressed_mul.extend(compress(&generator, modulus_len));
+    compressed_mul.extend_from_slice(&scalar);
+    assert_eq!(API::run(&v3(OPERATION_G1_MUL, &compressed_mul)).unwrap(), point);
+
+    // Y of the generator is even, so the other flag gives the negated point
+    let mut negated = compressed_mul.clone();
+    negated[parameters.len() + modulus_len] = COMPRESSED_Y_IS_ODD;
+    assert_ne!(API::run(&v3(OPERATION_G1_MUL, &negated)).unwrap(), point);
+
+    let mut add = parameters.clone();
+    add.extend_from_slice(&generator);
+    add.extend_from_slice(&point);
+    let sum = API::run(&v1(OPERATION_G1_ADD, &add)).unwrap();
+
+    let mut compressed_add = parameters.clone();
+    compressed_add.extend(compress(&generator, modulus_len));
+    compressed_add.extend(compress(&point, modulus_len));
+    assert_eq!(API::run(&v3(OPERATION_G1_ADD, &compressed_add)).unwrap(), sum);
+
+    // adding the point at infinity
+    let mut compressed_add = parameters.clone();
+    compressed_add.extend(compress(&point, modulus_len));
+    compressed_add.extend(compress(&vec![0u8; 2 * modulus_len], modulus_len));
+    assert_eq!(API::run(&v3(OPERATION_G1_ADD, &compressed_add)).unwrap(), point);
+}
+
+#[test]
+fn test_g2_operations_on_compressed_points_in_fp3() {
+    // MNT6-298, p = 1 mod 4 so Y is recovered by Tonelli-Shanks in Fp3
+    let modulus_len = 38;
+    let modulus = BigUint::from_str_radix("475922286169261325753349249653048451545124878552823515553267735739164647307408490559963137", 10).unwrap();
+    let b = BigUint::from_str_radix("106700080510851735677967319632585352256454251201367587890185989362936000262606668469523074", 10).unwrap();
+    let order = BigUint::from_str_radix("475922286169261325753349249653048451545124879242694725395555128576210262817955800483758081", 10).unwrap();
+    let non_residue = BigUint::from(5u64);
+    let fp = |value: &BigUint| pad_for_len_be(value.to_bytes_be(), modulus_len);
+    let fp3 = |c0: &BigUint, c1: &BigUint, c2: &BigUint| {
+        let mut encoding = fp(c0);
+        encoding.extend(fp(c1));
+        encoding.extend(fp(c2));
+
+        encoding
+    };
+    let zero = BigUint::from(0u64);
+
+    // twist by u, so A = a*u^2 and B = b*u^3 = b*5
+    let mut parameters = vec![modulus_len as u8];
+    parameters.extend(fp(&modulus));
+    parameters.push(EXTENSION_DEGREE_3);
+    parameters.extend(fp(&non_residue));
+    parameters.extend(fp3(&zero, &zero, &BigUint::from(11u64)));
+    parameters.extend(fp3(&((&b * &non_residue) % &modulus), &zero, &zero));
+    parameters.push(modulus_len as u8);
+    parameters.extend(fp(&order));
+
+    let coordinate = |c0: &str, c1: &str, c2: &str| {
+        fp3(&BigUint::from_str_radix(c0, 10).unwrap(), &BigUint::from_str_radix(c1, 10).unwrap(), &BigUint::from_str_radix(c2, 10).unwrap())
+    };
+    let mut q = coordinate(
+        "421456435772811846256826561593908322288509115489119907560382401870203318738334702321297427",
+        "103072927438548502463527009961344915021167584706439945404959058962657261178393635706405114",
+        "143029172143731852627002926324735183809768363301149009204849580478324784395590388826052558"
+    );
+    q.extend(coordinate(
+        "464673596668689463130099227575639512541218133445388869383893594087634649237515554342751377",
+        "100642907501977375184575075967118071807821117960152743335603284583254620685343989304941678",
+        "123019855502969896026940545715841181300275180157288044663051565390506010149881373807142903"
+    ));
+    let scalar = pad_for_len_be(vec![0xbc, 0x61, 0x4e], modulus_len);
+
+    let mut mul = parameters.clone();
+    mul.extend_from_slice(&q);
+    mul.extend_from_slice(&scalar);
+    let point = API::run(&v1(OPERATION_G2_MUL, &mul)).unwrap();
+
+    let mut compressed_mul = parameters.clone();
+    compressed_mul.extend(compress(&q, modulus_len));
+    compressed_mul.extend_from_slice(&scalar);
+    assert_eq!(API::run(&v3(OPERATION_G2_MUL, &compressed_mul)).unwrap(), point);
+
+    let mut add = parameters.clone();
+    add.extend_from_slice(&q);
+    add.extend_from_slice(&point);
+    let sum = API::run(&v1(OPERATION_G2_ADD, &add)).unwrap();
+
+    let mut compressed_add = parameters.clone();
+    compressed_add.extend(compress(&q, modulus_len));
+    compressed_add.extend(compress(&point, modulus_len));
+    assert_eq!(API::run(&v3(OPERATION_G2_ADD, &compressed_add)).unwrap(), sum);
+}
@@ -0,0 +1,255 @@
+// Property based round trips of the fixed length encodings for random moduli of every
+// supported number of limbs. Moduli only need to be odd for Montgomery form, and points don't
+// need to be on the curve, since neither is checked when decoding
+
+use core::marker::PhantomData;
+
+use num_bigint::BigUint;
+use proptest::prelude::*;
+
+use crate::errors::{ApiError, ErrorCode};
+use crate::field::*;
+use crate::fp::Fp;
+use crate::extension_towers::{fp2, fp3};
+use crate::integers::MaxFieldUint;
+use crate::public_interface::decode_fp::*;
+use crate::public_interface::decode_g1::*;
+use crate::public_interface::decode_g2::*;
+use crate::public_interface::PointEncoding;
+use crate::representation::ElementRepr;
+use crate::traits::ZeroAndOne;
+use crate::expand_for_modulus_limbs;
+use crate::weierstrass::{Group, CurveOverFpParameters, CurveOverFp2Parameters};
+use crate::weierstrass::curve::{CurvePoint, WeierstrassCurve};
+
+const NUM_ELEMENTS: usize = 4;
+const SEED_BYTE_LEN: usize = 160;
+
+#[derive(Debug, Clone)]
+struct Case {
+    modulus: BigUint,
+    // canonical, below the modulus
+    elements: Vec<BigUint>,
+    // not below the modulus, but fits into the encoding length
+    non_canonical: BigUint,
+}
+
+impl Case {
+    fn modulus_len(&self) -> usize {
+        (self.modulus.bits() + 7) / 8
+    }
+
+    fn encode(&self, value: &BigUint) -> Vec<u8> {
+        let bytes = value.to_bytes_be();
+        let mut encoding = vec![0u8; self.modulus_len() - bytes.len()];
+        encoding.extend(bytes);
+
+        encoding
+    }
+
+    fn encode_all(&self, values: &[BigUint]) -> Vec<u8> {
+        values.iter().flat_map(|v| self.encode(v)).collect()
+    }
+
+    fn modulus_limbs(&self) -> usize {
+        calculate_num_limbs(self.modulus.bits()).unwrap()
+    }
+}
+
+fn case_strategy() -> impl Strategy<Value = Case> {
+    let seed = || proptest::collection::vec(any::<u8>(), SEED_BYTE_LEN);
+    (2usize..=1023, seed(), proptest::collection::vec(seed(), NUM_ELEMENTS), seed()).prop_map(|(bits, modulus_seed, element_seeds, offset_seed)| {
+        let one = BigUint::from(1u64);
+        let top_bit = &one << (bits - 1);
+        let modulus = (BigUint::from_bytes_be(&modulus_seed) % &top_bit) | &top_bit | &one;
+        let elements = element_seeds.iter().map(|s| BigUint::from_bytes_be(s) % &modulus).collect();
+        let encoding_bound = &one << (((bits + 7) / 8) * 8);
+        let non_canonical = &modulus + BigUint::from_bytes_be(&offset_seed) % (encoding_bound - &modulus);
+
+        Case {
+            modulus,
+            elements,
+            non_canonical,
+        }
+    })
+}
+
+fn expect_code<T>(result: Result<T, ApiError>, code: ErrorCode) -> Result<(), TestCaseError> {
+    match result {
+        Err(e) => prop_assert_eq!(e.code(), code),
+        Ok(..) => prop_assert!(false, "encoding must be rejected"),
+    }
+
+    Ok(())
+}
+
+struct RoundTrips<FE: ElementRepr> {
+    _marker: PhantomData<FE>
+}
+
+impl<FE: ElementRepr> RoundTrips<FE> {
+    fn field(case: &Case) -> PrimeField<FE> {
+        field_from_modulus::<FE>(&MaxFieldUint::from_big_endian(&case.modulus.to_bytes_be())).unwrap()
+    }
+
+    fn fp(case: &Case) -> Result<(), TestCaseError> {
+        let field = Self::field(case);
+        let len = case.modulus_len();
+        for element in case.elements.iter() {
+            let encoding = case.encode(element);
+            let (x, rest) = decode_fp(&encoding, len, &field).unwrap();
+            prop_assert!(rest.is_empty());
+            let serialized = serialize_fp_fixed_len(len, &x).unwrap();
+            prop_assert_eq!(&serialized, &encoding);
+            let (y, _) = decode_fp(&serialized, len, &field).unwrap();
+            prop_assert!(x == y);
+
+            expect_code(decode_fp(&encoding[..(len - 1)], len, &field), ErrorCode::InputTooShort { expected: len, got: len - 1 })?;
+        }
+        expect_code(decode_fp(&case.encode(&case.non_canonical), len, &field), ErrorCode::NotInField)?;
+
+        Ok(())
+    }
+
+    fn fp2(case: &Case) -> Result<(), TestCaseError> {
+        let field = Self::field(case);
+        let len = case.modulus_len();
+        // non-residue is irrelevant for encoding
+        let extension = fp2::Extension2::new(Fp::one(&field));
+        let values = &case.elements[..2];
+        let encoding = case.encode_all(values);
+
+        let (x, rest) = decode_fp2(&encoding, len, &extension).unwrap();
+        prop_assert!(rest.is_empty());
+        let serialized = serialize_fp2_fixed_len(len, &x).unwrap();
+        prop_assert_eq!(&serialized, &encoding);
+        let (y, _) = decode_fp2(&serialized, len, &extension).unwrap();
+        prop_assert!(x == y);
+
+        prop_assert!(decode_fp2(&encoding[..(2 * len - 1)], len, &extension).is_err());
+        for i in 0..2 {
+            let mut values = values.to_vec();
+            values[i] = case.non_canonical.clone();
+            prop_assert!(decode_fp2(&case.encode_all(&values), len, &extension).is_err());
+        }
+
+        Ok(())
+    }
+
+    fn fp3(case: &Case) -> Result<(), TestCaseError> {
+        let field = Self::field(case);
+        let len = case.modulus_len();
+        let extension = fp3::Extension3::new(Fp::one(&field));
+        let values = &case.elements[..3];
+        let encoding = case.encode_all(values);
+
+        let (x, rest) = decode_fp3(&encoding, len, &extension).unwrap();
+        prop_assert!(rest.is_empty());
+        let serialized = serialize_fp3_fixed_len(len, &x).unwrap();
+        prop_assert_eq!(&serialized, &encoding);
+        let (y, _) = decode_fp3(&serialized, len, &extension).unwrap();
+        prop_assert!(x == y);
+
+        prop_assert!(decode_fp3(&encoding[..(3 * len - 1)], len, &extension).is_err());
+        for i in 0..3 {
+            let mut values = values.to_vec();
+            values[i] = case.non_canonical.clone();
+            prop_assert!(decode_fp3(&case.encode_all(&values), len, &extension).is_err());
+        }
+
+        Ok(())
+    }
+
+    fn g1(case: &Case) -> Result<(), TestCaseError> {
+        let field = Self::field(case);
+        let len = case.modulus_len();
+        let params = CurveOverFpParameters::new(&field);
+        let order = [1u64];
+        let curve = WeierstrassCurve::new(&order, Fp::zero(&field), Fp::one(&field), &params).unwrap();
+        let values = &case.elements[..2];
+        let encoding = case.encode_all(values);
+
+        let (point, rest) = decode_g1_point_from_xy(&encoding, len, &curve, PointEncoding::Uncompressed).unwrap();
+        prop_assert!(rest.is_empty());
+        let serialized = serialize_g1_point(len, &point).unwrap();
+        prop_assert_eq!(&serialized, &encoding);
+        let (decoded, _) = decode_g1_point_from_xy(&serialized, len, &curve, PointEncoding::Uncompressed).unwrap();
+        prop_assert!(decoded.into_xy() == point.into_xy());
+
+        // zero coordinates are the point at infinity
+        let zero = CurvePoint::zero(&curve);
+        let serialized = serialize_g1_point(len, &zero).unwrap();
+        prop_assert_eq!(&serialized, &vec![0u8; 2 * len]);
+        let (decoded, _) = decode_g1_point_from_xy(&serialized, len, &curve, PointEncoding::Uncompressed).unwrap();
+        prop_assert!(decoded.is_zero());
+
+        prop_assert!(decode_g1_point_from_xy(&encoding[..(2 * len - 1)], len, &curve, PointEncoding::Uncompressed).is_err());
+        for i in 0..2 {
+            let mut values = values.to_vec();
+            values[i] = case.non_canonical.clone();
+            expect_code(decode_g1_point_from_xy(&case.encode_all(&values), len, &curve, PointEncoding::Uncompressed), ErrorCode::NotInField)?;
+        }
+
+        Ok(())
+    }
+
+    fn g2_in_fp2(case: &Case) -> Result<(), TestCaseError> {
+        let field = Self::field(case);
+        let len = case.modulus_len();
+        let extension = fp2::Extension2::new(Fp::one(&field));
+        let params = CurveOverFp2Parameters::new(&extension);
+        let order = [1u64];
+        let curve = WeierstrassCurve::new(&order, fp2::Fp2::zero(&extension), fp2::Fp2::one(&extension), &params).unwrap();
+        let values = &case.elements[..4];
+        let encoding = case.encode_all(values);
+
+        let (point, rest) = decode_g2_point_from_xy_in_fp2(&encoding, len, &curve, PointEncoding::Uncompressed).unwrap();
+        prop_assert!(rest.is_empty());
+        let serialized = serialize_g2_point_in_fp2(len, &point).unwrap();
+        prop_assert_eq!(&serialized, &encoding);
+        let (decoded, _) = decode_g2_point_from_xy_in_fp2(&serialized, len, &curve, PointEncoding::Uncompressed).unwrap();
+        prop_assert!(decoded.into_xy() == point.into_xy());
+
+        let zero = CurvePoint::zero(&curve);
+        let serialized = serialize_g2_point_in_fp2(len, &zero).unwrap();
+        prop_assert_eq!(&serialized, &vec![0u8; 4 * len]);
+        let (decoded, _) = decode_g2_point_from_xy_in_fp2(&serialized, len, &curve, PointEncoding::Uncompressed).unwrap();
+        prop_assert!(decoded.is_zero());
+
+        prop_assert!(decode_g2_point_from_xy_in_fp2(&encoding[..(4 * len - 1)], len, &curve, PointEncoding::Uncompressed).is_err());
+        for i in 0..4 {
+            let mut values = values.to_vec();
+            values[i] = case.non_canonical.clone();
+            prop_assert!(decode_g2_point_from_xy_in_fp2(&case.encode_all(&values), len, &curve, PointEncoding::Uncompressed).is_err());
+        }
+
+        Ok(())
+    }
+}
+
+proptest! {
+    #[test]
+    fn test_fp_round_trip(case in case_strategy()) {
+        expand_for_modulus_limbs!(case.modulus_limbs(), RoundTrips, &case, fp)?;
+    }
+
+    #[test]
+    fn test_fp2_round_trip(case in case_strategy()) {
+        expand_for_modulus_limbs!(case.modulus_limbs(), RoundTrips, &case, fp2)?;
+    }
+
+    #[test]
+    fn test_fp3_round_trip(case in case_strategy()) {
+        expand_for_modulus_limbs!(case.modulus_limbs(), RoundTrips, &case, fp3)?;
+    }
+
+    #[test]
+    fn test_g1_point_round_trip(case in case_strategy()) {
+        expand_for_modulus_limbs!(case.modulus_limbs(), RoundTrips, &case, g1)?;
+    }
+
+    #[test]
+    fn test_g2_point_in_fp2_round_trip(case in case_strategy()) {
+        expand_for_modulus_limbs!(case.modulus_limbs(), RoundTrips, &case, g2_in_fp2)?;
+    }
+}
@@ -0,0 +1,310 @@
+// Runs the same operations through arkworks and through the public API on random inputs
+// and compares the results. Curve parameters for the calls are taken from arkworks as well,
+// so any disagreement in encoding, arithmetic or pairing is caught
+
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ec::bls12::{Bls12, Bls12Config};
+use ark_ec::bn::{Bn, BnConfig};
+use ark_ec::pairing::Pairing;
+use ark_ec::short_weierstrass::SWCurveConfig;
+use ark_ff::{BigInteger, Field, PrimeField, Zero, One};
+use ark_ff::fields::{Fp2Config, Fp6Config};
+
+use rand::{Rng, SeedableRng};
+use rand_xorshift::XorShiftRng;
+
+use crate::public_interface::API;
+use crate::public_interface::constants::*;
+use crate::public_interface::encoding::*;
+
+const NUM_TESTS: usize = 16;
+const NUM_PAIRING_TESTS: usize = 4;
+const MAX_MULTIEXP_PAIRS: usize = 4;
+
+pub(crate) trait ArkworksEngine: Pairing {
+    fn g1_descriptor() -> G1CurveDescriptor;
+    fn g2_descriptor() -> G2CurveDescriptor;
+    fn pairing_call() -> PairingCall;
+}
+
+fn modulus<F: PrimeField>() -> Vec<u8> {
+    F::MODULUS.to_bytes_be()
+}
+
+/// Coefficients `c0, c1, ...` of the element over the prime field
+fn coefficients<F: Field>(element: &F) -> Vec<Vec<u8>> {
+    element.to_base_prime_field_elements().map(|c| c.into_bigint().to_bytes_be()).collect()
+}
+
+fn limbs_to_bytes(limbs: &[u64]) -> Vec<u8> {
+    limbs.iter().rev().flat_map(|limb| limb.to_be_bytes().to_vec()).collect()
+}
+
+fn g1_descriptor_for<C: SWCurveConfig>() -> G1CurveDescriptor where C::BaseField: PrimeField {
+    G1CurveDescriptor::new(
+        &modulus::<C::BaseField>(),
+        &C::COEFF_A.into_bigint().to_bytes_be(),
+        &C::COEFF_B.into_bigint().to_bytes_be(),
+        &modulus::<C::ScalarField>()
+    ).unwrap()
+}
+
+fn g2_descriptor_for<C: SWCurveConfig>(non_residue: &[u8]) -> G2CurveDescriptor {
+    let a = coefficients(&C::COEFF_A);
+    let b = coefficients(&C::COEFF_B);
+    let a: Vec<&[u8]> = a.iter().map(|c| &c[..]).collect();
+    let b: Vec<&[u8]> = b.iter().map(|c| &c[..]).collect();
+
+    G2CurveDescriptor::new(
+        &modulus::<<C::BaseField as Field>::BasePrimeField>(),
+        a.len(),
+        non_residue,
+        &a,
+        &b,
+        &modulus::<C::ScalarField>()
+    ).unwrap()
+}
+
+impl<P: Bls12Config> ArkworksEngine for Bls12<P> where P::G1Config: SWCurveConfig<BaseField = P::Fp> {
+    fn g1_descriptor() -> G1CurveDescriptor {
+        g1_descriptor_for::<P::G1Config>()
+    }
+
+    fn g2_descriptor() -> G2CurveDescriptor {
+        g2_descriptor_for::<P::G2Config>(&P::Fp2Config::NONRESIDUE.into_bigint().to_bytes_be())
+    }
+
+    fn pairing_call() -> PairingCall {
+        let fp6_non_residue = coefficients(&P::Fp6Config::NONRESIDUE);
+        let twist_type = match P::TWIST_TYPE {
+            ark_ec::bls12::TwistType::M => TwistType::M,
+            ark_ec::bls12::TwistType::D => TwistType::D,
+        };
+
+        PairingCall::bls12(
+            Self::g1_descriptor(),
+            &P::Fp2Config::NONRESIDUE.into_bigint().to_bytes_be(),
+            [&fp6_non_residue[0], &fp6_non_residue[1]],
+            twist_type,
+            &limbs_to_bytes(P::X),
+            P::X_IS_NEGATIVE
+        ).unwrap()
+    }
+}
+
+impl<P: BnConfig> ArkworksEngine for Bn<P> where P::G1Config: SWCurveConfig<BaseField = P::Fp> {
+    fn g1_descriptor() -> G1CurveDescriptor {
+        g1_descriptor_for::<P::G1Config>()
+    }
+
+    fn g2_descriptor() -> G2CurveDescriptor {
+        g2_descriptor_for::<P::G2Config>(&P::Fp2Config::NONRESIDUE.into_bigint().to_bytes_be())
+    }
+
+    fn pairing_call() -> PairingCall {
+        let fp6_non_residue = coefficients(&P::Fp6Config::NONRESIDUE);
+        let twist_type = match P::TWIST_TYPE {
+            ark_ec::bn::TwistType::M => TwistType::M,
+            ark_ec::bn::TwistType::D => TwistType::D,
+        };
+
+        PairingCall::bn(
+            Self::g1_descriptor(),
+            &P::Fp2Config::NONRESIDUE.into_bigint().to_bytes_be(),
+            [&fp6_non_residue[0], &fp6_non_residue[1]],
+            twist_type,
+            &limbs_to_bytes(P::X),
+            P::X_IS_NEGATIVE
+        ).unwrap()
+    }
+}
+
+fn random_scalar<F: PrimeField, R: Rng>(rng: &mut R) -> F {
+    // twice the length of the order, so reduction doesn't skew the distribution
+    let mut bytes = vec![0u8; (F::MODULUS_BIT_SIZE as usize + 7) / 8 * 2];
+    rng.fill(&mut bytes[..]);
+
+    F::from_be_bytes_mod_order(&bytes)
+}
+
+fn random_point<G: AffineRepr, R: Rng>(rng: &mut R) -> G {
+    (G::generator() * random_scalar::<G::ScalarField, R>(rng)).into_affine()
+}
+
+fn scalar_to_bytes<F: PrimeField>(scalar: &F) -> Vec<u8> {
+    scalar.into_bigint().to_bytes_be()
+}
+
+fn to_g1_point<G: AffineRepr>(point: &G) -> G1Point {
+    match point.xy() {
+        Some((x, y)) => {
+            let (x, y) = (coefficients(x), coefficients(y));
+            G1Point::new(&x[0], &y[0])
+        },
+        None => G1Point::zero()
+    }
+}
+
+fn to_g2_point<G: AffineRepr>(point: &G) -> G2Point {
+    match point.xy() {
+        Some((x, y)) => {
+            let (x, y) = (coefficients(x), coefficients(y));
+            G2Point::new(&[&x[0], &x[1]], &[&y[0], &y[1]])
+        },
+        None => G2Point::zero()
+    }
+}
+
+/// Output of the precompile for the point: all coordinates padded to the modulus length,
+/// the point at infinity is all zeroes
+fn encode_output<G: AffineRepr>(point: &G, modulus_len: usize) -> Vec<u8> {
+    let extension_degree = G::BaseField::extension_degree() as usize;
+    match point.xy() {
+        Some((x, y)) => {
+            coefficients(x).into_iter().chain(coefficients(y).into_iter()).flat_map(|c| {
+                let mut padded = vec![0u8; modulus_len - c.len()];
+                padded.extend(c);
+
+                padded
+            }).collect()
+        },
+        None => vec![0u8; 2 * extension_degree * modulus_len]
+    }
+}
+
+fn run_g1_ops<E: ArkworksEngine>() {
+    let rng = &mut XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+    let descriptor = E::g1_descriptor();
+    let modulus_len = descriptor.modulus_len();
+    let run = |input: Vec<u8>| API::run(&input).unwrap();
+
+    for _ in 0..NUM_TESTS {
+        let p: E::G1Affine = random_point(rng);
+        let q: E::G1Affine = random_point(rng);
+        let minus_p = (-p.into_group()).into_affine();
+
+        for (a, b) in [(p, q), (p, p), (p, minus_p), (p, E::G1Affine::zero())].iter() {
+            let expected = (a.into_group() + b.into_group()).into_affine();
+            let output = run(descriptor.add(&to_g1_point(a), &to_g1_point(b)).unwrap());
+            assert_eq!(output, encode_output(&expected, modulus_len), "G1 addition of {} and {}", a, b);
+        }
+
+        let scalar: E::ScalarField = random_scalar(rng);
+        for s in [scalar, E::ScalarField::zero(), E::ScalarField::one(), -E::ScalarField::one()].iter() {
+            let expected = (p * s).into_affine();
+            let output = run(descriptor.mul(&to_g1_point(&p), &scalar_to_bytes(s)).unwrap());
+            assert_eq!(output, encode_output(&expected, modulus_len), "G1 multiplication of {} by {}", p, s);
+        }
+
+        let num_pairs = rng.gen_range(1, MAX_MULTIEXP_PAIRS + 1);
+        let mut expected = E::G1::zero();
+        let mut pairs = vec![];
+        for _ in 0..num_pairs {
+            let base: E::G1Affine = random_point(rng);
+            let scalar: E::ScalarField = random_scalar(rng);
+            expected += base * scalar;
+            pairs.push((to_g1_point(&base), scalar_to_bytes(&scalar)));
+        }
+        let output = run(descriptor.multiexp(&pairs).unwrap());
+        assert_eq!(output, encode_output(&expected.into_affine(), modulus_len), "G1 multiexponentiation of {} pairs", num_pairs);
+    }
+}
+
+fn run_g2_ops<E: ArkworksEngine>() {
+    let rng = &mut XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+    let descriptor = E::g2_descriptor();
+    let modulus_len = descriptor.modulus_len();
+    let run = |input: Vec<u8>| API::run(&input).unwrap();
+
+    for _ in 0..NUM_TESTS {
+        let p: E::G2Affine = random_point(rng);
+        let q: E::G2Affine = random_point(rng);
+        let minus_p = (-p.into_group()).into_affine();
+
+        for (a, b) in [(p, q), (p, p), (p, minus_p), (p, E::G2Affine::zero())].iter() {
+            let expected = (a.into_group() + b.into_group()).into_affine();
+            let output = run(descriptor.add(&to_g2_point(a), &to_g2_point(b)).unwrap());
+            assert_eq!(output, encode_output(&expected, modulus_len), "G2 addition of {} and {}", a, b);
+        }
+
+        let scalar: E::ScalarField = random_scalar(rng);
+        for s in [scalar, E::ScalarField::zero(), E::ScalarField::one(), -E::ScalarField::one()].iter() {
+            let expected = (p * s).into_affine();
+            let output = run(descriptor.mul(&to_g2_point(&p), &scalar_to_bytes(s)).unwrap());
+            assert_eq!(output, encode_output(&expected, modulus_len), "G2 multiplication of {} by {}", p, s);
+        }
+
+        let num_pairs = rng.gen_range(1, MAX_MULTIEXP_PAIRS + 1);
+        let mut expected = E::G2::zero();
+        let mut pairs = vec![];
+        for _ in 0..num_pairs {
+            let base: E::G2Affine = random_point(rng);
+            let scalar: E::ScalarField = random_scalar(rng);
+            expected += base * scalar;
+            pairs.push((to_g2_point(&base), scalar_to_bytes(&scalar)));
+        }
+        let output = run(descriptor.multiexp(&pairs).unwrap());
+        assert_eq!(output, encode_output(&expected.into_affine(), modulus_len), "G2 multiexponentiation of {} pairs", num_pairs);
+    }
+}
+
+fn run_pairings<E: ArkworksEngine>() {
+    let rng = &mut XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+    let g1 = E::G1Affine::generator();
+    let g2 = E::G2Affine::generator();
+
+    for i in 0..NUM_PAIRING_TESTS {
+        // e(a*P, b*Q) * e(c*P, d*Q) is one for d = -a*b/c, and any other d is a negative case
+        let a: E::ScalarField = random_scalar(rng);
+        let b: E::ScalarField = random_scalar(rng);
+        let c: E::ScalarField = random_scalar(rng);
+        let d = if i % 2 == 0 {
+            -(a * b) * c.inverse().unwrap()
+        } else {
+            random_scalar(rng)
+        };
+        let g1_points = [(g1 * a).into_affine(), (g1 * c).into_affine(), E::G1Affine::zero()];
+        let g2_points = [(g2 * b).into_affine(), (g2 * d).into_affine(), g2];
+
+        let expected = E::multi_pairing(g1_points.iter(), g2_points.iter()).is_zero();
+
+        let mut call = E::pairing_call();
+        for (p, q) in g1_points.iter().zip(g2_points.iter()) {
+            call.add_pair(to_g1_point(p), to_g2_point(q), true);
+        }
+        let output = API::run(&call.encode().unwrap()).unwrap();
+        let expected_output = if expected { BOOLEAN_TRUE } else { BOOLEAN_FALSE };
+        assert_eq!(output, vec![expected_output], "pairing for a = {}, b = {}, c = {}, d = {}", a, b, c, d);
+        assert_eq!(expected, i % 2 == 0);
+    }
+}
+
+#[test]
+fn test_bls12_381_g1_ops_against_arkworks() {
+    run_g1_ops::<ark_bls12_381::Bls12_381>();
+}
+
+#[test]
+fn test_bls12_381_g2_ops_against_arkworks() {
+    run_g2_ops::<ark_bls12_381::Bls12_381>();
+}
+
+#[test]
+fn test_bls12_381_pairing_against_arkworks() {
+    run_pairings::<ark_bls12_381::Bls12_381>();
+}
+
+#[test]
+fn test_bn254_g1_ops_against_arkworks() {
+    run_g1_ops::<ark_bn254::Bn254>();
+}
+
+#[test]
+fn test_bn254_g2_ops_against_arkworks() {
+    run_g2_ops::<ark_bn254::Bn254>();
+}
+
+#[test]
+fn test_bn254_pairing_against_arkworks() {
+    run_pairings::<ark_bn254::Bn254>();
+}
@@ -0,0 +1,242 @@
+use crate::public_interface::constants::*;
+use crate::public_interface::ExecutionContext;
+use crate::public_interface::{EdwardsApi, PublicEdwardsApi, ErrorCode, API};
+
+use num_bigint::BigUint;
+use num_traits::Num;
+
+use crate::test::parsers::*;
+
+const MODULUS_LENGTH: usize = 32;
+
+struct EdwardsTestVector {
+    modulus: &'static str,
+    a: &'static str,
+    d: &'static str,
+    order: &'static str,
+    points: [(&'static str, &'static str); 3],
+    sum_of_first_two: (&'static str, &'static str),
+    scalars: [&'static str; 3],
+    first_point_mul: (&'static str, &'static str),
+    multiexp: (&'static str, &'static str),
+}
+
+// points are in the prime order subgroup, results are calculated with affine formulas
+const JUBJUB: EdwardsTestVector = EdwardsTestVector {
+    modulus: "52435875175126190479447740508185965837690552500527637822603658699938581184513",
+    a: "52435875175126190479447740508185965837690552500527637822603658699938581184512",
+    d: "19257038036680949359750312669786877991949435402254120286184196891950884077233",
+    order: "6554484396890773809930967563523245729705921265872317281365359162392183254199",
+    points: [
+        ("26010153862830793743911730662926303622536112353870575676876095452509902076443", "33870355149453697655464584064870436861767017640968433840972803788419917420560"),
+        ("51899377517447338995032987599924353901205609129075980784315063292640741906158", "50406216435971274894022286472668010686701787464420308087128239603369814079915"),
+        ("28336281903124990867587793011069573392383982287722241916350956173377953689573", "39385640392217313770878525135509063452020585410343666726093009378539878503883"),
+    ],
+    sum_of_first_two: ("44060069191204623643104044227965085792192143049200013460565434038562519958529", "47929095630810891917171482184472197992402733800537209412537038982953195071379"),
+    scalars: [
+        "446371678903360124661747118626766461972311602250509962735",
+        "6554484396890773809930967563523245729705921265872317281365359162392183254196",
+        "12345"
+    ],
+    first_point_mul: ("46947349278997826202472190389027324563270203846095631812441126109037190278831", "41541816816478192550668002693483592794340116669317606593134380962264724009568"),
+    multiexp: ("35758996995148333067065040319559260043333859877740254242205229223910395517602", "5542894085096964907078486865447631981019203587216563662915622711357114476587"),
+};
+
+const BABY_JUBJUB: EdwardsTestVector = EdwardsTestVector {
+    modulus: "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    a: "168700",
+    d: "168696",
+    order: "2736030358979909402780800718157159386076813972158567259200215660948447373041",
+    points: [
+        ("358516342758523649525127563468154015622506126220229724044281060162052206790", "18950597308922899508734255418393467380154095681512244002334855292397492297153"),
+        ("16734594408996303869319894344085303168250827785523633825810382279253408233178", "21633314229490940747934748186263429472726391000134528807474240178502021766789"),
+        ("624559856211298137211360602482424070045703174493626290866711148274275643253", "20891085652997354572748064901953736505124493106384980926928056114348770992485"),
+    ],
+    sum_of_first_two: ("14199200750389449209375742518905341276154117290626643894778518937238780638934", "8540282347950069110111733092711864821174760764769274658962884672243005043396"),
+    scalars: [
+        "446371678903360124661747118626766461972311602250509962735",
+        "2736030358979909402780800718157159386076813972158567259200215660948447373038",
+        "12345"
+    ],
+    first_point_mul: ("6244746676557904402934431319359034658592108431643115609096505698186190392894", "8606169473692200504932468215113318143640728204773997434200772037763439291137"),
+    multiexp: ("16035379761535494849024810843075002065279970396808116701022146303363178043188", "6271995910382423080187153910869299502947759617687336720139366290139211951921"),
+};
+
+// both a and d are non-squares, so formulas are only complete for points of odd order
+const BANDERSNATCH: EdwardsTestVector = EdwardsTestVector {
+    modulus: "52435875175126190479447740508185965837690552500527637822603658699938581184513",
+    a: "52435875175126190479447740508185965837690552500527637822603658699938581184508",
+    d: "45022363124591815672509500913686876175488063829319466900776701791074614335719",
+    order: "13108968793781547619861935127046491459309155893440570251786403306729687672801",
+    points: [
+        ("42953313128401762646511805751462280806346757485365780868914657536359212328933", "38056756617395563458528099435476877644581404414324250147778359488304757565082"),
+        ("35397776266695016626421078869619453604837888697985001444415277074889477949000", "25768264061215539101477600617822838798053349124270174294749158996782998379877"),
+        ("20813443861373469401799287114415407292593276758795295545554820307520087105013", "32132560517831236097865326892371463723012432673986389899250992077211853261834"),
+    ],
+    sum_of_first_two: ("49480663342173368186921795492181518977472094677468645282580616747603254880826", "22352354807691639142387337371377550532238533535845440309911380530927955549753"),
+    scalars: [
+        "446371678903360124661747118626766461972311602250509962735",
+        "13108968793781547619861935127046491459309155893440570251786403306729687672798",
+        "12345"
+    ],
+    first_point_mul: ("42534001736459768316043506989469909906191419520707531907204446409633644699194", "33737699856663879240768056065293125392246724233131959102511938763238851038717"),
+    multiexp: ("796856023184721360120258969602157542691283186534097807835090792325715429131", "9732300037531737965740440437865672850239762596776563925900340354074173389577"),
+};
+
+fn encode_dec(value: &str, len: usize) -> Vec<u8> {
+    let value = BigUint::from_str_radix(value, 10).unwrap();
+
+    pad_for_len_be(value.to_bytes_be(), len)
+}
+
+fn encode_point(point: (&str, &str)) -> Vec<u8> {
+    let mut encoding = encode_dec(point.0, MODULUS_LENGTH);
+    encoding.extend(encode_dec(point.1, MODULUS_LENGTH));
+
+    encoding
+}
+
+fn order_length(vector: &EdwardsTestVector) -> usize {
+    BigUint::from_str_radix(vector.order, 10).unwrap().to_bytes_be().len()
+}
+
+// - Lengths of modulus (in bytes)
+// - Field modulus
+// - Curve A
+// - Curve D
+// - Length of the group order
+// - Group order
+fn assemble_prefix(vector: &EdwardsTestVector) -> Vec<u8> {
+    let order_len = order_length(vector);
+    let mut calldata = vec![MODULUS_LENGTH as u8];
+    calldata.extend(encode_dec(vector.modulus, MODULUS_LENGTH));
+    calldata.extend(encode_dec(vector.a, MODULUS_LENGTH));
+    calldata.extend(encode_dec(vector.d, MODULUS_LENGTH));
+    calldata.push(order_len as u8);
+    calldata.extend(encode_dec(vector.order, order_len));
+
+    calldata
+}
+
+fn assemble_addition(vector: &EdwardsTestVector) -> Vec<u8> {
+    let mut calldata = assemble_prefix(vector);
+    calldata.extend(encode_point(vector.points[0]));
+    calldata.extend(encode_point(vector.points[1]));
+
+    calldata
+}
+
+fn assemble_multiplication(vector: &EdwardsTestVector) -> Vec<u8> {
+    let mut calldata = assemble_prefix(vector);
+    calldata.extend(encode_point(vector.points[0]));
+    calldata.extend(encode_dec(vector.scalars[0], order_length(vector)));
+
+    calldata
+}
+
+fn assemble_multiexp(vector: &EdwardsTestVector) -> Vec<u8> {
+    let mut calldata = assemble_prefix(vector);
+    calldata.push(3u8);
+    for (point, scalar) in vector.points.iter().zip(vector.scalars.iter()) {
+        calldata.extend(encode_point(*point));
+        calldata.extend(encode_dec(scalar, order_length(vector)));
+    }
+
+    calldata
+}
+
+fn run_test_vector(vector: &EdwardsTestVector) {
+    let result = PublicEdwardsApi::add_points(&assemble_addition(vector), &ExecutionContext::default()).unwrap();
+    assert_eq!(result, encode_point(vector.sum_of_first_two));
+
+    let result = PublicEdwardsApi::mul_point(&assemble_multiplication(vector), &ExecutionContext::default()).unwrap();
+    assert_eq!(result, encode_point(vector.first_point_mul));
+
+    let result = PublicEdwardsApi::multiexp(&assemble_multiexp(vector), &ExecutionContext::default()).unwrap();
+    assert_eq!(result, encode_point(vector.multiexp));
+
+    // same through the operation byte
+    let mut input = vec![OPERATION_EDWARDS_MULTIEXP];
+    input.extend(assemble_multiexp(vector));
+    assert_eq!(API::run(&input).unwrap(), encode_point(vector.multiexp));
+}
+
+#[test]
+fn test_jubjub_operations() {
+    run_test_vector(&JUBJUB);
+}
+
+#[test]
+fn test_baby_jubjub_operations() {
+    run_test_vector(&BABY_JUBJUB);
+}
+
+#[test]
+fn test_bandersnatch_operations() {
+    run_test_vector(&BANDERSNATCH);
+}
+
+#[test]
+fn test_edwards_neutral_element() {
+    let neutral = encode_point(("0", "1"));
+
+    // P + (-P) = (0, 1)
+    let (x, y) = JUBJUB.points[0];
+    let minus_x = BigUint::from_str_radix(JUBJUB.modulus, 10).unwrap() - BigUint::from_str_radix(x, 10).unwrap();
+    let mut calldata = assemble_prefix(&JUBJUB);
+    calldata.extend(encode_point((x, y)));
+    calldata.extend(encode_point((&minus_x.to_str_radix(10), y)));
+    assert_eq!(PublicEdwardsApi::add_points(&calldata, &ExecutionContext::default()).unwrap(), neutral);
+
+    // r * P = (0, 1)
+    let mut calldata = assemble_prefix(&JUBJUB);
+    calldata.extend(encode_point(JUBJUB.points[0]));
+    calldata.extend(encode_dec(JUBJUB.order, order_length(&JUBJUB)));
+    assert_eq!(PublicEdwardsApi::mul_point(&calldata, &ExecutionContext::default()).unwrap(), neutral);
+
+    // (0, 1) + P = P
+    let mut calldata = assemble_prefix(&JUBJUB);
+    calldata.extend(neutral.clone());
+    calldata.extend(encode_point(JUBJUB.points[0]));
+    assert_eq!(PublicEdwardsApi::add_points(&calldata, &ExecutionContext::default()).unwrap(), encode_point(JUBJUB.points[0]));
+}
+
+#[test]
+fn test_edwards_invalid_inputs() {
+    // (0, 0) is not on the curve
+    let mut calldata = assemble_prefix(&BABY_JUBJUB);
+    calldata.extend(encode_point(("0", "0")));
+    calldata.extend(encode_point(BABY_JUBJUB.points[0]));
+    assert_eq!(PublicEdwardsApi::add_points(&calldata, &ExecutionContext::default()).unwrap_err().code(), ErrorCode::NotOnCurve);
+
+    // a == d is a singular curve
+    let mut vector = BABY_JUBJUB;
+    vector.d = vector.a;
+    assert!(PublicEdwardsApi::add_points(&assemble_addition(&vector), &ExecutionContext::default()).is_err());
+
+    // garbage at the end
+    let mut calldata = assemble_multiplication(&BABY_JUBJUB);
+    calldata.push(0u8);
+    let err = PublicEdwardsApi::mul_point(&calldata, &ExecutionContext::default()).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::GarbageAtTheEnd);
+    assert_eq!(err.offset(calldata.len()), Some(calldata.len() - 1));
+}
+
+#[test]
+fn test_meter_edwards_operations() {
+    let mut input = vec![OPERATION_EDWARDS_ADD];
+    input.extend(assemble_addition(&JUBJUB));
+    let addition = crate::gas_meter::GasMeter::meter(&input).unwrap();
+
+    let mut input = vec![OPERATION_EDWARDS_MUL];
+    input.extend(assemble_multiplication(&JUBJUB));
+    let multiplication = crate::gas_meter::GasMeter::meter(&input).unwrap();
+
+    let mut input = vec![OPERATION_EDWARDS_MULTIEXP];
+    input.extend(assemble_multiexp(&JUBJUB));
+    let multiexp = crate::gas_meter::GasMeter::meter(&input).unwrap();
+
+    assert!(addition > 0);
+    assert!(multiplication > addition);
+    assert!(multiexp > multiplication);
+}
@@ -0,0 +1,335 @@
+use crate::public_interface::constants::*;
+use crate::public_interface::{FieldApi, PublicFieldApi, ErrorCode, API};
+
+use num_bigint::BigUint;
+use num_traits::{Num, Zero};
+use rand::{RngCore, SeedableRng};
+use rand_xorshift::XorShiftRng;
+
+use crate::test::parsers::*;
+
+const BN254_MODULUS: &str = "30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47";
+
+const BN254_SCALAR_MODULUS: &str = "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001";
+
+// 2^255 - 1 is divisible by 7 and 31
+const COMPOSITE_MODULUS: &str = "7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff";
+
+// - Lengths of modulus (in bytes)
+// - Field modulus
+// - Operands, each padded to the modulus length
+fn assemble_field_input(modulus: &BigUint, operands: &[&BigUint]) -> Vec<u8> {
+    let modulus_len = modulus.to_bytes_be().len();
+
+    let mut calldata = vec![modulus_len as u8];
+    calldata.extend(modulus.to_bytes_be());
+    for el in operands.iter() {
+        calldata.extend(pad_for_len_be(el.to_bytes_be(), modulus_len));
+    }
+
+    calldata
+}
+
+fn assemble_exp_input(modulus: &BigUint, base: &BigUint, exponent: &BigUint) -> Vec<u8> {
+    let mut calldata = assemble_field_input(modulus, &[base]);
+    let exponent_encoding = exponent.to_bytes_be();
+    calldata.push(exponent_encoding.len() as u8);
+    calldata.extend(exponent_encoding);
+
+    calldata
+}
+
+fn random_element(rng: &mut XorShiftRng, modulus: &BigUint) -> BigUint {
+    let mut buff = vec![0u8; 64];
+    rng.fill_bytes(&mut buff);
+
+    BigUint::from_bytes_be(&buff) % modulus
+}
+
+#[test]
+fn test_field_arithmetic_against_bigint() {
+    let modulus = BigUint::from_str_radix(BN254_MODULUS, 16).unwrap();
+    let modulus_len = modulus.to_bytes_be().len();
+    let mut rng = XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+
+    for _ in 0..20 {
+        let a = random_element(&mut rng, &modulus);
+        let b = random_element(&mut rng, &modulus);
+
+        let sum = PublicFieldApi::add(&assemble_field_input(&modulus, &[&a, &b])).unwrap();
+        assert_eq!(sum, pad_for_len_be(((&a + &b) % &modulus).to_bytes_be(), modulus_len));
+
+        let product = PublicFieldApi::mul(&assemble_field_input(&modulus, &[&a, &b])).unwrap();
+        assert_eq!(product, pad_for_len_be(((&a * &b) % &modulus).to_bytes_be(), modulus_len));
+
+        let exponent = random_element(&mut rng, &modulus);
+        let power = PublicFieldApi::exp(&assemble_exp_input(&modulus, &a, &exponent)).unwrap();
+        assert_eq!(power, pad_for_len_be(a.modpow(&exponent, &modulus).to_bytes_be(), modulus_len));
+
+        if a.is_zero() {
+            continue;
+        }
+        let inverse = PublicFieldApi::inverse(&assemble_field_input(&modulus, &[&a])).unwrap();
+        let expected = a.modpow(&(&modulus - BigUint::from(2u64)), &modulus);
+        assert_eq!(inverse, pad_for_len_be(expected.to_bytes_be(), modulus_len));
+    }
+}
+
+#[test]
+fn test_field_operations_through_api() {
+    let modulus = BigUint::from_str_radix(BN254_MODULUS, 16).unwrap();
+    let two = BigUint::from(2u64);
+    let three = BigUint::from(3u64);
+
+    let mut input = vec![OPERATION_FIELD_MUL];
+    input.extend(assemble_field_input(&modulus, &[&two, &three]));
+    assert_eq!(API::run(&input).unwrap(), pad_for_len_be(vec![6u8], 32));
+
+    // 2^0 == 1
+    let mut input = vec![OPERATION_FIELD_EXP];
+    input.extend(assemble_exp_input(&modulus, &two, &BigUint::zero()));
+    assert_eq!(API::run(&input).unwrap(), pad_for_len_be(vec![1u8], 32));
+
+    // (p - 1) + 2 == 1
+    let minus_one = &modulus - BigUint::from(1u64);
+    let mut input = vec![OPERATION_FIELD_ADD];
+    input.extend(assemble_field_input(&modulus, &[&minus_one, &two]));
+    assert_eq!(API::run(&input).unwrap(), pad_for_len_be(vec![1u8], 32));
+
+    let mut input = vec![OPERATION_FIELD_INVERSE];
+    input.extend(assemble_field_input(&modulus, &[&minus_one]));
+    assert_eq!(API::run(&input).unwrap(), pad_for_len_be(minus_one.to_bytes_be(), 32));
+}
+
+#[test]
+fn test_field_operations_invalid_inputs() {
+    let modulus = BigUint::from_str_radix(BN254_MODULUS, 16).unwrap();
+    let one = BigUint::from(1u64);
+
+    // operand is not below the modulus
+    assert!(PublicFieldApi::add(&assemble_field_input(&modulus, &[&modulus, &one])).is_err());
+
+    let mut input = assemble_field_input(&modulus, &[&one, &one]);
+    input.push(0u8);
+    assert!(PublicFieldApi::mul(&input).is_err());
+
+    // missing second operand
+    assert!(PublicFieldApi::mul(&assemble_field_input(&modulus, &[&one])).is_err());
+
+    assert!(PublicFieldApi::inverse(&assemble_field_input(&modulus, &[&BigUint::zero()])).is_err());
+
+    // even modulus
+    let even_modulus = &modulus + &one;
+    assert!(PublicFieldApi::add(&assemble_field_input(&even_modulus, &[&one, &one])).is_err());
+
+    // 7 shares a factor with the modulus
+    let composite = BigUint::from_str_radix(COMPOSITE_MODULUS, 16).unwrap();
+    assert!(PublicFieldApi::inverse(&assemble_field_input(&composite, &[&BigUint::from(7u64)])).is_err());
+    let inverse = PublicFieldApi::inverse(&assemble_field_input(&composite, &[&BigUint::from(2u64)])).unwrap();
+    assert_eq!(inverse, pad_for_len_be(((&composite + &one) / BigUint::from(2u64)).to_bytes_be(), 32));
+
+    let mut input = assemble_exp_input(&modulus, &one, &one);
+    input.push(0u8);
+    assert!(PublicFieldApi::exp(&input).is_err());
+}
+
+#[test]
+fn test_field_operations_error_codes() {
+    let modulus = BigUint::from_str_radix(BN254_MODULUS, 16).unwrap();
+    let one = BigUint::from(1u64);
+
+    // second operand is not below the modulus and starts after the length byte, modulus and first operand
+    let mut input = vec![OPERATION_FIELD_ADD];
+    input.extend(assemble_field_input(&modulus, &[&one, &modulus]));
+    let err = API::run(&input).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::NotInField);
+    assert_eq!(err.offset(input.len()), Some(1 + 1 + 32 + 32));
+
+    // second operand is truncated, the total length is checked before parsing
+    let mut input = vec![OPERATION_FIELD_MUL];
+    input.extend(assemble_field_input(&modulus, &[&one, &one]));
+    input.truncate(input.len() - 1);
+    let err = API::run(&input).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::InputTooShort { expected: 1 + 32 + 32 + 32, got: 1 + 32 + 32 + 31 });
+    assert_eq!(err.offset(input.len()), Some(input.len()));
+
+    // decoders report the value that is truncated
+    let mut input = assemble_field_input(&modulus, &[&one, &one]);
+    input.truncate(input.len() - 1);
+    let err = PublicFieldApi::mul(&input).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::InputTooShort { expected: 32, got: 31 });
+    assert_eq!(err.offset(input.len()), Some(1 + 32 + 32));
+
+    let mut input = vec![OPERATION_FIELD_MUL];
+    input.extend(assemble_field_input(&modulus, &[&one, &one]));
+    input.extend(vec![0u8; 3]);
+    let err = API::run(&input).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::GarbageAtTheEnd);
+    assert_eq!(err.offset(input.len()), Some(input.len() - 3));
+
+    let even_modulus = &modulus + &one;
+    let mut input = vec![OPERATION_FIELD_ADD];
+    input.extend(assemble_field_input(&even_modulus, &[&one, &one]));
+    assert_eq!(API::run(&input).unwrap_err().code(), ErrorCode::InvalidModulus);
+
+    // 4 is a square, so it can not define Fp2
+    let input = assemble_sqrt_ext2_input(&modulus, &BigUint::from(4u64), &one, &one);
+    assert_eq!(PublicFieldApi::sqrt_ext2(&input).unwrap_err().code(), ErrorCode::NonResidueInvalid);
+
+    // errors without a code are still classified
+    let mut input = vec![OPERATION_FIELD_INVERSE];
+    input.extend(assemble_field_input(&modulus, &[&BigUint::zero()]));
+    let err = API::run(&input).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::InvalidInput);
+    assert_eq!(err.offset(input.len()), None);
+}
+
+fn assemble_sqrt_ext2_input(modulus: &BigUint, non_residue: &BigUint, c0: &BigUint, c1: &BigUint) -> Vec<u8> {
+    let modulus_len = modulus.to_bytes_be().len();
+    let mut calldata = vec![modulus_len as u8];
+    calldata.extend(modulus.to_bytes_be());
+    calldata.push(EXTENSION_DEGREE_2);
+    calldata.extend(pad_for_len_be(non_residue.to_bytes_be(), modulus_len));
+    calldata.extend(pad_for_len_be(c0.to_bytes_be(), modulus_len));
+    calldata.extend(pad_for_len_be(c1.to_bytes_be(), modulus_len));
+
+    calldata
+}
+
+// (c0 + c1*u)^2 with u^2 = non_residue
+fn square_ext2(modulus: &BigUint, non_residue: &BigUint, c0: &BigUint, c1: &BigUint) -> (BigUint, BigUint) {
+    let r0 = (c0 * c0 + non_residue * c1 * c1) % modulus;
+    let r1 = (BigUint::from(2u64) * c0 * c1) % modulus;
+
+    (r0, r1)
+}
+
+fn is_square(modulus: &BigUint, el: &BigUint) -> bool {
+    let power = (modulus - BigUint::from(1u64)) >> 1;
+
+    el.modpow(&power, modulus) != modulus - BigUint::from(1u64)
+}
+
+#[test]
+fn test_sqrt_ext2() {
+    let modulus = BigUint::from_str_radix(BN254_MODULUS, 16).unwrap();
+    let modulus_len = modulus.to_bytes_be().len();
+    let mut rng = XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+
+    // p = 3 mod 4, so -1 and -4 are both non-residues, but only u^2 = -1 uses the specialized algorithm
+    for non_residue in [&modulus - BigUint::from(1u64), &modulus - BigUint::from(4u64)].iter() {
+        for _ in 0..10 {
+            let x0 = random_element(&mut rng, &modulus);
+            let x1 = random_element(&mut rng, &modulus);
+            let (c0, c1) = square_ext2(&modulus, non_residue, &x0, &x1);
+
+            let mut input = vec![OPERATION_FIELD_EXT2_SQRT];
+            input.extend(assemble_sqrt_ext2_input(&modulus, non_residue, &c0, &c1));
+            let root = API::run(&input).unwrap();
+            assert_eq!(root.len(), 2 * modulus_len);
+
+            let r0 = BigUint::from_bytes_be(&root[..modulus_len]);
+            let r1 = BigUint::from_bytes_be(&root[modulus_len..]);
+            assert_eq!(square_ext2(&modulus, non_residue, &r0, &r1), (c0.clone(), c1.clone()));
+
+            // the even root is returned
+            let sign = if r0.is_zero() { &r1 } else { &r0 };
+            assert!((sign % BigUint::from(2u64)).is_zero());
+
+            // elements of the base field are squares in Fp2
+            let root = PublicFieldApi::sqrt_ext2(&assemble_sqrt_ext2_input(&modulus, non_residue, &x0, &BigUint::zero())).unwrap();
+            let r0 = BigUint::from_bytes_be(&root[..modulus_len]);
+            let r1 = BigUint::from_bytes_be(&root[modulus_len..]);
+            assert_eq!(square_ext2(&modulus, non_residue, &r0, &r1), (x0.clone(), BigUint::zero()));
+        }
+
+        // a is a square in Fp2 iff its norm is a square in Fp
+        loop {
+            let c0 = random_element(&mut rng, &modulus);
+            let c1 = random_element(&mut rng, &modulus);
+            let t = (non_residue * &c1 * &c1) % &modulus;
+            let norm = (&c0 * &c0 + &modulus - t) % &modulus;
+            if is_square(&modulus, &norm) {
+                continue;
+            }
+            assert!(PublicFieldApi::sqrt_ext2(&assemble_sqrt_ext2_input(&modulus, non_residue, &c0, &c1)).is_err());
+            break;
+        }
+    }
+
+    // p = 1 mod 4 uses Tonelli-Shanks in the base field, 5 generates the multiplicative group
+    let modulus = BigUint::from_str_radix(BN254_SCALAR_MODULUS, 16).unwrap();
+    let non_residue = BigUint::from(5u64);
+    for _ in 0..10 {
+        let x0 = random_element(&mut rng, &modulus);
+        let x1 = random_element(&mut rng, &modulus);
+        let (c0, c1) = square_ext2(&modulus, &non_residue, &x0, &x1);
+
+        let root = PublicFieldApi::sqrt_ext2(&assemble_sqrt_ext2_input(&modulus, &non_residue, &c0, &c1)).unwrap();
+        let r0 = BigUint::from_bytes_be(&root[..modulus_len]);
+        let r1 = BigUint::from_bytes_be(&root[modulus_len..]);
+        assert_eq!(square_ext2(&modulus, &non_residue, &r0, &r1), (c0, c1));
+    }
+
+    // 4 is a square, so it can not define Fp2
+    let input = assemble_sqrt_ext2_input(&modulus, &BigUint::from(4u64), &BigUint::from(4u64), &BigUint::zero());
+    assert!(PublicFieldApi::sqrt_ext2(&input).is_err());
+}
+
+#[test]
+fn test_meter_field_operations() {
+    let modulus = BigUint::from_str_radix(BN254_MODULUS, 16).unwrap();
+    let a = BigUint::from(5u64);
+
+    let mut input = vec![OPERATION_FIELD_MUL];
+    input.extend(assemble_field_input(&modulus, &[&a, &a]));
+    let mul_gas = crate::gas_meter::GasMeter::meter(&input).unwrap();
+    assert!(mul_gas > 0);
+    input.push(0u8);
+    assert!(crate::gas_meter::GasMeter::meter(&input).is_err());
+
+    let mut input = vec![OPERATION_FIELD_INVERSE];
+    input.extend(assemble_field_input(&modulus, &[&a]));
+    let inverse_gas = crate::gas_meter::GasMeter::meter(&input).unwrap();
+    assert!(inverse_gas > mul_gas);
+
+    let mut input = vec![OPERATION_FIELD_EXP];
+    input.extend(assemble_exp_input(&modulus, &a, &modulus));
+    let exp_gas = crate::gas_meter::GasMeter::meter(&input).unwrap();
+    assert!(exp_gas > mul_gas);
+
+    let mut input = vec![OPERATION_FIELD_EXT2_SQRT];
+    input.extend(assemble_sqrt_ext2_input(&modulus, &(&modulus - BigUint::from(1u64)), &a, &a));
+    let sqrt_gas = crate::gas_meter::GasMeter::meter(&input).unwrap();
+    assert!(sqrt_gas > inverse_gas);
+    input.push(0u8);
+    assert!(crate::gas_meter::GasMeter::meter(&input).is_err());
+}
+
+#[cfg(feature = "execution_report")]
+#[test]
+fn test_field_operations_execution_report() {
+    let modulus = BigUint::from_str_radix(BN254_MODULUS, 16).unwrap();
+    let a = BigUint::from(5u64);
+
+    let mut input = vec![OPERATION_FIELD_MUL];
+    input.extend(assemble_field_input(&modulus, &[&a, &a]));
+    let (output, mul_report) = API::run_with_metadata(&input).unwrap();
+    assert_eq!(output, API::run(&input).unwrap());
+    assert_eq!(mul_report.parsed_limbs, 4);
+    assert_eq!(mul_report.num_pairs, 0);
+    assert!(mul_report.field_multiplications >= 1);
+
+    // counters are reset between calls and are not touched by plain `run`
+    let (_, report) = API::run_with_metadata(&input).unwrap();
+    assert_eq!(report.field_multiplications, mul_report.field_multiplications);
+
+    let mut input = vec![OPERATION_FIELD_EXP];
+    input.extend(assemble_exp_input(&modulus, &a, &modulus));
+    let (_, exp_report) = API::run_with_metadata(&input).unwrap();
+    assert_eq!(exp_report.parsed_limbs, 4);
+    assert!(exp_report.field_multiplications >= 254);
+
+    input.push(0u8);
+    assert_eq!(API::run_with_metadata(&input).unwrap_err().code(), ErrorCode::GarbageAtTheEnd);
+}
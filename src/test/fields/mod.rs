@@ -98,3 +98,413 @@ fn test_fp3_inversion() {
     assert_eq!(maybe_one, Fp3::one(&extension_3));
 }
 
+
+#[test]
+fn test_fp2_quadratic_residues() {
+    use num_bigint::BigUint;
+    use rand::{RngCore, SeedableRng};
+    use rand_xorshift::XorShiftRng;
+    use crate::fp::Fp;
+    use crate::traits::{FieldElement, ZeroAndOne};
+    use crate::extension_towers::fp2::{Fp2, is_quadratic_residue};
+    use crate::engines::bls12_381::*;
+
+    let mut modulus_encoding = vec![0u8; BLS12_381_MODULUS_UINT.as_ref().len() * 8];
+    BLS12_381_MODULUS_UINT.to_big_endian(&mut modulus_encoding);
+    let modulus = BigUint::from_bytes_be(&modulus_encoding);
+
+    // known sextic non-residue 1 + u
+    let mut non_residue = BLS12_381_FP2_ONE.clone();
+    non_residue.c1 = BLS12_381_FP_ONE.clone();
+    assert!(!is_quadratic_residue(&non_residue));
+    // u is a square for p = 3 mod 4, so the search ends at 1 + u
+    assert_eq!(crate::square_root::find_quadratic_non_residue_ext2(&BLS12_381_EXTENSION_2_FIELD), Some(non_residue.clone()));
+
+    assert!(is_quadratic_residue(&BLS12_381_FP2_ZERO));
+    assert!(is_quadratic_residue(&BLS12_381_FP2_ONE));
+
+    let mut rng = XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+    let mut random_fp = || {
+        let mut buff = vec![0u8; 96];
+        rng.fill_bytes(&mut buff);
+        let num = BigUint::from_bytes_be(&buff) % &modulus;
+
+        Fp::from_be_bytes(&BLS12_381_FIELD, &num.to_bytes_be(), true).unwrap()
+    };
+
+    for _ in 0..100 {
+        let mut el = Fp2::zero(&BLS12_381_EXTENSION_2_FIELD);
+        el.c0 = random_fp();
+        el.c1 = random_fp();
+        if el.is_zero() {
+            continue;
+        }
+
+        let mut square = el.clone();
+        square.square();
+        assert!(is_quadratic_residue(&square));
+
+        let mut non_square = square.clone();
+        non_square.mul_assign(&non_residue);
+        assert!(!is_quadratic_residue(&non_square));
+    }
+}
+
+#[test]
+fn test_fp12_tower_from_be_bytes() {
+    use num_bigint::BigUint;
+    use rand::{RngCore, SeedableRng};
+    use rand_xorshift::XorShiftRng;
+    use crate::fp::Fp;
+    use crate::traits::{FieldElement, ZeroAndOne};
+    use crate::extension_towers::fp2::{Fp2, Extension2};
+    use crate::extension_towers::fp6_as_3_over_2::{Fp6, Extension3Over2};
+    use crate::extension_towers::fp12_as_2_over3_over_2::{Fp12, Extension2Over3Over2};
+    use crate::engines::bn254::*;
+
+    let mut modulus_encoding = vec![0u8; BN254_MODULUS.as_ref().len() * 8];
+    BN254_MODULUS.to_big_endian(&mut modulus_encoding);
+    let modulus = BigUint::from_bytes_be(&modulus_encoding);
+
+    // build the tower from scratch using only public constructors
+    let mut fp_non_residue = Fp::one(&*BN254_BASE_FIELD);
+    fp_non_residue.negate();
+    let mut extension_2 = Extension2::new(fp_non_residue);
+    extension_2.calculate_frobenius_coeffs(&*BN254_MODULUS).expect("must work");
+
+    let mut fp2_non_residue_encoding = vec![0u8; 64];
+    fp2_non_residue_encoding[31] = 9;
+    fp2_non_residue_encoding[63] = 1;
+    let fp2_non_residue = Fp2::from_be_bytes(&extension_2, &fp2_non_residue_encoding).unwrap();
+    let mut extension_6 = Extension3Over2::new(fp2_non_residue);
+    extension_6.calculate_frobenius_coeffs_optimized(&*BN254_MODULUS).expect("must work");
+
+    let mut extension_12 = Extension2Over3Over2::new(Fp6::zero(&extension_6));
+    extension_12.calculate_frobenius_coeffs_optimized(&*BN254_MODULUS).expect("must work");
+
+    let mut rng = XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+
+    for _ in 0..10 {
+        let mut encoding = vec![];
+        for _ in 0..12 {
+            let mut buff = vec![0u8; 64];
+            rng.fill_bytes(&mut buff);
+            let num = BigUint::from_bytes_be(&buff) % &modulus;
+            let num_encoding = num.to_bytes_be();
+            encoding.extend(vec![0u8; 32 - num_encoding.len()]);
+            encoding.extend(num_encoding);
+        }
+
+        let el = Fp12::from_be_bytes(&extension_12, &encoding).unwrap();
+        let first_coeff = Fp::from_be_bytes(&*BN254_BASE_FIELD, &encoding[0..32], false).unwrap();
+        let last_coeff = Fp::from_be_bytes(&*BN254_BASE_FIELD, &encoding[352..384], false).unwrap();
+        assert!(el.c0.c0.c0 == first_coeff);
+        assert!(el.c1.c2.c1 == last_coeff);
+
+        // same element in the tower of the engine
+        let engine_el = Fp12::from_be_bytes(&*BN254_EXT12_FIELD, &encoding).unwrap();
+
+        let mut squared = el.clone();
+        squared.square();
+        let mut engine_squared = engine_el.clone();
+        engine_squared.square();
+        assert_eq!(format!("{}", squared), format!("{}", engine_squared));
+
+        let inverse = el.inverse().unwrap();
+        let mut maybe_one = el.clone();
+        maybe_one.mul_assign(&inverse);
+        assert!(maybe_one == Fp12::one(&extension_12));
+
+        let mut frobenius = el.clone();
+        frobenius.frobenius_map(1);
+        assert!(frobenius == el.pow(BN254_MODULUS.as_ref()));
+
+        let mut frobenius_6 = el.clone();
+        frobenius_6.frobenius_map(6);
+        frobenius_6.frobenius_map(6);
+        assert!(frobenius_6 == el);
+    }
+
+    assert!(Fp12::from_be_bytes(&extension_12, &[0u8; 383]).is_err());
+    assert!(Fp12::from_be_bytes(&extension_12, &[]).is_err());
+
+    let mut not_in_field = vec![0u8; 64];
+    not_in_field[32..].copy_from_slice(&modulus.to_bytes_be());
+    assert!(Fp2::from_be_bytes(&extension_2, &not_in_field).is_err());
+}
+
+#[test]
+fn test_lazy_reduction_tower_multiplication() {
+    use num_bigint::BigUint;
+    use rand::{RngCore, SeedableRng};
+    use rand_xorshift::XorShiftRng;
+    use crate::representation::ElementRepr;
+    use crate::field::SizedPrimeField;
+    use crate::traits::FieldElement;
+    use crate::extension_towers::fp2::Fp2;
+    use crate::extension_towers::fp6_as_3_over_2::Fp6;
+    use crate::extension_towers::fp12_as_2_over3_over_2::{Fp12, Extension2Over3Over2};
+
+    fn random_element<'a, E: ElementRepr, F: SizedPrimeField<Repr = E>, R: RngCore>(
+        extension_12: &'a Extension2Over3Over2<'a, E, F>,
+        modulus: &MaxFieldUint,
+        rng: &mut R
+    ) -> Fp12<'a, E, F> {
+        let mut modulus_encoding = vec![0u8; modulus.as_ref().len() * 8];
+        modulus.to_big_endian(&mut modulus_encoding);
+        let modulus = BigUint::from_bytes_be(&modulus_encoding);
+        let element_len = E::NUM_LIMBS * 8;
+
+        let mut encoding = vec![];
+        for _ in 0..12 {
+            let mut buff = vec![0u8; element_len * 2];
+            rng.fill_bytes(&mut buff);
+            let num = (BigUint::from_bytes_be(&buff) % &modulus).to_bytes_be();
+            encoding.extend(vec![0u8; element_len - num.len()]);
+            encoding.extend(num);
+        }
+
+        Fp12::from_be_bytes(extension_12, &encoding).unwrap()
+    }
+
+    // schoolbook formulas down to the base field, without any lazy reduction
+    fn fp2_mul<'a, E: ElementRepr, F: SizedPrimeField<Repr = E>>(a: &Fp2<'a, E, F>, b: &Fp2<'a, E, F>) -> Fp2<'a, E, F> {
+        let mut c0 = a.c0;
+        c0.mul_assign(&b.c0);
+        let mut t = a.c1;
+        t.mul_assign(&b.c1);
+        t.mul_by_nonresidue(a.extension_field);
+        c0.add_assign(&t);
+
+        let mut c1 = a.c0;
+        c1.mul_assign(&b.c1);
+        let mut t = a.c1;
+        t.mul_assign(&b.c0);
+        c1.add_assign(&t);
+
+        Fp2 { c0, c1, extension_field: a.extension_field }
+    }
+
+    fn fp6_mul<'a, E: ElementRepr, F: SizedPrimeField<Repr = E>>(a: &Fp6<'a, E, F>, b: &Fp6<'a, E, F>) -> Fp6<'a, E, F> {
+        let mut c0 = fp2_mul(&a.c1, &b.c2);
+        c0.add_assign(&fp2_mul(&a.c2, &b.c1));
+        c0.mul_by_nonresidue(a.extension_field);
+        c0.add_assign(&fp2_mul(&a.c0, &b.c0));
+
+        let mut c1 = fp2_mul(&a.c2, &b.c2);
+        c1.mul_by_nonresidue(a.extension_field);
+        c1.add_assign(&fp2_mul(&a.c0, &b.c1));
+        c1.add_assign(&fp2_mul(&a.c1, &b.c0));
+
+        let mut c2 = fp2_mul(&a.c0, &b.c2);
+        c2.add_assign(&fp2_mul(&a.c1, &b.c1));
+        c2.add_assign(&fp2_mul(&a.c2, &b.c0));
+
+        Fp6 { c0, c1, c2, extension_field: a.extension_field }
+    }
+
+    fn fp12_mul<'a, E: ElementRepr, F: SizedPrimeField<Repr = E>>(a: &Fp12<'a, E, F>, b: &Fp12<'a, E, F>) -> Fp12<'a, E, F> {
+        let mut c0 = fp6_mul(&a.c1, &b.c1);
+        c0.mul_by_nonresidue(a.extension_field);
+        c0.add_assign(&fp6_mul(&a.c0, &b.c0));
+
+        let mut c1 = fp6_mul(&a.c0, &b.c1);
+        c1.add_assign(&fp6_mul(&a.c1, &b.c0));
+
+        Fp12 { c0, c1, extension_field: a.extension_field }
+    }
+
+    fn check<'a, E: ElementRepr, F: SizedPrimeField<Repr = E>>(extension_12: &'a Extension2Over3Over2<'a, E, F>, modulus: &MaxFieldUint) {
+        let mut rng = XorShiftRng::from_seed([0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc, 0xe5]);
+        for _ in 0..100 {
+            let a = random_element(extension_12, modulus, &mut rng);
+            let b = random_element(extension_12, modulus, &mut rng);
+
+            let mut fp2 = a.c0.c0;
+            fp2.mul_assign(&b.c1.c2);
+            assert!(fp2 == fp2_mul(&a.c0.c0, &b.c1.c2));
+
+            let mut fp6 = a.c1;
+            fp6.mul_assign(&b.c0);
+            assert!(fp6 == fp6_mul(&a.c1, &b.c0));
+
+            let mut fp12 = a;
+            fp12.mul_assign(&b);
+            assert!(fp12 == fp12_mul(&a, &b));
+
+            let mut squared = a;
+            squared.square();
+            assert!(squared == fp12_mul(&a, &a));
+        }
+    }
+
+    // Fp6 non-residue 1 + u
+    let extension_12 = crate::engines::bls12_381::BLS12_381_EXTENSION_12_FIELD;
+    check(&extension_12, &crate::engines::bls12_381::BLS12_381_MODULUS_UINT);
+
+    // Fp6 non-residue 9 + u is multiplied in the reduced form
+    check(&*crate::engines::bn254::BN254_EXT12_FIELD, &*crate::engines::bn254::BN254_MODULUS);
+}
+
+#[test]
+fn test_fpk_karatsuba_multiplication() {
+    use rand::{RngCore, SeedableRng};
+    use rand_xorshift::XorShiftRng;
+    use crate::fp::Fp;
+    use crate::traits::{FieldElement, ZeroAndOne};
+    use crate::extension_towers::fpk::{FpK, ExtensionK, MAX_BINOMIAL_EXTENSION_DEGREE};
+    use crate::engines::bn254::*;
+
+    let field = &*BN254_BASE_FIELD;
+    let mut rng = XorShiftRng::from_seed([0x8d, 0x31, 0x3d, 0x76, 0x5d, 0xbe, 0x62, 0x59, 0xe5, 0xbc, 0x06, 0x54, 0x32, 0x37, 0xdb, 0x17]);
+    let random_fp = |rng: &mut XorShiftRng| {
+        // top byte is zero, so it's always below the modulus
+        let mut encoding = [0u8; 32];
+        rng.fill_bytes(&mut encoding[1..]);
+
+        Fp::from_be_bytes(field, &encoding, false).unwrap()
+    };
+
+    for degree in 2..=MAX_BINOMIAL_EXTENSION_DEGREE {
+        let non_residue = random_fp(&mut rng);
+        let extension = ExtensionK::new(non_residue, degree).unwrap();
+
+        for _ in 0..10 {
+            let a_coeffs: Vec<_> = (0..degree).map(|_| random_fp(&mut rng)).collect();
+            let b_coeffs: Vec<_> = (0..degree).map(|_| random_fp(&mut rng)).collect();
+            let a = FpK::from_coeffs(&extension, &a_coeffs).unwrap();
+            let b = FpK::from_coeffs(&extension, &b_coeffs).unwrap();
+
+            // schoolbook reference
+            let mut expected = vec![Fp::zero(field); degree];
+            for i in 0..degree {
+                for j in 0..degree {
+                    let mut t = a_coeffs[i];
+                    t.mul_assign(&b_coeffs[j]);
+                    if i + j >= degree {
+                        t.mul_assign(&non_residue);
+                    }
+                    expected[(i + j) % degree].add_assign(&t);
+                }
+            }
+            let expected = FpK::from_coeffs(&extension, &expected).unwrap();
+
+            let mut product = a;
+            product.mul_assign(&b);
+            assert!(product == expected);
+
+            let mut squared = a;
+            squared.square();
+            let mut a_times_a = a;
+            a_times_a.mul_assign(&a);
+            assert!(squared == a_times_a);
+        }
+    }
+}
+
+#[test]
+fn test_fp12_compressed_cyclotomic_exp() {
+    use rand::{RngCore, SeedableRng};
+    use rand_xorshift::XorShiftRng;
+    use crate::traits::{FieldElement, ZeroAndOne};
+    use crate::extension_towers::fp12_as_2_over3_over_2::Fp12;
+    use crate::engines::bls12_381::*;
+
+    let extension_12 = BLS12_381_EXTENSION_12_FIELD;
+    let mut rng = XorShiftRng::from_seed([0x17, 0xdb, 0x37, 0x32, 0x59, 0x62, 0xbe, 0x5d, 0x54, 0x06, 0xbc, 0xe5, 0x76, 0x3d, 0x31, 0x8d]);
+
+    for _ in 0..10 {
+        // f^((p^6 - 1) * (p^2 + 1)) is in the cyclotomic subgroup
+        let mut encoding = vec![0u8; 12 * 48];
+        for chunk in encoding.chunks_mut(48) {
+            rng.fill_bytes(&mut chunk[1..]);
+            chunk[1] &= 0x0f;
+        }
+        let f = Fp12::from_be_bytes(&extension_12, &encoding).unwrap();
+        let mut r = f;
+        r.conjugate();
+        r.mul_assign(&f.inverse().unwrap());
+        let mut r_p2 = r;
+        r_p2.frobenius_map(2);
+        r.mul_assign(&r_p2);
+
+        let exponents = [
+            vec![0xd201000000010000],
+            vec![0x44e992b44a6909f1],
+            vec![1u64],
+            vec![0u64],
+            vec![rng.next_u64(), rng.next_u64()],
+        ];
+        for exp in exponents.iter() {
+            assert!(r.cyclotomic_exp_compressed(exp) == r.cyclotomic_exp(exp));
+        }
+    }
+
+    // g2 and g3 are zero, so the powers can not be decompressed
+    let one = Fp12::one(&extension_12);
+    assert!(one.cyclotomic_exp_compressed(&[0xd201000000010000]) == one);
+}
+
+#[test]
+fn test_tonelli_shanks_sqrt() {
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+    use crate::field::{U256Repr, new_field};
+    use crate::fp::Fp;
+    use crate::traits::{FieldElement, ZeroAndOne};
+    use crate::square_root::{sqrt, legendre_symbol_fp, LegendreSymbol};
+    use crate::test_utils::RandomElement;
+
+    let mut rng = XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+
+    // p = 5 mod 8 and p = 1 mod 2^28 (BN254 scalar field)
+    for modulus in ["57896044618658097711785492504343953926634992332820282019728792003956564819949",
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617"].iter() {
+        let field = new_field::<U256Repr>(modulus, 10).unwrap();
+
+        assert!(sqrt(&Fp::zero(&field)).unwrap().is_zero());
+
+        for _ in 0..20 {
+            let el = Fp::random(&field, &mut rng);
+            let mut square = el.clone();
+            square.square();
+
+            let root = sqrt(&square).unwrap();
+            let mut minus_el = el.clone();
+            minus_el.negate();
+            assert!(root == el || root == minus_el);
+
+            if legendre_symbol_fp(&el) == LegendreSymbol::QuadraticNonResidue {
+                assert!(sqrt(&el).is_none());
+            } else {
+                let mut check = sqrt(&el).unwrap();
+                check.square();
+                assert!(check == el);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn test_zeroize_field_elements() {
+    use zeroize::Zeroize;
+    use crate::field::{U256Repr, new_field};
+    use crate::fp::Fp;
+    use crate::extension_towers::fp2::{Fp2, Extension2};
+    use crate::traits::{FieldElement, ZeroAndOne};
+
+    let field = new_field::<U256Repr>("21888242871839275222246405745257275088696311157297823662689037894645226208583", 10).unwrap();
+    let mut el = Fp::one(&field);
+    el.double();
+    el.zeroize();
+    assert!(el.is_zero());
+    assert!(el.repr.as_ref().iter().all(|limb| *limb == 0));
+
+    let mut non_residue = Fp::one(&field);
+    non_residue.negate();
+    let extension = Extension2::new(non_residue);
+    let mut el = Fp2::one(&extension);
+    el.zeroize();
+    assert!(el.is_zero());
+}
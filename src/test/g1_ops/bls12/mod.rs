@@ -4,38 +4,16 @@ use crate::test::parsers::*;
 
 use super::*;
 
-pub(crate) fn assemble_single_curve_params(curve: JsonBls12PairingCurveParameters) -> (Vec<u8>, usize, usize) {
+pub(crate) fn assemble_single_curve_params(curve: &JsonBls12PairingCurveParameters) -> (Vec<u8>, usize, usize) {
     // - Lengths of modulus (in bytes)
     // - Field modulus
-    // - Extension degree
-    // - Non-redisue
     // - Curve A
     // - Curve B
+    // - Length of the group order (in bytes)
+    // - Group order
+    let descriptor = G1CurveDescriptor::new(&curve.q.to_bytes_be(), &curve.a.to_bytes_be(), &curve.b.to_bytes_be(), &curve.r.to_bytes_be()).unwrap();
 
-    // first determine the length of the modulus
-    let modulus = curve.q;
-    let modulus_length = modulus.clone().to_bytes_be().len();
-
-    let modulus_len_encoded = vec![modulus_length as u8];
-    let modulus_encoded = pad_for_len_be(modulus.clone().to_bytes_be(), modulus_length);
-
-    let a_encoded = pad_for_len_be(curve.a.to_bytes_be(), modulus_length);
-    let b_encoded = pad_for_len_be(curve.b.to_bytes_be(), modulus_length);
-
-    let group_size = curve.r;
-    let group_size_encoded = group_size.clone().to_bytes_be();
-    let group_size_length = group_size_encoded.len();
-    let group_len_encoded = vec![group_size_length as u8];
-
-    let mut calldata = vec![];
-    calldata.extend(modulus_len_encoded.into_iter());
-    calldata.extend(modulus_encoded.into_iter());
-    calldata.extend(a_encoded.into_iter());
-    calldata.extend(b_encoded.into_iter());
-    calldata.extend(group_len_encoded.into_iter());
-    calldata.extend(group_size_encoded.into_iter());
-
-    (calldata, modulus_length, group_size_length)
+    (descriptor.encode().unwrap(), descriptor.modulus_len(), descriptor.group_order_len())
 }
 
 pub(crate) fn assemble_single_point_scalar_pair(
@@ -104,7 +82,7 @@ fn test_g1_mul_from_vectors() {
     let curves = read_dir_and_grab_curves::<JsonBls12PairingCurveParameters>("src/test/test_vectors/bls12/");
     assert!(curves.len() != 0);
     for (curve, _) in curves.into_iter() {
-        let (calldata, modulus_len, group_len) = assemble_single_curve_params(curve.clone());
+        let (calldata, modulus_len, group_len) = assemble_single_curve_params(&curve);
         for pair in curve.g1_mul_vectors.into_iter() {
             let (points_data, expected_result) = assemble_single_point_scalar_pair(pair, modulus_len, group_len);
 
@@ -123,6 +101,211 @@ fn test_g1_mul_from_vectors() {
     }
 }
 
+// Multiexp over all the vectors of a curve must match the sum of the individual multiplication results
+#[test]
+fn test_g1_multiexp_from_vectors() {
+    let curves = read_dir_and_grab_curves::<JsonBls12PairingCurveParameters>("src/test/test_vectors/bls12/");
+    assert!(curves.len() != 0);
+    for (curve, _) in curves.into_iter() {
+        let (calldata, modulus_len, group_len) = assemble_single_curve_params(&curve);
+        let num_pairs = curve.g1_mul_vectors.len();
+        assert!(num_pairs != 0 && num_pairs < 256);
+
+        let mut multiexp_calldata = calldata.clone();
+        multiexp_calldata.push(num_pairs as u8);
+        let mut expected: Option<Vec<u8>> = None;
+        for pair in curve.g1_mul_vectors.into_iter() {
+            let (points_data, result) = assemble_single_point_scalar_pair(pair, modulus_len, group_len);
+            multiexp_calldata.extend(points_data);
+
+            expected = match expected {
+                None => Some(result),
+                Some(acc) => {
+                    let mut add_calldata = calldata.clone();
+                    add_calldata.extend(acc);
+                    add_calldata.extend(result);
+
+                    Some(call_g1_engine_add(&add_calldata[..]).expect("addition must work"))
+                }
+            };
+        }
+
+        let result = call_g1_engine_multiexp(&multiexp_calldata[..]);
+        if result.is_err() {
+            panic!("{}", result.err().unwrap());
+        }
+
+        let result = result.expect("guaranteed to exist");
+        assert_eq!(result.len(), 2 * modulus_len);
+        assert!(result == expected.expect("guaranteed to exist"));
+    }
+}
+
+// Bases and results of the multiplication vectors are in the subgroup
+#[test]
+fn test_g1_subgroup_check_from_vectors() {
+    let curves = read_dir_and_grab_curves::<JsonBls12PairingCurveParameters>("src/test/test_vectors/bls12/");
+    assert!(curves.len() != 0);
+    for (curve, _) in curves.into_iter() {
+        let (calldata, modulus_len, group_len) = assemble_single_curve_params(&curve);
+        for pair in curve.g1_mul_vectors.into_iter() {
+            let (points_data, result) = assemble_single_point_scalar_pair(pair, modulus_len, group_len);
+
+            let mut base_calldata = calldata.clone();
+            base_calldata.extend_from_slice(&points_data[..(2 * modulus_len)]);
+            assert_eq!(call_g1_engine_subgroup_check(&base_calldata[..]).unwrap(), vec![BOOLEAN_TRUE]);
+
+            let mut result_calldata = calldata.clone();
+            result_calldata.extend(result);
+            assert_eq!(call_g1_engine_subgroup_check(&result_calldata[..]).unwrap(), vec![BOOLEAN_TRUE]);
+        }
+    }
+}
+
+// BLS12-381 curve description in G1 calls, and modulus length
+fn assemble_bls12_381_curve_params() -> (Vec<u8>, usize) {
+    use crate::engines::bls12_381::*;
+    use crate::public_interface::decode_fp::serialize_fp_fixed_len;
+    use num_bigint::BigUint;
+    use num_traits::Num;
+
+    let modulus_len = 48;
+    let modulus = BigUint::from_str_radix("1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab", 16).unwrap();
+    let order = BigUint::from_str_radix("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001", 16).unwrap();
+
+    let mut calldata = vec![modulus_len as u8];
+    calldata.extend(pad_for_len_be(modulus.to_bytes_be(), modulus_len));
+    calldata.extend(serialize_fp_fixed_len(modulus_len, &BLS12_381_G1_CURVE.a).unwrap());
+    calldata.extend(serialize_fp_fixed_len(modulus_len, &BLS12_381_G1_CURVE.b).unwrap());
+    calldata.push(order.to_bytes_be().len() as u8);
+    calldata.extend(order.to_bytes_be());
+
+    (calldata, modulus_len)
+}
+
+#[test]
+fn test_g1_subgroup_check_outside_subgroup() {
+    use crate::engines::bls12_381::*;
+    use crate::public_interface::decode_g1::serialize_g1_point;
+    use crate::test_utils::random_point_outside_subgroup;
+    use crate::public_interface::API;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    let (calldata, modulus_len) = assemble_bls12_381_curve_params();
+
+    let mut generator = calldata.clone();
+    generator.extend(serialize_g1_point(modulus_len, &BLS12_381_G1_GENERATOR).unwrap());
+    assert_eq!(call_g1_engine_subgroup_check(&generator[..]).unwrap(), vec![BOOLEAN_TRUE]);
+
+    let mut infinity = calldata.clone();
+    infinity.extend(vec![0u8; 2 * modulus_len]);
+    assert_eq!(call_g1_engine_subgroup_check(&infinity[..]).unwrap(), vec![BOOLEAN_TRUE]);
+
+    let rng = &mut XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+    let p = random_point_outside_subgroup(&BLS12_381_G1_CURVE, rng).unwrap();
+    let mut outside = calldata.clone();
+    outside.extend(serialize_g1_point(modulus_len, &p).unwrap());
+    assert_eq!(call_g1_engine_subgroup_check(&outside[..]).unwrap(), vec![BOOLEAN_FALSE]);
+
+    let mut api_input = vec![OPERATION_G1_SUBGROUP_CHECK];
+    api_input.extend(outside.iter().cloned());
+    assert_eq!(API::run(&api_input).unwrap(), vec![BOOLEAN_FALSE]);
+
+    // priced as a multiplication by a scalar of the order length
+    #[cfg(feature = "gas_metering")]
+    {
+        let mut mul_input = vec![OPERATION_G1_MUL];
+        mul_input.extend(outside.iter().cloned());
+        mul_input.extend(vec![0xffu8; 32]);
+        assert_eq!(API::estimate_gas(&api_input).unwrap(), API::estimate_gas(&mul_input).unwrap());
+    }
+
+    let mut not_on_curve = generator.clone();
+    let last = not_on_curve.len() - 1;
+    not_on_curve[last] ^= 1;
+    assert!(call_g1_engine_subgroup_check(&not_on_curve[..]).is_err());
+
+    let mut garbage = generator.clone();
+    garbage.push(0u8);
+    assert!(call_g1_engine_subgroup_check(&garbage[..]).is_err());
+}
+
+#[test]
+fn test_g1_clear_cofactor() {
+    use crate::engines::bls12_381::*;
+    use crate::weierstrass::Group;
+    use crate::public_interface::decode_g1::serialize_g1_point;
+    use crate::public_interface::API;
+    use crate::test_utils::random_point_outside_subgroup;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    let (calldata, modulus_len) = assemble_bls12_381_curve_params();
+    let cofactor = crate::test::u64_vec_to_be_bytes(&BLS12_381_G1_MAPPING_H_EFF);
+
+    let rng = &mut XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+    for _ in 0..4 {
+        let p = random_point_outside_subgroup(&BLS12_381_G1_CURVE, rng).unwrap();
+
+        let mut input = calldata.clone();
+        input.extend(serialize_g1_point(modulus_len, &p).unwrap());
+        input.push(cofactor.len() as u8);
+        input.extend(cofactor.iter().cloned());
+
+        let result = call_g1_engine_clear_cofactor(&input).unwrap();
+        assert_eq!(result, serialize_g1_point(modulus_len, &p.mul(&BLS12_381_G1_MAPPING_H_EFF[..])).unwrap());
+
+        let mut check = calldata.clone();
+        check.extend(result.iter().cloned());
+        assert_eq!(call_g1_engine_subgroup_check(&check).unwrap(), vec![BOOLEAN_TRUE]);
+
+        let mut api_input = vec![OPERATION_G1_CLEAR_COFACTOR];
+        api_input.extend(input.iter().cloned());
+        assert_eq!(API::run(&api_input).unwrap(), result);
+    }
+
+    let mut zero_cofactor = calldata.clone();
+    zero_cofactor.extend(serialize_g1_point(modulus_len, &BLS12_381_G1_GENERATOR).unwrap());
+    zero_cofactor.extend(vec![1u8, 0u8]);
+    assert!(call_g1_engine_clear_cofactor(&zero_cofactor).is_err());
+}
+
+#[test]
+fn test_g1_validate_point() {
+    use crate::engines::bls12_381::*;
+    use crate::public_interface::decode_g1::serialize_g1_point;
+    use crate::public_interface::API;
+
+    let (calldata, modulus_len) = assemble_bls12_381_curve_params();
+    let validate = |point: &[u8]| {
+        let mut input = calldata.clone();
+        input.extend_from_slice(point);
+        call_g1_engine_validate_point(&input).unwrap()
+    };
+
+    let generator = serialize_g1_point(modulus_len, &BLS12_381_G1_GENERATOR).unwrap();
+    assert_eq!(validate(&generator), vec![POINT_IS_VALID]);
+
+    assert_eq!(validate(&vec![0u8; 2 * modulus_len]), vec![POINT_IS_AT_INFINITY]);
+
+    let mut not_on_curve = generator.clone();
+    not_on_curve[2 * modulus_len - 1] ^= 1;
+    assert_eq!(validate(&not_on_curve), vec![POINT_IS_NOT_ON_CURVE]);
+
+    let mut not_in_field = generator.clone();
+    not_in_field[0] = 0xff;
+    assert_eq!(validate(&not_in_field), vec![POINT_COORDINATE_NOT_IN_FIELD]);
+
+    let mut api_input = vec![OPERATION_G1_VALIDATE_POINT];
+    api_input.extend(calldata.iter().cloned());
+    api_input.extend(not_on_curve.iter().cloned());
+    assert_eq!(API::run(&api_input).unwrap(), vec![POINT_IS_NOT_ON_CURVE]);
+
+    api_input.push(0u8);
+    assert!(API::run(&api_input).is_err());
+}
+
 extern crate hex;
 extern crate csv;
 
@@ -137,7 +320,7 @@ fn dump_g1_mul_vectors() {
     let mut writer = Writer::from_path("src/test/test_vectors/bls12/g1_mul.csv").expect("must open a test file");
     writer.write_record(&["input", "result"]).expect("must write header");
     for (curve, _) in curves.into_iter() {
-        let (calldata, modulus_len, group_len) = assemble_single_curve_params(curve.clone());
+        let (calldata, modulus_len, group_len) = assemble_single_curve_params(&curve);
         for pair in curve.g1_mul_vectors.into_iter() {
             let (points_data, expected_result) = assemble_single_point_scalar_pair(pair, modulus_len, group_len);
             let mut input_data = vec![OPERATION_G1_MUL];
@@ -162,4 +345,3 @@ fn dump_g1_mul_vectors() {
 //         call_bls12_engine(&calldata[..]).expect("must use");
 //     });
 // }
-
@@ -7,38 +7,16 @@ use super::*;
 pub(crate) fn assemble_single_curve_params(curve: JsonBnPairingCurveParameters) -> (Vec<u8>, usize, usize) {
     // - Lengths of modulus (in bytes)
     // - Field modulus
-    // - Extension degree
-    // - Non-redisue
     // - Curve A
     // - Curve B
-
-    // first determine the length of the modulus
+    // - Length of the group order (in bytes)
+    // - Group order
     let modulus = curve.q;
-    let modulus_length = modulus.clone().to_bytes_be().len();
-
-    let modulus_len_encoded = vec![modulus_length as u8];
-    let modulus_encoded = pad_for_len_be(modulus.clone().to_bytes_be(), modulus_length);
-
-    let a_encoded = apply_sign(curve.a, &modulus);
-    let a_encoded = pad_for_len_be(a_encoded.to_bytes_be(), modulus_length);
-
-    let b_encoded = apply_sign(curve.b, &modulus);
-    let b_encoded = pad_for_len_be(b_encoded.to_bytes_be(), modulus_length);
+    let a = apply_sign(curve.a, &modulus);
+    let b = apply_sign(curve.b, &modulus);
+    let descriptor = G1CurveDescriptor::new(&modulus.to_bytes_be(), &a.to_bytes_be(), &b.to_bytes_be(), &curve.r.to_bytes_be()).unwrap();
 
-    let group_size = curve.r;
-    let group_size_encoded = group_size.clone().to_bytes_be();
-    let group_size_length = group_size_encoded.len();
-    let group_len_encoded = vec![group_size_length as u8];
-
-    let mut calldata = vec![];
-    calldata.extend(modulus_len_encoded.into_iter());
-    calldata.extend(modulus_encoded.into_iter());
-    calldata.extend(a_encoded.into_iter());
-    calldata.extend(b_encoded.into_iter());
-    calldata.extend(group_len_encoded.into_iter());
-    calldata.extend(group_size_encoded.into_iter());
-
-    (calldata, modulus_length, group_size_length)
+    (descriptor.encode().unwrap(), descriptor.modulus_len(), descriptor.group_order_len())
 }
 
 pub(crate) fn assemble_single_point_scalar_pair(
@@ -126,6 +104,47 @@ fn test_g1_mul_from_vectors() {
     }
 }
 
+// base points and results of the multiplications are summed with a chain of additions as a reference,
+// repeated points make the tree reduction double some of the partial sums
+#[test]
+fn test_g1_sum_from_vectors() {
+    let curves = read_dir_and_grab_curves::<JsonBnPairingCurveParameters>("src/test/test_vectors/bn/");
+    assert!(curves.len() != 0);
+    for (curve, filename) in curves.into_iter() {
+        let (calldata, modulus_len, group_len) = assemble_single_curve_params(curve.clone());
+        for pair in curve.g1_mul_vectors.into_iter() {
+            let (points_data, result) = assemble_single_point_scalar_pair(pair, modulus_len, group_len);
+            let base = points_data[..2 * modulus_len].to_vec();
+            let points = vec![base.clone(), result.clone(), base.clone(), result.clone(), result, base.clone(), base];
+
+            let mut expected = points[0].clone();
+            for num_points in 1..=points.len() {
+                if num_points > 1 {
+                    let mut addition = calldata.clone();
+                    addition.extend(expected);
+                    addition.extend(points[num_points - 1].iter());
+                    expected = call_g1_engine_add(&addition).unwrap();
+                }
+
+                let mut sum = calldata.clone();
+                sum.push(num_points as u8);
+                for point in points[..num_points].iter() {
+                    sum.extend(point.iter());
+                }
+                let result = call_g1_engine_sum(&sum);
+                if result.is_err() {
+                    panic!("Error for file {}: {}", filename, result.err().unwrap());
+                }
+                assert!(result.unwrap() == expected, "file {}, {} points", filename, num_points);
+
+                let mut call = vec![OPERATION_G1_SUM];
+                call.extend(sum);
+                assert!(crate::public_interface::API::run(&call).unwrap() == expected);
+            }
+        }
+    }
+}
+
 extern crate hex;
 extern crate csv;
 
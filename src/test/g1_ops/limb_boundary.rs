@@ -0,0 +1,157 @@
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+
+use crate::test::parsers::pad_for_len_be;
+
+use super::*;
+
+// Curve y^2 = x^3 + 3 with a point (1, 2) exists over any prime field with p > 3,
+// so the same shape can be used for every modulus length
+fn assemble_calldata_for_modulus(modulus: &BigUint) -> (Vec<u8>, usize) {
+    let modulus_len = modulus.to_bytes_be().len();
+
+    let mut calldata = vec![modulus_len as u8];
+    calldata.extend(modulus.to_bytes_be());
+    calldata.extend(pad_for_len_be(vec![0u8], modulus_len));
+    calldata.extend(pad_for_len_be(vec![3u8], modulus_len));
+    // group order only has to be prime, it is used to size the scalar encoding
+    calldata.extend(vec![1u8, 0xfb]);
+
+    (calldata, modulus_len)
+}
+
+fn encode_point(x: &BigUint, y: &BigUint, modulus_len: usize) -> Vec<u8> {
+    let mut encoding = pad_for_len_be(x.to_bytes_be(), modulus_len);
+    encoding.extend(pad_for_len_be(y.to_bytes_be(), modulus_len));
+
+    encoding
+}
+
+fn decode_point(encoding: &[u8], modulus_len: usize) -> (BigUint, BigUint) {
+    assert_eq!(encoding.len(), 2 * modulus_len);
+    let x = BigUint::from_bytes_be(&encoding[..modulus_len]);
+    let y = BigUint::from_bytes_be(&encoding[modulus_len..]);
+
+    (x, y)
+}
+
+fn is_on_curve(x: &BigUint, y: &BigUint, modulus: &BigUint) -> bool {
+    let lhs = (y * y) % modulus;
+    let rhs = (x * x * x + BigUint::from(3u64)) % modulus;
+
+    lhs == rhs
+}
+
+fn inverse(value: &BigUint, modulus: &BigUint) -> BigUint {
+    value.modpow(&(modulus - BigUint::from(2u64)), modulus)
+}
+
+// affine doubling of (1, 2) done with big integers as a reference
+fn reference_double_generator(modulus: &BigUint) -> (BigUint, BigUint) {
+    let x = BigUint::one();
+    let y = BigUint::from(2u64);
+    let lambda = (BigUint::from(3u64) * &x * &x * inverse(&(BigUint::from(2u64) * &y), modulus)) % modulus;
+    let x_3 = (&lambda * &lambda + modulus + modulus - &x - &x) % modulus;
+    let y_3 = (lambda * ((&x + modulus - &x_3) % modulus) + modulus - &y) % modulus;
+
+    (x_3, y_3)
+}
+
+fn run_add_and_mul_for_modulus(modulus: &BigUint) {
+    let (curve_calldata, modulus_len) = assemble_calldata_for_modulus(modulus);
+    let generator = encode_point(&BigUint::one(), &BigUint::from(2u64), modulus_len);
+
+    let mut calldata = curve_calldata.clone();
+    calldata.extend(generator.clone());
+    calldata.extend(generator.clone());
+    let doubled_by_addition = call_g1_engine_add(&calldata).expect(&format!("addition must work for {} bit modulus", modulus.bits()));
+
+    let mut calldata = curve_calldata.clone();
+    calldata.extend(generator.clone());
+    calldata.push(2u8);
+    let doubled_by_multiplication = call_g1_engine_mul(&calldata).expect(&format!("multiplication must work for {} bit modulus", modulus.bits()));
+
+    assert_eq!(doubled_by_addition, doubled_by_multiplication);
+
+    let (x, y) = decode_point(&doubled_by_addition, modulus_len);
+    assert!(is_on_curve(&x, &y, modulus));
+    assert_eq!((x.clone(), y.clone()), reference_double_generator(modulus));
+
+    let mut calldata = curve_calldata.clone();
+    calldata.extend(doubled_by_addition.clone());
+    calldata.extend(generator.clone());
+    let tripled_by_addition = call_g1_engine_add(&calldata).expect("addition must work");
+
+    let mut calldata = curve_calldata.clone();
+    calldata.extend(generator.clone());
+    calldata.push(3u8);
+    let tripled_by_multiplication = call_g1_engine_mul(&calldata).expect("multiplication must work");
+
+    assert_eq!(tripled_by_addition, tripled_by_multiplication);
+
+    let (x, y) = decode_point(&tripled_by_addition, modulus_len);
+    assert!(is_on_curve(&x, &y, modulus));
+    assert!(!x.is_zero());
+}
+
+fn prime_below_power_of_two(bits: usize, offset: u64) -> BigUint {
+    (BigUint::one() << bits) - BigUint::from(offset)
+}
+
+fn prime_above_power_of_two(bits: usize, offset: u64) -> BigUint {
+    (BigUint::one() << bits) + BigUint::from(offset)
+}
+
+#[test]
+fn test_g1_ops_around_limb_boundaries() {
+    // (largest prime below 2^(k-1), largest prime below 2^k, smallest prime above 2^k)
+    let boundaries = vec![
+        (256, 19, 189, 297),
+        (320, 795, 197, 27),
+        (384, 31, 317, 231),
+        (448, 325, 203, 211),
+        (512, 187, 569, 75),
+    ];
+
+    for (bits, below_offset, at_offset, above_offset) in boundaries.into_iter() {
+        let below = prime_below_power_of_two(bits - 1, below_offset);
+        assert_eq!(below.bits(), bits - 1);
+        let at = prime_below_power_of_two(bits, at_offset);
+        assert_eq!(at.bits(), bits);
+        let above = prime_above_power_of_two(bits, above_offset);
+        assert_eq!(above.bits(), bits + 1);
+
+        for modulus in vec![below, at, above].iter() {
+            run_add_and_mul_for_modulus(modulus);
+        }
+    }
+}
+
+#[test]
+fn test_limb_count_keeps_spare_bit() {
+    use crate::field::calculate_num_limbs;
+
+    assert_eq!(calculate_num_limbs(254).unwrap(), 4);
+    assert_eq!(calculate_num_limbs(255).unwrap(), 4);
+    assert_eq!(calculate_num_limbs(256).unwrap(), 5);
+    assert_eq!(calculate_num_limbs(319).unwrap(), 5);
+    assert_eq!(calculate_num_limbs(320).unwrap(), 6);
+    assert_eq!(calculate_num_limbs(512).unwrap(), 9);
+    assert_eq!(calculate_num_limbs(1023).unwrap(), 16);
+    assert!(calculate_num_limbs(1024).is_err());
+}
+
+#[test]
+fn test_g1_ops_for_largest_modulus() {
+    let modulus = prime_below_power_of_two(1023, 361);
+    assert_eq!(modulus.bits(), 1023);
+    run_add_and_mul_for_modulus(&modulus);
+
+    // 1024 bit modulus fits into the byte length limit, but not into the limbs
+    let modulus = prime_below_power_of_two(1024, 105);
+    let (mut calldata, modulus_len) = assemble_calldata_for_modulus(&modulus);
+    assert_eq!(modulus_len, 128);
+    calldata.extend(encode_point(&BigUint::one(), &BigUint::from(2u64), modulus_len));
+    calldata.push(2u8);
+    assert!(call_g1_engine_mul(&calldata).is_err());
+}
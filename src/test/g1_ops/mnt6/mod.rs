@@ -9,34 +9,14 @@ pub(crate) fn assemble_single_curve_params(curve: JsonMnt6PairingCurveParameters
     // - Field modulus
     // - Curve A
     // - Curve B
-
-    // first determine the length of the modulus
+    // - Length of the group order (in bytes)
+    // - Group order
     let modulus = curve.q;
-    let modulus_length = modulus.clone().to_bytes_be().len();
-
-    let modulus_len_encoded = vec![modulus_length as u8];
-    let modulus_encoded = pad_for_len_be(modulus.clone().to_bytes_be(), modulus_length);
-
-    let a_encoded = apply_sign(curve.a, &modulus);
-    let a_encoded = pad_for_len_be(a_encoded.to_bytes_be(), modulus_length);
+    let a = apply_sign(curve.a, &modulus);
+    let b = apply_sign(curve.b, &modulus);
+    let descriptor = G1CurveDescriptor::new(&modulus.to_bytes_be(), &a.to_bytes_be(), &b.to_bytes_be(), &curve.r.to_bytes_be()).unwrap();
 
-    let b_encoded = apply_sign(curve.b, &modulus);
-    let b_encoded = pad_for_len_be(b_encoded.to_bytes_be(), modulus_length);
-
-    let group_size = curve.r;
-    let group_size_encoded = group_size.clone().to_bytes_be();
-    let group_size_length = group_size_encoded.len();
-    let group_len_encoded = vec![group_size_length as u8];
-
-    let mut calldata = vec![];
-    calldata.extend(modulus_len_encoded.into_iter());
-    calldata.extend(modulus_encoded.into_iter());
-    calldata.extend(a_encoded.into_iter());
-    calldata.extend(b_encoded.into_iter());
-    calldata.extend(group_len_encoded.into_iter());
-    calldata.extend(group_size_encoded.into_iter());
-
-    (calldata, modulus_length, group_size_length)
+    (descriptor.encode().unwrap(), descriptor.modulus_len(), descriptor.group_order_len())
 }
 
 pub(crate) fn assemble_single_point_scalar_pair(
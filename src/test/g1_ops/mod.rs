@@ -3,17 +3,37 @@ pub(crate) mod bn;
 pub(crate) mod mnt4;
 pub(crate) mod mnt6;
 
+mod limb_boundary;
+
 use crate::public_interface::{G1Api, PublicG1Api};
+use crate::public_interface::ExecutionContext;
+use crate::public_interface::encoding::G1CurveDescriptor;
 use crate::errors::ApiError;
 
 pub(crate) fn call_g1_engine_add(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
-    PublicG1Api::add_points(&bytes)
+    PublicG1Api::add_points(&bytes, &ExecutionContext::default())
 }
 
 pub(crate) fn call_g1_engine_mul(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
-    PublicG1Api::mul_point(&bytes)
+    PublicG1Api::mul_point(&bytes, &ExecutionContext::default())
 }
 
 pub(crate) fn call_g1_engine_multiexp(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
-    PublicG1Api::multiexp(&bytes)
-}
\ No newline at end of file
+    PublicG1Api::multiexp(&bytes, &ExecutionContext::default())
+}
+
+pub(crate) fn call_g1_engine_sum(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+    PublicG1Api::sum_points(&bytes, &ExecutionContext::default())
+}
+
+pub(crate) fn call_g1_engine_subgroup_check(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+    PublicG1Api::check_subgroup(&bytes, &ExecutionContext::default())
+}
+
+pub(crate) fn call_g1_engine_clear_cofactor(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+    PublicG1Api::clear_cofactor(&bytes, &ExecutionContext::default())
+}
+
+pub(crate) fn call_g1_engine_validate_point(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+    PublicG1Api::validate_point(&bytes, &ExecutionContext::default())
+}
@@ -5,55 +5,45 @@ use super::*;
 
 const EXTENSION_DEGREE: usize = 2;
 
-pub(crate) fn assemble_single_curve_params(curve: JsonBls12PairingCurveParameters) -> (Vec<u8>, usize, usize) {
+pub(crate) fn assemble_single_curve_params(curve: &JsonBls12PairingCurveParameters) -> (Vec<u8>, usize, usize) {
     // - Lengths of modulus (in bytes)
     // - Field modulus
     // - Extension degree
-    // - Non-redisue
+    // - Non-residue
     // - Curve A
     // - Curve B
-
-    // first determine the length of the modulus
-    let modulus = curve.q;
-    let modulus_length = modulus.clone().to_bytes_be().len();
-
-    let modulus_len_encoded = vec![modulus_length as u8];
-    let modulus_encoded = pad_for_len_be(modulus.clone().to_bytes_be(), modulus_length);
-
-    let encoded_extension_degree = vec![EXTENSION_DEGREE as u8];
-
-    let fp2_nonres_encoded = {
-        let (mut nonres, is_positive) = curve.non_residue;
-        if !is_positive {
-            nonres = modulus.clone() - nonres;
-        }
-        pad_for_len_be(nonres.to_bytes_be(), modulus_length)
+    // - Length of the group order (in bytes)
+    // - Group order
+    let modulus = &curve.q;
+    let non_residue = {
+        let (nonres, is_positive) = curve.non_residue.clone();
+        if is_positive { nonres } else { modulus - nonres }
     };
+    let descriptor = G2CurveDescriptor::new(
+        &modulus.to_bytes_be(),
+        EXTENSION_DEGREE,
+        &non_residue.to_bytes_be(),
+        &[&curve.a_twist_0.to_bytes_be()[..], &curve.a_twist_1.to_bytes_be()[..]],
+        &[&curve.b_twist_0.to_bytes_be()[..], &curve.b_twist_1.to_bytes_be()[..]],
+        &curve.r.to_bytes_be()
+    ).unwrap();
+
+    (descriptor.encode().unwrap(), descriptor.modulus_len(), descriptor.group_order_len())
+}
 
-    let mut a_encoded = pad_for_len_be(curve.a_twist_0.to_bytes_be(), modulus_length);
-    a_encoded.extend(pad_for_len_be(curve.a_twist_1.to_bytes_be(), modulus_length));
-    let mut b_encoded = pad_for_len_be(curve.b_twist_0.to_bytes_be(), modulus_length);
-    b_encoded.extend(pad_for_len_be(curve.b_twist_1.to_bytes_be(), modulus_length));
-
-    // now we make two random scalars and do scalar multiplications in G1 and G2 to get pairs that should
-    // at the end of the day pair to identity element
-
-    let group_size = curve.r;
-    let group_size_encoded = group_size.clone().to_bytes_be();
-    let group_size_length = group_size_encoded.len();
-    let group_len_encoded = vec![group_size_length as u8];
+// - Twist type
+// - Curve B over the base field
+// - Twist non-residue (non-residue for Fp6)
+pub(crate) fn assemble_twist_description(curve: &JsonBls12PairingCurveParameters, modulus_len: usize) -> Vec<u8> {
+    let modulus = &curve.q;
+    let mut description = vec![if curve.is_d_type { TWIST_TYPE_D } else { TWIST_TYPE_M }];
+    description.extend(pad_for_len_be(curve.b.to_bytes_be(), modulus_len));
+    for (nonres, is_positive) in vec![curve.quadratic_non_residue_0.clone(), curve.quadratic_non_residue_1.clone()].into_iter() {
+        let nonres = if is_positive { nonres } else { modulus - nonres };
+        description.extend(pad_for_len_be(nonres.to_bytes_be(), modulus_len));
+    }
 
-    let mut calldata = vec![];
-    calldata.extend(modulus_len_encoded.into_iter());
-    calldata.extend(modulus_encoded.into_iter());
-    calldata.extend(encoded_extension_degree.into_iter());
-    calldata.extend(fp2_nonres_encoded.into_iter());
-    calldata.extend(a_encoded.into_iter());
-    calldata.extend(b_encoded.into_iter());
-    calldata.extend(group_len_encoded.into_iter());
-    calldata.extend(group_size_encoded.into_iter());
-
-    (calldata, modulus_length, group_size_length)
+    description
 }
 
 fn assemble_single_point_scalar_pair(
@@ -98,7 +88,7 @@ fn test_g2_mul_from_vectors() {
     let curves = read_dir_and_grab_curves::<JsonBls12PairingCurveParameters>("src/test/test_vectors/bls12/");
     assert!(curves.len() != 0);
     for (curve, _) in curves.into_iter() {
-        let (calldata, modulus_len, group_len) = assemble_single_curve_params(curve.clone());
+        let (calldata, modulus_len, group_len) = assemble_single_curve_params(&curve);
         for pair in curve.g2_mul_vectors.into_iter() {
             let (points_data, expected_result) = assemble_single_point_scalar_pair(pair, modulus_len, group_len);
 
@@ -117,6 +107,180 @@ fn test_g2_mul_from_vectors() {
     }
 }
 
+// Multiexp over all the vectors of a curve must match the sum of the individual multiplication results
+#[test]
+fn test_g2_multiexp_from_vectors() {
+    let curves = read_dir_and_grab_curves::<JsonBls12PairingCurveParameters>("src/test/test_vectors/bls12/");
+    assert!(curves.len() != 0);
+    for (curve, _) in curves.into_iter() {
+        let (calldata, modulus_len, group_len) = assemble_single_curve_params(&curve);
+        let num_pairs = curve.g2_mul_vectors.len();
+        assert!(num_pairs != 0 && num_pairs < 256);
+
+        let mut multiexp_calldata = calldata.clone();
+        multiexp_calldata.push(num_pairs as u8);
+        let mut expected: Option<Vec<u8>> = None;
+        for pair in curve.g2_mul_vectors.into_iter() {
+            let (points_data, result) = assemble_single_point_scalar_pair(pair, modulus_len, group_len);
+            multiexp_calldata.extend(points_data);
+
+            expected = match expected {
+                None => Some(result),
+                Some(acc) => {
+                    let mut add_calldata = calldata.clone();
+                    add_calldata.extend(acc);
+                    add_calldata.extend(result);
+
+                    Some(call_g2_engine_add(&add_calldata[..]).expect("addition must work"))
+                }
+            };
+        }
+
+        let result = call_g2_engine_multiexp(&multiexp_calldata[..]);
+        if result.is_err() {
+            panic!("{}", result.err().expect("guaranteed to exist"));
+        }
+
+        let result = result.expect("guaranteed to exist");
+        assert_eq!(result.len(), 4 * modulus_len);
+        assert!(result == expected.expect("guaranteed to exist"));
+    }
+}
+
+// Bases and results of the multiplication vectors are in the subgroup
+#[test]
+fn test_g2_subgroup_check_from_vectors() {
+    let curves = read_dir_and_grab_curves::<JsonBls12PairingCurveParameters>("src/test/test_vectors/bls12/");
+    assert!(curves.len() != 0);
+    for (curve, _) in curves.into_iter() {
+        let (calldata, modulus_len, group_len) = assemble_single_curve_params(&curve);
+        for pair in curve.g2_mul_vectors.into_iter() {
+            let (points_data, result) = assemble_single_point_scalar_pair(pair, modulus_len, group_len);
+
+            let mut base_calldata = calldata.clone();
+            base_calldata.extend_from_slice(&points_data[..(4 * modulus_len)]);
+            assert_eq!(call_g2_engine_subgroup_check(&base_calldata[..]).unwrap(), vec![BOOLEAN_TRUE]);
+
+            let mut result_calldata = calldata.clone();
+            result_calldata.extend(result);
+            assert_eq!(call_g2_engine_subgroup_check(&result_calldata[..]).unwrap(), vec![BOOLEAN_TRUE]);
+        }
+    }
+}
+
+// BLS12-381 curve description in G2 calls, and modulus length
+fn assemble_bls12_381_curve_params() -> (Vec<u8>, usize) {
+    use crate::engines::bls12_381::*;
+    use crate::public_interface::decode_fp::{serialize_fp_fixed_len, serialize_fp2_fixed_len};
+    use num_bigint::BigUint;
+    use num_traits::Num;
+
+    let modulus_len = 48;
+    let modulus = BigUint::from_str_radix("1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab", 16).unwrap();
+    let order = BigUint::from_str_radix("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001", 16).unwrap();
+
+    let mut calldata = vec![modulus_len as u8];
+    calldata.extend(pad_for_len_be(modulus.to_bytes_be(), modulus_len));
+    calldata.push(EXTENSION_DEGREE_2);
+    calldata.extend(serialize_fp_fixed_len(modulus_len, &BLS12_381_EXTENSION_2_FIELD.non_residue).unwrap());
+    calldata.extend(serialize_fp2_fixed_len(modulus_len, &BLS12_381_G2_CURVE.a).unwrap());
+    calldata.extend(serialize_fp2_fixed_len(modulus_len, &BLS12_381_G2_CURVE.b).unwrap());
+    calldata.push(order.to_bytes_be().len() as u8);
+    calldata.extend(order.to_bytes_be());
+
+    (calldata, modulus_len)
+}
+
+#[test]
+fn test_g2_subgroup_check_outside_subgroup() {
+    use crate::engines::bls12_381::*;
+    use crate::public_interface::decode_g2::serialize_g2_point_in_fp2;
+    use crate::test_utils::random_point_outside_subgroup;
+    use crate::public_interface::API;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    let (calldata, modulus_len) = assemble_bls12_381_curve_params();
+
+    let mut generator = calldata.clone();
+    generator.extend(serialize_g2_point_in_fp2(modulus_len, &BLS12_381_G2_GENERATOR).unwrap());
+    assert_eq!(call_g2_engine_subgroup_check(&generator[..]).unwrap(), vec![BOOLEAN_TRUE]);
+
+    let rng = &mut XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+    let p = random_point_outside_subgroup(&BLS12_381_G2_CURVE, rng).unwrap();
+    let mut outside = calldata.clone();
+    outside.extend(serialize_g2_point_in_fp2(modulus_len, &p).unwrap());
+    assert_eq!(call_g2_engine_subgroup_check(&outside[..]).unwrap(), vec![BOOLEAN_FALSE]);
+
+    let mut api_input = vec![OPERATION_G2_SUBGROUP_CHECK];
+    api_input.extend(outside.iter().cloned());
+    assert_eq!(API::run(&api_input).unwrap(), vec![BOOLEAN_FALSE]);
+
+    let mut not_on_curve = generator.clone();
+    let last = not_on_curve.len() - 1;
+    not_on_curve[last] ^= 1;
+    assert!(call_g2_engine_subgroup_check(&not_on_curve[..]).is_err());
+}
+
+#[test]
+fn test_g2_clear_cofactor() {
+    use crate::engines::bls12_381::*;
+    use crate::weierstrass::Group;
+    use crate::public_interface::decode_g2::serialize_g2_point_in_fp2;
+    use crate::test_utils::random_point_outside_subgroup;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    let (calldata, modulus_len) = assemble_bls12_381_curve_params();
+    let cofactor = crate::test::u64_vec_to_be_bytes(&BLS12_381_G2_MAPPING_H_EFF);
+
+    let rng = &mut XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+    let p = random_point_outside_subgroup(&BLS12_381_G2_CURVE, rng).unwrap();
+
+    let mut input = calldata.clone();
+    input.extend(serialize_g2_point_in_fp2(modulus_len, &p).unwrap());
+    input.push(cofactor.len() as u8);
+    input.extend(cofactor.iter().cloned());
+
+    let result = call_g2_engine_clear_cofactor(&input).unwrap();
+    assert_eq!(result, serialize_g2_point_in_fp2(modulus_len, &p.mul(&BLS12_381_G2_MAPPING_H_EFF[..])).unwrap());
+
+    let mut check = calldata.clone();
+    check.extend(result.iter().cloned());
+    assert_eq!(call_g2_engine_subgroup_check(&check).unwrap(), vec![BOOLEAN_TRUE]);
+
+    let mut garbage = input.clone();
+    garbage.push(0u8);
+    assert!(call_g2_engine_clear_cofactor(&garbage).is_err());
+}
+
+#[test]
+fn test_g2_validate_point() {
+    use crate::engines::bls12_381::*;
+    use crate::public_interface::decode_g2::serialize_g2_point_in_fp2;
+
+    let (calldata, modulus_len) = assemble_bls12_381_curve_params();
+    let validate = |point: &[u8]| {
+        let mut input = calldata.clone();
+        input.extend_from_slice(point);
+        call_g2_engine_validate_point(&input).unwrap()
+    };
+
+    let generator = serialize_g2_point_in_fp2(modulus_len, &BLS12_381_G2_GENERATOR).unwrap();
+    assert_eq!(validate(&generator), vec![POINT_IS_VALID]);
+
+    assert_eq!(validate(&vec![0u8; 4 * modulus_len]), vec![POINT_IS_AT_INFINITY]);
+
+    let mut not_on_curve = generator.clone();
+    not_on_curve[4 * modulus_len - 1] ^= 1;
+    assert_eq!(validate(&not_on_curve), vec![POINT_IS_NOT_ON_CURVE]);
+
+    // c1 of Y is above the modulus
+    let mut not_in_field = generator.clone();
+    not_in_field[3 * modulus_len] = 0xff;
+    assert_eq!(validate(&not_in_field), vec![POINT_COORDINATE_NOT_IN_FIELD]);
+}
+
 extern crate hex;
 extern crate csv;
 
@@ -131,7 +295,7 @@ fn dump_g2_mul_vectors() {
     let mut writer = Writer::from_path("src/test/test_vectors/bls12/g2_mul.csv").expect("must open a test file");
     writer.write_record(&["input", "result"]).expect("must write header");
     for (curve, _) in curves.into_iter() {
-        let (calldata, modulus_len, group_len) = assemble_single_curve_params(curve.clone());
+        let (calldata, modulus_len, group_len) = assemble_single_curve_params(&curve);
         for pair in curve.g2_mul_vectors.into_iter() {
             let (points_data, expected_result) = assemble_single_point_scalar_pair(pair, modulus_len, group_len);
             let mut input_data = vec![OPERATION_G2_MUL];
@@ -158,3 +322,33 @@ fn dump_g2_mul_vectors() {
 //     });
 // }
 
+#[test]
+fn test_g2_mul_with_twist_description_from_vectors() {
+    use crate::public_interface::API;
+
+    let curves = read_dir_and_grab_curves::<JsonBls12PairingCurveParameters>("src/test/test_vectors/bls12/");
+    assert!(curves.len() != 0);
+    for (curve, _) in curves.into_iter() {
+        let (calldata, modulus_len, group_len) = assemble_single_curve_params(&curve);
+        let description = assemble_twist_description(&curve, modulus_len);
+        let pair = curve.g2_mul_vectors[0].clone();
+        let (points_data, expected_result) = assemble_single_point_scalar_pair(pair, modulus_len, group_len);
+
+        let mut input = vec![ABI_VERSION_PREFIX, ABI_VERSION_2, OPERATION_G2_MUL];
+        input.extend(calldata.clone());
+        input.extend(description);
+        input.extend(points_data.clone());
+        assert_eq!(API::run(&input).unwrap(), expected_result);
+
+        let mut input_v1 = vec![OPERATION_G2_MUL];
+        input_v1.extend(calldata);
+        input_v1.extend(points_data);
+        #[cfg(feature = "gas_metering")]
+        assert_eq!(API::estimate_gas(&input).unwrap(), API::estimate_gas(&input_v1).unwrap());
+
+        // B of the curve is not B of the other twist
+        let twist_type_offset = input.len() - 7 * modulus_len - group_len - 1;
+        input[twist_type_offset] = if curve.is_d_type { TWIST_TYPE_M } else { TWIST_TYPE_D };
+        assert!(API::run(&input).is_err());
+    }
+}
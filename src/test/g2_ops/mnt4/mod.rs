@@ -9,51 +9,26 @@ pub(crate) fn assemble_single_curve_params(curve: JsonMnt4PairingCurveParameters
     // - Lengths of modulus (in bytes)
     // - Field modulus
     // - Extension degree
-    // - Non-redisue
+    // - Non-residue
     // - Curve A
     // - Curve B
-
-    // first determine the length of the modulus
-    let modulus = curve.q;
-    let modulus_length = modulus.clone().to_bytes_be().len();
-
-    let modulus_len_encoded = vec![modulus_length as u8];
-    let modulus_encoded = pad_for_len_be(modulus.clone().to_bytes_be(), modulus_length);
-
-    let encoded_extension_degree = vec![EXTENSION_DEGREE as u8];
-
-    let fp2_nonres_encoded = {
-        let (mut nonres, is_positive) = curve.non_residue;
-        if !is_positive {
-            nonres = modulus.clone() - nonres;
-        }
-        pad_for_len_be(nonres.to_bytes_be(), modulus_length)
+    // - Length of the group order (in bytes)
+    // - Group order
+    let modulus = &curve.q;
+    let non_residue = {
+        let (nonres, is_positive) = curve.non_residue.clone();
+        if is_positive { nonres } else { modulus - nonres }
     };
-
-    let mut a_encoded = pad_for_len_be(curve.a_twist_0.to_bytes_be(), modulus_length);
-    a_encoded.extend(pad_for_len_be(curve.a_twist_1.to_bytes_be(), modulus_length));
-    let mut b_encoded = pad_for_len_be(curve.b_twist_0.to_bytes_be(), modulus_length);
-    b_encoded.extend(pad_for_len_be(curve.b_twist_1.to_bytes_be(), modulus_length));
-
-    // now we make two random scalars and do scalar multiplications in G1 and G2 to get pairs that should
-    // at the end of the day pair to identity element
-
-    let group_size = curve.r;
-    let group_size_encoded = group_size.clone().to_bytes_be();
-    let group_size_length = group_size_encoded.len();
-    let group_len_encoded = vec![group_size_length as u8];
-
-    let mut calldata = vec![];
-    calldata.extend(modulus_len_encoded.into_iter());
-    calldata.extend(modulus_encoded.into_iter());
-    calldata.extend(encoded_extension_degree.into_iter());
-    calldata.extend(fp2_nonres_encoded.into_iter());
-    calldata.extend(a_encoded.into_iter());
-    calldata.extend(b_encoded.into_iter());
-    calldata.extend(group_len_encoded.into_iter());
-    calldata.extend(group_size_encoded.into_iter());
-
-    (calldata, modulus_length, group_size_length)
+    let descriptor = G2CurveDescriptor::new(
+        &modulus.to_bytes_be(),
+        EXTENSION_DEGREE,
+        &non_residue.to_bytes_be(),
+        &[&curve.a_twist_0.to_bytes_be()[..], &curve.a_twist_1.to_bytes_be()[..]],
+        &[&curve.b_twist_0.to_bytes_be()[..], &curve.b_twist_1.to_bytes_be()[..]],
+        &curve.r.to_bytes_be()
+    ).unwrap();
+
+    (descriptor.encode().unwrap(), descriptor.modulus_len(), descriptor.group_order_len())
 }
 
 pub(crate) fn assemble_single_point_scalar_pair(
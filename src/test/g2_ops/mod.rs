@@ -4,16 +4,34 @@ pub(crate) mod mnt4;
 pub(crate) mod mnt6;
 
 use crate::public_interface::{G2Api, PublicG2Api};
+use crate::public_interface::ExecutionContext;
+use crate::public_interface::encoding::G2CurveDescriptor;
 use crate::errors::ApiError;
 
 pub(crate) fn call_g2_engine_add(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
-    PublicG2Api::add_points(&bytes)
+    PublicG2Api::add_points(&bytes, &ExecutionContext::default())
 }
 
 pub(crate) fn call_g2_engine_mul(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
-    PublicG2Api::mul_point(&bytes)
+    PublicG2Api::mul_point(&bytes, &ExecutionContext::default())
 }
 
 pub(crate) fn call_g2_engine_multiexp(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
-    PublicG2Api::multiexp(&bytes)
-}
\ No newline at end of file
+    PublicG2Api::multiexp(&bytes, &ExecutionContext::default())
+}
+
+pub(crate) fn call_g2_engine_sum(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+    PublicG2Api::sum_points(&bytes, &ExecutionContext::default())
+}
+
+pub(crate) fn call_g2_engine_subgroup_check(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+    PublicG2Api::check_subgroup(&bytes, &ExecutionContext::default())
+}
+
+pub(crate) fn call_g2_engine_clear_cofactor(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+    PublicG2Api::clear_cofactor(&bytes, &ExecutionContext::default())
+}
+
+pub(crate) fn call_g2_engine_validate_point(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+    PublicG2Api::validate_point(&bytes, &ExecutionContext::default())
+}
@@ -17,6 +17,7 @@ use super::*;
 pub(crate) struct ArithmeticReport {
     pub modulus_limbs: usize,
     pub group_limbs: usize,
+    pub order_bit_length: usize,
     pub num_mul_pairs: usize,
     pub a_is_zero: bool,
     pub ext_degree: usize,
@@ -73,16 +74,6 @@ pub(crate) fn encode_g2_point_ext3(point: ((BigUint, BigUint, BigUint), (BigUint
     res                
 }
 
-extern crate csv;
-use std::path::Path;
-
-use csv::{Writer};
-use std::fs::File;
-
-pub(crate) struct ArithmeticReportWriter {
-    writer: Writer<File>
-}
-
 #[derive(Clone, Debug)]
 pub(crate) struct MaxReportFilter {
     current_max: Option<ArithmeticReport>
@@ -102,6 +93,7 @@ impl MaxReportFilter {
             let mut current = self.current_max.take().unwrap();
             assert_eq!(current.modulus_limbs, report.modulus_limbs, "current = {:?}, other = {:?}", current, report);
             assert_eq!(current.group_limbs, report.group_limbs, "current = {:?}, other = {:?}", current, report);
+            assert_eq!(current.order_bit_length, report.order_bit_length, "current = {:?}, other = {:?}", current, report);
             assert_eq!(current.num_mul_pairs, report.num_mul_pairs, "current = {:?}, other = {:?}", current, report);
             assert_eq!(current.a_is_zero, report.a_is_zero, "current = {:?}, other = {:?}", current, report);
             assert_eq!(current.ext_degree, report.ext_degree, "current = {:?}, other = {:?}", current, report);
@@ -127,49 +119,36 @@ impl MaxReportFilter {
     }
 }
 
-impl ArithmeticReportWriter {
-    pub(crate) fn new_for_path<P: AsRef<Path>>(path: P) -> Self {
-        let mut writer = Writer::from_path(path).expect("must open a test file");
-        writer.write_record(&[
-            "modulus_limbs", 
-            "group_limbs",
-            "num_mul_pairs", 
-            "a_is_zero", 
-            "ext_degree", 
-            "run_microseconds_add",
-            "run_microseconds_mul",
-            "run_microseconds_multiexp"
-        ]).expect("must write header");
-        writer.flush().expect("must finalize writing");
-
-        Self {
-            writer
-        }
+impl Report for ArithmeticReport {
+    const COLUMNS: &'static [&'static str] = &[
+        "modulus_limbs",
+        "group_limbs",
+        "order_bit_length",
+        "num_mul_pairs",
+        "a_is_zero",
+        "ext_degree",
+        "run_microseconds_add",
+        "run_microseconds_mul",
+        "run_microseconds_multiexp"
+    ];
+
+    fn values(&self) -> Vec<u64> {
+        vec![
+            self.modulus_limbs as u64,
+            self.group_limbs as u64,
+            self.order_bit_length as u64,
+            self.num_mul_pairs as u64,
+            flag(self.a_is_zero),
+            self.ext_degree as u64,
+            self.run_microseconds_add,
+            self.run_microseconds_mul,
+            self.run_microseconds_multiexp
+        ]
     }
-
-    pub fn write_report(&mut self, report: ArithmeticReport) {
-        let a_is_zero = if report.a_is_zero {
-            "1"
-        } else {
-            "0"
-        };
-
-        self.writer.write_record(&[
-            report.modulus_limbs.to_string(),
-            report.group_limbs.to_string(),
-            report.num_mul_pairs.to_string(),
-            a_is_zero.to_owned(),
-            report.ext_degree.to_string(),
-            report.run_microseconds_add.to_string(),
-            report.run_microseconds_mul.to_string(),
-            report.run_microseconds_multiexp.to_string()
-            ]
-        ).expect("must write a record");
-
-        self.writer.flush().expect("must write to disk");
-    } 
 }
 
+pub(crate) type ArithmeticReportWriter = ReportWriter<ArithmeticReport>;
+
 pub(crate) fn process_for_ext2(
     curve: JsonMnt4PairingCurveParameters, 
     g1_worst_case_pair: JsonG1PointScalarMultiplicationPair,
@@ -190,6 +169,7 @@ pub(crate) fn process_for_ext2(
 
     let limbs = calculate_num_limbs(&curve.q).expect("must work");
     let group_order_limbs = crate::test::num_units_for_group_order(&curve.r).expect("must work");
+    let order_bit_length = curve.r.bits();
     let (common_g1_data, modulus_length, group_length) = g1_mnt4::assemble_single_curve_params(curve.clone());
     let (common_g2_data, _, _) = g2_mnt4::assemble_single_curve_params(curve.clone());
 
@@ -290,6 +270,7 @@ pub(crate) fn process_for_ext2(
     let report_g1 = ArithmeticReport {
         modulus_limbs: limbs,
         group_limbs: group_order_limbs,
+        order_bit_length: order_bit_length,
         num_mul_pairs: num_mul_pairs_g1,
         a_is_zero: a_is_zero,
         ext_degree: 1,
@@ -303,6 +284,7 @@ pub(crate) fn process_for_ext2(
     let report_g2 = ArithmeticReport {
         modulus_limbs: limbs,
         group_limbs: group_order_limbs,
+        order_bit_length: order_bit_length,
         num_mul_pairs: num_mul_pairs_g2,
         a_is_zero: a_is_zero,
         ext_degree: 2,
@@ -336,6 +318,7 @@ pub(crate) fn process_for_ext3(
 
     let limbs = calculate_num_limbs(&curve.q).expect("must work");
     let group_order_limbs = crate::test::num_units_for_group_order(&curve.r).expect("must work");
+    let order_bit_length = curve.r.bits();
     let (common_g1_data, modulus_length, group_length) = g1_mnt6::assemble_single_curve_params(curve.clone());
     let (common_g2_data, _, _) = g2_mnt6::assemble_single_curve_params(curve.clone());
 
@@ -436,6 +419,7 @@ pub(crate) fn process_for_ext3(
     let report_g1 = ArithmeticReport {
         modulus_limbs: limbs,
         group_limbs: group_order_limbs,
+        order_bit_length: order_bit_length,
         num_mul_pairs: num_mul_pairs_g1,
         a_is_zero: a_is_zero,
         ext_degree: 1,
@@ -449,6 +433,7 @@ pub(crate) fn process_for_ext3(
     let report_g2 = ArithmeticReport {
         modulus_limbs: limbs,
         group_limbs: group_order_limbs,
+        order_bit_length: order_bit_length,
         num_mul_pairs: num_mul_pairs_g2,
         a_is_zero: a_is_zero,
         ext_degree: 3,
@@ -9,6 +9,15 @@ use crate::test::pairings::bls12::*;
 
 use super::*;
 
+use core::marker::PhantomData;
+use crate::field::*;
+use crate::fp::Fp;
+use crate::integers::MaxFieldUint;
+use crate::representation::ElementRepr;
+use crate::square_root::{legendre_symbol_fp, sqrt, LegendreSymbol};
+use crate::traits::FieldElement;
+use crate::expand_for_modulus_limbs;
+
 pub(crate) struct Bls12Report {
     pub(crate) x_bit_length: usize,
     pub(crate) x_hamming_weight: usize,
@@ -16,120 +25,68 @@ pub(crate) struct Bls12Report {
     pub(crate) group_limbs: usize,
     pub(crate) num_pairs: usize,
     pub(crate) x_is_negative: bool,
+    pub(crate) line_evaluations: u64,
     pub(crate) run_microseconds: u64,
+    pub(crate) run_microseconds_miller_loop: u64,
+    pub(crate) run_microseconds_final_exp: u64,
 }
 
-extern crate csv;
-use std::path::Path;
-
-use csv::{Writer};
-use std::fs::File;
-
-fn write_reports<P: AsRef<Path>>(reports: Vec<Bls12Report>, path: P) {
-    assert!(reports.len() != 0);
-    let mut writer = Writer::from_path(path).expect("must open a test file");
-    writer.write_record(&[
-        "x_bit_length", 
-        "x_hamming_weight", 
-        "modulus_limbs", 
+impl Report for Bls12Report {
+    const COLUMNS: &'static [&'static str] = &[
+        "x_bit_length",
+        "x_hamming_weight",
+        "modulus_limbs",
         "group_limbs",
-        "num_pairs", 
-        "x_is_negative", 
-        "run_microseconds"
-    ]).expect("must write header");
-    for report in reports.into_iter() {
-        let x_is_negative = if report.x_is_negative {
-            "1"
-        } else {
-            "0"
-        };
-        writer.write_record(&[
-            report.x_bit_length.to_string(),
-            report.x_hamming_weight.to_string(),
-            report.modulus_limbs.to_string(),
-            report.group_limbs.to_string(),
-            report.num_pairs.to_string(),
-            x_is_negative.to_owned(),
-            report.run_microseconds.to_string()
-            ]
-        ).expect("must write a record");
-    }
-    writer.flush().expect("must finalize writing");
-}
-
-pub(crate) struct Bls12ReportWriter {
-    writer: Writer<File>
-}
-
-impl Bls12ReportWriter {
-    pub(crate) fn new_for_path<P: AsRef<Path>>(path: P) -> Self {
-        let mut writer = Writer::from_path(path).expect("must open a test file");
-        writer.write_record(&[
-            "x_bit_length", 
-            "x_hamming_weight", 
-            "modulus_limbs", 
-            "group_limbs",
-            "num_pairs", 
-            "x_is_negative", 
-            "run_microseconds"
-        ]).expect("must write header");
-        writer.flush().expect("must finalize writing");
-
-        Self {
-            writer
-        }
-    }
+        "num_pairs",
+        "x_is_negative",
+        "line_evaluations",
+        "run_microseconds",
+        "run_microseconds_miller_loop",
+        "run_microseconds_final_exp"
+    ];
 
-    pub fn write_report(&mut self, report: Bls12Report) {
-        let x_is_negative = if report.x_is_negative {
-            "1"
-        } else {
-            "0"
-        };
-        self.writer.write_record(&[
-            report.x_bit_length.to_string(),
-            report.x_hamming_weight.to_string(),
-            report.modulus_limbs.to_string(),
-            report.group_limbs.to_string(),
-            report.num_pairs.to_string(),
-            x_is_negative.to_owned(),
-            report.run_microseconds.to_string()
-            ]
-        ).expect("must write a record");
-
-        self.writer.flush().expect("must write to disk");
-    } 
+    fn values(&self) -> Vec<u64> {
+        vec![
+            self.x_bit_length as u64,
+            self.x_hamming_weight as u64,
+            self.modulus_limbs as u64,
+            self.group_limbs as u64,
+            self.num_pairs as u64,
+            flag(self.x_is_negative),
+            self.line_evaluations,
+            self.run_microseconds,
+            self.run_microseconds_miller_loop,
+            self.run_microseconds_final_exp
+        ]
+    }
 }
 
+pub(crate) type Bls12ReportWriter = ReportWriter<Bls12Report>;
+
 pub(crate) fn process_for_curve_and_bit_sizes(
-    curve: JsonBls12PairingCurveParameters, 
+    curve: &JsonBls12PairingCurveParameters, 
     bits: usize, 
     hamming: usize, 
     num_pairs: usize
 ) -> Vec<(Bls12Report, Vec<u8>)> {
-    use std::time::Instant;
-    
     let mut reports = vec![];
+
+    let limbs = crate::test::calculate_num_limbs(&curve.q).expect("must work");
+    let group_order_limbs = crate::test::num_units_for_group_order(&curve.r).expect("must work");
+    // everything except X is the same for all the measurements
+    let parts = assemble_calldata_around_x(curve, num_pairs, false);
+    if parts.is_err() {
+        return reports;
+    }
+    let (prefix, suffix) = parts.unwrap();
     
     let new_x = make_x_bit_length_and_hamming_weight(bits, hamming);
     // for x_is_negative in vec![false, true] {
     for x_is_negative in vec![true] {
-        let mut new_curve = curve.clone();
-        new_curve.x = (new_x.clone(), x_is_negative);
-        let limbs = crate::test::calculate_num_limbs(&new_curve.q).expect("must work");
-        let group_order_limbs = crate::test::num_units_for_group_order(&new_curve.r).expect("must work");
-        let mut input_data = vec![OPERATION_PAIRING];
-        let calldata = assemble_single_curve_params(new_curve, num_pairs, false);
-        if calldata.is_err() {
-            continue
-        };
-        let calldata = calldata.unwrap();
-        input_data.extend(calldata);
+        let input_data = sweep_calldata(&prefix, &suffix, &new_x, x_is_negative);
         // println!("{}", hex::encode(&input_data));
-        let now = Instant::now();
-        let res = API::run(&input_data);
-        let elapsed = now.elapsed();
-        if let Ok(result_data) = res {
+        let res = run_and_time_pairing(&input_data);
+        if let Ok((result_data, timings)) = res {
             let report = Bls12Report {
                 x_bit_length: bits,
                 x_hamming_weight: hamming,
@@ -137,7 +94,10 @@ pub(crate) fn process_for_curve_and_bit_sizes(
                 group_limbs: group_order_limbs,
                 num_pairs: num_pairs,
                 x_is_negative: x_is_negative,
-                run_microseconds: elapsed.as_micros() as u64,
+                line_evaluations: timings.line_evaluations,
+                run_microseconds: timings.run_microseconds,
+                run_microseconds_miller_loop: timings.run_microseconds_miller_loop,
+                run_microseconds_final_exp: timings.run_microseconds_final_exp,
             };
 
             reports.push((report, result_data));
@@ -149,6 +109,372 @@ pub(crate) fn process_for_curve_and_bit_sizes(
     reports
 }
 
+// NOTE: `x_is_negative` goes into the `is_positive` slot of the X encoding, exactly as the sweep
+// always did, so new reports stay comparable with the earlier ones
+fn sweep_calldata(prefix: &[u8], suffix: &[u8], x: &BigUint, x_is_negative: bool) -> Vec<u8> {
+    let x_encoding = encode_x(x, x_is_negative);
+
+    let mut input_data = Vec::with_capacity(1 + prefix.len() + x_encoding.len() + suffix.len());
+    input_data.push(OPERATION_PAIRING);
+    input_data.extend_from_slice(prefix);
+    input_data.extend(x_encoding);
+    input_data.extend_from_slice(suffix);
+
+    input_data
+}
+
+#[test]
+fn test_sweep_calldata_matches_full_assembly() {
+    let curves = read_dir_and_grab_curves::<JsonBls12PairingCurveParameters>("src/test/test_vectors/bls12/");
+    let (curve, _) = curves.into_iter().next().expect("must have at least one curve");
+
+    let num_pairs = 2;
+    let new_x = make_x_bit_length_and_hamming_weight(16, 3);
+    let (prefix, suffix) = assemble_calldata_around_x(&curve, num_pairs, false).unwrap();
+
+    for x_is_negative in vec![false, true] {
+        let mut new_curve = curve.clone();
+        new_curve.x = (new_x.clone(), x_is_negative);
+        let mut expected = vec![OPERATION_PAIRING];
+        expected.extend(assemble_single_curve_params(&new_curve, num_pairs, false).unwrap());
+
+        assert_eq!(sweep_calldata(&prefix, &suffix, &new_x, x_is_negative), expected);
+    }
+}
+
+// Classes of the Fp2 non-residue. Multiplication by a small constant is done with a few additions,
+// while a full-width value costs a full multiplication in every Fp2 (and so Fp6/Fp12) multiplication
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum NonResidueClass {
+    Original,
+    SmallNegative,
+    SmallPositive,
+    RandomFullWidth,
+}
+
+impl NonResidueClass {
+    pub(crate) const ALL: [NonResidueClass; 4] = [
+        NonResidueClass::Original,
+        NonResidueClass::SmallNegative,
+        NonResidueClass::SmallPositive,
+        NonResidueClass::RandomFullWidth,
+    ];
+
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            NonResidueClass::Original => "original",
+            NonResidueClass::SmallNegative => "small_negative",
+            NonResidueClass::SmallPositive => "small_positive",
+            NonResidueClass::RandomFullWidth => "random_full_width",
+        }
+    }
+}
+
+/// Same measurement as `Bls12Report` for a class of the Fp2 non-residue, the class is
+/// written as its index in `NonResidueClass::ALL`
+pub(crate) struct NonResidueClassReport {
+    pub(crate) class: NonResidueClass,
+    pub(crate) report: Bls12Report,
+}
+
+impl Report for NonResidueClassReport {
+    const COLUMNS: &'static [&'static str] = &[
+        "non_residue_class",
+        "x_bit_length",
+        "x_hamming_weight",
+        "modulus_limbs",
+        "group_limbs",
+        "num_pairs",
+        "x_is_negative",
+        "run_microseconds"
+    ];
+
+    fn values(&self) -> Vec<u64> {
+        vec![
+            self.class as u64,
+            self.report.x_bit_length as u64,
+            self.report.x_hamming_weight as u64,
+            self.report.modulus_limbs as u64,
+            self.report.group_limbs as u64,
+            self.report.num_pairs as u64,
+            flag(self.report.x_is_negative),
+            self.report.run_microseconds
+        ]
+    }
+}
+
+const MAX_SMALL_NON_RESIDUE: u64 = 1000;
+
+fn signed_into_field(value: &(BigUint, bool), modulus: &BigUint) -> BigUint {
+    let (value, is_positive) = value;
+    let value = value % modulus;
+    if *is_positive || value.is_zero() {
+        value
+    } else {
+        modulus - value
+    }
+}
+
+/// Arithmetic of the crate in the base field of a test curve, specialized by the number
+/// of limbs of the modulus. Arguments are the value and the modulus
+struct BaseField<FE: ElementRepr> {
+    _marker: PhantomData<FE>
+}
+
+impl<FE: ElementRepr> BaseField<FE> {
+    fn with_element<T, F: FnOnce(&Fp<'_, FE, PrimeField<FE>>) -> T>(value_and_modulus: &(&BigUint, &BigUint), f: F) -> T {
+        let (value, modulus) = *value_and_modulus;
+        let field = field_from_modulus::<FE>(&MaxFieldUint::from_big_endian(&modulus.to_bytes_be())).expect("must be a valid modulus");
+        let element = Fp::from_be_bytes(&field, &(value % modulus).to_bytes_be(), true).expect("must be in the field");
+
+        f(&element)
+    }
+
+    fn into_biguint(element: &Fp<'_, FE, PrimeField<FE>>) -> BigUint {
+        BigUint::from_bytes_be(&u64_vec_to_be_bytes(element.into_repr().as_ref()))
+    }
+
+    fn is_quadratic_residue(value_and_modulus: &(&BigUint, &BigUint)) -> bool {
+        Self::with_element(value_and_modulus, |element| legendre_symbol_fp(element) == LegendreSymbol::QuadraticResidue)
+    }
+
+    fn inverse(value_and_modulus: &(&BigUint, &BigUint)) -> Option<BigUint> {
+        Self::with_element(value_and_modulus, |element| element.inverse().map(|inverse| Self::into_biguint(&inverse)))
+    }
+
+    fn sqrt(value_and_modulus: &(&BigUint, &BigUint)) -> Option<BigUint> {
+        Self::with_element(value_and_modulus, |element| sqrt(element).map(|root| Self::into_biguint(&root)))
+    }
+}
+
+fn is_quadratic_residue(value: &BigUint, modulus: &BigUint) -> bool {
+    let value_and_modulus = (value, modulus);
+    expand_for_modulus_limbs!(crate::test::calculate_num_limbs(modulus).expect("must work"), BaseField, &value_and_modulus, is_quadratic_residue)
+}
+
+fn inverse(value: &BigUint, modulus: &BigUint) -> BigUint {
+    let value_and_modulus = (value, modulus);
+    expand_for_modulus_limbs!(crate::test::calculate_num_limbs(modulus).expect("must work"), BaseField, &value_and_modulus, inverse).expect("must be invertible")
+}
+
+// only used to re-encode the test curve
+fn sqrt_of(value: &BigUint, modulus: &BigUint) -> Option<BigUint> {
+    let value_and_modulus = (value, modulus);
+    expand_for_modulus_limbs!(crate::test::calculate_num_limbs(modulus).expect("must work"), BaseField, &value_and_modulus, sqrt)
+}
+
+// Returns a non-residue of the requested class in the same signed form as the curve files use
+fn find_non_residue<R: rand::Rng>(
+    class: NonResidueClass, 
+    curve: &JsonBls12PairingCurveParameters, 
+    rng: &mut R
+) -> Option<(BigUint, bool)> {
+    let modulus = &curve.q;
+    let is_non_residue = |candidate: &(BigUint, bool)| {
+        let value = signed_into_field(candidate, modulus);
+        !value.is_zero() && !is_quadratic_residue(&value, modulus)
+    };
+
+    match class {
+        NonResidueClass::Original => {
+            Some(curve.non_residue.clone())
+        },
+        NonResidueClass::SmallNegative => {
+            (1..=MAX_SMALL_NON_RESIDUE).map(|k| (BigUint::from(k), false)).find(|c| is_non_residue(c))
+        },
+        NonResidueClass::SmallPositive => {
+            (2..=MAX_SMALL_NON_RESIDUE).map(|k| (BigUint::from(k), true)).find(|c| is_non_residue(c))
+        },
+        NonResidueClass::RandomFullWidth => {
+            let byte_len = modulus.to_bytes_be().len();
+            loop {
+                let bytes: Vec<u8> = (0..byte_len).map(|_| rng.gen()).collect();
+                let candidate = (BigUint::from_bytes_be(&bytes) % modulus, true);
+                if candidate.0.bits() + 8 >= modulus.bits() && is_non_residue(&candidate) {
+                    return Some(candidate);
+                }
+            }
+        }
+    }
+}
+
+// Fp2 is built as Fp[u]/(u^2 - beta). Any other non-residue is beta' = beta * c^2, so u' = c * u
+// satisfies u'^2 = beta' and an element a + b*u of the same field is a + (b/c)*u'. Re-encoding all the Fp2
+// values this way gives the same curve, generators and twist in the new representation
+pub(crate) fn substitute_fp_non_residue(
+    curve: &JsonBls12PairingCurveParameters, 
+    non_residue: (BigUint, bool)
+) -> JsonBls12PairingCurveParameters {
+    let modulus = &curve.q;
+    let old_non_residue = signed_into_field(&curve.non_residue, modulus);
+    let new_non_residue = signed_into_field(&non_residue, modulus);
+    assert!(!new_non_residue.is_zero() && !is_quadratic_residue(&new_non_residue, modulus), "must be a non-residue");
+
+    let ratio = (new_non_residue * inverse(&old_non_residue, modulus)) % modulus;
+    let c = sqrt_of(&ratio, modulus).expect("ratio of two non-residues is a residue");
+    let c_inv = inverse(&c, modulus);
+    let rescale = |value: &BigUint| (value * &c_inv) % modulus;
+
+    let mut new_curve = curve.clone();
+    new_curve.non_residue = non_residue;
+    new_curve.quadratic_non_residue_1 = (rescale(&signed_into_field(&curve.quadratic_non_residue_1, modulus)), true);
+    new_curve.a_twist_1 = rescale(&curve.a_twist_1);
+    new_curve.b_twist_1 = rescale(&curve.b_twist_1);
+    new_curve.g2_x_1 = rescale(&curve.g2_x_1);
+    new_curve.g2_y_1 = rescale(&curve.g2_y_1);
+    for vector in new_curve.g2_mul_vectors.iter_mut() {
+        vector.base_x_1 = rescale(&vector.base_x_1);
+        vector.base_y_1 = rescale(&vector.base_y_1);
+        vector.result_x_1 = rescale(&vector.result_x_1);
+        vector.result_y_1 = rescale(&vector.result_y_1);
+    }
+
+    new_curve
+}
+
+pub(crate) fn process_for_curve_and_non_residues(
+    curve: &JsonBls12PairingCurveParameters, 
+    bits: usize, 
+    hamming: usize, 
+    num_pairs: usize
+) -> Vec<(NonResidueClass, Bls12Report)> {
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    let rng = &mut XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+
+    let mut reports = vec![];
+    for class in NonResidueClass::ALL.iter() {
+        let non_residue = match find_non_residue(*class, curve, rng) {
+            Some(non_residue) => non_residue,
+            // there may be no small non-residue of the sign
+            None => continue
+        };
+        let new_curve = substitute_fp_non_residue(curve, non_residue);
+        for (report, _) in process_for_curve_and_bit_sizes(&new_curve, bits, hamming, num_pairs).into_iter() {
+            reports.push((*class, report));
+        }
+    }
+
+    reports
+}
+
+fn write_non_residue_reports<P: AsRef<Path>>(reports: Vec<(NonResidueClass, Bls12Report)>, path: P) {
+    assert!(reports.len() != 0);
+    let mut writer = ReportWriter::<NonResidueClassReport>::new_for_path(path);
+    for (class, report) in reports.into_iter() {
+        writer.write_report(NonResidueClassReport { class, report });
+    }
+}
+
+#[test]
+fn test_substituted_non_residues_give_valid_pairings() {
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    let curves = read_dir_and_grab_curves::<JsonBls12PairingCurveParameters>("src/test/test_vectors/bls12/");
+    let (curve, _) = curves.into_iter().next().expect("must have at least one curve");
+    let rng = &mut XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+
+    for class in NonResidueClass::ALL.iter() {
+        let non_residue = find_non_residue(*class, &curve, rng).expect("must find a non-residue");
+        let new_curve = substitute_fp_non_residue(&curve, non_residue.clone());
+        let modulus = &curve.q;
+        let value = signed_into_field(&non_residue, modulus);
+        match class {
+            NonResidueClass::SmallNegative => assert!(non_residue.0 <= BigUint::from(MAX_SMALL_NON_RESIDUE) && !non_residue.1),
+            NonResidueClass::SmallPositive => assert!(non_residue.0 <= BigUint::from(MAX_SMALL_NON_RESIDUE) && non_residue.1),
+            NonResidueClass::RandomFullWidth => assert!(value.bits() + 8 >= modulus.bits()),
+            NonResidueClass::Original => assert_eq!(new_curve.g2_x_1, curve.g2_x_1),
+        }
+
+        let mut calldata = vec![OPERATION_PAIRING];
+        calldata.extend(assemble_single_curve_params(&new_curve, 2, true).unwrap());
+        let result = API::run(&calldata).expect(&format!("pairing must work for {} non-residue", class.as_str()));
+        assert_eq!(result, vec![1u8], "pairing must be valid for {} non-residue", class.as_str());
+    }
+}
+
+#[test]
+#[ignore]
+fn test_bench_bls12_non_residue_classes() {
+    const SAMPLES: usize = 10;
+
+    let curves = read_dir_and_grab_curves::<JsonBls12PairingCurveParameters>("src/test/test_vectors/bls12/");
+    let mut total_results = vec![];
+    for (curve, _) in curves.iter() {
+        for _ in 0..SAMPLES {
+            for num_pairs in vec![2, 4, 8].into_iter() {
+                total_results.extend(process_for_curve_and_non_residues(curve, 64, 8, num_pairs));
+            }
+        }
+    }
+
+    write_non_residue_reports(total_results, "src/test/gas_meter/bls12/non_residue_classes.csv");
+}
+pub(crate) struct SharedG2Report {
+    pub(crate) num_pairs: usize,
+    pub(crate) num_distinct_g2: usize,
+    pub(crate) run_microseconds: u64,
+}
+
+impl Report for SharedG2Report {
+    const COLUMNS: &'static [&'static str] = &[
+        "num_pairs",
+        "num_distinct_g2",
+        "run_microseconds"
+    ];
+
+    fn values(&self) -> Vec<u64> {
+        vec![
+            self.num_pairs as u64,
+            self.num_distinct_g2 as u64,
+            self.run_microseconds
+        ]
+    }
+}
+
+// Twist points that repeat within one call are prepared once, so the cost of a call depends
+// on the number of distinct G2 points and not only on the number of pairs
+#[test]
+#[ignore]
+fn test_bench_bls12_shared_g2_points() {
+    use std::time::Instant;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+    use crate::engines::bls12_381::*;
+    use crate::pairings::PairingEngine;
+    use crate::test_utils::random_point;
+
+    const SAMPLES: usize = 10;
+
+    let rng = &mut XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+    let engine = BLS12_381_PAIRING_ENGINE;
+
+    let mut writer = ReportWriter::<SharedG2Report>::new_for_path("src/test/gas_meter/bls12/shared_g2.csv");
+
+    for num_pairs in vec![2, 4, 8, 16].into_iter() {
+        let points: Vec<_> = (0..num_pairs).map(|_| random_point(&BLS12_381_G1_GENERATOR, rng)).collect();
+        let distinct: Vec<_> = (0..num_pairs).map(|_| random_point(&BLS12_381_G2_GENERATOR, rng)).collect();
+        let mut num_distinct = 1;
+        while num_distinct <= num_pairs {
+            let twists: Vec<_> = (0..num_pairs).map(|i| distinct[i % num_distinct].clone()).collect();
+            for _ in 0..SAMPLES {
+                let now = Instant::now();
+                let _ = engine.pair(&points, &twists).unwrap();
+                let elapsed = now.elapsed();
+
+                writer.write_report(SharedG2Report {
+                    num_pairs,
+                    num_distinct_g2: num_distinct,
+                    run_microseconds: elapsed.as_micros() as u64
+                });
+            }
+            num_distinct *= 2;
+        }
+    }
+}
+
 // fn process_curve(curve: JsonBls12PairingCurveParameters) -> Vec<Bls12Report> {
 //     let max_bits = MAX_BLS12_X_BIT_LENGTH;
 //     let max_bits = 64;
@@ -161,7 +487,7 @@ pub(crate) fn process_for_curve_and_bit_sizes(
 //         for hamming in (1..=bits).step_by(2) {
 //             for num_pairs in (2..=max_num_pairs).step_by(2) {
 //                 let subreports = process_for_curve_and_bit_sizes(
-//                     curve.clone(), bits, hamming, num_pairs
+//                     &curve, bits, hamming, num_pairs
 //                 );
 //                 reports.extend(subreports.0);
 //             }
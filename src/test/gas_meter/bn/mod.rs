@@ -1,5 +1,4 @@
 use crate::test::*;
-use crate::public_interface::API;
 use crate::public_interface::constants::*;
 use crate::public_interface::sane_limits::*;
 use crate::public_interface::decode_utils::*;
@@ -18,62 +17,48 @@ pub(crate) struct BnReport {
     pub(crate) x_is_negative: bool,
     pub(crate) x_bit_length: usize,
     pub(crate) x_hamming_weight: usize,
+    pub(crate) line_evaluations: u64,
     pub(crate) run_microseconds: u64,
+    pub(crate) run_microseconds_miller_loop: u64,
+    pub(crate) run_microseconds_final_exp: u64,
 }
 
-extern crate csv;
-use std::path::Path;
-
-use csv::{Writer};
-use std::fs::File;
-
-pub(crate) struct BnReportWriter {
-    writer: Writer<File>
-}
-
-impl BnReportWriter {
-    pub(crate) fn new_for_path<P: AsRef<Path>>(path: P) -> Self {
-        let mut writer = Writer::from_path(path).expect("must open a test file");
-        writer.write_record(&["six_u_plus_two_bit_length", 
-                            "six_u_plus_two_hamming",
-                            "modulus_limbs", 
-                            "group_limbs",
-                            "num_pairs", 
-                            "x_is_negative", 
-                            "x_bit_length", 
-                            "x_hamming_weight", 
-                            "run_microseconds"
-                        ]).expect("must write header");
-        writer.flush().expect("must finalize writing");
-
-        Self {
-            writer
-        }
+impl Report for BnReport {
+    const COLUMNS: &'static [&'static str] = &[
+        "six_u_plus_two_bit_length",
+        "six_u_plus_two_hamming",
+        "modulus_limbs",
+        "group_limbs",
+        "num_pairs",
+        "x_is_negative",
+        "x_bit_length",
+        "x_hamming_weight",
+        "line_evaluations",
+        "run_microseconds",
+        "run_microseconds_miller_loop",
+        "run_microseconds_final_exp"
+    ];
+
+    fn values(&self) -> Vec<u64> {
+        vec![
+            self.six_u_plus_two_bit_length as u64,
+            self.six_u_plus_two_hamming as u64,
+            self.modulus_limbs as u64,
+            self.group_limbs as u64,
+            self.num_pairs as u64,
+            flag(self.x_is_negative),
+            self.x_bit_length as u64,
+            self.x_hamming_weight as u64,
+            self.line_evaluations,
+            self.run_microseconds,
+            self.run_microseconds_miller_loop,
+            self.run_microseconds_final_exp
+        ]
     }
-
-    pub fn write_report(&mut self, report: BnReport) {
-        let x_is_negative = if report.x_is_negative {
-            "1"
-        } else {
-            "0"
-        };
-        self.writer.write_record(&[
-            report.six_u_plus_two_bit_length.to_string(),
-            report.six_u_plus_two_hamming.to_string(),
-            report.modulus_limbs.to_string(),
-            report.group_limbs.to_string(),
-            report.num_pairs.to_string(),
-            x_is_negative.to_owned(),
-            report.x_bit_length.to_string(),
-            report.x_hamming_weight.to_string(),
-            report.run_microseconds.to_string()
-            ]
-        ).expect("must write a record");
-
-        self.writer.flush().expect("must write to disk");
-    } 
 }
 
+pub(crate) type BnReportWriter = ReportWriter<BnReport>;
+
 pub(crate) fn process_for_curve_and_bit_sizes(
     curve: JsonBnPairingCurveParameters, 
     bits: usize, hamming: usize, 
@@ -101,10 +86,8 @@ pub(crate) fn process_for_curve_and_bit_sizes(
         };
         let calldata = calldata.unwrap();
         input_data.extend(calldata);
-        let now = Instant::now();
-        let res = API::run(&input_data);
-        let elapsed = now.elapsed();
-        if let Ok(result_data) = res {
+        let res = run_and_time_pairing(&input_data);
+        if let Ok((result_data, timings)) = res {
             let report = BnReport {
                 six_u_plus_two_bit_length: six_u_plus_two_bit_length,
                 six_u_plus_two_hamming: six_u_plus_two_hamming,
@@ -114,7 +97,10 @@ pub(crate) fn process_for_curve_and_bit_sizes(
                 x_is_negative: x_is_negative,
                 x_bit_length: bits,
                 x_hamming_weight: hamming,
-                run_microseconds: elapsed.as_micros() as u64,
+                line_evaluations: timings.line_evaluations,
+                run_microseconds: timings.run_microseconds,
+                run_microseconds_miller_loop: timings.run_microseconds_miller_loop,
+                run_microseconds_final_exp: timings.run_microseconds_final_exp,
             };
 
             reports.push((report, result_data));
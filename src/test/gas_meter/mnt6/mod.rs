@@ -1,5 +1,4 @@
 use crate::test::*;
-use crate::public_interface::API;
 use crate::public_interface::constants::*;
 use crate::public_interface::sane_limits::*;
 use crate::public_interface::decode_utils::*;
@@ -22,76 +21,54 @@ pub(crate) struct Mnt6Report {
     pub(crate) exp_w0_is_negative: bool,
     pub(crate) exp_w1_bit_length: usize,
     pub(crate) exp_w1_hamming: usize,
+    pub(crate) line_evaluations: u64,
     pub(crate) run_microseconds: u64,
+    pub(crate) run_microseconds_miller_loop: u64,
+    pub(crate) run_microseconds_final_exp: u64,
 }
 
-extern crate csv;
-use std::path::Path;
-
-use csv::{Writer};
-use std::fs::File;
-
-pub(crate) struct Mnt6ReportWriter {
-    writer: Writer<File>
-}
-
-impl Mnt6ReportWriter {
-    pub(crate) fn new_for_path<P: AsRef<Path>>(path: P) -> Self {
-        let mut writer = Writer::from_path(path).expect("must open a test file");
-        writer.write_record(&[
-                            "modulus_limbs", 
-                            "group_limbs",
-                            "num_pairs", 
-                            "x_is_negative", 
-                            "x_bit_length", 
-                            "x_hamming_weight",
-                            "exp_w0_bit_length",
-                            "exp_w0_hamming",
-                            "exp_w0_is_negative",
-                            "exp_w1_bit_length",
-                            "exp_w1_hamming",
-                            "run_microseconds"
-                        ]).expect("must write header");
-        writer.flush().expect("must finalize writing");
-
-        Self {
-            writer
-        }
+impl Report for Mnt6Report {
+    const COLUMNS: &'static [&'static str] = &[
+        "modulus_limbs",
+        "group_limbs",
+        "num_pairs",
+        "x_is_negative",
+        "x_bit_length",
+        "x_hamming_weight",
+        "exp_w0_bit_length",
+        "exp_w0_hamming",
+        "exp_w0_is_negative",
+        "exp_w1_bit_length",
+        "exp_w1_hamming",
+        "line_evaluations",
+        "run_microseconds",
+        "run_microseconds_miller_loop",
+        "run_microseconds_final_exp"
+    ];
+
+    fn values(&self) -> Vec<u64> {
+        vec![
+            self.modulus_limbs as u64,
+            self.group_order_limbs as u64,
+            self.num_pairs as u64,
+            flag(self.x_is_negative),
+            self.x_bit_length as u64,
+            self.x_hamming_weight as u64,
+            self.exp_w0_bit_length as u64,
+            self.exp_w0_hamming as u64,
+            flag(self.exp_w0_is_negative),
+            self.exp_w1_bit_length as u64,
+            self.exp_w1_hamming as u64,
+            self.line_evaluations,
+            self.run_microseconds,
+            self.run_microseconds_miller_loop,
+            self.run_microseconds_final_exp
+        ]
     }
-
-    pub fn write_report(&mut self, report: Mnt6Report) {
-        let x_is_negative = if report.x_is_negative {
-            "1"
-        } else {
-            "0"
-        };
-
-        let exp_w0_is_negative = if report.exp_w0_is_negative {
-            "1"
-        } else {
-            "0"
-        };
-
-        self.writer.write_record(&[
-            report.modulus_limbs.to_string(),
-            report.group_order_limbs.to_string(),
-            report.num_pairs.to_string(),
-            x_is_negative.to_owned(),
-            report.x_bit_length.to_string(),
-            report.x_hamming_weight.to_string(),
-            report.exp_w0_bit_length.to_string(),
-            report.exp_w0_hamming.to_string(),
-            exp_w0_is_negative.to_owned(),
-            report.exp_w1_bit_length.to_string(),
-            report.exp_w1_hamming.to_string(),
-            report.run_microseconds.to_string(),
-            ]
-        ).expect("must write a record");
-
-        self.writer.flush().expect("must write to disk");
-    } 
 }
 
+pub(crate) type Mnt6ReportWriter = ReportWriter<Mnt6Report>;
+
 pub(crate) fn process_for_curve_and_bit_sizes(
     curve: JsonMnt6PairingCurveParameters, 
     bits: usize, 
@@ -102,8 +79,6 @@ pub(crate) fn process_for_curve_and_bit_sizes(
     w_1_hamming: usize,
     num_pairs: usize
 ) -> Vec<(Mnt6Report, Vec<u8>, Vec<u8>)> {
-    use std::time::Instant;
-    
     let mut reports = vec![];
     
     let new_x = make_x_bit_length_and_hamming_weight(bits, hamming);
@@ -125,10 +100,8 @@ pub(crate) fn process_for_curve_and_bit_sizes(
         };
         let calldata = calldata.unwrap();
         input_data.extend(calldata);
-        let now = Instant::now();
-        let res = API::run(&input_data);
-        let elapsed = now.elapsed();
-        if let Ok(res_data) = res {
+        let res = run_and_time_pairing(&input_data);
+        if let Ok((res_data, timings)) = res {
             let report = Mnt6Report {
                 modulus_limbs: limbs,
                 group_order_limbs, 
@@ -141,7 +114,10 @@ pub(crate) fn process_for_curve_and_bit_sizes(
                 exp_w0_is_negative: exp_w0_is_negative,
                 exp_w1_bit_length: w_1_bits,
                 exp_w1_hamming: w_1_hamming,
-                run_microseconds: elapsed.as_micros() as u64,
+                line_evaluations: timings.line_evaluations,
+                run_microseconds: timings.run_microseconds,
+                run_microseconds_miller_loop: timings.run_microseconds_miller_loop,
+                run_microseconds_final_exp: timings.run_microseconds_final_exp,
             };
 
             reports.push((report, res_data, input_data));
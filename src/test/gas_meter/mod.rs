@@ -11,6 +11,252 @@ use crate::test::biguint_to_u64_vec;
 use num_bigint::BigUint;
 use num_traits::Zero;
 
+extern crate csv;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::marker::PhantomData;
+use std::path::Path;
+
+use csv::Writer;
+
+/// Single measurement that is written as a row of the report. Columns are named after the
+/// measured parameters in snake case, the running time goes last as `run_microseconds` (or
+/// `run_microseconds_<operation>` if one report measures multiple operations or stages of one
+/// operation). Every column
+/// is an unsigned integer, that is the schema shared by all output formats
+pub(crate) trait Report {
+    const COLUMNS: &'static [&'static str];
+
+    /// Values in the same order as `COLUMNS`
+    fn values(&self) -> Vec<u64>;
+}
+
+/// Flags are written as `1` and `0`, so that reports can be fitted without conversion
+pub(crate) fn flag(value: bool) -> u64 {
+    if value { 1 } else { 0 }
+}
+
+/// Output format of the reports. Selected by the `REPORT_FORMAT` variable (`csv`, `jsonl`
+/// or `parquet`), CSV is used if it's not set
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ReportFormat {
+    Csv,
+    /// One JSON object per line with the column names as keys
+    JsonLines,
+    /// Requires the `gas_meter_parquet` feature
+    Parquet,
+}
+
+impl ReportFormat {
+    pub(crate) fn from_env() -> Self {
+        match std::env::var("REPORT_FORMAT") {
+            Ok(format) => Self::from_name(&format),
+            Err(_) => ReportFormat::Csv
+        }
+    }
+
+    pub(crate) fn from_name(name: &str) -> Self {
+        match name {
+            "csv" => ReportFormat::Csv,
+            "jsonl" => ReportFormat::JsonLines,
+            "parquet" => ReportFormat::Parquet,
+            _ => panic!("`REPORT_FORMAT` must be one of `csv`, `jsonl` or `parquet`, got `{}`", name)
+        }
+    }
+
+    pub(crate) fn extension(&self) -> &'static str {
+        match self {
+            ReportFormat::Csv => "csv",
+            ReportFormat::JsonLines => "jsonl",
+            ReportFormat::Parquet => "parquet",
+        }
+    }
+}
+
+enum ReportSink {
+    Csv(Writer<File>),
+    JsonLines(BufWriter<File>),
+    #[cfg(feature = "gas_meter_parquet")]
+    Parquet(parquet_sink::ParquetSink),
+}
+
+/// Writes the header on creation and flushes after every report, so that partial results
+/// of long measurements are not lost. Parquet is written in row groups and is only
+/// complete once the writer is dropped
+pub(crate) struct ReportWriter<R: Report> {
+    sink: ReportSink,
+    _marker: PhantomData<R>
+}
+
+impl<R: Report> ReportWriter<R> {
+    /// Extension of the `path` is replaced to match the format from `REPORT_FORMAT`
+    pub(crate) fn new_for_path<P: AsRef<Path>>(path: P) -> Self {
+        let format = ReportFormat::from_env();
+        let path = path.as_ref().with_extension(format.extension());
+
+        Self::new_for_path_with_format(path, format)
+    }
+
+    pub(crate) fn new_for_path_with_format<P: AsRef<Path>>(path: P, format: ReportFormat) -> Self {
+        let sink = match format {
+            ReportFormat::Csv => {
+                let mut writer = Writer::from_path(path).expect("must open a test file");
+                writer.write_record(R::COLUMNS).expect("must write header");
+                writer.flush().expect("must finalize writing");
+
+                ReportSink::Csv(writer)
+            },
+            ReportFormat::JsonLines => {
+                let file = File::create(path).expect("must open a test file");
+
+                ReportSink::JsonLines(BufWriter::new(file))
+            },
+            #[cfg(feature = "gas_meter_parquet")]
+            ReportFormat::Parquet => {
+                ReportSink::Parquet(parquet_sink::ParquetSink::new_for_path(path, R::COLUMNS))
+            },
+            #[cfg(not(feature = "gas_meter_parquet"))]
+            ReportFormat::Parquet => {
+                panic!("Parquet reports require the `gas_meter_parquet` feature");
+            }
+        };
+
+        Self {
+            sink,
+            _marker: PhantomData
+        }
+    }
+
+    pub(crate) fn write_report(&mut self, report: R) {
+        let values = report.values();
+        debug_assert_eq!(values.len(), R::COLUMNS.len());
+        match &mut self.sink {
+            ReportSink::Csv(writer) => {
+                let record: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+                writer.write_record(&record).expect("must write a record");
+                writer.flush().expect("must write to disk");
+            },
+            ReportSink::JsonLines(writer) => {
+                let mut object = serde_json::Map::new();
+                for (column, value) in R::COLUMNS.iter().zip(values.into_iter()) {
+                    object.insert(column.to_string(), serde_json::Value::from(value));
+                }
+                serde_json::to_writer(&mut *writer, &object).expect("must write a record");
+                writer.write_all(b"\n").expect("must write a record");
+                writer.flush().expect("must write to disk");
+            },
+            #[cfg(feature = "gas_meter_parquet")]
+            ReportSink::Parquet(sink) => {
+                sink.write_row(values);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "gas_meter_parquet")]
+mod parquet_sink {
+    use std::fs::File;
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use parquet::data_type::Int64Type;
+    use parquet::file::properties::WriterProperties;
+    use parquet::file::writer::SerializedFileWriter;
+    use parquet::schema::parser::parse_message_type;
+
+    /// Rows are buffered column-wise and written as a row group once this many are collected
+    const ROW_GROUP_SIZE: usize = 4096;
+
+    pub(super) struct ParquetSink {
+        writer: Option<SerializedFileWriter<File>>,
+        columns: Vec<Vec<i64>>,
+    }
+
+    impl ParquetSink {
+        pub(super) fn new_for_path<P: AsRef<Path>>(path: P, columns: &[&str]) -> Self {
+            // every column is an unsigned 64 bit integer, same as for CSV and JSON
+            let fields: Vec<String> = columns.iter().map(|c| format!("REQUIRED INT64 {} (INTEGER(64,false));", c)).collect();
+            let schema = parse_message_type(&format!("message report {{ {} }}", fields.join(" "))).expect("must make a schema");
+            let file = File::create(path).expect("must open a test file");
+            let writer = SerializedFileWriter::new(file, Arc::new(schema), Arc::new(WriterProperties::builder().build())).expect("must write header");
+
+            Self {
+                writer: Some(writer),
+                columns: vec![vec![]; columns.len()],
+            }
+        }
+
+        pub(super) fn write_row(&mut self, values: Vec<u64>) {
+            for (column, value) in self.columns.iter_mut().zip(values.into_iter()) {
+                column.push(value as i64);
+            }
+            if self.columns[0].len() >= ROW_GROUP_SIZE {
+                self.flush_row_group();
+            }
+        }
+
+        fn flush_row_group(&mut self) {
+            if self.columns[0].is_empty() {
+                return;
+            }
+            let writer = self.writer.as_mut().expect("is not closed");
+            let mut row_group = writer.next_row_group().expect("must start a row group");
+            for column in self.columns.iter_mut() {
+                let mut column_writer = row_group.next_column().expect("must write a column").expect("schema has this column");
+                column_writer.typed::<Int64Type>().write_batch(column, None, None).expect("must write a column");
+                column_writer.close().expect("must write a column");
+                column.clear();
+            }
+            row_group.close().expect("must write to disk");
+        }
+    }
+
+    impl Drop for ParquetSink {
+        fn drop(&mut self) {
+            self.flush_row_group();
+            if let Some(writer) = self.writer.take() {
+                writer.close().expect("must finalize writing");
+            }
+        }
+    }
+}
+
+/// Running time of a pairing call. Miller loop and final exponentiation times and the number
+/// of line evaluations are only measured with the `execution_report` feature and are zero
+/// without it
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct PairingTimings {
+    pub(crate) run_microseconds: u64,
+    pub(crate) run_microseconds_miller_loop: u64,
+    pub(crate) run_microseconds_final_exp: u64,
+    pub(crate) line_evaluations: u64,
+}
+
+#[cfg(feature = "execution_report")]
+pub(crate) fn run_and_time_pairing(input: &[u8]) -> Result<(Vec<u8>, PairingTimings), crate::errors::ApiError> {
+    let (output, report) = crate::public_interface::API::run_with_metadata(input)?;
+    let timings = PairingTimings {
+        run_microseconds: report.elapsed.as_micros() as u64,
+        run_microseconds_miller_loop: report.miller_loop_elapsed.as_micros() as u64,
+        run_microseconds_final_exp: report.final_exp_elapsed.as_micros() as u64,
+        line_evaluations: report.line_evaluations,
+    };
+
+    Ok((output, timings))
+}
+
+#[cfg(not(feature = "execution_report"))]
+pub(crate) fn run_and_time_pairing(input: &[u8]) -> Result<(Vec<u8>, PairingTimings), crate::errors::ApiError> {
+    let now = std::time::Instant::now();
+    let output = crate::public_interface::API::run(input)?;
+    let timings = PairingTimings {
+        run_microseconds: now.elapsed().as_micros() as u64,
+        ..PairingTimings::default()
+    };
+
+    Ok((output, timings))
+}
+
 pub(crate) fn make_x_bit_length_and_hamming_weight(bit_length: usize, hamming_weight: usize) -> BigUint {
     assert!(bit_length > 0);
     assert!(hamming_weight > 0);
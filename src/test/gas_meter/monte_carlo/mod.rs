@@ -121,7 +121,7 @@ extern crate pbr;
 //                 let curve_num = bls12_curves_rng.sample(&mut rng);
 //                 let (curve, _) = (& bls12_curves[curve_num]).clone();
 //                 for num_pairs in pairs.iter() {
-//                     let reports = bls12::process_for_curve_and_bit_sizes(curve.clone(), x_bits, x_hamming, *num_pairs);
+//                     let reports = bls12::process_for_curve_and_bit_sizes(&curve, x_bits, x_hamming, *num_pairs);
 //                     for r in reports.into_iter() {
 //                         bls12_writer.write_report(r);
 //                     }
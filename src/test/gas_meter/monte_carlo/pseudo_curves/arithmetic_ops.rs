@@ -157,6 +157,264 @@ fn run_deterministic_parallel_search_no_filtering() {
     pb.finish();
 }
 
+const ORDER_BIT_LENGTH_STEP: usize = 32;
+
+// order becomes `2^bits - 1`, that is the worst case for double-and-add, same as the full limbs
+// in `gen_params`, and multiexp scalars are reduced by it
+fn set_order_bit_length_ext2(
+    curve: &mut JsonMnt4PairingCurveParameters,
+    g1_worst_case: &mut JsonG1PointScalarMultiplicationPair,
+    g2_worst_case: &mut JsonG2PointScalarMultiplicationPair,
+    bits: usize
+) {
+    let order = (BigUint::from(1u64) << bits) - BigUint::from(1u64);
+    for pair in curve.g1_mul_vectors.iter_mut() {
+        pair.scalar = &pair.scalar % &order;
+    }
+    for pair in curve.g2_mul_vectors.iter_mut() {
+        pair.scalar = &pair.scalar % &order;
+    }
+    g1_worst_case.scalar = order.clone();
+    g2_worst_case.scalar = order.clone();
+    curve.r = order;
+}
+
+fn set_order_bit_length_ext3(
+    curve: &mut JsonMnt6PairingCurveParameters,
+    g1_worst_case: &mut JsonG1PointScalarMultiplicationPair,
+    g2_worst_case: &mut JsonG2Ext3PointScalarMultiplicationPair,
+    bits: usize
+) {
+    let order = (BigUint::from(1u64) << bits) - BigUint::from(1u64);
+    for pair in curve.g1_mul_vectors.iter_mut() {
+        pair.scalar = &pair.scalar % &order;
+    }
+    for pair in curve.g2_mul_vectors.iter_mut() {
+        pair.scalar = &pair.scalar % &order;
+    }
+    g1_worst_case.scalar = order.clone();
+    g2_worst_case.scalar = order.clone();
+    curve.r = order;
+}
+
+// reports for `a != 0` and `a = 0` and every multiexp length, `multiexp_len` must be decreasing
+fn sweep_multiexp_len_ext2(
+    curve: JsonMnt4PairingCurveParameters,
+    g1_worst_case: JsonG1PointScalarMultiplicationPair,
+    g2_worst_case: JsonG2PointScalarMultiplicationPair,
+    multiexp_len: &[usize]
+) -> Vec<arithmetic_ops::ArithmeticReport> {
+    let mut curve_a_zero = curve.clone();
+    make_a_zero_ext2(&mut curve_a_zero);
+
+    let mut reports = vec![];
+    for mut curve in vec![curve, curve_a_zero].into_iter() {
+        for len in multiexp_len.iter() {
+            trim_multiexp_ext_2(&mut curve, *len);
+            reports.extend(arithmetic_ops::process_for_ext2(curve.clone(), g1_worst_case.clone(), g2_worst_case.clone()));
+        }
+    }
+
+    reports
+}
+
+fn sweep_multiexp_len_ext3(
+    curve: JsonMnt6PairingCurveParameters,
+    g1_worst_case: JsonG1PointScalarMultiplicationPair,
+    g2_worst_case: JsonG2Ext3PointScalarMultiplicationPair,
+    multiexp_len: &[usize]
+) -> Vec<arithmetic_ops::ArithmeticReport> {
+    let mut curve_a_zero = curve.clone();
+    make_a_zero_ext3(&mut curve_a_zero);
+
+    let mut reports = vec![];
+    for mut curve in vec![curve, curve_a_zero].into_iter() {
+        for len in multiexp_len.iter() {
+            trim_multiexp_ext_3(&mut curve, *len);
+            reports.extend(arithmetic_ops::process_for_ext3(curve.clone(), g1_worst_case.clone(), g2_worst_case.clone()));
+        }
+    }
+
+    reports
+}
+
+// Same as above, but the group order is swept by bit length instead of limbs, since the
+// double-and-add in MUL and the window size in MULTIEXP depend on the bits of the scalar
+#[test]
+#[ignore]
+fn run_order_bit_length_sweep() {
+    assert!(crate::features::in_gas_metering());
+
+    use std::thread;
+
+    use std::sync::mpsc::{channel, TryRecvError};
+
+    use rayon::prelude::*;
+
+    use rand::{SeedableRng};
+    use rand_xorshift::XorShiftRng;
+
+    let rng = XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+
+    let samples = std::env::var("NUM_SAMPLES").expect("`NUM_SAMPLES` variable must be set");
+    let samples : usize  = samples.parse().expect("`NUM_SAMPLES` variable must be an unsigned integer");
+    assert!(samples > 0);
+
+    let mut writer = arithmetic_ops::ArithmeticReportWriter::new_for_path(format!("src/test/gas_meter/pseudo_curves/monte_carlo_arith_order_bit_length_{}.csv", samples));
+
+    let multiexp_len = vec![128, 64, 32, 16, 8, 4, 2];
+    let max_multiexp_len = multiexp_len[0];
+
+    use indicatif::{ProgressBar, ProgressStyle};
+
+    let pb = ProgressBar::new(1u64);
+
+    pb.set_style(ProgressStyle::default_bar()
+        .template("[{elapsed_precise}|{eta_precise}] {bar:50} {pos:>7}/{len:7} {msg}")
+        .progress_chars("##-"));
+
+    let mut parameters_space = vec![];
+    let (tx, rx) = channel();
+    for num_limbs in NUM_LIMBS_MIN..=NUM_LIMBS_MAX {
+        for order_bit_length in (ORDER_BIT_LENGTH_STEP..=(NUM_GROUP_LIMBS_MAX * 64)).step_by(ORDER_BIT_LENGTH_STEP) {
+            parameters_space.push((num_limbs, order_bit_length, rng.clone(), pb.clone(), tx.clone()));
+        }
+    }
+
+    drop(tx);
+
+    pb.set_length((parameters_space.len() * samples) as u64);
+
+    let handler = thread::spawn(move || {
+        parameters_space.into_par_iter().for_each(|(num_limbs, order_bit_length, mut rng, pb, tx)| {
+            let num_group_limbs = order_bit_length.div_ceil(64);
+            for _ in 0..samples {
+                let (mut curve, mut g1_worst_case, mut g2_worst_case) = gen_params::random_mul_params_a_non_zero_ext3(num_limbs, num_group_limbs, max_multiexp_len, &mut rng);
+                set_order_bit_length_ext3(&mut curve, &mut g1_worst_case, &mut g2_worst_case, order_bit_length);
+                for report in sweep_multiexp_len_ext3(curve, g1_worst_case, g2_worst_case, &multiexp_len).into_iter() {
+                    tx.send(report).unwrap();
+                }
+
+                let (mut curve, mut g1_worst_case, mut g2_worst_case) = gen_params::random_mul_params_a_non_zero_ext2(num_limbs, num_group_limbs, max_multiexp_len, &mut rng);
+                set_order_bit_length_ext2(&mut curve, &mut g1_worst_case, &mut g2_worst_case, order_bit_length);
+                for report in sweep_multiexp_len_ext2(curve, g1_worst_case, g2_worst_case, &multiexp_len).into_iter() {
+                    tx.send(report).unwrap();
+                }
+
+                pb.inc(1);
+            }
+        });
+    });
+
+    loop {
+        let subres = rx.try_recv();
+        match subres {
+            Ok(r) => {
+                writer.write_report(r);
+            },
+            Err(TryRecvError::Empty) => {
+                std::thread::sleep(std::time::Duration::from_millis(1000u64));
+            },
+            Err(TryRecvError::Disconnected) => {
+                handler.join().unwrap();
+                break;
+            }
+        }
+    }
+
+    pb.finish();
+}
+
+// G2 over Fp2 and Fp3 are priced separately, so they are measured on the same modulus and
+// written into separate reports, one per extension degree. G1 reports are dropped
+#[test]
+#[ignore]
+fn run_g2_twists_sweep() {
+    assert!(crate::features::in_gas_metering());
+
+    use std::thread;
+
+    use std::sync::mpsc::{channel, TryRecvError};
+
+    use rayon::prelude::*;
+
+    use rand::{SeedableRng};
+    use rand_xorshift::XorShiftRng;
+
+    let rng = XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+
+    let samples = std::env::var("NUM_SAMPLES").expect("`NUM_SAMPLES` variable must be set");
+    let samples : usize  = samples.parse().expect("`NUM_SAMPLES` variable must be an unsigned integer");
+    assert!(samples > 0);
+
+    let mut ext2_writer = arithmetic_ops::ArithmeticReportWriter::new_for_path(format!("src/test/gas_meter/pseudo_curves/monte_carlo_arith_g2_ext2_{}.csv", samples));
+    let mut ext3_writer = arithmetic_ops::ArithmeticReportWriter::new_for_path(format!("src/test/gas_meter/pseudo_curves/monte_carlo_arith_g2_ext3_{}.csv", samples));
+
+    let multiexp_len = vec![128, 64, 32, 16, 8, 4, 2];
+    let max_multiexp_len = multiexp_len[0];
+
+    use indicatif::{ProgressBar, ProgressStyle};
+
+    let pb = ProgressBar::new(1u64);
+
+    pb.set_style(ProgressStyle::default_bar()
+        .template("[{elapsed_precise}|{eta_precise}] {bar:50} {pos:>7}/{len:7} {msg}")
+        .progress_chars("##-"));
+
+    let mut parameters_space = vec![];
+    let (tx, rx) = channel();
+    for num_limbs in NUM_LIMBS_MIN..=NUM_LIMBS_MAX {
+        for num_group_limbs in NUM_GROUP_LIMBS_MIN..=NUM_GROUP_LIMBS_MAX {
+            parameters_space.push((num_limbs, num_group_limbs, rng.clone(), pb.clone(), tx.clone()));
+        }
+    }
+
+    drop(tx);
+
+    pb.set_length((parameters_space.len() * samples) as u64);
+
+    let handler = thread::spawn(move || {
+        parameters_space.into_par_iter().for_each(|(num_limbs, num_group_limbs, mut rng, pb, tx)| {
+            for _ in 0..samples {
+                // modulus is the first thing drawn from the RNG, so both twists get the same one
+                let (curve_ext3, g1_worst_case_ext3, g2_worst_case_ext3) = gen_params::random_mul_params_a_non_zero_ext3(num_limbs, num_group_limbs, max_multiexp_len, &mut rng.clone());
+                let (curve_ext2, g1_worst_case_ext2, g2_worst_case_ext2) = gen_params::random_mul_params_a_non_zero_ext2(num_limbs, num_group_limbs, max_multiexp_len, &mut rng);
+                assert_eq!(curve_ext2.q, curve_ext3.q);
+
+                let reports = sweep_multiexp_len_ext3(curve_ext3, g1_worst_case_ext3, g2_worst_case_ext3, &multiexp_len).into_iter()
+                    .chain(sweep_multiexp_len_ext2(curve_ext2, g1_worst_case_ext2, g2_worst_case_ext2, &multiexp_len).into_iter());
+                for report in reports.filter(|r| r.ext_degree != 1) {
+                    tx.send(report).unwrap();
+                }
+
+                pb.inc(1);
+            }
+        });
+    });
+
+    loop {
+        let subres = rx.try_recv();
+        match subres {
+            Ok(r) => {
+                match r.ext_degree {
+                    2 => ext2_writer.write_report(r),
+                    3 => ext3_writer.write_report(r),
+                    _ => unreachable!()
+                }
+            },
+            Err(TryRecvError::Empty) => {
+                std::thread::sleep(std::time::Duration::from_millis(1000u64));
+            },
+            Err(TryRecvError::Disconnected) => {
+                handler.join().unwrap();
+                break;
+            }
+        }
+    }
+
+    pb.finish();
+}
+
 fn run(bytes: &[u8]) {
     let _ = API::run(&bytes);
 }
\ No newline at end of file
@@ -66,7 +66,7 @@ mod parallel_measurements;
 //                 let num_group_limbs = group_limbs_rng.sample(&mut rng);
 //                 let curve = gen_params::random_bls12_params(num_limbs, num_group_limbs, &mut rng);
 //                 for num_pairs in pairs.iter() {
-//                     let reports = bls12::process_for_curve_and_bit_sizes(curve.clone(), x_bits, x_hamming, *num_pairs);
+//                     let reports = bls12::process_for_curve_and_bit_sizes(&curve, x_bits, x_hamming, *num_pairs);
 //                     for (r, _) in reports.into_iter() {
 //                         got_results = true;
 //                         bls12_writer.write_report(r);
@@ -208,7 +208,7 @@ mod parallel_measurements;
 //                 let num_group_limbs = group_limbs_rng.sample(&mut rng);
 //                 let curve = gen_params::random_bls12_params(num_limbs, num_group_limbs, &mut rng);
 //                 for num_pairs in pairs.iter() {
-//                     let reports = bls12::process_for_curve_and_bit_sizes(curve.clone(), x_bits, x_hamming, *num_pairs);
+//                     let reports = bls12::process_for_curve_and_bit_sizes(&curve, x_bits, x_hamming, *num_pairs);
 //                     for r in reports.into_iter() {
 //                         got_results = true;
 //                         bls12_writer.write_report(r);
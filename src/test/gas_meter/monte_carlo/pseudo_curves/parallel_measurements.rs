@@ -87,7 +87,7 @@ fn parallel_measure_one_off_pairing_costs() {
                     let x_hamming = 1;
                     let curve = gen_params::random_bls12_params(num_limbs, num_group_limbs, &mut rng);
                     for num_pairs in pairs.iter() {
-                        let reports = bls12::process_for_curve_and_bit_sizes(curve.clone(), x_bits, x_hamming, *num_pairs);
+                        let reports = bls12::process_for_curve_and_bit_sizes(&curve, x_bits, x_hamming, *num_pairs);
                         for (r, res_vec) in reports.into_iter() {
                             assert_eq!(res_vec.len(), 1);
                             assert_eq!(res_vec[0], 1u8);
@@ -307,7 +307,7 @@ fn parallel_measure_bls12_bn_pairing_costs() {
                     let x_hamming = x_hamming.sample(&mut rng);
                     let curve = gen_params::random_bls12_params(num_limbs, num_group_limbs, &mut rng);
                     for num_pairs in pairs.iter() {
-                        let reports = bls12::process_for_curve_and_bit_sizes(curve.clone(), x_bits, x_hamming, *num_pairs);
+                        let reports = bls12::process_for_curve_and_bit_sizes(&curve, x_bits, x_hamming, *num_pairs);
                         for (r, _) in reports.into_iter() {
                             bls_tx.send(r).unwrap();
                         }
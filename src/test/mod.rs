@@ -1,14 +1,26 @@
 pub(crate) mod pairings;
 pub(crate) mod g2_ops;
 pub(crate) mod g1_ops;
+pub(crate) mod edwards_ops;
+pub(crate) mod montgomery_ops;
+pub(crate) mod binary_ops;
+pub(crate) mod field_ops;
 pub(crate) mod parsers;
 pub(crate) mod public_api;
 pub(crate) mod spec_generator;
 pub(crate) mod arithmetic_tests;
+mod decode_round_trips;
+mod panic_free;
+mod compressed_points;
 
 mod fields;
+mod tiny_curves;
 // mod fuzzing;
-mod gas_meter;
+pub(crate) mod gas_meter;
+#[cfg(feature = "differential_tests")]
+mod differential;
+#[cfg(feature = "server")]
+mod server;
 
 use num_bigint::BigUint;
 use num_traits::Zero;
@@ -34,18 +46,7 @@ pub(crate) fn num_units_for_group_order(order: &BigUint) -> Result<usize, ApiErr
 }
 
 pub(crate) fn calculate_num_limbs(modulus: &BigUint) -> Result<usize, ()> {
-    let bitlength = modulus.bits();
-
-    let mut num_limbs = (bitlength / 64) + 1;
-    if num_limbs < 4 {
-        num_limbs = 4;
-    }
-
-    if num_limbs > 16 {
-        return Err(());
-    }
-
-    Ok(num_limbs)
+    crate::field::calculate_num_limbs(modulus.bits())
 }
     
 pub(crate) fn biguint_to_u64_vec(mut v: BigUint) -> Vec<u64> {
@@ -61,6 +62,18 @@ pub(crate) fn biguint_to_u64_vec(mut v: BigUint) -> Vec<u64> {
     ret
 }
 
+// minimal big endian encoding of little endian limbs
+pub(crate) fn u64_vec_to_be_bytes(v: &[u64]) -> Vec<u8> {
+    let mut ret: Vec<u8> = v.iter().rev().flat_map(|limb| limb.to_be_bytes().to_vec()).collect();
+    let leading_zeros = ret.iter().take_while(|b| **b == 0).count();
+    if leading_zeros == ret.len() {
+        return vec![0u8];
+    }
+    ret.drain(..leading_zeros);
+
+    ret
+}
+
 #[cfg(test)]
 mod test {
     #[test]
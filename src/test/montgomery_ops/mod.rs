@@ -0,0 +1,121 @@
+use crate::public_interface::constants::*;
+use crate::public_interface::ExecutionContext;
+use crate::public_interface::{MontgomeryApi, PublicMontgomeryApi, API};
+
+use num_bigint::BigUint;
+use num_traits::Num;
+
+use crate::test::parsers::*;
+
+// - Lengths of modulus (in bytes)
+// - Field modulus
+// - Curve A
+// - Curve B
+// - Length of the group order
+// - Group order
+// - x-coordinate
+// - scalar
+fn assemble_ladder_input(
+    modulus: &str,
+    a: &str,
+    group_order: &str,
+    u: &str,
+    scalar: &str
+) -> Vec<u8> {
+    let modulus = BigUint::from_str_radix(modulus, 16).unwrap();
+    let modulus_len = modulus.to_bytes_be().len();
+    let group_order = BigUint::from_str_radix(group_order, 16).unwrap();
+    let group_order_len = group_order.to_bytes_be().len();
+
+    let mut calldata = vec![modulus_len as u8];
+    calldata.extend(modulus.to_bytes_be());
+    calldata.extend(pad_for_len_be(BigUint::from_str_radix(a, 16).unwrap().to_bytes_be(), modulus_len));
+    calldata.extend(pad_for_len_be(BigUint::from(1u64).to_bytes_be(), modulus_len));
+    calldata.push(group_order_len as u8);
+    calldata.extend(group_order.to_bytes_be());
+    calldata.extend(pad_for_len_be(BigUint::from_str_radix(u, 16).unwrap().to_bytes_be(), modulus_len));
+    calldata.extend(pad_for_len_be(BigUint::from_str_radix(scalar, 16).unwrap().to_bytes_be(), group_order_len));
+
+    calldata
+}
+
+fn decode_hex(value: &str) -> Vec<u8> {
+    hex::decode(value).unwrap()
+}
+
+const X25519_MODULUS: &str = "7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed";
+const X25519_A: &str = "76d06";
+const X25519_ORDER: &str = "1000000000000000000000000000000014def9dea2f79cd65812631a5cf5d3ed";
+
+// RFC 7748, section 6.1. Keys there are little endian, here they are reversed to big endian
+// and secret scalars are clamped
+#[test]
+fn test_x25519_diffie_hellman() {
+    let alice_public = PublicMontgomeryApi::ladder(&assemble_ladder_input(
+        X25519_MODULUS,
+        X25519_A,
+        X25519_ORDER,
+        "9",
+        "6a2cb91da5fb77b12a99c0eb872f4cdf4566b25172c1163c7da518730a6d0770"
+    ), &ExecutionContext::default()).unwrap();
+    assert_eq!(alice_public, decode_hex("6a4e9baa8ea9a4ebf41a38260d3abf0d5af73eb4dc7d8b7454a7308909f02085"));
+
+    let bob_public = PublicMontgomeryApi::ladder(&assemble_ladder_input(
+        X25519_MODULUS,
+        X25519_A,
+        X25519_ORDER,
+        "9",
+        "6be088ff278b2f1cfdb6182629b13b6fe60e80838b7fe1794b8a4a627e08ab58"
+    ), &ExecutionContext::default()).unwrap();
+    assert_eq!(bob_public, decode_hex("4f2b886f147efcad4d67785bc843833f3735e4ecc2615bd3b4c17d7b7ddb9ede"));
+
+    let mut input = vec![OPERATION_MONTGOMERY_LADDER];
+    input.extend(assemble_ladder_input(
+        X25519_MODULUS,
+        X25519_A,
+        X25519_ORDER,
+        "6a4e9baa8ea9a4ebf41a38260d3abf0d5af73eb4dc7d8b7454a7308909f02085",
+        "6be088ff278b2f1cfdb6182629b13b6fe60e80838b7fe1794b8a4a627e08ab58"
+    ));
+    let shared_secret = API::run(&input).unwrap();
+    assert_eq!(shared_secret, decode_hex("4217161e3c9bf076339ed147c9217ee0250f3580f43b8e72e12dcea45b9d5d4a"));
+}
+
+// RFC 7748, section 6.2
+#[test]
+fn test_x448_public_key() {
+    let alice_public = PublicMontgomeryApi::ladder(&assemble_ladder_input(
+        "fffffffffffffffffffffffffffffffffffffffffffffffffffffffeffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        "262a6",
+        "3fffffffffffffffffffffffffffffffffffffffffffffffffffffff7cca23e9c44edb49aed63690216cc2728dc58f552378c292ab5844f3",
+        "5",
+        "eb7298a5c0d8c29a1dab27f1a6826300917389449741a974f5bac9d98dc298d46555bce8bae89eeed400584bb046cf75579f51d125498f98"
+    ), &ExecutionContext::default()).unwrap();
+    assert_eq!(alice_public, decode_hex("a01fc432e5807f17530d1288da125b0cd453d941726436c8bbd9c5222c3da7fa639ce03db8d23b274a0721a1aed5227de6e3b731ccf7089b"));
+}
+
+#[test]
+fn test_montgomery_ladder_invalid_inputs() {
+    // A^2 == 4 is a singular curve
+    let input = assemble_ladder_input(X25519_MODULUS, "2", X25519_ORDER, "9", "5");
+    assert!(PublicMontgomeryApi::ladder(&input, &ExecutionContext::default()).is_err());
+
+    // x-coordinate is not a field element
+    let input = assemble_ladder_input(X25519_MODULUS, X25519_A, X25519_ORDER, X25519_MODULUS, "5");
+    assert!(PublicMontgomeryApi::ladder(&input, &ExecutionContext::default()).is_err());
+
+    let mut input = assemble_ladder_input(X25519_MODULUS, X25519_A, X25519_ORDER, "9", "5");
+    input.push(0u8);
+    assert!(PublicMontgomeryApi::ladder(&input, &ExecutionContext::default()).is_err());
+}
+
+#[test]
+fn test_meter_montgomery_ladder() {
+    let mut input = vec![OPERATION_MONTGOMERY_LADDER];
+    input.extend(assemble_ladder_input(X25519_MODULUS, X25519_A, X25519_ORDER, "9", "5"));
+    let gas = crate::gas_meter::GasMeter::meter(&input).unwrap();
+    assert!(gas > 0);
+
+    input.push(0u8);
+    assert!(crate::gas_meter::GasMeter::meter(&input).is_err());
+}
@@ -1,4 +1,5 @@
 use crate::public_interface::constants::*;
+use crate::public_interface::ExecutionContext;
 use crate::public_interface::{PublicG1Api, G1Api, PublicG2Api, G2Api};
 use crate::errors::ApiError;
 
@@ -10,8 +11,37 @@ use super::call_pairing_engine;
 use crate::test::g1_ops;
 use crate::test::g2_ops;
 
-pub(crate) fn assemble_single_curve_params(curve: JsonBls12PairingCurveParameters, pairs: usize, check_subgroup: bool) -> Result<Vec<u8>, ApiError>  {
-    let curve_clone = curve.clone();
+pub(crate) fn assemble_single_curve_params(curve: &JsonBls12PairingCurveParameters, pairs: usize, check_subgroup: bool) -> Result<Vec<u8>, ApiError>  {
+    let (prefix, suffix) = assemble_calldata_around_x(curve, pairs, check_subgroup)?;
+    let (x, x_is_positive) = &curve.x;
+
+    let mut calldata = prefix;
+    calldata.extend(encode_x(x, *x_is_positive));
+    calldata.extend(suffix);
+
+    Ok(calldata)
+}
+
+// - length of X
+// - X
+// - sign of X
+pub(crate) fn encode_x(x: &BigUint, x_is_positive: bool) -> Vec<u8> {
+    let x_encoded = x.to_bytes_be();
+
+    let mut encoding = vec![x_encoded.len() as u8];
+    encoding.extend(x_encoded);
+    if x_is_positive { 
+        encoding.push(0u8);
+    } else { 
+        encoding.push(1u8);
+    }
+
+    encoding
+}
+
+// Returns the parts of the calldata before and after the encoding of the parameter X. 
+// Pairs do not depend on X, so sweeps over X can assemble them once.
+pub(crate) fn assemble_calldata_around_x(curve: &JsonBls12PairingCurveParameters, pairs: usize, check_subgroup: bool) -> Result<(Vec<u8>, Vec<u8>), ApiError>  {
     assert!(pairs % 2 == 0);
     // - Curve type
     // - Lengths of modulus (in bytes)
@@ -27,68 +57,61 @@ pub(crate) fn assemble_single_curve_params(curve: JsonBls12PairingCurveParameter
     // - list of encoded pairs
 
     // first determine the length of the modulus
-    let modulus = curve.q;
-    let modulus_length = modulus.clone().to_bytes_be().len();
+    let modulus = &curve.q;
+    let modulus_length = modulus.to_bytes_be().len();
 
     let curve_type = vec![BLS12];
     let modulus_len_encoded = vec![modulus_length as u8];
-    let modulus_encoded = pad_for_len_be(modulus.clone().to_bytes_be(), modulus_length);
+    let modulus_encoded = pad_for_len_be(modulus.to_bytes_be(), modulus_length);
 
     let a_encoded = pad_for_len_be(curve.a.to_bytes_be(), modulus_length);
     let b_encoded = pad_for_len_be(curve.b.to_bytes_be(), modulus_length);
 
     let fp2_nonres_encoded = {
-        let (mut nonres, is_positive) = curve.non_residue;
+        let (mut nonres, is_positive) = curve.non_residue.clone();
         if !is_positive {
-            nonres = modulus.clone() - nonres;
+            nonres = modulus - nonres;
         }
         pad_for_len_be(nonres.to_bytes_be(), modulus_length)
     };
 
     let fp6_nonres_encoded_c0 = {
-        let (mut nonres, is_positive) = curve.quadratic_non_residue_0;
+        let (mut nonres, is_positive) = curve.quadratic_non_residue_0.clone();
         if !is_positive {
-            nonres = modulus.clone() - nonres;
+            nonres = modulus - nonres;
         }
         pad_for_len_be(nonres.to_bytes_be(), modulus_length)
     };
 
     let fp6_nonres_encoded_c1 = {
-        let (mut nonres, is_positive) = curve.quadratic_non_residue_1;
+        let (mut nonres, is_positive) = curve.quadratic_non_residue_1.clone();
         if !is_positive {
-            nonres = modulus.clone() - nonres;
+            nonres = modulus - nonres;
         }
         pad_for_len_be(nonres.to_bytes_be(), modulus_length)
     };
 
     let twist_type = if curve.is_d_type { vec![TWIST_TYPE_D] } else { vec![TWIST_TYPE_M] };
 
-    let (x_decoded, x_is_positive) = curve.x;
-    let x_sign = if x_is_positive { vec![0u8] } else { vec![1u8] };
-    let x_encoded = x_decoded.to_bytes_be();
-    let x_length = vec![x_encoded.len() as u8];
-
     // now we make two random scalars and do scalar multiplications in G1 and G2 to get pairs that should
     // at the end of the day pair to identity element
 
-    let group_size = curve.r;
-    let group_size_encoded = group_size.clone().to_bytes_be();
+    let group_size = &curve.r;
+    let group_size_encoded = group_size.to_bytes_be();
     let group_size_length = group_size_encoded.len();
     let group_len_encoded = vec![group_size_length as u8];
 
     // first parse generators
     // g1 generator
-    let g1_x = curve.g1_x;
-    let g1_x = pad_for_len_be(g1_x.to_bytes_be(), modulus_length);
-    let g1_y = curve.g1_y;
-    let g1_y = pad_for_len_be(g1_y.to_bytes_be(), modulus_length);
+    let g1_x = pad_for_len_be(curve.g1_x.to_bytes_be(), modulus_length);
+    let g1_y = pad_for_len_be(curve.g1_y.to_bytes_be(), modulus_length);
 
     // g2 generator
-    let g2_x_0 = curve.g2_x_0;
-    let g2_x_1 = curve.g2_x_1;
+    let g2_x_0 = &curve.g2_x_0;
+    let g2_x_1 = &curve.g2_x_1;
 
-    let g2_y_0 = curve.g2_y_0;
-    let g2_y_1 = curve.g2_y_1;
+    let g2_y_0 = &curve.g2_y_0;
+    let g2_y_1 = &curve.g2_y_1;
 
     let num_pairs = vec![pairs as u8];
 
@@ -113,6 +136,10 @@ pub(crate) fn assemble_single_curve_params(curve: JsonBls12PairingCurveParameter
 
     let rng = &mut XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
 
+    // G1 and G2 parts of the calldata do not depend on the number of pairs
+    let (g1_common_bytes, _, _) = g1_ops::bls12::assemble_single_curve_params(curve);
+    let (g2_common_bytes, _, _) = g2_ops::bls12::assemble_single_curve_params(curve);
+
     {
         fn make_random_scalar<R: Rng>(rng: &mut R, group_size_length: usize, group_size: &BigUint) -> BigUint {
             let random_scalar_bytes: Vec<u8> = (0..group_size_length).map(|_| rng.gen()).collect();
@@ -134,15 +161,12 @@ pub(crate) fn assemble_single_curve_params(curve: JsonBls12PairingCurveParameter
             // - Y
             // - Scalar
             
-            let r1 = make_random_scalar(rng, group_size_length, &group_size);
-            let r2 = make_random_scalar(rng, group_size_length, &group_size);
-            let r3 = (r1.clone() * &r2) % &group_size;
+            let r1 = make_random_scalar(rng, group_size_length, group_size);
+            let r2 = make_random_scalar(rng, group_size_length, group_size);
+            let r3 = (r1.clone() * &r2) % group_size;
             let r3 = group_size.clone() - r3;
 
             // pair (g1^r1, g2^r2)*(g1^(-r1*r2), g2)
-            let (g1_common_bytes, _, _) = g1_ops::bls12::assemble_single_curve_params(curve_clone.clone());
-            let (g2_common_bytes, _, _) = g2_ops::bls12::assemble_single_curve_params(curve_clone.clone());
-
             let g1_encoded_0 = {
                 let mut mul_calldata = vec![];
                 mul_calldata.extend(g1_common_bytes.clone());
@@ -150,7 +174,7 @@ pub(crate) fn assemble_single_curve_params(curve: JsonBls12PairingCurveParameter
                 mul_calldata.extend_from_slice(&g1_y[..]);
                 mul_calldata.extend(pad_for_len_be(r1.to_bytes_be(), group_size_length));
 
-                let g1 = PublicG1Api::mul_point(&mul_calldata[..])?;
+                let g1 = PublicG1Api::mul_point(&mul_calldata[..], &ExecutionContext::default())?;
 
                 g1
             };
@@ -161,7 +185,7 @@ pub(crate) fn assemble_single_curve_params(curve: JsonBls12PairingCurveParameter
                 mul_calldata.extend(g2_generator_encoding.clone());
                 mul_calldata.extend(pad_for_len_be(r2.to_bytes_be(), group_size_length));
 
-                let g2 = PublicG2Api::mul_point(&mul_calldata[..])?;
+                let g2 = PublicG2Api::mul_point(&mul_calldata[..], &ExecutionContext::default())?;
 
                 g2
             };
@@ -173,7 +197,7 @@ pub(crate) fn assemble_single_curve_params(curve: JsonBls12PairingCurveParameter
                 mul_calldata.extend_from_slice(&g1_y[..]);
                 mul_calldata.extend(pad_for_len_be(r3.to_bytes_be(), group_size_length));
 
-                let g1 = PublicG1Api::mul_point(&mul_calldata[..])?;
+                let g1 = PublicG1Api::mul_point(&mul_calldata[..], &ExecutionContext::default())?;
 
                 g1
             };
@@ -188,40 +212,40 @@ pub(crate) fn assemble_single_curve_params(curve: JsonBls12PairingCurveParameter
         }
     }
 
-    let mut calldata = vec![];
-    calldata.extend(curve_type.into_iter());
-    calldata.extend(modulus_len_encoded.into_iter());
-    calldata.extend(modulus_encoded.into_iter());
-    calldata.extend(a_encoded.into_iter());
-    calldata.extend(b_encoded.into_iter());
-    calldata.extend(group_len_encoded.into_iter());
-    calldata.extend(group_size_encoded.into_iter());
-    calldata.extend(fp2_nonres_encoded.into_iter());
-    calldata.extend(fp6_nonres_encoded_c0.into_iter());
-    calldata.extend(fp6_nonres_encoded_c1.into_iter());
-    calldata.extend(twist_type.into_iter());
-    calldata.extend(x_length.into_iter());
-    calldata.extend(x_encoded.into_iter());
-    calldata.extend(x_sign.into_iter());
-    calldata.extend(num_pairs.into_iter());
+    let mut prefix = vec![];
+    prefix.extend(curve_type.into_iter());
+    prefix.extend(modulus_len_encoded.into_iter());
+    prefix.extend(modulus_encoded.into_iter());
+    prefix.extend(a_encoded.into_iter());
+    prefix.extend(b_encoded.into_iter());
+    prefix.extend(group_len_encoded.into_iter());
+    prefix.extend(group_size_encoded.into_iter());
+    prefix.extend(fp2_nonres_encoded.into_iter());
+    prefix.extend(fp6_nonres_encoded_c0.into_iter());
+    prefix.extend(fp6_nonres_encoded_c1.into_iter());
+    prefix.extend(twist_type.into_iter());
+
+    let mut suffix = vec![];
+    suffix.extend(num_pairs.into_iter());
     for (g1, g2) in g1_encodings.into_iter().zip(g2_encodings.into_iter()) {
         if check_subgroup {
-            calldata.extend(vec![1u8]);
+            suffix.extend(vec![1u8]);
         } else {
-            calldata.extend(vec![0u8]);
+            suffix.extend(vec![0u8]);
         }
-        calldata.extend(g1.into_iter());
+        suffix.extend(g1.into_iter());
         if check_subgroup {
-            calldata.extend(vec![1u8]);
+            suffix.extend(vec![1u8]);
         } else {
-            calldata.extend(vec![0u8]);
+            suffix.extend(vec![0u8]);
         }
-        calldata.extend(g2.into_iter());
+        suffix.extend(g2.into_iter());
     }
 
-    Ok(calldata)
+    Ok((prefix, suffix))
 }
 
+
 // #[test]
 // fn test_single() {
 //     let calldata = assemble_single();
@@ -237,7 +261,7 @@ fn test_bls12_pairings_from_vectors() {
     let curves = read_dir_and_grab_curves("src/test/test_vectors/bls12/");
     assert!(curves.len() != 0);
     for (curve, _) in curves.into_iter() {
-        let calldata = assemble_single_curve_params(curve, 2, true).unwrap();
+        let calldata = assemble_single_curve_params(&curve, 2, true).unwrap();
         let result = call_pairing_engine(&calldata[..]);
         if !result.is_ok() {
             println!("Error {}", result.err().unwrap());
@@ -254,7 +278,7 @@ fn test_bench_bls12_pairings_from_vectors() {
     let curves = read_dir_and_grab_curves("src/test/test_vectors/bls12/");
     assert!(curves.len() != 0);
     for (curve, _) in curves.into_iter() {
-        let calldata = assemble_single_curve_params(curve, 2, true).unwrap();
+        let calldata = assemble_single_curve_params(&curve, 2, true).unwrap();
         let start = std::time::Instant::now();
         let result = call_pairing_engine(&calldata[..]);
         println!("Taken {:?}", start.elapsed());
@@ -283,7 +307,7 @@ fn dump_pairing_vectors() {
     writer.write_record(&["input", "result"]).expect("must write header");
     for (curve, _) in curves.into_iter() {
         let mut input_data = vec![OPERATION_PAIRING];
-        let calldata = assemble_single_curve_params(curve.clone(), 2, true).unwrap();
+        let calldata = assemble_single_curve_params(&curve, 2, true).unwrap();
         input_data.extend(calldata);
         let expected_result = vec![1u8];
         writer.write_record(&[
@@ -306,7 +330,7 @@ fn dump_fuzzing_vectors() {
     // writer.write_record(&["input", "result"]).expect("must write header");
     for (curve, _) in curves.into_iter() {
         let mut input_data = vec![OPERATION_PAIRING];
-        let calldata = assemble_single_curve_params(curve.clone(), 2, true).unwrap();
+        let calldata = assemble_single_curve_params(&curve, 2, true).unwrap();
         input_data.extend(calldata);
         let filename = hex::encode(&input_data);
         let mut f = File::create(&format!("src/test/test_vectors/bls12/fuzzing_corpus/{}", &filename[0..40])).unwrap();
@@ -514,21 +538,244 @@ fn test_call_public_api_on_bls12_381() {
     let calldata = assemble_bls12_381(4);
     use crate::public_interface::PairingApi;
 
-    let result = crate::public_interface::PublicPairingApi::pair(&calldata).unwrap();
+    let result = crate::public_interface::PublicPairingApi::pair(&calldata, &ExecutionContext::default()).unwrap();
     assert!(result.len() == 1);
     assert!(result[0] == 1);
 }
 
+#[cfg(feature = "execution_report")]
+#[test]
+fn test_pairing_execution_report_on_bls12_381() {
+    use crate::public_interface::API;
+
+    let mut input = vec![OPERATION_PAIRING];
+    input.extend(assemble_bls12_381(4));
+    let (output, report) = API::run_with_metadata(&input).unwrap();
+    assert_eq!(output, vec![1u8]);
+    assert_eq!(report.num_pairs, 4);
+    // at least a doubling line per pair for every bit of |x| except the top one
+    assert!(report.line_evaluations >= 4 * 63);
+    assert!(report.miller_loop_elapsed > std::time::Duration::from_secs(0));
+    assert!(report.final_exp_elapsed > std::time::Duration::from_secs(0));
+    assert!(report.miller_loop_elapsed + report.final_exp_elapsed <= report.elapsed);
+
+    // line evaluations don't depend on the machine
+    let (_, other) = API::run_with_metadata(&input).unwrap();
+    assert_eq!(other.line_evaluations, report.line_evaluations);
+
+    // chunks of a parallel Miller loop count into the report of the call on the worker threads
+    #[cfg(feature = "parallel")]
+    {
+        let single_thread = rayon::ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+        let four_threads = rayon::ThreadPoolBuilder::new().num_threads(4).build().unwrap();
+        let (_, sequential) = single_thread.install(|| API::run_with_metadata(&input)).unwrap();
+        let (_, parallel) = four_threads.install(|| API::run_with_metadata(&input)).unwrap();
+        assert_eq!(parallel.line_evaluations, sequential.line_evaluations);
+        // every chunk repeats the squarings of the accumulator
+        assert!(parallel.field_multiplications > sequential.field_multiplications);
+    }
+}
+
+#[test]
+fn test_run_bls12_377_with_config() {
+    use crate::public_interface::{API, Limits};
+    use crate::gas_meter::GasMeter;
+
+    // not BLS12-381, that takes the fast path for well known curves without the limits
+    let mut input = vec![OPERATION_PAIRING];
+    input.extend(assemble_bls12_377(2));
+
+    // x of BLS12-377 has 64 bits
+    let strict = Limits {
+        max_bls12_x_bit_length: 63,
+        ..Limits::default()
+    };
+    assert!(API::run_with_config(&input, &strict).is_err());
+    assert!(GasMeter::meter_with_config(&input, &strict).is_err());
+    assert_eq!(API::run_with_config(&input, &Limits::default()).unwrap(), vec![1u8]);
+    assert_eq!(GasMeter::meter_with_config(&input, &Limits::default()).unwrap(), GasMeter::meter(&input).unwrap());
+
+    // limits are restored after the call
+    assert_eq!(API::run(&input).unwrap(), vec![1u8]);
+
+    let too_wide = Limits {
+        max_bls12_x_bit_length: 4096,
+        ..Limits::default()
+    };
+    assert!(too_wide.validate().is_err());
+    assert!(API::run_with_config(&input, &too_wide).is_err());
+}
+
+#[test]
+fn test_detect_twist_type_on_bls12() {
+    use crate::public_interface::API;
+    use crate::gas_meter::GasMeter;
+
+    // curve type, modulus, A, B, group order, Fp2 and Fp6 non-residues
+    let twist_type_offset = 1 + 1 + 3 * 48 + 1 + 32 + 3 * 48;
+    // BLS12-377 has a D-type twist and BLS12-381 has an M-type one
+    for (calldata, twist_type) in vec![(assemble_bls12_377(2), TWIST_TYPE_D), (assemble_bls12_381(2), TWIST_TYPE_M)].into_iter() {
+        assert_eq!(calldata[twist_type_offset], twist_type);
+        let mut input = vec![OPERATION_PAIRING];
+        input.extend(calldata);
+        input[OPERATION_ENCODING_LENGTH + twist_type_offset] = TWIST_TYPE_DETECT;
+        assert_eq!(API::run(&input).unwrap(), vec![1u8]);
+        assert!(GasMeter::meter(&input).is_ok());
+
+        // G2 points are not on the other twist
+        let other = if twist_type == TWIST_TYPE_D { TWIST_TYPE_M } else { TWIST_TYPE_D };
+        input[OPERATION_ENCODING_LENGTH + twist_type_offset] = other;
+        assert!(API::run(&input).is_err());
+    }
+
+    // nothing to detect from if G2 point is zero
+    let mut input = vec![OPERATION_PAIRING];
+    input.extend(assemble_bls12_377(1));
+    input[OPERATION_ENCODING_LENGTH + twist_type_offset] = TWIST_TYPE_DETECT;
+    let g2_start = input.len() - 4 * 48;
+    for b in input[g2_start..].iter_mut() {
+        *b = 0u8;
+    }
+    assert_eq!(API::run(&input).unwrap(), vec![1u8]);
+}
+
+#[test]
+fn test_detect_twist_type_on_bls12_for_every_operation() {
+    use crate::public_interface::API;
+
+    // BLS12-381 has an M-type twist, it must be detected in the layout of every operation
+    let calldata = assemble_bls12_381(2);
+    let twist_type_offset = 1 + 1 + 3 * 48 + 1 + 32 + 3 * 48;
+    // twist type, length of x, x and its sign
+    let pairs_offset = twist_type_offset + 1 + 1 + 8 + 1;
+    let curve = &calldata[..pairs_offset];
+    let pairs = &calldata[pairs_offset..];
+    // number of pairs and the subgroup check flags are skipped
+    let p = &pairs[2..(2 + 96)];
+    let q = &pairs[(2 + 96 + 1)..(2 + 96 + 1 + 192)];
+
+    let with_twist_type = |operation: u8, body: &[u8], twist_type: u8| {
+        let mut input = vec![operation];
+        input.extend_from_slice(curve);
+        input.extend_from_slice(body);
+        input[OPERATION_ENCODING_LENGTH + twist_type_offset] = twist_type;
+
+        input
+    };
+
+    let mut one = vec![0u8; 32];
+    one[31] = 1;
+    let miller_loop_value = API::run(&with_twist_type(OPERATION_MILLER_LOOP, pairs, TWIST_TYPE_M)).unwrap();
+    let final_exp = [vec![1u8], miller_loop_value].concat();
+    let equation = [vec![2u8, SIGN_PLUS, 1], p.to_vec(), vec![1], q.to_vec(), vec![SIGN_MINUS, 1], p.to_vec(), vec![1], q.to_vec()].concat();
+    // secret key is one, so the public key is the generator and the signature is the message
+    let bls_verify = [vec![BLS_PUBLIC_KEY_IN_G1], p.to_vec(), p.to_vec(), q.to_vec(), q.to_vec()].concat();
+    // tau = z = y = 1 and the commitment is G, so both sides of the opening check are one
+    let kzg_opening = [p.to_vec(), q.to_vec(), q.to_vec(), p.to_vec(), one.clone(), one.clone(), p.to_vec()].concat();
+
+    let cases = vec![
+        (OPERATION_PAIRING, pairs.to_vec(), true),
+        (OPERATION_PAIRING_GT, pairs.to_vec(), true),
+        (OPERATION_MILLER_LOOP, pairs.to_vec(), true),
+        (OPERATION_FINAL_EXP, final_exp, false),
+        (OPERATION_PAIRING_EQUATION, equation, true),
+        (OPERATION_BLS_VERIFY, bls_verify, true),
+        (OPERATION_KZG_OPENING, kzg_opening, true),
+    ];
+    for (operation, body, has_g2_points) in cases.into_iter() {
+        let expected = API::run(&with_twist_type(operation, &body, TWIST_TYPE_M)).unwrap();
+        assert_eq!(API::run(&with_twist_type(operation, &body, TWIST_TYPE_DETECT)).unwrap(), expected, "operation {}", operation);
+        if has_g2_points {
+            assert!(API::run(&with_twist_type(operation, &body, TWIST_TYPE_D)).is_err(), "operation {}", operation);
+        }
+        if operation == OPERATION_PAIRING_EQUATION || operation == OPERATION_BLS_VERIFY || operation == OPERATION_KZG_OPENING {
+            assert_eq!(expected, vec![1u8]);
+        }
+    }
+}
+
 #[test]
 fn test_call_public_api_on_bls12_377() {
     let calldata = assemble_bls12_377(4);
     use crate::public_interface::PairingApi;
 
-    let result = crate::public_interface::PublicPairingApi::pair(&calldata).unwrap();
+    let result = crate::public_interface::PublicPairingApi::pair(&calldata, &ExecutionContext::default()).unwrap();
     assert!(result.len() == 1);
     assert!(result[0] == 1);
 }
 
+#[test]
+fn test_call_public_api_pair_to_gt_on_bls12_377() {
+    let calldata = assemble_bls12_377(4);
+    use crate::public_interface::PairingApi;
+    use crate::public_interface::API;
+
+    let result = crate::public_interface::PublicPairingApi::pair_to_gt(&calldata, &ExecutionContext::default()).unwrap();
+    assert_eq!(result, super::encoded_gt_one(48, 12));
+
+    let mut api_input = vec![OPERATION_PAIRING_GT];
+    api_input.extend(calldata);
+    assert_eq!(API::run(&api_input).unwrap(), result);
+}
+
+#[test]
+fn test_call_public_api_miller_loop_and_final_exp_on_bls12_377() {
+    use crate::public_interface::PairingApi;
+    use crate::public_interface::PublicPairingApi;
+    use crate::public_interface::API;
+
+    // curve parameters without the number of pairs
+    let mut final_exp_input = assemble_bls12_377(0);
+    final_exp_input.pop();
+
+    // (P, Q) and then (P, Q), (-P, Q) in separate calls
+    let mut api_input = vec![OPERATION_MILLER_LOOP];
+    api_input.extend(assemble_bls12_377(1));
+    let single = API::run(&api_input).unwrap();
+    assert_eq!(single.len(), 12 * 48);
+    assert!(single != super::encoded_gt_one(48, 12));
+    let double = PublicPairingApi::miller_loop(&assemble_bls12_377(2), &ExecutionContext::default()).unwrap();
+
+    final_exp_input.push(2u8);
+    final_exp_input.extend(single);
+    final_exp_input.extend(double);
+
+    let mut api_input = vec![OPERATION_FINAL_EXP];
+    api_input.extend(final_exp_input.clone());
+    let result = API::run(&api_input).unwrap();
+    assert_eq!(result, PublicPairingApi::pair_to_gt(&assemble_bls12_377(3), &ExecutionContext::default()).unwrap());
+    assert!(result != super::encoded_gt_one(48, 12));
+
+    final_exp_input.extend(vec![0u8; 48]);
+    assert!(PublicPairingApi::final_exp(&final_exp_input, &ExecutionContext::default()).is_err());
+}
+
+#[test]
+fn test_call_public_api_with_pairing_mode_on_bls12_377() {
+    use crate::public_interface::PairingApi;
+    use crate::public_interface::PublicPairingApi;
+
+    let calldata = assemble_bls12_377(1);
+    let miller_loop = PublicPairingApi::miller_loop(&calldata, &ExecutionContext::default()).unwrap();
+
+    let mut miller_loop_only = calldata.clone();
+    miller_loop_only.push(PAIRING_MODE_MILLER_LOOP_ONLY);
+    assert_eq!(PublicPairingApi::pair(&miller_loop_only, &ExecutionContext::default()).unwrap(), miller_loop);
+    assert_eq!(PublicPairingApi::pair_to_gt(&miller_loop_only, &ExecutionContext::default()).unwrap(), miller_loop);
+
+    let mut full = calldata.clone();
+    full.push(PAIRING_MODE_FULL);
+    assert_eq!(PublicPairingApi::pair(&full, &ExecutionContext::default()).unwrap(), vec![0u8]);
+    assert_eq!(PublicPairingApi::pair_to_gt(&full, &ExecutionContext::default()).unwrap(), PublicPairingApi::pair_to_gt(&calldata, &ExecutionContext::default()).unwrap());
+
+    let mut unknown_mode = calldata.clone();
+    unknown_mode.push(0x02);
+    assert!(PublicPairingApi::pair(&unknown_mode, &ExecutionContext::default()).is_err());
+
+    full.push(PAIRING_MODE_FULL);
+    assert!(PublicPairingApi::pair(&full, &ExecutionContext::default()).is_err());
+}
+
 #[test]
 // #[ignore]
 fn test_print_bls12_381_test_vector() {
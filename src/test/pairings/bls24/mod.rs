@@ -0,0 +1,56 @@
+use crate::public_interface::constants::*;
+
+use super::{ToyCurve, encode_loop_parameter, check_toy_curve_public_api, check_toy_curve_metering};
+
+const MODULUS_LENGTH: usize = 20;
+
+// Toy BLS24 curve with x = -65831 and 159 bit modulus, so the test runs fast.
+// Tower is built with u^2 = 5 and v^3 = w, twist is of D type
+pub(crate) fn assemble_bls24_test_curve(num_point_pairs: usize) -> Vec<u8> {
+    let curve = ToyCurve {
+        curve_type: BLS24,
+        modulus_len: MODULUS_LENGTH,
+        modulus: "509561329363378480588473444847905545412792304537",
+        a: 0,
+        b: 1,
+        group_order: "352730990601061952998750963997732433121",
+        p: (
+            "457790000106890064450002378016534442147523860194",
+            "126137624424856046836872075631991664015010953721",
+        ),
+        q: &[
+            "341414800597707762976808732983152662637523783877",
+            "141691995327673744953574629504250241877962255762",
+            "48799140057592223441658588006612166393686753186",
+            "241749287001419140937726197234860175718091732121",
+            "419085460258284150223517265584578688306909997541",
+            "123955142087292394082021673448497760936131371517",
+            "341315689391155539071066006374380341141222365689",
+            "240884755628479114790426109864863130144025085584",
+        ],
+    };
+
+    // - non-residue for Fp2
+    // - non-residue for Fp12 (element of Fp4)
+    // - twist type M/D
+    // - parameter X
+    // - sign of X
+    let mut parameters = curve.encode_fp(5);
+    for c in &[0u64, 0u64, 1u64, 0u64] {
+        parameters.extend(curve.encode_fp(*c));
+    }
+    parameters.push(TWIST_TYPE_D);
+    parameters.extend(encode_loop_parameter(65831, SIGN_MINUS));
+
+    curve.assemble(&parameters, num_point_pairs)
+}
+
+#[test]
+fn test_call_public_api_on_bls24_test_curve() {
+    check_toy_curve_public_api(assemble_bls24_test_curve, MODULUS_LENGTH, 24);
+}
+
+#[test]
+fn test_meter_bls24_test_curve() {
+    check_toy_curve_metering(assemble_bls24_test_curve);
+}
@@ -0,0 +1,77 @@
+use crate::public_interface::constants::*;
+
+use super::{ToyCurve, encode_loop_parameter, check_toy_curve_public_api, check_toy_curve_metering};
+
+const MODULUS_LENGTH: usize = 26;
+
+// Toy BLS48 curve with x = 2470 and 202 bit modulus, so the test runs fast.
+// Tower is built with u^2 = 7 and v^3 = z, twist is of M type
+pub(crate) fn assemble_bls48_test_curve(num_point_pairs: usize) -> Vec<u8> {
+    let curve = ToyCurve {
+        curve_type: BLS48,
+        modulus_len: MODULUS_LENGTH,
+        modulus: "3900067982257971406335440851621796993324203292652129302034457",
+        a: 0,
+        b: 3,
+        group_order: "1919337073641697218700435018344997774751611743900000001",
+        p: (
+            "2641370903402414242612271307501014726222144089429485725166488",
+            "3087211598124698020374593264074694280951826493863881981732576",
+        ),
+        q: &[
+            "1585387700922967484138448046750633547266985906819082994714235",
+            "292248448323320783298965750228767034467036945337084642323085",
+            "2020626530661459583475400761730480531540089448256456460314711",
+            "78414097465827443393635218431737947398492396672317293996707",
+            "2536471156010521759574467534662665518958170754486352754494860",
+            "1055401862197179448121823939747794538999176719927579330322724",
+            "3158174701352875811551568975273897444817917163296742886733530",
+            "1349498142669987742534881038685040800567111404564229139567347",
+            "2268228669291471517814589403078783872134082615778993839567007",
+            "3372321471751855924645780185672526664061135388950539443626298",
+            "2648837602189229517984008457693353255950825045171828691071686",
+            "2694276481419130563590638602744359019809409325705329858556040",
+            "3549767552226999541970503494567971144234144403631053061850180",
+            "2367026011743096389371376740538259858507684864222539037866236",
+            "3596750488297576840311415936440173397305319914116969136939683",
+            "299549090430057466043922345220449868310043063233727892403313",
+        ],
+    };
+
+    // - non-residue for Fp2
+    // - non-residue for Fp24 (element of Fp8)
+    // - twist type M/D
+    // - parameter X
+    // - sign of X
+    let mut parameters = curve.encode_fp(7);
+    for c in &[0u64, 0u64, 0u64, 0u64, 1u64, 0u64, 0u64, 0u64] {
+        parameters.extend(curve.encode_fp(*c));
+    }
+    parameters.push(TWIST_TYPE_M);
+    parameters.extend(encode_loop_parameter(2470, SIGN_PLUS));
+
+    curve.assemble(&parameters, num_point_pairs)
+}
+
+#[test]
+fn test_call_public_api_on_bls48_test_curve() {
+    check_toy_curve_public_api(assemble_bls48_test_curve, MODULUS_LENGTH, 48);
+}
+
+#[test]
+fn test_meter_bls48_test_curve() {
+    check_toy_curve_metering(assemble_bls48_test_curve);
+}
+
+#[test]
+fn test_meter_bls48_test_curve_above_bls24() {
+    let mut input = vec![OPERATION_PAIRING];
+    input.extend(assemble_bls48_test_curve(2));
+    let bls48_gas = crate::gas_meter::GasMeter::meter(&input).unwrap();
+
+    let mut input = vec![OPERATION_PAIRING];
+    input.extend(super::bls24::assemble_bls24_test_curve(2));
+    let bls24_gas = crate::gas_meter::GasMeter::meter(&input).unwrap();
+
+    assert!(bls48_gas > bls24_gas);
+}
@@ -1,4 +1,5 @@
 use crate::public_interface::constants::*;
+use crate::public_interface::ExecutionContext;
 use crate::public_interface::{PublicG1Api, G1Api, PublicG2Api, G2Api};
 use crate::errors::ApiError;
 
@@ -156,7 +157,7 @@ pub(crate) fn assemble_single_curve_params(curve: JsonBnPairingCurveParameters,
                 mul_calldata.extend_from_slice(&g1_y[..]);
                 mul_calldata.extend(pad_for_len_be(r1.to_bytes_be(), group_size_length));
 
-                let g1 = PublicG1Api::mul_point(&mul_calldata[..])?;
+                let g1 = PublicG1Api::mul_point(&mul_calldata[..], &ExecutionContext::default())?;
 
                 g1
             };
@@ -167,7 +168,7 @@ pub(crate) fn assemble_single_curve_params(curve: JsonBnPairingCurveParameters,
                 mul_calldata.extend(g2_generator_encoding.clone());
                 mul_calldata.extend(pad_for_len_be(r2.to_bytes_be(), group_size_length));
 
-                let g2 = PublicG2Api::mul_point(&mul_calldata[..])?;
+                let g2 = PublicG2Api::mul_point(&mul_calldata[..], &ExecutionContext::default())?;
 
                 g2
             };
@@ -179,7 +180,7 @@ pub(crate) fn assemble_single_curve_params(curve: JsonBnPairingCurveParameters,
                 mul_calldata.extend_from_slice(&g1_y[..]);
                 mul_calldata.extend(pad_for_len_be(r3.to_bytes_be(), group_size_length));
 
-                let g1 = PublicG1Api::mul_point(&mul_calldata[..])?;
+                let g1 = PublicG1Api::mul_point(&mul_calldata[..], &ExecutionContext::default())?;
 
                 g1
             };
@@ -415,7 +416,7 @@ fn test_call_public_api_on_bn254() {
     let calldata = assemble_bn254(4);
     use crate::public_interface::PairingApi;
 
-    let result = crate::public_interface::PublicPairingApi::pair(&calldata).unwrap();
+    let result = crate::public_interface::PublicPairingApi::pair(&calldata, &ExecutionContext::default()).unwrap();
     assert!(result.len() == 1);
     assert!(result[0] == 1);
 }
@@ -473,4 +474,391 @@ fn strip_0x_and_pad(string: &str) -> String {
     }
 
     std::string::String::from_utf8(string).unwrap()
-}
\ No newline at end of file
+}
+#[test]
+fn test_pairing_call_encoding_matches_assembled() {
+    use crate::public_interface::API;
+    use crate::public_interface::encoding::*;
+    use num_traits::Num;
+
+    let from_dec = |s: &str| BigUint::from_str_radix(s, 10).unwrap().to_bytes_be();
+
+    let modulus = BigUint::from_str_radix("21888242871839275222246405745257275088696311157297823662689037894645226208583", 10).unwrap();
+    let g1 = G1CurveDescriptor::new(
+        &modulus.to_bytes_be(),
+        &[0u8],
+        &[3u8],
+        &from_dec("21888242871839275222246405745257275088548364400416034343698204186575808495617")
+    ).unwrap();
+    let minus_one = (&modulus - BigUint::from(1u64)).to_bytes_be();
+    let mut call = PairingCall::bn(g1, &minus_one, [&[9u8], &[1u8]], TwistType::D, &4965661367192848881u64.to_be_bytes(), false).unwrap();
+
+    let p = G1Point::new(&[1u8], &[2u8]);
+    let minus_p = G1Point::new(&[1u8], &(&modulus - BigUint::from(2u64)).to_bytes_be());
+    let q = G2Point::new(
+        &[
+            &from_dec("10857046999023057135944570762232829481370756359578518086990519993285655852781"),
+            &from_dec("11559732032986387107991004021392285783925812861821192530917403151452391805634")
+        ],
+        &[
+            &from_dec("8495653923123431417604973247489272438418190587263600148770280649306958101930"),
+            &from_dec("4082367875863433681332203403145435568316851327593401208105741076214120093531")
+        ]
+    );
+    call.add_pair(p, q.clone(), true).add_pair(minus_p, q, true);
+
+    let encoding = call.encode().unwrap();
+    let mut expected = vec![OPERATION_PAIRING];
+    expected.extend(assemble_bn254(2));
+    assert_eq!(encoding, expected);
+    assert_eq!(API::run(&encoding).unwrap(), vec![BOOLEAN_TRUE]);
+
+    call.miller_loop_only();
+    assert_eq!(call.encode().unwrap().len(), encoding.len() + 1);
+
+    let too_long = vec![1u8; 33];
+    assert!(G1CurveDescriptor::new(&modulus.to_bytes_be(), &too_long, &[3u8], &[7u8]).is_err());
+    assert!(PairingCall::bn(G1CurveDescriptor::new(&modulus.to_bytes_be(), &[0u8], &[3u8], &[7u8]).unwrap(), &too_long, [&[9u8], &[1u8]], TwistType::D, &[1u8], false).is_err());
+}
+
+fn bn254_modulus() -> BigUint {
+    use num_traits::Num;
+
+    BigUint::from_str_radix("21888242871839275222246405745257275088696311157297823662689037894645226208583", 10).unwrap()
+}
+
+/// BN254 curve, its twist and the pairing call for them, with the generators of G1 and G2
+pub(crate) fn bn254_curves_and_generators() -> (
+    crate::public_interface::encoding::G1CurveDescriptor,
+    crate::public_interface::encoding::G2CurveDescriptor,
+    crate::public_interface::encoding::PairingCall,
+    crate::public_interface::encoding::G1Point,
+    crate::public_interface::encoding::G2Point
+) {
+    use crate::public_interface::encoding::*;
+    use num_traits::Num;
+
+    let from_dec = |s: &str| BigUint::from_str_radix(s, 10).unwrap().to_bytes_be();
+
+    let modulus = bn254_modulus();
+    let order = from_dec("21888242871839275222246405745257275088548364400416034343698204186575808495617");
+    let g1 = G1CurveDescriptor::new(&modulus.to_bytes_be(), &[0u8], &[3u8], &order).unwrap();
+    let minus_one = (&modulus - BigUint::from(1u64)).to_bytes_be();
+    // B' = 3 / (9 + u)
+    let g2 = G2CurveDescriptor::new(
+        &modulus.to_bytes_be(),
+        2,
+        &minus_one,
+        &[&[0u8], &[0u8]],
+        &[
+            &from_dec("19485874751759354771024239261021720505790618469301721065564631296452457478373"),
+            &from_dec("266929791119991161246907387137283842545076965332900288569378510910307636690")
+        ],
+        &order
+    ).unwrap();
+    let call = PairingCall::bn(g1.clone(), &minus_one, [&[9u8], &[1u8]], TwistType::D, &4965661367192848881u64.to_be_bytes(), false).unwrap();
+
+    let p = G1Point::new(&[1u8], &[2u8]);
+    let q = G2Point::new(
+        &[
+            &from_dec("10857046999023057135944570762232829481370756359578518086990519993285655852781"),
+            &from_dec("11559732032986387107991004021392285783925812861821192530917403151452391805634")
+        ],
+        &[
+            &from_dec("8495653923123431417604973247489272438418190587263600148770280649306958101930"),
+            &from_dec("4082367875863433681332203403145435568316851327593401208105741076214120093531")
+        ]
+    );
+
+    (g1, g2, call, p, q)
+}
+
+#[test]
+fn test_pairing_equation_on_bn254() {
+    use crate::public_interface::API;
+    use crate::public_interface::encoding::*;
+    use crate::gas_meter::GasMeter;
+
+    let (_, _, call, p, q) = bn254_curves_and_generators();
+    let minus_p = G1Point::new(&[1u8], &(bn254_modulus() - BigUint::from(2u64)).to_bytes_be());
+
+    let equation = |signed_pairs: &[(&G1Point, bool)]| {
+        let mut call = call.clone();
+        for (g1, is_negative) in signed_pairs.iter() {
+            call.add_pair_with_sign((*g1).clone(), q.clone(), true, *is_negative);
+        }
+
+        call.encode().unwrap()
+    };
+
+    // e(P, Q) == e(P, Q)
+    let same_sides = equation(&[(&p, false), (&p, true)]);
+    assert_eq!(same_sides[0], OPERATION_PAIRING_EQUATION);
+    assert_eq!(API::run(&same_sides).unwrap(), vec![BOOLEAN_TRUE]);
+
+    // e(P, Q) == e(-P, Q) only if e(P, Q)^2 == 1
+    let opposite_sides = equation(&[(&p, false), (&minus_p, true)]);
+    assert_eq!(API::run(&opposite_sides).unwrap(), vec![BOOLEAN_FALSE]);
+
+    // without minus signs it's the plain pairing
+    assert_eq!(API::run(&equation(&[(&p, false), (&minus_p, false)])).unwrap(), vec![BOOLEAN_TRUE]);
+    assert_eq!(API::run(&equation(&[(&minus_p, true), (&p, true)])).unwrap(), vec![BOOLEAN_TRUE]);
+
+    // priced as the pairing with the same number of pairs
+    let mut pairing = call.clone();
+    pairing.add_pair(p.clone(), q.clone(), true).add_pair(p.clone(), q.clone(), true);
+    assert_eq!(GasMeter::meter(&same_sides).unwrap(), GasMeter::meter(&pairing.encode().unwrap()).unwrap());
+
+    // no pairing mode after the pairs
+    let mut with_mode = same_sides.clone();
+    with_mode.push(PAIRING_MODE_FULL);
+    assert!(API::run(&with_mode).is_err());
+    assert!(GasMeter::meter(&with_mode).is_err());
+
+    let mut miller_loop_only = call.clone();
+    miller_loop_only.add_pair_with_sign(p.clone(), q.clone(), true, true).miller_loop_only();
+    assert!(miller_loop_only.encode().is_err());
+
+    // sign of the last pair is the first byte after the first pair
+    let pair_len = 1 + 1 + 2 * 32 + 1 + 4 * 32;
+    let mut invalid_sign = same_sides.clone();
+    invalid_sign[same_sides.len() - pair_len] = 2;
+    assert!(API::run(&invalid_sign).is_err());
+    assert!(GasMeter::meter(&invalid_sign).is_err());
+}
+
+#[test]
+fn test_bls_verify_on_bn254() {
+    use crate::public_interface::API;
+    use crate::public_interface::encoding::*;
+    use crate::gas_meter::GasMeter;
+
+    let (g1, g2, call, p, q) = bn254_curves_and_generators();
+    let g1_mul = |point: &G1Point, scalar: u64| {
+        let output = API::run(&g1.mul(point, &scalar.to_be_bytes()).unwrap()).unwrap();
+
+        G1Point::new(&output[..32], &output[32..])
+    };
+    let g2_mul = |point: &G2Point, scalar: u64| {
+        let output = API::run(&g2.mul(point, &scalar.to_be_bytes()).unwrap()).unwrap();
+
+        G2Point::new(&[&output[..32], &output[32..64]], &[&output[64..96], &output[96..]])
+    };
+
+    let secret_key = 0x1962u64;
+
+    // public key in G1, message and signature in G2
+    let public_key = g1_mul(&p, secret_key);
+    let message = g2_mul(&q, 7);
+    let signature = g2_mul(&message, secret_key);
+    let valid = call.bls_verify_with_g1_public_key(&p, &public_key, &message, &signature).unwrap();
+    assert_eq!(valid[0], OPERATION_BLS_VERIFY);
+    assert_eq!(API::run(&valid).unwrap(), vec![BOOLEAN_TRUE]);
+    let forged = call.bls_verify_with_g1_public_key(&p, &public_key, &message, &g2_mul(&message, secret_key + 1)).unwrap();
+    assert_eq!(API::run(&forged).unwrap(), vec![BOOLEAN_FALSE]);
+    let other_message = call.bls_verify_with_g1_public_key(&p, &public_key, &q, &signature).unwrap();
+    assert_eq!(API::run(&other_message).unwrap(), vec![BOOLEAN_FALSE]);
+
+    // priced as the pairing of two pairs with all points checked to be in the subgroup
+    let mut pairing = call.clone();
+    pairing.add_pair(public_key.clone(), message.clone(), true).add_pair(p.clone(), signature.clone(), true);
+    assert_eq!(GasMeter::meter(&valid).unwrap(), GasMeter::meter(&pairing.encode().unwrap()).unwrap());
+
+    // public key in G2, message and signature in G1
+    let public_key = g2_mul(&q, secret_key);
+    let message = g1_mul(&p, 11);
+    let signature = g1_mul(&message, secret_key);
+    let valid = call.bls_verify_with_g2_public_key(&q, &public_key, &message, &signature).unwrap();
+    assert_eq!(API::run(&valid).unwrap(), vec![BOOLEAN_TRUE]);
+    let forged = call.bls_verify_with_g2_public_key(&q, &public_key, &message, &p).unwrap();
+    assert_eq!(API::run(&forged).unwrap(), vec![BOOLEAN_FALSE]);
+    assert!(GasMeter::meter(&valid).is_ok());
+
+    // identity public key would accept the identity signature for any message
+    let zero_public_key = call.bls_verify_with_g2_public_key(&q, &G2Point::zero(), &message, &G1Point::zero()).unwrap();
+    assert!(API::run(&zero_public_key).is_err());
+
+    let mut garbage = valid.clone();
+    garbage.push(0u8);
+    assert!(API::run(&garbage).is_err());
+    assert!(GasMeter::meter(&garbage).is_err());
+
+    let mut unknown_group = valid.clone();
+    let point_len = 2 * 32;
+    let group_position = valid.len() - 2 * point_len - 2 * 2 * point_len - 1;
+    assert_eq!(unknown_group[group_position], BLS_PUBLIC_KEY_IN_G2);
+    unknown_group[group_position] = 0;
+    assert!(API::run(&unknown_group).is_err());
+    assert!(GasMeter::meter(&unknown_group).is_err());
+
+    let mut not_on_curve = valid.clone();
+    *not_on_curve.last_mut().unwrap() ^= 1;
+    assert!(API::run(&not_on_curve).is_err());
+}
+
+#[test]
+fn test_kzg_opening_on_bn254() {
+    use crate::public_interface::API;
+    use crate::public_interface::encoding::*;
+    use crate::gas_meter::GasMeter;
+
+    let (g1, g2, call, p, q) = bn254_curves_and_generators();
+    let g1_mul = |point: &G1Point, scalar: u64| {
+        let output = API::run(&g1.mul(point, &scalar.to_be_bytes()).unwrap()).unwrap();
+
+        G1Point::new(&output[..32], &output[32..])
+    };
+    let tau = 0x1962u64;
+    let tau_q = {
+        let output = API::run(&g2.mul(&q, &tau.to_be_bytes()).unwrap()).unwrap();
+
+        G2Point::new(&[&output[..32], &output[32..64]], &[&output[64..96], &output[96..]])
+    };
+
+    // f(x) = 2x^2 + 5x + 3 opened at 4 to f(4) = 55 with the quotient (f(x) - 55) / (x - 4) = 2x + 13
+    let commitment = g1_mul(&p, 2 * tau * tau + 5 * tau + 3);
+    let proof = g1_mul(&p, 2 * tau + 13);
+    let kzg_verify = |commitment: &G1Point, point: u64, value: u64, proof: &G1Point| {
+        call.kzg_verify(&p, &q, &tau_q, commitment, &point.to_be_bytes(), &value.to_be_bytes(), proof).unwrap()
+    };
+
+    let valid = kzg_verify(&commitment, 4, 55, &proof);
+    assert_eq!(valid[0], OPERATION_KZG_OPENING);
+    assert_eq!(API::run(&valid).unwrap(), vec![BOOLEAN_TRUE]);
+    assert_eq!(API::run(&kzg_verify(&commitment, 4, 56, &proof)).unwrap(), vec![BOOLEAN_FALSE]);
+    assert_eq!(API::run(&kzg_verify(&commitment, 5, 55, &proof)).unwrap(), vec![BOOLEAN_FALSE]);
+    assert_eq!(API::run(&kzg_verify(&commitment, 4, 55, &g1_mul(&p, 2 * tau + 14))).unwrap(), vec![BOOLEAN_FALSE]);
+
+    // constant polynomial has the zero proof, both pairs are dropped
+    let constant = g1_mul(&p, 7);
+    assert_eq!(API::run(&kzg_verify(&constant, 4, 7, &G1Point::zero())).unwrap(), vec![BOOLEAN_TRUE]);
+    assert_eq!(API::run(&kzg_verify(&constant, 4, 8, &G1Point::zero())).unwrap(), vec![BOOLEAN_FALSE]);
+
+    // two pairs with all points checked to be in the subgroup and the multiplications in G1
+    let mut pairing = call.clone();
+    pairing.add_pair(commitment.clone(), q.clone(), true).add_pair(proof.clone(), tau_q.clone(), true);
+    assert!(GasMeter::meter(&valid).unwrap() > GasMeter::meter(&pairing.encode().unwrap()).unwrap());
+
+    let zero_generator = call.kzg_verify(&G1Point::zero(), &q, &tau_q, &commitment, &[4u8], &[55u8], &proof).unwrap();
+    assert!(API::run(&zero_generator).is_err());
+
+    let mut garbage = valid.clone();
+    garbage.push(0u8);
+    assert!(API::run(&garbage).is_err());
+    assert!(GasMeter::meter(&garbage).is_err());
+
+    let truncated = &valid[..valid.len() - 1];
+    assert!(API::run(truncated).is_err());
+    assert!(GasMeter::meter(truncated).is_err());
+
+    let mut not_on_curve = valid.clone();
+    *not_on_curve.last_mut().unwrap() ^= 1;
+    assert!(API::run(&not_on_curve).is_err());
+}
+
+#[test]
+fn test_typed_api_pairing_and_curve_ops() {
+    use crate::public_interface::encoding::TwistType;
+    use crate::public_interface::typed::*;
+    use crate::integers::*;
+    use num_traits::Num;
+
+    let from_dec = |s: &str| MaxFieldUint::from_big_endian(&BigUint::from_str_radix(s, 10).unwrap().to_bytes_be());
+
+    let modulus = from_dec("21888242871839275222246405745257275088696311157297823662689037894645226208583");
+    let order = MaxGroupSizeUint::from_big_endian(&BigUint::from_str_radix("21888242871839275222246405745257275088548364400416034343698204186575808495617", 10).unwrap().to_bytes_be());
+    let curve = Curve::new(modulus, MaxFieldUint::zero(), MaxFieldUint::from(3u64), order).unwrap();
+
+    let p = G1Affine::new(MaxFieldUint::from(1u64), MaxFieldUint::from(2u64));
+    let minus_p = G1Affine::new(MaxFieldUint::from(1u64), modulus - MaxFieldUint::from(2u64));
+    let two_p = curve.mul(&p, &MaxGroupSizeUint::from(2u64)).unwrap();
+    assert_eq!(curve.add(&p, &p).unwrap(), two_p);
+    assert_eq!(curve.add(&p, &minus_p).unwrap(), G1Affine::zero());
+    assert_eq!(curve.multiexp(&[(p, MaxGroupSizeUint::from(3u64)), (minus_p, MaxGroupSizeUint::from(1u64))]).unwrap(), two_p);
+    assert!(curve.is_on_curve(&two_p).unwrap());
+    assert!(curve.add(&p, &G1Affine::new(MaxFieldUint::from(1u64), MaxFieldUint::from(3u64))).is_err());
+    assert!(curve.add(&p, &G1Affine::new(modulus, MaxFieldUint::from(2u64))).is_err());
+
+    let scalars = [MaxGroupSizeUint::from(2u64), MaxGroupSizeUint::zero(), order, order - MaxGroupSizeUint::from(1u64)];
+    assert_eq!(curve.mul_fixed_base(&p, &scalars).unwrap(), vec![two_p, G1Affine::zero(), G1Affine::zero(), minus_p]);
+    assert!(curve.mul_fixed_base(&G1Affine::new(MaxFieldUint::from(1u64), MaxFieldUint::from(3u64)), &scalars).is_err());
+
+    let engine = Engine::bn(
+        curve, 
+        modulus - MaxFieldUint::from(1u64), 
+        [MaxFieldUint::from(9u64), MaxFieldUint::from(1u64)], 
+        TwistType::D, 
+        MaxLoopParametersUint::from(4965661367192848881u64), 
+        false
+    ).unwrap();
+    let q = G2Affine::new(
+        [
+            from_dec("10857046999023057135944570762232829481370756359578518086990519993285655852781"),
+            from_dec("11559732032986387107991004021392285783925812861821192530917403151452391805634")
+        ],
+        [
+            from_dec("8495653923123431417604973247489272438418190587263600148770280649306958101930"),
+            from_dec("4082367875863433681332203403145435568316851327593401208105741076214120093531")
+        ]
+    );
+    assert!(engine.pairing(&[(p, q), (minus_p, q)]).unwrap());
+    assert!(engine.pairing(&[(two_p, q), (minus_p, q), (minus_p, q)]).unwrap());
+    assert!(!engine.pairing(&[(p, q)]).unwrap());
+    assert!(engine.pairing(&[(G1Affine::zero(), q)]).unwrap());
+
+    // Fp2 non-residue for the tower must not be a square
+    assert!(Engine::bn(curve, modulus - MaxFieldUint::from(1u64), [MaxFieldUint::from(1u64), MaxFieldUint::zero()], TwistType::D, MaxLoopParametersUint::from(4965661367192848881u64), false).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_typed_api_engine_from_json() {
+    use crate::public_interface::typed::*;
+
+    let engine_json = r#"{
+        "family": "bn",
+        "curve": {
+            "modulus": "0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47",
+            "a": "0x0",
+            "b": "0x3",
+            "order": "0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001"
+        },
+        "fp_non_residue": "0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd46",
+        "fp2_non_residue": ["0x9", "0x1"],
+        "twist_type": "D",
+        "loop_parameter": "0x44e992b44a6909f1",
+        "loop_parameter_is_negative": false
+    }"#;
+    let pairs_json = r#"[
+        [
+            {"x": "0x1", "y": "0x2"},
+            {
+                "x": ["0x1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed", "0x198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2"],
+                "y": ["0x12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa", "0x090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b"]
+            }
+        ],
+        [
+            {"x": "0x1", "y": "0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd45"},
+            {
+                "x": ["0x1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed", "0x198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2"],
+                "y": ["0x12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa", "0x090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b"]
+            }
+        ]
+    ]"#;
+
+    let engine: Engine = serde_json::from_str(engine_json).unwrap();
+    let pairs: Vec<(G1Affine, G2Affine)> = serde_json::from_str(pairs_json).unwrap();
+    assert!(engine.pairing(&pairs).unwrap());
+
+    let serialized = serde_json::to_string(&engine).unwrap();
+    assert!(serialized.contains("\"b\":\"0x3\""));
+    assert_eq!(serde_json::from_str::<Engine>(&serialized).unwrap(), engine);
+    let serialized = serde_json::to_string(&pairs).unwrap();
+    assert_eq!(serde_json::from_str::<Vec<(G1Affine, G2Affine)>>(&serialized).unwrap(), pairs);
+
+    // parameters are validated on load
+    assert!(serde_json::from_str::<Engine>(&engine_json.replace("\"0x9\"", "\"0x1\"")).is_err());
+    assert!(serde_json::from_str::<Curve>(r#"{"modulus": "0x30", "a": "0x0", "b": "0x3", "order": "0x7"}"#).is_err());
+    assert!(serde_json::from_str::<G1Affine>(r#"{"x": "0xzz", "y": "0x2"}"#).is_err());
+    assert!(serde_json::from_str::<G1Affine>(&format!(r#"{{"x": "0x1{}", "y": "0x2"}}"#, "0".repeat(256))).is_err());
+}
@@ -0,0 +1,58 @@
+use crate::public_interface::constants::*;
+use crate::public_interface::ExecutionContext;
+
+use super::{ToyCurve, encode_loop_parameter, check_toy_curve_public_api, check_toy_curve_metering};
+
+const MODULUS_LENGTH: usize = 18;
+
+// Toy curve with embedding degree 6 and 139 bit modulus, so the test runs fast.
+// Fp3 is built with u^3 = 11, twist y^2 = x^3 + 2 is of D type and
+// -1085797004 - 2344861129 * p is divisible by the group order
+pub(crate) fn assemble_bw6_test_curve(num_point_pairs: usize, ate_loop_count_2_sign: u8) -> Vec<u8> {
+    let curve = ToyCurve {
+        curve_type: BW6,
+        modulus_len: MODULUS_LENGTH,
+        modulus: "461213518087780290495466348546711772191201",
+        a: 0,
+        b: 22,
+        group_order: "9223372036854788173",
+        p: (
+            "344648827532110128529698186265253138548165",
+            "443923173248018889622668723838080333274019",
+        ),
+        q: &[
+            "226162852731288560233755255709866552999025",
+            "218500077896441239969305494774620509128679",
+        ],
+    };
+
+    // - non-residue for Fp3
+    // - twist type M/D
+    // - first ate loop count
+    // - sign of the first ate loop count
+    // - second ate loop count
+    // - sign of the second ate loop count
+    let mut parameters = curve.encode_fp(11);
+    parameters.push(TWIST_TYPE_D);
+    parameters.extend(encode_loop_parameter(1085797004, SIGN_MINUS));
+    parameters.extend(encode_loop_parameter(2344861129, ate_loop_count_2_sign));
+
+    curve.assemble(&parameters, num_point_pairs)
+}
+
+#[test]
+fn test_call_public_api_on_bw6_test_curve() {
+    use crate::public_interface::PairingApi;
+    use crate::public_interface::PublicPairingApi;
+
+    check_toy_curve_public_api(|num_point_pairs| assemble_bw6_test_curve(num_point_pairs, SIGN_MINUS), MODULUS_LENGTH, 6);
+
+    // loop counts that do not give a pairing are rejected
+    let result = PublicPairingApi::pair(&assemble_bw6_test_curve(1, SIGN_PLUS), &ExecutionContext::default());
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_meter_bw6_test_curve() {
+    check_toy_curve_metering(|num_point_pairs| assemble_bw6_test_curve(num_point_pairs, SIGN_MINUS));
+}
@@ -0,0 +1,102 @@
+use crate::public_interface::constants::*;
+use crate::public_interface::ExecutionContext;
+
+use num_bigint::BigUint;
+use num_traits::Num;
+
+use super::{ToyCurve, encode_loop_parameter, check_toy_curve_public_api, check_toy_curve_metering};
+
+const MODULUS_LENGTH: usize = 11;
+
+const FINAL_EXP_HARD_PART: &str = "1799208151994759109712912690976886054790052464049937194354814033004831421883430758152855";
+
+// Toy Cocks-Pinch curve y^2 = x^3 + 7 with embedding degree 5, 83 bit modulus and
+// 41 bit group order. Fp5 is built with z^5 = 2, ate loop count is t - 1
+pub(crate) fn assemble_cocks_pinch_test_curve_with_params(
+    num_point_pairs: usize,
+    embedding_degree: u8,
+    ate_loop_count: u64,
+    final_exp_hard_part: &str
+) -> Vec<u8> {
+    let curve = ToyCurve {
+        curve_type: COCKS_PINCH,
+        modulus_len: MODULUS_LENGTH,
+        modulus: "6669147293370943146157621",
+        a: 0,
+        b: 7,
+        group_order: "1099511628571",
+        p: (
+            "3852697215329928555837742",
+            "485752745167586870853691",
+        ),
+        q: &[
+            "84451820603761329244309",
+            "6087127693465387264541652",
+            "2464468506536579002497788",
+            "6263390187983063537673046",
+            "3197107704930740033495559",
+            "2343156159961721298578647",
+            "329734223208893644627253",
+            "4902287778180822052674421",
+            "562624720711297297974601",
+            "2862101813947284359295557",
+        ],
+    };
+
+    // - embedding degree
+    // - non-residue for Fp^k
+    // - ate loop count
+    // - sign of the ate loop count
+    // - hard part of the final exponentiation
+    let mut parameters = vec![embedding_degree];
+    parameters.extend(curve.encode_fp(2));
+    parameters.extend(encode_loop_parameter(ate_loop_count, SIGN_PLUS));
+    let hard_part = BigUint::from_str_radix(final_exp_hard_part, 10).unwrap().to_bytes_be();
+    parameters.push(hard_part.len() as u8);
+    parameters.extend(hard_part);
+
+    curve.assemble(&parameters, num_point_pairs)
+}
+
+pub(crate) fn assemble_cocks_pinch_test_curve(num_point_pairs: usize) -> Vec<u8> {
+    assemble_cocks_pinch_test_curve_with_params(num_point_pairs, 5, 3792171496596, FINAL_EXP_HARD_PART)
+}
+
+#[test]
+fn test_call_public_api_on_cocks_pinch_test_curve() {
+    check_toy_curve_public_api(assemble_cocks_pinch_test_curve, MODULUS_LENGTH, 5);
+}
+
+#[test]
+fn test_cocks_pinch_inconsistent_parameters() {
+    use crate::public_interface::PairingApi;
+    use crate::public_interface::PublicPairingApi;
+
+    // embedding degree is out of the limits
+    for k in &[1u8, 13u8] {
+        let input = assemble_cocks_pinch_test_curve_with_params(2, *k, 3792171496596, FINAL_EXP_HARD_PART);
+        assert!(PublicPairingApi::pair(&input, &ExecutionContext::default()).is_err());
+    }
+
+    // group order doesn't divide Phi_4(p)
+    let input = assemble_cocks_pinch_test_curve_with_params(2, 4, 3792171496596, FINAL_EXP_HARD_PART);
+    assert!(PublicPairingApi::pair(&input, &ExecutionContext::default()).is_err());
+
+    // ate loop count is not a power of p modulo the group order
+    let input = assemble_cocks_pinch_test_curve_with_params(2, 5, 3792171496597, FINAL_EXP_HARD_PART);
+    assert!(PublicPairingApi::pair(&input, &ExecutionContext::default()).is_err());
+
+    // hard part is not Phi_5(p) / r
+    let input = assemble_cocks_pinch_test_curve_with_params(
+        2,
+        5,
+        3792171496596,
+        "1799208151994759109712912690976886054790052464049937194354814033004831421883430758152857"
+    );
+    assert!(PublicPairingApi::pair(&input, &ExecutionContext::default()).is_err());
+}
+
+#[test]
+fn test_meter_cocks_pinch_test_curve() {
+    check_toy_curve_metering(assemble_cocks_pinch_test_curve);
+}
@@ -0,0 +1,56 @@
+use crate::public_interface::constants::*;
+
+use super::{ToyCurve, encode_loop_parameter, check_toy_curve_public_api, check_toy_curve_metering};
+
+const MODULUS_LENGTH: usize = 20;
+
+// Toy KSS16 curve y^2 = x^3 + 5x with x = -84045 and 154 bit modulus, so the test runs fast.
+// Tower is built with u^2 = 2, v^2 = u and t^4 = 2 + v, twist is of M type
+pub(crate) fn assemble_kss16_test_curve(num_point_pairs: usize) -> Vec<u8> {
+    let curve = ToyCurve {
+        curve_type: KSS16,
+        modulus_len: MODULUS_LENGTH,
+        modulus: "17942477277728791684549136700205802947266958333",
+        a: 5,
+        b: 0,
+        group_order: "40643299649185454740867818722884673",
+        p: (
+            "5399182452450254504523203832035213105968782836",
+            "11034869319790254163250885232701303103978798695",
+        ),
+        q: &[
+            "17053033852143890331558587767401947948970909371",
+            "13878401213269477078486144407317034842854086905",
+            "17589869197630374436435019256005671350157759415",
+            "15067195570525305636873859161502710529656507463",
+            "3187907940622497455066339938792659585484632742",
+            "4110035121887103554498184448125497378613842213",
+            "16038278261138142161186031517219614027916683837",
+            "13783057708050799853750345858066910700920726264",
+        ],
+    };
+
+    // - non-residue for Fp2
+    // - non-residue for Fp16 (element of Fp4)
+    // - twist type M/D
+    // - parameter X
+    // - sign of X
+    let mut parameters = curve.encode_fp(2);
+    for c in &[2u64, 0u64, 1u64, 0u64] {
+        parameters.extend(curve.encode_fp(*c));
+    }
+    parameters.push(TWIST_TYPE_M);
+    parameters.extend(encode_loop_parameter(84045, SIGN_MINUS));
+
+    curve.assemble(&parameters, num_point_pairs)
+}
+
+#[test]
+fn test_call_public_api_on_kss16_test_curve() {
+    check_toy_curve_public_api(assemble_kss16_test_curve, MODULUS_LENGTH, 16);
+}
+
+#[test]
+fn test_meter_kss16_test_curve() {
+    check_toy_curve_metering(assemble_kss16_test_curve);
+}
@@ -0,0 +1,54 @@
+use crate::public_interface::constants::*;
+
+use super::{ToyCurve, encode_loop_parameter, check_toy_curve_public_api, check_toy_curve_metering};
+
+const MODULUS_LENGTH: usize = 15;
+
+// Toy KSS18 curve with x = 29834 and 115 bit modulus, so the test runs fast.
+// Tower is built with u^3 = 3 and w^6 = 2 + u, twist is of D type
+pub(crate) fn assemble_kss18_test_curve(num_point_pairs: usize) -> Vec<u8> {
+    let curve = ToyCurve {
+        curve_type: KSS18,
+        modulus_len: MODULUS_LENGTH,
+        modulus: "29891339064057947269104759486668551",
+        a: 0,
+        b: 3,
+        group_order: "2055771269706915143651449",
+        p: (
+            "20348325738303987274204642675031933",
+            "9125504658269780392299759789195774",
+        ),
+        q: &[
+            "23647863247977117649259236473995796",
+            "26242209167743785876686397305904356",
+            "11270792907538847780638122983850104",
+            "28625498509801273066805536237290511",
+            "24683992006752141764924395052106815",
+            "16434150333801080175706901282939000",
+        ],
+    };
+
+    // - non-residue for Fp3
+    // - non-residue for Fp18 (element of Fp3)
+    // - twist type M/D
+    // - parameter X
+    // - sign of X
+    let mut parameters = curve.encode_fp(3);
+    for c in &[2u64, 1u64, 0u64] {
+        parameters.extend(curve.encode_fp(*c));
+    }
+    parameters.push(TWIST_TYPE_D);
+    parameters.extend(encode_loop_parameter(29834, SIGN_PLUS));
+
+    curve.assemble(&parameters, num_point_pairs)
+}
+
+#[test]
+fn test_call_public_api_on_kss18_test_curve() {
+    check_toy_curve_public_api(assemble_kss18_test_curve, MODULUS_LENGTH, 18);
+}
+
+#[test]
+fn test_meter_kss18_test_curve() {
+    check_toy_curve_metering(assemble_kss18_test_curve);
+}
@@ -1,4 +1,5 @@
 use crate::public_interface::constants::*;
+use crate::public_interface::ExecutionContext;
 use crate::public_interface::{PublicG1Api, G1Api, PublicG2Api, G2Api};
 use crate::errors::ApiError;
 
@@ -151,7 +152,7 @@ pub(crate) fn assemble_single_curve_params(curve: JsonMnt4PairingCurveParameters
                 mul_calldata.extend_from_slice(&g1_y[..]);
                 mul_calldata.extend(pad_for_len_be(r1.to_bytes_be(), group_size_length));
 
-                let g1 = PublicG1Api::mul_point(&mul_calldata[..])?;
+                let g1 = PublicG1Api::mul_point(&mul_calldata[..], &ExecutionContext::default())?;
 
                 g1
             };
@@ -162,7 +163,7 @@ pub(crate) fn assemble_single_curve_params(curve: JsonMnt4PairingCurveParameters
                 mul_calldata.extend(g2_generator_encoding.clone());
                 mul_calldata.extend(pad_for_len_be(r2.to_bytes_be(), group_size_length));
 
-                let g2 = PublicG2Api::mul_point(&mul_calldata[..])?;
+                let g2 = PublicG2Api::mul_point(&mul_calldata[..], &ExecutionContext::default())?;
 
                 g2
             };
@@ -174,7 +175,7 @@ pub(crate) fn assemble_single_curve_params(curve: JsonMnt4PairingCurveParameters
                 mul_calldata.extend_from_slice(&g1_y[..]);
                 mul_calldata.extend(pad_for_len_be(r3.to_bytes_be(), group_size_length));
 
-                let g1 = PublicG1Api::mul_point(&mul_calldata[..])?;
+                let g1 = PublicG1Api::mul_point(&mul_calldata[..], &ExecutionContext::default())?;
 
                 g1
             };
@@ -341,11 +342,38 @@ fn test_call_public_api_on_mnt4_753() {
     let calldata = assemble_mnt4_753(4);
     use crate::public_interface::PairingApi;
 
-    let result = crate::public_interface::PublicPairingApi::pair(&calldata).unwrap();
+    let result = crate::public_interface::PublicPairingApi::pair(&calldata, &ExecutionContext::default()).unwrap();
     assert!(result.len() == 1);
     assert!(result[0] == 1);
 }
 
+#[test]
+fn test_call_public_api_pair_to_gt_on_mnt4_753() {
+    let calldata = assemble_mnt4_753(4);
+    use crate::public_interface::PairingApi;
+
+    let result = crate::public_interface::PublicPairingApi::pair_to_gt(&calldata, &ExecutionContext::default()).unwrap();
+    assert_eq!(result, super::encoded_gt_one(95, 4));
+}
+
+#[test]
+fn test_call_public_api_final_exp_on_mnt4_753() {
+    let calldata = assemble_mnt4_753(4);
+    use crate::public_interface::PairingApi;
+    use crate::public_interface::PublicPairingApi;
+
+    let miller_loop = PublicPairingApi::miller_loop(&calldata, &ExecutionContext::default()).unwrap();
+    assert_eq!(miller_loop.len(), 4 * 95);
+
+    let mut final_exp_input = assemble_mnt4_753(0);
+    final_exp_input.pop();
+    final_exp_input.push(1u8);
+    final_exp_input.extend(miller_loop);
+
+    let result = PublicPairingApi::final_exp(&final_exp_input, &ExecutionContext::default()).unwrap();
+    assert_eq!(result, super::encoded_gt_one(95, 4));
+}
+
 #[test]
 #[ignore]
 fn test_print_mnt4_test_vector() {
@@ -1,4 +1,5 @@
 use crate::public_interface::constants::*;
+use crate::public_interface::ExecutionContext;
 use crate::public_interface::{PublicG1Api, G1Api, PublicG2Api, G2Api};
 use crate::errors::ApiError;
 
@@ -152,7 +153,7 @@ pub(crate) fn assemble_single_curve_params(curve: JsonMnt6PairingCurveParameters
                 mul_calldata.extend_from_slice(&g1_y[..]);
                 mul_calldata.extend(pad_for_len_be(r1.to_bytes_be(), group_size_length));
 
-                let g1 = PublicG1Api::mul_point(&mul_calldata[..])?;
+                let g1 = PublicG1Api::mul_point(&mul_calldata[..], &ExecutionContext::default())?;
 
                 g1
             };
@@ -163,7 +164,7 @@ pub(crate) fn assemble_single_curve_params(curve: JsonMnt6PairingCurveParameters
                 mul_calldata.extend(g2_generator_encoding.clone());
                 mul_calldata.extend(pad_for_len_be(r2.to_bytes_be(), group_size_length));
 
-                let g2 = PublicG2Api::mul_point(&mul_calldata[..])?;
+                let g2 = PublicG2Api::mul_point(&mul_calldata[..], &ExecutionContext::default())?;
 
                 g2
             };
@@ -175,7 +176,7 @@ pub(crate) fn assemble_single_curve_params(curve: JsonMnt6PairingCurveParameters
                 mul_calldata.extend_from_slice(&g1_y[..]);
                 mul_calldata.extend(pad_for_len_be(r3.to_bytes_be(), group_size_length));
 
-                let g1 = PublicG1Api::mul_point(&mul_calldata[..])?;
+                let g1 = PublicG1Api::mul_point(&mul_calldata[..], &ExecutionContext::default())?;
 
                 g1
             };
@@ -1,11 +1,154 @@
 pub(crate) mod bls12;
+pub(crate) mod bls24;
+pub(crate) mod bls48;
+pub(crate) mod bw6;
+pub(crate) mod kss16;
+pub(crate) mod kss18;
+pub(crate) mod cocks_pinch;
 pub(crate) mod bn;
 pub(crate) mod mnt4;
 pub(crate) mod mnt6;
 
-use crate::public_interface::{PairingApi, PublicPairingApi};
+use crate::public_interface::{PairingApi, PublicPairingApi, API};
+use crate::public_interface::ExecutionContext;
+use crate::public_interface::constants::*;
+use crate::gas_meter::GasMeter;
 use crate::errors::ApiError;
+use crate::test::parsers::pad_for_len_be;
+
+use num_bigint::BigUint;
+use num_traits::Num;
 
 pub(crate) fn call_pairing_engine(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
-    PublicPairingApi::pair(&bytes)
-}
\ No newline at end of file
+    PublicPairingApi::pair(&bytes, &ExecutionContext::default())
+}
+
+// Fixed length encoding of one in GT of degree `extension_degree`
+pub(crate) fn encoded_gt_one(modulus_len: usize, extension_degree: usize) -> Vec<u8> {
+    let mut encoding = vec![0u8; modulus_len * extension_degree];
+    encoding[modulus_len - 1] = 1u8;
+
+    encoding
+}
+// Part of a toy curve encoding that doesn't depend on the family: the curve in Weierstrass form,
+// the group order and a pair (P, Q) that the pairs are made of
+pub(crate) struct ToyCurve<'a> {
+    pub(crate) curve_type: u8,
+    pub(crate) modulus_len: usize,
+    pub(crate) modulus: &'a str,
+    pub(crate) a: u64,
+    pub(crate) b: u64,
+    pub(crate) group_order: &'a str,
+    pub(crate) p: (&'a str, &'a str),
+    // coordinates of Q in the order of encoding, elements of the base field
+    pub(crate) q: &'a [&'a str],
+}
+
+impl<'a> ToyCurve<'a> {
+    pub(crate) fn encode_fp(&self, value: u64) -> Vec<u8> {
+        pad_for_len_be(BigUint::from(value).to_bytes_be(), self.modulus_len)
+    }
+
+    pub(crate) fn assemble(&self, family_parameters: &[u8], num_point_pairs: usize) -> Vec<u8> {
+        // - Curve type
+        // - Lengths of modulus (in bytes)
+        // - Field modulus
+        // - Curve A
+        // - Curve B
+        // - Group order
+        // - family specific parameters
+        // - number of pairs
+        // - list of encoded pairs
+        let modulus = BigUint::from_str_radix(self.modulus, 10).unwrap();
+        let group_order = BigUint::from_str_radix(self.group_order, 10).unwrap();
+        let group_order_len = group_order.to_bytes_be().len();
+
+        let mut calldata = vec![self.curve_type, self.modulus_len as u8];
+        calldata.extend(pad_for_len_be(modulus.to_bytes_be(), self.modulus_len));
+        calldata.extend(self.encode_fp(self.a));
+        calldata.extend(self.encode_fp(self.b));
+        calldata.push(group_order_len as u8);
+        calldata.extend(pad_for_len_be(group_order.to_bytes_be(), group_order_len));
+        calldata.extend_from_slice(family_parameters);
+        calldata.push(num_point_pairs as u8);
+
+        let p_x = BigUint::from_str_radix(self.p.0, 10).unwrap();
+        let p_y = BigUint::from_str_radix(self.p.1, 10).unwrap();
+        let minus_p_y = modulus - p_y.clone();
+
+        let mut g2_encoding = vec![BOOLEAN_TRUE];
+        for c in self.q.iter() {
+            let c = BigUint::from_str_radix(c, 10).unwrap();
+            g2_encoding.extend(pad_for_len_be(c.to_bytes_be(), self.modulus_len));
+        }
+
+        // pairs alternate between (P, Q) and (-P, Q)
+        for i in 0..num_point_pairs {
+            let y = if i % 2 == 0 { &p_y } else { &minus_p_y };
+            calldata.push(BOOLEAN_TRUE);
+            calldata.extend(pad_for_len_be(p_x.to_bytes_be(), self.modulus_len));
+            calldata.extend(pad_for_len_be(y.to_bytes_be(), self.modulus_len));
+            calldata.extend(g2_encoding.clone());
+        }
+
+        calldata
+    }
+}
+
+// Length prefixed big endian encoding of a loop parameter followed by its sign
+pub(crate) fn encode_loop_parameter(value: u64, sign: u8) -> Vec<u8> {
+    let value = BigUint::from(value).to_bytes_be();
+    let mut encoding = vec![value.len() as u8];
+    encoding.extend(value);
+    encoding.push(sign);
+
+    encoding
+}
+
+// Public API checks for a toy curve assembled by `assemble` from the number of pairs:
+// pairs of the assembled curves cancel each other, the Miller loop alone is the same through
+// `miller_loop` and the pairing mode and its final exponentiation is the pairing in GT
+pub(crate) fn check_toy_curve_public_api<F: Fn(usize) -> Vec<u8>>(assemble: F, modulus_len: usize, extension_degree: usize) {
+    let result = PublicPairingApi::pair(&assemble(4), &ExecutionContext::default()).unwrap();
+    assert_eq!(result, vec![1u8]);
+
+    let result = PublicPairingApi::pair(&assemble(1), &ExecutionContext::default()).unwrap();
+    assert_eq!(result, vec![0u8]);
+
+    let result = PublicPairingApi::pair_to_gt(&assemble(2), &ExecutionContext::default()).unwrap();
+    assert_eq!(result, encoded_gt_one(modulus_len, extension_degree));
+
+    // curve parameters without the number of pairs
+    let mut final_exp_input = assemble(0);
+    final_exp_input.pop();
+
+    let miller_loop = PublicPairingApi::miller_loop(&assemble(1), &ExecutionContext::default()).unwrap();
+    assert_eq!(miller_loop.len(), extension_degree * modulus_len);
+
+    let mut miller_loop_only = assemble(1);
+    miller_loop_only.push(PAIRING_MODE_MILLER_LOOP_ONLY);
+    assert_eq!(PublicPairingApi::pair(&miller_loop_only, &ExecutionContext::default()).unwrap(), miller_loop);
+
+    final_exp_input.push(1u8);
+    final_exp_input.extend(miller_loop);
+
+    let mut api_input = vec![OPERATION_FINAL_EXP];
+    api_input.extend(final_exp_input);
+    let result = API::run(&api_input).unwrap();
+    assert_eq!(result, PublicPairingApi::pair_to_gt(&assemble(1), &ExecutionContext::default()).unwrap());
+    assert!(result != encoded_gt_one(modulus_len, extension_degree));
+}
+
+// Gas of a toy curve grows with the number of pairs
+pub(crate) fn check_toy_curve_metering<F: Fn(usize) -> Vec<u8>>(assemble: F) {
+    let mut input = vec![OPERATION_PAIRING];
+    input.extend(assemble(2));
+    let two_pairs = GasMeter::meter(&input).unwrap();
+
+    let mut input = vec![OPERATION_PAIRING];
+    input.extend(assemble(4));
+    let four_pairs = GasMeter::meter(&input).unwrap();
+
+    assert!(two_pairs > 0);
+    assert!(four_pairs > two_pairs);
+}
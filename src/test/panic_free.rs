@@ -0,0 +1,127 @@
+// Property based fuzzing of the public API. Valid BN254 inputs for every kind of operation are
+// mutated (bytes overwritten, the input truncated or extended), and random inputs are tried for
+// every operation type. Neither the execution nor the gas metering may panic on any of them, and
+// the protected entry point must give the same results as the plain one
+
+use proptest::prelude::*;
+
+use crate::public_interface::{API, ApiError, ErrorCode};
+use crate::errors::catch_internal_error;
+use crate::public_interface::encoding::*;
+use crate::gas_meter::GasMeter;
+use crate::test::pairings::bn::bn254_curves_and_generators;
+
+#[derive(Debug, Clone)]
+enum Mutation {
+    Overwrite(usize, u8),
+    Truncate(usize),
+    Insert(usize, Vec<u8>),
+}
+
+impl Mutation {
+    fn apply(&self, input: &mut Vec<u8>) {
+        match self {
+            Mutation::Overwrite(position, value) => {
+                if !input.is_empty() {
+                    let len = input.len();
+                    input[position % len] = *value;
+                }
+            },
+            Mutation::Truncate(position) => {
+                input.truncate(position % (input.len() + 1));
+            },
+            Mutation::Insert(position, bytes) => {
+                let position = position % (input.len() + 1);
+                input.splice(position..position, bytes.iter().cloned());
+            }
+        }
+    }
+}
+
+fn mutation_strategy() -> impl Strategy<Value = Mutation> {
+    prop_oneof![
+        4 => (any::<usize>(), any::<u8>()).prop_map(|(position, value)| Mutation::Overwrite(position, value)),
+        1 => any::<usize>().prop_map(Mutation::Truncate),
+        1 => (any::<usize>(), proptest::collection::vec(any::<u8>(), 1..8)).prop_map(|(position, bytes)| Mutation::Insert(position, bytes)),
+    ]
+}
+
+fn valid_inputs() -> Vec<Vec<u8>> {
+    let (g1, g2, call, p, q) = bn254_curves_and_generators();
+    let scalar = 0x1962u64.to_be_bytes();
+
+    let mut pairing = call.clone();
+    pairing.add_pair(p.clone(), q.clone(), true).add_pair(G1Point::zero(), q.clone(), true);
+
+    let mut miller_loop = pairing.clone();
+    miller_loop.miller_loop_only();
+
+    let mut equation = call.clone();
+    equation.add_pair_with_sign(p.clone(), q.clone(), true, false).add_pair_with_sign(p.clone(), q.clone(), true, true);
+
+    vec![
+        g1.add(&p, &p).unwrap(),
+        g1.mul(&p, &scalar).unwrap(),
+        g1.multiexp(&[(p.clone(), scalar.to_vec()), (G1Point::zero(), vec![1u8])]).unwrap(),
+        g1.sum(&[p.clone(), p.clone()]).unwrap(),
+        g2.add(&q, &q).unwrap(),
+        g2.mul(&q, &scalar).unwrap(),
+        g2.multiexp(&[(q.clone(), scalar.to_vec())]).unwrap(),
+        pairing.encode().unwrap(),
+        miller_loop.encode().unwrap(),
+        equation.encode().unwrap(),
+        call.kzg_verify(&p, &q, &q, &p, &[4u8], &[55u8], &G1Point::zero()).unwrap(),
+    ]
+}
+
+fn assert_no_panic(input: &[u8]) -> Result<(), TestCaseError> {
+    let result = std::panic::catch_unwind(|| {
+        let _ = GasMeter::meter(input);
+        API::run(input)
+    });
+    prop_assert!(result.is_ok(), "panicked on input {}", hex::encode(input));
+    prop_assert_eq!(result.unwrap(), API::run_protected(input));
+
+    Ok(())
+}
+
+#[test]
+fn test_panic_is_reported_as_internal_error() {
+    let result: Result<(), ApiError> = catch_internal_error(|| panic!("broken invariant {}", 1962));
+    let error = result.unwrap_err();
+    assert_eq!(error.code(), ErrorCode::InternalError);
+    assert_eq!(error, ApiError::InternalError("broken invariant 1962".to_owned()));
+
+    let result: Result<(), ApiError> = catch_internal_error(|| panic!("static message"));
+    assert_eq!(result.unwrap_err(), ApiError::InternalError("static message".to_owned()));
+}
+
+#[test]
+fn test_protected_run_of_valid_inputs() {
+    for input in valid_inputs() {
+        assert_eq!(API::run_protected(&input).unwrap(), API::run(&input).unwrap());
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn test_mutated_inputs_do_not_panic(seed in any::<prop::sample::Index>(), mutations in proptest::collection::vec(mutation_strategy(), 1..4)) {
+        let inputs = valid_inputs();
+        let mut input = inputs[seed.index(inputs.len())].clone();
+        for mutation in mutations.iter() {
+            mutation.apply(&mut input);
+        }
+
+        assert_no_panic(&input)?;
+    }
+
+    #[test]
+    fn test_random_inputs_do_not_panic(operation in any::<u8>(), body in proptest::collection::vec(any::<u8>(), 0..256)) {
+        let mut input = vec![operation];
+        input.extend(body);
+
+        assert_no_panic(&input)?;
+    }
+}
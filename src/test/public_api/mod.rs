@@ -1,4 +1,5 @@
 use crate::public_interface::{OperationType, perform_operation, ApiError};
+use crate::public_interface::{ExecutionContext, PointEncoding};
 
 use crate::weierstrass::{Group, CurveOverFpParameters};
 use crate::weierstrass::curve::{CurvePoint, WeierstrassCurve};
@@ -71,7 +72,7 @@ impl<FE: ElementRepr> Tester<FE> {
     fn parse_and_compare_muls(bytes: &[u8]) -> Result<(), ApiError> {
         let (field, modulus_len, _, rest) = parse_base_field_from_encoding::<FE>(&bytes)?;
         let (a, b, rest) = parse_ab_in_base_field_from_encoding(&rest, modulus_len, &field)?;
-        let (order_len, order, rest) = parse_group_order_from_encoding(rest)?;
+        let (order_len, order, rest) = parse_group_order_from_encoding(rest, &ExecutionContext::default())?;
 
         let fp_params = CurveOverFpParameters::new(&field);
 
@@ -79,7 +80,7 @@ impl<FE: ElementRepr> Tester<FE> {
             ApiError::InputError("Curve shape is not supported".to_owned())
         })?;
 
-        let (p_0, rest) = decode_g1_point_from_xy(rest, modulus_len, &curve)?;
+        let (p_0, rest) = decode_g1_point_from_xy(rest, modulus_len, &curve, PointEncoding::Uncompressed)?;
         let (scalar, rest) = decode_scalar_representation(rest, order_len)?;
 
         if rest.len() != 0 {
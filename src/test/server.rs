@@ -0,0 +1,80 @@
+use serde_json::{json, Value};
+
+use crate::public_interface::API;
+use crate::server::handle;
+
+fn first_g1_mul_vector() -> (String, String) {
+    let contents = std::fs::read_to_string("src/test/test_vectors/bls12/g1_mul.csv").unwrap();
+    let line = contents.lines().nth(1).unwrap();
+    let mut columns = line.split(',');
+
+    (columns.next().unwrap().to_owned(), columns.next().unwrap().to_owned())
+}
+
+fn request(method: &str, params: Value) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": method,
+        "params": params,
+    })
+}
+
+fn call(request: Value) -> Value {
+    serde_json::from_str(&handle(&request.to_string())).unwrap()
+}
+
+#[test]
+fn test_run() {
+    let (input, output) = first_g1_mul_vector();
+    let response = call(request("eip1962_run", json!([input])));
+    assert_eq!(response["id"], json!(1));
+    assert_eq!(response["result"], json!(output));
+}
+
+#[test]
+fn test_estimate_gas() {
+    let (input, _) = first_g1_mul_vector();
+    let gas = API::estimate_gas(&hex::decode(&input[2..]).unwrap()).unwrap();
+    let response = call(request("eip1962_estimateGas", json!([input])));
+    assert_eq!(response["result"], json!(format!("0x{:x}", gas)));
+}
+
+#[test]
+fn test_execution_error() {
+    let (input, _) = first_g1_mul_vector();
+    let response = call(request("eip1962_run", json!([&input[..100]])));
+    assert_eq!(response["error"]["code"], json!(-32000));
+    assert_eq!(response["error"]["data"]["error"], json!("InputTooShort"));
+    assert_eq!(response["error"]["data"]["offset"], json!(2));
+}
+
+#[test]
+fn test_invalid_requests() {
+    let response = call(request("eth_call", json!(["0x01"])));
+    assert_eq!(response["error"]["code"], json!(-32601));
+
+    for params in [json!([]), json!(["01"]), json!(["0x012"]), json!([1]), json!("0x01")] {
+        let response = call(request("eip1962_run", params));
+        assert_eq!(response["error"]["code"], json!(-32602));
+    }
+
+    let response: Value = serde_json::from_str(&handle("{")).unwrap();
+    assert_eq!(response["error"]["code"], json!(-32700));
+
+    let response = call(json!({"jsonrpc": "1.0", "id": 1, "method": "eip1962_run", "params": ["0x"]}));
+    assert_eq!(response["error"]["code"], json!(-32600));
+}
+
+#[test]
+fn test_batch() {
+    let (input, output) = first_g1_mul_vector();
+    let mut second = request("eip1962_run", json!(["0x"]));
+    second["id"] = json!("second");
+    let response = call(json!([request("eip1962_run", json!([input])), second]));
+    let responses = response.as_array().unwrap();
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0]["result"], json!(output));
+    assert_eq!(responses[1]["id"], json!("second"));
+    assert_eq!(responses[1]["error"]["code"], json!(-32000));
+}
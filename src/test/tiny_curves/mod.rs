@@ -0,0 +1,358 @@
+// BLS12 curves over tiny prime fields (single limb moduli), small enough
+// to check group orders and pairing values by brute force
+
+use crate::public_interface::constants::*;
+use crate::public_interface::sane_limits::*;
+
+use crate::test::g1_ops::{call_g1_engine_add, call_g1_engine_mul};
+use crate::test::g2_ops::call_g2_engine_mul;
+use crate::test::pairings::call_pairing_engine;
+
+struct TinyBls12 {
+    x: u64,
+    x_is_negative: bool,
+    p: u64,
+    r: u64,
+    b: u64,
+    fp2_non_residue: u64,
+    fp6_non_residue: (u64, u64),
+    is_d_type: bool,
+    g1: (u64, u64),
+    g2: ((u64, u64), (u64, u64)),
+}
+
+// p = (x-1)^2 * (x^4 - x^2 + 1) / 3 + x, r = x^4 - x^2 + 1
+const TINY_CURVES: [TinyBls12; 3] = [
+    // 6 bit
+    TinyBls12 {
+        x: 2, x_is_negative: true, p: 37, r: 13, b: 3,
+        fp2_non_residue: 2, fp6_non_residue: (1, 3), is_d_type: false,
+        g1: (15, 14), g2: ((20, 0), (17, 34))
+    },
+    // 13 bit
+    TinyBls12 {
+        x: 5, x_is_negative: true, p: 7207, r: 601, b: 1,
+        fp2_non_residue: 7206, fp6_non_residue: (1, 3), is_d_type: false,
+        g1: (2587, 1501), g2: ((216, 5026), (4249, 632))
+    },
+    // 30 bit
+    TinyBls12 {
+        x: 38, x_is_negative: true, p: 1056432313, r: 2083693, b: 4,
+        fp2_non_residue: 5, fp6_non_residue: (1, 5), is_d_type: true,
+        g1: (102334308, 1010452228), g2: ((43909707, 279154640), (431508210, 516146026))
+    },
+];
+
+fn byte_len(value: u64) -> usize {
+    ((64 - value.leading_zeros() as usize) + 7) / 8
+}
+
+fn encode(value: u64, len: usize) -> Vec<u8> {
+    value.to_be_bytes()[(8 - len)..].to_vec()
+}
+
+fn mul_mod(a: u64, b: u64, p: u64) -> u64 {
+    ((a as u128 * b as u128) % (p as u128)) as u64
+}
+
+fn pow_mod(base: u64, mut exp: u64, p: u64) -> u64 {
+    let mut result = 1u64;
+    let mut base = base % p;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, p);
+        }
+        base = mul_mod(base, base, p);
+        exp >>= 1;
+    }
+
+    result
+}
+
+// affine addition on y^2 = x^3 + b, None is the point at infinity
+fn reference_add(a: Option<(u64, u64)>, b: Option<(u64, u64)>, p: u64) -> Option<(u64, u64)> {
+    let (x1, y1) = match a { Some(a) => a, None => return b };
+    let (x2, y2) = match b { Some(b) => b, None => return a };
+    let lambda = if x1 == x2 {
+        if (y1 + y2) % p == 0 {
+            return None;
+        }
+        mul_mod(mul_mod(3, mul_mod(x1, x1, p), p), pow_mod(2 * y1 % p, p - 2, p), p)
+    } else {
+        mul_mod((y2 + p - y1) % p, pow_mod((x2 + p - x1) % p, p - 2, p), p)
+    };
+    let x3 = (mul_mod(lambda, lambda, p) + 2 * p - x1 - x2) % p;
+    let y3 = (mul_mod(lambda, (x1 + p - x3) % p, p) + p - y1) % p;
+
+    Some((x3, y3))
+}
+
+impl TinyBls12 {
+    fn modulus_len(&self) -> usize {
+        byte_len(self.p)
+    }
+
+    fn group_len(&self) -> usize {
+        byte_len(self.r)
+    }
+
+    fn trace(&self) -> i64 {
+        if self.x_is_negative { 1 - self.x as i64 } else { 1 + self.x as i64 }
+    }
+
+    fn encode_fp(&self, value: u64) -> Vec<u8> {
+        encode(value, self.modulus_len())
+    }
+
+    fn encode_scalar(&self, value: u64) -> Vec<u8> {
+        encode(value, self.group_len())
+    }
+
+    fn encode_g1(&self, point: Option<(u64, u64)>) -> Vec<u8> {
+        let (x, y) = point.unwrap_or((0, 0));
+        let mut encoding = self.encode_fp(x);
+        encoding.extend(self.encode_fp(y));
+
+        encoding
+    }
+
+    fn encode_g2(&self) -> Vec<u8> {
+        let ((x0, x1), (y0, y1)) = self.g2;
+        let mut encoding = vec![];
+        for c in [x0, x1, y0, y1].iter() {
+            encoding.extend(self.encode_fp(*c));
+        }
+
+        encoding
+    }
+
+    fn g1_curve_calldata(&self) -> Vec<u8> {
+        let mut calldata = vec![self.modulus_len() as u8];
+        calldata.extend(self.encode_fp(self.p));
+        calldata.extend(self.encode_fp(0));
+        calldata.extend(self.encode_fp(self.b));
+        calldata.push(self.group_len() as u8);
+        calldata.extend(self.encode_scalar(self.r));
+
+        calldata
+    }
+
+    fn g2_curve_calldata(&self) -> Vec<u8> {
+        let (xi_0, xi_1) = self.fp6_non_residue;
+        // b' = b/xi for D twist and b*xi for M twist
+        let b_twist = if self.is_d_type {
+            let norm = (mul_mod(xi_0, xi_0, self.p) + self.p - mul_mod(self.fp2_non_residue, mul_mod(xi_1, xi_1, self.p), self.p)) % self.p;
+            let norm_inv = pow_mod(norm, self.p - 2, self.p);
+            (mul_mod(mul_mod(self.b, xi_0, self.p), norm_inv, self.p), mul_mod(mul_mod(self.b, self.p - xi_1, self.p), norm_inv, self.p))
+        } else {
+            (mul_mod(self.b, xi_0, self.p), mul_mod(self.b, xi_1, self.p))
+        };
+
+        let mut calldata = vec![self.modulus_len() as u8];
+        calldata.extend(self.encode_fp(self.p));
+        calldata.push(EXTENSION_DEGREE_2);
+        calldata.extend(self.encode_fp(self.fp2_non_residue));
+        calldata.extend(self.encode_fp(0));
+        calldata.extend(self.encode_fp(0));
+        calldata.extend(self.encode_fp(b_twist.0));
+        calldata.extend(self.encode_fp(b_twist.1));
+        calldata.push(self.group_len() as u8);
+        calldata.extend(self.encode_scalar(self.r));
+
+        calldata
+    }
+
+    fn pairing_calldata(&self, pairs: &[(Vec<u8>, Vec<u8>)]) -> Vec<u8> {
+        let mut calldata = vec![BLS12, self.modulus_len() as u8];
+        calldata.extend(self.encode_fp(self.p));
+        calldata.extend(self.encode_fp(0));
+        calldata.extend(self.encode_fp(self.b));
+        calldata.push(self.group_len() as u8);
+        calldata.extend(self.encode_scalar(self.r));
+        calldata.extend(self.encode_fp(self.fp2_non_residue));
+        calldata.extend(self.encode_fp(self.fp6_non_residue.0));
+        calldata.extend(self.encode_fp(self.fp6_non_residue.1));
+        calldata.push(if self.is_d_type { TWIST_TYPE_D } else { TWIST_TYPE_M });
+        calldata.push(byte_len(self.x) as u8);
+        calldata.extend(encode(self.x, byte_len(self.x)));
+        calldata.push(if self.x_is_negative { SIGN_MINUS } else { SIGN_PLUS });
+        calldata.push(pairs.len() as u8);
+        for (g1, g2) in pairs.iter() {
+            calldata.push(BOOLEAN_TRUE);
+            calldata.extend_from_slice(g1);
+            calldata.push(BOOLEAN_TRUE);
+            calldata.extend_from_slice(g2);
+        }
+
+        calldata
+    }
+
+    fn g1_mul(&self, point: Option<(u64, u64)>, scalar: u64) -> Vec<u8> {
+        let mut calldata = self.g1_curve_calldata();
+        calldata.extend(self.encode_g1(point));
+        calldata.extend(self.encode_scalar(scalar));
+
+        call_g1_engine_mul(&calldata).expect("multiplication must work")
+    }
+
+    fn g2_mul(&self, scalar: u64) -> Vec<u8> {
+        let mut calldata = self.g2_curve_calldata();
+        calldata.extend(self.encode_g2());
+        calldata.extend(self.encode_scalar(scalar));
+
+        call_g2_engine_mul(&calldata).expect("multiplication must work")
+    }
+
+    fn pair(&self, pairs: &[(Vec<u8>, Vec<u8>)]) -> bool {
+        let result = call_pairing_engine(&self.pairing_calldata(pairs)).expect("pairing must work");
+        assert_eq!(result.len(), 1);
+
+        result[0] == 1u8
+    }
+
+    fn count_points_by_brute_force(&self) -> u64 {
+        let p = self.p;
+        // point at infinity
+        let mut count = 1u64;
+        for x in 0..p {
+            let rhs = (mul_mod(mul_mod(x, x, p), x, p) + self.b) % p;
+            if rhs == 0 {
+                count += 1;
+            } else if pow_mod(rhs, (p - 1) / 2, p) == 1 {
+                count += 2;
+            }
+        }
+
+        count
+    }
+}
+
+#[test]
+fn test_tiny_fields_use_smallest_representation() {
+    for curve in TINY_CURVES.iter() {
+        assert!(curve.p < 1u64 << 31);
+        assert_eq!(crate::test::calculate_num_limbs(&num_bigint::BigUint::from(curve.p)).unwrap(), NUM_LIMBS_MIN);
+    }
+}
+
+#[test]
+fn test_tiny_g1_group_order_by_brute_force() {
+    for curve in TINY_CURVES.iter().filter(|c| c.p < 1 << 16) {
+        let num_points = curve.count_points_by_brute_force();
+        assert_eq!(num_points as i64, curve.p as i64 + 1 - curve.trace());
+        assert_eq!(num_points % curve.r, 0);
+    }
+
+    for curve in TINY_CURVES.iter() {
+        let generator = Some(curve.g1);
+        assert_eq!(curve.g1_mul(generator, curve.r), curve.encode_g1(None));
+
+        // walk over the whole subgroup (or its beginning for larger curves) and compare
+        // with the reference affine arithmetic
+        let steps = std::cmp::min(curve.r, 1000);
+        let mut expected = None;
+        for k in 1..steps {
+            expected = reference_add(expected, generator, curve.p);
+            assert_eq!(curve.g1_mul(generator, k), curve.encode_g1(expected), "{}*G mismatch for p = {}", k, curve.p);
+
+            let mut calldata = curve.g1_curve_calldata();
+            calldata.extend(curve.encode_g1(expected));
+            calldata.extend(curve.encode_g1(generator));
+            let next = call_g1_engine_add(&calldata).expect("addition must work");
+            assert_eq!(next, curve.encode_g1(reference_add(expected, generator, curve.p)));
+        }
+    }
+}
+
+#[test]
+fn test_tiny_g2_subgroup_order() {
+    for curve in TINY_CURVES.iter() {
+        let zero = vec![0u8; 4 * curve.modulus_len()];
+        assert_ne!(curve.g2_mul(1), zero);
+        assert_ne!(curve.g2_mul(curve.r - 1), zero);
+        assert_eq!(curve.g2_mul(curve.r), zero);
+    }
+}
+
+#[test]
+fn test_tiny_pairings_by_brute_force() {
+    let curve = &TINY_CURVES[0];
+    let minus_g1 = Some((curve.g1.0, curve.p - curve.g1.1));
+    let g2 = curve.encode_g2();
+
+    // e(a*P, Q) != 1 for every non-trivial a, and e(a*P, Q) * e(-P, a*Q) == 1
+    for a in 1..curve.r {
+        let a_g1 = curve.g1_mul(Some(curve.g1), a);
+        assert!(!curve.pair(&[(a_g1.clone(), g2.clone())]));
+
+        let a_g2 = curve.g2_mul(a);
+        assert!(curve.pair(&[(a_g1, g2.clone()), (curve.encode_g1(minus_g1), a_g2)]));
+    }
+
+    // e(a*P, b*Q) * e(-(a*b)*P, Q) == 1 for all a, b
+    for a in 1..curve.r {
+        for b in 1..curve.r {
+            let a_g1 = curve.g1_mul(Some(curve.g1), a);
+            let b_g2 = curve.g2_mul(b);
+            let minus_ab_g1 = curve.g1_mul(minus_g1, (a * b) % curve.r);
+            assert!(curve.pair(&[(a_g1, b_g2), (minus_ab_g1, g2.clone())]), "bilinearity failed for a = {}, b = {}", a, b);
+        }
+    }
+}
+
+#[test]
+fn test_tiny_pairings_bilinearity() {
+    for curve in TINY_CURVES.iter() {
+        let minus_g1 = Some((curve.g1.0, curve.p - curve.g1.1));
+        let (a, b) = (curve.r / 3, curve.r / 5 + 1);
+
+        let a_g1 = curve.g1_mul(Some(curve.g1), a);
+        let b_g2 = curve.g2_mul(b);
+        let minus_ab_g1 = curve.g1_mul(minus_g1, mul_mod(a, b, curve.r));
+
+        assert!(curve.pair(&[(a_g1.clone(), b_g2.clone()), (minus_ab_g1, curve.encode_g2())]));
+        assert!(!curve.pair(&[(a_g1, b_g2)]));
+    }
+}
+
+#[test]
+fn test_sane_lower_bounds() {
+    // y^2 = x^3 + 1 has a point (0, 1) over any field
+    let encode_add = |modulus_len: usize, modulus: u64, group_len: usize| {
+        let mut calldata = vec![modulus_len as u8];
+        calldata.extend(encode(modulus, modulus_len));
+        calldata.extend(encode(0, modulus_len));
+        calldata.extend(encode(1, modulus_len));
+        calldata.push(group_len as u8);
+        calldata.extend(encode(0xfb, group_len));
+        for _ in 0..2 {
+            calldata.extend(encode(0, modulus_len));
+            calldata.extend(encode(1, modulus_len));
+        }
+
+        call_g1_engine_add(&calldata)
+    };
+
+    assert!(encode_add(MIN_MODULUS_BYTE_LEN, MIN_MODULUS, MIN_GROUP_BYTE_LEN).is_ok());
+    assert!(encode_add(MIN_MODULUS_BYTE_LEN, MIN_MODULUS - 2, MIN_GROUP_BYTE_LEN).is_err());
+    assert!(encode_add(MIN_MODULUS_BYTE_LEN, MIN_MODULUS, MIN_GROUP_BYTE_LEN - 1).is_err());
+
+    let mut calldata = vec![(MIN_MODULUS_BYTE_LEN - 1) as u8, 0, 0];
+    calldata.extend(vec![1u8, 0xff]);
+    assert!(call_g1_engine_add(&calldata).is_err());
+
+    // zero length encoding of the loop parameter
+    let curve = &TINY_CURVES[0];
+    let pair = (curve.encode_g1(Some(curve.g1)), curve.encode_g2());
+    let calldata = curve.pairing_calldata(&[pair]);
+    let modulus_len = curve.modulus_len();
+    // x, sign of x, number of pairs and a single pair follow the length of x
+    let tail_len = 1 + 1 + 1 + (1 + 2 * modulus_len) + (1 + 4 * modulus_len);
+    let x_length_position = calldata.len() - tail_len - 1;
+    assert_eq!(calldata[x_length_position], MIN_LOOP_PARAMETERS_BYTE_LEN as u8);
+    let mut malformed = calldata[..x_length_position].to_vec();
+    malformed.push((MIN_LOOP_PARAMETERS_BYTE_LEN - 1) as u8);
+    malformed.extend_from_slice(&calldata[(x_length_position + 2)..]);
+    assert!(call_pairing_engine(&malformed).is_err());
+    assert!(call_pairing_engine(&calldata).is_ok());
+}
@@ -0,0 +1,273 @@
+//! Generators of random field elements and curve points for tests.
+//! Every function takes an explicit RNG, so seeding it (e.g. `XorShiftRng::from_seed`)
+//! makes a failing case reproducible.
+
+use rand::Rng;
+
+use crate::field::SizedPrimeField;
+use crate::fp::Fp;
+use crate::representation::ElementRepr;
+use crate::extension_towers::fp2::{Fp2, Extension2};
+use crate::extension_towers::fp3::{Fp3, Extension3};
+use crate::traits::{FieldElement, ZeroAndOne};
+use crate::weierstrass::{Group, CurveParameters};
+use crate::weierstrass::curve::{WeierstrassCurve, CurvePoint};
+use crate::square_root::{sqrt, sqrt_ext2};
+
+/// Number of random points tried before giving up on finding one outside of the
+/// prime order subgroup. A random point lands in the subgroup with probability 1/cofactor,
+/// so running out of attempts means the curve has (almost surely) no cofactor
+const MAX_ATTEMPTS_OUTSIDE_SUBGROUP: usize = 64;
+
+/// Field elements that can be sampled uniformly and have a square root available,
+/// that is enough to find points on curves over such fields
+pub trait RandomElement: FieldElement + ZeroAndOne {
+    fn random<R: Rng>(params: <Self as ZeroAndOne>::Params, rng: &mut R) -> Self;
+    fn square_root(&self) -> Option<Self>;
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E>> RandomElement for Fp<'a, E, F> {
+    fn random<R: Rng>(field: &'a F, rng: &mut R) -> Self {
+        random_fp(field, rng)
+    }
+
+    fn square_root(&self) -> Option<Self> {
+        sqrt(self)
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E>> RandomElement for Fp2<'a, E, F> {
+    fn random<R: Rng>(extension: &'a Extension2<'a, E, F>, rng: &mut R) -> Self {
+        random_fp2(extension, rng)
+    }
+
+    fn square_root(&self) -> Option<Self> {
+        sqrt_ext2(self)
+    }
+}
+
+/// Samples an integer uniformly in [0, bound) by rejection on bit-masked random limbs
+fn random_below<R: Rng>(bound: &[u64], rng: &mut R) -> Vec<u64> {
+    let num_bits = crate::representation::num_bits(bound);
+    assert!(num_bits > 0, "bound must be non-zero");
+    let num_limbs = ((num_bits + 63) / 64) as usize;
+    let top_bits = num_bits % 64;
+
+    loop {
+        let mut candidate: Vec<u64> = (0..num_limbs).map(|_| rng.gen()).collect();
+        if top_bits != 0 {
+            candidate[num_limbs - 1] &= (1u64 << top_bits) - 1;
+        }
+
+        let is_below = candidate.iter().rev()
+            .zip(bound[..num_limbs].iter().rev())
+            .find(|(c, b)| c != b)
+            .map(|(c, b)| c < b)
+            .unwrap_or(false);
+
+        if is_below {
+            return candidate;
+        }
+    }
+}
+
+pub fn random_fp<'a, E: ElementRepr, F: SizedPrimeField<Repr = E>, R: Rng>(field: &'a F, rng: &mut R) -> Fp<'a, E, F> {
+    let limbs = random_below(field.modulus().as_ref(), rng);
+    let mut repr = E::default();
+    repr.as_mut()[..limbs.len()].copy_from_slice(&limbs);
+
+    Fp::from_repr(field, repr).expect("is below the modulus")
+}
+
+pub fn random_fp2<'a, E: ElementRepr, F: SizedPrimeField<Repr = E>, R: Rng>(extension: &'a Extension2<'a, E, F>, rng: &mut R) -> Fp2<'a, E, F> {
+    let mut element = Fp2::zero(extension);
+    element.c0 = random_fp(extension.field, rng);
+    element.c1 = random_fp(extension.field, rng);
+
+    element
+}
+
+pub fn random_fp3<'a, E: ElementRepr, F: SizedPrimeField<Repr = E>, R: Rng>(extension: &'a Extension3<'a, E, F>, rng: &mut R) -> Fp3<'a, E, F> {
+    let mut element = Fp3::zero(extension);
+    element.c0 = random_fp(extension.field, rng);
+    element.c1 = random_fp(extension.field, rng);
+    element.c2 = random_fp(extension.field, rng);
+
+    element
+}
+
+/// Non-zero scalar below the given group order
+pub fn random_scalar<R: Rng>(order: &[u64], rng: &mut R) -> Vec<u64> {
+    loop {
+        let scalar = random_below(order, rng);
+        if scalar.iter().any(|&limb| limb != 0) {
+            return scalar;
+        }
+    }
+}
+
+/// Random non-identity multiple of the generator, so it lies in the same subgroup
+pub fn random_point<'a, C: CurveParameters, R: Rng>(generator: &CurvePoint<'a, C>, rng: &mut R) -> CurvePoint<'a, C> {
+    let scalar = random_scalar(generator.curve.subgroup_order_repr, rng);
+    let mut point = generator.mul(&scalar);
+    point.normalize();
+
+    point
+}
+
+/// Random affine point on the full curve group found by try-and-increment on x.
+/// Returns `None` if square roots are not available in the base field
+/// (only p = 3 mod 4 is supported for now)
+pub fn random_point_on_curve<'a, C: CurveParameters, R: Rng>(curve: &'a WeierstrassCurve<'a, C>, rng: &mut R) -> Option<CurvePoint<'a, C>>
+    where C::BaseFieldElement: RandomElement
+{
+    let one = C::BaseFieldElement::one(curve.params.params());
+
+    let mut x = C::BaseFieldElement::random(curve.params.params(), rng);
+    let mut probe = one.clone();
+    probe.double();
+    probe.double();
+    if probe.square_root().is_none() {
+        // 4 is always a square, so the field has no square root implementation
+        return None;
+    }
+
+    loop {
+        // y^2 = x^3 + a*x + b
+        let mut rhs = x.clone();
+        rhs.square();
+        rhs.add_assign(&curve.a);
+        rhs.mul_assign(&x);
+        rhs.add_assign(&curve.b);
+
+        if let Some(mut y) = rhs.square_root() {
+            if rng.gen::<bool>() {
+                y.negate();
+            }
+            // (0, 0) can not be on a curve since b is non-zero
+            return Some(CurvePoint::point_from_xy(curve, x, y));
+        }
+
+        x.add_assign(&one);
+    }
+}
+
+/// Random point of the prime order subgroup obtained by clearing the cofactor
+/// (or any multiple of it that keeps the subgroup, like an effective cofactor)
+pub fn random_point_in_subgroup<'a, C: CurveParameters, R: Rng>(
+    curve: &'a WeierstrassCurve<'a, C>,
+    cofactor: &[u64],
+    rng: &mut R
+) -> Option<CurvePoint<'a, C>>
+    where C::BaseFieldElement: RandomElement
+{
+    loop {
+        let point = random_point_on_curve(curve, rng)?;
+        let mut point = point.mul(cofactor);
+        if !point.is_zero() {
+            point.normalize();
+
+            return Some(point);
+        }
+    }
+}
+
+/// Random point on the curve that is deliberately NOT in the prime order subgroup.
+/// Returns `None` for curves without a cofactor
+pub fn random_point_outside_subgroup<'a, C: CurveParameters, R: Rng>(
+    curve: &'a WeierstrassCurve<'a, C>,
+    rng: &mut R
+) -> Option<CurvePoint<'a, C>>
+    where C::BaseFieldElement: RandomElement
+{
+    for _ in 0..MAX_ATTEMPTS_OUTSIDE_SUBGROUP {
+        let point = random_point_on_curve(curve, rng)?;
+        if !point.check_correct_subgroup() {
+            return Some(point);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::engines::bls12_381::*;
+    use crate::engines::bn254::*;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    fn rng() -> XorShiftRng {
+        XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15])
+    }
+
+    #[test]
+    fn test_random_elements_are_reproducible() {
+        let a = random_fp2(&BLS12_381_EXTENSION_2_FIELD, &mut rng());
+        let b = random_fp2(&BLS12_381_EXTENSION_2_FIELD, &mut rng());
+        assert!(a == b);
+        assert!(a.c0 != a.c1);
+
+        let a = random_point_on_curve(&BLS12_381_G1_CURVE, &mut rng()).unwrap();
+        let b = random_point_on_curve(&BLS12_381_G1_CURVE, &mut rng()).unwrap();
+        assert!(a.into_xy() == b.into_xy());
+    }
+
+    #[test]
+    fn test_random_scalar_is_below_order() {
+        let rng = &mut rng();
+        let order = [0x1234u64, 0x1];
+        for _ in 0..1000 {
+            let scalar = random_scalar(&order, rng);
+            assert!(scalar[1] < 1 || (scalar[1] == 1 && scalar[0] < 0x1234));
+            assert!(scalar[0] != 0 || scalar[1] != 0);
+        }
+    }
+
+    #[test]
+    fn test_random_g1_points() {
+        let rng = &mut rng();
+        for _ in 0..16 {
+            let p = random_point(&BLS12_381_G1_GENERATOR, rng);
+            assert!(p.is_on_curve());
+            assert!(p.check_correct_subgroup());
+
+            let p = random_point_in_subgroup(&BLS12_381_G1_CURVE, &BLS12_381_G1_MAPPING_H_EFF, rng).unwrap();
+            assert!(p.is_on_curve());
+            assert!(p.check_correct_subgroup());
+
+            let p = random_point_outside_subgroup(&BLS12_381_G1_CURVE, rng).unwrap();
+            assert!(p.is_on_curve());
+            assert!(!p.check_correct_subgroup());
+        }
+    }
+
+    #[test]
+    fn test_random_g2_points() {
+        let rng = &mut rng();
+        for _ in 0..4 {
+            let p = random_point(&BLS12_381_G2_GENERATOR, rng);
+            assert!(p.is_on_curve());
+            assert!(p.check_correct_subgroup());
+
+            let p = random_point_in_subgroup(&BLS12_381_G2_CURVE, &BLS12_381_G2_MAPPING_H_EFF, rng).unwrap();
+            assert!(p.is_on_curve());
+            assert!(p.check_correct_subgroup());
+
+            let p = random_point_outside_subgroup(&BLS12_381_G2_CURVE, rng).unwrap();
+            assert!(p.is_on_curve());
+            assert!(!p.check_correct_subgroup());
+        }
+    }
+
+    #[test]
+    fn test_no_points_outside_subgroup_without_cofactor() {
+        let rng = &mut rng();
+        let p = random_point_on_curve(&*BN254_G1_CURVE, rng).unwrap();
+        assert!(p.is_on_curve());
+        assert!(p.check_correct_subgroup());
+
+        assert!(random_point_outside_subgroup(&*BN254_G1_CURVE, rng).is_none());
+    }
+}
@@ -1,4 +1,4 @@
-use std::fmt;
+use core::fmt;
 
 /// This trait represents an element of a field.
 pub trait FieldElement:
@@ -31,6 +31,15 @@ pub trait FieldElement:
     /// Multiplies another element by this element.
     fn mul_assign(&mut self, other: &Self);
 
+    /// Multiplies every element of `a` by the element of `b` at the same position. Products
+    /// are independent, so implementations may compute several of them at once
+    fn batch_mul_assign(a: &mut [Self], b: &[Self]) {
+        debug_assert_eq!(a.len(), b.len());
+        for (a, b) in a.iter_mut().zip(b.iter()) {
+            a.mul_assign(b);
+        }
+    }
+
     /// Computes the multiplicative inverse of this element, if nonzero.
     fn inverse(&self) -> Option<Self>;
 
@@ -52,6 +61,45 @@ pub trait ZeroAndOne {
     // fn get_params(&self) -> Self::Params;
 }
 
+/// Selection between two values that doesn't branch on the condition, so it can be
+/// used with secret dependent choices
+pub trait ConditionalSelect: Sized {
+    /// Returns `b` if `choice` is set and `a` otherwise
+    fn conditional_select(a: &Self, b: &Self, choice: bool) -> Self;
+
+    /// Replaces this value with `other` if `choice` is set
+    fn conditional_assign(&mut self, other: &Self, choice: bool) {
+        *self = Self::conditional_select(self, other, choice);
+    }
+
+    /// Swaps `a` and `b` if `choice` is set
+    fn conditional_swap(a: &mut Self, b: &mut Self, choice: bool) {
+        let new_a = Self::conditional_select(a, b, choice);
+        *b = Self::conditional_select(b, a, choice);
+        *a = new_a;
+    }
+}
+
+/// Clearing of secret intermediates. With the `zeroize` feature the value is overwritten with zeroes
+/// in a way that is not optimized out, otherwise this is a no-op
+pub trait ZeroizeSecret {
+    fn zeroize_secret(&mut self);
+}
+
+#[cfg(feature = "zeroize")]
+impl<T: zeroize::Zeroize + ?Sized> ZeroizeSecret for T {
+    #[inline(always)]
+    fn zeroize_secret(&mut self) {
+        self.zeroize();
+    }
+}
+
+#[cfg(not(feature = "zeroize"))]
+impl<T: ?Sized> ZeroizeSecret for T {
+    #[inline(always)]
+    fn zeroize_secret(&mut self) {}
+}
+
 pub trait FieldExtension {
     const EXTENSION_DEGREE: usize;
 
@@ -0,0 +1,51 @@
+//! JS bindings for `wasm32-unknown-unknown`, e.g. for light clients that want to check
+//! precompile outputs in a browser. Build with
+//!
+//! `wasm-pack build --target web -- --no-default-features --features "wasm eip_2537 eip_196 mappings"`
+//!
+//! Inputs and outputs are `Uint8Array`, errors are thrown as strings with the same
+//! description as `ApiError` would give.
+
+use wasm_bindgen::prelude::*;
+
+use crate::errors::ApiError;
+use crate::public_interface::API;
+
+fn into_js_error(error: ApiError) -> JsValue {
+    JsValue::from_str(&error.to_string())
+}
+
+/// Same encoding as `API::run`: operation byte followed by the operation input
+#[wasm_bindgen]
+pub fn run(input: &[u8]) -> Result<Vec<u8>, JsValue> {
+    API::run(input).map_err(into_js_error)
+}
+
+/// Gas for the same input as `run`, returned as `BigInt`
+#[cfg(feature = "gas_metering")]
+#[wasm_bindgen(js_name = estimateGas)]
+pub fn estimate_gas(input: &[u8]) -> Result<u64, JsValue> {
+    API::estimate_gas(input).map_err(into_js_error)
+}
+
+/// EIP-2537 precompile, `operation` is one of the BLS12-381 precompile raw values
+#[cfg(feature = "eip_2537")]
+#[wasm_bindgen(js_name = eip2537PerformOperation)]
+pub fn eip2537_perform_operation(operation: u8, input: &[u8]) -> Result<Vec<u8>, JsValue> {
+    use crate::public_interface::eip2537::{Eip2537OperationType, perform_operation};
+
+    let operation = Eip2537OperationType::from_u8(operation).ok_or_else(|| JsValue::from_str("Unknown operation type"))?;
+
+    perform_operation(operation, input).map_err(into_js_error)
+}
+
+/// EIP-196/197 precompile, `operation` is one of the BN254 precompile raw values
+#[cfg(feature = "eip_196")]
+#[wasm_bindgen(js_name = eip196PerformOperation)]
+pub fn eip196_perform_operation(operation: u8, input: &[u8]) -> Result<Vec<u8>, JsValue> {
+    use crate::public_interface::eip196::{Eip196OperationType, perform_operation};
+
+    let operation = Eip196OperationType::from_u8(operation).ok_or_else(|| JsValue::from_str("Unknown operation type"))?;
+
+    perform_operation(operation, input).map_err(into_js_error)
+}
@@ -1,14 +1,19 @@
-use crate::traits::{FieldElement, BitIterator};
-use super::{CurveType, Group};
+use crate::traits::{FieldElement, BitIterator, ConditionalSelect, ZeroizeSecret};
+use super::{CurveType, CoordinateSystem, PointOperation, Group};
 use super::CurveParameters;
+use super::glv::GlvEndomorphism;
 use crate::traits::ZeroAndOne;
+use crate::representation::constant_time_eq;
+use crate::execution_context::ExecutionContext;
+use crate::alloc_prelude::*;
 
 pub struct WeierstrassCurve<'a, C: CurveParameters> {
     pub(crate) a: C::BaseFieldElement,
     pub(crate) b: C::BaseFieldElement,
     pub(crate) curve_type: CurveType,
     pub(crate) subgroup_order_repr: &'a [u64],
-    pub(crate) params: &'a C
+    pub(crate) params: &'a C,
+    pub(crate) endomorphism: Option<GlvEndomorphism<C>>
 }
 
 impl<'a, C: CurveParameters> Clone for WeierstrassCurve<'a, C> {
@@ -18,7 +23,8 @@ impl<'a, C: CurveParameters> Clone for WeierstrassCurve<'a, C> {
             b: self.b.clone(),
             curve_type: self.curve_type,
             subgroup_order_repr: self.subgroup_order_repr,
-            params: self.params
+            params: self.params,
+            endomorphism: self.endomorphism.clone()
         }
     }
 }
@@ -36,6 +42,14 @@ impl<'a, C: CurveParameters> WeierstrassCurve<'a, C> {
                 return Err(());
             }
             curve_type = CurveType::AIsZero;
+        } else {
+            let mut minus_three = C::BaseFieldElement::one(params.params());
+            minus_three.double();
+            minus_three.add_assign(&C::BaseFieldElement::one(params.params()));
+            minus_three.negate();
+            if a == minus_three {
+                curve_type = CurveType::AIsMinus3;
+            }
         }
         if b.is_zero() {
             return Err(());
@@ -46,9 +60,45 @@ impl<'a, C: CurveParameters> WeierstrassCurve<'a, C> {
             b: b,
             curve_type: curve_type,
             subgroup_order_repr: subgroup_order,
-            params: params
+            params: params,
+            endomorphism: None
+        })
+    }
+
+    /// Curve y^2 = x^3 + a*x as used by pairing families with quartic twists. It has a point (0, 0)
+    /// of order 2 that is indistinguishable from the encoding of the point at infinity, so
+    /// it's only suitable when points are checked to be in the (odd order) subgroup
+    pub(crate) fn new_with_zero_b(
+        subgroup_order: &'a [u64],
+        a: C::BaseFieldElement,
+        params: &'a C
+    ) -> Result<Self, ()> {
+        if a.is_zero() {
+            return Err(());
+        }
+
+        let b = C::BaseFieldElement::zero(params.params());
+
+        Ok(Self {
+            a: a,
+            b: b,
+            curve_type: CurveType::Generic,
+            subgroup_order_repr: subgroup_order,
+            params: params,
+            endomorphism: None
         })
     }
+
+    /// Right hand side of the curve equation, x^3 + a*x + b
+    pub(crate) fn evaluate_rhs(&self, x: &C::BaseFieldElement) -> C::BaseFieldElement {
+        let mut rhs = x.clone();
+        rhs.square();
+        rhs.add_assign(&self.a);
+        rhs.mul_assign(&x);
+        rhs.add_assign(&self.b);
+
+        rhs
+    }
 }
 
 pub struct CurvePoint<'a, C: CurveParameters> {
@@ -70,7 +120,13 @@ impl<'a, C: CurveParameters> Clone for CurvePoint<'a, C> {
     }
 }
 
+/// Converts all points to affine coordinates with a single field inversion (Montgomery's trick).
+/// Points at infinity and already normalized points are left as is, all the points are left as
+/// is if the product of `z` coordinates is not invertible
 pub fn batch_normalize<'a, C: CurveParameters>(v: &mut [CurvePoint<'a, C>]) {
+    if v.is_empty() {
+        return;
+    }
     let mut prod = Vec::with_capacity(v.len());
     let one = C::BaseFieldElement::one(v[0].curve.params.params());
     let mut tmp = one.clone();
@@ -82,11 +138,11 @@ pub fn batch_normalize<'a, C: CurveParameters>(v: &mut [CurvePoint<'a, C>]) {
         prod.push(tmp.clone());
     }
 
-    if tmp.is_zero() {
-        return;
-    }
-
-    tmp = tmp.inverse().unwrap(); // Guaranteed to be nonzero.
+    // nothing is changed if the product is not invertible, e.g. for a modulus that is not prime
+    tmp = match tmp.inverse() {
+        Some(inverse) => inverse,
+        None => return
+    };
 
     // Second pass: iterate backwards to compute inverses
     for (g, s) in v.iter_mut()
@@ -105,19 +161,303 @@ pub fn batch_normalize<'a, C: CurveParameters>(v: &mut [CurvePoint<'a, C>]) {
         tmp = newtmp;
     }
 
-    // Perform affine transformations
-    for g in v.iter_mut()
+    // Perform affine transformations, products for different points are independent
+    // and go through the batched multiplication
+    let mut z: Vec<_> = v.iter().filter(|g| !g.is_normalized()).map(|g| g.z.clone()).collect(); // 1/z
+    let mut zz = z.clone();
+    C::BaseFieldElement::batch_mul_assign(&mut zz, &z); // 1/z^2
+    let mut x: Vec<_> = v.iter().filter(|g| !g.is_normalized()).map(|g| g.x.clone()).collect();
+    C::BaseFieldElement::batch_mul_assign(&mut x, &zz); // x/z^2
+    C::BaseFieldElement::batch_mul_assign(&mut z, &zz); // 1/z^3
+    let mut y: Vec<_> = v.iter().filter(|g| !g.is_normalized()).map(|g| g.y.clone()).collect();
+    C::BaseFieldElement::batch_mul_assign(&mut y, &z); // y/z^3
+
+    for ((g, x), y) in v.iter_mut()
                 .filter(|g| !g.is_normalized())
+                .zip(x.into_iter())
+                .zip(y.into_iter())
     {
-        let mut z = g.z.clone(); // 1/z
-        z.square(); // 1/z^2
-        g.x.mul_assign(&z); // x/z^2
-        z.mul_assign(&g.z); // 1/z^3
-        g.y.mul_assign(&z); // y/z^3
+        g.x = x;
+        g.y = y;
         g.z = one.clone(); // z = 1
     }
 }
 
+/// Sum of the points by a tree reduction. Every level normalizes the second half of
+/// the partial sums with a single inversion and adds it to the first half with
+/// the batched mixed addition, an odd point is carried to the next level
+pub fn sum_points<'a, C: CurveParameters>(curve: &'a WeierstrassCurve<C>, mut points: Vec<CurvePoint<'a, C>>, ctx: &ExecutionContext) -> CurvePoint<'a, C> {
+    while points.len() > 1 {
+        if !ctx.charge(points.len() / 2) {
+            return CurvePoint::zero(curve);
+        }
+
+        let odd = if points.len() % 2 == 1 {
+            points.pop()
+        } else {
+            None
+        };
+        let half = points.len() / 2;
+        let mut others = points.split_off(half);
+        batch_normalize(&mut others);
+        CurvePoint::batch_add_assign_mixed(&mut points, &others);
+        points.extend(odd);
+    }
+
+    points.pop().unwrap_or_else(|| CurvePoint::zero(curve))
+}
+
+/// Number of teeth of the comb, so the table of a fixed base has `2^FIXED_BASE_COMB_TEETH` points
+const FIXED_BASE_COMB_TEETH: usize = 6;
+
+/// Precomputed table of the Lim-Lee comb for a fixed base P. Scalars of up to `FIXED_BASE_COMB_TEETH * spacing` bits
+/// are split into `FIXED_BASE_COMB_TEETH` rows of `spacing` bits, and the entry for a mask of the teeth
+/// is the sum of 2^(i*spacing)*P over the teeth i set in the mask. Multiplication then takes `spacing`
+/// doublings and at most as many mixed additions, so building the table pays off for the generator
+/// and other bases that are multiplied many times
+pub struct FixedBaseTable<'a, C: CurveParameters> {
+    base: CurvePoint<'a, C>,
+    table: Vec<CurvePoint<'a, C>>,
+    spacing: usize,
+}
+
+impl<'a, C: CurveParameters> FixedBaseTable<'a, C> {
+    /// Table for scalars of up to `num_bits` bits, longer scalars are multiplied as for any other base
+    pub fn new(base: &CurvePoint<'a, C>, num_bits: usize) -> Self {
+        let spacing = core::cmp::max((num_bits + FIXED_BASE_COMB_TEETH - 1) / FIXED_BASE_COMB_TEETH, 1);
+
+        // 2^(i*spacing)*P for every tooth
+        let mut teeth = Vec::with_capacity(FIXED_BASE_COMB_TEETH);
+        let mut tooth = base.clone();
+        for i in 0..FIXED_BASE_COMB_TEETH {
+            if i != 0 {
+                for _ in 0..spacing {
+                    tooth.double();
+                }
+            }
+            teeth.push(tooth.clone());
+        }
+        batch_normalize(&mut teeth);
+
+        // every entry is the entry without the highest tooth of the mask plus this tooth
+        let mut table = Vec::with_capacity(1 << FIXED_BASE_COMB_TEETH);
+        table.push(CurvePoint::zero(base.curve));
+        for mask in 1usize..(1 << FIXED_BASE_COMB_TEETH) {
+            let highest = (usize::BITS - 1 - mask.leading_zeros()) as usize;
+            let mut entry = table[mask ^ (1 << highest)].clone();
+            entry.add_assign_mixed(&teeth[highest]);
+            table.push(entry);
+        }
+        batch_normalize(&mut table);
+
+        Self {
+            base: base.clone(),
+            table: table,
+            spacing: spacing
+        }
+    }
+
+    pub fn mul<S: AsRef<[u64]>>(&self, exp: S) -> CurvePoint<'a, C> {
+        // table lookups depend on the scalar
+        if CoordinateSystem::for_operation(PointOperation::FixedBaseMul) == CoordinateSystem::Projective {
+            return self.base.mul_constant_time_impl(exp);
+        }
+
+        self.sum_of_multiples(&[exp], &ExecutionContext::default())
+    }
+
+    /// Sum of the multiples of the base by all the scalars, doublings are shared between them
+    pub fn sum_of_multiples<S: AsRef<[u64]>>(&self, scalars: &[S], ctx: &ExecutionContext) -> CurvePoint<'a, C> {
+        let max_bits = (self.spacing * FIXED_BASE_COMB_TEETH) as u32;
+        let (scalars, long_scalars): (Vec<&[u64]>, Vec<&[u64]>) = scalars.iter()
+            .map(|s| s.as_ref())
+            .partition(|s| crate::representation::num_bits(s) <= max_bits);
+
+        let mut res = CurvePoint::zero(self.base.curve);
+        for row in (0..self.spacing).rev() {
+            if !ctx.charge(scalars.len() + 1) {
+                return CurvePoint::zero(self.base.curve);
+            }
+
+            res.double();
+            for exp in scalars.iter() {
+                res.add_assign_mixed(&self.table[self.mask(exp, row)]);
+            }
+        }
+
+        for exp in long_scalars.into_iter() {
+            res.add_assign(&self.base.mul(exp));
+        }
+
+        res
+    }
+
+    /// Bits of the scalar at the position `row` of every tooth
+    fn mask(&self, exp: &[u64], row: usize) -> usize {
+        let mut mask = 0;
+        for tooth in 0..FIXED_BASE_COMB_TEETH {
+            let bit = tooth * self.spacing + row;
+            let limb = bit / 64;
+            if limb < exp.len() && (exp[limb] >> (bit % 64)) & 1 == 1 {
+                mask |= 1 << tooth;
+            }
+        }
+
+        mask
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<'a, C: CurveParameters> zeroize::Zeroize for CurvePoint<'a, C> {
+    fn zeroize(&mut self) {
+        self.x.zeroize_secret();
+        self.y.zeroize_secret();
+        self.z.zeroize_secret();
+    }
+}
+
+impl<'a, C: CurveParameters> ConditionalSelect for CurvePoint<'a, C> {
+    #[inline(always)]
+    fn conditional_select(a: &Self, b: &Self, choice: bool) -> Self {
+        Self {
+            curve: a.curve,
+            x: C::BaseFieldElement::conditional_select(&a.x, &b.x, choice),
+            y: C::BaseFieldElement::conditional_select(&a.y, &b.y, choice),
+            z: C::BaseFieldElement::conditional_select(&a.z, &b.z, choice),
+        }
+    }
+}
+
+/// Point in homogeneous projective coordinates (X/Z, Y/Z) of `CoordinateSystem::Projective`,
+/// the point at infinity is (0, 1, 0). Complete addition formulas of Renes, Costello and Batina
+/// (https://eprint.iacr.org/2015/1060) have no special cases there, so they don't branch
+struct ProjectivePoint<C: CurveParameters> {
+    x: C::BaseFieldElement,
+    y: C::BaseFieldElement,
+    z: C::BaseFieldElement
+}
+
+impl<C: CurveParameters> Clone for ProjectivePoint<C> {
+    fn clone(&self) -> Self {
+        Self {
+            x: self.x.clone(),
+            y: self.y.clone(),
+            z: self.z.clone()
+        }
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<C: CurveParameters> zeroize::Zeroize for ProjectivePoint<C> {
+    fn zeroize(&mut self) {
+        self.x.zeroize_secret();
+        self.y.zeroize_secret();
+        self.z.zeroize_secret();
+    }
+}
+
+impl<C: CurveParameters> ConditionalSelect for ProjectivePoint<C> {
+    #[inline(always)]
+    fn conditional_select(a: &Self, b: &Self, choice: bool) -> Self {
+        Self {
+            x: C::BaseFieldElement::conditional_select(&a.x, &b.x, choice),
+            y: C::BaseFieldElement::conditional_select(&a.y, &b.y, choice),
+            z: C::BaseFieldElement::conditional_select(&a.z, &b.z, choice),
+        }
+    }
+}
+
+impl<C: CurveParameters> ProjectivePoint<C> {
+    fn zero(curve: &WeierstrassCurve<C>) -> Self {
+        Self {
+            x: C::BaseFieldElement::zero(curve.params.params()),
+            y: C::BaseFieldElement::one(curve.params.params()),
+            z: C::BaseFieldElement::zero(curve.params.params()),
+        }
+    }
+
+    /// Algorithm 1 of the paper for any A, `b3` is 3*B. It also doubles, exceptions are only
+    /// the points that differ by a point of order two, so never points of an odd order subgroup
+    fn add_complete(&self, other: &Self, a: &C::BaseFieldElement, b3: &C::BaseFieldElement) -> Self {
+        let mut t0 = self.x.clone();
+        t0.mul_assign(&other.x);
+        let mut t1 = self.y.clone();
+        t1.mul_assign(&other.y);
+        let mut t2 = self.z.clone();
+        t2.mul_assign(&other.z);
+
+        // X1*Y2 + X2*Y1
+        let mut t3 = self.x.clone();
+        t3.add_assign(&self.y);
+        let mut t4 = other.x.clone();
+        t4.add_assign(&other.y);
+        t3.mul_assign(&t4);
+        t4 = t0.clone();
+        t4.add_assign(&t1);
+        t3.sub_assign(&t4);
+
+        // X1*Z2 + X2*Z1
+        t4 = self.x.clone();
+        t4.add_assign(&self.z);
+        let mut t5 = other.x.clone();
+        t5.add_assign(&other.z);
+        t4.mul_assign(&t5);
+        t5 = t0.clone();
+        t5.add_assign(&t2);
+        t4.sub_assign(&t5);
+
+        // Y1*Z2 + Y2*Z1
+        t5 = self.y.clone();
+        t5.add_assign(&self.z);
+        let mut x3 = other.y.clone();
+        x3.add_assign(&other.z);
+        t5.mul_assign(&x3);
+        x3 = t1.clone();
+        x3.add_assign(&t2);
+        t5.sub_assign(&x3);
+
+        let mut z3 = t4.clone();
+        z3.mul_assign(a);
+        x3 = t2.clone();
+        x3.mul_assign(b3);
+        z3.add_assign(&x3);
+        x3 = t1.clone();
+        x3.sub_assign(&z3);
+        z3.add_assign(&t1);
+        let mut y3 = x3.clone();
+        y3.mul_assign(&z3);
+
+        t1 = t0.clone();
+        t1.double();
+        t1.add_assign(&t0);
+        t2.mul_assign(a);
+        t4.mul_assign(b3);
+        t1.add_assign(&t2);
+        t2.negate();
+        t2.add_assign(&t0);
+        t2.mul_assign(a);
+        t4.add_assign(&t2);
+
+        t0 = t1.clone();
+        t0.mul_assign(&t4);
+        y3.add_assign(&t0);
+        t0 = t5.clone();
+        t0.mul_assign(&t4);
+        x3.mul_assign(&t3);
+        x3.sub_assign(&t0);
+        t0 = t3;
+        t0.mul_assign(&t1);
+        z3.mul_assign(&t5);
+        z3.add_assign(&t0);
+
+        Self {
+            x: x3,
+            y: y3,
+            z: z3
+        }
+    }
+}
+
 impl<'a, C: CurveParameters> CurvePoint<'a, C> {    
     pub fn zero(curve: &'a WeierstrassCurve<C>) -> Self {
         Self {
@@ -439,6 +779,142 @@ impl<'a, C: CurveParameters> CurvePoint<'a, C> {
         }
     }
 
+    /// Same formulas as `add_assign_mixed_generic_impl` for many independent pairs at once, so
+    /// every field multiplication step is a single batched multiplication over the pairs. Zero
+    /// and projective operands and pairs that need doubling are handled one by one
+    fn batch_add_assign_mixed_generic_impl(points: &mut [Self], others: &[Self]) {
+        debug_assert_eq!(points.len(), others.len());
+        if points.is_empty() {
+            return;
+        }
+
+        let one = C::BaseFieldElement::one(points[0].curve.params.params());
+        let mut lanes = Vec::with_capacity(points.len());
+        for (i, (p, o)) in points.iter_mut().zip(others.iter()).enumerate() {
+            if p.is_zero() || o.is_zero() || o.z != one {
+                p.add_assign_mixed_generic_impl(o);
+            } else {
+                lanes.push(i);
+            }
+        }
+
+        let batch_mul = C::BaseFieldElement::batch_mul_assign;
+
+        // Z1Z1 = Z1^2
+        let z1: Vec<_> = lanes.iter().map(|&i| points[i].z.clone()).collect();
+        let mut z1z1 = z1.clone();
+        batch_mul(&mut z1z1, &z1);
+
+        // U2 = X2*Z1Z1
+        let mut u2: Vec<_> = lanes.iter().map(|&i| others[i].x.clone()).collect();
+        batch_mul(&mut u2, &z1z1);
+
+        // S2 = Y2*Z1*Z1Z1
+        let mut s2: Vec<_> = lanes.iter().map(|&i| others[i].y.clone()).collect();
+        batch_mul(&mut s2, &z1);
+        batch_mul(&mut s2, &z1z1);
+
+        // equal points are doubled
+        let mut keep = Vec::with_capacity(lanes.len());
+        for (k, &i) in lanes.iter().enumerate() {
+            if points[i].x == u2[k] && points[i].y == s2[k] {
+                points[i].double();
+            } else {
+                keep.push(k);
+            }
+        }
+        let lanes: Vec<usize> = keep.iter().map(|&k| lanes[k]).collect();
+        let z1z1: Vec<_> = keep.iter().map(|&k| z1z1[k].clone()).collect();
+        let x1: Vec<_> = lanes.iter().map(|&i| points[i].x.clone()).collect();
+        let y1: Vec<_> = lanes.iter().map(|&i| points[i].y.clone()).collect();
+
+        // H = U2-X1
+        let h: Vec<_> = keep.iter().zip(x1.iter()).map(|(&k, x1)| {
+            let mut h = u2[k].clone();
+            h.sub_assign(x1);
+
+            h
+        }).collect();
+
+        // HH = H^2
+        let mut hh = h.clone();
+        batch_mul(&mut hh, &h);
+
+        // I = 4*HH
+        let i: Vec<_> = hh.iter().map(|hh| {
+            let mut i = hh.clone();
+            i.double();
+            i.double();
+
+            i
+        }).collect();
+
+        // J = H*I
+        let mut j = h.clone();
+        batch_mul(&mut j, &i);
+
+        // r = 2*(S2-Y1)
+        let r: Vec<_> = keep.iter().zip(y1.iter()).map(|(&k, y1)| {
+            let mut r = s2[k].clone();
+            r.sub_assign(y1);
+            r.double();
+
+            r
+        }).collect();
+
+        // V = X1*I
+        let mut v = x1.clone();
+        batch_mul(&mut v, &i);
+
+        // X3 = r^2 - J - 2*V
+        let mut x3 = r.clone();
+        batch_mul(&mut x3, &r);
+        for ((x3, j), v) in x3.iter_mut().zip(j.iter()).zip(v.iter()) {
+            x3.sub_assign(j);
+            x3.sub_assign(v);
+            x3.sub_assign(v);
+        }
+
+        // Y3 = r*(V-X3)-2*Y1*J
+        batch_mul(&mut j, &y1);
+        let mut y3: Vec<_> = v.iter().zip(x3.iter()).map(|(v, x3)| {
+            let mut y3 = v.clone();
+            y3.sub_assign(x3);
+
+            y3
+        }).collect();
+        batch_mul(&mut y3, &r);
+        for (y3, j) in y3.iter_mut().zip(j.iter()) {
+            let mut j = j.clone();
+            j.double();
+            y3.sub_assign(&j);
+        }
+
+        // Z3 = (Z1+H)^2-Z1Z1-HH
+        let z1_plus_h: Vec<_> = lanes.iter().zip(h.iter()).map(|(&i, h)| {
+            let mut z = points[i].z.clone();
+            z.add_assign(h);
+
+            z
+        }).collect();
+        let mut z3 = z1_plus_h.clone();
+        batch_mul(&mut z3, &z1_plus_h);
+        for ((z3, z1z1), hh) in z3.iter_mut().zip(z1z1.iter()).zip(hh.iter()) {
+            z3.sub_assign(z1z1);
+            z3.sub_assign(hh);
+        }
+
+        for (((&i, x3), y3), z3) in lanes.iter().zip(x3.into_iter()).zip(y3.into_iter()).zip(z3.into_iter()) {
+            if z3.is_zero() {
+                points[i] = Self::zero(points[i].curve);
+            } else {
+                points[i].x = x3;
+                points[i].y = y3;
+                points[i].z = z3;
+            }
+        }
+    }
+
     fn negate_impl(&mut self) {
         if !self.is_zero() {
             self.y.negate()
@@ -488,26 +964,28 @@ impl<'a, C: CurveParameters> CurvePoint<'a, C> {
 
         let mut precomp = self.clone();
         precomp_table[index_for_positive] = precomp.clone();
-        let mut neg_precomp = precomp.clone();
-        neg_precomp.negate();
-        precomp_table[index_for_positive-1] = neg_precomp;
 
         for i in 1..index_for_positive {
             precomp.add_assign(&two_self);
             precomp_table[index_for_positive+i] = precomp.clone();
-            let mut neg_precomp = precomp.clone();
+        }
+
+        // every table entry is added many times, so one inversion to make all the
+        // additions below mixed ones pays off. Negation keeps the entries affine
+        batch_normalize(&mut precomp_table[index_for_positive..]);
+
+        for i in 0..index_for_positive {
+            let mut neg_precomp = precomp_table[index_for_positive+i].clone();
             neg_precomp.negate();
             precomp_table[index_for_positive-1-i] = neg_precomp;
         }
 
-        // batch_normalize(&mut precomp_table);
-
-        let wnaf = exp.wnaf(window_size);
+        let mut wnaf = exp.wnaf(window_size);
 
         let mut res = Self::zero(&self.curve);
         let mut found_nonzero = false;
 
-        for w in wnaf.into_iter().rev() {
+        for &w in wnaf.iter().rev() {
             if found_nonzero {
                 res.double();
             }
@@ -523,6 +1001,122 @@ impl<'a, C: CurveParameters> CurvePoint<'a, C> {
             }
         }
         
+        wnaf.zeroize_secret();
+        precomp_table.zeroize_secret();
+
+        res
+    }
+
+    /// Fixed window multiplication for secret scalars. Every window of the scalar up to its
+    /// limb length is processed, every table entry is touched on lookup and points are added
+    /// and doubled with complete formulas, so the sequence of field operations doesn't depend
+    /// on the scalar. Multiples of points outside of an odd order subgroup may be wrong
+    pub(crate) fn mul_constant_time_impl<S: AsRef<[u64]>>(&self, exp: S) -> Self {
+        let b3 = self.curve_b3();
+        let result = self.mul_projective_constant_time(exp.as_ref(), &b3);
+
+        Self::from_projective(self.curve, result)
+    }
+
+    /// Sum of the multiples of the bases by secret scalars, each one is made by
+    /// `mul_constant_time_impl` and they are added up with the same complete formulas
+    pub(crate) fn multiexp_constant_time<S: AsRef<[u64]>>(bases: &[Self], scalars: &[S]) -> Self {
+        debug_assert!(bases.len() == scalars.len());
+        debug_assert!(bases.len() > 0);
+
+        let curve = bases[0].curve;
+        let b3 = bases[0].curve_b3();
+        let mut result = ProjectivePoint::zero(curve);
+        for (base, scalar) in bases.iter().zip(scalars.iter()) {
+            let mut multiple = base.mul_projective_constant_time(scalar.as_ref(), &b3);
+            result = result.add_complete(&multiple, &curve.a, &b3);
+            multiple.zeroize_secret();
+        }
+
+        Self::from_projective(curve, result)
+    }
+
+    fn curve_b3(&self) -> C::BaseFieldElement {
+        let mut b3 = self.curve.b.clone();
+        b3.double();
+        b3.add_assign(&self.curve.b);
+
+        b3
+    }
+
+    /// (X*Z, Y, Z^3) is the same point as (X/Z^2, Y/Z^3)
+    fn into_projective(&self) -> ProjectivePoint<C> {
+        let mut x = self.x.clone();
+        x.mul_assign(&self.z);
+        let mut z = self.z.clone();
+        z.square();
+        z.mul_assign(&self.z);
+        let mut projective = ProjectivePoint {
+            x: x,
+            y: self.y.clone(),
+            z: z
+        };
+        projective.conditional_assign(&ProjectivePoint::zero(self.curve), self.z.is_zero());
+
+        projective
+    }
+
+    /// (X*Z, Y*Z^2, Z) is the same point as (X/Z, Y/Z)
+    fn from_projective(curve: &'a WeierstrassCurve<'a, C>, point: ProjectivePoint<C>) -> Self {
+        let ProjectivePoint { mut x, mut y, z } = point;
+        x.mul_assign(&z);
+        let mut z_2 = z.clone();
+        z_2.square();
+        y.mul_assign(&z_2);
+        let mut result = Self {
+            curve: curve,
+            x: x,
+            y: y,
+            z: z
+        };
+        let is_zero = result.z.is_zero();
+        result.conditional_assign(&Self::zero(curve), is_zero);
+
+        result
+    }
+
+    fn mul_projective_constant_time(&self, exp: &[u64], b3: &C::BaseFieldElement) -> ProjectivePoint<C> {
+        const WINDOW_SIZE: usize = 4;
+        const WINDOW_MASK: u64 = (1 << WINDOW_SIZE) - 1;
+
+        let a = &self.curve.a;
+        let mut base = self.into_projective();
+
+        // [0]P, [1]P, ..., [2^w - 1]P
+        let mut table = Vec::with_capacity(1 << WINDOW_SIZE);
+        table.push(ProjectivePoint::zero(self.curve));
+        for i in 1..(1 << WINDOW_SIZE) {
+            let next = table[i - 1].add_complete(&base, a, b3);
+            table.push(next);
+        }
+
+        let mut res = ProjectivePoint::zero(self.curve);
+        let num_windows = exp.len() * 64 / WINDOW_SIZE;
+        for window in (0..num_windows).rev() {
+            for _ in 0..WINDOW_SIZE {
+                res = res.add_complete(&res, a, b3);
+            }
+
+            let bit = window * WINDOW_SIZE;
+            let digit = (exp[bit / 64] >> (bit % 64)) & WINDOW_MASK;
+
+            let mut selected = ProjectivePoint::zero(self.curve);
+            for (i, point) in table.iter().enumerate() {
+                selected.conditional_assign(point, constant_time_eq(i as u64, digit));
+            }
+
+            res = res.add_complete(&selected, a, b3);
+            selected.zeroize_secret();
+        }
+
+        table.zeroize_secret();
+        base.zeroize_secret();
+
         res
     }
 
@@ -551,7 +1145,7 @@ impl<'a, C: CurveParameters> CurvePoint<'a, C> {
         return self.z.is_zero();
     }
 
-    fn double_generic_impl(&mut self) {
+    pub(crate) fn double_generic_impl(&mut self) {
         if self.is_zero() {
             return;
         }
@@ -679,6 +1273,62 @@ impl<'a, C: CurveParameters> CurvePoint<'a, C> {
         self.y.sub_assign(&c);
     }
 
+    fn double_a_is_minus_3_impl(&mut self) {
+        if self.is_zero() {
+            return;
+        }
+
+        // http://www.hyperelliptic.org/EFD/g1p/auto-shortw-jacobian-3.html#doubling-dbl-2001-b
+
+        // delta = Z1^2
+        let mut delta = self.z.clone();
+        delta.square();
+
+        // gamma = Y1^2
+        let mut gamma = self.y.clone();
+        gamma.square();
+
+        // beta = X1*gamma
+        let mut beta = self.x.clone();
+        beta.mul_assign(&gamma);
+
+        // alpha = 3*(X1-delta)*(X1+delta)
+        let mut alpha = self.x.clone();
+        alpha.sub_assign(&delta);
+        let mut t = self.x.clone();
+        t.add_assign(&delta);
+        alpha.mul_assign(&t);
+        let mut t = alpha.clone();
+        t.double();
+        alpha.add_assign(&t);
+
+        // X3 = alpha^2-8*beta
+        beta.double();
+        beta.double();
+        let mut x = alpha.clone();
+        x.square();
+        x.sub_assign(&beta);
+        x.sub_assign(&beta);
+
+        // Z3 = (Y1+Z1)^2-gamma-delta
+        self.z.add_assign(&self.y);
+        self.z.square();
+        self.z.sub_assign(&gamma);
+        self.z.sub_assign(&delta);
+
+        // Y3 = alpha*(4*beta-X3)-8*gamma^2
+        self.y = beta;
+        self.y.sub_assign(&x);
+        self.y.mul_assign(&alpha);
+        gamma.square();
+        gamma.double();
+        gamma.double();
+        gamma.double();
+        self.y.sub_assign(&gamma);
+
+        self.x = x;
+    }
+
     fn check_correct_subgroup_impl(&self) -> bool {
         // let p = self.mul_impl(&self.curve.subgroup_order_repr);
         let p = self.wnaf_mul_impl(self.curve.subgroup_order_repr);
@@ -704,6 +1354,10 @@ impl<'a, C: CurveParameters> Group for CurvePoint<'a, C> {
         }
     }
 
+    fn batch_add_assign_mixed(points: &mut [Self], others: &[Self]) {
+        Self::batch_add_assign_mixed_generic_impl(points, others);
+    }
+
     fn sub_assign(&mut self, other: &Self) {
         let mut other_neg = other.clone();
         other_neg.negate();
@@ -719,9 +1373,23 @@ impl<'a, C: CurveParameters> Group for CurvePoint<'a, C> {
     }
 
     fn mul<S: AsRef<[u64]>>(&self, exp: S) -> Self {
+        // fuzzing allows points that are not on the curve, so keep the reference double-and-add
+        // to have the same result as other implementations
+        if crate::features::in_fuzzing() {
+            return self.mul_impl(exp);
+        }
+        if CoordinateSystem::for_operation(PointOperation::Mul) == CoordinateSystem::Projective {
+            return self.mul_constant_time_impl(exp);
+        }
+        if let Some(endomorphism) = self.curve.endomorphism.as_ref() {
+            return endomorphism.mul(self, exp.as_ref());
+        }
         match self.curve.curve_type {
             _ => {
-                return self.mul_impl(exp);
+                let exp = exp.as_ref();
+                let window_size = crate::wnaf::window_size_for_scalar_bits(crate::representation::num_bits(exp));
+
+                return self.wnaf_mul_with_window_size_impl(exp, window_size);
             },
         }
     }
@@ -741,7 +1409,10 @@ impl<'a, C: CurveParameters> Group for CurvePoint<'a, C> {
             },
             CurveType::AIsZero => {
                 self.double_a_is_zero_impl();
-            }
+            },
+            CurveType::AIsMinus3 => {
+                self.double_a_is_minus_3_impl();
+            },
             _ => {unimplemented!()}
         }
     }
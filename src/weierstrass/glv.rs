@@ -0,0 +1,637 @@
+//! GLV scalar multiplication for curves with `a = 0` over prime fields with p = 1 mod 3.
+//! Such curves have the endomorphism (x, y) -> (beta * x, y) where beta is a non-trivial
+//! cube root of unity. On the prime order subgroup it acts as multiplication by
+//! a cube root of unity lambda modulo the group order, so [k]P can be computed as
+//! [k1]P + [k2](beta * x, y) with k1 and k2 of half the length of k.
+//!
+//! This is only valid for points of the prime order subgroup, so the endomorphism should
+//! only be attached to curves where every point that reaches multiplication is known
+//! to be in the subgroup (e.g. curves with cofactor 1).
+//!
+//! `ScalarDecomposition` and `cube_root_of_unity_mod` don't depend on the point types, so
+//! other implementations of the curve arithmetic may use them for the same decomposition.
+
+use crate::field::SizedPrimeField;
+use crate::fp::Fp;
+use crate::representation::ElementRepr;
+use crate::traits::{FieldElement, ZeroAndOne};
+use crate::integers::{MaxFieldSquaredUint, MaxGroupSizeUint};
+use crate::representation::num_bits;
+use crate::square_root::sqrt;
+use crate::primality::is_probable_prime;
+use crate::execution_context::ExecutionContext;
+use super::{CurveParameters, CurveOverFpParameters, Group};
+use super::curve::{CurvePoint, WeierstrassCurve, batch_normalize};
+
+/// Small generators to try when looking for a non-trivial cube root of unity
+const MAX_CUBE_ROOT_CANDIDATE: u64 = 64;
+
+/// Small x coordinates to try when looking for a point on a decoded curve, about
+/// a half of them are on the curve
+const MAX_POINT_CANDIDATE: u64 = 64;
+
+/// Largest group order for the decomposition, products of scalars and the basis are
+/// computed in `MaxFieldSquaredUint` and must not overflow
+const MAX_ORDER_BITS: u32 = 1024;
+
+/// Integer as a magnitude and a sign, zero is never negative
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SignedInteger {
+    magnitude: MaxFieldSquaredUint,
+    is_negative: bool,
+}
+
+impl SignedInteger {
+    pub fn magnitude(&self) -> &MaxFieldSquaredUint {
+        &self.magnitude
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.is_negative
+    }
+
+    fn from_unsigned(magnitude: MaxFieldSquaredUint) -> Self {
+        Self {
+            magnitude,
+            is_negative: false
+        }
+    }
+
+    fn negated(&self) -> Self {
+        Self {
+            magnitude: self.magnitude,
+            is_negative: !self.is_negative && !self.magnitude.is_zero()
+        }
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        if self.is_negative == other.is_negative {
+            return Self {
+                magnitude: self.magnitude + other.magnitude,
+                is_negative: self.is_negative
+            };
+        }
+
+        if self.magnitude >= other.magnitude {
+            Self {
+                magnitude: self.magnitude - other.magnitude,
+                is_negative: self.is_negative && self.magnitude != other.magnitude
+            }
+        } else {
+            Self {
+                magnitude: other.magnitude - self.magnitude,
+                is_negative: other.is_negative
+            }
+        }
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        self.add(&other.negated())
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        let magnitude = self.magnitude * other.magnitude;
+
+        Self {
+            magnitude,
+            is_negative: (self.is_negative != other.is_negative) && !magnitude.is_zero()
+        }
+    }
+
+    /// Rounds self / divisor to the nearest integer, divisor is positive
+    fn div_round(&self, divisor: &MaxFieldSquaredUint) -> Self {
+        let two = MaxFieldSquaredUint::from(2u64);
+        let magnitude = (self.magnitude * two + *divisor) / (*divisor * two);
+
+        Self {
+            magnitude,
+            is_negative: self.is_negative && !magnitude.is_zero()
+        }
+    }
+
+    fn norm_squared(a: &Self, b: &Self) -> MaxFieldSquaredUint {
+        a.magnitude * a.magnitude + b.magnitude * b.magnitude
+    }
+}
+
+/// Splits scalars k into (k1, k2) with k = k1 + k2 * lambda modulo the group order and both halves
+/// of about the half length of the order, for any endomorphism that acts as multiplication by lambda
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScalarDecomposition {
+    order: MaxFieldSquaredUint,
+    // short basis (a1, b1), (a2, b2) of the lattice {(a, b): a + b * lambda = 0 mod order}
+    a1: SignedInteger,
+    b1: SignedInteger,
+    a2: SignedInteger,
+    b2: SignedInteger,
+}
+
+pub struct GlvEndomorphism<C: CurveParameters> {
+    pub(crate) beta: C::BaseFieldElement,
+    decomposition: ScalarDecomposition,
+}
+
+impl<C: CurveParameters> Clone for GlvEndomorphism<C> {
+    fn clone(&self) -> Self {
+        Self {
+            beta: self.beta.clone(),
+            decomposition: self.decomposition,
+        }
+    }
+}
+
+fn pow_mod(base: MaxFieldSquaredUint, exp: MaxFieldSquaredUint, modulus: &MaxFieldSquaredUint) -> MaxFieldSquaredUint {
+    let mut result = MaxFieldSquaredUint::one();
+    let base = base % *modulus;
+    for i in (0..exp.bits()).rev() {
+        result = (result * result) % *modulus;
+        if exp.bit(i) {
+            result = (result * base) % *modulus;
+        }
+    }
+
+    result
+}
+
+/// Non-trivial cube root of unity modulo a prime `modulus`, the other one is its square.
+/// There are none if `modulus - 1` is not divisible by 3
+pub fn cube_root_of_unity_mod(modulus: &MaxFieldSquaredUint) -> Option<MaxFieldSquaredUint> {
+    let one = MaxFieldSquaredUint::one();
+    let three = MaxFieldSquaredUint::from(3u64);
+    let minus_one = *modulus - one;
+    if minus_one % three != MaxFieldSquaredUint::zero() {
+        return None;
+    }
+    let exp = minus_one / three;
+    for g in 2..MAX_CUBE_ROOT_CANDIDATE {
+        let candidate = pow_mod(MaxFieldSquaredUint::from(g), exp, modulus);
+        if candidate != one {
+            return Some(candidate);
+        }
+    }
+
+    None
+}
+
+/// Non-trivial cube root of unity in the base field
+fn cube_root_of_unity_in_field<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>(field: &'a F) -> Option<Fp<'a, FE, F>> {
+    let modulus = MaxFieldSquaredUint::from(field.modulus().as_ref());
+    let three = MaxFieldSquaredUint::from(3u64);
+    let minus_one = modulus - MaxFieldSquaredUint::one();
+    if minus_one % three != MaxFieldSquaredUint::zero() {
+        return None;
+    }
+    let exp = minus_one / three;
+    let one = Fp::one(field);
+    for g in 2..MAX_CUBE_ROOT_CANDIDATE {
+        let base = Fp::from_repr(field, FE::from(g)).ok()?;
+        let candidate = base.pow(exp.as_ref());
+        if candidate != one {
+            return Some(candidate);
+        }
+    }
+
+    None
+}
+
+impl ScalarDecomposition {
+    /// Decomposition for the endomorphism acting as multiplication by `lambda` modulo the prime `order`,
+    /// both are little endian limbs. `lambda` must be a root of unity other than one, e.g. from
+    /// `cube_root_of_unity_mod`, and the order is at most 1024 bits long
+    pub fn new(lambda: &[u64], order: &[u64]) -> Option<Self> {
+        let order_bits = num_bits(order);
+        if order_bits < 2 || order_bits > MAX_ORDER_BITS || num_bits(lambda) > order_bits {
+            return None;
+        }
+
+        let order = MaxFieldSquaredUint::from(order);
+        let lambda = MaxFieldSquaredUint::from(lambda);
+        if lambda >= order || lambda <= MaxFieldSquaredUint::one() {
+            return None;
+        }
+
+        Some(Self::from_lambda(&lambda, &order))
+    }
+
+    fn from_lambda(lambda: &MaxFieldSquaredUint, order: &MaxFieldSquaredUint) -> Self {
+        // Extended Euclidean algorithm on (order, lambda) keeping s_i * order + t_i * lambda = r_i,
+        // see "Guide to Elliptic Curve Cryptography", algorithm 3.74
+        let mut r = [*order, *lambda, MaxFieldSquaredUint::zero()];
+        let mut t = [SignedInteger::from_unsigned(MaxFieldSquaredUint::zero()), SignedInteger::from_unsigned(MaxFieldSquaredUint::one()), SignedInteger::from_unsigned(MaxFieldSquaredUint::zero())];
+
+        let step = |r: &mut [MaxFieldSquaredUint; 3], t: &mut [SignedInteger; 3]| {
+            let (q, rem) = r[0].div_mod(r[1]);
+            let next_t = t[0].sub(&SignedInteger::from_unsigned(q).mul(&t[1]));
+            r[0] = r[1];
+            r[1] = rem;
+            t[0] = t[1];
+            t[1] = next_t;
+        };
+
+        // stop at the last remainder r_l that is at least sqrt(order)
+        while r[1] * r[1] >= *order {
+            step(&mut r, &mut t);
+        }
+        // now r[0] = r_l, r[1] = r_{l+1}
+        let (r_l, t_l) = (r[0], t[0]);
+        let (r_l1, t_l1) = (r[1], t[1]);
+        step(&mut r, &mut t);
+        let (r_l2, t_l2) = (r[1], t[1]);
+
+        let a1 = SignedInteger::from_unsigned(r_l1);
+        let b1 = t_l1.negated();
+
+        let first = SignedInteger::norm_squared(&SignedInteger::from_unsigned(r_l), &t_l);
+        let second = SignedInteger::norm_squared(&SignedInteger::from_unsigned(r_l2), &t_l2);
+        let (a2, b2) = if first <= second {
+            (SignedInteger::from_unsigned(r_l), t_l.negated())
+        } else {
+            (SignedInteger::from_unsigned(r_l2), t_l2.negated())
+        };
+
+        Self {
+            order: *order,
+            a1,
+            b1,
+            a2,
+            b2,
+        }
+    }
+
+    /// Splits k into (k1, k2) with k = k1 + k2 * lambda mod order. The scalar is reduced by the order first
+    /// and may have any length. Both halves are about half the length of the order by absolute value
+    pub fn decompose(&self, scalar: &[u64]) -> (SignedInteger, SignedInteger) {
+        let k = SignedInteger::from_unsigned(self.reduce(scalar));
+
+        let c1 = self.b2.mul(&k).div_round(&self.order);
+        let c2 = self.b1.negated().mul(&k).div_round(&self.order);
+
+        let k1 = k.sub(&c1.mul(&self.a1)).sub(&c2.mul(&self.a2));
+        let k2 = c1.mul(&self.b1).add(&c2.mul(&self.b2)).negated();
+
+        (k1, k2)
+    }
+
+    /// Scalar modulo the order. Scalars that don't fit into `MaxFieldSquaredUint` are reduced
+    /// limb by limb from the most significant one, the order is at most half as long, so
+    /// the remainder shifted by a limb still fits
+    fn reduce(&self, scalar: &[u64]) -> MaxFieldSquaredUint {
+        if scalar.len() <= self.order.as_ref().len() {
+            return MaxFieldSquaredUint::from(scalar) % self.order;
+        }
+
+        scalar.iter().rev().fold(MaxFieldSquaredUint::zero(), |remainder, &limb| {
+            ((remainder << 64u32) + MaxFieldSquaredUint::from(limb)) % self.order
+        })
+    }
+
+    /// Same as `decompose` for scalars and halves as big integers, scalars may have any length
+    #[cfg(feature = "num-bigint")]
+    pub fn decompose_bigint(&self, scalar: &num_bigint::BigUint) -> (num_bigint::BigInt, num_bigint::BigInt) {
+        use num_bigint::{BigInt, BigUint, Sign};
+
+        let to_be_bytes = |value: &MaxFieldSquaredUint| {
+            let mut bytes = vec![0u8; value.as_ref().len() * 8];
+            value.to_big_endian(&mut bytes);
+
+            bytes
+        };
+
+        let scalar = scalar % BigUint::from_bytes_be(&to_be_bytes(&self.order));
+        let limbs: Vec<u64> = scalar.to_bytes_le().chunks(8).map(|chunk| {
+            chunk.iter().rev().fold(0u64, |limb, &byte| (limb << 8) | byte as u64)
+        }).collect();
+        let (k1, k2) = self.decompose(&limbs);
+
+        let to_bigint = |k: &SignedInteger| {
+            let sign = if k.is_negative { Sign::Minus } else { Sign::Plus };
+
+            BigInt::from_bytes_be(sign, &to_be_bytes(&k.magnitude))
+        };
+
+        (to_bigint(&k1), to_bigint(&k2))
+    }
+}
+
+impl<C: CurveParameters> GlvEndomorphism<C> {
+    fn from_beta_and_lambda(beta: C::BaseFieldElement, lambda: &MaxFieldSquaredUint, order: &MaxFieldSquaredUint) -> Self {
+        Self {
+            beta,
+            decomposition: ScalarDecomposition::from_lambda(lambda, order)
+        }
+    }
+
+    /// Decomposition of scalars for this endomorphism
+    pub fn decomposition(&self) -> &ScalarDecomposition {
+        &self.decomposition
+    }
+
+    pub(crate) fn apply<'a>(&self, point: &CurvePoint<'a, C>) -> CurvePoint<'a, C> {
+        let mut result = point.clone();
+        result.x.mul_assign(&self.beta);
+
+        result
+    }
+
+    /// [k]P as [k1]P + [k2]phi(P) with a joint double-and-add over both half length scalars
+    pub(crate) fn mul<'a>(&self, point: &CurvePoint<'a, C>, scalar: &[u64]) -> CurvePoint<'a, C> {
+        let (k1, k2) = self.decomposition.decompose(scalar);
+
+        let mut p1 = point.clone();
+        if k1.is_negative {
+            p1.negate();
+        }
+        let mut p2 = self.apply(point);
+        if k2.is_negative {
+            p2.negate();
+        }
+        let mut p12 = p1.clone();
+        p12.add_assign(&p2);
+
+        // affine table for mixed additions in the loop
+        let mut table = [p1, p2, p12];
+        batch_normalize(&mut table);
+        let [p1, p2, p12] = table;
+
+        let num_bits = core::cmp::max(k1.magnitude.bits(), k2.magnitude.bits());
+        let mut result = CurvePoint::zero(point.curve);
+        for i in (0..num_bits).rev() {
+            result.double();
+            match (k1.magnitude.bit(i), k2.magnitude.bit(i)) {
+                (true, true) => result.add_assign(&p12),
+                (true, false) => result.add_assign(&p1),
+                (false, true) => result.add_assign(&p2),
+                (false, false) => {}
+            }
+        }
+
+        result
+    }
+}
+
+/// Finds the endomorphism for a curve over a prime field if it has `a = 0` and both the base field
+/// and the group order have cube roots of unity. `generator` is any non-zero point of the prime
+/// order subgroup and is used to pair beta with the matching lambda
+pub(crate) fn detect_glv_endomorphism<'a, 'b, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>(
+    curve: &WeierstrassCurve<'b, CurveOverFpParameters<'a, FE, F>>,
+    generator: &CurvePoint<'b, CurveOverFpParameters<'a, FE, F>>
+) -> Option<GlvEndomorphism<CurveOverFpParameters<'a, FE, F>>> {
+    if !curve.a.is_zero() || generator.is_zero() {
+        return None;
+    }
+
+    let field = curve.params.field;
+    let beta = cube_root_of_unity_in_field(field)?;
+
+    let order = MaxFieldSquaredUint::from(curve.subgroup_order_repr);
+    if order.bits() < 2 {
+        return None;
+    }
+    let lambda = cube_root_of_unity_mod(&order)?;
+    let lambda_squared = (lambda * lambda) % order;
+
+    let mut phi_generator = generator.clone();
+    phi_generator.x.mul_assign(&beta);
+    let phi_generator = phi_generator.into_xy();
+
+    for lambda in [lambda, lambda_squared].iter() {
+        let multiplied = generator.mul(lambda.as_ref());
+        if !multiplied.is_zero() && multiplied.into_xy() == phi_generator {
+            return Some(GlvEndomorphism::from_beta_and_lambda(beta, lambda, &order));
+        }
+    }
+
+    None
+}
+
+/// Finds the endomorphism for a curve decoded from generic parameters, where the declared group
+/// order is not known to be the number of points on the curve.
+///
+/// If the order r is prime and some non-zero point P on the curve has [r]P = 0, then r divides
+/// the number of points. By the Hasse bound there are at most p + 1 + 2*sqrt(p) points, so if
+/// r is larger than half of that the cofactor is 1 and every point on the curve is in the
+/// subgroup, which is what GLV multiplication needs
+pub(crate) fn detect_glv_endomorphism_for_decoded_curve<'a, 'b, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>(
+    curve: &WeierstrassCurve<'b, CurveOverFpParameters<'a, FE, F>>,
+    ctx: &ExecutionContext
+) -> Option<GlvEndomorphism<CurveOverFpParameters<'a, FE, F>>> {
+    // most of the decoded curves are rejected here without any exponentiations
+    if !curve.a.is_zero() {
+        return None;
+    }
+
+    let field = curve.params.field;
+    let modulus = MaxFieldSquaredUint::from(field.modulus().as_ref());
+    let order = MaxFieldSquaredUint::from(curve.subgroup_order_repr);
+    // 2*sqrt(p) < 2^(bits(p)/2 + 2)
+    let two_sqrt_bound = MaxFieldSquaredUint::one() << ((modulus.bits() / 2 + 2) as u32);
+    if order << 1 <= modulus + MaxFieldSquaredUint::one() + two_sqrt_bound {
+        return None;
+    }
+    if order.bits() > MAX_ORDER_BITS as usize {
+        return None;
+    }
+    if !is_probable_prime(&MaxGroupSizeUint::from(curve.subgroup_order_repr), ctx) {
+        return None;
+    }
+
+    let point = find_point_on_curve(curve)?;
+    if !point.mul_impl(curve.subgroup_order_repr).is_zero() {
+        return None;
+    }
+
+    detect_glv_endomorphism(curve, &point)
+}
+
+fn find_point_on_curve<'a, 'b, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>(
+    curve: &'b WeierstrassCurve<'b, CurveOverFpParameters<'a, FE, F>>
+) -> Option<CurvePoint<'b, CurveOverFpParameters<'a, FE, F>>> {
+    let field = curve.params.field;
+    for x in 0..MAX_POINT_CANDIDATE {
+        let x = Fp::from_repr(field, FE::from(x)).ok()?;
+        let y = match sqrt(&curve.evaluate_rhs(&x)) {
+            Some(y) => y,
+            None => continue
+        };
+        let point = CurvePoint::point_from_xy(curve, x, y);
+        if !point.is_zero() {
+            return Some(point);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::engines::bls12_381::*;
+    use crate::test_utils::{random_point, random_scalar};
+    use rand::{Rng, SeedableRng};
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_glv_decomposition_is_short_and_correct() {
+        let glv = detect_glv_endomorphism(&BLS12_381_G1_CURVE, &BLS12_381_G1_GENERATOR).expect("BLS12-381 has an endomorphism");
+        let order = MaxFieldSquaredUint::from(&BLS12_381_SUBGROUP_ORDER[..]);
+        let lambda = {
+            // recover lambda from the basis: a1 + b1 * lambda = 0 mod order
+            let b1_inv = pow_mod(glv.decomposition.b1.magnitude % order, order - MaxFieldSquaredUint::from(2u64), &order);
+            let a1 = glv.decomposition.a1.magnitude % order;
+            let mut lambda = (a1 * b1_inv) % order;
+            // lambda = -a1 / b1
+            if glv.decomposition.b1.is_negative == glv.decomposition.a1.is_negative {
+                lambda = (order - lambda) % order;
+            }
+            lambda
+        };
+
+        let rng = &mut XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        for _ in 0..100 {
+            let k = random_scalar(&BLS12_381_SUBGROUP_ORDER, rng);
+            let (k1, k2) = glv.decomposition().decompose(&k);
+            assert!(k1.magnitude.bits() <= 130);
+            assert!(k2.magnitude.bits() <= 130);
+
+            let recombined = k1.add(&k2.mul(&SignedInteger::from_unsigned(lambda)));
+            let mut recombined_mod = recombined.magnitude % order;
+            if recombined.is_negative && !recombined_mod.is_zero() {
+                recombined_mod = order - recombined_mod;
+            }
+            assert_eq!(recombined_mod, MaxFieldSquaredUint::from(&k[..]));
+        }
+    }
+
+    #[test]
+    fn test_glv_mul_matches_double_and_add() {
+        let glv = detect_glv_endomorphism(&BLS12_381_G1_CURVE, &BLS12_381_G1_GENERATOR).expect("BLS12-381 has an endomorphism");
+
+        let rng = &mut XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        for _ in 0..20 {
+            let p = random_point(&BLS12_381_G1_GENERATOR, rng);
+            let k = random_scalar(&BLS12_381_SUBGROUP_ORDER, rng);
+
+            let expected = p.mul(&k);
+            let glv_result = glv.mul(&p, &k);
+            assert!(expected.into_xy() == glv_result.into_xy());
+        }
+
+        // edge scalars
+        let p = random_point(&BLS12_381_G1_GENERATOR, rng);
+        assert!(glv.mul(&p, &[0u64]).is_zero());
+        assert!(glv.mul(&p, &BLS12_381_SUBGROUP_ORDER).is_zero());
+        assert!(glv.mul(&p, &[1u64]).into_xy() == p.into_xy());
+    }
+
+    #[test]
+    fn test_bn254_g1_uses_glv() {
+        use crate::engines::bn254::*;
+
+        assert!(BN254_G1_CURVE.endomorphism.is_some());
+
+        let rng = &mut XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        for _ in 0..20 {
+            let p = random_point(&*BN254_G1_GENERATOR, rng);
+            // arbitrary 256 bit scalar, not reduced by the group order
+            let k: Vec<u64> = (0..4).map(|_| rng.gen()).collect();
+
+            assert!(p.mul(&k).into_xy() == p.mul_impl(&k).into_xy());
+        }
+    }
+
+    #[test]
+    fn test_public_decomposition_of_bn254_scalars() {
+        use crate::engines::bn254::*;
+
+        let order = MaxFieldSquaredUint::from(BN254_G1_CURVE.subgroup_order_repr);
+        let lambda = cube_root_of_unity_mod(&order).expect("BN254 group order is 1 mod 3");
+        let decomposition = ScalarDecomposition::new(lambda.as_ref(), BN254_G1_CURVE.subgroup_order_repr).unwrap();
+
+        let rng = &mut XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        for _ in 0..20 {
+            let k: Vec<u64> = (0..4).map(|_| rng.gen()).collect();
+            let (k1, k2) = decomposition.decompose(&k);
+            assert!(k1.magnitude().bits() <= 128 && k2.magnitude().bits() <= 128);
+
+            let recombined = k1.add(&k2.mul(&SignedInteger::from_unsigned(lambda)));
+            let mut recombined_mod = *recombined.magnitude() % order;
+            if recombined.is_negative() && !recombined_mod.is_zero() {
+                recombined_mod = order - recombined_mod;
+            }
+            assert_eq!(recombined_mod, MaxFieldSquaredUint::from(&k[..]) % order);
+
+            #[cfg(feature = "num-bigint")]
+            {
+                use num_bigint::{BigInt, BigUint, Sign};
+
+                let to_bigint = |k: &SignedInteger| {
+                    let mut bytes = vec![0u8; k.magnitude().as_ref().len() * 8];
+                    k.magnitude().to_big_endian(&mut bytes);
+
+                    BigInt::from_bytes_be(if k.is_negative() { Sign::Minus } else { Sign::Plus }, &bytes)
+                };
+                let k: BigUint = k.iter().rev().fold(BigUint::from(0u64), |acc, &limb| (acc << 64) + BigUint::from(limb));
+                assert_eq!(decomposition.decompose_bigint(&k), (to_bigint(&k1), to_bigint(&k2)));
+            }
+        }
+
+        // trivial roots of unity and the ones that are not reduced don't give a short basis
+        assert!(ScalarDecomposition::new(&[1u64], BN254_G1_CURVE.subgroup_order_repr).is_none());
+        assert!(ScalarDecomposition::new(BN254_G1_CURVE.subgroup_order_repr, BN254_G1_CURVE.subgroup_order_repr).is_none());
+        assert!(ScalarDecomposition::new(lambda.as_ref(), &[1u64]).is_none());
+    }
+
+    #[test]
+    fn test_decomposition_of_oversized_scalars() {
+        let glv = detect_glv_endomorphism(&BLS12_381_G1_CURVE, &BLS12_381_G1_GENERATOR).expect("BLS12-381 has an endomorphism");
+        let capacity = MaxFieldSquaredUint::zero().as_ref().len();
+
+        let rng = &mut XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        let k = random_scalar(&BLS12_381_SUBGROUP_ORDER, rng);
+
+        // k + order * 2^(64 * capacity) doesn't fit and must not be truncated to k
+        let mut oversized = vec![0u64; capacity + BLS12_381_SUBGROUP_ORDER.len()];
+        oversized[..k.len()].copy_from_slice(&k);
+        oversized[capacity..].copy_from_slice(&BLS12_381_SUBGROUP_ORDER);
+        let (k1, k2) = glv.decomposition().decompose(&k);
+        let (o1, o2) = glv.decomposition().decompose(&oversized);
+        assert_eq!((k1.magnitude(), k1.is_negative()), (o1.magnitude(), o1.is_negative()));
+        assert_eq!((k2.magnitude(), k2.is_negative()), (o2.magnitude(), o2.is_negative()));
+
+        // 2^(64 * capacity) alone is not a multiple of the order
+        let mut power = vec![0u64; capacity + 1];
+        power[capacity] = 1;
+        let (p1, p2) = glv.decomposition().decompose(&power);
+        assert!(!p1.magnitude().is_zero() || !p2.magnitude().is_zero());
+
+        let p = random_point(&BLS12_381_G1_GENERATOR, rng);
+        assert!(glv.mul(&p, &oversized).into_xy() == glv.mul(&p, &k).into_xy());
+    }
+
+    #[test]
+    fn test_no_endomorphism_for_generic_curve() {
+        assert!(detect_glv_endomorphism(&BLS12_381_G1_CURVE_ISOGENY, &BLS12_381_G1_GENERATOR).is_none());
+    }
+
+    #[test]
+    fn test_detection_for_decoded_curves() {
+        use crate::engines::bn254::*;
+
+        let ctx = ExecutionContext::default();
+
+        // same curve as BN254 G1, but without the attached endomorphism
+        let bn254 = WeierstrassCurve::new(BN254_G1_CURVE.subgroup_order_repr, BN254_G1_CURVE.a.clone(), BN254_G1_CURVE.b.clone(), BN254_G1_CURVE.params).unwrap();
+        let glv = detect_glv_endomorphism_for_decoded_curve(&bn254, &ctx).expect("BN254 G1 has cofactor 1");
+        let rng = &mut XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        let p = random_point(&*BN254_G1_GENERATOR, rng);
+        let k: Vec<u64> = (0..4).map(|_| rng.gen()).collect();
+        assert!(glv.mul(&p, &k).into_xy() == p.mul_impl(&k).into_xy());
+
+        // BLS12-381 G1 has the endomorphism, but also points outside of the subgroup
+        assert!(detect_glv_endomorphism_for_decoded_curve(&BLS12_381_G1_CURVE, &ctx).is_none());
+        // declared order is not the order of the points
+        let wrong_order = [BN254_G1_CURVE.subgroup_order_repr[0] + 2, BN254_G1_CURVE.subgroup_order_repr[1], BN254_G1_CURVE.subgroup_order_repr[2], BN254_G1_CURVE.subgroup_order_repr[3]];
+        let wrong = WeierstrassCurve::new(&wrong_order, BN254_G1_CURVE.a.clone(), BN254_G1_CURVE.b.clone(), BN254_G1_CURVE.params).unwrap();
+        assert!(detect_glv_endomorphism_for_decoded_curve(&wrong, &ctx).is_none());
+    }
+}
@@ -6,13 +6,48 @@ pub(crate) enum CurveType {
     BIsZero,
 }
 
+/// Coordinates in which an operation adds and doubles points
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub(crate) enum CoordinateSystem {
+    /// (X/Z^2, Y/Z^3) of `CurvePoint`, the cheapest doubling with dedicated formulas for A = 0
+    /// and A = -3 and mixed addition of affine points, but with branches for the special cases
+    Jacobian,
+    /// (X/Z, Y/Z) with the complete formulas that have no special cases, so don't branch
+    Projective,
+}
+
+/// Operations on points that select their coordinate system
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub(crate) enum PointOperation {
+    Mul,
+    FixedBaseMul,
+    Multiexp,
+}
+
+impl CoordinateSystem {
+    /// Jacobian coordinates are the fastest for every operation on public scalars. Secret scalars
+    /// need the sequence of field operations to not depend on them, that only the complete
+    /// projective formulas give
+    pub(crate) fn for_operation(operation: PointOperation) -> Self {
+        match operation {
+            PointOperation::Mul | PointOperation::FixedBaseMul | PointOperation::Multiexp => {
+                if crate::features::in_constant_time() {
+                    CoordinateSystem::Projective
+                } else {
+                    CoordinateSystem::Jacobian
+                }
+            }
+        }
+    }
+}
+
 use crate::field::SizedPrimeField;
 use crate::representation::ElementRepr;
 use crate::traits::FieldElement;
-use crate::traits::ZeroAndOne;
+use crate::traits::{ZeroAndOne, ConditionalSelect, ZeroizeSecret};
 
 pub trait CurveParameters: Clone + Send + Sync {
-    type BaseFieldElement: FieldElement + ZeroAndOne;
+    type BaseFieldElement: FieldElement + ZeroAndOne + ConditionalSelect + ZeroizeSecret;
     fn params(&self) -> <Self::BaseFieldElement as ZeroAndOne>::Params;
 }
 
@@ -104,9 +139,104 @@ impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> CurveOverFp3Parameters<
 }
 
 
+use crate::extension_towers::fp4_as_2_over_2;
+
+pub struct CurveOverFp4Parameters<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> {
+    pub field: &'a fp4_as_2_over_2::Extension2Over2<'a, FE, F>,
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> Clone for CurveOverFp4Parameters<'a, FE, F> {
+    fn clone(&self) -> Self {
+        Self {
+            field: self.field
+        }
+    }
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> CurveParameters for CurveOverFp4Parameters<'a, FE, F> {
+    type BaseFieldElement = fp4_as_2_over_2::Fp4<'a, FE, F>;
+    fn params(&self) -> <Self::BaseFieldElement as ZeroAndOne>::Params {
+        self.field
+    }
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> CurveOverFp4Parameters<'a, FE, F> {
+    pub fn new(field: &'a fp4_as_2_over_2::Extension2Over2<'a, FE, F>) -> Self {
+        Self {
+            field
+        }
+    }
+}
+
+use crate::extension_towers::fp8_as_2_over_4;
+
+pub struct CurveOverFp8Parameters<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> {
+    pub field: &'a fp8_as_2_over_4::Extension2Over4<'a, FE, F>,
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> Clone for CurveOverFp8Parameters<'a, FE, F> {
+    fn clone(&self) -> Self {
+        Self {
+            field: self.field
+        }
+    }
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> CurveParameters for CurveOverFp8Parameters<'a, FE, F> {
+    type BaseFieldElement = fp8_as_2_over_4::Fp8<'a, FE, F>;
+    fn params(&self) -> <Self::BaseFieldElement as ZeroAndOne>::Params {
+        self.field
+    }
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> CurveOverFp8Parameters<'a, FE, F> {
+    pub fn new(field: &'a fp8_as_2_over_4::Extension2Over4<'a, FE, F>) -> Self {
+        Self {
+            field
+        }
+    }
+}
+
+use crate::extension_towers::fpk;
+
+pub struct CurveOverFpKParameters<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> {
+    pub field: &'a fpk::ExtensionK<'a, FE, F>,
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> Clone for CurveOverFpKParameters<'a, FE, F> {
+    fn clone(&self) -> Self {
+        Self {
+            field: self.field
+        }
+    }
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> CurveParameters for CurveOverFpKParameters<'a, FE, F> {
+    type BaseFieldElement = fpk::FpK<'a, FE, F>;
+    fn params(&self) -> <Self::BaseFieldElement as ZeroAndOne>::Params {
+        self.field
+    }
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> CurveOverFpKParameters<'a, FE, F> {
+    pub fn new(field: &'a fpk::ExtensionK<'a, FE, F>) -> Self {
+        Self {
+            field
+        }
+    }
+}
+
 pub trait Group: Sized + Clone {
     fn add_assign(&mut self, other: &Self);
     fn add_assign_mixed(&mut self, other: &Self);
+    /// Mixed addition of `others[i]` into `points[i]` for every `i`. Additions are independent,
+    /// so implementations may share the work between them
+    fn batch_add_assign_mixed(points: &mut [Self], others: &[Self]) {
+        debug_assert_eq!(points.len(), others.len());
+        for (p, o) in points.iter_mut().zip(others.iter()) {
+            p.add_assign_mixed(o);
+        }
+    }
     fn sub_assign(&mut self, other: &Self);
     fn negate(&mut self);
     fn double(&mut self);
@@ -117,4 +247,5 @@ pub trait Group: Sized + Clone {
     fn check_correct_subgroup(&self) -> bool;
 }
 
-pub mod curve;
\ No newline at end of file
+pub mod curve;
+pub mod glv;
\ No newline at end of file
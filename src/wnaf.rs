@@ -1,5 +1,6 @@
 use crate::traits::FieldElement;
 use crate::representation::IntoWnaf;
+use crate::alloc_prelude::*;
 
 #[allow(dead_code)]
 pub(crate) fn calculate_wnaf_table<F: FieldElement>(base: &F, window: usize) -> Vec<F> {
@@ -78,6 +79,18 @@ impl<F: FieldElement> WnafBase<F> {
     // }
 } 
 
+/// Window size for a single variable base multiplication by a scalar of `num_bits` bits.
+/// Precomputation takes 2^(w-2) additions and the main loop about `num_bits / (w + 1)`
+pub(crate) fn window_size_for_scalar_bits(num_bits: u32) -> u32 {
+    match num_bits {
+        0..=31 => 3,
+        32..=95 => 4,
+        96..=319 => 5,
+        320..=895 => 6,
+        _ => 7
+    }
+}
+
 impl<'a> IntoWnaf for &'a [u64] {
 // impl IntoWnaf for Vec<u64> {
     fn wnaf(&self, window: u32) -> Vec<i64> {
@@ -134,8 +147,10 @@ impl<'a> IntoWnaf for &'a [u64] {
             return vec![];
         }
     
-        let mut res = Vec::with_capacity(self.len() * 64);
+        let mut res = Vec::with_capacity(self.len() * 64 + 1);
         let mut e = self.to_vec();
+        // rounding up of a negative digit can carry out of the top limb
+        e.push(0u64);
 
         let max = (1 << window) as i64;
         let midpoint = (1 << (window - 1)) as i64;
@@ -198,6 +213,18 @@ mod tests {
         println!("{:?}", res);
     }
 
+    #[test]
+    fn test_wnaf_of_all_ones_scalar() {
+        let repr = vec![u64::max_value(); 2];
+        for window in 2..8 {
+            let wnaf = (&repr[..]).wnaf(window);
+            assert_eq!(wnaf.len(), 129);
+            assert_eq!(*wnaf.last().unwrap(), 1);
+            assert_eq!(wnaf[0], -1);
+            assert!(wnaf[1..128].iter().all(|d| *d == 0));
+        }
+    }
+
     // #[test]
     // fn test_correctness_wnaf_mul() {
     //     use crate::field::{U256Repr, new_field};